@@ -0,0 +1,19 @@
+//! Build script for openclaw-ffi: regenerates the C header with cbindgen.
+
+use std::path::PathBuf;
+
+fn main() {
+    let crate_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let header = crate_dir.join("include").join("openclaw.h");
+
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    match cbindgen::generate(&crate_dir) {
+        Ok(bindings) => {
+            bindings.write_to_file(header);
+        }
+        // Keep building with the checked-in header
+        Err(e) => println!("cargo:warning=cbindgen failed, header not regenerated: {e}"),
+    }
+}
@@ -0,0 +1,26 @@
+#ifndef OPENCLAW_H
+#define OPENCLAW_H
+
+/* Generated with cbindgen:0.27.0 */
+
+/* Generated by cbindgen from bridge/openclaw-ffi. Do not edit. */
+
+#include <stddef.h>
+
+// Result of a C API call.
+typedef enum OpenClawStatus {
+  // Success.
+  OPEN_CLAW_STATUS_OK = 0,
+  // A required pointer was null or a string was not valid UTF-8.
+  OPEN_CLAW_STATUS_INVALID_ARGUMENT = 1,
+  // The config could not be loaded or failed validation.
+  OPEN_CLAW_STATUS_CONFIG = 2,
+  // The event store could not be opened, read, or written.
+  OPEN_CLAW_STATUS_EVENT_STORE = 3,
+  // Input failed validation.
+  OPEN_CLAW_STATUS_VALIDATION = 4,
+  // JSON could not be parsed or produced.
+  OPEN_CLAW_STATUS_JSON = 5,
+} OpenClawStatus;
+
+#endif  /* OPENCLAW_H */
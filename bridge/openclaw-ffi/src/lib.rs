@@ -0,0 +1,556 @@
+//! # `OpenClaw` C API
+//!
+//! A stable C ABI over the Rust core for hosts that cannot use the Node.js
+//! bindings (Go via cgo, Swift, Java via Panama or JNA).
+//!
+//! ## Conventions
+//!
+//! - Functions return an [`OpenClawStatus`]; `OPENCLAW_STATUS_OK` is zero.
+//! - After a failure, [`openclaw_last_error`] describes it. The message is
+//!   per thread.
+//! - Strings are UTF-8 and NUL-terminated. Strings returned through
+//!   out-parameters belong to the caller and must be released with
+//!   [`openclaw_string_free`].
+//! - Structured values (configs, events, projections) are exchanged as JSON.
+//! - An [`OpenClawEventStore`] handle may be shared between threads and is
+//!   released with [`openclaw_event_store_free`].
+//!
+//! The header is generated by cbindgen into `include/openclaw.h`.
+
+#![allow(unsafe_code)]
+#![warn(missing_docs)]
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::path::Path;
+
+use openclaw_core::config::Config;
+use openclaw_core::events::{EventStore, SessionEvent, SessionEventKind};
+use openclaw_core::types::SessionKey;
+use openclaw_core::validation::validate_message_content;
+
+/// Message length limit used when none is given.
+const DEFAULT_MAX_MESSAGE_LEN: usize = 100_000;
+
+/// Result of a C API call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenClawStatus {
+    /// Success.
+    Ok = 0,
+    /// A required pointer was null or a string was not valid UTF-8.
+    InvalidArgument = 1,
+    /// The config could not be loaded or failed validation.
+    Config = 2,
+    /// The event store could not be opened, read, or written.
+    EventStore = 3,
+    /// Input failed validation.
+    Validation = 4,
+    /// JSON could not be parsed or produced.
+    Json = 5,
+}
+
+/// Opaque event store handle.
+pub struct OpenClawEventStore {
+    store: EventStore,
+}
+
+/// A failed call: its status and message.
+struct FfiError {
+    status: OpenClawStatus,
+    message: String,
+}
+
+impl FfiError {
+    fn new(status: OpenClawStatus, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Run a call body, recording its error message for `openclaw_last_error`.
+fn run(body: impl FnOnce() -> Result<(), FfiError>) -> OpenClawStatus {
+    let (status, message) = match body() {
+        Ok(()) => (OpenClawStatus::Ok, None),
+        Err(e) => {
+            // Messages with interior NULs are truncated at the first one
+            let bytes: Vec<u8> = e.message.bytes().take_while(|&b| b != 0).collect();
+            (e.status, CString::new(bytes).ok())
+        }
+    };
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+    status
+}
+
+/// Borrow a string argument.
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a NUL-terminated string that outlives `'a`.
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, FfiError> {
+    if ptr.is_null() {
+        return Err(FfiError::new(
+            OpenClawStatus::InvalidArgument,
+            format!("{name} is null"),
+        ));
+    }
+    // SAFETY: non-null and NUL-terminated per the caller's contract
+    unsafe { CStr::from_ptr(ptr) }.to_str().map_err(|_| {
+        FfiError::new(
+            OpenClawStatus::InvalidArgument,
+            format!("{name} is not valid UTF-8"),
+        )
+    })
+}
+
+/// Borrow an event store handle.
+///
+/// # Safety
+///
+/// `ptr` must be null or a live handle from `openclaw_event_store_open`.
+unsafe fn store_arg<'a>(ptr: *const OpenClawEventStore) -> Result<&'a EventStore, FfiError> {
+    // SAFETY: null or a live handle per the caller's contract
+    unsafe { ptr.as_ref() }
+        .map(|handle| &handle.store)
+        .ok_or_else(|| FfiError::new(OpenClawStatus::InvalidArgument, "store is null"))
+}
+
+/// Hand a string to the caller through an out-parameter.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+unsafe fn write_string(out: *mut *mut c_char, value: String) -> Result<(), FfiError> {
+    if out.is_null() {
+        return Err(FfiError::new(
+            OpenClawStatus::InvalidArgument,
+            "output pointer is null",
+        ));
+    }
+    let value = CString::new(value)
+        .map_err(|_| FfiError::new(OpenClawStatus::Json, "result contains an interior NUL byte"))?;
+    // SAFETY: non-null and writable per the caller's contract
+    unsafe { out.write(value.into_raw()) };
+    Ok(())
+}
+
+/// Serialize a value as JSON for the caller.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+unsafe fn write_json(out: *mut *mut c_char, value: &impl serde::Serialize) -> Result<(), FfiError> {
+    let json = serde_json::to_string(value)
+        .map_err(|e| FfiError::new(OpenClawStatus::Json, format!("Serialization error: {e}")))?;
+    // SAFETY: forwarded from the caller's contract
+    unsafe { write_string(out, json) }
+}
+
+fn store_error(context: &str, e: impl std::fmt::Display) -> FfiError {
+    FfiError::new(OpenClawStatus::EventStore, format!("{context}: {e}"))
+}
+
+/// Library version as a static string. Do not free it.
+#[unsafe(no_mangle)]
+#[must_use]
+pub const extern "C" fn openclaw_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Message describing the last failed call on this thread, or null.
+///
+/// The string is owned by the library and valid until the next call on
+/// this thread. Do not free it.
+#[unsafe(no_mangle)]
+#[must_use]
+pub extern "C" fn openclaw_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Free a string returned by this library. Null is ignored.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned through an out-parameter of this
+/// library that has not already been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn openclaw_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: allocated by `CString::into_raw` in `write_string`
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// Load and validate a config file, returning it as JSON.
+///
+/// Pass a null `path` to load the default config
+/// (`~/.openclaw/openclaw.json`).
+///
+/// # Safety
+///
+/// `path` must be null or a NUL-terminated string; `out_json` must be
+/// valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn openclaw_config_load(
+    path: *const c_char,
+    out_json: *mut *mut c_char,
+) -> OpenClawStatus {
+    run(|| {
+        let config = if path.is_null() {
+            Config::load_default()
+        } else {
+            // SAFETY: non-null and NUL-terminated per the caller's contract
+            Config::load(Path::new(unsafe { str_arg(path, "path") }?))
+        }
+        .map_err(|e| FfiError::new(OpenClawStatus::Config, format!("Config load error: {e}")))?;
+
+        // SAFETY: writable per the caller's contract
+        unsafe { write_json(out_json, &config) }
+    })
+}
+
+/// Check that a config file loads and validates.
+///
+/// Returns `OPENCLAW_STATUS_CONFIG` with the reason in
+/// `openclaw_last_error` if it does not.
+///
+/// # Safety
+///
+/// `path` must be a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn openclaw_config_validate(path: *const c_char) -> OpenClawStatus {
+    run(|| {
+        // SAFETY: NUL-terminated per the caller's contract
+        let path = unsafe { str_arg(path, "path") }?;
+        Config::load(Path::new(path))
+            .map(|_| ())
+            .map_err(|e| FfiError::new(OpenClawStatus::Config, e.to_string()))
+    })
+}
+
+/// Open or create an event store at `path`.
+///
+/// # Safety
+///
+/// `path` must be a NUL-terminated string; `out_store` must be valid for
+/// writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn openclaw_event_store_open(
+    path: *const c_char,
+    out_store: *mut *mut OpenClawEventStore,
+) -> OpenClawStatus {
+    run(|| {
+        // SAFETY: NUL-terminated per the caller's contract
+        let path = unsafe { str_arg(path, "path") }?;
+        if out_store.is_null() {
+            return Err(FfiError::new(
+                OpenClawStatus::InvalidArgument,
+                "output pointer is null",
+            ));
+        }
+        let store = EventStore::open(Path::new(path))
+            .map_err(|e| store_error("EventStore open error", e))?;
+
+        let handle = Box::into_raw(Box::new(OpenClawEventStore { store }));
+        // SAFETY: non-null and writable per the caller's contract
+        unsafe { out_store.write(handle) };
+        Ok(())
+    })
+}
+
+/// Close an event store, flushing pending writes. Null is ignored.
+///
+/// # Safety
+///
+/// `store` must be null or a handle from `openclaw_event_store_open` that
+/// has not already been freed and is not in use on another thread.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn openclaw_event_store_free(store: *mut OpenClawEventStore) {
+    if !store.is_null() {
+        // SAFETY: allocated by `Box::into_raw` in `openclaw_event_store_open`
+        let handle = unsafe { Box::from_raw(store) };
+        if let Err(e) = handle.store.flush() {
+            run(|| Err(store_error("Flush error", e)));
+        }
+    }
+}
+
+/// Append an event to a session and return its ID (hex).
+///
+/// `kind_json` is the event kind as JSON, tagged by `type`, for example
+/// `{"type": "message_received", "content": "Hi", "attachments": []}`.
+///
+/// # Safety
+///
+/// `store` must be a live handle; `session_key`, `agent_id`, and
+/// `kind_json` must be NUL-terminated strings; `out_event_id` must be null
+/// or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn openclaw_event_store_append(
+    store: *const OpenClawEventStore,
+    session_key: *const c_char,
+    agent_id: *const c_char,
+    kind_json: *const c_char,
+    out_event_id: *mut *mut c_char,
+) -> OpenClawStatus {
+    run(|| {
+        // SAFETY: per the caller's contract
+        let (store, session_key, agent_id, kind_json) = unsafe {
+            (
+                store_arg(store)?,
+                str_arg(session_key, "session_key")?,
+                str_arg(agent_id, "agent_id")?,
+                str_arg(kind_json, "kind_json")?,
+            )
+        };
+        let kind: SessionEventKind = serde_json::from_str(kind_json)
+            .map_err(|e| FfiError::new(OpenClawStatus::Json, format!("Invalid event: {e}")))?;
+
+        let event = SessionEvent::new(SessionKey::new(session_key), agent_id.to_string(), kind);
+        let id = store
+            .append(&event)
+            .map_err(|e| store_error("Append error", e))?;
+
+        if out_event_id.is_null() {
+            return Ok(());
+        }
+        // SAFETY: non-null and writable per the caller's contract
+        unsafe { write_string(out_event_id, id.to_hex()) }
+    })
+}
+
+/// Get a session's events as a JSON array, oldest first.
+///
+/// # Safety
+///
+/// `store` must be a live handle; `session_key` must be a NUL-terminated
+/// string; `out_json` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn openclaw_event_store_get_events(
+    store: *const OpenClawEventStore,
+    session_key: *const c_char,
+    out_json: *mut *mut c_char,
+) -> OpenClawStatus {
+    run(|| {
+        // SAFETY: per the caller's contract
+        let (store, session_key) =
+            unsafe { (store_arg(store)?, str_arg(session_key, "session_key")?) };
+        let events = store
+            .get_events(&SessionKey::new(session_key))
+            .map_err(|e| store_error("Query error", e))?;
+
+        // SAFETY: writable per the caller's contract
+        unsafe { write_json(out_json, &events) }
+    })
+}
+
+/// Get a session's current state (its projection) as JSON.
+///
+/// # Safety
+///
+/// `store` must be a live handle; `session_key` must be a NUL-terminated
+/// string; `out_json` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn openclaw_event_store_get_projection(
+    store: *const OpenClawEventStore,
+    session_key: *const c_char,
+    out_json: *mut *mut c_char,
+) -> OpenClawStatus {
+    run(|| {
+        // SAFETY: per the caller's contract
+        let (store, session_key) =
+            unsafe { (store_arg(store)?, str_arg(session_key, "session_key")?) };
+        let projection = store
+            .get_projection(&SessionKey::new(session_key))
+            .map_err(|e| store_error("Projection error", e))?;
+
+        // SAFETY: writable per the caller's contract
+        unsafe { write_json(out_json, &projection) }
+    })
+}
+
+/// List session keys as a JSON array of strings.
+///
+/// # Safety
+///
+/// `store` must be a live handle; `out_json` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn openclaw_event_store_list_sessions(
+    store: *const OpenClawEventStore,
+    out_json: *mut *mut c_char,
+) -> OpenClawStatus {
+    run(|| {
+        // SAFETY: live handle per the caller's contract
+        let store = unsafe { store_arg(store) }?;
+        let sessions: Vec<String> = store
+            .list_sessions()
+            .map_err(|e| store_error("List error", e))?
+            .into_iter()
+            .map(|key| key.as_ref().to_string())
+            .collect();
+
+        // SAFETY: writable per the caller's contract
+        unsafe { write_json(out_json, &sessions) }
+    })
+}
+
+/// Validate message content and return the sanitized text.
+///
+/// Strips null bytes and control characters and normalizes Unicode. Pass
+/// zero for `max_len` to use the default limit (100,000 bytes). Returns
+/// `OPENCLAW_STATUS_VALIDATION` if the message is too long.
+///
+/// # Safety
+///
+/// `content` must be a NUL-terminated string; `out_sanitized` must be
+/// valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn openclaw_validate_message(
+    content: *const c_char,
+    max_len: usize,
+    out_sanitized: *mut *mut c_char,
+) -> OpenClawStatus {
+    run(|| {
+        // SAFETY: NUL-terminated per the caller's contract
+        let content = unsafe { str_arg(content, "content") }?;
+        let max_len = if max_len == 0 {
+            DEFAULT_MAX_MESSAGE_LEN
+        } else {
+            max_len
+        };
+        let sanitized = validate_message_content(content, max_len)
+            .map_err(|e| FfiError::new(OpenClawStatus::Validation, e.to_string()))?;
+
+        // SAFETY: writable per the caller's contract
+        unsafe { write_string(out_sanitized, sanitized) }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Take ownership of a returned string.
+    fn take(s: *mut c_char) -> String {
+        let value = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string();
+        unsafe { openclaw_string_free(s) };
+        value
+    }
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(openclaw_last_error()) }
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_event_store_roundtrip() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = CString::new(temp.path().to_str().unwrap()).unwrap();
+        let key = CString::new("agent:default:telegram:dm:1").unwrap();
+        let agent = CString::new("default").unwrap();
+        let kind =
+            CString::new(r#"{"type": "message_received", "content": "Hi", "attachments": []}"#)
+                .unwrap();
+
+        let mut store = std::ptr::null_mut();
+        let mut out = std::ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                openclaw_event_store_open(path.as_ptr(), &raw mut store),
+                OpenClawStatus::Ok
+            );
+            assert_eq!(
+                openclaw_event_store_append(
+                    store,
+                    key.as_ptr(),
+                    agent.as_ptr(),
+                    kind.as_ptr(),
+                    &raw mut out
+                ),
+                OpenClawStatus::Ok
+            );
+            assert_eq!(take(out).len(), 64);
+
+            assert_eq!(
+                openclaw_event_store_get_events(store, key.as_ptr(), &raw mut out),
+                OpenClawStatus::Ok
+            );
+            let events: serde_json::Value = serde_json::from_str(&take(out)).unwrap();
+            assert_eq!(events[0]["kind"]["content"], "Hi");
+
+            assert_eq!(
+                openclaw_event_store_list_sessions(store, &raw mut out),
+                OpenClawStatus::Ok
+            );
+            assert_eq!(take(out), r#"["agent:default:telegram:dm:1"]"#);
+
+            let bad = CString::new(r#"{"type": "nonsense"}"#).unwrap();
+            assert_eq!(
+                openclaw_event_store_append(
+                    store,
+                    key.as_ptr(),
+                    agent.as_ptr(),
+                    bad.as_ptr(),
+                    std::ptr::null_mut()
+                ),
+                OpenClawStatus::Json
+            );
+            assert!(last_error().starts_with("Invalid event"));
+
+            openclaw_event_store_free(store);
+        }
+    }
+
+    #[test]
+    fn test_validate_message() {
+        let content = CString::new("hello\u{7}world").unwrap();
+        let mut out = std::ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                openclaw_validate_message(content.as_ptr(), 0, &raw mut out),
+                OpenClawStatus::Ok
+            );
+            assert_eq!(take(out), "helloworld");
+
+            assert_eq!(
+                openclaw_validate_message(content.as_ptr(), 4, &raw mut out),
+                OpenClawStatus::Validation
+            );
+            assert_eq!(
+                openclaw_validate_message(std::ptr::null(), 0, &raw mut out),
+                OpenClawStatus::InvalidArgument
+            );
+        }
+        assert_eq!(last_error(), "content is null");
+    }
+
+    #[test]
+    fn test_config_validate() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("openclaw.json");
+        std::fs::write(&path, "{}").unwrap();
+        let path = CString::new(path.to_str().unwrap()).unwrap();
+        let missing = CString::new(temp.path().join("missing.json").to_str().unwrap()).unwrap();
+
+        unsafe {
+            assert_eq!(openclaw_config_validate(path.as_ptr()), OpenClawStatus::Ok);
+            assert!(openclaw_last_error().is_null());
+            assert_eq!(
+                openclaw_config_validate(missing.as_ptr()),
+                OpenClawStatus::Config
+            );
+        }
+    }
+}
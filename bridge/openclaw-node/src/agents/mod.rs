@@ -1,5 +1,7 @@
 //! Agent runtime and tool bindings.
 
+mod runtime;
 mod tools;
 
-pub use tools::{JsToolDefinition, JsToolResult, ToolRegistry};
+pub use runtime::{JsAgentOptions, JsAgentReply, NodeAgentRuntime};
+pub use tools::{JsToolDefinition, JsToolResult, NodeToolRegistry, ToolRegistry};
@@ -0,0 +1,290 @@
+//! Agent runtime bindings.
+
+use napi::bindgen_prelude::*;
+use napi::JsObject;
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi_derive::napi;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use openclaw_agents::runtime::{AgentContext, AgentReply, AgentRuntime};
+use openclaw_core::events::{SessionMessage, SessionProjection};
+use openclaw_core::secrets::ApiKey;
+use openclaw_core::types::{AgentId, ChannelId, SessionKey};
+use openclaw_providers::{AnthropicProvider, OpenAIProvider, Provider};
+
+use super::tools::NodeToolRegistry;
+use crate::error::OpenClawError;
+use crate::providers::{JsTokenUsage, convert_usage};
+
+/// Options for `NodeAgentRuntime`.
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct JsAgentOptions {
+    /// Agent ID (default: "default")
+    pub agent_id: Option<String>,
+    /// Model to use
+    pub model: Option<String>,
+    /// System prompt
+    pub system_prompt: Option<String>,
+    /// Maximum tokens per response
+    pub max_tokens: Option<u32>,
+    /// Sampling temperature
+    pub temperature: Option<f64>,
+    /// Custom provider endpoint
+    pub base_url: Option<String>,
+}
+
+/// An agent's reply to a message.
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct JsAgentReply {
+    /// Response text
+    pub content: String,
+    /// Model that produced the response
+    pub model: String,
+    /// Token usage (zero for streamed replies)
+    pub usage: JsTokenUsage,
+}
+
+impl From<AgentReply> for JsAgentReply {
+    fn from(reply: AgentReply) -> Self {
+        Self {
+            usage: convert_usage(&reply.usage),
+            content: reply.content,
+            model: reply.model,
+        }
+    }
+}
+
+/// Agent runtime with per-session conversation history.
+///
+/// ```javascript
+/// const agent = new NodeAgentRuntime('anthropic', process.env.ANTHROPIC_API_KEY, {
+///   model: 'claude-3-5-sonnet-20241022',
+///   systemPrompt: 'You are a helpful assistant.',
+/// });
+///
+/// const reply = await agent.processMessage('user-1', 'Hello!');
+///
+/// await agent.processMessageStream('user-1', 'Tell me more', (delta) => {
+///   process.stdout.write(delta);
+/// });
+/// ```
+#[napi]
+pub struct NodeAgentRuntime {
+    inner: Arc<RuntimeState>,
+    tools: Option<NodeToolRegistry>,
+}
+
+/// State shared with in-flight requests.
+struct RuntimeState {
+    provider: Arc<dyn Provider>,
+    agent_id: AgentId,
+    options: JsAgentOptions,
+    sessions: Mutex<HashMap<String, SessionProjection>>,
+}
+
+#[napi]
+impl NodeAgentRuntime {
+    /// Create a runtime for a provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - "anthropic" or "openai"
+    /// * `api_key` - Provider API key
+    /// * `options` - Model, prompt, and sampling options
+    #[napi(constructor)]
+    pub fn new(provider: String, api_key: String, options: Option<JsAgentOptions>) -> Result<Self> {
+        let options = options.unwrap_or_default();
+        let key = ApiKey::new(api_key);
+        let provider: Arc<dyn Provider> = match (provider.as_str(), options.base_url.clone()) {
+            ("anthropic", None) => Arc::new(AnthropicProvider::new(key)),
+            ("anthropic", Some(url)) => Arc::new(AnthropicProvider::with_base_url(key, url)),
+            ("openai", None) => Arc::new(OpenAIProvider::new(key)),
+            ("openai", Some(url)) => Arc::new(OpenAIProvider::with_base_url(key, url)),
+            (other, _) => {
+                return Err(
+                    OpenClawError::config_error(format!("Unknown provider: {other}")).into(),
+                );
+            }
+        };
+
+        Ok(Self {
+            inner: Arc::new(RuntimeState {
+                provider,
+                agent_id: options
+                    .agent_id
+                    .clone()
+                    .map_or_else(AgentId::default_agent, AgentId::new),
+                options,
+                sessions: Mutex::new(HashMap::new()),
+            }),
+            tools: None,
+        })
+    }
+
+    /// Offer the tools in a registry to the model.
+    ///
+    /// Tools registered later are picked up on the next message.
+    #[napi]
+    pub fn set_tools(&mut self, tools: &NodeToolRegistry) {
+        self.tools = Some(tools.clone());
+    }
+
+    /// Process a message and resolve with the agent's reply.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_key` - Conversation to continue
+    /// * `message` - User message
+    #[napi]
+    pub async fn process_message(
+        &self,
+        session_key: String,
+        message: String,
+    ) -> Result<JsAgentReply> {
+        let runtime = self.runtime();
+        let mut ctx = self.inner.context(&session_key, &runtime);
+        let reply = runtime
+            .process(&mut ctx, &message)
+            .await
+            .map_err(|e| OpenClawError::agent_error(e.to_string()))?;
+
+        self.inner.record(&session_key, message, &reply.content);
+        Ok(reply.into())
+    }
+
+    /// Process a message, calling `callback` with each piece of response
+    /// text as it streams in.
+    ///
+    /// Resolves with the full reply once the stream ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_key` - Conversation to continue
+    /// * `message` - User message
+    /// * `callback` - Function called with each text delta
+    #[napi(ts_return_type = "Promise<JsAgentReply>")]
+    pub fn process_message_stream(
+        &self,
+        env: Env,
+        session_key: String,
+        message: String,
+        #[napi(ts_arg_type = "(delta: string) => void")] callback: JsFunction,
+    ) -> Result<JsObject> {
+        let tsfn: ThreadsafeFunction<String, ErrorStrategy::Fatal> =
+            callback.create_threadsafe_function(0, |ctx| Ok(vec![ctx.value]))?;
+        let runtime = self.runtime();
+        let inner = self.inner.clone();
+
+        env.spawn_future(async move {
+            let mut ctx = inner.context(&session_key, &runtime);
+            let on_text = |delta: &str| {
+                let _ = tsfn.call(delta.to_string(), ThreadsafeFunctionCallMode::NonBlocking);
+            };
+            let reply = runtime
+                .process_streaming(&mut ctx, &message, &on_text)
+                .await
+                .map_err(|e| OpenClawError::agent_error(e.to_string()))?;
+
+            inner.record(&session_key, message, &reply.content);
+            Ok(JsAgentReply::from(reply))
+        })
+    }
+
+    /// Forget a session's conversation history.
+    ///
+    /// Returns whether the session existed.
+    #[napi]
+    pub fn clear_session(&self, session_key: String) -> bool {
+        self.inner
+            .sessions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&session_key)
+            .is_some()
+    }
+
+    /// Keys of sessions with history.
+    #[napi]
+    #[must_use]
+    pub fn sessions(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .inner
+            .sessions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .keys()
+            .cloned()
+            .collect();
+        keys.sort();
+        keys
+    }
+}
+
+impl NodeAgentRuntime {
+    /// Build a Rust runtime with the current options and tools.
+    fn runtime(&self) -> AgentRuntime {
+        let options = &self.inner.options;
+        let mut runtime = AgentRuntime::new(self.inner.provider.clone());
+        if let Some(model) = &options.model {
+            runtime = runtime.with_model(model.clone());
+        }
+        if let Some(prompt) = &options.system_prompt {
+            runtime = runtime.with_system_prompt(prompt.clone());
+        }
+        if let Some(max_tokens) = options.max_tokens {
+            runtime = runtime.with_max_tokens(max_tokens);
+        }
+        if let Some(temperature) = options.temperature {
+            #[allow(clippy::cast_possible_truncation)]
+            let temperature = temperature as f32;
+            runtime = runtime.with_temperature(temperature);
+        }
+        if let Some(tools) = &self.tools {
+            runtime = runtime.with_tools(Arc::new(tools.to_registry()));
+        }
+        runtime
+    }
+}
+
+impl RuntimeState {
+    /// Build the context for a message, starting from the session's
+    /// history so far.
+    fn context(&self, session_key: &str, runtime: &AgentRuntime) -> AgentContext {
+        let key = SessionKey::new(session_key);
+        let session = self
+            .sessions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(session_key)
+            .cloned()
+            .unwrap_or_else(|| self.new_session(key.clone()));
+
+        AgentContext::new(self.agent_id.clone(), key, session, runtime.tools().clone())
+    }
+
+    /// Append a completed exchange to the session's history.
+    fn record(&self, session_key: &str, message: String, reply: &str) {
+        let mut sessions = self.sessions.lock().unwrap_or_else(PoisonError::into_inner);
+        let session = sessions
+            .entry(session_key.to_string())
+            .or_insert_with(|| self.new_session(SessionKey::new(session_key)));
+        session.messages.push(SessionMessage::Inbound(message));
+        session
+            .messages
+            .push(SessionMessage::Outbound(reply.to_string()));
+        session.message_count += 2;
+        drop(sessions);
+    }
+
+    fn new_session(&self, key: SessionKey) -> SessionProjection {
+        SessionProjection::new(
+            key,
+            self.agent_id.to_string(),
+            ChannelId::new("node"),
+            "node".to_string(),
+        )
+    }
+}
@@ -1,11 +1,16 @@
 //! Tool registry and execution bindings.
 
 use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction};
 use napi_derive::napi;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, PoisonError};
 use tokio::sync::RwLock;
 
+use openclaw_agents::tools::{
+    Tool as RustTool, ToolError, ToolRegistry as RustToolRegistry, ToolResult,
+};
+
 use crate::error::OpenClawError;
 
 /// Result of a tool execution.
@@ -229,3 +234,198 @@ impl Default for ToolRegistry {
         Self::new()
     }
 }
+
+/// A tool implemented by a JavaScript callback.
+struct JsTool {
+    name: String,
+    description: String,
+    input_schema: serde_json::Value,
+    callback: ThreadsafeFunction<serde_json::Value, ErrorStrategy::Fatal>,
+}
+
+#[async_trait::async_trait]
+impl RustTool for JsTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        self.input_schema.clone()
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+    ) -> std::result::Result<ToolResult, ToolError> {
+        let promise: Promise<JsToolResult> = self
+            .callback
+            .call_async(params)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+        let result = promise
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+
+        Ok(ToolResult {
+            success: result.success,
+            content: result.content,
+            error: result.error,
+            sources: Vec::new(),
+        })
+    }
+}
+
+/// Registry of JavaScript tools that the Rust agent runtime can call.
+///
+/// Pass it to `NodeAgentRuntime.setTools` to offer the tools to the
+/// model; `execute` calls a tool through the same Rust path.
+///
+/// ```javascript
+/// const tools = new NodeToolRegistry();
+///
+/// tools.register('lookup', 'Look up an order', schema, async (params) => {
+///   return { success: true, content: await findOrder(params.id), error: null };
+/// });
+///
+/// runtime.setTools(tools);
+/// ```
+#[napi]
+#[derive(Clone)]
+pub struct NodeToolRegistry {
+    tools: Arc<std::sync::RwLock<HashMap<String, Arc<dyn RustTool>>>>,
+}
+
+#[napi]
+impl NodeToolRegistry {
+    /// Create a new empty registry.
+    #[napi(constructor)]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            tools: Arc::new(std::sync::RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Register a tool backed by a JavaScript function.
+    ///
+    /// Registering a name again replaces the earlier tool.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Unique tool name
+    /// * `description` - Description shown to the model
+    /// * `input_schema` - JSON schema for parameters
+    /// * `execute_fn` - Async function that takes params and returns `JsToolResult`
+    #[napi]
+    pub fn register(
+        &self,
+        env: Env,
+        name: String,
+        description: String,
+        input_schema: serde_json::Value,
+        #[napi(ts_arg_type = "(params: any) => Promise<JsToolResult>")] execute_fn: JsFunction,
+    ) -> Result<()> {
+        let mut callback: ThreadsafeFunction<serde_json::Value, ErrorStrategy::Fatal> =
+            execute_fn.create_threadsafe_function(0, |ctx| Ok(vec![ctx.value]))?;
+        // A registered tool should not keep the process alive on its own
+        callback.unref(&env)?;
+
+        let tool = JsTool {
+            name: name.clone(),
+            description,
+            input_schema,
+            callback,
+        };
+        self.tools
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(name, Arc::new(tool));
+        Ok(())
+    }
+
+    /// Unregister a tool by name.
+    #[napi]
+    pub fn unregister(&self, name: String) -> bool {
+        self.tools
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&name)
+            .is_some()
+    }
+
+    /// List registered tool names.
+    #[napi]
+    #[must_use]
+    pub fn list(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .tools
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .keys()
+            .cloned()
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Get all tool definitions.
+    #[napi]
+    #[must_use]
+    pub fn definitions(&self) -> Vec<JsToolDefinition> {
+        let mut definitions: Vec<JsToolDefinition> = self
+            .tools
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .map(|t| JsToolDefinition {
+                name: t.name().to_string(),
+                description: t.description().to_string(),
+                input_schema: t.input_schema(),
+            })
+            .collect();
+        definitions.sort_by(|a, b| a.name.cmp(&b.name));
+        definitions
+    }
+
+    /// Execute a tool by name, as the agent runtime would.
+    #[napi]
+    pub async fn execute(&self, name: String, params: serde_json::Value) -> Result<JsToolResult> {
+        let result = self
+            .to_registry()
+            .execute(&name, params)
+            .await
+            .map_err(|e| OpenClawError::tool_error(e.to_string()))?;
+
+        Ok(JsToolResult {
+            success: result.success,
+            content: result.content,
+            error: result.error,
+        })
+    }
+}
+
+impl NodeToolRegistry {
+    /// Snapshot the registered tools into a Rust tool registry.
+    pub(crate) fn to_registry(&self) -> RustToolRegistry {
+        let mut registry = RustToolRegistry::new();
+        for tool in self
+            .tools
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+        {
+            registry.register(tool.clone());
+        }
+        registry
+    }
+}
+
+impl Default for NodeToolRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,214 @@
+//! Channel bindings for sending messages through configured channels.
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use std::sync::Arc;
+
+use openclaw_channels::{
+    ChannelRegistry, DiscordChannel, MatrixChannel, OutboundContext, SignalChannel, SlackChannel,
+    TelegramChannel, WhatsAppChannel,
+};
+use openclaw_core::config::ChannelsConfig;
+use openclaw_core::secrets::ApiKey;
+
+use crate::error::OpenClawError;
+
+/// Options for an outbound message.
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct JsSendOptions {
+    /// Message ID to reply to
+    pub reply_to: Option<String>,
+    /// Thread to post in
+    pub thread_id: Option<String>,
+}
+
+/// Result of a delivered message.
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct JsDeliveryResult {
+    /// Message ID assigned by the channel
+    pub message_id: String,
+    /// Channel the message was sent through
+    pub channel: String,
+    /// Chat the message was sent to
+    pub chat_id: Option<String>,
+    /// Delivery time (RFC 3339)
+    pub timestamp: String,
+}
+
+/// Connection status of a channel.
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct JsChannelProbe {
+    /// Channel ID
+    pub channel: String,
+    /// Whether the channel is reachable with its credentials
+    pub connected: bool,
+    /// Bot or account identifier
+    pub account_id: Option<String>,
+    /// Bot or account display name
+    pub display_name: Option<String>,
+    /// Error message (if not connected)
+    pub error: Option<String>,
+}
+
+/// Registry of the channels configured in an `OpenClaw` config.
+///
+/// ```javascript
+/// const channels = NodeChannelRegistry.fromConfig(loadDefaultConfig());
+///
+/// await channels.send('telegram', '123456', 'Build finished', { replyTo: null, threadId: null });
+/// ```
+#[napi]
+pub struct NodeChannelRegistry {
+    inner: Arc<ChannelRegistry>,
+}
+
+#[napi]
+impl NodeChannelRegistry {
+    /// Create a registry from a configuration JSON string (as returned by
+    /// `loadConfig`).
+    ///
+    /// Channels without credentials are skipped. Signal needs the URL of a
+    /// signal-cli REST API, which the config does not carry, so it is only
+    /// registered when `signal_api_url` is given.
+    #[napi(factory)]
+    pub fn from_config(config_json: String, signal_api_url: Option<String>) -> Result<Self> {
+        let config: serde_json::Value = serde_json::from_str(&config_json)
+            .map_err(|e| OpenClawError::config_error(format!("Invalid config JSON: {e}")))?;
+        let channels: ChannelsConfig = config
+            .get("channels")
+            .cloned()
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|e| OpenClawError::config_error(format!("Invalid channels config: {e}")))?
+            .unwrap_or_default();
+
+        Ok(Self {
+            inner: Arc::new(build_registry(&channels, signal_api_url)),
+        })
+    }
+
+    /// IDs of the configured channels.
+    #[napi]
+    #[must_use]
+    pub fn list(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.inner.list().into_iter().map(String::from).collect();
+        ids.sort();
+        ids
+    }
+
+    /// Send a text message through a channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - Channel ID, e.g. "telegram"
+    /// * `chat_id` - Target chat, channel, or room
+    /// * `text` - Message text
+    /// * `options` - Reply and thread targeting
+    #[napi]
+    pub async fn send(
+        &self,
+        channel: String,
+        chat_id: String,
+        text: String,
+        options: Option<JsSendOptions>,
+    ) -> Result<JsDeliveryResult> {
+        let outbound = self.inner.get_outbound(&channel).ok_or_else(|| {
+            OpenClawError::new(
+                "CHANNEL_NOT_FOUND",
+                format!("Channel not configured: {channel}"),
+            )
+        })?;
+        let options = options.unwrap_or_default();
+        let ctx = OutboundContext {
+            chat_id,
+            reply_to: options.reply_to,
+            thread_id: options.thread_id,
+        };
+
+        let result = outbound
+            .send_text(ctx, &text)
+            .await
+            .map_err(|e| OpenClawError::new("CHANNEL_ERROR", e.to_string()))?;
+
+        Ok(JsDeliveryResult {
+            message_id: result.message_id,
+            channel: result.channel.to_string(),
+            chat_id: result.chat_id,
+            timestamp: result.timestamp.to_rfc3339(),
+        })
+    }
+
+    /// Check that each configured channel can connect.
+    #[napi]
+    pub async fn probe(&self) -> Vec<JsChannelProbe> {
+        let mut probes: Vec<JsChannelProbe> = self
+            .inner
+            .probe_all()
+            .await
+            .into_iter()
+            .map(|(channel, result)| match result {
+                Ok(probe) => JsChannelProbe {
+                    channel,
+                    connected: probe.connected,
+                    account_id: probe.account_id,
+                    display_name: probe.display_name,
+                    error: probe.error,
+                },
+                Err(e) => JsChannelProbe {
+                    channel,
+                    connected: false,
+                    account_id: None,
+                    display_name: None,
+                    error: Some(e.to_string()),
+                },
+            })
+            .collect();
+        probes.sort_by(|a, b| a.channel.cmp(&b.channel));
+        probes
+    }
+}
+
+/// Register an outbound channel for each configured set of credentials.
+fn build_registry(config: &ChannelsConfig, signal_api_url: Option<String>) -> ChannelRegistry {
+    let mut registry = ChannelRegistry::new();
+
+    if let Some(token) = config.telegram.as_ref().and_then(|c| c.bot_token.clone()) {
+        registry.register_outbound(Arc::new(TelegramChannel::new(ApiKey::new(token))));
+    }
+    if let Some(token) = config.discord.as_ref().and_then(|c| c.bot_token.clone()) {
+        registry.register_outbound(Arc::new(DiscordChannel::new(ApiKey::new(token))));
+    }
+    if let Some(token) = config.slack.as_ref().and_then(|c| c.bot_token.clone()) {
+        registry.register_outbound(Arc::new(SlackChannel::new(ApiKey::new(token))));
+    }
+    let matrix = config
+        .matrix
+        .as_ref()
+        .and_then(|c| c.homeserver.clone().zip(c.access_token.clone()));
+    if let Some((homeserver, token)) = matrix {
+        registry.register_outbound(Arc::new(MatrixChannel::new(homeserver, ApiKey::new(token))));
+    }
+    if let Some(whatsapp) = config.whatsapp.as_ref() {
+        let credentials = whatsapp
+            .access_token
+            .clone()
+            .zip(whatsapp.phone_number_id.clone());
+        if let Some((token, phone_number_id)) = credentials {
+            let channel = WhatsAppChannel::new(ApiKey::new(token), phone_number_id);
+            let channel = match whatsapp.app_secret.clone() {
+                Some(secret) => channel.with_app_secret(ApiKey::new(secret)),
+                None => channel,
+            };
+            registry.register_outbound(Arc::new(channel));
+        }
+    }
+    let signal_phone = config.signal.as_ref().and_then(|c| c.phone_number.clone());
+    if let Some((api_url, phone)) = signal_api_url.zip(signal_phone) {
+        registry.register_outbound(Arc::new(SignalChannel::new(api_url, phone)));
+    }
+
+    registry
+}
@@ -50,7 +50,38 @@ impl OpenClawError {
                 message: "Rate limited by provider".to_string(),
                 details: None,
                 status: Some(429),
-                retry_after: Some(*retry_after_secs as u32),
+                retry_after: retry_after_secs.and_then(|secs| u32::try_from(secs).ok()),
+            },
+            ProviderError::Overloaded {
+                message,
+                retry_after_secs,
+            } => Self {
+                code: "PROVIDER_OVERLOADED".to_string(),
+                message: message.clone(),
+                details: None,
+                status: Some(503),
+                retry_after: retry_after_secs.and_then(|secs| u32::try_from(secs).ok()),
+            },
+            ProviderError::Auth(msg) => Self {
+                code: "AUTH_ERROR".to_string(),
+                message: msg.clone(),
+                details: None,
+                status: Some(401),
+                retry_after: None,
+            },
+            ProviderError::ContextTooLong(msg) => Self {
+                code: "CONTEXT_TOO_LONG".to_string(),
+                message: msg.clone(),
+                details: None,
+                status: Some(400),
+                retry_after: None,
+            },
+            ProviderError::ContentFiltered(msg) => Self {
+                code: "CONTENT_FILTERED".to_string(),
+                message: msg.clone(),
+                details: None,
+                status: Some(400),
+                retry_after: None,
             },
             ProviderError::Network(err) => Self {
                 code: "NETWORK_ERROR".to_string(),
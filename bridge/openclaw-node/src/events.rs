@@ -39,7 +39,8 @@ impl NodeEventStore {
     /// * `session_key` - The session key
     /// * `agent_id` - The agent ID
     /// * `event_type` - Event type: "`session_started`", "`message_received`", "`message_sent`",
-    ///                  "`agent_response`", "`session_ended`", "`state_changed`", "`tool_called`", "`tool_result`"
+    ///                  "`agent_response`", "`session_ended`", "`state_changed`", "`tool_called`", "`tool_result`",
+    ///                  "`experiment_assigned`"
     /// * `data` - JSON data for the event
     #[napi]
     pub fn append_event(
@@ -107,6 +108,53 @@ impl NodeEventStore {
             .collect())
     }
 
+    /// Call `callback` with each event appended through this store, as a
+    /// JSON string.
+    ///
+    /// Pass `session_key` to receive only that session's events. The
+    /// subscription keeps the process alive until `unsubscribe` is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - Function called with each event's JSON
+    /// * `session_key` - Only deliver events for this session
+    #[napi]
+    pub fn subscribe(
+        &self,
+        #[napi(ts_arg_type = "(event: string) => void")] callback: JsFunction,
+        session_key: Option<String>,
+    ) -> Result<NodeEventSubscription> {
+        use napi::threadsafe_function::{
+            ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode,
+        };
+        use tokio::sync::broadcast::error::RecvError;
+
+        let tsfn: ThreadsafeFunction<String, ErrorStrategy::Fatal> =
+            callback.create_threadsafe_function(0, |ctx| Ok(vec![ctx.value]))?;
+        let mut events = self.store.subscribe();
+
+        let task = napi::tokio::spawn(async move {
+            loop {
+                let event = match events.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                if session_key
+                    .as_deref()
+                    .is_some_and(|key| key != event.session_key.as_ref())
+                {
+                    continue;
+                }
+                if let Ok(json) = serde_json::to_string(&event) {
+                    let _ = tsfn.call(json, ThreadsafeFunctionCallMode::NonBlocking);
+                }
+            }
+        });
+
+        Ok(NodeEventSubscription { task: Some(task) })
+    }
+
     /// Flush pending writes to disk.
     #[napi]
     pub fn flush(&self) -> Result<()> {
@@ -116,6 +164,30 @@ impl NodeEventStore {
     }
 }
 
+/// A live event subscription created by `NodeEventStore.subscribe`.
+#[napi]
+pub struct NodeEventSubscription {
+    task: Option<napi::tokio::task::JoinHandle<()>>,
+}
+
+#[napi]
+impl NodeEventSubscription {
+    /// Stop delivering events.
+    #[napi]
+    pub fn unsubscribe(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+
+    /// Whether events are still being delivered.
+    #[napi(getter)]
+    #[must_use]
+    pub fn active(&self) -> bool {
+        self.task.as_ref().is_some_and(|task| !task.is_finished())
+    }
+}
+
 /// Parse event type string into `SessionEventKind`.
 fn parse_event_kind(event_type: &str, data: &serde_json::Value) -> Result<SessionEventKind> {
     match event_type {
@@ -126,6 +198,7 @@ fn parse_event_kind(event_type: &str, data: &serde_json::Value) -> Result<Sessio
         "message_received" => Ok(SessionEventKind::MessageReceived {
             content: data["content"].as_str().unwrap_or("").to_string(),
             attachments: vec![],
+            sender_id: data["sender_id"].as_str().map(String::from),
         }),
         "message_sent" => Ok(SessionEventKind::MessageSent {
             content: data["content"].as_str().unwrap_or("").to_string(),
@@ -140,6 +213,12 @@ fn parse_event_kind(event_type: &str, data: &serde_json::Value) -> Result<Sessio
                 cache_read_tokens: data["tokens"]["cache_read_tokens"].as_u64(),
                 cache_write_tokens: data["tokens"]["cache_write_tokens"].as_u64(),
             },
+            latency_ms: data["latency_ms"].as_u64(),
+            citations: data
+                .get("citations")
+                .cloned()
+                .and_then(|c| serde_json::from_value(c).ok())
+                .unwrap_or_default(),
         }),
         "session_ended" => Ok(SessionEventKind::SessionEnded {
             reason: data["reason"].as_str().unwrap_or("unknown").to_string(),
@@ -157,6 +236,20 @@ fn parse_event_kind(event_type: &str, data: &serde_json::Value) -> Result<Sessio
             result: data.get("result").cloned().unwrap_or_default(),
             success: data["success"].as_bool().unwrap_or(true),
         }),
+        "delegated_usage" => Ok(SessionEventKind::DelegatedUsage {
+            agent_id: data["agent_id"].as_str().unwrap_or("").to_string(),
+            child_session: data["child_session"].as_str().unwrap_or("").to_string(),
+            tokens: TokenUsage {
+                input_tokens: data["tokens"]["input_tokens"].as_u64().unwrap_or(0),
+                output_tokens: data["tokens"]["output_tokens"].as_u64().unwrap_or(0),
+                cache_read_tokens: data["tokens"]["cache_read_tokens"].as_u64(),
+                cache_write_tokens: data["tokens"]["cache_write_tokens"].as_u64(),
+            },
+        }),
+        "experiment_assigned" => Ok(SessionEventKind::ExperimentAssigned {
+            experiment: data["experiment"].as_str().unwrap_or("").to_string(),
+            variant: data["variant"].as_str().unwrap_or("").to_string(),
+        }),
         _ => Err(
             OpenClawError::event_store_error(format!("Unknown event type: {event_type}")).into(),
         ),
@@ -0,0 +1,151 @@
+//! Client for a running gateway's JSON-RPC API.
+
+use std::time::Duration;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use openclaw_core::config::GatewayConfig;
+
+use crate::error::OpenClawError;
+
+/// Client for calling RPC methods on a running gateway, over TCP or the
+/// gateway's Unix domain socket.
+///
+/// ```javascript
+/// const gateway = NodeGatewayClient.fromConfig(loadDefaultConfig(), process.env.OPENCLAW_TOKEN);
+///
+/// const sessions = await gateway.call('session.list', {});
+/// ```
+#[napi]
+pub struct NodeGatewayClient {
+    client: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+#[napi]
+impl NodeGatewayClient {
+    /// Create a client for the local gateway in a configuration JSON string
+    /// (as returned by `loadConfig`): its Unix socket if one is configured,
+    /// else its port on the loopback address.
+    #[napi(factory)]
+    pub fn from_config(config_json: String, token: Option<String>) -> Result<Self> {
+        let config: serde_json::Value = serde_json::from_str(&config_json)
+            .map_err(|e| OpenClawError::config_error(format!("Invalid config JSON: {e}")))?;
+        let gateway: GatewayConfig = config
+            .get("gateway")
+            .cloned()
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|e| OpenClawError::config_error(format!("Invalid gateway config: {e}")))?
+            .unwrap_or_default();
+        let base_path = &gateway.proxy.base_path;
+
+        match &gateway.unix_socket {
+            Some(socket) => {
+                Self::connect_socket(socket.path.display().to_string(), token).map(|mut client| {
+                    client.base_url.push_str(base_path);
+                    client
+                })
+            }
+            None => Ok(Self::connect_url(
+                format!("http://127.0.0.1:{}{base_path}", gateway.port),
+                token,
+            )),
+        }
+    }
+
+    /// Create a client for a gateway listening on a Unix domain socket.
+    #[napi(factory)]
+    pub fn connect_socket(path: String, token: Option<String>) -> Result<Self> {
+        let client = socket_client(std::path::PathBuf::from(path))?;
+        Ok(Self {
+            client,
+            // The host is only sent as the Host header
+            base_url: "http://localhost".to_string(),
+            token,
+        })
+    }
+
+    /// Create a client for a gateway at `url` (e.g. `http://127.0.0.1:18789`).
+    #[napi(factory)]
+    #[must_use]
+    pub fn connect_url(url: String, token: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: url.trim_end_matches('/').to_string(),
+            token,
+        }
+    }
+
+    /// Call an RPC method and return its result.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name, e.g. "session.list"
+    /// * `params` - Method parameters
+    /// * `timeout_ms` - Time to wait for the answer (default 30000)
+    #[napi]
+    pub async fn call(
+        &self,
+        method: String,
+        params: Option<serde_json::Value>,
+        timeout_ms: Option<u32>,
+    ) -> Result<serde_json::Value> {
+        let mut request = self
+            .client
+            .post(format!("{}/rpc", self.base_url))
+            .timeout(Duration::from_millis(u64::from(
+                timeout_ms.unwrap_or(30_000),
+            )))
+            .json(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": "1",
+                "method": method,
+                "params": params.unwrap_or_else(|| serde_json::json!({})),
+            }));
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let mut response: serde_json::Value = request
+            .send()
+            .await
+            .map_err(|e| OpenClawError::new("GATEWAY_UNREACHABLE", e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| OpenClawError::new("GATEWAY_ERROR", e.to_string()))?;
+
+        if let Some(error) = response.get("error").filter(|e| !e.is_null()) {
+            let mut js_error = OpenClawError::new(
+                "GATEWAY_RPC_ERROR",
+                error["message"].as_str().unwrap_or("Request failed"),
+            );
+            js_error.retry_after = error["data"]["retry_after"]
+                .as_u64()
+                .and_then(|secs| u32::try_from(secs).ok());
+            js_error.details = Some(error.clone());
+            return Err(js_error.into());
+        }
+        Ok(response["result"].take())
+    }
+}
+
+/// A client sending every request over the socket at `path`.
+#[cfg(unix)]
+fn socket_client(path: std::path::PathBuf) -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .unix_socket(path)
+        .build()
+        .map_err(|e| OpenClawError::new("GATEWAY_ERROR", e.to_string()).into())
+}
+
+#[cfg(not(unix))]
+fn socket_client(_path: std::path::PathBuf) -> Result<reqwest::Client> {
+    Err(OpenClawError::new(
+        "GATEWAY_ERROR",
+        "Unix sockets are not supported on this platform",
+    )
+    .into())
+}
@@ -8,7 +8,10 @@
 //! - **Event Store**: Append-only event storage with CRDT projections
 //! - **Providers**: Anthropic Claude and `OpenAI` GPT API clients
 //! - **Auth**: Encrypted credential storage with safe API key handling
-//! - **Tools**: Tool registry for agent tool execution
+//! - **Agents**: Agent runtime with per-session history and streaming replies
+//! - **Tools**: JavaScript tools the agent runtime can call
+//! - **Channels**: Send messages through configured channels
+//! - **Gateway**: Call a running gateway's RPC methods over TCP or its Unix socket
 //! - **Validation**: Input validation and session key building
 //!
 //! ## Example
@@ -50,7 +53,7 @@ pub use config::{load_config, load_default_config, validate_config};
 
 // Event storage
 mod events;
-pub use events::NodeEventStore;
+pub use events::{NodeEventStore, NodeEventSubscription};
 
 // Validation
 mod validation;
@@ -69,4 +72,15 @@ pub use auth::{CredentialStore, NodeApiKey};
 
 // Agents
 pub mod agents;
-pub use agents::{JsToolDefinition, JsToolResult, ToolRegistry};
+pub use agents::{
+    JsAgentOptions, JsAgentReply, JsToolDefinition, JsToolResult, NodeAgentRuntime,
+    NodeToolRegistry, ToolRegistry,
+};
+
+// Channels
+pub mod channels;
+pub use channels::{JsChannelProbe, JsDeliveryResult, JsSendOptions, NodeChannelRegistry};
+
+// Gateway client
+pub mod gateway;
+pub use gateway::NodeGatewayClient;
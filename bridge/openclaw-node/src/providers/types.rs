@@ -3,6 +3,7 @@
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
 
+use openclaw_core::types::TokenUsage;
 use openclaw_providers::{
     CompletionRequest, CompletionResponse, ContentBlock, Message, MessageContent, Role, StopReason,
     Tool as ProviderTool,
@@ -166,6 +167,7 @@ pub fn convert_request(req: JsCompletionRequest) -> CompletionRequest {
         tools: req
             .tools
             .map(|tools| tools.iter().map(convert_js_tool).collect()),
+        response_format: None,
     }
 }
 
@@ -220,11 +222,18 @@ pub fn convert_response(resp: CompletionResponse) -> JsCompletionResponse {
         } else {
             Some(tool_calls)
         },
-        usage: JsTokenUsage {
-            input_tokens: resp.usage.input_tokens as u32,
-            output_tokens: resp.usage.output_tokens as u32,
-            cache_read_tokens: resp.usage.cache_read_tokens.map(|v| v as u32),
-            cache_write_tokens: resp.usage.cache_write_tokens.map(|v| v as u32),
-        },
+        usage: convert_usage(&resp.usage),
+    }
+}
+
+/// Convert internal `TokenUsage` to `JsTokenUsage`.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub fn convert_usage(usage: &TokenUsage) -> JsTokenUsage {
+    JsTokenUsage {
+        input_tokens: usage.input_tokens as u32,
+        output_tokens: usage.output_tokens as u32,
+        cache_read_tokens: usage.cache_read_tokens.map(|v| v as u32),
+        cache_write_tokens: usage.cache_write_tokens.map(|v| v as u32),
     }
 }
@@ -48,6 +48,7 @@ pub fn validate_path(path: String) -> String {
 /// * `account_id` - Account/bot ID on the channel
 /// * `peer_type` - "dm", "group", "channel", or "thread"
 /// * `peer_id` - The peer/user/group ID
+/// * `thread_id` - Thread the session covers, for thread-scoped sessions
 #[napi]
 #[must_use]
 pub fn build_session_key(
@@ -56,6 +57,7 @@ pub fn build_session_key(
     account_id: String,
     peer_type: String,
     peer_id: String,
+    thread_id: Option<String>,
 ) -> String {
     let pt = match peer_type.as_str() {
         "dm" => PeerType::Dm,
@@ -71,6 +73,7 @@ pub fn build_session_key(
         &account_id,
         pt,
         &PeerId::new(&peer_id),
+        thread_id.as_deref(),
     )
     .as_ref()
     .to_string()
@@ -0,0 +1,171 @@
+//! Provider that replays canned completions.
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Mutex, PoisonError};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use openclaw_core::types::TokenUsage;
+use openclaw_providers::traits::{
+    CompletionRequest, CompletionResponse, ContentBlock, Provider, ProviderError, StopReason,
+    StreamingChunk,
+};
+
+/// A canned model response: plain text, or text with tool calls.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MockCompletion {
+    /// Reply with this text and end the turn.
+    Text(String),
+    /// Reply with optional text and tool calls.
+    #[serde(rename_all = "camelCase")]
+    Full {
+        /// Reply text.
+        #[serde(default)]
+        text: Option<String>,
+        /// Tools the model calls.
+        #[serde(default)]
+        tool_calls: Vec<MockToolCall>,
+    },
+}
+
+/// A tool call in a canned completion.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MockToolCall {
+    /// Tool name.
+    pub name: String,
+    /// Parameters passed to the tool.
+    #[serde(default)]
+    pub input: serde_json::Value,
+}
+
+/// Provider that answers each request with the next queued completion and
+/// records the requests it was sent.
+#[derive(Debug, Default)]
+pub struct MockProvider {
+    queue: Mutex<VecDeque<MockCompletion>>,
+    requests: Mutex<Vec<CompletionRequest>>,
+}
+
+impl MockProvider {
+    /// Create a provider that replies with `completions` in order.
+    #[must_use]
+    pub fn new(completions: impl IntoIterator<Item = MockCompletion>) -> Self {
+        Self {
+            queue: Mutex::new(completions.into_iter().collect()),
+            requests: Mutex::default(),
+        }
+    }
+
+    /// Queue more completions.
+    pub fn push(&self, completions: impl IntoIterator<Item = MockCompletion>) {
+        self.queue
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend(completions);
+    }
+
+    /// Number of completions not yet used.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.queue
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Drop completions not yet used, returning how many there were.
+    pub fn clear(&self) -> usize {
+        let mut queue = self.queue.lock().unwrap_or_else(PoisonError::into_inner);
+        let unused = queue.len();
+        queue.clear();
+        unused
+    }
+
+    /// Requests received so far.
+    #[must_use]
+    pub fn requests(&self) -> Vec<CompletionRequest> {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+#[async_trait]
+impl Provider for MockProvider {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
+        Ok(vec!["mock".to_string()])
+    }
+
+    async fn complete(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<CompletionResponse, ProviderError> {
+        let index = {
+            let mut requests = self.requests.lock().unwrap_or_else(PoisonError::into_inner);
+            requests.push(request.clone());
+            requests.len()
+        };
+        let completion = self
+            .queue
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front()
+            .ok_or_else(|| {
+                ProviderError::Config(format!(
+                    "Mock provider has no completion left for request {index}"
+                ))
+            })?;
+
+        let (text, tool_calls) = match completion {
+            MockCompletion::Text(text) => (Some(text), Vec::new()),
+            MockCompletion::Full { text, tool_calls } => (text, tool_calls),
+        };
+        let stop_reason = if tool_calls.is_empty() {
+            StopReason::EndTurn
+        } else {
+            StopReason::ToolUse
+        };
+        let mut content: Vec<ContentBlock> = text
+            .into_iter()
+            .map(|text| ContentBlock::Text { text })
+            .collect();
+        content.extend(
+            tool_calls
+                .into_iter()
+                .enumerate()
+                .map(|(n, call)| ContentBlock::ToolUse {
+                    id: format!("mock_{index}_{n}"),
+                    name: call.name,
+                    input: call.input,
+                }),
+        );
+
+        Ok(CompletionResponse {
+            id: format!("mock_{index}"),
+            model: request.model,
+            content,
+            stop_reason: Some(stop_reason),
+            usage: TokenUsage::default(),
+        })
+    }
+
+    async fn complete_stream(
+        &self,
+        _request: CompletionRequest,
+    ) -> Result<
+        Pin<Box<dyn futures::Stream<Item = Result<StreamingChunk, ProviderError>> + Send>>,
+        ProviderError,
+    > {
+        Err(ProviderError::Config(
+            "Streaming not supported by mock provider".to_string(),
+        ))
+    }
+}
@@ -0,0 +1,414 @@
+//! Golden-transcript regression tests for agents.
+//!
+//! A scenario file (JSON5) lists the user messages of a conversation, the
+//! completions a [`MockProvider`] answers each one with, canned results for
+//! the tools the model calls, and assertions on each reply. Running it
+//! drives a real [`AgentRuntime`] — prompt, templates, tool policy — so
+//! prompt and config changes can be checked in CI without calling a model.
+//!
+//! ```json5
+//! {
+//!   name: "order status",
+//!   agent: "support",
+//!   toolResults: { lookup_order: "Order 42: shipped" },
+//!   turns: [
+//!     {
+//!       user: "Where is order 42?",
+//!       completions: [
+//!         { toolCalls: [{ name: "lookup_order", input: { id: 42 } }] },
+//!         "Order 42 has shipped.",
+//!       ],
+//!       expect: { contains: ["shipped"], tools: ["lookup_order"] },
+//!     },
+//!   ],
+//! }
+//! ```
+
+mod mock;
+
+pub use mock::{MockCompletion, MockProvider, MockToolCall};
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use openclaw_core::config::AgentOverrides;
+use openclaw_core::events::{DryRunMode, SessionEvent, SessionEventKind, SessionProjection};
+use openclaw_core::types::{AgentId, ChannelId, SessionKey};
+
+use crate::runtime::{AgentContext, AgentReply, AgentRuntime};
+
+/// File extensions recognised as scenario files.
+pub const SCENARIO_EXTENSIONS: &[&str] = &["json5", "json"];
+
+/// Harness errors.
+#[derive(Error, Debug)]
+pub enum HarnessError {
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Scenario file could not be parsed.
+    #[error("Invalid scenario {}: {message}", path.display())]
+    Invalid {
+        /// Scenario file.
+        path: PathBuf,
+        /// Parser error.
+        message: String,
+    },
+}
+
+/// A scripted conversation and what each reply must look like.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scenario {
+    /// Scenario name; the file name if omitted.
+    #[serde(default)]
+    pub name: String,
+    /// Agent whose configuration the scenario runs against.
+    #[serde(default = "default_agent")]
+    pub agent: String,
+    /// Model parameters changed for this scenario.
+    #[serde(default, flatten)]
+    pub overrides: AgentOverrides,
+    /// Result returned for each call to a tool, by tool name.
+    #[serde(default)]
+    pub tool_results: HashMap<String, serde_json::Value>,
+    /// User messages, in order.
+    pub turns: Vec<ScenarioTurn>,
+}
+
+fn default_agent() -> String {
+    "default".to_string()
+}
+
+/// One user message in a scenario.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioTurn {
+    /// Message from the user.
+    pub user: String,
+    /// Model completions for this turn: one, plus one after each round of
+    /// tool calls.
+    pub completions: Vec<MockCompletion>,
+    /// Assertions on the reply.
+    #[serde(default)]
+    pub expect: Expectation,
+}
+
+/// Assertions on an agent reply.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Expectation {
+    /// Exact reply text.
+    pub equals: Option<String>,
+    /// Text the reply must contain.
+    pub contains: Vec<String>,
+    /// Text the reply must not contain.
+    pub excludes: Vec<String>,
+    /// Tools the model must have called.
+    pub tools: Vec<String>,
+    /// Tools the agent's policy must have refused.
+    pub denied: Vec<String>,
+}
+
+impl Expectation {
+    /// Assertions `reply` fails, as messages.
+    #[must_use]
+    pub fn check(&self, reply: &AgentReply) -> Vec<String> {
+        let mut failures = Vec::new();
+        if let Some(expected) = self.equals.as_ref().filter(|e| **e != reply.content) {
+            failures.push(format!(
+                "expected reply {expected:?}, got {:?}",
+                reply.content
+            ));
+        }
+        for text in &self.contains {
+            if !reply.content.contains(text.as_str()) {
+                failures.push(format!("reply does not contain {text:?}"));
+            }
+        }
+        for text in &self.excludes {
+            if reply.content.contains(text.as_str()) {
+                failures.push(format!("reply contains {text:?}"));
+            }
+        }
+        for tool in &self.tools {
+            if !reply.planned_tools.iter().any(|call| call.name == *tool) {
+                failures.push(format!("tool '{tool}' was not called"));
+            }
+        }
+        for tool in &self.denied {
+            if !reply.denied_tools.iter().any(|call| call.name == *tool) {
+                failures.push(format!("tool '{tool}' was not denied"));
+            }
+        }
+        failures
+    }
+}
+
+/// Outcome of one scenario turn.
+#[derive(Debug, Clone, Serialize)]
+pub struct TurnReport {
+    /// Message from the user.
+    pub user: String,
+    /// Agent reply; `None` if the turn failed to run.
+    pub reply: Option<String>,
+    /// Failed assertions and errors.
+    pub failures: Vec<String>,
+}
+
+/// Outcome of a scenario.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioReport {
+    /// Scenario name.
+    pub name: String,
+    /// Turns run, in order. A turn that fails to run ends the scenario.
+    pub turns: Vec<TurnReport>,
+}
+
+impl ScenarioReport {
+    /// Whether every turn ran and met its assertions.
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.turns.iter().all(|turn| turn.failures.is_empty())
+    }
+}
+
+impl Scenario {
+    /// Parse a scenario from JSON5.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the source is not a valid scenario.
+    pub fn parse(source: &str) -> Result<Self, HarnessError> {
+        json5::from_str(source).map_err(|e| HarnessError::Invalid {
+            path: PathBuf::new(),
+            message: e.to_string(),
+        })
+    }
+
+    /// Load a scenario file. The name defaults to the file stem.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self, HarnessError> {
+        let source = std::fs::read_to_string(path)?;
+        let mut scenario = Self::parse(&source).map_err(|e| match e {
+            HarnessError::Invalid { message, .. } => HarnessError::Invalid {
+                path: path.to_path_buf(),
+                message,
+            },
+            e @ HarnessError::Io(_) => e,
+        })?;
+        if scenario.name.is_empty() {
+            scenario.name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+        }
+        Ok(scenario)
+    }
+
+    /// Scenario files at `path`: the file itself, or those directly inside
+    /// a directory, sorted by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if `path` is missing or the directory cannot be read.
+    pub fn discover(path: &Path) -> Result<Vec<PathBuf>, HarnessError> {
+        if path.is_file() {
+            return Ok(vec![path.to_path_buf()]);
+        }
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            let file = entry?.path();
+            let is_scenario = file
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| SCENARIO_EXTENSIONS.contains(&ext));
+            if file.is_file() && is_scenario {
+                files.push(file);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Run the scenario against `runtime`, with its provider replaced by
+    /// the scenario's completions.
+    ///
+    /// Tool calls are simulated: no tool runs, and each call is answered
+    /// from `toolResults`. Every turn must use all of its completions.
+    pub async fn run(&self, runtime: &AgentRuntime) -> ScenarioReport {
+        let provider = Arc::new(MockProvider::default());
+        let runtime = runtime
+            .with_overrides(&self.overrides)
+            .with_provider(provider.clone())
+            .with_tool_stubs(self.tool_stubs())
+            .with_dry_run(Some(DryRunMode::Simulate));
+
+        let session_key = SessionKey::new(format!("agent:{}:test", self.agent));
+        let session = SessionProjection::new(
+            session_key.clone(),
+            self.agent.clone(),
+            ChannelId::new("test"),
+            "test".to_string(),
+        );
+        let mut ctx = AgentContext::new(
+            AgentId::new(self.agent.clone()),
+            session_key,
+            session,
+            runtime.tools().clone(),
+        );
+
+        let mut report = ScenarioReport {
+            name: self.name.clone(),
+            turns: Vec::new(),
+        };
+        for turn in &self.turns {
+            provider.push(turn.completions.iter().cloned());
+            let reply = match runtime.process(&mut ctx, &turn.user).await {
+                Ok(reply) => reply,
+                Err(e) => {
+                    report.turns.push(TurnReport {
+                        user: turn.user.clone(),
+                        reply: None,
+                        failures: vec![e.to_string()],
+                    });
+                    break;
+                }
+            };
+
+            let mut failures = turn.expect.check(&reply);
+            let unused = provider.clear();
+            if unused > 0 {
+                failures.push(format!("{unused} completion(s) were not used"));
+            }
+            self.record(&mut ctx, &turn.user, &reply);
+            report.turns.push(TurnReport {
+                user: turn.user.clone(),
+                reply: Some(reply.content),
+                failures,
+            });
+        }
+        report
+    }
+
+    fn tool_stubs(&self) -> HashMap<String, String> {
+        self.tool_results
+            .iter()
+            .map(|(name, result)| {
+                let result = result
+                    .as_str()
+                    .map_or_else(|| result.to_string(), String::from);
+                (name.clone(), result)
+            })
+            .collect()
+    }
+
+    /// Add a turn to the conversation later turns see.
+    fn record(&self, ctx: &mut AgentContext, user: &str, reply: &AgentReply) {
+        for kind in [
+            SessionEventKind::MessageReceived {
+                content: user.to_string(),
+                attachments: vec![],
+                sender_id: None,
+            },
+            SessionEventKind::AgentResponse {
+                content: reply.content.clone(),
+                model: reply.model.clone(),
+                tokens: reply.usage.clone(),
+                latency_ms: None,
+                citations: vec![],
+            },
+        ] {
+            ctx.session.apply(&SessionEvent::new(
+                ctx.session_key.clone(),
+                self.agent.clone(),
+                kind,
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openclaw_core::config::ToolPolicy;
+
+    const SCENARIO: &str = r#"{
+        agent: "support",
+        systemPrompt: "You answer order questions.",
+        toolResults: { lookup_order: "Order 42: shipped" },
+        turns: [
+            {
+                user: "Where is order 42?",
+                completions: [
+                    { toolCalls: [{ name: "lookup_order", input: { id: 42 } }] },
+                    "Order 42 has shipped.",
+                ],
+                expect: { contains: ["shipped"], tools: ["lookup_order"] },
+            },
+            {
+                user: "Delete my account",
+                completions: [
+                    { toolCalls: [{ name: "bash", input: { command: "rm -rf /" } }] },
+                    "I can't do that.",
+                ],
+                expect: { denied: ["bash"], excludes: ["Done"] },
+            },
+            {
+                user: "Thanks",
+                completions: ["Bye!", "Extra"],
+                expect: { equals: "You're welcome!" },
+            },
+        ],
+    }"#;
+
+    #[tokio::test]
+    async fn test_run_scenario() {
+        let scenario = Scenario::parse(SCENARIO).unwrap();
+        assert_eq!(
+            scenario.overrides.system_prompt.as_deref(),
+            Some("You answer order questions.")
+        );
+
+        let runtime = AgentRuntime::new(Arc::new(MockProvider::default()))
+            .with_tool_policy(ToolPolicy::new(vec!["lookup_*".to_string()], Vec::new()));
+        let report = scenario.run(&runtime).await;
+
+        assert_eq!(report.turns.len(), 3);
+        assert!(report.turns[0].failures.is_empty());
+        assert_eq!(
+            report.turns[0].reply.as_deref(),
+            Some("Order 42 has shipped.")
+        );
+        assert!(report.turns[1].failures.is_empty());
+        assert_eq!(
+            report.turns[2].failures,
+            vec![
+                "expected reply \"You're welcome!\", got \"Bye!\"".to_string(),
+                "1 completion(s) were not used".to_string(),
+            ]
+        );
+        assert!(!report.passed());
+    }
+
+    #[tokio::test]
+    async fn test_run_out_of_completions() {
+        let scenario = Scenario::parse(
+            r#"{ turns: [{ user: "Hi", completions: [{ toolCalls: [{ name: "search" }] }] }] }"#,
+        )
+        .unwrap();
+        let runtime = AgentRuntime::new(Arc::new(MockProvider::default()));
+        let report = scenario.run(&runtime).await;
+
+        assert_eq!(report.turns.len(), 1);
+        assert!(report.turns[0].reply.is_none());
+        assert!(report.turns[0].failures[0].contains("no completion left"));
+    }
+}
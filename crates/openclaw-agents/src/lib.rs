@@ -1,18 +1,42 @@
 //! # `OpenClaw` Agents
 //!
-//! Agent runtime, workflow engine, and sandboxed execution.
+//! Agent runtime, workflow engine, scheduler, workspaces, sandboxed
+//! execution, and a scenario harness for regression-testing agents.
 //!
 //! Patterns from m9m: workflow nodes, bubblewrap sandboxing.
 
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
+pub mod harness;
+pub mod notify;
+pub mod persona;
+pub mod prompts;
 pub mod runtime;
 pub mod sandbox;
+pub mod scheduler;
+pub mod skills;
 pub mod tools;
 pub mod workflow;
+pub mod workspace;
 
-pub use runtime::{AgentContext, AgentRuntime};
-pub use sandbox::{SandboxConfig, SandboxLevel, SandboxOutput, execute_sandboxed};
-pub use tools::ToolRegistry;
-pub use workflow::{Workflow, WorkflowEngine, WorkflowNode};
+pub use harness::{MockProvider, Scenario, ScenarioReport};
+pub use notify::{NotificationTemplates, NotifyError};
+pub use persona::{PersonaManifest, PersonaPack};
+pub use prompts::{PromptLibrary, PromptTemplate, PromptVariables};
+pub use runtime::{
+    AgentContext, AgentReply, AgentRuntime, AttachmentResolver, ConcurrencyLimiter,
+    ContextProvider, DeniedToolCall, HttpAttachmentResolver, SessionStateStore,
+};
+pub use sandbox::{EgressProxy, SandboxConfig, SandboxLevel, SandboxOutput, execute_sandboxed};
+pub use scheduler::{ScheduleRunner, ScheduleStateStore, ScheduledTask, Scheduler};
+pub use skills::{Skill, SkillLibrary};
+pub use tools::{
+    ApprovalGate, DelegateTool, GitTool, PeerProfileTool, RemindTool, SessionStateTool,
+    ToolRegistry, ToolSource,
+};
+pub use workflow::{
+    AgentNode, NodeRetryPolicy, Workflow, WorkflowDefinition, WorkflowEngine, WorkflowNode,
+    WorkflowRegistry,
+};
+pub use workspace::{Workspace, WorkspaceError};
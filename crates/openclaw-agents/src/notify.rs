@@ -0,0 +1,258 @@
+//! Outbound notification templates.
+//!
+//! Templates are configured under `notifications` and sent through any
+//! channel with the `notify.send` RPC method or a schedule's `template`.
+//! Bodies are rendered in the same sandbox as prompt templates, with the
+//! caller's variables over the template's defaults, plus `channel`, `chat`,
+//! `date`, and `time`.
+
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::Utc;
+use thiserror::Error;
+
+use openclaw_core::config::NotificationTemplateConfig;
+
+use crate::prompts::environment;
+
+/// Notification template errors.
+#[derive(Error, Debug)]
+pub enum NotifyError {
+    /// No template with this name.
+    #[error("Notification template not found: {0}")]
+    NotFound(String),
+
+    /// A template body does not compile.
+    #[error("Invalid notification template '{name}': {message}")]
+    Invalid {
+        /// Template name.
+        name: String,
+        /// Compiler error.
+        message: String,
+    },
+
+    /// Required variables were not supplied.
+    #[error("Notification template '{name}' is missing variable(s): {}", missing.join(", "))]
+    MissingVariables {
+        /// Template name.
+        name: String,
+        /// Variables with no value.
+        missing: Vec<String>,
+    },
+
+    /// Template failed to render.
+    #[error("Failed to render notification template '{name}': {message}")]
+    Render {
+        /// Template name.
+        name: String,
+        /// Renderer error.
+        message: String,
+    },
+}
+
+/// The configured notification templates.
+#[derive(Debug, Clone, Default)]
+pub struct NotificationTemplates {
+    templates: HashMap<String, NotificationTemplateConfig>,
+}
+
+impl NotificationTemplates {
+    /// Load templates, checking that every body compiles.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Invalid` for the first body that does not compile.
+    pub fn new(
+        templates: HashMap<String, NotificationTemplateConfig>,
+    ) -> Result<Self, NotifyError> {
+        let env = environment();
+        for (name, template) in &templates {
+            for body in std::iter::once(&template.body).chain(template.channels.values()) {
+                env.template_from_str(body)
+                    .map_err(|e| NotifyError::Invalid {
+                        name: name.clone(),
+                        message: e.to_string(),
+                    })?;
+            }
+        }
+        Ok(Self { templates })
+    }
+
+    /// Look up a template.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&NotificationTemplateConfig> {
+        self.templates.get(name)
+    }
+
+    /// Template names, sorted.
+    #[must_use]
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.templates.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Variables a template's bodies read, sorted.
+    #[must_use]
+    pub fn variables(&self, name: &str) -> BTreeSet<String> {
+        let env = environment();
+        self.templates
+            .get(name)
+            .into_iter()
+            .flat_map(|t| std::iter::once(&t.body).chain(t.channels.values()))
+            .filter_map(|body| env.template_from_str(body).ok())
+            .flat_map(|t| t.undeclared_variables(false))
+            .collect()
+    }
+
+    /// Render template `name` for `chat` on `channel`. Undefined variables
+    /// render as empty strings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` for an unknown template, `MissingVariables` if a
+    /// required variable has no value, or `Render` if rendering fails.
+    pub fn render(
+        &self,
+        name: &str,
+        channel: &str,
+        chat: &str,
+        vars: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<String, NotifyError> {
+        let template = self
+            .templates
+            .get(name)
+            .ok_or_else(|| NotifyError::NotFound(name.to_string()))?;
+
+        let now = Utc::now();
+        let mut context = serde_json::Map::new();
+        context.insert("channel".to_string(), channel.into());
+        context.insert("chat".to_string(), chat.into());
+        context.insert(
+            "date".to_string(),
+            now.format("%Y-%m-%d").to_string().into(),
+        );
+        context.insert("time".to_string(), now.format("%H:%M").to_string().into());
+        context.extend(template.defaults.clone());
+        context.extend(vars.clone());
+
+        let missing: Vec<String> = template
+            .required
+            .iter()
+            .filter(|var| context.get(*var).is_none_or(serde_json::Value::is_null))
+            .cloned()
+            .collect();
+        if !missing.is_empty() {
+            return Err(NotifyError::MissingVariables {
+                name: name.to_string(),
+                missing,
+            });
+        }
+
+        let body = template.channels.get(channel).unwrap_or(&template.body);
+        environment()
+            .render_str(body, serde_json::Value::Object(context))
+            .map_err(|e| NotifyError::Render {
+                name: name.to_string(),
+                message: e.to_string(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn templates() -> NotificationTemplates {
+        let mut config = HashMap::new();
+        config.insert(
+            "deploy".to_string(),
+            NotificationTemplateConfig {
+                description: None,
+                body: "Deployed {{ service }} {{ version }} to {{ env }}".to_string(),
+                channels: HashMap::from([(
+                    "slack".to_string(),
+                    "*{{ service }}* `{{ version }}` is live on {{ chat }}".to_string(),
+                )]),
+                required: vec!["service".to_string(), "version".to_string()],
+                defaults: json!({ "env": "production" }).as_object().cloned().unwrap(),
+            },
+        );
+        NotificationTemplates::new(config).unwrap()
+    }
+
+    fn vars(value: serde_json::Value) -> serde_json::Map<String, serde_json::Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_render_with_defaults_and_channel_body() {
+        let templates = templates();
+        let release = vars(json!({ "service": "api", "version": "1.2" }));
+
+        assert_eq!(
+            templates
+                .render("deploy", "telegram", "42", &release)
+                .unwrap(),
+            "Deployed api 1.2 to production"
+        );
+        assert_eq!(
+            templates
+                .render("deploy", "slack", "#ops", &release)
+                .unwrap(),
+            "*api* `1.2` is live on #ops"
+        );
+
+        let staging = vars(json!({ "service": "api", "version": "1.2", "env": "staging" }));
+        assert!(
+            templates
+                .render("deploy", "telegram", "42", &staging)
+                .unwrap()
+                .ends_with("to staging")
+        );
+    }
+
+    #[test]
+    fn test_render_errors() {
+        let templates = templates();
+        assert!(matches!(
+            templates.render("nope", "slack", "#ops", &serde_json::Map::new()),
+            Err(NotifyError::NotFound(_))
+        ));
+
+        let err = templates
+            .render(
+                "deploy",
+                "slack",
+                "#ops",
+                &vars(json!({ "service": "api" })),
+            )
+            .unwrap_err();
+        match err {
+            NotifyError::MissingVariables { missing, .. } => assert_eq!(missing, ["version"]),
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn test_invalid_body_is_rejected() {
+        let config = HashMap::from([(
+            "bad".to_string(),
+            NotificationTemplateConfig {
+                body: "{% if %}".to_string(),
+                ..NotificationTemplateConfig::default()
+            },
+        )]);
+        assert!(matches!(
+            NotificationTemplates::new(config),
+            Err(NotifyError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn test_variables() {
+        let names: Vec<String> = templates().variables("deploy").into_iter().collect();
+        assert_eq!(names, ["chat", "env", "service", "version"]);
+    }
+}
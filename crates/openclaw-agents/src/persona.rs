@@ -0,0 +1,431 @@
+//! Persona packs: shareable agent definitions.
+//!
+//! A pack is a `.tar.gz` archive holding a `persona.json` manifest (metadata
+//! and the agent config, including its allowlist) plus optional prompt
+//! templates under `prompts/` and skills under `skills/`. Installing a pack
+//! writes those files to the agent's directory; the caller adds the agent
+//! config to the config file.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use openclaw_core::config::AgentConfig;
+
+/// Manifest file name at the root of a pack.
+pub const MANIFEST_FILE: &str = "persona.json";
+
+/// Current pack format version.
+pub const PACK_FORMAT: u32 = 1;
+
+/// Maximum total unpacked size of a pack.
+pub const MAX_PACK_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Maximum number of files in a pack.
+pub const MAX_PACK_FILES: usize = 256;
+
+/// Directories a pack may contain besides the manifest.
+const PACK_DIRS: [&str; 2] = ["prompts", "skills"];
+
+/// Persona pack errors.
+#[derive(Error, Debug)]
+pub enum PersonaError {
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Manifest could not be parsed or written.
+    #[error("Manifest error: {0}")]
+    Manifest(#[from] serde_json::Error),
+
+    /// Pack content failed validation.
+    #[error("Invalid persona pack: {0}")]
+    Invalid(String),
+
+    /// Agent is already installed.
+    #[error("Agent '{0}' already exists")]
+    Exists(String),
+}
+
+/// Pack metadata and agent definition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonaManifest {
+    /// Pack format version.
+    #[serde(default = "default_format")]
+    pub format: u32,
+    /// Agent ID the pack installs as by default.
+    pub name: String,
+    /// Pack version.
+    pub version: String,
+    /// Short description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Author.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Agent configuration.
+    pub agent: AgentConfig,
+}
+
+const fn default_format() -> u32 {
+    PACK_FORMAT
+}
+
+/// A persona pack loaded into memory.
+#[derive(Debug, Clone)]
+pub struct PersonaPack {
+    /// Manifest.
+    pub manifest: PersonaManifest,
+    /// Prompt and skill files by relative path (e.g., `prompts/greeting.md`).
+    pub files: BTreeMap<String, Vec<u8>>,
+}
+
+impl PersonaPack {
+    /// Create a pack from an agent config with no extra files.
+    #[must_use]
+    pub fn new(name: impl Into<String>, version: impl Into<String>, agent: AgentConfig) -> Self {
+        Self {
+            manifest: PersonaManifest {
+                format: PACK_FORMAT,
+                name: name.into(),
+                version: version.into(),
+                description: None,
+                author: None,
+                agent,
+            },
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Build a pack from an installed agent.
+    ///
+    /// `agent` should be the resolved config; `extends` is dropped so the
+    /// pack is self-contained. Files are collected from `agent_dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the agent directory cannot be read or the result
+    /// is not a valid pack.
+    pub fn export(
+        name: &str,
+        version: &str,
+        agent: &AgentConfig,
+        agent_dir: &Path,
+    ) -> Result<Self, PersonaError> {
+        let mut agent = agent.clone();
+        agent.extends = None;
+        let mut pack = Self::new(name, version, agent);
+
+        for dir in PACK_DIRS {
+            collect_files(&agent_dir.join(dir), dir, &mut pack.files)?;
+        }
+
+        pack.validate()?;
+        Ok(pack)
+    }
+
+    /// Read and validate a pack archive.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the archive cannot be read or fails validation.
+    pub fn read(path: &Path) -> Result<Self, PersonaError> {
+        let mut archive = tar::Archive::new(GzDecoder::new(File::open(path)?));
+        let mut manifest = None;
+        let mut files = BTreeMap::new();
+        let mut total: u64 = 0;
+
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let entry_type = entry.header().entry_type();
+            let raw_path = entry.path()?.to_path_buf();
+            let path = normalize_entry_path(&raw_path)?;
+
+            if entry_type.is_dir() {
+                continue;
+            }
+            if !entry_type.is_file() {
+                return Err(PersonaError::Invalid(format!(
+                    "{path}: only regular files are allowed"
+                )));
+            }
+
+            total += entry.header().size()?;
+            if total > MAX_PACK_SIZE {
+                return Err(PersonaError::Invalid(format!(
+                    "unpacked size exceeds {MAX_PACK_SIZE} bytes"
+                )));
+            }
+
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)?;
+
+            if path == MANIFEST_FILE {
+                manifest = Some(serde_json::from_slice::<PersonaManifest>(&data)?);
+            } else {
+                files.insert(path, data);
+            }
+        }
+
+        let manifest =
+            manifest.ok_or_else(|| PersonaError::Invalid(format!("missing {MANIFEST_FILE}")))?;
+        let pack = Self { manifest, files };
+        pack.validate()?;
+        Ok(pack)
+    }
+
+    /// Write the pack as a `.tar.gz` archive.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be written.
+    pub fn write(&self, path: &Path) -> Result<(), PersonaError> {
+        let encoder = GzEncoder::new(File::create(path)?, Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+
+        let manifest = serde_json::to_vec_pretty(&self.manifest)?;
+        append_file(&mut builder, MANIFEST_FILE, &manifest)?;
+        for (name, data) in &self.files {
+            append_file(&mut builder, name, data)?;
+        }
+
+        builder.into_inner()?.finish()?;
+        Ok(())
+    }
+
+    /// Check the manifest and file layout.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Invalid` describing the first problem found.
+    pub fn validate(&self) -> Result<(), PersonaError> {
+        let manifest = &self.manifest;
+
+        if manifest.format > PACK_FORMAT {
+            return Err(PersonaError::Invalid(format!(
+                "format {} is newer than supported ({PACK_FORMAT})",
+                manifest.format
+            )));
+        }
+        validate_agent_id(&manifest.name)?;
+        if manifest.version.trim().is_empty() {
+            return Err(PersonaError::Invalid("version is empty".to_string()));
+        }
+        if manifest.agent.model.is_empty() {
+            return Err(PersonaError::Invalid("agent model is empty".to_string()));
+        }
+        if manifest.agent.extends.is_some() {
+            return Err(PersonaError::Invalid(
+                "agent must not use 'extends'; export the resolved config".to_string(),
+            ));
+        }
+
+        if self.files.len() > MAX_PACK_FILES {
+            return Err(PersonaError::Invalid(format!(
+                "more than {MAX_PACK_FILES} files"
+            )));
+        }
+        for name in self.files.keys() {
+            let top = name.split('/').next().unwrap_or_default();
+            if !PACK_DIRS.contains(&top) || !name.contains('/') {
+                return Err(PersonaError::Invalid(format!(
+                    "{name}: files must be under prompts/ or skills/"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Install prompt and skill files under `agents_dir/<id>`.
+    ///
+    /// Existing prompts and skills are replaced only if `overwrite` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Exists` if the agent already has files and `overwrite` is
+    /// false, or an error if the files cannot be written.
+    pub fn install(
+        &self,
+        agents_dir: &Path,
+        id: &str,
+        overwrite: bool,
+    ) -> Result<PathBuf, PersonaError> {
+        validate_agent_id(id)?;
+        let target = agents_dir.join(id);
+
+        let existing: Vec<PathBuf> = PACK_DIRS
+            .iter()
+            .map(|d| target.join(d))
+            .filter(|d| d.exists())
+            .collect();
+        if !existing.is_empty() {
+            if !overwrite {
+                return Err(PersonaError::Exists(id.to_string()));
+            }
+            for dir in existing {
+                std::fs::remove_dir_all(dir)?;
+            }
+        }
+
+        std::fs::create_dir_all(&target)?;
+        for (name, data) in &self.files {
+            let path = target.join(name);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, data)?;
+        }
+        Ok(target)
+    }
+}
+
+/// Agent IDs become directory names, so keep them to a safe alphabet.
+fn validate_agent_id(id: &str) -> Result<(), PersonaError> {
+    let valid = !id.is_empty()
+        && id.len() <= 64
+        && !id.starts_with('.')
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(PersonaError::Invalid(format!("invalid agent name '{id}'")))
+    }
+}
+
+/// Turn an archive path into a `/`-separated relative path, rejecting
+/// absolute paths and traversal.
+fn normalize_entry_path(path: &Path) -> Result<String, PersonaError> {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+            Component::CurDir => {}
+            _ => {
+                return Err(PersonaError::Invalid(format!(
+                    "{}: unsafe path",
+                    path.display()
+                )));
+            }
+        }
+    }
+    Ok(parts.join("/"))
+}
+
+fn collect_files(
+    dir: &Path,
+    prefix: &str,
+    files: &mut BTreeMap<String, Vec<u8>>,
+) -> Result<(), PersonaError> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = format!("{prefix}/{}", entry.file_name().to_string_lossy());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(&entry.path(), &name, files)?;
+        } else if file_type.is_file() {
+            files.insert(name, std::fs::read(entry.path())?);
+        }
+    }
+    Ok(())
+}
+
+fn append_file<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    name: &str,
+    data: &[u8],
+) -> Result<(), PersonaError> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(u64::try_from(chrono::Utc::now().timestamp()).unwrap_or_default());
+    header.set_cksum();
+    builder.append_data(&mut header, name, data)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn sample_pack() -> PersonaPack {
+        let mut pack = PersonaPack::new(
+            "support",
+            "1.0.0",
+            AgentConfig {
+                system_prompt: Some("You help customers.".to_string()),
+                ..AgentConfig::default()
+            },
+        );
+        pack.files
+            .insert("prompts/greeting.md".to_string(), b"Hello!".to_vec());
+        pack.files
+            .insert("skills/refunds/SKILL.md".to_string(), b"# Refunds".to_vec());
+        pack
+    }
+
+    #[test]
+    fn test_roundtrip_and_install() {
+        let temp = tempdir().unwrap();
+        let archive = temp.path().join("support.tar.gz");
+        sample_pack().write(&archive).unwrap();
+
+        let pack = PersonaPack::read(&archive).unwrap();
+        assert_eq!(pack.manifest.name, "support");
+        assert_eq!(
+            pack.manifest.agent.system_prompt.as_deref(),
+            Some("You help customers.")
+        );
+        assert_eq!(pack.files.len(), 2);
+
+        let agents_dir = temp.path().join("agents");
+        let dir = pack.install(&agents_dir, "support", false).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("skills/refunds/SKILL.md")).unwrap(),
+            "# Refunds"
+        );
+        assert!(matches!(
+            pack.install(&agents_dir, "support", false),
+            Err(PersonaError::Exists(_))
+        ));
+        pack.install(&agents_dir, "support", true).unwrap();
+
+        let exported = PersonaPack::export("support", "1.0.1", &pack.manifest.agent, &dir).unwrap();
+        assert_eq!(exported.files, pack.files);
+    }
+
+    #[test]
+    fn test_validation() {
+        let mut pack = sample_pack();
+        pack.files.insert("bin/run.sh".to_string(), Vec::new());
+        assert!(pack.validate().is_err());
+
+        let mut pack = sample_pack();
+        pack.manifest.name = "../evil".to_string();
+        assert!(pack.validate().is_err());
+
+        let mut pack = sample_pack();
+        pack.manifest.agent.extends = Some("base".to_string());
+        assert!(pack.validate().is_err());
+
+        assert!(normalize_entry_path(Path::new("../persona.json")).is_err());
+        assert_eq!(
+            normalize_entry_path(Path::new("./prompts/a.md")).unwrap(),
+            "prompts/a.md"
+        );
+    }
+}
@@ -0,0 +1,440 @@
+//! System prompt templates.
+//!
+//! A template is a `<name>.md` file in `~/.openclaw/prompts/`: optional
+//! front matter (as in skills) followed by a minijinja template. An agent
+//! uses one by setting its system prompt to `template:<name>`; the template
+//! is rendered for every request with variables such as `{{ user_name }}`,
+//! `{{ channel }}`, and `{{ date }}`.
+//!
+//! Templates are rendered without filesystem access and with a fuel limit,
+//! so a template cannot include other files or loop forever.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use minijinja::Environment;
+use serde::Serialize;
+use thiserror::Error;
+
+use openclaw_core::Config;
+use openclaw_core::validation::limits::MAX_PROMPT_TEMPLATE_SIZE;
+
+use crate::runtime::AgentContext;
+use crate::skills::parse_front_matter;
+
+/// Prefix that marks a system prompt as a template reference.
+pub const TEMPLATE_PREFIX: &str = "template:";
+
+/// Template file extension.
+pub const TEMPLATE_EXTENSION: &str = "md";
+
+/// Names of the variables every template can use.
+pub const BUILT_IN_VARIABLES: &[&str] = &[
+    "user_name",
+    "channel",
+    "date",
+    "time",
+    "agent_id",
+    "peer_id",
+    "session_key",
+];
+
+/// Rendering budget; each template instruction costs one unit.
+const RENDER_FUEL: u64 = 50_000;
+
+/// Prompt template errors.
+#[derive(Error, Debug)]
+pub enum PromptError {
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// No template with this name.
+    #[error("Prompt template not found: {0}")]
+    NotFound(String),
+
+    /// Template file exceeds `MAX_PROMPT_TEMPLATE_SIZE`.
+    #[error("{} is {size} bytes (limit {})", path.display(), MAX_PROMPT_TEMPLATE_SIZE)]
+    TooLarge {
+        /// Template file.
+        path: PathBuf,
+        /// File size.
+        size: u64,
+    },
+
+    /// Template file could not be parsed.
+    #[error("Invalid template: {0}")]
+    Invalid(String),
+
+    /// Template failed to render.
+    #[error("Failed to render template '{name}': {message}")]
+    Render {
+        /// Template name.
+        name: String,
+        /// Renderer error.
+        message: String,
+    },
+}
+
+/// Variables available to a template.
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptVariables {
+    /// Name of the person the agent is talking to.
+    pub user_name: String,
+    /// Channel the conversation is on.
+    pub channel: String,
+    /// Current UTC date (`YYYY-MM-DD`).
+    pub date: String,
+    /// Current UTC time (`HH:MM`).
+    pub time: String,
+    /// Agent ID.
+    pub agent_id: String,
+    /// Peer ID on the channel.
+    pub peer_id: String,
+    /// Session key.
+    pub session_key: String,
+    /// Other values, e.g. from [`AgentContext::values`].
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+impl PromptVariables {
+    /// Variables for a turn in `ctx`.
+    ///
+    /// `user_name` comes from a `user_name` context value or session state
+    /// entry, falling back to the peer ID. Other context values are passed
+    /// through under their own names.
+    #[must_use]
+    pub fn from_context(ctx: &AgentContext) -> Self {
+        let now = Utc::now();
+        let mut variables = Self {
+            user_name: ctx.session.peer_id.clone(),
+            channel: ctx.session.channel.to_string(),
+            date: now.format("%Y-%m-%d").to_string(),
+            time: now.format("%H:%M").to_string(),
+            agent_id: ctx.agent_id.to_string(),
+            peer_id: ctx.session.peer_id.clone(),
+            session_key: ctx.session_key.to_string(),
+            extra: BTreeMap::new(),
+        };
+        if let Some(serde_json::Value::String(name)) = ctx.session.custom_state.get("user_name") {
+            variables.user_name.clone_from(name);
+        }
+        for (key, value) in &ctx.values {
+            variables.set(key, value.clone());
+        }
+        variables
+    }
+
+    /// Placeholder variables for previewing a template.
+    #[must_use]
+    pub fn preview() -> Self {
+        let now = Utc::now();
+        Self {
+            user_name: "Ada".to_string(),
+            channel: "cli".to_string(),
+            date: now.format("%Y-%m-%d").to_string(),
+            time: now.format("%H:%M").to_string(),
+            agent_id: "default".to_string(),
+            peer_id: "preview".to_string(),
+            session_key: "agent:default:cli:preview".to_string(),
+            extra: BTreeMap::new(),
+        }
+    }
+
+    /// Set a variable. Built-in variables take the value's string form.
+    pub fn set(&mut self, key: &str, value: serde_json::Value) {
+        let field = match key {
+            "user_name" => &mut self.user_name,
+            "channel" => &mut self.channel,
+            "date" => &mut self.date,
+            "time" => &mut self.time,
+            "agent_id" => &mut self.agent_id,
+            "peer_id" => &mut self.peer_id,
+            "session_key" => &mut self.session_key,
+            _ => {
+                self.extra.insert(key.to_string(), value);
+                return;
+            }
+        };
+        *field = match value {
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        };
+    }
+
+    /// Whether `name` has a value.
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        BUILT_IN_VARIABLES.contains(&name) || self.extra.contains_key(name)
+    }
+}
+
+/// A loaded prompt template.
+#[derive(Debug, Clone)]
+pub struct PromptTemplate {
+    /// Template name (its file stem).
+    pub name: String,
+    /// One-line description from front matter.
+    pub description: Option<String>,
+    /// Template source (the file after the front matter).
+    pub body: String,
+    /// Template file path.
+    pub path: PathBuf,
+}
+
+impl PromptTemplate {
+    /// Load the template at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TooLarge` if the file exceeds `MAX_PROMPT_TEMPLATE_SIZE`,
+    /// or `Invalid` if it is not UTF-8, its front matter is unterminated,
+    /// or the template does not compile.
+    pub fn load(path: &Path) -> Result<Self, PromptError> {
+        let name = path
+            .file_stem()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| PromptError::Invalid(format!("bad template file {}", path.display())))?
+            .to_string();
+
+        let size = std::fs::metadata(path)?.len();
+        if size > MAX_PROMPT_TEMPLATE_SIZE as u64 {
+            return Err(PromptError::TooLarge {
+                path: path.to_path_buf(),
+                size,
+            });
+        }
+        let content = String::from_utf8(std::fs::read(path)?)
+            .map_err(|_| PromptError::Invalid(format!("{} is not UTF-8", path.display())))?;
+
+        let (mut metadata, body) = parse_front_matter(&content).ok_or_else(|| {
+            PromptError::Invalid(format!("{}: unterminated front matter", path.display()))
+        })?;
+
+        Self::from_source(name, metadata.remove("description"), body.trim(), path)
+    }
+
+    /// Create a template from source, checking that it compiles.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Invalid` if the template does not compile.
+    pub fn from_source(
+        name: impl Into<String>,
+        description: Option<String>,
+        body: impl Into<String>,
+        path: impl Into<PathBuf>,
+    ) -> Result<Self, PromptError> {
+        let template = Self {
+            name: name.into(),
+            description,
+            body: body.into(),
+            path: path.into(),
+        };
+        environment()
+            .template_from_str(&template.body)
+            .map_err(|e| PromptError::Invalid(format!("{}: {e}", template.name)))?;
+        Ok(template)
+    }
+
+    /// Render the template. Undefined variables render as empty strings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Render` if rendering fails or runs out of fuel.
+    pub fn render(&self, variables: &PromptVariables) -> Result<String, PromptError> {
+        environment()
+            .render_str(&self.body, variables)
+            .map_err(|e| PromptError::Render {
+                name: self.name.clone(),
+                message: e.to_string(),
+            })
+    }
+
+    /// Top-level variables the template reads without defining them.
+    #[must_use]
+    pub fn variables(&self) -> BTreeSet<String> {
+        let env = environment();
+        env.template_from_str(&self.body)
+            .map(|t| t.undeclared_variables(false).into_iter().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Sandboxed renderer: no loader, so `include` and `import` fail.
+pub(crate) fn environment() -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_fuel(Some(RENDER_FUEL));
+    env
+}
+
+/// Name of the template a system prompt refers to, if it is a reference.
+#[must_use]
+pub fn template_name(system_prompt: &str) -> Option<&str> {
+    system_prompt
+        .strip_prefix(TEMPLATE_PREFIX)
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+}
+
+/// Templates discovered in a directory.
+#[derive(Debug, Default)]
+pub struct PromptLibrary {
+    templates: BTreeMap<String, PromptTemplate>,
+    errors: Vec<(PathBuf, PromptError)>,
+}
+
+impl PromptLibrary {
+    /// Discover `*.md` templates in `dir`. Templates that fail to load are
+    /// recorded in [`PromptLibrary::errors`].
+    #[must_use]
+    pub fn discover(dir: &Path) -> Self {
+        let mut library = Self::default();
+
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return library;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == TEMPLATE_EXTENSION))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            match PromptTemplate::load(&path) {
+                Ok(template) => {
+                    library.templates.insert(template.name.clone(), template);
+                }
+                Err(e) => library.errors.push((path, e)),
+            }
+        }
+        library
+    }
+
+    /// Templates in the shared `~/.openclaw/prompts/` directory.
+    #[must_use]
+    pub fn load_default() -> Self {
+        Self::discover(&Config::prompts_dir())
+    }
+
+    /// Look up a template by name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&PromptTemplate> {
+        self.templates.get(name)
+    }
+
+    /// All templates, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = &PromptTemplate> {
+        self.templates.values()
+    }
+
+    /// Template files that failed to load.
+    #[must_use]
+    pub fn errors(&self) -> &[(PathBuf, PromptError)] {
+        &self.errors
+    }
+
+    /// Template a configured system prompt refers to.
+    ///
+    /// Returns `Ok(None)` for a plain prompt.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if the prompt names a template that is not
+    /// installed.
+    pub fn resolve(&self, system_prompt: &str) -> Result<Option<&PromptTemplate>, PromptError> {
+        template_name(system_prompt)
+            .map(|name| {
+                self.get(name)
+                    .ok_or_else(|| PromptError::NotFound(name.to_string()))
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_render() {
+        let template = PromptTemplate::from_source(
+            "support-bot",
+            None,
+            "You are helping {{ user_name }} on {{ channel }}.{% if plan %} Plan: {{ plan }}.{% endif %}{{ missing }}",
+            "support-bot.md",
+        )
+        .unwrap();
+
+        let mut variables = PromptVariables::preview();
+        assert_eq!(
+            template.render(&variables).unwrap(),
+            "You are helping Ada on cli."
+        );
+
+        variables.set("plan", serde_json::json!("pro"));
+        variables.set("channel", serde_json::json!("telegram"));
+        assert_eq!(
+            template.render(&variables).unwrap(),
+            "You are helping Ada on telegram. Plan: pro."
+        );
+
+        let names: Vec<String> = template.variables().into_iter().collect();
+        assert_eq!(names, ["channel", "missing", "plan", "user_name"]);
+    }
+
+    #[test]
+    fn test_template_is_sandboxed() {
+        assert!(matches!(
+            PromptTemplate::from_source("bad", None, "{% if %}", "bad.md"),
+            Err(PromptError::Invalid(_))
+        ));
+
+        let include =
+            PromptTemplate::from_source("inc", None, "{% include 'other.md' %}", "inc.md").unwrap();
+        assert!(include.render(&PromptVariables::preview()).is_err());
+
+        let looping = PromptTemplate::from_source(
+            "loop",
+            None,
+            "{% for i in range(10000) %}{% for j in range(10000) %}x{% endfor %}{% endfor %}",
+            "loop.md",
+        )
+        .unwrap();
+        assert!(matches!(
+            looping.render(&PromptVariables::preview()),
+            Err(PromptError::Render { .. })
+        ));
+    }
+
+    #[test]
+    fn test_library() {
+        let temp = tempdir().unwrap();
+        std::fs::write(
+            temp.path().join("support-bot.md"),
+            "---\ndescription: Customer support\n---\nHello {{ user_name }}.\n",
+        )
+        .unwrap();
+        std::fs::write(temp.path().join("notes.txt"), "ignored").unwrap();
+        std::fs::write(temp.path().join("broken.md"), "{% endif %}").unwrap();
+
+        let library = PromptLibrary::discover(temp.path());
+        let names: Vec<&str> = library.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["support-bot"]);
+        assert_eq!(library.errors().len(), 1);
+
+        let template = library.resolve("template:support-bot").unwrap().unwrap();
+        assert_eq!(template.description.as_deref(), Some("Customer support"));
+        assert_eq!(template.body, "Hello {{ user_name }}.");
+
+        assert!(library.resolve("You are helpful.").unwrap().is_none());
+        assert!(matches!(
+            library.resolve("template:missing"),
+            Err(PromptError::NotFound(_))
+        ));
+    }
+}
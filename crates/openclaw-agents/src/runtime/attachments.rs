@@ -0,0 +1,225 @@
+//! Loading inbound attachments for the model.
+//!
+//! Channels hand the runtime attachment references (URLs, data URIs);
+//! an [`AttachmentResolver`] turns them into bytes for inline delivery.
+
+use async_trait::async_trait;
+use base64::Engine;
+
+use openclaw_core::types::Attachment;
+
+/// Errors loading an attachment.
+#[derive(Debug, thiserror::Error)]
+pub enum AttachmentError {
+    /// Attachment is larger than allowed.
+    #[error("{name} exceeds {max} byte limit")]
+    TooLarge {
+        /// File name or URL.
+        name: String,
+        /// Byte limit.
+        max: u64,
+    },
+
+    /// Attachment could not be fetched.
+    #[error("Failed to fetch {0}")]
+    Fetch(String),
+
+    /// Attachment reference or type is not supported.
+    #[error("Unsupported attachment: {0}")]
+    Unsupported(String),
+}
+
+/// Bytes of a loaded attachment.
+#[derive(Debug, Clone)]
+pub struct ResolvedAttachment {
+    /// File contents.
+    pub data: Vec<u8>,
+    /// MIME type reported by the source, if any.
+    pub mime_type: Option<String>,
+}
+
+/// Loads attachment contents.
+#[async_trait]
+pub trait AttachmentResolver: Send + Sync {
+    /// Load `attachment`, failing if it exceeds `max_bytes`.
+    async fn resolve(
+        &self,
+        attachment: &Attachment,
+        max_bytes: u64,
+    ) -> Result<ResolvedAttachment, AttachmentError>;
+}
+
+/// Resolves `http(s)://` URLs and `data:` URIs.
+///
+/// Local paths are rejected so a message cannot make the gateway read its
+/// own files.
+#[derive(Debug, Clone, Default)]
+pub struct HttpAttachmentResolver {
+    client: reqwest::Client,
+}
+
+impl HttpAttachmentResolver {
+    /// Create a resolver using `client`.
+    #[must_use]
+    pub const fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+
+    async fn download(
+        &self,
+        attachment: &Attachment,
+        max_bytes: u64,
+    ) -> Result<ResolvedAttachment, AttachmentError> {
+        let fetch_error =
+            |e: reqwest::Error| AttachmentError::Fetch(format!("{}: {e}", name(attachment)));
+
+        let mut response = self
+            .client
+            .get(&attachment.url)
+            .send()
+            .await
+            .map_err(fetch_error)?;
+        if !response.status().is_success() {
+            return Err(AttachmentError::Fetch(format!(
+                "{}: {}",
+                name(attachment),
+                response.status()
+            )));
+        }
+        if response.content_length().is_some_and(|len| len > max_bytes) {
+            return Err(too_large(attachment, max_bytes));
+        }
+
+        let mime_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(';').next())
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty() && v != "application/octet-stream");
+
+        let mut data = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(fetch_error)? {
+            if (data.len() + chunk.len()) as u64 > max_bytes {
+                return Err(too_large(attachment, max_bytes));
+            }
+            data.extend_from_slice(&chunk);
+        }
+
+        Ok(ResolvedAttachment { data, mime_type })
+    }
+}
+
+#[async_trait]
+impl AttachmentResolver for HttpAttachmentResolver {
+    async fn resolve(
+        &self,
+        attachment: &Attachment,
+        max_bytes: u64,
+    ) -> Result<ResolvedAttachment, AttachmentError> {
+        if attachment.size.is_some_and(|size| size > max_bytes) {
+            return Err(too_large(attachment, max_bytes));
+        }
+
+        if let Some(uri) = attachment.url.strip_prefix("data:") {
+            return decode_data_uri(attachment, uri, max_bytes);
+        }
+        if attachment.url.starts_with("http://") || attachment.url.starts_with("https://") {
+            return self.download(attachment, max_bytes).await;
+        }
+        Err(AttachmentError::Unsupported(name(attachment)))
+    }
+}
+
+/// Decode the part of a `data:` URI after the scheme.
+fn decode_data_uri(
+    attachment: &Attachment,
+    uri: &str,
+    max_bytes: u64,
+) -> Result<ResolvedAttachment, AttachmentError> {
+    let (header, payload) = uri
+        .split_once(',')
+        .ok_or_else(|| AttachmentError::Unsupported("malformed data URI".to_string()))?;
+    let Some(mime_type) = header.strip_suffix(";base64") else {
+        return Err(AttachmentError::Unsupported(
+            "data URI is not base64-encoded".to_string(),
+        ));
+    };
+
+    // Base64 expands by 4/3; check before decoding
+    if payload.len() as u64 / 4 * 3 > max_bytes {
+        return Err(too_large(attachment, max_bytes));
+    }
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(payload)
+        .map_err(|e| AttachmentError::Unsupported(format!("invalid data URI: {e}")))?;
+
+    Ok(ResolvedAttachment {
+        data,
+        mime_type: Some(mime_type.to_string()).filter(|m| !m.is_empty()),
+    })
+}
+
+fn name(attachment: &Attachment) -> String {
+    if let Some(filename) = &attachment.filename {
+        return filename.clone();
+    }
+    if attachment.url.starts_with("data:") {
+        return "inline attachment".to_string();
+    }
+    attachment.url.clone()
+}
+
+fn too_large(attachment: &Attachment, max: u64) -> AttachmentError {
+    AttachmentError::TooLarge {
+        name: name(attachment),
+        max,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openclaw_core::types::AttachmentKind;
+
+    fn image(url: &str) -> Attachment {
+        Attachment {
+            kind: AttachmentKind::Image,
+            url: url.to_string(),
+            mime_type: None,
+            filename: None,
+            size: None,
+            thumbnail_url: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_data_uri() {
+        let resolver = HttpAttachmentResolver::default();
+
+        let loaded = resolver
+            .resolve(&image("data:image/png;base64,aGVsbG8="), 1024)
+            .await
+            .unwrap();
+        assert_eq!(loaded.data, b"hello");
+        assert_eq!(loaded.mime_type.as_deref(), Some("image/png"));
+
+        assert!(matches!(
+            resolver
+                .resolve(&image("data:image/png;base64,aGVsbG8="), 2)
+                .await,
+            Err(AttachmentError::TooLarge { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_local_paths_rejected() {
+        let resolver = HttpAttachmentResolver::default();
+        for url in ["/etc/passwd", "file:///etc/passwd"] {
+            assert!(matches!(
+                resolver.resolve(&image(url), 1024).await,
+                Err(AttachmentError::Unsupported(_))
+            ));
+        }
+    }
+}
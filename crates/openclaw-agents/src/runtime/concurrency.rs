@@ -0,0 +1,177 @@
+//! Per-agent concurrency limits.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use serde::Serialize;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use openclaw_core::config::AgentConcurrencyConfig;
+
+/// Concurrency counters for one agent.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConcurrencyStats {
+    /// Maximum simultaneous provider calls, if limited.
+    pub max_concurrent: Option<usize>,
+    /// Maximum queued messages.
+    pub max_queued: usize,
+    /// Provider calls in flight.
+    pub active: usize,
+    /// Messages waiting for a slot.
+    pub queued: usize,
+    /// Messages rejected because the queue was full.
+    pub rejected: u64,
+}
+
+/// Bounds simultaneous provider calls and the queue waiting for them.
+#[derive(Debug)]
+pub struct ConcurrencyLimiter {
+    semaphore: Option<Arc<Semaphore>>,
+    max_concurrent: usize,
+    max_queued: usize,
+    busy_message: String,
+    queued: AtomicUsize,
+    rejected: AtomicU64,
+}
+
+/// Slot held for the duration of a provider call.
+#[derive(Debug)]
+pub struct ConcurrencyPermit {
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl ConcurrencyLimiter {
+    /// Create a limiter allowing `max_concurrent` calls and `max_queued` waiters.
+    #[must_use]
+    pub fn new(max_concurrent: usize, max_queued: usize) -> Self {
+        Self {
+            semaphore: Some(Arc::new(Semaphore::new(max_concurrent.max(1)))),
+            max_concurrent: max_concurrent.max(1),
+            max_queued,
+            ..Self::unlimited()
+        }
+    }
+
+    /// Create a limiter that never blocks.
+    #[must_use]
+    pub fn unlimited() -> Self {
+        Self {
+            semaphore: None,
+            max_concurrent: 0,
+            max_queued: 0,
+            busy_message: AgentConcurrencyConfig::default().busy_message,
+            queued: AtomicUsize::new(0),
+            rejected: AtomicU64::new(0),
+        }
+    }
+
+    /// Create a limiter from an agent's config.
+    #[must_use]
+    pub fn from_config(config: &AgentConcurrencyConfig) -> Self {
+        let limiter = config.max_concurrent.map_or_else(Self::unlimited, |n| {
+            Self::new(n as usize, config.max_queued as usize)
+        });
+        limiter.with_busy_message(config.busy_message.clone())
+    }
+
+    /// Set the reply sent when the queue is full.
+    #[must_use]
+    pub fn with_busy_message(mut self, message: impl Into<String>) -> Self {
+        self.busy_message = message.into();
+        self
+    }
+
+    /// Reply sent when the queue is full.
+    #[must_use]
+    pub fn busy_message(&self) -> &str {
+        &self.busy_message
+    }
+
+    /// Wait for a free slot.
+    ///
+    /// Returns `None` without waiting if every slot is taken and the queue
+    /// is full.
+    pub async fn acquire(&self) -> Option<ConcurrencyPermit> {
+        let Some(semaphore) = &self.semaphore else {
+            return Some(ConcurrencyPermit { _permit: None });
+        };
+
+        if let Ok(permit) = semaphore.clone().try_acquire_owned() {
+            return Some(ConcurrencyPermit {
+                _permit: Some(permit),
+            });
+        }
+
+        let reserved = self
+            .queued
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < self.max_queued).then_some(n + 1)
+            });
+        if reserved.is_err() {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+
+        let permit = semaphore.clone().acquire_owned().await.ok();
+        self.queued.fetch_sub(1, Ordering::AcqRel);
+        permit.map(|p| ConcurrencyPermit { _permit: Some(p) })
+    }
+
+    /// Snapshot of the counters.
+    #[must_use]
+    pub fn stats(&self) -> ConcurrencyStats {
+        ConcurrencyStats {
+            max_concurrent: self.semaphore.as_ref().map(|_| self.max_concurrent),
+            max_queued: self.max_queued,
+            active: self
+                .semaphore
+                .as_ref()
+                .map_or(0, |s| self.max_concurrent - s.available_permits()),
+            queued: self.queued.load(Ordering::Acquire),
+            rejected: self.rejected.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for ConcurrencyLimiter {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_queue_overflow() {
+        let limiter = Arc::new(ConcurrencyLimiter::new(1, 1));
+
+        let held = limiter.acquire().await.unwrap();
+        assert_eq!(limiter.stats().active, 1);
+
+        let waiter = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire().await.is_some() }
+        });
+        while limiter.stats().queued == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        // Slot taken and queue full
+        assert!(limiter.acquire().await.is_none());
+        assert_eq!(limiter.stats().rejected, 1);
+
+        drop(held);
+        assert!(waiter.await.unwrap());
+        assert_eq!(limiter.stats().queued, 0);
+    }
+
+    #[tokio::test]
+    async fn test_unlimited() {
+        let limiter = ConcurrencyLimiter::from_config(&AgentConcurrencyConfig::default());
+        let _a = limiter.acquire().await.unwrap();
+        let _b = limiter.acquire().await.unwrap();
+        assert_eq!(limiter.stats().max_concurrent, None);
+    }
+}
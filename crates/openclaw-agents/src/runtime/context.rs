@@ -0,0 +1,351 @@
+//! System prompt assembly.
+//!
+//! Each turn's system prompt is built from sections. The runtime supplies
+//! the agent's own prompt, its skills, and session state; anything else
+//! comes from [`ContextProvider`]s queried for every turn. Sections are
+//! joined in [`PromptSection`] order. Over the token budget, the lowest
+//! priority sections are shortened or dropped first.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use openclaw_core::config::PromptAssemblyConfig;
+use openclaw_core::peers::PeerProfileStore;
+
+use super::AgentContext;
+use crate::prompts::PromptVariables;
+
+/// Smallest part of a section worth keeping when it is shortened.
+const MIN_SECTION_TOKENS: usize = 32;
+
+/// Appended to a shortened section.
+const TRUNCATION_MARKER: &str = "\n[...]";
+
+/// Where a section goes in the system prompt, first to last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptSection {
+    /// The agent's configured prompt.
+    Identity,
+    /// Skill instructions.
+    Skills,
+    /// Facts recalled from earlier conversations.
+    Memory,
+    /// How to write for the conversation's channel.
+    Channel,
+    /// Current time and who the user is.
+    Profile,
+    /// Facts saved in this session.
+    State,
+}
+
+impl PromptSection {
+    /// Default priority; lower priority sections are cut first.
+    #[must_use]
+    pub const fn priority(self) -> u8 {
+        match self {
+            Self::Identity => 100,
+            Self::State => 80,
+            Self::Skills => 60,
+            Self::Channel => 40,
+            Self::Profile => 30,
+            Self::Memory => 20,
+        }
+    }
+}
+
+/// Supplies a system prompt section for each turn.
+#[async_trait]
+pub trait ContextProvider: Send + Sync {
+    /// Provider name, for logs.
+    fn name(&self) -> &str;
+
+    /// Section the text goes in.
+    fn section(&self) -> PromptSection;
+
+    /// Priority when the prompt is over budget; lower is cut first.
+    fn priority(&self) -> u8 {
+        self.section().priority()
+    }
+
+    /// Text for a turn in `ctx`, or `None` to add nothing.
+    async fn provide(&self, ctx: &AgentContext) -> Option<String>;
+}
+
+/// A section's text for one turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptPart {
+    /// Section the text goes in.
+    pub section: PromptSection,
+    /// Priority when the prompt is over budget.
+    pub priority: u8,
+    /// Where the text came from.
+    pub source: String,
+    /// Section text.
+    pub text: String,
+}
+
+impl PromptPart {
+    /// A part at its section's default priority.
+    #[must_use]
+    pub fn new(section: PromptSection, source: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            section,
+            priority: section.priority(),
+            source: source.into(),
+            text: text.into(),
+        }
+    }
+}
+
+/// Rough token count: one token per four characters.
+#[must_use]
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Join parts in section order, fitting them into `budget` tokens if set.
+///
+/// Parts are kept whole from the highest priority down; the first that
+/// does not fit is shortened to the space left, and the rest are dropped.
+/// Identity parts are never cut.
+#[must_use]
+pub fn assemble(mut parts: Vec<PromptPart>, budget: Option<usize>) -> Option<String> {
+    parts.retain(|part| !part.text.trim().is_empty());
+    if let Some(budget) = budget {
+        fit(&mut parts, budget);
+    }
+    if parts.is_empty() {
+        return None;
+    }
+    parts.sort_by_key(|part| part.section);
+    Some(
+        parts
+            .iter()
+            .map(|part| part.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    )
+}
+
+fn fit(parts: &mut Vec<PromptPart>, budget: usize) {
+    let mut order: Vec<usize> = (0..parts.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(parts[i].priority));
+
+    let mut remaining = budget;
+    let mut dropped = Vec::new();
+    for i in order {
+        let part = &mut parts[i];
+        let tokens = estimate_tokens(&part.text);
+        if tokens <= remaining || part.section == PromptSection::Identity {
+            remaining = remaining.saturating_sub(tokens);
+        } else if remaining >= MIN_SECTION_TOKENS {
+            tracing::debug!(
+                source = %part.source,
+                tokens,
+                kept = remaining,
+                "Shortening system prompt section"
+            );
+            let keep = (remaining * 4).saturating_sub(TRUNCATION_MARKER.len());
+            part.text = part.text.chars().take(keep).collect();
+            part.text.push_str(TRUNCATION_MARKER);
+            remaining = 0;
+        } else {
+            tracing::debug!(source = %part.source, tokens, "Dropping system prompt section");
+            dropped.push(i);
+        }
+    }
+    dropped.sort_unstable();
+    for i in dropped.into_iter().rev() {
+        parts.remove(i);
+    }
+}
+
+/// Notes on writing for channels, by channel ID.
+const DEFAULT_ETIQUETTE: &[(&str, &str)] = &[
+    (
+        "telegram",
+        "Keep replies conversational. Basic Markdown renders; avoid tables.",
+    ),
+    (
+        "discord",
+        "Markdown renders. Keep replies under 2000 characters where you can.",
+    ),
+    (
+        "slack",
+        "Slack mrkdwn renders. Use short paragraphs and lists; avoid headings.",
+    ),
+    (
+        "whatsapp",
+        "Write short plain-text messages; only *bold* and _italic_ render.",
+    ),
+    (
+        "signal",
+        "Write short plain-text messages; formatting does not render.",
+    ),
+    ("matrix", "Markdown renders. Keep replies conversational."),
+];
+
+/// Tells the model how to write for the conversation's channel.
+#[derive(Debug, Clone)]
+pub struct ChannelEtiquette {
+    notes: HashMap<String, String>,
+}
+
+impl ChannelEtiquette {
+    /// Built-in notes, with `overrides` replacing or adding channels.
+    #[must_use]
+    pub fn new(overrides: &HashMap<String, String>) -> Self {
+        let mut notes: HashMap<String, String> = DEFAULT_ETIQUETTE
+            .iter()
+            .map(|(channel, note)| ((*channel).to_string(), (*note).to_string()))
+            .collect();
+        notes.extend(overrides.clone());
+        Self { notes }
+    }
+}
+
+#[async_trait]
+impl ContextProvider for ChannelEtiquette {
+    fn name(&self) -> &'static str {
+        "channel_etiquette"
+    }
+
+    fn section(&self) -> PromptSection {
+        PromptSection::Channel
+    }
+
+    async fn provide(&self, ctx: &AgentContext) -> Option<String> {
+        let channel = ctx.session.channel.as_ref();
+        let note = self.notes.get(channel).filter(|note| !note.is_empty())?;
+        Some(format!(
+            "# Channel\n\nThis conversation is on {channel}. {note}"
+        ))
+    }
+}
+
+/// Tells the model the current time and who it is talking to.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CurrentTime;
+
+#[async_trait]
+impl ContextProvider for CurrentTime {
+    fn name(&self) -> &'static str {
+        "current_time"
+    }
+
+    fn section(&self) -> PromptSection {
+        PromptSection::Profile
+    }
+
+    async fn provide(&self, ctx: &AgentContext) -> Option<String> {
+        let variables = PromptVariables::from_context(ctx);
+        Some(format!(
+            "# Context\n\nCurrent time: {} UTC\nUser: {}",
+            Utc::now().format("%A %Y-%m-%d %H:%M"),
+            variables.user_name
+        ))
+    }
+}
+
+/// Tells the model what is known about the person it is talking to.
+pub struct PeerProfiles {
+    store: Arc<PeerProfileStore>,
+}
+
+impl PeerProfiles {
+    /// Read profiles from `store`.
+    #[must_use]
+    pub const fn new(store: Arc<PeerProfileStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl ContextProvider for PeerProfiles {
+    fn name(&self) -> &'static str {
+        "peer_profile"
+    }
+
+    fn section(&self) -> PromptSection {
+        PromptSection::Profile
+    }
+
+    async fn provide(&self, ctx: &AgentContext) -> Option<String> {
+        let session = &ctx.session;
+        match self.store.get(session.channel.as_ref(), &session.peer_id) {
+            Ok(profile) => profile?.prompt(),
+            Err(e) => {
+                tracing::warn!("Failed to load peer profile: {}", e);
+                None
+            }
+        }
+    }
+}
+
+/// Providers an agent's prompt settings turn on.
+#[must_use]
+pub fn configured_providers(config: &PromptAssemblyConfig) -> Vec<Arc<dyn ContextProvider>> {
+    let mut providers: Vec<Arc<dyn ContextProvider>> = Vec::new();
+    if config.etiquette {
+        providers.push(Arc::new(ChannelEtiquette::new(&config.channel_etiquette)));
+    }
+    if config.current_time {
+        providers.push(Arc::new(CurrentTime));
+    }
+    providers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assemble_orders_sections() {
+        let prompt = assemble(
+            vec![
+                PromptPart::new(PromptSection::State, "state", "# State"),
+                PromptPart::new(PromptSection::Identity, "identity", "You are Clawd."),
+                PromptPart::new(PromptSection::Channel, "etiquette", "  "),
+                PromptPart::new(PromptSection::Skills, "skills", "# Skills"),
+            ],
+            None,
+        );
+        assert_eq!(
+            prompt.as_deref(),
+            Some("You are Clawd.\n\n# Skills\n\n# State")
+        );
+        assert_eq!(assemble(Vec::new(), None), None);
+    }
+
+    #[test]
+    fn test_budget_cuts_lowest_priority_first() {
+        let identity = "i".repeat(400);
+        let memory = "m".repeat(400);
+        let parts = vec![
+            PromptPart::new(PromptSection::Identity, "identity", identity.clone()),
+            PromptPart::new(PromptSection::Memory, "memory", memory),
+            PromptPart::new(PromptSection::Channel, "etiquette", "c".repeat(200)),
+        ];
+
+        // Identity (100) and channel (50) fit; memory is shortened to 50
+        let prompt = assemble(parts.clone(), Some(200)).unwrap();
+        let sections: Vec<&str> = prompt.split("\n\n").collect();
+        assert_eq!(sections[0], identity);
+        assert!(sections[1].ends_with(TRUNCATION_MARKER));
+        assert_eq!(estimate_tokens(sections[1]), 50);
+        assert_eq!(sections[2].len(), 200);
+
+        // No room left for memory at all
+        let prompt = assemble(parts.clone(), Some(160)).unwrap();
+        assert!(!prompt.contains('m'));
+
+        // Identity is kept even when it alone is over budget
+        let prompt = assemble(parts, Some(10)).unwrap();
+        assert_eq!(prompt, identity);
+    }
+}
@@ -0,0 +1,104 @@
+//! Dry-run handling of model tool calls.
+//!
+//! In dry-run mode no tool runs. Under [`DryRunMode::Simulate`] each call is
+//! answered with a synthetic result so the model can describe the rest of
+//! its approach; under [`DryRunMode::Plan`] the run stops at the first tool
+//! calls and they are returned as the plan.
+
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use openclaw_providers::traits::ContentBlock;
+
+/// Model turns allowed for one message when tool calls are simulated.
+pub const MAX_SIMULATED_TURNS: usize = 8;
+
+/// A tool call the model made that was not executed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedToolCall {
+    /// Tool name.
+    pub name: String,
+    /// Parameters the model passed.
+    pub input: serde_json::Value,
+}
+
+/// Tool calls in a model response, with their tool use IDs.
+pub fn tool_calls(content: &[ContentBlock]) -> Vec<(String, PlannedToolCall)> {
+    content
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::ToolUse { id, name, input } => Some((
+                id.clone(),
+                PlannedToolCall {
+                    name: name.clone(),
+                    input: input.clone(),
+                },
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Synthetic result returned to the model for a simulated call, or `stub`
+/// if the runtime has a fixed result for the tool.
+pub fn simulated_result(
+    tool_use_id: String,
+    call: &PlannedToolCall,
+    stub: Option<&str>,
+) -> ContentBlock {
+    let content = stub.map_or_else(
+        || {
+            format!(
+                "[dry run] Would have executed {} with {}. Nothing was run; continue as if it succeeded.",
+                call.name, call.input
+            )
+        },
+        String::from,
+    );
+    ContentBlock::ToolResult {
+        tool_use_id,
+        content,
+        is_error: None,
+    }
+}
+
+/// Render planned calls for operators.
+#[must_use]
+pub fn plan_summary(calls: &[PlannedToolCall]) -> String {
+    let mut summary = String::from("Planned tool calls (dry run, nothing was executed):");
+    for (i, call) in calls.iter().enumerate() {
+        let _ = write!(summary, "\n{}. {} {}", i + 1, call.name, call.input);
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_tool_calls_and_summary() {
+        let content = vec![
+            ContentBlock::Text {
+                text: "Let me check.".to_string(),
+            },
+            ContentBlock::ToolUse {
+                id: "tu_1".to_string(),
+                name: "bash".to_string(),
+                input: json!({ "command": "ls" }),
+            },
+        ];
+
+        let calls = tool_calls(&content);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].0, "tu_1");
+
+        let calls: Vec<PlannedToolCall> = calls.into_iter().map(|(_, call)| call).collect();
+        assert_eq!(
+            plan_summary(&calls),
+            "Planned tool calls (dry run, nothing was executed):\n1. bash {\"command\":\"ls\"}"
+        );
+    }
+}
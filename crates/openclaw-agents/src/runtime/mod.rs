@@ -1,13 +1,51 @@
 //! Agent runtime.
 
+mod attachments;
+mod concurrency;
+mod context;
+mod dry_run;
+mod moderation;
+mod replay;
+mod state;
+mod structured;
+
+pub use attachments::{
+    AttachmentError, AttachmentResolver, HttpAttachmentResolver, ResolvedAttachment,
+};
+pub use concurrency::{ConcurrencyLimiter, ConcurrencyPermit, ConcurrencyStats};
+pub use context::{
+    ChannelEtiquette, ContextProvider, CurrentTime, PeerProfiles, PromptPart, PromptSection,
+    assemble, configured_providers, estimate_tokens,
+};
+pub use dry_run::{MAX_SIMULATED_TURNS, PlannedToolCall, plan_summary};
+pub use moderation::{
+    Moderation, ModerationFinding, ModerationStage, Moderator, ProviderModerator, Violation,
+    WordListModerator,
+};
+pub use replay::{DiffLine, ReplayReport, ReplayTurn, diff_lines};
+pub use state::{SessionStateError, SessionStateStore, session_state_prompt, validate_state_key};
+pub use structured::{MAX_REPAIR_ATTEMPTS, OutputValidator};
+
 use std::collections::HashMap;
+use std::fmt::Write;
 use std::sync::Arc;
 
-use openclaw_core::events::SessionProjection;
-use openclaw_core::types::{AgentId, SessionKey};
-use openclaw_providers::traits::Provider;
+use base64::Engine;
 
-use crate::tools::ToolRegistry;
+use openclaw_core::config::{
+    AgentConfig, AgentOverrides, ImageDelivery, ModerationConfig, PromptAssemblyConfig, ToolPolicy,
+    VisionConfig,
+};
+use openclaw_core::events::{DryRunMode, SessionProjection};
+use openclaw_core::types::{AgentId, Attachment, AttachmentKind, Citation, SessionKey, TokenUsage};
+use openclaw_providers::traits::{
+    ContentBlock, ImageSource, MessageContent, Provider, ResponseFormat,
+};
+
+use crate::prompts::{PromptTemplate, PromptVariables};
+use crate::sandbox::EgressProxy;
+use crate::skills::{Skill, skills_prompt};
+use crate::tools::{ApprovalGate, DelegationScope, ToolError, ToolRegistry, ToolResult};
 
 /// Agent execution context.
 pub struct AgentContext {
@@ -21,6 +59,8 @@ pub struct AgentContext {
     pub tools: Arc<ToolRegistry>,
     /// Custom context values.
     pub values: HashMap<String, serde_json::Value>,
+    /// Sources collected from tool results for the current turn.
+    pub citations: Vec<Citation>,
 }
 
 impl AgentContext {
@@ -38,6 +78,7 @@ impl AgentContext {
             session,
             tools,
             values: HashMap::new(),
+            citations: Vec::new(),
         }
     }
 
@@ -51,16 +92,69 @@ impl AgentContext {
     pub fn get(&self, key: &str) -> Option<&serde_json::Value> {
         self.values.get(key)
     }
+
+    /// Record sources from a tool result and return content for the model.
+    ///
+    /// Each source is numbered (deduplicated by URL) and listed after the
+    /// tool output so the model can cite it as `[n]`.
+    pub fn record_tool_result(&mut self, result: &ToolResult) -> String {
+        if result.sources.is_empty() {
+            return result.content.clone();
+        }
+
+        let mut content = result.content.clone();
+        content.push_str("\n\nSources:");
+
+        for source in &result.sources {
+            let existing = source.url.as_ref().and_then(|url| {
+                self.citations
+                    .iter()
+                    .find(|c| c.url.as_ref() == Some(url))
+                    .map(|c| c.index)
+            });
+
+            let index = existing.unwrap_or_else(|| {
+                let index = u32::try_from(self.citations.len() + 1).unwrap_or(u32::MAX);
+                self.citations.push(Citation {
+                    index,
+                    title: source.title.clone(),
+                    url: source.url.clone(),
+                    snippet: source.snippet.clone(),
+                });
+                index
+            });
+
+            let _ = write!(content, "\n[{index}] {}", source.title);
+        }
+
+        content
+    }
 }
 
 /// Agent runtime for executing agent logic.
+///
+/// Clones share the provider, tools, and concurrency limiter.
+#[derive(Clone)]
 pub struct AgentRuntime {
     provider: Arc<dyn Provider>,
     tools: Arc<ToolRegistry>,
     model: String,
     system_prompt: Option<String>,
+    prompt_template: Option<PromptTemplate>,
     max_tokens: u32,
     temperature: f32,
+    limiter: Arc<ConcurrencyLimiter>,
+    vision: VisionConfig,
+    resolver: Arc<dyn AttachmentResolver>,
+    skills: Vec<Skill>,
+    dry_run: Option<DryRunMode>,
+    tool_policy: ToolPolicy,
+    tool_stubs: HashMap<String, String>,
+    context_providers: Vec<Arc<dyn ContextProvider>>,
+    prompt_budget: Option<usize>,
+    moderation: Moderation,
+    egress: Option<Arc<EgressProxy>>,
+    approvals: Arc<ApprovalGate>,
 }
 
 impl AgentRuntime {
@@ -72,11 +166,31 @@ impl AgentRuntime {
             tools: Arc::new(ToolRegistry::new()),
             model: "claude-3-5-sonnet-20241022".to_string(),
             system_prompt: None,
+            prompt_template: None,
             max_tokens: 4096,
             temperature: 0.7,
+            limiter: Arc::new(ConcurrencyLimiter::unlimited()),
+            vision: VisionConfig::default(),
+            resolver: Arc::new(HttpAttachmentResolver::default()),
+            skills: Vec::new(),
+            dry_run: None,
+            tool_policy: ToolPolicy::default(),
+            tool_stubs: HashMap::new(),
+            context_providers: Vec::new(),
+            prompt_budget: None,
+            moderation: Moderation::default(),
+            egress: None,
+            approvals: Arc::new(ApprovalGate::new()),
         }
     }
 
+    /// Use a different provider.
+    #[must_use]
+    pub fn with_provider(mut self, provider: Arc<dyn Provider>) -> Self {
+        self.provider = provider;
+        self
+    }
+
     /// Set the model to use.
     #[must_use]
     pub fn with_model(mut self, model: impl Into<String>) -> Self {
@@ -91,6 +205,55 @@ impl AgentRuntime {
         self
     }
 
+    /// Render the system prompt from a template for each turn. Takes
+    /// precedence over [`AgentRuntime::with_system_prompt`].
+    #[must_use]
+    pub fn with_prompt_template(mut self, template: PromptTemplate) -> Self {
+        self.prompt_template = Some(template);
+        self
+    }
+
+    /// Add a system prompt section queried for every turn.
+    #[must_use]
+    pub fn with_context_provider(mut self, provider: Arc<dyn ContextProvider>) -> Self {
+        self.context_providers.push(provider);
+        self
+    }
+
+    /// Limit the system prompt to about `max_tokens`, cutting the least
+    /// important sections first.
+    #[must_use]
+    pub const fn with_prompt_budget(mut self, max_tokens: Option<usize>) -> Self {
+        self.prompt_budget = max_tokens;
+        self
+    }
+
+    /// Apply an agent's prompt settings: its budget and the built-in
+    /// sections it turns on.
+    #[must_use]
+    pub fn with_prompt_assembly(mut self, config: &PromptAssemblyConfig) -> Self {
+        self.context_providers.extend(configured_providers(config));
+        self.prompt_budget = config
+            .max_tokens
+            .map(|max| usize::try_from(max).unwrap_or(usize::MAX));
+        self
+    }
+
+    /// Screen messages and replies as an agent's moderation settings say.
+    /// The provider moderator, if enabled, uses the current provider.
+    #[must_use]
+    pub fn with_moderation(mut self, config: &ModerationConfig) -> Self {
+        self.moderation.configure(config, &self.provider);
+        self
+    }
+
+    /// Add a moderator, such as a plugin's, to the agent's moderation.
+    #[must_use]
+    pub fn with_moderator(mut self, moderator: Arc<dyn Moderator>) -> Self {
+        self.moderation.add(moderator);
+        self
+    }
+
     /// Set max tokens.
     #[must_use]
     pub const fn with_max_tokens(mut self, max_tokens: u32) -> Self {
@@ -112,12 +275,189 @@ impl AgentRuntime {
         self
     }
 
+    /// Set up `tools` for `agent_id` as `config` says and use them: each
+    /// tool's sandbox profile, network access through an egress proxy
+    /// started for the agent when egress is enabled, and operator approval
+    /// through the runtime's approval gate for the tools `approval` lists,
+    /// and the tool concurrency limits. The proxy runs as long as the
+    /// runtime or a clone of it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Tool` if a sandbox profile does not fit its tool, or
+    /// `Config` if a concurrency limit is zero or the egress proxy cannot
+    /// start.
+    pub async fn with_agent_tools(
+        mut self,
+        agent_id: &str,
+        mut tools: ToolRegistry,
+        config: &AgentConfig,
+    ) -> Result<Self, AgentRuntimeError> {
+        config
+            .tool_concurrency
+            .validate()
+            .map_err(AgentRuntimeError::Config)?;
+        tools
+            .apply_sandbox_profiles(&config.sandbox)
+            .map_err(|e| AgentRuntimeError::Tool(e.to_string()))?;
+        if config.egress.enabled {
+            let proxy = EgressProxy::start(agent_id, config.egress.clone())
+                .await
+                .map_err(|e| {
+                    AgentRuntimeError::Config(format!("Egress proxy failed to start: {e}"))
+                })?;
+            tools.route_egress(&proxy);
+            self.egress = Some(Arc::new(proxy));
+        }
+        tools.require_approval(&self.approvals, agent_id, &config.approval);
+        tools.limit_concurrency(&config.tool_concurrency);
+        self.tools = Arc::new(tools);
+        Ok(self)
+    }
+
+    /// Decide approvals for tools set up by
+    /// [`AgentRuntime::with_agent_tools`] in `gate`, such as the gateway's,
+    /// instead of a gate of the runtime's own. Set before the tools.
+    #[must_use]
+    pub fn with_approval_gate(mut self, gate: Arc<ApprovalGate>) -> Self {
+        self.approvals = gate;
+        self
+    }
+
+    /// Gate the agent's tool calls wait on for operator approval.
+    #[must_use]
+    pub const fn approval_gate(&self) -> &Arc<ApprovalGate> {
+        &self.approvals
+    }
+
+    /// Egress proxy the agent's sandboxed tools reach the network through.
+    #[must_use]
+    pub const fn egress_proxy(&self) -> Option<&Arc<EgressProxy>> {
+        self.egress.as_ref()
+    }
+
+    /// A copy of this runtime with changed model parameters. An explicit
+    /// system prompt replaces any prompt template.
+    #[must_use]
+    pub fn with_overrides(&self, overrides: &AgentOverrides) -> Self {
+        let mut runtime = self.clone();
+        if let Some(model) = &overrides.model {
+            runtime.model.clone_from(model);
+        }
+        if let Some(temperature) = overrides.temperature {
+            runtime.temperature = temperature;
+        }
+        if let Some(max_tokens) = overrides.max_tokens {
+            runtime.max_tokens = max_tokens;
+        }
+        if let Some(prompt) = &overrides.system_prompt {
+            runtime.system_prompt = Some(prompt.clone()).filter(|p| !p.is_empty());
+            runtime.prompt_template = None;
+        }
+        runtime
+    }
+
+    /// Restrict which tools the model is offered and may run.
+    #[must_use]
+    pub fn with_tool_policy(mut self, policy: ToolPolicy) -> Self {
+        self.tool_policy = policy;
+        self
+    }
+
+    /// Limit simultaneous provider calls and queued messages.
+    #[must_use]
+    pub fn with_concurrency(mut self, limiter: ConcurrencyLimiter) -> Self {
+        self.limiter = Arc::new(limiter);
+        self
+    }
+
+    /// Set the skills appended to the system prompt.
+    #[must_use]
+    pub fn with_skills(mut self, skills: Vec<Skill>) -> Self {
+        self.skills = skills;
+        self
+    }
+
+    /// Configure image input.
+    #[must_use]
+    pub const fn with_vision(mut self, vision: VisionConfig) -> Self {
+        self.vision = vision;
+        self
+    }
+
+    /// Set how image attachments are loaded for inline delivery.
+    #[must_use]
+    pub fn with_attachment_resolver(mut self, resolver: Arc<dyn AttachmentResolver>) -> Self {
+        self.resolver = resolver;
+        self
+    }
+
+    /// Simulate tool calls in every session instead of leaving them to the
+    /// caller. A session's own dry-run mode takes precedence.
+    #[must_use]
+    pub const fn with_dry_run(mut self, mode: Option<DryRunMode>) -> Self {
+        self.dry_run = mode;
+        self
+    }
+
+    /// Answer simulated calls to these tools with fixed results, by tool
+    /// name, instead of the generic dry-run note.
+    #[must_use]
+    pub fn with_tool_stubs(mut self, stubs: HashMap<String, String>) -> Self {
+        self.tool_stubs = stubs;
+        self
+    }
+
+    /// Dry-run mode for a turn in `ctx`, if tool calls are simulated.
+    #[must_use]
+    pub fn dry_run_mode(&self, ctx: &AgentContext) -> Option<DryRunMode> {
+        ctx.session.dry_run.or(self.dry_run)
+    }
+
+    /// Whether images attached to messages reach the model.
+    #[must_use]
+    pub fn accepts_images(&self) -> bool {
+        self.vision.enabled && self.provider.supports_vision(&self.model)
+    }
+
+    /// Get the provider.
+    #[must_use]
+    pub const fn provider(&self) -> &Arc<dyn Provider> {
+        &self.provider
+    }
+
     /// Get the tool registry.
     #[must_use]
     pub const fn tools(&self) -> &Arc<ToolRegistry> {
         &self.tools
     }
 
+    /// Which tools the agent may use.
+    #[must_use]
+    pub const fn tool_policy(&self) -> &ToolPolicy {
+        &self.tool_policy
+    }
+
+    /// Run a tool the model asked for, if the agent's policy allows it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Denied` if the policy forbids the tool, otherwise whatever
+    /// the registry returns.
+    pub async fn execute_tool(
+        &self,
+        name: &str,
+        params: serde_json::Value,
+    ) -> Result<ToolResult, ToolError> {
+        if let Some(reason) = self.tool_policy.denial(name) {
+            return Err(ToolError::Denied {
+                tool: name.to_string(),
+                reason,
+            });
+        }
+        self.tools.execute(name, params).await
+    }
+
     /// Get the model name.
     #[must_use]
     pub fn model(&self) -> &str {
@@ -130,6 +470,83 @@ impl AgentRuntime {
         self.system_prompt.as_deref()
     }
 
+    /// Get the system prompt template.
+    #[must_use]
+    pub const fn prompt_template(&self) -> Option<&PromptTemplate> {
+        self.prompt_template.as_ref()
+    }
+
+    /// Get the skills appended to the system prompt.
+    #[must_use]
+    pub fn skills(&self) -> &[Skill] {
+        &self.skills
+    }
+
+    /// System prompt sent to the model: the configured prompt followed by
+    /// any skills. A template is returned unrendered.
+    #[must_use]
+    pub fn effective_system_prompt(&self) -> Option<String> {
+        let prompt = self
+            .prompt_template
+            .as_ref()
+            .map(|t| t.body.clone())
+            .or_else(|| self.system_prompt.clone());
+        self.append_skills(prompt)
+    }
+
+    fn append_skills(&self, prompt: Option<String>) -> Option<String> {
+        match (prompt, skills_prompt(&self.skills)) {
+            (Some(prompt), Some(skills)) => Some(format!("{prompt}\n\n{skills}")),
+            (prompt, skills) => skills.or(prompt),
+        }
+    }
+
+    /// System prompt for a turn in `ctx`: the rendered template or
+    /// configured prompt, skills, each context provider's section, and any
+    /// saved session state, fitted to the prompt budget.
+    async fn session_system_prompt(&self, ctx: &AgentContext) -> Option<String> {
+        let identity = self.prompt_template.as_ref().map_or_else(
+            || self.system_prompt.clone(),
+            |template| {
+                Some(
+                    template
+                        .render(&PromptVariables::from_context(ctx))
+                        .unwrap_or_else(|e| {
+                            tracing::warn!("{e}; using the template unrendered");
+                            template.body.clone()
+                        }),
+                )
+            },
+        );
+
+        let mut parts = Vec::new();
+        let built_in = [
+            (PromptSection::Identity, "system_prompt", identity),
+            (PromptSection::Skills, "skills", skills_prompt(&self.skills)),
+            (
+                PromptSection::State,
+                "session_state",
+                session_state_prompt(&ctx.session.custom_state),
+            ),
+        ];
+        for (section, source, text) in built_in {
+            if let Some(text) = text {
+                parts.push(PromptPart::new(section, source, text));
+            }
+        }
+        for provider in &self.context_providers {
+            if let Some(text) = provider.provide(ctx).await {
+                parts.push(PromptPart {
+                    section: provider.section(),
+                    priority: provider.priority(),
+                    source: provider.name().to_string(),
+                    text,
+                });
+            }
+        }
+        assemble(parts, self.prompt_budget)
+    }
+
     /// Get max tokens.
     #[must_use]
     pub const fn max_tokens(&self) -> u32 {
@@ -142,6 +559,24 @@ impl AgentRuntime {
         self.temperature
     }
 
+    /// The agent's content moderation.
+    #[must_use]
+    pub const fn moderation(&self) -> &Moderation {
+        &self.moderation
+    }
+
+    /// Reply to send when the agent is too busy to take a message.
+    #[must_use]
+    pub fn busy_message(&self) -> &str {
+        self.limiter.busy_message()
+    }
+
+    /// Get concurrency counters.
+    #[must_use]
+    pub fn concurrency_stats(&self) -> ConcurrencyStats {
+        self.limiter.stats()
+    }
+
     /// Process a user message and return a response.
     ///
     /// # Errors
@@ -152,64 +587,570 @@ impl AgentRuntime {
         ctx: &mut AgentContext,
         message: &str,
     ) -> Result<String, AgentRuntimeError> {
-        use openclaw_providers::traits::{CompletionRequest, Message, MessageContent, Role};
+        Ok(self.process(ctx, message).await?.content)
+    }
 
-        // Build messages from session history
-        let mut messages: Vec<Message> = ctx
-            .session
-            .messages
-            .iter()
+    /// Process a user message and return the response with model and usage.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Busy` if the agent's queue is full, or an error if the
+    /// provider call fails.
+    pub async fn process(
+        &self,
+        ctx: &mut AgentContext,
+        message: &str,
+    ) -> Result<AgentReply, AgentRuntimeError> {
+        self.process_with_attachments(ctx, message, &[]).await
+    }
+
+    /// Process a user message with attachments.
+    ///
+    /// Image attachments are passed to the model when vision is enabled
+    /// and the model supports it; otherwise the model is told they were
+    /// attached.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Busy` if the agent's queue is full, `Blocked` if moderation
+    /// holds back the message or reply, or an error if the provider call
+    /// fails. Images that cannot be loaded are skipped.
+    pub async fn process_with_attachments(
+        &self,
+        ctx: &mut AgentContext,
+        message: &str,
+        attachments: &[Attachment],
+    ) -> Result<AgentReply, AgentRuntimeError> {
+        let session_key = ctx.session_key.clone();
+        DelegationScope::enter(
+            &session_key,
+            self.run_with_attachments(ctx, message, attachments),
+        )
+        .await
+    }
+
+    /// [`Self::process_with_attachments`] within a delegation scope.
+    async fn run_with_attachments(
+        &self,
+        ctx: &mut AgentContext,
+        message: &str,
+        attachments: &[Attachment],
+    ) -> Result<AgentReply, AgentRuntimeError> {
+        use openclaw_providers::traits::{Message, Role};
+
+        let mut flagged = Vec::new();
+        self.moderate(ctx, message, ModerationStage::Inbound, &mut flagged)
+            .await?;
+        let mut request = self.build_request(ctx, message, attachments).await;
+
+        // Call provider, waiting for a free slot
+        let permit = self
+            .limiter
+            .acquire()
+            .await
+            .ok_or(AgentRuntimeError::Busy)?;
+        let mut response = self.provider.complete(request.clone()).await?;
+        let mut usage = response.usage.clone();
+
+        // In dry-run mode, answer tool calls without running them. Calls
+        // to tools outside the agent's policy are refused either way.
+        let dry_run = self.dry_run_mode(ctx);
+        let mut planned_tools = Vec::new();
+        let mut denied_tools = Vec::new();
+        if let Some(mode) = dry_run {
+            for _ in 0..MAX_SIMULATED_TURNS {
+                let calls = dry_run::tool_calls(&response.content);
+                if calls.is_empty() {
+                    break;
+                }
+                tracing::info!(
+                    session = %ctx.session_key,
+                    calls = calls.len(),
+                    ?mode,
+                    "Dry run: skipping tool calls"
+                );
+                let mut results = Vec::new();
+                for (id, call) in calls {
+                    if let Some(reason) = self.tool_policy.denial(&call.name) {
+                        results.push(denied_result(id, &call.name, &reason));
+                        denied_tools.push(DeniedToolCall::new(call, reason));
+                    } else {
+                        let stub = self.tool_stubs.get(&call.name).map(String::as_str);
+                        results.push(dry_run::simulated_result(id, &call, stub));
+                        planned_tools.push(call);
+                    }
+                }
+                if mode == DryRunMode::Plan {
+                    break;
+                }
+
+                request.messages.push(Message {
+                    role: Role::Assistant,
+                    content: MessageContent::Blocks(response.content),
+                });
+                request.messages.push(Message {
+                    role: Role::Tool,
+                    content: MessageContent::Blocks(results),
+                });
+                response = self.provider.complete(request.clone()).await?;
+                usage.accumulate(&response.usage);
+            }
+        } else {
+            denied_tools.extend(
+                dry_run::tool_calls(&response.content)
+                    .into_iter()
+                    .filter_map(|(_, call)| {
+                        let reason = self.tool_policy.denial(&call.name)?;
+                        Some(DeniedToolCall::new(call, reason))
+                    }),
+            );
+        }
+        drop(permit);
+        for call in &denied_tools {
+            tracing::warn!(
+                session = %ctx.session_key,
+                tool = %call.name,
+                reason = %call.reason,
+                "Model requested a tool the agent may not use"
+            );
+        }
+
+        let text = response_text(&response.content);
+        let text = if dry_run == Some(DryRunMode::Plan) && !planned_tools.is_empty() {
+            let plan = plan_summary(&planned_tools);
+            if text.is_empty() {
+                plan
+            } else {
+                format!("{text}\n\n{plan}")
+            }
+        } else {
+            text
+        };
+        self.moderate(ctx, &text, ModerationStage::Outbound, &mut flagged)
+            .await?;
+
+        // Keep only sources the response actually cites
+        let citations = std::mem::take(&mut ctx.citations)
+            .into_iter()
+            .filter(|c| text.contains(&format!("[{}]", c.index)))
+            .collect();
+
+        Ok(AgentReply {
+            content: text,
+            model: response.model,
+            usage,
+            citations,
+            planned_tools,
+            denied_tools,
+            data: None,
+            flagged,
+        })
+    }
+
+    /// Process a user message whose reply must match a JSON schema.
+    ///
+    /// The schema is passed to the provider's native structured output
+    /// and stated in the system prompt. Replies that do not match are
+    /// sent back for correction up to [`MAX_REPAIR_ATTEMPTS`] times. No
+    /// tools are offered.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Busy` if the agent's queue is full, `Blocked` if moderation
+    /// holds back the message or reply, `InvalidOutput` if the schema is
+    /// invalid or no reply matched it, or an error if the provider call
+    /// fails.
+    pub async fn process_structured(
+        &self,
+        ctx: &mut AgentContext,
+        message: &str,
+        attachments: &[Attachment],
+        format: &ResponseFormat,
+    ) -> Result<AgentReply, AgentRuntimeError> {
+        let session_key = ctx.session_key.clone();
+        DelegationScope::enter(
+            &session_key,
+            self.run_structured(ctx, message, attachments, format),
+        )
+        .await
+    }
+
+    /// [`Self::process_structured`] within a delegation scope.
+    async fn run_structured(
+        &self,
+        ctx: &mut AgentContext,
+        message: &str,
+        attachments: &[Attachment],
+        format: &ResponseFormat,
+    ) -> Result<AgentReply, AgentRuntimeError> {
+        use openclaw_providers::traits::{Message, Role};
+
+        let validator = OutputValidator::new(format).map_err(AgentRuntimeError::InvalidOutput)?;
+        let mut flagged = Vec::new();
+        self.moderate(ctx, message, ModerationStage::Inbound, &mut flagged)
+            .await?;
+        let mut request = self.build_request(ctx, message, attachments).await;
+        let instructions = structured::schema_instructions(format);
+        request.system = Some(match request.system.take() {
+            Some(system) => format!("{system}\n\n{instructions}"),
+            None => instructions,
+        });
+        request.tools = None;
+        request.response_format = Some(format.clone());
+
+        let permit = self
+            .limiter
+            .acquire()
+            .await
+            .ok_or(AgentRuntimeError::Busy)?;
+        let mut response = self.provider.complete(request.clone()).await?;
+        let mut usage = response.usage.clone();
+        let mut attempts = 0;
+        let data = loop {
+            let text = response_text(&response.content);
+            match validator.check(&text) {
+                Ok(data) => break data,
+                Err(problems) if attempts < MAX_REPAIR_ATTEMPTS => {
+                    attempts += 1;
+                    tracing::debug!(
+                        session = %ctx.session_key,
+                        attempt = attempts,
+                        "Reply did not match schema: {}",
+                        problems
+                    );
+                    request.messages.push(Message {
+                        role: Role::Assistant,
+                        content: MessageContent::Text(text),
+                    });
+                    request.messages.push(Message {
+                        role: Role::User,
+                        content: MessageContent::Text(structured::repair_prompt(&problems)),
+                    });
+                    response = self.provider.complete(request.clone()).await?;
+                    usage.accumulate(&response.usage);
+                }
+                Err(problems) => return Err(AgentRuntimeError::InvalidOutput(problems)),
+            }
+        };
+        drop(permit);
+        let content = data.to_string();
+        self.moderate(ctx, &content, ModerationStage::Outbound, &mut flagged)
+            .await?;
+
+        Ok(AgentReply {
+            content,
+            model: response.model,
+            usage,
+            citations: Vec::new(),
+            planned_tools: Vec::new(),
+            denied_tools: Vec::new(),
+            data: Some(data),
+            flagged,
+        })
+    }
+
+    /// Process a user message, passing response text to `on_text` as it
+    /// streams in.
+    ///
+    /// In dry-run mode the reply is produced as by [`Self::process`] and
+    /// passed to `on_text` in one piece. Streamed replies report no token
+    /// usage. A streamed reply has been seen by the time it is screened,
+    /// so outbound violations are only flagged.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Busy` if the agent's queue is full, `Blocked` if moderation
+    /// holds back the message, or an error if the provider call or stream
+    /// fails.
+    pub async fn process_streaming(
+        &self,
+        ctx: &mut AgentContext,
+        message: &str,
+        on_text: &(dyn Fn(&str) + Send + Sync),
+    ) -> Result<AgentReply, AgentRuntimeError> {
+        let session_key = ctx.session_key.clone();
+        DelegationScope::enter(&session_key, self.run_streaming(ctx, message, on_text)).await
+    }
+
+    /// [`Self::process_streaming`] within a delegation scope.
+    async fn run_streaming(
+        &self,
+        ctx: &mut AgentContext,
+        message: &str,
+        on_text: &(dyn Fn(&str) + Send + Sync),
+    ) -> Result<AgentReply, AgentRuntimeError> {
+        use futures::StreamExt;
+        use openclaw_providers::traits::ChunkType;
+
+        if self.dry_run_mode(ctx).is_some() {
+            let reply = self.process(ctx, message).await?;
+            on_text(&reply.content);
+            return Ok(reply);
+        }
+
+        let mut flagged = Vec::new();
+        self.moderate(ctx, message, ModerationStage::Inbound, &mut flagged)
+            .await?;
+        let request = self.build_request(ctx, message, &[]).await;
+        let permit = self
+            .limiter
+            .acquire()
+            .await
+            .ok_or(AgentRuntimeError::Busy)?;
+        let mut stream = self.provider.complete_stream(request).await?;
+        let mut text = String::new();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            if let (ChunkType::ContentBlockDelta, Some(delta)) = (chunk.chunk_type, chunk.delta) {
+                on_text(&delta);
+                text.push_str(&delta);
+            }
+        }
+        drop(permit);
+        if let Some(mut violation) = self
+            .moderation
+            .screen(&text, ModerationStage::Outbound, ctx)
+            .await
+        {
+            violation.blocked = false;
+            flagged.push(violation);
+        }
+
+        let citations = std::mem::take(&mut ctx.citations)
+            .into_iter()
+            .filter(|c| text.contains(&format!("[{}]", c.index)))
+            .collect();
+
+        Ok(AgentReply {
+            content: text,
+            model: self.model.clone(),
+            usage: TokenUsage::default(),
+            citations,
+            planned_tools: Vec::new(),
+            denied_tools: Vec::new(),
+            data: None,
+            flagged,
+        })
+    }
+
+    /// Screen `text` at `stage`, adding violations let through to
+    /// `flagged`.
+    async fn moderate(
+        &self,
+        ctx: &AgentContext,
+        text: &str,
+        stage: ModerationStage,
+        flagged: &mut Vec<Violation>,
+    ) -> Result<(), AgentRuntimeError> {
+        match self.moderation.screen(text, stage, ctx).await {
+            Some(violation) if violation.blocked => Err(AgentRuntimeError::Blocked(violation)),
+            Some(violation) => {
+                flagged.push(violation);
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Build a completion request from the session history and a new
+    /// user message.
+    ///
+    /// Sources attached to tool results in the history are numbered into
+    /// `ctx.citations` so the reply can cite them.
+    async fn build_request(
+        &self,
+        ctx: &mut AgentContext,
+        message: &str,
+        attachments: &[Attachment],
+    ) -> openclaw_providers::traits::CompletionRequest {
+        use openclaw_providers::traits::{CompletionRequest, Message, Role};
+
+        // Build messages from the current conversation
+        let history = ctx.session.context().to_vec();
+        let mut messages: Vec<Message> = history
+            .into_iter()
             .map(|m| match m {
                 openclaw_core::events::SessionMessage::Inbound(text) => Message {
                     role: Role::User,
-                    content: MessageContent::Text(text.clone()),
+                    content: MessageContent::Text(text),
                 },
                 openclaw_core::events::SessionMessage::Outbound(text) => Message {
                     role: Role::Assistant,
-                    content: MessageContent::Text(text.clone()),
-                },
-                openclaw_core::events::SessionMessage::Tool { name, result } => Message {
-                    role: Role::Tool,
-                    content: MessageContent::Text(format!("[{name}]: {result}")),
+                    content: MessageContent::Text(text),
                 },
+                openclaw_core::events::SessionMessage::Tool { name, result } => {
+                    let result = serde_json::from_str::<ToolResult>(&result)
+                        .map_or(result, |result| ctx.record_tool_result(&result));
+                    Message {
+                        role: Role::Tool,
+                        content: MessageContent::Text(format!("[{name}]: {result}")),
+                    }
+                }
             })
             .collect();
 
         // Add current message
         messages.push(Message {
             role: Role::User,
-            content: MessageContent::Text(message.to_string()),
+            content: self.user_content(message, attachments).await,
         });
 
-        // Build request
-        let request = CompletionRequest {
+        CompletionRequest {
             model: self.model.clone(),
             messages,
-            system: self.system_prompt.clone(),
+            system: self.session_system_prompt(ctx).await,
             max_tokens: self.max_tokens,
             temperature: self.temperature,
             stop: None,
-            tools: Some(self.tools.as_tool_definitions()),
-        };
-
-        // Call provider
-        let response = self.provider.complete(request).await?;
+            tools: Some(
+                self.tools
+                    .as_tool_definitions()
+                    .into_iter()
+                    .filter(|tool| self.tool_policy.allows(&tool.name))
+                    .collect(),
+            ),
+            response_format: None,
+        }
+    }
 
-        // Extract text response
-        let text = response
-            .content
+    /// Build the content of a user turn, with image blocks first.
+    async fn user_content(&self, message: &str, attachments: &[Attachment]) -> MessageContent {
+        let images: Vec<&Attachment> = attachments
             .iter()
-            .filter_map(|block| {
-                if let openclaw_providers::traits::ContentBlock::Text { text } = block {
-                    Some(text.as_str())
-                } else {
-                    None
+            .filter(|a| a.kind == AttachmentKind::Image)
+            .collect();
+        if images.is_empty() {
+            return MessageContent::Text(message.to_string());
+        }
+        if !self.accepts_images() {
+            return MessageContent::Text(format!(
+                "{message}\n\n[{} image(s) attached; this agent cannot view images]",
+                images.len()
+            ));
+        }
+
+        let mut blocks = Vec::new();
+        let mut text = message.to_string();
+        for attachment in images.iter().take(self.vision.max_images) {
+            match self.image_source(attachment).await {
+                Ok(source) => blocks.push(ContentBlock::Image { source }),
+                Err(e) => {
+                    tracing::warn!("Skipping image attachment: {}", e);
+                    let _ = write!(text, "\n\n[image could not be loaded: {e}]");
                 }
-            })
-            .collect::<Vec<_>>()
-            .join("\n");
+            }
+        }
+        if images.len() > self.vision.max_images {
+            let _ = write!(
+                text,
+                "\n\n[{} more image(s) omitted]",
+                images.len() - self.vision.max_images
+            );
+        }
+
+        if !text.is_empty() {
+            blocks.push(ContentBlock::Text { text });
+        }
+        MessageContent::Blocks(blocks)
+    }
 
-        Ok(text)
+    /// Reference an image by URL or load it for inline delivery.
+    async fn image_source(&self, attachment: &Attachment) -> Result<ImageSource, AttachmentError> {
+        let is_http =
+            attachment.url.starts_with("https://") || attachment.url.starts_with("http://");
+        if self.vision.delivery == ImageDelivery::Url && is_http {
+            return Ok(ImageSource::url(&attachment.url));
+        }
+
+        let resolved = self
+            .resolver
+            .resolve(attachment, self.vision.max_image_bytes)
+            .await?;
+        let media_type = attachment
+            .mime_type
+            .clone()
+            .or(resolved.mime_type)
+            .unwrap_or_default();
+        if !SUPPORTED_IMAGE_TYPES.contains(&media_type.as_str()) {
+            return Err(AttachmentError::Unsupported(format!(
+                "image type {media_type:?}"
+            )));
+        }
+
+        Ok(ImageSource::base64(
+            media_type,
+            base64::engine::general_purpose::STANDARD.encode(resolved.data),
+        ))
+    }
+}
+
+/// Text blocks of a response, one per line.
+fn response_text(content: &[ContentBlock]) -> String {
+    content
+        .iter()
+        .filter_map(|block| {
+            if let ContentBlock::Text { text } = block {
+                Some(text.as_str())
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Image formats accepted by vision models.
+const SUPPORTED_IMAGE_TYPES: &[&str] = &["image/jpeg", "image/png", "image/gif", "image/webp"];
+
+/// Agent response with model and token usage.
+#[derive(Debug, Clone)]
+pub struct AgentReply {
+    /// Response text.
+    pub content: String,
+    /// Model that produced the response.
+    pub model: String,
+    /// Token usage.
+    pub usage: TokenUsage,
+    /// Sources cited in the response.
+    pub citations: Vec<Citation>,
+    /// Tool calls skipped because the turn ran in dry-run mode.
+    pub planned_tools: Vec<PlannedToolCall>,
+    /// Tool calls refused because the agent may not use the tool.
+    pub denied_tools: Vec<DeniedToolCall>,
+    /// Reply parsed and checked against the requested schema, for
+    /// structured replies.
+    pub data: Option<serde_json::Value>,
+    /// Content moderation caught but let through.
+    pub flagged: Vec<Violation>,
+}
+
+/// A tool call refused by the agent's tool policy.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DeniedToolCall {
+    /// Tool name.
+    pub name: String,
+    /// Parameters the model passed.
+    pub input: serde_json::Value,
+    /// Why the tool is not allowed.
+    pub reason: String,
+}
+
+impl DeniedToolCall {
+    fn new(call: PlannedToolCall, reason: String) -> Self {
+        Self {
+            name: call.name,
+            input: call.input,
+            reason,
+        }
+    }
+}
+
+/// Result returned to the model for a refused call.
+fn denied_result(tool_use_id: String, name: &str, reason: &str) -> ContentBlock {
+    ContentBlock::ToolResult {
+        tool_use_id,
+        content: format!("Tool {name} is not available to this agent ({reason}). It was not run."),
+        is_error: Some(true),
     }
 }
 
@@ -227,6 +1168,18 @@ pub enum AgentRuntimeError {
     /// Configuration error.
     #[error("Config error: {0}")]
     Config(String),
+
+    /// Too many messages already waiting for this agent.
+    #[error("Agent is busy")]
+    Busy,
+
+    /// Reply did not match the requested schema.
+    #[error("Invalid structured output: {0}")]
+    InvalidOutput(String),
+
+    /// Moderation held back the message or reply.
+    #[error("Blocked by moderation: {}", .0.reason)]
+    Blocked(Violation),
 }
 
 #[cfg(test)]
@@ -249,4 +1202,890 @@ mod tests {
 
         assert_eq!(ctx.agent_id.as_ref(), "default");
     }
+
+    #[test]
+    fn test_record_tool_result_numbers_sources() {
+        use crate::tools::ToolSource;
+
+        let mut ctx = AgentContext::new(
+            AgentId::default_agent(),
+            SessionKey::new("test"),
+            openclaw_core::events::SessionProjection::new(
+                SessionKey::new("test"),
+                "default".to_string(),
+                openclaw_core::types::ChannelId::telegram(),
+                "user".to_string(),
+            ),
+            Arc::new(ToolRegistry::new()),
+        );
+
+        let source = |title: &str, url: &str| ToolSource {
+            title: title.to_string(),
+            url: Some(url.to_string()),
+            snippet: None,
+        };
+
+        let first = ToolResult::success("results").with_sources(vec![
+            source("Rust Book", "https://doc.rust-lang.org/book"),
+            source("Tokio", "https://tokio.rs"),
+        ]);
+        let content = ctx.record_tool_result(&first);
+        assert!(content.contains("[1] Rust Book"));
+        assert!(content.contains("[2] Tokio"));
+
+        // Same URL keeps its number
+        let second = ToolResult::success("more")
+            .with_sources(vec![source("Tokio docs", "https://tokio.rs")]);
+        let content = ctx.record_tool_result(&second);
+        assert!(content.contains("[2] Tokio docs"));
+        assert_eq!(ctx.citations.len(), 2);
+    }
+
+    /// Records the last request and replies with fixed text, streamed or not.
+    struct CapturingProvider {
+        last: std::sync::Mutex<Option<openclaw_providers::traits::CompletionRequest>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for CapturingProvider {
+        fn name(&self) -> &'static str {
+            "capturing"
+        }
+
+        async fn list_models(
+            &self,
+        ) -> Result<Vec<String>, openclaw_providers::traits::ProviderError> {
+            Ok(vec![])
+        }
+
+        async fn complete(
+            &self,
+            request: openclaw_providers::traits::CompletionRequest,
+        ) -> Result<
+            openclaw_providers::traits::CompletionResponse,
+            openclaw_providers::traits::ProviderError,
+        > {
+            let model = request.model.clone();
+            *self.last.lock().unwrap() = Some(request);
+            Ok(openclaw_providers::traits::CompletionResponse {
+                id: "resp-1".to_string(),
+                model,
+                content: vec![ContentBlock::Text {
+                    text: "A cat.".to_string(),
+                }],
+                stop_reason: None,
+                usage: TokenUsage::default(),
+            })
+        }
+
+        async fn complete_stream(
+            &self,
+            request: openclaw_providers::traits::CompletionRequest,
+        ) -> Result<
+            std::pin::Pin<
+                Box<
+                    dyn futures::Stream<
+                            Item = Result<
+                                openclaw_providers::traits::StreamingChunk,
+                                openclaw_providers::traits::ProviderError,
+                            >,
+                        > + Send,
+                >,
+            >,
+            openclaw_providers::traits::ProviderError,
+        > {
+            use openclaw_providers::traits::{ChunkType, StreamingChunk};
+
+            *self.last.lock().unwrap() = Some(request);
+            let chunk = |chunk_type, delta: Option<&str>| {
+                Ok(StreamingChunk {
+                    chunk_type,
+                    delta: delta.map(String::from),
+                    index: Some(0),
+                })
+            };
+            Ok(Box::pin(futures::stream::iter(vec![
+                chunk(ChunkType::MessageStart, None),
+                chunk(ChunkType::ContentBlockDelta, Some("A ")),
+                chunk(ChunkType::ContentBlockDelta, Some("cat.")),
+                chunk(ChunkType::MessageStop, None),
+            ])))
+        }
+
+        fn supports_vision(&self, model: &str) -> bool {
+            model == "vision-model"
+        }
+    }
+
+    fn context() -> AgentContext {
+        AgentContext::new(
+            AgentId::default_agent(),
+            SessionKey::new("test"),
+            openclaw_core::events::SessionProjection::new(
+                SessionKey::new("test"),
+                "default".to_string(),
+                openclaw_core::types::ChannelId::telegram(),
+                "user".to_string(),
+            ),
+            Arc::new(ToolRegistry::new()),
+        )
+    }
+
+    fn image(url: &str) -> Attachment {
+        Attachment {
+            kind: AttachmentKind::Image,
+            url: url.to_string(),
+            mime_type: None,
+            filename: None,
+            size: None,
+            thumbnail_url: None,
+        }
+    }
+
+    fn last_user_content(provider: &CapturingProvider) -> MessageContent {
+        let request = provider.last.lock().unwrap().take().unwrap();
+        request.messages.last().unwrap().content.clone()
+    }
+
+    #[tokio::test]
+    async fn test_process_streaming() {
+        let provider = Arc::new(CapturingProvider {
+            last: std::sync::Mutex::new(None),
+        });
+        let runtime = AgentRuntime::new(provider.clone()).with_model("test-model");
+        let deltas = std::sync::Mutex::new(Vec::new());
+
+        let reply = runtime
+            .process_streaming(&mut context(), "What is this?", &|delta| {
+                deltas.lock().unwrap().push(delta.to_string());
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(reply.content, "A cat.");
+        assert_eq!(reply.model, "test-model");
+        assert_eq!(*deltas.lock().unwrap(), vec!["A ", "cat."]);
+        assert!(matches!(
+            last_user_content(&provider),
+            MessageContent::Text(text) if text == "What is this?"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_moderation() {
+        let provider = Arc::new(CapturingProvider {
+            last: std::sync::Mutex::new(None),
+        });
+        let config = ModerationConfig {
+            keywords: vec!["cat".to_string()],
+            ..ModerationConfig::default()
+        };
+        let runtime = AgentRuntime::new(provider.clone()).with_moderation(&config);
+
+        // Blocked messages never reach the provider
+        let err = runtime
+            .process(&mut context(), "Tell me about my cat")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AgentRuntimeError::Blocked(Violation {
+                stage: ModerationStage::Inbound,
+                ..
+            })
+        ));
+        assert!(provider.last.lock().unwrap().is_none());
+
+        // The reply "A cat." is caught on the way out
+        let err = runtime.process(&mut context(), "Hi").await.unwrap_err();
+        assert!(matches!(
+            err,
+            AgentRuntimeError::Blocked(Violation {
+                stage: ModerationStage::Outbound,
+                ..
+            })
+        ));
+
+        let flagging = AgentRuntime::new(provider).with_moderation(&ModerationConfig {
+            action: openclaw_core::config::ModerationAction::Flag,
+            ..config
+        });
+        let reply = flagging
+            .process(&mut context(), "Tell me about my cat")
+            .await
+            .unwrap();
+        assert_eq!(reply.content, "A cat.");
+        assert_eq!(reply.flagged.len(), 2);
+        assert!(
+            reply
+                .flagged
+                .iter()
+                .all(|v| !v.blocked && v.moderator == "word_list")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_images_passed_to_vision_model() {
+        let provider = Arc::new(CapturingProvider {
+            last: std::sync::Mutex::new(None),
+        });
+        let runtime = AgentRuntime::new(provider.clone())
+            .with_model("vision-model")
+            .with_vision(VisionConfig {
+                enabled: true,
+                max_images: 1,
+                ..VisionConfig::default()
+            });
+        let attachments = [
+            image("data:image/png;base64,aGVsbG8="),
+            image("data:image/png;base64,aGVsbG8="),
+        ];
+
+        runtime
+            .process_with_attachments(&mut context(), "What is this?", &attachments)
+            .await
+            .unwrap();
+
+        let MessageContent::Blocks(blocks) = last_user_content(&provider) else {
+            panic!("expected content blocks");
+        };
+        assert!(matches!(
+            &blocks[0],
+            ContentBlock::Image { source } if source.media_type == "image/png" && source.data == "aGVsbG8="
+        ));
+        assert!(matches!(
+            &blocks[1],
+            ContentBlock::Text { text } if text.starts_with("What is this?") && text.contains("1 more image(s) omitted")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_images_described_when_unsupported() {
+        let provider = Arc::new(CapturingProvider {
+            last: std::sync::Mutex::new(None),
+        });
+        let attachments = [image("data:image/png;base64,aGVsbG8=")];
+
+        // Vision enabled but the model is text-only
+        let runtime = AgentRuntime::new(provider.clone())
+            .with_model("text-model")
+            .with_vision(VisionConfig {
+                enabled: true,
+                ..VisionConfig::default()
+            });
+        assert!(!runtime.accepts_images());
+        runtime
+            .process_with_attachments(&mut context(), "Look", &attachments)
+            .await
+            .unwrap();
+        assert!(matches!(
+            last_user_content(&provider),
+            MessageContent::Text(text) if text.contains("this agent cannot view images")
+        ));
+
+        // Vision-capable model, but vision not enabled for the agent
+        let runtime = AgentRuntime::new(provider.clone()).with_model("vision-model");
+        assert!(!runtime.accepts_images());
+    }
+
+    #[test]
+    fn test_effective_system_prompt() {
+        let provider = Arc::new(CapturingProvider {
+            last: std::sync::Mutex::new(None),
+        });
+        let skill = Skill {
+            name: "refunds".to_string(),
+            description: Some("Handle refunds".to_string()),
+            metadata: std::collections::BTreeMap::new(),
+            body: "Check the order date.".to_string(),
+            path: std::path::PathBuf::from("refunds/skill.md"),
+        };
+
+        let runtime = AgentRuntime::new(provider.clone());
+        assert_eq!(runtime.effective_system_prompt(), None);
+
+        let runtime = AgentRuntime::new(provider.clone()).with_skills(vec![skill.clone()]);
+        assert!(
+            runtime
+                .effective_system_prompt()
+                .unwrap()
+                .starts_with("# Skills")
+        );
+
+        let prompt = AgentRuntime::new(provider)
+            .with_system_prompt("You help customers.")
+            .with_skills(vec![skill])
+            .effective_system_prompt()
+            .unwrap();
+        assert!(prompt.starts_with("You help customers.\n\n# Skills"));
+        assert!(prompt.contains("## refunds\nHandle refunds\n\nCheck the order date."));
+    }
+
+    #[tokio::test]
+    async fn test_session_state_in_system_prompt() {
+        let provider = Arc::new(CapturingProvider {
+            last: std::sync::Mutex::new(None),
+        });
+        let runtime = AgentRuntime::new(provider.clone()).with_system_prompt("You help customers.");
+        let mut ctx = context();
+        ctx.session
+            .custom_state
+            .insert("units".to_string(), serde_json::json!("metric"));
+
+        runtime.process(&mut ctx, "Hi").await.unwrap();
+
+        let request = provider.last.lock().unwrap().take().unwrap();
+        let system = request.system.unwrap();
+        assert!(system.starts_with("You help customers.\n\n# Session State"));
+        assert!(system.contains("- units: \"metric\""));
+    }
+
+    /// Recalls a fixed fact.
+    struct FixedMemory(&'static str);
+
+    #[async_trait::async_trait]
+    impl ContextProvider for FixedMemory {
+        fn name(&self) -> &'static str {
+            "memory"
+        }
+
+        fn section(&self) -> PromptSection {
+            PromptSection::Memory
+        }
+
+        async fn provide(&self, _ctx: &AgentContext) -> Option<String> {
+            Some(format!("# Memory\n\n{}", self.0))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_context_providers_in_system_prompt() {
+        let provider = Arc::new(CapturingProvider {
+            last: std::sync::Mutex::new(None),
+        });
+        let runtime = AgentRuntime::new(provider.clone())
+            .with_system_prompt("You help customers.")
+            .with_context_provider(Arc::new(FixedMemory("Prefers email over phone calls.")))
+            .with_prompt_assembly(&PromptAssemblyConfig {
+                current_time: false,
+                ..PromptAssemblyConfig::default()
+            });
+        let mut ctx = context();
+        ctx.session
+            .custom_state
+            .insert("units".to_string(), serde_json::json!("metric"));
+
+        runtime.process(&mut ctx, "Hi").await.unwrap();
+        let system = provider
+            .last
+            .lock()
+            .unwrap()
+            .take()
+            .unwrap()
+            .system
+            .unwrap();
+        let headings: Vec<&str> = system
+            .lines()
+            .filter(|line| line.starts_with("# "))
+            .collect();
+        assert_eq!(headings, ["# Memory", "# Channel", "# Session State"]);
+        assert!(system.starts_with("You help customers."));
+        assert!(system.contains("This conversation is on telegram."));
+
+        // Over budget, memory goes before anything else
+        let runtime = runtime.with_prompt_budget(Some(estimate_tokens(&system) - 3));
+        runtime.process(&mut ctx, "Hi").await.unwrap();
+        let system = provider
+            .last
+            .lock()
+            .unwrap()
+            .take()
+            .unwrap()
+            .system
+            .unwrap();
+        assert!(!system.contains("Prefers email"));
+        assert!(system.contains("# Session State"));
+    }
+
+    #[tokio::test]
+    async fn test_prompt_template_rendered_per_turn() {
+        let provider = Arc::new(CapturingProvider {
+            last: std::sync::Mutex::new(None),
+        });
+        let template = PromptTemplate::from_source(
+            "support-bot",
+            None,
+            "Help {{ user_name }} on {{ channel }} ({{ tier }}).",
+            "support-bot.md",
+        )
+        .unwrap();
+        let runtime = AgentRuntime::new(provider.clone()).with_prompt_template(template);
+        let mut ctx = context();
+        ctx.set("tier", serde_json::json!("gold"));
+
+        runtime.process(&mut ctx, "Hi").await.unwrap();
+        let request = provider.last.lock().unwrap().take().unwrap();
+        assert_eq!(
+            request.system.as_deref(),
+            Some("Help user on telegram (gold).")
+        );
+
+        ctx.session
+            .custom_state
+            .insert("user_name".to_string(), serde_json::json!("Grace"));
+        runtime.process(&mut ctx, "Hi again").await.unwrap();
+        let request = provider.last.lock().unwrap().take().unwrap();
+        assert!(
+            request
+                .system
+                .unwrap()
+                .starts_with("Help Grace on telegram (gold).")
+        );
+    }
+
+    /// Calls `bash` until it sees a tool result, then answers.
+    struct ToolCallingProvider {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for ToolCallingProvider {
+        fn name(&self) -> &'static str {
+            "tool-calling"
+        }
+
+        async fn list_models(
+            &self,
+        ) -> Result<Vec<String>, openclaw_providers::traits::ProviderError> {
+            Ok(vec![])
+        }
+
+        async fn complete(
+            &self,
+            request: openclaw_providers::traits::CompletionRequest,
+        ) -> Result<
+            openclaw_providers::traits::CompletionResponse,
+            openclaw_providers::traits::ProviderError,
+        > {
+            use openclaw_providers::traits::Role;
+
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let saw_result = request.messages.last().map(|m| m.role) == Some(Role::Tool);
+            let content = if saw_result {
+                vec![ContentBlock::Text {
+                    text: "Cleaned up old logs.".to_string(),
+                }]
+            } else {
+                vec![
+                    ContentBlock::Text {
+                        text: "I'll remove the old logs.".to_string(),
+                    },
+                    ContentBlock::ToolUse {
+                        id: "tu_1".to_string(),
+                        name: "bash".to_string(),
+                        input: serde_json::json!({ "command": "rm -rf logs" }),
+                    },
+                ]
+            };
+            Ok(openclaw_providers::traits::CompletionResponse {
+                id: "resp-1".to_string(),
+                model: request.model,
+                content,
+                stop_reason: None,
+                usage: TokenUsage {
+                    input_tokens: 10,
+                    output_tokens: 5,
+                    ..TokenUsage::default()
+                },
+            })
+        }
+
+        async fn complete_stream(
+            &self,
+            _request: openclaw_providers::traits::CompletionRequest,
+        ) -> Result<
+            std::pin::Pin<
+                Box<
+                    dyn futures::Stream<
+                            Item = Result<
+                                openclaw_providers::traits::StreamingChunk,
+                                openclaw_providers::traits::ProviderError,
+                            >,
+                        > + Send,
+                >,
+            >,
+            openclaw_providers::traits::ProviderError,
+        > {
+            Err(openclaw_providers::traits::ProviderError::Config(
+                "unsupported".to_string(),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_modes() {
+        let provider = Arc::new(ToolCallingProvider {
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        });
+        let runtime = AgentRuntime::new(provider.clone());
+
+        // Plan mode stops at the first tool call
+        let mut ctx = context();
+        ctx.session.dry_run = Some(DryRunMode::Plan);
+        let reply = runtime.process(&mut ctx, "Free up disk").await.unwrap();
+        assert_eq!(
+            provider.calls.swap(0, std::sync::atomic::Ordering::SeqCst),
+            1
+        );
+        assert_eq!(reply.planned_tools.len(), 1);
+        assert_eq!(reply.planned_tools[0].name, "bash");
+        assert!(
+            reply
+                .content
+                .starts_with("I'll remove the old logs.\n\nPlanned tool calls")
+        );
+
+        // Simulate mode feeds back a synthetic result and continues
+        let runtime = runtime.with_dry_run(Some(DryRunMode::Simulate));
+        let mut ctx = context();
+        let reply = runtime.process(&mut ctx, "Free up disk").await.unwrap();
+        assert_eq!(
+            provider.calls.swap(0, std::sync::atomic::Ordering::SeqCst),
+            2
+        );
+        assert_eq!(reply.content, "Cleaned up old logs.");
+        assert_eq!(reply.planned_tools.len(), 1);
+        assert_eq!(reply.usage.input_tokens, 20);
+
+        // Without dry run, tool calls are left to the caller
+        let runtime = runtime.with_dry_run(None);
+        let reply = runtime
+            .process(&mut context(), "Free up disk")
+            .await
+            .unwrap();
+        assert!(reply.planned_tools.is_empty());
+        assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tool_policy_denies_calls() {
+        let provider = Arc::new(ToolCallingProvider {
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        });
+        let runtime = AgentRuntime::new(provider)
+            .with_tool_policy(ToolPolicy::new(vec!["fs.*".to_string()], Vec::new()))
+            .with_dry_run(Some(DryRunMode::Simulate));
+
+        // The refusal goes back to the model, which carries on without it
+        let reply = runtime
+            .process(&mut context(), "Free up disk")
+            .await
+            .unwrap();
+        assert_eq!(reply.content, "Cleaned up old logs.");
+        assert!(reply.planned_tools.is_empty());
+        assert_eq!(reply.denied_tools.len(), 1);
+        assert_eq!(reply.denied_tools[0].name, "bash");
+        assert_eq!(
+            reply.denied_tools[0].reason,
+            "not in the agent's allowed tools"
+        );
+
+        // Outside dry run the call is reported and cannot be executed
+        let runtime = runtime.with_dry_run(None);
+        let reply = runtime
+            .process(&mut context(), "Free up disk")
+            .await
+            .unwrap();
+        assert_eq!(reply.denied_tools.len(), 1);
+        let err = runtime
+            .execute_tool("bash", serde_json::json!({ "command": "ls" }))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::Denied { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_tool_stubs() {
+        use crate::harness::{MockCompletion, MockProvider, MockToolCall};
+        use openclaw_providers::traits::ContentBlock;
+
+        let provider = Arc::new(MockProvider::new([
+            MockCompletion::Full {
+                text: None,
+                tool_calls: vec![MockToolCall {
+                    name: "weather".to_string(),
+                    input: serde_json::json!({ "city": "Oslo" }),
+                }],
+            },
+            MockCompletion::Text("It is raining in Oslo.".to_string()),
+        ]));
+        let runtime = AgentRuntime::new(provider.clone())
+            .with_tool_stubs(HashMap::from([(
+                "weather".to_string(),
+                "Rain, 8C".to_string(),
+            )]))
+            .with_dry_run(Some(DryRunMode::Simulate));
+
+        let reply = runtime
+            .process(&mut context(), "Weather in Oslo?")
+            .await
+            .unwrap();
+        assert_eq!(reply.content, "It is raining in Oslo.");
+        assert_eq!(provider.remaining(), 0);
+
+        // The model saw the stubbed result, not the dry-run note
+        let requests = provider.requests();
+        let Some(MessageContent::Blocks(blocks)) = requests[1].messages.last().map(|m| &m.content)
+        else {
+            panic!("expected tool results");
+        };
+        assert!(matches!(
+            &blocks[0],
+            ContentBlock::ToolResult { content, .. } if content == "Rain, 8C"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_process_structured_repairs_reply() {
+        use crate::harness::{MockCompletion, MockProvider};
+
+        let provider = Arc::new(MockProvider::new([
+            MockCompletion::Text(r#"{"priority": "high"}"#.to_string()),
+            MockCompletion::Text(r#"{"priority": 1}"#.to_string()),
+        ]));
+        let runtime = AgentRuntime::new(provider.clone()).with_system_prompt("Triage.");
+        let format = ResponseFormat::new(
+            "ticket",
+            serde_json::json!({
+                "type": "object",
+                "properties": { "priority": { "type": "integer" } },
+                "required": ["priority"],
+            }),
+        );
+
+        let reply = runtime
+            .process_structured(&mut context(), "Printer on fire", &[], &format)
+            .await
+            .unwrap();
+        assert_eq!(reply.data, Some(serde_json::json!({ "priority": 1 })));
+
+        // The second request carried the schema and the correction
+        let requests = provider.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].response_format, Some(format.clone()));
+        assert!(
+            requests[1]
+                .system
+                .as_deref()
+                .unwrap()
+                .starts_with("Triage.")
+        );
+        assert!(matches!(
+            &requests[1].messages.last().unwrap().content,
+            MessageContent::Text(text) if text.contains("/priority")
+        ));
+
+        // Out of repair attempts
+        provider.push(vec![MockCompletion::Text("{}".to_string()); 3]);
+        let err = runtime
+            .process_structured(&mut context(), "Printer on fire", &[], &format)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AgentRuntimeError::InvalidOutput(_)));
+        assert_eq!(provider.remaining(), 0);
+    }
+
+    #[test]
+    fn test_with_overrides() {
+        let provider = Arc::new(CapturingProvider {
+            last: std::sync::Mutex::new(None),
+        });
+        let runtime = AgentRuntime::new(provider)
+            .with_system_prompt("Be brief.")
+            .with_concurrency(ConcurrencyLimiter::new(2, 4));
+        let updated = runtime.with_overrides(&AgentOverrides {
+            model: Some("gpt-4o".to_string()),
+            temperature: Some(0.2),
+            ..AgentOverrides::default()
+        });
+
+        assert_eq!(updated.model(), "gpt-4o");
+        assert!((updated.temperature() - 0.2).abs() < f32::EPSILON);
+        assert_eq!(updated.system_prompt(), Some("Be brief."));
+        assert_eq!(runtime.model(), "claude-3-5-sonnet-20241022");
+        assert_eq!(updated.concurrency_stats().max_concurrent, Some(2));
+
+        let cleared = updated.with_overrides(&AgentOverrides {
+            system_prompt: Some(String::new()),
+            ..AgentOverrides::default()
+        });
+        assert_eq!(cleared.system_prompt(), None);
+    }
+
+    #[tokio::test]
+    async fn test_with_agent_tools() {
+        use crate::harness::MockProvider;
+        use crate::sandbox::SandboxLevel;
+        use crate::tools::{ApprovalDecision, ApprovalEvent, BashTool};
+        use openclaw_core::config::{EgressConfig, SandboxProfileConfig};
+
+        let mut tools = ToolRegistry::new();
+        tools.register(Arc::new(BashTool::new()));
+        let mut config = AgentConfig {
+            egress: EgressConfig {
+                enabled: true,
+                allowed_domains: vec!["api.github.com".to_string()],
+            },
+            ..AgentConfig::default()
+        };
+        config.sandbox.insert(
+            "bash".to_string(),
+            SandboxProfileConfig {
+                level: Some(SandboxLevel::Strict),
+                ..SandboxProfileConfig::default()
+            },
+        );
+
+        let runtime = AgentRuntime::new(Arc::new(MockProvider::default()))
+            .with_agent_tools("dev", tools, &config)
+            .await
+            .unwrap();
+        let proxy = runtime.egress_proxy().unwrap();
+        let sandbox = runtime.tools().get("bash").unwrap().sandbox().unwrap();
+        assert_eq!(sandbox.level, SandboxLevel::Strict);
+        assert_eq!(sandbox.egress_proxy, Some(proxy.addr()));
+        assert_eq!(sandbox.egress_socket.as_deref(), proxy.socket_path());
+        assert!(sandbox.unshares_network());
+
+        // Tools the approval policy lists wait for a decision
+        let gate = Arc::new(ApprovalGate::new());
+        let mut tools = ToolRegistry::new();
+        tools.register(Arc::new(BashTool::new()));
+        let mut config = AgentConfig::default();
+        config.approval.tools = vec!["bash".to_string()];
+        config.approval.timeout_secs = 0;
+        let runtime = AgentRuntime::new(Arc::new(MockProvider::default()))
+            .with_approval_gate(gate.clone())
+            .with_agent_tools("dev", tools, &config)
+            .await
+            .unwrap();
+        assert!(Arc::ptr_eq(runtime.approval_gate(), &gate));
+        let result = runtime
+            .execute_tool("bash", serde_json::json!({ "command": "true" }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not approved"));
+
+        // A call waiting for approval holds its slot
+        let mut tools = ToolRegistry::new();
+        tools.register(Arc::new(BashTool::new()));
+        let mut config = AgentConfig::default();
+        config.approval.tools = vec!["bash".to_string()];
+        config
+            .tool_concurrency
+            .per_tool
+            .insert("bash".to_string(), 1);
+        config.tool_concurrency.max_queued = 0;
+        let runtime = AgentRuntime::new(Arc::new(MockProvider::default()))
+            .with_agent_tools("dev", tools, &config)
+            .await
+            .unwrap();
+        let mut events = runtime.approval_gate().subscribe();
+        let first = tokio::spawn({
+            let runtime = runtime.clone();
+            async move {
+                runtime
+                    .execute_tool("bash", serde_json::json!({ "command": "true" }))
+                    .await
+            }
+        });
+        let Ok(ApprovalEvent::Requested(request)) = events.recv().await else {
+            panic!("expected an approval request");
+        };
+        let second = runtime
+            .execute_tool("bash", serde_json::json!({ "command": "true" }))
+            .await;
+        assert!(matches!(second, Err(ToolError::Busy(_))));
+        let denied = ApprovalDecision::Denied {
+            by: "ops".to_string(),
+            reason: None,
+        };
+        runtime.approval_gate().decide(&request.id, denied).unwrap();
+        assert!(!first.await.unwrap().unwrap().success);
+
+        config.tool_concurrency.max_concurrent = Some(0);
+        let result = AgentRuntime::new(Arc::new(MockProvider::default()))
+            .with_agent_tools("dev", ToolRegistry::new(), &config)
+            .await;
+        assert!(matches!(result, Err(AgentRuntimeError::Config(_))));
+
+        // Without egress, no proxy is started
+        let runtime = AgentRuntime::new(Arc::new(MockProvider::default()))
+            .with_agent_tools("dev", ToolRegistry::new(), &AgentConfig::default())
+            .await
+            .unwrap();
+        assert!(runtime.egress_proxy().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_replay() {
+        use openclaw_core::events::{SessionEvent, SessionEventKind};
+
+        let provider = Arc::new(CapturingProvider {
+            last: std::sync::Mutex::new(None),
+        });
+        let runtime = AgentRuntime::new(provider.clone()).with_model("candidate");
+        let key = SessionKey::new("recorded");
+        let event = |kind| SessionEvent::new(key.clone(), "default".to_string(), kind);
+        let events = vec![
+            event(SessionEventKind::SessionStarted {
+                channel: "telegram".to_string(),
+                peer_id: "alice".to_string(),
+            }),
+            event(SessionEventKind::MessageReceived {
+                content: "What is this?".to_string(),
+                attachments: vec![],
+                sender_id: None,
+            }),
+            event(SessionEventKind::AgentResponse {
+                content: "A dog.".to_string(),
+                model: "baseline".to_string(),
+                tokens: TokenUsage::default(),
+                latency_ms: None,
+                citations: vec![],
+            }),
+            event(SessionEventKind::MessageReceived {
+                content: "Are you sure?".to_string(),
+                attachments: vec![],
+                sender_id: None,
+            }),
+        ];
+
+        let report = runtime
+            .replay(&events, Arc::new(ToolRegistry::new()))
+            .await
+            .unwrap();
+        assert_eq!(report.model, "candidate");
+        assert_eq!(report.turns.len(), 2);
+        assert_eq!(report.changed, 2);
+        assert_eq!(report.turns[0].recorded.as_deref(), Some("A dog."));
+        assert_eq!(report.turns[0].replayed, "A cat.");
+        assert_eq!(
+            report.turns[0].diff,
+            vec![
+                DiffLine::Removed("A dog.".to_string()),
+                DiffLine::Added("A cat.".to_string()),
+            ]
+        );
+        assert!(report.turns[1].recorded.is_none());
+
+        // Later turns see the recorded reply, not the replayed one
+        let request = provider.last.lock().unwrap().take().unwrap();
+        assert_eq!(request.messages.len(), 3);
+        assert!(matches!(
+            &request.messages[1].content,
+            MessageContent::Text(text) if text == "A dog."
+        ));
+    }
 }
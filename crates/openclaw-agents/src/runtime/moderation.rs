@@ -0,0 +1,371 @@
+//! Content moderation.
+//!
+//! Inbound messages are screened before they reach the model and replies
+//! before they are returned. Each [`Moderator`] judges the text on its
+//! own and the first to object decides. Built-in moderators match word
+//! lists and ask the provider's moderation endpoint; plugins and embedders
+//! add their own with [`super::AgentRuntime::with_moderator`]. A moderator
+//! that fails is logged and skipped.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use openclaw_core::config::{ModerationAction, ModerationConfig};
+use openclaw_providers::traits::Provider;
+
+use super::AgentContext;
+
+/// When content is screened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationStage {
+    /// A message on its way to the model.
+    Inbound,
+    /// A reply on its way to the user.
+    Outbound,
+}
+
+impl ModerationStage {
+    /// Stage name.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Inbound => "inbound",
+            Self::Outbound => "outbound",
+        }
+    }
+}
+
+impl std::fmt::Display for ModerationStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What a moderator objected to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModerationFinding {
+    /// Why the content was caught.
+    pub reason: String,
+    /// Policy categories it falls under, if the moderator has them.
+    pub categories: Vec<String>,
+}
+
+impl ModerationFinding {
+    /// A finding without categories.
+    #[must_use]
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+            categories: Vec::new(),
+        }
+    }
+}
+
+/// Content caught by moderation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Violation {
+    /// When it was caught.
+    pub stage: ModerationStage,
+    /// Moderator that caught it.
+    pub moderator: String,
+    /// Why it was caught.
+    pub reason: String,
+    /// Policy categories it falls under.
+    pub categories: Vec<String>,
+    /// Whether it was held back rather than let through.
+    pub blocked: bool,
+}
+
+/// Judges messages and replies.
+#[async_trait]
+pub trait Moderator: Send + Sync {
+    /// Moderator name, recorded with violations.
+    fn name(&self) -> &str;
+
+    /// Check `text` at `stage` of a turn in `ctx`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the failure if the text could not be
+    /// checked.
+    async fn check(
+        &self,
+        text: &str,
+        stage: ModerationStage,
+        ctx: &AgentContext,
+    ) -> Result<Option<ModerationFinding>, String>;
+}
+
+/// Catches keywords, as whole words ignoring case, and regular
+/// expressions.
+#[derive(Debug, Clone)]
+pub struct WordListModerator {
+    keywords: Option<Regex>,
+    patterns: Vec<Regex>,
+}
+
+impl WordListModerator {
+    /// Catch `keywords` and `patterns`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a pattern is not a valid regular expression.
+    pub fn new(keywords: &[String], patterns: &[String]) -> Result<Self, regex::Error> {
+        let keywords = if keywords.is_empty() {
+            None
+        } else {
+            let alternatives: Vec<String> = keywords
+                .iter()
+                .map(|keyword| regex::escape(keyword.trim()))
+                .collect();
+            // Word boundaries that also work for keywords like "c++"
+            Some(Regex::new(&format!(
+                r"(?i)(?:^|\W)({})(?:\W|$)",
+                alternatives.join("|")
+            ))?)
+        };
+        let patterns = patterns
+            .iter()
+            .map(String::as_str)
+            .map(Regex::new)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { keywords, patterns })
+    }
+
+    fn find(&self, text: &str) -> Option<ModerationFinding> {
+        if let Some(keyword) = self
+            .keywords
+            .as_ref()
+            .and_then(|keywords| keywords.captures(text))
+            .and_then(|captures| captures.get(1))
+        {
+            return Some(ModerationFinding::new(format!(
+                "contains '{}'",
+                keyword.as_str()
+            )));
+        }
+        self.patterns
+            .iter()
+            .find(|pattern| pattern.is_match(text))
+            .map(|pattern| ModerationFinding::new(format!("matches /{}/", pattern.as_str())))
+    }
+}
+
+#[async_trait]
+impl Moderator for WordListModerator {
+    fn name(&self) -> &'static str {
+        "word_list"
+    }
+
+    async fn check(
+        &self,
+        text: &str,
+        _stage: ModerationStage,
+        _ctx: &AgentContext,
+    ) -> Result<Option<ModerationFinding>, String> {
+        Ok(self.find(text))
+    }
+}
+
+/// Asks a provider's moderation endpoint.
+pub struct ProviderModerator {
+    provider: Arc<dyn Provider>,
+    categories: Vec<String>,
+}
+
+impl ProviderModerator {
+    /// Ask `provider`, counting only `categories` (every flagged
+    /// category if empty).
+    #[must_use]
+    pub const fn new(provider: Arc<dyn Provider>, categories: Vec<String>) -> Self {
+        Self {
+            provider,
+            categories,
+        }
+    }
+}
+
+#[async_trait]
+impl Moderator for ProviderModerator {
+    fn name(&self) -> &str {
+        self.provider.name()
+    }
+
+    async fn check(
+        &self,
+        text: &str,
+        _stage: ModerationStage,
+        _ctx: &AgentContext,
+    ) -> Result<Option<ModerationFinding>, String> {
+        let result = self
+            .provider
+            .moderate(text)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !result.flagged {
+            return Ok(None);
+        }
+        let categories: Vec<String> = if self.categories.is_empty() {
+            result.categories
+        } else {
+            result
+                .categories
+                .into_iter()
+                .filter(|category| self.categories.contains(category))
+                .collect()
+        };
+        if categories.is_empty() && !self.categories.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(ModerationFinding {
+            reason: format!("flagged by {} moderation", self.provider.name()),
+            categories,
+        }))
+    }
+}
+
+/// An agent's moderators and what happens to content they catch.
+#[derive(Clone)]
+pub struct Moderation {
+    moderators: Vec<Arc<dyn Moderator>>,
+    inbound: bool,
+    outbound: bool,
+    action: ModerationAction,
+    notify_admins: bool,
+    blocked_message: Option<String>,
+}
+
+impl Moderation {
+    /// Apply an agent's settings, adding the moderators they turn on.
+    /// Moderators already added are kept.
+    pub(crate) fn configure(&mut self, config: &ModerationConfig, provider: &Arc<dyn Provider>) {
+        self.inbound = config.inbound;
+        self.outbound = config.outbound;
+        self.action = config.action;
+        self.notify_admins = config.notify_admins;
+        self.blocked_message.clone_from(&config.blocked_message);
+        if !config.keywords.is_empty() || !config.patterns.is_empty() {
+            // Patterns are checked when the config is loaded
+            match WordListModerator::new(&config.keywords, &config.patterns) {
+                Ok(moderator) => self.moderators.push(Arc::new(moderator)),
+                Err(e) => tracing::error!("Moderation word list ignored: {}", e),
+            }
+        }
+        if config.provider {
+            self.moderators.push(Arc::new(ProviderModerator::new(
+                provider.clone(),
+                config.categories.clone(),
+            )));
+        }
+    }
+
+    pub(crate) fn add(&mut self, moderator: Arc<dyn Moderator>) {
+        self.moderators.push(moderator);
+    }
+
+    /// Whether anything is screened.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        !self.moderators.is_empty() && (self.inbound || self.outbound)
+    }
+
+    /// Whether operators are told about violations.
+    #[must_use]
+    pub const fn notify_admins(&self) -> bool {
+        self.notify_admins
+    }
+
+    /// Reply sent in place of blocked content, if set.
+    #[must_use]
+    pub fn blocked_message(&self) -> Option<&str> {
+        self.blocked_message.as_deref()
+    }
+
+    /// Screen `text` at `stage` of a turn in `ctx`.
+    pub async fn screen(
+        &self,
+        text: &str,
+        stage: ModerationStage,
+        ctx: &AgentContext,
+    ) -> Option<Violation> {
+        let screened = match stage {
+            ModerationStage::Inbound => self.inbound,
+            ModerationStage::Outbound => self.outbound,
+        };
+        if !screened || text.trim().is_empty() {
+            return None;
+        }
+
+        for moderator in &self.moderators {
+            match moderator.check(text, stage, ctx).await {
+                Ok(Some(finding)) => {
+                    let violation = Violation {
+                        stage,
+                        moderator: moderator.name().to_string(),
+                        reason: finding.reason,
+                        categories: finding.categories,
+                        blocked: self.action == ModerationAction::Block,
+                    };
+                    tracing::warn!(
+                        session = %ctx.session_key,
+                        %stage,
+                        moderator = %violation.moderator,
+                        reason = %violation.reason,
+                        blocked = violation.blocked,
+                        "Moderation caught content"
+                    );
+                    return Some(violation);
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!(
+                    session = %ctx.session_key,
+                    moderator = moderator.name(),
+                    "Moderator failed: {}",
+                    e
+                ),
+            }
+        }
+        None
+    }
+}
+
+impl Default for Moderation {
+    fn default() -> Self {
+        Self {
+            moderators: Vec::new(),
+            inbound: true,
+            outbound: true,
+            action: ModerationAction::default(),
+            notify_admins: false,
+            blocked_message: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_list() {
+        let moderator = WordListModerator::new(
+            &["buy now".to_string(), "c++".to_string()],
+            &[r"\b\d{4}-\d{4}-\d{4}-\d{4}\b".to_string()],
+        )
+        .unwrap();
+
+        let finding = moderator.find("Please BUY NOW!").unwrap();
+        assert_eq!(finding.reason, "contains 'BUY NOW'");
+        assert!(moderator.find("I like c++").is_some());
+        // Whole words only
+        assert!(moderator.find("buy nowhere").is_none());
+        assert!(moderator.find("card 1234-5678-9012-3456").is_some());
+        assert!(moderator.find("hello").is_none());
+
+        assert!(WordListModerator::new(&[], &["(open".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,237 @@
+//! Re-running a recorded session against a different model or prompt.
+//!
+//! Replay feeds each recorded turn's inbound messages back through an agent
+//! and diffs the new reply against the recorded one, so two configurations
+//! can be compared on real conversations.
+
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use openclaw_core::events::{DryRunMode, SessionEvent, SessionEventKind, SessionProjection};
+use openclaw_core::types::{AgentId, ChannelId, TokenUsage};
+
+use super::{AgentContext, AgentRuntime, AgentRuntimeError};
+use crate::tools::ToolRegistry;
+
+/// One line of a diff between a recorded and a replayed reply.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "op", content = "text", rename_all = "snake_case")]
+pub enum DiffLine {
+    /// In both replies.
+    Same(String),
+    /// Only in the recorded reply.
+    Removed(String),
+    /// Only in the replayed reply.
+    Added(String),
+}
+
+/// A recorded turn and its replayed reply.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayTurn {
+    /// Inbound message, or messages answered together joined by newlines.
+    pub input: String,
+    /// Reply recorded in the session; `None` if the turn was unanswered.
+    pub recorded: Option<String>,
+    /// Reply from the replay.
+    pub replayed: String,
+    /// Whether the replayed reply differs from the recorded one.
+    pub changed: bool,
+    /// Line diff from the recorded to the replayed reply.
+    pub diff: Vec<DiffLine>,
+    /// Tokens used by the replay.
+    pub usage: TokenUsage,
+}
+
+/// Outcome of replaying a session.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayReport {
+    /// Model the replay ran on.
+    pub model: String,
+    /// Replayed turns, in order.
+    pub turns: Vec<ReplayTurn>,
+    /// Number of turns whose reply changed.
+    pub changed: usize,
+    /// Tokens used across all turns.
+    pub usage: TokenUsage,
+}
+
+impl AgentRuntime {
+    /// Re-run each recorded turn of a session and diff the replies with
+    /// the recorded ones.
+    ///
+    /// Every turn sees the recorded conversation before it, not earlier
+    /// replayed replies, so each turn is compared like for like. Tool calls
+    /// are planned, never run.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a provider call fails.
+    pub async fn replay(
+        &self,
+        events: &[SessionEvent],
+        tools: Arc<ToolRegistry>,
+    ) -> Result<ReplayReport, AgentRuntimeError> {
+        let mut report = ReplayReport {
+            model: self.model.clone(),
+            turns: Vec::new(),
+            changed: 0,
+            usage: TokenUsage::default(),
+        };
+        let mut projection: Option<SessionProjection> = None;
+        // Conversation before the current turn, and its inbound messages
+        let mut pending: Option<(SessionProjection, Vec<String>)> = None;
+
+        for event in events {
+            match &event.kind {
+                SessionEventKind::MessageReceived { content, .. } => {
+                    let history = projection.clone().unwrap_or_else(|| new_projection(event));
+                    pending
+                        .get_or_insert_with(|| (history, Vec::new()))
+                        .1
+                        .push(content.clone());
+                }
+                SessionEventKind::AgentResponse { content, .. } => {
+                    if let Some((history, inputs)) = pending.take() {
+                        let turn = self
+                            .replay_turn(history, &inputs, Some(content), &tools)
+                            .await?;
+                        report.add(turn);
+                    }
+                }
+                _ => {}
+            }
+            projection
+                .get_or_insert_with(|| new_projection(event))
+                .apply(event);
+        }
+
+        if let Some((history, inputs)) = pending {
+            let turn = self.replay_turn(history, &inputs, None, &tools).await?;
+            report.add(turn);
+        }
+        Ok(report)
+    }
+
+    async fn replay_turn(
+        &self,
+        mut history: SessionProjection,
+        inputs: &[String],
+        recorded: Option<&str>,
+        tools: &Arc<ToolRegistry>,
+    ) -> Result<ReplayTurn, AgentRuntimeError> {
+        history.dry_run = Some(DryRunMode::Plan);
+        let input = inputs.join("\n");
+        let mut ctx = AgentContext::new(
+            AgentId::new(history.agent_id.clone()),
+            history.session_key.clone(),
+            history,
+            tools.clone(),
+        );
+
+        let reply = self.process(&mut ctx, &input).await?;
+        Ok(ReplayTurn {
+            changed: recorded != Some(reply.content.as_str()),
+            diff: diff_lines(recorded.unwrap_or_default(), &reply.content),
+            input,
+            recorded: recorded.map(String::from),
+            replayed: reply.content,
+            usage: reply.usage,
+        })
+    }
+}
+
+impl ReplayReport {
+    fn add(&mut self, turn: ReplayTurn) {
+        if turn.changed {
+            self.changed += 1;
+        }
+        self.usage.accumulate(&turn.usage);
+        self.turns.push(turn);
+    }
+}
+
+/// Projection for a session whose first event is `event`, as the event
+/// store creates it.
+fn new_projection(event: &SessionEvent) -> SessionProjection {
+    let (channel, peer_id) = match &event.kind {
+        SessionEventKind::SessionStarted { channel, peer_id } => {
+            (channel.as_str(), peer_id.as_str())
+        }
+        _ => ("unknown", "unknown"),
+    };
+    SessionProjection::new(
+        event.session_key.clone(),
+        event.agent_id.clone(),
+        ChannelId::new(channel),
+        peer_id.to_string(),
+    )
+}
+
+/// Line diff from `old` to `new`, keeping the longest run of common lines.
+#[must_use]
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // common[i][j]: length of the longest common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            diff.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines(
+            "Hello\nThe answer is 4.\nBye",
+            "Hello\nThe answer is four.\nBye",
+        );
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Same("Hello".to_string()),
+                DiffLine::Removed("The answer is 4.".to_string()),
+                DiffLine::Added("The answer is four.".to_string()),
+                DiffLine::Same("Bye".to_string()),
+            ]
+        );
+
+        assert_eq!(
+            diff_lines("", "new"),
+            vec![DiffLine::Added("new".to_string())]
+        );
+        assert!(
+            diff_lines("same", "same")
+                .iter()
+                .all(|l| matches!(l, DiffLine::Same(_)))
+        );
+    }
+}
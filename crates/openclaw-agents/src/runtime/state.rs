@@ -0,0 +1,283 @@
+//! Per-session key-value state.
+//!
+//! Agents store structured facts (preferences, task lists) on a session as
+//! `StateChanged` events, so they survive restarts and replay with the rest
+//! of the session. The session projection holds the current values.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::hash::BuildHasher;
+use std::sync::Arc;
+
+use openclaw_core::events::{EventStore, EventStoreError, SessionEvent, SessionEventKind};
+use openclaw_core::types::SessionKey;
+use openclaw_core::validation::limits::{
+    MAX_STATE_KEY_LENGTH, MAX_STATE_KEYS, MAX_STATE_VALUE_SIZE,
+};
+
+/// Session state errors.
+#[derive(Debug, thiserror::Error)]
+pub enum SessionStateError {
+    /// Key is empty, too long, or has disallowed characters.
+    #[error(
+        "Invalid state key '{0}': use up to {max} letters, digits, '.', '_' or '-'",
+        max = MAX_STATE_KEY_LENGTH
+    )]
+    InvalidKey(String),
+
+    /// Value exceeds `MAX_STATE_VALUE_SIZE` when serialized.
+    #[error("Value for '{key}' is {size} bytes (limit {max})", max = MAX_STATE_VALUE_SIZE)]
+    ValueTooLarge {
+        /// State key.
+        key: String,
+        /// Serialized size.
+        size: usize,
+    },
+
+    /// Session already has `MAX_STATE_KEYS` keys.
+    #[error("Session already has {max} state keys", max = MAX_STATE_KEYS)]
+    TooManyKeys,
+
+    /// Event store error (including unknown session).
+    #[error(transparent)]
+    Store(#[from] EventStoreError),
+}
+
+/// Reads and writes session state through the event store.
+#[derive(Clone)]
+pub struct SessionStateStore {
+    event_store: Arc<EventStore>,
+}
+
+impl SessionStateStore {
+    /// Create a state store backed by `event_store`.
+    #[must_use]
+    pub const fn new(event_store: Arc<EventStore>) -> Self {
+        Self { event_store }
+    }
+
+    /// All state for a session.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Store` if the session does not exist or cannot be read.
+    pub fn all(
+        &self,
+        session_key: &SessionKey,
+    ) -> Result<HashMap<String, serde_json::Value>, SessionStateError> {
+        Ok(self.event_store.get_projection(session_key)?.custom_state)
+    }
+
+    /// Value of `key`, if set.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Store` if the session does not exist or cannot be read.
+    pub fn get(
+        &self,
+        session_key: &SessionKey,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, SessionStateError> {
+        Ok(self.all(session_key)?.remove(key))
+    }
+
+    /// Set `key` to `value`. Setting `null` deletes the key.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidKey`, `ValueTooLarge`, or `TooManyKeys` if a limit
+    /// is exceeded, or `Store` if the session does not exist or the event
+    /// cannot be written.
+    pub fn set(
+        &self,
+        session_key: &SessionKey,
+        key: &str,
+        value: serde_json::Value,
+    ) -> Result<(), SessionStateError> {
+        validate_state_key(key)?;
+        let size = serde_json::to_vec(&value)
+            .map_err(EventStoreError::from)?
+            .len();
+        if size > MAX_STATE_VALUE_SIZE {
+            return Err(SessionStateError::ValueTooLarge {
+                key: key.to_string(),
+                size,
+            });
+        }
+
+        let projection = self.event_store.get_projection(session_key)?;
+        if !value.is_null()
+            && !projection.custom_state.contains_key(key)
+            && projection.custom_state.len() >= MAX_STATE_KEYS
+        {
+            return Err(SessionStateError::TooManyKeys);
+        }
+
+        self.event_store.append(&SessionEvent::new(
+            session_key.clone(),
+            projection.agent_id,
+            SessionEventKind::StateChanged {
+                key: key.to_string(),
+                value,
+            },
+        ))?;
+        Ok(())
+    }
+
+    /// Delete `key`. Returns whether it was set.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Store` if the session does not exist or the event cannot be
+    /// written.
+    pub fn delete(&self, session_key: &SessionKey, key: &str) -> Result<bool, SessionStateError> {
+        if self.get(session_key, key)?.is_none() {
+            return Ok(false);
+        }
+        self.set(session_key, key, serde_json::Value::Null)?;
+        Ok(true)
+    }
+}
+
+impl std::fmt::Debug for SessionStateStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionStateStore").finish_non_exhaustive()
+    }
+}
+
+/// Check a state key: 1 to `MAX_STATE_KEY_LENGTH` ASCII letters, digits,
+/// `.`, `_`, or `-`.
+///
+/// # Errors
+///
+/// Returns `InvalidKey` if the key does not qualify.
+pub fn validate_state_key(key: &str) -> Result<(), SessionStateError> {
+    let valid = !key.is_empty()
+        && key.len() <= MAX_STATE_KEY_LENGTH
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if valid {
+        Ok(())
+    } else {
+        Err(SessionStateError::InvalidKey(key.to_string()))
+    }
+}
+
+/// Render session state as a system prompt section.
+#[must_use]
+pub fn session_state_prompt<S: BuildHasher>(
+    state: &HashMap<String, serde_json::Value, S>,
+) -> Option<String> {
+    if state.is_empty() {
+        return None;
+    }
+
+    let mut keys: Vec<&String> = state.keys().collect();
+    keys.sort_unstable();
+
+    let mut prompt = String::from("# Session State\n\nFacts saved earlier in this conversation:\n");
+    for key in keys {
+        let _ = write!(prompt, "\n- {key}: {}", state[key]);
+    }
+    Some(prompt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openclaw_core::types::ChannelId;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn store_with_session(dir: &std::path::Path) -> (SessionStateStore, SessionKey) {
+        let event_store = Arc::new(EventStore::open(dir).unwrap());
+        let session_key = SessionKey::new("telegram:42");
+        event_store
+            .append(&SessionEvent::new(
+                session_key.clone(),
+                "default".to_string(),
+                SessionEventKind::SessionStarted {
+                    channel: ChannelId::telegram().to_string(),
+                    peer_id: "42".to_string(),
+                },
+            ))
+            .unwrap();
+        (SessionStateStore::new(event_store), session_key)
+    }
+
+    #[test]
+    fn test_set_get_delete() {
+        let temp = tempdir().unwrap();
+        let (store, session) = store_with_session(temp.path());
+
+        store
+            .set(&session, "preferences.units", json!("metric"))
+            .unwrap();
+        store
+            .set(&session, "todo", json!(["buy milk", "call bob"]))
+            .unwrap();
+        assert_eq!(
+            store.get(&session, "preferences.units").unwrap(),
+            Some(json!("metric"))
+        );
+        assert_eq!(store.all(&session).unwrap().len(), 2);
+
+        assert!(store.delete(&session, "todo").unwrap());
+        assert!(!store.delete(&session, "todo").unwrap());
+        assert_eq!(store.get(&session, "todo").unwrap(), None);
+
+        let prompt = session_state_prompt(&store.all(&session).unwrap()).unwrap();
+        assert!(prompt.contains("- preferences.units: \"metric\""));
+    }
+
+    #[test]
+    fn test_limits() {
+        let temp = tempdir().unwrap();
+        let (store, session) = store_with_session(temp.path());
+
+        assert!(matches!(
+            store.set(&session, "bad key", json!(1)),
+            Err(SessionStateError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            store.set(&session, "big", json!("x".repeat(MAX_STATE_VALUE_SIZE))),
+            Err(SessionStateError::ValueTooLarge { .. })
+        ));
+
+        for i in 0..MAX_STATE_KEYS {
+            store.set(&session, &format!("k{i}"), json!(i)).unwrap();
+        }
+        assert!(matches!(
+            store.set(&session, "one-more", json!(true)),
+            Err(SessionStateError::TooManyKeys)
+        ));
+        // Existing keys can still be updated
+        store.set(&session, "k0", json!("updated")).unwrap();
+
+        assert!(matches!(
+            store.set(&SessionKey::new("missing"), "k", json!(1)),
+            Err(SessionStateError::Store(_))
+        ));
+    }
+
+    #[test]
+    fn test_error_messages() {
+        assert_eq!(
+            SessionStateError::InvalidKey("bad key".to_string()).to_string(),
+            "Invalid state key 'bad key': use up to 128 letters, digits, '.', '_' or '-'"
+        );
+        let too_large = SessionStateError::ValueTooLarge {
+            key: "notes".to_string(),
+            size: 20_000,
+        };
+        assert_eq!(
+            too_large.to_string(),
+            "Value for 'notes' is 20000 bytes (limit 16384)"
+        );
+        assert_eq!(
+            SessionStateError::TooManyKeys.to_string(),
+            "Session already has 64 state keys"
+        );
+    }
+}
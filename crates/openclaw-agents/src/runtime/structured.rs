@@ -0,0 +1,116 @@
+//! Replies constrained to a JSON schema.
+
+use openclaw_providers::traits::ResponseFormat;
+
+/// Times the model is asked to fix a reply that does not match the schema.
+pub const MAX_REPAIR_ATTEMPTS: u32 = 2;
+
+/// Checks replies against a response format's schema.
+pub struct OutputValidator {
+    validator: jsonschema::Validator,
+}
+
+impl OutputValidator {
+    /// Compile the format's schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the problem if the schema is invalid.
+    pub fn new(format: &ResponseFormat) -> Result<Self, String> {
+        let validator = jsonschema::validator_for(&format.schema)
+            .map_err(|e| format!("invalid schema for {}: {e}", format.name))?;
+        Ok(Self { validator })
+    }
+
+    /// Parse a reply and check it against the schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns what is wrong with the reply, one problem per line.
+    pub fn check(&self, text: &str) -> Result<serde_json::Value, String> {
+        let value: serde_json::Value = serde_json::from_str(strip_fences(text))
+            .map_err(|e| format!("reply is not valid JSON: {e}"))?;
+        let problems: Vec<String> = self
+            .validator
+            .iter_errors(&value)
+            .map(|e| {
+                let path = e.instance_path.to_string();
+                if path.is_empty() {
+                    e.to_string()
+                } else {
+                    format!("{path}: {e}")
+                }
+            })
+            .collect();
+        if problems.is_empty() {
+            Ok(value)
+        } else {
+            Err(problems.join("\n"))
+        }
+    }
+}
+
+/// System prompt lines asking for a reply matching the format, for
+/// providers without native structured output.
+#[must_use]
+pub fn schema_instructions(format: &ResponseFormat) -> String {
+    format!(
+        "Reply with only a JSON value ({}) matching this JSON schema, with no other text:\n{}",
+        format.name, format.schema
+    )
+}
+
+/// Message asking the model to correct a reply.
+#[must_use]
+pub fn repair_prompt(problems: &str) -> String {
+    format!(
+        "Your reply did not match the required JSON schema:\n{problems}\n\nReply again with only the corrected JSON."
+    )
+}
+
+/// Drop a Markdown code fence around a reply, if any.
+fn strip_fences(text: &str) -> &str {
+    let trimmed = text.trim();
+    let Some(inner) = trimmed
+        .strip_prefix("```")
+        .and_then(|rest| rest.strip_suffix("```"))
+    else {
+        return trimmed;
+    };
+    // Skip the info string, e.g. ```json
+    inner
+        .split_once('\n')
+        .map_or(inner, |(_, body)| body)
+        .trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_reply() {
+        let format = ResponseFormat::new(
+            "ticket",
+            serde_json::json!({
+                "type": "object",
+                "properties": { "priority": { "type": "integer" } },
+                "required": ["priority"],
+            }),
+        );
+        let validator = OutputValidator::new(&format).unwrap();
+
+        let data = validator.check("```json\n{\"priority\": 2}\n```").unwrap();
+        assert_eq!(data["priority"], 2);
+
+        let problems = validator.check(r#"{"priority": "high"}"#).unwrap_err();
+        assert!(problems.starts_with("/priority: "));
+        assert!(validator.check("{}").is_err());
+        assert!(
+            validator
+                .check("not json")
+                .unwrap_err()
+                .contains("not valid JSON")
+        );
+    }
+}
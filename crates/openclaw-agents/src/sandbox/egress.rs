@@ -0,0 +1,471 @@
+//! Egress proxy for sandboxed commands.
+//!
+//! A local HTTP proxy that forwards `CONNECT` tunnels and plain `http://`
+//! requests only to hosts on an agent's allowlist, refuses private and
+//! loopback addresses, and writes every request to the audit log
+//! (`security` tracing target).
+//!
+//! The proxy listens on loopback and on a unix socket. Sandboxes with
+//! their own network namespace cannot reach the host's loopback, so the
+//! socket is mounted into them instead.
+
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+use openclaw_core::config::EgressConfig;
+
+/// Largest request head the proxy reads.
+const MAX_HEAD_SIZE: usize = 16 * 1024;
+
+/// A running egress proxy for one agent.
+///
+/// The proxy stops, and its socket is removed, when this is dropped.
+pub struct EgressProxy {
+    addr: SocketAddr,
+    socket: Option<PathBuf>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl EgressProxy {
+    /// Start a proxy on a free loopback port, and on unix a socket in the
+    /// temp directory, enforcing `policy` for `agent_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a listening socket cannot be bound.
+    pub async fn start(agent_id: impl Into<String>, policy: EgressConfig) -> std::io::Result<Self> {
+        let agent_id: String = agent_id.into();
+        let agent_id: Arc<str> = Arc::from(agent_id);
+        let policy = Arc::new(policy);
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let mut tasks = Vec::new();
+
+        #[cfg(unix)]
+        let socket = {
+            let path = std::env::temp_dir().join(format!(
+                "openclaw-egress-{}-{}.sock",
+                std::process::id(),
+                addr.port()
+            ));
+            let _ = std::fs::remove_file(&path);
+            let unix_listener = tokio::net::UnixListener::bind(&path)?;
+            let (agent_id, policy) = (agent_id.clone(), policy.clone());
+            tasks.push(tokio::spawn(async move {
+                loop {
+                    match unix_listener.accept().await {
+                        Ok((stream, _)) => serve(stream, &agent_id, &policy),
+                        Err(e) => tracing::warn!("Egress proxy accept failed: {}", e),
+                    }
+                }
+            }));
+            Some(path)
+        };
+        #[cfg(not(unix))]
+        let socket = None;
+
+        tracing::info!(agent = %agent_id, %addr, ?socket, "Egress proxy listening");
+        tasks.push(tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => serve(stream, &agent_id, &policy),
+                    Err(e) => tracing::warn!("Egress proxy accept failed: {}", e),
+                }
+            }
+        }));
+
+        Ok(Self {
+            addr,
+            socket,
+            tasks,
+        })
+    }
+
+    /// Loopback address the proxy listens on.
+    #[must_use]
+    pub const fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Unix socket the proxy listens on; `None` where unix sockets are
+    /// unavailable.
+    #[must_use]
+    pub fn socket_path(&self) -> Option<&Path> {
+        self.socket.as_deref()
+    }
+}
+
+impl Drop for EgressProxy {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+        if let Some(socket) = &self.socket {
+            let _ = std::fs::remove_file(socket);
+        }
+    }
+}
+
+impl std::fmt::Debug for EgressProxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EgressProxy")
+            .field("addr", &self.addr)
+            .field("socket", &self.socket)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Handle one client connection in its own task.
+fn serve<S>(stream: S, agent_id: &Arc<str>, policy: &Arc<EgressConfig>)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let agent_id = agent_id.clone();
+    let policy = policy.clone();
+    tokio::spawn(async move {
+        if let Err(e) = handle_connection(stream, &agent_id, &policy).await {
+            tracing::debug!(agent = %agent_id, "Egress connection closed: {}", e);
+        }
+    });
+}
+
+/// Whether `ip` is loopback, private, link-local, or otherwise not a
+/// public internet address.
+#[must_use]
+pub fn is_private_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                || a == 0
+                // Carrier-grade NAT (100.64.0.0/10)
+                || (a == 100 && (b & 0xc0) == 64)
+                // Benchmarking (198.18.0.0/15) and reserved (240.0.0.0/4)
+                || (a == 198 && (b & 0xfe) == 18)
+                || a >= 240
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_private_address(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            v6.is_loopback()
+                || v6.is_unspecified()
+                // Unique local (fc00::/7) and link-local (fe80::/10)
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+/// A parsed proxy request.
+struct ProxyRequest {
+    method: String,
+    host: String,
+    port: u16,
+    /// Whether this is a `CONNECT` tunnel.
+    tunnel: bool,
+    /// Bytes to send upstream before relaying.
+    forward: Vec<u8>,
+}
+
+async fn handle_connection<S>(
+    mut client: S,
+    agent_id: &str,
+    policy: &EgressConfig,
+) -> std::io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let Some((head, rest)) = read_head(&mut client).await? else {
+        return respond(&mut client, "400 Bad Request").await;
+    };
+    let Some(request) = parse_request(&head, &rest) else {
+        audit(agent_id, "?", "?", 0, false, "malformed request");
+        return respond(&mut client, "400 Bad Request").await;
+    };
+
+    let upstream = match resolve_allowed(&request, policy).await {
+        Ok(addr) => addr,
+        Err(reason) => {
+            audit(
+                agent_id,
+                &request.method,
+                &request.host,
+                request.port,
+                false,
+                reason,
+            );
+            return respond(&mut client, "403 Forbidden").await;
+        }
+    };
+
+    let Ok(mut server) = TcpStream::connect(upstream).await else {
+        audit(
+            agent_id,
+            &request.method,
+            &request.host,
+            request.port,
+            false,
+            "connect failed",
+        );
+        return respond(&mut client, "502 Bad Gateway").await;
+    };
+    audit(
+        agent_id,
+        &request.method,
+        &request.host,
+        request.port,
+        true,
+        "allowed",
+    );
+
+    if request.tunnel {
+        client
+            .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+            .await?;
+    }
+    server.write_all(&request.forward).await?;
+    tokio::io::copy_bidirectional(&mut client, &mut server).await?;
+    Ok(())
+}
+
+/// Read up to the end of the request head, returning the head and any
+/// bytes read past it.
+async fn read_head(
+    stream: &mut (impl AsyncRead + Unpin),
+) -> std::io::Result<Option<(String, Vec<u8>)>> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            let rest = buf.split_off(end + 4);
+            return Ok(String::from_utf8(buf).ok().map(|head| (head, rest)));
+        }
+        if buf.len() > MAX_HEAD_SIZE {
+            return Ok(None);
+        }
+    }
+}
+
+/// Parse a `CONNECT host:port` or absolute-form `http://` request.
+fn parse_request(head: &str, rest: &[u8]) -> Option<ProxyRequest> {
+    let (request_line, headers) = head.split_once("\r\n")?;
+    let mut parts = request_line.split(' ');
+    let method = parts.next()?.to_string();
+    let target = parts.next()?;
+    let version = parts.next()?;
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) = split_authority(target, 443)?;
+        return Some(ProxyRequest {
+            method,
+            host,
+            port,
+            tunnel: true,
+            forward: rest.to_vec(),
+        });
+    }
+
+    // Plain HTTP: rewrite to origin-form for the upstream server
+    let without_scheme = target.strip_prefix("http://")?;
+    let (authority, path) = without_scheme
+        .find('/')
+        .map_or((without_scheme, "/"), |i| without_scheme.split_at(i));
+    let (host, port) = split_authority(authority, 80)?;
+
+    let mut forward = format!("{method} {path} {version}\r\n{headers}").into_bytes();
+    forward.extend_from_slice(rest);
+    Some(ProxyRequest {
+        method,
+        host,
+        port,
+        tunnel: false,
+        forward,
+    })
+}
+
+/// Split `host[:port]` or `[v6]:port`.
+fn split_authority(authority: &str, default_port: u16) -> Option<(String, u16)> {
+    let (host, port) = if let Some(v6) = authority.strip_prefix('[') {
+        let (host, after) = v6.split_once(']')?;
+        (host, after.strip_prefix(':'))
+    } else {
+        match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    let port = port.map_or(Some(default_port), |p| p.parse().ok())?;
+    if host.is_empty() {
+        return None;
+    }
+    Some((host.to_string(), port))
+}
+
+/// Check the allowlist and resolve the host to a public address.
+async fn resolve_allowed(
+    request: &ProxyRequest,
+    policy: &EgressConfig,
+) -> Result<SocketAddr, &'static str> {
+    if !policy.allows_host(&request.host) {
+        return Err("domain not allowed");
+    }
+
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((request.host.as_str(), request.port))
+        .await
+        .map_err(|_| "resolution failed")?
+        .collect();
+    // Connect to a checked address so a second lookup cannot rebind it
+    if addrs.iter().any(|a| is_private_address(a.ip())) {
+        return Err("private address");
+    }
+    addrs.into_iter().next().ok_or("resolution failed")
+}
+
+async fn respond(stream: &mut (impl AsyncWrite + Unpin), status: &str) -> std::io::Result<()> {
+    stream
+        .write_all(
+            format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .as_bytes(),
+        )
+        .await
+}
+
+fn audit(agent_id: &str, method: &str, host: &str, port: u16, allowed: bool, reason: &str) {
+    tracing::info!(
+        target: "security",
+        agent = agent_id,
+        method,
+        host,
+        port,
+        allowed,
+        reason,
+        "Sandbox egress request"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_private_addresses() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:10.0.0.1",
+        ] {
+            assert!(is_private_address(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in ["1.1.1.1", "140.82.112.3", "2606:4700:4700::1111"] {
+            assert!(!is_private_address(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[test]
+    fn test_parse_request() {
+        let request = parse_request("CONNECT api.github.com:443 HTTP/1.1\r\n\r\n", &[]).unwrap();
+        assert_eq!(request.host, "api.github.com");
+        assert_eq!(request.port, 443);
+        assert!(request.tunnel);
+
+        let request = parse_request(
+            "GET http://example.com/a?b=1 HTTP/1.1\r\nHost: example.com\r\n\r\n",
+            &[],
+        )
+        .unwrap();
+        assert_eq!(request.port, 80);
+        assert_eq!(
+            String::from_utf8(request.forward).unwrap(),
+            "GET /a?b=1 HTTP/1.1\r\nHost: example.com\r\n\r\n"
+        );
+
+        assert_eq!(
+            split_authority("[::1]:8080", 80),
+            Some(("::1".to_string(), 8080))
+        );
+        assert!(parse_request("GET https://example.com/ HTTP/1.1\r\n\r\n", &[]).is_none());
+    }
+
+    async fn proxy_status(proxy: &EgressProxy, request: &str) -> String {
+        let mut stream = TcpStream::connect(proxy.addr()).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response.lines().next().unwrap_or_default().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_proxy_denies() {
+        let proxy = EgressProxy::start(
+            "default",
+            EgressConfig {
+                enabled: true,
+                allowed_domains: vec!["localhost".to_string()],
+            },
+        )
+        .await
+        .unwrap();
+
+        // Not on the allowlist
+        assert_eq!(
+            proxy_status(&proxy, "CONNECT example.com:443 HTTP/1.1\r\n\r\n").await,
+            "HTTP/1.1 403 Forbidden"
+        );
+        // Allowed, but resolves to loopback
+        assert_eq!(
+            proxy_status(&proxy, "CONNECT localhost:443 HTTP/1.1\r\n\r\n").await,
+            "HTTP/1.1 403 Forbidden"
+        );
+        assert_eq!(
+            proxy_status(&proxy, "NONSENSE\r\n\r\n").await,
+            "HTTP/1.1 400 Bad Request"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_proxy_unix_socket() {
+        let proxy = EgressProxy::start("default", EgressConfig::default())
+            .await
+            .unwrap();
+        let socket = proxy.socket_path().unwrap().to_path_buf();
+
+        let mut stream = tokio::net::UnixStream::connect(&socket).await.unwrap();
+        stream
+            .write_all(b"CONNECT example.com:443 HTTP/1.1\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 403 Forbidden"));
+
+        drop(proxy);
+        assert!(!socket.exists());
+    }
+}
@@ -4,12 +4,28 @@
 //! - Linux: bubblewrap (bwrap)
 //! - macOS: sandbox-exec with Seatbelt profiles
 //! - Windows: Job Objects (limited)
+//!
+//! Network access is either off, unrestricted, or routed through an
+//! [`EgressProxy`] that enforces a domain allowlist. At `Strict` and above
+//! on Linux the sandbox always gets its own network namespace; the proxy
+//! is then reached through its unix socket, relayed to a loopback port
+//! inside the sandbox with `socat`.
+
+mod egress;
+
+pub use egress::{EgressProxy, is_private_address};
+pub use openclaw_core::config::SandboxLevel;
 
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::process::Command;
 use std::time::Duration;
 use thiserror::Error;
 
+use openclaw_core::config::SandboxProfileConfig;
+
+use crate::workspace::Workspace;
+
 /// Sandbox errors.
 #[derive(Error, Debug)]
 pub enum SandboxError {
@@ -30,22 +46,6 @@ pub enum SandboxError {
     ExecutionError(String),
 }
 
-/// Sandbox security levels.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
-pub enum SandboxLevel {
-    /// No isolation - NEVER use in production.
-    None = 0,
-    /// Basic filesystem isolation.
-    Minimal = 1,
-    /// PID namespace + resource limits (default).
-    #[default]
-    Standard = 2,
-    /// Network isolation + seccomp filtering.
-    Strict = 3,
-    /// No host filesystem access.
-    Paranoid = 4,
-}
-
 /// Sandbox configuration.
 #[derive(Debug, Clone)]
 pub struct SandboxConfig {
@@ -65,6 +65,11 @@ pub struct SandboxConfig {
     pub env_allowlist: Vec<String>,
     /// Whether network access is allowed.
     pub network_allowed: bool,
+    /// Route network access through this egress proxy instead.
+    pub egress_proxy: Option<SocketAddr>,
+    /// Unix socket of the egress proxy, for sandboxes without the host's
+    /// network.
+    pub egress_socket: Option<PathBuf>,
     /// Working directory.
     pub work_dir: Option<PathBuf>,
 }
@@ -80,11 +85,68 @@ impl Default for SandboxConfig {
             readonly_paths: vec![],
             env_allowlist: vec!["PATH".into(), "HOME".into(), "LANG".into(), "TERM".into()],
             network_allowed: false,
+            egress_proxy: None,
+            egress_socket: None,
             work_dir: None,
         }
     }
 }
 
+impl SandboxConfig {
+    /// Apply the fields `profile` sets, keeping the rest.
+    #[must_use]
+    pub fn with_profile(mut self, profile: &SandboxProfileConfig) -> Self {
+        if let Some(level) = profile.level {
+            self.level = level;
+        }
+        if let Some(network) = profile.network {
+            self.network_allowed = network;
+        }
+        if let Some(paths) = &profile.allowed_paths {
+            self.allowed_paths.clone_from(paths);
+        }
+        if let Some(paths) = &profile.readonly_paths {
+            self.readonly_paths.clone_from(paths);
+        }
+        if let Some(mb) = profile.max_memory_mb {
+            self.max_memory_mb = mb;
+        }
+        if let Some(secs) = profile.max_cpu_seconds {
+            self.max_cpu_seconds = secs;
+        }
+        if let Some(fds) = profile.max_file_descriptors {
+            self.max_file_descriptors = fds;
+        }
+        self
+    }
+
+    /// Give commands network access only through `proxy`.
+    #[must_use]
+    pub fn with_egress(mut self, proxy: &EgressProxy) -> Self {
+        self.egress_proxy = Some(proxy.addr());
+        self.egress_socket = proxy.socket_path().map(PathBuf::from);
+        self
+    }
+
+    /// Whether commands get a network namespace of their own, with no
+    /// route out except the egress proxy's socket.
+    #[must_use]
+    pub fn unshares_network(&self) -> bool {
+        !self.network_allowed && self.level >= SandboxLevel::Strict
+    }
+
+    /// Mount `workspace` read-write and run commands in it.
+    #[must_use]
+    pub fn with_workspace(mut self, workspace: &Workspace) -> Self {
+        let root = workspace.root().to_path_buf();
+        if !self.allowed_paths.contains(&root) {
+            self.allowed_paths.push(root.clone());
+        }
+        self.work_dir = Some(root);
+        self
+    }
+}
+
 /// Output from sandboxed execution.
 #[derive(Debug, Clone)]
 pub struct SandboxOutput {
@@ -102,6 +164,41 @@ pub struct SandboxOutput {
     pub kill_reason: Option<String>,
 }
 
+/// Path the egress proxy's socket is mounted at inside the sandbox.
+#[cfg(target_os = "linux")]
+const SANDBOX_EGRESS_SOCKET: &str = "/run/openclaw/egress.sock";
+
+/// Loopback port inside the sandbox relayed to the egress proxy's socket.
+const SANDBOX_EGRESS_PORT: u16 = 3128;
+
+/// Whether the Linux sandbox reaches the proxy through its socket rather
+/// than the host's loopback.
+fn relays_egress(config: &SandboxConfig) -> bool {
+    cfg!(target_os = "linux") && config.unshares_network() && config.egress_socket.is_some()
+}
+
+/// Proxy variables set in the sandbox when an egress proxy is configured.
+fn proxy_env(config: &SandboxConfig) -> Vec<(&'static str, String)> {
+    config.egress_proxy.map_or_else(Vec::new, |addr| {
+        let url = if relays_egress(config) {
+            format!("http://127.0.0.1:{SANDBOX_EGRESS_PORT}")
+        } else {
+            format!("http://{addr}")
+        };
+        [
+            "HTTP_PROXY",
+            "HTTPS_PROXY",
+            "ALL_PROXY",
+            "http_proxy",
+            "https_proxy",
+            "all_proxy",
+        ]
+        .into_iter()
+        .map(|var| (var, url.clone()))
+        .collect()
+    })
+}
+
 /// Execute a command in a sandbox.
 ///
 /// # Arguments
@@ -220,10 +317,24 @@ fn execute_sandboxed_linux(
         }
     }
 
-    // Network isolation
-    if !config.network_allowed && config.level >= SandboxLevel::Strict {
+    // Network isolation; the egress proxy's socket is the only way out
+    let relay = relays_egress(config);
+    if config.unshares_network() {
         bwrap.arg("--unshare-net");
     }
+    if let Some(socket) = config.egress_socket.as_ref().filter(|_| relay) {
+        if !Command::new("which")
+            .arg("socat")
+            .output()?
+            .status
+            .success()
+        {
+            return Err(SandboxError::NotAvailable(
+                "socat is needed to reach the egress proxy from an isolated network".to_string(),
+            ));
+        }
+        bwrap.arg("--bind").arg(socket).arg(SANDBOX_EGRESS_SOCKET);
+    }
 
     // Add allowed paths (read-write)
     for path in &config.allowed_paths {
@@ -242,14 +353,26 @@ fn execute_sandboxed_linux(
             bwrap.arg("--setenv").arg(var).arg(val);
         }
     }
+    for (var, val) in proxy_env(config) {
+        bwrap.arg("--setenv").arg(var).arg(val);
+    }
 
     // Working directory
     if let Some(work_dir) = &config.work_dir {
         bwrap.arg("--chdir").arg(work_dir);
     }
 
-    // The actual command
-    bwrap.arg("--").arg(command).args(args);
+    // The actual command, behind a relay from a loopback port to the
+    // proxy's socket when the network is isolated
+    bwrap.arg("--");
+    if relay {
+        bwrap
+            .arg("sh")
+            .arg("-c")
+            .arg(egress_relay_script())
+            .arg("sh");
+    }
+    bwrap.arg(command).args(args);
 
     // Execute with timing
     let start = Instant::now();
@@ -266,6 +389,21 @@ fn execute_sandboxed_linux(
     })
 }
 
+/// Shell script that relays `SANDBOX_EGRESS_PORT` to the mounted proxy
+/// socket, waits for the relay to listen, then runs its arguments.
+#[cfg(target_os = "linux")]
+fn egress_relay_script() -> String {
+    format!(
+        "socat TCP-LISTEN:{SANDBOX_EGRESS_PORT},bind=127.0.0.1,fork,reuseaddr UNIX-CONNECT:{SANDBOX_EGRESS_SOCKET} \
+         </dev/null >/dev/null 2>&1 &\n\
+         i=0\n\
+         while [ $i -lt 50 ] && ! grep -q ':{SANDBOX_EGRESS_PORT:04X} 00000000:0000 0A' /proc/net/tcp; do\n\
+         sleep 0.02; i=$((i + 1))\n\
+         done\n\
+         exec \"$@\""
+    )
+}
+
 /// macOS sandboxing using sandbox-exec with Seatbelt profiles.
 #[cfg(target_os = "macos")]
 fn execute_sandboxed_macos(
@@ -300,6 +438,7 @@ fn execute_sandboxed_macos(
             sandbox_cmd.env(var, val);
         }
     }
+    sandbox_cmd.envs(proxy_env(config));
 
     // Working directory
     if let Some(work_dir) = &config.work_dir {
@@ -398,7 +537,15 @@ fn generate_seatbelt_profile(config: &SandboxConfig) -> Result<String, SandboxEr
     );
 
     // Network access based on config
-    if config.network_allowed {
+    if let Some(proxy) = config.egress_proxy {
+        profile.push_str(&format!(
+            r#"
+; Allow network access only through the egress proxy
+(allow network-outbound (remote ip "localhost:{}"))
+"#,
+            proxy.port()
+        ));
+    } else if config.network_allowed {
         profile.push_str(
             r#"
 ; Allow network access
@@ -533,6 +680,7 @@ fn execute_sandboxed_windows(
             cmd.env(var, val);
         }
     }
+    cmd.envs(proxy_env(config));
 
     // Working directory
     if let Some(work_dir) = &config.work_dir {
@@ -628,6 +776,7 @@ fn execute_sandboxed_windows(
             cmd.env(var, val);
         }
     }
+    cmd.envs(proxy_env(config));
     if let Some(work_dir) = &config.work_dir {
         cmd.current_dir(work_dir);
     }
@@ -731,6 +880,81 @@ mod tests {
         assert!(!config.network_allowed);
     }
 
+    #[test]
+    fn test_with_profile() {
+        let config = SandboxConfig::default().with_profile(&SandboxProfileConfig {
+            level: Some(SandboxLevel::Paranoid),
+            max_memory_mb: Some(128),
+            ..Default::default()
+        });
+        assert_eq!(config.level, SandboxLevel::Paranoid);
+        assert_eq!(config.max_memory_mb, 128);
+        // Unset fields are kept
+        assert_eq!(config.max_cpu_seconds, 60);
+        assert!(!config.network_allowed);
+    }
+
+    #[test]
+    fn test_with_workspace() {
+        let workspace = Workspace::for_agent(std::path::Path::new("/srv/ws"), "dev");
+        let config = SandboxConfig::default()
+            .with_workspace(&workspace)
+            .with_workspace(&workspace);
+        assert_eq!(config.allowed_paths, vec![PathBuf::from("/srv/ws/dev")]);
+        assert_eq!(config.work_dir, Some(PathBuf::from("/srv/ws/dev")));
+    }
+
+    #[test]
+    fn test_proxy_env() {
+        assert!(proxy_env(&SandboxConfig::default()).is_empty());
+
+        let config = SandboxConfig {
+            egress_proxy: Some("127.0.0.1:3128".parse().unwrap()),
+            ..Default::default()
+        };
+        let env = proxy_env(&config);
+        assert!(env.contains(&("HTTPS_PROXY", "http://127.0.0.1:3128".to_string())));
+    }
+
+    #[test]
+    fn test_strict_unshares_network_with_proxy() {
+        let config = SandboxConfig {
+            level: SandboxLevel::Strict,
+            egress_proxy: Some("127.0.0.1:40000".parse().unwrap()),
+            egress_socket: Some(PathBuf::from("/tmp/egress.sock")),
+            ..Default::default()
+        };
+        assert!(config.unshares_network());
+        let env = proxy_env(&config);
+        let expected = if cfg!(target_os = "linux") {
+            "http://127.0.0.1:3128"
+        } else {
+            "http://127.0.0.1:40000"
+        };
+        assert!(env.contains(&("HTTPS_PROXY", expected.to_string())));
+
+        // Below Strict the host network is shared and the proxy reached
+        // directly
+        let config = SandboxConfig {
+            level: SandboxLevel::Standard,
+            ..config
+        };
+        assert!(!config.unshares_network());
+        assert!(
+            proxy_env(&config).contains(&("HTTPS_PROXY", "http://127.0.0.1:40000".to_string()))
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_egress_relay_script() {
+        let script = egress_relay_script();
+        assert!(script.contains("TCP-LISTEN:3128,bind=127.0.0.1"));
+        assert!(script.contains("UNIX-CONNECT:/run/openclaw/egress.sock"));
+        assert!(script.contains(":0C38 00000000:0000 0A"));
+        assert!(script.ends_with("exec \"$@\""));
+    }
+
     #[test]
     fn test_sandbox_level_ordering() {
         assert!(SandboxLevel::Paranoid > SandboxLevel::Strict);
@@ -0,0 +1,747 @@
+//! Cron scheduler for agent prompts and workflows.
+//!
+//! Tasks come from `Config::schedules`. Last-run state is persisted to a
+//! JSON file so missed runs can be caught up (per `CatchUpPolicy`) after a
+//! restart. Manual runs are requested by dropping a marker file into the
+//! store's `run-now` directory, which the running scheduler picks up on its
+//! next tick. Each task runs in a job of its own, so a slow task doesn't
+//! hold up the others.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinSet;
+
+use openclaw_core::config::{CatchUpPolicy, ScheduleConfig};
+
+/// How often the scheduler checks for due tasks.
+pub const TICK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Maximum missed occurrences run under `CatchUpPolicy::All`.
+pub const MAX_CATCH_UP_RUNS: usize = 24;
+
+/// Scheduler errors.
+#[derive(Debug, thiserror::Error)]
+pub enum ScheduleError {
+    /// Cron expression could not be parsed.
+    #[error("Invalid cron expression for '{id}': {message}")]
+    InvalidCron {
+        /// Schedule ID.
+        id: String,
+        /// Parser message.
+        message: String,
+    },
+
+    /// Unknown schedule ID.
+    #[error("Schedule not found: {0}")]
+    NotFound(String),
+
+    /// State persistence error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// State (de)serialization error.
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    /// Task execution failed.
+    #[error("Run failed: {0}")]
+    Run(String),
+}
+
+/// Parse a cron expression in UTC. Five-field expressions get a leading
+/// `0` seconds field.
+///
+/// # Errors
+///
+/// Returns error if the expression is invalid.
+pub fn parse_cron(expr: &str) -> Result<cron::Schedule, cron::error::Error> {
+    if expr.split_whitespace().count() == 5 {
+        cron::Schedule::from_str(&format!("0 {expr}"))
+    } else {
+        cron::Schedule::from_str(expr)
+    }
+}
+
+/// A schedule with its parsed cron expression.
+#[derive(Debug, Clone)]
+pub struct ScheduledTask {
+    /// Schedule ID.
+    pub id: String,
+    /// Task configuration.
+    pub config: ScheduleConfig,
+    schedule: cron::Schedule,
+}
+
+impl ScheduledTask {
+    /// Parse a task from configuration (see [`parse_cron`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the cron expression is invalid.
+    pub fn new(id: impl Into<String>, config: ScheduleConfig) -> Result<Self, ScheduleError> {
+        let id = id.into();
+        let schedule = parse_cron(&config.cron).map_err(|e| ScheduleError::InvalidCron {
+            id: id.clone(),
+            message: e.to_string(),
+        })?;
+
+        Ok(Self {
+            id,
+            config,
+            schedule,
+        })
+    }
+
+    /// Next occurrence strictly after `after`, without jitter.
+    #[must_use]
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.schedule.after(&after).next()
+    }
+
+    /// Random delay for one firing, up to `jitter_secs`.
+    ///
+    /// Drawn afresh for every occurrence, so tasks sharing a cron
+    /// expression are spread out differently each time.
+    #[must_use]
+    pub fn jitter(&self) -> chrono::Duration {
+        if self.config.jitter_secs == 0 {
+            return chrono::Duration::zero();
+        }
+
+        let secs = rand::thread_rng().gen_range(0..=self.config.jitter_secs);
+        chrono::Duration::seconds(i64::try_from(secs).unwrap_or(i64::MAX))
+    }
+
+    /// When the first occurrence after `after` fires, with fresh jitter.
+    fn fire_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.next_after(after).map(|t| t + self.jitter())
+    }
+
+    /// Occurrences in `(after, now]`, oldest first.
+    fn missed(&self, after: DateTime<Utc>, now: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        self.schedule
+            .after(&after)
+            .take_while(|t| *t <= now)
+            .collect()
+    }
+}
+
+/// Outcome of the most recent run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    /// Run completed.
+    Success,
+    /// Run returned an error.
+    Failed,
+    /// Missed occurrence dropped by the catch-up policy.
+    Skipped,
+}
+
+/// Persisted state of a scheduled task.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskState {
+    /// Latest occurrence that has been handled (run or skipped).
+    pub last_scheduled: Option<DateTime<Utc>>,
+    /// When the next occurrence fires, jitter included.
+    pub next_run: Option<DateTime<Utc>>,
+    /// When the task last actually ran.
+    pub last_run: Option<DateTime<Utc>>,
+    /// Outcome of the last run.
+    pub last_status: Option<RunStatus>,
+    /// Error from the last failed run.
+    pub last_error: Option<String>,
+    /// Total number of runs.
+    pub run_count: u64,
+}
+
+/// File-backed store for scheduler state.
+#[derive(Debug, Clone)]
+pub struct ScheduleStateStore {
+    dir: PathBuf,
+}
+
+impl ScheduleStateStore {
+    /// Open a store rooted at `dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the directory cannot be created.
+    pub fn open(dir: &Path) -> Result<Self, ScheduleError> {
+        std::fs::create_dir_all(dir.join("run-now"))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    fn state_path(&self) -> PathBuf {
+        self.dir.join("state.json")
+    }
+
+    fn request_path(&self, id: &str) -> PathBuf {
+        // IDs come from config keys; keep them to a single path segment
+        let name: String = id
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join("run-now").join(name)
+    }
+
+    /// Load state for all tasks.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the state file exists but cannot be read.
+    pub fn load(&self) -> Result<HashMap<String, TaskState>, ScheduleError> {
+        let path = self.state_path();
+        if !path.exists() {
+            return Ok(HashMap::new());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save state for all tasks.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the state file cannot be written.
+    pub fn save(&self, state: &HashMap<String, TaskState>) -> Result<(), ScheduleError> {
+        let tmp = self.dir.join("state.json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(state)?)?;
+        std::fs::rename(tmp, self.state_path())?;
+        Ok(())
+    }
+
+    /// Ask the running scheduler to run a task on its next tick.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the request marker cannot be written.
+    pub fn request_run(&self, id: &str) -> Result<(), ScheduleError> {
+        std::fs::write(self.request_path(id), id)?;
+        Ok(())
+    }
+
+    /// Consume a pending run request, returning whether one existed.
+    fn take_request(&self, id: &str) -> bool {
+        std::fs::remove_file(self.request_path(id)).is_ok()
+    }
+}
+
+/// Executes scheduled tasks.
+#[async_trait]
+pub trait ScheduleRunner: Send + Sync {
+    /// Run a task for the given occurrence.
+    async fn run(
+        &self,
+        task: &ScheduledTask,
+        scheduled_for: DateTime<Utc>,
+    ) -> Result<(), ScheduleError>;
+}
+
+/// A run the scheduler decided to perform.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DueRun {
+    /// Schedule ID.
+    pub task_id: String,
+    /// Occurrence being run (or request time for manual runs).
+    pub scheduled_for: DateTime<Utc>,
+    /// Whether the run was requested manually.
+    pub manual: bool,
+}
+
+/// Outcome of one run in a task's job.
+struct RunOutcome {
+    finished_at: DateTime<Utc>,
+    result: Result<(), ScheduleError>,
+}
+
+/// Cron scheduler.
+pub struct Scheduler {
+    tasks: Vec<ScheduledTask>,
+    store: ScheduleStateStore,
+    state: HashMap<String, TaskState>,
+    started_at: DateTime<Utc>,
+    running: JoinSet<(String, Vec<RunOutcome>)>,
+}
+
+impl Scheduler {
+    /// Create a scheduler for the configured tasks.
+    ///
+    /// Tasks that have never run are scheduled from now; occurrences before
+    /// the first start are not back-filled.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a cron expression is invalid or state cannot be loaded.
+    pub fn new(
+        configs: &HashMap<String, ScheduleConfig>,
+        store: ScheduleStateStore,
+    ) -> Result<Self, ScheduleError> {
+        let mut tasks = configs
+            .iter()
+            .map(|(id, config)| ScheduledTask::new(id, config.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        tasks.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let state = store.load()?;
+
+        Ok(Self {
+            tasks,
+            store,
+            state,
+            started_at: Utc::now(),
+            running: JoinSet::new(),
+        })
+    }
+
+    /// Configured tasks, sorted by ID.
+    #[must_use]
+    pub fn tasks(&self) -> &[ScheduledTask] {
+        &self.tasks
+    }
+
+    /// Persisted state of a task.
+    #[must_use]
+    pub fn state(&self, id: &str) -> Option<&TaskState> {
+        self.state.get(id)
+    }
+
+    /// Next time a task will run, including jitter once it has been drawn.
+    #[must_use]
+    pub fn next_run(&self, id: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let task = self.tasks.iter().find(|t| t.id == id)?;
+        if !task.config.enabled {
+            return None;
+        }
+        self.state
+            .get(id)
+            .and_then(|state| state.next_run)
+            .or_else(|| task.next_after(now))
+    }
+
+    /// Work out which runs are due at `now`.
+    ///
+    /// Marks handled occurrences (including skipped ones) in memory; call
+    /// [`Scheduler::tick`] to also execute and persist.
+    pub fn due(&mut self, now: DateTime<Utc>) -> Vec<DueRun> {
+        let mut due = Vec::new();
+
+        for task in &self.tasks {
+            if self.store.take_request(&task.id) {
+                due.push(DueRun {
+                    task_id: task.id.clone(),
+                    scheduled_for: now,
+                    manual: true,
+                });
+            }
+
+            if !task.config.enabled {
+                continue;
+            }
+
+            let state = self.state.entry(task.id.clone()).or_default();
+            let since = state.last_scheduled.unwrap_or(self.started_at);
+            let Some(fires_at) = state.next_run.or_else(|| task.fire_after(since)) else {
+                continue;
+            };
+            state.next_run = Some(fires_at);
+            if fires_at > now {
+                continue;
+            }
+
+            let missed = task.missed(since, now);
+            let Some(&latest) = missed.last() else {
+                // Drawn for a cron expression that has since changed
+                state.next_run = task.fire_after(since);
+                continue;
+            };
+            state.last_scheduled = Some(latest);
+            // The next firing is drawn whether this one runs or is skipped
+            state.next_run = task.fire_after(latest);
+
+            let runs: Vec<DateTime<Utc>> = match task.config.catch_up {
+                // Only the occurrence that just came due
+                CatchUpPolicy::Skip => {
+                    let on_time = missed.len() == 1
+                        && now - fires_at
+                            <= chrono::Duration::from_std(TICK_INTERVAL * 2).unwrap_or_default();
+                    if on_time {
+                        vec![latest]
+                    } else {
+                        state.last_status = Some(RunStatus::Skipped);
+                        Vec::new()
+                    }
+                }
+                CatchUpPolicy::Once => vec![latest],
+                CatchUpPolicy::All => {
+                    let skip = missed.len().saturating_sub(MAX_CATCH_UP_RUNS);
+                    missed.into_iter().skip(skip).collect()
+                }
+            };
+
+            due.extend(runs.into_iter().map(|scheduled_for| DueRun {
+                task_id: task.id.clone(),
+                scheduled_for,
+                manual: false,
+            }));
+        }
+
+        due
+    }
+
+    /// Start everything due at `now` and persist the resulting state.
+    ///
+    /// Each task's runs go in a job of their own, oldest first. Outcomes
+    /// are recorded as jobs finish: by the scheduler loop, or by the next
+    /// tick or [`Scheduler::settle`].
+    pub fn tick(&mut self, now: DateTime<Utc>, runner: &Arc<dyn ScheduleRunner>) -> Vec<DueRun> {
+        self.collect_finished();
+        let due = self.due(now);
+
+        for task in &self.tasks {
+            let runs: Vec<DueRun> = due
+                .iter()
+                .filter(|run| run.task_id == task.id)
+                .cloned()
+                .collect();
+            if runs.is_empty() {
+                continue;
+            }
+
+            let task = task.clone();
+            let runner = runner.clone();
+            self.running.spawn(async move {
+                let mut outcomes = Vec::with_capacity(runs.len());
+                for run in runs {
+                    tracing::info!(
+                        "Running schedule '{}' for {}{}",
+                        task.id,
+                        run.scheduled_for,
+                        if run.manual { " (manual)" } else { "" }
+                    );
+                    let result = runner.run(&task, run.scheduled_for).await;
+                    outcomes.push(RunOutcome {
+                        finished_at: Utc::now(),
+                        result,
+                    });
+                }
+                (task.id, outcomes)
+            });
+        }
+
+        self.save();
+        due
+    }
+
+    /// Wait for every started run to finish and record the outcomes.
+    pub async fn settle(&mut self) {
+        while let Some(joined) = self.running.join_next().await {
+            self.finish(joined);
+        }
+        self.save();
+    }
+
+    /// Run the scheduler loop forever.
+    pub async fn run(mut self, runner: Arc<dyn ScheduleRunner>) {
+        let mut interval = tokio::time::interval(TICK_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    self.tick(Utc::now(), &runner);
+                }
+                Some(joined) = self.running.join_next() => {
+                    self.finish(joined);
+                    self.save();
+                }
+            }
+        }
+    }
+
+    /// Record the outcomes of jobs that have already finished.
+    fn collect_finished(&mut self) {
+        while let Some(joined) = self.running.try_join_next() {
+            self.finish(joined);
+        }
+    }
+
+    /// Record the outcomes of a finished job.
+    fn finish(&mut self, joined: Result<(String, Vec<RunOutcome>), tokio::task::JoinError>) {
+        let (task_id, outcomes) = match joined {
+            Ok(job) => job,
+            Err(e) => {
+                tracing::warn!("Schedule job failed: {}", e);
+                return;
+            }
+        };
+
+        let state = self.state.entry(task_id.clone()).or_default();
+        for outcome in outcomes {
+            state.last_run = Some(outcome.finished_at);
+            state.run_count += 1;
+            match outcome.result {
+                Ok(()) => {
+                    state.last_status = Some(RunStatus::Success);
+                    state.last_error = None;
+                }
+                Err(e) => {
+                    tracing::warn!("Schedule '{}' failed: {}", task_id, e);
+                    state.last_status = Some(RunStatus::Failed);
+                    state.last_error = Some(e.to_string());
+                }
+            }
+        }
+    }
+
+    fn save(&self) {
+        if let Err(e) = self.store.save(&self.state) {
+            tracing::warn!("Failed to save schedule state: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tempfile::tempdir;
+
+    fn config(cron: &str, catch_up: CatchUpPolicy) -> ScheduleConfig {
+        ScheduleConfig {
+            cron: cron.to_string(),
+            enabled: true,
+            agent: "default".to_string(),
+            prompt: Some("summarize".to_string()),
+            workflow: None,
+            input: serde_json::Value::Null,
+            deliver: None,
+            template: None,
+            vars: serde_json::Map::new(),
+            jitter_secs: 0,
+            catch_up,
+        }
+    }
+
+    fn scheduler(dir: &Path, catch_up: CatchUpPolicy, started_at: DateTime<Utc>) -> Scheduler {
+        let mut configs = HashMap::new();
+        configs.insert("hourly".to_string(), config("0 * * * *", catch_up));
+        scheduler_for(dir, &configs, started_at)
+    }
+
+    fn scheduler_for(
+        dir: &Path,
+        configs: &HashMap<String, ScheduleConfig>,
+        started_at: DateTime<Utc>,
+    ) -> Scheduler {
+        let mut scheduler =
+            Scheduler::new(configs, ScheduleStateStore::open(dir).unwrap()).unwrap();
+        scheduler.started_at = started_at;
+        scheduler
+    }
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<DateTime<Utc>>>);
+
+    #[async_trait]
+    impl ScheduleRunner for Recorder {
+        async fn run(
+            &self,
+            _task: &ScheduledTask,
+            scheduled_for: DateTime<Utc>,
+        ) -> Result<(), ScheduleError> {
+            self.0.lock().unwrap().push(scheduled_for);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_invalid_cron() {
+        let result = ScheduledTask::new("bad", config("not a cron", CatchUpPolicy::Once));
+        assert!(matches!(result, Err(ScheduleError::InvalidCron { .. })));
+    }
+
+    /// Never finishes runs of `slow`; reports other runs as they happen.
+    struct Stalling(tokio::sync::mpsc::UnboundedSender<String>);
+
+    #[async_trait]
+    impl ScheduleRunner for Stalling {
+        async fn run(
+            &self,
+            task: &ScheduledTask,
+            _scheduled_for: DateTime<Utc>,
+        ) -> Result<(), ScheduleError> {
+            if task.id == "slow" {
+                std::future::pending::<()>().await;
+            }
+            let _ = self.0.send(task.id.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_jitter_is_drawn_per_firing() {
+        let mut cfg = config("0 * * * *", CatchUpPolicy::Once);
+        cfg.jitter_secs = 120;
+        let task = ScheduledTask::new("report", cfg).unwrap();
+
+        let draws: Vec<chrono::Duration> = (0..50).map(|_| task.jitter()).collect();
+        assert!(draws.iter().all(|d| *d <= chrono::Duration::seconds(120)));
+        assert!(draws.iter().any(|d| *d != draws[0]));
+    }
+
+    #[test]
+    fn test_jittered_firing_waits_for_its_delay() {
+        let temp = tempdir().unwrap();
+        let mut cfg = config("0 * * * *", CatchUpPolicy::Skip);
+        cfg.jitter_secs = 600;
+        let mut configs = HashMap::new();
+        configs.insert("report".to_string(), cfg);
+        let mut scheduler = scheduler_for(temp.path(), &configs, at("2024-01-01T00:30:00Z"));
+
+        // Nothing fires before the delay drawn for the occurrence
+        assert!(scheduler.due(at("2024-01-01T00:40:00Z")).is_empty());
+        let fires_at = scheduler
+            .next_run("report", at("2024-01-01T00:40:00Z"))
+            .unwrap();
+        assert!(fires_at >= at("2024-01-01T01:00:00Z"));
+        assert!(fires_at <= at("2024-01-01T01:10:00Z"));
+        if fires_at > at("2024-01-01T01:00:00Z") {
+            assert!(
+                scheduler
+                    .due(fires_at - chrono::Duration::seconds(1))
+                    .is_empty()
+            );
+        }
+
+        let due = scheduler.due(fires_at);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].scheduled_for, at("2024-01-01T01:00:00Z"));
+        assert!(scheduler.next_run("report", fires_at).unwrap() >= at("2024-01-01T02:00:00Z"));
+    }
+
+    #[test]
+    fn test_catch_up_policies() {
+        let temp = tempdir().unwrap();
+        let start = at("2024-01-01T00:30:00Z");
+        let now = at("2024-01-01T03:30:00Z");
+
+        let mut once = scheduler(&temp.path().join("once"), CatchUpPolicy::Once, start);
+        let due = once.due(now);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].scheduled_for, at("2024-01-01T03:00:00Z"));
+        assert!(once.due(now).is_empty());
+
+        let mut all = scheduler(&temp.path().join("all"), CatchUpPolicy::All, start);
+        assert_eq!(all.due(now).len(), 3);
+
+        let mut skip = scheduler(&temp.path().join("skip"), CatchUpPolicy::Skip, start);
+        assert!(skip.due(now).is_empty());
+        assert_eq!(
+            skip.state("hourly").unwrap().last_status,
+            Some(RunStatus::Skipped)
+        );
+        // The skip schedules the next occurrence, which then runs
+        assert_eq!(
+            skip.next_run("hourly", now),
+            Some(at("2024-01-01T04:00:00Z"))
+        );
+        assert_eq!(skip.due(at("2024-01-01T04:00:10Z")).len(), 1);
+    }
+
+    #[test]
+    fn test_skip_after_late_tick_keeps_later_runs() {
+        let temp = tempdir().unwrap();
+        let mut skip = scheduler(temp.path(), CatchUpPolicy::Skip, at("2024-01-01T00:30:00Z"));
+
+        // A tick well after the occurrence skips it
+        assert!(skip.due(at("2024-01-01T01:05:00Z")).is_empty());
+        for hour in 2..5 {
+            let due = skip.due(at(&format!("2024-01-01T0{hour}:00:05Z")));
+            assert_eq!(due.len(), 1, "run at {hour}:00");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_state_persists_across_restart() {
+        let temp = tempdir().unwrap();
+        let recorder = Arc::new(Recorder::default());
+        let runner: Arc<dyn ScheduleRunner> = recorder.clone();
+
+        let mut first = scheduler(temp.path(), CatchUpPolicy::Once, at("2024-01-01T00:30:00Z"));
+        first.tick(at("2024-01-01T01:00:05Z"), &runner);
+        first.settle().await;
+
+        // Restarted later: last_scheduled comes from disk, not started_at
+        let mut second = scheduler(temp.path(), CatchUpPolicy::Once, at("2024-01-01T05:00:00Z"));
+        assert_eq!(second.state("hourly").unwrap().run_count, 1);
+        second.tick(at("2024-01-01T05:00:05Z"), &runner);
+        second.settle().await;
+
+        let runs = recorder.0.lock().unwrap().clone();
+        assert_eq!(
+            runs,
+            vec![at("2024-01-01T01:00:00Z"), at("2024-01-01T05:00:00Z")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_now_request() {
+        let temp = tempdir().unwrap();
+        let runner: Arc<dyn ScheduleRunner> = Arc::new(Recorder::default());
+        let now = at("2024-01-01T00:40:00Z");
+
+        let mut scheduler = scheduler(temp.path(), CatchUpPolicy::Once, at("2024-01-01T00:30:00Z"));
+        ScheduleStateStore::open(temp.path())
+            .unwrap()
+            .request_run("hourly")
+            .unwrap();
+
+        let due = scheduler.tick(now, &runner);
+        assert_eq!(due.len(), 1);
+        assert!(due[0].manual);
+
+        // Request is consumed
+        assert!(scheduler.tick(now, &runner).is_empty());
+        scheduler.settle().await;
+        assert_eq!(scheduler.state("hourly").unwrap().run_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_slow_task_does_not_hold_up_others() {
+        let temp = tempdir().unwrap();
+        let mut configs = HashMap::new();
+        configs.insert("slow".to_string(), config("0 * * * *", CatchUpPolicy::Once));
+        configs.insert("fast".to_string(), config("0 * * * *", CatchUpPolicy::Once));
+        let mut scheduler = scheduler_for(temp.path(), &configs, at("2024-01-01T00:30:00Z"));
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let runner: Arc<dyn ScheduleRunner> = Arc::new(Stalling(tx));
+        assert_eq!(scheduler.tick(at("2024-01-01T01:00:05Z"), &runner).len(), 2);
+
+        let ran = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap();
+        assert_eq!(ran.as_deref(), Some("fast"));
+    }
+}
@@ -0,0 +1,292 @@
+//! Markdown skills loaded into the agent prompt.
+//!
+//! A skill is a directory holding a `skill.md` file: optional front matter
+//! (`key: value` lines between `---` fences) followed by instructions.
+//! Skills are discovered in the shared `~/.openclaw/skills/` directory and
+//! in an agent's own `skills/` directory, which takes precedence. Each
+//! agent lists the skills to inject into its system prompt.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use openclaw_core::Config;
+use openclaw_core::validation::limits::MAX_SKILL_FILE_SIZE;
+
+/// Skill file name (`SKILL.md` is also accepted).
+pub const SKILL_FILE: &str = "skill.md";
+
+/// Skill loading errors.
+#[derive(Error, Debug)]
+pub enum SkillError {
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Skill directory has no skill file.
+    #[error("{} has no skill.md", .0.display())]
+    Missing(PathBuf),
+
+    /// Skill file exceeds `MAX_SKILL_FILE_SIZE`.
+    #[error("{} is {size} bytes (limit {})", path.display(), MAX_SKILL_FILE_SIZE)]
+    TooLarge {
+        /// Skill file.
+        path: PathBuf,
+        /// File size.
+        size: u64,
+    },
+
+    /// Skill file could not be parsed.
+    #[error("Invalid skill: {0}")]
+    Invalid(String),
+}
+
+/// A loaded skill.
+#[derive(Debug, Clone)]
+pub struct Skill {
+    /// Skill name (its directory name).
+    pub name: String,
+    /// One-line description from front matter.
+    pub description: Option<String>,
+    /// Other front matter fields.
+    pub metadata: BTreeMap<String, String>,
+    /// Instructions (the file after the front matter).
+    pub body: String,
+    /// Skill file path.
+    pub path: PathBuf,
+}
+
+impl Skill {
+    /// Load the skill in directory `dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Missing` if there is no skill file, `TooLarge` if it exceeds
+    /// `MAX_SKILL_FILE_SIZE`, or `Invalid` if it is not UTF-8 or its front
+    /// matter is unterminated.
+    pub fn load(dir: &Path) -> Result<Self, SkillError> {
+        let name = dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| SkillError::Invalid(format!("bad skill directory {}", dir.display())))?
+            .to_string();
+        let path = [SKILL_FILE, "SKILL.md"]
+            .iter()
+            .map(|f| dir.join(f))
+            .find(|p| p.is_file())
+            .ok_or_else(|| SkillError::Missing(dir.to_path_buf()))?;
+
+        let size = std::fs::metadata(&path)?.len();
+        if size > MAX_SKILL_FILE_SIZE as u64 {
+            return Err(SkillError::TooLarge { path, size });
+        }
+        let content = String::from_utf8(std::fs::read(&path)?)
+            .map_err(|_| SkillError::Invalid(format!("{} is not UTF-8", path.display())))?;
+
+        let (mut metadata, body) = parse_front_matter(&content).ok_or_else(|| {
+            SkillError::Invalid(format!("{}: unterminated front matter", path.display()))
+        })?;
+        let description = metadata.remove("description");
+
+        Ok(Self {
+            name,
+            description,
+            metadata,
+            body: body.trim().to_string(),
+            path,
+        })
+    }
+}
+
+/// Split `---` fenced front matter from the body.
+///
+/// Returns `None` if the front matter is never closed.
+pub(crate) fn parse_front_matter(content: &str) -> Option<(BTreeMap<String, String>, &str)> {
+    let mut metadata = BTreeMap::new();
+    let Some(rest) = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    else {
+        return Some((metadata, content));
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        offset += line.len();
+        let line = line.trim();
+        if line == "---" {
+            return Some((metadata, &rest[offset..]));
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+            metadata.insert(key.trim().to_lowercase(), value.to_string());
+        }
+    }
+    None
+}
+
+/// Skills discovered in one or more directories.
+#[derive(Debug, Default)]
+pub struct SkillLibrary {
+    skills: BTreeMap<String, Skill>,
+    errors: Vec<(PathBuf, SkillError)>,
+}
+
+impl SkillLibrary {
+    /// Discover skills in `dirs`; a skill in a later directory replaces one
+    /// with the same name in an earlier one. Skills that fail to load are
+    /// recorded in [`SkillLibrary::errors`].
+    #[must_use]
+    pub fn discover(dirs: &[PathBuf]) -> Self {
+        let mut library = Self::default();
+
+        for dir in dirs {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+            let mut paths: Vec<PathBuf> = entries
+                .filter_map(Result::ok)
+                .map(|e| e.path())
+                .filter(|p| p.is_dir())
+                .collect();
+            paths.sort();
+
+            for path in paths {
+                match Skill::load(&path) {
+                    Ok(skill) => {
+                        library.skills.insert(skill.name.clone(), skill);
+                    }
+                    Err(e) => library.errors.push((path, e)),
+                }
+            }
+        }
+        library
+    }
+
+    /// Skills available to `agent_id`: shared skills plus the agent's own.
+    #[must_use]
+    pub fn for_agent(agent_id: &str) -> Self {
+        Self::discover(&[
+            Config::skills_dir(),
+            Config::agents_dir().join(agent_id).join("skills"),
+        ])
+    }
+
+    /// Look up a skill by name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Skill> {
+        self.skills.get(name)
+    }
+
+    /// All skills, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = &Skill> {
+        self.skills.values()
+    }
+
+    /// Skill directories that failed to load.
+    #[must_use]
+    pub fn errors(&self) -> &[(PathBuf, SkillError)] {
+        &self.errors
+    }
+
+    /// Skills named in `names`, in order. Unknown names are logged and
+    /// skipped.
+    #[must_use]
+    pub fn select(&self, names: &[String]) -> Vec<Skill> {
+        names
+            .iter()
+            .filter_map(|name| {
+                let skill = self.skills.get(name);
+                if skill.is_none() {
+                    tracing::warn!("Skill '{}' is enabled but not installed", name);
+                }
+                skill.cloned()
+            })
+            .collect()
+    }
+}
+
+/// Render skills as a system prompt section.
+#[must_use]
+pub fn skills_prompt(skills: &[Skill]) -> Option<String> {
+    if skills.is_empty() {
+        return None;
+    }
+
+    let mut prompt = String::from(
+        "# Skills\n\nFollow a skill's instructions when the task matches its description.",
+    );
+    for skill in skills {
+        prompt.push_str("\n\n## ");
+        prompt.push_str(&skill.name);
+        if let Some(description) = &skill.description {
+            prompt.push('\n');
+            prompt.push_str(description);
+        }
+        if !skill.body.is_empty() {
+            prompt.push_str("\n\n");
+            prompt.push_str(&skill.body);
+        }
+    }
+    Some(prompt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_skill(root: &Path, name: &str, content: &str) {
+        let dir = root.join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(SKILL_FILE), content).unwrap();
+    }
+
+    #[test]
+    fn test_load_with_front_matter() {
+        let temp = tempdir().unwrap();
+        write_skill(
+            temp.path(),
+            "refunds",
+            "---\ndescription: \"Handle refund requests\"\nversion: 1.2\n---\n\n# Refunds\n\nCheck the order date first.\n",
+        );
+
+        let skill = Skill::load(&temp.path().join("refunds")).unwrap();
+        assert_eq!(skill.name, "refunds");
+        assert_eq!(skill.description.as_deref(), Some("Handle refund requests"));
+        assert_eq!(skill.metadata["version"], "1.2");
+        assert_eq!(skill.body, "# Refunds\n\nCheck the order date first.");
+    }
+
+    #[test]
+    fn test_discover_and_select() {
+        let shared = tempdir().unwrap();
+        let agent = tempdir().unwrap();
+        write_skill(shared.path(), "greeting", "Say hello.");
+        write_skill(shared.path(), "refunds", "Shared refunds.");
+        write_skill(agent.path(), "refunds", "Agent refunds.");
+        write_skill(shared.path(), "broken", "---\ndescription: never closed\n");
+        write_skill(shared.path(), "huge", &"x".repeat(MAX_SKILL_FILE_SIZE + 1));
+
+        let library =
+            SkillLibrary::discover(&[shared.path().to_path_buf(), agent.path().to_path_buf()]);
+        assert_eq!(library.iter().count(), 2);
+        assert_eq!(library.get("refunds").unwrap().body, "Agent refunds.");
+        assert_eq!(library.errors().len(), 2);
+        assert!(
+            library
+                .errors()
+                .iter()
+                .any(|(_, e)| matches!(e, SkillError::TooLarge { .. }))
+        );
+
+        let selected = library.select(&["refunds".to_string(), "missing".to_string()]);
+        assert_eq!(selected.len(), 1);
+
+        let prompt = skills_prompt(&selected).unwrap();
+        assert!(prompt.starts_with("# Skills"));
+        assert!(prompt.contains("## refunds\n\nAgent refunds."));
+        assert!(skills_prompt(&[]).is_none());
+    }
+}
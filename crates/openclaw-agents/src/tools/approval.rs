@@ -0,0 +1,421 @@
+//! Operator approval for sensitive tool calls.
+//!
+//! Tools listed in an agent's `approval` policy are wrapped in an
+//! [`ApprovalTool`]. Each call is held in the shared [`ApprovalGate`] until
+//! an operator approves or denies it (over RPC or the CLI), or the policy's
+//! timeout passes, in which case it is denied.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::{broadcast, oneshot};
+
+use super::{DelegationScope, Tool, ToolError, ToolResult, execute_with_timeout};
+use crate::sandbox::SandboxConfig;
+
+/// Approval errors.
+#[derive(Error, Debug)]
+pub enum ApprovalError {
+    /// No pending request has this ID (it may have been decided or timed out).
+    #[error("No pending approval: {0}")]
+    NotFound(String),
+}
+
+/// A tool call waiting for a decision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    /// Request ID.
+    pub id: String,
+    /// Agent making the call.
+    pub agent_id: String,
+    /// Session the call is made in, if known.
+    pub session_key: Option<String>,
+    /// Tool name.
+    pub tool: String,
+    /// Tool parameters.
+    pub params: serde_json::Value,
+    /// When the call was made.
+    pub requested_at: DateTime<Utc>,
+    /// When the call is denied if still undecided.
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Outcome of an approval request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum ApprovalDecision {
+    /// The call may run.
+    Approved {
+        /// Who approved it.
+        by: String,
+    },
+    /// The call was rejected.
+    Denied {
+        /// Who denied it.
+        by: String,
+        /// Reason passed back to the model.
+        reason: Option<String>,
+    },
+    /// Nobody decided before the timeout.
+    TimedOut,
+}
+
+/// Change to the set of pending approvals.
+#[derive(Debug, Clone)]
+pub enum ApprovalEvent {
+    /// A call is waiting for a decision.
+    Requested(ApprovalRequest),
+    /// A pending call was decided or timed out.
+    Resolved {
+        /// Request ID.
+        id: String,
+        /// Outcome.
+        decision: ApprovalDecision,
+    },
+}
+
+/// Pending requests with the channel that wakes each waiting call.
+type PendingMap = HashMap<String, (ApprovalRequest, oneshot::Sender<ApprovalDecision>)>;
+
+/// Pending approvals shared between agents and the operator API.
+pub struct ApprovalGate {
+    pending: Mutex<PendingMap>,
+    events: broadcast::Sender<ApprovalEvent>,
+}
+
+impl ApprovalGate {
+    /// Create an empty gate.
+    #[must_use]
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(64);
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            events,
+        }
+    }
+
+    /// Receive an event whenever a request is added or resolved.
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<ApprovalEvent> {
+        self.events.subscribe()
+    }
+
+    /// Requests waiting for a decision, oldest first.
+    #[must_use]
+    pub fn pending(&self) -> Vec<ApprovalRequest> {
+        let mut requests: Vec<ApprovalRequest> = self
+            .lock()
+            .values()
+            .map(|(request, _)| request.clone())
+            .collect();
+        requests.sort_by_key(|r| r.requested_at);
+        requests
+    }
+
+    /// Hold a call to `tool` until it is decided or `timeout` passes.
+    pub async fn request(
+        &self,
+        agent_id: &str,
+        session_key: Option<String>,
+        tool: &str,
+        params: serde_json::Value,
+        timeout: Duration,
+    ) -> ApprovalDecision {
+        let (sender, receiver) = oneshot::channel();
+        let now = Utc::now();
+        let request = {
+            let mut pending = self.lock();
+            let mut nonce = now.timestamp_nanos_opt().unwrap_or_default();
+            while pending.contains_key(&format!("apr-{nonce:x}")) {
+                nonce += 1;
+            }
+            let request = ApprovalRequest {
+                id: format!("apr-{nonce:x}"),
+                agent_id: agent_id.to_string(),
+                session_key,
+                tool: tool.to_string(),
+                params,
+                requested_at: now,
+                expires_at: chrono::Duration::from_std(timeout)
+                    .ok()
+                    .and_then(|t| now.checked_add_signed(t))
+                    .unwrap_or(DateTime::<Utc>::MAX_UTC),
+            };
+            pending.insert(request.id.clone(), (request.clone(), sender));
+            request
+        };
+
+        let id = request.id.clone();
+        tracing::info!(id = %id, agent = agent_id, tool, "Tool call awaiting approval");
+        let _ = self.events.send(ApprovalEvent::Requested(request));
+
+        if let Ok(Ok(decision)) = tokio::time::timeout(timeout, receiver).await {
+            return decision;
+        }
+
+        // Timed out (or the gate dropped the sender); deny unless a
+        // decision raced in
+        if self.lock().remove(&id).is_some() {
+            tracing::info!(id = %id, "Tool approval timed out");
+            let _ = self.events.send(ApprovalEvent::Resolved {
+                id,
+                decision: ApprovalDecision::TimedOut,
+            });
+        }
+        ApprovalDecision::TimedOut
+    }
+
+    /// Decide a pending request.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if no request with `id` is pending.
+    pub fn decide(
+        &self,
+        id: &str,
+        decision: ApprovalDecision,
+    ) -> Result<ApprovalRequest, ApprovalError> {
+        let (request, sender) = self
+            .lock()
+            .remove(id)
+            .ok_or_else(|| ApprovalError::NotFound(id.to_string()))?;
+
+        tracing::info!(id, tool = %request.tool, ?decision, "Tool approval decided");
+        let _ = sender.send(decision.clone());
+        let _ = self.events.send(ApprovalEvent::Resolved {
+            id: id.to_string(),
+            decision,
+        });
+        Ok(request)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, PendingMap> {
+        self.pending
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Default for ApprovalGate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for ApprovalGate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApprovalGate")
+            .field("pending", &self.lock().len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Tool wrapper that waits for approval before each call.
+pub struct ApprovalTool {
+    inner: Arc<dyn Tool>,
+    gate: Arc<ApprovalGate>,
+    agent_id: String,
+    timeout: Duration,
+}
+
+impl ApprovalTool {
+    /// Wrap `inner` so calls made by `agent_id` wait up to `timeout` for a
+    /// decision in `gate`.
+    #[must_use]
+    pub fn new(
+        inner: Arc<dyn Tool>,
+        gate: Arc<ApprovalGate>,
+        agent_id: impl Into<String>,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            inner,
+            gate,
+            agent_id: agent_id.into(),
+            timeout,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ApprovalTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        self.inner.input_schema()
+    }
+
+    async fn execute(&self, params: serde_json::Value) -> Result<ToolResult, ToolError> {
+        let session_key = DelegationScope::current().map(|s| s.session_key.to_string());
+        let decision = self
+            .gate
+            .request(
+                &self.agent_id,
+                session_key,
+                self.name(),
+                params.clone(),
+                self.timeout,
+            )
+            .await;
+
+        match decision {
+            // Only the approved call counts against the inner tool's timeout
+            ApprovalDecision::Approved { .. } => {
+                execute_with_timeout(self.inner.as_ref(), params).await
+            }
+            ApprovalDecision::Denied { reason, .. } => Ok(ToolResult::error(format!(
+                "An operator denied this {} call{}",
+                self.name(),
+                reason.map(|r| format!(": {r}")).unwrap_or_default()
+            ))),
+            ApprovalDecision::TimedOut => Ok(ToolResult::error(format!(
+                "This {} call was not approved within {} seconds",
+                self.name(),
+                self.timeout.as_secs()
+            ))),
+        }
+    }
+
+    fn sandbox(&self) -> Option<&SandboxConfig> {
+        self.inner.sandbox()
+    }
+
+    fn with_sandbox(&self, config: SandboxConfig) -> Option<Arc<dyn Tool>> {
+        let inner = self.inner.with_sandbox(config)?;
+        Some(Arc::new(Self {
+            inner,
+            gate: self.gate.clone(),
+            agent_id: self.agent_id.clone(),
+            timeout: self.timeout,
+        }))
+    }
+
+    fn requires_approval(&self) -> bool {
+        self.inner.requires_approval()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::ToolRegistry;
+    use openclaw_core::config::ToolApprovalConfig;
+
+    struct BashTool;
+
+    #[async_trait]
+    impl Tool for BashTool {
+        fn name(&self) -> &'static str {
+            "bash"
+        }
+
+        fn description(&self) -> &'static str {
+            "Run a command"
+        }
+
+        fn input_schema(&self) -> serde_json::Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        async fn execute(&self, _params: serde_json::Value) -> Result<ToolResult, ToolError> {
+            Ok(ToolResult::success("ran"))
+        }
+    }
+
+    fn registry(gate: &Arc<ApprovalGate>) -> ToolRegistry {
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(BashTool));
+        registry.require_approval(
+            gate,
+            "ops",
+            &ToolApprovalConfig {
+                tools: vec!["bash".to_string()],
+                ..ToolApprovalConfig::default()
+            },
+        );
+        registry
+    }
+
+    async fn next_request(events: &mut broadcast::Receiver<ApprovalEvent>) -> ApprovalRequest {
+        match events.recv().await.unwrap() {
+            ApprovalEvent::Requested(request) => request,
+            ApprovalEvent::Resolved { .. } => panic!("expected a request"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_approve_and_deny() {
+        let gate = Arc::new(ApprovalGate::new());
+        let registry = Arc::new(registry(&gate));
+        let mut events = gate.subscribe();
+
+        let call = tokio::spawn({
+            let registry = registry.clone();
+            async move { registry.execute("bash", serde_json::json!({})).await }
+        });
+        let request = next_request(&mut events).await;
+        assert_eq!(request.agent_id, "ops");
+        assert_eq!(gate.pending().len(), 1);
+        gate.decide(
+            &request.id,
+            ApprovalDecision::Approved {
+                by: "admin".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(call.await.unwrap().unwrap().content, "ran");
+        assert!(gate.pending().is_empty());
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            ApprovalEvent::Resolved { .. }
+        ));
+
+        let call = tokio::spawn({
+            let registry = registry.clone();
+            async move { registry.execute("bash", serde_json::json!({})).await }
+        });
+        let request = next_request(&mut events).await;
+        gate.decide(
+            &request.id,
+            ApprovalDecision::Denied {
+                by: "admin".to_string(),
+                reason: Some("not on prod".to_string()),
+            },
+        )
+        .unwrap();
+        let result = call.await.unwrap().unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().ends_with("not on prod"));
+
+        assert!(matches!(
+            gate.decide(&request.id, ApprovalDecision::TimedOut),
+            Err(ApprovalError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_timeout_denies() {
+        let gate = Arc::new(ApprovalGate::new());
+        let tool = ApprovalTool::new(
+            Arc::new(BashTool),
+            gate.clone(),
+            "ops",
+            Duration::from_secs(60),
+        );
+
+        let result = tool.execute(serde_json::json!({})).await.unwrap();
+        assert!(!result.success);
+        assert!(gate.pending().is_empty());
+    }
+}
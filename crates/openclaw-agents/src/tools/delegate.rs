@@ -0,0 +1,409 @@
+//! Agent-to-agent delegation tool.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+use async_trait::async_trait;
+
+use openclaw_core::events::{EventStore, SessionEvent, SessionEventKind, SessionProjection};
+use openclaw_core::types::{AgentId, ChannelId, SessionKey, TokenUsage};
+
+use super::{Tool, ToolError, ToolResult};
+use crate::runtime::{AgentContext, AgentRuntime};
+
+/// Default maximum delegation depth.
+pub const DEFAULT_MAX_DELEGATION_DEPTH: u32 = 3;
+
+/// Shared directory of agent runtimes that can be delegated to.
+pub type AgentDirectory = Arc<RwLock<HashMap<String, Arc<AgentRuntime>>>>;
+
+tokio::task_local! {
+    static SCOPE: DelegationScope;
+}
+
+/// Session context of the agent currently running on this task.
+///
+/// Established by `AgentRuntime` around each top-level run and by
+/// `DelegateTool` around each delegated run, so tools know the session
+/// they run in and nested delegations know their parent session and depth.
+#[derive(Debug, Clone)]
+pub struct DelegationScope {
+    /// Session the current agent is running in.
+    pub session_key: SessionKey,
+    /// Delegation depth (0 for a top-level session).
+    pub depth: u32,
+    usage: Arc<Mutex<TokenUsage>>,
+}
+
+impl DelegationScope {
+    /// Create a top-level scope for a session.
+    #[must_use]
+    pub fn root(session_key: SessionKey) -> Self {
+        Self {
+            session_key,
+            depth: 0,
+            usage: Arc::new(Mutex::new(TokenUsage::default())),
+        }
+    }
+
+    /// Usage delegated from this scope so far.
+    #[must_use]
+    pub fn delegated_usage(&self) -> TokenUsage {
+        self.usage.lock().map(|u| u.clone()).unwrap_or_default()
+    }
+
+    /// Run a future within this scope.
+    pub async fn run<F: Future>(self, fut: F) -> F::Output {
+        SCOPE.scope(self, fut).await
+    }
+
+    /// Run a future in the current scope, or in a new top-level scope for
+    /// `session_key` if there is none. Agent runs start here, so tools and
+    /// agents they delegate to see the session they run in.
+    pub async fn enter<F: Future>(session_key: &SessionKey, fut: F) -> F::Output {
+        if SCOPE.try_with(|_| ()).is_ok() {
+            fut.await
+        } else {
+            Self::root(session_key.clone()).run(fut).await
+        }
+    }
+
+    /// Get the scope of the current task, if any.
+    #[must_use]
+    pub fn current() -> Option<Self> {
+        SCOPE.try_with(Clone::clone).ok()
+    }
+}
+
+/// Tool that lets one agent invoke another configured agent.
+///
+/// The target agent runs in its own session, derived from the parent
+/// session key. Token usage (including nested delegations) is recorded on
+/// the parent session as a `DelegatedUsage` event.
+pub struct DelegateTool {
+    agents: AgentDirectory,
+    event_store: Arc<EventStore>,
+    max_depth: u32,
+}
+
+impl DelegateTool {
+    /// Create a delegate tool.
+    #[must_use]
+    pub const fn new(agents: AgentDirectory, event_store: Arc<EventStore>) -> Self {
+        Self {
+            agents,
+            event_store,
+            max_depth: DEFAULT_MAX_DELEGATION_DEPTH,
+        }
+    }
+
+    /// Set the maximum delegation depth.
+    #[must_use]
+    pub const fn with_max_depth(mut self, max_depth: u32) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    fn append(&self, event: &SessionEvent) -> Result<(), ToolError> {
+        self.event_store
+            .append(event)
+            .map(|_| ())
+            .map_err(|e| ToolError::ExecutionFailed(format!("Failed to record event: {e}")))
+    }
+}
+
+#[async_trait]
+impl Tool for DelegateTool {
+    fn name(&self) -> &'static str {
+        "delegate"
+    }
+
+    fn description(&self) -> &'static str {
+        "Delegate a task to another agent and return its answer"
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "agent_id": {
+                    "type": "string",
+                    "description": "ID of the agent to delegate to"
+                },
+                "prompt": {
+                    "type": "string",
+                    "description": "Task description for the agent"
+                }
+            },
+            "required": ["agent_id", "prompt"]
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value) -> Result<ToolResult, ToolError> {
+        let agent_id = params["agent_id"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParams("Missing 'agent_id' parameter".to_string()))?;
+        let prompt = params["prompt"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParams("Missing 'prompt' parameter".to_string()))?;
+
+        let parent = DelegationScope::current();
+        let depth = parent.as_ref().map_or(0, |p| p.depth) + 1;
+        if depth > self.max_depth {
+            return Ok(ToolResult::error(format!(
+                "Delegation depth limit reached ({})",
+                self.max_depth
+            )));
+        }
+
+        let runtime = self
+            .agents
+            .read()
+            .map_err(|_| ToolError::ExecutionFailed("Agent directory poisoned".to_string()))?
+            .get(agent_id)
+            .cloned();
+        let Some(runtime) = runtime else {
+            return Ok(ToolResult::error(format!("Agent not found: {agent_id}")));
+        };
+
+        let parent_key = parent
+            .as_ref()
+            .map_or_else(|| "delegate".to_string(), |p| p.session_key.to_string());
+        let nonce = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default();
+        let child_key = SessionKey::new(format!("{parent_key}:delegate:{agent_id}:{nonce:x}"));
+
+        self.append(&SessionEvent::new(
+            child_key.clone(),
+            agent_id.to_string(),
+            SessionEventKind::SessionStarted {
+                channel: "delegate".to_string(),
+                peer_id: parent_key.clone(),
+            },
+        ))?;
+        self.append(&SessionEvent::new(
+            child_key.clone(),
+            agent_id.to_string(),
+            SessionEventKind::MessageReceived {
+                content: prompt.to_string(),
+                attachments: vec![],
+                sender_id: None,
+            },
+        ))?;
+
+        let mut ctx = AgentContext::new(
+            AgentId::new(agent_id),
+            child_key.clone(),
+            SessionProjection::new(
+                child_key.clone(),
+                agent_id.to_string(),
+                ChannelId::new("delegate"),
+                parent_key,
+            ),
+            runtime.tools().clone(),
+        );
+
+        let scope = DelegationScope {
+            session_key: child_key.clone(),
+            depth,
+            usage: Arc::new(Mutex::new(TokenUsage::default())),
+        };
+        let nested = scope.usage.clone();
+
+        let reply = match scope.run(runtime.process(&mut ctx, prompt)).await {
+            Ok(reply) => reply,
+            Err(e) => return Ok(ToolResult::error(format!("Delegated agent failed: {e}"))),
+        };
+
+        self.append(&SessionEvent::new(
+            child_key.clone(),
+            agent_id.to_string(),
+            SessionEventKind::AgentResponse {
+                content: reply.content.clone(),
+                model: reply.model.clone(),
+                tokens: reply.usage.clone(),
+                latency_ms: None,
+                citations: reply.citations.clone(),
+            },
+        ))?;
+
+        // Attribute this run plus anything it delegated to the parent
+        let mut total = reply.usage;
+        if let Ok(nested) = nested.lock() {
+            total.accumulate(&nested);
+        }
+
+        if let Some(parent) = parent {
+            if let Ok(mut usage) = parent.usage.lock() {
+                usage.accumulate(&total);
+            }
+            self.append(&SessionEvent::new(
+                parent.session_key,
+                agent_id.to_string(),
+                SessionEventKind::DelegatedUsage {
+                    agent_id: agent_id.to_string(),
+                    child_session: child_key.to_string(),
+                    tokens: total,
+                },
+            ))?;
+        }
+
+        Ok(ToolResult::success(reply.content))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::pin::Pin;
+
+    use openclaw_providers::traits::{
+        CompletionRequest, CompletionResponse, ContentBlock, Provider, ProviderError,
+        StreamingChunk,
+    };
+    use tempfile::tempdir;
+
+    /// Provider answering every request with the text `reply` makes.
+    struct FixedProvider(fn() -> String);
+
+    fn done() -> String {
+        "done".to_string()
+    }
+
+    /// The delegation scope a request was made in.
+    fn scope() -> String {
+        DelegationScope::current().map_or_else(
+            || "none".to_string(),
+            |s| format!("{}@{}", s.session_key, s.depth),
+        )
+    }
+
+    #[async_trait]
+    impl Provider for FixedProvider {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
+            Ok(vec![])
+        }
+
+        async fn complete(
+            &self,
+            request: CompletionRequest,
+        ) -> Result<CompletionResponse, ProviderError> {
+            Ok(CompletionResponse {
+                id: "1".to_string(),
+                model: request.model,
+                content: vec![ContentBlock::Text { text: (self.0)() }],
+                stop_reason: None,
+                usage: TokenUsage {
+                    input_tokens: 10,
+                    output_tokens: 5,
+                    cache_read_tokens: None,
+                    cache_write_tokens: None,
+                },
+            })
+        }
+
+        async fn complete_stream(
+            &self,
+            _request: CompletionRequest,
+        ) -> Result<
+            Pin<Box<dyn futures::Stream<Item = Result<StreamingChunk, ProviderError>> + Send>>,
+            ProviderError,
+        > {
+            Err(ProviderError::Config("unsupported".to_string()))
+        }
+    }
+
+    fn setup() -> (DelegateTool, Arc<EventStore>, tempfile::TempDir) {
+        let temp = tempdir().unwrap();
+        let store = Arc::new(EventStore::open(temp.path()).unwrap());
+        let agents: AgentDirectory = Arc::new(RwLock::new(HashMap::new()));
+        agents.write().unwrap().insert(
+            "helper".to_string(),
+            Arc::new(AgentRuntime::new(Arc::new(FixedProvider(done)))),
+        );
+        agents.write().unwrap().insert(
+            "scoped".to_string(),
+            Arc::new(AgentRuntime::new(Arc::new(FixedProvider(scope)))),
+        );
+        (DelegateTool::new(agents, store.clone()), store, temp)
+    }
+
+    #[tokio::test]
+    async fn test_delegate_attributes_usage() {
+        let (tool, store, _temp) = setup();
+        let parent = SessionKey::new("parent");
+
+        let result = DelegationScope::root(parent.clone())
+            .run(tool.execute(serde_json::json!({ "agent_id": "helper", "prompt": "hi" })))
+            .await
+            .unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.content, "done");
+
+        let events = store.get_events(&parent).unwrap();
+        let attributed = events.iter().find_map(|e| match &e.kind {
+            SessionEventKind::DelegatedUsage { tokens, .. } => Some(tokens.total()),
+            _ => None,
+        });
+        assert_eq!(attributed, Some(15));
+    }
+
+    #[tokio::test]
+    async fn test_runs_enter_scope() {
+        let (tool, _store, _temp) = setup();
+
+        // A top-level run roots a scope in its session
+        let key = SessionKey::new("top");
+        let runtime = AgentRuntime::new(Arc::new(FixedProvider(scope)));
+        let mut ctx = AgentContext::new(
+            AgentId::new("scoped"),
+            key.clone(),
+            SessionProjection::new(key, "scoped".to_string(), ChannelId::new("api"), "u".into()),
+            runtime.tools().clone(),
+        );
+        let reply = runtime.process(&mut ctx, "hi").await.unwrap();
+        assert_eq!(reply.content, "top@0");
+
+        // A delegated run keeps the scope delegation set up
+        let result = DelegationScope::root(SessionKey::new("parent"))
+            .run(tool.execute(serde_json::json!({ "agent_id": "scoped", "prompt": "hi" })))
+            .await
+            .unwrap();
+        assert!(result.content.starts_with("parent:delegate:scoped:"));
+        assert!(result.content.ends_with("@1"));
+    }
+
+    #[tokio::test]
+    async fn test_delegate_depth_limit() {
+        let (tool, _store, _temp) = setup();
+        let tool = tool.with_max_depth(1);
+
+        let scope = DelegationScope {
+            session_key: SessionKey::new("deep"),
+            depth: 1,
+            usage: Arc::new(Mutex::new(TokenUsage::default())),
+        };
+        let result = scope
+            .run(tool.execute(serde_json::json!({ "agent_id": "helper", "prompt": "hi" })))
+            .await
+            .unwrap();
+
+        assert!(!result.success);
+    }
+
+    #[tokio::test]
+    async fn test_delegate_unknown_agent() {
+        let (tool, _store, _temp) = setup();
+
+        let result = tool
+            .execute(serde_json::json!({ "agent_id": "missing", "prompt": "hi" }))
+            .await
+            .unwrap();
+
+        assert!(!result.success);
+    }
+}
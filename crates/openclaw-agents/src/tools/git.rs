@@ -0,0 +1,597 @@
+//! Git tools.
+//!
+//! Each operation is its own tool (`git_clone`, `git_status`, ...) so
+//! approval policies can single one out. All run the git CLI in the tool's
+//! sandbox, inside the working directory the sandbox is given (normally
+//! the agent's workspace). Clone and push only reach repositories on the
+//! agent's allowlist, and `git_push` always waits for approval.
+
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use openclaw_core::config::GitToolsConfig;
+
+use super::{Tool, ToolError, ToolResult};
+use crate::sandbox::{SandboxConfig, SandboxOutput};
+
+/// Most commits `git_log` returns.
+pub const MAX_LOG_ENTRIES: u64 = 100;
+
+/// Host files network operations need to resolve names and verify TLS.
+const NETWORK_READONLY_PATHS: [&str; 3] = ["/etc/resolv.conf", "/etc/hosts", "/etc/ssl"];
+
+/// A git operation exposed as a tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitOperation {
+    /// Clone an allowed repository into the workspace.
+    Clone,
+    /// Show working tree status.
+    Status,
+    /// Show changes.
+    Diff,
+    /// Show recent commits.
+    Log,
+    /// List, create, or switch branches.
+    Branch,
+    /// Stage all changes and commit.
+    Commit,
+    /// Push to an allowed remote.
+    Push,
+}
+
+impl GitOperation {
+    /// Every operation.
+    pub const ALL: [Self; 7] = [
+        Self::Clone,
+        Self::Status,
+        Self::Diff,
+        Self::Log,
+        Self::Branch,
+        Self::Commit,
+        Self::Push,
+    ];
+
+    /// Tool name.
+    #[must_use]
+    pub const fn tool_name(self) -> &'static str {
+        match self {
+            Self::Clone => "git_clone",
+            Self::Status => "git_status",
+            Self::Diff => "git_diff",
+            Self::Log => "git_log",
+            Self::Branch => "git_branch",
+            Self::Commit => "git_commit",
+            Self::Push => "git_push",
+        }
+    }
+
+    const fn description(self) -> &'static str {
+        match self {
+            Self::Clone => "Clone an allowed git repository into the workspace",
+            Self::Status => "Show the working tree status of a repository in the workspace",
+            Self::Diff => "Show unstaged, staged, or ref changes in a repository",
+            Self::Log => "Show recent commits in a repository",
+            Self::Branch => "List branches, or create and optionally switch to a branch",
+            Self::Commit => "Stage all changes in a repository and commit them",
+            Self::Push => "Push a branch to an allowed remote (requires operator approval)",
+        }
+    }
+
+    const fn uses_network(self) -> bool {
+        matches!(self, Self::Clone | Self::Push)
+    }
+}
+
+/// Tool running one git operation.
+pub struct GitTool {
+    operation: GitOperation,
+    config: GitToolsConfig,
+    sandbox_config: SandboxConfig,
+}
+
+impl GitTool {
+    /// Create a tool for `operation` with the agent's git settings.
+    #[must_use]
+    pub fn new(operation: GitOperation, config: GitToolsConfig) -> Self {
+        Self {
+            operation,
+            config,
+            sandbox_config: SandboxConfig::default(),
+        }
+    }
+
+    /// Tools for every operation.
+    #[must_use]
+    pub fn all(config: &GitToolsConfig) -> Vec<Arc<dyn Tool>> {
+        GitOperation::ALL
+            .into_iter()
+            .map(|op| Arc::new(Self::new(op, config.clone())) as Arc<dyn Tool>)
+            .collect()
+    }
+
+    /// Run in `config`; its working directory is where repositories live.
+    #[must_use]
+    pub fn with_sandbox_config(mut self, config: SandboxConfig) -> Self {
+        self.sandbox_config = config;
+        self
+    }
+
+    /// Resolve a repository path relative to the working directory.
+    fn repo_dir(&self, params: &serde_json::Value) -> Result<PathBuf, ToolError> {
+        let path = params["path"].as_str().unwrap_or(".");
+        let relative = Path::new(path);
+        if relative
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(ToolError::InvalidParams(format!(
+                "'path' must stay inside the workspace: {path}"
+            )));
+        }
+        let work_dir = self.sandbox_config.work_dir.as_ref().ok_or_else(|| {
+            ToolError::ExecutionFailed("git tools need a workspace to run in".to_string())
+        })?;
+        Ok(work_dir.join(relative))
+    }
+
+    /// Sandbox for this operation; clone and push get network access.
+    fn operation_sandbox(&self) -> SandboxConfig {
+        let mut config = self.sandbox_config.clone();
+        if self.operation.uses_network() {
+            if config.egress_proxy.is_none() {
+                config.network_allowed = true;
+            }
+            config.readonly_paths.extend(
+                NETWORK_READONLY_PATHS
+                    .iter()
+                    .map(PathBuf::from)
+                    .filter(|p| p.exists()),
+            );
+        }
+        config
+    }
+
+    /// Run git in `dir` with hardening options and the configured author.
+    async fn git(&self, dir: &Path, args: &[&str]) -> Result<SandboxOutput, ToolError> {
+        let mut full: Vec<String> = vec![
+            "-C".to_string(),
+            dir.display().to_string(),
+            // Repository hooks and local file remotes could escape the tool
+            "-c".to_string(),
+            "core.hooksPath=/dev/null".to_string(),
+            "-c".to_string(),
+            "protocol.file.allow=never".to_string(),
+            "-c".to_string(),
+            format!("user.name={}", self.config.author_name),
+            "-c".to_string(),
+            format!("user.email={}", self.config.author_email),
+        ];
+        full.extend(args.iter().map(ToString::to_string));
+
+        let config = self.operation_sandbox();
+        tokio::task::spawn_blocking(move || {
+            let args: Vec<&str> = full.iter().map(String::as_str).collect();
+            crate::sandbox::execute_sandboxed("git", &args, &config)
+        })
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?
+        .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
+    }
+
+    async fn run(&self, dir: &Path, args: &[&str]) -> Result<ToolResult, ToolError> {
+        let output = self.git(dir, args).await?;
+        Ok(to_result(output))
+    }
+
+    async fn clone_repo(&self, params: &serde_json::Value) -> Result<ToolResult, ToolError> {
+        let url = required(params, "url")?;
+        if !self.config.allows_repo(url) {
+            return Ok(ToolResult::error(format!(
+                "Repository {url} is not on this agent's git allowlist"
+            )));
+        }
+        let name = params["path"].as_str().map_or_else(
+            || {
+                url.trim_end_matches('/')
+                    .trim_end_matches(".git")
+                    .rsplit(['/', ':'])
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            },
+            ToString::to_string,
+        );
+        let dest = self.repo_dir(&serde_json::json!({ "path": name }))?;
+        let work_dir = self.repo_dir(&serde_json::json!({}))?;
+
+        let dest = dest.display().to_string();
+        let output = self.git(&work_dir, &["clone", "--", url, &dest]).await?;
+        // git reports clone progress on stderr
+        if output.exit_code == 0 {
+            Ok(ToolResult::success(format!("Cloned {url} into {name}")))
+        } else {
+            Ok(to_result(output))
+        }
+    }
+
+    async fn push(&self, params: &serde_json::Value) -> Result<ToolResult, ToolError> {
+        let dir = self.repo_dir(params)?;
+        let remote = params["remote"].as_str().unwrap_or("origin");
+        check_arg(remote, "remote")?;
+
+        let url = self
+            .git(&dir, &["remote", "get-url", "--push", remote])
+            .await?;
+        if url.exit_code != 0 {
+            return Ok(to_result(url));
+        }
+        let url = url.stdout.trim();
+        if !self.config.allows_repo(url) {
+            return Ok(ToolResult::error(format!(
+                "Remote {remote} ({url}) is not on this agent's git allowlist"
+            )));
+        }
+
+        let branch = params["branch"].as_str().unwrap_or("HEAD");
+        check_arg(branch, "branch")?;
+        let output = self.git(&dir, &["push", remote, branch]).await?;
+        if output.exit_code == 0 {
+            Ok(ToolResult::success(format!(
+                "Pushed {branch} to {remote}\n{}",
+                output.stderr.trim()
+            )))
+        } else {
+            Ok(to_result(output))
+        }
+    }
+}
+
+/// A required string parameter.
+fn required<'a>(params: &'a serde_json::Value, name: &str) -> Result<&'a str, ToolError> {
+    params[name]
+        .as_str()
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| ToolError::InvalidParams(format!("Missing '{name}' parameter")))
+}
+
+/// Reject values git would read as options.
+fn check_arg(value: &str, name: &str) -> Result<(), ToolError> {
+    if value.is_empty() || value.starts_with('-') || value.contains('\0') {
+        return Err(ToolError::InvalidParams(format!("Invalid {name}: {value}")));
+    }
+    Ok(())
+}
+
+fn to_result(output: SandboxOutput) -> ToolResult {
+    if output.exit_code == 0 {
+        ToolResult::success(output.stdout)
+    } else if output.stderr.trim().is_empty() {
+        ToolResult::error(format!("git exited with code {}", output.exit_code))
+    } else {
+        ToolResult::error(output.stderr)
+    }
+}
+
+#[async_trait]
+impl Tool for GitTool {
+    fn name(&self) -> &'static str {
+        self.operation.tool_name()
+    }
+
+    fn description(&self) -> &'static str {
+        self.operation.description()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        let path = serde_json::json!({
+            "type": "string",
+            "description": "Repository directory, relative to the workspace"
+        });
+        let (properties, required) = match self.operation {
+            GitOperation::Clone => (
+                serde_json::json!({
+                    "url": { "type": "string", "description": "Repository URL" },
+                    "path": {
+                        "type": "string",
+                        "description": "Directory to clone into (defaults to the repository name)"
+                    }
+                }),
+                vec!["url"],
+            ),
+            GitOperation::Status => (serde_json::json!({ "path": path }), vec![]),
+            GitOperation::Diff => (
+                serde_json::json!({
+                    "path": path,
+                    "staged": { "type": "boolean", "description": "Show staged changes" },
+                    "ref": { "type": "string", "description": "Compare against this commit or branch" }
+                }),
+                vec![],
+            ),
+            GitOperation::Log => (
+                serde_json::json!({
+                    "path": path,
+                    "limit": {
+                        "type": "integer",
+                        "description": format!("Commits to show (default 20, max {MAX_LOG_ENTRIES})")
+                    }
+                }),
+                vec![],
+            ),
+            GitOperation::Branch => (
+                serde_json::json!({
+                    "path": path,
+                    "name": { "type": "string", "description": "Branch to create (lists branches if omitted)" },
+                    "checkout": { "type": "boolean", "description": "Switch to the branch, creating it if needed" }
+                }),
+                vec![],
+            ),
+            GitOperation::Commit => (
+                serde_json::json!({
+                    "path": path,
+                    "message": { "type": "string", "description": "Commit message" }
+                }),
+                vec!["message"],
+            ),
+            GitOperation::Push => (
+                serde_json::json!({
+                    "path": path,
+                    "remote": { "type": "string", "description": "Remote name (default origin)" },
+                    "branch": { "type": "string", "description": "Branch to push (default the current branch)" }
+                }),
+                vec![],
+            ),
+        };
+        serde_json::json!({
+            "type": "object",
+            "properties": properties,
+            "required": required
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value) -> Result<ToolResult, ToolError> {
+        match self.operation {
+            GitOperation::Clone => self.clone_repo(&params).await,
+            GitOperation::Status => {
+                let dir = self.repo_dir(&params)?;
+                self.run(&dir, &["status", "--short", "--branch"]).await
+            }
+            GitOperation::Diff => {
+                let dir = self.repo_dir(&params)?;
+                let mut args = vec!["diff", "--no-color"];
+                if params["staged"].as_bool().unwrap_or(false) {
+                    args.push("--cached");
+                }
+                if let Some(reference) = params["ref"].as_str() {
+                    check_arg(reference, "ref")?;
+                    args.push(reference);
+                }
+                args.push("--");
+                self.run(&dir, &args).await
+            }
+            GitOperation::Log => {
+                let dir = self.repo_dir(&params)?;
+                let limit = params["limit"]
+                    .as_u64()
+                    .unwrap_or(20)
+                    .clamp(1, MAX_LOG_ENTRIES)
+                    .to_string();
+                self.run(
+                    &dir,
+                    &[
+                        "log",
+                        "--no-color",
+                        "--format=%h %ad %an: %s",
+                        "--date=short",
+                        "-n",
+                        &limit,
+                    ],
+                )
+                .await
+            }
+            GitOperation::Branch => {
+                let dir = self.repo_dir(&params)?;
+                let Some(name) = params["name"].as_str() else {
+                    return self.run(&dir, &["branch", "--list", "--no-color"]).await;
+                };
+                check_arg(name, "branch name")?;
+                if params["checkout"].as_bool().unwrap_or(false) {
+                    let exists = self
+                        .git(&dir, &["rev-parse", "--verify", "--quiet", name])
+                        .await?
+                        .exit_code
+                        == 0;
+                    let args: &[&str] = if exists {
+                        &["switch", name]
+                    } else {
+                        &["switch", "-c", name]
+                    };
+                    self.run(&dir, args).await
+                } else {
+                    self.run(&dir, &["branch", name]).await
+                }
+            }
+            GitOperation::Commit => {
+                let dir = self.repo_dir(&params)?;
+                let message = required(&params, "message")?;
+                let staged = self.git(&dir, &["add", "--all"]).await?;
+                if staged.exit_code != 0 {
+                    return Ok(to_result(staged));
+                }
+                self.run(&dir, &["commit", "--no-verify", "-m", message])
+                    .await
+            }
+            GitOperation::Push => self.push(&params).await,
+        }
+    }
+
+    fn sandbox(&self) -> Option<&SandboxConfig> {
+        Some(&self.sandbox_config)
+    }
+
+    fn with_sandbox(&self, config: SandboxConfig) -> Option<Arc<dyn Tool>> {
+        Some(Arc::new(Self {
+            operation: self.operation,
+            config: self.config.clone(),
+            sandbox_config: config,
+        }))
+    }
+
+    fn requires_approval(&self) -> bool {
+        self.operation == GitOperation::Push
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(operation: GitOperation) -> GitTool {
+        GitTool::new(
+            operation,
+            GitToolsConfig {
+                allowed_repos: vec!["github.com/neul-labs/*".to_string()],
+                ..Default::default()
+            },
+        )
+        .with_sandbox_config(SandboxConfig {
+            work_dir: Some(PathBuf::from("/srv/ws/dev")),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_tools() {
+        let tools = GitTool::all(&GitToolsConfig::default());
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert_eq!(
+            names,
+            [
+                "git_clone",
+                "git_status",
+                "git_diff",
+                "git_log",
+                "git_branch",
+                "git_commit",
+                "git_push"
+            ]
+        );
+        assert_eq!(
+            tools
+                .iter()
+                .filter(|t| t.requires_approval())
+                .map(|t| t.name())
+                .collect::<Vec<_>>(),
+            ["git_push"]
+        );
+        assert_eq!(
+            tool(GitOperation::Commit).input_schema()["required"],
+            serde_json::json!(["message"])
+        );
+    }
+
+    #[test]
+    fn test_repo_dir() {
+        let status = tool(GitOperation::Status);
+        assert_eq!(
+            status
+                .repo_dir(&serde_json::json!({ "path": "openclaw-rs" }))
+                .unwrap(),
+            PathBuf::from("/srv/ws/dev/openclaw-rs")
+        );
+        assert!(
+            status
+                .repo_dir(&serde_json::json!({ "path": "../other" }))
+                .is_err()
+        );
+        assert!(
+            status
+                .repo_dir(&serde_json::json!({ "path": "/etc" }))
+                .is_err()
+        );
+
+        let unmounted = GitTool::new(GitOperation::Status, GitToolsConfig::default());
+        assert!(matches!(
+            unmounted.repo_dir(&serde_json::json!({})),
+            Err(ToolError::ExecutionFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_network_sandbox() {
+        assert!(
+            !tool(GitOperation::Status)
+                .operation_sandbox()
+                .network_allowed
+        );
+        assert!(
+            tool(GitOperation::Clone)
+                .operation_sandbox()
+                .network_allowed
+        );
+
+        let proxied = tool(GitOperation::Push).with_sandbox_config(SandboxConfig {
+            egress_proxy: Some("127.0.0.1:3128".parse().unwrap()),
+            ..Default::default()
+        });
+        assert!(!proxied.operation_sandbox().network_allowed);
+    }
+
+    #[tokio::test]
+    async fn test_push_requires_approval() {
+        use crate::tools::{ApprovalDecision, ApprovalEvent, ApprovalGate, ToolRegistry};
+        use openclaw_core::config::ToolApprovalConfig;
+
+        let gate = Arc::new(ApprovalGate::new());
+        let mut events = gate.subscribe();
+        let mut registry = ToolRegistry::new();
+        for tool in GitTool::all(&GitToolsConfig::default()) {
+            registry.register(tool);
+        }
+        // The policy lists nothing, but push is gated anyway
+        registry.require_approval(&gate, "dev", &ToolApprovalConfig::default());
+        let registry = Arc::new(registry);
+
+        let call = tokio::spawn({
+            let registry = registry.clone();
+            async move { registry.execute("git_push", serde_json::json!({})).await }
+        });
+        let ApprovalEvent::Requested(request) = events.recv().await.unwrap() else {
+            panic!("expected a request");
+        };
+        assert_eq!(request.tool, "git_push");
+        gate.decide(
+            &request.id,
+            ApprovalDecision::Denied {
+                by: "admin".to_string(),
+                reason: None,
+            },
+        )
+        .unwrap();
+        assert!(!call.await.unwrap().unwrap().success);
+    }
+
+    #[tokio::test]
+    async fn test_rejections() {
+        let clone = tool(GitOperation::Clone);
+        let result = clone
+            .execute(serde_json::json!({ "url": "https://github.com/other/repo" }))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("allowlist"));
+
+        let diff = tool(GitOperation::Diff);
+        assert!(matches!(
+            diff.execute(serde_json::json!({ "ref": "--output=/tmp/x" }))
+                .await,
+            Err(ToolError::InvalidParams(_))
+        ));
+        assert!(matches!(
+            tool(GitOperation::Commit)
+                .execute(serde_json::json!({}))
+                .await,
+            Err(ToolError::InvalidParams(_))
+        ));
+    }
+}
@@ -1,14 +1,40 @@
 //! Tool registry and execution.
 
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use openclaw_core::config::{
+    SandboxProfileConfig, ToolApprovalConfig, ToolConcurrencyConfig, WorkspaceConfig,
+};
 use openclaw_providers::traits::Tool as ToolDefinition;
 
+use crate::runtime::ConcurrencyLimiter;
+use crate::sandbox::{EgressProxy, SandboxConfig};
+
+mod approval;
+mod delegate;
+mod git;
+mod profile;
+mod remind;
+mod state;
+mod workspace;
+
+pub use approval::{
+    ApprovalDecision, ApprovalError, ApprovalEvent, ApprovalGate, ApprovalRequest, ApprovalTool,
+};
+pub use delegate::{AgentDirectory, DEFAULT_MAX_DELEGATION_DEPTH, DelegateTool, DelegationScope};
+pub use git::{GitOperation, GitTool, MAX_LOG_ENTRIES};
+pub use profile::PeerProfileTool;
+pub use remind::{MAX_REMINDER_DELAY_DAYS, RemindTool, parse_delay};
+pub use state::SessionStateTool;
+pub use workspace::WorkspaceTool;
+
 /// Tool execution errors.
 #[derive(Error, Debug)]
 pub enum ToolError {
@@ -27,6 +53,19 @@ pub enum ToolError {
     /// Tool timed out.
     #[error("Tool timed out")]
     Timeout,
+
+    /// Too many calls of the tool are already waiting to run.
+    #[error("Tool busy: {0}")]
+    Busy(String),
+
+    /// The agent's tool policy forbids the tool.
+    #[error("Tool not allowed: {tool} ({reason})")]
+    Denied {
+        /// Tool name.
+        tool: String,
+        /// Why the tool is not allowed.
+        reason: String,
+    },
 }
 
 /// Tool execution result.
@@ -38,6 +77,22 @@ pub struct ToolResult {
     pub content: String,
     /// Error message if failed.
     pub error: Option<String>,
+    /// Sources backing the result (search/knowledge tools).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<ToolSource>,
+}
+
+/// Source metadata attached to a tool result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolSource {
+    /// Source title.
+    pub title: String,
+    /// Source URL, if any.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Relevant excerpt.
+    #[serde(default)]
+    pub snippet: Option<String>,
 }
 
 impl ToolResult {
@@ -48,6 +103,7 @@ impl ToolResult {
             success: true,
             content: content.into(),
             error: None,
+            sources: Vec::new(),
         }
     }
 
@@ -58,8 +114,16 @@ impl ToolResult {
             success: false,
             content: String::new(),
             error: Some(error.into()),
+            sources: Vec::new(),
         }
     }
+
+    /// Attach source metadata for citation.
+    #[must_use]
+    pub fn with_sources(mut self, sources: Vec<ToolSource>) -> Self {
+        self.sources = sources;
+        self
+    }
 }
 
 /// Tool trait for implementing custom tools.
@@ -76,11 +140,49 @@ pub trait Tool: Send + Sync {
 
     /// Execute the tool.
     async fn execute(&self, params: serde_json::Value) -> Result<ToolResult, ToolError>;
+
+    /// Sandbox the tool runs commands in, if it uses one.
+    fn sandbox(&self) -> Option<&SandboxConfig> {
+        None
+    }
+
+    /// A copy of this tool that runs in `config`; `None` if the tool does
+    /// not use a sandbox.
+    fn with_sandbox(&self, _config: SandboxConfig) -> Option<Arc<dyn Tool>> {
+        None
+    }
+
+    /// Whether every call needs operator approval, whatever the agent's
+    /// approval policy lists.
+    fn requires_approval(&self) -> bool {
+        false
+    }
+
+    /// How long a call may run before the registry fails it with
+    /// `Timeout`; unbounded if `None`.
+    fn execution_timeout(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Run `tool`, failing with `Timeout` once its execution timeout passes.
+pub(crate) async fn execute_with_timeout(
+    tool: &dyn Tool,
+    params: serde_json::Value,
+) -> Result<ToolResult, ToolError> {
+    let Some(timeout) = tool.execution_timeout() else {
+        return tool.execute(params).await;
+    };
+    tokio::time::timeout(timeout, tool.execute(params))
+        .await
+        .map_err(|_| ToolError::Timeout)?
 }
 
 /// Registry of available tools.
 pub struct ToolRegistry {
     tools: HashMap<String, Arc<dyn Tool>>,
+    limiter: ConcurrencyLimiter,
+    tool_limiters: HashMap<String, ConcurrencyLimiter>,
 }
 
 impl ToolRegistry {
@@ -89,6 +191,8 @@ impl ToolRegistry {
     pub fn new() -> Self {
         Self {
             tools: HashMap::new(),
+            limiter: ConcurrencyLimiter::unlimited(),
+            tool_limiters: HashMap::new(),
         }
     }
 
@@ -97,6 +201,100 @@ impl ToolRegistry {
         self.tools.insert(tool.name().to_string(), tool);
     }
 
+    /// Wrap the tools that `policy` lists, and those that always require
+    /// approval, so each call by `agent_id` waits for a decision in `gate`.
+    pub fn require_approval(
+        &mut self,
+        gate: &Arc<ApprovalGate>,
+        agent_id: &str,
+        policy: &ToolApprovalConfig,
+    ) {
+        for (name, tool) in &mut self.tools {
+            if policy.requires_approval(name) || tool.requires_approval() {
+                *tool = Arc::new(ApprovalTool::new(
+                    tool.clone(),
+                    gate.clone(),
+                    agent_id,
+                    policy.timeout_for(name),
+                ));
+            }
+        }
+    }
+
+    /// Apply per-tool sandbox profiles on top of the sandbox each tool
+    /// declares.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if a profile names an unregistered tool, or
+    /// `InvalidParams` if the tool does not use a sandbox.
+    pub fn apply_sandbox_profiles(
+        &mut self,
+        profiles: &HashMap<String, SandboxProfileConfig>,
+    ) -> Result<(), ToolError> {
+        for (name, profile) in profiles {
+            let tool = self
+                .tools
+                .get_mut(name)
+                .ok_or_else(|| ToolError::NotFound(name.clone()))?;
+            let unsandboxed =
+                || ToolError::InvalidParams(format!("Tool '{name}' does not use a sandbox"));
+            let config = tool
+                .sandbox()
+                .ok_or_else(unsandboxed)?
+                .clone()
+                .with_profile(profile);
+            *tool = tool.with_sandbox(config).ok_or_else(unsandboxed)?;
+        }
+        Ok(())
+    }
+
+    /// Give every sandboxed tool network access only through `proxy`.
+    /// The caller keeps the proxy running.
+    pub fn route_egress(&mut self, proxy: &EgressProxy) {
+        for tool in self.tools.values_mut() {
+            if let Some(config) = tool.sandbox() {
+                let config = config.clone().with_egress(proxy);
+                if let Some(routed) = tool.with_sandbox(config) {
+                    *tool = routed;
+                }
+            }
+        }
+    }
+
+    /// Run every sandboxed tool in `agent_id`'s workspace under `base`,
+    /// enforcing the quota in `config`. Tools without a sandbox are left
+    /// as they are.
+    pub fn mount_workspace(&mut self, base: &Path, agent_id: &str, config: &WorkspaceConfig) {
+        for tool in self.tools.values_mut() {
+            if tool.sandbox().is_some() {
+                *tool = Arc::new(WorkspaceTool::new(
+                    tool.clone(),
+                    base,
+                    agent_id,
+                    config.clone(),
+                ));
+            }
+        }
+    }
+
+    /// Bound simultaneous calls overall and per tool as `config` sets,
+    /// queueing calls that wait for a slot.
+    pub fn limit_concurrency(&mut self, config: &ToolConcurrencyConfig) {
+        let max_queued = usize::try_from(config.max_queued).unwrap_or(usize::MAX);
+        let limiter = |max: u32| {
+            ConcurrencyLimiter::new(usize::try_from(max).unwrap_or(usize::MAX), max_queued)
+        };
+        self.limiter = config
+            .max_concurrent
+            .map_or_else(ConcurrencyLimiter::unlimited, limiter);
+        self.tool_limiters = config
+            .per_tool
+            .iter()
+            .map(|(name, max)| (name.clone(), limiter(*max)))
+            .collect();
+    }
+
     /// Get a tool by name.
     #[must_use]
     pub fn get(&self, name: &str) -> Option<&Arc<dyn Tool>> {
@@ -109,11 +307,13 @@ impl ToolRegistry {
         self.tools.keys().map(String::as_str).collect()
     }
 
-    /// Execute a tool by name.
+    /// Execute a tool by name once a slot is free, within the tool's
+    /// execution timeout.
     ///
     /// # Errors
     ///
-    /// Returns error if tool not found or execution fails.
+    /// Returns `Busy` if the queue for a slot is full, `Timeout` if the
+    /// call overruns, or error if tool not found or execution fails.
     pub async fn execute(
         &self,
         name: &str,
@@ -123,7 +323,16 @@ impl ToolRegistry {
             .tools
             .get(name)
             .ok_or_else(|| ToolError::NotFound(name.to_string()))?;
-        tool.execute(params).await
+
+        // Take the tool's own slot first, so a call waiting on it holds
+        // none of the overall slots
+        let busy = || ToolError::Busy(name.to_string());
+        let _tool_slot = match self.tool_limiters.get(name) {
+            Some(limiter) => Some(limiter.acquire().await.ok_or_else(busy)?),
+            None => None,
+        };
+        let _slot = self.limiter.acquire().await.ok_or_else(busy)?;
+        execute_with_timeout(tool.as_ref(), params).await
     }
 
     /// Get tool definitions for provider API.
@@ -148,7 +357,8 @@ impl Default for ToolRegistry {
 
 /// Built-in bash tool for command execution.
 pub struct BashTool {
-    sandbox_config: crate::sandbox::SandboxConfig,
+    sandbox_config: SandboxConfig,
+    egress_proxy: Option<Arc<EgressProxy>>,
 }
 
 impl BashTool {
@@ -156,17 +366,27 @@ impl BashTool {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            sandbox_config: crate::sandbox::SandboxConfig::default(),
+            sandbox_config: SandboxConfig::default(),
+            egress_proxy: None,
         }
     }
 
     /// Create with custom sandbox config.
     #[must_use]
-    pub const fn with_sandbox_config(config: crate::sandbox::SandboxConfig) -> Self {
+    pub const fn with_sandbox_config(config: SandboxConfig) -> Self {
         Self {
             sandbox_config: config,
+            egress_proxy: None,
         }
     }
+
+    /// Give commands network access only through `proxy`.
+    #[must_use]
+    pub fn with_egress_proxy(mut self, proxy: Arc<EgressProxy>) -> Self {
+        self.sandbox_config = self.sandbox_config.with_egress(&proxy);
+        self.egress_proxy = Some(proxy);
+        self
+    }
 }
 
 impl Default for BashTool {
@@ -203,10 +423,16 @@ impl Tool for BashTool {
             .as_str()
             .ok_or_else(|| ToolError::InvalidParams("Missing 'command' parameter".to_string()))?;
 
-        // Execute in sandbox
-        let output =
-            crate::sandbox::execute_sandboxed("bash", &["-c", command], &self.sandbox_config)
-                .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+        // Execute in sandbox, off the async runtime so the egress proxy
+        // keeps serving while the command runs
+        let command = command.to_string();
+        let config = self.sandbox_config.clone();
+        let output = tokio::task::spawn_blocking(move || {
+            crate::sandbox::execute_sandboxed("bash", &["-c", &command], &config)
+        })
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?
+        .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
 
         if output.exit_code == 0 {
             Ok(ToolResult::success(output.stdout))
@@ -219,6 +445,17 @@ impl Tool for BashTool {
             Ok(ToolResult::error(error_msg))
         }
     }
+
+    fn sandbox(&self) -> Option<&SandboxConfig> {
+        Some(&self.sandbox_config)
+    }
+
+    fn with_sandbox(&self, config: SandboxConfig) -> Option<Arc<dyn Tool>> {
+        Some(Arc::new(Self {
+            sandbox_config: config,
+            egress_proxy: self.egress_proxy.clone(),
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -243,4 +480,183 @@ mod tests {
         assert_eq!(defs.len(), 1);
         assert_eq!(defs[0].name, "bash");
     }
+
+    #[test]
+    fn test_sandbox_profiles() {
+        use crate::sandbox::SandboxLevel;
+
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(BashTool::new()));
+
+        let profiles = HashMap::from([(
+            "bash".to_string(),
+            SandboxProfileConfig {
+                level: Some(SandboxLevel::Paranoid),
+                ..Default::default()
+            },
+        )]);
+        registry.apply_sandbox_profiles(&profiles).unwrap();
+        let sandbox = registry.get("bash").unwrap().sandbox().unwrap();
+        assert_eq!(sandbox.level, SandboxLevel::Paranoid);
+        assert_eq!(sandbox.max_memory_mb, 512);
+
+        let profiles = HashMap::from([("git".to_string(), SandboxProfileConfig::default())]);
+        assert!(matches!(
+            registry.apply_sandbox_profiles(&profiles),
+            Err(ToolError::NotFound(_))
+        ));
+    }
+
+    /// Reports the directory its sandbox runs in.
+    struct WorkDirTool(SandboxConfig);
+
+    #[async_trait]
+    impl Tool for WorkDirTool {
+        fn name(&self) -> &'static str {
+            "pwd"
+        }
+
+        fn description(&self) -> &'static str {
+            "Print the working directory"
+        }
+
+        fn input_schema(&self) -> serde_json::Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        async fn execute(&self, params: serde_json::Value) -> Result<ToolResult, ToolError> {
+            let dir = self.0.work_dir.clone().unwrap_or_default();
+            if let Some(file) = params["write"].as_str() {
+                std::fs::write(dir.join(file), [0u8; 2048]).unwrap();
+            }
+            Ok(ToolResult::success(dir.display().to_string()))
+        }
+
+        fn sandbox(&self) -> Option<&SandboxConfig> {
+            Some(&self.0)
+        }
+
+        fn with_sandbox(&self, config: SandboxConfig) -> Option<Arc<dyn Tool>> {
+            Some(Arc::new(Self(config)))
+        }
+    }
+
+    /// Sleeps for `ms`, with a one second execution timeout.
+    struct SlowTool;
+
+    #[async_trait]
+    impl Tool for SlowTool {
+        fn name(&self) -> &'static str {
+            "slow"
+        }
+
+        fn description(&self) -> &'static str {
+            "Sleep"
+        }
+
+        fn input_schema(&self) -> serde_json::Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        async fn execute(&self, params: serde_json::Value) -> Result<ToolResult, ToolError> {
+            let ms = params["ms"].as_u64().unwrap_or_default();
+            tokio::time::sleep(Duration::from_millis(ms)).await;
+            Ok(ToolResult::success("done"))
+        }
+
+        fn execution_timeout(&self) -> Option<Duration> {
+            Some(Duration::from_secs(1))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limits_and_timeout() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(SlowTool));
+        registry.limit_concurrency(&ToolConcurrencyConfig {
+            per_tool: HashMap::from([("slow".to_string(), 1)]),
+            max_queued: 1,
+            ..Default::default()
+        });
+        let registry = Arc::new(registry);
+
+        let call = |ms: u64| {
+            let registry = registry.clone();
+            tokio::spawn(async move {
+                registry
+                    .execute("slow", serde_json::json!({ "ms": ms }))
+                    .await
+            })
+        };
+        let running = call(300);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let queued = call(0);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // One call runs, one waits, and the queue is full
+        assert!(matches!(
+            registry.execute("slow", serde_json::json!({})).await,
+            Err(ToolError::Busy(_))
+        ));
+        assert!(running.await.unwrap().unwrap().success);
+        assert!(queued.await.unwrap().unwrap().success);
+
+        assert!(matches!(
+            registry
+                .execute("slow", serde_json::json!({ "ms": 5000 }))
+                .await,
+            Err(ToolError::Timeout)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_mount_workspace() {
+        use openclaw_core::types::SessionKey;
+
+        let temp = tempfile::tempdir().unwrap();
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(WorkDirTool(SandboxConfig::default())));
+        registry.mount_workspace(
+            temp.path(),
+            "dev",
+            &WorkspaceConfig {
+                per_session: true,
+                quota_mb: Some(1),
+                expire_after_hours: None,
+            },
+        );
+
+        let result = registry
+            .execute("pwd", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert_eq!(
+            result.content,
+            temp.path().join("dev").display().to_string()
+        );
+
+        let scope = DelegationScope::root(SessionKey::new("s1"));
+        let result = scope
+            .run(registry.execute("pwd", serde_json::json!({})))
+            .await
+            .unwrap();
+        let session_dir = temp.path().join("dev/sessions/s1");
+        assert_eq!(result.content, session_dir.display().to_string());
+        assert!(session_dir.join(crate::workspace::MARKER_FILE).exists());
+
+        // Over quota: the call that overruns is flagged, the next refused
+        std::fs::write(temp.path().join("dev/big"), vec![0u8; 1024 * 1023]).unwrap();
+        let result = registry
+            .execute("pwd", serde_json::json!({ "write": "more" }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(!result.content.is_empty());
+        assert!(result.error.unwrap().contains("quota exceeded"));
+        let result = registry
+            .execute("pwd", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert!(result.content.is_empty());
+    }
 }
@@ -0,0 +1,189 @@
+//! Peer profile tool.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use openclaw_core::events::EventStore;
+use openclaw_core::peers::{
+    PeerProfile, PeerProfileError, PeerProfileStore, ProfileSource, ProfileUpdate,
+};
+
+use super::{DelegationScope, Tool, ToolError, ToolResult};
+
+/// Tool that lets the agent read and note what it learns about the person
+/// it is talking to: name, language, timezone, notes, and preferences.
+///
+/// Values are saved as learned, so they never replace ones set by an
+/// operator or the person, and are shown to the model in later turns'
+/// system prompt.
+pub struct PeerProfileTool {
+    profiles: Arc<PeerProfileStore>,
+    event_store: Arc<EventStore>,
+}
+
+impl PeerProfileTool {
+    /// Create a peer profile tool. Sessions are looked up in `event_store`
+    /// to find who the agent is talking to.
+    #[must_use]
+    pub const fn new(profiles: Arc<PeerProfileStore>, event_store: Arc<EventStore>) -> Self {
+        Self {
+            profiles,
+            event_store,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for PeerProfileTool {
+    fn name(&self) -> &'static str {
+        "peer_profile"
+    }
+
+    fn description(&self) -> &'static str {
+        "Read or update what you know about the user: name, language, timezone, notes, preferences"
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["get", "update"],
+                    "description": "Operation to perform"
+                },
+                "display_name": {
+                    "type": "string",
+                    "description": "Name the user wants to be called"
+                },
+                "language": {
+                    "type": "string",
+                    "description": "Preferred language as a BCP 47 tag, e.g. \"de\" or \"pt-BR\""
+                },
+                "timezone": {
+                    "type": "string",
+                    "description": "IANA timezone, e.g. \"Europe/Berlin\""
+                },
+                "notes": {
+                    "type": "string",
+                    "description": "Notes about the user; replaces existing notes"
+                },
+                "preferences": {
+                    "type": "object",
+                    "additionalProperties": { "type": ["boolean", "null"] },
+                    "description": "Preference flags to set, e.g. {\"brief_replies\": true}; null removes one"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value) -> Result<ToolResult, ToolError> {
+        let action = params["action"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParams("Missing 'action' parameter".to_string()))?;
+        let session_key = DelegationScope::current()
+            .map(|s| s.session_key)
+            .ok_or_else(|| ToolError::ExecutionFailed("No current session".to_string()))?;
+        let session = self
+            .event_store
+            .get_projection(&session_key)
+            .map_err(|e| ToolError::ExecutionFailed(format!("Unknown session: {e}")))?;
+        let channel = session.channel.as_ref();
+
+        let result = match action {
+            "get" => self.profiles.get(channel, &session.peer_id).map(|profile| {
+                profile
+                    .as_ref()
+                    .and_then(PeerProfile::prompt)
+                    .unwrap_or_else(|| "Nothing is known about this user yet".to_string())
+            }),
+            "update" => {
+                let update: ProfileUpdate = serde_json::from_value(params.clone())
+                    .map_err(|e| ToolError::InvalidParams(e.to_string()))?;
+                if update.is_empty() {
+                    return Err(ToolError::InvalidParams("Nothing to update".to_string()));
+                }
+                self.profiles
+                    .update(channel, &session.peer_id, &update, ProfileSource::Learned)
+                    .map(|_| "Profile updated".to_string())
+            }
+            other => {
+                return Err(ToolError::InvalidParams(format!(
+                    "Unknown action '{other}'"
+                )));
+            }
+        };
+
+        match result {
+            Ok(content) => Ok(ToolResult::success(content)),
+            // Invalid values are for the model to correct
+            Err(e @ PeerProfileError::Invalid(_)) => Ok(ToolResult::error(e.to_string())),
+            Err(e) => Err(ToolError::ExecutionFailed(e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openclaw_core::events::{SessionEvent, SessionEventKind};
+    use openclaw_core::types::{ChannelId, SessionKey};
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_learned_profile() {
+        let temp = tempdir().unwrap();
+        let event_store = Arc::new(EventStore::open(&temp.path().join("events")).unwrap());
+        let profiles = Arc::new(PeerProfileStore::open(&temp.path().join("peers")).unwrap());
+        let session_key = SessionKey::new("telegram:7");
+        event_store
+            .append(&SessionEvent::new(
+                session_key.clone(),
+                "default".to_string(),
+                SessionEventKind::SessionStarted {
+                    channel: ChannelId::telegram().to_string(),
+                    peer_id: "7".to_string(),
+                },
+            ))
+            .unwrap();
+        profiles
+            .update(
+                "telegram",
+                "7",
+                &ProfileUpdate {
+                    language: Some("en".to_string()),
+                    ..ProfileUpdate::default()
+                },
+                ProfileSource::Explicit,
+            )
+            .unwrap();
+        let tool = PeerProfileTool::new(profiles.clone(), event_store);
+        let scope = DelegationScope::root(session_key);
+
+        let result = scope
+            .clone()
+            .run(tool.execute(json!({
+                "action": "update",
+                "display_name": "Ada",
+                "language": "fr",
+                "preferences": { "brief_replies": true }
+            })))
+            .await
+            .unwrap();
+        assert!(result.success);
+
+        let profile = profiles.get("telegram", "7").unwrap().unwrap();
+        assert_eq!(profile.display_name.as_deref(), Some("Ada"));
+        assert_eq!(profile.language.as_deref(), Some("en"));
+        assert_eq!(profile.sources["display_name"], ProfileSource::Learned);
+
+        let result = scope
+            .run(tool.execute(json!({ "action": "update", "timezone": "not a zone" })))
+            .await
+            .unwrap();
+        assert!(!result.success);
+    }
+}
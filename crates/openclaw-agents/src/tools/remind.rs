@@ -0,0 +1,246 @@
+//! Reminder tool.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+
+use openclaw_core::events::{EventStore, SessionEvent, SessionEventKind};
+use openclaw_core::types::SessionKey;
+
+use super::{DelegationScope, Tool, ToolError, ToolResult};
+
+/// Longest delay a reminder may be scheduled for.
+pub const MAX_REMINDER_DELAY_DAYS: i64 = 365;
+
+/// Tool that schedules a message to be sent back to the user later.
+///
+/// Reminders are stored as `ReminderScheduled` events, so they survive a
+/// restart; the gateway delivers them through the session's channel once due.
+pub struct RemindTool {
+    event_store: Arc<EventStore>,
+}
+
+impl RemindTool {
+    /// Create a reminder tool.
+    #[must_use]
+    pub const fn new(event_store: Arc<EventStore>) -> Self {
+        Self { event_store }
+    }
+}
+
+#[async_trait]
+impl Tool for RemindTool {
+    fn name(&self) -> &'static str {
+        "remind"
+    }
+
+    fn description(&self) -> &'static str {
+        "Schedule a reminder message to be sent to the user after a delay or at a time"
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "message": {
+                    "type": "string",
+                    "description": "Reminder text to send"
+                },
+                "delay": {
+                    "type": "string",
+                    "description": "Delay such as \"2 hours\", \"30m\", or \"1d 4h\""
+                },
+                "at": {
+                    "type": "string",
+                    "description": "Absolute time (RFC 3339), instead of delay"
+                },
+                "session_key": {
+                    "type": "string",
+                    "description": "Session to remind (defaults to the current session)"
+                }
+            },
+            "required": ["message"]
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value) -> Result<ToolResult, ToolError> {
+        let message = params["message"]
+            .as_str()
+            .filter(|m| !m.trim().is_empty())
+            .ok_or_else(|| ToolError::InvalidParams("Missing 'message' parameter".to_string()))?;
+
+        let now = Utc::now();
+        let due_at = match (params["delay"].as_str(), params["at"].as_str()) {
+            (Some(delay), _) => {
+                let delay = parse_delay(delay).ok_or_else(|| {
+                    ToolError::InvalidParams(format!("Unrecognized delay: {delay}"))
+                })?;
+                now + delay
+            }
+            (None, Some(at)) => DateTime::parse_from_rfc3339(at)
+                .map_err(|e| ToolError::InvalidParams(format!("Invalid 'at' time: {e}")))?
+                .with_timezone(&Utc),
+            (None, None) => {
+                return Err(ToolError::InvalidParams(
+                    "Either 'delay' or 'at' is required".to_string(),
+                ));
+            }
+        };
+
+        if due_at <= now {
+            return Ok(ToolResult::error("Reminder time is in the past"));
+        }
+        if due_at - now > Duration::days(MAX_REMINDER_DELAY_DAYS) {
+            return Ok(ToolResult::error(format!(
+                "Reminders can be at most {MAX_REMINDER_DELAY_DAYS} days ahead"
+            )));
+        }
+
+        let session_key = params["session_key"]
+            .as_str()
+            .map(SessionKey::new)
+            .or_else(|| DelegationScope::current().map(|s| s.session_key))
+            .ok_or_else(|| ToolError::InvalidParams("No session to remind".to_string()))?;
+
+        let projection = self
+            .event_store
+            .get_projection(&session_key)
+            .map_err(|e| ToolError::ExecutionFailed(format!("Unknown session: {e}")))?;
+
+        let nonce = now.timestamp_nanos_opt().unwrap_or_default();
+        let reminder_id = format!("rem-{nonce:x}");
+
+        self.event_store
+            .append(&SessionEvent::new(
+                session_key,
+                projection.agent_id,
+                SessionEventKind::ReminderScheduled {
+                    reminder_id: reminder_id.clone(),
+                    message: message.to_string(),
+                    due_at,
+                },
+            ))
+            .map_err(|e| ToolError::ExecutionFailed(format!("Failed to save reminder: {e}")))?;
+
+        Ok(ToolResult::success(format!(
+            "Reminder {reminder_id} set for {}",
+            due_at.format("%Y-%m-%d %H:%M UTC")
+        )))
+    }
+}
+
+/// Parse a human delay like `"in 2 hours"`, `"90m"`, or `"1d 4h"`.
+#[must_use]
+pub fn parse_delay(input: &str) -> Option<Duration> {
+    let input = input.trim().to_lowercase();
+    let input = input.strip_prefix("in ").unwrap_or(&input);
+
+    let mut total = Duration::zero();
+    let mut rest = input.trim();
+    let mut matched = false;
+
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if digits == 0 {
+            return None;
+        }
+        let amount: i64 = rest[..digits].parse().ok()?;
+        rest = rest[digits..].trim_start();
+
+        let unit_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let unit = &rest[..unit_len];
+        rest = rest[unit_len..].trim_start_matches([' ', ',']).trim_start();
+        rest = rest.strip_prefix("and ").unwrap_or(rest);
+
+        let step = match unit {
+            "s" | "sec" | "secs" | "second" | "seconds" => Duration::try_seconds(amount)?,
+            "m" | "min" | "mins" | "minute" | "minutes" => Duration::try_minutes(amount)?,
+            "h" | "hr" | "hrs" | "hour" | "hours" => Duration::try_hours(amount)?,
+            "d" | "day" | "days" => Duration::try_days(amount)?,
+            "w" | "week" | "weeks" => Duration::try_weeks(amount)?,
+            _ => return None,
+        };
+        total = total.checked_add(&step)?;
+        matched = true;
+    }
+
+    matched.then_some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openclaw_core::types::ChannelId;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_delay() {
+        assert_eq!(parse_delay("in 2 hours"), Some(Duration::hours(2)));
+        assert_eq!(parse_delay("90m"), Some(Duration::minutes(90)));
+        assert_eq!(
+            parse_delay("1d 4h"),
+            Some(Duration::days(1) + Duration::hours(4))
+        );
+        assert_eq!(
+            parse_delay("1 hour and 30 minutes"),
+            Some(Duration::minutes(90))
+        );
+        assert_eq!(parse_delay("soon"), None);
+        assert_eq!(parse_delay("2 fortnights"), None);
+        assert_eq!(parse_delay(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_remind_persists_event() {
+        let temp = tempdir().unwrap();
+        let store = Arc::new(EventStore::open(temp.path()).unwrap());
+        let session_key = SessionKey::new("telegram:123");
+        store
+            .append(&SessionEvent::new(
+                session_key.clone(),
+                "default".to_string(),
+                SessionEventKind::SessionStarted {
+                    channel: ChannelId::telegram().to_string(),
+                    peer_id: "123".to_string(),
+                },
+            ))
+            .unwrap();
+
+        let tool = RemindTool::new(store.clone());
+        let result = DelegationScope::root(session_key.clone())
+            .run(tool.execute(serde_json::json!({
+                "message": "Stretch",
+                "delay": "in 2 hours"
+            })))
+            .await
+            .unwrap();
+        assert!(result.success);
+
+        let pending = store.pending_reminders().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].session_key, session_key);
+        assert_eq!(pending[0].message, "Stretch");
+        assert!(store.due_reminders(Utc::now()).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_remind_rejects_past() {
+        let temp = tempdir().unwrap();
+        let tool = RemindTool::new(Arc::new(EventStore::open(temp.path()).unwrap()));
+
+        let result = tool
+            .execute(serde_json::json!({
+                "message": "Too late",
+                "at": "2000-01-01T00:00:00Z",
+                "session_key": "s"
+            }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+    }
+}
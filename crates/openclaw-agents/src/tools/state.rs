@@ -0,0 +1,179 @@
+//! Session state tool.
+
+use async_trait::async_trait;
+
+use crate::runtime::{SessionStateError, SessionStateStore};
+
+use super::{DelegationScope, Tool, ToolError, ToolResult};
+
+/// Tool that lets the agent remember structured facts for the current
+/// session (preferences, task lists) across turns.
+///
+/// Values are stored as `StateChanged` events and shown to the model in
+/// later turns' system prompt.
+pub struct SessionStateTool {
+    store: SessionStateStore,
+}
+
+impl SessionStateTool {
+    /// Create a session state tool.
+    #[must_use]
+    pub const fn new(store: SessionStateStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for SessionStateTool {
+    fn name(&self) -> &'static str {
+        "session_state"
+    }
+
+    fn description(&self) -> &'static str {
+        "Remember facts for this conversation: get, set, delete, or list keys"
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["get", "set", "delete", "list"],
+                    "description": "Operation to perform"
+                },
+                "key": {
+                    "type": "string",
+                    "description": "State key, e.g. \"preferences.units\" (not needed for list)"
+                },
+                "value": {
+                    "description": "JSON value to store (set only)"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value) -> Result<ToolResult, ToolError> {
+        let action = params["action"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParams("Missing 'action' parameter".to_string()))?;
+        let session_key = DelegationScope::current()
+            .map(|s| s.session_key)
+            .ok_or_else(|| ToolError::ExecutionFailed("No current session".to_string()))?;
+        let key = || {
+            params["key"]
+                .as_str()
+                .ok_or_else(|| ToolError::InvalidParams("Missing 'key' parameter".to_string()))
+        };
+
+        let result = match action {
+            "list" => self.store.all(&session_key).map(|state| {
+                let mut state: Vec<_> = state.into_iter().collect();
+                state.sort_by(|a, b| a.0.cmp(&b.0));
+                if state.is_empty() {
+                    "No state saved".to_string()
+                } else {
+                    state
+                        .iter()
+                        .map(|(k, v)| format!("{k}: {v}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }
+            }),
+            "get" => {
+                let key = key()?;
+                self.store.get(&session_key, key).map(|value| {
+                    value.map_or_else(|| format!("'{key}' is not set"), |v| v.to_string())
+                })
+            }
+            "set" => {
+                let key = key()?;
+                let value = params.get("value").cloned().ok_or_else(|| {
+                    ToolError::InvalidParams("Missing 'value' parameter".to_string())
+                })?;
+                self.store
+                    .set(&session_key, key, value)
+                    .map(|()| format!("Saved '{key}'"))
+            }
+            "delete" => {
+                let key = key()?;
+                self.store.delete(&session_key, key).map(|existed| {
+                    if existed {
+                        format!("Deleted '{key}'")
+                    } else {
+                        format!("'{key}' was not set")
+                    }
+                })
+            }
+            other => {
+                return Err(ToolError::InvalidParams(format!(
+                    "Unknown action '{other}'"
+                )));
+            }
+        };
+
+        match result {
+            Ok(content) => Ok(ToolResult::success(content)),
+            Err(SessionStateError::Store(e)) => Err(ToolError::ExecutionFailed(e.to_string())),
+            // Limit violations are for the model to correct
+            Err(e) => Ok(ToolResult::error(e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openclaw_core::events::{EventStore, SessionEvent, SessionEventKind};
+    use openclaw_core::types::{ChannelId, SessionKey};
+    use serde_json::json;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_session_state_tool() {
+        let temp = tempdir().unwrap();
+        let event_store = Arc::new(EventStore::open(temp.path()).unwrap());
+        let session_key = SessionKey::new("telegram:7");
+        event_store
+            .append(&SessionEvent::new(
+                session_key.clone(),
+                "default".to_string(),
+                SessionEventKind::SessionStarted {
+                    channel: ChannelId::telegram().to_string(),
+                    peer_id: "7".to_string(),
+                },
+            ))
+            .unwrap();
+        let tool = SessionStateTool::new(SessionStateStore::new(event_store.clone()));
+        let scope = DelegationScope::root(session_key.clone());
+
+        let result = scope
+            .clone()
+            .run(tool.execute(json!({ "action": "set", "key": "tasks", "value": ["a", "b"] })))
+            .await
+            .unwrap();
+        assert!(result.success);
+
+        let result = scope
+            .clone()
+            .run(tool.execute(json!({ "action": "get", "key": "tasks" })))
+            .await
+            .unwrap();
+        assert_eq!(result.content, r#"["a","b"]"#);
+
+        let result = scope
+            .clone()
+            .run(tool.execute(json!({ "action": "set", "key": "bad key", "value": 1 })))
+            .await
+            .unwrap();
+        assert!(!result.success);
+
+        let projection = event_store.get_projection(&session_key).unwrap();
+        assert_eq!(projection.custom_state["tasks"], json!(["a", "b"]));
+
+        // No session outside an agent run
+        assert!(tool.execute(json!({ "action": "list" })).await.is_err());
+    }
+}
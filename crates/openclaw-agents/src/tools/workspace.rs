@@ -0,0 +1,118 @@
+//! Running sandboxed tools in the agent's workspace.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use openclaw_core::config::WorkspaceConfig;
+
+use super::{DelegationScope, Tool, ToolError, ToolResult};
+use crate::sandbox::SandboxConfig;
+use crate::workspace::{Workspace, WorkspaceError};
+
+/// Tool wrapper that mounts the calling session's workspace into the
+/// sandbox and enforces its quota.
+pub struct WorkspaceTool {
+    inner: Arc<dyn Tool>,
+    base: PathBuf,
+    agent_id: String,
+    config: WorkspaceConfig,
+}
+
+impl WorkspaceTool {
+    /// Wrap `inner` so calls by `agent_id` run in a workspace under `base`.
+    #[must_use]
+    pub fn new(
+        inner: Arc<dyn Tool>,
+        base: impl Into<PathBuf>,
+        agent_id: impl Into<String>,
+        config: WorkspaceConfig,
+    ) -> Self {
+        Self {
+            inner,
+            base: base.into(),
+            agent_id: agent_id.into(),
+            config,
+        }
+    }
+
+    fn workspace(&self) -> Workspace {
+        let session_key = DelegationScope::current().map(|s| s.session_key.to_string());
+        Workspace::from_config(
+            &self.base,
+            &self.agent_id,
+            session_key.as_deref(),
+            &self.config,
+        )
+    }
+}
+
+fn quota_error(e: &WorkspaceError) -> ToolResult {
+    ToolResult::error(format!("{e}. Delete files in the workspace to continue."))
+}
+
+#[async_trait]
+impl Tool for WorkspaceTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        self.inner.input_schema()
+    }
+
+    async fn execute(&self, params: serde_json::Value) -> Result<ToolResult, ToolError> {
+        let workspace = self.workspace();
+        workspace
+            .create()
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+        if let Err(e @ WorkspaceError::QuotaExceeded { .. }) = workspace.check_quota() {
+            return Ok(quota_error(&e));
+        }
+
+        let tool = self
+            .inner
+            .sandbox()
+            .cloned()
+            .and_then(|config| self.inner.with_sandbox(config.with_workspace(&workspace)))
+            .unwrap_or_else(|| self.inner.clone());
+        let result = tool.execute(params).await?;
+
+        // The call already ran; report the overrun so the agent frees space
+        match workspace.check_quota() {
+            Err(e @ WorkspaceError::QuotaExceeded { .. }) => Ok(ToolResult {
+                content: result.content,
+                ..quota_error(&e)
+            }),
+            _ => Ok(result),
+        }
+    }
+
+    fn sandbox(&self) -> Option<&SandboxConfig> {
+        self.inner.sandbox()
+    }
+
+    fn with_sandbox(&self, config: SandboxConfig) -> Option<Arc<dyn Tool>> {
+        let inner = self.inner.with_sandbox(config)?;
+        Some(Arc::new(Self {
+            inner,
+            base: self.base.clone(),
+            agent_id: self.agent_id.clone(),
+            config: self.config.clone(),
+        }))
+    }
+
+    fn requires_approval(&self) -> bool {
+        self.inner.requires_approval()
+    }
+
+    fn execution_timeout(&self) -> Option<Duration> {
+        self.inner.execution_timeout()
+    }
+}
@@ -0,0 +1,158 @@
+//! Asking an agent from a workflow.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use openclaw_core::events::SessionProjection;
+use openclaw_core::types::{AgentId, ChannelId, SessionKey};
+use openclaw_providers::traits::ResponseFormat;
+
+use super::{NodeContext, NodeOutput, WorkflowError, WorkflowNode};
+use crate::runtime::{AgentContext, AgentRuntime};
+
+/// Key the reply is stored under by default.
+const DEFAULT_OUTPUT_KEY: &str = "reply";
+
+/// Node that sends its input to an agent and continues with the reply.
+///
+/// The message is the input's `message` field if it is a string, otherwise
+/// the whole input as JSON. Each run starts a fresh conversation. With an
+/// output schema the reply is checked against it and stored as data;
+/// otherwise it is stored as text.
+pub struct AgentNode {
+    id: String,
+    runtime: Arc<AgentRuntime>,
+    agent_id: AgentId,
+    output_schema: Option<ResponseFormat>,
+    output_key: String,
+}
+
+impl AgentNode {
+    /// Create a node that asks agent `agent_id` using `runtime`.
+    #[must_use]
+    pub fn new(id: impl Into<String>, runtime: Arc<AgentRuntime>, agent_id: AgentId) -> Self {
+        Self {
+            id: id.into(),
+            runtime,
+            agent_id,
+            output_schema: None,
+            output_key: DEFAULT_OUTPUT_KEY.to_string(),
+        }
+    }
+
+    /// Require a reply matching `format`'s schema.
+    #[must_use]
+    pub fn with_output_schema(mut self, format: ResponseFormat) -> Self {
+        self.output_schema = Some(format);
+        self
+    }
+
+    /// Store the reply under `key` instead of `reply`.
+    #[must_use]
+    pub fn with_output_key(mut self, key: impl Into<String>) -> Self {
+        self.output_key = key.into();
+        self
+    }
+
+    /// Message sent to the agent for `input`.
+    fn message(input: &Value) -> String {
+        input
+            .get("message")
+            .and_then(Value::as_str)
+            .map_or_else(|| input.to_string(), str::to_string)
+    }
+}
+
+#[async_trait]
+impl WorkflowNode for AgentNode {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn node_type(&self) -> &'static str {
+        "agent"
+    }
+
+    async fn execute(&self, ctx: NodeContext) -> Result<NodeOutput, WorkflowError> {
+        let session_key = SessionKey::new(format!("workflow:{}", self.id));
+        let session = SessionProjection::new(
+            session_key.clone(),
+            self.agent_id.as_ref().to_string(),
+            ChannelId::new("workflow"),
+            "workflow".to_string(),
+        );
+        let mut agent_ctx = AgentContext::new(
+            self.agent_id.clone(),
+            session_key,
+            session,
+            self.runtime.tools().clone(),
+        );
+
+        let message = Self::message(&ctx.input);
+        let reply = match &self.output_schema {
+            Some(format) => {
+                self.runtime
+                    .process_structured(&mut agent_ctx, &message, &[], format)
+                    .await
+            }
+            None => self.runtime.process(&mut agent_ctx, &message).await,
+        }
+        .map_err(|e| WorkflowError::ExecutionFailed(format!("Agent node {}: {e}", self.id)))?;
+        let result = reply.data.unwrap_or(Value::String(reply.content));
+
+        let data = match ctx.input {
+            Value::Object(mut fields) => {
+                fields.insert(self.output_key.clone(), result);
+                Value::Object(fields)
+            }
+            _ => serde_json::json!({ &self.output_key: result }),
+        };
+        Ok(NodeOutput::continue_with(data))
+    }
+
+    fn output_schema(&self) -> Option<&Value> {
+        self.output_schema.as_ref().map(|format| &format.schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::harness::{MockCompletion, MockProvider};
+    use crate::workflow::{Workflow, WorkflowEngine};
+
+    #[tokio::test]
+    async fn test_typed_reply() {
+        let provider = Arc::new(MockProvider::new([MockCompletion::Text(
+            r#"{"category": "billing"}"#.to_string(),
+        )]));
+        let runtime = Arc::new(AgentRuntime::new(provider.clone()));
+        let format = ResponseFormat::new(
+            "triage",
+            json!({
+                "type": "object",
+                "properties": { "category": { "enum": ["billing", "bug"] } },
+                "required": ["category"],
+            }),
+        );
+
+        let mut workflow = Workflow::new("triage", "Triage", "classify");
+        workflow.add_node(Arc::new(
+            AgentNode::new("classify", runtime, AgentId::default_agent())
+                .with_output_schema(format)
+                .with_output_key("triage"),
+        ));
+
+        let result = WorkflowEngine::new()
+            .execute(&workflow, json!({ "message": "I was charged twice" }))
+            .await
+            .unwrap();
+        assert_eq!(result["triage"]["category"], "billing");
+        assert_eq!(result["message"], "I was charged twice");
+        assert!(provider.requests()[0].response_format.is_some());
+    }
+}
@@ -0,0 +1,523 @@
+//! What workflow runs did, node by node, and where that is kept.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::suspended::run_path;
+use super::{ERROR_CONDITION, RunOutcome, Workflow, WorkflowError, WorkflowNode};
+
+/// Longest recorded input or output, in characters of JSON.
+const MAX_VALUE_CHARS: usize = 2000;
+
+/// Runs kept by a [`RunHistoryStore`] unless set otherwise.
+pub const DEFAULT_MAX_RUNS: usize = 1000;
+
+/// Where a recorded run is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    /// Still executing.
+    Running,
+    /// Suspended until a person answers.
+    Waiting,
+    /// Finished.
+    Completed,
+    /// Stopped by an error.
+    Failed,
+}
+
+/// How one node execution ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    /// The node produced output.
+    Completed,
+    /// The node failed after any retries.
+    Failed,
+    /// The node is waiting for input.
+    Waiting,
+}
+
+/// One node execution in a run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeStep {
+    /// Node ID.
+    pub node_id: String,
+    /// Node type.
+    pub node_type: String,
+    /// How the execution ended.
+    pub status: StepStatus,
+    /// When the node started; for a resumed node, when it began waiting.
+    pub started_at: DateTime<Utc>,
+    /// How long the node took.
+    pub duration_ms: u64,
+    /// Node input, truncated.
+    pub input: Value,
+    /// Node output, truncated.
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub output: Value,
+    /// Branch the node took.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Node that ran next, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
+    /// Why the node failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl NodeStep {
+    /// A completed step of `node` that started at `started_at` on `input`.
+    pub(super) fn begin(node: &dyn WorkflowNode, started_at: DateTime<Utc>, input: &Value) -> Self {
+        Self {
+            node_id: node.id().to_string(),
+            node_type: node.node_type().to_string(),
+            status: StepStatus::Completed,
+            started_at,
+            duration_ms: elapsed_ms(started_at, Utc::now()),
+            input: recorded(input),
+            output: Value::Null,
+            branch: None,
+            next: None,
+            error: None,
+        }
+    }
+}
+
+/// Record of one workflow run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Run ID, shared with the run's [`SuspendedRun`](super::SuspendedRun).
+    pub id: String,
+    /// Workflow run.
+    pub workflow_id: String,
+    /// Where the run is.
+    pub status: RunStatus,
+    /// When the run started.
+    pub started_at: DateTime<Utc>,
+    /// When the run completed or failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Node executions, in order.
+    #[serde(default)]
+    pub steps: Vec<NodeStep>,
+    /// Workflow output, truncated.
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub output: Value,
+    /// Why the run failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RunRecord {
+    /// A running run of `workflow_id` started at `now`.
+    #[must_use]
+    pub fn new(id: impl Into<String>, workflow_id: impl Into<String>, now: DateTime<Utc>) -> Self {
+        Self {
+            id: id.into(),
+            workflow_id: workflow_id.into(),
+            status: RunStatus::Running,
+            started_at: now,
+            finished_at: None,
+            steps: Vec::new(),
+            output: Value::Null,
+            error: None,
+        }
+    }
+
+    /// How long the run took, once it has finished.
+    #[must_use]
+    pub fn duration_ms(&self) -> Option<u64> {
+        self.finished_at
+            .map(|finished| elapsed_ms(self.started_at, finished))
+    }
+
+    /// Record how the run stopped.
+    pub(super) fn finish(
+        &mut self,
+        result: &Result<RunOutcome, WorkflowError>,
+        now: DateTime<Utc>,
+    ) {
+        match result {
+            Ok(RunOutcome::Completed(output)) => {
+                self.status = RunStatus::Completed;
+                self.output = recorded(output);
+                self.finished_at = Some(now);
+            }
+            Ok(RunOutcome::Suspended(_)) => self.status = RunStatus::Waiting,
+            Err(e) => {
+                self.status = RunStatus::Failed;
+                self.error = Some(e.to_string());
+                self.finished_at = Some(now);
+            }
+        }
+    }
+
+    /// Take back the step waiting at `node_id` so its resumption replaces
+    /// it, returning when it started.
+    pub(super) fn resume_step(&mut self, node_id: &str) -> Option<DateTime<Utc>> {
+        self.status = RunStatus::Running;
+        let waiting = self
+            .steps
+            .last()
+            .is_some_and(|step| step.status == StepStatus::Waiting && step.node_id == node_id);
+        if waiting {
+            self.steps.pop().map(|step| step.started_at)
+        } else {
+            None
+        }
+    }
+
+    /// The run laid over its workflow's graph, for drawing. Without the
+    /// workflow, the graph holds only the nodes and edges the run used.
+    #[must_use]
+    pub fn graph(&self, workflow: Option<&Workflow>) -> RunGraph {
+        let mut graph = RunGraph::default();
+        if let Some(workflow) = workflow {
+            for node in &workflow.nodes {
+                graph.node(node.id(), node.node_type());
+            }
+            graph.edges = workflow
+                .edges
+                .iter()
+                .map(|edge| GraphEdge {
+                    from: edge.from.clone(),
+                    to: edge.to.clone(),
+                    condition: edge.condition.clone(),
+                    traversed: 0,
+                })
+                .collect();
+        }
+
+        for step in &self.steps {
+            let node = graph.node(&step.node_id, &step.node_type);
+            node.visits += 1;
+            node.duration_ms = node.duration_ms.saturating_add(step.duration_ms);
+            node.status = Some(step.status);
+            if let Some(next) = &step.next {
+                let condition = if step.status == StepStatus::Failed {
+                    Some(ERROR_CONDITION.to_string())
+                } else {
+                    step.branch.clone()
+                };
+                graph.traverse(&step.node_id, next, condition);
+            }
+        }
+        graph
+    }
+}
+
+/// A workflow's nodes and edges annotated with what one run did.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunGraph {
+    /// Nodes, in workflow order.
+    pub nodes: Vec<GraphNode>,
+    /// Edges, declared ones first.
+    pub edges: Vec<GraphEdge>,
+}
+
+impl RunGraph {
+    fn node(&mut self, id: &str, node_type: &str) -> &mut GraphNode {
+        let index = self
+            .nodes
+            .iter()
+            .position(|node| node.id == id)
+            .unwrap_or_else(|| {
+                self.nodes.push(GraphNode {
+                    id: id.to_string(),
+                    node_type: node_type.to_string(),
+                    visits: 0,
+                    duration_ms: 0,
+                    status: None,
+                });
+                self.nodes.len() - 1
+            });
+        &mut self.nodes[index]
+    }
+
+    /// Count a hop from `from` to `to`, on the declared edge it followed
+    /// if there is one.
+    fn traverse(&mut self, from: &str, to: &str, condition: Option<String>) {
+        let between = |edge: &GraphEdge| edge.from == from && edge.to == to;
+        let declared = self
+            .edges
+            .iter()
+            .position(|edge| between(edge) && edge.condition == condition)
+            .or_else(|| self.edges.iter().position(between));
+        if let Some(index) = declared {
+            self.edges[index].traversed += 1;
+            return;
+        }
+        self.edges.push(GraphEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            condition,
+            traversed: 1,
+        });
+    }
+}
+
+/// A node in a [`RunGraph`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphNode {
+    /// Node ID.
+    pub id: String,
+    /// Node type.
+    #[serde(rename = "type")]
+    pub node_type: String,
+    /// How many times the run executed the node.
+    pub visits: usize,
+    /// Time spent in the node across visits.
+    pub duration_ms: u64,
+    /// How the node's last execution ended; `None` if it never ran.
+    pub status: Option<StepStatus>,
+}
+
+/// An edge in a [`RunGraph`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphEdge {
+    /// Source node ID.
+    pub from: String,
+    /// Target node ID.
+    pub to: String,
+    /// Branch, or [`ERROR_CONDITION`], the edge is taken on.
+    pub condition: Option<String>,
+    /// How many times the run took the edge.
+    pub traversed: usize,
+}
+
+/// Run records on disk, one JSON file per run.
+#[derive(Debug, Clone)]
+pub struct RunHistoryStore {
+    dir: PathBuf,
+    max_runs: usize,
+}
+
+impl RunHistoryStore {
+    /// Open a store rooted at `dir`, keeping the latest
+    /// [`DEFAULT_MAX_RUNS`] runs.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the directory cannot be created.
+    pub fn open(dir: &Path) -> Result<Self, WorkflowError> {
+        std::fs::create_dir_all(dir)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            max_runs: DEFAULT_MAX_RUNS,
+        })
+    }
+
+    /// Keep the latest `max_runs` runs.
+    #[must_use]
+    pub const fn with_max_runs(mut self, max_runs: usize) -> Self {
+        self.max_runs = max_runs;
+        self
+    }
+
+    /// Save a record, replacing any with its ID, and drop the oldest
+    /// records once there are too many.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be written.
+    pub fn save(&self, record: &RunRecord) -> Result<(), WorkflowError> {
+        let path = run_path(&self.dir, &record.id);
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(record)?)?;
+        std::fs::rename(tmp, path)?;
+        self.prune()
+    }
+
+    /// Load run `id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file exists but cannot be read.
+    pub fn load(&self, id: &str) -> Result<Option<RunRecord>, WorkflowError> {
+        let path = run_path(&self.dir, id);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Up to `limit` runs, newest first, of `workflow_id` or of every
+    /// workflow. Unreadable files are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the directory cannot be read.
+    pub fn list(
+        &self,
+        workflow_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<RunRecord>, WorkflowError> {
+        let mut runs = Vec::new();
+        for path in self.files()? {
+            let record = std::fs::read_to_string(&path)
+                .map_err(WorkflowError::from)
+                .and_then(|content| Ok(serde_json::from_str::<RunRecord>(&content)?));
+            match record {
+                Ok(record) if workflow_id.is_none_or(|id| id == record.workflow_id) => {
+                    runs.push(record);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Skipping workflow run {}: {}", path.display(), e),
+            }
+        }
+        runs.sort_by_key(|run| std::cmp::Reverse(run.started_at));
+        runs.truncate(limit);
+        Ok(runs)
+    }
+
+    fn files(&self) -> Result<Vec<PathBuf>, WorkflowError> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                files.push(path);
+            }
+        }
+        Ok(files)
+    }
+
+    /// Remove the least recently written records beyond `max_runs`. Waits
+    /// for a tenth more than that so most saves skip the sort.
+    fn prune(&self) -> Result<(), WorkflowError> {
+        let mut files = self.files()?;
+        if files.len() <= self.max_runs.saturating_add(self.max_runs / 10) {
+            return Ok(());
+        }
+        files.sort_by_cached_key(|path| {
+            std::fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .ok()
+        });
+        for path in &files[..files.len() - self.max_runs] {
+            if let Err(e) = std::fs::remove_file(path) {
+                tracing::warn!("Failed to remove workflow run {}: {}", path.display(), e);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `value` as recorded: unchanged if short, else its JSON cut short.
+pub(super) fn recorded(value: &Value) -> Value {
+    let json = value.to_string();
+    if json.chars().count() <= MAX_VALUE_CHARS {
+        return value.clone();
+    }
+    let mut truncated: String = json.chars().take(MAX_VALUE_CHARS).collect();
+    truncated.push_str("...[truncated]");
+    Value::String(truncated)
+}
+
+fn elapsed_ms(from: DateTime<Utc>, to: DateTime<Utc>) -> u64 {
+    u64::try_from((to - from).num_milliseconds()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::workflow::testing::StubNode;
+    use crate::workflow::{WaitForInputNode, WorkflowEngine};
+
+    #[tokio::test]
+    async fn test_records_steps_across_resume() {
+        let temp = tempfile::tempdir().unwrap();
+        let history = Arc::new(RunHistoryStore::open(temp.path()).unwrap());
+        let mut workflow = Workflow::new("review", "Review", "ask");
+        workflow.add_node(Arc::new(
+            WaitForInputNode::new("ask", "Ok?").with_choices(["yes"]),
+        ));
+        workflow.add_node(Arc::new(StubNode::new("done")));
+        workflow.add_node(Arc::new(StubNode::new("unused")));
+        workflow.add_conditional_edge("ask", "done", "yes");
+        workflow.add_edge("ask", "unused");
+        let engine = WorkflowEngine::new().with_history(history.clone());
+
+        let RunOutcome::Suspended(run) = engine
+            .start(&workflow, json!({ "blob": "x".repeat(3000) }))
+            .await
+            .unwrap()
+        else {
+            panic!("workflow did not wait for input");
+        };
+        let record = history.load(&run.id).unwrap().unwrap();
+        assert_eq!(record.status, RunStatus::Waiting);
+        assert_eq!(record.steps[0].status, StepStatus::Waiting);
+        assert!(
+            record.steps[0]
+                .input
+                .as_str()
+                .unwrap()
+                .ends_with("[truncated]")
+        );
+
+        engine
+            .resume(&workflow, &run, Some(json!("yes")))
+            .await
+            .unwrap();
+        let record = history.load(&run.id).unwrap().unwrap();
+        assert_eq!(record.status, RunStatus::Completed);
+        assert!(record.duration_ms().is_some());
+        let steps: Vec<_> = record
+            .steps
+            .iter()
+            .map(|s| (s.node_id.as_str(), s.status))
+            .collect();
+        assert_eq!(
+            steps,
+            [
+                ("ask", StepStatus::Completed),
+                ("done", StepStatus::Completed)
+            ]
+        );
+
+        let graph = record.graph(Some(&workflow));
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.nodes[2].status, None);
+        let taken: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|e| (e.to.as_str(), e.traversed))
+            .collect();
+        assert_eq!(taken, [("done", 1), ("unused", 0)]);
+    }
+
+    #[test]
+    fn test_list_filters_and_prunes() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = RunHistoryStore::open(temp.path()).unwrap().with_max_runs(3);
+        let now = Utc::now();
+        for (i, workflow) in ["a", "b", "a"].iter().enumerate() {
+            let started = now + chrono::Duration::seconds(i64::try_from(i).unwrap());
+            store
+                .save(&RunRecord::new(format!("run-{i}"), *workflow, started))
+                .unwrap();
+        }
+
+        let runs = store.list(Some("a"), 10).unwrap();
+        let ids: Vec<_> = runs.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["run-2", "run-0"]);
+        assert_eq!(store.list(None, 1).unwrap()[0].id, "run-2");
+
+        for i in 3..6 {
+            store
+                .save(&RunRecord::new(format!("run-{i}"), "a", now))
+                .unwrap();
+        }
+        assert!(store.list(None, 10).unwrap().len() <= 3);
+    }
+}
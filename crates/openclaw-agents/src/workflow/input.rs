@@ -0,0 +1,219 @@
+//! Waiting for a person to answer.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{NodeContext, NodeOutput, WorkflowError, WorkflowNode};
+
+/// Branch a [`WaitForInputNode`] takes when nobody answers in time.
+pub const TIMEOUT_BRANCH: &str = "timeout";
+
+/// What a suspended workflow is waiting for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputRequest {
+    /// Question shown to the person.
+    pub prompt: String,
+    /// Suggested answers, e.g. `approve` and `reject`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<String>,
+    /// How long to wait before timing out; forever if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+impl InputRequest {
+    /// When a wait starting at `from` times out.
+    #[must_use]
+    pub fn deadline(&self, from: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let timeout =
+            chrono::Duration::milliseconds(i64::try_from(self.timeout_ms?).unwrap_or(i64::MAX));
+        Some(
+            from.checked_add_signed(timeout)
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+        )
+    }
+}
+
+/// Node that suspends the workflow until a person replies.
+///
+/// The reply is stored in the node's input under `reply` (see
+/// [`WaitForInputNode::with_output_key`]). A reply matching one of the
+/// choices takes the branch of that name; any other reply continues. If
+/// the wait times out, the node takes [`TIMEOUT_BRANCH`] with its input
+/// unchanged.
+pub struct WaitForInputNode {
+    id: String,
+    request: InputRequest,
+    output_key: String,
+}
+
+impl WaitForInputNode {
+    /// Create a node that asks `prompt`.
+    #[must_use]
+    pub fn new(id: impl Into<String>, prompt: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            request: InputRequest {
+                prompt: prompt.into(),
+                choices: Vec::new(),
+                timeout_ms: None,
+            },
+            output_key: "reply".to_string(),
+        }
+    }
+
+    /// Offer `choices` as answers, each routing to the branch of its name.
+    #[must_use]
+    pub fn with_choices(mut self, choices: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.request.choices = choices.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Stop waiting after `timeout`.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.request.timeout_ms = Some(u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX));
+        self
+    }
+
+    /// Store the reply under `key` instead of `reply`.
+    #[must_use]
+    pub fn with_output_key(mut self, key: impl Into<String>) -> Self {
+        self.output_key = key.into();
+        self
+    }
+
+    /// Choice that `reply` picks, ignoring case and surrounding space.
+    fn choice(&self, reply: &Value) -> Option<&str> {
+        let text = reply.as_str()?.trim();
+        self.request
+            .choices
+            .iter()
+            .find(|choice| choice.eq_ignore_ascii_case(text))
+            .map(String::as_str)
+    }
+}
+
+#[async_trait]
+impl WorkflowNode for WaitForInputNode {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn node_type(&self) -> &'static str {
+        "wait_for_input"
+    }
+
+    async fn execute(&self, ctx: NodeContext) -> Result<NodeOutput, WorkflowError> {
+        Ok(NodeOutput::wait_for_input(ctx.input, self.request.clone()))
+    }
+
+    async fn resume(
+        &self,
+        ctx: NodeContext,
+        reply: Option<Value>,
+    ) -> Result<NodeOutput, WorkflowError> {
+        let Some(reply) = reply else {
+            return Ok(NodeOutput::branch(ctx.input, TIMEOUT_BRANCH));
+        };
+        let branch = self.choice(&reply).map(String::from);
+        let data = match ctx.input {
+            Value::Object(mut fields) => {
+                fields.insert(self.output_key.clone(), reply);
+                Value::Object(fields)
+            }
+            _ => serde_json::json!({ self.output_key.clone(): reply }),
+        };
+        let mut output = NodeOutput::continue_with(data);
+        output.branch = branch;
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::workflow::testing::StubNode;
+    use crate::workflow::{RunOutcome, SuspendedRunStore, Workflow, WorkflowEngine};
+
+    fn approval_workflow() -> (Workflow, Arc<StubNode>, Arc<StubNode>) {
+        let approved = Arc::new(StubNode::new("approved"));
+        let expired = Arc::new(StubNode::new("expired"));
+        let mut workflow = Workflow::new("approval", "Approval", "ask");
+        workflow.add_node(Arc::new(
+            WaitForInputNode::new("ask", "Ship it?")
+                .with_choices(["approve", "reject"])
+                .with_timeout(Duration::from_secs(60)),
+        ));
+        workflow.add_node(approved.clone());
+        workflow.add_node(expired.clone());
+        workflow.add_conditional_edge("ask", "approved", "approve");
+        workflow.add_conditional_edge("ask", "expired", TIMEOUT_BRANCH);
+        (workflow, approved, expired)
+    }
+
+    #[tokio::test]
+    async fn test_suspend_persist_and_resume() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = SuspendedRunStore::open(temp.path()).unwrap();
+        let (workflow, approved, _) = approval_workflow();
+        let engine = WorkflowEngine::new();
+
+        let RunOutcome::Suspended(run) = engine
+            .start(&workflow, json!({ "build": 7 }))
+            .await
+            .unwrap()
+        else {
+            panic!("workflow did not wait for input");
+        };
+        assert_eq!(run.node_id, "ask");
+        assert_eq!(run.request.prompt, "Ship it?");
+        assert!(run.expires_at.is_some());
+        store.save(&run).unwrap();
+
+        let run = store.load(&run.id).unwrap().unwrap();
+        let outcome = engine
+            .resume(&workflow, &run, Some(json!(" Approve ")))
+            .await
+            .unwrap();
+        assert!(matches!(outcome, RunOutcome::Completed(ref data) if data["build"] == 7));
+        assert_eq!(approved.calls()[0].input["reply"], " Approve ");
+
+        assert!(store.remove(&run.id).unwrap());
+        assert!(store.list().unwrap().is_empty());
+        assert!(matches!(
+            engine.execute(&workflow, json!({})).await,
+            Err(WorkflowError::Suspended(node)) if node == "ask"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_and_free_text() {
+        let (workflow, approved, expired) = approval_workflow();
+        let engine = WorkflowEngine::new();
+        let RunOutcome::Suspended(run) = engine.start(&workflow, json!({})).await.unwrap() else {
+            panic!("workflow did not wait for input");
+        };
+
+        engine.resume(&workflow, &run, None).await.unwrap();
+        assert_eq!(expired.call_count(), 1);
+
+        // Free text has no unconditional edge to follow, so the run ends
+        let outcome = engine
+            .resume(&workflow, &run, Some(json!("maybe later")))
+            .await
+            .unwrap();
+        assert!(
+            matches!(outcome, RunOutcome::Completed(ref data) if data["reply"] == "maybe later")
+        );
+        assert_eq!(approved.call_count(), 0);
+    }
+}
@@ -2,13 +2,41 @@
 //!
 //! Execute agent logic as a graph of workflow nodes.
 
+mod agent;
+mod history;
+mod input;
+mod registry;
+mod subworkflow;
+mod suspended;
+pub mod testing;
+mod trigger;
+
+pub use agent::AgentNode;
+pub use history::{
+    DEFAULT_MAX_RUNS, GraphEdge, GraphNode, NodeStep, RunGraph, RunHistoryStore, RunRecord,
+    RunStatus, StepStatus,
+};
+pub use input::{InputRequest, TIMEOUT_BRANCH, WaitForInputNode};
+pub use registry::WorkflowRegistry;
+pub use subworkflow::{DEFAULT_MAX_DEPTH, SubWorkflowNode};
+pub use suspended::{InputOrigin, SuspendedRun, SuspendedRunStore};
+pub use trigger::WorkflowTrigger;
+
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+/// Node ID that ends the workflow when routed to.
+pub const END_NODE: &str = "__end__";
+
+/// Edge condition followed when the source node fails.
+pub const ERROR_CONDITION: &str = "__error__";
+
 /// Workflow execution errors.
 #[derive(Error, Debug)]
 pub enum WorkflowError {
@@ -31,9 +59,27 @@ pub enum WorkflowError {
     /// Cycle detected in workflow.
     #[error("Cycle detected at node: {0}")]
     CycleDetected(String),
+
+    /// No registered workflow with this ID.
+    #[error("Workflow not found: {0}")]
+    WorkflowNotFound(String),
+
+    /// A node is waiting for input, which only [`WorkflowEngine::start`]
+    /// can suspend for.
+    #[error("Workflow is waiting for input at node: {0}")]
+    Suspended(String),
+
+    /// Suspended run persistence error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Suspended run (de)serialization error.
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
 }
 
 /// Node execution context.
+#[derive(Debug, Clone)]
 pub struct NodeContext {
     /// Input data.
     pub input: serde_json::Value,
@@ -41,9 +87,14 @@ pub struct NodeContext {
     pub config: serde_json::Value,
     /// Shared workflow state.
     pub state: HashMap<String, serde_json::Value>,
+    /// Workflows available to sub-workflow nodes.
+    pub registry: Option<Arc<WorkflowRegistry>>,
+    /// Sub-workflow nesting depth; 0 for a top-level run.
+    pub depth: usize,
 }
 
 /// Result of node execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NodeOutput {
     /// Output data.
     pub data: serde_json::Value,
@@ -51,6 +102,8 @@ pub struct NodeOutput {
     pub next: Option<String>,
     /// Branch name (conditional routing).
     pub branch: Option<String>,
+    /// Input to wait for before the workflow continues.
+    pub suspend: Option<InputRequest>,
 }
 
 impl NodeOutput {
@@ -61,6 +114,7 @@ impl NodeOutput {
             data,
             next: None,
             branch: None,
+            suspend: None,
         }
     }
 
@@ -71,6 +125,7 @@ impl NodeOutput {
             data,
             next: Some(node_id.into()),
             branch: None,
+            suspend: None,
         }
     }
 
@@ -81,6 +136,7 @@ impl NodeOutput {
             data,
             next: None,
             branch: Some(branch_name.into()),
+            suspend: None,
         }
     }
 
@@ -89,8 +145,22 @@ impl NodeOutput {
     pub fn end(data: serde_json::Value) -> Self {
         Self {
             data,
-            next: Some("__end__".to_string()),
+            next: Some(END_NODE.to_string()),
             branch: None,
+            suspend: None,
+        }
+    }
+
+    /// Create output that suspends the workflow until `request` is
+    /// answered; the node's [`WorkflowNode::resume`] then decides where
+    /// it goes.
+    #[must_use]
+    pub const fn wait_for_input(data: serde_json::Value, request: InputRequest) -> Self {
+        Self {
+            data,
+            next: None,
+            branch: None,
+            suspend: Some(request),
         }
     }
 }
@@ -107,6 +177,19 @@ pub trait WorkflowNode: Send + Sync {
     /// Execute the node.
     async fn execute(&self, ctx: NodeContext) -> Result<NodeOutput, WorkflowError>;
 
+    /// Continue after this node waited for input, with the reply or `None`
+    /// if the wait timed out. `ctx` is the context the node suspended with.
+    async fn resume(
+        &self,
+        _ctx: NodeContext,
+        _reply: Option<serde_json::Value>,
+    ) -> Result<NodeOutput, WorkflowError> {
+        Err(WorkflowError::ExecutionFailed(format!(
+            "Node {} cannot resume",
+            self.id()
+        )))
+    }
+
     /// Input schema (optional).
     fn input_schema(&self) -> Option<&serde_json::Value> {
         None
@@ -129,6 +212,92 @@ pub struct WorkflowEdge {
     pub condition: Option<String>,
 }
 
+/// How often a failing node is retried.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeRetryPolicy {
+    /// Attempts in total, including the first.
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Wait before the first retry; each later wait doubles.
+    #[serde(default)]
+    pub backoff_ms: u64,
+    /// Upper bound on a single wait.
+    #[serde(default)]
+    pub max_backoff_ms: Option<u64>,
+}
+
+const fn default_max_attempts() -> u32 {
+    1
+}
+
+impl NodeRetryPolicy {
+    /// Policy allowing `max_attempts` attempts with no wait between them.
+    #[must_use]
+    pub const fn attempts(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            backoff_ms: 0,
+            max_backoff_ms: None,
+        }
+    }
+
+    /// Set the wait before the first retry.
+    #[must_use]
+    pub fn with_backoff(mut self, backoff: Duration, max_backoff: Option<Duration>) -> Self {
+        self.backoff_ms = duration_ms(backoff);
+        self.max_backoff_ms = max_backoff.map(duration_ms);
+        self
+    }
+
+    /// Wait before retry number `retry` (0-based).
+    #[must_use]
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let ms = self
+            .backoff_ms
+            .saturating_mul(2u64.saturating_pow(retry))
+            .min(self.max_backoff_ms.unwrap_or(u64::MAX));
+        Duration::from_millis(ms)
+    }
+}
+
+impl Default for NodeRetryPolicy {
+    fn default() -> Self {
+        Self::attempts(default_max_attempts())
+    }
+}
+
+fn duration_ms(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Serializable form of a workflow: its routing and error handling.
+///
+/// Nodes are code, so they are supplied separately when the workflow is
+/// built with [`Workflow::from_definition`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowDefinition {
+    /// Workflow ID.
+    pub id: String,
+    /// Workflow name.
+    pub name: String,
+    /// Starting node ID.
+    pub start_node: String,
+    /// Edges connecting nodes.
+    #[serde(default)]
+    pub edges: Vec<WorkflowEdge>,
+    /// Retry policies by node ID.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub retry: HashMap<String, NodeRetryPolicy>,
+    /// Node run when a node fails and has no error edge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_handler: Option<String>,
+    /// What starts the workflow.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub triggers: Vec<WorkflowTrigger>,
+}
+
 /// Workflow definition.
 pub struct Workflow {
     /// Workflow ID.
@@ -141,6 +310,12 @@ pub struct Workflow {
     pub edges: Vec<WorkflowEdge>,
     /// Starting node ID.
     pub start_node: String,
+    /// Retry policies by node ID; nodes without one run once.
+    pub retry: HashMap<String, NodeRetryPolicy>,
+    /// Node run when a node fails and has no error edge.
+    pub failure_handler: Option<String>,
+    /// What starts the workflow, besides running it directly.
+    pub triggers: Vec<WorkflowTrigger>,
 }
 
 impl Workflow {
@@ -157,6 +332,62 @@ impl Workflow {
             nodes: Vec::new(),
             edges: Vec::new(),
             start_node: start_node.into(),
+            retry: HashMap::new(),
+            failure_handler: None,
+            triggers: Vec::new(),
+        }
+    }
+
+    /// Build a workflow from its definition and nodes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidWorkflow` if the definition names a node that is
+    /// not among `nodes`, or has a trigger that cannot fire.
+    pub fn from_definition(
+        definition: WorkflowDefinition,
+        nodes: impl IntoIterator<Item = Arc<dyn WorkflowNode>>,
+    ) -> Result<Self, WorkflowError> {
+        let workflow = Self {
+            id: definition.id,
+            name: definition.name,
+            nodes: nodes.into_iter().collect(),
+            edges: definition.edges,
+            start_node: definition.start_node,
+            retry: definition.retry,
+            failure_handler: definition.failure_handler,
+            triggers: definition.triggers,
+        };
+
+        let referenced = std::iter::once(&workflow.start_node)
+            .chain(workflow.edges.iter().flat_map(|e| [&e.from, &e.to]))
+            .chain(workflow.retry.keys())
+            .chain(workflow.failure_handler.iter());
+        for id in referenced {
+            if id != END_NODE && workflow.find_node(id).is_none() {
+                return Err(WorkflowError::InvalidWorkflow(format!(
+                    "{} refers to unknown node {id}",
+                    workflow.id
+                )));
+            }
+        }
+        for trigger in &workflow.triggers {
+            trigger.validate()?;
+        }
+        Ok(workflow)
+    }
+
+    /// The workflow's definition, for saving.
+    #[must_use]
+    pub fn definition(&self) -> WorkflowDefinition {
+        WorkflowDefinition {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            start_node: self.start_node.clone(),
+            edges: self.edges.clone(),
+            retry: self.retry.clone(),
+            failure_handler: self.failure_handler.clone(),
+            triggers: self.triggers.clone(),
         }
     }
 
@@ -188,6 +419,42 @@ impl Workflow {
         });
     }
 
+    /// Add an edge followed when `from` fails. The target receives
+    /// `{"error", "node", "input"}` describing the failure.
+    pub fn add_error_edge(&mut self, from: impl Into<String>, to: impl Into<String>) {
+        self.add_conditional_edge(from, to, ERROR_CONDITION);
+    }
+
+    /// Retry `node_id` according to `policy` when it fails.
+    pub fn set_retry(&mut self, node_id: impl Into<String>, policy: NodeRetryPolicy) {
+        self.retry.insert(node_id.into(), policy);
+    }
+
+    /// Run `node_id` when a node fails and has no error edge.
+    pub fn set_failure_handler(&mut self, node_id: impl Into<String>) {
+        self.failure_handler = Some(node_id.into());
+    }
+
+    /// Node that handles a failure of `node_id`: its error edge, or the
+    /// failure handler unless the handler itself failed.
+    #[must_use]
+    pub fn error_target(&self, node_id: &str) -> Option<String> {
+        self.outgoing_edges(node_id)
+            .iter()
+            .find(|e| e.condition.as_deref() == Some(ERROR_CONDITION))
+            .map(|e| e.to.clone())
+            .or_else(|| {
+                self.failure_handler
+                    .clone()
+                    .filter(|handler| handler != node_id)
+            })
+    }
+
+    /// Start the workflow on `trigger`.
+    pub fn add_trigger(&mut self, trigger: WorkflowTrigger) {
+        self.triggers.push(trigger);
+    }
+
     /// Find node by ID.
     #[must_use]
     pub fn find_node(&self, id: &str) -> Option<&Arc<dyn WorkflowNode>> {
@@ -199,11 +466,50 @@ impl Workflow {
     pub fn outgoing_edges(&self, node_id: &str) -> Vec<&WorkflowEdge> {
         self.edges.iter().filter(|e| e.from == node_id).collect()
     }
+
+    /// Node that runs after `node_id` produced `output`, or `None` if the
+    /// workflow ends there.
+    ///
+    /// An explicit next node wins; otherwise a branch takes the edge with
+    /// that condition, and plain output takes the first unconditional edge.
+    #[must_use]
+    pub fn next_node(&self, node_id: &str, output: &NodeOutput) -> Option<String> {
+        if let Some(next) = &output.next {
+            return (next != END_NODE).then(|| next.clone());
+        }
+        let edges = self.outgoing_edges(node_id);
+        edges
+            .iter()
+            .find(|e| e.condition.as_ref() == output.branch.as_ref())
+            .map(|e| e.to.clone())
+    }
+}
+
+/// How a workflow run stopped.
+#[derive(Debug, Clone)]
+pub enum RunOutcome {
+    /// The workflow finished with this output.
+    Completed(serde_json::Value),
+    /// A node is waiting for input; resume with [`WorkflowEngine::resume`].
+    Suspended(Box<SuspendedRun>),
+}
+
+/// Where a run is in its workflow.
+struct Cursor {
+    node_id: String,
+    data: serde_json::Value,
+    state: HashMap<String, serde_json::Value>,
+    depth: usize,
 }
 
+/// Result of a node that already ran, and when it started.
+type Pending = (Result<NodeOutput, WorkflowError>, DateTime<Utc>);
+
 /// Workflow execution engine.
 pub struct WorkflowEngine {
     max_iterations: usize,
+    registry: Option<Arc<WorkflowRegistry>>,
+    history: Option<Arc<RunHistoryStore>>,
 }
 
 impl WorkflowEngine {
@@ -212,9 +518,31 @@ impl WorkflowEngine {
     pub const fn new() -> Self {
         Self {
             max_iterations: 1000,
+            registry: None,
+            history: None,
         }
     }
 
+    /// Resolve sub-workflows and [`Self::execute_by_id`] from `registry`.
+    #[must_use]
+    pub fn with_registry(mut self, registry: Arc<WorkflowRegistry>) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Record each run in `history`.
+    #[must_use]
+    pub fn with_history(mut self, history: Arc<RunHistoryStore>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Registered workflows, if any.
+    #[must_use]
+    pub const fn registry(&self) -> Option<&Arc<WorkflowRegistry>> {
+        self.registry.as_ref()
+    }
+
     /// Set maximum iterations (cycle protection).
     #[must_use]
     pub const fn with_max_iterations(mut self, max: usize) -> Self {
@@ -232,60 +560,275 @@ impl WorkflowEngine {
         workflow: &Workflow,
         input: serde_json::Value,
     ) -> Result<serde_json::Value, WorkflowError> {
-        let mut current_node_id = workflow.start_node.clone();
-        let mut data = input;
-        let state = HashMap::new();
+        self.execute_at_depth(workflow, input, 0).await
+    }
+
+    /// Execute the registered workflow `id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WorkflowNotFound` if no such workflow is registered, or
+    /// the error of the run.
+    pub async fn execute_by_id(
+        &self,
+        id: &str,
+        input: serde_json::Value,
+    ) -> Result<serde_json::Value, WorkflowError> {
+        let workflow = self
+            .registry
+            .as_ref()
+            .and_then(|registry| registry.get(id))
+            .cloned()
+            .ok_or_else(|| WorkflowError::WorkflowNotFound(id.to_string()))?;
+        self.execute(&workflow, input).await
+    }
+
+    /// Execute a workflow nested `depth` sub-workflows deep.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if execution fails or cycle detected, and
+    /// `Suspended` if a node waits for input.
+    pub async fn execute_at_depth(
+        &self,
+        workflow: &Workflow,
+        input: serde_json::Value,
+        depth: usize,
+    ) -> Result<serde_json::Value, WorkflowError> {
+        let cursor = Cursor {
+            node_id: workflow.start_node.clone(),
+            data: input,
+            state: HashMap::new(),
+            depth,
+        };
+        let now = Utc::now();
+        let mut record = RunRecord::new(SuspendedRun::new_id(now), workflow.id.clone(), now);
+        let result = match self.drive(workflow, cursor, &mut record, None).await {
+            Ok(RunOutcome::Suspended(run)) => Err(WorkflowError::Suspended(run.node_id)),
+            result => result,
+        };
+        self.save_record(record, &result);
+        match result? {
+            RunOutcome::Completed(output) => Ok(output),
+            RunOutcome::Suspended(run) => Err(WorkflowError::Suspended(run.node_id)),
+        }
+    }
+
+    /// Start a workflow that may wait for input.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if execution fails or cycle detected.
+    pub async fn start(
+        &self,
+        workflow: &Workflow,
+        input: serde_json::Value,
+    ) -> Result<RunOutcome, WorkflowError> {
+        let cursor = Cursor {
+            node_id: workflow.start_node.clone(),
+            data: input,
+            state: HashMap::new(),
+            depth: 0,
+        };
+        let now = Utc::now();
+        let mut record = RunRecord::new(SuspendedRun::new_id(now), workflow.id.clone(), now);
+        let result = self.drive(workflow, cursor, &mut record, None).await;
+        self.save_record(record, &result);
+        result
+    }
+
+    /// Continue a suspended run with `reply`, or `None` if its wait timed
+    /// out. The run may suspend again, keeping its ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidWorkflow` if the run belongs to another workflow,
+    /// or the error of the run.
+    pub async fn resume(
+        &self,
+        workflow: &Workflow,
+        run: &SuspendedRun,
+        reply: Option<serde_json::Value>,
+    ) -> Result<RunOutcome, WorkflowError> {
+        if run.workflow_id != workflow.id {
+            return Err(WorkflowError::InvalidWorkflow(format!(
+                "Run {} belongs to workflow {}, not {}",
+                run.id, run.workflow_id, workflow.id
+            )));
+        }
+        let node = workflow
+            .find_node(&run.node_id)
+            .ok_or_else(|| WorkflowError::NodeNotFound(run.node_id.clone()))?;
+        let cursor = Cursor {
+            node_id: run.node_id.clone(),
+            data: run.input.clone(),
+            state: run.state.clone(),
+            depth: 0,
+        };
+        let mut record = self.load_record(run);
+        let started_at = record.resume_step(&run.node_id).unwrap_or(run.suspended_at);
+        let resumed = node.resume(self.context(&cursor), reply).await;
+        let result = self
+            .drive(workflow, cursor, &mut record, Some((resumed, started_at)))
+            .await;
+        self.save_record(record, &result);
+        result
+    }
+
+    /// The record of suspended `run`, or a fresh one if it was not kept.
+    fn load_record(&self, run: &SuspendedRun) -> RunRecord {
+        let loaded = self.history.as_ref().and_then(|history| {
+            history
+                .load(&run.id)
+                .inspect_err(|e| tracing::warn!("Failed to load workflow run {}: {}", run.id, e))
+                .ok()
+                .flatten()
+        });
+        loaded.unwrap_or_else(|| {
+            RunRecord::new(run.id.clone(), run.workflow_id.clone(), run.suspended_at)
+        })
+    }
+
+    /// Save `record` to the history, if there is one, with `result`.
+    fn save_record(&self, mut record: RunRecord, result: &Result<RunOutcome, WorkflowError>) {
+        let Some(history) = &self.history else {
+            return;
+        };
+        record.finish(result, Utc::now());
+        if let Err(e) = history.save(&record) {
+            tracing::warn!("Failed to save workflow run {}: {}", record.id, e);
+        }
+    }
+
+    fn context(&self, cursor: &Cursor) -> NodeContext {
+        NodeContext {
+            input: cursor.data.clone(),
+            config: serde_json::Value::Object(serde_json::Map::new()),
+            state: cursor.state.clone(),
+            registry: self.registry.clone(),
+            depth: cursor.depth,
+        }
+    }
+
+    /// Run from `cursor` until the workflow ends or a node waits for input,
+    /// adding each node's step to `record`. `pending` is the result of the
+    /// node at the cursor, if it already ran.
+    async fn drive(
+        &self,
+        workflow: &Workflow,
+        mut cursor: Cursor,
+        record: &mut RunRecord,
+        mut pending: Option<Pending>,
+    ) -> Result<RunOutcome, WorkflowError> {
         let mut iterations = 0;
 
         loop {
             iterations += 1;
             if iterations > self.max_iterations {
-                return Err(WorkflowError::CycleDetected(current_node_id));
+                return Err(WorkflowError::CycleDetected(cursor.node_id));
             }
 
             // Find and execute current node
             let node = workflow
-                .find_node(&current_node_id)
-                .ok_or_else(|| WorkflowError::NodeNotFound(current_node_id.clone()))?;
-
-            let ctx = NodeContext {
-                input: data.clone(),
-                config: serde_json::Value::Object(serde_json::Map::new()),
-                state: state.clone(),
+                .find_node(&cursor.node_id)
+                .ok_or_else(|| WorkflowError::NodeNotFound(cursor.node_id.clone()))?;
+            let (result, started_at) = if let Some(pending) = pending.take() {
+                pending
+            } else {
+                let started_at = Utc::now();
+                let policy = workflow.retry.get(&cursor.node_id);
+                let result = execute_with_retry(node.as_ref(), self.context(&cursor), policy).await;
+                (result, started_at)
             };
-
-            let output = node.execute(ctx).await?;
-            data = output.data;
-
-            // Determine next node
-            let next_node = if let Some(explicit_next) = output.next {
-                if explicit_next == "__end__" {
-                    break;
+            let mut step = NodeStep::begin(node.as_ref(), started_at, &cursor.data);
+            let mut output = match result {
+                Ok(output) => output,
+                Err(e) => {
+                    let target = workflow.error_target(&cursor.node_id);
+                    step.status = StepStatus::Failed;
+                    step.error = Some(e.to_string());
+                    step.next.clone_from(&target);
+                    record.steps.push(step);
+                    let Some(target) = target else {
+                        return Err(e);
+                    };
+                    tracing::warn!(
+                        "Workflow {} node {} failed, routing to {}: {}",
+                        workflow.id,
+                        cursor.node_id,
+                        target,
+                        e
+                    );
+                    cursor.data = serde_json::json!({
+                        "error": e.to_string(),
+                        "node": cursor.node_id,
+                        "input": cursor.data,
+                    });
+                    cursor.node_id = target;
+                    continue;
                 }
-                Some(explicit_next)
-            } else if let Some(branch) = output.branch {
-                // Find edge matching the branch
-                workflow
-                    .outgoing_edges(&current_node_id)
-                    .iter()
-                    .find(|e| e.condition.as_ref() == Some(&branch))
-                    .map(|e| e.to.clone())
-            } else {
-                // Take first unconditional edge
-                workflow
-                    .outgoing_edges(&current_node_id)
-                    .iter()
-                    .find(|e| e.condition.is_none())
-                    .map(|e| e.to.clone())
             };
 
+            if let Some(request) = output.suspend.take() {
+                step.status = StepStatus::Waiting;
+                record.steps.push(step);
+                let now = Utc::now();
+                return Ok(RunOutcome::Suspended(Box::new(SuspendedRun {
+                    id: record.id.clone(),
+                    workflow_id: workflow.id.clone(),
+                    node_id: cursor.node_id,
+                    input: cursor.data,
+                    state: cursor.state,
+                    expires_at: request.deadline(now),
+                    request,
+                    origin: None,
+                    suspended_at: now,
+                })));
+            }
+
+            let next_node = workflow.next_node(&cursor.node_id, &output);
+            step.output = history::recorded(&output.data);
+            step.branch = output.branch;
+            step.next.clone_from(&next_node);
+            record.steps.push(step);
+            cursor.data = output.data;
+
             match next_node {
-                Some(next) => current_node_id = next,
+                Some(next) => cursor.node_id = next,
                 None => break, // No more nodes
             }
         }
 
-        Ok(data)
+        Ok(RunOutcome::Completed(cursor.data))
+    }
+}
+
+/// Execute a node, retrying failures as `policy` allows.
+async fn execute_with_retry(
+    node: &dyn WorkflowNode,
+    ctx: NodeContext,
+    policy: Option<&NodeRetryPolicy>,
+) -> Result<NodeOutput, WorkflowError> {
+    let max_attempts = policy.map_or(1, |p| p.max_attempts.max(1));
+    let mut attempt = 1;
+    loop {
+        match node.execute(ctx.clone()).await {
+            Ok(output) => return Ok(output),
+            Err(e) if attempt < max_attempts => {
+                let wait = policy.map_or(Duration::ZERO, |p| p.backoff(attempt - 1));
+                tracing::debug!(
+                    "Node {} failed (attempt {}/{}), retrying in {:?}: {}",
+                    node.id(),
+                    attempt,
+                    max_attempts,
+                    wait,
+                    e
+                );
+                tokio::time::sleep(wait).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
     }
 }
 
@@ -325,7 +868,44 @@ impl WorkflowNode for PassthroughNode {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
     use super::*;
+    use crate::workflow::testing::StubNode;
+
+    /// Fails until it has been called `failures` times.
+    struct FlakyNode {
+        failures: u32,
+        calls: AtomicU32,
+    }
+
+    impl FlakyNode {
+        fn new(failures: u32) -> Self {
+            Self {
+                failures,
+                calls: AtomicU32::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl WorkflowNode for FlakyNode {
+        fn id(&self) -> &'static str {
+            "flaky"
+        }
+
+        fn node_type(&self) -> &'static str {
+            "flaky"
+        }
+
+        async fn execute(&self, ctx: NodeContext) -> Result<NodeOutput, WorkflowError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if call <= self.failures {
+                return Err(WorkflowError::ExecutionFailed(format!("failure {call}")));
+            }
+            Ok(NodeOutput::continue_with(ctx.input))
+        }
+    }
 
     #[tokio::test]
     async fn test_simple_workflow() {
@@ -352,4 +932,111 @@ mod tests {
         let result = engine.execute(&workflow, serde_json::json!({})).await;
         assert!(matches!(result, Err(WorkflowError::NodeNotFound(_))));
     }
+
+    #[tokio::test]
+    async fn test_retry_policy() {
+        let flaky = Arc::new(FlakyNode::new(2));
+        let mut workflow = Workflow::new("retry", "Retry", "flaky");
+        workflow.add_node(flaky.clone());
+        workflow.set_retry(
+            "flaky",
+            NodeRetryPolicy::attempts(3).with_backoff(Duration::from_millis(1), None),
+        );
+
+        let result = WorkflowEngine::new()
+            .execute(&workflow, serde_json::json!({"ok": true}))
+            .await
+            .unwrap();
+        assert_eq!(result["ok"], true);
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 3);
+
+        workflow.set_retry("flaky", NodeRetryPolicy::attempts(2));
+        let flaky = Arc::new(FlakyNode::new(5));
+        let node: Arc<dyn WorkflowNode> = flaky.clone();
+        workflow.nodes = vec![node];
+        let result = WorkflowEngine::new()
+            .execute(&workflow, serde_json::json!({}))
+            .await;
+        assert!(matches!(result, Err(WorkflowError::ExecutionFailed(_))));
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        let policy = NodeRetryPolicy::attempts(5)
+            .with_backoff(Duration::from_millis(100), Some(Duration::from_millis(300)));
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2), Duration::from_millis(300));
+    }
+
+    #[tokio::test]
+    async fn test_error_edge_and_failure_handler() {
+        let fallback = Arc::new(StubNode::new("fallback").ending());
+        let handler = Arc::new(StubNode::new("handler").ending());
+        let mut workflow = Workflow::new("errors", "Errors", "first");
+        workflow.add_node(Arc::new(StubNode::new("first").failing("boom")));
+        workflow.add_node(Arc::new(StubNode::new("second").failing("bang")));
+        workflow.add_node(fallback.clone());
+        workflow.add_node(handler.clone());
+        workflow.add_error_edge("first", "fallback");
+        workflow.set_failure_handler("handler");
+
+        let engine = WorkflowEngine::new();
+        let result = engine
+            .execute(&workflow, serde_json::json!({"n": 1}))
+            .await
+            .unwrap();
+        assert_eq!(result["node"], "first");
+        assert_eq!(result["input"]["n"], 1);
+        assert!(result["error"].as_str().unwrap().contains("boom"));
+        assert_eq!(fallback.call_count(), 1);
+        assert_eq!(handler.call_count(), 0);
+
+        // No error edge: the failure handler takes over
+        workflow.start_node = "second".to_string();
+        let result = engine
+            .execute(&workflow, serde_json::json!({}))
+            .await
+            .unwrap();
+        assert_eq!(result["node"], "second");
+        assert_eq!(handler.call_count(), 1);
+    }
+
+    #[test]
+    fn test_definition_round_trip() {
+        let definition: WorkflowDefinition = serde_json::from_value(serde_json::json!({
+            "id": "w",
+            "name": "W",
+            "startNode": "a",
+            "edges": [
+                {"from": "a", "to": "b"},
+                {"from": "a", "to": "cleanup", "condition": ERROR_CONDITION}
+            ],
+            "retry": {"a": {"maxAttempts": 3, "backoffMs": 50}},
+            "failureHandler": "cleanup"
+        }))
+        .unwrap();
+        let nodes: Vec<Arc<dyn WorkflowNode>> = vec![
+            Arc::new(PassthroughNode::new("a")),
+            Arc::new(PassthroughNode::new("b")),
+            Arc::new(PassthroughNode::new("cleanup")),
+        ];
+        let workflow = Workflow::from_definition(definition, nodes).unwrap();
+        assert_eq!(workflow.retry["a"].max_attempts, 3);
+        assert_eq!(workflow.error_target("a").as_deref(), Some("cleanup"));
+        assert_eq!(workflow.error_target("b").as_deref(), Some("cleanup"));
+        assert_eq!(workflow.error_target("cleanup"), None);
+
+        let saved = serde_json::to_value(workflow.definition()).unwrap();
+        assert_eq!(saved["failureHandler"], "cleanup");
+        assert_eq!(saved["retry"]["a"]["backoffMs"], 50);
+
+        let mut missing = workflow.definition();
+        missing.failure_handler = Some("nowhere".to_string());
+        assert!(matches!(
+            Workflow::from_definition(missing, Vec::new()),
+            Err(WorkflowError::InvalidWorkflow(_))
+        ));
+    }
 }
@@ -0,0 +1,72 @@
+//! Workflows by ID.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::Workflow;
+
+/// Registered workflows, looked up by ID.
+#[derive(Default)]
+pub struct WorkflowRegistry {
+    workflows: HashMap<String, Arc<Workflow>>,
+}
+
+impl WorkflowRegistry {
+    /// Create an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a workflow under its own ID, replacing any with that ID.
+    pub fn register(&mut self, workflow: Arc<Workflow>) {
+        self.workflows.insert(workflow.id.clone(), workflow);
+    }
+
+    /// Register a workflow under `id`, replacing any with that ID.
+    pub fn insert(&mut self, id: impl Into<String>, workflow: Arc<Workflow>) {
+        self.workflows.insert(id.into(), workflow);
+    }
+
+    /// Workflow registered as `id`.
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<&Arc<Workflow>> {
+        self.workflows.get(id)
+    }
+
+    /// Registered IDs, sorted.
+    #[must_use]
+    pub fn ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.workflows.keys().map(String::as_str).collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Number of registered workflows.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.workflows.len()
+    }
+
+    /// Whether no workflows are registered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.workflows.is_empty()
+    }
+}
+
+impl FromIterator<(String, Arc<Workflow>)> for WorkflowRegistry {
+    fn from_iter<I: IntoIterator<Item = (String, Arc<Workflow>)>>(iter: I) -> Self {
+        Self {
+            workflows: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl std::fmt::Debug for WorkflowRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkflowRegistry")
+            .field("workflows", &self.ids())
+            .finish()
+    }
+}
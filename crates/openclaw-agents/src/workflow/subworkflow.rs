@@ -0,0 +1,182 @@
+//! Running another workflow as a node.
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::{NodeContext, NodeOutput, WorkflowEngine, WorkflowError, WorkflowNode};
+
+/// Nesting depth sub-workflows may reach by default.
+pub const DEFAULT_MAX_DEPTH: usize = 8;
+
+/// Node that runs a registered workflow and continues with its result.
+///
+/// The child workflow is looked up by ID in the registry of the engine
+/// running this node. Its input is this node's input, or an object built
+/// from parts of it with [`SubWorkflowNode::map_input`].
+pub struct SubWorkflowNode {
+    id: String,
+    workflow_id: String,
+    input_map: Vec<(String, String)>,
+    output_key: Option<String>,
+    max_depth: usize,
+}
+
+impl SubWorkflowNode {
+    /// Create a node that runs workflow `workflow_id`.
+    #[must_use]
+    pub fn new(id: impl Into<String>, workflow_id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            workflow_id: workflow_id.into(),
+            input_map: Vec::new(),
+            output_key: None,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// Set child input field `key` to the value at JSON pointer `pointer`
+    /// (e.g. `/message/text`) in this node's input. Once any field is
+    /// mapped, only mapped fields are passed.
+    #[must_use]
+    pub fn map_input(mut self, key: impl Into<String>, pointer: impl Into<String>) -> Self {
+        self.input_map.push((key.into(), pointer.into()));
+        self
+    }
+
+    /// Keep this node's input and store the child's result under `key`,
+    /// instead of replacing the input with it.
+    #[must_use]
+    pub fn with_output_key(mut self, key: impl Into<String>) -> Self {
+        self.output_key = Some(key.into());
+        self
+    }
+
+    /// Fail instead of nesting deeper than `max_depth`.
+    #[must_use]
+    pub const fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Input for the child workflow.
+    fn child_input(&self, input: &Value) -> Value {
+        if self.input_map.is_empty() {
+            return input.clone();
+        }
+        let fields = self
+            .input_map
+            .iter()
+            .map(|(key, pointer)| {
+                let value = input.pointer(pointer).cloned().unwrap_or(Value::Null);
+                (key.clone(), value)
+            })
+            .collect();
+        Value::Object(fields)
+    }
+}
+
+#[async_trait]
+impl WorkflowNode for SubWorkflowNode {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn node_type(&self) -> &'static str {
+        "subworkflow"
+    }
+
+    async fn execute(&self, ctx: NodeContext) -> Result<NodeOutput, WorkflowError> {
+        if ctx.depth >= self.max_depth {
+            return Err(WorkflowError::ExecutionFailed(format!(
+                "Sub-workflow {} exceeds depth limit {}",
+                self.workflow_id, self.max_depth
+            )));
+        }
+        let registry = ctx.registry.clone().ok_or_else(|| {
+            WorkflowError::ExecutionFailed(format!(
+                "No workflow registry to find {}",
+                self.workflow_id
+            ))
+        })?;
+        let workflow = registry
+            .get(&self.workflow_id)
+            .cloned()
+            .ok_or_else(|| WorkflowError::WorkflowNotFound(self.workflow_id.clone()))?;
+
+        let result = WorkflowEngine::new()
+            .with_registry(registry)
+            .execute_at_depth(&workflow, self.child_input(&ctx.input), ctx.depth + 1)
+            .await?;
+
+        let data = match (&self.output_key, ctx.input) {
+            (Some(key), Value::Object(mut fields)) => {
+                fields.insert(key.clone(), result);
+                Value::Object(fields)
+            }
+            (Some(key), _) => serde_json::json!({ key: result }),
+            (None, _) => result,
+        };
+        Ok(NodeOutput::continue_with(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::workflow::testing::StubNode;
+    use crate::workflow::{Workflow, WorkflowRegistry};
+
+    #[tokio::test]
+    async fn test_runs_registered_workflow() {
+        let child_node = Arc::new(StubNode::new("child").returning(json!("done")));
+        let mut child = Workflow::new("child", "Child", "child");
+        child.add_node(child_node.clone());
+
+        let mut parent = Workflow::new("parent", "Parent", "call");
+        parent.add_node(Arc::new(
+            SubWorkflowNode::new("call", "child")
+                .map_input("text", "/message/text")
+                .with_output_key("child"),
+        ));
+
+        let mut registry = WorkflowRegistry::new();
+        registry.register(Arc::new(child));
+        let engine = WorkflowEngine::new().with_registry(Arc::new(registry));
+
+        let result = engine
+            .execute(&parent, json!({ "message": { "text": "hi" } }))
+            .await
+            .unwrap();
+        assert_eq!(result["child"], "done");
+        assert_eq!(result["message"]["text"], "hi");
+        assert_eq!(child_node.calls()[0].input, json!({ "text": "hi" }));
+        assert_eq!(child_node.calls()[0].depth, 1);
+    }
+
+    #[tokio::test]
+    async fn test_depth_limit_and_missing_workflow() {
+        let mut looping = Workflow::new("loop", "Loop", "again");
+        looping.add_node(Arc::new(
+            SubWorkflowNode::new("again", "loop").with_max_depth(3),
+        ));
+        let looping = Arc::new(looping);
+        let mut registry = WorkflowRegistry::new();
+        registry.register(looping.clone());
+        let engine = WorkflowEngine::new().with_registry(Arc::new(registry));
+
+        let result = engine.execute(&looping, json!({})).await;
+        assert!(matches!(
+            result,
+            Err(WorkflowError::ExecutionFailed(m)) if m.contains("depth limit 3")
+        ));
+
+        let mut orphan = Workflow::new("orphan", "Orphan", "call");
+        orphan.add_node(Arc::new(SubWorkflowNode::new("call", "missing")));
+        let result = engine.execute(&orphan, json!({})).await;
+        assert!(matches!(result, Err(WorkflowError::WorkflowNotFound(_))));
+    }
+}
@@ -0,0 +1,165 @@
+//! Workflow runs waiting for input, and where they are kept.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{InputRequest, WorkflowError};
+
+/// Conversation a suspended run asked its question in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputOrigin {
+    /// Channel ID.
+    pub channel: String,
+    /// Chat the prompt is sent to.
+    pub chat_id: String,
+    /// Peer whose reply resumes the run.
+    pub peer_id: String,
+}
+
+/// A workflow run stopped at a node that is waiting for input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuspendedRun {
+    /// Run ID.
+    pub id: String,
+    /// Workflow being run.
+    pub workflow_id: String,
+    /// Node that is waiting.
+    pub node_id: String,
+    /// The waiting node's input.
+    pub input: serde_json::Value,
+    /// Shared workflow state.
+    #[serde(default)]
+    pub state: HashMap<String, serde_json::Value>,
+    /// What the node asked for.
+    pub request: InputRequest,
+    /// Conversation the prompt went to; `None` for runs answered over RPC.
+    #[serde(default)]
+    pub origin: Option<InputOrigin>,
+    /// When the run suspended.
+    pub suspended_at: DateTime<Utc>,
+    /// When the wait times out.
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl SuspendedRun {
+    /// ID for a run starting at `now`.
+    #[must_use]
+    pub fn new_id(now: DateTime<Utc>) -> String {
+        format!("run-{:x}", now.timestamp_nanos_opt().unwrap_or_default())
+    }
+
+    /// Whether the wait has timed out by `now`.
+    #[must_use]
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+}
+
+/// Suspended runs on disk, one JSON file per run.
+#[derive(Debug, Clone)]
+pub struct SuspendedRunStore {
+    dir: PathBuf,
+}
+
+impl SuspendedRunStore {
+    /// Open a store rooted at `dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the directory cannot be created.
+    pub fn open(dir: &Path) -> Result<Self, WorkflowError> {
+        std::fs::create_dir_all(dir)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        run_path(&self.dir, id)
+    }
+
+    /// Save a run, replacing any with its ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be written.
+    pub fn save(&self, run: &SuspendedRun) -> Result<(), WorkflowError> {
+        let path = self.path(&run.id);
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(run)?)?;
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    /// Load run `id`, if it is still waiting.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file exists but cannot be read.
+    pub fn load(&self, id: &str) -> Result<Option<SuspendedRun>, WorkflowError> {
+        let path = self.path(id);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Remove run `id`, returning whether it was waiting. Only one caller
+    /// gets `true`, so it alone resumes the run.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be removed.
+    pub fn remove(&self, id: &str) -> Result<bool, WorkflowError> {
+        match std::fs::remove_file(self.path(id)) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// All waiting runs, oldest first. Unreadable files are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the directory cannot be read.
+    pub fn list(&self) -> Result<Vec<SuspendedRun>, WorkflowError> {
+        let mut runs = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let run = std::fs::read_to_string(&path)
+                .map_err(WorkflowError::from)
+                .and_then(|content| Ok(serde_json::from_str::<SuspendedRun>(&content)?));
+            match run {
+                Ok(run) => runs.push(run),
+                Err(e) => tracing::warn!("Skipping suspended run {}: {}", path.display(), e),
+            }
+        }
+        runs.sort_by_key(|run| run.suspended_at);
+        Ok(runs)
+    }
+}
+
+/// File in `dir` holding run `id`.
+pub(super) fn run_path(dir: &Path, id: &str) -> PathBuf {
+    // IDs arrive over RPC; keep them to a single path segment
+    let name: String = id
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    dir.join(format!("{name}.json"))
+}
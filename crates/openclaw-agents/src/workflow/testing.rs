@@ -0,0 +1,404 @@
+//! Helpers for unit-testing workflow nodes.
+//!
+//! Run a single node against a fabricated [`NodeContext`], check where its
+//! output routes, and stand in for neighbouring nodes with [`StubNode`],
+//! all without building a workflow or running the engine.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::{
+    END_NODE, NodeContext, NodeOutput, Workflow, WorkflowError, WorkflowNode, WorkflowRegistry,
+};
+
+/// Builder for a [`NodeContext`].
+#[derive(Debug, Clone)]
+pub struct ContextBuilder {
+    ctx: NodeContext,
+}
+
+impl ContextBuilder {
+    /// Context with `input`, empty config, and empty state.
+    #[must_use]
+    pub fn new(input: Value) -> Self {
+        Self {
+            ctx: NodeContext {
+                input,
+                config: Value::Object(serde_json::Map::new()),
+                state: HashMap::new(),
+                registry: None,
+                depth: 0,
+            },
+        }
+    }
+
+    /// Set the node configuration.
+    #[must_use]
+    pub fn config(mut self, config: Value) -> Self {
+        self.ctx.config = config;
+        self
+    }
+
+    /// Add a shared state entry.
+    #[must_use]
+    pub fn state(mut self, key: impl Into<String>, value: Value) -> Self {
+        self.ctx.state.insert(key.into(), value);
+        self
+    }
+
+    /// Make `registry` available to sub-workflow nodes.
+    #[must_use]
+    pub fn registry(mut self, registry: Arc<WorkflowRegistry>) -> Self {
+        self.ctx.registry = Some(registry);
+        self
+    }
+
+    /// Set the sub-workflow nesting depth.
+    #[must_use]
+    pub const fn depth(mut self, depth: usize) -> Self {
+        self.ctx.depth = depth;
+        self
+    }
+
+    /// Build the context.
+    #[must_use]
+    pub fn build(self) -> NodeContext {
+        self.ctx
+    }
+
+    /// Execute `node` with this context.
+    ///
+    /// # Errors
+    ///
+    /// Returns the node's error.
+    pub async fn run(self, node: &dyn WorkflowNode) -> Result<NodeOutput, WorkflowError> {
+        node.execute(self.ctx).await
+    }
+}
+
+/// Execute `node` with `input`, empty config, and empty state.
+///
+/// # Errors
+///
+/// Returns the node's error.
+pub async fn run_node(node: &dyn WorkflowNode, input: Value) -> Result<NodeOutput, WorkflowError> {
+    ContextBuilder::new(input).run(node).await
+}
+
+/// Where a node's output sends the workflow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Route {
+    /// Follow the first unconditional edge.
+    Continue,
+    /// Go to this node.
+    Goto(String),
+    /// Follow the edge for this branch.
+    Branch(String),
+    /// End the workflow.
+    End,
+}
+
+impl Route {
+    /// Route requested by `output`.
+    #[must_use]
+    pub fn of(output: &NodeOutput) -> Self {
+        match (&output.next, &output.branch) {
+            (Some(next), _) if next == END_NODE => Self::End,
+            (Some(next), _) => Self::Goto(next.clone()),
+            (None, Some(branch)) => Self::Branch(branch.clone()),
+            (None, None) => Self::Continue,
+        }
+    }
+}
+
+/// Assert that `output` routes as `expected`.
+///
+/// # Panics
+///
+/// Panics if it routes anywhere else.
+#[track_caller]
+pub fn assert_route(output: &NodeOutput, expected: &Route) {
+    let actual = Route::of(output);
+    assert_eq!(
+        &actual, expected,
+        "node routed to {actual:?}, expected {expected:?}"
+    );
+}
+
+/// Assert that `output` takes branch `name`.
+///
+/// # Panics
+///
+/// Panics if it routes anywhere else.
+#[track_caller]
+pub fn assert_branch(output: &NodeOutput, name: &str) {
+    assert_route(output, &Route::Branch(name.to_string()));
+}
+
+/// Assert that `output` goes to node `id`.
+///
+/// # Panics
+///
+/// Panics if it routes anywhere else.
+#[track_caller]
+pub fn assert_goto(output: &NodeOutput, id: &str) {
+    assert_route(output, &Route::Goto(id.to_string()));
+}
+
+/// Assert that `output` ends the workflow.
+///
+/// # Panics
+///
+/// Panics if it routes anywhere else.
+#[track_caller]
+pub fn assert_ends(output: &NodeOutput) {
+    assert_route(output, &Route::End);
+}
+
+/// Assert that, in `workflow`, `output` from `node_id` leads to `expected`
+/// (`None` for the end of the workflow).
+///
+/// # Panics
+///
+/// Panics if the workflow's edges lead elsewhere.
+#[track_caller]
+pub fn assert_next(
+    workflow: &Workflow,
+    node_id: &str,
+    output: &NodeOutput,
+    expected: Option<&str>,
+) {
+    let actual = workflow.next_node(node_id, output);
+    assert_eq!(
+        actual.as_deref(),
+        expected,
+        "{node_id} routed to {actual:?}, expected {expected:?}"
+    );
+}
+
+/// What a [`StubNode`] returns.
+#[derive(Debug, Clone)]
+enum StubResult {
+    /// Pass the input through.
+    Input,
+    /// Return this data.
+    Data(Value),
+    /// Fail with this message.
+    Fail(String),
+}
+
+/// Node with canned behavior that records the contexts it ran with.
+///
+/// By default it passes its input through and continues.
+#[derive(Debug)]
+pub struct StubNode {
+    id: String,
+    node_type: String,
+    result: StubResult,
+    route: Route,
+    calls: Mutex<Vec<NodeContext>>,
+}
+
+impl StubNode {
+    /// Create a stub that passes its input through.
+    #[must_use]
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            node_type: "stub".to_string(),
+            result: StubResult::Input,
+            route: Route::Continue,
+            calls: Mutex::default(),
+        }
+    }
+
+    /// Report this node type.
+    #[must_use]
+    pub fn with_type(mut self, node_type: impl Into<String>) -> Self {
+        self.node_type = node_type.into();
+        self
+    }
+
+    /// Return `data` instead of the input.
+    #[must_use]
+    pub fn returning(mut self, data: Value) -> Self {
+        self.result = StubResult::Data(data);
+        self
+    }
+
+    /// Fail with `message`.
+    #[must_use]
+    pub fn failing(mut self, message: impl Into<String>) -> Self {
+        self.result = StubResult::Fail(message.into());
+        self
+    }
+
+    /// Take branch `name`.
+    #[must_use]
+    pub fn branching(mut self, name: impl Into<String>) -> Self {
+        self.route = Route::Branch(name.into());
+        self
+    }
+
+    /// Go to node `id`.
+    #[must_use]
+    pub fn going_to(mut self, id: impl Into<String>) -> Self {
+        self.route = Route::Goto(id.into());
+        self
+    }
+
+    /// End the workflow.
+    #[must_use]
+    pub fn ending(mut self) -> Self {
+        self.route = Route::End;
+        self
+    }
+
+    /// Contexts the node ran with, in order.
+    #[must_use]
+    pub fn calls(&self) -> Vec<NodeContext> {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Number of times the node ran.
+    #[must_use]
+    pub fn call_count(&self) -> usize {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+}
+
+#[async_trait]
+impl WorkflowNode for StubNode {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn node_type(&self) -> &str {
+        &self.node_type
+    }
+
+    async fn execute(&self, ctx: NodeContext) -> Result<NodeOutput, WorkflowError> {
+        let input = ctx.input.clone();
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(ctx);
+
+        let data = match &self.result {
+            StubResult::Input => input,
+            StubResult::Data(data) => data.clone(),
+            StubResult::Fail(message) => {
+                return Err(WorkflowError::ExecutionFailed(message.clone()));
+            }
+        };
+        Ok(match &self.route {
+            Route::Continue => NodeOutput::continue_with(data),
+            Route::Goto(id) => NodeOutput::goto(data, id),
+            Route::Branch(name) => NodeOutput::branch(data, name),
+            Route::End => NodeOutput::end(data),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::workflow::WorkflowEngine;
+
+    /// Routes on the sign of `input.value`, scaled by `config.factor`.
+    struct SignNode;
+
+    #[async_trait]
+    impl WorkflowNode for SignNode {
+        fn id(&self) -> &'static str {
+            "sign"
+        }
+
+        fn node_type(&self) -> &'static str {
+            "sign"
+        }
+
+        async fn execute(&self, ctx: NodeContext) -> Result<NodeOutput, WorkflowError> {
+            let value = ctx.input["value"].as_i64().unwrap_or_default();
+            let factor = ctx.config["factor"].as_i64().unwrap_or(1);
+            let data = json!({ "value": value * factor });
+            Ok(if value < 0 {
+                NodeOutput::branch(data, "negative")
+            } else {
+                NodeOutput::continue_with(data)
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_node_and_routes() {
+        let output = run_node(&SignNode, json!({ "value": -2 })).await.unwrap();
+        assert_branch(&output, "negative");
+
+        let output = ContextBuilder::new(json!({ "value": 3 }))
+            .config(json!({ "factor": 10 }))
+            .run(&SignNode)
+            .await
+            .unwrap();
+        assert_route(&output, &Route::Continue);
+        assert_eq!(output.data["value"], 30);
+
+        let mut workflow = Workflow::new("w", "W", "sign");
+        workflow.add_edge("sign", "positive");
+        workflow.add_conditional_edge("sign", "fix", "negative");
+        assert_next(&workflow, "sign", &output, Some("positive"));
+        let negative = NodeOutput::branch(json!({}), "negative");
+        assert_next(&workflow, "sign", &negative, Some("fix"));
+        assert_next(&workflow, "sign", &NodeOutput::end(json!({})), None);
+    }
+
+    #[tokio::test]
+    async fn test_stub_node() {
+        let stub = StubNode::new("lookup")
+            .returning(json!({ "found": true }))
+            .going_to("reply");
+        let output = ContextBuilder::new(json!({ "q": "x" }))
+            .state("user", json!("alice"))
+            .run(&stub)
+            .await
+            .unwrap();
+        assert_goto(&output, "reply");
+        assert_eq!(output.data, json!({ "found": true }));
+        assert_eq!(stub.calls()[0].state["user"], "alice");
+
+        let failing = StubNode::new("broken").failing("boom");
+        assert!(matches!(
+            run_node(&failing, json!({})).await,
+            Err(WorkflowError::ExecutionFailed(m)) if m == "boom"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stubs_in_engine() {
+        let first = Arc::new(StubNode::new("first").branching("b"));
+        let second = Arc::new(StubNode::new("second").ending());
+        let mut workflow = Workflow::new("w", "W", "first");
+        workflow.add_node(first.clone());
+        workflow.add_node(second.clone());
+        workflow.add_conditional_edge("first", "second", "b");
+
+        let result = WorkflowEngine::new()
+            .execute(&workflow, json!({ "n": 1 }))
+            .await
+            .unwrap();
+        assert_eq!(result, json!({ "n": 1 }));
+        assert_eq!(first.call_count(), 1);
+        assert_eq!(second.calls()[0].input, json!({ "n": 1 }));
+    }
+}
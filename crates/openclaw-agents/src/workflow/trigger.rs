@@ -0,0 +1,152 @@
+//! What starts a workflow.
+
+use serde::{Deserialize, Serialize};
+
+use crate::scheduler::parse_cron;
+
+use super::WorkflowError;
+
+/// Something that starts a run of the workflow declaring it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum WorkflowTrigger {
+    /// An inbound message whose text matches `pattern`.
+    Message {
+        /// Pattern the whole text must match, ignoring case; `*` matches
+        /// any text and `?` any one character, e.g. `deploy *`.
+        pattern: String,
+        /// Only messages on this channel.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        channel: Option<String>,
+    },
+    /// A cron schedule in UTC (5 fields, or 6 with leading seconds).
+    Cron {
+        /// Cron expression.
+        cron: String,
+        /// Input for each run.
+        #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+        input: serde_json::Value,
+    },
+    /// A POST to `/hooks/workflow/{id}`.
+    Webhook {
+        /// Value the `X-Openclaw-Secret` header must carry.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        secret: Option<String>,
+    },
+    /// A session event of this type, e.g. `session_ended`.
+    Event {
+        /// Event type.
+        event: String,
+    },
+}
+
+impl WorkflowTrigger {
+    /// Check that the trigger can fire.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidWorkflow` for an unparseable cron expression.
+    pub fn validate(&self) -> Result<(), WorkflowError> {
+        if let Self::Cron { cron, .. } = self {
+            parse_cron(cron).map_err(|e| {
+                WorkflowError::InvalidWorkflow(format!("Invalid cron '{cron}': {e}"))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Whether this is a message trigger matching `text` on `channel`.
+    #[must_use]
+    pub fn matches_message(&self, channel: &str, text: &str) -> bool {
+        match self {
+            Self::Message {
+                pattern,
+                channel: only,
+            } => {
+                only.as_deref().is_none_or(|only| only == channel)
+                    && glob_match(&pattern.to_lowercase(), &text.trim().to_lowercase())
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Match `text` against a pattern where `*` is any text and `?` any one
+/// character.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Last `*` seen, and the text position it has consumed up to
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_patterns() {
+        let trigger = WorkflowTrigger::Message {
+            pattern: "deploy *".to_string(),
+            channel: Some("slack".to_string()),
+        };
+        assert!(trigger.matches_message("slack", "Deploy api to prod"));
+        assert!(trigger.matches_message("slack", "  deploy x "));
+        assert!(!trigger.matches_message("slack", "please deploy"));
+        assert!(!trigger.matches_message("telegram", "deploy api"));
+
+        assert!(glob_match("a?c*", "abcdef"));
+        assert!(glob_match("*x*y", "axbxy"));
+        assert!(!glob_match("a?c", "ac"));
+        assert!(glob_match("*", ""));
+    }
+
+    #[test]
+    fn test_parse_and_validate() {
+        let triggers: Vec<WorkflowTrigger> = serde_json::from_value(serde_json::json!([
+            {"type": "cron", "cron": "0 9 * * 1"},
+            {"type": "webhook"},
+            {"type": "event", "event": "session_ended"}
+        ]))
+        .unwrap();
+        assert!(triggers.iter().all(|t| t.validate().is_ok()));
+        assert_eq!(
+            triggers[2],
+            WorkflowTrigger::Event {
+                event: "session_ended".to_string()
+            }
+        );
+
+        let bad = WorkflowTrigger::Cron {
+            cron: "every day".to_string(),
+            input: serde_json::Value::Null,
+        };
+        assert!(matches!(
+            bad.validate(),
+            Err(WorkflowError::InvalidWorkflow(_))
+        ));
+    }
+}
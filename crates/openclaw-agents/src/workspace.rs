@@ -0,0 +1,464 @@
+//! Agent workspaces.
+//!
+//! A workspace is a directory under the state dir that an agent's sandboxed
+//! tools can write to: `workspace/<agent>` for the agent, or
+//! `workspace/<agent>/sessions/<session>` when each session gets its own.
+//! A marker file records who owns the directory; its modification time is
+//! the last time the workspace was used, which drives expiry.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use openclaw_core::config::WorkspaceConfig;
+
+/// Marker file written in every workspace.
+pub const MARKER_FILE: &str = ".workspace.json";
+
+/// Directory holding an agent's session workspaces.
+const SESSIONS_DIR: &str = "sessions";
+
+/// Workspace errors.
+#[derive(Error, Debug)]
+pub enum WorkspaceError {
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The workspace uses more than its quota.
+    #[error("Workspace quota exceeded ({used} of {quota} bytes used)")]
+    QuotaExceeded {
+        /// Bytes in use.
+        used: u64,
+        /// Quota in bytes.
+        quota: u64,
+    },
+}
+
+/// Owner recorded in the marker file.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Marker {
+    agent_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    session_key: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+/// An agent or session workspace directory.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    root: PathBuf,
+    agent_id: String,
+    session_key: Option<String>,
+    quota: Option<u64>,
+}
+
+impl Workspace {
+    /// Workspace for an agent under `base`.
+    #[must_use]
+    pub fn for_agent(base: &Path, agent_id: &str) -> Self {
+        Self {
+            root: base.join(sanitize_segment(agent_id)),
+            agent_id: agent_id.to_string(),
+            session_key: None,
+            quota: None,
+        }
+    }
+
+    /// Workspace for a session, nested under its agent's workspace.
+    #[must_use]
+    pub fn for_session(base: &Path, agent_id: &str, session_key: &str) -> Self {
+        let agent = Self::for_agent(base, agent_id);
+        Self {
+            root: agent
+                .root
+                .join(SESSIONS_DIR)
+                .join(sanitize_segment(session_key)),
+            session_key: Some(session_key.to_string()),
+            ..agent
+        }
+    }
+
+    /// Workspace an agent configured with `config` uses for `session_key`.
+    ///
+    /// The session is ignored unless `config.per_session` is set.
+    #[must_use]
+    pub fn from_config(
+        base: &Path,
+        agent_id: &str,
+        session_key: Option<&str>,
+        config: &WorkspaceConfig,
+    ) -> Self {
+        let workspace = match session_key {
+            Some(key) if config.per_session => Self::for_session(base, agent_id, key),
+            _ => Self::for_agent(base, agent_id),
+        };
+        Self {
+            quota: config.quota_bytes(),
+            ..workspace
+        }
+    }
+
+    /// Limit the workspace to `bytes`.
+    #[must_use]
+    pub const fn with_quota(mut self, bytes: u64) -> Self {
+        self.quota = Some(bytes);
+        self
+    }
+
+    /// Workspace directory.
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Owning agent.
+    #[must_use]
+    pub fn agent_id(&self) -> &str {
+        &self.agent_id
+    }
+
+    /// Owning session, for session workspaces.
+    #[must_use]
+    pub fn session_key(&self) -> Option<&str> {
+        self.session_key.as_deref()
+    }
+
+    /// Quota in bytes, if any.
+    #[must_use]
+    pub const fn quota(&self) -> Option<u64> {
+        self.quota
+    }
+
+    /// Whether the directory exists.
+    #[must_use]
+    pub fn exists(&self) -> bool {
+        self.root.is_dir()
+    }
+
+    /// Create the directory if needed and mark it used.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the directory or marker cannot be written.
+    pub fn create(&self) -> Result<(), WorkspaceError> {
+        std::fs::create_dir_all(&self.root)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&self.root, std::fs::Permissions::from_mode(0o700))?;
+        }
+        self.touch()?;
+        Ok(())
+    }
+
+    /// Mark the workspace used now, writing the marker if it is missing.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the marker cannot be written.
+    pub fn touch(&self) -> std::io::Result<()> {
+        let marker = self.root.join(MARKER_FILE);
+        if !marker.exists() {
+            let content = serde_json::to_vec_pretty(&Marker {
+                agent_id: self.agent_id.clone(),
+                session_key: self.session_key.clone(),
+                created_at: Utc::now(),
+            })?;
+            return std::fs::write(marker, content);
+        }
+        std::fs::File::options()
+            .write(true)
+            .open(marker)?
+            .set_modified(SystemTime::now())
+    }
+
+    fn last_used_at(&self) -> Option<SystemTime> {
+        std::fs::metadata(self.root.join(MARKER_FILE))
+            .or_else(|_| std::fs::metadata(&self.root))
+            .and_then(|meta| meta.modified())
+            .ok()
+    }
+
+    /// When the workspace was last used.
+    #[must_use]
+    pub fn last_used(&self) -> Option<DateTime<Utc>> {
+        self.last_used_at().map(DateTime::from)
+    }
+
+    /// Bytes used by files in the workspace, including session workspaces
+    /// nested in an agent's. Symlinks are not followed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the directory cannot be read.
+    pub fn usage(&self) -> std::io::Result<u64> {
+        if !self.exists() {
+            return Ok(0);
+        }
+        dir_size(&self.root)
+    }
+
+    /// Current usage, or `QuotaExceeded` if it is over the quota.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QuotaExceeded` if usage exceeds the quota, or an IO error if
+    /// the directory cannot be read.
+    pub fn check_quota(&self) -> Result<u64, WorkspaceError> {
+        let used = self.usage()?;
+        match self.quota {
+            Some(quota) if used > quota => Err(WorkspaceError::QuotaExceeded { used, quota }),
+            _ => Ok(used),
+        }
+    }
+
+    /// Delete the workspace's files, keeping the directory, its marker, and
+    /// any session workspaces.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a file cannot be removed.
+    pub fn clean(&self) -> std::io::Result<()> {
+        if !self.exists() {
+            return Ok(());
+        }
+        for entry in std::fs::read_dir(&self.root)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            if name == MARKER_FILE || (self.session_key.is_none() && name == SESSIONS_DIR) {
+                continue;
+            }
+            if entry.file_type()?.is_dir() {
+                std::fs::remove_dir_all(entry.path())?;
+            } else {
+                std::fs::remove_file(entry.path())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete the workspace directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the directory cannot be removed.
+    pub fn remove(&self) -> std::io::Result<()> {
+        if !self.exists() {
+            return Ok(());
+        }
+        std::fs::remove_dir_all(&self.root)
+    }
+
+    /// Session workspaces nested in this agent workspace.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the sessions directory cannot be read.
+    pub fn sessions(&self) -> std::io::Result<Vec<Self>> {
+        let dir = self.root.join(SESSIONS_DIR);
+        if self.session_key.is_some() || !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut sessions = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let root = entry.path();
+            let session_key = read_marker(&root)
+                .and_then(|m| m.session_key)
+                .unwrap_or_else(|| entry.file_name().to_string_lossy().into_owned());
+            sessions.push(Self {
+                root,
+                agent_id: self.agent_id.clone(),
+                session_key: Some(session_key),
+                quota: self.quota,
+            });
+        }
+        sessions.sort_by(|a, b| a.session_key.cmp(&b.session_key));
+        Ok(sessions)
+    }
+
+    /// Remove session workspaces not used for `max_idle`, returning them.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a workspace cannot be read or removed.
+    pub fn expire_sessions(&self, max_idle: Duration) -> std::io::Result<Vec<Self>> {
+        let now = SystemTime::now();
+        let mut expired = Vec::new();
+        for session in self.sessions()? {
+            let idle = session
+                .last_used_at()
+                .and_then(|used| now.duration_since(used).ok())
+                .unwrap_or_default();
+            if idle > max_idle {
+                session.remove()?;
+                tracing::info!(
+                    agent = %self.agent_id,
+                    session = session.session_key().unwrap_or_default(),
+                    "Expired session workspace"
+                );
+                expired.push(session);
+            }
+        }
+        Ok(expired)
+    }
+}
+
+/// Agent workspaces under `base`, sorted by agent.
+///
+/// # Errors
+///
+/// Returns error if `base` exists but cannot be read.
+pub fn list_workspaces(base: &Path) -> std::io::Result<Vec<Workspace>> {
+    if !base.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut workspaces = Vec::new();
+    for entry in std::fs::read_dir(base)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let root = entry.path();
+        let agent_id = read_marker(&root).map_or_else(
+            || entry.file_name().to_string_lossy().into_owned(),
+            |m| m.agent_id,
+        );
+        workspaces.push(Workspace {
+            root,
+            agent_id,
+            session_key: None,
+            quota: None,
+        });
+    }
+    workspaces.sort_by(|a, b| a.agent_id.cmp(&b.agent_id));
+    Ok(workspaces)
+}
+
+fn read_marker(root: &Path) -> Option<Marker> {
+    let content = std::fs::read(root.join(MARKER_FILE)).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+fn dir_size(dir: &Path) -> std::io::Result<u64> {
+    let mut total = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let meta = entry.path().symlink_metadata()?;
+        if meta.is_dir() {
+            total += dir_size(&entry.path())?;
+        } else if meta.is_file() {
+            total += meta.len();
+        }
+    }
+    Ok(total)
+}
+
+/// Map an ID to a single safe path segment.
+fn sanitize_segment(id: &str) -> String {
+    id.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .trim_start_matches('.')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_lifecycle() {
+        let temp = tempdir().unwrap();
+        let agent = Workspace::for_agent(temp.path(), "dev");
+        let session = Workspace::for_session(temp.path(), "dev", "telegram:42");
+        assert_eq!(session.root(), temp.path().join("dev/sessions/telegram_42"));
+
+        agent.create().unwrap();
+        session.create().unwrap();
+        std::fs::write(agent.root().join("notes.txt"), "hello").unwrap();
+        std::fs::write(session.root().join("out.bin"), [0u8; 100]).unwrap();
+        assert!(agent.last_used().is_some());
+
+        // Agent usage includes its sessions; the markers count too
+        let session_usage = session.usage().unwrap();
+        assert!(session_usage >= 100);
+        assert!(agent.usage().unwrap() >= session_usage + 5);
+
+        let sessions = agent.sessions().unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_key(), Some("telegram:42"));
+
+        let listed = list_workspaces(temp.path()).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].agent_id(), "dev");
+
+        // Cleaning the agent keeps the marker and session workspaces
+        agent.clean().unwrap();
+        assert!(!agent.root().join("notes.txt").exists());
+        assert!(agent.root().join(MARKER_FILE).exists());
+        assert!(session.root().join("out.bin").exists());
+
+        assert!(
+            agent
+                .expire_sessions(Duration::from_secs(3600))
+                .unwrap()
+                .is_empty()
+        );
+        let expired = agent.expire_sessions(Duration::ZERO).unwrap();
+        assert_eq!(expired.len(), 1);
+        assert!(!session.exists());
+    }
+
+    #[test]
+    fn test_quota() {
+        let temp = tempdir().unwrap();
+        let config = WorkspaceConfig {
+            per_session: true,
+            quota_mb: Some(1),
+            expire_after_hours: None,
+        };
+        let workspace = Workspace::from_config(temp.path(), "dev", Some("s1"), &config);
+        assert_eq!(workspace.session_key(), Some("s1"));
+        assert_eq!(workspace.quota(), Some(1024 * 1024));
+
+        workspace.create().unwrap();
+        assert!(workspace.check_quota().is_ok());
+
+        let workspace = workspace.with_quota(10);
+        std::fs::write(workspace.root().join("big"), [0u8; 1024]).unwrap();
+        assert!(matches!(
+            workspace.check_quota(),
+            Err(WorkspaceError::QuotaExceeded { quota: 10, .. })
+        ));
+
+        // Without perSession the agent workspace is used
+        let shared =
+            Workspace::from_config(temp.path(), "dev", Some("s1"), &WorkspaceConfig::default());
+        assert_eq!(shared.session_key(), None);
+        assert_eq!(shared.quota(), None);
+    }
+
+    #[test]
+    fn test_sanitize_segment() {
+        assert_eq!(sanitize_segment("telegram:123"), "telegram_123");
+        assert_eq!(sanitize_segment("../evil"), "_evil");
+    }
+}
@@ -0,0 +1,312 @@
+//! Splitting long messages.
+//!
+//! Channels cap message length (`text_chunk_limit`). Long replies are split
+//! at paragraph breaks where possible, then between lines, then after
+//! sentences. Fenced code blocks are kept whole, or split between their
+//! lines with each part fenced again under the same language tag. Parts
+//! can be numbered, e.g. `(2/3)`.
+
+/// Split Markdown into chunks of at most `limit` characters.
+///
+/// A chunk ends at its last paragraph break if that keeps it at least half
+/// full, otherwise at a line break. A line longer than `limit` is split
+/// after a sentence, else at whitespace. Text that fits is returned as is.
+#[must_use]
+pub fn chunk_text(text: &str, limit: usize) -> Vec<String> {
+    let limit = limit.max(1);
+    if char_len(text) <= limit {
+        return vec![text.to_string()];
+    }
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    // Byte offset in `current` just after its last blank line
+    let mut paragraph_end: Option<usize> = None;
+
+    for block in blocks(text) {
+        let pieces = if char_len(block.text) <= limit {
+            vec![block.text.to_string()]
+        } else {
+            split_block(&block, limit)
+        };
+        for piece in pieces {
+            if char_len(&current) + char_len(&piece) > limit {
+                match paragraph_end.filter(|&end| char_len(&current[..end]) >= limit / 2) {
+                    Some(end) => {
+                        let rest = current.split_off(end);
+                        push_chunk(&mut chunks, &mut current);
+                        current = rest.trim_start_matches(['\r', '\n']).to_string();
+                        if char_len(&current) + char_len(&piece) > limit {
+                            push_chunk(&mut chunks, &mut current);
+                        }
+                    }
+                    None => push_chunk(&mut chunks, &mut current),
+                }
+                paragraph_end = None;
+            }
+            let blank = piece.trim().is_empty();
+            if current.is_empty() && blank {
+                continue;
+            }
+            current.push_str(&piece);
+            if blank {
+                paragraph_end = Some(current.len());
+            }
+        }
+    }
+    push_chunk(&mut chunks, &mut current);
+    chunks
+}
+
+/// Chunk `text` and number the parts when there is more than one, keeping
+/// each numbered part within `limit`.
+#[must_use]
+pub fn chunk_numbered(text: &str, limit: usize) -> Vec<String> {
+    numbered(limit, |limit| chunk_text(text, limit))
+}
+
+/// Split with `split`, reserving room for part numbers, then number the
+/// parts. Parts are left unnumbered if the limit is too small for them.
+pub fn numbered(limit: usize, split: impl Fn(usize) -> Vec<String>) -> Vec<String> {
+    let mut parts = split(limit);
+    let mut reserved = 0;
+    while parts.len() > 1 && suffix_len(parts.len()) > reserved {
+        reserved = suffix_len(parts.len());
+        if reserved >= limit {
+            return split(limit);
+        }
+        parts = split(limit - reserved);
+    }
+
+    let total = parts.len();
+    if total < 2 {
+        return parts;
+    }
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(i, part)| format!("{part}{}", part_suffix(i + 1, total)))
+        .collect()
+}
+
+fn part_suffix(index: usize, total: usize) -> String {
+    format!("\n\n({index}/{total})")
+}
+
+/// Longest suffix for `total` parts.
+fn suffix_len(total: usize) -> usize {
+    char_len(&part_suffix(total, total))
+}
+
+pub fn char_len(s: &str) -> usize {
+    s.chars().count()
+}
+
+fn push_chunk(chunks: &mut Vec<String>, current: &mut String) {
+    let chunk = std::mem::take(current);
+    let chunk = chunk.trim_end();
+    if !chunk.is_empty() {
+        chunks.push(chunk.to_string());
+    }
+}
+
+/// A line of text, or a whole fenced code block.
+pub struct Block<'a> {
+    pub text: &'a str,
+    /// Opening fence line, with any language tag, for code blocks.
+    pub fence: Option<&'a str>,
+}
+
+pub fn is_fence(line: &str) -> bool {
+    line.trim_start().starts_with("```")
+}
+
+/// Split text into lines, keeping each fenced code block as one block.
+pub fn blocks(text: &str) -> Vec<Block<'_>> {
+    let mut blocks = Vec::new();
+    let mut code_start: Option<(usize, &str)> = None;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        match code_start {
+            Some((begin, fence)) if is_fence(line) => {
+                blocks.push(Block {
+                    text: &text[begin..offset],
+                    fence: Some(fence),
+                });
+                code_start = None;
+            }
+            Some(_) => {}
+            None if is_fence(line) => code_start = Some((start, line.trim())),
+            None => blocks.push(Block {
+                text: line,
+                fence: None,
+            }),
+        }
+    }
+    // Unclosed fence: the rest is code
+    if let Some((begin, fence)) = code_start {
+        blocks.push(Block {
+            text: &text[begin..],
+            fence: Some(fence),
+        });
+    }
+    blocks
+}
+
+/// Split a block longer than `limit`.
+fn split_block(block: &Block<'_>, limit: usize) -> Vec<String> {
+    let Some(fence) = block.fence else {
+        return split_line(block.text, limit);
+    };
+
+    let open = format!("{fence}\n");
+    let close = "```\n";
+    let overhead = char_len(&open) + char_len(close);
+    if overhead >= limit {
+        return split_line(block.text, limit);
+    }
+    let budget = limit - overhead;
+
+    let mut lines = block.text.split_inclusive('\n').skip(1).collect::<Vec<_>>();
+    if lines.last().is_some_and(|l| is_fence(l)) {
+        lines.pop();
+    }
+
+    let mut pieces = Vec::new();
+    let mut body = String::new();
+    for line in lines.iter().flat_map(|l| split_line(l, budget)) {
+        if char_len(&body) + char_len(&line) > budget && !body.is_empty() {
+            pieces.push(format!("{open}{}{close}", std::mem::take(&mut body)));
+        }
+        body.push_str(&line);
+        if !body.ends_with('\n') && char_len(&body) < budget {
+            body.push('\n');
+        }
+    }
+    if !body.is_empty() {
+        if !body.ends_with('\n') {
+            body.push('\n');
+        }
+        pieces.push(format!("{open}{body}{close}"));
+    }
+    pieces
+}
+
+/// Split a line into pieces of at most `limit` characters, preferring to
+/// break after a sentence in the second half of the piece, then after
+/// whitespace.
+fn split_line(line: &str, limit: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut rest = line;
+    while char_len(rest) > limit {
+        let end = rest
+            .char_indices()
+            .nth(limit)
+            .map_or(rest.len(), |(i, _)| i);
+        let window = &rest[..end];
+        let half = window.char_indices().nth(limit / 2).map_or(end, |(i, _)| i);
+
+        let breaks = window
+            .char_indices()
+            .filter(|(_, c)| c.is_whitespace())
+            .map(|(i, c)| (window[..i].ends_with(['.', '!', '?']), i + c.len_utf8()));
+        let (mut sentence, mut space) = (None, None);
+        for (after_sentence, cut) in breaks {
+            if after_sentence && cut >= half {
+                sentence = Some(cut);
+            }
+            space = Some(cut);
+        }
+        let cut = sentence.or(space).unwrap_or(end);
+
+        pieces.push(rest[..cut].to_string());
+        rest = &rest[cut..];
+    }
+    if !rest.is_empty() {
+        pieces.push(rest.to_string());
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_keeps_code_blocks_together() {
+        let text = "Intro line.\n\n```\nfn a() {}\nfn b() {}\n```\nOutro.";
+        let chunks = chunk_text(text, 30);
+        assert_eq!(
+            chunks,
+            ["Intro line.", "```\nfn a() {}\nfn b() {}\n```", "Outro."]
+        );
+        assert_eq!(chunk_text("short", 30), ["short"]);
+    }
+
+    #[test]
+    fn test_chunk_splits_long_code_block() {
+        let code = (0..10)
+            .map(|i| format!("line {i}\n"))
+            .collect::<Vec<_>>()
+            .concat();
+        let text = format!("```py\n{code}```\n");
+        let chunks = chunk_text(&text, 30);
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(char_len(chunk) <= 30, "{chunk:?}");
+            assert!(chunk.starts_with("```py\n") && chunk.ends_with("```"));
+        }
+        let joined: String = chunks
+            .iter()
+            .map(|c| c.trim_start_matches("```py\n").trim_end_matches("```"))
+            .collect();
+        assert_eq!(joined, code);
+    }
+
+    #[test]
+    fn test_chunk_prefers_paragraphs() {
+        let text = "First paragraph here.\n\nSecond a.\nSecond b.";
+        assert_eq!(
+            chunk_text(text, 36),
+            ["First paragraph here.", "Second a.\nSecond b."]
+        );
+    }
+
+    #[test]
+    fn test_chunk_splits_long_line_after_sentence() {
+        let chunks = chunk_text("One two three. Four five six seven", 24);
+        assert_eq!(chunks, ["One two three.", "Four five six seven"]);
+
+        let chunks = chunk_text("aaaa bbbb cccc dddd", 10);
+        assert_eq!(chunks, ["aaaa bbbb", "cccc dddd"]);
+        assert!(
+            chunk_text(&"x".repeat(25), 10)
+                .iter()
+                .all(|c| char_len(c) <= 10)
+        );
+    }
+
+    #[test]
+    fn test_chunk_numbered() {
+        assert_eq!(chunk_numbered("short", 30), ["short"]);
+
+        let text = "alpha beta gamma delta epsilon zeta eta theta";
+        let parts = chunk_numbered(text, 20);
+        let total = parts.len();
+        assert!(total > 1);
+        for (i, part) in parts.iter().enumerate() {
+            assert!(char_len(part) <= 20, "{part:?}");
+            assert!(part.ends_with(&format!("\n\n({}/{total})", i + 1)));
+        }
+
+        // No room for numbers
+        assert!(
+            chunk_numbered("aaaa bbbb", 5)
+                .iter()
+                .all(|p| !p.contains('('))
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Citation rendering.
+//!
+//! Agent responses reference sources as `[n]`. Each channel gets the
+//! citation list rendered in a form its clients display well.
+
+use openclaw_core::types::{ChannelId, Citation};
+
+/// How citations are rendered for a channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CitationStyle {
+    /// Markdown footnote links (`[1]: https://...`).
+    MarkdownFootnotes,
+    /// Slack `<url|title>` links.
+    SlackLinks,
+    /// Plain numbered list with raw URLs.
+    NumberedList,
+}
+
+impl CitationStyle {
+    /// Pick the citation style for a channel.
+    #[must_use]
+    pub fn for_channel(channel: &ChannelId) -> Self {
+        match channel.as_ref() {
+            "slack" => Self::SlackLinks,
+            "discord" | "telegram" | "matrix" => Self::MarkdownFootnotes,
+            _ => Self::NumberedList,
+        }
+    }
+}
+
+/// Append a sources section to `text` in the given style.
+///
+/// Returns `text` unchanged when there are no citations.
+#[must_use]
+pub fn render_citations(text: &str, citations: &[Citation], style: CitationStyle) -> String {
+    if citations.is_empty() {
+        return text.to_string();
+    }
+
+    let mut sorted: Vec<&Citation> = citations.iter().collect();
+    sorted.sort_by_key(|c| c.index);
+
+    let lines: Vec<String> = sorted
+        .into_iter()
+        .map(|c| {
+            let index = c.index;
+            match (style, &c.url) {
+                (CitationStyle::MarkdownFootnotes, Some(url)) => {
+                    format!("[{index}] [{}]({url})", c.title)
+                }
+                (CitationStyle::SlackLinks, Some(url)) => format!("[{index}] <{url}|{}>", c.title),
+                (CitationStyle::NumberedList, Some(url)) => format!("{index}. {} - {url}", c.title),
+                (CitationStyle::NumberedList, None) => format!("{index}. {}", c.title),
+                (_, None) => format!("[{index}] {}", c.title),
+            }
+        })
+        .collect();
+
+    let heading = match style {
+        CitationStyle::MarkdownFootnotes => "**Sources**",
+        CitationStyle::SlackLinks => "*Sources*",
+        CitationStyle::NumberedList => "Sources:",
+    };
+
+    format!("{}\n\n{heading}\n{}", text.trim_end(), lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn citations() -> Vec<Citation> {
+        vec![
+            Citation {
+                index: 2,
+                title: "Tokio".to_string(),
+                url: Some("https://tokio.rs".to_string()),
+                snippet: None,
+            },
+            Citation {
+                index: 1,
+                title: "Internal notes".to_string(),
+                url: None,
+                snippet: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_style_for_channel() {
+        assert_eq!(
+            CitationStyle::for_channel(&ChannelId::slack()),
+            CitationStyle::SlackLinks
+        );
+        assert_eq!(
+            CitationStyle::for_channel(&ChannelId::telegram()),
+            CitationStyle::MarkdownFootnotes
+        );
+        assert_eq!(
+            CitationStyle::for_channel(&ChannelId::signal()),
+            CitationStyle::NumberedList
+        );
+    }
+
+    #[test]
+    fn test_render_styles() {
+        let text = "Tokio is async [2], see notes [1].";
+
+        let md = render_citations(text, &citations(), CitationStyle::MarkdownFootnotes);
+        assert!(md.ends_with("[1] Internal notes\n[2] [Tokio](https://tokio.rs)"));
+
+        let slack = render_citations(text, &citations(), CitationStyle::SlackLinks);
+        assert!(slack.contains("[2] <https://tokio.rs|Tokio>"));
+
+        let plain = render_citations(text, &citations(), CitationStyle::NumberedList);
+        assert!(plain.contains("Sources:\n1. Internal notes\n2. Tokio - https://tokio.rs"));
+    }
+
+    #[test]
+    fn test_render_without_citations() {
+        assert_eq!(
+            render_citations("hi", &[], CitationStyle::NumberedList),
+            "hi"
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Chat commands.
+//!
+//! People can manage their session from the chat with slash commands,
+//! which the gateway answers without calling the model.
+
+use openclaw_core::config::ChatCommandsConfig;
+use openclaw_core::types::{ChannelId, PeerId};
+
+/// A slash command sent in chat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatCommand<'a> {
+    /// Forget the conversation so far.
+    Reset,
+    /// Show the session's model, or switch it to the one given.
+    Model(Option<&'a str>),
+    /// Show the session's token usage.
+    Usage,
+    /// List the available commands.
+    Help,
+}
+
+impl ChatCommand<'_> {
+    /// Names of all commands.
+    pub const NAMES: [&'static str; 4] = ["reset", "model", "usage", "help"];
+
+    /// The command's name, without the slash.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Reset => "reset",
+            Self::Model(_) => "model",
+            Self::Usage => "usage",
+            Self::Help => "help",
+        }
+    }
+}
+
+/// Parse a chat command. Other text, including unknown commands, is
+/// `None` and goes to the agent.
+///
+/// Accepts `/reset`, `/model [name]`, `/usage`, and `/help`, also with the
+/// `@botname` suffix Telegram adds in groups (`/help@claw_bot`).
+#[must_use]
+pub fn parse_chat_command(text: &str) -> Option<ChatCommand<'_>> {
+    let rest = text.trim().strip_prefix('/')?;
+    let (name, arg) = rest
+        .split_once(char::is_whitespace)
+        .map_or((rest, ""), |(name, arg)| (name, arg.trim()));
+    let name = name.split('@').next().unwrap_or(name);
+
+    match name.to_ascii_lowercase().as_str() {
+        "reset" => Some(ChatCommand::Reset),
+        "model" => Some(ChatCommand::Model(Some(arg).filter(|a| !a.is_empty()))),
+        "usage" => Some(ChatCommand::Usage),
+        "help" => Some(ChatCommand::Help),
+        _ => None,
+    }
+}
+
+/// Whether a command is run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandAccess {
+    /// Run the command.
+    Run,
+    /// The command is turned off; the text goes to the agent.
+    Off,
+    /// The sender may not use commands.
+    Denied,
+}
+
+/// Decides which chat commands run, for whom.
+#[derive(Debug, Clone, Default)]
+pub struct ChatCommands {
+    config: ChatCommandsConfig,
+}
+
+impl ChatCommands {
+    /// Create from configuration.
+    #[must_use]
+    pub const fn new(config: ChatCommandsConfig) -> Self {
+        Self { config }
+    }
+
+    /// Whether `command` from `peer` on `channel` runs for `agent`.
+    #[must_use]
+    pub fn access(
+        &self,
+        agent: &str,
+        channel: &ChannelId,
+        peer: &PeerId,
+        command: &ChatCommand<'_>,
+    ) -> CommandAccess {
+        if !self.config.enabled_for(agent, command.name()) {
+            return CommandAccess::Off;
+        }
+        let allow = self.config.allow_for(agent);
+        let allowed = allow.is_empty()
+            || allow.iter().any(|entry| {
+                (entry.channel == "*" || entry.channel == channel.as_ref())
+                    && (entry.peer_id == "*" || entry.peer_id == peer.as_ref())
+            });
+        if allowed {
+            CommandAccess::Run
+        } else {
+            CommandAccess::Denied
+        }
+    }
+
+    /// Models `/model` may switch sessions of `agent` to.
+    #[must_use]
+    pub fn models(&self, agent: &str) -> &[String] {
+        self.config.models_for(agent)
+    }
+
+    /// Commands interpreted for `agent`, for `/help`.
+    #[must_use]
+    pub fn available(&self, agent: &str) -> Vec<&'static str> {
+        ChatCommand::NAMES
+            .into_iter()
+            .filter(|name| self.config.enabled_for(agent, name))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openclaw_core::config::{AgentChatCommands, AllowlistEntry};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_parse_chat_command() {
+        assert_eq!(parse_chat_command(" /reset "), Some(ChatCommand::Reset));
+        assert_eq!(parse_chat_command("/model"), Some(ChatCommand::Model(None)));
+        assert_eq!(
+            parse_chat_command("/model  claude-haiku-4-5 "),
+            Some(ChatCommand::Model(Some("claude-haiku-4-5")))
+        );
+        assert_eq!(
+            parse_chat_command("/HELP@claw_bot"),
+            Some(ChatCommand::Help)
+        );
+        assert_eq!(parse_chat_command("/usage"), Some(ChatCommand::Usage));
+        assert_eq!(parse_chat_command("/resetting"), None);
+        assert_eq!(parse_chat_command("/feedback up"), None);
+        assert_eq!(parse_chat_command("please /reset"), None);
+    }
+
+    #[test]
+    fn test_command_access() {
+        let commands = ChatCommands::new(ChatCommandsConfig {
+            allow: vec![AllowlistEntry {
+                channel: "telegram".to_string(),
+                peer_id: "42".to_string(),
+                label: None,
+            }],
+            agents: HashMap::from([(
+                "support".to_string(),
+                AgentChatCommands {
+                    disabled: Some(vec!["reset".to_string()]),
+                    ..AgentChatCommands::default()
+                },
+            )]),
+            ..ChatCommandsConfig::default()
+        });
+        let telegram = ChannelId::telegram();
+
+        let access = |agent, peer| {
+            commands.access(agent, &telegram, &PeerId::new(peer), &ChatCommand::Reset)
+        };
+        assert_eq!(access("default", "42"), CommandAccess::Run);
+        assert_eq!(access("default", "7"), CommandAccess::Denied);
+        assert_eq!(access("support", "42"), CommandAccess::Off);
+        assert_eq!(commands.available("support"), ["model", "usage", "help"]);
+    }
+}
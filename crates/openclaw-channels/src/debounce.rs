@@ -0,0 +1,384 @@
+//! Inbound message debouncing.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+use openclaw_core::config::DebounceConfig;
+use openclaw_core::types::Attachment;
+
+/// Outcome of submitting a message to the debouncer.
+#[derive(Debug)]
+pub enum Debounced {
+    /// The batch is complete; answer it as one agent turn.
+    Flush(DebouncedBatch),
+    /// The message joined a batch that another caller will answer.
+    Coalesced,
+}
+
+/// Messages to answer together.
+#[derive(Debug)]
+pub struct DebouncedBatch {
+    /// Message texts, joined by newlines.
+    pub message: String,
+    /// Attachments from every message in the batch.
+    pub attachments: Vec<Attachment>,
+    /// Number of messages in the batch.
+    pub count: usize,
+    /// Marks the session busy until the turn is answered.
+    pub turn: TurnGuard,
+}
+
+/// Held while an agent answers a batch; dropping it marks the session idle.
+#[derive(Debug)]
+pub struct TurnGuard {
+    sessions: Option<(Arc<SessionMap>, String)>,
+}
+
+impl Drop for TurnGuard {
+    fn drop(&mut self) {
+        let Some((sessions, key)) = self.sessions.take() else {
+            return;
+        };
+        let mut sessions = lock(&sessions);
+        if let Some(state) = sessions.get_mut(&key) {
+            state.busy = false;
+            state.notify.notify_waiters();
+            if state.pending.is_none() {
+                sessions.remove(&key);
+            }
+        }
+    }
+}
+
+/// Debounce state per session key.
+type SessionMap = Mutex<HashMap<String, SessionState>>;
+
+#[derive(Debug, Default)]
+struct SessionState {
+    pending: Option<Batch>,
+    busy: bool,
+    notify: Arc<Notify>,
+}
+
+#[derive(Debug)]
+struct Batch {
+    messages: Vec<String>,
+    attachments: Vec<Attachment>,
+    first: Instant,
+    last: Instant,
+    /// The agent was answering the previous batch while this one waited.
+    held: bool,
+}
+
+/// Coalesces rapid consecutive messages in a session into one agent turn.
+///
+/// The first message of a batch waits until the peer has been quiet for the
+/// window, or for at most `max_wait`. Messages arriving meanwhile join the
+/// batch. If the agent is still answering the session's previous batch,
+/// the new one is flushed as soon as the agent is idle again.
+#[derive(Debug)]
+pub struct InboundDebouncer {
+    window: Duration,
+    max_wait: Duration,
+    channels: HashMap<String, Duration>,
+    agents: HashMap<String, Duration>,
+    sessions: Arc<SessionMap>,
+}
+
+impl InboundDebouncer {
+    /// Create a debouncer from configuration.
+    #[must_use]
+    pub fn new(config: &DebounceConfig) -> Self {
+        let millis = |map: &HashMap<String, u64>| -> HashMap<String, Duration> {
+            map.iter()
+                .map(|(id, ms)| (id.clone(), Duration::from_millis(*ms)))
+                .collect()
+        };
+        Self {
+            window: Duration::from_millis(config.window_ms),
+            max_wait: Duration::from_millis(config.max_wait_ms),
+            channels: millis(&config.channels),
+            agents: millis(&config.agents),
+            sessions: Arc::default(),
+        }
+    }
+
+    /// Create a debouncer that answers every message on its own.
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self::new(&DebounceConfig::default())
+    }
+
+    /// Debounce window for messages on `channel` to `agent_id`.
+    #[must_use]
+    pub fn window(&self, channel: &str, agent_id: &str) -> Duration {
+        self.agents
+            .get(agent_id)
+            .or_else(|| self.channels.get(channel))
+            .copied()
+            .unwrap_or(self.window)
+    }
+
+    /// Submit a message in `session_key`.
+    ///
+    /// Returns [`Debounced::Flush`] to the caller that should answer the
+    /// batch, once it is complete, and [`Debounced::Coalesced`] right away
+    /// to callers whose message joined it.
+    pub async fn submit(
+        &self,
+        session_key: &str,
+        window: Duration,
+        message: &str,
+        attachments: Vec<Attachment>,
+    ) -> Debounced {
+        if window.is_zero() {
+            return Debounced::Flush(DebouncedBatch {
+                message: message.to_string(),
+                attachments,
+                count: 1,
+                turn: TurnGuard { sessions: None },
+            });
+        }
+
+        let notify = {
+            let mut sessions = lock(&self.sessions);
+            let state = sessions.entry(session_key.to_string()).or_default();
+            let now = Instant::now();
+            if let Some(batch) = &mut state.pending {
+                batch.messages.push(message.to_string());
+                batch.attachments.extend(attachments);
+                batch.last = now;
+                state.notify.notify_waiters();
+                return Debounced::Coalesced;
+            }
+            state.pending = Some(Batch {
+                messages: vec![message.to_string()],
+                attachments,
+                first: now,
+                last: now,
+                held: false,
+            });
+            let notify = state.notify.clone();
+            drop(sessions);
+            notify
+        };
+        let mut waiting = Waiting {
+            sessions: &self.sessions,
+            key: session_key,
+            done: false,
+        };
+
+        loop {
+            // Register before checking so a wake-up in between is not lost
+            let notified = notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            let deadline = {
+                let mut sessions = lock(&self.sessions);
+                let Some(state) = sessions.get_mut(session_key) else {
+                    return Debounced::Coalesced;
+                };
+                let Some(batch) = &mut state.pending else {
+                    return Debounced::Coalesced;
+                };
+                let cap = batch.first + self.max_wait;
+                let deadline = (batch.last + window).min(cap);
+                let now = Instant::now();
+                let ready = if state.busy {
+                    batch.held = true;
+                    now >= cap
+                } else {
+                    batch.held || now >= deadline
+                };
+                if let Some(batch) = state.pending.take_if(|_| ready) {
+                    state.busy = true;
+                    waiting.done = true;
+                    return Debounced::Flush(DebouncedBatch {
+                        message: batch.messages.join("\n"),
+                        attachments: batch.attachments,
+                        count: batch.messages.len(),
+                        turn: TurnGuard {
+                            sessions: Some((self.sessions.clone(), session_key.to_string())),
+                        },
+                    });
+                }
+                let deadline = if state.busy { cap } else { deadline };
+                drop(sessions);
+                deadline
+            };
+
+            tokio::select! {
+                () = notified => {}
+                () = tokio::time::sleep_until(deadline) => {}
+            }
+        }
+    }
+
+    /// Number of sessions with messages waiting to be answered.
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        lock(&self.sessions)
+            .values()
+            .filter(|s| s.pending.is_some())
+            .count()
+    }
+}
+
+/// Drops the batch if its caller stops waiting, so later messages start a
+/// new one instead of joining a batch nobody will answer.
+struct Waiting<'a> {
+    sessions: &'a SessionMap,
+    key: &'a str,
+    done: bool,
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+        let mut sessions = lock(self.sessions);
+        if let Some(state) = sessions.get_mut(self.key) {
+            state.pending = None;
+            if !state.busy {
+                sessions.remove(self.key);
+            }
+        }
+    }
+}
+
+impl Default for InboundDebouncer {
+    fn default() -> Self {
+        Self::disabled()
+    }
+}
+
+fn lock(sessions: &SessionMap) -> MutexGuard<'_, HashMap<String, SessionState>> {
+    sessions
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_millis(500);
+
+    fn debouncer() -> Arc<InboundDebouncer> {
+        Arc::new(InboundDebouncer::new(&DebounceConfig {
+            window_ms: 500,
+            max_wait_ms: 2000,
+            ..DebounceConfig::default()
+        }))
+    }
+
+    fn flushed(result: Debounced) -> DebouncedBatch {
+        match result {
+            Debounced::Flush(batch) => batch,
+            Debounced::Coalesced => panic!("expected a flush"),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_coalesces_rapid_messages() {
+        let debouncer = debouncer();
+        let leader = tokio::spawn({
+            let debouncer = debouncer.clone();
+            async move { debouncer.submit("s1", WINDOW, "hi", vec![]).await }
+        });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(matches!(
+            debouncer
+                .submit("s1", WINDOW, "are you there", vec![])
+                .await,
+            Debounced::Coalesced
+        ));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(matches!(
+            debouncer.submit("s1", WINDOW, "?", vec![]).await,
+            Debounced::Coalesced
+        ));
+        assert_eq!(debouncer.pending(), 1);
+
+        let batch = flushed(leader.await.unwrap());
+        assert_eq!(batch.message, "hi\nare you there\n?");
+        assert_eq!(batch.count, 3);
+        assert_eq!(debouncer.pending(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_max_wait_caps_batch() {
+        let debouncer = debouncer();
+        let started = Instant::now();
+        let leader = tokio::spawn({
+            let debouncer = debouncer.clone();
+            async move { debouncer.submit("s1", WINDOW, "0", vec![]).await }
+        });
+        for i in 1..10 {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            if leader.is_finished() {
+                break;
+            }
+            assert!(matches!(
+                debouncer.submit("s1", WINDOW, &i.to_string(), vec![]).await,
+                Debounced::Coalesced
+            ));
+        }
+        let batch = flushed(leader.await.unwrap());
+        assert!(batch.count > 1);
+        assert!(started.elapsed() <= Duration::from_millis(2100));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_flushes_when_agent_idle() {
+        let debouncer = debouncer();
+        let first = flushed(debouncer.submit("s1", WINDOW, "first", vec![]).await);
+
+        // Agent is busy with `first`; the next batch waits past its window
+        let next = tokio::spawn({
+            let debouncer = debouncer.clone();
+            async move { debouncer.submit("s1", WINDOW, "second", vec![]).await }
+        });
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert!(!next.is_finished());
+
+        let idle_at = Instant::now();
+        drop(first);
+        let batch = flushed(next.await.unwrap());
+        assert_eq!(batch.message, "second");
+        assert!(idle_at.elapsed() < WINDOW);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_and_overrides() {
+        let debouncer = InboundDebouncer::disabled();
+        assert!(debouncer.window("telegram", "default").is_zero());
+        let batch = flushed(debouncer.submit("s1", Duration::ZERO, "hi", vec![]).await);
+        assert_eq!(batch.count, 1);
+
+        let debouncer = InboundDebouncer::new(&DebounceConfig {
+            window_ms: 100,
+            channels: HashMap::from([("telegram".to_string(), 200)]),
+            agents: HashMap::from([("support".to_string(), 300)]),
+            ..DebounceConfig::default()
+        });
+        assert_eq!(
+            debouncer.window("discord", "default"),
+            Duration::from_millis(100)
+        );
+        assert_eq!(
+            debouncer.window("telegram", "default"),
+            Duration::from_millis(200)
+        );
+        assert_eq!(
+            debouncer.window("telegram", "support"),
+            Duration::from_millis(300)
+        );
+    }
+}
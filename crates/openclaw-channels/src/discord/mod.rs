@@ -8,12 +8,13 @@ use tokio::sync::RwLock;
 
 use openclaw_core::secrets::ApiKey;
 use openclaw_core::types::{
-    Attachment, AttachmentKind, ChannelId, DeliveryResult, Message, PeerId, PeerType,
+    Attachment, AttachmentKind, ChannelId, DeliveryResult, Message, MessageReaction, PeerId,
+    PeerType,
 };
 
 use crate::traits::{
     Channel, ChannelCapabilities, ChannelContext, ChannelError, ChannelInbound, ChannelOutbound,
-    ChannelProbe, DeliveryMode, OutboundContext,
+    ChannelProbe, ChannelReactions, DeliveryMode, OutboundContext,
 };
 
 const DISCORD_API_BASE: &str = "https://discord.com/api/v10";
@@ -118,6 +119,17 @@ impl DiscordChannel {
 
         Ok(())
     }
+
+    /// Endpoint for the bot's own reaction on a message.
+    ///
+    /// Custom emoji are passed as `name:id`.
+    fn own_reaction_endpoint(ctx: &OutboundContext, message_id: &str, emoji: &str) -> String {
+        format!(
+            "/channels/{}/messages/{message_id}/reactions/{}/@me",
+            ctx.chat_id,
+            urlencoding::encode(emoji.trim_matches(|c| c == '<' || c == '>'))
+        )
+    }
 }
 
 #[async_trait]
@@ -272,6 +284,28 @@ impl ChannelOutbound for DiscordChannel {
         })
     }
 
+    async fn add_reaction(
+        &self,
+        ctx: OutboundContext,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<(), ChannelError> {
+        let endpoint = Self::own_reaction_endpoint(&ctx, message_id, emoji);
+        self.call_no_response(reqwest::Method::PUT, &endpoint, None::<&()>)
+            .await
+    }
+
+    async fn remove_reaction(
+        &self,
+        ctx: OutboundContext,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<(), ChannelError> {
+        let endpoint = Self::own_reaction_endpoint(&ctx, message_id, emoji);
+        self.call_no_response(reqwest::Method::DELETE, &endpoint, None::<&()>)
+            .await
+    }
+
     fn text_chunk_limit(&self) -> usize {
         2000 // Discord message limit
     }
@@ -307,8 +341,10 @@ impl ChannelInbound for DiscordChannel {
             PeerType::Dm
         };
 
-        let state = futures::executor::block_on(self.state.read());
-        let account_id = state.bot_id.clone().unwrap_or_default();
+        let account_id = futures::executor::block_on(self.state.read())
+            .bot_id
+            .clone()
+            .unwrap_or_default();
 
         // Convert attachments
         let attachments = msg
@@ -383,6 +419,47 @@ impl ChannelInbound for DiscordChannel {
     }
 }
 
+impl ChannelReactions for DiscordChannel {
+    type RawReaction = DiscordReactionEvent;
+
+    fn normalize_reaction(&self, raw: Self::RawReaction) -> Result<MessageReaction, ChannelError> {
+        let raw_value = serde_json::to_value(&raw).unwrap_or_default();
+
+        let removed = match raw.t.as_deref() {
+            Some("MESSAGE_REACTION_ADD") => false,
+            Some("MESSAGE_REACTION_REMOVE") => true,
+            _ => return Err(ChannelError::Config("Not a reaction event".to_string())),
+        };
+        let reaction = raw
+            .d
+            .ok_or_else(|| ChannelError::Config("No reaction data in event".to_string()))?;
+
+        // Custom emoji are identified by `name:id`, Unicode emoji by name
+        let emoji = match (reaction.emoji.name, reaction.emoji.id) {
+            (Some(name), Some(id)) => format!("{name}:{id}"),
+            (Some(name), None) => name,
+            (None, _) => return Err(ChannelError::Config("Reaction has no emoji".to_string())),
+        };
+
+        let account_id = futures::executor::block_on(self.state.read())
+            .bot_id
+            .clone()
+            .unwrap_or_default();
+
+        Ok(MessageReaction {
+            channel: ChannelId::discord(),
+            account_id,
+            peer_id: PeerId::new(reaction.user_id),
+            chat_id: reaction.channel_id,
+            message_id: reaction.message_id,
+            emoji,
+            removed,
+            timestamp: chrono::Utc::now(),
+            raw: Some(raw_value),
+        })
+    }
+}
+
 // Discord API types
 
 /// Discord user object.
@@ -541,6 +618,39 @@ pub struct DiscordGatewayEvent {
     pub d: Option<DiscordMessage>,
 }
 
+/// Gateway reaction event (`MESSAGE_REACTION_ADD` / `MESSAGE_REACTION_REMOVE`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscordReactionEvent {
+    /// Event type.
+    pub t: Option<String>,
+    /// Event data.
+    pub d: Option<DiscordReaction>,
+}
+
+/// Reaction event data.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscordReaction {
+    /// User who reacted.
+    pub user_id: String,
+    /// Channel ID.
+    pub channel_id: String,
+    /// Message ID.
+    pub message_id: String,
+    /// Guild ID (if in a guild).
+    pub guild_id: Option<String>,
+    /// Emoji used.
+    pub emoji: DiscordEmoji,
+}
+
+/// Discord emoji object.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscordEmoji {
+    /// Custom emoji ID (`None` for Unicode emoji).
+    pub id: Option<String>,
+    /// Emoji name, or the Unicode emoji itself.
+    pub name: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -567,4 +677,34 @@ mod tests {
         let channel = DiscordChannel::new(ApiKey::new("test".to_string()));
         assert_eq!(channel.text_chunk_limit(), 2000);
     }
+
+    #[test]
+    fn test_normalize_reaction() {
+        let channel = DiscordChannel::new(ApiKey::new("test".to_string()));
+        let event: DiscordReactionEvent = serde_json::from_value(serde_json::json!({
+            "t": "MESSAGE_REACTION_REMOVE",
+            "d": {
+                "user_id": "42",
+                "channel_id": "100",
+                "message_id": "200",
+                "emoji": { "id": "555", "name": "party" }
+            }
+        }))
+        .unwrap();
+
+        let reaction = channel.normalize_reaction(event).unwrap();
+        assert_eq!(reaction.message_id, "200");
+        assert_eq!(reaction.emoji, "party:555");
+        assert!(reaction.removed);
+
+        let ctx = OutboundContext {
+            chat_id: "100".to_string(),
+            reply_to: None,
+            thread_id: None,
+        };
+        assert_eq!(
+            DiscordChannel::own_reaction_endpoint(&ctx, "200", "👍"),
+            "/channels/100/messages/200/reactions/%F0%9F%91%8D/@me"
+        );
+    }
 }
@@ -0,0 +1,91 @@
+//! Feedback normalization.
+//!
+//! Maps platform reactions and `/feedback` commands onto a common rating.
+
+use openclaw_core::events::FeedbackRating;
+
+/// Feedback parsed from a `/feedback` command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedbackCommand {
+    /// Rating.
+    pub rating: FeedbackRating,
+    /// Optional comment following the rating.
+    pub comment: Option<String>,
+}
+
+/// Map a reaction emoji or shortcode to a rating.
+///
+/// Handles skin-tone variants and the shortcodes used by Slack/Discord.
+#[must_use]
+pub fn rating_from_reaction(reaction: &str) -> Option<FeedbackRating> {
+    // Strip skin-tone modifiers and variation selectors
+    let base: String = reaction
+        .trim()
+        .trim_matches(':')
+        .chars()
+        .filter(|c| !('\u{1F3FB}'..='\u{1F3FF}').contains(c) && *c != '\u{FE0F}')
+        .collect();
+
+    match base.as_str() {
+        "👍" | "+1" | "thumbsup" | "thumbs_up" | "like" => Some(FeedbackRating::Positive),
+        "👎" | "-1" | "thumbsdown" | "thumbs_down" | "dislike" => Some(FeedbackRating::Negative),
+        _ => None,
+    }
+}
+
+/// Parse a `/feedback` command.
+///
+/// Accepts `/feedback up|down|good|bad|👍|👎 [comment]`.
+#[must_use]
+pub fn parse_feedback_command(text: &str) -> Option<FeedbackCommand> {
+    let rest = text.trim().strip_prefix("/feedback")?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let mut parts = rest.trim().splitn(2, char::is_whitespace);
+    let token = parts.next()?.to_lowercase();
+
+    let rating = match token.as_str() {
+        "up" | "good" | "yes" | "+" => FeedbackRating::Positive,
+        "down" | "bad" | "no" | "-" => FeedbackRating::Negative,
+        other => rating_from_reaction(other)?,
+    };
+
+    let comment = parts
+        .next()
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(String::from);
+
+    Some(FeedbackCommand { rating, comment })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reactions() {
+        assert_eq!(rating_from_reaction("👍"), Some(FeedbackRating::Positive));
+        assert_eq!(rating_from_reaction("👍🏽"), Some(FeedbackRating::Positive));
+        assert_eq!(rating_from_reaction(":+1:"), Some(FeedbackRating::Positive));
+        assert_eq!(rating_from_reaction("👎"), Some(FeedbackRating::Negative));
+        assert_eq!(rating_from_reaction("🎉"), None);
+    }
+
+    #[test]
+    fn test_feedback_command() {
+        let cmd = parse_feedback_command("/feedback down too verbose").unwrap();
+        assert_eq!(cmd.rating, FeedbackRating::Negative);
+        assert_eq!(cmd.comment.as_deref(), Some("too verbose"));
+
+        let cmd = parse_feedback_command("/feedback 👍").unwrap();
+        assert_eq!(cmd.rating, FeedbackRating::Positive);
+        assert!(cmd.comment.is_none());
+
+        assert!(parse_feedback_command("/feedback").is_none());
+        assert!(parse_feedback_command("/feedbackup").is_none());
+        assert!(parse_feedback_command("hello").is_none());
+    }
+}
@@ -0,0 +1,478 @@
+//! Outbound text formatting.
+//!
+//! Agents reply in Markdown. Before delivery each reply is converted to the
+//! channel's native markup and split into messages that fit the channel's
+//! length limit (see [`chunk_text`]).
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use openclaw_core::config::{ChannelFormatConfig, OutboundFormat};
+use openclaw_core::types::{ChannelId, DeliveryResult};
+
+use crate::chunking::{blocks, char_len, chunk_text, is_fence, numbered};
+use crate::traits::{ChannelError, ChannelOutbound, OutboundContext};
+
+/// Converts Markdown replies for a channel.
+pub trait OutboundFormatter: Send + Sync {
+    /// Convert `markdown` to the text that is sent.
+    fn format(&self, markdown: &str) -> String;
+}
+
+impl OutboundFormatter for OutboundFormat {
+    fn format(&self, markdown: &str) -> String {
+        let markup = match self {
+            Self::Markdown => return markdown.to_string(),
+            Self::TelegramHtml => &TELEGRAM_HTML,
+            Self::SlackMrkdwn => &SLACK_MRKDWN,
+            Self::Whatsapp => &WHATSAPP,
+            Self::Plain => &PLAIN,
+        };
+        convert(markdown, markup)
+    }
+}
+
+/// The format a channel's clients display natively.
+#[must_use]
+pub fn default_format(channel: &ChannelId) -> OutboundFormat {
+    match channel.as_ref() {
+        "telegram" => OutboundFormat::TelegramHtml,
+        "slack" => OutboundFormat::SlackMrkdwn,
+        "whatsapp" => OutboundFormat::Whatsapp,
+        "signal" => OutboundFormat::Plain,
+        _ => OutboundFormat::Markdown,
+    }
+}
+
+/// Formats and chunks replies before delivery.
+///
+/// Each channel uses, in order: a formatter registered with
+/// [`OutboundPipeline::with_formatter`], the format set in config, or
+/// [`default_format`].
+#[derive(Clone, Default)]
+pub struct OutboundPipeline {
+    formatters: HashMap<String, Arc<dyn OutboundFormatter>>,
+    config: HashMap<String, ChannelFormatConfig>,
+}
+
+impl OutboundPipeline {
+    /// Create a pipeline with per-channel config overrides.
+    #[must_use]
+    pub fn new(config: HashMap<String, ChannelFormatConfig>) -> Self {
+        Self {
+            formatters: HashMap::new(),
+            config,
+        }
+    }
+
+    /// Use a custom formatter for a channel.
+    #[must_use]
+    pub fn with_formatter(
+        mut self,
+        channel: impl Into<String>,
+        formatter: Arc<dyn OutboundFormatter>,
+    ) -> Self {
+        self.formatters.insert(channel.into(), formatter);
+        self
+    }
+
+    /// The same pipeline with new config overrides; custom formatters are
+    /// kept.
+    #[must_use]
+    pub fn reconfigured(&self, config: HashMap<String, ChannelFormatConfig>) -> Self {
+        Self {
+            formatters: self.formatters.clone(),
+            config,
+        }
+    }
+
+    /// Format `text` for `channel` and split it into messages of at most
+    /// `limit` characters (or the configured chunk limit, if lower),
+    /// numbered unless the channel's config turns that off.
+    #[must_use]
+    pub fn prepare(&self, channel: &str, text: &str, limit: usize) -> Vec<String> {
+        let overrides = self.config.get(channel);
+        let limit = overrides
+            .and_then(|c| c.chunk_limit)
+            .map_or(limit, |l| l.min(limit))
+            .max(1);
+        let builtin = overrides
+            .and_then(|c| c.format)
+            .unwrap_or_else(|| default_format(&ChannelId::new(channel)));
+        let formatter: &dyn OutboundFormatter = match self.formatters.get(channel) {
+            Some(custom) => custom.as_ref(),
+            None => &builtin,
+        };
+
+        let number_parts = overrides.is_none_or(|c| c.number_parts);
+        if number_parts {
+            numbered(limit, |limit| split_formatted(formatter, text, limit))
+        } else {
+            split_formatted(formatter, text, limit)
+        }
+    }
+
+    /// Format and send `text`, one message per chunk. Only the first
+    /// message replies to `ctx.reply_to`.
+    ///
+    /// Returns the result for the first message.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first delivery error; earlier chunks stay delivered.
+    pub async fn send(
+        &self,
+        channel: &dyn ChannelOutbound,
+        mut ctx: OutboundContext,
+        text: &str,
+    ) -> Result<DeliveryResult, ChannelError> {
+        let mut first = None;
+        for message in self.prepare(channel.id(), text, channel.text_chunk_limit()) {
+            let result = channel.send_text(ctx.clone(), &message).await?;
+            ctx.reply_to = None;
+            first.get_or_insert(result);
+        }
+        match first {
+            Some(result) => Ok(result),
+            // Nothing to send after formatting; let the channel reject it
+            None => channel.send_text(ctx, text).await,
+        }
+    }
+}
+
+/// Chunk Markdown, then format each chunk. Markup can make a chunk longer,
+/// so a chunk that no longer fits is split again more finely.
+fn split_formatted(formatter: &dyn OutboundFormatter, text: &str, limit: usize) -> Vec<String> {
+    let mut pending: Vec<String> = chunk_text(text, limit).into_iter().rev().collect();
+    let mut messages = Vec::new();
+    while let Some(chunk) = pending.pop() {
+        let message = formatter.format(&chunk);
+        let message_len = char_len(&message);
+        let chunk_len = char_len(&chunk);
+        if message_len <= limit || chunk_len <= 1 {
+            messages.push(message);
+            continue;
+        }
+        let target = (chunk_len * limit / message_len).clamp(1, chunk_len - 1);
+        pending.extend(chunk_text(&chunk, target).into_iter().rev());
+    }
+    messages
+}
+
+/// Markup for one output format.
+struct Markup {
+    escape: fn(&str) -> String,
+    bold: (&'static str, &'static str),
+    italic: (&'static str, &'static str),
+    strike: (&'static str, &'static str),
+    code: (&'static str, &'static str),
+    link: fn(&str, &str) -> String,
+    code_block: fn(&str, &str) -> String,
+    bullet: &'static str,
+}
+
+const TELEGRAM_HTML: Markup = Markup {
+    escape: escape_html,
+    bold: ("<b>", "</b>"),
+    italic: ("<i>", "</i>"),
+    strike: ("<s>", "</s>"),
+    code: ("<code>", "</code>"),
+    link: |text, url| {
+        format!(
+            "<a href=\"{}\">{text}</a>",
+            escape_html(url).replace('"', "&quot;")
+        )
+    },
+    code_block: |lang, body| {
+        if lang.is_empty() {
+            format!("<pre>{}</pre>", escape_html(body))
+        } else {
+            format!(
+                "<pre><code class=\"language-{}\">{}</code></pre>",
+                escape_html(lang),
+                escape_html(body)
+            )
+        }
+    },
+    bullet: "• ",
+};
+
+const SLACK_MRKDWN: Markup = Markup {
+    escape: escape_html,
+    bold: ("*", "*"),
+    italic: ("_", "_"),
+    strike: ("~", "~"),
+    code: ("`", "`"),
+    link: |text, url| format!("<{url}|{text}>"),
+    code_block: |_, body| format!("```\n{}```", escape_html(body)),
+    bullet: "• ",
+};
+
+const WHATSAPP: Markup = Markup {
+    escape: str::to_string,
+    bold: ("*", "*"),
+    italic: ("_", "_"),
+    strike: ("~", "~"),
+    code: ("`", "`"),
+    link: link_with_url,
+    code_block: |_, body| format!("```\n{body}```"),
+    bullet: "• ",
+};
+
+const PLAIN: Markup = Markup {
+    escape: str::to_string,
+    bold: ("", ""),
+    italic: ("", ""),
+    strike: ("", ""),
+    code: ("", ""),
+    link: link_with_url,
+    code_block: |_, body| body.trim_end_matches('\n').to_string(),
+    bullet: "• ",
+};
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn link_with_url(text: &str, url: &str) -> String {
+    if text == url {
+        url.to_string()
+    } else {
+        format!("{text} ({url})")
+    }
+}
+
+/// Convert Markdown to `markup`, block by block.
+fn convert(markdown: &str, markup: &Markup) -> String {
+    let mut out = String::with_capacity(markdown.len());
+    for block in blocks(markdown) {
+        if let Some(fence) = block.fence {
+            let lang = fence.trim_start().trim_start_matches('`').trim();
+            let mut lines = block.text.split_inclusive('\n').skip(1).collect::<Vec<_>>();
+            if lines.last().is_some_and(|l| is_fence(l)) {
+                lines.pop();
+            }
+            out.push_str(&(markup.code_block)(lang, &lines.concat()));
+            if block.text.ends_with('\n') {
+                out.push('\n');
+            }
+        } else {
+            let (line, newline) = block
+                .text
+                .strip_suffix('\n')
+                .map_or((block.text, false), |l| (l, true));
+            out.push_str(&convert_line(line, markup));
+            if newline {
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+/// Convert one line: headings become bold, list markers become bullets.
+fn convert_line(line: &str, markup: &Markup) -> String {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+
+    let heading = trimmed.trim_start_matches('#');
+    if heading.len() < trimmed.len() && heading.starts_with(' ') {
+        let (open, close) = markup.bold;
+        return format!(
+            "{indent}{open}{}{close}",
+            convert_inline(heading.trim(), markup)
+        );
+    }
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(item) = trimmed.strip_prefix(marker) {
+            return format!("{indent}{}{}", markup.bullet, convert_inline(item, markup));
+        }
+    }
+    convert_inline(line, markup)
+}
+
+/// Convert inline spans: code, bold, italic, strikethrough, and links.
+fn convert_inline(text: &str, markup: &Markup) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut plain_start = 0;
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        let span = if rest.starts_with('`') {
+            closed_span(rest, "`").map(|(inner, len)| {
+                let (open, close) = markup.code;
+                (format!("{open}{}{close}", (markup.escape)(inner)), len)
+            })
+        } else if rest.starts_with("**") || rest.starts_with("__") {
+            closed_span(rest, &rest[..2]).map(|(inner, len)| {
+                let (open, close) = markup.bold;
+                (
+                    format!("{open}{}{close}", convert_inline(inner, markup)),
+                    len,
+                )
+            })
+        } else if rest.starts_with("~~") {
+            closed_span(rest, "~~").map(|(inner, len)| {
+                let (open, close) = markup.strike;
+                (
+                    format!("{open}{}{close}", convert_inline(inner, markup)),
+                    len,
+                )
+            })
+        } else if (rest.starts_with('*') || rest.starts_with('_'))
+            && !text[..i].ends_with(|c: char| c.is_alphanumeric())
+        {
+            closed_span(rest, &rest[..1])
+                .filter(|(inner, _)| !inner.starts_with(' ') && !inner.ends_with(' '))
+                .map(|(inner, len)| {
+                    let (open, close) = markup.italic;
+                    (
+                        format!("{open}{}{close}", convert_inline(inner, markup)),
+                        len,
+                    )
+                })
+        } else if rest.starts_with('[') {
+            link_span(rest).map(|(label, url, len)| {
+                let label = convert_inline(label, markup);
+                ((markup.link)(&label, url), len)
+            })
+        } else {
+            None
+        };
+
+        match span {
+            Some((converted, len)) => {
+                out.push_str(&(markup.escape)(&text[plain_start..i]));
+                out.push_str(&converted);
+                i += len;
+                plain_start = i;
+            }
+            None => i += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    out.push_str(&(markup.escape)(&text[plain_start..]));
+    out
+}
+
+/// A span delimited by `delimiter` at the start of `text`: its inner text
+/// and total byte length.
+fn closed_span<'a>(text: &'a str, delimiter: &str) -> Option<(&'a str, usize)> {
+    let after = &text[delimiter.len()..];
+    let end = after.find(delimiter)?;
+    (end > 0).then(|| (&after[..end], delimiter.len() * 2 + end))
+}
+
+/// A `[label](url)` link at the start of `text`: label, URL, and total byte
+/// length.
+fn link_span(text: &str) -> Option<(&str, &str, usize)> {
+    let label_end = text.find("](")?;
+    let url_start = label_end + 2;
+    let url_end = url_start + text[url_start..].find(')')?;
+    let url = &text[url_start..url_end];
+    if url.is_empty() || url.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((&text[1..label_end], url, url_end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPLY: &str = "# Result\n\nUse **bold**, _italic_ and `a < b`.\n- See [docs](https://example.com/x)\n\n```rust\nlet x = 1 < 2;\n```\n";
+
+    #[test]
+    fn test_telegram_html() {
+        assert_eq!(
+            OutboundFormat::TelegramHtml.format(REPLY),
+            "<b>Result</b>\n\nUse <b>bold</b>, <i>italic</i> and <code>a &lt; b</code>.\n• See <a href=\"https://example.com/x\">docs</a>\n\n<pre><code class=\"language-rust\">let x = 1 &lt; 2;\n</code></pre>\n"
+        );
+    }
+
+    #[test]
+    fn test_slack_and_plain() {
+        assert_eq!(
+            OutboundFormat::SlackMrkdwn.format(REPLY),
+            "*Result*\n\nUse *bold*, _italic_ and `a &lt; b`.\n• See <https://example.com/x|docs>\n\n```\nlet x = 1 &lt; 2;\n```\n"
+        );
+        assert_eq!(
+            OutboundFormat::Plain.format(REPLY),
+            "Result\n\nUse bold, italic and a < b.\n• See docs (https://example.com/x)\n\nlet x = 1 < 2;\n"
+        );
+    }
+
+    #[test]
+    fn test_inline_edge_cases() {
+        let plain = OutboundFormat::Plain;
+        assert_eq!(plain.format("snake_case_name"), "snake_case_name");
+        assert_eq!(plain.format("2 * 3 * 4"), "2 * 3 * 4");
+        assert_eq!(plain.format("unclosed **bold"), "unclosed **bold");
+        assert_eq!(
+            plain.format("~~gone~~ [x](not a url)"),
+            "gone [x](not a url)"
+        );
+        assert_eq!(OutboundFormat::Markdown.format("**as is**"), "**as is**");
+    }
+
+    struct Upper;
+
+    impl OutboundFormatter for Upper {
+        fn format(&self, markdown: &str) -> String {
+            markdown.to_uppercase()
+        }
+    }
+
+    #[test]
+    fn test_pipeline_overrides() {
+        let mut config = HashMap::new();
+        config.insert(
+            "telegram".to_string(),
+            ChannelFormatConfig {
+                format: Some(OutboundFormat::Plain),
+                chunk_limit: Some(12),
+                number_parts: false,
+            },
+        );
+        let pipeline = OutboundPipeline::new(config);
+
+        assert_eq!(
+            pipeline.prepare("telegram", "**hello** world again", 100),
+            ["hello", "world again"]
+        );
+        assert_eq!(pipeline.prepare("slack", "**hi**", 100), ["*hi*"]);
+
+        let pipeline = pipeline
+            .with_formatter("telegram", Arc::new(Upper))
+            .reconfigured(HashMap::new());
+        assert_eq!(pipeline.prepare("telegram", "hi", 100), ["HI"]);
+    }
+
+    #[test]
+    fn test_pipeline_numbers_parts() {
+        let pipeline = OutboundPipeline::default();
+        assert_eq!(
+            pipeline.prepare("discord", "First part.\n\nSecond part.", 20),
+            ["First part.\n\n(1/2)", "Second part.\n\n(2/2)"]
+        );
+    }
+
+    #[test]
+    fn test_pipeline_rechunks_expanded_markup() {
+        let mut config = HashMap::new();
+        config.insert(
+            "telegram".to_string(),
+            ChannelFormatConfig {
+                number_parts: false,
+                ..ChannelFormatConfig::default()
+            },
+        );
+        let pipeline = OutboundPipeline::new(config);
+        let messages = pipeline.prepare("telegram", "<<<<<<<<<<", 20);
+        assert!(messages.len() > 1);
+        assert!(messages.iter().all(|m| char_len(m) <= 20));
+        assert_eq!(messages.concat(), "&lt;".repeat(10));
+    }
+}
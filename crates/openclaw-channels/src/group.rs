@@ -0,0 +1,331 @@
+//! Group chat etiquette.
+//!
+//! In a group the agent answers only messages that mention it or reply to
+//! one of its messages, leaves a cooldown between answers, and answers in
+//! a thread where the channel has them. Direct messages are always
+//! answered.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use openclaw_core::config::GroupsConfig;
+use openclaw_core::types::{Message, PeerType};
+
+/// Messages sent per group that are remembered to recognize replies.
+const SENT_PER_CHAT: usize = 256;
+
+/// Whether the agent answers a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupDecision {
+    /// Answer the message.
+    Answer {
+        /// Thread to answer in, if any.
+        thread_id: Option<String>,
+    },
+    /// The message doesn't mention the agent or reply to it.
+    NotAddressed,
+    /// The agent answered in the group too recently.
+    CoolingDown {
+        /// Time until the agent answers again.
+        retry_after: Duration,
+    },
+}
+
+impl GroupDecision {
+    /// Whether the agent answers the message.
+    #[must_use]
+    pub const fn is_answer(&self) -> bool {
+        matches!(self, Self::Answer { .. })
+    }
+
+    /// Why the message goes unanswered, if it does.
+    #[must_use]
+    pub const fn reason(&self) -> Option<&'static str> {
+        match self {
+            Self::Answer { .. } => None,
+            Self::NotAddressed => Some("not_addressed"),
+            Self::CoolingDown { .. } => Some("cooldown"),
+        }
+    }
+}
+
+/// What is remembered about one group.
+#[derive(Debug, Clone, Default)]
+struct ChatState {
+    last_answer: Option<Instant>,
+    sent: VecDeque<String>,
+}
+
+/// Applies group chat etiquette to inbound messages.
+#[derive(Debug, Default)]
+pub struct GroupPolicy {
+    config: GroupsConfig,
+    names: Vec<String>,
+    chats: Mutex<HashMap<String, ChatState>>,
+}
+
+impl GroupPolicy {
+    /// Create a policy from configuration.
+    #[must_use]
+    pub fn new(config: GroupsConfig) -> Self {
+        Self {
+            names: config.names.iter().map(|name| handle(name)).collect(),
+            config,
+            chats: Mutex::default(),
+        }
+    }
+
+    /// A policy with new configuration that still knows which messages the
+    /// agent sent and when it last answered.
+    #[must_use]
+    pub fn reconfigured(&self, config: GroupsConfig) -> Self {
+        let policy = Self::new(config);
+        policy.lock().clone_from(&self.lock());
+        policy
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, ChatState>> {
+        self.chats.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Decide whether the agent answers `message`, sent in `chat_id`, at
+    /// `now`. `threads` is whether the channel supports threads. An answer
+    /// starts the group's cooldown.
+    #[must_use]
+    pub fn admit(
+        &self,
+        message: &Message,
+        chat_id: &str,
+        threads: bool,
+        now: Instant,
+    ) -> GroupDecision {
+        if message.peer_type == PeerType::Dm {
+            return GroupDecision::Answer {
+                thread_id: message.thread_id.clone(),
+            };
+        }
+
+        let channel = message.channel.as_ref();
+        let mut chats = self.lock();
+        let chat = chats.entry(format!("{channel}:{chat_id}")).or_default();
+
+        let replies_to_agent = message
+            .reply_to
+            .as_ref()
+            .is_some_and(|id| chat.sent.contains(id));
+        if self.config.require_mention_for(channel, chat_id)
+            && !replies_to_agent
+            && !self.mentions_agent(message)
+        {
+            return GroupDecision::NotAddressed;
+        }
+
+        let cooldown = Duration::from_secs(self.config.cooldown_secs_for(channel, chat_id));
+        if let Some(last) = chat.last_answer {
+            let elapsed = now.saturating_duration_since(last);
+            if elapsed < cooldown {
+                return GroupDecision::CoolingDown {
+                    retry_after: cooldown.saturating_sub(elapsed),
+                };
+            }
+        }
+        chat.last_answer = Some(now);
+        drop(chats);
+
+        let thread_id = message.thread_id.clone().or_else(|| {
+            (threads && self.config.threads_for(channel, chat_id) && thread_on_message(channel))
+                .then(|| message.id.clone())
+        });
+        GroupDecision::Answer { thread_id }
+    }
+
+    /// Remember that the agent sent `message_id` in `chat_id`, so replies
+    /// to it address the agent.
+    pub fn record_sent(&self, channel: &str, chat_id: &str, message_id: &str) {
+        let mut chats = self.lock();
+        let sent = &mut chats
+            .entry(format!("{channel}:{chat_id}"))
+            .or_default()
+            .sent;
+        if sent.len() == SENT_PER_CHAT {
+            sent.pop_front();
+        }
+        sent.push_back(message_id.to_string());
+        drop(chats);
+    }
+
+    fn mentions_agent(&self, message: &Message) -> bool {
+        message.mentions_me()
+            || message
+                .mentions
+                .iter()
+                .any(|mention| self.names.contains(&handle(mention)))
+    }
+}
+
+/// A mention or name compared without its `@` or case.
+fn handle(name: &str) -> String {
+    name.trim_start_matches('@').to_lowercase()
+}
+
+/// Whether a thread on `channel` is addressed by the ID of the message it
+/// hangs off. Elsewhere, answering as a reply to the message keeps the
+/// conversation together.
+fn thread_on_message(channel: &str) -> bool {
+    matches!(channel, "slack" | "matrix" | "mock")
+}
+
+/// Parse the mentions in a message's text, in `channel`'s syntax:
+/// `<@id>` on Discord and Slack, `@user:server` on Matrix, and `@handle`
+/// elsewhere.
+#[must_use]
+pub fn parse_mentions(channel: &str, text: &str) -> Vec<String> {
+    match channel {
+        "discord" | "slack" => text
+            .split("<@")
+            .skip(1)
+            .filter_map(|rest| {
+                let (inner, _) = rest.split_once('>')?;
+                // `<@!id>` is Discord's nickname form, `<@id|name>` Slack's
+                let id = inner.trim_start_matches('!').split('|').next()?;
+                (!id.is_empty()).then(|| id.to_string())
+            })
+            .collect(),
+        _ => text
+            .split_whitespace()
+            .filter_map(|word| {
+                let word = word
+                    .trim_start_matches(|c: char| c != '@' && !c.is_alphanumeric())
+                    .trim_end_matches(|c: char| !c.is_alphanumeric());
+                let name = word.strip_prefix('@')?;
+                if channel == "matrix" {
+                    name.contains(':').then(|| word.to_string())
+                } else {
+                    (!name.is_empty()).then(|| name.to_string())
+                }
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openclaw_core::config::GroupOverrides;
+    use openclaw_core::types::{ChannelId, PeerId};
+
+    fn group_message(id: &str, text: &str) -> Message {
+        Message {
+            id: id.to_string(),
+            channel: ChannelId::slack(),
+            account_id: "U0BOT".to_string(),
+            peer_id: PeerId::new("U0ALICE"),
+            peer_type: PeerType::Group,
+            content: text.to_string(),
+            attachments: vec![],
+            timestamp: chrono::Utc::now(),
+            reply_to: None,
+            thread_id: None,
+            mentions: parse_mentions("slack", text),
+            raw: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_mentions() {
+        assert_eq!(
+            parse_mentions("discord", "<@123> and <@!456>, hi"),
+            ["123", "456"]
+        );
+        assert_eq!(parse_mentions("slack", "hey <@U0BOT|claw>"), ["U0BOT"]);
+        assert_eq!(
+            parse_mentions("matrix", "@claw:example.org, ping @notauser"),
+            ["@claw:example.org"]
+        );
+        assert_eq!(
+            parse_mentions("telegram", "(@claw_bot) what's up? me@example.com"),
+            ["claw_bot"]
+        );
+    }
+
+    #[test]
+    fn test_mention_gating_and_threads() {
+        let policy = GroupPolicy::new(GroupsConfig::default());
+        let now = Instant::now();
+
+        let chatter = group_message("1.1", "lunch anyone?");
+        assert_eq!(
+            policy.admit(&chatter, "C1", true, now),
+            GroupDecision::NotAddressed
+        );
+
+        let mention = group_message("1.2", "<@U0BOT> what's the build status?");
+        assert_eq!(
+            policy.admit(&mention, "C1", true, now),
+            GroupDecision::Answer {
+                thread_id: Some("1.2".to_string())
+            }
+        );
+
+        // A reply to the agent's answer addresses it without a mention
+        policy.record_sent("slack", "C1", "1.3");
+        let mut reply = group_message("1.4", "and yesterday?");
+        reply.reply_to = Some("1.3".to_string());
+        assert!(policy.admit(&reply, "C1", false, now).is_answer());
+
+        // Direct messages are always answered
+        let mut dm = group_message("1.5", "hello");
+        dm.peer_type = PeerType::Dm;
+        assert!(policy.admit(&dm, "U0ALICE", true, now).is_answer());
+    }
+
+    #[test]
+    fn test_group_cooldown() {
+        let policy = GroupPolicy::new(GroupsConfig {
+            names: vec!["@Claw".to_string()],
+            chats: HashMap::from([(
+                "slack:C1".to_string(),
+                GroupOverrides {
+                    require_mention: Some(false),
+                    cooldown_secs: Some(60),
+                    ..GroupOverrides::default()
+                },
+            )]),
+            ..GroupsConfig::default()
+        });
+        let now = Instant::now();
+
+        assert!(
+            policy
+                .admit(&group_message("1", "hi"), "C1", true, now)
+                .is_answer()
+        );
+        assert_eq!(
+            policy.admit(
+                &group_message("2", "hi?"),
+                "C1",
+                true,
+                now + Duration::from_secs(20)
+            ),
+            GroupDecision::CoolingDown {
+                retry_after: Duration::from_secs(40)
+            }
+        );
+        assert!(
+            policy
+                .admit(
+                    &group_message("3", "hi!"),
+                    "C1",
+                    true,
+                    now + Duration::from_secs(60)
+                )
+                .is_answer()
+        );
+
+        // Other groups have their own cooldown, and still need a mention
+        let mut named = group_message("4", "@claw hello");
+        named.mentions = parse_mentions("telegram", &named.content);
+        assert!(policy.admit(&named, "C2", true, now).is_answer());
+    }
+}
@@ -0,0 +1,346 @@
+//! Working hours and do-not-disturb windows.
+//!
+//! Channels and agents can be given hours outside which messages get an
+//! automatic reply instead of reaching the agent, or wait until hours
+//! resume. A peer can still get through by starting a message with
+//! `/urgent`, which keeps their session open for a while.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+use chrono::{DateTime, Datelike, Days, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+
+use openclaw_core::config::{HoursWindow, OutsideHoursAction, WorkingHours, WorkingHoursConfig};
+
+/// Whether a message may reach the agent now.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HoursDecision {
+    /// Inside working hours, or no hours apply.
+    Open,
+    /// Outside working hours.
+    Closed {
+        /// When hours resume, if within the next week.
+        opens_at: Option<DateTime<Utc>>,
+        /// `opens_at` in the schedule's timezone, e.g. "Mon 09:00 CET".
+        opens_at_local: Option<String>,
+        /// What to do with the message.
+        action: OutsideHoursAction,
+        /// Configured reply, with `{opens_at}` filled in (`None` for the
+        /// localized message).
+        message: Option<String>,
+        /// Whether `/urgent` would get the message through.
+        allow_urgent: bool,
+    },
+}
+
+impl HoursDecision {
+    /// Whether the message may reach the agent.
+    #[must_use]
+    pub const fn is_open(&self) -> bool {
+        matches!(self, Self::Open)
+    }
+}
+
+/// A daily window with its days and times parsed.
+#[derive(Debug, Clone)]
+struct Window {
+    days: Vec<Weekday>,
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl Window {
+    fn parse(window: &HoursWindow) -> Option<Self> {
+        let (days, start, end) = window.parse().ok()?;
+        Some(Self { days, start, end })
+    }
+
+    fn starts_on(&self, day: Weekday) -> bool {
+        self.days.is_empty() || self.days.contains(&day)
+    }
+
+    fn contains(&self, local: NaiveDateTime) -> bool {
+        let (day, time) = (local.weekday(), local.time());
+        if self.start < self.end {
+            self.starts_on(day) && self.start <= time && time < self.end
+        } else {
+            // Runs past midnight: the evening of its day and the morning after
+            (self.starts_on(day) && time >= self.start)
+                || (self.starts_on(day.pred()) && time < self.end)
+        }
+    }
+}
+
+/// One channel's or agent's hours, parsed.
+#[derive(Debug, Clone)]
+struct Schedule {
+    tz: Tz,
+    open: Vec<Window>,
+    quiet: Vec<Window>,
+    hours: WorkingHours,
+}
+
+impl Schedule {
+    /// Parse `hours`, or `None` if they are invalid; configuration
+    /// validation reports why.
+    fn parse(hours: &WorkingHours) -> Option<Self> {
+        Some(Self {
+            tz: hours.tz().ok()?,
+            open: hours
+                .open
+                .iter()
+                .map(Window::parse)
+                .collect::<Option<_>>()?,
+            quiet: hours
+                .quiet
+                .iter()
+                .map(Window::parse)
+                .collect::<Option<_>>()?,
+            hours: hours.clone(),
+        })
+    }
+
+    fn is_open_at(&self, local: NaiveDateTime) -> bool {
+        (self.open.is_empty() || self.open.iter().any(|w| w.contains(local)))
+            && !self.quiet.iter().any(|w| w.contains(local))
+    }
+
+    fn is_open(&self, now: DateTime<Utc>) -> bool {
+        self.is_open_at(now.with_timezone(&self.tz).naive_local())
+    }
+
+    /// When hours next resume after `now`, looking a week ahead. Hours
+    /// only change at window boundaries, so those are the candidates.
+    fn next_open(&self, now: DateTime<Utc>) -> Option<DateTime<Tz>> {
+        let today = now.with_timezone(&self.tz).date_naive();
+        let windows: Vec<&Window> = self.open.iter().chain(&self.quiet).collect();
+        let mut boundaries: Vec<DateTime<Tz>> = (0..=7)
+            .filter_map(|offset| today.checked_add_days(Days::new(offset)))
+            .flat_map(|date| {
+                windows
+                    .iter()
+                    .flat_map(move |w| [date.and_time(w.start), date.and_time(w.end)])
+            })
+            .filter_map(|local| self.tz.from_local_datetime(&local).earliest())
+            .filter(|t| t.with_timezone(&Utc) > now)
+            .collect();
+        boundaries.sort();
+        boundaries
+            .into_iter()
+            .find(|t| self.is_open_at(t.naive_local()))
+    }
+}
+
+/// Applies working hours to inbound messages.
+#[derive(Debug, Default)]
+pub struct WorkingHoursPolicy {
+    channels: HashMap<String, Schedule>,
+    agents: HashMap<String, Schedule>,
+    /// Sessions opened with `/urgent`, and until when.
+    urgent: Mutex<HashMap<String, DateTime<Utc>>>,
+}
+
+impl WorkingHoursPolicy {
+    /// Create a policy from configuration.
+    #[must_use]
+    pub fn new(config: &WorkingHoursConfig) -> Self {
+        let parse = |schedules: &HashMap<String, WorkingHours>| {
+            schedules
+                .iter()
+                .filter_map(|(id, hours)| Some((id.clone(), Schedule::parse(hours)?)))
+                .collect()
+        };
+        Self {
+            channels: parse(&config.channels),
+            agents: parse(&config.agents),
+            urgent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn schedule(&self, channel: &str, agent: &str) -> Option<&Schedule> {
+        self.agents
+            .get(agent)
+            .or_else(|| self.channels.get(channel))
+    }
+
+    /// Check whether a message in `session_key`, on `channel` for `agent`,
+    /// may reach the agent at `now`.
+    #[must_use]
+    pub fn check(
+        &self,
+        channel: &str,
+        agent: &str,
+        session_key: &str,
+        now: DateTime<Utc>,
+    ) -> HoursDecision {
+        let Some(schedule) = self.schedule(channel, agent) else {
+            return HoursDecision::Open;
+        };
+        if schedule.is_open(now) || self.is_urgent(session_key, now) {
+            return HoursDecision::Open;
+        }
+
+        let opens_at = schedule.next_open(now);
+        let opens_at_local = opens_at
+            .as_ref()
+            .map(|t| t.format("%a %H:%M %Z").to_string());
+        HoursDecision::Closed {
+            opens_at: opens_at.map(|t| t.with_timezone(&Utc)),
+            message: schedule.hours.message.as_ref().map(|message| {
+                message.replace("{opens_at}", opens_at_local.as_deref().unwrap_or("later"))
+            }),
+            opens_at_local,
+            action: schedule.hours.outside,
+            allow_urgent: schedule.hours.allow_urgent,
+        }
+    }
+
+    /// Open `session_key` for the schedule's urgent period, if its hours
+    /// allow `/urgent`. Returns until when it is open.
+    #[must_use]
+    pub fn grant_urgent(
+        &self,
+        channel: &str,
+        agent: &str,
+        session_key: &str,
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let schedule = self.schedule(channel, agent)?;
+        if !schedule.hours.allow_urgent {
+            return None;
+        }
+        let until = now + chrono::Duration::minutes(i64::from(schedule.hours.urgent_minutes));
+        let mut urgent = self.urgent.lock().unwrap_or_else(PoisonError::into_inner);
+        urgent.retain(|_, open_until| *open_until > now);
+        urgent.insert(session_key.to_string(), until);
+        drop(urgent);
+        Some(until)
+    }
+
+    fn is_urgent(&self, session_key: &str, now: DateTime<Utc>) -> bool {
+        self.urgent
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(session_key)
+            .is_some_and(|until| *until > now)
+    }
+}
+
+/// Parse an `/urgent` command, returning the message that follows it.
+#[must_use]
+pub fn parse_urgent_command(text: &str) -> Option<&str> {
+    let rest = text.trim_start().strip_prefix("/urgent")?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(rest.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn office_hours(outside: OutsideHoursAction) -> WorkingHoursConfig {
+        let window = |days: &[&str], start: &str, end: &str| HoursWindow {
+            days: days.iter().map(ToString::to_string).collect(),
+            start: start.to_string(),
+            end: end.to_string(),
+        };
+        WorkingHoursConfig {
+            channels: HashMap::from([(
+                "telegram".to_string(),
+                WorkingHours {
+                    timezone: "Europe/Berlin".to_string(),
+                    open: vec![window(
+                        &["mon", "tue", "wed", "thu", "fri"],
+                        "09:00",
+                        "18:00",
+                    )],
+                    quiet: vec![window(&[], "12:00", "13:00")],
+                    outside,
+                    message: Some("Back {opens_at}".to_string()),
+                    ..WorkingHours::default()
+                },
+            )]),
+            agents: HashMap::new(),
+        }
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_open_and_quiet_windows() {
+        let policy = WorkingHoursPolicy::new(&office_hours(OutsideHoursAction::AutoReply));
+        let check = |now: &str| policy.check("telegram", "default", "s1", utc(now));
+
+        // Wednesday 10:00 in Berlin (CET, UTC+1)
+        assert!(check("2025-01-15T09:00:00Z").is_open());
+        // Lunch is quiet
+        let HoursDecision::Closed {
+            opens_at, message, ..
+        } = check("2025-01-15T11:30:00Z")
+        else {
+            panic!("Expected closed at lunch");
+        };
+        assert_eq!(opens_at, Some(utc("2025-01-15T12:00:00Z")));
+        assert_eq!(message.as_deref(), Some("Back Wed 13:00 CET"));
+
+        // Friday evening reopens on Monday morning
+        let HoursDecision::Closed { opens_at, .. } = check("2025-01-17T18:30:00Z") else {
+            panic!("Expected closed on Friday evening");
+        };
+        assert_eq!(opens_at, Some(utc("2025-01-20T08:00:00Z")));
+
+        // Other channels have no hours
+        assert!(
+            policy
+                .check("discord", "default", "s1", utc("2025-01-18T03:00:00Z"))
+                .is_open()
+        );
+    }
+
+    #[test]
+    fn test_overnight_window() {
+        let window = Window::parse(&HoursWindow {
+            days: vec!["fri".to_string()],
+            start: "22:00".to_string(),
+            end: "06:00".to_string(),
+        })
+        .unwrap();
+        let at = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        assert!(window.contains(at("2025-01-17 23:00")));
+        assert!(window.contains(at("2025-01-18 05:59")));
+        assert!(!window.contains(at("2025-01-18 23:00")));
+        assert!(!window.contains(at("2025-01-17 05:00")));
+    }
+
+    #[test]
+    fn test_urgent_opens_session() {
+        let policy = WorkingHoursPolicy::new(&office_hours(OutsideHoursAction::Queue));
+        let night = utc("2025-01-15T23:00:00Z");
+        assert!(!policy.check("telegram", "default", "s1", night).is_open());
+
+        let until = policy
+            .grant_urgent("telegram", "default", "s1", night)
+            .unwrap();
+        assert_eq!(until, utc("2025-01-15T23:30:00Z"));
+        assert!(policy.check("telegram", "default", "s1", night).is_open());
+        assert!(!policy.check("telegram", "default", "s2", night).is_open());
+        assert!(
+            !policy
+                .check("telegram", "default", "s1", utc("2025-01-15T23:45:00Z"))
+                .is_open()
+        );
+
+        assert_eq!(
+            parse_urgent_command("/urgent server is down"),
+            Some("server is down")
+        );
+        assert_eq!(parse_urgent_command("/urgent"), Some(""));
+        assert_eq!(parse_urgent_command("/urgently"), None);
+        assert_eq!(parse_urgent_command("not /urgent"), None);
+    }
+}
@@ -6,6 +6,15 @@
 #![warn(missing_docs)]
 
 mod allowlist;
+mod chunking;
+mod citations;
+mod commands;
+mod debounce;
+mod feedback;
+mod format;
+mod group;
+mod hours;
+mod ratelimit;
 mod registry;
 mod routing;
 mod traits;
@@ -14,6 +23,8 @@ mod traits;
 pub mod discord;
 /// Matrix channel adapter.
 pub mod matrix;
+/// Mock channel adapter for development.
+pub mod mock;
 /// Signal channel adapter.
 pub mod signal;
 /// Slack channel adapter.
@@ -24,16 +35,28 @@ pub mod telegram;
 pub mod whatsapp;
 
 pub use allowlist::{Allowlist, AllowlistEntry};
+pub use chunking::{chunk_numbered, chunk_text};
+pub use citations::{CitationStyle, render_citations};
+pub use commands::{ChatCommand, ChatCommands, CommandAccess, parse_chat_command};
+pub use debounce::{Debounced, DebouncedBatch, InboundDebouncer, TurnGuard};
+pub use feedback::{FeedbackCommand, parse_feedback_command, rating_from_reaction};
+pub use format::{OutboundFormatter, OutboundPipeline, default_format};
+pub use group::{GroupDecision, GroupPolicy, parse_mentions};
+pub use hours::{HoursDecision, WorkingHoursPolicy, parse_urgent_command};
+pub use ratelimit::{
+    InboundRateLimiter, MAX_COUNTED_CHANNELS, OTHER_CHANNELS, RateLimitDecision, RateLimitStats,
+};
 pub use registry::ChannelRegistry;
-pub use routing::AgentRouter;
+pub use routing::{AgentRouter, Experiment, VariantAssignment};
 pub use traits::{
     Channel, ChannelCapabilities, ChannelContext, ChannelError, ChannelInbound, ChannelOutbound,
-    ChannelProbe, DeliveryMode, OutboundContext,
+    ChannelProbe, ChannelReactions, DeliveryMode, OutboundContext,
 };
 
 // Re-export channel implementations
 pub use discord::DiscordChannel;
 pub use matrix::MatrixChannel;
+pub use mock::MockChannel;
 pub use signal::SignalChannel;
 pub use slack::SlackChannel;
 pub use telegram::TelegramChannel;
@@ -3,17 +3,20 @@
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use openclaw_core::secrets::ApiKey;
 use openclaw_core::types::{
-    Attachment, AttachmentKind, ChannelId, DeliveryResult, Message, PeerId, PeerType,
+    Attachment, AttachmentKind, ChannelId, DeliveryResult, Message, MessageReaction, PeerId,
+    PeerType,
 };
 
+use crate::group::parse_mentions;
 use crate::traits::{
     Channel, ChannelCapabilities, ChannelContext, ChannelError, ChannelInbound, ChannelOutbound,
-    ChannelProbe, DeliveryMode, OutboundContext,
+    ChannelProbe, ChannelReactions, DeliveryMode, OutboundContext,
 };
 
 /// Matrix channel adapter.
@@ -30,6 +33,8 @@ struct MatrixState {
     device_id: Option<String>,
     connected: bool,
     next_batch: Option<String>,
+    /// Reaction event IDs we sent, by (room, target event, key), for redaction.
+    reactions: HashMap<(String, String, String), String>,
 }
 
 impl MatrixChannel {
@@ -192,6 +197,7 @@ impl ChannelOutbound for MatrixChannel {
                 in_reply_to: Some(InReplyTo { event_id: reply_to }),
                 rel_type: None,
                 event_id: None,
+                key: None,
             });
         }
 
@@ -257,6 +263,68 @@ impl ChannelOutbound for MatrixChannel {
         })
     }
 
+    async fn add_reaction(
+        &self,
+        ctx: OutboundContext,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<(), ChannelError> {
+        let room_id = urlencoding::encode(&ctx.chat_id);
+        let txn_id = Self::txn_id();
+        let path = format!("/rooms/{room_id}/send/m.reaction/{txn_id}");
+
+        let content = ReactionContent {
+            relates_to: RelatesTo {
+                in_reply_to: None,
+                rel_type: Some("m.annotation".to_string()),
+                event_id: Some(message_id.to_string()),
+                key: Some(emoji.to_string()),
+            },
+        };
+
+        let result: SendEventResponse = self
+            .call(reqwest::Method::PUT, &path, Some(&content))
+            .await?;
+
+        self.state.write().await.reactions.insert(
+            (ctx.chat_id, message_id.to_string(), emoji.to_string()),
+            result.event_id,
+        );
+        Ok(())
+    }
+
+    async fn remove_reaction(
+        &self,
+        ctx: OutboundContext,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<(), ChannelError> {
+        // Matrix removes a reaction by redacting the annotation event
+        let key = (ctx.chat_id, message_id.to_string(), emoji.to_string());
+        let reaction_id = self
+            .state
+            .read()
+            .await
+            .reactions
+            .get(&key)
+            .cloned()
+            .ok_or_else(|| {
+                ChannelError::DeliveryFailed(format!("No reaction {emoji} on {message_id}"))
+            })?;
+
+        let room_id = urlencoding::encode(&key.0);
+        let event_id = urlencoding::encode(&reaction_id);
+        let txn_id = Self::txn_id();
+        let path = format!("/rooms/{room_id}/redact/{event_id}/{txn_id}");
+
+        let _: SendEventResponse = self
+            .call(reqwest::Method::PUT, &path, Some(&serde_json::json!({})))
+            .await?;
+
+        self.state.write().await.reactions.remove(&key);
+        Ok(())
+    }
+
     fn text_chunk_limit(&self) -> usize {
         // Matrix doesn't have a strict limit, but events should be < 64KB
         60000
@@ -286,10 +354,13 @@ impl ChannelInbound for MatrixChannel {
             .content
             .ok_or_else(|| ChannelError::Config("No content in event".to_string()))?;
 
-        let state = futures::executor::block_on(self.state.read());
-        let account_id = state.user_id.clone().unwrap_or_default();
+        let account_id = futures::executor::block_on(self.state.read())
+            .user_id
+            .clone()
+            .unwrap_or_default();
 
         // Extract text content
+        let mentions = message_mentions(&content);
         let text = content.body.unwrap_or_default();
 
         // Handle attachments (m.image, m.video, m.audio, m.file)
@@ -335,12 +406,12 @@ impl ChannelInbound for MatrixChannel {
             account_id,
             peer_id: PeerId::new(sender),
             peer_type,
+            mentions,
             content: text,
             attachments,
             timestamp,
             reply_to,
             thread_id: None, // Matrix uses reply chains, not explicit threads
-            mentions: Vec::new(),
             raw: Some(raw_value),
         })
     }
@@ -351,6 +422,56 @@ impl ChannelInbound for MatrixChannel {
     }
 }
 
+impl ChannelReactions for MatrixChannel {
+    type RawReaction = MatrixEvent;
+
+    /// Normalize an `m.reaction` event.
+    ///
+    /// Removals arrive as redactions of the reaction event, which do not name
+    /// the target message, so only additions are surfaced.
+    fn normalize_reaction(&self, raw: Self::RawReaction) -> Result<MessageReaction, ChannelError> {
+        let raw_value = serde_json::to_value(&raw).unwrap_or_default();
+
+        if raw.event_type.as_deref() != Some("m.reaction") {
+            return Err(ChannelError::Config("Not a reaction event".to_string()));
+        }
+
+        let relation = raw
+            .content
+            .and_then(|c| c.relates_to)
+            .filter(|r| r.rel_type.as_deref() == Some("m.annotation"))
+            .ok_or_else(|| ChannelError::Config("Reaction has no annotation".to_string()))?;
+
+        let (Some(sender), Some(room_id), Some(event_id), Some(key)) =
+            (raw.sender, raw.room_id, relation.event_id, relation.key)
+        else {
+            return Err(ChannelError::Config(
+                "Incomplete reaction event".to_string(),
+            ));
+        };
+
+        let account_id = futures::executor::block_on(self.state.read())
+            .user_id
+            .clone()
+            .unwrap_or_default();
+
+        Ok(MessageReaction {
+            channel: ChannelId::matrix(),
+            account_id,
+            peer_id: PeerId::new(sender),
+            chat_id: room_id,
+            message_id: event_id,
+            emoji: key,
+            removed: false,
+            timestamp: raw
+                .origin_server_ts
+                .and_then(chrono::DateTime::from_timestamp_millis)
+                .unwrap_or_else(chrono::Utc::now),
+            raw: Some(raw_value),
+        })
+    }
+}
+
 // Matrix API types
 
 /// whoami response.
@@ -380,6 +501,13 @@ struct MessageContent {
     relates_to: Option<RelatesTo>,
 }
 
+/// Content for m.reaction.
+#[derive(Debug, Serialize)]
+struct ReactionContent {
+    #[serde(rename = "m.relates_to")]
+    relates_to: RelatesTo,
+}
+
 /// Media message content.
 #[derive(Debug, Serialize)]
 struct MediaMessageContent {
@@ -411,6 +539,9 @@ struct RelatesTo {
     rel_type: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     event_id: Option<String>,
+    /// Annotation key (the emoji, for reactions).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key: Option<String>,
 }
 
 /// In-reply-to reference.
@@ -419,6 +550,44 @@ struct InReplyTo {
     event_id: String,
 }
 
+/// Users a message mentions intentionally (`m.mentions`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MatrixMentions {
+    /// Mentioned user IDs.
+    #[serde(default)]
+    pub user_ids: Vec<String>,
+}
+
+/// Users mentioned in a message: listed in `m.mentions`, linked as pills
+/// in the formatted body, or written out in the plain body.
+fn message_mentions(content: &MatrixMessageContent) -> Vec<String> {
+    let mut mentions: Vec<String> = content
+        .mentions
+        .iter()
+        .flat_map(|m| m.user_ids.iter().cloned())
+        .collect();
+    if let Some(html) = &content.formatted_body {
+        mentions.extend(
+            html.split("https://matrix.to/#/")
+                .skip(1)
+                .filter_map(|rest| {
+                    let link = &rest[..rest.find(['"', '\'', '?', '>'])?];
+                    let user = link
+                        .replace("%40", "@")
+                        .replace("%3A", ":")
+                        .replace("%3a", ":");
+                    user.starts_with('@').then_some(user)
+                }),
+        );
+    }
+    if let Some(body) = &content.body {
+        mentions.extend(parse_mentions("matrix", body));
+    }
+    mentions.sort();
+    mentions.dedup();
+    mentions
+}
+
 /// Matrix room event.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MatrixEvent {
@@ -453,6 +622,13 @@ pub struct MatrixMessageContent {
     /// Relations (replies, threads).
     #[serde(rename = "m.relates_to")]
     pub relates_to: Option<RelatesTo>,
+    /// Users the message mentions.
+    #[serde(
+        rename = "m.mentions",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub mentions: Option<MatrixMentions>,
 }
 
 #[cfg(test)]
@@ -475,4 +651,50 @@ mod tests {
         assert!(caps.threads);
         assert!(caps.editing);
     }
+
+    #[test]
+    fn test_normalize_reaction() {
+        let channel = MatrixChannel::new("https://matrix.org", ApiKey::new("test".to_string()));
+        let event: MatrixEvent = serde_json::from_value(serde_json::json!({
+            "event_id": "$reaction",
+            "type": "m.reaction",
+            "room_id": "!room:matrix.org",
+            "sender": "@alice:matrix.org",
+            "origin_server_ts": 1_700_000_000_000_i64,
+            "content": {
+                "m.relates_to": {
+                    "rel_type": "m.annotation",
+                    "event_id": "$target",
+                    "key": "👍"
+                }
+            }
+        }))
+        .unwrap();
+
+        let reaction = channel.normalize_reaction(event).unwrap();
+        assert_eq!(reaction.chat_id, "!room:matrix.org");
+        assert_eq!(reaction.message_id, "$target");
+        assert_eq!(reaction.emoji, "👍");
+    }
+
+    #[test]
+    fn test_normalize_mentions() {
+        let channel = MatrixChannel::new("https://matrix.org", ApiKey::new("test".to_string()));
+        let event: MatrixEvent = serde_json::from_value(serde_json::json!({
+            "event_id": "$msg",
+            "type": "m.room.message",
+            "room_id": "!room:matrix.org",
+            "sender": "@alice:matrix.org",
+            "content": {
+                "msgtype": "m.text",
+                "body": "Claw: can you ask @bob:matrix.org?",
+                "formatted_body": "<a href=\"https://matrix.to/#/%40claw%3Amatrix.org\">Claw</a>: can you ask @bob:matrix.org?",
+                "m.mentions": { "user_ids": ["@claw:matrix.org"] }
+            }
+        }))
+        .unwrap();
+
+        let message = channel.normalize(event).unwrap();
+        assert_eq!(message.mentions, ["@bob:matrix.org", "@claw:matrix.org"]);
+    }
 }
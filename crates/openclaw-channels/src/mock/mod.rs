@@ -0,0 +1,656 @@
+//! Mock channel adapter for development.
+//!
+//! Inbound messages come from a file, stdin, or [`MockChannel::inject`];
+//! outbound messages are recorded in memory instead of being delivered, so
+//! the full pipeline can be exercised without platform credentials.
+//!
+//! Input is read line by line. A line is either a JSON object
+//! (`{"peerId": "alice", "text": "hi", "chatId": "room-1"}`) or plain text
+//! sent by the default peer. Blank lines are skipped.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use openclaw_core::types::{
+    Attachment, ChannelId, DeliveryResult, Message, MessageReaction, PeerId, PeerType,
+};
+
+use crate::group::parse_mentions;
+use crate::traits::{
+    Channel, ChannelCapabilities, ChannelContext, ChannelError, ChannelInbound, ChannelOutbound,
+    ChannelProbe, ChannelReactions, DeliveryMode, OutboundContext,
+};
+
+/// Channel ID of the mock channel.
+pub const MOCK_CHANNEL_ID: &str = "mock";
+
+/// Peer that plain-text input lines are sent from.
+pub const DEFAULT_PEER: &str = "dev";
+
+/// Where the mock channel reads inbound messages from once started.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockInput {
+    /// Read lines from standard input.
+    Stdin,
+    /// Read lines from a file.
+    File(PathBuf),
+}
+
+/// An inbound message as written in input files or injected over RPC.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockInbound {
+    /// Sender; [`DEFAULT_PEER`] if omitted.
+    #[serde(default = "default_peer")]
+    pub peer_id: String,
+    /// Message text.
+    #[serde(default)]
+    pub text: String,
+    /// Chat the message was sent in; a DM with the sender if omitted.
+    #[serde(default)]
+    pub chat_id: Option<String>,
+    /// Thread the message was sent in.
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    /// Message this one replies to.
+    #[serde(default)]
+    pub reply_to: Option<String>,
+    /// Attached media.
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+}
+
+/// An inbound reaction as injected over RPC.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockReaction {
+    /// Who reacted; [`DEFAULT_PEER`] if omitted.
+    #[serde(default = "default_peer")]
+    pub peer_id: String,
+    /// Chat the message is in; a DM with the reacting peer if omitted.
+    #[serde(default)]
+    pub chat_id: Option<String>,
+    /// Message reacted to.
+    pub message_id: String,
+    /// Reaction emoji.
+    pub emoji: String,
+    /// Whether the reaction was removed rather than added.
+    #[serde(default)]
+    pub removed: bool,
+}
+
+fn default_peer() -> String {
+    DEFAULT_PEER.to_string()
+}
+
+impl MockInbound {
+    /// A text message from `peer_id`.
+    #[must_use]
+    pub fn text(peer_id: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            peer_id: peer_id.into(),
+            text: text.into(),
+            chat_id: None,
+            thread_id: None,
+            reply_to: None,
+            attachments: Vec::new(),
+        }
+    }
+
+    /// Parse one input line; `None` for blank lines.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Config` if a line starting with `{` is not a valid message.
+    pub fn parse_line(line: &str) -> Result<Option<Self>, ChannelError> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(None);
+        }
+        if line.starts_with('{') {
+            return serde_json::from_str(line)
+                .map(Some)
+                .map_err(|e| ChannelError::Config(format!("Invalid mock message: {e}")));
+        }
+        Ok(Some(Self::text(DEFAULT_PEER, line)))
+    }
+}
+
+/// Chat a mock message was sent in, to address replies to: its `chatId`,
+/// or the sender for DMs.
+#[must_use]
+pub fn chat_id(message: &Message) -> String {
+    message
+        .raw
+        .as_ref()
+        .and_then(|raw| raw["chatId"].as_str())
+        .map_or_else(|| message.peer_id.to_string(), String::from)
+}
+
+/// A message the mock channel was asked to deliver.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockOutbound {
+    /// ID assigned to the delivery.
+    pub message_id: String,
+    /// Target chat.
+    pub chat_id: String,
+    /// Message replied to.
+    pub reply_to: Option<String>,
+    /// Target thread.
+    pub thread_id: Option<String>,
+    /// When the delivery was recorded.
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// What was delivered.
+    #[serde(flatten)]
+    pub action: MockAction,
+}
+
+/// Content of a recorded delivery.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum MockAction {
+    /// A text message.
+    Text {
+        /// Message text.
+        text: String,
+    },
+    /// Media attachments.
+    Media {
+        /// Attachments sent.
+        attachments: Vec<Attachment>,
+    },
+    /// A reaction added to or removed from a message.
+    #[serde(rename_all = "camelCase")]
+    Reaction {
+        /// Message reacted to.
+        target_id: String,
+        /// Reaction emoji.
+        emoji: String,
+        /// Whether the reaction was removed.
+        removed: bool,
+    },
+}
+
+/// Mock channel adapter.
+pub struct MockChannel {
+    shared: Arc<Shared>,
+    input: Option<MockInput>,
+    inbound_rx: Mutex<Option<mpsc::UnboundedReceiver<Message>>>,
+    outbound: Mutex<Vec<MockOutbound>>,
+    reader: Mutex<Option<JoinHandle<()>>>,
+}
+
+/// State shared with the input reader task.
+struct Shared {
+    account_id: String,
+    next_id: AtomicU64,
+    inbound_tx: mpsc::UnboundedSender<Message>,
+}
+
+impl Shared {
+    fn next_id(&self, prefix: &str) -> String {
+        format!(
+            "{prefix}-{}",
+            self.next_id.fetch_add(1, Ordering::Relaxed) + 1
+        )
+    }
+
+    fn normalize(&self, raw: MockInbound) -> Message {
+        let raw_value = serde_json::to_value(&raw).ok();
+        let peer_type = match &raw.chat_id {
+            Some(chat) if *chat != raw.peer_id => PeerType::Group,
+            _ => PeerType::Dm,
+        };
+        Message {
+            id: self.next_id("mock-in"),
+            channel: ChannelId::mock(),
+            account_id: self.account_id.clone(),
+            peer_id: PeerId::new(raw.peer_id),
+            peer_type,
+            mentions: parse_mentions(MOCK_CHANNEL_ID, &raw.text),
+            content: raw.text,
+            attachments: raw.attachments,
+            timestamp: chrono::Utc::now(),
+            reply_to: raw.reply_to,
+            thread_id: raw.thread_id,
+            raw: raw_value,
+        }
+    }
+
+    fn deliver(&self, message: Message) -> Result<(), ChannelError> {
+        self.inbound_tx
+            .send(message)
+            .map_err(|_| ChannelError::NotConnected)
+    }
+}
+
+impl Default for MockChannel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockChannel {
+    /// Create a mock channel that only receives injected messages.
+    #[must_use]
+    pub fn new() -> Self {
+        let (inbound_tx, inbound_rx) = mpsc::unbounded_channel();
+        Self {
+            shared: Arc::new(Shared {
+                account_id: "mock".to_string(),
+                next_id: AtomicU64::new(0),
+                inbound_tx,
+            }),
+            input: None,
+            inbound_rx: Mutex::new(Some(inbound_rx)),
+            outbound: Mutex::default(),
+            reader: Mutex::default(),
+        }
+    }
+
+    /// Also read inbound messages from `input` once started.
+    #[must_use]
+    pub fn with_input(mut self, input: MockInput) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    /// Queue an inbound message as if it arrived from the platform.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotConnected` if the inbound receiver was dropped.
+    pub fn inject(&self, raw: MockInbound) -> Result<Message, ChannelError> {
+        let message = self.shared.normalize(raw);
+        self.shared.deliver(message.clone())?;
+        Ok(message)
+    }
+
+    /// Take the receiver of inbound messages. Returns `None` after the
+    /// first call.
+    pub fn take_inbound(&self) -> Option<mpsc::UnboundedReceiver<Message>> {
+        self.inbound_rx
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+    }
+
+    /// Deliveries recorded so far.
+    #[must_use]
+    pub fn outbound(&self) -> Vec<MockOutbound> {
+        self.outbound
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Take and clear the recorded deliveries.
+    pub fn take_outbound(&self) -> Vec<MockOutbound> {
+        std::mem::take(&mut *self.outbound.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    fn record(&self, ctx: OutboundContext, action: MockAction) -> DeliveryResult {
+        let entry = MockOutbound {
+            message_id: self.shared.next_id("mock-out"),
+            chat_id: ctx.chat_id,
+            reply_to: ctx.reply_to,
+            thread_id: ctx.thread_id,
+            timestamp: chrono::Utc::now(),
+            action,
+        };
+        let result = DeliveryResult {
+            message_id: entry.message_id.clone(),
+            channel: ChannelId::mock(),
+            timestamp: entry.timestamp,
+            chat_id: Some(entry.chat_id.clone()),
+            meta: None,
+        };
+        self.outbound
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(entry);
+        result
+    }
+
+    fn is_reading(&self) -> bool {
+        self.reader
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+            .is_some_and(|reader| !reader.is_finished())
+    }
+}
+
+/// Forward each line of `input` as an inbound message until it ends.
+async fn read_lines(input: impl AsyncRead + Unpin, shared: Arc<Shared>) {
+    let mut lines = BufReader::new(input).lines();
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(e) => {
+                tracing::warn!("Mock channel input failed: {e}");
+                break;
+            }
+        };
+        match MockInbound::parse_line(&line) {
+            Ok(Some(raw)) => {
+                if shared.deliver(shared.normalize(raw)).is_err() {
+                    break;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Skipping mock input line: {e}"),
+        }
+    }
+}
+
+#[async_trait]
+impl Channel for MockChannel {
+    fn id(&self) -> &'static str {
+        MOCK_CHANNEL_ID
+    }
+
+    fn label(&self) -> &'static str {
+        "Mock"
+    }
+
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            text: true,
+            images: true,
+            videos: true,
+            voice: true,
+            files: true,
+            threads: true,
+            reactions: true,
+            editing: false,
+            deletion: false,
+        }
+    }
+
+    async fn start(&self, _ctx: ChannelContext) -> Result<(), ChannelError> {
+        if self.is_reading() {
+            return Ok(());
+        }
+        let shared = self.shared.clone();
+        let reader = match &self.input {
+            None => return Ok(()),
+            Some(MockInput::Stdin) => tokio::spawn(read_lines(tokio::io::stdin(), shared)),
+            Some(MockInput::File(path)) => {
+                let file = tokio::fs::File::open(path).await.map_err(|e| {
+                    ChannelError::Config(format!("Cannot open {}: {e}", path.display()))
+                })?;
+                tokio::spawn(read_lines(file, shared))
+            }
+        };
+        *self.reader.lock().unwrap_or_else(PoisonError::into_inner) = Some(reader);
+        tracing::info!("Mock channel reading from {:?}", self.input);
+        Ok(())
+    }
+
+    async fn stop(&self) -> Result<(), ChannelError> {
+        let reader = self
+            .reader
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        if let Some(reader) = reader {
+            reader.abort();
+        }
+        Ok(())
+    }
+
+    async fn probe(&self) -> Result<ChannelProbe, ChannelError> {
+        Ok(ChannelProbe {
+            connected: true,
+            account_id: Some(self.shared.account_id.clone()),
+            display_name: Some("Mock".to_string()),
+            error: None,
+        })
+    }
+}
+
+#[async_trait]
+impl ChannelOutbound for MockChannel {
+    async fn send_text(
+        &self,
+        ctx: OutboundContext,
+        text: &str,
+    ) -> Result<DeliveryResult, ChannelError> {
+        Ok(self.record(
+            ctx,
+            MockAction::Text {
+                text: text.to_string(),
+            },
+        ))
+    }
+
+    async fn send_media(
+        &self,
+        ctx: OutboundContext,
+        media: &[Attachment],
+    ) -> Result<DeliveryResult, ChannelError> {
+        Ok(self.record(
+            ctx,
+            MockAction::Media {
+                attachments: media.to_vec(),
+            },
+        ))
+    }
+
+    async fn add_reaction(
+        &self,
+        ctx: OutboundContext,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<(), ChannelError> {
+        self.record(
+            ctx,
+            MockAction::Reaction {
+                target_id: message_id.to_string(),
+                emoji: emoji.to_string(),
+                removed: false,
+            },
+        );
+        Ok(())
+    }
+
+    async fn remove_reaction(
+        &self,
+        ctx: OutboundContext,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<(), ChannelError> {
+        self.record(
+            ctx,
+            MockAction::Reaction {
+                target_id: message_id.to_string(),
+                emoji: emoji.to_string(),
+                removed: true,
+            },
+        );
+        Ok(())
+    }
+
+    fn text_chunk_limit(&self) -> usize {
+        4096
+    }
+
+    fn delivery_mode(&self) -> DeliveryMode {
+        DeliveryMode::Immediate
+    }
+}
+
+#[async_trait]
+impl ChannelInbound for MockChannel {
+    type RawMessage = MockInbound;
+
+    fn normalize(&self, raw: Self::RawMessage) -> Result<Message, ChannelError> {
+        Ok(self.shared.normalize(raw))
+    }
+
+    async fn acknowledge(&self, _message_id: &str) -> Result<(), ChannelError> {
+        Ok(())
+    }
+}
+
+impl ChannelReactions for MockChannel {
+    type RawReaction = MockReaction;
+
+    fn normalize_reaction(&self, raw: Self::RawReaction) -> Result<MessageReaction, ChannelError> {
+        if raw.emoji.is_empty() {
+            return Err(ChannelError::Config("Reaction has no emoji".to_string()));
+        }
+        let raw_value = serde_json::to_value(&raw).ok();
+        Ok(MessageReaction {
+            channel: ChannelId::mock(),
+            account_id: self.shared.account_id.clone(),
+            chat_id: raw.chat_id.unwrap_or_else(|| raw.peer_id.clone()),
+            peer_id: PeerId::new(raw.peer_id),
+            message_id: raw.message_id,
+            emoji: raw.emoji,
+            removed: raw.removed,
+            timestamp: chrono::Utc::now(),
+            raw: raw_value,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    fn ctx(chat_id: &str) -> OutboundContext {
+        OutboundContext {
+            chat_id: chat_id.to_string(),
+            reply_to: None,
+            thread_id: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_line() {
+        assert!(MockInbound::parse_line("  ").unwrap().is_none());
+
+        let plain = MockInbound::parse_line("hello there").unwrap().unwrap();
+        assert_eq!(plain.peer_id, DEFAULT_PEER);
+        assert_eq!(plain.text, "hello there");
+
+        let json =
+            MockInbound::parse_line(r#"{"peerId": "alice", "text": "hi", "chatId": "room"}"#)
+                .unwrap()
+                .unwrap();
+        assert_eq!(json.peer_id, "alice");
+        assert_eq!(json.chat_id.as_deref(), Some("room"));
+
+        assert!(MockInbound::parse_line("{not json").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_inject() {
+        let channel = MockChannel::new();
+        let mut inbound = channel.take_inbound().unwrap();
+        assert!(channel.take_inbound().is_none());
+
+        let dm = channel.inject(MockInbound::text("alice", "hi")).unwrap();
+        assert_eq!(dm.peer_type, PeerType::Dm);
+        assert_eq!(dm.channel, ChannelId::mock());
+
+        let mut raw = MockInbound::text("bob", "hello all");
+        raw.chat_id = Some("room".to_string());
+        let group = channel.normalize(raw).unwrap();
+        assert_eq!(group.peer_type, PeerType::Group);
+        assert_eq!(chat_id(&group), "room");
+        assert_eq!(chat_id(&dm), "alice");
+
+        let received = inbound.recv().await.unwrap();
+        assert_eq!(received.id, dm.id);
+        assert_eq!(received.content, "hi");
+    }
+
+    #[tokio::test]
+    async fn test_records_outbound() {
+        let channel = MockChannel::new();
+        let sent = channel.send_text(ctx("alice"), "pong").await.unwrap();
+        channel
+            .add_reaction(ctx("alice"), "mock-in-1", "👍")
+            .await
+            .unwrap();
+
+        let outbound = channel.outbound();
+        assert_eq!(outbound.len(), 2);
+        assert_eq!(outbound[0].message_id, sent.message_id);
+        assert_eq!(
+            outbound[0].action,
+            MockAction::Text {
+                text: "pong".to_string()
+            }
+        );
+        assert!(matches!(
+            &outbound[1].action,
+            MockAction::Reaction { emoji, removed: false, .. } if emoji == "👍"
+        ));
+
+        assert_eq!(channel.take_outbound().len(), 2);
+        assert!(channel.outbound().is_empty());
+    }
+
+    #[test]
+    fn test_normalize_reaction() {
+        let channel = MockChannel::new();
+        let raw: MockReaction = serde_json::from_value(serde_json::json!({
+            "peerId": "alice",
+            "messageId": "mock-out-1",
+            "emoji": "👍"
+        }))
+        .unwrap();
+
+        let reaction = channel.normalize_reaction(raw.clone()).unwrap();
+        assert_eq!(reaction.channel, ChannelId::mock());
+        assert_eq!(reaction.chat_id, "alice");
+        assert_eq!(reaction.message_id, "mock-out-1");
+        assert!(!reaction.removed);
+
+        let empty = MockReaction {
+            emoji: String::new(),
+            ..raw
+        };
+        assert!(channel.normalize_reaction(empty).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reads_input_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "first").unwrap();
+        writeln!(file).unwrap();
+        writeln!(file, r#"{{"peerId": "alice", "text": "second"}}"#).unwrap();
+
+        let channel = MockChannel::new().with_input(MockInput::File(file.path().to_path_buf()));
+        let mut inbound = channel.take_inbound().unwrap();
+        channel
+            .start(ChannelContext {
+                agent_id: "default".to_string(),
+                account_id: "default".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let first = inbound.recv().await.unwrap();
+        assert_eq!(first.peer_id, PeerId::new(DEFAULT_PEER));
+        assert_eq!(first.content, "first");
+        let second = inbound.recv().await.unwrap();
+        assert_eq!(second.peer_id, PeerId::new("alice"));
+        assert_eq!(second.content, "second");
+    }
+}
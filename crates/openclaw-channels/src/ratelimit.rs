@@ -0,0 +1,301 @@
+//! Inbound message rate limiting.
+
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use governor::clock::{Clock, DefaultClock};
+use governor::state::keyed::DefaultKeyedStateStore;
+use governor::{Quota, RateLimiter};
+use serde::Serialize;
+
+use openclaw_core::config::InboundRateLimitConfig;
+use openclaw_core::types::{ChannelId, PeerId};
+
+type KeyedLimiter = RateLimiter<String, DefaultKeyedStateStore<String>, DefaultClock>;
+
+/// Most channels whose throttled messages are counted separately; beyond
+/// this, new channels are counted under [`OTHER_CHANNELS`].
+pub const MAX_COUNTED_CHANNELS: usize = 64;
+
+/// Key in [`RateLimitStats::limited_by_channel`] for channels past
+/// [`MAX_COUNTED_CHANNELS`].
+pub const OTHER_CHANNELS: &str = "other";
+
+/// Outcome of an inbound rate limit check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RateLimitDecision {
+    /// Message may be processed.
+    Allowed,
+    /// Message was throttled.
+    Limited {
+        /// Time until the next message would be accepted.
+        retry_after: Duration,
+        /// Configured reply to send back to the peer (`None` for the
+        /// localized `rate-limited` message).
+        cooldown_message: Option<String>,
+    },
+}
+
+impl RateLimitDecision {
+    /// Whether the message may be processed.
+    #[must_use]
+    pub const fn is_allowed(&self) -> bool {
+        matches!(self, Self::Allowed)
+    }
+}
+
+/// Rate limiter counters.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RateLimitStats {
+    /// Messages allowed.
+    pub allowed: u64,
+    /// Messages throttled.
+    pub limited: u64,
+    /// Messages throttled, by channel.
+    pub limited_by_channel: HashMap<String, u64>,
+}
+
+/// Token-bucket rate limiter keyed by (channel, peer) and by channel.
+///
+/// Protects against spam triggering expensive LLM calls. `GatewayRateLimiter`
+/// covers HTTP clients; this covers messages arriving from channels.
+pub struct InboundRateLimiter {
+    enabled: bool,
+    peer_limiter: KeyedLimiter,
+    channel_limiter: Option<KeyedLimiter>,
+    cooldown_message: Option<String>,
+    clock: DefaultClock,
+    allowed: AtomicU64,
+    limited: AtomicU64,
+    limited_by_channel: Mutex<HashMap<String, u64>>,
+}
+
+impl InboundRateLimiter {
+    /// Create a rate limiter from configuration.
+    #[must_use]
+    pub fn new(config: &InboundRateLimitConfig) -> Self {
+        let peer_quota = Quota::per_minute(non_zero(config.peer_per_minute))
+            .allow_burst(non_zero(config.peer_burst));
+
+        Self {
+            enabled: config.enabled,
+            peer_limiter: RateLimiter::keyed(peer_quota),
+            channel_limiter: config
+                .channel_per_minute
+                .map(|n| RateLimiter::keyed(Quota::per_minute(non_zero(n)))),
+            cooldown_message: config.cooldown_message.clone(),
+            clock: DefaultClock::default(),
+            allowed: AtomicU64::new(0),
+            limited: AtomicU64::new(0),
+            limited_by_channel: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Create a limiter that allows everything.
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self::new(&InboundRateLimitConfig {
+            enabled: false,
+            ..InboundRateLimitConfig::default()
+        })
+    }
+
+    /// Check whether a message from `peer_id` on `channel` may be processed.
+    pub fn check(&self, channel: &ChannelId, peer_id: &PeerId) -> RateLimitDecision {
+        if !self.enabled {
+            return RateLimitDecision::Allowed;
+        }
+
+        let peer_key = format!("{}:{}", channel.as_ref(), peer_id.as_ref());
+        let mut result = self.peer_limiter.check_key(&peer_key);
+
+        if result.is_ok() {
+            if let Some(limiter) = &self.channel_limiter {
+                result = limiter.check_key(&channel.as_ref().to_string());
+            }
+        }
+
+        match result {
+            Ok(()) => {
+                self.allowed.fetch_add(1, Ordering::Relaxed);
+                RateLimitDecision::Allowed
+            }
+            Err(not_until) => {
+                self.limited.fetch_add(1, Ordering::Relaxed);
+                if let Ok(mut by_channel) = self.limited_by_channel.lock() {
+                    let key = if by_channel.len() < MAX_COUNTED_CHANNELS
+                        || by_channel.contains_key(channel.as_ref())
+                    {
+                        channel.as_ref()
+                    } else {
+                        OTHER_CHANNELS
+                    };
+                    *by_channel.entry(key.to_string()).or_insert(0) += 1;
+                }
+
+                let retry_after = not_until.wait_time_from(self.clock.now());
+                let secs = retry_after.as_secs().max(1);
+                tracing::debug!("Rate limited {} on {} for {}s", peer_id, channel, secs);
+
+                RateLimitDecision::Limited {
+                    retry_after,
+                    cooldown_message: self
+                        .cooldown_message
+                        .as_ref()
+                        .map(|message| message.replace("{retry_after}", &secs.to_string())),
+                }
+            }
+        }
+    }
+
+    /// Drop state for peers and channels whose buckets have refilled.
+    ///
+    /// Those are indistinguishable from ones never seen, so this only frees
+    /// memory; call it periodically so one-off senders don't accumulate.
+    pub fn retain_recent(&self) {
+        self.peer_limiter.retain_recent();
+        self.peer_limiter.shrink_to_fit();
+        if let Some(limiter) = &self.channel_limiter {
+            limiter.retain_recent();
+            limiter.shrink_to_fit();
+        }
+    }
+
+    /// Snapshot of the counters.
+    #[must_use]
+    pub fn stats(&self) -> RateLimitStats {
+        RateLimitStats {
+            allowed: self.allowed.load(Ordering::Relaxed),
+            limited: self.limited.load(Ordering::Relaxed),
+            limited_by_channel: self
+                .limited_by_channel
+                .lock()
+                .map(|m| m.clone())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl Default for InboundRateLimiter {
+    fn default() -> Self {
+        Self::new(&InboundRateLimitConfig::default())
+    }
+}
+
+fn non_zero(n: u32) -> NonZeroU32 {
+    NonZeroU32::new(n).unwrap_or(NonZeroU32::MIN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(burst: u32) -> InboundRateLimitConfig {
+        InboundRateLimitConfig {
+            peer_per_minute: 1,
+            peer_burst: burst,
+            ..InboundRateLimitConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_peer_burst_then_limited() {
+        let limiter = InboundRateLimiter::new(&InboundRateLimitConfig {
+            cooldown_message: Some("Wait {retry_after}s".to_string()),
+            ..config(2)
+        });
+        let channel = ChannelId::telegram();
+        let peer = PeerId::new("123");
+
+        assert!(limiter.check(&channel, &peer).is_allowed());
+        assert!(limiter.check(&channel, &peer).is_allowed());
+
+        match limiter.check(&channel, &peer) {
+            RateLimitDecision::Limited {
+                cooldown_message, ..
+            } => {
+                let message = cooldown_message.unwrap();
+                assert!(message.starts_with("Wait ") && !message.contains("{retry_after}"));
+            }
+            RateLimitDecision::Allowed => panic!("expected limit"),
+        }
+
+        // Other peers are unaffected
+        assert!(limiter.check(&channel, &PeerId::new("456")).is_allowed());
+
+        let stats = limiter.stats();
+        assert_eq!(stats.allowed, 3);
+        assert_eq!(stats.limited, 1);
+        assert_eq!(stats.limited_by_channel.get("telegram"), Some(&1));
+    }
+
+    #[test]
+    fn test_channel_limit() {
+        let limiter = InboundRateLimiter::new(&InboundRateLimitConfig {
+            channel_per_minute: Some(1),
+            ..InboundRateLimitConfig::default()
+        });
+        let channel = ChannelId::discord();
+
+        assert!(limiter.check(&channel, &PeerId::new("a")).is_allowed());
+        assert!(!limiter.check(&channel, &PeerId::new("b")).is_allowed());
+        assert!(
+            limiter
+                .check(&ChannelId::telegram(), &PeerId::new("b"))
+                .is_allowed()
+        );
+    }
+
+    #[test]
+    fn test_retain_recent_drops_refilled_peers() {
+        // One message per millisecond, so buckets refill almost at once
+        let limiter = InboundRateLimiter::new(&InboundRateLimitConfig {
+            peer_per_minute: 60_000,
+            peer_burst: 1,
+            channel_per_minute: Some(60_000),
+            ..InboundRateLimitConfig::default()
+        });
+        let channel = ChannelId::telegram();
+        for peer in ["a", "b", "c"] {
+            assert!(limiter.check(&channel, &PeerId::new(peer)).is_allowed());
+        }
+        assert_eq!(limiter.peer_limiter.len(), 3);
+
+        std::thread::sleep(Duration::from_millis(20));
+        limiter.retain_recent();
+        assert!(limiter.peer_limiter.is_empty());
+        assert!(limiter.channel_limiter.as_ref().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_limited_by_channel_is_capped() {
+        let limiter = InboundRateLimiter::new(&InboundRateLimitConfig {
+            channel_per_minute: Some(1),
+            ..InboundRateLimitConfig::default()
+        });
+        let peer = PeerId::new("a");
+        for i in 0..MAX_COUNTED_CHANNELS + 2 {
+            let channel = ChannelId::new(format!("channel-{i}"));
+            assert!(limiter.check(&channel, &peer).is_allowed());
+            assert!(!limiter.check(&channel, &peer).is_allowed());
+        }
+
+        let stats = limiter.stats();
+        assert_eq!(stats.limited_by_channel.len(), MAX_COUNTED_CHANNELS + 1);
+        assert_eq!(stats.limited_by_channel.get(OTHER_CHANNELS), Some(&2));
+    }
+
+    #[test]
+    fn test_disabled() {
+        let limiter = InboundRateLimiter::disabled();
+        let channel = ChannelId::telegram();
+        let peer = PeerId::new("123");
+
+        for _ in 0..100 {
+            assert!(limiter.check(&channel, &peer).is_allowed());
+        }
+    }
+}
@@ -3,11 +3,12 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::traits::{Channel, ChannelError, ChannelProbe};
+use crate::traits::{Channel, ChannelError, ChannelOutbound, ChannelProbe};
 
 /// Registry of available channels.
 pub struct ChannelRegistry {
     channels: HashMap<String, Arc<dyn Channel>>,
+    outbound: HashMap<String, Arc<dyn ChannelOutbound>>,
 }
 
 impl ChannelRegistry {
@@ -16,6 +17,7 @@ impl ChannelRegistry {
     pub fn new() -> Self {
         Self {
             channels: HashMap::new(),
+            outbound: HashMap::new(),
         }
     }
 
@@ -24,12 +26,25 @@ impl ChannelRegistry {
         self.channels.insert(channel.id().to_string(), channel);
     }
 
+    /// Register a channel that can also send messages.
+    pub fn register_outbound<C: ChannelOutbound + 'static>(&mut self, channel: Arc<C>) {
+        let id = channel.id().to_string();
+        self.channels.insert(id.clone(), channel.clone());
+        self.outbound.insert(id, channel);
+    }
+
     /// Get a channel by ID.
     #[must_use]
     pub fn get(&self, id: &str) -> Option<&Arc<dyn Channel>> {
         self.channels.get(id)
     }
 
+    /// Get a channel's outbound interface by ID.
+    #[must_use]
+    pub fn get_outbound(&self, id: &str) -> Option<&Arc<dyn ChannelOutbound>> {
+        self.outbound.get(id)
+    }
+
     /// List all channel IDs.
     #[must_use]
     pub fn list(&self) -> Vec<&str> {
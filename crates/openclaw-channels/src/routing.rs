@@ -1,10 +1,13 @@
 //! Agent routing.
 
-use openclaw_core::types::{AgentId, ChannelId, PeerId};
+use openclaw_core::config::{ExperimentConfig, ExperimentVariant};
+use openclaw_core::types::{AgentId, ChannelId, PeerId, SessionKey};
 
 /// Route messages to appropriate agents.
+#[derive(Clone)]
 pub struct AgentRouter {
     routes: Vec<RouteRule>,
+    experiments: Vec<Experiment>,
     default_agent: AgentId,
 }
 
@@ -27,10 +30,48 @@ impl AgentRouter {
     pub const fn new(default_agent: AgentId) -> Self {
         Self {
             routes: Vec::new(),
+            experiments: Vec::new(),
             default_agent,
         }
     }
 
+    /// Agent that receives messages no rule matches.
+    #[must_use]
+    pub const fn default_agent(&self) -> &AgentId {
+        &self.default_agent
+    }
+
+    /// Add an experiment.
+    pub fn add_experiment(&mut self, experiment: Experiment) {
+        self.experiments.push(experiment);
+    }
+
+    /// Replace all experiments, keeping routing rules.
+    pub fn set_experiments(&mut self, experiments: Vec<Experiment>) {
+        self.experiments = experiments;
+    }
+
+    /// Get all experiments.
+    #[must_use]
+    pub fn experiments(&self) -> &[Experiment] {
+        &self.experiments
+    }
+
+    /// Assign a new session for `agent_id` to an experiment variant.
+    ///
+    /// Returns `None` if no enabled experiment targets the agent.
+    #[must_use]
+    pub fn assign_variant(
+        &self,
+        agent_id: &AgentId,
+        session_key: &SessionKey,
+    ) -> Option<VariantAssignment> {
+        self.experiments
+            .iter()
+            .filter(|e| e.enabled && e.agent.as_ref() == agent_id.as_ref())
+            .find_map(|e| e.assign(session_key))
+    }
+
     /// Add a routing rule.
     pub fn add_rule(&mut self, rule: RouteRule) {
         self.routes.push(rule);
@@ -60,6 +101,74 @@ impl AgentRouter {
     }
 }
 
+/// A/B experiment splitting an agent's sessions between variants.
+#[derive(Debug, Clone)]
+pub struct Experiment {
+    /// Experiment ID.
+    pub id: String,
+    /// Agent whose sessions are split.
+    pub agent: AgentId,
+    /// Whether new sessions are being assigned.
+    pub enabled: bool,
+    /// Variants with traffic weights.
+    pub variants: Vec<ExperimentVariant>,
+}
+
+/// Result of assigning a session to a variant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantAssignment {
+    /// Experiment ID.
+    pub experiment: String,
+    /// Variant name.
+    pub variant: String,
+    /// Agent that serves the variant.
+    pub agent_id: AgentId,
+}
+
+impl Experiment {
+    /// Create an experiment from configuration.
+    #[must_use]
+    pub fn from_config(id: impl Into<String>, config: &ExperimentConfig) -> Self {
+        Self {
+            id: id.into(),
+            agent: AgentId::new(&config.agent),
+            enabled: config.enabled,
+            variants: config.variants.clone(),
+        }
+    }
+
+    /// Deterministically assign a session to a variant.
+    ///
+    /// The same session key always lands in the same variant, so restarts
+    /// and replicas agree without shared state.
+    #[must_use]
+    pub fn assign(&self, session_key: &SessionKey) -> Option<VariantAssignment> {
+        let total: u64 = self.variants.iter().map(|v| u64::from(v.weight)).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let bucket = fnv1a(format!("{}:{}", self.id, session_key).as_bytes()) % total;
+
+        let mut cumulative = 0;
+        self.variants.iter().find_map(|v| {
+            cumulative += u64::from(v.weight);
+            (bucket < cumulative).then(|| VariantAssignment {
+                experiment: self.id.clone(),
+                variant: v.name.clone(),
+                agent_id: AgentId::new(&v.agent),
+            })
+        })
+    }
+}
+
+/// FNV-1a hash (stable across builds, unlike `DefaultHasher`).
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
 impl Default for AgentRouter {
     fn default() -> Self {
         Self::new(AgentId::default_agent())
@@ -93,4 +202,65 @@ mod tests {
         let agent = router.route(&ChannelId::telegram(), &PeerId::new("other"));
         assert_eq!(agent.as_ref(), "default");
     }
+
+    fn variant(name: &str, weight: u32) -> ExperimentVariant {
+        ExperimentVariant {
+            name: name.to_string(),
+            agent: format!("agent-{name}"),
+            weight,
+            input_cost_per_mtok: None,
+            output_cost_per_mtok: None,
+        }
+    }
+
+    #[test]
+    fn test_experiment_assignment_is_deterministic() {
+        let mut router = AgentRouter::default();
+        router.add_experiment(Experiment {
+            id: "prompt-v2".to_string(),
+            agent: AgentId::default_agent(),
+            enabled: true,
+            variants: vec![variant("a", 50), variant("b", 50)],
+        });
+
+        let mut seen_a = false;
+        let mut seen_b = false;
+        for i in 0..64 {
+            let key = SessionKey::new(format!("session-{i}"));
+            let first = router
+                .assign_variant(&AgentId::default_agent(), &key)
+                .unwrap();
+            let second = router
+                .assign_variant(&AgentId::default_agent(), &key)
+                .unwrap();
+            assert_eq!(first, second);
+            seen_a |= first.variant == "a";
+            seen_b |= first.variant == "b";
+        }
+        assert!(seen_a && seen_b);
+
+        assert!(
+            router
+                .assign_variant(&AgentId::new("other"), &SessionKey::new("x"))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_experiment_zero_weight_variant() {
+        let experiment = Experiment {
+            id: "off".to_string(),
+            agent: AgentId::default_agent(),
+            enabled: true,
+            variants: vec![variant("a", 0), variant("b", 1)],
+        };
+
+        for i in 0..16 {
+            let assignment = experiment
+                .assign(&SessionKey::new(format!("s{i}")))
+                .unwrap();
+            assert_eq!(assignment.variant, "b");
+            assert_eq!(assignment.agent_id.as_ref(), "agent-b");
+        }
+    }
 }
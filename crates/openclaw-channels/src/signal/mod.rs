@@ -1,18 +1,21 @@
 //! Signal channel adapter using Signal CLI REST API.
 
 use async_trait::async_trait;
+use base64::Engine;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use openclaw_core::types::{
-    Attachment, AttachmentKind, ChannelId, DeliveryResult, Message, PeerId, PeerType,
+    Attachment, AttachmentKind, ChannelId, DeliveryResult, Message, MessageReaction, PeerId,
+    PeerType,
 };
+use openclaw_core::validation::limits;
 
 use crate::traits::{
     Channel, ChannelCapabilities, ChannelContext, ChannelError, ChannelInbound, ChannelOutbound,
-    ChannelProbe, DeliveryMode, OutboundContext,
+    ChannelProbe, ChannelReactions, DeliveryMode, OutboundContext,
 };
 
 /// Signal channel adapter.
@@ -113,6 +116,146 @@ impl SignalChannel {
 
         Ok(())
     }
+
+    /// Build reaction parameters for a message.
+    ///
+    /// Signal identifies messages by author and timestamp. `message_id` is
+    /// either `author:timestamp` or a bare timestamp, in which case the chat
+    /// peer is assumed to be the author (true for direct messages).
+    fn reaction_params(
+        ctx: &OutboundContext,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<ReactionParams, ChannelError> {
+        let (author, timestamp) = message_id
+            .rsplit_once(':')
+            .unwrap_or((ctx.chat_id.as_str(), message_id));
+        let timestamp = timestamp.parse().map_err(|_| {
+            ChannelError::DeliveryFailed(format!("Invalid Signal message ID: {message_id}"))
+        })?;
+
+        Ok(ReactionParams {
+            recipient: ctx.chat_id.clone(),
+            reaction: emoji.to_string(),
+            target_author: author.to_string(),
+            timestamp,
+        })
+    }
+
+    /// Load an attachment and encode it as a signal-cli data URI.
+    ///
+    /// Accepts `http(s)://` URLs, `file://` URLs, and local paths.
+    async fn encode_attachment(&self, attachment: &Attachment) -> Result<String, ChannelError> {
+        let max = limits::MAX_ATTACHMENT_SIZE;
+        if attachment.size.is_some_and(|s| s > max as u64) {
+            return Err(too_large(attachment, max));
+        }
+
+        let (data, fetched_mime) =
+            if attachment.url.starts_with("http://") || attachment.url.starts_with("https://") {
+                self.download(attachment, max).await?
+            } else {
+                let path = attachment
+                    .url
+                    .strip_prefix("file://")
+                    .unwrap_or(&attachment.url);
+                let size = tokio::fs::metadata(path)
+                    .await
+                    .map_err(|e| ChannelError::DeliveryFailed(format!("{path}: {e}")))?
+                    .len();
+                if size > max as u64 {
+                    return Err(too_large(attachment, max));
+                }
+                let data = tokio::fs::read(path)
+                    .await
+                    .map_err(|e| ChannelError::DeliveryFailed(format!("{path}: {e}")))?;
+                (data, None)
+            };
+
+        let filename = attachment.filename.clone().unwrap_or_else(|| {
+            attachment
+                .url
+                .rsplit('/')
+                .next()
+                .and_then(|name| name.split(['?', '#']).next())
+                .filter(|name| !name.is_empty())
+                .unwrap_or("attachment")
+                .to_string()
+        });
+        let mime_type = attachment
+            .mime_type
+            .clone()
+            .or(fetched_mime)
+            .unwrap_or_else(|| {
+                mime_guess::from_path(&filename)
+                    .first_or_octet_stream()
+                    .essence_str()
+                    .to_string()
+            });
+
+        Ok(format!(
+            "data:{mime_type};filename={filename};base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(data)
+        ))
+    }
+
+    /// Download an attachment, stopping once it exceeds `max` bytes.
+    async fn download(
+        &self,
+        attachment: &Attachment,
+        max: usize,
+    ) -> Result<(Vec<u8>, Option<String>), ChannelError> {
+        let mut response = self
+            .client
+            .get(&attachment.url)
+            .send()
+            .await
+            .map_err(|e| ChannelError::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(ChannelError::DeliveryFailed(format!(
+                "Failed to fetch {}: {}",
+                attachment.url,
+                response.status()
+            )));
+        }
+        if response
+            .content_length()
+            .is_some_and(|len| len > max as u64)
+        {
+            return Err(too_large(attachment, max));
+        }
+
+        let mime_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(';').next())
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty() && v != "application/octet-stream");
+
+        let mut data = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| ChannelError::Network(e.to_string()))?
+        {
+            if data.len() + chunk.len() > max {
+                return Err(too_large(attachment, max));
+            }
+            data.extend_from_slice(&chunk);
+        }
+
+        Ok((data, mime_type))
+    }
+}
+
+fn too_large(attachment: &Attachment, max: usize) -> ChannelError {
+    ChannelError::DeliveryFailed(format!(
+        "Attachment {} exceeds {} byte limit",
+        attachment.filename.as_deref().unwrap_or(&attachment.url),
+        max
+    ))
 }
 
 #[async_trait]
@@ -221,13 +364,58 @@ impl ChannelOutbound for SignalChannel {
         ctx: OutboundContext,
         media: &[Attachment],
     ) -> Result<DeliveryResult, ChannelError> {
-        // Signal CLI REST API expects base64 attachments
-        // For URLs, we'd need to download and encode them
-        // Simplified: send as text with URLs
-        let urls: Vec<String> = media.iter().map(|a| a.url.clone()).collect();
-        let text = urls.join("\n");
+        let mut attachments = Vec::with_capacity(media.len());
+        for attachment in media {
+            attachments.push(self.encode_attachment(attachment).await?);
+        }
+
+        let params = SendMessageParams {
+            number: self.phone_number.clone(),
+            recipients: vec![ctx.chat_id.clone()],
+            message: String::new(),
+            base64_attachments: Some(attachments),
+        };
+
+        let results: Vec<SendResult> = self
+            .call(reqwest::Method::POST, "/v2/send", Some(&params))
+            .await?;
+
+        let timestamp = results
+            .first()
+            .map(|r| r.timestamp.to_string())
+            .unwrap_or_default();
 
-        self.send_text(ctx, &text).await
+        Ok(DeliveryResult {
+            message_id: timestamp,
+            channel: ChannelId::signal(),
+            timestamp: chrono::Utc::now(),
+            chat_id: Some(ctx.chat_id),
+            meta: None,
+        })
+    }
+
+    async fn add_reaction(
+        &self,
+        ctx: OutboundContext,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<(), ChannelError> {
+        let params = Self::reaction_params(&ctx, message_id, emoji)?;
+        let endpoint = format!("/v1/reactions/{}", self.phone_number);
+        self.call_no_response(reqwest::Method::POST, &endpoint, Some(&params))
+            .await
+    }
+
+    async fn remove_reaction(
+        &self,
+        ctx: OutboundContext,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<(), ChannelError> {
+        let params = Self::reaction_params(&ctx, message_id, emoji)?;
+        let endpoint = format!("/v1/reactions/{}", self.phone_number);
+        self.call_no_response(reqwest::Method::DELETE, &endpoint, Some(&params))
+            .await
     }
 
     fn text_chunk_limit(&self) -> usize {
@@ -307,10 +495,16 @@ impl ChannelInbound for SignalChannel {
             timestamp,
             reply_to: data_message.quote.map(|q| q.id.to_string()),
             thread_id: None,
+            // Mentions of this account become its number, the account ID
             mentions: data_message
                 .mentions
-                .map(|m| m.into_iter().map(|mention| mention.uuid).collect())
-                .unwrap_or_default(),
+                .unwrap_or_default()
+                .into_iter()
+                .map(|mention| match mention.number {
+                    Some(number) if number == self.phone_number => number,
+                    _ => mention.uuid,
+                })
+                .collect(),
             raw: Some(raw_value),
         })
     }
@@ -321,6 +515,47 @@ impl ChannelInbound for SignalChannel {
     }
 }
 
+impl ChannelReactions for SignalChannel {
+    type RawReaction = SignalMessage;
+
+    fn normalize_reaction(&self, raw: Self::RawReaction) -> Result<MessageReaction, ChannelError> {
+        let raw_value = serde_json::to_value(&raw).unwrap_or_default();
+
+        let envelope = raw
+            .envelope
+            .ok_or_else(|| ChannelError::Config("No envelope in message".to_string()))?;
+
+        let source = envelope
+            .source
+            .ok_or_else(|| ChannelError::Config("No source in envelope".to_string()))?;
+
+        let data_message = envelope
+            .data_message
+            .ok_or_else(|| ChannelError::Config("No data message".to_string()))?;
+
+        let reaction = data_message
+            .reaction
+            .ok_or_else(|| ChannelError::Config("Not a reaction".to_string()))?;
+
+        let chat_id = data_message
+            .group_info
+            .map_or_else(|| source.clone(), |g| g.group_id);
+
+        Ok(MessageReaction {
+            channel: ChannelId::signal(),
+            account_id: self.phone_number.clone(),
+            peer_id: PeerId::new(source),
+            chat_id,
+            message_id: reaction.target_sent_timestamp.to_string(),
+            emoji: reaction.emoji,
+            removed: reaction.is_remove,
+            timestamp: chrono::DateTime::from_timestamp_millis(envelope.timestamp.unwrap_or(0))
+                .unwrap_or_else(chrono::Utc::now),
+            raw: Some(raw_value),
+        })
+    }
+}
+
 // Signal CLI REST API types
 
 /// About response.
@@ -339,6 +574,15 @@ struct SendMessageParams {
     base64_attachments: Option<Vec<String>>,
 }
 
+/// Reaction parameters.
+#[derive(Debug, Serialize)]
+struct ReactionParams {
+    recipient: String,
+    reaction: String,
+    target_author: String,
+    timestamp: i64,
+}
+
 /// Send result.
 #[derive(Debug, Deserialize)]
 struct SendResult {
@@ -388,6 +632,24 @@ pub struct SignalDataMessage {
     pub quote: Option<SignalQuote>,
     /// Mentions.
     pub mentions: Option<Vec<SignalMention>>,
+    /// Reaction (if this message is a reaction).
+    pub reaction: Option<SignalReaction>,
+}
+
+/// Signal reaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalReaction {
+    /// Reaction emoji.
+    pub emoji: String,
+    /// Author of the message reacted to.
+    #[serde(rename = "targetAuthor")]
+    pub target_author: Option<String>,
+    /// Timestamp (ID) of the message reacted to.
+    #[serde(rename = "targetSentTimestamp")]
+    pub target_sent_timestamp: i64,
+    /// Whether the reaction was removed.
+    #[serde(rename = "isRemove", default)]
+    pub is_remove: bool,
 }
 
 /// Signal attachment.
@@ -429,6 +691,9 @@ pub struct SignalQuote {
 pub struct SignalMention {
     /// Mentioned user UUID.
     pub uuid: String,
+    /// Mentioned user's phone number, if known.
+    #[serde(default)]
+    pub number: Option<String>,
     /// Start position in text.
     pub start: Option<i32>,
     /// Length of mention.
@@ -455,4 +720,80 @@ mod tests {
         assert!(!caps.threads);
         assert!(!caps.editing);
     }
+
+    #[test]
+    fn test_reactions() {
+        let channel = SignalChannel::new("http://localhost:8080", "+1234567890");
+        let message: SignalMessage = serde_json::from_value(serde_json::json!({
+            "envelope": {
+                "source": "+15550001",
+                "timestamp": 1_700_000_000_500_i64,
+                "dataMessage": {
+                    "reaction": {
+                        "emoji": "❤️",
+                        "targetAuthor": "+1234567890",
+                        "targetSentTimestamp": 1_700_000_000_000_i64,
+                        "isRemove": false
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        let reaction = channel.normalize_reaction(message).unwrap();
+        assert_eq!(reaction.chat_id, "+15550001");
+        assert_eq!(reaction.message_id, "1700000000000");
+        assert!(!reaction.removed);
+
+        let ctx = OutboundContext {
+            chat_id: "group.abc".to_string(),
+            reply_to: None,
+            thread_id: None,
+        };
+        let params = SignalChannel::reaction_params(&ctx, "+15550001:1700000000000", "👍").unwrap();
+        assert_eq!(params.target_author, "+15550001");
+        assert_eq!(params.timestamp, 1_700_000_000_000);
+        assert!(SignalChannel::reaction_params(&ctx, "abc", "👍").is_err());
+    }
+
+    fn local_attachment(path: &std::path::Path, mime_type: Option<&str>) -> Attachment {
+        Attachment {
+            kind: AttachmentKind::Image,
+            url: path.to_string_lossy().into_owned(),
+            mime_type: mime_type.map(String::from),
+            filename: None,
+            size: None,
+            thumbnail_url: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_encode_local_attachment() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("chart.png");
+        std::fs::write(&path, b"png").unwrap();
+
+        let channel = SignalChannel::new("http://localhost:8080", "+1234567890");
+        let encoded = channel
+            .encode_attachment(&local_attachment(&path, None))
+            .await
+            .unwrap();
+        assert_eq!(encoded, "data:image/png;filename=chart.png;base64,cG5n");
+
+        let encoded = channel
+            .encode_attachment(&local_attachment(&path, Some("image/webp")))
+            .await
+            .unwrap();
+        assert!(encoded.starts_with("data:image/webp;"));
+    }
+
+    #[tokio::test]
+    async fn test_encode_rejects_oversized() {
+        let channel = SignalChannel::new("http://localhost:8080", "+1234567890");
+        let mut attachment = local_attachment(std::path::Path::new("/nonexistent"), None);
+        attachment.size = Some(limits::MAX_ATTACHMENT_SIZE as u64 + 1);
+
+        let err = channel.encode_attachment(&attachment).await.unwrap_err();
+        assert!(err.to_string().contains("exceeds"));
+    }
 }
@@ -8,12 +8,14 @@ use tokio::sync::RwLock;
 
 use openclaw_core::secrets::ApiKey;
 use openclaw_core::types::{
-    Attachment, AttachmentKind, ChannelId, DeliveryResult, Message, PeerId, PeerType,
+    Attachment, AttachmentKind, ChannelId, DeliveryResult, Message, MessageReaction, PeerId,
+    PeerType,
 };
 
+use crate::group::parse_mentions;
 use crate::traits::{
     Channel, ChannelCapabilities, ChannelContext, ChannelError, ChannelInbound, ChannelOutbound,
-    ChannelProbe, DeliveryMode, OutboundContext,
+    ChannelProbe, ChannelReactions, DeliveryMode, OutboundContext,
 };
 
 const SLACK_API_BASE: &str = "https://slack.com/api";
@@ -234,6 +236,38 @@ impl ChannelOutbound for SlackChannel {
         })
     }
 
+    async fn add_reaction(
+        &self,
+        ctx: OutboundContext,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<(), ChannelError> {
+        let params = ReactionParams {
+            channel: ctx.chat_id,
+            timestamp: message_id.to_string(),
+            name: slack_reaction_name(emoji),
+        };
+        self.call::<serde_json::Value>("reactions.add", Some(&params))
+            .await
+            .map(|_| ())
+    }
+
+    async fn remove_reaction(
+        &self,
+        ctx: OutboundContext,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<(), ChannelError> {
+        let params = ReactionParams {
+            channel: ctx.chat_id,
+            timestamp: message_id.to_string(),
+            name: slack_reaction_name(emoji),
+        };
+        self.call::<serde_json::Value>("reactions.remove", Some(&params))
+            .await
+            .map(|_| ())
+    }
+
     fn text_chunk_limit(&self) -> usize {
         40000 // Slack text limit (blocks have different limits)
     }
@@ -272,8 +306,10 @@ impl ChannelInbound for SlackChannel {
             PeerType::Group
         };
 
-        let state = futures::executor::block_on(self.state.read());
-        let account_id = state.bot_user_id.clone().unwrap_or_default();
+        let account_id = futures::executor::block_on(self.state.read())
+            .bot_user_id
+            .clone()
+            .unwrap_or_default();
 
         // Convert files to attachments
         let attachments = event
@@ -311,18 +347,19 @@ impl ChannelInbound for SlackChannel {
             .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
             .unwrap_or_else(chrono::Utc::now);
 
+        let content = event.text.unwrap_or_default();
         Ok(Message {
             id: event.ts.clone().unwrap_or_default(),
             channel: ChannelId::slack(),
             account_id,
             peer_id: PeerId::new(user_id),
             peer_type,
-            content: event.text.unwrap_or_default(),
+            mentions: parse_mentions("slack", &content),
+            content,
             attachments,
             timestamp,
             reply_to: None, // Slack uses thread_ts, not explicit replies
             thread_id: event.thread_ts,
-            mentions: Vec::new(), // Would need to parse <@USER_ID> from text
             raw: Some(raw_value),
         })
     }
@@ -333,6 +370,76 @@ impl ChannelInbound for SlackChannel {
     }
 }
 
+impl ChannelReactions for SlackChannel {
+    type RawReaction = SlackEvent;
+
+    fn normalize_reaction(&self, raw: Self::RawReaction) -> Result<MessageReaction, ChannelError> {
+        let raw_value = serde_json::to_value(&raw).unwrap_or_default();
+
+        let event = raw
+            .event
+            .ok_or_else(|| ChannelError::Config("No event data".to_string()))?;
+
+        let removed = match event.event_type.as_str() {
+            "reaction_added" => false,
+            "reaction_removed" => true,
+            _ => return Err(ChannelError::Config("Not a reaction event".to_string())),
+        };
+
+        let (Some(user), Some(emoji), Some(item)) = (event.user, event.reaction, event.item) else {
+            return Err(ChannelError::Config(
+                "Incomplete reaction event".to_string(),
+            ));
+        };
+
+        let account_id = futures::executor::block_on(self.state.read())
+            .bot_user_id
+            .clone()
+            .unwrap_or_default();
+
+        let timestamp = event
+            .event_ts
+            .as_ref()
+            .and_then(|ts| ts.split('.').next())
+            .and_then(|s| s.parse::<i64>().ok())
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            .unwrap_or_else(chrono::Utc::now);
+
+        Ok(MessageReaction {
+            channel: ChannelId::slack(),
+            account_id,
+            peer_id: PeerId::new(user),
+            chat_id: item.channel.unwrap_or_default(),
+            message_id: item.ts.unwrap_or_default(),
+            emoji,
+            removed,
+            timestamp,
+            raw: Some(raw_value),
+        })
+    }
+}
+
+/// Map an emoji to the shortcode Slack's reaction API expects.
+///
+/// Shortcodes (with or without colons) pass through; common Unicode emoji
+/// are translated.
+fn slack_reaction_name(emoji: &str) -> String {
+    let name = match emoji.trim().trim_end_matches('\u{FE0F}') {
+        "👍" => "+1",
+        "👎" => "-1",
+        "❤" => "heart",
+        "👀" => "eyes",
+        "✅" => "white_check_mark",
+        "🎉" => "tada",
+        "😂" => "joy",
+        "🙏" => "pray",
+        "🔥" => "fire",
+        "🤔" => "thinking_face",
+        other => other.trim_matches(':'),
+    };
+    name.to_string()
+}
+
 // Slack API types
 
 /// Generic Slack API response wrapper.
@@ -377,6 +484,14 @@ struct ChatPostMessageParams {
     attachments: Option<Vec<SlackAttachment>>,
 }
 
+/// reactions.add / reactions.remove parameters.
+#[derive(Debug, Serialize)]
+struct ReactionParams {
+    channel: String,
+    timestamp: String,
+    name: String,
+}
+
 /// Slack attachment (legacy format, still works).
 #[derive(Debug, Serialize)]
 struct SlackAttachment {
@@ -426,6 +541,24 @@ pub struct SlackMessageEvent {
     pub files: Option<Vec<SlackFile>>,
     /// Message subtype (e.g., "`bot_message`").
     pub subtype: Option<String>,
+    /// Reaction shortcode (reaction events).
+    pub reaction: Option<String>,
+    /// Item reacted to (reaction events).
+    pub item: Option<SlackReactionItem>,
+    /// Event timestamp.
+    pub event_ts: Option<String>,
+}
+
+/// Item a reaction was added to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlackReactionItem {
+    /// Item type (e.g., "message").
+    #[serde(rename = "type")]
+    pub item_type: String,
+    /// Channel ID.
+    pub channel: Option<String>,
+    /// Message timestamp.
+    pub ts: Option<String>,
 }
 
 /// Slack file object.
@@ -470,4 +603,29 @@ mod tests {
         let channel = SlackChannel::new(ApiKey::new("test".to_string()));
         assert_eq!(channel.text_chunk_limit(), 40000);
     }
+
+    #[test]
+    fn test_normalize_reaction() {
+        let channel = SlackChannel::new(ApiKey::new("test".to_string()));
+        let event: SlackEvent = serde_json::from_value(serde_json::json!({
+            "type": "event_callback",
+            "event": {
+                "type": "reaction_added",
+                "user": "U123",
+                "reaction": "thumbsup",
+                "item": { "type": "message", "channel": "C1", "ts": "1700000000.000100" },
+                "event_ts": "1700000001.000200"
+            }
+        }))
+        .unwrap();
+
+        let reaction = channel.normalize_reaction(event).unwrap();
+        assert_eq!(reaction.chat_id, "C1");
+        assert_eq!(reaction.message_id, "1700000000.000100");
+        assert_eq!(reaction.emoji, "thumbsup");
+        assert!(!reaction.removed);
+
+        assert_eq!(slack_reaction_name("👍"), "+1");
+        assert_eq!(slack_reaction_name(":eyes:"), "eyes");
+    }
 }
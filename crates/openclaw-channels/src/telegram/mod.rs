@@ -11,6 +11,7 @@ use openclaw_core::types::{
     Attachment, AttachmentKind, ChannelId, DeliveryResult, Message, PeerId, PeerType,
 };
 
+use crate::group::parse_mentions;
 use crate::traits::{
     Channel, ChannelCapabilities, ChannelContext, ChannelError, ChannelInbound, ChannelOutbound,
     ChannelProbe, DeliveryMode, OutboundContext,
@@ -298,19 +299,24 @@ impl ChannelInbound for TelegramChannel {
             });
         }
 
+        let content = message.text.unwrap_or_default();
+        let mentions = message.entities.as_ref().map_or_else(
+            || parse_mentions("telegram", &content),
+            |entities| entity_mentions(&content, entities, state.username.as_deref(), &account_id),
+        );
         Ok(Message {
             id: message.message_id.to_string(),
             channel: ChannelId::telegram(),
             account_id,
             peer_id: PeerId::new(from.id.to_string()),
             peer_type,
-            content: message.text.unwrap_or_default(),
+            mentions,
+            content,
             attachments,
             timestamp: chrono::DateTime::from_timestamp(message.date, 0)
                 .unwrap_or_else(chrono::Utc::now),
             reply_to: message.reply_to_message.map(|m| m.message_id.to_string()),
             thread_id: message.message_thread_id.map(|id| id.to_string()),
-            mentions: Vec::new(),
             raw: Some(raw_value),
         })
     }
@@ -359,6 +365,9 @@ pub struct TelegramMessage {
     pub chat: TelegramChat,
     pub from: Option<TelegramUser>,
     pub text: Option<String>,
+    /// Mentions, links, and formatting in `text`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entities: Option<Vec<TelegramEntity>>,
     pub caption: Option<String>,
     pub reply_to_message: Option<Box<Self>>,
     pub message_thread_id: Option<i64>,
@@ -368,6 +377,49 @@ pub struct TelegramMessage {
     pub video: Option<TelegramVideo>,
 }
 
+/// A span of a message's text with a meaning, such as a mention.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegramEntity {
+    /// Entity type (`mention`, `text_mention`, `url`, ...).
+    #[serde(rename = "type")]
+    pub entity_type: String,
+    /// Start, in UTF-16 code units.
+    pub offset: usize,
+    /// Length, in UTF-16 code units.
+    pub length: usize,
+    /// The user, for `text_mention` (users without a username).
+    pub user: Option<TelegramUser>,
+}
+
+/// Users mentioned by `entities` in `text`: by username, or by ID for
+/// users without one. A mention of the bot's own username becomes its
+/// account ID.
+fn entity_mentions(
+    text: &str,
+    entities: &[TelegramEntity],
+    bot_username: Option<&str>,
+    account_id: &str,
+) -> Vec<String> {
+    let utf16: Vec<u16> = text.encode_utf16().collect();
+    entities
+        .iter()
+        .filter_map(|entity| match entity.entity_type.as_str() {
+            "mention" => {
+                let span = utf16.get(entity.offset..entity.offset + entity.length)?;
+                let username = String::from_utf16_lossy(span);
+                let username = username.trim_start_matches('@');
+                if bot_username.is_some_and(|bot| bot.eq_ignore_ascii_case(username)) {
+                    Some(account_id.to_string())
+                } else {
+                    Some(username.to_string())
+                }
+            }
+            "text_mention" => entity.user.as_ref().map(|user| user.id.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TelegramChat {
     pub id: i64,
@@ -437,6 +489,35 @@ mod tests {
         assert_eq!(channel.id(), "telegram");
     }
 
+    #[test]
+    fn test_entity_mentions() {
+        let entity = |entity_type: &str, offset, length, user| TelegramEntity {
+            entity_type: entity_type.to_string(),
+            offset,
+            length,
+            user,
+        };
+        let ada = TelegramUser {
+            id: 42,
+            is_bot: false,
+            first_name: "Ada".to_string(),
+            last_name: None,
+            username: None,
+        };
+        // The emoji is two UTF-16 code units, shifting the offsets after it
+        let text = "🦀 @Claw_Bot ask Ada and @bob";
+        let entities = [
+            entity("mention", 3, 9, None),
+            entity("text_mention", 17, 3, Some(ada)),
+            entity("mention", 25, 4, None),
+            entity("bold", 0, 2, None),
+        ];
+        assert_eq!(
+            entity_mentions(text, &entities, Some("claw_bot"), "7001"),
+            ["7001", "42", "bob"]
+        );
+    }
+
     #[test]
     fn test_capabilities() {
         let channel = TelegramChannel::new(ApiKey::new("test".to_string()));
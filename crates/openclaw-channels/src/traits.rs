@@ -3,7 +3,7 @@
 use async_trait::async_trait;
 use thiserror::Error;
 
-use openclaw_core::types::{Attachment, DeliveryResult, Message};
+use openclaw_core::types::{Attachment, DeliveryResult, Message, MessageReaction};
 
 /// Channel errors.
 #[derive(Error, Debug)]
@@ -31,6 +31,10 @@ pub enum ChannelError {
     /// Configuration error.
     #[error("Configuration error: {0}")]
     Config(String),
+
+    /// Operation not supported by this channel.
+    #[error("Not supported: {0}")]
+    Unsupported(String),
 }
 
 /// Channel capabilities.
@@ -139,6 +143,40 @@ pub trait ChannelOutbound: Channel {
         media: &[Attachment],
     ) -> Result<DeliveryResult, ChannelError>;
 
+    /// Add an emoji reaction to a message.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Unsupported` unless the channel implements reactions.
+    async fn add_reaction(
+        &self,
+        _ctx: OutboundContext,
+        _message_id: &str,
+        _emoji: &str,
+    ) -> Result<(), ChannelError> {
+        Err(ChannelError::Unsupported(format!(
+            "reactions on {}",
+            self.id()
+        )))
+    }
+
+    /// Remove a reaction previously added by this account.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Unsupported` unless the channel implements reactions.
+    async fn remove_reaction(
+        &self,
+        _ctx: OutboundContext,
+        _message_id: &str,
+        _emoji: &str,
+    ) -> Result<(), ChannelError> {
+        Err(ChannelError::Unsupported(format!(
+            "reactions on {}",
+            self.id()
+        )))
+    }
+
     /// Maximum text message length.
     fn text_chunk_limit(&self) -> usize;
 
@@ -158,3 +196,16 @@ pub trait ChannelInbound: Channel {
     /// Acknowledge message receipt.
     async fn acknowledge(&self, message_id: &str) -> Result<(), ChannelError>;
 }
+
+/// Inbound reaction handling trait.
+pub trait ChannelReactions: Channel {
+    /// Raw reaction event from the platform.
+    type RawReaction;
+
+    /// Normalize a raw reaction event to common format.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Config` if the event is not a reaction.
+    fn normalize_reaction(&self, raw: Self::RawReaction) -> Result<MessageReaction, ChannelError>;
+}
@@ -1,5 +1,11 @@
 //! WhatsApp channel adapter using the Cloud API.
 
+mod signature;
+mod template;
+
+pub use signature::{SIGNATURE_HEADER, verify_signature};
+pub use template::{TemplateButton, TemplateMessage, TemplateParameter};
+
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -8,12 +14,14 @@ use tokio::sync::RwLock;
 
 use openclaw_core::secrets::ApiKey;
 use openclaw_core::types::{
-    Attachment, AttachmentKind, ChannelId, DeliveryResult, Message, PeerId, PeerType,
+    Attachment, AttachmentKind, ChannelId, DeliveryResult, Message, MessageReaction, PeerId,
+    PeerType,
 };
 
+use crate::group::parse_mentions;
 use crate::traits::{
     Channel, ChannelCapabilities, ChannelContext, ChannelError, ChannelInbound, ChannelOutbound,
-    ChannelProbe, DeliveryMode, OutboundContext,
+    ChannelProbe, ChannelReactions, DeliveryMode, OutboundContext,
 };
 
 const WHATSAPP_API_BASE: &str = "https://graph.facebook.com/v18.0";
@@ -23,6 +31,7 @@ pub struct WhatsAppChannel {
     client: Client,
     access_token: ApiKey,
     phone_number_id: String,
+    app_secret: Option<ApiKey>,
     state: Arc<RwLock<WhatsAppState>>,
 }
 
@@ -45,10 +54,131 @@ impl WhatsAppChannel {
             client: Client::new(),
             access_token,
             phone_number_id: phone_number_id.into(),
+            app_secret: None,
             state: Arc::new(RwLock::new(WhatsAppState::default())),
         }
     }
 
+    /// Set the app secret used to verify webhook signatures.
+    #[must_use]
+    pub fn with_app_secret(mut self, app_secret: ApiKey) -> Self {
+        self.app_secret = Some(app_secret);
+        self
+    }
+
+    /// Verify a webhook request body against its `X-Hub-Signature-256` header.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Config` if no app secret is set, or `AuthFailed` if the
+    /// signature is missing or does not match.
+    pub fn verify_webhook(&self, body: &[u8], signature: Option<&str>) -> Result<(), ChannelError> {
+        let app_secret = self.app_secret.as_ref().ok_or_else(|| {
+            ChannelError::Config("WhatsApp app secret is not configured".to_string())
+        })?;
+        let signature = signature.ok_or_else(|| {
+            ChannelError::AuthFailed(format!("Missing {SIGNATURE_HEADER} header"))
+        })?;
+        verify_signature(app_secret.expose(), body, signature)
+    }
+
+    /// Verify and parse a webhook request body.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if verification fails or the body is not a webhook payload.
+    pub fn parse_webhook(
+        &self,
+        body: &[u8],
+        signature: Option<&str>,
+    ) -> Result<WhatsAppWebhookPayload, ChannelError> {
+        self.verify_webhook(body, signature)?;
+        serde_json::from_slice(body)
+            .map_err(|e| ChannelError::Config(format!("Invalid webhook payload: {e}")))
+    }
+
+    /// Send a pre-approved template message.
+    ///
+    /// Required to start a conversation, or to message a user more than 24
+    /// hours after their last message.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the API rejects the template or parameters.
+    pub async fn send_template(
+        &self,
+        ctx: OutboundContext,
+        template: &TemplateMessage,
+    ) -> Result<DeliveryResult, ChannelError> {
+        let endpoint = format!("/{}/messages", self.phone_number_id);
+
+        let params = SendMessageRequest {
+            messaging_product: "whatsapp".to_string(),
+            recipient_type: "individual".to_string(),
+            to: ctx.chat_id.clone(),
+            message_type: "template".to_string(),
+            text: None,
+            image: None,
+            video: None,
+            audio: None,
+            document: None,
+            context: None,
+            reaction: None,
+            template: Some(template.to_wire()),
+        };
+
+        let result: SendMessageResponse = self
+            .call(reqwest::Method::POST, &endpoint, Some(&params))
+            .await?;
+
+        Ok(DeliveryResult {
+            message_id: result
+                .messages
+                .first()
+                .map(|m| m.id.clone())
+                .unwrap_or_default(),
+            channel: ChannelId::whatsapp(),
+            timestamp: chrono::Utc::now(),
+            chat_id: Some(ctx.chat_id),
+            meta: Some(serde_json::json!({
+                "template": template.name,
+                "language": template.language,
+            })),
+        })
+    }
+
+    /// Send a reaction; an empty emoji removes the current one.
+    async fn send_reaction(
+        &self,
+        ctx: OutboundContext,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<(), ChannelError> {
+        let endpoint = format!("/{}/messages", self.phone_number_id);
+
+        let params = SendMessageRequest {
+            messaging_product: "whatsapp".to_string(),
+            recipient_type: "individual".to_string(),
+            to: ctx.chat_id,
+            message_type: "reaction".to_string(),
+            text: None,
+            image: None,
+            video: None,
+            audio: None,
+            document: None,
+            context: None,
+            reaction: Some(ReactionContent {
+                message_id: message_id.to_string(),
+                emoji: emoji.to_string(),
+            }),
+            template: None,
+        };
+
+        self.call::<SendMessageResponse>(reqwest::Method::POST, &endpoint, Some(&params))
+            .await
+            .map(|_| ())
+    }
+
     /// Call a `WhatsApp` Cloud API endpoint.
     async fn call<T: for<'de> Deserialize<'de>>(
         &self,
@@ -186,6 +316,8 @@ impl ChannelOutbound for WhatsAppChannel {
             audio: None,
             document: None,
             context: ctx.reply_to.map(|id| MessageContext { message_id: id }),
+            reaction: None,
+            template: None,
         };
 
         let result: SendMessageResponse = self
@@ -273,6 +405,8 @@ impl ChannelOutbound for WhatsAppChannel {
                     .reply_to
                     .clone()
                     .map(|id| MessageContext { message_id: id }),
+                reaction: None,
+                template: None,
             };
 
             let result: SendMessageResponse = self
@@ -295,6 +429,25 @@ impl ChannelOutbound for WhatsAppChannel {
         })
     }
 
+    async fn add_reaction(
+        &self,
+        ctx: OutboundContext,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<(), ChannelError> {
+        self.send_reaction(ctx, message_id, emoji).await
+    }
+
+    async fn remove_reaction(
+        &self,
+        ctx: OutboundContext,
+        message_id: &str,
+        _emoji: &str,
+    ) -> Result<(), ChannelError> {
+        // WhatsApp allows one reaction per message; an empty emoji clears it
+        self.send_reaction(ctx, message_id, "").await
+    }
+
     fn text_chunk_limit(&self) -> usize {
         4096 // WhatsApp text message limit
     }
@@ -407,12 +560,12 @@ impl ChannelInbound for WhatsAppChannel {
             account_id: self.phone_number_id.clone(),
             peer_id: PeerId::new(message.from),
             peer_type,
+            mentions: parse_mentions("whatsapp", &content),
             content,
             attachments,
             timestamp,
             reply_to: message.context.map(|c| c.id),
             thread_id: None,
-            mentions: Vec::new(),
             raw: Some(raw_value),
         })
     }
@@ -423,6 +576,47 @@ impl ChannelInbound for WhatsAppChannel {
     }
 }
 
+impl ChannelReactions for WhatsAppChannel {
+    type RawReaction = WhatsAppWebhookPayload;
+
+    fn normalize_reaction(&self, raw: Self::RawReaction) -> Result<MessageReaction, ChannelError> {
+        let raw_value = serde_json::to_value(&raw).unwrap_or_default();
+
+        let message = raw
+            .entry
+            .into_iter()
+            .flat_map(|e| e.changes)
+            .filter_map(|c| c.value.messages)
+            .flatten()
+            .next()
+            .ok_or_else(|| ChannelError::Config("No message in webhook".to_string()))?;
+
+        let reaction = message
+            .reaction
+            .ok_or_else(|| ChannelError::Config("Not a reaction".to_string()))?;
+
+        // An empty or missing emoji means the reaction was removed
+        let emoji = reaction.emoji.unwrap_or_default();
+
+        Ok(MessageReaction {
+            channel: ChannelId::whatsapp(),
+            account_id: self.phone_number_id.clone(),
+            peer_id: PeerId::new(message.from.clone()),
+            chat_id: message.from,
+            message_id: reaction.message_id,
+            removed: emoji.is_empty(),
+            emoji,
+            timestamp: message
+                .timestamp
+                .parse::<i64>()
+                .ok()
+                .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+                .unwrap_or_else(chrono::Utc::now),
+            raw: Some(raw_value),
+        })
+    }
+}
+
 // WhatsApp Cloud API types
 
 /// Phone number info.
@@ -452,6 +646,17 @@ struct SendMessageRequest {
     document: Option<DocumentContent>,
     #[serde(skip_serializing_if = "Option::is_none")]
     context: Option<MessageContext>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reaction: Option<ReactionContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    template: Option<serde_json::Value>,
+}
+
+/// Reaction content.
+#[derive(Debug, Serialize)]
+struct ReactionContent {
+    message_id: String,
+    emoji: String,
 }
 
 /// Text content.
@@ -594,6 +799,17 @@ pub struct WebhookMessage {
     pub caption: Option<String>,
     /// Context (for replies).
     pub context: Option<WebhookContext>,
+    /// Reaction content.
+    pub reaction: Option<WebhookReaction>,
+}
+
+/// Webhook reaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookReaction {
+    /// ID of the message reacted to.
+    pub message_id: String,
+    /// Reaction emoji (absent or empty when removed).
+    pub emoji: Option<String>,
 }
 
 /// Webhook text.
@@ -671,4 +887,62 @@ mod tests {
         let channel = WhatsAppChannel::new(ApiKey::new("test".to_string()), "123456789");
         assert_eq!(channel.text_chunk_limit(), 4096);
     }
+
+    #[test]
+    fn test_parse_webhook() {
+        let body = br#"{"object":"whatsapp_business_account","entry":[]}"#;
+        let signature = "sha256=d3e4f9da0ce6c71ab3dba55929b8eeeee2455349e534924ead19f98d143e904f";
+
+        let channel = WhatsAppChannel::new(ApiKey::new("test".to_string()), "123456789");
+        assert!(matches!(
+            channel.parse_webhook(body, Some(signature)),
+            Err(ChannelError::Config(_))
+        ));
+
+        let channel = channel.with_app_secret(ApiKey::new("app-secret".to_string()));
+        let payload = channel.parse_webhook(body, Some(signature)).unwrap();
+        assert_eq!(payload.object, "whatsapp_business_account");
+        assert!(matches!(
+            channel.parse_webhook(body, None),
+            Err(ChannelError::AuthFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_normalize_reaction() {
+        let channel = WhatsAppChannel::new(ApiKey::new("test".to_string()), "123456789");
+        let payload = |emoji: serde_json::Value| -> WhatsAppWebhookPayload {
+            serde_json::from_value(serde_json::json!({
+                "object": "whatsapp_business_account",
+                "entry": [{
+                    "id": "1",
+                    "changes": [{
+                        "field": "messages",
+                        "value": {
+                            "messages": [{
+                                "id": "wamid.R",
+                                "from": "15550001",
+                                "timestamp": "1700000000",
+                                "type": "reaction",
+                                "reaction": { "message_id": "wamid.M", "emoji": emoji }
+                            }]
+                        }
+                    }]
+                }]
+            }))
+            .unwrap()
+        };
+
+        let reaction = channel
+            .normalize_reaction(payload(serde_json::json!("👍")))
+            .unwrap();
+        assert_eq!(reaction.message_id, "wamid.M");
+        assert_eq!(reaction.emoji, "👍");
+        assert!(!reaction.removed);
+
+        let reaction = channel
+            .normalize_reaction(payload(serde_json::json!("")))
+            .unwrap();
+        assert!(reaction.removed);
+    }
 }
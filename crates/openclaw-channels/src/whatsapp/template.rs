@@ -0,0 +1,261 @@
+//! Pre-approved template messages.
+//!
+//! Business-initiated conversations (or any message outside the 24-hour
+//! customer service window) must use a template approved in Meta Business
+//! Manager. Templates are referenced by name and language, with parameters
+//! filling their `{{1}}`-style placeholders.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// A value substituted into a template placeholder.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TemplateParameter {
+    /// Text for a positional placeholder (`{{1}}`).
+    Text {
+        /// Substituted text.
+        text: String,
+    },
+    /// Text for a named placeholder (`{{customer_name}}`).
+    Named {
+        /// Placeholder name.
+        name: String,
+        /// Substituted text.
+        text: String,
+    },
+    /// Header image.
+    Image {
+        /// Public media URL.
+        link: String,
+    },
+    /// Header video.
+    Video {
+        /// Public media URL.
+        link: String,
+    },
+    /// Header document.
+    Document {
+        /// Public media URL.
+        link: String,
+        /// Filename shown to the recipient.
+        filename: Option<String>,
+    },
+}
+
+impl TemplateParameter {
+    /// Positional text parameter.
+    #[must_use]
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text { text: text.into() }
+    }
+
+    /// Named text parameter.
+    #[must_use]
+    pub fn named(name: impl Into<String>, text: impl Into<String>) -> Self {
+        Self::Named {
+            name: name.into(),
+            text: text.into(),
+        }
+    }
+
+    fn to_wire(&self) -> Value {
+        match self {
+            Self::Text { text } => json!({ "type": "text", "text": text }),
+            Self::Named { name, text } => {
+                json!({ "type": "text", "parameter_name": name, "text": text })
+            }
+            Self::Image { link } => json!({ "type": "image", "image": { "link": link } }),
+            Self::Video { link } => json!({ "type": "video", "video": { "link": link } }),
+            Self::Document { link, filename } => {
+                let mut document = json!({ "link": link });
+                if let Some(filename) = filename {
+                    document["filename"] = json!(filename);
+                }
+                json!({ "type": "document", "document": document })
+            }
+        }
+    }
+}
+
+/// A dynamic URL or quick-reply button parameter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateButton {
+    /// Button position in the template (0-based).
+    pub index: u8,
+    /// `url` or `quick_reply`.
+    pub sub_type: String,
+    /// URL suffix or quick-reply payload.
+    pub value: String,
+}
+
+/// A template message to send.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateMessage {
+    /// Approved template name.
+    pub name: String,
+    /// Template language code (e.g., `en_US`).
+    pub language: String,
+    /// Header parameters.
+    #[serde(default)]
+    pub header: Vec<TemplateParameter>,
+    /// Body parameters.
+    #[serde(default)]
+    pub body: Vec<TemplateParameter>,
+    /// Button parameters.
+    #[serde(default)]
+    pub buttons: Vec<TemplateButton>,
+}
+
+impl TemplateMessage {
+    /// Create a template message with no parameters.
+    #[must_use]
+    pub fn new(name: impl Into<String>, language: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            language: language.into(),
+            header: Vec::new(),
+            body: Vec::new(),
+            buttons: Vec::new(),
+        }
+    }
+
+    /// Add a header parameter.
+    #[must_use]
+    pub fn with_header(mut self, parameter: TemplateParameter) -> Self {
+        self.header.push(parameter);
+        self
+    }
+
+    /// Add a body parameter.
+    #[must_use]
+    pub fn with_body(mut self, parameter: TemplateParameter) -> Self {
+        self.body.push(parameter);
+        self
+    }
+
+    /// Add a button parameter.
+    #[must_use]
+    pub fn with_button(
+        mut self,
+        index: u8,
+        sub_type: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.buttons.push(TemplateButton {
+            index,
+            sub_type: sub_type.into(),
+            value: value.into(),
+        });
+        self
+    }
+
+    /// Substitute body parameters into the template's approved body text.
+    ///
+    /// Positional parameters fill `{{1}}`, `{{2}}`, ... in order; named
+    /// parameters fill `{{name}}`. Unmatched placeholders are left as is.
+    #[must_use]
+    pub fn render(&self, text: &str) -> String {
+        let mut rendered = text.to_string();
+        let mut position = 0;
+        for parameter in &self.body {
+            match parameter {
+                TemplateParameter::Text { text } => {
+                    position += 1;
+                    rendered = rendered.replace(&format!("{{{{{position}}}}}"), text);
+                }
+                TemplateParameter::Named { name, text } => {
+                    rendered = rendered.replace(&format!("{{{{{name}}}}}"), text);
+                }
+                _ => {}
+            }
+        }
+        rendered
+    }
+
+    /// The `template` object of a Cloud API send request.
+    pub(super) fn to_wire(&self) -> Value {
+        let mut components = Vec::new();
+        if !self.header.is_empty() {
+            components.push(json!({
+                "type": "header",
+                "parameters": self.header.iter().map(TemplateParameter::to_wire).collect::<Vec<_>>(),
+            }));
+        }
+        if !self.body.is_empty() {
+            components.push(json!({
+                "type": "body",
+                "parameters": self.body.iter().map(TemplateParameter::to_wire).collect::<Vec<_>>(),
+            }));
+        }
+        for button in &self.buttons {
+            let parameter = if button.sub_type == "quick_reply" {
+                json!({ "type": "payload", "payload": button.value })
+            } else {
+                json!({ "type": "text", "text": button.value })
+            };
+            components.push(json!({
+                "type": "button",
+                "sub_type": button.sub_type,
+                "index": button.index.to_string(),
+                "parameters": [parameter],
+            }));
+        }
+
+        let mut template = json!({
+            "name": self.name,
+            "language": { "code": self.language },
+        });
+        if !components.is_empty() {
+            template["components"] = Value::Array(components);
+        }
+        template
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order_update() -> TemplateMessage {
+        TemplateMessage::new("order_update", "en_US")
+            .with_header(TemplateParameter::Image {
+                link: "https://example.com/box.png".to_string(),
+            })
+            .with_body(TemplateParameter::text("Ada"))
+            .with_body(TemplateParameter::text("#1042"))
+            .with_button(0, "url", "1042")
+    }
+
+    #[test]
+    fn test_render() {
+        let rendered = order_update().render("Hi {{1}}, order {{2}} has shipped. {{3}}");
+        assert_eq!(rendered, "Hi Ada, order #1042 has shipped. {{3}}");
+
+        let named = TemplateMessage::new("welcome", "en")
+            .with_body(TemplateParameter::named("first_name", "Ada"))
+            .render("Welcome, {{first_name}}!");
+        assert_eq!(named, "Welcome, Ada!");
+    }
+
+    #[test]
+    fn test_wire_format() {
+        let wire = order_update().to_wire();
+        assert_eq!(wire["name"], "order_update");
+        assert_eq!(wire["language"]["code"], "en_US");
+
+        let components = wire["components"].as_array().unwrap();
+        assert_eq!(components.len(), 3);
+        assert_eq!(components[0]["type"], "header");
+        assert_eq!(
+            components[0]["parameters"][0]["image"]["link"],
+            "https://example.com/box.png"
+        );
+        assert_eq!(components[1]["parameters"][1]["text"], "#1042");
+        assert_eq!(components[2]["sub_type"], "url");
+        assert_eq!(components[2]["index"], "0");
+
+        let bare = TemplateMessage::new("hello_world", "en_US").to_wire();
+        assert!(bare.get("components").is_none());
+    }
+}
@@ -2,8 +2,12 @@
 
 use std::path::PathBuf;
 
+use openclaw_core::secrets::MASTER_KEY_ENV;
+use openclaw_core::{Config, CredentialStore, MasterKey};
 use openclaw_gateway::auth::{User, UserRole, UserStore, setup::generate_password};
 
+use super::configure::{CREDENTIALS_KEY_PURPOSE, master_key_source};
+use super::gateway_rpc;
 use crate::ui;
 
 /// Arguments for admin commands.
@@ -33,6 +37,29 @@ pub enum AdminAction {
     Disable { username: String },
     /// Delete a user.
     Delete { username: String },
+    /// Inspect or change a running gateway's log filter.
+    LogLevel {
+        action: LogLevelAction,
+        /// Admin access token for the gateway.
+        token: Option<String>,
+        /// Gateway port (defaults to the configured port).
+        port: Option<u16>,
+    },
+    /// Generate a new master key and re-encrypt stored credentials.
+    RotateSecrets,
+}
+
+/// Log level actions.
+pub enum LogLevelAction {
+    /// Show the active filter.
+    Get,
+    /// Set the level for a module, or the default level.
+    Set {
+        level: String,
+        module: Option<String>,
+    },
+    /// Restore the filter the gateway started with.
+    Reset,
 }
 
 /// Run the admin command.
@@ -41,6 +68,19 @@ pub enum AdminAction {
 ///
 /// Returns error if the operation fails.
 pub async fn run_admin(args: AdminArgs) -> anyhow::Result<()> {
+    // Log level changes go to the running gateway, not the user store
+    if let AdminAction::LogLevel {
+        action,
+        token,
+        port,
+    } = args.action
+    {
+        return log_level(action, token, port).await;
+    }
+    if matches!(args.action, AdminAction::RotateSecrets) {
+        return rotate_secrets();
+    }
+
     let data_dir = args.data_dir.unwrap_or_else(|| {
         dirs::data_dir()
             .unwrap_or_else(|| PathBuf::from("."))
@@ -79,6 +119,9 @@ pub async fn run_admin(args: AdminArgs) -> anyhow::Result<()> {
         AdminAction::Delete { username } => {
             delete_user(&store, &username)?;
         }
+        AdminAction::LogLevel { .. } | AdminAction::RotateSecrets => {
+            unreachable!("handled above")
+        }
     }
 
     Ok(())
@@ -221,3 +264,73 @@ fn delete_user(store: &UserStore, username: &str) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+async fn log_level(
+    action: LogLevelAction,
+    token: Option<String>,
+    port: Option<u16>,
+) -> anyhow::Result<()> {
+    let (method, params) = match action {
+        LogLevelAction::Get => ("admin.log_level.get", serde_json::json!({})),
+        LogLevelAction::Set { level, module } => (
+            "admin.log_level.set",
+            serde_json::json!({ "level": level, "module": module }),
+        ),
+        LogLevelAction::Reset => ("admin.log_level.reset", serde_json::json!({})),
+    };
+
+    let result = gateway_rpc(port, token.as_deref(), method, params).await?;
+    let filter = result["filter"].as_str().unwrap_or_default();
+    if method == "admin.log_level.get" {
+        ui::kv("Filter", filter);
+        ui::kv("Initial", result["initial"].as_str().unwrap_or_default());
+    } else {
+        ui::success(&format!("Log filter set to '{filter}'"));
+    }
+
+    Ok(())
+}
+
+/// Replace the master key and re-encrypt the credential store under the
+/// new derived key.
+fn rotate_secrets() -> anyhow::Result<()> {
+    if Config::load_default()?.settings.encrypt_event_store {
+        anyhow::bail!(
+            "Rotating the master key is not supported while settings.encryptEventStore is enabled"
+        );
+    }
+
+    let from_env = std::env::var_os(MASTER_KEY_ENV).is_some();
+    let source = master_key_source()?;
+    let old_master = MasterKey::load_from(source.as_ref())?;
+    let new_master = MasterKey::generate();
+
+    // Persist the new key before re-encrypting so it cannot be lost
+    if !from_env {
+        source.store(&new_master)?;
+    }
+
+    let old_key = old_master.derive(CREDENTIALS_KEY_PURPOSE);
+    let mut store = CredentialStore::from_key(old_key.clone(), Config::credentials_dir());
+    let rotated = match store.rotate_key(&old_key, new_master.derive(CREDENTIALS_KEY_PURPOSE)) {
+        Ok(rotated) => rotated,
+        Err(e) => {
+            if !from_env {
+                source.store(&old_master)?;
+            }
+            anyhow::bail!("Failed to re-encrypt credentials (store unchanged): {e}");
+        }
+    };
+
+    ui::success(&format!("Re-encrypted {rotated} credential(s)"));
+    if from_env {
+        ui::warning(&format!(
+            "{MASTER_KEY_ENV} is set; replace it with the new key before the next run:"
+        ));
+        println!("{}", new_master.to_hex());
+    } else {
+        ui::kv("Master key source", source.name());
+    }
+
+    Ok(())
+}
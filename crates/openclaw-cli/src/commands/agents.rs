@@ -0,0 +1,281 @@
+//! Agent commands - inspect, import, export, and tune configured agents.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use openclaw_agents::PersonaPack;
+use openclaw_core::Config;
+
+use super::gateway_rpc;
+use crate::ui;
+
+/// Agent command arguments.
+#[derive(Debug, Clone)]
+pub enum AgentsAction {
+    /// List configured agents.
+    List,
+    /// Show one agent's configuration.
+    Show {
+        /// Agent ID.
+        id: String,
+        /// Show the effective config after applying `extends`.
+        resolved: bool,
+    },
+    /// Install an agent from a persona pack.
+    Import {
+        /// Path to the pack archive.
+        path: PathBuf,
+        /// Agent ID to install as (defaults to the pack name).
+        id: Option<String>,
+        /// Replace an existing agent with the same ID.
+        force: bool,
+    },
+    /// Export an agent as a persona pack.
+    Export {
+        /// Agent ID.
+        id: String,
+        /// Output archive path (defaults to `<id>.tar.gz`).
+        output: Option<PathBuf>,
+        /// Version recorded in the pack manifest.
+        version: String,
+    },
+    /// Change a running agent's model parameters.
+    Set {
+        /// Agent ID.
+        id: String,
+        /// `key=value` assignments.
+        assignments: Vec<String>,
+        /// Admin access token for the gateway.
+        token: Option<String>,
+        /// Gateway port (defaults to the configured port).
+        port: Option<u16>,
+    },
+}
+
+/// Parameters `agents set` can change.
+const SETTABLE: &[&str] = &["model", "temperature", "max_tokens", "system_prompt"];
+
+/// Run the agents command.
+///
+/// # Errors
+///
+/// Returns error if the config cannot be loaded, the agent does not exist,
+/// a persona pack is invalid, or the gateway rejects a change.
+pub async fn run_agents(action: AgentsAction) -> Result<()> {
+    // Parameter changes go to the running gateway, which saves them
+    if let AgentsAction::Set {
+        id,
+        assignments,
+        token,
+        port,
+    } = action
+    {
+        return set_agent(&id, &assignments, token.as_deref(), port).await;
+    }
+
+    let config = Config::load_default()?;
+
+    match action {
+        AgentsAction::List => {
+            list_agents(&config);
+            Ok(())
+        }
+        AgentsAction::Show { id, resolved } => show_agent(&config, &id, resolved),
+        AgentsAction::Import { path, id, force } => {
+            import_agent(&config, &path, id.as_deref(), force)
+        }
+        AgentsAction::Export {
+            id,
+            output,
+            version,
+        } => export_agent(&config, &id, output, &version),
+        AgentsAction::Set { .. } => unreachable!("handled above"),
+    }
+}
+
+fn list_agents(config: &Config) {
+    if config.agents.is_empty() {
+        ui::info("No agents configured.");
+        ui::info("Add entries under 'agents' in your config file.");
+        return;
+    }
+
+    let mut ids: Vec<&String> = config.agents.keys().collect();
+    ids.sort();
+
+    ui::info(&format!("Agents ({}):", ids.len()));
+    println!();
+    println!("{:<20} {:<32} {:<20}", "ID", "MODEL", "EXTENDS");
+    println!("{}", "-".repeat(72));
+
+    for id in ids {
+        let agent = &config.agents[id];
+        println!(
+            "{:<20} {:<32} {:<20}",
+            id,
+            agent.model,
+            agent.extends.as_deref().unwrap_or("-")
+        );
+    }
+}
+
+fn show_agent(config: &Config, id: &str, resolved: bool) -> Result<()> {
+    let agent = config
+        .agents
+        .get(id)
+        .ok_or_else(|| anyhow::anyhow!("Agent not found: {id}"))?;
+
+    let chain = inheritance_chain(config, id);
+    if chain.len() > 1 {
+        ui::kv("Inherits", &chain.join(" -> "));
+    }
+
+    let value = if resolved {
+        serde_json::to_value(agent)?
+    } else {
+        // The declared entry, as written in the config file
+        let content = std::fs::read_to_string(Config::default_path())?;
+        let raw: serde_json::Value = json5::from_str(&content)?;
+        raw["agents"][id].clone()
+    };
+
+    println!("{}", serde_json::to_string_pretty(&value)?);
+    if !resolved && chain.len() > 1 {
+        ui::info("Use --resolved to show the effective configuration");
+    }
+    Ok(())
+}
+
+fn import_agent(config: &Config, path: &Path, id: Option<&str>, force: bool) -> Result<()> {
+    let pack = PersonaPack::read(path)?;
+    let manifest = &pack.manifest;
+    let id = id.unwrap_or(&manifest.name);
+
+    if config.agents.contains_key(id) && !force {
+        anyhow::bail!("Agent '{id}' already exists; use --force to replace it");
+    }
+
+    let agent_dir = pack.install(&Config::agents_dir(), id, force)?;
+
+    // Add the agent to the config file, keeping everything else as written
+    let config_path = Config::default_path();
+    let mut raw: serde_json::Value = if config_path.exists() {
+        json5::from_str(&std::fs::read_to_string(&config_path)?)?
+    } else {
+        serde_json::json!({})
+    };
+    if !raw["agents"].is_object() {
+        raw["agents"] = serde_json::json!({});
+    }
+    raw["agents"][id] = serde_json::to_value(&manifest.agent)?;
+
+    if let Some(parent) = config_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&config_path, serde_json::to_string_pretty(&raw)?)?;
+
+    ui::success(&format!(
+        "Imported persona '{}' v{} as agent '{id}'",
+        manifest.name, manifest.version
+    ));
+    if let Some(description) = &manifest.description {
+        ui::kv("Description", description);
+    }
+    ui::kv(
+        "Files",
+        &format!("{} ({})", pack.files.len(), agent_dir.display()),
+    );
+    Ok(())
+}
+
+fn export_agent(config: &Config, id: &str, output: Option<PathBuf>, version: &str) -> Result<()> {
+    let agent = config
+        .agents
+        .get(id)
+        .ok_or_else(|| anyhow::anyhow!("Agent not found: {id}"))?;
+
+    let pack = PersonaPack::export(id, version, agent, &Config::agents_dir().join(id))?;
+    let output = output.unwrap_or_else(|| PathBuf::from(format!("{id}.tar.gz")));
+    pack.write(&output)?;
+
+    ui::success(&format!("Exported agent '{id}' to {}", output.display()));
+    ui::kv("Files", &pack.files.len().to_string());
+    Ok(())
+}
+
+async fn set_agent(
+    id: &str,
+    assignments: &[String],
+    token: Option<&str>,
+    port: Option<u16>,
+) -> Result<()> {
+    let params = agent_update_params(id, assignments)?;
+    let result = gateway_rpc(port, token, "agent.update", params).await?;
+
+    let changes = result["changes"].as_array().cloned().unwrap_or_default();
+    if changes.is_empty() {
+        ui::info(&format!("Agent '{id}' already has these settings"));
+    } else {
+        ui::success(&format!("Updated agent '{id}'"));
+        for change in &changes {
+            ui::kv(
+                change["field"].as_str().unwrap_or_default(),
+                &format!("{} -> {}", change["old"], change["new"]),
+            );
+        }
+    }
+    if result["persisted"].as_bool() != Some(true) {
+        ui::warning("The gateway has no config file; the change lasts until it restarts");
+    }
+    Ok(())
+}
+
+/// Build `agent.update` params from `key=value` assignments.
+fn agent_update_params(id: &str, assignments: &[String]) -> Result<serde_json::Value> {
+    if assignments.is_empty() {
+        anyhow::bail!("Nothing to set; use key=value with {}", SETTABLE.join(", "));
+    }
+
+    let mut params = serde_json::json!({ "agent_id": id });
+    for assignment in assignments {
+        let (key, value) = assignment
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Expected key=value, got '{assignment}'"))?;
+        let key = key.trim();
+        params[key] = match key {
+            "model" | "system_prompt" => serde_json::json!(value),
+            "temperature" => serde_json::json!(
+                value
+                    .parse::<f64>()
+                    .map_err(|_| anyhow::anyhow!("temperature must be a number"))?
+            ),
+            "max_tokens" => serde_json::json!(
+                value
+                    .parse::<u32>()
+                    .map_err(|_| anyhow::anyhow!("max_tokens must be a positive integer"))?
+            ),
+            _ => anyhow::bail!(
+                "Unknown setting '{key}'; use one of {}",
+                SETTABLE.join(", ")
+            ),
+        };
+    }
+    Ok(params)
+}
+
+/// Agent IDs from `id` up through its `extends` ancestors.
+fn inheritance_chain(config: &Config, id: &str) -> Vec<String> {
+    let mut chain = vec![id.to_string()];
+    let mut current = config.agents.get(id);
+
+    // Cycles are rejected at load time, but never loop forever
+    while let Some(parent) = current.and_then(|a| a.extends.as_deref()) {
+        if chain.len() > config.agents.len() {
+            break;
+        }
+        chain.push(parent.to_string());
+        current = config.agents.get(parent);
+    }
+    chain
+}
@@ -0,0 +1,99 @@
+//! Approvals commands - review tool calls waiting for sign-off.
+
+use anyhow::Result;
+
+use super::gateway_rpc;
+use crate::ui;
+
+/// Approvals command arguments.
+#[derive(Debug, Clone)]
+pub struct ApprovalsArgs {
+    /// The approvals action to perform.
+    pub action: ApprovalsAction,
+    /// Access token for the gateway.
+    pub token: Option<String>,
+    /// Gateway port (defaults to the configured port).
+    pub port: Option<u16>,
+}
+
+/// Approvals actions.
+#[derive(Debug, Clone)]
+pub enum ApprovalsAction {
+    /// List pending tool calls.
+    List,
+    /// Let a pending call run.
+    Approve {
+        /// Approval request ID.
+        id: String,
+    },
+    /// Reject a pending call.
+    Deny {
+        /// Approval request ID.
+        id: String,
+        /// Reason passed back to the agent.
+        reason: Option<String>,
+    },
+}
+
+/// Run the approvals command.
+///
+/// # Errors
+///
+/// Returns error if the gateway cannot be reached or rejects the request.
+pub async fn run_approvals(args: ApprovalsArgs) -> Result<()> {
+    let token = args.token.as_deref();
+
+    match args.action {
+        ApprovalsAction::List => {
+            let result =
+                gateway_rpc(args.port, token, "approvals.list", serde_json::json!({})).await?;
+            list_approvals(&result);
+        }
+        ApprovalsAction::Approve { id } => {
+            gateway_rpc(
+                args.port,
+                token,
+                "approvals.approve",
+                serde_json::json!({ "id": id }),
+            )
+            .await?;
+            ui::success(&ui::text("cli-approvals-approved", &[("id", id.into())]));
+        }
+        ApprovalsAction::Deny { id, reason } => {
+            gateway_rpc(
+                args.port,
+                token,
+                "approvals.deny",
+                serde_json::json!({ "id": id, "reason": reason }),
+            )
+            .await?;
+            ui::success(&ui::text("cli-approvals-denied", &[("id", id.into())]));
+        }
+    }
+    Ok(())
+}
+
+fn list_approvals(result: &serde_json::Value) {
+    let approvals = result["approvals"].as_array().cloned().unwrap_or_default();
+    if approvals.is_empty() {
+        ui::info(&ui::text("cli-approvals-none", &[]));
+        return;
+    }
+
+    println!(
+        "{:<22} {:<12} {:<14} {:<24} EXPIRES",
+        "ID", "AGENT", "TOOL", "SESSION"
+    );
+    println!("{}", "-".repeat(96));
+    for approval in &approvals {
+        println!(
+            "{:<22} {:<12} {:<14} {:<24} {}",
+            approval["id"].as_str().unwrap_or_default(),
+            approval["agent_id"].as_str().unwrap_or_default(),
+            approval["tool"].as_str().unwrap_or_default(),
+            approval["session_key"].as_str().unwrap_or("-"),
+            approval["expires_at"].as_str().unwrap_or_default(),
+        );
+        println!("  params: {}", approval["params"]);
+    }
+}
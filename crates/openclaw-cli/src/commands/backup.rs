@@ -0,0 +1,292 @@
+//! Backup commands - take, list, verify, and restore encrypted backups of
+//! sessions, config, and credentials in object storage.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use openclaw_core::backup::{BackupStore, DEFAULT_EVENT_STORE, Snapshot, namespace_event_store};
+use openclaw_core::config::GlobalSettings;
+use openclaw_core::{BlobStore, Config, CredentialStore, EventStore};
+use openclaw_gateway::{BackupSchedule, GatewayConfig};
+
+use super::configure::{CREDENTIALS_KEY_PURPOSE, load_master_key};
+use super::sessions::EVENT_STORE_KEY_PURPOSE;
+use crate::ui;
+
+/// Purpose the backup key is derived for from the master key.
+pub const BACKUP_KEY_PURPOSE: &str = "backup";
+
+/// Backup command arguments.
+#[derive(Debug, Clone)]
+pub struct BackupArgs {
+    /// Subcommand.
+    pub action: BackupAction,
+    /// Gateway data directory override.
+    pub data_dir: Option<PathBuf>,
+}
+
+/// Backup actions.
+#[derive(Debug, Clone)]
+pub enum BackupAction {
+    /// Take a backup now.
+    Run,
+    /// List backups.
+    List,
+    /// Download a backup and check it against its manifest.
+    Verify {
+        /// Backup ID.
+        id: String,
+    },
+    /// Restore a backup.
+    Restore {
+        /// Backup ID.
+        id: String,
+    },
+}
+
+/// Run the backup command.
+///
+/// Event stores are opened directly, so the gateway must be stopped to take
+/// or restore a backup of a sled store.
+///
+/// # Errors
+///
+/// Returns error if the backup store is unavailable, or the backup cannot
+/// be taken, verified, or restored.
+pub async fn run_backup(args: BackupArgs) -> Result<()> {
+    let config = Config::load_default()?;
+    let data_dir = args
+        .data_dir
+        .unwrap_or_else(|| GatewayConfig::default().data_dir);
+    let store = backup_store(&config)?;
+
+    match args.action {
+        BackupAction::Run => take_backup(&store, &config, &data_dir).await,
+        BackupAction::List => list_backups(&store).await,
+        BackupAction::Verify { id } => verify_backup(&store, &id).await,
+        BackupAction::Restore { id } => restore_backup(&store, &config, &data_dir, &id).await,
+    }
+}
+
+/// Backup store for the configured object storage, encrypted with a key
+/// derived from the master key.
+///
+/// # Errors
+///
+/// Returns error if the master key or storage credentials are unavailable.
+pub fn backup_store(config: &Config) -> Result<BackupStore> {
+    let master_key = load_master_key()?;
+    Ok(BackupStore::new(
+        blob_store(config)?,
+        master_key.derive(BACKUP_KEY_PURPOSE),
+    ))
+}
+
+/// Blob store for the configured object storage.
+///
+/// Storage credentials are read from the credential store when the master
+/// key is available.
+///
+/// # Errors
+///
+/// Returns error if the storage backend cannot be initialized.
+pub fn blob_store(config: &Config) -> Result<BlobStore> {
+    let credentials = load_master_key().ok().map(|master_key| {
+        CredentialStore::from_key(
+            master_key.derive(CREDENTIALS_KEY_PURPOSE),
+            Config::credentials_dir(),
+        )
+    });
+    Ok(BlobStore::from_config(
+        &config.settings.storage,
+        credentials.as_ref(),
+    )?)
+}
+
+/// The gateway's backup schedule, if `settings.backup.intervalHours` is
+/// set.
+///
+/// # Errors
+///
+/// Returns error if the backup store is unavailable.
+pub fn backup_schedule(config: &Config) -> Result<Option<BackupSchedule>> {
+    let Some(hours) = config.settings.backup.interval_hours else {
+        return Ok(None);
+    };
+    Ok(Some(BackupSchedule {
+        store: backup_store(config)?,
+        interval: std::time::Duration::from_secs(u64::from(hours.max(1)) * 60 * 60),
+        keep: config.settings.backup.keep.map(|keep| keep as usize),
+        config_path: Some(Config::default_path()),
+        credentials_dir: Some(Config::credentials_dir()),
+    }))
+}
+
+async fn take_backup(store: &BackupStore, config: &Config, data_dir: &Path) -> Result<()> {
+    let mut snapshot = Snapshot::new();
+    for (name, path, archive_dir) in event_stores(config, data_dir) {
+        if !EventStore::backend_path(&path, config.settings.event_store_backend).exists() {
+            continue;
+        }
+        let events = open_store(&path, &config.settings)?;
+        let entries = snapshot.add_event_store(&name, &events, Some(&archive_dir))?;
+        ui::kv(&name, &format!("{entries} entries"));
+    }
+    snapshot.add_config(&Config::default_path())?;
+    let credentials = snapshot.add_credentials(&Config::credentials_dir())?;
+    ui::kv("credentials", &credentials.to_string());
+
+    let manifest = store.upload(&snapshot).await?;
+    println!();
+    ui::success(&format!("Uploaded backup {}", manifest.id));
+    ui::kv("Size", &format!("{} KB", manifest.size / 1024));
+    ui::kv("Ranges", &manifest.ranges.len().to_string());
+
+    if let Some(keep) = config.settings.backup.keep {
+        let pruned = store.prune(keep as usize).await?;
+        if !pruned.is_empty() {
+            ui::info(&format!("Deleted {} old backup(s)", pruned.len()));
+        }
+    }
+    Ok(())
+}
+
+async fn list_backups(store: &BackupStore) -> Result<()> {
+    let backups = store.list().await?;
+    if backups.is_empty() {
+        ui::info("No backups.");
+        return Ok(());
+    }
+
+    ui::info(&format!("Backups ({}):", backups.len()));
+    println!();
+    println!(
+        "{:<28} {:<18} {:>10} {:>6}",
+        "ID", "CREATED", "SIZE", "FILES"
+    );
+    println!("{}", "-".repeat(65));
+    for manifest in backups {
+        println!(
+            "{:<28} {:<18} {:>7} KB {:>6}",
+            manifest.id,
+            manifest.created_at.format("%Y-%m-%d %H:%M"),
+            manifest.size / 1024,
+            manifest.files.len()
+        );
+    }
+    Ok(())
+}
+
+async fn verify_backup(store: &BackupStore, id: &str) -> Result<()> {
+    let manifest = store.verify(id).await?;
+    ui::success(&format!("Backup {id} is intact"));
+    ui::kv("Ranges", &manifest.ranges.len().to_string());
+    ui::kv("Files", &manifest.files.len().to_string());
+    Ok(())
+}
+
+async fn restore_backup(
+    store: &BackupStore,
+    config: &Config,
+    data_dir: &Path,
+    id: &str,
+) -> Result<()> {
+    // Nothing is touched until every range and file has been verified
+    let (manifest, snapshot) = store.download(id).await?;
+    ui::success(&format!(
+        "Verified backup {} from {}",
+        manifest.id,
+        manifest.created_at.format("%Y-%m-%d %H:%M")
+    ));
+
+    let config = if let Some(data) = snapshot.config() {
+        let path = Config::default_path();
+        set_aside(&path)?;
+        std::fs::write(&path, data)?;
+        ui::kv("config", &path.display().to_string());
+        // The restored config decides the backend and namespaces
+        Config::load(&path)?
+    } else {
+        config.clone()
+    };
+    let backend = config.settings.event_store_backend;
+
+    let credentials_dir = Config::credentials_dir();
+    set_aside(&credentials_dir)?;
+    let credentials = snapshot.restore_credentials(&credentials_dir)?;
+    ui::kv("credentials", &credentials.to_string());
+
+    let locations = event_stores(&config, data_dir);
+    for name in snapshot.event_stores() {
+        let Some((_, path, archive_dir)) = locations.iter().find(|(n, ..)| n == name) else {
+            ui::warning(&format!(
+                "Skipping event store '{name}': namespace is not configured"
+            ));
+            continue;
+        };
+        set_aside(&EventStore::backend_path(path, backend))?;
+        let entries = snapshot.restore_event_store(name, path, backend, Some(archive_dir))?;
+        ui::kv(name, &format!("{entries} entries"));
+    }
+
+    println!();
+    ui::info("Replaced files were kept with a .pre-restore suffix.");
+    ui::info("Start the gateway to use the restored state.");
+    Ok(())
+}
+
+/// Name, path, and archive directory of every configured event store.
+fn event_stores(config: &Config, data_dir: &Path) -> Vec<(String, PathBuf, PathBuf)> {
+    let mut stores = vec![(
+        DEFAULT_EVENT_STORE.to_string(),
+        data_dir.join("events"),
+        GatewayConfig::default().archive_dir,
+    )];
+    for id in config.gateway.namespaces.keys() {
+        let dir = data_dir.join("namespaces").join(id);
+        stores.push((
+            namespace_event_store(id),
+            dir.join("events"),
+            dir.join("archive"),
+        ));
+    }
+    stores
+}
+
+fn open_store(path: &Path, settings: &GlobalSettings) -> Result<EventStore> {
+    let key = if settings.encrypt_event_store {
+        Some(load_master_key()?.derive(EVENT_STORE_KEY_PURPOSE))
+    } else {
+        None
+    };
+    EventStore::open_with(path, settings.event_store_backend, key).map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to open event store ({e}); is the gateway running? Scheduled backups run inside the gateway."
+        )
+    })
+}
+
+/// Move `path` (and a `SQLite` database's side files) out of the way,
+/// keeping it with a `.pre-restore` suffix.
+fn set_aside(path: &Path) -> Result<()> {
+    for suffix in ["", "-wal", "-shm"] {
+        let mut from = path.as_os_str().to_owned();
+        from.push(suffix);
+        let from = PathBuf::from(from);
+        if !from.exists() {
+            continue;
+        }
+        let mut to = from.as_os_str().to_owned();
+        to.push(".pre-restore");
+        let to = PathBuf::from(to);
+        if to.exists() {
+            anyhow::bail!(
+                "{} already exists; move it away before restoring",
+                to.display()
+            );
+        }
+        std::fs::rename(&from, &to)?;
+    }
+    Ok(())
+}
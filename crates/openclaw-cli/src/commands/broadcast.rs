@@ -0,0 +1,167 @@
+//! Broadcast commands - send one message to many chats.
+
+use std::time::Duration;
+
+use anyhow::Result;
+
+use super::gateway_rpc;
+use crate::ui;
+
+/// How often `broadcast send --wait` checks progress.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Broadcast command arguments.
+#[derive(Debug, Clone)]
+pub struct BroadcastArgs {
+    /// The broadcast action to perform.
+    pub action: BroadcastAction,
+    /// Access token for the gateway.
+    pub token: Option<String>,
+    /// Gateway port (defaults to the configured port).
+    pub port: Option<u16>,
+}
+
+/// Broadcast actions.
+#[derive(Debug, Clone)]
+pub enum BroadcastAction {
+    /// Start a broadcast.
+    Send {
+        /// Message text.
+        text: String,
+        /// Channel ID (defaults to the group's).
+        channel: Option<String>,
+        /// Configured broadcast group.
+        group: Option<String>,
+        /// Include peers allowlisted on the channel.
+        allowlist: bool,
+        /// Chats to send to.
+        to: Vec<String>,
+        /// Wait for delivery to finish.
+        wait: bool,
+    },
+    /// Show delivery progress.
+    Status {
+        /// Broadcast ID.
+        id: String,
+    },
+}
+
+/// Run the broadcast command.
+///
+/// # Errors
+///
+/// Returns error if the gateway cannot be reached or rejects the request.
+pub async fn run_broadcast(args: BroadcastArgs) -> Result<()> {
+    let token = args.token.as_deref();
+
+    match args.action {
+        BroadcastAction::Send {
+            text,
+            channel,
+            group,
+            allowlist,
+            to,
+            wait,
+        } => {
+            let result = gateway_rpc(
+                args.port,
+                token,
+                "broadcast.send",
+                serde_json::json!({
+                    "text": text,
+                    "channel": channel,
+                    "group": group,
+                    "allowlist": allowlist,
+                    "chat_ids": to,
+                }),
+            )
+            .await?;
+            let id = result["broadcast_id"].as_str().unwrap_or_default();
+            ui::success(&format!(
+                "Broadcast {id} started: {} chat(s) on {}",
+                result["targets"],
+                result["channel"].as_str().unwrap_or_default()
+            ));
+
+            if wait {
+                let status = loop {
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                    let status = broadcast_status(args.port, token, id).await?;
+                    if status["completed"].as_bool().unwrap_or(false) {
+                        break status;
+                    }
+                    ui::info(&format!(
+                        "{} of {} sent",
+                        status["total"]
+                            .as_u64()
+                            .unwrap_or(0)
+                            .saturating_sub(status["pending"].as_u64().unwrap_or(0)),
+                        status["total"]
+                    ));
+                };
+                print_status(&status);
+            } else {
+                ui::info(&format!(
+                    "Check progress with: openclaw broadcast status {id}"
+                ));
+            }
+        }
+        BroadcastAction::Status { id } => {
+            let status = broadcast_status(args.port, token, &id).await?;
+            print_status(&status);
+        }
+    }
+    Ok(())
+}
+
+async fn broadcast_status(
+    port: Option<u16>,
+    token: Option<&str>,
+    id: &str,
+) -> Result<serde_json::Value> {
+    gateway_rpc(
+        port,
+        token,
+        "broadcast.status",
+        serde_json::json!({ "broadcast_id": id }),
+    )
+    .await
+}
+
+fn print_status(status: &serde_json::Value) {
+    ui::kv(
+        "Broadcast",
+        status["broadcast_id"].as_str().unwrap_or_default(),
+    );
+    ui::kv("Channel", status["channel"].as_str().unwrap_or_default());
+    ui::kv("Sent by", status["sent_by"].as_str().unwrap_or_default());
+    ui::kv("Started", status["started_at"].as_str().unwrap_or_default());
+    ui::kv(
+        "Delivered",
+        &format!("{} of {}", status["delivered"], status["total"]),
+    );
+    ui::kv("Failed", &status["failed"].to_string());
+    if !status["completed"].as_bool().unwrap_or(false) {
+        ui::kv("Pending", &status["pending"].to_string());
+    }
+
+    let deliveries = status["deliveries"].as_array().cloned().unwrap_or_default();
+    if deliveries.is_empty() {
+        return;
+    }
+    println!();
+    println!("{:<24} {:<8} RESULT", "CHAT", "ATTEMPTS");
+    println!("{}", "-".repeat(64));
+    for delivery in &deliveries {
+        let outcome = delivery["error"].as_str().map_or_else(
+            || format!("sent ({})", delivery["message_id"].as_str().unwrap_or("-")),
+            |error| format!("failed: {error}"),
+        );
+        println!(
+            "{:<24} {:<8} {}",
+            delivery["chat_id"].as_str().unwrap_or_default(),
+            delivery["attempts"].as_u64().unwrap_or(0),
+            outcome
+        );
+    }
+}
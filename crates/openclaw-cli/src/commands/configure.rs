@@ -2,8 +2,48 @@
 
 use crate::ui::{self, prompts};
 use anyhow::Result;
+use openclaw_core::MasterKey;
+use openclaw_core::config::MasterKeySource;
+use openclaw_core::secrets::{KeySource, MASTER_PASSPHRASE_ENV, key_source};
+use std::io::IsTerminal;
 use std::path::PathBuf;
 
+/// Purpose the credential store key is derived for from the master key.
+pub const CREDENTIALS_KEY_PURPOSE: &str = "credentials";
+
+/// Open the master key source selected by `settings.masterKeySource`,
+/// prompting for the passphrase if needed and not set in the environment.
+///
+/// # Errors
+///
+/// Returns error if the config cannot be loaded or no passphrase is
+/// available.
+pub fn master_key_source() -> Result<Box<dyn KeySource>> {
+    let source = openclaw_core::Config::load_default()?
+        .settings
+        .master_key_source;
+
+    let passphrase = if source == MasterKeySource::Passphrase
+        && std::env::var_os(MASTER_PASSPHRASE_ENV).is_none()
+        && std::io::stdin().is_terminal()
+    {
+        Some(prompts::password("Master key passphrase")?.into())
+    } else {
+        None
+    };
+
+    Ok(key_source(source, passphrase)?)
+}
+
+/// Load the master key from `OPENCLAW_MASTER_KEY` or the configured source.
+///
+/// # Errors
+///
+/// Returns error if the key source is unavailable.
+pub fn load_master_key() -> Result<MasterKey> {
+    Ok(MasterKey::load_from(master_key_source()?.as_ref())?)
+}
+
 /// Configure command arguments.
 #[derive(Debug, Clone, Default)]
 pub struct ConfigureArgs {
@@ -77,9 +117,12 @@ async fn configure_auth() -> Result<()> {
     let cred_path = get_credentials_path();
     std::fs::create_dir_all(&cred_path)?;
 
-    // Generate encryption key (in production, derive from master password)
-    let encryption_key: [u8; 32] = rand::random();
-    let store = openclaw_core::CredentialStore::new(encryption_key, cred_path);
+    // Encrypt with a key derived from the master key
+    let master_key = load_master_key()?;
+    let store = openclaw_core::CredentialStore::from_key(
+        master_key.derive(CREDENTIALS_KEY_PURPOSE),
+        cred_path,
+    );
 
     store.store(provider_name, &openclaw_core::ApiKey::new(api_key))?;
     ui::success(&format!("{provider_name} credentials stored"));
@@ -1,8 +1,14 @@
 //! Daemon management command.
 
-use crate::ui;
+use crate::ui::{self, HealthStatus};
 use anyhow::Result;
+use openclaw_ipc::control::{ControlClient, ControlCommand, ControlError, default_control_address};
 use std::path::PathBuf;
+use std::time::Duration;
+
+/// How long to wait for the gateway to answer a control command. Covers a
+/// full channel probe.
+const CONTROL_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Daemon command arguments.
 #[derive(Debug, Clone)]
@@ -19,6 +25,12 @@ pub enum DaemonAction {
     Start,
     Stop,
     Status,
+    Reload,
+    Probe { channel: Option<String> },
+    StartChannel { channel: String },
+    StopChannel { channel: String },
+    EnableChannel { channel: String },
+    DisableChannel { channel: String },
 }
 
 impl Default for DaemonArgs {
@@ -37,6 +49,12 @@ pub async fn run_daemon(args: DaemonArgs) -> Result<()> {
         DaemonAction::Start => start_daemon().await,
         DaemonAction::Stop => stop_daemon().await,
         DaemonAction::Status => daemon_status().await,
+        DaemonAction::Reload => reload_daemon().await,
+        DaemonAction::Probe { channel } => probe_daemon(channel).await,
+        DaemonAction::StartChannel { channel } => start_channel(channel).await,
+        DaemonAction::StopChannel { channel } => stop_channel(channel).await,
+        DaemonAction::EnableChannel { channel } => set_channel_enabled(channel, true).await,
+        DaemonAction::DisableChannel { channel } => set_channel_enabled(channel, false).await,
     }
 }
 
@@ -134,6 +152,16 @@ async fn start_daemon() -> Result<()> {
 async fn stop_daemon() -> Result<()> {
     ui::info("Stopping OpenClaw daemon...");
 
+    // A service manager would restart a gateway that exits on its own, so
+    // only stop over the control socket when no service is installed.
+    if !service_installed() {
+        match control(ControlCommand::Shutdown).await {
+            Ok(_) => ui::success("Daemon stopped"),
+            Err(e) => ui::error(&format!("No running gateway found: {e}")),
+        }
+        return Ok(());
+    }
+
     #[cfg(target_os = "macos")]
     {
         let plist_path = get_launchd_plist_path();
@@ -162,11 +190,6 @@ async fn stop_daemon() -> Result<()> {
         }
     }
 
-    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
-    {
-        ui::error("Daemon stop not supported on this platform");
-    }
-
     Ok(())
 }
 
@@ -174,6 +197,11 @@ async fn stop_daemon() -> Result<()> {
 async fn daemon_status() -> Result<()> {
     ui::header("OpenClaw Daemon Status");
 
+    if let Ok(status) = control(ControlCommand::Status).await {
+        print_status(&status);
+        return Ok(());
+    }
+
     #[cfg(target_os = "macos")]
     {
         let output = std::process::Command::new("launchctl")
@@ -230,6 +258,189 @@ async fn daemon_status() -> Result<()> {
     Ok(())
 }
 
+/// Reload the running gateway's configuration.
+async fn reload_daemon() -> Result<()> {
+    let result = control(ControlCommand::ReloadConfig).await?;
+
+    ui::success(&format!(
+        "Reloaded configuration from {}",
+        result["source"].as_str().unwrap_or("defaults")
+    ));
+    let restart_required = json_strings(&result["restart_required"]);
+    if !restart_required.is_empty() {
+        ui::warning(&format!(
+            "Restart the daemon to apply: {}",
+            restart_required.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// Probe the running gateway's channels.
+async fn probe_daemon(channel: Option<String>) -> Result<()> {
+    let result = control(ControlCommand::ProbeChannels(channel)).await?;
+
+    ui::header("Channel Probe");
+    let channels = result["channels"].as_array().cloned().unwrap_or_default();
+    if channels.is_empty() {
+        ui::info("No channels registered");
+    }
+    for channel in &channels {
+        let id = channel["id"].as_str().unwrap_or_default();
+        if channel["connected"].as_bool().unwrap_or(false) {
+            ui::health_check(id, HealthStatus::Ok, channel["account_id"].as_str());
+        } else {
+            ui::health_check(id, HealthStatus::Error, channel["error"].as_str());
+        }
+    }
+
+    Ok(())
+}
+
+/// Start a channel in the running gateway.
+async fn start_channel(channel: String) -> Result<()> {
+    let health = control(ControlCommand::StartChannel(channel)).await?;
+    ui::success(&format!(
+        "Starting channel {} (was {})",
+        health["id"].as_str().unwrap_or_default(),
+        health["state"].as_str().unwrap_or("unknown")
+    ));
+    Ok(())
+}
+
+/// Stop a channel in the running gateway.
+async fn stop_channel(channel: String) -> Result<()> {
+    let health = control(ControlCommand::StopChannel(channel)).await?;
+    let id = health["id"].as_str().unwrap_or_default();
+    ui::success(&format!("Channel {id} stopped"));
+    ui::info(&format!(
+        "Start it again with: openclaw daemon start-channel {id}"
+    ));
+    Ok(())
+}
+
+/// Enable or disable a channel in the running gateway.
+async fn set_channel_enabled(channel: String, enabled: bool) -> Result<()> {
+    let command = if enabled {
+        ControlCommand::EnableChannel(channel)
+    } else {
+        ControlCommand::DisableChannel(channel)
+    };
+    let result = control(command).await?;
+    let id = result["health"]["id"].as_str().unwrap_or_default();
+    if enabled {
+        ui::success(&format!("Channel {id} enabled"));
+    } else {
+        ui::success(&format!("Channel {id} disabled"));
+    }
+    if result["persisted"].as_bool() != Some(true) {
+        ui::warning("No config file; the setting lasts until the gateway restarts");
+    }
+    Ok(())
+}
+
+/// Send a command to the running gateway's control socket.
+pub async fn control(command: ControlCommand) -> Result<serde_json::Value, ControlError> {
+    let client = ControlClient::connect(&default_control_address(), CONTROL_TIMEOUT)?;
+    client.call(&command).await
+}
+
+/// Print the status reported by a running gateway.
+fn print_status(status: &serde_json::Value) {
+    ui::success("Daemon is running");
+    ui::kv("Version", status["version"].as_str().unwrap_or_default());
+    ui::kv("PID", &status["pid"].to_string());
+    ui::kv(
+        "Uptime",
+        &format!("{}s", status["uptime_secs"].as_u64().unwrap_or_default()),
+    );
+    ui::kv("Address", status["address"].as_str().unwrap_or_default());
+    ui::kv(
+        "Config",
+        status["config_source"]
+            .as_str()
+            .unwrap_or("built-in defaults"),
+    );
+
+    let agents = json_strings(&status["agents"]);
+    ui::kv(
+        "Agents",
+        &if agents.is_empty() {
+            "none".to_string()
+        } else {
+            agents.join(", ")
+        },
+    );
+    let channels = json_strings(&status["channels"]);
+    ui::kv(
+        "Channels",
+        &if channels.is_empty() {
+            "none".to_string()
+        } else {
+            channels.join(", ")
+        },
+    );
+    ui::kv(
+        "Sessions",
+        &status["storage"]["sessions"]
+            .as_u64()
+            .unwrap_or_default()
+            .to_string(),
+    );
+
+    let health = status["channel_health"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    if !health.is_empty() {
+        ui::header("Channels");
+    }
+    for channel in &health {
+        let id = channel["id"].as_str().unwrap_or_default();
+        let state = channel["state"].as_str().unwrap_or("unknown");
+        let restarts = channel["restarts"].as_u64().unwrap_or_default();
+        let detail = match channel["last_error"].as_str() {
+            Some(error) if restarts > 0 => {
+                format!("{state}, {restarts} restart(s), last error: {error}")
+            }
+            _ => state.to_string(),
+        };
+        let health = match state {
+            "running" => HealthStatus::Ok,
+            "starting" | "stopped" => HealthStatus::Warning,
+            "backoff" => HealthStatus::Error,
+            _ => HealthStatus::Unknown,
+        };
+        ui::health_check(id, health, Some(&detail));
+    }
+}
+
+fn json_strings(value: &serde_json::Value) -> Vec<&str> {
+    value
+        .as_array()
+        .map(|items| items.iter().filter_map(serde_json::Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+/// Whether the daemon is installed as a system service.
+fn service_installed() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        get_launchd_plist_path().exists()
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        get_systemd_service_path().exists()
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        false
+    }
+}
+
 // Platform-specific implementations
 
 #[cfg(target_os = "macos")]
@@ -258,6 +469,7 @@ fn install_launchd_service() -> Result<()> {
         <string>{}</string>
         <string>gateway</string>
         <string>run</string>
+        <string>--log-file</string>
     </array>
     <key>RunAtLoad</key>
     <true/>
@@ -327,7 +539,7 @@ After=network.target
 
 [Service]
 Type=simple
-ExecStart={} gateway run
+ExecStart={} gateway run --log-file
 Restart=always
 RestartSec=5
 
@@ -1,8 +1,24 @@
 //! Doctor command - health checks and auto-repair.
 
+use super::configure::{CREDENTIALS_KEY_PURPOSE, load_master_key};
+use super::daemon::control;
+use super::sessions::EVENT_STORE_KEY_PURPOSE;
 use crate::ui::{self, HealthStatus};
 use anyhow::Result;
-use std::path::PathBuf;
+use openclaw_agents::sandbox::{SandboxConfig, execute_sandboxed};
+use openclaw_core::events::EventStore;
+use openclaw_core::{ApiKey, Config, CredentialStore, SessionKey};
+use openclaw_gateway::{Check, CheckStatus, ReadinessReport};
+use openclaw_ipc::control::ControlCommand;
+use openclaw_providers::{AnthropicProvider, OpenAIProvider, Provider};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long a provider may take to list its models.
+const PROVIDER_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `OpenRouter`'s OpenAI-compatible API.
+const OPENROUTER_BASE_URL: &str = "https://openrouter.ai/api/v1";
 
 /// Doctor command arguments.
 #[derive(Debug, Clone, Default)]
@@ -20,154 +36,144 @@ pub async fn run_doctor(args: DoctorArgs) -> Result<()> {
     ui::header("OpenClaw Doctor");
     println!();
 
-    let mut issues_found = 0;
-    let mut repairs_made = 0;
+    let mut tally = Tally::new(args.repair);
 
     // Check 1: Configuration
     ui::info("Checking configuration...");
-    match check_config() {
-        CheckResult::Ok => {
-            ui::health_check("Configuration", HealthStatus::Ok, None);
-        }
-        CheckResult::Warning(msg) => {
-            ui::health_check("Configuration", HealthStatus::Warning, Some(&msg));
-            issues_found += 1;
-        }
-        CheckResult::Error(msg) => {
-            ui::health_check("Configuration", HealthStatus::Error, Some(&msg));
-            issues_found += 1;
-
-            if args.repair {
-                ui::info("  → Creating default configuration...");
-                if create_default_config().is_ok() {
-                    ui::success("  → Configuration created");
-                    repairs_made += 1;
-                }
-            }
+    let config_path = get_config_path();
+    let config = check_config();
+    if tally.record("Configuration", &config) {
+        if !config_path.exists() {
+            tally.repair("Creating default configuration", create_default_config);
+        } else if args.force {
+            tally.repair("Replacing configuration (backup kept)", || {
+                backup_and_replace_config(&config_path)
+            });
         }
     }
 
-    // Check 2: State directory
+    // Check 2: Configuration schema
+    tally.record("Configuration keys", &check_config_keys(&config_path));
+
+    // Check 3: State directory
     ui::info("Checking state directory...");
-    match check_state_dir() {
-        CheckResult::Ok => {
-            ui::health_check("State directory", HealthStatus::Ok, None);
-        }
-        CheckResult::Warning(msg) => {
-            ui::health_check("State directory", HealthStatus::Warning, Some(&msg));
-            issues_found += 1;
-        }
-        CheckResult::Error(msg) => {
-            ui::health_check("State directory", HealthStatus::Error, Some(&msg));
-            issues_found += 1;
-
-            if args.repair {
-                ui::info("  → Creating state directory...");
-                if create_state_dir().is_ok() {
-                    ui::success("  → State directory created");
-                    repairs_made += 1;
-                }
-            }
-        }
+    let state_dir = check_state_dir();
+    if tally.record("State directory", &state_dir) {
+        tally.repair("Creating state directory", create_state_dir);
     }
 
-    // Check 3: Credentials
+    // Check 4: Credentials
     ui::info("Checking credentials...");
-    match check_credentials() {
-        CheckResult::Ok => {
-            ui::health_check("Credentials", HealthStatus::Ok, None);
-        }
-        CheckResult::Warning(msg) => {
-            ui::health_check("Credentials", HealthStatus::Warning, Some(&msg));
-            issues_found += 1;
-        }
-        CheckResult::Error(msg) => {
-            ui::health_check("Credentials", HealthStatus::Error, Some(&msg));
-            issues_found += 1;
-        }
+    tally.record("Credentials", &check_credentials());
+    if tally.record(
+        "Credential permissions",
+        &check_credential_permissions(&get_credentials_path()),
+    ) {
+        tally.repair("Restricting credential permissions", || {
+            restrict_credential_permissions(&get_credentials_path())
+        });
     }
 
-    // Check 4: Sandbox availability
+    // Check 5: Sandbox availability
     ui::info("Checking sandbox...");
-    match check_sandbox() {
-        CheckResult::Ok => {
-            ui::health_check("Sandbox", HealthStatus::Ok, None);
+    if tally.record("Sandbox", &check_sandbox()) {
+        ui::info("  → Install bubblewrap (e.g. 'apt install bubblewrap') to enable sandboxing");
+    } else {
+        tally.record("Sandbox execution", &check_sandbox_execution());
+    }
+
+    // Check 6: Provider API keys
+    ui::info("Checking providers...");
+    let providers = configured_providers();
+    if providers.is_empty() {
+        tally.record(
+            "Providers",
+            &CheckResult::Skipped("no API keys configured".to_string()),
+        );
+    }
+    for (name, provider) in &providers {
+        if tally.record(
+            &format!("Provider {name}"),
+            &check_provider(provider.as_ref()).await,
+        ) {
+            ui::info("  → Update the key with: openclaw configure auth");
         }
-        CheckResult::Warning(msg) => {
-            ui::health_check("Sandbox", HealthStatus::Warning, Some(&msg));
-            issues_found += 1;
+    }
+
+    // Check 7: Event store integrity
+    ui::info("Checking event store...");
+    let events_path = openclaw_gateway::GatewayConfig::default()
+        .data_dir
+        .join("events");
+    match open_event_store(&events_path) {
+        Ok(store) => {
+            let (result, corrupt_sessions) = check_event_store(&store);
+            if tally.record("Event store", &result) {
+                for key in &corrupt_sessions {
+                    tally.repair(&format!("Rebuilding session {key}"), || {
+                        store.rebuild_projection(key)?;
+                        Ok(())
+                    });
+                }
+            }
         }
-        CheckResult::Error(msg) => {
-            ui::health_check("Sandbox", HealthStatus::Error, Some(&msg));
-            issues_found += 1;
+        Err(result) => {
+            tally.record("Event store", &result);
         }
     }
 
-    // Check 5: Gateway connectivity (if running)
+    // Check 8: Gateway connectivity (if running)
     ui::info("Checking gateway...");
-    match check_gateway().await {
-        CheckResult::Ok => {
-            ui::health_check("Gateway", HealthStatus::Ok, Some("running"));
-        }
-        CheckResult::Warning(msg) => {
-            ui::health_check("Gateway", HealthStatus::Warning, Some(&msg));
-        }
-        CheckResult::Error(msg) => {
-            ui::health_check("Gateway", HealthStatus::Error, Some(&msg));
-        }
+    let (gateway, dependencies) = check_gateway().await;
+    let gateway_running = !matches!(gateway, CheckResult::Skipped(_));
+    tally.record("Gateway", &gateway);
+    for check in &dependencies {
+        let result = match check.status {
+            CheckStatus::Ok => CheckResult::Ok,
+            CheckStatus::Degraded => CheckResult::Warning(check.detail.clone().unwrap_or_default()),
+            CheckStatus::Failed => CheckResult::Error(check.detail.clone().unwrap_or_default()),
+        };
+        tally.record(&format!("Gateway {}", check.name), &result);
     }
 
-    // Check 6: Shell completion
-    ui::info("Checking shell completion...");
-    match check_shell_completion() {
-        CheckResult::Ok => {
-            ui::health_check("Shell completion", HealthStatus::Ok, None);
-        }
-        CheckResult::Warning(msg) => {
-            ui::health_check("Shell completion", HealthStatus::Warning, Some(&msg));
-            issues_found += 1;
-        }
-        CheckResult::Error(msg) => {
-            ui::health_check("Shell completion", HealthStatus::Error, Some(&msg));
-            issues_found += 1;
+    // Check 9: Channels (through the running gateway)
+    if gateway_running {
+        ui::info("Checking channels...");
+        for (id, result) in check_channels().await {
+            if tally.record(&format!("Channel {id}"), &result) {
+                tally
+                    .repair_async(&format!("Restarting channel {id}"), async {
+                        control(ControlCommand::StartChannel(id.clone())).await?;
+                        Ok::<_, anyhow::Error>(())
+                    })
+                    .await;
+            }
         }
     }
 
+    // Check 10: Shell completion
+    ui::info("Checking shell completion...");
+    tally.record("Shell completion", &check_shell_completion());
+
     // Deep scan
     if args.deep {
         ui::info("Running deep scan...");
 
         // Check for multiple gateway instances
-        match check_multiple_gateways() {
-            CheckResult::Ok => {
-                ui::health_check(
-                    "Gateway instances",
-                    HealthStatus::Ok,
-                    Some("single instance"),
-                );
-            }
-            CheckResult::Warning(msg) => {
-                ui::health_check("Gateway instances", HealthStatus::Warning, Some(&msg));
-                issues_found += 1;
-            }
-            CheckResult::Error(msg) => {
-                ui::health_check("Gateway instances", HealthStatus::Error, Some(&msg));
-                issues_found += 1;
-            }
-        }
+        tally.record("Gateway instances", &check_multiple_gateways());
     }
 
     // Summary
     println!();
     ui::header("Summary");
 
-    if issues_found == 0 {
+    if tally.issues == 0 {
         ui::success("All checks passed!");
     } else {
-        ui::warning(&format!("{issues_found} issue(s) found"));
+        ui::warning(&format!("{} issue(s) found", tally.issues));
 
         if args.repair {
-            ui::info(&format!("{repairs_made} repair(s) made"));
+            ui::info(&format!("{} repair(s) made", tally.repairs));
         } else {
             ui::info("Run with --repair to fix issues automatically");
         }
@@ -181,6 +187,81 @@ enum CheckResult {
     Ok,
     Warning(String),
     Error(String),
+    /// The check could not run; not counted as an issue.
+    Skipped(String),
+}
+
+/// Issue and repair counts.
+struct Tally {
+    issues: usize,
+    repairs: usize,
+    repair: bool,
+}
+
+impl Tally {
+    const fn new(repair: bool) -> Self {
+        Self {
+            issues: 0,
+            repairs: 0,
+            repair,
+        }
+    }
+
+    /// Print a check result. Returns whether it is an issue.
+    fn record(&mut self, name: &str, result: &CheckResult) -> bool {
+        match result {
+            CheckResult::Ok => {
+                ui::health_check(name, HealthStatus::Ok, None);
+                false
+            }
+            CheckResult::Warning(msg) => {
+                ui::health_check(name, HealthStatus::Warning, Some(msg));
+                self.issues += 1;
+                true
+            }
+            CheckResult::Error(msg) => {
+                ui::health_check(name, HealthStatus::Error, Some(msg));
+                self.issues += 1;
+                true
+            }
+            CheckResult::Skipped(msg) => {
+                ui::health_check(name, HealthStatus::Unknown, Some(msg));
+                false
+            }
+        }
+    }
+
+    /// Run a repair if repairs were requested.
+    fn repair(&mut self, action: &str, repair: impl FnOnce() -> Result<()>) {
+        if !self.repair {
+            return;
+        }
+        ui::info(&format!("  → {action}..."));
+        self.repaired(repair());
+    }
+
+    /// Run an async repair if repairs were requested.
+    async fn repair_async(
+        &mut self,
+        action: &str,
+        repair: impl std::future::Future<Output = Result<()>>,
+    ) {
+        if !self.repair {
+            return;
+        }
+        ui::info(&format!("  → {action}..."));
+        self.repaired(repair.await);
+    }
+
+    fn repaired(&mut self, result: Result<()>) {
+        match result {
+            Ok(()) => {
+                ui::success("  → Done");
+                self.repairs += 1;
+            }
+            Err(e) => ui::error(&format!("  → Failed: {e}")),
+        }
+    }
 }
 
 /// Check configuration file.
@@ -191,12 +272,29 @@ fn check_config() -> CheckResult {
         return CheckResult::Error("Config file not found".to_string());
     }
 
-    match openclaw_core::Config::load_default() {
+    match Config::load(&config_path) {
         Ok(_) => CheckResult::Ok,
         Err(e) => CheckResult::Error(format!("Invalid config: {e}")),
     }
 }
 
+/// Check for config keys that no setting reads (usually typos).
+fn check_config_keys(config_path: &Path) -> CheckResult {
+    let Ok(content) = std::fs::read_to_string(config_path) else {
+        return CheckResult::Skipped("no config file".to_string());
+    };
+    let Ok(raw) = json5::from_str::<serde_json::Value>(&content) else {
+        return CheckResult::Skipped("config does not parse".to_string());
+    };
+
+    let unknown = Config::unknown_keys(&raw);
+    if unknown.is_empty() {
+        CheckResult::Ok
+    } else {
+        CheckResult::Warning(format!("Unknown keys: {}", unknown.join(", ")))
+    }
+}
+
 /// Check state directory.
 fn check_state_dir() -> CheckResult {
     let state_dir = get_state_dir();
@@ -239,6 +337,68 @@ fn check_credentials() -> CheckResult {
     CheckResult::Ok
 }
 
+/// Check that only the owner can read the credentials directory and files.
+fn check_credential_permissions(cred_path: &Path) -> CheckResult {
+    if !cred_path.exists() {
+        return CheckResult::Skipped("no credentials directory".to_string());
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut loose = Vec::new();
+        let paths = std::iter::once(cred_path.to_path_buf()).chain(
+            std::fs::read_dir(cred_path)
+                .into_iter()
+                .flatten()
+                .filter_map(std::result::Result::ok)
+                .map(|entry| entry.path()),
+        );
+        for path in paths {
+            let mode = std::fs::metadata(&path).map_or(0, |m| m.permissions().mode());
+            if mode & 0o077 != 0 {
+                loose.push(path.file_name().map_or_else(
+                    || path.display().to_string(),
+                    |name| name.to_string_lossy().into_owned(),
+                ));
+            }
+        }
+
+        if loose.is_empty() {
+            CheckResult::Ok
+        } else {
+            CheckResult::Error(format!("Readable by other users: {}", loose.join(", ")))
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        CheckResult::Skipped("not supported on this platform".to_string())
+    }
+}
+
+/// Restrict the credentials directory to 0700 and its files to 0600.
+fn restrict_credential_permissions(cred_path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::set_permissions(cred_path, std::fs::Permissions::from_mode(0o700))?;
+        for entry in std::fs::read_dir(cred_path)? {
+            let path = entry?.path();
+            if path.is_file() {
+                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    let _ = cred_path;
+
+    Ok(())
+}
+
 /// Check sandbox availability.
 fn check_sandbox() -> CheckResult {
     if openclaw_agents::sandbox::is_sandbox_available() {
@@ -253,7 +413,7 @@ fn check_sandbox() -> CheckResult {
 
         #[cfg(target_os = "macos")]
         {
-            CheckResult::Ok // sandbox-exec is always available on macOS
+            CheckResult::Warning("sandbox-exec not found".to_string())
         }
 
         #[cfg(target_os = "windows")]
@@ -268,32 +428,185 @@ fn check_sandbox() -> CheckResult {
     }
 }
 
-/// Check gateway connectivity.
-async fn check_gateway() -> CheckResult {
+/// Run a trivial command in the sandbox.
+fn check_sandbox_execution() -> CheckResult {
+    match execute_sandboxed("echo", &["openclaw-doctor"], &SandboxConfig::default()) {
+        Ok(output) if output.exit_code == 0 && output.stdout.trim() == "openclaw-doctor" => {
+            CheckResult::Ok
+        }
+        Ok(output) => CheckResult::Error(format!(
+            "Test command exited with {}: {}",
+            output.exit_code,
+            output.stderr.trim()
+        )),
+        Err(e) => CheckResult::Error(e.to_string()),
+    }
+}
+
+/// Providers with an API key, from the credential store or environment.
+fn configured_providers() -> Vec<(&'static str, Box<dyn Provider>)> {
+    let cred_path = get_credentials_path();
+    let store = cred_path
+        .exists()
+        .then(load_master_key)
+        .and_then(Result::ok)
+        .map(|master_key| {
+            CredentialStore::from_key(master_key.derive(CREDENTIALS_KEY_PURPOSE), cred_path)
+        });
+    let key = |name: &str, env: &str| {
+        store
+            .as_ref()
+            .and_then(|store| store.load(name).ok())
+            .or_else(|| std::env::var(env).ok().map(ApiKey::new))
+    };
+
+    let mut providers: Vec<(&'static str, Box<dyn Provider>)> = Vec::new();
+    if let Some(key) = key("anthropic", "ANTHROPIC_API_KEY") {
+        providers.push(("anthropic", Box::new(AnthropicProvider::new(key))));
+    }
+    if let Some(key) = key("openai", "OPENAI_API_KEY") {
+        providers.push(("openai", Box::new(OpenAIProvider::new(key))));
+    }
+    if let Some(key) = key("openrouter", "OPENROUTER_API_KEY") {
+        providers.push((
+            "openrouter",
+            Box::new(OpenAIProvider::with_base_url(key, OPENROUTER_BASE_URL)),
+        ));
+    }
+    providers
+}
+
+/// Check a provider's API key with a model list call.
+async fn check_provider(provider: &dyn Provider) -> CheckResult {
+    match tokio::time::timeout(PROVIDER_CHECK_TIMEOUT, provider.list_models()).await {
+        Ok(Ok(models)) if models.is_empty() => {
+            CheckResult::Warning("Key accepted but no models listed".to_string())
+        }
+        Ok(Ok(_)) => CheckResult::Ok,
+        Ok(Err(e)) => CheckResult::Error(e.to_string()),
+        Err(_) => CheckResult::Warning("Timed out".to_string()),
+    }
+}
+
+/// Open the event store, or describe why the check can't run.
+fn open_event_store(path: &Path) -> Result<EventStore, CheckResult> {
+    let settings = Config::load_default()
+        .map(|c| c.settings)
+        .unwrap_or_default();
+    if !EventStore::backend_path(path, settings.event_store_backend).exists() {
+        return Err(CheckResult::Skipped("no event store yet".to_string()));
+    }
+
+    let key = if settings.encrypt_event_store {
+        let key = load_master_key()
+            .map_err(|e| CheckResult::Skipped(format!("master key unavailable: {e}")))?
+            .derive(EVENT_STORE_KEY_PURPOSE);
+        Some(key)
+    } else {
+        None
+    };
+    // sled holds an exclusive lock while the gateway has the store open
+    EventStore::open_with(path, settings.event_store_backend, key)
+        .map_err(|e| CheckResult::Skipped(format!("cannot open ({e}); is the gateway running?")))
+}
+
+/// Decode every stored entry. Also returns sessions whose projection can
+/// be rebuilt.
+fn check_event_store(store: &EventStore) -> (CheckResult, Vec<SessionKey>) {
+    let report = match store.verify() {
+        Ok(report) => report,
+        Err(e) => return (CheckResult::Error(e.to_string()), vec![]),
+    };
+
+    if report.is_ok() {
+        return (CheckResult::Ok, vec![]);
+    }
+    let mut problems = Vec::new();
+    if !report.corrupt_events.is_empty() {
+        problems.push(format!(
+            "{} unreadable event(s); restore from backup",
+            report.corrupt_events.len()
+        ));
+    }
+    if !report.corrupt_sessions.is_empty() {
+        problems.push(format!(
+            "{} unreadable session summary(ies)",
+            report.corrupt_sessions.len()
+        ));
+    }
+    (
+        CheckResult::Error(problems.join("; ")),
+        report.corrupt_sessions,
+    )
+}
+
+/// Check gateway connectivity and, if it is running, its readiness checks.
+async fn check_gateway() -> (CheckResult, Vec<Check>) {
     // Try to connect to the default gateway port
     let addr = "127.0.0.1:18789";
 
-    match tokio::net::TcpStream::connect(addr).await {
-        Ok(_) => {
-            // Try HTTP health check
-            let client = reqwest::Client::new();
-            match client
-                .get("http://127.0.0.1:18789/health")
-                .timeout(std::time::Duration::from_secs(2))
-                .send()
-                .await
-            {
-                Ok(resp) if resp.status().is_success() => CheckResult::Ok,
-                Ok(resp) => {
-                    CheckResult::Warning(format!("Gateway returned status {}", resp.status()))
-                }
-                Err(_) => {
-                    CheckResult::Warning("Gateway running but health check failed".to_string())
-                }
-            }
-        }
-        Err(_) => CheckResult::Warning("Gateway not running".to_string()),
+    if tokio::net::TcpStream::connect(addr).await.is_err() {
+        return (CheckResult::Skipped("not running".to_string()), vec![]);
     }
+
+    // The readiness endpoint answers 503 with the same report when not ready
+    let client = reqwest::Client::new();
+    let response = client
+        .get("http://127.0.0.1:18789/readyz")
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await;
+    let Ok(response) = response else {
+        return (
+            CheckResult::Warning("Gateway running but health check failed".to_string()),
+            vec![],
+        );
+    };
+    let status = response.status();
+    match response.json::<ReadinessReport>().await {
+        Ok(report) if report.ready => (CheckResult::Ok, report.checks),
+        Ok(report) => (
+            CheckResult::Error("Gateway is not ready".to_string()),
+            report.checks,
+        ),
+        Err(_) => (
+            CheckResult::Warning(format!("Gateway returned status {status}")),
+            vec![],
+        ),
+    }
+}
+
+/// Probe each channel through the running gateway's control socket.
+async fn check_channels() -> Vec<(String, CheckResult)> {
+    let result = match control(ControlCommand::ProbeChannels(None)).await {
+        Ok(result) => result,
+        Err(e) => {
+            return vec![(
+                "probe".to_string(),
+                CheckResult::Skipped(format!("control socket unavailable: {e}")),
+            )];
+        }
+    };
+
+    result["channels"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|channel| {
+            let id = channel["id"].as_str().unwrap_or_default().to_string();
+            let result = if channel["connected"].as_bool().unwrap_or(false) {
+                CheckResult::Ok
+            } else {
+                CheckResult::Error(
+                    channel["error"]
+                        .as_str()
+                        .unwrap_or("not connected")
+                        .to_string(),
+                )
+            };
+            (id, result)
+        })
+        .collect()
 }
 
 /// Check shell completion setup.
@@ -332,8 +645,7 @@ fn create_default_config() -> Result<()> {
     let default_config = serde_json::json!({
         "gateway": {
             "mode": "local",
-            "port": 18789,
-            "bind": "loopback"
+            "port": 18789
         },
         "agents": {
             "defaults": {
@@ -346,6 +658,17 @@ fn create_default_config() -> Result<()> {
     Ok(())
 }
 
+/// Move an invalid config aside and write the default one.
+fn backup_and_replace_config(config_path: &Path) -> Result<()> {
+    let backup = config_path.with_extension("json.bak");
+    std::fs::rename(config_path, &backup)?;
+    ui::info(&format!(
+        "  → Previous config saved to {}",
+        backup.display()
+    ));
+    create_default_config()
+}
+
 /// Create state directory.
 fn create_state_dir() -> Result<()> {
     let state_dir = get_state_dir();
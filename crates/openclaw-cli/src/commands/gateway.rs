@@ -1,14 +1,22 @@
 //! Gateway command - start and manage the gateway server.
 
-use crate::ui;
+use super::GatewayEndpoint;
+use super::backup::{backup_schedule, blob_store};
+use super::configure::load_master_key;
+use super::sessions::EVENT_STORE_KEY_PURPOSE;
+use crate::ui::{self, HealthStatus};
 use anyhow::Result;
 use openclaw_core::config::BindMode;
+use openclaw_gateway::{StartupReport, UiEvent};
+use tokio::sync::broadcast::error::RecvError;
 
 /// Gateway command arguments.
 #[derive(Debug, Clone)]
 pub struct GatewayArgs {
     /// Subcommand.
     pub action: GatewayAction,
+    /// Handle to the CLI's reloadable log filter.
+    pub log_filter: Option<openclaw_gateway::LogFilter>,
 }
 
 /// Gateway actions.
@@ -29,6 +37,7 @@ impl Default for GatewayArgs {
     fn default() -> Self {
         Self {
             action: GatewayAction::Status,
+            log_filter: None,
         }
     }
 }
@@ -36,13 +45,20 @@ impl Default for GatewayArgs {
 /// Run the gateway command.
 pub async fn run_gateway(args: GatewayArgs) -> Result<()> {
     match args.action {
-        GatewayAction::Run { port, bind, force } => run_gateway_server(port, bind, force).await,
+        GatewayAction::Run { port, bind, force } => {
+            run_gateway_server(port, bind, force, args.log_filter).await
+        }
         GatewayAction::Status => gateway_status().await,
     }
 }
 
 /// Start the gateway server.
-async fn run_gateway_server(port: Option<u16>, bind: Option<String>, force: bool) -> Result<()> {
+async fn run_gateway_server(
+    port: Option<u16>,
+    bind: Option<String>,
+    force: bool,
+    log_filter: Option<openclaw_gateway::LogFilter>,
+) -> Result<()> {
     // Load configuration
     let config = if let Ok(c) = openclaw_core::Config::load_default() {
         c
@@ -61,7 +77,12 @@ async fn run_gateway_server(port: Option<u16>, bind: Option<String>, force: bool
     });
 
     // Check if port is already in use
-    if !force {
+    let listen_tcp = config
+        .gateway
+        .unix_socket
+        .as_ref()
+        .is_none_or(|socket| socket.tcp);
+    if !force && listen_tcp {
         if let Ok(listener) = std::net::TcpListener::bind(format!("{bind_address}:{server_port}")) {
             drop(listener);
         } else {
@@ -73,7 +94,12 @@ async fn run_gateway_server(port: Option<u16>, bind: Option<String>, force: bool
     }
 
     ui::header("Starting OpenClaw Gateway");
-    ui::kv("Address", &format!("{bind_address}:{server_port}"));
+    if listen_tcp {
+        ui::kv("Address", &format!("{bind_address}:{server_port}"));
+    }
+    if let Some(socket) = &config.gateway.unix_socket {
+        ui::kv("Socket", &socket.path.display().to_string());
+    }
     let mode_str = match &config.gateway.mode {
         BindMode::Local => "local",
         BindMode::Public => "public",
@@ -82,41 +108,189 @@ async fn run_gateway_server(port: Option<u16>, bind: Option<String>, force: bool
     ui::kv("Mode", mode_str);
     println!();
 
-    let gateway_config = openclaw_gateway::GatewayConfig {
-        port: server_port,
-        bind_address,
-        cors: true,
-        ..Default::default()
-    };
+    let gateway_config = gateway_config(&config, server_port, bind_address, log_filter)?;
 
     ui::info("Gateway is starting...");
-    ui::info("Press Ctrl+C to stop");
+    ui::info("Press Ctrl+C to stop (in-flight agent runs are drained first)");
     println!();
 
-    // Start the gateway
-    openclaw_gateway::start(gateway_config).await?;
+    // Start the gateway, printing the startup report once boot finishes
+    let gateway = openclaw_gateway::Gateway::new(gateway_config)?;
+    let mut events = gateway.events().await.subscribe();
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(envelope) => {
+                    if let UiEvent::GatewayStarted { report } = envelope.event {
+                        print_startup_report(&report);
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+    gateway.run().await?;
 
     Ok(())
 }
 
+/// Gateway configuration from the loaded config file.
+fn gateway_config(
+    config: &openclaw_core::Config,
+    port: u16,
+    bind_address: String,
+    log_filter: Option<openclaw_gateway::LogFilter>,
+) -> Result<openclaw_gateway::GatewayConfig> {
+    let config_path = openclaw_core::Config::default_path();
+    let namespaces: std::collections::HashMap<_, _> = config
+        .gateway
+        .namespaces
+        .iter()
+        .map(|(id, ns)| {
+            openclaw_gateway::NamespaceConfig::from_config(id, ns).map(|ns| (id.clone(), ns))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(openclaw_gateway::GatewayConfig {
+        port,
+        bind_address,
+        cors: config.gateway.cors.clone(),
+        timeouts: openclaw_gateway::RequestTimeouts::from_config(&config.gateway),
+        inbound_rate_limit: config.channels.rate_limit.clone(),
+        outbound_formatting: config.channels.formatting.clone(),
+        inbound_debounce: config.channels.debounce.clone(),
+        working_hours: config.channels.working_hours.clone(),
+        groups: config.channels.groups.clone(),
+        commands: config.channels.commands.clone(),
+        attachment_policy: config.channels.attachments.clone(),
+        locale: config.channels.locale.clone(),
+        disabled_channels: config.channels.disabled.clone(),
+        whatsapp: config.channels.whatsapp.clone(),
+        wire_log: config.providers.wire_log.clone(),
+        recording: config.providers.recording.clone(),
+        experiments: config.experiments.clone(),
+        schedules: config.schedules.clone(),
+        notifications: openclaw_agents::NotificationTemplates::new(config.notifications.clone())?,
+        broadcast: config.channels.broadcast.clone(),
+        allowlist: config
+            .agents
+            .iter()
+            .collect::<std::collections::BTreeMap<_, _>>()
+            .into_values()
+            .flat_map(|agent| agent.allowlist.iter().cloned())
+            .collect(),
+        deleted_session_retention_days: config.settings.deleted_session_retention_days,
+        archive_ended_after_days: config.settings.archive_ended_after_days,
+        idle_sessions: config.settings.idle_sessions.clone(),
+        event_store_key: if config.settings.encrypt_event_store {
+            Some(load_master_key()?.derive(EVENT_STORE_KEY_PURPOSE))
+        } else {
+            None
+        },
+        event_store_format: config.settings.event_store_format,
+        event_store_backend: config.settings.event_store_backend,
+        backup: backup_schedule(config)?,
+        blobs: Some(blob_store(config)?),
+        log_filter,
+        config_source: config_path
+            .exists()
+            .then(|| config_path.display().to_string()),
+        boot_budget: std::time::Duration::from_millis(config.gateway.boot_budget_ms),
+        drain_timeout: std::time::Duration::from_secs(config.gateway.drain_timeout_secs),
+        mcp: config.gateway.mcp,
+        control_address: Some(openclaw_ipc::control::default_control_address()),
+        namespaces,
+        cluster: config
+            .gateway
+            .cluster
+            .as_ref()
+            .map(openclaw_gateway::ClusterConfig::from_config),
+        health: config.gateway.health.clone(),
+        limits: config.gateway.limits.clone(),
+        rate_limit: config.gateway.rate_limit.clone(),
+        proxy: config.gateway.proxy.clone(),
+        unix_socket: config.gateway.unix_socket.clone(),
+        oidc: config.gateway.oidc.clone(),
+        ..Default::default()
+    })
+}
+
+/// Pretty-print the gateway startup report.
+fn print_startup_report(report: &StartupReport) {
+    ui::header("Startup Report");
+    ui::kv(
+        "Config",
+        report
+            .config_source
+            .as_deref()
+            .unwrap_or("built-in defaults"),
+    );
+    ui::kv("Address", &report.address);
+    ui::kv(
+        "Agents",
+        &if report.agents.is_empty() {
+            "none".to_string()
+        } else {
+            report.agents.join(", ")
+        },
+    );
+    ui::kv(
+        "Storage",
+        &format!(
+            "{} sessions, {} events, {} KiB",
+            report.storage.sessions,
+            report.storage.events,
+            report.storage.size_bytes / 1024
+        ),
+    );
+
+    if !report.channels.is_empty() {
+        println!();
+        println!("Channels:");
+        for channel in &report.channels {
+            let (status, detail) = if channel.connected {
+                (HealthStatus::Ok, channel.account_id.as_deref())
+            } else {
+                (HealthStatus::Error, channel.error.as_deref())
+            };
+            ui::health_check(&channel.id, status, detail);
+        }
+    }
+
+    println!();
+    println!("Boot phases:");
+    for phase in &report.phases {
+        println!("  {:<16} {:>6}ms", phase.name, phase.duration_ms);
+    }
+    println!("  {:<16} {:>6}ms", "total", report.total_ms);
+
+    if report.over_budget() {
+        println!();
+        ui::warning(&format!(
+            "Boot took {}ms, over the {}ms budget (gateway.bootBudgetMs)",
+            report.total_ms, report.budget_ms
+        ));
+    }
+    println!();
+}
+
 /// Check gateway status.
 async fn gateway_status() -> Result<()> {
     ui::header("Gateway Status");
 
-    // Get configured port
-    let port = match openclaw_core::Config::load_default() {
-        Ok(c) => c.gateway.port,
-        Err(_) => 18789,
-    };
+    let endpoint = GatewayEndpoint::resolve(None);
+    let target = &endpoint.target;
 
     // Try to connect
-    if let Ok(_) = tokio::net::TcpStream::connect(format!("127.0.0.1:{port}")).await {
-        ui::success(&format!("Gateway is running on port {port}"));
+    if endpoint.is_listening().await {
+        ui::success(&format!("Gateway is running on {target}"));
 
         // Try health check
-        let client = reqwest::Client::new();
-        match client
-            .get(format!("http://127.0.0.1:{port}/health"))
+        match endpoint
+            .client
+            .get(format!("{}/health", endpoint.base_url))
             .timeout(std::time::Duration::from_secs(2))
             .send()
             .await
@@ -138,7 +312,7 @@ async fn gateway_status() -> Result<()> {
             }
         }
     } else {
-        ui::warning(&format!("Gateway is not running on port {port}"));
+        ui::warning(&format!("Gateway is not running on {target}"));
         ui::info("Start with: openclaw gateway run");
     }
 
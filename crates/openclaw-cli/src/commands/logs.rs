@@ -0,0 +1,108 @@
+//! Logs command - read the gateway's log files.
+
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Result;
+
+use openclaw_core::Config;
+use openclaw_gateway::active_log_file;
+
+use crate::ui;
+
+/// How often a followed log file is checked for new lines.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Logs command arguments.
+#[derive(Debug, Clone)]
+pub struct LogsArgs {
+    /// Subcommand.
+    pub action: LogsAction,
+}
+
+/// Logs actions.
+#[derive(Debug, Clone)]
+pub enum LogsAction {
+    /// Print the end of the active log file.
+    Tail {
+        /// Lines printed before following.
+        lines: usize,
+        /// Keep printing lines as they are written, across rotations.
+        follow: bool,
+    },
+}
+
+/// Run the logs command.
+///
+/// # Errors
+///
+/// Returns error if there are no log files or they cannot be read.
+pub async fn run_logs(args: LogsArgs) -> Result<()> {
+    let dir =
+        Config::load_default().map_or_else(|_| Config::logs_dir(), |c| c.settings.log_file.dir());
+
+    match args.action {
+        LogsAction::Tail { lines, follow } => tail(&dir, lines, follow).await,
+    }
+}
+
+async fn tail(dir: &Path, lines: usize, follow: bool) -> Result<()> {
+    let Some(mut path) = active_log_file(dir) else {
+        ui::info(&format!("No log files in {}.", dir.display()));
+        ui::info(
+            "The installed service writes them; enable settings.logFile to write them from 'openclaw gateway run' too.",
+        );
+        return Ok(());
+    };
+
+    let contents = std::fs::read(&path)?;
+    let mut offset = contents.len() as u64;
+    let text = String::from_utf8_lossy(&contents);
+    let all: Vec<&str> = text.lines().collect();
+    {
+        let mut out = std::io::stdout().lock();
+        for line in &all[all.len().saturating_sub(lines)..] {
+            writeln!(out, "{line}")?;
+        }
+        out.flush()?;
+    }
+
+    if !follow {
+        return Ok(());
+    }
+
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        offset = print_from(&path, offset)?;
+
+        // Finish the old file before switching to a rotated one
+        if let Some(next) = active_log_file(dir).filter(|next| *next != path) {
+            path = next;
+            offset = print_from(&path, 0)?;
+        }
+    }
+}
+
+/// Print `path` from `offset`, returning the new end offset.
+fn print_from(path: &Path, offset: u64) -> Result<u64> {
+    let mut file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(offset),
+        Err(e) => return Err(e.into()),
+    };
+    // A file that shrank was replaced; read it from the start
+    let offset = if file.metadata()?.len() < offset {
+        0
+    } else {
+        offset
+    };
+    file.seek(SeekFrom::Start(offset))?;
+    let mut new = Vec::new();
+    let read = file.read_to_end(&mut new)?;
+
+    let mut out = std::io::stdout().lock();
+    out.write_all(&new)?;
+    out.flush()?;
+    Ok(offset + read as u64)
+}
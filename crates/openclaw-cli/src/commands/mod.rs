@@ -1,21 +1,192 @@
 //! CLI command implementations.
 
 pub mod admin;
+pub mod agents;
+pub mod approvals;
+pub mod backup;
+pub mod broadcast;
 pub mod completion;
 pub mod config;
 pub mod configure;
 pub mod daemon;
 pub mod doctor;
 pub mod gateway;
+pub mod logs;
 pub mod onboard;
+pub mod peers;
+pub mod privacy;
+pub mod prompts;
+pub mod schedule;
+pub mod selftest;
+pub mod sessions;
+pub mod skills;
 pub mod status;
+pub mod test;
+pub mod workspace;
 
 pub use admin::run_admin;
+pub use agents::run_agents;
+pub use approvals::run_approvals;
+pub use backup::run_backup;
+pub use broadcast::run_broadcast;
 pub use completion::run_completion;
 pub use config::run_config;
 pub use configure::run_configure;
 pub use daemon::run_daemon;
 pub use doctor::run_doctor;
 pub use gateway::run_gateway;
+pub use logs::run_logs;
 pub use onboard::run_onboard;
+pub use peers::run_peers;
+pub use privacy::run_privacy;
+pub use prompts::run_prompts;
+pub use schedule::run_schedule;
+pub use selftest::run_selftest;
+pub use sessions::run_sessions;
+pub use skills::run_skills;
 pub use status::run_status;
+pub use test::run_test;
+pub use workspace::run_workspace;
+
+/// How the CLI reaches the local gateway.
+pub struct GatewayEndpoint {
+    /// Client for the gateway's transport.
+    pub client: reqwest::Client,
+    /// URL of the gateway's routes, base path included.
+    pub base_url: String,
+    /// The port or socket path, for messages.
+    pub target: GatewayTarget,
+}
+
+/// Where the local gateway listens.
+pub enum GatewayTarget {
+    /// A loopback TCP port.
+    Port(u16),
+    /// A Unix domain socket.
+    Socket(std::path::PathBuf),
+}
+
+impl GatewayEndpoint {
+    /// The endpoint for `port`, or else the configured Unix socket or port.
+    pub fn resolve(port: Option<u16>) -> Self {
+        let config = openclaw_core::Config::load_default().ok();
+        let base_path = config
+            .as_ref()
+            .map(|c| c.gateway.proxy.base_path.clone())
+            .unwrap_or_default();
+
+        #[cfg(unix)]
+        if port.is_none() {
+            if let Some(socket) = config.as_ref().and_then(|c| c.gateway.unix_socket.as_ref()) {
+                if let Ok(client) = reqwest::Client::builder()
+                    .unix_socket(socket.path.clone())
+                    .build()
+                {
+                    return Self {
+                        client,
+                        // The host is only sent as the Host header
+                        base_url: format!("http://localhost{base_path}"),
+                        target: GatewayTarget::Socket(socket.path.clone()),
+                    };
+                }
+            }
+        }
+
+        let port = port.unwrap_or_else(|| config.as_ref().map_or(18789, |c| c.gateway.port));
+        Self {
+            client: reqwest::Client::new(),
+            base_url: format!("http://127.0.0.1:{port}{base_path}"),
+            target: GatewayTarget::Port(port),
+        }
+    }
+
+    /// Whether something accepts connections at the endpoint.
+    pub async fn is_listening(&self) -> bool {
+        match &self.target {
+            GatewayTarget::Port(port) => {
+                tokio::net::TcpStream::connect(format!("127.0.0.1:{port}"))
+                    .await
+                    .is_ok()
+            }
+            #[cfg(unix)]
+            GatewayTarget::Socket(path) => tokio::net::UnixStream::connect(path).await.is_ok(),
+            #[cfg(not(unix))]
+            GatewayTarget::Socket(_) => false,
+        }
+    }
+
+    /// Message for a gateway that could not be reached.
+    pub fn unreachable(&self, error: &dyn std::fmt::Display) -> String {
+        match &self.target {
+            GatewayTarget::Port(port) => crate::ui::text(
+                "cli-gateway-unreachable",
+                &[
+                    ("port", port.to_string().into()),
+                    ("error", error.to_string().into()),
+                ],
+            ),
+            GatewayTarget::Socket(path) => crate::ui::text(
+                "cli-gateway-unreachable-socket",
+                &[
+                    ("path", path.display().to_string().into()),
+                    ("error", error.to_string().into()),
+                ],
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for GatewayTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Port(port) => write!(f, "port {port}"),
+            Self::Socket(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Call an RPC method on the local gateway and return its result.
+///
+/// `port` defaults to the configured Unix socket, or else the configured
+/// gateway port.
+///
+/// # Errors
+///
+/// Returns error if the gateway cannot be reached or the call fails.
+pub async fn gateway_rpc(
+    port: Option<u16>,
+    token: Option<&str>,
+    method: &str,
+    params: serde_json::Value,
+) -> anyhow::Result<serde_json::Value> {
+    let endpoint = GatewayEndpoint::resolve(port);
+
+    let mut request = endpoint
+        .client
+        .post(format!("{}/rpc", endpoint.base_url))
+        .timeout(std::time::Duration::from_secs(5))
+        .json(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": "1",
+            "method": method,
+            "params": params,
+        }));
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+
+    let mut response: serde_json::Value = request
+        .send()
+        .await
+        .map_err(|e| anyhow::anyhow!("{}", endpoint.unreachable(&e)))?
+        .json()
+        .await?;
+
+    if let Some(error) = response.get("error").filter(|e| !e.is_null()) {
+        return Err(anyhow::anyhow!(
+            "{}",
+            error["message"].as_str().unwrap_or("Request failed")
+        ));
+    }
+    Ok(response["result"].take())
+}
@@ -1,5 +1,6 @@
 //! Onboarding wizard command.
 
+use super::configure::{CREDENTIALS_KEY_PURPOSE, load_master_key};
 use crate::ui::{
     self,
     prompts::{self, AuthProvider, OnboardFlow},
@@ -159,9 +160,10 @@ pub async fn run_onboard(args: OnboardArgs) -> Result<()> {
         let cred_path = get_credentials_path();
         std::fs::create_dir_all(&cred_path)?;
 
-        // Generate encryption key (in production, derive from master password)
-        let encryption_key: [u8; 32] = rand::random();
-        let store = CredentialStore::new(encryption_key, cred_path);
+        // Encrypt with a key derived from the master key
+        let master_key = load_master_key()?;
+        let store =
+            CredentialStore::from_key(master_key.derive(CREDENTIALS_KEY_PURPOSE), cred_path);
 
         let provider_name = match provider {
             AuthProvider::Anthropic => "anthropic",
@@ -0,0 +1,205 @@
+//! Peers commands - view and edit what agents know about the people they talk to.
+
+use anyhow::Result;
+
+use super::gateway_rpc;
+use crate::ui;
+
+/// Peers command arguments.
+#[derive(Debug, Clone)]
+pub struct PeersArgs {
+    /// The peers action to perform.
+    pub action: PeersAction,
+    /// Access token for the gateway.
+    pub token: Option<String>,
+    /// Gateway port (defaults to the configured port).
+    pub port: Option<u16>,
+}
+
+/// Peers actions.
+#[derive(Debug, Clone)]
+pub enum PeersAction {
+    /// List profiles.
+    List {
+        /// Only peers on this channel.
+        channel: Option<String>,
+    },
+    /// Show one profile.
+    Show {
+        /// Channel ID.
+        channel: String,
+        /// Peer ID on the channel.
+        peer_id: String,
+    },
+    /// Set profile fields.
+    Set {
+        /// Channel ID.
+        channel: String,
+        /// Peer ID on the channel.
+        peer_id: String,
+        /// Name to call the peer.
+        name: Option<String>,
+        /// Preferred language.
+        language: Option<String>,
+        /// IANA timezone.
+        timezone: Option<String>,
+        /// Notes shown to agents.
+        notes: Option<String>,
+        /// Preference flags as `name=true|false|unset`.
+        prefs: Vec<String>,
+    },
+    /// Delete a profile.
+    Delete {
+        /// Channel ID.
+        channel: String,
+        /// Peer ID on the channel.
+        peer_id: String,
+    },
+}
+
+/// Run the peers command.
+///
+/// # Errors
+///
+/// Returns error if the gateway cannot be reached or rejects the request.
+pub async fn run_peers(args: PeersArgs) -> Result<()> {
+    let token = args.token.as_deref();
+
+    match args.action {
+        PeersAction::List { channel } => {
+            let result = gateway_rpc(
+                args.port,
+                token,
+                "peers.list",
+                serde_json::json!({ "channel": channel }),
+            )
+            .await?;
+            list_peers(&result);
+        }
+        PeersAction::Show { channel, peer_id } => {
+            let result = gateway_rpc(
+                args.port,
+                token,
+                "peers.get",
+                serde_json::json!({ "channel": channel, "peer_id": peer_id }),
+            )
+            .await?;
+            show_peer(&result["profile"]);
+        }
+        PeersAction::Set {
+            channel,
+            peer_id,
+            name,
+            language,
+            timezone,
+            notes,
+            prefs,
+        } => {
+            let mut params = serde_json::json!({
+                "channel": channel,
+                "peer_id": peer_id,
+                "display_name": name,
+                "language": language,
+                "timezone": timezone,
+                "notes": notes,
+            });
+            params["preferences"] = preference_params(&prefs)?;
+            let result = gateway_rpc(args.port, token, "peers.update", params).await?;
+            let peer = format!("{channel}:{peer_id}");
+            ui::success(&ui::text("cli-peers-updated", &[("peer", peer.into())]));
+            show_peer(&result["profile"]);
+        }
+        PeersAction::Delete { channel, peer_id } => {
+            let result = gateway_rpc(
+                args.port,
+                token,
+                "peers.delete",
+                serde_json::json!({ "channel": channel, "peer_id": peer_id }),
+            )
+            .await?;
+            let peer = format!("{channel}:{peer_id}");
+            if result["deleted"].as_bool().unwrap_or_default() {
+                ui::success(&ui::text("cli-peers-deleted", &[("peer", peer.into())]));
+            } else {
+                ui::info(&ui::text("cli-peers-missing", &[("peer", peer.into())]));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parse `name=true|false|unset` assignments into the update's preferences.
+fn preference_params(prefs: &[String]) -> Result<serde_json::Value> {
+    let mut preferences = serde_json::Map::new();
+    for pref in prefs {
+        let (key, value) = pref
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Expected name=true|false|unset, got '{pref}'"))?;
+        let value = match value.trim() {
+            "true" | "yes" | "on" => serde_json::Value::Bool(true),
+            "false" | "no" | "off" => serde_json::Value::Bool(false),
+            "unset" => serde_json::Value::Null,
+            other => {
+                anyhow::bail!("Preference '{key}' must be true, false or unset, got '{other}'")
+            }
+        };
+        preferences.insert(key.trim().to_string(), value);
+    }
+    Ok(serde_json::Value::Object(preferences))
+}
+
+fn list_peers(result: &serde_json::Value) {
+    let peers = result["peers"].as_array().cloned().unwrap_or_default();
+    if peers.is_empty() {
+        ui::info(&ui::text("cli-peers-none", &[]));
+        return;
+    }
+
+    println!(
+        "{:<12} {:<24} {:<20} {:<8} {:<20} UPDATED",
+        "CHANNEL", "PEER", "NAME", "LANG", "TIMEZONE"
+    );
+    println!("{}", "-".repeat(104));
+    for peer in &peers {
+        println!(
+            "{:<12} {:<24} {:<20} {:<8} {:<20} {}",
+            peer["channel"].as_str().unwrap_or_default(),
+            peer["peer_id"].as_str().unwrap_or_default(),
+            peer["display_name"].as_str().unwrap_or("-"),
+            peer["language"].as_str().unwrap_or("-"),
+            peer["timezone"].as_str().unwrap_or("-"),
+            peer["updated_at"].as_str().unwrap_or_default(),
+        );
+    }
+}
+
+fn show_peer(profile: &serde_json::Value) {
+    let sources = &profile["sources"];
+    // Mark values the agent picked up on its own
+    let field = |name: &str| {
+        profile[name].as_str().map_or_else(
+            || "-".to_string(),
+            |value| {
+                if sources[name] == "learned" {
+                    format!("{value} (learned)")
+                } else {
+                    value.to_string()
+                }
+            },
+        )
+    };
+
+    ui::kv("Channel", profile["channel"].as_str().unwrap_or_default());
+    ui::kv("Peer", profile["peer_id"].as_str().unwrap_or_default());
+    ui::kv("Name", &field("display_name"));
+    ui::kv("Language", &field("language"));
+    ui::kv("Timezone", &field("timezone"));
+    if let Some(preferences) = profile["preferences"].as_object() {
+        for (name, value) in preferences {
+            ui::kv(&format!("Pref {name}"), &value.to_string());
+        }
+    }
+    if let Some(notes) = profile["notes"].as_str() {
+        ui::kv("Notes", notes);
+    }
+}
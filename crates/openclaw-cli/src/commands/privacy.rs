@@ -0,0 +1,99 @@
+//! Privacy commands - erase what the gateway stores about a person.
+
+use anyhow::Result;
+
+use super::gateway_rpc;
+use crate::ui;
+
+/// Privacy command arguments.
+#[derive(Debug, Clone)]
+pub struct PrivacyArgs {
+    /// The privacy action to perform.
+    pub action: PrivacyAction,
+    /// Access token for the gateway.
+    pub token: Option<String>,
+    /// Gateway port (defaults to the configured port).
+    pub port: Option<u16>,
+}
+
+/// Privacy actions.
+#[derive(Debug, Clone)]
+pub enum PrivacyAction {
+    /// Erase a peer's sessions, profile, uploads, and workspaces.
+    Forget {
+        /// Channel ID.
+        channel: String,
+        /// Peer ID on the channel.
+        peer_id: String,
+        /// Skip the confirmation prompt.
+        yes: bool,
+    },
+}
+
+/// Run the privacy command.
+///
+/// # Errors
+///
+/// Returns error if the gateway cannot be reached or rejects the request.
+pub async fn run_privacy(args: PrivacyArgs) -> Result<()> {
+    let token = args.token.as_deref();
+
+    match args.action {
+        PrivacyAction::Forget {
+            channel,
+            peer_id,
+            yes,
+        } => {
+            let peer = format!("{channel}:{peer_id}");
+            if !yes {
+                ui::warning(&ui::text(
+                    "cli-privacy-forget-warning",
+                    &[("peer", peer.clone().into())],
+                ));
+                if !ui::prompts::confirm("Are you sure you want to continue?")? {
+                    return Ok(());
+                }
+            }
+
+            let result = gateway_rpc(
+                args.port,
+                token,
+                "privacy.forget",
+                serde_json::json!({ "channel": channel, "peer_id": peer_id }),
+            )
+            .await?;
+            ui::success(&ui::text("cli-privacy-forgotten", &[("peer", peer.into())]));
+            show_report(&result);
+        }
+    }
+    Ok(())
+}
+
+fn show_report(report: &serde_json::Value) {
+    let count = |name: &str| report[name].as_u64().unwrap_or_default().to_string();
+    let sessions = report["sessions"].as_array().cloned().unwrap_or_default();
+    let archived = sessions
+        .iter()
+        .filter(|s| s["archived"].as_bool().unwrap_or_default())
+        .count();
+
+    ui::kv(
+        "Sessions",
+        &format!("{} ({archived} archived)", sessions.len()),
+    );
+    ui::kv("Events", &count("events"));
+    ui::kv("Group messages", &count("redacted"));
+    ui::kv("Uploads", &count("uploads"));
+    ui::kv("Workspaces", &count("workspaces"));
+    ui::kv("Wire log entries", &count("wire_log_entries"));
+    ui::kv("Recordings", &count("recordings"));
+    ui::kv(
+        "Profile",
+        if report["profile"].as_bool().unwrap_or_default() {
+            "deleted"
+        } else {
+            "none"
+        },
+    );
+    ui::kv("Audit ID", report["peer_hash"].as_str().unwrap_or_default());
+}
@@ -0,0 +1,132 @@
+//! Prompts commands - list and test system prompt templates.
+
+use anyhow::Result;
+
+use openclaw_agents::prompts::{PromptLibrary, PromptVariables, TEMPLATE_PREFIX, template_name};
+use openclaw_core::Config;
+
+use crate::ui;
+
+/// Prompts command arguments.
+#[derive(Debug, Clone)]
+pub enum PromptsAction {
+    /// List installed templates.
+    List,
+    /// Render a template with sample variables.
+    Test {
+        /// Template name.
+        name: String,
+        /// Variable overrides as `key=value`.
+        vars: Vec<String>,
+    },
+}
+
+/// Run the prompts command.
+///
+/// # Errors
+///
+/// Returns error if the config cannot be loaded, the template is not
+/// installed, or it fails to render.
+pub fn run_prompts(action: PromptsAction) -> Result<()> {
+    match action {
+        PromptsAction::List => {
+            list_prompts(&Config::load_default()?);
+            Ok(())
+        }
+        PromptsAction::Test { name, vars } => test_prompt(&name, &vars),
+    }
+}
+
+fn list_prompts(config: &Config) {
+    let library = PromptLibrary::load_default();
+
+    for (path, error) in library.errors() {
+        ui::warning(&format!("Skipped {}: {error}", path.display()));
+    }
+    if library.iter().next().is_none() {
+        ui::info("No prompt templates installed.");
+        ui::info(&format!(
+            "Add templates as {}/<name>.md and set an agent's systemPrompt to \"{TEMPLATE_PREFIX}<name>\"",
+            Config::prompts_dir().display()
+        ));
+        return;
+    }
+
+    println!("{:<20} {:<24} DESCRIPTION", "NAME", "USED BY");
+    println!("{}", "-".repeat(72));
+    for template in library.iter() {
+        let mut used_by: Vec<&str> = config
+            .agents
+            .iter()
+            .filter(|(_, a)| {
+                a.system_prompt.as_deref().and_then(template_name) == Some(template.name.as_str())
+            })
+            .map(|(id, _)| id.as_str())
+            .collect();
+        used_by.sort_unstable();
+
+        println!(
+            "{:<20} {:<24} {}",
+            template.name,
+            if used_by.is_empty() {
+                "-".to_string()
+            } else {
+                used_by.join(", ")
+            },
+            template.description.as_deref().unwrap_or("")
+        );
+    }
+
+    // Agents that refer to templates that are not installed
+    let references = config.agents.iter().filter_map(|(id, agent)| {
+        Some((id, agent.system_prompt.as_deref().and_then(template_name)?))
+    });
+    for (id, name) in references {
+        if library.get(name).is_none() {
+            ui::warning(&format!(
+                "Agent '{id}' uses template '{name}', which is not installed"
+            ));
+        }
+    }
+}
+
+fn test_prompt(name: &str, vars: &[String]) -> Result<()> {
+    let library = PromptLibrary::load_default();
+    let Some(template) = library.get(name) else {
+        if let Some((_, error)) = library
+            .errors()
+            .iter()
+            .find(|(path, _)| path.file_stem().is_some_and(|stem| stem == name))
+        {
+            anyhow::bail!("{error}");
+        }
+        anyhow::bail!(
+            "Prompt template '{name}' not found in {}",
+            Config::prompts_dir().display()
+        );
+    };
+
+    let mut variables = PromptVariables::preview();
+    for var in vars {
+        let (key, value) = var
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Expected key=value, got '{var}'"))?;
+        variables.set(key.trim(), serde_json::Value::String(value.to_string()));
+    }
+
+    let rendered = template.render(&variables)?;
+
+    ui::kv("Template", &template.path.display().to_string());
+    for missing in template
+        .variables()
+        .iter()
+        .filter(|v| !variables.contains(v))
+    {
+        ui::warning(&format!(
+            "Variable '{missing}' is not built in and renders empty unless the embedder sets it (try --var {missing}=...)"
+        ));
+    }
+    println!();
+    println!("{rendered}");
+    Ok(())
+}
@@ -0,0 +1,123 @@
+//! Schedule commands - inspect and trigger scheduled tasks.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::Utc;
+
+use openclaw_agents::scheduler::{RunStatus, ScheduleStateStore, Scheduler};
+
+use crate::ui;
+
+/// Schedule command arguments.
+#[derive(Debug, Clone)]
+pub struct ScheduleArgs {
+    /// Subcommand.
+    pub action: ScheduleAction,
+    /// Gateway data directory override.
+    pub data_dir: Option<PathBuf>,
+}
+
+/// Schedule actions.
+#[derive(Debug, Clone)]
+pub enum ScheduleAction {
+    /// List configured schedules.
+    List,
+    /// Run a schedule on the gateway's next tick.
+    RunNow {
+        /// Schedule ID.
+        id: String,
+    },
+}
+
+/// Run the schedule command.
+///
+/// # Errors
+///
+/// Returns error if the config is invalid or schedule state cannot be accessed.
+pub async fn run_schedule(args: ScheduleArgs) -> Result<()> {
+    let config = openclaw_core::Config::load_default()?;
+    let data_dir = args
+        .data_dir
+        .unwrap_or_else(|| openclaw_gateway::GatewayConfig::default().data_dir);
+    let store = ScheduleStateStore::open(&data_dir.join("schedules"))?;
+    let scheduler = Scheduler::new(&config.schedules, store.clone())?;
+
+    match args.action {
+        ScheduleAction::List => {
+            list_schedules(&scheduler);
+            Ok(())
+        }
+        ScheduleAction::RunNow { id } => {
+            run_now(&scheduler, &store, &id, config.gateway.port).await
+        }
+    }
+}
+
+fn list_schedules(scheduler: &Scheduler) {
+    let tasks = scheduler.tasks();
+    if tasks.is_empty() {
+        ui::info("No schedules configured.");
+        ui::info("Add entries under 'schedules' in your config file.");
+        return;
+    }
+
+    let now = Utc::now();
+    ui::info(&format!("Schedules ({}):", tasks.len()));
+    println!();
+    println!(
+        "{:<20} {:<16} {:<18} {:<18} {:<8}",
+        "ID", "CRON", "NEXT RUN", "LAST RUN", "STATUS"
+    );
+    println!("{}", "-".repeat(84));
+
+    for task in tasks {
+        let next = scheduler.next_run(&task.id, now).map_or_else(
+            || "disabled".to_string(),
+            |t| t.format("%Y-%m-%d %H:%M").to_string(),
+        );
+        let state = scheduler.state(&task.id);
+        let last = state.and_then(|s| s.last_run).map_or_else(
+            || "never".to_string(),
+            |t| t.format("%Y-%m-%d %H:%M").to_string(),
+        );
+        let status = match state.and_then(|s| s.last_status) {
+            Some(RunStatus::Success) => "ok",
+            Some(RunStatus::Failed) => "failed",
+            Some(RunStatus::Skipped) => "skipped",
+            None => "-",
+        };
+
+        println!(
+            "{:<20} {:<16} {:<18} {:<18} {:<8}",
+            task.id, task.config.cron, next, last, status
+        );
+        if let Some(error) = state.and_then(|s| s.last_error.as_deref()) {
+            println!("  last error: {error}");
+        }
+    }
+}
+
+async fn run_now(
+    scheduler: &Scheduler,
+    store: &ScheduleStateStore,
+    id: &str,
+    port: u16,
+) -> Result<()> {
+    if !scheduler.tasks().iter().any(|t| t.id == id) {
+        anyhow::bail!("Schedule not found: {id}");
+    }
+
+    store.request_run(id)?;
+    ui::success(&format!("Queued '{id}' to run on the gateway's next tick"));
+
+    if tokio::net::TcpStream::connect(format!("127.0.0.1:{port}"))
+        .await
+        .is_err()
+    {
+        ui::warning(&format!("Gateway is not running on port {port}"));
+        ui::info("The run will start once the gateway is up: openclaw gateway run");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,454 @@
+//! Selftest command - end-to-end verification of an install.
+//!
+//! Spins up a throwaway gateway on a random loopback port with the mock
+//! channel, a provider replaying bundled recordings, and an echo tool, then
+//! drives a short conversation through the channel over RPC - a message
+//! with an attachment and a tool call - and checks the resulting delivery,
+//! events, and stats.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use serde_json::json;
+
+use openclaw_agents::MockProvider;
+use openclaw_agents::runtime::AgentRuntime;
+use openclaw_agents::tools::{Tool, ToolError, ToolRegistry, ToolResult};
+use openclaw_channels::mock::MockChannel;
+use openclaw_core::config::RecordingMode;
+use openclaw_core::events::EventStore;
+use openclaw_gateway::{AuthConfig, AuthState, GatewayBuilder, GatewayConfig, User, UserRole};
+use openclaw_providers::recording::Recording;
+use openclaw_providers::traits::CompletionRequest;
+use openclaw_providers::{Cassette, RecordingProvider};
+
+use crate::ui;
+
+/// Username of the throwaway admin account.
+const SELFTEST_USER: &str = "selftest";
+
+/// Mock channel peer the conversation is held with.
+const SELFTEST_PEER: &str = "selftest-peer";
+
+/// Recorded provider calls the conversation is answered from: `ping` is
+/// echoed back, and `echo pong` is answered with a `selftest_echo` call.
+const RECORDINGS: &[&str] = &[
+    include_str!("../../recordings/selftest/ping.json"),
+    include_str!("../../recordings/selftest/echo.json"),
+];
+
+/// Selftest command arguments.
+#[derive(Debug, Clone)]
+pub struct SelftestArgs {
+    /// Keep the temporary data directory after the run.
+    pub keep: bool,
+    /// Seconds to wait for the gateway to come up.
+    pub timeout_secs: u64,
+}
+
+impl Default for SelftestArgs {
+    fn default() -> Self {
+        Self {
+            keep: false,
+            timeout_secs: 10,
+        }
+    }
+}
+
+/// Run the end-to-end self-test.
+pub async fn run_selftest(args: SelftestArgs) -> Result<()> {
+    ui::header("OpenClaw Selftest");
+    println!();
+
+    let temp = tempfile::Builder::new()
+        .prefix("openclaw-selftest-")
+        .tempdir()
+        .context("Failed to create temp directory")?;
+    let data_dir = temp.path().to_path_buf();
+
+    let result = run_steps(&data_dir, args.timeout_secs).await;
+
+    if args.keep {
+        let kept = temp.keep();
+        ui::info(&format!("Data kept at {}", kept.display()));
+    }
+
+    println!();
+    match result {
+        Ok(()) => {
+            ui::success("Selftest passed");
+            Ok(())
+        }
+        Err(e) => {
+            ui::error(&format!("Selftest failed: {e:#}"));
+            Err(e)
+        }
+    }
+}
+
+async fn run_steps(data_dir: &Path, timeout_secs: u64) -> Result<()> {
+    const TOTAL: usize = 7;
+
+    // Step 1: Start gateway
+    ui::step(1, TOTAL, "Starting gateway on a random port");
+    let port = free_port()?;
+    let password = random_password();
+    let handle = start_gateway(data_dir, port, &password)?;
+    let client = RpcClient::new(port);
+    client
+        .wait_ready(Duration::from_secs(timeout_secs))
+        .await
+        .context("Gateway did not become healthy")?;
+    ui::success(&format!("Gateway listening on 127.0.0.1:{port}"));
+
+    let outcome = async {
+        // Step 2: Authenticate
+        ui::step(2, TOTAL, "Authenticating");
+        let login = client
+            .call(
+                "auth.login",
+                json!({ "username": SELFTEST_USER, "password": password }),
+            )
+            .await?;
+        let token = login["token"]
+            .as_str()
+            .context("Login returned no token")?
+            .to_string();
+        let client = client.with_token(token);
+        ui::success("Login succeeded");
+
+        // Step 3: Inbound message with an attachment
+        ui::step(3, TOTAL, "Sending message with attachment through agent");
+        let reply = client
+            .call(
+                "mock.inject",
+                json!({
+                    "peer_id": SELFTEST_PEER,
+                    "text": "ping",
+                    "attachments": [{
+                        "kind": "document",
+                        "url": "data:text/plain;base64,c2VsZnRlc3Q=",
+                        "mime_type": "text/plain",
+                        "filename": "notes.txt",
+                        "size": 8,
+                    }],
+                }),
+            )
+            .await?;
+        let response = reply["response"].as_str().unwrap_or_default();
+        if response != "selftest: ping" {
+            bail!("Unexpected agent response: {response:?}");
+        }
+        let session_key = reply["session_key"]
+            .as_str()
+            .context("mock.inject returned no session_key")?
+            .to_string();
+        ui::success(&format!("Agent responded in {session_key}"));
+
+        // Step 4: Outbound delivery
+        ui::step(4, TOTAL, "Verifying delivery");
+        let outbound = client
+            .call("mock.outbound", json!({ "clear": true }))
+            .await?;
+        let delivered = outbound["messages"].as_array().cloned().unwrap_or_default();
+        let [sent] = delivered.as_slice() else {
+            bail!("Expected one delivery, got {}", delivered.len());
+        };
+        if sent["chatId"] != SELFTEST_PEER
+            || sent["replyTo"] != reply["message_id"]
+            || sent["text"] != response
+        {
+            bail!("Unexpected delivery: {sent}");
+        }
+        ui::success("Reply delivered to the channel");
+
+        // Step 5: Tool call, planned by the agent and run by the caller
+        ui::step(5, TOTAL, "Running the agent's tool call");
+        run_tool_call(&client, &session_key).await?;
+        ui::success("Tool call executed");
+
+        // Step 6: Events
+        ui::step(6, TOTAL, "Verifying events");
+        let count = check_events(&client, &session_key).await?;
+        ui::success(&format!("{count} events recorded"));
+
+        // Step 7: Stats
+        ui::step(7, TOTAL, "Verifying stats");
+        let stats = client.call("session.stats", json!({})).await?;
+        if stats["total"].as_u64().unwrap_or(0) < 1 {
+            bail!("Stats report no sessions: {stats}");
+        }
+        ui::success("Stats report the session");
+
+        Ok(())
+    }
+    .await;
+
+    handle.abort();
+    outcome
+}
+
+/// Have the agent plan a `selftest_echo` call, then run it as the caller.
+async fn run_tool_call(client: &RpcClient, session_key: &str) -> Result<()> {
+    let set_dry_run = |mode: serde_json::Value| {
+        client.call(
+            "session.dry_run",
+            json!({ "session_key": session_key, "mode": mode }),
+        )
+    };
+    set_dry_run(json!("plan")).await?;
+    let plan = client
+        .call(
+            "mock.inject",
+            json!({ "peer_id": SELFTEST_PEER, "text": "echo pong" }),
+        )
+        .await?;
+    set_dry_run(serde_json::Value::Null).await?;
+    let call = &plan["planned_tools"][0];
+    if call["name"] != "selftest_echo" {
+        bail!("Agent did not call the echo tool: {plan}");
+    }
+    let tool = client
+        .call(
+            "tools.execute",
+            json!({
+                "tool_name": call["name"],
+                "params": call["input"],
+                "session_key": session_key,
+            }),
+        )
+        .await?;
+    if tool["success"].as_bool() != Some(true) || tool["content"] != "pong" {
+        bail!("Unexpected tool result: {tool}");
+    }
+    Ok(())
+}
+
+/// Check the session log, returning how many events it holds.
+async fn check_events(client: &RpcClient, session_key: &str) -> Result<usize> {
+    let events = client
+        .call("session.events", json!({ "session_key": session_key }))
+        .await?;
+    let events = events["events"].as_array().cloned().unwrap_or_default();
+    let kinds: Vec<&str> = events
+        .iter()
+        .filter_map(|e| e["kind"]["type"].as_str())
+        .collect();
+    for expected in ["session_started", "message_received", "agent_response"] {
+        if !kinds.contains(&expected) {
+            bail!("Missing {expected} event (got {kinds:?})");
+        }
+    }
+    let attached = events.iter().any(|e| {
+        e["kind"]["type"] == "message_received"
+            && e["kind"]["attachments"][0]["mime_type"] == "text/plain"
+    });
+    if !attached {
+        bail!("Attachment was not recorded on the inbound message");
+    }
+    Ok(kinds.len())
+}
+
+/// Build and spawn a gateway backed by `data_dir`.
+fn start_gateway(
+    data_dir: &Path,
+    port: u16,
+    password: &str,
+) -> Result<tokio::task::JoinHandle<()>> {
+    let auth = AuthState::initialize(AuthConfig::default(), data_dir)
+        .map_err(|e| anyhow::anyhow!("Auth init failed: {e}"))?;
+    let user = User::new(SELFTEST_USER, password, UserRole::Admin)
+        .map_err(|e| anyhow::anyhow!("User creation failed: {e}"))?;
+    auth.users
+        .create(&user)
+        .map_err(|e| anyhow::anyhow!("User creation failed: {e}"))?;
+
+    let event_store = Arc::new(
+        EventStore::open(&data_dir.join("events"))
+            .map_err(|e| anyhow::anyhow!("Event store open failed: {e}"))?,
+    );
+
+    let mut tools = ToolRegistry::new();
+    tools.register(Arc::new(EchoTool));
+    let tools = Arc::new(tools);
+
+    // Replay mode never reaches the inner provider: a request without a
+    // recording fails the run
+    let provider = RecordingProvider::new(
+        Arc::new(MockProvider::default()),
+        selftest_cassette(&data_dir.join("recordings"))?,
+    );
+    let runtime = AgentRuntime::new(Arc::new(provider))
+        .with_model("selftest")
+        .with_tools(tools.clone());
+
+    let config = GatewayConfig {
+        port,
+        bind_address: "127.0.0.1".to_string(),
+        data_dir: data_dir.to_path_buf(),
+        ..GatewayConfig::default()
+    };
+
+    let gateway = GatewayBuilder::new()
+        .with_config(config)
+        .with_event_store(event_store)
+        .with_auth_state(Arc::new(auth))
+        .with_tool_registry(tools)
+        .with_agent("default", Arc::new(runtime))
+        .with_mock_channel(Arc::new(MockChannel::new()))
+        .build()?;
+
+    Ok(tokio::spawn(async move {
+        if let Err(e) = gateway.run().await {
+            tracing::error!("Selftest gateway exited: {}", e);
+        }
+    }))
+}
+
+/// Write the bundled recordings to a replay-only cassette in `dir`.
+///
+/// Recordings are stored under the key of their request as this build
+/// computes it.
+fn selftest_cassette(dir: &Path) -> Result<Cassette> {
+    let cassette = Cassette::new(dir, RecordingMode::Replay);
+    for raw in RECORDINGS {
+        let recording: Recording =
+            serde_json::from_str(raw).context("Bundled recording is invalid")?;
+        let request: CompletionRequest = serde_json::from_value(recording.request)
+            .context("Bundled recording has an invalid request")?;
+        let (request, key) = Cassette::key(&request)?;
+        cassette.save(&key, &recording.provider, request, &recording.response)?;
+    }
+    Ok(cassette)
+}
+
+/// Find a free loopback port.
+fn free_port() -> Result<u16> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    Ok(listener.local_addr()?.port())
+}
+
+fn random_password() -> String {
+    use rand::Rng;
+    use rand::distributions::Alphanumeric;
+
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(24)
+        .map(char::from)
+        .collect()
+}
+
+/// Minimal JSON-RPC client for the selftest.
+struct RpcClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl RpcClient {
+    fn new(port: u16) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: format!("http://127.0.0.1:{port}"),
+            token: None,
+        }
+    }
+
+    fn with_token(&self, token: String) -> Self {
+        Self {
+            http: self.http.clone(),
+            base_url: self.base_url.clone(),
+            token: Some(token),
+        }
+    }
+
+    async fn wait_ready(&self, timeout: Duration) -> Result<()> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let ready = self
+                .http
+                .get(format!("{}/health", self.base_url))
+                .send()
+                .await
+                .is_ok_and(|r| r.status().is_success());
+            if ready {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                bail!("Timed out after {}s", timeout.as_secs());
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    async fn call(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value> {
+        let mut request = self
+            .http
+            .post(format!("{}/rpc", self.base_url))
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": "selftest",
+                "method": method,
+                "params": params,
+            }));
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response: serde_json::Value = request
+            .send()
+            .await
+            .with_context(|| format!("{method} request failed"))?
+            .json()
+            .await
+            .with_context(|| format!("{method} returned invalid JSON"))?;
+
+        if let Some(error) = response.get("error").filter(|e| !e.is_null()) {
+            bail!("{method} failed: {}", error["message"]);
+        }
+        Ok(response["result"].clone())
+    }
+}
+
+/// Tool that returns its `text` parameter.
+struct EchoTool;
+
+#[async_trait]
+impl Tool for EchoTool {
+    fn name(&self) -> &'static str {
+        "selftest_echo"
+    }
+
+    fn description(&self) -> &'static str {
+        "Echo the given text"
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": { "text": { "type": "string" } },
+            "required": ["text"]
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value) -> Result<ToolResult, ToolError> {
+        let text = params["text"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParams("Missing text".to_string()))?;
+        Ok(ToolResult::success(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_selftest_passes() {
+        run_selftest(SelftestArgs::default()).await.unwrap();
+    }
+}
@@ -0,0 +1,590 @@
+//! Session commands - list, soft-delete, restore, purge, archive, and
+//! encrypt sessions, migrate and compact stored events, move the store to
+//! `SQLite`, toggle dry-run mode, export transcripts, and fork and replay
+//! sessions.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::Utc;
+
+use openclaw_core::Config;
+use openclaw_core::config::{EventStoreBackend, EventStoreFormat};
+use openclaw_core::events::{
+    DryRunMode, EVENT_SCHEMA_VERSION, EventStore, SessionEvent, SessionEventKind, Transcript,
+    TranscriptFormat, fork_key,
+};
+use openclaw_core::types::SessionKey;
+
+use super::backup::blob_store;
+use super::configure::load_master_key;
+use super::gateway_rpc;
+use crate::ui;
+
+/// Purpose the event store key is derived for from the master key.
+pub const EVENT_STORE_KEY_PURPOSE: &str = "event-store";
+
+/// Session command arguments.
+#[derive(Debug, Clone)]
+pub struct SessionsArgs {
+    /// Subcommand.
+    pub action: SessionsAction,
+    /// Gateway data directory override.
+    pub data_dir: Option<PathBuf>,
+}
+
+/// Session actions.
+#[derive(Debug, Clone)]
+pub enum SessionsAction {
+    /// List sessions.
+    List {
+        /// Show only deleted sessions.
+        deleted: bool,
+        /// Show only archived sessions.
+        archived: bool,
+    },
+    /// Soft-delete a session.
+    Delete {
+        /// Session key.
+        key: String,
+        /// Optional reason recorded with the deletion.
+        reason: Option<String>,
+    },
+    /// Restore a soft-deleted session.
+    Restore {
+        /// Session key.
+        key: String,
+    },
+    /// Permanently remove a deleted session.
+    Purge {
+        /// Session key.
+        key: String,
+    },
+    /// Purge every deleted session past its retention period.
+    PurgeExpired,
+    /// Move ended sessions to compressed cold storage.
+    Archive {
+        /// Archive only this session.
+        key: Option<String>,
+        /// Archive sessions ended more than this many days ago (defaults to
+        /// `settings.archiveEndedAfterDays`).
+        older_than_days: Option<u32>,
+    },
+    /// Encrypt an existing plaintext event store.
+    Encrypt,
+    /// Rewrite stored events in the current schema version.
+    Migrate,
+    /// Rewrite stored events in the configured storage format.
+    Compact,
+    /// Copy the sled event store into a `SQLite` file.
+    MigrateStorage,
+    /// Turn dry-run mode on or off for a session.
+    DryRun {
+        /// Session key.
+        key: String,
+        /// `simulate` or `plan`; `None` turns dry run off.
+        mode: Option<String>,
+    },
+    /// Render a session transcript.
+    Export {
+        /// Session key.
+        key: String,
+        /// `md` or `html`.
+        format: String,
+        /// Output path; stdout if `None`.
+        output: Option<PathBuf>,
+    },
+    /// Copy a session up to an event into a new session.
+    Fork {
+        /// Session key.
+        key: String,
+        /// Last event to copy (ULID).
+        at: String,
+        /// Suffix for the new session key; a timestamp if `None`.
+        suffix: Option<String>,
+    },
+    /// Re-run a session's turns on the gateway with other settings and
+    /// diff the replies.
+    Replay {
+        /// Session key.
+        key: String,
+        /// `agent.update`-style overrides: model, temperature, max tokens,
+        /// system prompt, and optionally `agent_id`.
+        overrides: serde_json::Value,
+        /// Access token.
+        token: Option<String>,
+        /// Gateway port override.
+        port: Option<u16>,
+    },
+}
+
+/// Run the sessions command.
+///
+/// The event store is opened directly, so the gateway must be stopped,
+/// except for replay, which runs on the gateway.
+///
+/// # Errors
+///
+/// Returns error if the event store cannot be opened or the session is
+/// missing or in the wrong state.
+pub async fn run_sessions(args: SessionsArgs) -> Result<()> {
+    if let SessionsAction::Replay {
+        key,
+        overrides,
+        token,
+        port,
+    } = &args.action
+    {
+        return replay_session(key, overrides.clone(), token.as_deref(), *port).await;
+    }
+
+    let data_dir = args
+        .data_dir
+        .unwrap_or_else(|| openclaw_gateway::GatewayConfig::default().data_dir);
+    let path = data_dir.join("events");
+
+    if matches!(args.action, SessionsAction::Encrypt) {
+        return encrypt_store(&path);
+    }
+    if matches!(args.action, SessionsAction::MigrateStorage) {
+        return migrate_storage(&path);
+    }
+
+    let config = Config::load_default()?;
+    let settings = &config.settings;
+    let key = if settings.encrypt_event_store {
+        Some(load_master_key()?.derive(EVENT_STORE_KEY_PURPOSE))
+    } else {
+        None
+    };
+    let store = EventStore::open_with(&path, settings.event_store_backend, key)
+        .map_err(|e| anyhow::anyhow!("Failed to open event store ({e}); is the gateway running?"))?
+        .with_archive_dir(openclaw_gateway::GatewayConfig::default().archive_dir)
+        .with_archive_blobs(blob_store(&config)?.blocking()?, "sessions")
+        .with_format(settings.event_store_format);
+
+    match args.action {
+        SessionsAction::List { archived: true, .. } => list_archived(&store),
+        SessionsAction::List { deleted, .. } => list_sessions(&store, deleted),
+        SessionsAction::Delete { key, reason } => delete_session(&store, &key, reason),
+        SessionsAction::Restore { key } => restore_session(&store, &key),
+        SessionsAction::Purge { key } => purge_session(&store, &key),
+        SessionsAction::PurgeExpired => purge_expired(&store),
+        SessionsAction::Archive {
+            key,
+            older_than_days,
+        } => archive_sessions(&store, key.as_deref(), older_than_days),
+        SessionsAction::Migrate => migrate_events(&store),
+        SessionsAction::Compact => compact_events(&store),
+        SessionsAction::DryRun { key, mode } => set_dry_run(&store, &key, mode.as_deref()),
+        SessionsAction::Export {
+            key,
+            format,
+            output,
+        } => export_session(&store, &key, &format, output),
+        SessionsAction::Fork { key, at, suffix } => fork_session(&store, &key, &at, suffix),
+        SessionsAction::Encrypt | SessionsAction::MigrateStorage => {
+            unreachable!("handled before opening the store")
+        }
+        SessionsAction::Replay { .. } => unreachable!("handled above"),
+    }
+}
+
+fn list_sessions(store: &EventStore, deleted: bool) -> Result<()> {
+    let mut sessions = Vec::new();
+    for key in store.list_sessions()? {
+        let projection = store.get_projection(&key)?;
+        if projection.is_deleted() == deleted {
+            sessions.push((key, projection));
+        }
+    }
+
+    if sessions.is_empty() {
+        ui::info(if deleted {
+            "No deleted sessions."
+        } else {
+            "No sessions."
+        });
+        return Ok(());
+    }
+
+    ui::info(&format!("Sessions ({}):", sessions.len()));
+    println!();
+    println!(
+        "{:<36} {:<12} {:<10} {:<18}",
+        "KEY",
+        "AGENT",
+        "MESSAGES",
+        if deleted { "DELETED" } else { "LAST ACTIVE" }
+    );
+    println!("{}", "-".repeat(78));
+
+    for (key, projection) in sessions {
+        let when = if deleted {
+            projection.deleted_at
+        } else {
+            Some(projection.last_activity)
+        };
+        println!(
+            "{:<36} {:<12} {:<10} {:<18}",
+            key.as_ref(),
+            projection.agent_id,
+            projection.message_count,
+            when.map_or_else(String::new, |t| t.format("%Y-%m-%d %H:%M").to_string())
+        );
+    }
+
+    Ok(())
+}
+
+fn list_archived(store: &EventStore) -> Result<()> {
+    let archived = store.list_archived()?;
+    if archived.is_empty() {
+        ui::info("No archived sessions.");
+        return Ok(());
+    }
+
+    ui::info(&format!("Archived sessions ({}):", archived.len()));
+    println!();
+    println!(
+        "{:<36} {:<12} {:<10} {:<18}",
+        "KEY", "AGENT", "EVENTS", "LAST ACTIVE"
+    );
+    println!("{}", "-".repeat(78));
+
+    for session in archived {
+        println!(
+            "{:<36} {:<12} {:<10} {:<18}",
+            session.session_key.as_ref(),
+            session.agent_id,
+            session.events,
+            session.last_activity.format("%Y-%m-%d %H:%M")
+        );
+    }
+
+    println!();
+    ui::info("Archived sessions are restored automatically when accessed.");
+    Ok(())
+}
+
+fn migrate_events(store: &EventStore) -> Result<()> {
+    let report = store.migrate()?;
+    if report.events_migrated == 0 {
+        ui::info(&format!(
+            "All {} event(s) already use schema version {EVENT_SCHEMA_VERSION}.",
+            report.events_checked
+        ));
+    } else {
+        ui::success(&format!(
+            "Migrated {} of {} event(s) from schema version {} to {EVENT_SCHEMA_VERSION}",
+            report.events_migrated, report.events_checked, report.from_version
+        ));
+    }
+    Ok(())
+}
+
+fn compact_events(store: &EventStore) -> Result<()> {
+    let report = store.compact()?;
+    let format = match report.format {
+        EventStoreFormat::Json => "JSON",
+        EventStoreFormat::Binary => "binary",
+    };
+    if report.events_converted == 0 {
+        ui::info(&format!(
+            "All {} event(s) already use the {format} format.",
+            report.events_checked
+        ));
+        return Ok(());
+    }
+    ui::success(&format!(
+        "Converted {} of {} event(s) to the {format} format",
+        report.events_converted, report.events_checked
+    ));
+    ui::kv(
+        "Size",
+        &format!(
+            "{} KiB -> {} KiB",
+            report.bytes_before / 1024,
+            report.bytes_after / 1024
+        ),
+    );
+    Ok(())
+}
+
+fn load(store: &EventStore, key: &str) -> Result<(SessionKey, String, bool)> {
+    let session_key = SessionKey::new(key);
+    let projection = store
+        .get_projection(&session_key)
+        .map_err(|_| anyhow::anyhow!("Session not found: {key}"))?;
+    let deleted = projection.is_deleted();
+    Ok((session_key, projection.agent_id, deleted))
+}
+
+fn delete_session(store: &EventStore, key: &str, reason: Option<String>) -> Result<()> {
+    let (session_key, agent_id, deleted) = load(store, key)?;
+    if deleted {
+        ui::info(&format!("Session '{key}' is already deleted"));
+        return Ok(());
+    }
+
+    store.append(&SessionEvent::new(
+        session_key,
+        agent_id,
+        SessionEventKind::SessionDeleted { reason },
+    ))?;
+    store.flush()?;
+    ui::success(&format!("Deleted session '{key}'"));
+    ui::info("Restore it with: openclaw sessions restore <key>");
+    Ok(())
+}
+
+fn restore_session(store: &EventStore, key: &str) -> Result<()> {
+    let (session_key, agent_id, deleted) = load(store, key)?;
+    if !deleted {
+        anyhow::bail!("Session '{key}' is not deleted");
+    }
+
+    store.append(&SessionEvent::new(
+        session_key,
+        agent_id,
+        SessionEventKind::SessionRestored,
+    ))?;
+    store.flush()?;
+    ui::success(&format!("Restored session '{key}'"));
+    Ok(())
+}
+
+fn set_dry_run(store: &EventStore, key: &str, mode: Option<&str>) -> Result<()> {
+    let mode = match mode {
+        None => None,
+        Some("simulate") => Some(DryRunMode::Simulate),
+        Some("plan") => Some(DryRunMode::Plan),
+        Some(other) => anyhow::bail!("Unknown dry-run mode '{other}' (use simulate or plan)"),
+    };
+    let (session_key, agent_id, _) = load(store, key)?;
+
+    store.append(&SessionEvent::new(
+        session_key,
+        agent_id,
+        SessionEventKind::DryRunChanged { mode },
+    ))?;
+    store.flush()?;
+    match mode {
+        Some(DryRunMode::Simulate) => ui::success(&format!(
+            "Session '{key}' now simulates tool calls instead of running them"
+        )),
+        Some(DryRunMode::Plan) => ui::success(&format!(
+            "Session '{key}' now stops at tool calls and returns a plan"
+        )),
+        None => ui::success(&format!("Dry run turned off for session '{key}'")),
+    }
+    Ok(())
+}
+
+fn fork_session(store: &EventStore, key: &str, at: &str, suffix: Option<String>) -> Result<()> {
+    let at: ulid::Ulid = at
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid event ID '{at}': {e}"))?;
+    let suffix = suffix.unwrap_or_else(|| Utc::now().format("%Y%m%d%H%M%S").to_string());
+    if suffix.is_empty()
+        || !suffix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        anyhow::bail!("Suffix may only contain letters, digits, '-' and '_'");
+    }
+    let (session_key, _, _) = load(store, key)?;
+
+    let target = fork_key(&session_key, &suffix);
+    let copied = store.fork_session(&session_key, at, &target)?;
+    store.flush()?;
+    ui::success(&format!("Forked session '{key}' into '{target}'"));
+    ui::kv("Events copied", &copied.to_string());
+    Ok(())
+}
+
+async fn replay_session(
+    key: &str,
+    mut params: serde_json::Value,
+    token: Option<&str>,
+    port: Option<u16>,
+) -> Result<()> {
+    params["session_key"] = serde_json::json!(key);
+    let report = gateway_rpc(port, token, "session.replay", params).await?;
+
+    let turns = report["turns"].as_array().cloned().unwrap_or_default();
+    if turns.is_empty() {
+        ui::info(&format!("Session '{key}' has no messages to replay"));
+        return Ok(());
+    }
+    for (n, turn) in turns.iter().enumerate() {
+        println!();
+        ui::header(&format!(
+            "Turn {} {}",
+            n + 1,
+            if turn["changed"].as_bool() == Some(true) {
+                "(changed)"
+            } else {
+                "(same)"
+            }
+        ));
+        for line in turn["input"].as_str().unwrap_or_default().lines() {
+            println!("> {line}");
+        }
+        for line in turn["diff"].as_array().into_iter().flatten() {
+            let text = line["text"].as_str().unwrap_or_default();
+            match line["op"].as_str() {
+                Some("removed") => println!("- {text}"),
+                Some("added") => println!("+ {text}"),
+                _ => println!("  {text}"),
+            }
+        }
+    }
+
+    println!();
+    ui::success(&format!(
+        "Replayed {} turns on {}; {} changed",
+        turns.len(),
+        report["model"].as_str().unwrap_or("unknown model"),
+        report["changed"]
+    ));
+    ui::kv(
+        "Tokens",
+        &format!(
+            "{} in, {} out",
+            report["usage"]["input_tokens"], report["usage"]["output_tokens"]
+        ),
+    );
+    Ok(())
+}
+
+fn export_session(
+    store: &EventStore,
+    key: &str,
+    format: &str,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let format = TranscriptFormat::parse(format)
+        .ok_or_else(|| anyhow::anyhow!("Unknown format '{format}' (use md or html)"))?;
+    let session_key = SessionKey::new(key);
+    let projection = store
+        .get_projection(&session_key)
+        .map_err(|_| anyhow::anyhow!("Session not found: {key}"))?;
+    let events = store.get_events(&session_key)?;
+
+    let mut transcript = Transcript::new(&projection, &events);
+    let config = Config::load_default()?;
+    let variant = projection
+        .experiment
+        .as_ref()
+        .and_then(|id| config.experiments.get(id))
+        .and_then(|e| {
+            e.variants
+                .iter()
+                .find(|v| projection.variant.as_deref() == Some(v.name.as_str()))
+        });
+    if let Some(v) =
+        variant.filter(|v| v.input_cost_per_mtok.is_some() || v.output_cost_per_mtok.is_some())
+    {
+        transcript = transcript.with_rates(
+            v.input_cost_per_mtok.unwrap_or(0.0),
+            v.output_cost_per_mtok.unwrap_or(0.0),
+        );
+    }
+
+    let rendered = transcript.render(format);
+    match output {
+        Some(path) => {
+            std::fs::write(&path, rendered)?;
+            ui::success(&format!("Exported session '{key}' to {}", path.display()));
+        }
+        None => print!("{rendered}"),
+    }
+    Ok(())
+}
+
+fn purge_session(store: &EventStore, key: &str) -> Result<()> {
+    let (session_key, _, deleted) = load(store, key)?;
+    if !deleted {
+        anyhow::bail!("Session '{key}' must be deleted before it can be purged");
+    }
+
+    let removed = store.purge_session(&session_key)?;
+    store.flush()?;
+    ui::success(&format!("Purged session '{key}' ({removed} events)"));
+    Ok(())
+}
+
+fn purge_expired(store: &EventStore) -> Result<()> {
+    let config = openclaw_core::Config::load_default()?;
+    let retention =
+        chrono::Duration::days(i64::from(config.settings.deleted_session_retention_days));
+
+    let purged = store.purge_deleted_before(Utc::now() - retention)?;
+    store.flush()?;
+    ui::success(&format!("Purged {} expired session(s)", purged.len()));
+    Ok(())
+}
+
+fn archive_sessions(
+    store: &EventStore,
+    key: Option<&str>,
+    older_than_days: Option<u32>,
+) -> Result<()> {
+    if let Some(key) = key {
+        let archived = store.archive_session(&SessionKey::new(key))?;
+        store.flush()?;
+        ui::success(&format!(
+            "Archived session '{key}' ({} events) to {}",
+            archived.events,
+            archived.path.display()
+        ));
+        return Ok(());
+    }
+
+    let days = match older_than_days {
+        Some(days) => days,
+        None => openclaw_core::Config::load_default()?
+            .settings
+            .archive_ended_after_days
+            .ok_or_else(|| {
+                anyhow::anyhow!("Pass --older-than or set settings.archiveEndedAfterDays")
+            })?,
+    };
+
+    let archived =
+        store.archive_ended_before(Utc::now() - chrono::Duration::days(i64::from(days)))?;
+    store.flush()?;
+    ui::success(&format!("Archived {} ended session(s)", archived.len()));
+    Ok(())
+}
+
+fn migrate_storage(path: &std::path::Path) -> Result<()> {
+    let copied = EventStore::migrate_to_sqlite(path).map_err(|e| {
+        anyhow::anyhow!("Failed to migrate event store ({e}); is the gateway running?")
+    })?;
+
+    let target = EventStore::backend_path(path, EventStoreBackend::Sqlite);
+    ui::success(&format!("Copied {copied} entries to {}", target.display()));
+    println!();
+    ui::info("Set settings.eventStoreBackend to \"sqlite\" before starting the gateway.");
+    ui::info(&format!(
+        "The sled store at {} is left in place as a backup.",
+        path.display()
+    ));
+    Ok(())
+}
+
+fn encrypt_store(path: &std::path::Path) -> Result<()> {
+    let key = load_master_key()?.derive(EVENT_STORE_KEY_PURPOSE);
+    let backup = EventStore::encrypt_store(path, &key).map_err(|e| {
+        anyhow::anyhow!("Failed to encrypt event store ({e}); is the gateway running?")
+    })?;
+
+    ui::success(&format!("Encrypted event store at {}", path.display()));
+    ui::kv("Plaintext backup", &backup.display().to_string());
+    println!();
+    ui::info("Set settings.encryptEventStore to true before starting the gateway.");
+    ui::info("Delete the backup and its archive files once the gateway starts cleanly.");
+    Ok(())
+}
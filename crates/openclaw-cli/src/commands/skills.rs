@@ -0,0 +1,153 @@
+//! Skills commands - list skills and enable them per agent.
+
+use anyhow::Result;
+
+use openclaw_agents::SkillLibrary;
+use openclaw_core::Config;
+
+use crate::ui;
+
+/// Skills command arguments.
+#[derive(Debug, Clone)]
+pub enum SkillsAction {
+    /// List installed skills.
+    List {
+        /// Include the agent's own skills and show which are enabled.
+        agent: Option<String>,
+    },
+    /// Enable a skill for an agent.
+    Enable {
+        /// Skill name.
+        name: String,
+        /// Agent ID.
+        agent: String,
+    },
+    /// Disable a skill for an agent.
+    Disable {
+        /// Skill name.
+        name: String,
+        /// Agent ID.
+        agent: String,
+    },
+}
+
+/// Run the skills command.
+///
+/// # Errors
+///
+/// Returns error if the config cannot be loaded or written, or the skill
+/// is not installed.
+pub fn run_skills(action: SkillsAction) -> Result<()> {
+    let config = Config::load_default()?;
+
+    match action {
+        SkillsAction::List { agent } => {
+            list_skills(&config, agent.as_deref());
+            Ok(())
+        }
+        SkillsAction::Enable { name, agent } => enable_skill(&config, &name, &agent),
+        SkillsAction::Disable { name, agent } => disable_skill(&config, &name, &agent),
+    }
+}
+
+fn list_skills(config: &Config, agent: Option<&str>) {
+    let library = agent.map_or_else(
+        || SkillLibrary::discover(&[Config::skills_dir()]),
+        SkillLibrary::for_agent,
+    );
+
+    for (path, error) in library.errors() {
+        ui::warning(&format!("Skipped {}: {error}", path.display()));
+    }
+    if library.iter().next().is_none() {
+        ui::info("No skills installed.");
+        ui::info(&format!(
+            "Add skills as {}/<name>/skill.md",
+            Config::skills_dir().display()
+        ));
+        return;
+    }
+
+    println!("{:<20} {:<24} DESCRIPTION", "NAME", "ENABLED FOR");
+    println!("{}", "-".repeat(72));
+    for skill in library.iter() {
+        let mut enabled: Vec<&str> = config
+            .agents
+            .iter()
+            .filter(|(id, a)| {
+                agent.is_none_or(|only| only == id.as_str()) && a.skills.contains(&skill.name)
+            })
+            .map(|(id, _)| id.as_str())
+            .collect();
+        enabled.sort_unstable();
+
+        println!(
+            "{:<20} {:<24} {}",
+            skill.name,
+            if enabled.is_empty() {
+                "-".to_string()
+            } else {
+                enabled.join(", ")
+            },
+            skill.description.as_deref().unwrap_or("")
+        );
+    }
+}
+
+fn enable_skill(config: &Config, name: &str, agent: &str) -> Result<()> {
+    if SkillLibrary::for_agent(agent).get(name).is_none() {
+        anyhow::bail!(
+            "Skill '{name}' not found in {} or the agent's skills directory",
+            Config::skills_dir().display()
+        );
+    }
+
+    let mut skills = config.get_agent(agent).skills;
+    if skills.iter().any(|s| s == name) {
+        ui::info(&format!("Skill '{name}' is already enabled for '{agent}'"));
+        return Ok(());
+    }
+    skills.push(name.to_string());
+    write_agent_skills(agent, &skills)?;
+
+    ui::success(&format!("Enabled skill '{name}' for agent '{agent}'"));
+    Ok(())
+}
+
+fn disable_skill(config: &Config, name: &str, agent: &str) -> Result<()> {
+    let mut skills = config.get_agent(agent).skills;
+    let before = skills.len();
+    skills.retain(|s| s != name);
+    if skills.len() == before {
+        ui::info(&format!("Skill '{name}' is not enabled for '{agent}'"));
+        return Ok(());
+    }
+    write_agent_skills(agent, &skills)?;
+
+    ui::success(&format!("Disabled skill '{name}' for agent '{agent}'"));
+    Ok(())
+}
+
+/// Set `agents.<agent>.skills` in the config file, keeping everything else
+/// as written.
+fn write_agent_skills(agent: &str, skills: &[String]) -> Result<()> {
+    let config_path = Config::default_path();
+    let mut raw: serde_json::Value = if config_path.exists() {
+        json5::from_str(&std::fs::read_to_string(&config_path)?)?
+    } else {
+        serde_json::json!({})
+    };
+    if !raw["agents"].is_object() {
+        raw["agents"] = serde_json::json!({});
+    }
+    if !raw["agents"][agent].is_object() {
+        raw["agents"][agent] = serde_json::json!({});
+    }
+    raw["agents"][agent]["skills"] = serde_json::to_value(skills)?;
+
+    if let Some(parent) = config_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&config_path, serde_json::to_string_pretty(&raw)?)?;
+    Ok(())
+}
@@ -15,24 +15,32 @@ pub struct StatusArgs {
 
 /// Run the status command.
 pub async fn run_status(args: StatusArgs) -> Result<()> {
-    ui::header("OpenClaw Status");
+    ui::header(&ui::text("cli-status-title", &[]));
 
     // Gateway status
     println!();
-    ui::info("Gateway");
+    ui::info(&ui::text("cli-status-gateway", &[]));
     let gateway_status = check_gateway_status(args.deep).await;
 
     match gateway_status {
         GatewayStatus::Running { port, version } => {
-            ui::health_check("Status", HealthStatus::Ok, Some("running"));
+            ui::health_check(
+                "Status",
+                HealthStatus::Ok,
+                Some(&ui::text("cli-status-running", &[])),
+            );
             ui::kv("  Port", &port.to_string());
             if let Some(v) = version {
                 ui::kv("  Version", &v);
             }
         }
         GatewayStatus::NotRunning => {
-            ui::health_check("Status", HealthStatus::Warning, Some("not running"));
-            ui::info("  Start with: openclaw gateway run");
+            ui::health_check(
+                "Status",
+                HealthStatus::Warning,
+                Some(&ui::text("cli-status-not-running", &[])),
+            );
+            ui::info(&format!("  {}", ui::text("cli-status-start-hint", &[])));
         }
         GatewayStatus::Error(msg) => {
             ui::health_check("Status", HealthStatus::Error, Some(&msg));
@@ -41,9 +49,13 @@ pub async fn run_status(args: StatusArgs) -> Result<()> {
 
     // Config status
     println!();
-    ui::info("Configuration");
+    ui::info(&ui::text("cli-status-config", &[]));
     if let Ok(config) = openclaw_core::Config::load_default() {
-        ui::health_check("Config", HealthStatus::Ok, Some("loaded"));
+        ui::health_check(
+            "Config",
+            HealthStatus::Ok,
+            Some(&ui::text("cli-status-config-loaded", &[])),
+        );
         if args.all {
             ui::kv("  Gateway Port", &config.gateway.port.to_string());
             // Show default agent model if configured
@@ -52,13 +64,17 @@ pub async fn run_status(args: StatusArgs) -> Result<()> {
             }
         }
     } else {
-        ui::health_check("Config", HealthStatus::Warning, Some("not found"));
-        ui::info("  Run 'openclaw onboard' to configure");
+        ui::health_check(
+            "Config",
+            HealthStatus::Warning,
+            Some(&ui::text("cli-status-config-missing", &[])),
+        );
+        ui::info(&format!("  {}", ui::text("cli-status-onboard-hint", &[])));
     }
 
     // Sandbox status
     println!();
-    ui::info("Sandbox");
+    ui::info(&ui::text("cli-status-sandbox", &[]));
     if openclaw_agents::sandbox::is_sandbox_available() {
         let sandbox_type = if cfg!(target_os = "linux") {
             "bubblewrap"
@@ -71,12 +87,16 @@ pub async fn run_status(args: StatusArgs) -> Result<()> {
         };
         ui::health_check("Sandbox", HealthStatus::Ok, Some(sandbox_type));
     } else {
-        ui::health_check("Sandbox", HealthStatus::Warning, Some("not available"));
+        ui::health_check(
+            "Sandbox",
+            HealthStatus::Warning,
+            Some(&ui::text("cli-status-sandbox-missing", &[])),
+        );
     }
 
     // Credentials status
     println!();
-    ui::info("Credentials");
+    ui::info(&ui::text("cli-status-credentials", &[]));
     let cred_path = dirs::home_dir()
         .map(|h| h.join(".openclaw").join("credentials"))
         .unwrap_or_default();
@@ -90,7 +110,10 @@ pub async fn run_status(args: StatusArgs) -> Result<()> {
             ui::health_check(
                 "Credentials",
                 HealthStatus::Ok,
-                Some(&format!("{count} provider(s)")),
+                Some(&ui::text(
+                    "cli-status-providers",
+                    &[("count", count.into())],
+                )),
             );
 
             if args.all {
@@ -106,10 +129,18 @@ pub async fn run_status(args: StatusArgs) -> Result<()> {
                 }
             }
         } else {
-            ui::health_check("Credentials", HealthStatus::Warning, Some("no API keys"));
+            ui::health_check(
+                "Credentials",
+                HealthStatus::Warning,
+                Some(&ui::text("cli-status-no-keys", &[])),
+            );
         }
     } else {
-        ui::health_check("Credentials", HealthStatus::Warning, Some("not configured"));
+        ui::health_check(
+            "Credentials",
+            HealthStatus::Warning,
+            Some(&ui::text("cli-status-not-configured", &[])),
+        );
     }
 
     // Deep probe
@@ -0,0 +1,149 @@
+//! Test commands - run agent scenario files against mocked model replies.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use openclaw_agents::harness::{MockProvider, Scenario, ScenarioReport};
+use openclaw_agents::prompts::PromptLibrary;
+use openclaw_agents::runtime::AgentRuntime;
+use openclaw_agents::skills::SkillLibrary;
+use openclaw_core::Config;
+use openclaw_core::config::AgentOverrides;
+
+use crate::ui;
+
+/// Directory scenarios are read from when no path is given.
+pub const DEFAULT_SCENARIO_DIR: &str = "tests/agents";
+
+/// Test actions.
+#[derive(Debug, Clone)]
+pub enum TestAction {
+    /// Run agent scenarios.
+    Agents {
+        /// Scenario files or directories; `tests/agents` if empty.
+        paths: Vec<PathBuf>,
+        /// Config file; the default config if `None`.
+        config: Option<PathBuf>,
+    },
+}
+
+/// Run the test command.
+///
+/// # Errors
+///
+/// Returns error if the config or a scenario cannot be loaded, or any
+/// scenario fails.
+pub async fn run_test(action: TestAction) -> Result<()> {
+    match action {
+        TestAction::Agents { paths, config } => test_agents(paths, config).await,
+    }
+}
+
+async fn test_agents(paths: Vec<PathBuf>, config: Option<PathBuf>) -> Result<()> {
+    let config = match config {
+        Some(path) => Config::load(&path)?,
+        None => Config::load_default()?,
+    };
+    let paths = if paths.is_empty() {
+        let default = PathBuf::from(DEFAULT_SCENARIO_DIR);
+        if !default.is_dir() {
+            ui::info(&format!(
+                "No scenarios found; add them under {DEFAULT_SCENARIO_DIR}/ or pass a path"
+            ));
+            return Ok(());
+        }
+        vec![default]
+    } else {
+        paths
+    };
+
+    let mut files = Vec::new();
+    for path in &paths {
+        files.extend(Scenario::discover(path)?);
+    }
+    if files.is_empty() {
+        ui::info(&format!(
+            "No scenarios found in {}",
+            paths
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+        return Ok(());
+    }
+
+    let prompts = PromptLibrary::load_default();
+    let mut failed = 0;
+    for file in &files {
+        let scenario = Scenario::load(file)?;
+        let report = match agent_runtime(&config, &prompts, &scenario.agent) {
+            Ok(runtime) => scenario.run(&runtime).await,
+            Err(e) => {
+                ui::error(&format!("{}: {e}", scenario.name));
+                failed += 1;
+                continue;
+            }
+        };
+        print_report(&report);
+        if !report.passed() {
+            failed += 1;
+        }
+    }
+
+    println!();
+    if failed > 0 {
+        anyhow::bail!("{failed} of {} scenarios failed", files.len());
+    }
+    ui::success(&format!("{} scenarios passed", files.len()));
+    Ok(())
+}
+
+/// Runtime for a configured agent. The scenario replaces its provider.
+fn agent_runtime(config: &Config, prompts: &PromptLibrary, id: &str) -> Result<AgentRuntime> {
+    let agent = config
+        .agents
+        .get(id)
+        .ok_or_else(|| anyhow::anyhow!("Agent not found: {id}"))?;
+
+    let mut runtime = AgentRuntime::new(Arc::new(MockProvider::default()))
+        .with_overrides(&AgentOverrides {
+            model: Some(agent.model.clone()),
+            temperature: Some(agent.temperature),
+            max_tokens: Some(agent.max_tokens),
+            system_prompt: None,
+        })
+        .with_tool_policy(agent.tool_policy())
+        .with_skills(SkillLibrary::for_agent(id).select(&agent.skills))
+        .with_prompt_assembly(&agent.prompt)
+        .with_moderation(&agent.moderation);
+    if let Some(prompt) = &agent.system_prompt {
+        runtime = match prompts.resolve(prompt)? {
+            Some(template) => runtime.with_prompt_template(template.clone()),
+            None => runtime.with_system_prompt(prompt.clone()),
+        };
+    }
+    Ok(runtime)
+}
+
+fn print_report(report: &ScenarioReport) {
+    if report.passed() {
+        ui::success(&report.name);
+        return;
+    }
+    ui::error(&report.name);
+    for (n, turn) in report.turns.iter().enumerate() {
+        if turn.failures.is_empty() {
+            continue;
+        }
+        println!("  Turn {}: {}", n + 1, turn.user);
+        if let Some(reply) = &turn.reply {
+            println!("    Reply: {reply}");
+        }
+        for failure in &turn.failures {
+            println!("    - {failure}");
+        }
+    }
+}
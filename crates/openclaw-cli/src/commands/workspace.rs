@@ -0,0 +1,197 @@
+//! Workspace commands - inspect and clean agent workspaces.
+
+use std::time::Duration;
+
+use anyhow::Result;
+
+use openclaw_agents::Workspace;
+use openclaw_agents::workspace::list_workspaces;
+use openclaw_core::Config;
+
+use crate::ui;
+
+/// Workspace actions.
+#[derive(Debug, Clone)]
+pub enum WorkspaceAction {
+    /// List agent and session workspaces.
+    List {
+        /// Only this agent's workspaces.
+        agent: Option<String>,
+    },
+    /// Show one workspace.
+    Show {
+        /// Agent ID.
+        agent: String,
+        /// Session key, for a session workspace.
+        session: Option<String>,
+    },
+    /// Delete a workspace's files.
+    Clean {
+        /// Agent ID.
+        agent: String,
+        /// Session key, for a session workspace.
+        session: Option<String>,
+    },
+    /// Remove idle session workspaces.
+    Expire {
+        /// Only this agent's sessions.
+        agent: Option<String>,
+        /// Idle hours, overriding each agent's `expireAfterHours`.
+        older_than_hours: Option<u64>,
+    },
+}
+
+/// Run the workspace command.
+///
+/// # Errors
+///
+/// Returns error if the config cannot be loaded or a workspace cannot be
+/// read or removed.
+pub fn run_workspace(action: WorkspaceAction) -> Result<()> {
+    let config = Config::load_default()?;
+    let base = Config::workspace_dir();
+
+    match action {
+        WorkspaceAction::List { agent } => {
+            let workspaces: Vec<Workspace> = list_workspaces(&base)?
+                .into_iter()
+                .filter(|w| agent.as_deref().is_none_or(|id| id == w.agent_id()))
+                .collect();
+            if workspaces.is_empty() {
+                ui::info("No workspaces.");
+                return Ok(());
+            }
+
+            println!(
+                "{:<16} {:<28} {:>10} {:>10}  LAST USED",
+                "AGENT", "SESSION", "SIZE", "QUOTA"
+            );
+            println!("{}", "-".repeat(90));
+            for workspace in workspaces {
+                let workspace = with_configured_quota(&config, workspace);
+                print_row(&workspace)?;
+                for session in workspace.sessions()? {
+                    print_row(&session)?;
+                }
+            }
+        }
+        WorkspaceAction::Show { agent, session } => {
+            let workspace = resolve(&config, &base, &agent, session.as_deref());
+            if !workspace.exists() {
+                anyhow::bail!("No workspace at {}", workspace.root().display());
+            }
+            ui::kv("Path", &workspace.root().display().to_string());
+            ui::kv("Size", &format_bytes(workspace.usage()?));
+            ui::kv(
+                "Quota",
+                &workspace
+                    .quota()
+                    .map_or_else(|| "-".to_string(), format_bytes),
+            );
+            ui::kv("Last used", &last_used(&workspace));
+            if session.is_none() {
+                ui::kv("Sessions", &workspace.sessions()?.len().to_string());
+            }
+        }
+        WorkspaceAction::Clean { agent, session } => {
+            let workspace = resolve(&config, &base, &agent, session.as_deref());
+            let before = workspace.usage()?;
+            workspace.clean()?;
+            let freed = before.saturating_sub(workspace.usage()?);
+            ui::success(&format!(
+                "Cleaned {} ({} freed)",
+                workspace.root().display(),
+                format_bytes(freed)
+            ));
+        }
+        WorkspaceAction::Expire {
+            agent,
+            older_than_hours,
+        } => {
+            let mut expired = 0;
+            for workspace in list_workspaces(&base)? {
+                if agent
+                    .as_deref()
+                    .is_some_and(|id| id != workspace.agent_id())
+                {
+                    continue;
+                }
+                let hours = older_than_hours.or_else(|| {
+                    config
+                        .agents
+                        .get(workspace.agent_id())
+                        .and_then(|a| a.workspace.expire_after_hours)
+                });
+                let Some(hours) = hours else {
+                    continue;
+                };
+                expired += workspace
+                    .expire_sessions(Duration::from_secs(hours.saturating_mul(3600)))?
+                    .len();
+            }
+            ui::success(&format!("Removed {expired} idle session workspace(s)"));
+        }
+    }
+    Ok(())
+}
+
+fn resolve(
+    config: &Config,
+    base: &std::path::Path,
+    agent: &str,
+    session: Option<&str>,
+) -> Workspace {
+    let workspace = session.map_or_else(
+        || Workspace::for_agent(base, agent),
+        |key| Workspace::for_session(base, agent, key),
+    );
+    with_configured_quota(config, workspace)
+}
+
+fn with_configured_quota(config: &Config, workspace: Workspace) -> Workspace {
+    match config
+        .agents
+        .get(workspace.agent_id())
+        .and_then(|a| a.workspace.quota_bytes())
+    {
+        Some(quota) => workspace.with_quota(quota),
+        None => workspace,
+    }
+}
+
+fn print_row(workspace: &Workspace) -> Result<()> {
+    println!(
+        "{:<16} {:<28} {:>10} {:>10}  {}",
+        workspace.agent_id(),
+        workspace.session_key().unwrap_or("-"),
+        format_bytes(workspace.usage()?),
+        workspace
+            .quota()
+            .map_or_else(|| "-".to_string(), format_bytes),
+        last_used(workspace),
+    );
+    Ok(())
+}
+
+fn last_used(workspace: &Workspace) -> String {
+    workspace.last_used().map_or_else(
+        || "-".to_string(),
+        |t| t.format("%Y-%m-%d %H:%M").to_string(),
+    )
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
@@ -4,7 +4,7 @@ mod commands;
 mod ui;
 
 use clap::{Parser, Subcommand};
-use tracing_subscriber::{EnvFilter, fmt, prelude::*};
+use tracing_subscriber::prelude::*;
 
 #[derive(Parser)]
 #[command(name = "openclaw")]
@@ -82,12 +82,89 @@ enum Commands {
         deep: bool,
     },
 
+    /// Run an end-to-end self-test against a throwaway gateway
+    Selftest {
+        /// Keep the temporary data directory
+        #[arg(long)]
+        keep: bool,
+
+        /// Seconds to wait for the gateway to start
+        #[arg(long, default_value_t = 10)]
+        timeout: u64,
+    },
+
+    /// Regression tests for agents
+    Test {
+        #[command(subcommand)]
+        action: TestCommands,
+    },
+
     /// Gateway operations
     Gateway {
         #[command(subcommand)]
         action: GatewayCommands,
     },
 
+    /// Scheduled task management
+    Schedule {
+        #[command(subcommand)]
+        action: ScheduleCommands,
+
+        /// Gateway data directory override
+        #[arg(long, global = true)]
+        data_dir: Option<std::path::PathBuf>,
+    },
+
+    /// Agent configuration
+    Agents {
+        #[command(subcommand)]
+        action: AgentsCommands,
+    },
+
+    /// Skills injected into agent prompts
+    Skills {
+        #[command(subcommand)]
+        action: SkillsCommands,
+    },
+
+    /// System prompt templates
+    Prompts {
+        #[command(subcommand)]
+        action: PromptsCommands,
+    },
+
+    /// Agent and session workspaces
+    Workspace {
+        #[command(subcommand)]
+        action: WorkspaceCommands,
+    },
+
+    /// Session management (list, delete, restore, purge)
+    Sessions {
+        #[command(subcommand)]
+        action: SessionsCommands,
+
+        /// Gateway data directory override
+        #[arg(long, global = true)]
+        data_dir: Option<std::path::PathBuf>,
+    },
+
+    /// Gateway log files
+    Logs {
+        #[command(subcommand)]
+        action: LogsCommands,
+    },
+
+    /// Encrypted backups to object storage
+    Backup {
+        #[command(subcommand)]
+        action: BackupCommands,
+
+        /// Gateway data directory override
+        #[arg(long, global = true)]
+        data_dir: Option<std::path::PathBuf>,
+    },
+
     /// Channel management
     Channels {
         /// List configured channels
@@ -146,6 +223,62 @@ enum Commands {
         #[arg(long, global = true)]
         data_dir: Option<std::path::PathBuf>,
     },
+
+    /// Review tool calls waiting for operator approval
+    Approvals {
+        #[command(subcommand)]
+        action: ApprovalsCommands,
+
+        /// Access token (admin or operator)
+        #[arg(long, env = "OPENCLAW_TOKEN", global = true)]
+        token: Option<String>,
+
+        /// Gateway port (defaults to the configured port)
+        #[arg(long, global = true)]
+        port: Option<u16>,
+    },
+
+    /// View and edit what agents know about the people they talk to
+    Peers {
+        #[command(subcommand)]
+        action: PeersCommands,
+
+        /// Access token (admin or operator to edit)
+        #[arg(long, env = "OPENCLAW_TOKEN", global = true)]
+        token: Option<String>,
+
+        /// Gateway port (defaults to the configured port)
+        #[arg(long, global = true)]
+        port: Option<u16>,
+    },
+
+    /// Erase what the gateway stores about a person
+    Privacy {
+        #[command(subcommand)]
+        action: PrivacyCommands,
+
+        /// Access token (admin or operator)
+        #[arg(long, env = "OPENCLAW_TOKEN", global = true)]
+        token: Option<String>,
+
+        /// Gateway port (defaults to the configured port)
+        #[arg(long, global = true)]
+        port: Option<u16>,
+    },
+
+    /// Send one message to many chats
+    Broadcast {
+        #[command(subcommand)]
+        action: BroadcastCommands,
+
+        /// Access token (admin or operator)
+        #[arg(long, env = "OPENCLAW_TOKEN", global = true)]
+        token: Option<String>,
+
+        /// Gateway port (defaults to the configured port)
+        #[arg(long, global = true)]
+        port: Option<u16>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -163,12 +296,363 @@ enum GatewayCommands {
         /// Force start even if port is in use
         #[arg(long)]
         force: bool,
+
+        /// Also write rotating log files (set by the installed service)
+        #[arg(long)]
+        log_file: bool,
     },
 
     /// Check gateway status
     Status,
 }
 
+#[derive(Subcommand)]
+enum ScheduleCommands {
+    /// List configured schedules with next and last run
+    List,
+
+    /// Run a schedule now
+    RunNow {
+        /// Schedule ID
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum AgentsCommands {
+    /// List configured agents
+    List,
+
+    /// Show an agent's configuration
+    Show {
+        /// Agent ID
+        id: String,
+
+        /// Show the effective config with inherited fields applied
+        #[arg(long)]
+        resolved: bool,
+    },
+
+    /// Install an agent from a persona pack (.tar.gz)
+    Import {
+        /// Path to the persona pack
+        path: std::path::PathBuf,
+
+        /// Agent ID to install as (defaults to the pack name)
+        #[arg(long)]
+        id: Option<String>,
+
+        /// Replace an existing agent with the same ID
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Export an agent as a persona pack (.tar.gz)
+    Export {
+        /// Agent ID
+        id: String,
+
+        /// Output path (defaults to <id>.tar.gz)
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+
+        /// Version recorded in the pack
+        #[arg(long, default_value = "0.1.0")]
+        version: String,
+    },
+
+    /// Change a running agent's model parameters and save them
+    Set {
+        /// Agent ID
+        id: String,
+
+        /// Settings as key=value: `model`, `temperature`, `max_tokens`, `system_prompt`
+        #[arg(required = true)]
+        assignments: Vec<String>,
+
+        /// Admin access token
+        #[arg(long, env = "OPENCLAW_TOKEN")]
+        token: Option<String>,
+
+        /// Gateway port (defaults to the configured port)
+        #[arg(long)]
+        port: Option<u16>,
+    },
+}
+
+#[derive(Subcommand)]
+enum WorkspaceCommands {
+    /// List agent and session workspaces
+    List {
+        /// Only this agent's workspaces
+        #[arg(long)]
+        agent: Option<String>,
+    },
+
+    /// Show a workspace's path, size, and quota
+    Show {
+        /// Agent ID
+        agent: String,
+
+        /// Session key, for a session workspace
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Delete a workspace's files
+    Clean {
+        /// Agent ID
+        agent: String,
+
+        /// Session key, for a session workspace
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Remove idle session workspaces
+    Expire {
+        /// Only this agent's sessions
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Remove sessions idle more than this many hours
+        #[arg(long, value_name = "HOURS")]
+        older_than: Option<u64>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TestCommands {
+    /// Run agent scenario files against mocked model replies
+    Agents {
+        /// Scenario files or directories (defaults to tests/agents)
+        paths: Vec<std::path::PathBuf>,
+
+        /// Config file to take agent settings from
+        #[arg(long)]
+        config: Option<std::path::PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum PromptsCommands {
+    /// List installed templates and the agents using them
+    List,
+
+    /// Render a template with sample variables
+    Test {
+        /// Template name
+        name: String,
+
+        /// Set a variable (repeatable), e.g. --var name=Grace
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SkillsCommands {
+    /// List installed skills
+    List {
+        /// Include this agent's own skills
+        #[arg(long)]
+        agent: Option<String>,
+    },
+
+    /// Enable a skill for an agent
+    Enable {
+        /// Skill name
+        name: String,
+
+        /// Agent ID
+        #[arg(long, default_value = "default")]
+        agent: String,
+    },
+
+    /// Disable a skill for an agent
+    Disable {
+        /// Skill name
+        name: String,
+
+        /// Agent ID
+        #[arg(long, default_value = "default")]
+        agent: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum LogsCommands {
+    /// Print the end of the active log file and follow it
+    Tail {
+        /// Lines to print before following
+        #[arg(short = 'n', long, default_value_t = 50)]
+        lines: usize,
+
+        /// Print the lines and exit instead of following
+        #[arg(long)]
+        no_follow: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum BackupCommands {
+    /// Back up sessions, config, and credentials now
+    Run,
+
+    /// List backups, newest first
+    List,
+
+    /// Download a backup and check every range against its manifest
+    Verify {
+        /// Backup ID
+        id: String,
+    },
+
+    /// Restore sessions, config, and credentials from a backup
+    Restore {
+        /// Backup ID
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum SessionsCommands {
+    /// List sessions
+    List {
+        /// Show deleted sessions instead of active ones
+        #[arg(long)]
+        deleted: bool,
+
+        /// Show archived sessions instead of active ones
+        #[arg(long, conflicts_with = "deleted")]
+        archived: bool,
+    },
+
+    /// Soft-delete a session (restorable until purged)
+    Delete {
+        /// Session key
+        key: String,
+
+        /// Reason recorded with the deletion
+        #[arg(long)]
+        reason: Option<String>,
+    },
+
+    /// Restore a deleted session
+    Restore {
+        /// Session key
+        key: String,
+    },
+
+    /// Permanently remove a deleted session
+    Purge {
+        /// Session key
+        key: String,
+    },
+
+    /// Purge deleted sessions past the retention period
+    PurgeExpired,
+
+    /// Move ended sessions to compressed cold storage
+    Archive {
+        /// Session key (all eligible sessions if omitted)
+        key: Option<String>,
+
+        /// Archive sessions ended more than this many days ago
+        #[arg(long, value_name = "DAYS", conflicts_with = "key")]
+        older_than: Option<u32>,
+    },
+
+    /// Encrypt the event store at rest with a key derived from the master key
+    Encrypt,
+
+    /// Rewrite stored events to the current schema version
+    Migrate,
+
+    /// Convert stored events to the format set by settings.eventStoreFormat
+    Compact,
+
+    /// Copy the sled event store into a `SQLite` file for `settings.eventStoreBackend`
+    MigrateStorage,
+
+    /// Preview tool calls in a session instead of running them
+    DryRun {
+        /// Session key
+        key: String,
+
+        /// simulate: answer tool calls with synthetic results; plan: stop
+        /// at the first tool calls and return them
+        #[arg(long, default_value = "plan")]
+        mode: String,
+
+        /// Turn dry run off and run tools normally
+        #[arg(long, conflicts_with = "mode")]
+        off: bool,
+    },
+
+    /// Render a session transcript as Markdown or HTML
+    Export {
+        /// Session key
+        key: String,
+
+        /// Output format: md or html
+        #[arg(long, default_value = "md")]
+        format: String,
+
+        /// Output path (prints to stdout if omitted)
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
+
+    /// Copy a session up to an event into a new session
+    Fork {
+        /// Session key
+        key: String,
+
+        /// ID of the last event to copy
+        #[arg(long)]
+        at: String,
+
+        /// Suffix for the new key, <key>:fork-<suffix> (defaults to a timestamp)
+        #[arg(long)]
+        suffix: Option<String>,
+    },
+
+    /// Re-run a session's turns with another model or prompt and diff the replies
+    Replay {
+        /// Session key
+        key: String,
+
+        /// Agent to replay with (defaults to the session's agent)
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Model to replay with
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Sampling temperature
+        #[arg(long)]
+        temperature: Option<f32>,
+
+        /// Maximum tokens per reply
+        #[arg(long)]
+        max_tokens: Option<u32>,
+
+        /// System prompt to replay with
+        #[arg(long)]
+        system_prompt: Option<String>,
+
+        /// Access token
+        #[arg(long, env = "OPENCLAW_TOKEN")]
+        token: Option<String>,
+
+        /// Gateway port (defaults to the configured port)
+        #[arg(long)]
+        port: Option<u16>,
+    },
+}
+
 #[derive(Subcommand)]
 enum ConfigCommands {
     /// Get a configuration value
@@ -209,6 +693,40 @@ enum DaemonCommands {
 
     /// Check daemon status
     Status,
+
+    /// Reload the running daemon's configuration
+    Reload,
+
+    /// Probe the running daemon's channels
+    Probe {
+        /// Channel ID (all channels if omitted)
+        channel: Option<String>,
+    },
+
+    /// Start a channel in the running daemon
+    StartChannel {
+        /// Channel ID
+        channel: String,
+    },
+
+    /// Stop a channel in the running daemon
+    StopChannel {
+        /// Channel ID
+        channel: String,
+    },
+
+    /// Enable and start a channel, saving the setting in the config file
+    EnableChannel {
+        /// Channel ID
+        channel: String,
+    },
+
+    /// Stop a channel once its messages are answered, and keep it stopped
+    /// across restarts
+    DisableChannel {
+        /// Channel ID
+        channel: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -262,22 +780,212 @@ enum AdminCommands {
         #[arg(long)]
         username: String,
     },
+
+    /// Inspect or change a running gateway's log level
+    LogLevel {
+        #[command(subcommand)]
+        action: LogLevelCommands,
+
+        /// Admin access token
+        #[arg(long, env = "OPENCLAW_TOKEN", global = true)]
+        token: Option<String>,
+
+        /// Gateway port (defaults to the configured port)
+        #[arg(long, global = true)]
+        port: Option<u16>,
+    },
+
+    /// Generate a new master key and re-encrypt stored credentials
+    RotateSecrets,
+}
+
+#[derive(Subcommand)]
+enum LogLevelCommands {
+    /// Show the active log filter
+    Get,
+
+    /// Set the log level, optionally for one module
+    Set {
+        /// Level: trace, debug, info, warn, error, or off
+        level: String,
+
+        /// Module path, e.g. `openclaw_channels::telegram`
+        #[arg(long)]
+        module: Option<String>,
+    },
+
+    /// Restore the log filter the gateway started with
+    Reset,
+}
+
+#[derive(Subcommand)]
+enum ApprovalsCommands {
+    /// List tool calls waiting for a decision
+    List,
+
+    /// Let a pending tool call run
+    Approve {
+        /// Approval request ID
+        id: String,
+    },
+
+    /// Reject a pending tool call
+    Deny {
+        /// Approval request ID
+        id: String,
+
+        /// Reason passed back to the agent
+        #[arg(long)]
+        reason: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum PeersCommands {
+    /// List peer profiles
+    List {
+        /// Only peers on this channel
+        #[arg(long)]
+        channel: Option<String>,
+    },
+
+    /// Show a peer's profile
+    Show {
+        /// Channel ID
+        channel: String,
+
+        /// Peer ID on the channel
+        peer_id: String,
+    },
+
+    /// Set profile fields (an empty value clears one)
+    Set {
+        /// Channel ID
+        channel: String,
+
+        /// Peer ID on the channel
+        peer_id: String,
+
+        /// Name to call the peer
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Preferred language, e.g. "de" or "pt-BR"
+        #[arg(long)]
+        language: Option<String>,
+
+        /// IANA timezone, e.g. "Europe/Berlin"
+        #[arg(long)]
+        timezone: Option<String>,
+
+        /// Notes shown to agents
+        #[arg(long)]
+        notes: Option<String>,
+
+        /// Preference flag as name=true|false|unset (repeatable)
+        #[arg(long = "pref")]
+        prefs: Vec<String>,
+    },
+
+    /// Delete a peer's profile
+    Delete {
+        /// Channel ID
+        channel: String,
+
+        /// Peer ID on the channel
+        peer_id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum PrivacyCommands {
+    /// Erase a peer's sessions, profile, uploads, and workspaces
+    Forget {
+        /// Channel ID
+        #[arg(long)]
+        channel: String,
+
+        /// Peer ID on the channel
+        #[arg(long = "peer")]
+        peer_id: String,
+
+        /// Skip the confirmation prompt
+        #[arg(long, short)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum BroadcastCommands {
+    /// Start a broadcast
+    Send {
+        /// Message text
+        text: String,
+
+        /// Channel to send on (defaults to the group's)
+        #[arg(long)]
+        channel: Option<String>,
+
+        /// Configured broadcast group to send to
+        #[arg(long)]
+        group: Option<String>,
+
+        /// Send to every peer allowlisted on the channel
+        #[arg(long)]
+        allowlist: bool,
+
+        /// Chat ID to send to (repeatable)
+        #[arg(long)]
+        to: Vec<String>,
+
+        /// Wait until every chat has been tried
+        #[arg(long)]
+        wait: bool,
+    },
+
+    /// Show a broadcast's delivery progress
+    Status {
+        /// Broadcast ID
+        id: String,
+    },
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
-    // Setup logging
-    let filter = if cli.verbose {
-        EnvFilter::new("debug")
-    } else {
-        EnvFilter::new("info")
+    // Setup logging. The filter is reloadable so a running gateway can
+    // change it over RPC, and secrets are scrubbed from every field. A
+    // gateway started by the service also writes rotating log files.
+    let (filter, log_filter) =
+        openclaw_gateway::LogFilter::layer(if cli.verbose { "debug" } else { "info" })?;
+    let settings = openclaw_core::Config::load_default()
+        .map(|c| c.settings)
+        .unwrap_or_default();
+    let file_logging = match &cli.command {
+        Some(Commands::Gateway {
+            action: GatewayCommands::Run { log_file, .. },
+        }) => *log_file || settings.log_file.enabled,
+        _ => false,
     };
+    let file_layer = file_logging.then(|| {
+        openclaw_gateway::output_layer(
+            &settings.log_format,
+            &settings.log_scrub_patterns,
+            openclaw_gateway::RollingLogFile::from_config(&settings.log_file),
+            false,
+        )
+    });
 
     tracing_subscriber::registry()
-        .with(fmt::layer().with_target(false))
         .with(filter)
+        .with(openclaw_gateway::output_layer(
+            &settings.log_format,
+            &settings.log_scrub_patterns,
+            std::io::stdout,
+            true,
+        ))
+        .with(file_layer)
         .init();
 
     // If no command, show help or run onboard for first-time users
@@ -342,18 +1050,207 @@ async fn main() -> anyhow::Result<()> {
             commands::run_status(args).await?;
         }
 
+        Commands::Selftest { keep, timeout } => {
+            let args = commands::selftest::SelftestArgs {
+                keep,
+                timeout_secs: timeout,
+            };
+            commands::run_selftest(args).await?;
+        }
+
+        Commands::Test { action } => {
+            use commands::test::TestAction;
+            commands::run_test(match action {
+                TestCommands::Agents { paths, config } => TestAction::Agents { paths, config },
+            })
+            .await?;
+        }
+
         Commands::Gateway { action } => {
             let args = match action {
-                GatewayCommands::Run { port, bind, force } => commands::gateway::GatewayArgs {
+                GatewayCommands::Run {
+                    port, bind, force, ..
+                } => commands::gateway::GatewayArgs {
                     action: commands::gateway::GatewayAction::Run { port, bind, force },
+                    log_filter: Some(log_filter),
                 },
                 GatewayCommands::Status => commands::gateway::GatewayArgs {
                     action: commands::gateway::GatewayAction::Status,
+                    log_filter: None,
                 },
             };
             commands::run_gateway(args).await?;
         }
 
+        Commands::Schedule { action, data_dir } => {
+            let args = commands::schedule::ScheduleArgs {
+                action: match action {
+                    ScheduleCommands::List => commands::schedule::ScheduleAction::List,
+                    ScheduleCommands::RunNow { id } => {
+                        commands::schedule::ScheduleAction::RunNow { id }
+                    }
+                },
+                data_dir,
+            };
+            commands::run_schedule(args).await?;
+        }
+
+        Commands::Agents { action } => {
+            use commands::agents::AgentsAction;
+            commands::run_agents(match action {
+                AgentsCommands::List => AgentsAction::List,
+                AgentsCommands::Show { id, resolved } => AgentsAction::Show { id, resolved },
+                AgentsCommands::Import { path, id, force } => {
+                    AgentsAction::Import { path, id, force }
+                }
+                AgentsCommands::Export {
+                    id,
+                    output,
+                    version,
+                } => AgentsAction::Export {
+                    id,
+                    output,
+                    version,
+                },
+                AgentsCommands::Set {
+                    id,
+                    assignments,
+                    token,
+                    port,
+                } => AgentsAction::Set {
+                    id,
+                    assignments,
+                    token,
+                    port,
+                },
+            })
+            .await?;
+        }
+
+        Commands::Skills { action } => {
+            use commands::skills::SkillsAction;
+            commands::run_skills(match action {
+                SkillsCommands::List { agent } => SkillsAction::List { agent },
+                SkillsCommands::Enable { name, agent } => SkillsAction::Enable { name, agent },
+                SkillsCommands::Disable { name, agent } => SkillsAction::Disable { name, agent },
+            })?;
+        }
+
+        Commands::Prompts { action } => {
+            use commands::prompts::PromptsAction;
+            commands::run_prompts(match action {
+                PromptsCommands::List => PromptsAction::List,
+                PromptsCommands::Test { name, vars } => PromptsAction::Test { name, vars },
+            })?;
+        }
+
+        Commands::Workspace { action } => {
+            use commands::workspace::WorkspaceAction;
+            commands::run_workspace(match action {
+                WorkspaceCommands::List { agent } => WorkspaceAction::List { agent },
+                WorkspaceCommands::Show { agent, session } => {
+                    WorkspaceAction::Show { agent, session }
+                }
+                WorkspaceCommands::Clean { agent, session } => {
+                    WorkspaceAction::Clean { agent, session }
+                }
+                WorkspaceCommands::Expire { agent, older_than } => WorkspaceAction::Expire {
+                    agent,
+                    older_than_hours: older_than,
+                },
+            })?;
+        }
+
+        Commands::Sessions { action, data_dir } => {
+            use commands::sessions::SessionsAction;
+            let args = commands::sessions::SessionsArgs {
+                action: match action {
+                    SessionsCommands::List { deleted, archived } => {
+                        SessionsAction::List { deleted, archived }
+                    }
+                    SessionsCommands::Delete { key, reason } => {
+                        SessionsAction::Delete { key, reason }
+                    }
+                    SessionsCommands::Restore { key } => SessionsAction::Restore { key },
+                    SessionsCommands::Purge { key } => SessionsAction::Purge { key },
+                    SessionsCommands::PurgeExpired => SessionsAction::PurgeExpired,
+                    SessionsCommands::Archive { key, older_than } => SessionsAction::Archive {
+                        key,
+                        older_than_days: older_than,
+                    },
+                    SessionsCommands::Encrypt => SessionsAction::Encrypt,
+                    SessionsCommands::Migrate => SessionsAction::Migrate,
+                    SessionsCommands::Compact => SessionsAction::Compact,
+                    SessionsCommands::MigrateStorage => SessionsAction::MigrateStorage,
+                    SessionsCommands::DryRun { key, mode, off } => SessionsAction::DryRun {
+                        key,
+                        mode: (!off).then_some(mode),
+                    },
+                    SessionsCommands::Export {
+                        key,
+                        format,
+                        output,
+                    } => SessionsAction::Export {
+                        key,
+                        format,
+                        output,
+                    },
+                    SessionsCommands::Fork { key, at, suffix } => {
+                        SessionsAction::Fork { key, at, suffix }
+                    }
+                    SessionsCommands::Replay {
+                        key,
+                        agent,
+                        model,
+                        temperature,
+                        max_tokens,
+                        system_prompt,
+                        token,
+                        port,
+                    } => SessionsAction::Replay {
+                        key,
+                        overrides: serde_json::json!({
+                            "agent_id": agent,
+                            "model": model,
+                            "temperature": temperature,
+                            "max_tokens": max_tokens,
+                            "system_prompt": system_prompt,
+                        }),
+                        token,
+                        port,
+                    },
+                },
+                data_dir,
+            };
+            commands::run_sessions(args).await?;
+        }
+
+        Commands::Logs { action } => {
+            let args = commands::logs::LogsArgs {
+                action: match action {
+                    LogsCommands::Tail { lines, no_follow } => commands::logs::LogsAction::Tail {
+                        lines,
+                        follow: !no_follow,
+                    },
+                },
+            };
+            commands::run_logs(args).await?;
+        }
+
+        Commands::Backup { action, data_dir } => {
+            use commands::backup::BackupAction;
+            let args = commands::backup::BackupArgs {
+                action: match action {
+                    BackupCommands::Run => BackupAction::Run,
+                    BackupCommands::List => BackupAction::List,
+                    BackupCommands::Verify { id } => BackupAction::Verify { id },
+                    BackupCommands::Restore { id } => BackupAction::Restore { id },
+                },
+                data_dir,
+            };
+            commands::run_backup(args).await?;
+        }
+
         Commands::Channels { list: _, probe } => {
             if probe {
                 ui::info("Probing channels...");
@@ -423,6 +1320,22 @@ async fn main() -> anyhow::Result<()> {
                     DaemonCommands::Start => commands::daemon::DaemonAction::Start,
                     DaemonCommands::Stop => commands::daemon::DaemonAction::Stop,
                     DaemonCommands::Status => commands::daemon::DaemonAction::Status,
+                    DaemonCommands::Reload => commands::daemon::DaemonAction::Reload,
+                    DaemonCommands::Probe { channel } => {
+                        commands::daemon::DaemonAction::Probe { channel }
+                    }
+                    DaemonCommands::StartChannel { channel } => {
+                        commands::daemon::DaemonAction::StartChannel { channel }
+                    }
+                    DaemonCommands::StopChannel { channel } => {
+                        commands::daemon::DaemonAction::StopChannel { channel }
+                    }
+                    DaemonCommands::EnableChannel { channel } => {
+                        commands::daemon::DaemonAction::EnableChannel { channel }
+                    }
+                    DaemonCommands::DisableChannel { channel } => {
+                        commands::daemon::DaemonAction::DisableChannel { channel }
+                    }
                 },
             };
             commands::run_daemon(args).await?;
@@ -504,11 +1417,139 @@ async fn main() -> anyhow::Result<()> {
                     AdminCommands::Delete { username } => {
                         commands::admin::AdminAction::Delete { username }
                     }
+                    AdminCommands::LogLevel {
+                        action,
+                        token,
+                        port,
+                    } => commands::admin::AdminAction::LogLevel {
+                        action: match action {
+                            LogLevelCommands::Get => commands::admin::LogLevelAction::Get,
+                            LogLevelCommands::Set { level, module } => {
+                                commands::admin::LogLevelAction::Set { level, module }
+                            }
+                            LogLevelCommands::Reset => commands::admin::LogLevelAction::Reset,
+                        },
+                        token,
+                        port,
+                    },
+                    AdminCommands::RotateSecrets => commands::admin::AdminAction::RotateSecrets,
                 },
                 data_dir,
             };
             commands::run_admin(args).await?;
         }
+
+        Commands::Approvals {
+            action,
+            token,
+            port,
+        } => {
+            use commands::approvals::ApprovalsAction;
+            let args = commands::approvals::ApprovalsArgs {
+                action: match action {
+                    ApprovalsCommands::List => ApprovalsAction::List,
+                    ApprovalsCommands::Approve { id } => ApprovalsAction::Approve { id },
+                    ApprovalsCommands::Deny { id, reason } => ApprovalsAction::Deny { id, reason },
+                },
+                token,
+                port,
+            };
+            commands::run_approvals(args).await?;
+        }
+
+        Commands::Peers {
+            action,
+            token,
+            port,
+        } => {
+            use commands::peers::PeersAction;
+            let args = commands::peers::PeersArgs {
+                action: match action {
+                    PeersCommands::List { channel } => PeersAction::List { channel },
+                    PeersCommands::Show { channel, peer_id } => {
+                        PeersAction::Show { channel, peer_id }
+                    }
+                    PeersCommands::Set {
+                        channel,
+                        peer_id,
+                        name,
+                        language,
+                        timezone,
+                        notes,
+                        prefs,
+                    } => PeersAction::Set {
+                        channel,
+                        peer_id,
+                        name,
+                        language,
+                        timezone,
+                        notes,
+                        prefs,
+                    },
+                    PeersCommands::Delete { channel, peer_id } => {
+                        PeersAction::Delete { channel, peer_id }
+                    }
+                },
+                token,
+                port,
+            };
+            commands::run_peers(args).await?;
+        }
+
+        Commands::Privacy {
+            action,
+            token,
+            port,
+        } => {
+            use commands::privacy::PrivacyAction;
+            let args = commands::privacy::PrivacyArgs {
+                action: match action {
+                    PrivacyCommands::Forget {
+                        channel,
+                        peer_id,
+                        yes,
+                    } => PrivacyAction::Forget {
+                        channel,
+                        peer_id,
+                        yes,
+                    },
+                },
+                token,
+                port,
+            };
+            commands::run_privacy(args).await?;
+        }
+
+        Commands::Broadcast {
+            action,
+            token,
+            port,
+        } => {
+            use commands::broadcast::BroadcastAction;
+            let args = commands::broadcast::BroadcastArgs {
+                action: match action {
+                    BroadcastCommands::Send {
+                        text,
+                        channel,
+                        group,
+                        allowlist,
+                        to,
+                        wait,
+                    } => BroadcastAction::Send {
+                        text,
+                        channel,
+                        group,
+                        allowlist,
+                        to,
+                        wait,
+                    },
+                    BroadcastCommands::Status { id } => BroadcastAction::Status { id },
+                },
+                token,
+                port,
+            };
+            commands::run_broadcast(args).await?;
+        }
     }
 
     Ok(())
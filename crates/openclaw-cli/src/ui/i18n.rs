@@ -0,0 +1,22 @@
+//! Localized CLI text.
+
+use std::sync::OnceLock;
+
+use openclaw_core::i18n::{FluentValue, Localizer, system_locale};
+
+struct Catalog {
+    localizer: Localizer,
+    locale: String,
+}
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+/// Message `id` in the user's locale (`OPENCLAW_LOCALE`, then `LANG`).
+#[must_use]
+pub fn text(id: &str, args: &[(&str, FluentValue<'_>)]) -> String {
+    let catalog = CATALOG.get_or_init(|| Catalog {
+        localizer: Localizer::builtin(),
+        locale: system_locale(),
+    });
+    catalog.localizer.message(&catalog.locale, id, args)
+}
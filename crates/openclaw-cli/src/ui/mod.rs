@@ -1,6 +1,8 @@
 //! Terminal UI utilities.
 
+pub mod i18n;
 pub mod output;
 pub mod prompts;
 
+pub use i18n::text;
 pub use output::*;
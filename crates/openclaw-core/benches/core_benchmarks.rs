@@ -26,6 +26,7 @@ fn bench_event_append(c: &mut Criterion) {
                     "bench-account",
                     PeerType::Dm,
                     &PeerId::new("bench-peer"),
+                    None,
                 );
 
                 b.iter(|| {
@@ -36,6 +37,7 @@ fn bench_event_append(c: &mut Criterion) {
                             SessionEventKind::MessageReceived {
                                 content: format!("Benchmark message {i}"),
                                 attachments: vec![],
+                                sender_id: None,
                             },
                         );
                         store.append(black_box(&event)).unwrap();
@@ -62,6 +64,7 @@ fn bench_session_key(c: &mut Criterion) {
                 black_box("test-account"),
                 black_box(PeerType::Dm),
                 black_box(&peer_id),
+                None,
             )
         });
     });
@@ -79,6 +82,7 @@ fn bench_projection_read(c: &mut Criterion) {
         "bench-account",
         PeerType::Dm,
         &PeerId::new("bench-peer"),
+        None,
     );
 
     // Create session
@@ -100,6 +104,7 @@ fn bench_projection_read(c: &mut Criterion) {
             SessionEventKind::MessageReceived {
                 content: format!("Message {i}"),
                 attachments: vec![],
+                sender_id: None,
             },
         );
         store.append(&event).unwrap();
@@ -130,6 +135,7 @@ fn bench_event_serialization(c: &mut Criterion) {
         "bench-account",
         PeerType::Dm,
         &PeerId::new("bench-peer"),
+        None,
     );
 
     let event = SessionEvent::new(
@@ -138,6 +144,7 @@ fn bench_event_serialization(c: &mut Criterion) {
         SessionEventKind::MessageReceived {
             content: "A typical message that might be sent to the agent".to_string(),
             attachments: vec![],
+            sender_id: None,
         },
     );
 
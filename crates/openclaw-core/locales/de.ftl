@@ -0,0 +1,56 @@
+# Nachrichten an Personen, die mit Agenten chatten
+
+rate-limited = Du sendest Nachrichten zu schnell. Bitte warte { $retry_after } s und versuche es dann erneut.
+not-allowed = Du bist leider nicht berechtigt, diesen Assistenten zu nutzen.
+feedback-thanks = Danke für dein Feedback!
+attachments-rejected = Folgendes konnte ich nicht annehmen: { $files }.
+attachment-too-large = größer als { $max }
+attachment-mime-mismatch = Inhalt ist { $detected }, nicht { $claimed }
+attachment-infected = vom Virenscanner gemeldet ({ $signature })
+attachment-unscanned = konnte nicht gescannt werden
+moderation-blocked = Darauf kann ich leider nicht antworten.
+outside-hours = Wir sind bis { $opens_at } außerhalb der Geschäftszeiten.
+outside-hours-closed = Wir sind außerhalb der Geschäftszeiten.
+outside-hours-queued = Wir sind bis { $opens_at } außerhalb der Geschäftszeiten. Wir beantworten deine Nachricht dann.
+outside-hours-urgent = Wenn es nicht warten kann, sende sie erneut und beginne mit /urgent.
+urgent-open = Verstanden. Nachrichten in den nächsten { $minutes } Minuten werden sofort beantwortet.
+command-not-allowed = Sie können /{ $command } hier nicht verwenden.
+session-reset = Erledigt. Wir beginnen ein neues Gespräch.
+model-current = Dieses Gespräch verwendet { $model }. Verfügbar: { $models }.
+model-fixed = Dieses Gespräch verwendet { $model }; das Modell kann nicht geändert werden.
+model-switched = Gewechselt zu { $model }.
+model-unknown = { $model } ist nicht verfügbar. Verfügbar: { $models }.
+usage-summary = Dieses Gespräch hat { $total } Tokens verbraucht ({ $input } Eingabe, { $output } Ausgabe) in { $responses } Antworten.
+commands-help = Verfügbare Befehle: { $commands }.
+
+# CLI-Ausgabe
+
+cli-gateway-unreachable = Gateway auf Port { $port } nicht erreichbar: { $error }
+cli-gateway-unreachable-socket = Gateway unter { $path } nicht erreichbar: { $error }
+cli-status-title = OpenClaw-Status
+cli-status-gateway = Gateway
+cli-status-config = Konfiguration
+cli-status-sandbox = Sandbox
+cli-status-credentials = Zugangsdaten
+cli-status-running = läuft
+cli-status-not-running = läuft nicht
+cli-status-start-hint = Starten mit: openclaw gateway run
+cli-status-config-loaded = geladen
+cli-status-config-missing = nicht gefunden
+cli-status-onboard-hint = Mit 'openclaw onboard' einrichten
+cli-status-sandbox-missing = nicht verfügbar
+cli-status-providers = { $count ->
+    [one] { $count } Anbieter
+   *[other] { $count } Anbieter
+}
+cli-status-no-keys = keine API-Schlüssel
+cli-status-not-configured = nicht eingerichtet
+cli-approvals-none = Keine Tool-Aufrufe warten auf Freigabe.
+cli-approvals-approved = { $id } freigegeben
+cli-approvals-denied = { $id } abgelehnt
+cli-peers-none = Keine Profile vorhanden.
+cli-peers-updated = { $peer } aktualisiert
+cli-peers-deleted = { $peer } gelöscht
+cli-peers-missing = Kein Profil für { $peer }
+cli-privacy-forget-warning = Alle gespeicherten Daten zu { $peer } werden gelöscht. Das kann nicht rückgängig gemacht werden.
+cli-privacy-forgotten = { $peer } vergessen
@@ -0,0 +1,56 @@
+# Messages sent to people chatting with agents
+
+rate-limited = You're sending messages too quickly. Please wait { $retry_after }s and try again.
+not-allowed = Sorry, you're not allowed to use this assistant.
+feedback-thanks = Thanks for your feedback!
+attachments-rejected = I couldn't accept { $files }.
+attachment-too-large = larger than { $max }
+attachment-mime-mismatch = contents are { $detected }, not { $claimed }
+attachment-infected = flagged by virus scan ({ $signature })
+attachment-unscanned = could not be scanned
+moderation-blocked = Sorry, I can't respond to that.
+outside-hours = We're outside working hours until { $opens_at }.
+outside-hours-closed = We're outside working hours.
+outside-hours-queued = We're outside working hours until { $opens_at }. We'll answer your message then.
+outside-hours-urgent = If it can't wait, send it again starting with /urgent.
+urgent-open = Got it. Messages you send in the next { $minutes } minutes will be answered right away.
+command-not-allowed = You can't use /{ $command } here.
+session-reset = Done. We're starting a fresh conversation.
+model-current = This conversation uses { $model }. You can switch to: { $models }.
+model-fixed = This conversation uses { $model }, which can't be changed.
+model-switched = Switched to { $model }.
+model-unknown = { $model } isn't available. You can switch to: { $models }.
+usage-summary = This conversation has used { $total } tokens ({ $input } in, { $output } out) over { $responses } responses.
+commands-help = Available commands: { $commands }.
+
+# CLI output
+
+cli-gateway-unreachable = Failed to reach gateway on port { $port }: { $error }
+cli-gateway-unreachable-socket = Failed to reach gateway at { $path }: { $error }
+cli-status-title = OpenClaw Status
+cli-status-gateway = Gateway
+cli-status-config = Configuration
+cli-status-sandbox = Sandbox
+cli-status-credentials = Credentials
+cli-status-running = running
+cli-status-not-running = not running
+cli-status-start-hint = Start with: openclaw gateway run
+cli-status-config-loaded = loaded
+cli-status-config-missing = not found
+cli-status-onboard-hint = Run 'openclaw onboard' to configure
+cli-status-sandbox-missing = not available
+cli-status-providers = { $count ->
+    [one] { $count } provider
+   *[other] { $count } providers
+}
+cli-status-no-keys = no API keys
+cli-status-not-configured = not configured
+cli-approvals-none = No tool calls waiting for approval.
+cli-approvals-approved = Approved { $id }
+cli-approvals-denied = Denied { $id }
+cli-peers-none = No peer profiles.
+cli-peers-updated = Updated { $peer }
+cli-peers-deleted = Deleted { $peer }
+cli-peers-missing = No profile for { $peer }
+cli-privacy-forget-warning = Erasing everything stored about { $peer }. This cannot be undone.
+cli-privacy-forgotten = Forgot { $peer }
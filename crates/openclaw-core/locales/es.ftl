@@ -0,0 +1,56 @@
+# Mensajes para las personas que chatean con los agentes
+
+rate-limited = Estás enviando mensajes demasiado rápido. Espera { $retry_after } s y vuelve a intentarlo.
+not-allowed = Lo siento, no tienes permiso para usar este asistente.
+feedback-thanks = ¡Gracias por tu opinión!
+attachments-rejected = No pude aceptar { $files }.
+attachment-too-large = mayor de { $max }
+attachment-mime-mismatch = el contenido es { $detected }, no { $claimed }
+attachment-infected = detectado por el antivirus ({ $signature })
+attachment-unscanned = no se pudo analizar
+moderation-blocked = Lo siento, no puedo responder a eso.
+outside-hours = Estamos fuera del horario de atención hasta { $opens_at }.
+outside-hours-closed = Estamos fuera del horario de atención.
+outside-hours-queued = Estamos fuera del horario de atención hasta { $opens_at }. Responderemos a tu mensaje entonces.
+outside-hours-urgent = Si no puede esperar, vuelve a enviarlo empezando con /urgent.
+urgent-open = Entendido. Los mensajes que envíes en los próximos { $minutes } minutos se responderán de inmediato.
+command-not-allowed = No puedes usar /{ $command } aquí.
+session-reset = Hecho. Empezamos una conversación nueva.
+model-current = Esta conversación usa { $model }. Puedes cambiar a: { $models }.
+model-fixed = Esta conversación usa { $model }, que no se puede cambiar.
+model-switched = Cambiado a { $model }.
+model-unknown = { $model } no está disponible. Puedes cambiar a: { $models }.
+usage-summary = Esta conversación ha usado { $total } tokens ({ $input } de entrada, { $output } de salida) en { $responses } respuestas.
+commands-help = Comandos disponibles: { $commands }.
+
+# Salida de la CLI
+
+cli-gateway-unreachable = No se pudo conectar con el gateway en el puerto { $port }: { $error }
+cli-gateway-unreachable-socket = No se pudo conectar con el gateway en { $path }: { $error }
+cli-status-title = Estado de OpenClaw
+cli-status-gateway = Gateway
+cli-status-config = Configuración
+cli-status-sandbox = Sandbox
+cli-status-credentials = Credenciales
+cli-status-running = en ejecución
+cli-status-not-running = detenido
+cli-status-start-hint = Inícialo con: openclaw gateway run
+cli-status-config-loaded = cargada
+cli-status-config-missing = no encontrada
+cli-status-onboard-hint = Ejecuta 'openclaw onboard' para configurarlo
+cli-status-sandbox-missing = no disponible
+cli-status-providers = { $count ->
+    [one] { $count } proveedor
+   *[other] { $count } proveedores
+}
+cli-status-no-keys = sin claves de API
+cli-status-not-configured = sin configurar
+cli-approvals-none = No hay llamadas a herramientas pendientes de aprobación.
+cli-approvals-approved = { $id } aprobada
+cli-approvals-denied = { $id } denegada
+cli-peers-none = No hay perfiles.
+cli-peers-updated = { $peer } actualizado
+cli-peers-deleted = { $peer } eliminado
+cli-peers-missing = No hay perfil para { $peer }
+cli-privacy-forget-warning = Se borrará todo lo almacenado sobre { $peer }. No se puede deshacer.
+cli-privacy-forgotten = { $peer } olvidado
@@ -0,0 +1,56 @@
+# Messages envoyés aux personnes qui discutent avec les agents
+
+rate-limited = Vous envoyez des messages trop rapidement. Patientez { $retry_after } s puis réessayez.
+not-allowed = Désolé, vous n'êtes pas autorisé à utiliser cet assistant.
+feedback-thanks = Merci pour votre retour !
+attachments-rejected = Je n'ai pas pu accepter { $files }.
+attachment-too-large = plus de { $max }
+attachment-mime-mismatch = le contenu est { $detected }, pas { $claimed }
+attachment-infected = signalé par l'antivirus ({ $signature })
+attachment-unscanned = n'a pas pu être analysé
+moderation-blocked = Désolé, je ne peux pas répondre à cela.
+outside-hours = Nous sommes en dehors des heures d'ouverture jusqu'à { $opens_at }.
+outside-hours-closed = Nous sommes en dehors des heures d'ouverture.
+outside-hours-queued = Nous sommes en dehors des heures d'ouverture jusqu'à { $opens_at }. Nous répondrons à votre message à ce moment-là.
+outside-hours-urgent = Si cela ne peut pas attendre, renvoyez-le en commençant par /urgent.
+urgent-open = C'est noté. Les messages envoyés dans les { $minutes } prochaines minutes recevront une réponse immédiate.
+command-not-allowed = Vous ne pouvez pas utiliser /{ $command } ici.
+session-reset = C'est fait. Nous repartons sur une nouvelle conversation.
+model-current = Cette conversation utilise { $model }. Modèles disponibles : { $models }.
+model-fixed = Cette conversation utilise { $model }, qui ne peut pas être changé.
+model-switched = Modèle changé pour { $model }.
+model-unknown = { $model } n'est pas disponible. Modèles disponibles : { $models }.
+usage-summary = Cette conversation a utilisé { $total } jetons ({ $input } en entrée, { $output } en sortie) sur { $responses } réponses.
+commands-help = Commandes disponibles : { $commands }.
+
+# Sortie de la CLI
+
+cli-gateway-unreachable = Impossible de joindre le gateway sur le port { $port } : { $error }
+cli-gateway-unreachable-socket = Impossible de joindre le gateway sur { $path } : { $error }
+cli-status-title = État d'OpenClaw
+cli-status-gateway = Gateway
+cli-status-config = Configuration
+cli-status-sandbox = Sandbox
+cli-status-credentials = Identifiants
+cli-status-running = en cours d'exécution
+cli-status-not-running = arrêté
+cli-status-start-hint = Démarrez-le avec : openclaw gateway run
+cli-status-config-loaded = chargée
+cli-status-config-missing = introuvable
+cli-status-onboard-hint = Lancez 'openclaw onboard' pour le configurer
+cli-status-sandbox-missing = indisponible
+cli-status-providers = { $count ->
+    [one] { $count } fournisseur
+   *[other] { $count } fournisseurs
+}
+cli-status-no-keys = aucune clé d'API
+cli-status-not-configured = non configuré
+cli-approvals-none = Aucun appel d'outil en attente d'approbation.
+cli-approvals-approved = { $id } approuvé
+cli-approvals-denied = { $id } refusé
+cli-peers-none = Aucun profil.
+cli-peers-updated = { $peer } mis à jour
+cli-peers-deleted = { $peer } supprimé
+cli-peers-missing = Aucun profil pour { $peer }
+cli-privacy-forget-warning = Toutes les données stockées sur { $peer } vont être effacées. Cette action est irréversible.
+cli-privacy-forgotten = { $peer } oublié
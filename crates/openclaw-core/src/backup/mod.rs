@@ -0,0 +1,690 @@
+//! Encrypted backups to object storage.
+//!
+//! A [`Snapshot`] collects event stores, their session archives, the config
+//! file, and stored credentials. It is packed into a zstd-compressed tar
+//! archive and split into ranges, each encrypted and uploaded separately
+//! under `backups/<id>/` in the configured blob store. A manifest recording
+//! the offset, length, and SHA-256 of every range and file is uploaded
+//! last, encrypted with the same key, so a backup without a manifest is
+//! incomplete and never listed. Every range and file is checked against
+//! the manifest before a backup is restored.
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Component, Path};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::config::EventStoreBackend;
+use crate::events::{EventStore, EventStoreError};
+use crate::secrets::{CredentialError, EncryptionKey};
+use crate::storage::{BlobNamespace, BlobStore, StorageError};
+
+/// Backup format version written to new manifests.
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// Name of the gateway's default event store in a snapshot.
+pub const DEFAULT_EVENT_STORE: &str = "default";
+
+/// Size of each uploaded range before encryption.
+const RANGE_SIZE: usize = 8 * 1024 * 1024;
+
+/// zstd compression level for snapshot archives.
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Snapshot entry holding the config file.
+const CONFIG_ENTRY: &str = "config";
+
+/// Backup errors.
+#[derive(Error, Debug)]
+pub enum BackupError {
+    /// Blob store error.
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
+
+    /// Event store error.
+    #[error("Event store error: {0}")]
+    Events(#[from] EventStoreError),
+
+    /// Encryption failed.
+    #[error("Encryption error: {0}")]
+    Crypto(#[from] CredentialError),
+
+    /// IO error reading or writing local files.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Manifest serialization error.
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    /// A range, file, or manifest does not match what was backed up.
+    #[error("Integrity check failed: {0}")]
+    Integrity(String),
+
+    /// No backup with this ID.
+    #[error("Backup not found: {0}")]
+    NotFound(String),
+}
+
+/// Name of a namespace's event store in a snapshot.
+#[must_use]
+pub fn namespace_event_store(id: &str) -> String {
+    format!("namespaces/{id}")
+}
+
+/// Files to back up, by entry name.
+#[derive(Debug, Default)]
+pub struct Snapshot {
+    entries: BTreeMap<String, Vec<u8>>,
+}
+
+impl Snapshot {
+    /// Create an empty snapshot.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an event store under `name`, with the session archives in
+    /// `archive_dir`. Returns the number of store entries added.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the store or an archive cannot be read.
+    pub fn add_event_store(
+        &mut self,
+        name: &str,
+        store: &EventStore,
+        archive_dir: Option<&Path>,
+    ) -> Result<usize, BackupError> {
+        let mut data = Vec::new();
+        let entries = store.export_snapshot(&mut data)?;
+        self.entries.insert(format!("events/{name}.snapshot"), data);
+        if let Some(dir) = archive_dir {
+            self.add_dir(&format!("archives/{name}"), dir)?;
+        }
+        Ok(entries)
+    }
+
+    /// Add the config file, if it exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be read.
+    pub fn add_config(&mut self, path: &Path) -> Result<(), BackupError> {
+        if path.exists() {
+            self.entries
+                .insert(CONFIG_ENTRY.to_string(), std::fs::read(path)?);
+        }
+        Ok(())
+    }
+
+    /// Add every stored credential. Credential files are already encrypted
+    /// with the credentials key and are copied as they are.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the directory cannot be read.
+    pub fn add_credentials(&mut self, dir: &Path) -> Result<usize, BackupError> {
+        self.add_dir("credentials", dir)
+    }
+
+    /// Names of the event stores in the snapshot.
+    #[must_use]
+    pub fn event_stores(&self) -> Vec<&str> {
+        self.entries
+            .keys()
+            .filter_map(|name| name.strip_prefix("events/")?.strip_suffix(".snapshot"))
+            .collect()
+    }
+
+    /// Recreate the event store `name` at `path`, and its archives in
+    /// `archive_dir`. Returns the number of store entries restored.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the snapshot has no such store, a store already
+    /// exists at `path`, or a file cannot be written.
+    pub fn restore_event_store(
+        &self,
+        name: &str,
+        path: &Path,
+        backend: EventStoreBackend,
+        archive_dir: Option<&Path>,
+    ) -> Result<usize, BackupError> {
+        let data = self
+            .entries
+            .get(&format!("events/{name}.snapshot"))
+            .ok_or_else(|| BackupError::NotFound(format!("event store '{name}'")))?;
+        let restored = EventStore::restore_snapshot(path, backend, &mut &data[..])?;
+        if let Some(dir) = archive_dir {
+            self.extract_dir(&format!("archives/{name}"), dir)?;
+        }
+        Ok(restored)
+    }
+
+    /// Contents of the config file, if it was backed up.
+    #[must_use]
+    pub fn config(&self) -> Option<&[u8]> {
+        self.entries.get(CONFIG_ENTRY).map(Vec::as_slice)
+    }
+
+    /// Write the backed-up credentials into `dir`, returning how many were
+    /// written.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a file cannot be written.
+    pub fn restore_credentials(&self, dir: &Path) -> Result<usize, BackupError> {
+        self.extract_dir("credentials", dir)
+    }
+
+    /// Add every file below `dir` as `<prefix>/<relative path>`.
+    fn add_dir(&mut self, prefix: &str, dir: &Path) -> Result<usize, BackupError> {
+        if !dir.exists() {
+            return Ok(0);
+        }
+        let mut added = 0;
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(current) = pending.pop() {
+            for entry in std::fs::read_dir(&current)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    pending.push(path);
+                    continue;
+                }
+                let relative = path
+                    .strip_prefix(dir)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                self.entries
+                    .insert(format!("{prefix}/{relative}"), std::fs::read(&path)?);
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+
+    /// Write every entry under `prefix/` to `dir`.
+    fn extract_dir(&self, prefix: &str, dir: &Path) -> Result<usize, BackupError> {
+        let prefix = format!("{prefix}/");
+        let mut written = 0;
+        for (name, data) in &self.entries {
+            let Some(relative) = name.strip_prefix(&prefix) else {
+                continue;
+            };
+            let relative = Path::new(relative);
+            if !relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+            {
+                return Err(BackupError::Integrity(format!(
+                    "unsafe entry name '{name}'"
+                )));
+            }
+            let path = dir.join(relative);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, data)?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// Manifest records for every entry.
+    fn files(&self) -> Vec<BackupFile> {
+        self.entries
+            .iter()
+            .map(|(name, data)| BackupFile {
+                name: name.clone(),
+                size: data.len() as u64,
+                sha256: sha256_hex(data),
+            })
+            .collect()
+    }
+
+    /// Pack into a compressed tar archive.
+    fn pack(&self) -> Result<Vec<u8>, BackupError> {
+        let encoder = zstd::Encoder::new(Vec::new(), COMPRESSION_LEVEL)?;
+        let mut builder = tar::Builder::new(encoder);
+        for (name, data) in &self.entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o600);
+            header.set_cksum();
+            builder.append_data(&mut header, name, data.as_slice())?;
+        }
+        Ok(builder.into_inner()?.finish()?)
+    }
+
+    /// Unpack an archive, checking every file against `files`.
+    fn unpack(archive: &[u8], files: &[BackupFile]) -> Result<Self, BackupError> {
+        let mut entries = BTreeMap::new();
+        let mut tar = tar::Archive::new(zstd::Decoder::new(archive)?);
+        for entry in tar.entries()? {
+            let mut entry = entry?;
+            let name = entry.path()?.to_string_lossy().into_owned();
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)?;
+            entries.insert(name, data);
+        }
+
+        let snapshot = Self { entries };
+        let unpacked = snapshot.files();
+        if unpacked.len() != files.len() {
+            return Err(BackupError::Integrity(format!(
+                "archive holds {} files, manifest lists {}",
+                unpacked.len(),
+                files.len()
+            )));
+        }
+        for (file, expected) in unpacked.iter().zip(files) {
+            if file != expected {
+                return Err(BackupError::Integrity(format!(
+                    "file '{}' does not match the manifest",
+                    expected.name
+                )));
+            }
+        }
+        Ok(snapshot)
+    }
+}
+
+/// A file in a backup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupFile {
+    /// Entry name.
+    pub name: String,
+    /// Size in bytes.
+    pub size: u64,
+    /// SHA-256 of the contents, hex encoded.
+    pub sha256: String,
+}
+
+/// One uploaded range of a backup archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupRange {
+    /// Offset of the range in the archive.
+    pub offset: u64,
+    /// Length of the range in the archive.
+    pub length: u64,
+    /// Size of the encrypted object.
+    pub stored_size: u64,
+    /// SHA-256 of the encrypted object, hex encoded.
+    pub sha256: String,
+}
+
+/// Description of a backup, uploaded after all of its ranges.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    /// Backup ID (a ULID, so IDs sort by creation time).
+    pub id: String,
+    /// When the backup was taken.
+    pub created_at: DateTime<Utc>,
+    /// Backup format version.
+    pub format_version: u32,
+    /// Files in the archive, by name.
+    pub files: Vec<BackupFile>,
+    /// Uploaded ranges, in archive order.
+    pub ranges: Vec<BackupRange>,
+    /// Archive size in bytes.
+    pub size: u64,
+    /// SHA-256 of the archive, hex encoded.
+    pub sha256: String,
+}
+
+/// Encrypted backups in a blob store.
+#[derive(Debug, Clone)]
+pub struct BackupStore {
+    blobs: BlobStore,
+    key: EncryptionKey,
+}
+
+impl BackupStore {
+    /// Store backups in `blobs`, encrypted with `key`.
+    #[must_use]
+    pub const fn new(blobs: BlobStore, key: EncryptionKey) -> Self {
+        Self { blobs, key }
+    }
+
+    /// Upload a snapshot as a new backup.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the snapshot cannot be packed or an upload fails.
+    /// Ranges uploaded before a failure are removed.
+    pub async fn upload(&self, snapshot: &Snapshot) -> Result<BackupManifest, BackupError> {
+        let archive = snapshot.pack()?;
+        let mut manifest = BackupManifest {
+            id: Ulid::new().to_string(),
+            created_at: Utc::now(),
+            format_version: BACKUP_FORMAT_VERSION,
+            files: snapshot.files(),
+            ranges: Vec::new(),
+            size: archive.len() as u64,
+            sha256: sha256_hex(&archive),
+        };
+
+        let uploaded = self.upload_archive(&mut manifest, &archive).await;
+        if let Err(e) = uploaded {
+            let _ = self.delete_objects(&manifest.id).await;
+            return Err(e);
+        }
+        Ok(manifest)
+    }
+
+    /// Every complete backup, newest first. Backups whose manifest cannot
+    /// be read are skipped with a warning.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the blob store cannot be listed.
+    pub async fn list(&self) -> Result<Vec<BackupManifest>, BackupError> {
+        let mut manifests = Vec::new();
+        for object in self.blobs.list(BlobNamespace::Backups).await? {
+            let Some(id) = object.key.strip_suffix("/manifest") else {
+                continue;
+            };
+            match self.manifest(id).await {
+                Ok(manifest) => manifests.push(manifest),
+                Err(e) => tracing::warn!("Skipping backup {}: {}", id, e),
+            }
+        }
+        manifests.sort_by(|a, b| b.id.cmp(&a.id));
+        Ok(manifests)
+    }
+
+    /// Read and authenticate a backup's manifest.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if there is no such backup, or `Integrity` if the
+    /// manifest cannot be decrypted with this key or was altered.
+    pub async fn manifest(&self, id: &str) -> Result<BackupManifest, BackupError> {
+        let sealed = match self
+            .blobs
+            .get(BlobNamespace::Backups, &manifest_key(id))
+            .await
+        {
+            Ok(sealed) => sealed,
+            Err(StorageError::NotFound(_) | StorageError::InvalidKey(_)) => {
+                return Err(BackupError::NotFound(id.to_string()));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let data = self.key.decrypt(&sealed).map_err(|_| {
+            BackupError::Integrity(format!(
+                "manifest of backup {id} cannot be decrypted; it was altered or written with a different master key"
+            ))
+        })?;
+        let manifest: BackupManifest = serde_json::from_slice(&data)?;
+        if manifest.id != id {
+            return Err(BackupError::Integrity(format!(
+                "manifest at {id} belongs to backup {}",
+                manifest.id
+            )));
+        }
+        if manifest.format_version > BACKUP_FORMAT_VERSION {
+            return Err(BackupError::Integrity(format!(
+                "backup {id} uses format version {}, newer than the supported version {BACKUP_FORMAT_VERSION}",
+                manifest.format_version
+            )));
+        }
+        Ok(manifest)
+    }
+
+    /// Download a backup and check every range and file against its
+    /// manifest.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if there is no such backup, `Integrity` if
+    /// anything does not match, or a storage error if a download fails.
+    pub async fn verify(&self, id: &str) -> Result<BackupManifest, BackupError> {
+        let (manifest, _) = self.download(id).await?;
+        Ok(manifest)
+    }
+
+    /// Download and verify a backup (see [`BackupStore::verify`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`BackupStore::verify`].
+    pub async fn download(&self, id: &str) -> Result<(BackupManifest, Snapshot), BackupError> {
+        let manifest = self.manifest(id).await?;
+        let mut archive = Vec::with_capacity(usize::try_from(manifest.size).unwrap_or_default());
+
+        for (index, range) in manifest.ranges.iter().enumerate() {
+            let sealed = self
+                .blobs
+                .get(BlobNamespace::Backups, &range_key(id, index))
+                .await
+                .map_err(|e| match e {
+                    StorageError::NotFound(_) => {
+                        BackupError::Integrity(format!("range {index} is missing"))
+                    }
+                    e => e.into(),
+                })?;
+            if sealed.len() as u64 != range.stored_size || sha256_hex(&sealed) != range.sha256 {
+                return Err(BackupError::Integrity(format!(
+                    "range {index} does not match the manifest"
+                )));
+            }
+            let data = self.key.decrypt(&sealed).map_err(|_| {
+                BackupError::Integrity(format!("range {index} cannot be decrypted"))
+            })?;
+            if archive.len() as u64 != range.offset || data.len() as u64 != range.length {
+                return Err(BackupError::Integrity(format!(
+                    "range {index} is out of place"
+                )));
+            }
+            archive.extend_from_slice(&data);
+        }
+
+        if archive.len() as u64 != manifest.size || sha256_hex(&archive) != manifest.sha256 {
+            return Err(BackupError::Integrity(
+                "archive does not match the manifest".to_string(),
+            ));
+        }
+        let snapshot = Snapshot::unpack(&archive, &manifest.files)?;
+        Ok((manifest, snapshot))
+    }
+
+    /// Delete a backup.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the blob store cannot be listed or a delete fails.
+    pub async fn delete(&self, id: &str) -> Result<(), BackupError> {
+        // Manifest first, so a partly deleted backup is no longer listed
+        self.blobs
+            .delete(BlobNamespace::Backups, &manifest_key(id))
+            .await?;
+        self.delete_objects(id).await
+    }
+
+    /// Delete all but the newest `keep` backups, returning the deleted IDs.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if listing or a delete fails.
+    pub async fn prune(&self, keep: usize) -> Result<Vec<String>, BackupError> {
+        let mut deleted = Vec::new();
+        for manifest in self.list().await?.into_iter().skip(keep) {
+            self.delete(&manifest.id).await?;
+            deleted.push(manifest.id);
+        }
+        Ok(deleted)
+    }
+
+    /// Upload every range of `archive`, then the manifest.
+    async fn upload_archive(
+        &self,
+        manifest: &mut BackupManifest,
+        archive: &[u8],
+    ) -> Result<(), BackupError> {
+        let mut offset = 0;
+        for (index, chunk) in archive.chunks(RANGE_SIZE).enumerate() {
+            let sealed = self.key.encrypt(chunk)?;
+            manifest.ranges.push(BackupRange {
+                offset,
+                length: chunk.len() as u64,
+                stored_size: sealed.len() as u64,
+                sha256: sha256_hex(&sealed),
+            });
+            self.blobs
+                .put(
+                    BlobNamespace::Backups,
+                    &range_key(&manifest.id, index),
+                    sealed,
+                )
+                .await?;
+            offset += chunk.len() as u64;
+        }
+
+        let sealed = self.key.encrypt(&serde_json::to_vec(&*manifest)?)?;
+        self.blobs
+            .put(BlobNamespace::Backups, &manifest_key(&manifest.id), sealed)
+            .await?;
+        Ok(())
+    }
+
+    /// Delete every object stored for a backup.
+    async fn delete_objects(&self, id: &str) -> Result<(), BackupError> {
+        let prefix = format!("{id}/");
+        for object in self.blobs.list(BlobNamespace::Backups).await? {
+            if object.key.starts_with(&prefix) {
+                self.blobs
+                    .delete(BlobNamespace::Backups, &object.key)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn manifest_key(id: &str) -> String {
+    format!("{id}/manifest")
+}
+
+fn range_key(id: &str, index: usize) -> String {
+    format!("{id}/range-{index:05}")
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{SessionEvent, SessionEventKind};
+    use crate::types::SessionKey;
+
+    fn backup_store(root: &Path) -> BackupStore {
+        BackupStore::new(
+            BlobStore::local(&root.join("blobs")).unwrap(),
+            EncryptionKey::new([7; 32]),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_backup_roundtrip() {
+        let temp = tempfile::tempdir().unwrap();
+        let key = SessionKey::new("s");
+        let config = temp.path().join("openclaw.json");
+        std::fs::write(&config, "{}").unwrap();
+        let credentials = temp.path().join("credentials");
+        std::fs::create_dir_all(&credentials).unwrap();
+        std::fs::write(credentials.join("openai"), b"sealed").unwrap();
+
+        let mut snapshot = Snapshot::new();
+        {
+            let store = EventStore::open(&temp.path().join("events")).unwrap();
+            store
+                .append(&SessionEvent::new(
+                    key.clone(),
+                    "agent".to_string(),
+                    SessionEventKind::SessionStarted {
+                        channel: "telegram".to_string(),
+                        peer_id: "peer".to_string(),
+                    },
+                ))
+                .unwrap();
+            snapshot
+                .add_event_store(DEFAULT_EVENT_STORE, &store, None)
+                .unwrap();
+        }
+        snapshot.add_config(&config).unwrap();
+        assert_eq!(snapshot.add_credentials(&credentials).unwrap(), 1);
+
+        let backups = backup_store(temp.path());
+        let manifest = backups.upload(&snapshot).await.unwrap();
+        assert_eq!(manifest.files.len(), 3);
+        assert_eq!(backups.list().await.unwrap()[0].id, manifest.id);
+
+        let (_, restored) = backups.download(&manifest.id).await.unwrap();
+        assert_eq!(restored.config(), Some(&b"{}"[..]));
+        assert_eq!(restored.event_stores(), vec![DEFAULT_EVENT_STORE]);
+        let target = temp.path().join("restored");
+        restored
+            .restore_event_store(DEFAULT_EVENT_STORE, &target, EventStoreBackend::Sled, None)
+            .unwrap();
+        let store = EventStore::open(&target).unwrap();
+        assert_eq!(store.get_events(&key).unwrap().len(), 1);
+        let dir = temp.path().join("restored-credentials");
+        assert_eq!(restored.restore_credentials(&dir).unwrap(), 1);
+        assert_eq!(std::fs::read(dir.join("openai")).unwrap(), b"sealed");
+
+        assert!(backups.prune(0).await.unwrap().contains(&manifest.id));
+        assert!(backups.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_detects_tampering() {
+        let temp = tempfile::tempdir().unwrap();
+        let config = temp.path().join("openclaw.json");
+        std::fs::write(&config, "{}").unwrap();
+        let mut snapshot = Snapshot::new();
+        snapshot.add_config(&config).unwrap();
+
+        let backups = backup_store(temp.path());
+        let manifest = backups.upload(&snapshot).await.unwrap();
+        backups.verify(&manifest.id).await.unwrap();
+
+        let range = range_key(&manifest.id, 0);
+        let mut data = backups
+            .blobs
+            .get(BlobNamespace::Backups, &range)
+            .await
+            .unwrap()
+            .to_vec();
+        data[20] ^= 1;
+        backups
+            .blobs
+            .put(BlobNamespace::Backups, &range, data)
+            .await
+            .unwrap();
+        assert!(matches!(
+            backups.verify(&manifest.id).await,
+            Err(BackupError::Integrity(_))
+        ));
+
+        let other = BackupStore::new(backups.blobs.clone(), EncryptionKey::new([8; 32]));
+        assert!(matches!(
+            other.manifest(&manifest.id).await,
+            Err(BackupError::Integrity(_))
+        ));
+        assert!(matches!(
+            backups.manifest("missing").await,
+            Err(BackupError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,216 @@
+//! Configuration inheritance.
+//!
+//! An agent with `extends: "base"` starts from the resolved `base` agent and
+//! overrides only the fields it sets. Objects merge key by key; arrays and
+//! scalars replace the inherited value. Sandbox profiles inherit the same
+//! way, and a tool's sandbox in an agent can extend a named profile.
+
+use serde_json::{Map, Value};
+
+use super::ConfigError;
+
+/// Resolve `extends` chains in the raw `agents` map.
+///
+/// # Errors
+///
+/// Returns `Validation` if an agent extends an unknown agent or the chain
+/// contains a cycle.
+pub fn resolve_agents(agents: &Map<String, Value>) -> Result<Map<String, Value>, ConfigError> {
+    resolve_all("Agent", agents)
+}
+
+/// Resolve `extends` chains in the raw `sandboxProfiles` map.
+///
+/// # Errors
+///
+/// Returns `Validation` if a profile extends an unknown profile or the
+/// chain contains a cycle.
+pub fn resolve_profiles(profiles: &Map<String, Value>) -> Result<Map<String, Value>, ConfigError> {
+    resolve_all("Sandbox profile", profiles)
+}
+
+/// Apply named profiles to the per-tool `sandbox` entries of resolved
+/// agents.
+///
+/// # Errors
+///
+/// Returns `Validation` if a tool sandbox extends an unknown profile.
+pub fn resolve_tool_sandboxes(
+    agents: &mut Map<String, Value>,
+    profiles: &Map<String, Value>,
+) -> Result<(), ConfigError> {
+    for (id, agent) in agents.iter_mut() {
+        let Some(sandboxes) = agent.get_mut("sandbox").and_then(Value::as_object_mut) else {
+            continue;
+        };
+        for (tool, sandbox) in sandboxes.iter_mut() {
+            let Some(name) = sandbox.get("extends").and_then(Value::as_str) else {
+                continue;
+            };
+            let mut base = profiles.get(name).cloned().ok_or_else(|| {
+                ConfigError::Validation(format!(
+                    "Agent '{id}' sandbox for '{tool}' extends unknown profile '{name}'"
+                ))
+            })?;
+            merge(&mut base, sandbox.clone());
+            *sandbox = base;
+        }
+    }
+    Ok(())
+}
+
+fn resolve_all(
+    kind: &str,
+    entries: &Map<String, Value>,
+) -> Result<Map<String, Value>, ConfigError> {
+    let mut resolved = Map::new();
+    for id in entries.keys() {
+        resolve_entry(kind, id, entries, &mut Vec::new(), &mut resolved)?;
+    }
+    Ok(resolved)
+}
+
+fn resolve_entry(
+    kind: &str,
+    id: &str,
+    entries: &Map<String, Value>,
+    chain: &mut Vec<String>,
+    resolved: &mut Map<String, Value>,
+) -> Result<Value, ConfigError> {
+    if let Some(done) = resolved.get(id) {
+        return Ok(done.clone());
+    }
+
+    if chain.iter().any(|c| c == id) {
+        chain.push(id.to_string());
+        return Err(ConfigError::Validation(format!(
+            "{kind} inheritance cycle: {}",
+            chain.join(" -> ")
+        )));
+    }
+
+    let declared = entries.get(id).ok_or_else(|| {
+        ConfigError::Validation(format!("Unknown {} '{id}'", kind.to_lowercase()))
+    })?;
+
+    let value = match declared.get("extends").and_then(Value::as_str) {
+        Some(parent) => {
+            if !entries.contains_key(parent) {
+                return Err(ConfigError::Validation(format!(
+                    "{kind} '{id}' extends unknown {} '{parent}'",
+                    kind.to_lowercase()
+                )));
+            }
+
+            chain.push(id.to_string());
+            let mut base = resolve_entry(kind, parent, entries, chain, resolved)?;
+            chain.pop();
+
+            merge(&mut base, declared.clone());
+            base
+        }
+        None => declared.clone(),
+    };
+
+    resolved.insert(id.to_string(), value.clone());
+    Ok(value)
+}
+
+/// Overlay `overlay` onto `base`, merging objects recursively.
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn agents(value: Value) -> Map<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_chain() {
+        let resolved = resolve_agents(&agents(json!({
+            "base": {
+                "model": "claude-3-5-sonnet-20241022",
+                "tools": ["search", "remind"],
+                "concurrency": { "maxConcurrent": 4, "maxQueued": 8 }
+            },
+            "support": {
+                "extends": "base",
+                "systemPrompt": "You are a support agent.",
+                "concurrency": { "maxQueued": 32 }
+            },
+            "support-fr": {
+                "extends": "support",
+                "tools": ["search"]
+            }
+        })))
+        .unwrap();
+
+        let fr = &resolved["support-fr"];
+        assert_eq!(fr["model"], "claude-3-5-sonnet-20241022");
+        assert_eq!(fr["systemPrompt"], "You are a support agent.");
+        assert_eq!(fr["tools"], json!(["search"]));
+        assert_eq!(fr["concurrency"]["maxConcurrent"], 4);
+        assert_eq!(fr["concurrency"]["maxQueued"], 32);
+        assert_eq!(fr["extends"], "support");
+    }
+
+    #[test]
+    fn test_cycle_and_unknown_parent() {
+        let err = resolve_agents(&agents(json!({
+            "a": { "extends": "b" },
+            "b": { "extends": "a" }
+        })))
+        .unwrap_err();
+        assert!(err.to_string().contains("cycle"));
+
+        let err = resolve_agents(&agents(json!({
+            "a": { "extends": "missing" }
+        })))
+        .unwrap_err();
+        assert!(err.to_string().contains("unknown agent 'missing'"));
+    }
+
+    #[test]
+    fn test_tool_sandbox_profiles() {
+        let profiles = resolve_profiles(&agents(json!({
+            "locked": { "level": "paranoid", "maxMemoryMb": 256 },
+            "repo": { "extends": "locked", "level": "standard", "allowedPaths": ["/work"] }
+        })))
+        .unwrap();
+        assert_eq!(profiles["repo"]["maxMemoryMb"], 256);
+
+        let mut resolved = resolve_agents(&agents(json!({
+            "dev": { "sandbox": { "git": { "extends": "repo", "network": true } } }
+        })))
+        .unwrap();
+        resolve_tool_sandboxes(&mut resolved, &profiles).unwrap();
+        let git = &resolved["dev"]["sandbox"]["git"];
+        assert_eq!(git["level"], "standard");
+        assert_eq!(git["allowedPaths"], json!(["/work"]));
+        assert_eq!(git["network"], true);
+
+        let mut resolved = resolve_agents(&agents(json!({
+            "dev": { "sandbox": { "bash": { "extends": "missing" } } }
+        })))
+        .unwrap();
+        let err = resolve_tool_sandboxes(&mut resolved, &profiles).unwrap_err();
+        assert!(err.to_string().contains("unknown profile 'missing'"));
+    }
+}
@@ -8,6 +8,8 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
+mod inherit;
+
 /// Configuration errors.
 #[derive(Error, Debug)]
 pub enum ConfigError {
@@ -54,6 +56,22 @@ pub struct Config {
     /// Global settings.
     #[serde(default)]
     pub settings: GlobalSettings,
+
+    /// A/B experiments by ID.
+    #[serde(default)]
+    pub experiments: HashMap<String, ExperimentConfig>,
+
+    /// Scheduled tasks by ID.
+    #[serde(default)]
+    pub schedules: HashMap<String, ScheduleConfig>,
+
+    /// Outbound notification templates by name.
+    #[serde(default)]
+    pub notifications: HashMap<String, NotificationTemplateConfig>,
+
+    /// Named sandbox profiles that tool sandboxes can extend.
+    #[serde(default)]
+    pub sandbox_profiles: HashMap<String, SandboxProfileConfig>,
 }
 
 impl Config {
@@ -73,16 +91,80 @@ impl Config {
 
     /// Load configuration from a specific path.
     ///
+    /// Agents that `extends` another agent are resolved against their base.
+    ///
     /// # Errors
     ///
-    /// Returns error if file cannot be read or parsed.
+    /// Returns error if file cannot be read or parsed, or agent inheritance
+    /// is invalid.
     pub fn load(path: &Path) -> Result<Self, ConfigError> {
         let content = std::fs::read_to_string(path)?;
-        let config: Self = json5::from_str(&content)?;
+        Self::from_json5(&content)
+    }
+
+    /// Parse configuration from a JSON5 string.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the content cannot be parsed, agent or sandbox
+    /// profile inheritance is invalid, or validation fails.
+    pub fn from_json5(content: &str) -> Result<Self, ConfigError> {
+        let mut value: serde_json::Value = json5::from_str(content)?;
+        let profiles = match value
+            .get_mut("sandboxProfiles")
+            .and_then(serde_json::Value::as_object_mut)
+        {
+            Some(profiles) => {
+                *profiles = inherit::resolve_profiles(profiles)?;
+                profiles.clone()
+            }
+            None => serde_json::Map::new(),
+        };
+        if let Some(agents) = value
+            .get_mut("agents")
+            .and_then(serde_json::Value::as_object_mut)
+        {
+            *agents = inherit::resolve_agents(agents)?;
+            inherit::resolve_tool_sandboxes(agents, &profiles)?;
+        }
+
+        let config: Self =
+            serde_json::from_value(value).map_err(|e| ConfigError::Validation(e.to_string()))?;
         config.validate()?;
         Ok(config)
     }
 
+    /// Keys in a raw config document that no setting reads, as dotted
+    /// paths (`gateway.prot`).
+    ///
+    /// Checks top-level keys and the fields of each settings section; maps
+    /// keyed by user-chosen IDs (agents, experiments, ...) are not checked.
+    #[must_use]
+    pub fn unknown_keys(raw: &serde_json::Value) -> Vec<String> {
+        let known = serde_json::to_value(Self::default()).unwrap_or_default();
+        let (Some(raw), Some(known)) = (raw.as_object(), known.as_object()) else {
+            return vec![];
+        };
+
+        let mut unknown = Vec::new();
+        for (key, value) in raw {
+            match (known.get(key), value.as_object()) {
+                (None, _) => unknown.push(key.clone()),
+                (Some(serde_json::Value::Object(section)), Some(fields)) if !section.is_empty() => {
+                    unknown.extend(
+                        fields
+                            .keys()
+                            .filter(|field| !section.contains_key(*field))
+                            .map(|field| format!("{key}.{field}")),
+                    );
+                }
+                _ => {}
+            }
+        }
+        unknown.sort();
+        unknown
+    }
+
     /// Save configuration to a path.
     ///
     /// # Errors
@@ -138,8 +220,54 @@ impl Config {
         Self::state_dir().join("agents")
     }
 
+    /// Get the shared skills directory.
+    #[must_use]
+    pub fn skills_dir() -> PathBuf {
+        Self::state_dir().join("skills")
+    }
+
+    /// Get the prompt templates directory.
+    #[must_use]
+    pub fn prompts_dir() -> PathBuf {
+        Self::state_dir().join("prompts")
+    }
+
+    /// Get the local blob storage directory.
+    #[must_use]
+    pub fn blobs_dir() -> PathBuf {
+        Self::state_dir().join("blobs")
+    }
+
+    /// Get the log directory.
+    #[must_use]
+    pub fn logs_dir() -> PathBuf {
+        Self::state_dir().join("logs")
+    }
+
+    /// Get the agent workspace directory.
+    #[must_use]
+    pub fn workspace_dir() -> PathBuf {
+        Self::state_dir().join("workspace")
+    }
+
+    /// Get the recorded provider calls directory.
+    #[must_use]
+    pub fn recordings_dir() -> PathBuf {
+        Self::state_dir().join("recordings")
+    }
+
     /// Validate the configuration.
     fn validate(&self) -> Result<(), ConfigError> {
+        self.validate_gateway()?;
+        self.validate_agents()?;
+        self.validate_experiments()?;
+        self.validate_schedules()?;
+        self.validate_channels()?;
+        self.validate_broadcast()
+    }
+
+    /// Check the port, timeouts, and gateway sections.
+    fn validate_gateway(&self) -> Result<(), ConfigError> {
         // Validate gateway port
         if self.gateway.port == 0 {
             return Err(ConfigError::Validation(
@@ -147,13 +275,218 @@ impl Config {
             ));
         }
 
-        // Validate agent configs
+        // Validate timeouts
+        if let Some((key, _)) = self
+            .gateway
+            .route_timeouts
+            .iter()
+            .chain(&self.gateway.method_timeouts)
+            .find(|(_, secs)| **secs == 0)
+        {
+            return Err(ConfigError::Validation(format!(
+                "Timeout for '{key}' cannot be 0"
+            )));
+        }
+        self.gateway
+            .limits
+            .validate()
+            .map_err(|e| ConfigError::Validation(format!("Gateway limits: {e}")))?;
+        self.gateway
+            .cors
+            .validate()
+            .map_err(|e| ConfigError::Validation(format!("Gateway CORS: {e}")))?;
+        self.gateway
+            .proxy
+            .validate()
+            .map_err(|e| ConfigError::Validation(format!("Gateway proxy: {e}")))?;
+        self.gateway
+            .rate_limit
+            .validate()
+            .map_err(|e| ConfigError::Validation(format!("Gateway rate limit: {e}")))?;
+        if let Some(socket) = &self.gateway.unix_socket {
+            socket
+                .validate()
+                .map_err(|e| ConfigError::Validation(format!("Gateway Unix socket: {e}")))?;
+        }
+        if let Some(oidc) = &self.gateway.oidc {
+            oidc.validate()
+                .map_err(|e| ConfigError::Validation(format!("Gateway OIDC: {e}")))?;
+        }
+
+        Ok(())
+    }
+
+    /// Check agents and the sandbox profiles they share.
+    fn validate_agents(&self) -> Result<(), ConfigError> {
         for (id, agent) in &self.agents {
             if agent.model.is_empty() {
                 return Err(ConfigError::Validation(format!(
                     "Agent '{id}' has empty model"
                 )));
             }
+            agent
+                .tool_policy()
+                .validate()
+                .map_err(|e| ConfigError::Validation(format!("Agent '{id}' tools: {e}")))?;
+            if agent.concurrency.max_concurrent == Some(0) {
+                return Err(ConfigError::Validation(format!(
+                    "Agent '{id}' maxConcurrent cannot be 0"
+                )));
+            }
+            agent.tool_concurrency.validate().map_err(|e| {
+                ConfigError::Validation(format!("Agent '{id}' toolConcurrency: {e}"))
+            })?;
+            if agent.prompt.max_tokens == Some(0) {
+                return Err(ConfigError::Validation(format!(
+                    "Agent '{id}' prompt maxTokens cannot be 0"
+                )));
+            }
+            agent
+                .moderation
+                .validate()
+                .map_err(|e| ConfigError::Validation(format!("Agent '{id}' moderation: {e}")))?;
+            for (tool, profile) in &agent.sandbox {
+                profile.validate().map_err(|e| {
+                    ConfigError::Validation(format!("Agent '{id}' sandbox for '{tool}': {e}"))
+                })?;
+            }
+            if agent.workspace.quota_mb == Some(0) {
+                return Err(ConfigError::Validation(format!(
+                    "Agent '{id}' workspace quotaMb cannot be 0"
+                )));
+            }
+            if agent.workspace.expire_after_hours == Some(0) {
+                return Err(ConfigError::Validation(format!(
+                    "Agent '{id}' workspace expireAfterHours cannot be 0"
+                )));
+            }
+        }
+
+        // Validate sandbox profiles
+        for (name, profile) in &self.sandbox_profiles {
+            profile
+                .validate()
+                .map_err(|e| ConfigError::Validation(format!("Sandbox profile '{name}': {e}")))?;
+        }
+
+        Ok(())
+    }
+
+    /// Check that every experiment can pick a variant.
+    fn validate_experiments(&self) -> Result<(), ConfigError> {
+        for (id, experiment) in &self.experiments {
+            if experiment.variants.iter().map(|v| v.weight).sum::<u32>() == 0 {
+                return Err(ConfigError::Validation(format!(
+                    "Experiment '{id}' has no weighted variants"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check schedules and the notification templates they use.
+    fn validate_schedules(&self) -> Result<(), ConfigError> {
+        for (id, schedule) in &self.schedules {
+            if schedule.prompt.is_some() && schedule.workflow.is_some() {
+                return Err(ConfigError::Validation(format!(
+                    "Schedule '{id}' cannot set both prompt and workflow"
+                )));
+            }
+            match &schedule.template {
+                None if schedule.prompt.is_none() && schedule.workflow.is_none() => {
+                    return Err(ConfigError::Validation(format!(
+                        "Schedule '{id}' must set a prompt, workflow, or template"
+                    )));
+                }
+                Some(template) if !self.notifications.contains_key(template) => {
+                    return Err(ConfigError::Validation(format!(
+                        "Schedule '{id}' uses unknown notification template '{template}'"
+                    )));
+                }
+                Some(_) if schedule.deliver.is_none() => {
+                    return Err(ConfigError::Validation(format!(
+                        "Schedule '{id}' sets a template but no deliver target"
+                    )));
+                }
+                _ => {}
+            }
+        }
+
+        // Validate notification templates
+        for (name, template) in &self.notifications {
+            if template.body.trim().is_empty() {
+                return Err(ConfigError::Validation(format!(
+                    "Notification template '{name}' has an empty body"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check attachment limits, locales, and working hours.
+    fn validate_channels(&self) -> Result<(), ConfigError> {
+        // Validate attachment limits
+        let attachments = &self.channels.attachments;
+        let limits = attachments.max_bytes.iter().chain(
+            attachments
+                .channels
+                .values()
+                .flat_map(|c| c.max_bytes.iter()),
+        );
+        for (kind, max) in limits {
+            if serde_json::from_value::<crate::types::AttachmentKind>(serde_json::json!(kind))
+                .is_err()
+            {
+                return Err(ConfigError::Validation(format!(
+                    "Unknown attachment kind '{kind}' in maxBytes"
+                )));
+            }
+            if *max == 0 {
+                return Err(ConfigError::Validation(format!(
+                    "Attachment limit for '{kind}' must be positive"
+                )));
+            }
+        }
+
+        // Validate locales
+        let locale = &self.channels.locale;
+        if let Some(tag) = std::iter::once(&locale.default)
+            .chain(locale.channels.values())
+            .chain(locale.peers.values())
+            .find(|tag| !crate::i18n::is_locale(tag))
+        {
+            return Err(ConfigError::Validation(format!("Invalid locale '{tag}'")));
+        }
+
+        self.channels
+            .working_hours
+            .validate()
+            .map_err(|e| ConfigError::Validation(format!("Working hours for {e}")))?;
+
+        Ok(())
+    }
+
+    /// Check broadcast rates and groups.
+    fn validate_broadcast(&self) -> Result<(), ConfigError> {
+        let broadcast = &self.channels.broadcast;
+        if let Some(channel) = std::iter::once(("default", broadcast.per_second))
+            .chain(broadcast.channels.iter().map(|(c, r)| (c.as_str(), *r)))
+            .find_map(|(channel, rate)| (rate.is_nan() || rate <= 0.0).then_some(channel))
+        {
+            return Err(ConfigError::Validation(format!(
+                "Broadcast rate for '{channel}' must be positive"
+            )));
+        }
+        if let Some(name) = broadcast
+            .groups
+            .iter()
+            .find_map(|(name, group)| group.chat_ids.is_empty().then_some(name))
+        {
+            return Err(ConfigError::Validation(format!(
+                "Broadcast group '{name}' has no chat IDs"
+            )));
         }
 
         Ok(())
@@ -181,13 +514,72 @@ pub struct GatewayConfig {
     #[serde(default)]
     pub mode: BindMode,
 
-    /// Enable CORS.
-    #[serde(default = "default_true")]
-    pub cors: bool,
+    /// Which web pages may call the gateway from a browser. `true` and
+    /// `false` are accepted for the default policy and for none.
+    #[serde(default)]
+    pub cors: CorsConfig,
 
     /// Request timeout in seconds.
     #[serde(default = "default_timeout")]
     pub timeout_secs: u64,
+
+    /// Per-route timeouts in seconds, keyed by path (e.g. `/rpc`).
+    #[serde(default)]
+    pub route_timeouts: HashMap<String, u64>,
+
+    /// Per-RPC-method timeouts in seconds.
+    ///
+    /// Keys are method names (`session.message`) or namespaces (`system.*`).
+    #[serde(default = "default_method_timeouts")]
+    pub method_timeouts: HashMap<String, u64>,
+
+    /// Boot time in milliseconds above which startup logs a warning.
+    #[serde(default = "default_boot_budget_ms")]
+    pub boot_budget_ms: u64,
+
+    /// Seconds shutdown waits for in-flight agent runs to finish.
+    #[serde(default = "default_drain_timeout_secs")]
+    pub drain_timeout_secs: u64,
+
+    /// Serve the Model Context Protocol endpoint at `/mcp`.
+    #[serde(default)]
+    pub mcp: bool,
+
+    /// Tenant namespaces by ID.
+    ///
+    /// Each namespace owns its agents, channels, sessions, and credentials.
+    /// With none configured, every user sees every session.
+    #[serde(default)]
+    pub namespaces: HashMap<String, NamespaceConfig>,
+
+    /// Distributed mode; omit to run everything in one process.
+    #[serde(default)]
+    pub cluster: Option<ClusterConfig>,
+
+    /// Readiness checks served at `/readyz`.
+    #[serde(default)]
+    pub health: HealthConfig,
+
+    /// Request size and slow-client limits.
+    #[serde(default)]
+    pub limits: RequestLimitsConfig,
+
+    /// RPC request rates per caller.
+    #[serde(default)]
+    pub rate_limit: RpcRateLimitConfig,
+
+    /// Running behind a reverse proxy.
+    #[serde(default)]
+    pub proxy: ProxyConfig,
+
+    /// Listen on a Unix domain socket (`None` for TCP only).
+    #[serde(default)]
+    pub unix_socket: Option<UnixSocketConfig>,
+
+    /// Single sign-on through an `OpenID` Connect provider (`None` for local
+    /// passwords only).
+    #[serde(default)]
+    pub oidc: Option<OidcConfig>,
 }
 
 impl Default for GatewayConfig {
@@ -195,138 +587,2758 @@ impl Default for GatewayConfig {
         Self {
             port: default_port(),
             mode: BindMode::default(),
-            cors: true,
+            cors: CorsConfig::default(),
             timeout_secs: default_timeout(),
+            route_timeouts: HashMap::new(),
+            method_timeouts: default_method_timeouts(),
+            boot_budget_ms: default_boot_budget_ms(),
+            drain_timeout_secs: default_drain_timeout_secs(),
+            mcp: false,
+            namespaces: HashMap::new(),
+            cluster: None,
+            health: HealthConfig::default(),
+            limits: RequestLimitsConfig::default(),
+            rate_limit: RpcRateLimitConfig::default(),
+            proxy: ProxyConfig::default(),
+            unix_socket: None,
+            oidc: None,
         }
     }
 }
 
-const fn default_port() -> u16 {
-    18789
+/// Cross-origin access for browsers.
+///
+/// Pages from `allowedOrigins` may call the HTTP API and open `WebSockets`;
+/// pages from anywhere else get no CORS headers, and their WebSocket
+/// upgrades are refused. Clients that send no `Origin`, such as the CLI,
+/// are not affected.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", from = "CorsSetting")]
+pub struct CorsConfig {
+    /// Origins (`scheme://host[:port]`) allowed to call the gateway, or
+    /// `*` for any.
+    pub allowed_origins: Vec<String>,
+
+    /// Let browsers send cookies and HTTP auth with cross-origin calls.
+    pub allow_credentials: bool,
+
+    /// Request headers cross-origin calls may send.
+    pub allowed_headers: Vec<String>,
+
+    /// Seconds browsers may cache a preflight answer.
+    pub max_age_secs: u64,
 }
 
-const fn default_timeout() -> u64 {
-    300
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: default_cors_origins(),
+            allow_credentials: false,
+            allowed_headers: default_cors_headers(),
+            max_age_secs: default_cors_max_age_secs(),
+        }
+    }
 }
 
-const fn default_true() -> bool {
-    true
+impl CorsConfig {
+    /// A policy that allows no cross-origin calls.
+    #[must_use]
+    pub fn none() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            ..Self::default()
+        }
+    }
+
+    /// Whether any origin is allowed.
+    #[must_use]
+    pub fn allows_any_origin(&self) -> bool {
+        self.allowed_origins.iter().any(|origin| origin == "*")
+    }
+
+    /// Whether a page from `origin` may call the gateway.
+    #[must_use]
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        let origin = origin.trim_end_matches('/');
+        self.allowed_origins
+            .iter()
+            .any(|allowed| allowed == "*" || allowed.eq_ignore_ascii_case(origin))
+    }
+
+    /// Check that origins and headers are well formed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first bad entry.
+    pub fn validate(&self) -> Result<(), String> {
+        for origin in &self.allowed_origins {
+            if origin == "*" {
+                if self.allow_credentials {
+                    return Err("allowCredentials cannot be used with origin '*'".to_string());
+                }
+                continue;
+            }
+            let host = origin
+                .strip_prefix("http://")
+                .or_else(|| origin.strip_prefix("https://"));
+            if host.is_none_or(|host| host.is_empty() || host.contains('/')) {
+                return Err(format!(
+                    "Invalid origin '{origin}' (expected scheme://host[:port])"
+                ));
+            }
+        }
+        if let Some(header) = self.allowed_headers.iter().find(|header| {
+            header.is_empty()
+                || !header
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        }) {
+            return Err(format!("Invalid header name '{header}'"));
+        }
+        Ok(())
+    }
 }
 
-/// Gateway bind mode.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum BindMode {
-    /// Bind to localhost only.
-    #[default]
-    Local,
-    /// Bind to all interfaces.
-    Public,
-    /// Custom bind address.
-    Custom(String),
+/// How `cors` may be written: a full policy, or `true`/`false` as in older
+/// configs.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CorsSetting {
+    Enabled(bool),
+    Policy(CorsPolicy),
 }
 
-/// Agent configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct AgentConfig {
-    /// Model to use (e.g., "claude-3-5-sonnet-20241022").
-    #[serde(default = "default_model")]
-    pub model: String,
+struct CorsPolicy {
+    #[serde(default = "default_cors_origins")]
+    allowed_origins: Vec<String>,
+    #[serde(default)]
+    allow_credentials: bool,
+    #[serde(default = "default_cors_headers")]
+    allowed_headers: Vec<String>,
+    #[serde(default = "default_cors_max_age_secs")]
+    max_age_secs: u64,
+}
 
-    /// Provider to use.
-    #[serde(default = "default_provider")]
-    pub provider: String,
+impl From<CorsSetting> for CorsConfig {
+    fn from(setting: CorsSetting) -> Self {
+        match setting {
+            CorsSetting::Enabled(true) => Self::default(),
+            CorsSetting::Enabled(false) => Self::none(),
+            CorsSetting::Policy(policy) => Self {
+                allowed_origins: policy.allowed_origins,
+                allow_credentials: policy.allow_credentials,
+                allowed_headers: policy.allowed_headers,
+                max_age_secs: policy.max_age_secs,
+            },
+        }
+    }
+}
 
-    /// System prompt.
-    #[serde(default)]
-    pub system_prompt: Option<String>,
+/// Request size and slow-client limits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestLimitsConfig {
+    /// Largest HTTP request body in bytes. Upload chunks have their own
+    /// limit.
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: u64,
 
-    /// Maximum tokens in response.
-    #[serde(default = "default_max_tokens")]
-    pub max_tokens: u32,
+    /// Seconds a request body may stall between chunks before the request
+    /// is answered with `408`.
+    #[serde(default = "default_body_read_timeout_secs")]
+    pub body_read_timeout_secs: u64,
 
-    /// Temperature for sampling.
-    #[serde(default = "default_temperature")]
-    pub temperature: f32,
+    /// Largest WebSocket message in bytes; larger ones close the
+    /// connection with code 1009.
+    #[serde(default = "default_max_body_bytes")]
+    pub ws_max_message_bytes: u64,
 
-    /// Enabled tools.
-    #[serde(default)]
-    pub tools: Vec<String>,
+    /// Bytes of unsent WebSocket output buffered before sends fail.
+    #[serde(default = "default_ws_max_write_buffer_bytes")]
+    pub ws_max_write_buffer_bytes: u64,
 
-    /// Allowlist patterns for this agent.
+    /// Seconds a WebSocket send may wait on a client that is not reading
+    /// before the connection is closed.
+    #[serde(default = "default_ws_send_timeout_secs")]
+    pub ws_send_timeout_secs: u64,
+
+    /// What to do when a client falls behind the event stream.
     #[serde(default)]
-    pub allowlist: Vec<AllowlistEntry>,
+    pub ws_lag_policy: WsLagPolicy,
 }
 
-impl Default for AgentConfig {
+impl Default for RequestLimitsConfig {
     fn default() -> Self {
         Self {
-            model: default_model(),
-            provider: default_provider(),
-            system_prompt: None,
-            max_tokens: default_max_tokens(),
-            temperature: default_temperature(),
-            tools: vec![],
-            allowlist: vec![],
+            max_body_bytes: default_max_body_bytes(),
+            body_read_timeout_secs: default_body_read_timeout_secs(),
+            ws_max_message_bytes: default_max_body_bytes(),
+            ws_max_write_buffer_bytes: default_ws_max_write_buffer_bytes(),
+            ws_send_timeout_secs: default_ws_send_timeout_secs(),
+            ws_lag_policy: WsLagPolicy::default(),
         }
     }
 }
 
-fn default_model() -> String {
-    "claude-3-5-sonnet-20241022".to_string()
-}
-
-fn default_provider() -> String {
-    "anthropic".to_string()
-}
-
-const fn default_max_tokens() -> u32 {
-    4096
-}
-
-const fn default_temperature() -> f32 {
-    0.7
+impl RequestLimitsConfig {
+    /// Check that every limit is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first limit that is zero.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in [
+            ("maxBodyBytes", self.max_body_bytes),
+            ("bodyReadTimeoutSecs", self.body_read_timeout_secs),
+            ("wsMaxMessageBytes", self.ws_max_message_bytes),
+            ("wsMaxWriteBufferBytes", self.ws_max_write_buffer_bytes),
+            ("wsSendTimeoutSecs", self.ws_send_timeout_secs),
+        ] {
+            if value == 0 {
+                return Err(format!("{name} cannot be 0"));
+            }
+        }
+        Ok(())
+    }
 }
 
-/// Allowlist entry for agent access control.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Running behind a reverse proxy such as nginx or Traefik.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct AllowlistEntry {
-    /// Channel pattern (e.g., "telegram", "*").
-    pub channel: String,
+pub struct ProxyConfig {
+    /// Path prefix every gateway route is served under (e.g. `/openclaw`),
+    /// for proxies that forward a sub-path without stripping it. Empty for
+    /// none.
+    #[serde(default)]
+    pub base_path: String,
 
-    /// Peer ID pattern (e.g., "123456789", "*").
-    pub peer_id: String,
+    /// Addresses or CIDR ranges (`10.0.0.0/8`) of the proxies. Only
+    /// requests from these have their `X-Forwarded-For`, `-Proto` and
+    /// `-Host` headers believed.
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
 
-    /// Optional label for this entry.
+    /// URL clients reach the gateway at, base path included (e.g.
+    /// `https://claw.example.com/openclaw`), for links the gateway hands
+    /// out. Unset to work it out from each request.
     #[serde(default)]
-    pub label: Option<String>,
+    pub public_url: Option<String>,
 }
 
-/// Channel configurations.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+impl ProxyConfig {
+    /// The trusted proxy ranges.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first entry that is not an address or
+    /// CIDR range.
+    pub fn trusted_ranges(&self) -> Result<Vec<IpRange>, String> {
+        self.trusted_proxies.iter().map(|s| s.parse()).collect()
+    }
+
+    /// Check the base path, proxy ranges and public URL.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first bad setting.
+    pub fn validate(&self) -> Result<(), String> {
+        let path = &self.base_path;
+        if !path.is_empty() && (!path.starts_with('/') || path.ends_with('/')) {
+            return Err(format!(
+                "basePath '{path}' must start with '/' and not end with one"
+            ));
+        }
+        self.trusted_ranges()?;
+        if let Some(url) = &self.public_url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(format!("publicUrl '{url}' must be an http(s) URL"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A Unix domain socket the gateway listens on, for local clients that
+/// should not need an open port.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnixSocketConfig {
+    /// Socket file path. A stale socket left by a previous run is replaced.
+    pub path: PathBuf,
+
+    /// Permissions of the socket file, in octal (default `"600"`, the
+    /// gateway's user only). Connecting needs write permission.
+    #[serde(default = "default_socket_mode")]
+    pub mode: String,
+
+    /// Keep listening on TCP as well (default: true).
+    #[serde(default = "default_true")]
+    pub tcp: bool,
+}
+
+fn default_socket_mode() -> String {
+    "600".to_string()
+}
+
+impl UnixSocketConfig {
+    /// The socket file's permission bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description if `mode` is not an octal mode.
+    pub fn permissions(&self) -> Result<u32, String> {
+        u32::from_str_radix(self.mode.trim_start_matches("0o"), 8)
+            .ok()
+            .filter(|mode| *mode <= 0o777)
+            .ok_or_else(|| format!("mode '{}' is not an octal mode like 660", self.mode))
+    }
+
+    /// Check the path and mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first bad setting.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.path.as_os_str().is_empty() {
+            return Err("path cannot be empty".to_string());
+        }
+        self.permissions().map(|_| ())
+    }
+}
+
+/// An IP address, or a range of them in CIDR notation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpRange {
+    network: std::net::IpAddr,
+    prefix: u8,
+}
+
+impl IpRange {
+    /// Whether `ip` is in the range. IPv4 addresses mapped into IPv6 match
+    /// IPv4 ranges.
+    #[must_use]
+    pub fn contains(&self, ip: std::net::IpAddr) -> bool {
+        use std::net::IpAddr;
+
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+            IpAddr::V4(_) => ip,
+        };
+        match (self.network, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => prefix_matches(
+                u128::from(u32::from(net)),
+                u128::from(u32::from(ip)),
+                32,
+                self.prefix,
+            ),
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_matches(u128::from(net), u128::from(ip), 128, self.prefix)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Whether the top `prefix` of `bits` bits of `a` and `b` agree.
+const fn prefix_matches(a: u128, b: u128, bits: u8, prefix: u8) -> bool {
+    let shift = bits - prefix;
+    shift >= bits || (a >> shift) == (b >> shift)
+}
+
+impl std::str::FromStr for IpRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid address or range '{s}'");
+        let (addr, prefix) = s.split_once('/').map_or((s, None), |(a, p)| (a, Some(p)));
+        let network: std::net::IpAddr = addr.trim().parse().map_err(|_| invalid())?;
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(p) => p
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|p| *p <= max)
+                .ok_or_else(invalid)?,
+            None => max,
+        };
+        Ok(Self { network, prefix })
+    }
+}
+
+/// RPC request rates per caller.
+///
+/// Signed-in callers are counted by user and anonymous ones by client
+/// address. Every call counts against the caller's overall budget and, if
+/// its method has one, that method's budget too.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcRateLimitConfig {
+    /// Enable RPC rate limiting.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Sustained calls per minute per caller, across all methods.
+    #[serde(default = "default_rpc_per_minute")]
+    pub per_minute: u32,
+
+    /// Calls a caller may make in a burst before the rate applies.
+    #[serde(default = "default_rpc_burst")]
+    pub burst: u32,
+
+    /// Calls per minute per caller for a method (`session.message`) or
+    /// namespace (`session.*`), on top of the overall budget.
+    #[serde(default = "default_method_rate_limits")]
+    pub methods: HashMap<String, u32>,
+
+    /// Overall calls per minute by username, replacing `perMinute` and
+    /// `burst` for that user.
+    #[serde(default)]
+    pub users: HashMap<String, u32>,
+}
+
+impl Default for RpcRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            per_minute: default_rpc_per_minute(),
+            burst: default_rpc_burst(),
+            methods: default_method_rate_limits(),
+            users: HashMap::new(),
+        }
+    }
+}
+
+impl RpcRateLimitConfig {
+    /// Check that every budget allows at least one call.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first budget that is zero.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.per_minute == 0 || self.burst == 0 {
+            return Err("perMinute and burst cannot be 0".to_string());
+        }
+        if let Some((key, _)) = self.methods.iter().find(|(_, n)| **n == 0) {
+            return Err(format!("Budget for method '{key}' cannot be 0"));
+        }
+        if let Some((name, _)) = self.users.iter().find(|(_, n)| **n == 0) {
+            return Err(format!("Budget for user '{name}' cannot be 0"));
+        }
+        Ok(())
+    }
+}
+
+/// `OpenID` Connect login settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OidcConfig {
+    /// Issuer URL; discovery is read from
+    /// `<issuer>/.well-known/openid-configuration`.
+    pub issuer: String,
+
+    /// Client ID registered with the provider.
+    pub client_id: String,
+
+    /// Environment variable holding the client secret (`None` for a public
+    /// client, which relies on PKCE alone).
+    #[serde(default)]
+    pub client_secret_env: Option<String>,
+
+    /// The gateway's callback URL as registered with the provider, ending
+    /// in `/auth/oidc/callback`.
+    pub redirect_url: String,
+
+    /// Web UI login page the browser returns to with the gateway's tokens.
+    #[serde(default = "default_oidc_ui_url")]
+    pub ui_url: String,
+
+    /// Scopes requested.
+    #[serde(default = "default_oidc_scopes")]
+    pub scopes: Vec<String>,
+
+    /// ID token claim used as the gateway username.
+    #[serde(default = "default_oidc_username_claim")]
+    pub username_claim: String,
+
+    /// Rules giving roles to matching users. The highest matching role
+    /// wins.
+    #[serde(default)]
+    pub role_rules: Vec<OidcRoleRule>,
+
+    /// Role for users no rule matches (`None` to refuse them).
+    #[serde(default)]
+    pub default_role: Option<String>,
+}
+
+impl OidcConfig {
+    /// Check the provider settings and role names.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.client_id.is_empty() {
+            return Err("clientId cannot be empty".to_string());
+        }
+        for (name, url) in [
+            ("issuer", &self.issuer),
+            ("redirectUrl", &self.redirect_url),
+            ("uiUrl", &self.ui_url),
+        ] {
+            if !url.starts_with("https://") && !url.starts_with("http://") {
+                return Err(format!("{name} must be an http(s) URL: {url}"));
+            }
+        }
+        let roles = self
+            .role_rules
+            .iter()
+            .map(|rule| &rule.role)
+            .chain(&self.default_role);
+        for role in roles {
+            if !OIDC_ROLES.contains(&role.as_str()) {
+                return Err(format!(
+                    "Unknown role '{role}' (expected one of {})",
+                    OIDC_ROLES.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Roles OIDC users can be given.
+const OIDC_ROLES: &[&str] = &["admin", "operator", "viewer"];
+
+/// Gives a role to users whose ID token has a claim value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OidcRoleRule {
+    /// Claim to check, e.g. `groups`.
+    pub claim: String,
+    /// Value the claim must equal, or contain when it is a list.
+    pub value: String,
+    /// Role given: `admin`, `operator`, or `viewer`.
+    pub role: String,
+}
+
+fn default_oidc_ui_url() -> String {
+    "http://localhost:3000/login".to_string()
+}
+
+fn default_oidc_scopes() -> Vec<String> {
+    vec![
+        "openid".to_string(),
+        "profile".to_string(),
+        "email".to_string(),
+    ]
+}
+
+fn default_oidc_username_claim() -> String {
+    "preferred_username".to_string()
+}
+
+/// What happens to a WebSocket client that falls behind the event stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WsLagPolicy {
+    /// Skip the events it missed and carry on.
+    #[default]
+    Skip,
+    /// Close the connection so the client reconnects and resyncs.
+    Disconnect,
+}
+
+/// Readiness check configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthConfig {
+    /// Check that each agent's provider is reachable (lists its models).
+    #[serde(default)]
+    pub probe_providers: bool,
+
+    /// Seconds a provider probe result is reused.
+    #[serde(default = "default_provider_probe_ttl_secs")]
+    pub provider_probe_ttl_secs: u64,
+
+    /// Free space in MiB below which the data directory fails readiness.
+    #[serde(default = "default_min_free_disk_mb")]
+    pub min_free_disk_mb: u64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            probe_providers: false,
+            provider_probe_ttl_secs: default_provider_probe_ttl_secs(),
+            min_free_disk_mb: default_min_free_disk_mb(),
+        }
+    }
+}
+
+/// Distributed gateway configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterConfig {
+    /// Message bus transport.
+    pub bus: BusKind,
+
+    /// Bus URL, e.g. `nats://localhost:4222` or `redis://localhost:6379`.
+    pub url: String,
+
+    /// Unique ID of this node (random if unset).
+    #[serde(default)]
+    pub node_id: Option<String>,
+
+    /// This node's role.
+    #[serde(default)]
+    pub role: NodeRole,
+
+    /// Number of job partitions; must match across nodes.
+    #[serde(default = "default_partitions")]
+    pub partitions: u32,
+
+    /// Partitions a worker consumes; if empty, it takes a share of the
+    /// partitions no other worker lists.
+    #[serde(default)]
+    pub worker_partitions: Vec<u32>,
+}
+
+const fn default_partitions() -> u32 {
+    16
+}
+
+/// Message bus transport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BusKind {
+    /// NATS core pub/sub.
+    Nats,
+    /// Redis streams.
+    Redis,
+}
+
+/// Role of a node in a distributed gateway.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeRole {
+    /// Accept requests and publish messages to workers.
+    #[default]
+    Gateway,
+    /// Run agents for published messages.
+    Worker,
+}
+
+/// A tenant namespace.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespaceConfig {
+    /// Agent IDs owned by the namespace.
+    #[serde(default)]
+    pub agents: Vec<String>,
+
+    /// Channel IDs owned by the namespace.
+    #[serde(default)]
+    pub channels: Vec<String>,
+
+    /// Role granted to each member, by username (`admin`, `operator`, or
+    /// `viewer`).
+    #[serde(default)]
+    pub members: HashMap<String, String>,
+
+    /// Credentials for the namespace's agents and channels, mapping a
+    /// credential name to the environment variable holding it.
+    #[serde(default)]
+    pub credentials: HashMap<String, String>,
+}
+
+const fn default_port() -> u16 {
+    18789
+}
+
+const fn default_timeout() -> u64 {
+    300
+}
+
+const fn default_boot_budget_ms() -> u64 {
+    5000
+}
+
+const fn default_drain_timeout_secs() -> u64 {
+    30
+}
+
+const fn default_max_body_bytes() -> u64 {
+    2 * 1024 * 1024
+}
+
+const fn default_body_read_timeout_secs() -> u64 {
+    30
+}
+
+const fn default_ws_max_write_buffer_bytes() -> u64 {
+    8 * 1024 * 1024
+}
+
+const fn default_ws_send_timeout_secs() -> u64 {
+    10
+}
+
+fn default_cors_origins() -> Vec<String> {
+    // The bundled web UI's own server
+    vec![
+        "http://localhost:3000".to_string(),
+        "http://127.0.0.1:3000".to_string(),
+    ]
+}
+
+fn default_cors_headers() -> Vec<String> {
+    vec!["authorization".to_string(), "content-type".to_string()]
+}
+
+const fn default_cors_max_age_secs() -> u64 {
+    600
+}
+
+const fn default_rpc_per_minute() -> u32 {
+    600
+}
+
+const fn default_rpc_burst() -> u32 {
+    60
+}
+
+fn default_method_rate_limits() -> HashMap<String, u32> {
+    // Calls that start agent turns or tool runs cost far more than reads
+    HashMap::from([
+        ("session.message".to_string(), 30),
+        ("session.replay".to_string(), 5),
+        ("tools.execute".to_string(), 30),
+        ("workflow.run".to_string(), 30),
+    ])
+}
+
+const fn default_provider_probe_ttl_secs() -> u64 {
+    60
+}
+
+const fn default_min_free_disk_mb() -> u64 {
+    100
+}
+
+fn default_method_timeouts() -> HashMap<String, u64> {
+    // Status calls should fail fast; agent turns may run many tool calls
+    HashMap::from([
+        ("system.*".to_string(), 10),
+        ("agent.status".to_string(), 10),
+        ("session.message".to_string(), 600),
+        ("session.replay".to_string(), 1800),
+    ])
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+/// Gateway bind mode.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BindMode {
+    /// Bind to localhost only.
+    #[default]
+    Local,
+    /// Bind to all interfaces.
+    Public,
+    /// Custom bind address.
+    Custom(String),
+}
+
+/// Agent configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentConfig {
+    /// Agent this one inherits from; only fields set here override it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+
+    /// Model to use (e.g., "claude-3-5-sonnet-20241022").
+    #[serde(default = "default_model")]
+    pub model: String,
+
+    /// Provider to use.
+    #[serde(default = "default_provider")]
+    pub provider: String,
+
+    /// System prompt.
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+
+    /// Maximum tokens in response.
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32,
+
+    /// Temperature for sampling.
+    #[serde(default = "default_temperature")]
+    pub temperature: f32,
+
+    /// Tools this agent may use: names or prefix wildcards (`"fs.*"`).
+    /// Empty allows every tool not denied.
+    #[serde(default)]
+    pub tools: Vec<String>,
+
+    /// Tools this agent may never use, even if `tools` allows them.
+    #[serde(default)]
+    pub denied_tools: Vec<String>,
+
+    /// Skills injected into the system prompt.
+    #[serde(default)]
+    pub skills: Vec<String>,
+
+    /// Sections added to the system prompt and its size limit.
+    #[serde(default)]
+    pub prompt: PromptAssemblyConfig,
+
+    /// Screening of inbound messages and replies.
+    #[serde(default)]
+    pub moderation: ModerationConfig,
+
+    /// Allowlist patterns for this agent.
+    #[serde(default)]
+    pub allowlist: Vec<AllowlistEntry>,
+
+    /// Limits on simultaneous provider calls and queued messages.
+    #[serde(default)]
+    pub concurrency: AgentConcurrencyConfig,
+
+    /// Limits on simultaneous tool calls and queued ones.
+    #[serde(default)]
+    pub tool_concurrency: ToolConcurrencyConfig,
+
+    /// Image input from inbound attachments.
+    #[serde(default)]
+    pub vision: VisionConfig,
+
+    /// Tools that need operator sign-off before they run.
+    #[serde(default)]
+    pub approval: ToolApprovalConfig,
+
+    /// Sandbox network access through the egress proxy.
+    #[serde(default)]
+    pub egress: EgressConfig,
+
+    /// Per-tool sandbox overrides, by tool name.
+    #[serde(default)]
+    pub sandbox: HashMap<String, SandboxProfileConfig>,
+
+    /// Working directory mounted into the agent's sandboxed tools.
+    #[serde(default)]
+    pub workspace: WorkspaceConfig,
+
+    /// Repositories and commit author for the git tools.
+    #[serde(default)]
+    pub git: GitToolsConfig,
+}
+
+impl Default for AgentConfig {
+    fn default() -> Self {
+        Self {
+            extends: None,
+            model: default_model(),
+            provider: default_provider(),
+            system_prompt: None,
+            max_tokens: default_max_tokens(),
+            temperature: default_temperature(),
+            tools: vec![],
+            denied_tools: vec![],
+            skills: vec![],
+            prompt: PromptAssemblyConfig::default(),
+            moderation: ModerationConfig::default(),
+            allowlist: vec![],
+            concurrency: AgentConcurrencyConfig::default(),
+            tool_concurrency: ToolConcurrencyConfig::default(),
+            vision: VisionConfig::default(),
+            approval: ToolApprovalConfig::default(),
+            egress: EgressConfig::default(),
+            sandbox: HashMap::new(),
+            workspace: WorkspaceConfig::default(),
+            git: GitToolsConfig::default(),
+        }
+    }
+}
+
+impl AgentConfig {
+    /// Which tools this agent may use.
+    #[must_use]
+    pub fn tool_policy(&self) -> ToolPolicy {
+        ToolPolicy::new(self.tools.clone(), self.denied_tools.clone())
+    }
+}
+
+/// Model parameters changed on a running agent. Unset fields are left
+/// as they are.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AgentOverrides {
+    /// Model to switch to.
+    pub model: Option<String>,
+    /// Sampling temperature.
+    pub temperature: Option<f32>,
+    /// Maximum tokens in a response.
+    pub max_tokens: Option<u32>,
+    /// System prompt; an empty string clears it.
+    pub system_prompt: Option<String>,
+}
+
+impl AgentOverrides {
+    /// Whether no field is set.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.model.is_none()
+            && self.temperature.is_none()
+            && self.max_tokens.is_none()
+            && self.system_prompt.is_none()
+    }
+
+    /// Check the values are usable.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message naming the first invalid field.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.model.as_deref().is_some_and(|m| m.trim().is_empty()) {
+            return Err("model must not be empty".to_string());
+        }
+        if let Some(temperature) = self.temperature.filter(|t| !(0.0..=2.0).contains(t)) {
+            return Err(format!(
+                "temperature must be between 0 and 2, got {temperature}"
+            ));
+        }
+        if self.max_tokens == Some(0) {
+            return Err("max_tokens must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+
+    /// Apply the set fields to an agent's config.
+    pub fn apply(&self, agent: &mut AgentConfig) {
+        if let Some(model) = &self.model {
+            agent.model.clone_from(model);
+        }
+        if let Some(temperature) = self.temperature {
+            agent.temperature = temperature;
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            agent.max_tokens = max_tokens;
+        }
+        if let Some(prompt) = &self.system_prompt {
+            agent.system_prompt = Some(prompt.clone()).filter(|p| !p.is_empty());
+        }
+    }
+
+    /// Write the set fields into `agents.<agent_id>` of the config file at
+    /// `path`, keeping everything else as written.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be read or written, or the result
+    /// would not load.
+    pub fn save(&self, path: &Path, agent_id: &str) -> Result<(), ConfigError> {
+        let mut raw: serde_json::Value = if path.exists() {
+            json5::from_str(&std::fs::read_to_string(path)?)?
+        } else {
+            serde_json::json!({})
+        };
+        if !raw["agents"].is_object() {
+            raw["agents"] = serde_json::json!({});
+        }
+        if !raw["agents"][agent_id].is_object() {
+            raw["agents"][agent_id] = serde_json::json!({});
+        }
+        let entry = &mut raw["agents"][agent_id];
+        if let Some(model) = &self.model {
+            entry["model"] = serde_json::json!(model);
+        }
+        if let Some(temperature) = self.temperature {
+            entry["temperature"] = serde_json::json!(temperature);
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            entry["maxTokens"] = serde_json::json!(max_tokens);
+        }
+        if let Some(prompt) = &self.system_prompt {
+            entry["systemPrompt"] = if prompt.is_empty() {
+                serde_json::Value::Null
+            } else {
+                serde_json::json!(prompt)
+            };
+        }
+
+        let content = serde_json::to_string_pretty(&raw)
+            .map_err(|e| ConfigError::Validation(e.to_string()))?;
+        Config::from_json5(&content)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+fn default_model() -> String {
+    "claude-3-5-sonnet-20241022".to_string()
+}
+
+fn default_provider() -> String {
+    "anthropic".to_string()
+}
+
+const fn default_max_tokens() -> u32 {
+    4096
+}
+
+const fn default_temperature() -> f32 {
+    0.7
+}
+
+/// Per-agent concurrency limits.
+///
+/// Keeps one busy agent from starving others that share the gateway and
+/// provider keys.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentConcurrencyConfig {
+    /// Maximum simultaneous provider calls (unlimited if unset).
+    #[serde(default)]
+    pub max_concurrent: Option<u32>,
+
+    /// Maximum messages waiting for a free slot before new ones are rejected.
+    #[serde(default = "default_max_queued")]
+    pub max_queued: u32,
+
+    /// Reply sent when the queue is full.
+    #[serde(default = "default_busy_message")]
+    pub busy_message: String,
+}
+
+impl Default for AgentConcurrencyConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: None,
+            max_queued: default_max_queued(),
+            busy_message: default_busy_message(),
+        }
+    }
+}
+
+const fn default_max_queued() -> u32 {
+    16
+}
+
+fn default_busy_message() -> String {
+    "I'm handling a lot of conversations right now. Please try again in a moment.".to_string()
+}
+
+/// Per-agent tool call limits.
+///
+/// A call first waits for a slot of its tool, then for one of the agent's
+/// overall slots. Calls beyond `max_queued` waiting for either fail.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolConcurrencyConfig {
+    /// Maximum simultaneous tool calls (unlimited if unset).
+    #[serde(default)]
+    pub max_concurrent: Option<u32>,
+
+    /// Maximum simultaneous calls of a tool, by tool name.
+    #[serde(default)]
+    pub per_tool: HashMap<String, u32>,
+
+    /// Maximum calls waiting for a free slot before new ones fail.
+    #[serde(default = "default_max_queued_tool_calls")]
+    pub max_queued: u32,
+}
+
+impl Default for ToolConcurrencyConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: None,
+            per_tool: HashMap::new(),
+            max_queued: default_max_queued_tool_calls(),
+        }
+    }
+}
+
+impl ToolConcurrencyConfig {
+    /// Check that every limit allows at least one call.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first zero limit.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_concurrent == Some(0) {
+            return Err("maxConcurrent cannot be 0".to_string());
+        }
+        match self.per_tool.iter().find(|(_, limit)| **limit == 0) {
+            Some((tool, _)) => Err(format!("perTool limit for '{tool}' cannot be 0")),
+            None => Ok(()),
+        }
+    }
+}
+
+const fn default_max_queued_tool_calls() -> u32 {
+    32
+}
+
+/// How an agent's system prompt is put together each turn.
+///
+/// The prompt is built from ordered sections: the configured prompt,
+/// skills, recalled memory, channel etiquette, the current time and user,
+/// and session state. Over `max_tokens`, the least important sections are
+/// shortened or left out; the configured prompt is always kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptAssemblyConfig {
+    /// Estimated token limit for the whole system prompt (unlimited if
+    /// unset).
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+
+    /// Add a note on how to write for the conversation's channel.
+    #[serde(default = "default_true")]
+    pub etiquette: bool,
+
+    /// Channel notes replacing the built-in ones, by channel ID.
+    #[serde(default)]
+    pub channel_etiquette: HashMap<String, String>,
+
+    /// Add the current time and the user's name.
+    #[serde(default = "default_true")]
+    pub current_time: bool,
+}
+
+impl Default for PromptAssemblyConfig {
+    fn default() -> Self {
+        Self {
+            max_tokens: None,
+            etiquette: true,
+            channel_etiquette: HashMap::new(),
+            current_time: true,
+        }
+    }
+}
+
+/// Per-agent content moderation.
+///
+/// Inbound messages are screened before they reach the model and replies
+/// before they are sent, against the keyword and pattern lists and, if
+/// enabled, the agent's provider moderation endpoint. Nothing is screened
+/// unless at least one of those is set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ModerationConfig {
+    /// Screen messages before they reach the model.
+    #[serde(default = "default_true")]
+    pub inbound: bool,
+
+    /// Screen replies before they are sent.
+    #[serde(default = "default_true")]
+    pub outbound: bool,
+
+    /// What happens to a message or reply that is caught.
+    #[serde(default)]
+    pub action: ModerationAction,
+
+    /// Words and phrases caught as whole words, ignoring case.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+
+    /// Regular expressions caught anywhere in the text.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+
+    /// Also ask the agent's provider moderation endpoint.
+    #[serde(default)]
+    pub provider: bool,
+
+    /// Provider categories that count (every flagged category if empty).
+    #[serde(default)]
+    pub categories: Vec<String>,
+
+    /// Tell operators watching the web UI about each violation.
+    #[serde(default)]
+    pub notify_admins: bool,
+
+    /// Reply sent in place of blocked content (a localized notice if
+    /// unset).
+    #[serde(default)]
+    pub blocked_message: Option<String>,
+}
+
+impl Default for ModerationConfig {
+    fn default() -> Self {
+        Self {
+            inbound: true,
+            outbound: true,
+            action: ModerationAction::default(),
+            keywords: Vec::new(),
+            patterns: Vec::new(),
+            provider: false,
+            categories: Vec::new(),
+            notify_admins: false,
+            blocked_message: None,
+        }
+    }
+}
+
+impl ModerationConfig {
+    /// Whether anything is screened.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        (self.inbound || self.outbound)
+            && (!self.keywords.is_empty() || !self.patterns.is_empty() || self.provider)
+    }
+
+    /// Check that every pattern is a valid regular expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first invalid pattern.
+    pub fn validate(&self) -> Result<(), String> {
+        for pattern in &self.patterns {
+            regex::Regex::new(pattern).map_err(|e| format!("pattern '{pattern}': {e}"))?;
+        }
+        if self.keywords.iter().any(|k| k.trim().is_empty()) {
+            return Err("keywords cannot be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// What happens to content caught by moderation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationAction {
+    /// Hold it back: blocked messages never reach the model, and blocked
+    /// replies are never sent.
+    #[default]
+    Block,
+    /// Let it through and record the violation.
+    Flag,
+}
+
+/// Per-agent image input.
+///
+/// Images are only sent when enabled here and the agent's model accepts
+/// them; otherwise the model is told that images were attached.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VisionConfig {
+    /// Pass inbound image attachments to the model.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// How images reach the provider.
+    #[serde(default)]
+    pub delivery: ImageDelivery,
+
+    /// Images passed per message; extras are dropped.
+    #[serde(default = "default_vision_max_images")]
+    pub max_images: usize,
+
+    /// Largest image downloaded for inline delivery.
+    #[serde(default = "default_vision_max_image_bytes")]
+    pub max_image_bytes: u64,
+}
+
+impl Default for VisionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            delivery: ImageDelivery::default(),
+            max_images: default_vision_max_images(),
+            max_image_bytes: default_vision_max_image_bytes(),
+        }
+    }
+}
+
+const fn default_vision_max_images() -> usize {
+    4
+}
+
+const fn default_vision_max_image_bytes() -> u64 {
+    5 * 1024 * 1024
+}
+
+/// How image attachments are sent to the provider.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageDelivery {
+    /// Download and send base64-encoded data.
+    #[default]
+    Inline,
+    /// Send public `http(s)` URLs for the provider to fetch; other
+    /// attachments are still sent inline.
+    Url,
+}
+
+/// Tools an agent may use.
+///
+/// Patterns are tool names, or end in `*` to match every tool with that
+/// prefix (`"fs.*"`, `"git_*"`, `"*"`). A denied tool is never allowed;
+/// without allow patterns, every tool that is not denied is.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolPolicy {
+    allow: Vec<String>,
+    deny: Vec<String>,
+}
+
+impl ToolPolicy {
+    /// Create a policy from allow and deny patterns.
+    #[must_use]
+    pub const fn new(allow: Vec<String>, deny: Vec<String>) -> Self {
+        Self { allow, deny }
+    }
+
+    /// Whether the policy allows every tool.
+    #[must_use]
+    pub fn is_unrestricted(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    /// Whether `tool` may be used.
+    #[must_use]
+    pub fn allows(&self, tool: &str) -> bool {
+        self.denial(tool).is_none()
+    }
+
+    /// Why `tool` may not be used, or `None` if it may.
+    #[must_use]
+    pub fn denial(&self, tool: &str) -> Option<String> {
+        if let Some(pattern) = self.deny.iter().find(|p| tool_pattern_matches(p, tool)) {
+            return Some(format!("denied by '{pattern}'"));
+        }
+        if !self.allow.is_empty() && !self.allow.iter().any(|p| tool_pattern_matches(p, tool)) {
+            return Some("not in the agent's allowed tools".to_string());
+        }
+        None
+    }
+
+    /// Check that every pattern is a name or a prefix wildcard.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first empty pattern or one with `*` before its end.
+    pub fn validate(&self) -> Result<(), String> {
+        for pattern in self.allow.iter().chain(&self.deny) {
+            let stem = pattern.strip_suffix('*').unwrap_or(pattern);
+            if pattern.is_empty() || stem.contains('*') {
+                return Err(format!(
+                    "invalid tool pattern '{pattern}' (use a name or a trailing '*')"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn tool_pattern_matches(pattern: &str, tool: &str) -> bool {
+    pattern
+        .strip_suffix('*')
+        .map_or(pattern == tool, |prefix| tool.starts_with(prefix))
+}
+
+/// Per-agent tool approval policy.
+///
+/// A listed tool pauses before running until an operator approves or
+/// denies the call; calls still pending after the timeout are denied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolApprovalConfig {
+    /// Tools that require approval (`"*"` for all).
+    #[serde(default)]
+    pub tools: Vec<String>,
+
+    /// Seconds to wait for a decision.
+    #[serde(default = "default_approval_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Per-tool timeout overrides in seconds.
+    #[serde(default)]
+    pub tool_timeouts: HashMap<String, u64>,
+}
+
+impl ToolApprovalConfig {
+    /// Whether calls to `tool` need approval.
+    #[must_use]
+    pub fn requires_approval(&self, tool: &str) -> bool {
+        self.tools.iter().any(|t| t == "*" || t == tool)
+    }
+
+    /// How long to wait for a decision on `tool`.
+    #[must_use]
+    pub fn timeout_for(&self, tool: &str) -> std::time::Duration {
+        std::time::Duration::from_secs(
+            self.tool_timeouts
+                .get(tool)
+                .copied()
+                .unwrap_or(self.timeout_secs),
+        )
+    }
+}
+
+impl Default for ToolApprovalConfig {
+    fn default() -> Self {
+        Self {
+            tools: vec![],
+            timeout_secs: default_approval_timeout_secs(),
+            tool_timeouts: HashMap::new(),
+        }
+    }
+}
+
+const fn default_approval_timeout_secs() -> u64 {
+    300
+}
+
+/// Per-agent sandbox egress policy.
+///
+/// When enabled, sandboxed commands reach the network only through a local
+/// proxy that allows the listed domains and never connects to private
+/// addresses.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EgressConfig {
+    /// Route sandbox network access through the egress proxy.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Domains the sandbox may reach. `"*.example.com"` matches
+    /// subdomains only; `"example.com"` matches that host only.
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+}
+
+impl EgressConfig {
+    /// Whether `host` is on the allowlist (case-insensitive).
+    #[must_use]
+    pub fn allows_host(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        self.allowed_domains.iter().any(|entry| {
+            let entry = entry.trim_end_matches('.').to_ascii_lowercase();
+            entry.strip_prefix("*.").map_or_else(
+                || host == entry,
+                |suffix| {
+                    host.len() > suffix.len() + 1
+                        && host.ends_with(suffix)
+                        && host.as_bytes()[host.len() - suffix.len() - 1] == b'.'
+                },
+            )
+        })
+    }
+}
+
+/// Agent workspace settings.
+///
+/// Each agent gets a directory under `~/.openclaw/workspace/<agent>` that
+/// its sandboxed tools can write to. With `perSession`, every session gets
+/// its own directory under the agent's instead.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceConfig {
+    /// Give each session its own workspace.
+    #[serde(default)]
+    pub per_session: bool,
+
+    /// Disk quota in MB (unlimited if unset).
+    #[serde(default)]
+    pub quota_mb: Option<u64>,
+
+    /// Remove session workspaces idle this many hours (kept if unset).
+    #[serde(default)]
+    pub expire_after_hours: Option<u64>,
+}
+
+impl WorkspaceConfig {
+    /// Quota in bytes, if one is set.
+    #[must_use]
+    pub const fn quota_bytes(&self) -> Option<u64> {
+        match self.quota_mb {
+            Some(mb) => Some(mb.saturating_mul(1024 * 1024)),
+            None => None,
+        }
+    }
+}
+
+/// Git tool settings.
+///
+/// Clone and push only reach repositories on the allowlist. Entries are
+/// remote URLs without scheme or `.git` suffix, such as
+/// `github.com/neul-labs/openclaw-rs`; a trailing `/*` matches every
+/// repository under that path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitToolsConfig {
+    /// Repositories that may be cloned and pushed to.
+    #[serde(default)]
+    pub allowed_repos: Vec<String>,
+
+    /// Commit author name.
+    #[serde(default = "default_git_author_name")]
+    pub author_name: String,
+
+    /// Commit author email.
+    #[serde(default = "default_git_author_email")]
+    pub author_email: String,
+}
+
+impl GitToolsConfig {
+    /// Whether the remote at `url` is on the allowlist.
+    ///
+    /// Local paths and `file://` URLs are never allowed.
+    #[must_use]
+    pub fn allows_repo(&self, url: &str) -> bool {
+        let Some(repo) = normalize_repo_url(url) else {
+            return false;
+        };
+        self.allowed_repos.iter().any(|entry| {
+            let entry = entry.trim_end_matches(".git").to_ascii_lowercase();
+            entry.strip_suffix("/*").map_or_else(
+                || repo == entry,
+                |prefix| {
+                    repo.len() > prefix.len() + 1
+                        && repo.starts_with(prefix)
+                        && repo.as_bytes()[prefix.len()] == b'/'
+                },
+            )
+        })
+    }
+}
+
+impl Default for GitToolsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_repos: vec![],
+            author_name: default_git_author_name(),
+            author_email: default_git_author_email(),
+        }
+    }
+}
+
+fn default_git_author_name() -> String {
+    "OpenClaw".to_string()
+}
+
+fn default_git_author_email() -> String {
+    "openclaw@localhost".to_string()
+}
+
+/// Reduce a remote URL to lowercase `host/path`.
+///
+/// Accepts `https://`, `http://`, `ssh://`, `git://`, and scp-style
+/// `user@host:path` remotes; returns `None` for anything else.
+fn normalize_repo_url(url: &str) -> Option<String> {
+    let url = url.trim();
+    let rest = if let Some((scheme, rest)) = url.split_once("://") {
+        if !matches!(scheme, "https" | "http" | "ssh" | "git") {
+            return None;
+        }
+        rest
+    } else {
+        // scp-style `git@github.com:org/repo`
+        let (user_host, path) = url.split_once(':')?;
+        if !user_host.contains('@') {
+            return None;
+        }
+        return normalize_repo_url(&format!("ssh://{user_host}/{path}"));
+    };
+
+    let (authority, path) = rest.split_once('/')?;
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = host.split(':').next().unwrap_or_default();
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    if host.is_empty() || path.is_empty() || path.split('/').any(|s| s.is_empty() || s == "..") {
+        return None;
+    }
+    Some(format!("{host}/{path}").to_ascii_lowercase())
+}
+
+/// Sandbox security levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SandboxLevel {
+    /// No isolation - NEVER use in production.
+    None = 0,
+    /// Basic filesystem isolation.
+    Minimal = 1,
+    /// PID namespace + resource limits (default).
+    #[default]
+    Standard = 2,
+    /// Network isolation + seccomp filtering.
+    Strict = 3,
+    /// No host filesystem access.
+    Paranoid = 4,
+}
+
+/// Sandbox settings for a tool.
+///
+/// Unset fields inherit from the profile named in `extends`, then from the
+/// sandbox the tool declares.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxProfileConfig {
+    /// Named profile (in `sandboxProfiles`) this one builds on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+
+    /// Security level.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<SandboxLevel>,
+
+    /// Whether network access is allowed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<bool>,
+
+    /// Absolute paths mounted read-write.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_paths: Option<Vec<PathBuf>>,
+
+    /// Absolute paths mounted read-only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readonly_paths: Option<Vec<PathBuf>>,
+
+    /// Maximum memory in MB.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_memory_mb: Option<u64>,
+
+    /// Maximum CPU time in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cpu_seconds: Option<u64>,
+
+    /// Maximum file descriptors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_descriptors: Option<u64>,
+}
+
+impl SandboxProfileConfig {
+    /// Check that paths are absolute, limits are non-zero, and no path is
+    /// both read-write and read-only.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        let allowed = self.allowed_paths.as_deref().unwrap_or_default();
+        let readonly = self.readonly_paths.as_deref().unwrap_or_default();
+        if let Some(path) = allowed.iter().chain(readonly).find(|p| !p.is_absolute()) {
+            return Err(format!("path '{}' is not absolute", path.display()));
+        }
+        if let Some(path) = allowed.iter().find(|p| readonly.contains(*p)) {
+            return Err(format!(
+                "path '{}' is both read-write and read-only",
+                path.display()
+            ));
+        }
+
+        for (name, limit) in [
+            ("maxMemoryMb", self.max_memory_mb),
+            ("maxCpuSeconds", self.max_cpu_seconds),
+            ("maxFileDescriptors", self.max_file_descriptors),
+        ] {
+            if limit == Some(0) {
+                return Err(format!("{name} cannot be 0"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A/B experiment comparing agent variants.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExperimentConfig {
+    /// Agent ID whose new sessions are split between variants.
+    pub agent: String,
+
+    /// Whether the experiment is assigning new sessions.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Variants and their traffic weights.
+    pub variants: Vec<ExperimentVariant>,
+}
+
+/// A single experiment variant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExperimentVariant {
+    /// Variant name (e.g., "control", "b").
+    pub name: String,
+
+    /// Agent ID that serves this variant.
+    pub agent: String,
+
+    /// Relative traffic weight.
+    #[serde(default = "default_variant_weight")]
+    pub weight: u32,
+
+    /// Cost per million input tokens, for reporting.
+    #[serde(default)]
+    pub input_cost_per_mtok: Option<f64>,
+
+    /// Cost per million output tokens, for reporting.
+    #[serde(default)]
+    pub output_cost_per_mtok: Option<f64>,
+}
+
+const fn default_variant_weight() -> u32 {
+    50
+}
+
+/// A task run on a cron schedule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleConfig {
+    /// Cron expression in UTC (5 fields, or 6 with leading seconds).
+    pub cron: String,
+
+    /// Whether the schedule is active.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Agent that runs the prompt.
+    #[serde(default = "default_schedule_agent")]
+    pub agent: String,
+
+    /// Prompt sent to the agent.
+    #[serde(default)]
+    pub prompt: Option<String>,
+
+    /// Workflow to execute instead of a prompt.
+    #[serde(default)]
+    pub workflow: Option<String>,
+
+    /// Input passed to the workflow.
+    #[serde(default)]
+    pub input: serde_json::Value,
+
+    /// Where to post the result.
+    #[serde(default)]
+    pub deliver: Option<ScheduleDelivery>,
+
+    /// Notification template the result is posted through. The prompt or
+    /// workflow output is available to it as `{{ output }}`; with neither,
+    /// the template alone is rendered and delivered.
+    #[serde(default)]
+    pub template: Option<String>,
+
+    /// Variables passed to the template.
+    #[serde(default)]
+    pub vars: serde_json::Map<String, serde_json::Value>,
+
+    /// Maximum random delay added to each run, in seconds.
+    #[serde(default)]
+    pub jitter_secs: u64,
+
+    /// What to do about runs missed while the daemon was down.
+    #[serde(default)]
+    pub catch_up: CatchUpPolicy,
+}
+
+fn default_schedule_agent() -> String {
+    "default".to_string()
+}
+
+/// Delivery target for scheduled task output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleDelivery {
+    /// Channel ID (e.g., "slack").
+    pub channel: String,
+
+    /// Chat/channel ID on the platform.
+    pub chat_id: String,
+
+    /// Thread to post into.
+    #[serde(default)]
+    pub thread_id: Option<String>,
+}
+
+/// Named outbound message with `{{ variable }}` placeholders.
+///
+/// Bodies are minijinja templates, rendered in the same sandbox as prompt
+/// templates.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationTemplateConfig {
+    /// What the template is for.
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Message body.
+    pub body: String,
+
+    /// Bodies for specific channels by channel ID (e.g., Slack mrkdwn),
+    /// used instead of `body` on those channels.
+    #[serde(default)]
+    pub channels: HashMap<String, String>,
+
+    /// Variables the caller must supply.
+    #[serde(default)]
+    pub required: Vec<String>,
+
+    /// Values for variables the caller does not supply.
+    #[serde(default)]
+    pub defaults: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Policy for runs missed while the scheduler was not running.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CatchUpPolicy {
+    /// Drop missed runs.
+    Skip,
+    /// Run once for any number of missed runs.
+    #[default]
+    Once,
+    /// Run every missed occurrence (bounded).
+    All,
+}
+
+/// Allowlist entry for agent access control.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllowlistEntry {
+    /// Channel pattern (e.g., "telegram", "*").
+    pub channel: String,
+
+    /// Peer ID pattern (e.g., "123456789", "*").
+    pub peer_id: String,
+
+    /// Optional label for this entry.
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// Channel configurations.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelsConfig {
+    /// Telegram channel config.
+    #[serde(default)]
+    pub telegram: Option<TelegramConfig>,
+
+    /// Discord channel config.
+    #[serde(default)]
+    pub discord: Option<DiscordConfig>,
+
+    /// Slack channel config.
+    #[serde(default)]
+    pub slack: Option<SlackConfig>,
+
+    /// Signal channel config.
+    #[serde(default)]
+    pub signal: Option<SignalConfig>,
+
+    /// Matrix channel config.
+    #[serde(default)]
+    pub matrix: Option<MatrixConfig>,
+
+    /// `WhatsApp` channel config.
+    #[serde(default)]
+    pub whatsapp: Option<WhatsAppConfig>,
+
+    /// Inbound message rate limiting.
+    #[serde(default)]
+    pub rate_limit: InboundRateLimitConfig,
+
+    /// Outbound text formatting overrides, by channel ID.
+    #[serde(default)]
+    pub formatting: HashMap<String, ChannelFormatConfig>,
+
+    /// Coalescing of rapid consecutive inbound messages.
+    #[serde(default)]
+    pub debounce: DebounceConfig,
+
+    /// Hours when agents answer.
+    #[serde(default)]
+    pub working_hours: WorkingHoursConfig,
+
+    /// How agents behave in group chats.
+    #[serde(default)]
+    pub groups: GroupsConfig,
+
+    /// Slash commands people can send in chat.
+    #[serde(default)]
+    pub commands: ChatCommandsConfig,
+
+    /// Sending one message to many chats.
+    #[serde(default)]
+    pub broadcast: BroadcastConfig,
+
+    /// Checks on inbound attachments.
+    #[serde(default)]
+    pub attachments: AttachmentPolicyConfig,
+
+    /// Language of system messages sent to peers.
+    #[serde(default)]
+    pub locale: LocaleConfig,
+
+    /// IDs of channels that stay stopped until enabled again.
+    #[serde(default)]
+    pub disabled: Vec<String>,
+}
+
+impl ChannelsConfig {
+    /// Whether the channel `id` should be started.
+    #[must_use]
+    pub fn is_enabled(&self, id: &str) -> bool {
+        !self.disabled.iter().any(|d| d == id)
+    }
+
+    /// Add or remove `channel_id` in `channels.disabled` of the config file
+    /// at `path`, keeping everything else as written.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be read or written, or the result
+    /// would not load.
+    pub fn save_enabled(path: &Path, channel_id: &str, enabled: bool) -> Result<(), ConfigError> {
+        let mut raw: serde_json::Value = if path.exists() {
+            json5::from_str(&std::fs::read_to_string(path)?)?
+        } else {
+            serde_json::json!({})
+        };
+        if !raw["channels"].is_object() {
+            raw["channels"] = serde_json::json!({});
+        }
+        let mut disabled: Vec<String> = raw["channels"]["disabled"]
+            .as_array()
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(serde_json::Value::as_str)
+                    .filter(|id| *id != channel_id)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !enabled {
+            disabled.push(channel_id.to_string());
+        }
+        raw["channels"]["disabled"] = serde_json::json!(disabled);
+
+        let content = serde_json::to_string_pretty(&raw)
+            .map_err(|e| ConfigError::Validation(e.to_string()))?;
+        Config::from_json5(&content)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+/// Language of system messages sent to peers: rate-limit notices,
+/// rejected attachments, and the like. Agent replies are not translated.
+///
+/// A peer's locale is the first of: its entry in `peers`, the language in
+/// its profile, its channel's entry in `channels`, and `default`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocaleConfig {
+    /// Locale when nothing more specific is set.
+    #[serde(default = "default_locale")]
+    pub default: String,
+
+    /// Locale by channel ID.
+    #[serde(default)]
+    pub channels: HashMap<String, String>,
+
+    /// Locale by peer, keyed `channel:peer_id`.
+    #[serde(default)]
+    pub peers: HashMap<String, String>,
+
+    /// Directory of `<locale>.ftl` files adding locales or overriding
+    /// built-in messages.
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+}
+
+impl LocaleConfig {
+    /// Locale set for a peer, if any.
+    #[must_use]
+    pub fn for_peer(&self, channel: &str, peer_id: &str) -> Option<&str> {
+        self.peers
+            .get(&format!("{channel}:{peer_id}"))
+            .map(String::as_str)
+    }
+
+    /// Locale for peers on `channel` without one of their own.
+    #[must_use]
+    pub fn for_channel(&self, channel: &str) -> &str {
+        self.channels.get(channel).unwrap_or(&self.default)
+    }
+}
+
+impl Default for LocaleConfig {
+    fn default() -> Self {
+        Self {
+            default: default_locale(),
+            channels: HashMap::new(),
+            peers: HashMap::new(),
+            dir: None,
+        }
+    }
+}
+
+fn default_locale() -> String {
+    crate::i18n::DEFAULT_LOCALE.to_string()
+}
+
+/// Checks on inbound attachments before the agent sees them. Rejected
+/// attachments are dropped from the message and the sender is told why.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentPolicyConfig {
+    /// Check inbound attachments.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Largest attachment accepted, in bytes, by kind (`image`, `video`,
+    /// `document`, ...). Kinds not listed use the global attachment limit.
+    #[serde(default)]
+    pub max_bytes: HashMap<String, u64>,
+
+    /// Reject attachments whose contents don't match their file extension
+    /// or declared MIME type.
+    #[serde(default = "default_true")]
+    pub sniff_mime: bool,
+
+    /// Scan attachments with a `ClamAV` daemon (`None` to skip scanning).
+    #[serde(default)]
+    pub clamav: Option<ClamAvConfig>,
+
+    /// Reply sent when attachments are rejected, instead of the localized
+    /// one. `{files}` is replaced with the rejected files and the reasons.
+    #[serde(default)]
+    pub rejection_message: Option<String>,
+
+    /// Overrides by channel ID.
+    #[serde(default)]
+    pub channels: HashMap<String, ChannelAttachmentPolicy>,
+}
+
+impl AttachmentPolicyConfig {
+    /// Whether attachments on `channel` are checked.
+    #[must_use]
+    pub fn enabled_for(&self, channel: &str) -> bool {
+        self.channels
+            .get(channel)
+            .and_then(|c| c.enabled)
+            .unwrap_or(self.enabled)
+    }
+
+    /// Size limit for attachments of `kind` on `channel`, capped at the
+    /// global attachment limit.
+    #[must_use]
+    pub fn max_bytes_for(&self, channel: &str, kind: &str) -> u64 {
+        self.channels
+            .get(channel)
+            .and_then(|c| c.max_bytes.get(kind))
+            .or_else(|| self.max_bytes.get(kind))
+            .copied()
+            .unwrap_or(u64::MAX)
+            .min(crate::validation::limits::MAX_ATTACHMENT_SIZE as u64)
+    }
+
+    /// Whether contents are checked against names and MIME types on `channel`.
+    #[must_use]
+    pub fn sniff_mime_for(&self, channel: &str) -> bool {
+        self.channels
+            .get(channel)
+            .and_then(|c| c.sniff_mime)
+            .unwrap_or(self.sniff_mime)
+    }
+
+    /// The `ClamAV` daemon to scan attachments on `channel` with, if any.
+    #[must_use]
+    pub fn clamav_for(&self, channel: &str) -> Option<&ClamAvConfig> {
+        let scan = self
+            .channels
+            .get(channel)
+            .and_then(|c| c.scan)
+            .unwrap_or(true);
+        self.clamav.as_ref().filter(|_| scan)
+    }
+}
+
+impl Default for AttachmentPolicyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_bytes: HashMap::new(),
+            sniff_mime: true,
+            clamav: None,
+            rejection_message: None,
+            channels: HashMap::new(),
+        }
+    }
+}
+
+/// Attachment policy overrides for one channel.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelAttachmentPolicy {
+    /// Check attachments on this channel.
+    #[serde(default)]
+    pub enabled: Option<bool>,
+
+    /// Size limits by kind, over the global ones.
+    #[serde(default)]
+    pub max_bytes: HashMap<String, u64>,
+
+    /// Check contents against names and MIME types.
+    #[serde(default)]
+    pub sniff_mime: Option<bool>,
+
+    /// Scan with `ClamAV`, when configured (defaults to on).
+    #[serde(default)]
+    pub scan: Option<bool>,
+}
+
+/// Connection to a `ClamAV` daemon (`clamd`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClamAvConfig {
+    /// `clamd` TCP address.
+    #[serde(default = "default_clamav_address")]
+    pub address: String,
+
+    /// Longest a scan may take, in seconds.
+    #[serde(default = "default_clamav_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Accept attachments when the daemon can't be reached or the scan
+    /// fails, instead of rejecting them.
+    #[serde(default)]
+    pub fail_open: bool,
+}
+
+impl Default for ClamAvConfig {
+    fn default() -> Self {
+        Self {
+            address: default_clamav_address(),
+            timeout_secs: default_clamav_timeout_secs(),
+            fail_open: false,
+        }
+    }
+}
+
+fn default_clamav_address() -> String {
+    "127.0.0.1:3310".to_string()
+}
+
+const fn default_clamav_timeout_secs() -> u64 {
+    30
+}
+
+/// Broadcasts send one message to many chats with the `broadcast.send` RPC
+/// method, paced per channel to stay under platform rate limits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastConfig {
+    /// Named groups of chats to broadcast to.
+    #[serde(default)]
+    pub groups: HashMap<String, BroadcastGroupConfig>,
+
+    /// Messages sent per second on a channel during a broadcast.
+    #[serde(default = "default_broadcast_per_second")]
+    pub per_second: f64,
+
+    /// Rate overrides by channel ID.
+    #[serde(default)]
+    pub channels: HashMap<String, f64>,
+
+    /// Times a message is retried after the channel reports rate limiting.
+    #[serde(default = "default_broadcast_max_retries")]
+    pub max_retries: u32,
+}
+
+impl BroadcastConfig {
+    /// Delay between consecutive messages on a channel.
+    #[must_use]
+    pub fn interval(&self, channel: &str) -> std::time::Duration {
+        let rate = self
+            .channels
+            .get(channel)
+            .copied()
+            .unwrap_or(self.per_second);
+        std::time::Duration::from_secs_f64(1.0 / rate)
+    }
+}
+
+impl Default for BroadcastConfig {
+    fn default() -> Self {
+        Self {
+            groups: HashMap::new(),
+            per_second: default_broadcast_per_second(),
+            channels: HashMap::new(),
+            max_retries: default_broadcast_max_retries(),
+        }
+    }
+}
+
+const fn default_broadcast_per_second() -> f64 {
+    1.0
+}
+
+const fn default_broadcast_max_retries() -> u32 {
+    3
+}
+
+/// A named group of chats on one channel.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastGroupConfig {
+    /// What the group is for.
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Channel the chats are on.
+    pub channel: String,
+
+    /// Chat IDs in the group.
+    pub chat_ids: Vec<String>,
+}
+
+/// Inbound message debouncing: consecutive messages from one peer that
+/// arrive within the window are answered as a single agent turn.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebounceConfig {
+    /// Quiet period to wait for further messages, in milliseconds (0 disables).
+    #[serde(default)]
+    pub window_ms: u64,
+
+    /// Longest a message is held before its batch is answered, in milliseconds.
+    #[serde(default = "default_debounce_max_wait_ms")]
+    pub max_wait_ms: u64,
+
+    /// Window overrides by channel ID.
+    #[serde(default)]
+    pub channels: HashMap<String, u64>,
+
+    /// Window overrides by agent ID (take precedence over channel overrides).
+    #[serde(default)]
+    pub agents: HashMap<String, u64>,
+}
+
+impl Default for DebounceConfig {
+    fn default() -> Self {
+        Self {
+            window_ms: 0,
+            max_wait_ms: default_debounce_max_wait_ms(),
+            channels: HashMap::new(),
+            agents: HashMap::new(),
+        }
+    }
+}
+
+const fn default_debounce_max_wait_ms() -> u64 {
+    5000
+}
+
+/// How agents behave in group chats. Direct messages are always answered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupsConfig {
+    /// Answer only messages that mention the agent or reply to it.
+    #[serde(default = "default_true")]
+    pub require_mention: bool,
+
+    /// Answer in a thread on channels that support them.
+    #[serde(default = "default_true")]
+    pub threads: bool,
+
+    /// Least time between answers in one group, in seconds (0 disables).
+    #[serde(default)]
+    pub cooldown_secs: u64,
+
+    /// Handles the agent answers to when mentioned (e.g. the Telegram bot
+    /// username), besides its account ID.
+    #[serde(default)]
+    pub names: Vec<String>,
+
+    /// Whether a session covers a whole group or one thread in it.
+    #[serde(default)]
+    pub session_scope: SessionScope,
+
+    /// Session scope by agent ID.
+    #[serde(default)]
+    pub agents: HashMap<String, SessionScope>,
+
+    /// Overrides by channel ID.
+    #[serde(default)]
+    pub channels: HashMap<String, GroupOverrides>,
+
+    /// Overrides by group, keyed `channel:chat_id` (take precedence over
+    /// channel overrides).
+    #[serde(default)]
+    pub chats: HashMap<String, GroupOverrides>,
+}
+
+impl GroupsConfig {
+    /// Overrides for `chat` on `channel`, most specific first.
+    fn overrides<'a>(
+        &'a self,
+        channel: &str,
+        chat: &str,
+    ) -> impl Iterator<Item = &'a GroupOverrides> + 'a {
+        self.chats
+            .get(&format!("{channel}:{chat}"))
+            .into_iter()
+            .chain(self.channels.get(channel))
+    }
+
+    /// Whether messages in `chat` on `channel` must address the agent.
+    #[must_use]
+    pub fn require_mention_for(&self, channel: &str, chat: &str) -> bool {
+        self.overrides(channel, chat)
+            .find_map(|o| o.require_mention)
+            .unwrap_or(self.require_mention)
+    }
+
+    /// Whether answers in `chat` on `channel` go in a thread.
+    #[must_use]
+    pub fn threads_for(&self, channel: &str, chat: &str) -> bool {
+        self.overrides(channel, chat)
+            .find_map(|o| o.threads)
+            .unwrap_or(self.threads)
+    }
+
+    /// Least time between answers in `chat` on `channel`, in seconds.
+    #[must_use]
+    pub fn cooldown_secs_for(&self, channel: &str, chat: &str) -> u64 {
+        self.overrides(channel, chat)
+            .find_map(|o| o.cooldown_secs)
+            .unwrap_or(self.cooldown_secs)
+    }
+
+    /// What sessions of `agent` cover in groups.
+    #[must_use]
+    pub fn session_scope_for(&self, agent: &str) -> SessionScope {
+        self.agents
+            .get(agent)
+            .copied()
+            .unwrap_or(self.session_scope)
+    }
+}
+
+impl Default for GroupsConfig {
+    fn default() -> Self {
+        Self {
+            require_mention: true,
+            threads: true,
+            cooldown_secs: 0,
+            names: Vec::new(),
+            session_scope: SessionScope::default(),
+            agents: HashMap::new(),
+            channels: HashMap::new(),
+            chats: HashMap::new(),
+        }
+    }
+}
+
+/// Slash commands (`/reset`, `/model`, `/usage`, `/help`) people can send
+/// in chat, answered by the gateway without reaching the agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatCommandsConfig {
+    /// Interpret commands; when off they reach the agent as messages.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Commands that reach the agent as messages instead (e.g. `"reset"`).
+    #[serde(default)]
+    pub disabled: Vec<String>,
+
+    /// Who may use commands. Empty lets anyone.
+    #[serde(default)]
+    pub allow: Vec<AllowlistEntry>,
+
+    /// Models `/model` may switch a session to. Empty fixes the model.
+    #[serde(default)]
+    pub models: Vec<String>,
+
+    /// Overrides by agent ID.
+    #[serde(default)]
+    pub agents: HashMap<String, AgentChatCommands>,
+}
+
+impl ChatCommandsConfig {
+    /// Whether `command` is interpreted for `agent`.
+    #[must_use]
+    pub fn enabled_for(&self, agent: &str, command: &str) -> bool {
+        let overrides = self.agents.get(agent);
+        overrides.and_then(|a| a.enabled).unwrap_or(self.enabled)
+            && !overrides
+                .and_then(|a| a.disabled.as_ref())
+                .unwrap_or(&self.disabled)
+                .iter()
+                .any(|c| c == command)
+    }
+
+    /// Who may use commands with `agent`.
+    #[must_use]
+    pub fn allow_for(&self, agent: &str) -> &[AllowlistEntry] {
+        self.agents
+            .get(agent)
+            .and_then(|a| a.allow.as_deref())
+            .unwrap_or(&self.allow)
+    }
+
+    /// Models `/model` may switch sessions of `agent` to.
+    #[must_use]
+    pub fn models_for(&self, agent: &str) -> &[String] {
+        self.agents
+            .get(agent)
+            .and_then(|a| a.models.as_deref())
+            .unwrap_or(&self.models)
+    }
+}
+
+impl Default for ChatCommandsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            disabled: Vec::new(),
+            allow: Vec::new(),
+            models: Vec::new(),
+            agents: HashMap::new(),
+        }
+    }
+}
+
+/// Chat command overrides for one agent.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentChatCommands {
+    /// Interpret commands.
+    #[serde(default)]
+    pub enabled: Option<bool>,
+
+    /// Commands that reach the agent as messages instead.
+    #[serde(default)]
+    pub disabled: Option<Vec<String>>,
+
+    /// Who may use commands.
+    #[serde(default)]
+    pub allow: Option<Vec<AllowlistEntry>>,
+
+    /// Models `/model` may switch to.
+    #[serde(default)]
+    pub models: Option<Vec<String>>,
+}
+
+/// What a session covers in a group chat.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionScope {
+    /// The whole group; its threads share one history.
+    #[default]
+    Group,
+    /// One thread; each thread has its own history.
+    Thread,
+}
+
+/// Group chat overrides for one channel or group.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupOverrides {
+    /// Answer only messages that address the agent.
+    #[serde(default)]
+    pub require_mention: Option<bool>,
+
+    /// Answer in a thread.
+    #[serde(default)]
+    pub threads: Option<bool>,
+
+    /// Least time between answers, in seconds.
+    #[serde(default)]
+    pub cooldown_secs: Option<u64>,
+}
+
+/// Hours when agents answer, by channel and agent. Channels and agents
+/// without hours answer around the clock.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkingHoursConfig {
+    /// Hours by channel ID.
+    #[serde(default)]
+    pub channels: HashMap<String, WorkingHours>,
+
+    /// Hours by agent ID (take precedence over channel hours).
+    #[serde(default)]
+    pub agents: HashMap<String, WorkingHours>,
+}
+
+impl WorkingHoursConfig {
+    /// Hours for messages on `channel` answered by `agent`, if any.
+    #[must_use]
+    pub fn hours_for(&self, channel: &str, agent: &str) -> Option<&WorkingHours> {
+        self.agents
+            .get(agent)
+            .or_else(|| self.channels.get(channel))
+    }
+
+    /// Check every schedule.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first invalid schedule.
+    pub fn validate(&self) -> Result<(), String> {
+        let schedules = self
+            .channels
+            .iter()
+            .map(|(id, hours)| ("channel", id, hours))
+            .chain(self.agents.iter().map(|(id, hours)| ("agent", id, hours)));
+        for (kind, id, hours) in schedules {
+            hours
+                .validate()
+                .map_err(|e| format!("{kind} '{id}': {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// When one channel or agent answers messages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct ChannelsConfig {
-    /// Telegram channel config.
+pub struct WorkingHours {
+    /// IANA timezone the windows are in, e.g. "Europe/Berlin".
+    #[serde(default = "default_hours_timezone")]
+    pub timezone: String,
+
+    /// When messages are answered; empty means around the clock.
     #[serde(default)]
-    pub telegram: Option<TelegramConfig>,
+    pub open: Vec<HoursWindow>,
 
-    /// Discord channel config.
+    /// Do-not-disturb windows, closed even inside `open`.
     #[serde(default)]
-    pub discord: Option<DiscordConfig>,
+    pub quiet: Vec<HoursWindow>,
 
-    /// Slack channel config.
+    /// What happens to messages outside hours.
     #[serde(default)]
-    pub slack: Option<SlackConfig>,
+    pub outside: OutsideHoursAction,
 
-    /// Signal channel config.
+    /// Reply sent outside hours, with `{opens_at}` replaced by when hours
+    /// resume (`None` for the localized message).
     #[serde(default)]
-    pub signal: Option<SignalConfig>,
+    pub message: Option<String>,
 
-    /// Matrix channel config.
+    /// Whether a message starting with `/urgent` is answered anyway.
+    #[serde(default = "default_true")]
+    pub allow_urgent: bool,
+
+    /// How long a session stays open after `/urgent`, in minutes.
+    #[serde(default = "default_urgent_minutes")]
+    pub urgent_minutes: u32,
+}
+
+impl Default for WorkingHours {
+    fn default() -> Self {
+        Self {
+            timezone: default_hours_timezone(),
+            open: Vec::new(),
+            quiet: Vec::new(),
+            outside: OutsideHoursAction::default(),
+            message: None,
+            allow_urgent: true,
+            urgent_minutes: default_urgent_minutes(),
+        }
+    }
+}
+
+impl WorkingHours {
+    /// The timezone.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if `timezone` is not an IANA timezone name.
+    pub fn tz(&self) -> Result<chrono_tz::Tz, String> {
+        self.timezone
+            .parse()
+            .map_err(|_| format!("'{}' is not a timezone", self.timezone))
+    }
+
+    /// Check the timezone and windows.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first problem.
+    pub fn validate(&self) -> Result<(), String> {
+        self.tz()?;
+        for window in self.open.iter().chain(&self.quiet) {
+            window.parse()?;
+        }
+        if self.urgent_minutes == 0 {
+            return Err("urgentMinutes must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A daily time window, e.g. 09:00 to 18:00 on weekdays. A window ending
+/// at or before its start runs past midnight into the next day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HoursWindow {
+    /// Days the window starts on (`mon` to `sun`); empty means every day.
     #[serde(default)]
-    pub matrix: Option<MatrixConfig>,
+    pub days: Vec<String>,
+
+    /// Start time, `HH:MM`.
+    pub start: String,
+
+    /// End time, `HH:MM`.
+    pub end: String,
+}
+
+impl HoursWindow {
+    /// The window's days, start time, and end time.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a day or time does not parse.
+    pub fn parse(
+        &self,
+    ) -> Result<(Vec<chrono::Weekday>, chrono::NaiveTime, chrono::NaiveTime), String> {
+        let days = self
+            .days
+            .iter()
+            .map(|day| {
+                day.parse()
+                    .map_err(|_| format!("'{day}' is not a day of the week"))
+            })
+            .collect::<Result<_, _>>()?;
+        let time = |value: &str| {
+            chrono::NaiveTime::parse_from_str(value, "%H:%M")
+                .map_err(|_| format!("'{value}' is not a time (HH:MM)"))
+        };
+        Ok((days, time(&self.start)?, time(&self.end)?))
+    }
+}
+
+/// What happens to a message that arrives outside working hours.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OutsideHoursAction {
+    /// Reply that hours are closed; the agent never sees the message.
+    #[default]
+    AutoReply,
+    /// Reply that hours are closed, and have the agent answer the message
+    /// when hours resume.
+    Queue,
+}
+
+fn default_hours_timezone() -> String {
+    "UTC".to_string()
+}
+
+const fn default_urgent_minutes() -> u32 {
+    30
+}
+
+/// How agent replies (written in Markdown) are converted for a channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboundFormat {
+    /// Send Markdown unchanged.
+    Markdown,
+    /// Telegram HTML (`<b>`, `<i>`, `<code>`, `<pre>`, `<a>`).
+    TelegramHtml,
+    /// Slack mrkdwn (`*bold*`, `_italic_`, `<url|text>`).
+    SlackMrkdwn,
+    /// `WhatsApp` formatting (`*bold*`, `_italic_`, `~strike~`).
+    Whatsapp,
+    /// Plain text with formatting removed.
+    Plain,
+}
+
+/// Outbound formatting for one channel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelFormatConfig {
+    /// Format to convert replies to (defaults to the channel's native one).
+    #[serde(default)]
+    pub format: Option<OutboundFormat>,
+
+    /// Maximum characters per message; lowers the channel's own limit.
+    #[serde(default)]
+    pub chunk_limit: Option<usize>,
+
+    /// Number the parts of a reply split into several messages.
+    #[serde(default = "default_true")]
+    pub number_parts: bool,
+}
+
+impl Default for ChannelFormatConfig {
+    fn default() -> Self {
+        Self {
+            format: None,
+            chunk_limit: None,
+            number_parts: true,
+        }
+    }
+}
+
+/// Inbound message rate limiting (token bucket per peer and per channel).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundRateLimitConfig {
+    /// Enable inbound rate limiting.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Sustained messages per minute allowed per (channel, peer).
+    #[serde(default = "default_peer_per_minute")]
+    pub peer_per_minute: u32,
+
+    /// Burst size per (channel, peer).
+    #[serde(default = "default_peer_burst")]
+    pub peer_burst: u32,
+
+    /// Sustained messages per minute allowed per channel (unlimited if unset).
+    #[serde(default)]
+    pub channel_per_minute: Option<u32>,
+
+    /// Reply sent when a peer is throttled, instead of the localized one.
+    /// `{retry_after}` is replaced with seconds.
+    #[serde(default)]
+    pub cooldown_message: Option<String>,
+}
+
+impl Default for InboundRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            peer_per_minute: default_peer_per_minute(),
+            peer_burst: default_peer_burst(),
+            channel_per_minute: None,
+            cooldown_message: None,
+        }
+    }
+}
+
+const fn default_peer_per_minute() -> u32 {
+    20
+}
+
+const fn default_peer_burst() -> u32 {
+    5
 }
 
 /// Telegram channel configuration.
@@ -389,80 +3401,413 @@ pub struct MatrixConfig {
     pub access_token: Option<String>,
 }
 
+/// `WhatsApp` Cloud API channel configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhatsAppConfig {
+    /// Cloud API access token.
+    pub access_token: Option<String>,
+
+    /// Business phone number ID.
+    pub phone_number_id: Option<String>,
+
+    /// App secret used to verify `X-Hub-Signature-256` on webhooks. The
+    /// gateway accepts inbound messages only when it is set.
+    pub app_secret: Option<String>,
+
+    /// Token Meta sends when subscribing the webhook.
+    #[serde(default)]
+    pub verify_token: Option<String>,
+}
+
 /// Provider configurations.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProvidersConfig {
     /// Anthropic configuration.
     #[serde(default)]
-    pub anthropic: Option<AnthropicConfig>,
+    pub anthropic: Option<AnthropicConfig>,
+
+    /// `OpenAI` configuration.
+    #[serde(default)]
+    pub openai: Option<OpenAIConfig>,
+
+    /// Ollama configuration.
+    #[serde(default)]
+    pub ollama: Option<OllamaConfig>,
+
+    /// Request/response wire log for debugging provider calls.
+    #[serde(default)]
+    pub wire_log: WireLogConfig,
+
+    /// Automatic retries for transient provider errors.
+    #[serde(default)]
+    pub retry: ProviderRetryConfig,
+
+    /// Record provider calls to disk, or replay recorded ones.
+    #[serde(default)]
+    pub recording: ProviderRecordingConfig,
+}
+
+/// Provider call recording configuration.
+///
+/// Recordings are keyed by a hash of the request, so replaying a run
+/// returns the recorded response for each identical request without
+/// calling the provider.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderRecordingConfig {
+    /// Whether calls are recorded, replayed, or neither.
+    #[serde(default)]
+    pub mode: RecordingMode,
+
+    /// Recordings directory (defaults to `~/.openclaw/recordings`).
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+}
+
+/// How provider calls use recordings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingMode {
+    /// Call the provider; nothing is recorded.
+    #[default]
+    Off,
+    /// Call the provider and record every response.
+    Record,
+    /// Answer only from recordings; unrecorded requests fail.
+    Replay,
+    /// Answer from recordings, recording requests not seen before.
+    Auto,
+}
+
+/// Provider retry configuration.
+///
+/// Rate-limited, overloaded, 5xx, and network errors are retried with
+/// exponential backoff and jitter; a provider-supplied `retry-after` is
+/// honored up to `maxBackoffMs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderRetryConfig {
+    /// Retries after the first attempt (0 disables retrying).
+    #[serde(default = "default_provider_max_retries")]
+    pub max_retries: u32,
+
+    /// Backoff before the first retry.
+    #[serde(default = "default_provider_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+
+    /// Upper bound on a single backoff.
+    #[serde(default = "default_provider_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+}
+
+impl Default for ProviderRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: default_provider_max_retries(),
+            initial_backoff_ms: default_provider_initial_backoff_ms(),
+            max_backoff_ms: default_provider_max_backoff_ms(),
+        }
+    }
+}
+
+const fn default_provider_max_retries() -> u32 {
+    3
+}
+
+const fn default_provider_initial_backoff_ms() -> u64 {
+    1000
+}
+
+const fn default_provider_max_backoff_ms() -> u64 {
+    30_000
+}
+
+/// Provider wire log configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WireLogConfig {
+    /// Enable the wire log.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Log file path (defaults to `~/.openclaw/logs/providers.jsonl`).
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+
+    /// Characters of request/response body kept per entry.
+    #[serde(default = "default_wire_log_body_chars")]
+    pub max_body_chars: usize,
+
+    /// Capture full bodies (debug environments only).
+    #[serde(default)]
+    pub full_bodies: bool,
+
+    /// Rotate the file once it reaches this size.
+    #[serde(default = "default_wire_log_file_bytes")]
+    pub max_file_bytes: u64,
+
+    /// Rotated files to keep.
+    #[serde(default = "default_wire_log_files")]
+    pub max_files: usize,
+}
+
+impl Default for WireLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: None,
+            max_body_chars: default_wire_log_body_chars(),
+            full_bodies: false,
+            max_file_bytes: default_wire_log_file_bytes(),
+            max_files: default_wire_log_files(),
+        }
+    }
+}
+
+const fn default_wire_log_body_chars() -> usize {
+    2048
+}
+
+const fn default_wire_log_file_bytes() -> u64 {
+    10 * 1024 * 1024
+}
+
+const fn default_wire_log_files() -> usize {
+    5
+}
+
+/// Anthropic provider configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnthropicConfig {
+    /// API key (prefer credential store).
+    pub api_key: Option<String>,
+
+    /// Base URL override.
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+/// `OpenAI` provider configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenAIConfig {
+    /// API key (prefer credential store).
+    pub api_key: Option<String>,
+
+    /// Base URL override.
+    #[serde(default)]
+    pub base_url: Option<String>,
+
+    /// Organization ID.
+    #[serde(default)]
+    pub org_id: Option<String>,
+}
+
+/// Ollama provider configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OllamaConfig {
+    /// Base URL.
+    #[serde(default = "default_ollama_url")]
+    pub base_url: String,
+}
+
+fn default_ollama_url() -> String {
+    "http://localhost:11434".to_string()
+}
+
+/// Global settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GlobalSettings {
+    /// Enable debug logging.
+    #[serde(default)]
+    pub debug: bool,
+
+    /// Log format.
+    #[serde(default)]
+    pub log_format: LogFormat,
+
+    /// Patterns whose following value is redacted from log output, in
+    /// addition to the built-in ones (e.g. `"session_secret="`).
+    #[serde(default)]
+    pub log_scrub_patterns: Vec<String>,
+
+    /// Rotating log files written by the gateway.
+    #[serde(default)]
+    pub log_file: LogFileConfig,
+
+    /// Telemetry enabled.
+    #[serde(default)]
+    pub telemetry: bool,
+
+    /// Object storage for attachments, backups, and archives.
+    #[serde(default)]
+    pub storage: StorageConfig,
+
+    /// Days a soft-deleted session is kept before it is purged.
+    #[serde(default = "default_deleted_session_retention_days")]
+    pub deleted_session_retention_days: u32,
+
+    /// Days after which ended sessions are moved to compressed cold
+    /// storage. Archival is off when unset.
+    #[serde(default)]
+    pub archive_ended_after_days: Option<u32>,
+
+    /// Automatic ending of inactive sessions.
+    #[serde(default)]
+    pub idle_sessions: IdleSessionConfig,
+
+    /// Encrypt session events at rest with a key derived from the master
+    /// key. Existing stores must be migrated with `openclaw sessions encrypt`.
+    #[serde(default)]
+    pub encrypt_event_store: bool,
+
+    /// Encoding for newly written session events. Both formats are read;
+    /// convert existing events with `openclaw sessions compact`.
+    #[serde(default)]
+    pub event_store_format: EventStoreFormat,
+
+    /// Database holding the event store. Move an existing sled store to
+    /// `SQLite` with `openclaw sessions migrate-storage`.
+    #[serde(default)]
+    pub event_store_backend: EventStoreBackend,
 
-    /// `OpenAI` configuration.
+    /// Where the master key is kept.
     #[serde(default)]
-    pub openai: Option<OpenAIConfig>,
+    pub master_key_source: MasterKeySource,
 
-    /// Ollama configuration.
+    /// Encrypted backups to `storage`.
     #[serde(default)]
-    pub ollama: Option<OllamaConfig>,
+    pub backup: BackupConfig,
 }
 
-/// Anthropic provider configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct AnthropicConfig {
-    /// API key (prefer credential store).
-    pub api_key: Option<String>,
+impl Default for GlobalSettings {
+    fn default() -> Self {
+        Self {
+            debug: false,
+            log_format: LogFormat::default(),
+            log_scrub_patterns: Vec::new(),
+            log_file: LogFileConfig::default(),
+            telemetry: false,
+            storage: StorageConfig::default(),
+            deleted_session_retention_days: default_deleted_session_retention_days(),
+            archive_ended_after_days: None,
+            idle_sessions: IdleSessionConfig::default(),
+            encrypt_event_store: false,
+            event_store_format: EventStoreFormat::default(),
+            event_store_backend: EventStoreBackend::default(),
+            master_key_source: MasterKeySource::default(),
+            backup: BackupConfig::default(),
+        }
+    }
+}
 
-    /// Base URL override.
-    #[serde(default)]
-    pub base_url: Option<String>,
+const fn default_deleted_session_retention_days() -> u32 {
+    30
 }
 
-/// `OpenAI` provider configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Ending sessions after a period of inactivity. The next message from the
+/// peer starts a fresh conversation; earlier history stays queryable.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct OpenAIConfig {
-    /// API key (prefer credential store).
-    pub api_key: Option<String>,
+pub struct IdleSessionConfig {
+    /// Minutes without activity after which a session is ended. Sessions
+    /// stay open indefinitely when unset.
+    #[serde(default)]
+    pub ttl_minutes: Option<u32>,
 
-    /// Base URL override.
+    /// Message sent to the peer when their session is ended.
     #[serde(default)]
-    pub base_url: Option<String>,
+    pub goodbye_message: Option<String>,
 
-    /// Organization ID.
+    /// Have the agent summarize the conversation in the goodbye message.
     #[serde(default)]
-    pub org_id: Option<String>,
+    pub summarize: bool,
 }
 
-/// Ollama provider configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Scheduled backups of sessions, config, and credentials.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct OllamaConfig {
-    /// Base URL.
-    #[serde(default = "default_ollama_url")]
-    pub base_url: String,
+pub struct BackupConfig {
+    /// Hours between backups taken by the running gateway. Scheduled
+    /// backups are off when unset; `openclaw backup run` works regardless.
+    #[serde(default)]
+    pub interval_hours: Option<u32>,
+
+    /// Number of backups to keep; older ones are deleted after each backup.
+    /// All backups are kept when unset.
+    #[serde(default)]
+    pub keep: Option<u32>,
 }
 
-fn default_ollama_url() -> String {
-    "http://localhost:11434".to_string()
+/// Object storage backend configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "lowercase")]
+pub enum StorageConfig {
+    /// Local directory (defaults to `~/.openclaw/blobs`).
+    #[default]
+    Local,
+    /// Local directory at an explicit path.
+    Dir {
+        /// Root directory for stored objects.
+        path: PathBuf,
+    },
+    /// S3-compatible object storage (AWS S3, `MinIO`, R2, ...).
+    S3(S3StorageConfig),
 }
 
-/// Global settings.
+/// S3-compatible storage settings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-#[derive(Default)]
-pub struct GlobalSettings {
-    /// Enable debug logging.
+pub struct S3StorageConfig {
+    /// Bucket name.
+    pub bucket: String,
+
+    /// Region (use any value for `MinIO`).
+    #[serde(default = "default_s3_region")]
+    pub region: String,
+
+    /// Custom endpoint URL for S3-compatible services.
     #[serde(default)]
-    pub debug: bool,
+    pub endpoint: Option<String>,
 
-    /// Log format.
+    /// Key prefix applied to every object.
     #[serde(default)]
-    pub log_format: LogFormat,
+    pub prefix: Option<String>,
 
-    /// Telemetry enabled.
+    /// Credential name in the credential store holding the access key ID.
     #[serde(default)]
-    pub telemetry: bool,
+    pub access_key_id_credential: Option<String>,
+
+    /// Credential name in the credential store holding the secret access key.
+    #[serde(default)]
+    pub secret_access_key_credential: Option<String>,
+
+    /// Allow plain HTTP endpoints (local `MinIO`).
+    #[serde(default)]
+    pub allow_http: bool,
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
+/// Where the master key is kept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MasterKeySource {
+    /// Hex key file in the state directory.
+    #[default]
+    File,
+    /// OS credential store (macOS Keychain, Linux Secret Service, Windows
+    /// Credential Manager).
+    Keychain,
+    /// Key file encrypted with a passphrase (Argon2id).
+    Passphrase,
 }
 
 /// Log format.
@@ -476,11 +3821,92 @@ pub enum LogFormat {
     Json,
 }
 
+/// Gateway log files, rotated daily and by size.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFileConfig {
+    /// Write log files whenever the gateway runs, not only as a daemon.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Directory for log files (defaults to `~/.openclaw/logs`).
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+
+    /// Start a new file for the day once the current one reaches this size.
+    #[serde(default = "default_log_file_bytes")]
+    pub max_file_bytes: u64,
+
+    /// Days of log files kept.
+    #[serde(default = "default_log_retention_days")]
+    pub retention_days: u32,
+}
+
+impl Default for LogFileConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: None,
+            max_file_bytes: default_log_file_bytes(),
+            retention_days: default_log_retention_days(),
+        }
+    }
+}
+
+impl LogFileConfig {
+    /// Directory log files are written to.
+    #[must_use]
+    pub fn dir(&self) -> PathBuf {
+        self.dir.clone().unwrap_or_else(Config::logs_dir)
+    }
+}
+
+const fn default_log_file_bytes() -> u64 {
+    50 * 1024 * 1024
+}
+
+const fn default_log_retention_days() -> u32 {
+    14
+}
+
+/// Encoding of session events in the event store.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventStoreFormat {
+    /// JSON records.
+    #[default]
+    Json,
+    /// Compact rkyv records.
+    Binary,
+}
+
+/// Database holding the event store.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventStoreBackend {
+    /// sled database directory.
+    #[default]
+    Sled,
+    /// Single `SQLite` file in WAL mode (requires the `sqlite` feature).
+    Sqlite,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_unknown_keys() {
+        let raw = serde_json::json!({
+            "gateway": { "port": 18789, "bind": "loopback" },
+            "agnets": {},
+            "agents": { "my-agent": { "model": "gpt-4o" } },
+            "settings": { "debug": true },
+        });
+        assert_eq!(Config::unknown_keys(&raw), ["agnets", "gateway.bind"]);
+    }
+
     #[test]
     fn test_default_config() {
         let config = Config::default();
@@ -535,6 +3961,753 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_storage_config_parsing() {
+        let config: Config = json5::from_str(
+            r#"{
+                settings: {
+                    storage: {
+                        backend: "s3",
+                        bucket: "openclaw",
+                        endpoint: "http://localhost:9000",
+                        allowHttp: true,
+                    },
+                },
+            }"#,
+        )
+        .unwrap();
+
+        match config.settings.storage {
+            StorageConfig::S3(s3) => {
+                assert_eq!(s3.bucket, "openclaw");
+                assert_eq!(s3.region, "us-east-1");
+                assert!(s3.allow_http);
+            }
+            other => panic!("unexpected storage config: {other:?}"),
+        }
+
+        let config = Config::default();
+        assert!(matches!(config.settings.storage, StorageConfig::Local));
+    }
+
+    #[test]
+    fn test_schedule_config_parsing() {
+        let config: Config = json5::from_str(
+            r#"{
+                schedules: {
+                    "daily-summary": {
+                        cron: "0 9 * * *",
+                        prompt: "Summarize yesterday",
+                        deliver: { channel: "slack", chatId: "C123" },
+                        jitterSecs: 60,
+                    },
+                },
+            }"#,
+        )
+        .unwrap();
+
+        let schedule = &config.schedules["daily-summary"];
+        assert_eq!(schedule.agent, "default");
+        assert_eq!(schedule.catch_up, CatchUpPolicy::Once);
+        assert_eq!(schedule.deliver.as_ref().unwrap().chat_id, "C123");
+        assert!(config.validate().is_ok());
+
+        let mut config = config;
+        config.schedules.get_mut("daily-summary").unwrap().prompt = None;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_notification_template_parsing() {
+        let mut config: Config = json5::from_str(
+            r#"{
+                notifications: {
+                    "disk-alert": {
+                        body: "Disk {{ host }} at {{ percent }}%",
+                        channels: { slack: ":warning: *{{ host }}* disk at {{ percent }}%" },
+                        required: ["host"],
+                        defaults: { percent: 90 },
+                    },
+                },
+                schedules: {
+                    "nightly-digest": {
+                        cron: "0 7 * * *",
+                        template: "disk-alert",
+                        vars: { host: "db1" },
+                        deliver: { channel: "telegram", chatId: "42" },
+                    },
+                },
+            }"#,
+        )
+        .unwrap();
+
+        let template = &config.notifications["disk-alert"];
+        assert_eq!(template.required, vec!["host"]);
+        assert_eq!(template.defaults["percent"], 90);
+        assert!(template.channels.contains_key("slack"));
+        assert!(config.validate().is_ok());
+
+        config.schedules.get_mut("nightly-digest").unwrap().deliver = None;
+        assert!(config.validate().is_err());
+
+        config.schedules.get_mut("nightly-digest").unwrap().template = Some("missing".into());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_attachment_policy_parsing() {
+        let mut config: Config = json5::from_str(
+            r#"{
+                channels: {
+                    attachments: {
+                        maxBytes: { image: 5000000, video: 999999999999 },
+                        clamav: { address: "clamd:3310" },
+                        channels: {
+                            telegram: { maxBytes: { image: 1000000 }, scan: false },
+                            signal: { sniffMime: false },
+                        },
+                    },
+                },
+            }"#,
+        )
+        .unwrap();
+
+        let policy = &config.channels.attachments;
+        let global_max = crate::validation::limits::MAX_ATTACHMENT_SIZE as u64;
+        assert_eq!(policy.max_bytes_for("slack", "image"), 5_000_000);
+        assert_eq!(policy.max_bytes_for("telegram", "image"), 1_000_000);
+        assert_eq!(policy.max_bytes_for("slack", "video"), global_max);
+        assert_eq!(policy.max_bytes_for("slack", "document"), global_max);
+        assert!(policy.sniff_mime_for("telegram"));
+        assert!(!policy.sniff_mime_for("signal"));
+        assert_eq!(policy.clamav_for("slack").unwrap().timeout_secs, 30);
+        assert!(policy.clamav_for("telegram").is_none());
+        assert!(config.validate().is_ok());
+
+        config
+            .channels
+            .attachments
+            .max_bytes
+            .insert("images".into(), 1);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_broadcast_config_parsing() {
+        let mut config: Config = json5::from_str(
+            r#"{
+                channels: {
+                    broadcast: {
+                        perSecond: 2,
+                        channels: { telegram: 20 },
+                        groups: {
+                            oncall: { channel: "slack", chatIds: ["C1", "C2"] },
+                        },
+                    },
+                },
+            }"#,
+        )
+        .unwrap();
+
+        let broadcast = &config.channels.broadcast;
+        assert_eq!(broadcast.groups["oncall"].chat_ids, ["C1", "C2"]);
+        assert_eq!(broadcast.max_retries, 3);
+        assert_eq!(broadcast.interval("slack").as_millis(), 500);
+        assert_eq!(broadcast.interval("telegram").as_millis(), 50);
+        assert!(config.validate().is_ok());
+
+        config
+            .channels
+            .broadcast
+            .channels
+            .insert("discord".into(), 0.0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_timeout_config_parsing() {
+        let config: Config = json5::from_str(
+            r#"{
+                gateway: {
+                    routeTimeouts: { "/rpc": 900 },
+                    methodTimeouts: { "session.*": 120 },
+                },
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.gateway.route_timeouts["/rpc"], 900);
+        assert_eq!(config.gateway.method_timeouts["session.*"], 120);
+        assert!(config.validate().is_ok());
+
+        let defaults = Config::default();
+        assert_eq!(defaults.gateway.method_timeouts["system.*"], 10);
+
+        let mut config = config;
+        config
+            .gateway
+            .method_timeouts
+            .insert("tools.execute".to_string(), 0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_request_limits() {
+        let config = Config::from_json5(
+            r#"{
+                gateway: {
+                    limits: { maxBodyBytes: 65536, wsLagPolicy: "disconnect" },
+                },
+            }"#,
+        )
+        .unwrap();
+        let limits = &config.gateway.limits;
+        assert_eq!(limits.max_body_bytes, 65536);
+        assert_eq!(limits.ws_max_message_bytes, 2 * 1024 * 1024);
+        assert_eq!(limits.ws_lag_policy, WsLagPolicy::Disconnect);
+
+        let mut config = config;
+        config.gateway.limits.ws_send_timeout_secs = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_cors_config() {
+        let config = Config::from_json5(
+            r#"{
+                gateway: {
+                    cors: { allowedOrigins: ["https://claw.example.com"], maxAgeSecs: 60 },
+                },
+            }"#,
+        )
+        .unwrap();
+        let cors = &config.gateway.cors;
+        assert!(cors.allows_origin("https://claw.example.com"));
+        assert!(cors.allows_origin("HTTPS://claw.example.com/"));
+        assert!(!cors.allows_origin("https://evil.example.com"));
+        assert_eq!(cors.allowed_headers, ["authorization", "content-type"]);
+        assert!(config.validate().is_ok());
+
+        // Older configs used a boolean
+        let legacy = Config::from_json5("{ gateway: { cors: false } }").unwrap();
+        assert!(legacy.gateway.cors.allowed_origins.is_empty());
+        let legacy = Config::from_json5("{ gateway: { cors: true } }").unwrap();
+        assert_eq!(legacy.gateway.cors, CorsConfig::default());
+
+        let mut config = config;
+        config.gateway.cors.allowed_origins = vec!["*".to_string()];
+        config.gateway.cors.allow_credentials = true;
+        assert!(config.validate().is_err());
+        config.gateway.cors.allowed_origins = vec!["claw.example.com".to_string()];
+        config.gateway.cors.allow_credentials = false;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_proxy_config() {
+        let mut config = Config::from_json5(
+            r#"{
+                gateway: {
+                    proxy: { basePath: "/openclaw", trustedProxies: ["10.0.0.0/8", "::1"] },
+                },
+            }"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        let ranges = config.gateway.proxy.trusted_ranges().unwrap();
+        assert!(ranges[0].contains("10.1.2.3".parse().unwrap()));
+        assert!(ranges[0].contains("::ffff:10.1.2.3".parse().unwrap()));
+        assert!(!ranges[0].contains("192.168.1.1".parse().unwrap()));
+        assert!(ranges[1].contains("::1".parse().unwrap()));
+        assert!(
+            "0.0.0.0/0"
+                .parse::<IpRange>()
+                .unwrap()
+                .contains("8.8.8.8".parse().unwrap())
+        );
+
+        config.gateway.proxy.trusted_proxies = vec!["10.0.0.0/33".to_string()];
+        assert!(config.validate().is_err());
+        config.gateway.proxy.trusted_proxies.clear();
+        config.gateway.proxy.base_path = "openclaw/".to_string();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_unix_socket_config() {
+        let mut config = Config::from_json5(
+            r#"{ gateway: { unixSocket: { path: "/run/openclaw/gateway.sock" } } }"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        let socket = config.gateway.unix_socket.as_mut().unwrap();
+        assert_eq!(socket.permissions(), Ok(0o600));
+        assert!(socket.tcp);
+
+        socket.mode = "0660".to_string();
+        assert_eq!(socket.permissions(), Ok(0o660));
+        socket.mode = "rw-rw----".to_string();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_rpc_rate_limit() {
+        let config = Config::from_json5(
+            r#"{
+                gateway: {
+                    rateLimit: { perMinute: 120, methods: { "agent.*": 10 }, users: { ci: 1200 } },
+                },
+            }"#,
+        )
+        .unwrap();
+        let rate_limit = &config.gateway.rate_limit;
+        assert_eq!(rate_limit.per_minute, 120);
+        assert_eq!(rate_limit.burst, 60);
+        assert_eq!(rate_limit.methods["agent.*"], 10);
+        // Setting methods replaces the defaults
+        assert!(!rate_limit.methods.contains_key("session.message"));
+        assert_eq!(rate_limit.users["ci"], 1200);
+
+        let mut config = config;
+        config
+            .gateway
+            .rate_limit
+            .users
+            .insert("idle".to_string(), 0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_oidc_config() {
+        let mut config = Config::from_json5(
+            r#"{
+                gateway: {
+                    oidc: {
+                        issuer: "https://id.example.com",
+                        clientId: "openclaw",
+                        redirectUrl: "https://claw.example.com/auth/oidc/callback",
+                        roleRules: [{ claim: "groups", value: "ops", role: "operator" }],
+                    },
+                },
+            }"#,
+        )
+        .unwrap();
+        let oidc = config.gateway.oidc.as_ref().unwrap();
+        assert_eq!(oidc.username_claim, "preferred_username");
+        assert_eq!(oidc.scopes, ["openid", "profile", "email"]);
+        assert!(config.validate().is_ok());
+
+        config.gateway.oidc.as_mut().unwrap().default_role = Some("root".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_agent_inheritance() {
+        let config = Config::from_json5(
+            r#"{
+                agents: {
+                    base: { model: "gpt-4o", temperature: 0.2, tools: ["search"] },
+                    support: { extends: "base", maxTokens: 1024 },
+                },
+            }"#,
+        )
+        .unwrap();
+
+        let support = &config.agents["support"];
+        assert_eq!(support.extends.as_deref(), Some("base"));
+        assert_eq!(support.model, "gpt-4o");
+        assert_eq!(support.tools, vec!["search".to_string()]);
+        assert_eq!(support.max_tokens, 1024);
+
+        assert!(
+            Config::from_json5(r#"{ agents: { a: { extends: "a" } } }"#)
+                .unwrap_err()
+                .to_string()
+                .contains("cycle")
+        );
+    }
+
+    #[test]
+    fn test_tool_policy() {
+        let config = Config::from_json5(
+            r#"{
+                agents: {
+                    default: { tools: ["fs.*", "search"], deniedTools: ["fs.delete"] },
+                    open: { deniedTools: ["bash"] },
+                },
+            }"#,
+        )
+        .unwrap();
+
+        let policy = config.agents["default"].tool_policy();
+        assert!(policy.allows("fs.read"));
+        assert!(policy.allows("search"));
+        assert_eq!(
+            policy.denial("fs.delete").as_deref(),
+            Some("denied by 'fs.delete'")
+        );
+        assert!(!policy.allows("bash"));
+
+        let open = config.agents["open"].tool_policy();
+        assert!(open.allows("fs.delete"));
+        assert!(!open.allows("bash"));
+        assert!(AgentConfig::default().tool_policy().is_unrestricted());
+
+        assert!(
+            Config::from_json5(r#"{ agents: { a: { tools: ["fs.*.read"] } } }"#)
+                .unwrap_err()
+                .to_string()
+                .contains("invalid tool pattern")
+        );
+    }
+
+    #[test]
+    fn test_agent_overrides_save() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("openclaw.json");
+        std::fs::write(
+            &path,
+            r#"{
+                // Comments are not kept, values are
+                gateway: { port: 9000 },
+                agents: { default: { model: "gpt-4o", systemPrompt: "Be brief." } },
+            }"#,
+        )
+        .unwrap();
+
+        let overrides = AgentOverrides {
+            temperature: Some(0.2),
+            max_tokens: Some(1024),
+            system_prompt: Some(String::new()),
+            ..AgentOverrides::default()
+        };
+        overrides.validate().unwrap();
+        overrides.save(&path, "default").unwrap();
+
+        let config = Config::load(&path).unwrap();
+        let agent = &config.agents["default"];
+        assert_eq!(config.gateway.port, 9000);
+        assert_eq!(agent.model, "gpt-4o");
+        assert!((agent.temperature - 0.2).abs() < f32::EPSILON);
+        assert_eq!(agent.max_tokens, 1024);
+        assert!(agent.system_prompt.is_none());
+
+        let mut applied = AgentConfig::default();
+        overrides.apply(&mut applied);
+        assert_eq!(applied.max_tokens, 1024);
+
+        assert!(AgentOverrides::default().is_empty());
+        let invalid = AgentOverrides {
+            temperature: Some(3.0),
+            ..AgentOverrides::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_channels_save_enabled() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("openclaw.json");
+        std::fs::write(
+            &path,
+            r#"{
+                gateway: { port: 9000 },
+                channels: { disabled: ["slack"], debounce: { windowMs: 500 } },
+            }"#,
+        )
+        .unwrap();
+
+        ChannelsConfig::save_enabled(&path, "telegram", false).unwrap();
+        // Disabling twice doesn't add a duplicate
+        ChannelsConfig::save_enabled(&path, "telegram", false).unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.channels.disabled, ["slack", "telegram"]);
+        assert!(!config.channels.is_enabled("telegram"));
+        assert_eq!(config.gateway.port, 9000);
+        assert_eq!(config.channels.debounce.window_ms, 500);
+
+        ChannelsConfig::save_enabled(&path, "slack", true).unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.channels.disabled, ["telegram"]);
+        assert!(config.channels.is_enabled("slack"));
+    }
+
+    #[test]
+    fn test_chat_commands_config() {
+        let config = Config::from_json5(
+            r#"{
+                channels: {
+                    commands: {
+                        disabled: ["usage"],
+                        allow: [{ channel: "*", peerId: "42" }],
+                        models: ["claude-sonnet-4-5", "claude-haiku-4-5"],
+                        agents: {
+                            support: { disabled: [], models: [] },
+                            public: { enabled: false },
+                        },
+                    },
+                },
+            }"#,
+        )
+        .unwrap();
+        let commands = &config.channels.commands;
+        assert!(commands.enabled_for("default", "reset"));
+        assert!(!commands.enabled_for("default", "usage"));
+        assert!(commands.enabled_for("support", "usage"));
+        assert!(!commands.enabled_for("public", "help"));
+        assert_eq!(commands.models_for("default").len(), 2);
+        assert!(commands.models_for("support").is_empty());
+        assert_eq!(commands.allow_for("support")[0].peer_id, "42");
+    }
+
+    #[test]
+    fn test_groups_config() {
+        let config = Config::from_json5(
+            r#"{
+                channels: {
+                    groups: {
+                        cooldownSecs: 30,
+                        names: ["claw_bot"],
+                        agents: { support: "thread" },
+                        channels: { slack: { threads: false, cooldownSecs: 10 } },
+                        chats: { "slack:C042": { requireMention: false } },
+                    },
+                },
+            }"#,
+        )
+        .unwrap();
+        let groups = &config.channels.groups;
+        assert!(groups.require_mention_for("slack", "C001"));
+        assert!(!groups.require_mention_for("slack", "C042"));
+        assert!(groups.threads_for("discord", "1"));
+        assert!(!groups.threads_for("slack", "C042"));
+        assert_eq!(groups.cooldown_secs_for("slack", "C042"), 10);
+        assert_eq!(groups.cooldown_secs_for("telegram", "-100"), 30);
+        assert_eq!(groups.session_scope_for("support"), SessionScope::Thread);
+        assert_eq!(groups.session_scope_for("default"), SessionScope::Group);
+    }
+
+    #[test]
+    fn test_working_hours_config() {
+        let config = Config::from_json5(
+            r#"{
+                channels: {
+                    workingHours: {
+                        channels: {
+                            telegram: {
+                                timezone: "Europe/Berlin",
+                                open: [
+                                    { days: ["mon", "tue", "wed", "thu", "fri"], start: "09:00", end: "18:00" },
+                                ],
+                                outside: "queue",
+                            },
+                        },
+                        agents: { support: { quiet: [{ start: "22:00", end: "07:00" }] } },
+                    },
+                },
+            }"#,
+        )
+        .unwrap();
+        let hours = &config.channels.working_hours;
+        let telegram = hours.hours_for("telegram", "default").unwrap();
+        assert_eq!(telegram.outside, OutsideHoursAction::Queue);
+        assert_eq!(telegram.tz().unwrap(), chrono_tz::Europe::Berlin);
+        let (days, start, _) = telegram.open[0].parse().unwrap();
+        assert_eq!(days.len(), 5);
+        assert_eq!(start, chrono::NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+        // Agent hours win over channel hours
+        let support = hours.hours_for("telegram", "support").unwrap();
+        assert_eq!(support.timezone, "UTC");
+        assert!(hours.hours_for("discord", "default").is_none());
+
+        for invalid in [
+            r#"{ timezone: "Mars/Olympus" }"#,
+            r#"{ open: [{ days: ["funday"], start: "09:00", end: "18:00" }] }"#,
+            r#"{ quiet: [{ start: "25:00", end: "07:00" }] }"#,
+        ] {
+            let json = format!(
+                "{{ channels: {{ workingHours: {{ channels: {{ slack: {invalid} }} }} }} }}"
+            );
+            assert!(Config::from_json5(&json).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_egress_allowlist() {
+        let config = Config::from_json5(
+            r#"{
+                agents: {
+                    default: {
+                        egress: { enabled: true, allowedDomains: ["api.github.com", "*.pypi.org"] },
+                    },
+                },
+            }"#,
+        )
+        .unwrap();
+
+        let egress = &config.agents["default"].egress;
+        assert!(egress.enabled);
+        assert!(egress.allows_host("API.GitHub.com."));
+        assert!(!egress.allows_host("github.com"));
+        assert!(egress.allows_host("files.pypi.org"));
+        assert!(!egress.allows_host("pypi.org"));
+        assert!(!egress.allows_host("evilpypi.org"));
+    }
+
+    #[test]
+    fn test_sandbox_profiles() {
+        let config = Config::from_json5(
+            r#"{
+                sandboxProfiles: {
+                    locked: { level: "paranoid", maxCpuSeconds: 10 },
+                },
+                agents: {
+                    dev: {
+                        sandbox: {
+                            bash: { extends: "locked" },
+                            git: { level: "standard", allowedPaths: ["/srv/workspace"] },
+                        },
+                    },
+                },
+            }"#,
+        )
+        .unwrap();
+
+        let sandbox = &config.agents["dev"].sandbox;
+        assert_eq!(sandbox["bash"].level, Some(SandboxLevel::Paranoid));
+        assert_eq!(sandbox["bash"].max_cpu_seconds, Some(10));
+        assert_eq!(sandbox["git"].network, None);
+
+        let err = Config::from_json5(
+            r#"{ agents: { dev: { sandbox: { git: { allowedPaths: ["workspace"] } } } } }"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("not absolute"));
+
+        let err =
+            Config::from_json5(r"{ sandboxProfiles: { p: { maxMemoryMb: 0 } } }").unwrap_err();
+        assert!(err.to_string().contains("maxMemoryMb cannot be 0"));
+    }
+
+    #[test]
+    fn test_workspace_config() {
+        let config = Config::from_json5(
+            r#"{
+                agents: {
+                    base: { workspace: { quotaMb: 100 } },
+                    dev: { extends: "base", workspace: { perSession: true, expireAfterHours: 24 } },
+                },
+            }"#,
+        )
+        .unwrap();
+
+        let workspace = &config.agents["dev"].workspace;
+        assert!(workspace.per_session);
+        assert_eq!(workspace.quota_bytes(), Some(100 * 1024 * 1024));
+        assert_eq!(workspace.expire_after_hours, Some(24));
+        assert_eq!(config.agents["base"].workspace.expire_after_hours, None);
+
+        let err =
+            Config::from_json5(r"{ agents: { dev: { workspace: { quotaMb: 0 } } } }").unwrap_err();
+        assert!(err.to_string().contains("quotaMb cannot be 0"));
+    }
+
+    #[test]
+    fn test_prompt_assembly_config() {
+        let config = Config::from_json5(
+            r#"{ agents: { dev: { prompt: { maxTokens: 2000, channelEtiquette: { sms: "Keep it short." } } } } }"#,
+        )
+        .unwrap();
+        let prompt = &config.agents["dev"].prompt;
+        assert_eq!(prompt.max_tokens, Some(2000));
+        assert!(prompt.etiquette && prompt.current_time);
+        assert_eq!(prompt.channel_etiquette["sms"], "Keep it short.");
+
+        let config = Config::from_json5(r"{ agents: { dev: { prompt: { maxTokens: 0 } } } }");
+        assert!(config.is_err());
+    }
+
+    #[test]
+    fn test_locale_config() {
+        let config = Config::from_json5(
+            r#"{ channels: { locale: { channels: { telegram: "de" }, peers: { "telegram:42": "pt-BR" } } } }"#,
+        )
+        .unwrap();
+        let locale = &config.channels.locale;
+        assert_eq!(locale.for_peer("telegram", "42"), Some("pt-BR"));
+        assert_eq!(locale.for_peer("telegram", "7"), None);
+        assert_eq!(locale.for_channel("telegram"), "de");
+        assert_eq!(locale.for_channel("slack"), "en");
+
+        let err = Config::from_json5(r#"{ channels: { locale: { default: "english please" } } }"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid locale 'english please'"));
+    }
+
+    #[test]
+    fn test_moderation_config() {
+        let config = Config::from_json5(
+            r#"{ agents: { dev: { moderation: { keywords: ["spam"], action: "flag" } } } }"#,
+        )
+        .unwrap();
+        let moderation = &config.agents["dev"].moderation;
+        assert!(moderation.is_enabled());
+        assert!(moderation.inbound && moderation.outbound);
+        assert_eq!(moderation.action, ModerationAction::Flag);
+        assert!(!ModerationConfig::default().is_enabled());
+
+        let err =
+            Config::from_json5(r#"{ agents: { dev: { moderation: { patterns: ["(open"] } } } }"#)
+                .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Agent 'dev' moderation: pattern '(open'")
+        );
+    }
+
+    #[test]
+    fn test_tool_concurrency_config() {
+        let config = Config::from_json5(
+            r"{ agents: { dev: { toolConcurrency: { maxConcurrent: 4, perTool: { bash: 1 } } } } }",
+        )
+        .unwrap();
+        let limits = &config.agents["dev"].tool_concurrency;
+        assert_eq!(limits.max_concurrent, Some(4));
+        assert_eq!(limits.per_tool["bash"], 1);
+        assert_eq!(limits.max_queued, 32);
+
+        let err = Config::from_json5(
+            r"{ agents: { dev: { toolConcurrency: { perTool: { bash: 0 } } } } }",
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("perTool limit for 'bash' cannot be 0")
+        );
+    }
+
+    #[test]
+    fn test_git_allows_repo() {
+        let git = GitToolsConfig {
+            allowed_repos: vec![
+                "github.com/neul-labs/*".to_string(),
+                "gitlab.com/team/app.git".to_string(),
+            ],
+            ..Default::default()
+        };
+
+        assert!(git.allows_repo("https://github.com/neul-labs/openclaw-rs.git"));
+        assert!(git.allows_repo("git@github.com:neul-labs/openclaw-rs.git"));
+        assert!(git.allows_repo("ssh://git@GitHub.com:22/neul-labs/docs/"));
+        assert!(git.allows_repo("https://token@gitlab.com/team/app"));
+        assert!(!git.allows_repo("https://github.com/neul-labs"));
+        assert!(!git.allows_repo("https://github.com/neul-labs-evil/repo"));
+        assert!(!git.allows_repo("https://gitlab.com/team/app2"));
+        assert!(!git.allows_repo("file:///srv/github.com/neul-labs/repo"));
+        assert!(!git.allows_repo("/srv/github.com/neul-labs/repo"));
+        assert!(!git.allows_repo("https://github.com/neul-labs/../other/repo"));
+        assert!(!GitToolsConfig::default().allows_repo("https://github.com/a/b"));
+    }
+
     #[test]
     fn test_state_dir() {
         let dir = Config::state_dir();
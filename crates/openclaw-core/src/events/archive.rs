@@ -0,0 +1,544 @@
+//! Cold storage for ended sessions.
+//!
+//! Archiving writes a session's events to a zstd-compressed JSONL file and
+//! removes the session from sled. The file goes to the `archives` namespace
+//! of a blob store when one is set, or else to a local directory. An index
+//! entry records where the archive lives, so reading or appending to an
+//! archived session rehydrates it transparently. Archives of an encrypted
+//! store are encrypted as a whole after compression.
+
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{EventId, EventStore, EventStoreError, SessionEvent, SessionState};
+use crate::storage::{BlobNamespace, BlockingBlobStore};
+use crate::types::SessionKey;
+
+/// zstd compression level for archives.
+const COMPRESSION_LEVEL: i32 = 9;
+
+/// Index entry for an archived session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedSession {
+    /// Session key.
+    pub session_key: SessionKey,
+    /// Agent ID.
+    pub agent_id: String,
+    /// Archive file, or its key in the blob store if `in_blob_store`.
+    pub path: PathBuf,
+    /// Whether the archive is kept in the blob store's `archives`
+    /// namespace rather than a local file.
+    #[serde(default)]
+    pub in_blob_store: bool,
+    /// Number of archived events.
+    pub events: usize,
+    /// Last activity before archiving.
+    pub last_activity: DateTime<Utc>,
+    /// When the session was archived.
+    pub archived_at: DateTime<Utc>,
+    /// Whether the archive file is encrypted with the store key.
+    #[serde(default)]
+    pub encrypted: bool,
+}
+
+/// Blob store archives are written to, with the key prefix that keeps
+/// this store's archives apart from others'.
+#[derive(Debug, Clone)]
+pub(super) struct ArchiveBlobs {
+    blobs: BlockingBlobStore,
+    prefix: String,
+}
+
+impl EventStore {
+    /// Directory archives are written to when no blob store is set.
+    /// Archiving is unavailable until one of them is set; rehydration
+    /// from files works regardless.
+    #[must_use]
+    pub fn with_archive_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.archive_dir = Some(dir.into());
+        self
+    }
+
+    /// Write archives to the blob store's `archives` namespace, under
+    /// `prefix`, instead of the archive directory.
+    ///
+    /// Archives already written to files stay where they are.
+    #[must_use]
+    pub fn with_archive_blobs(
+        mut self,
+        blobs: BlockingBlobStore,
+        prefix: impl Into<String>,
+    ) -> Self {
+        self.archive_blobs = Some(ArchiveBlobs {
+            blobs,
+            prefix: prefix.into(),
+        });
+        self
+    }
+
+    /// Move an ended session to cold storage.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if the session does not exist, `Archive` if
+    /// neither a blob store nor an archive directory is set, the session
+    /// has not ended, it still has pending reminders, or the archive cannot
+    /// be stored, or an IO error if the archive file cannot be written.
+    pub fn archive_session(
+        &self,
+        session_key: &SessionKey,
+    ) -> Result<ArchivedSession, EventStoreError> {
+        let projection = self.get_projection(session_key)?;
+        if projection.state != SessionState::Ended {
+            return Err(EventStoreError::Archive(format!(
+                "session {session_key} has not ended"
+            )));
+        }
+        if self.has_pending_reminders(session_key)? {
+            return Err(EventStoreError::Archive(format!(
+                "session {session_key} has pending reminders"
+            )));
+        }
+
+        let events = self.get_events(session_key)?;
+        let file_name = EventId::from_content(session_key.as_ref().as_bytes()).to_hex();
+        let extension = if self.is_encrypted() {
+            "jsonl.zst.enc"
+        } else {
+            "jsonl.zst"
+        };
+        let file_name = format!("{file_name}.{extension}");
+        let (path, in_blob_store) = match (&self.archive_blobs, &self.archive_dir) {
+            (Some(archive), _) => {
+                let key = format!("{}/{file_name}", archive.prefix.trim_matches('/'));
+                archive
+                    .blobs
+                    .put(BlobNamespace::Archives, &key, self.encode_archive(&events)?)
+                    .map_err(|e| EventStoreError::Archive(e.to_string()))?;
+                (PathBuf::from(key), true)
+            }
+            (None, Some(dir)) => {
+                std::fs::create_dir_all(dir)?;
+                let path = dir.join(file_name);
+                self.write_archive(&path, &events)?;
+                (path, false)
+            }
+            (None, None) => {
+                return Err(EventStoreError::Archive(
+                    "no archive storage configured".to_string(),
+                ));
+            }
+        };
+
+        let entry = ArchivedSession {
+            session_key: session_key.clone(),
+            agent_id: projection.agent_id,
+            path,
+            in_blob_store,
+            events: events.len(),
+            last_activity: projection.last_activity,
+            archived_at: Utc::now(),
+            encrypted: self.is_encrypted(),
+        };
+        self.archive_tree
+            .insert(session_key.as_ref().as_bytes(), serde_json::to_vec(&entry)?)?;
+        self.purge_session(session_key)?;
+
+        tracing::debug!("Archived session {} ({} events)", session_key, entry.events);
+        Ok(entry)
+    }
+
+    /// Archive ended, non-deleted sessions inactive since before `cutoff`.
+    ///
+    /// Sessions with pending reminders stay in active storage.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage fails or an archive cannot be written.
+    pub fn archive_ended_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<ArchivedSession>, EventStoreError> {
+        let mut archived = Vec::new();
+
+        for key in self.list_sessions()? {
+            let projection = self.get_projection(&key)?;
+            if projection.state == SessionState::Ended
+                && !projection.is_deleted()
+                && projection.last_activity < cutoff
+                && !self.has_pending_reminders(&key)?
+            {
+                archived.push(self.archive_session(&key)?);
+            }
+        }
+
+        Ok(archived)
+    }
+
+    /// Restore an archived session to active storage.
+    ///
+    /// Returns `false` if the session is not archived.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the archive cannot be read or replayed.
+    pub fn rehydrate(&self, session_key: &SessionKey) -> Result<bool, EventStoreError> {
+        let key = session_key.as_ref().as_bytes();
+        let Some(data) = self.archive_tree.get(key)? else {
+            return Ok(false);
+        };
+        let entry: ArchivedSession = serde_json::from_slice(&data)?;
+        let events = self.read_archive(&entry)?;
+
+        // Drop the index entry first so the session reads as active. The
+        // events were counted when first appended, so skip the counters.
+        self.archive_tree.remove(key)?;
+        for event in &events {
+            if let Err(e) = self.insert_event(event, false) {
+                self.archive_tree.insert(key, data)?;
+                return Err(e);
+            }
+        }
+
+        self.remove_archive(&entry)?;
+
+        tracing::debug!(
+            "Rehydrated session {} ({} events)",
+            session_key,
+            events.len()
+        );
+        Ok(true)
+    }
+
+    /// Whether a session is in cold storage.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage read fails.
+    pub fn is_archived(&self, session_key: &SessionKey) -> Result<bool, EventStoreError> {
+        self.archive_tree
+            .contains_key(session_key.as_ref().as_bytes())
+    }
+
+    /// List archived sessions, most recently active first.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage read fails.
+    pub fn list_archived(&self) -> Result<Vec<ArchivedSession>, EventStoreError> {
+        let mut archived = Vec::new();
+
+        for result in &self.archive_tree {
+            let (_, value) = result?;
+            archived.push(serde_json::from_slice::<ArchivedSession>(&value)?);
+        }
+
+        archived.sort_by_key(|a| std::cmp::Reverse(a.last_activity));
+        Ok(archived)
+    }
+
+    /// Rehydrate `session_key` if it is archived and not in active storage.
+    pub(super) fn rehydrate_if_archived(
+        &self,
+        session_key: &SessionKey,
+    ) -> Result<(), EventStoreError> {
+        if !self
+            .sessions_tree
+            .contains_key(session_key.as_ref().as_bytes())?
+        {
+            self.rehydrate(session_key)?;
+        }
+        Ok(())
+    }
+
+    fn has_pending_reminders(&self, session_key: &SessionKey) -> Result<bool, EventStoreError> {
+        Ok(self
+            .pending_reminders()?
+            .iter()
+            .any(|r| r.session_key == *session_key))
+    }
+
+    /// Replace an archive's events in place, keeping its index entry in
+    /// step.
+    pub(super) fn rewrite_archive(
+        &self,
+        entry: &ArchivedSession,
+        events: &[SessionEvent],
+    ) -> Result<(), EventStoreError> {
+        if entry.in_blob_store {
+            self.blobs_for(entry)?
+                .put(
+                    BlobNamespace::Archives,
+                    &entry.path.to_string_lossy(),
+                    self.encode_archive(events)?,
+                )
+                .map_err(|e| EventStoreError::Archive(e.to_string()))?;
+        } else {
+            self.write_archive(&entry.path, events)?;
+        }
+
+        let entry = ArchivedSession {
+            events: events.len(),
+            encrypted: self.is_encrypted(),
+            ..entry.clone()
+        };
+        self.archive_tree.insert(
+            entry.session_key.as_ref().as_bytes(),
+            serde_json::to_vec(&entry)?,
+        )?;
+        Ok(())
+    }
+
+    /// Delete an archive's file or blob. One already gone is not an error.
+    pub(super) fn remove_archive(&self, entry: &ArchivedSession) -> Result<(), EventStoreError> {
+        if entry.in_blob_store {
+            return self
+                .blobs_for(entry)?
+                .delete(BlobNamespace::Archives, &entry.path.to_string_lossy())
+                .map_err(|e| EventStoreError::Archive(e.to_string()));
+        }
+        std::fs::remove_file(&entry.path).or_else(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                Ok(())
+            } else {
+                Err(e)
+            }
+        })?;
+        Ok(())
+    }
+
+    /// Blob store holding an archive kept in one.
+    fn blobs_for(&self, entry: &ArchivedSession) -> Result<&BlockingBlobStore, EventStoreError> {
+        self.archive_blobs
+            .as_ref()
+            .map(|archive| &archive.blobs)
+            .ok_or_else(|| {
+                EventStoreError::Archive(format!(
+                    "archive of {} is in a blob store, which is not configured",
+                    entry.session_key
+                ))
+            })
+    }
+
+    /// Encode events as zstd-compressed JSONL, sealed with the store key.
+    fn encode_archive(&self, events: &[SessionEvent]) -> Result<Vec<u8>, EventStoreError> {
+        let mut encoder = zstd::Encoder::new(Vec::new(), COMPRESSION_LEVEL)?;
+        for event in events {
+            serde_json::to_writer(&mut encoder, event)?;
+            encoder.write_all(b"\n")?;
+        }
+        self.seal(encoder.finish()?)
+    }
+
+    /// Write an archive file, replacing `path` atomically.
+    pub(super) fn write_archive(
+        &self,
+        path: &Path,
+        events: &[SessionEvent],
+    ) -> Result<(), EventStoreError> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let data = self.encode_archive(events)?;
+
+        let mut file = File::create(&tmp)?;
+        file.write_all(&data)?;
+        file.sync_all()?;
+
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Read events from an archive written by [`EventStore::archive_session`].
+    pub(super) fn read_archive(
+        &self,
+        entry: &ArchivedSession,
+    ) -> Result<Vec<SessionEvent>, EventStoreError> {
+        let data = if entry.in_blob_store {
+            self.blobs_for(entry)?
+                .get(BlobNamespace::Archives, &entry.path.to_string_lossy())
+                .map_err(|e| EventStoreError::Archive(e.to_string()))?
+                .to_vec()
+        } else {
+            std::fs::read(&entry.path)?
+        };
+        let data = if entry.encrypted {
+            self.unseal(&data)?
+        } else {
+            Cow::Borrowed(data.as_slice())
+        };
+        let reader = BufReader::new(zstd::Decoder::new(data.as_ref())?);
+
+        let mut events = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                events.push(self.parse_event(&line)?);
+            }
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{SessionEventKind, SessionProjection};
+
+    fn ended_session(store: &EventStore, key: &SessionKey) {
+        for kind in [
+            SessionEventKind::SessionStarted {
+                channel: "telegram".to_string(),
+                peer_id: "user123".to_string(),
+            },
+            SessionEventKind::MessageReceived {
+                content: "Hello".to_string(),
+                attachments: vec![],
+                sender_id: None,
+            },
+            SessionEventKind::SessionEnded {
+                reason: "done".to_string(),
+            },
+        ] {
+            store
+                .append(&SessionEvent::new(key.clone(), "default".to_string(), kind))
+                .unwrap();
+        }
+    }
+
+    fn store(dir: &Path) -> EventStore {
+        EventStore::open(&dir.join("events"))
+            .unwrap()
+            .with_archive_dir(dir.join("archive"))
+    }
+
+    #[test]
+    fn test_archive_and_rehydrate() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = store(temp.path());
+        let key = SessionKey::new("agent:default:telegram:user123");
+        ended_session(&store, &key);
+        let before: SessionProjection = store.get_projection(&key).unwrap();
+
+        let entry = store.archive_session(&key).unwrap();
+        assert_eq!(entry.events, 3);
+        assert!(entry.path.exists());
+        assert!(store.is_archived(&key).unwrap());
+        assert!(store.list_sessions().unwrap().is_empty());
+        assert_eq!(store.stats().unwrap().events, 0);
+
+        // Reading the projection brings the session back
+        let after = store.get_projection(&key).unwrap();
+        assert_eq!(after.state, SessionState::Ended);
+        assert_eq!(after.message_count, before.message_count);
+        assert!(!store.is_archived(&key).unwrap());
+        assert!(!entry.path.exists());
+        assert_eq!(store.get_events(&key).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_archive_to_blob_store() {
+        let temp = tempfile::tempdir().unwrap();
+        let blobs = crate::storage::BlobStore::local(&temp.path().join("blobs")).unwrap();
+        let store = EventStore::open(&temp.path().join("events"))
+            .unwrap()
+            .with_archive_dir(temp.path().join("archive"))
+            .with_archive_blobs(blobs.blocking().unwrap(), "sessions");
+        let key = SessionKey::new("agent:default:telegram:user123");
+        ended_session(&store, &key);
+
+        let entry = store.archive_session(&key).unwrap();
+        assert!(entry.in_blob_store);
+        assert!(entry.path.starts_with("sessions"));
+        let object = temp
+            .path()
+            .join("blobs")
+            .join(BlobNamespace::Archives.as_str())
+            .join(&entry.path);
+        assert!(object.exists());
+        assert!(!temp.path().join("archive").exists());
+
+        assert_eq!(store.get_events(&key).unwrap().len(), 3);
+        assert!(!store.is_archived(&key).unwrap());
+        assert!(!object.exists());
+    }
+
+    #[test]
+    fn test_append_rehydrates() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = store(temp.path());
+        let key = SessionKey::new("agent:default:telegram:user123");
+        ended_session(&store, &key);
+        store.archive_session(&key).unwrap();
+
+        store
+            .append(&SessionEvent::new(
+                key.clone(),
+                "default".to_string(),
+                SessionEventKind::MessageReceived {
+                    content: "Back again".to_string(),
+                    attachments: vec![],
+                    sender_id: None,
+                },
+            ))
+            .unwrap();
+
+        assert_eq!(store.get_events(&key).unwrap().len(), 4);
+        assert_eq!(
+            store.get_projection(&key).unwrap().channel.as_ref(),
+            "telegram"
+        );
+    }
+
+    #[test]
+    fn test_archive_ended_before() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = store(temp.path());
+
+        let ended = SessionKey::new("agent:default:telegram:ended");
+        ended_session(&store, &ended);
+        let active = SessionKey::new("agent:default:telegram:active");
+        store
+            .append(&SessionEvent::new(
+                active.clone(),
+                "default".to_string(),
+                SessionEventKind::SessionStarted {
+                    channel: "telegram".to_string(),
+                    peer_id: "active".to_string(),
+                },
+            ))
+            .unwrap();
+
+        assert!(
+            store
+                .archive_ended_before(Utc::now() - chrono::Duration::days(1))
+                .unwrap()
+                .is_empty()
+        );
+
+        let archived = store
+            .archive_ended_before(Utc::now() + chrono::Duration::seconds(1))
+            .unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].session_key, ended);
+        assert_eq!(store.list_sessions().unwrap(), vec![active.clone()]);
+        assert!(store.archive_session(&active).is_err());
+    }
+
+    #[test]
+    fn test_archive_requires_dir() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = EventStore::open(temp.path()).unwrap();
+        let key = SessionKey::new("agent:default:telegram:user123");
+        ended_session(&store, &key);
+
+        assert!(matches!(
+            store.archive_session(&key),
+            Err(EventStoreError::Archive(_))
+        ));
+    }
+}
@@ -0,0 +1,317 @@
+//! Storage backends for the event store.
+//!
+//! The store keeps its data in a few named keyspaces ("trees") of ordered
+//! byte keys and values, plus a metadata keyspace for format markers. A
+//! [`StorageBackend`] provides them; sled is the default and `SQLite` is
+//! available with the `sqlite` feature.
+
+use std::path::Path;
+
+use super::EventStoreError;
+
+/// A key and its value.
+pub type Entry = (Vec<u8>, Vec<u8>);
+
+/// Key-value entries yielded by a scan, in key order.
+pub type Entries<'a> = Box<dyn Iterator<Item = Result<Entry, EventStoreError>> + 'a>;
+
+/// Callback for [`StorageTree::update`]: maps the current value to the new
+/// one, or to `None` to remove the key.
+pub type Update<'a> = dyn FnMut(Option<&[u8]>) -> Option<Vec<u8>> + 'a;
+
+/// Names of the trees the event store uses.
+pub(super) const TREES: [&str; 8] = [
+    "events",
+    "sessions",
+    "reminders",
+    "event_hashes",
+    "archived_sessions",
+    "agent_stats",
+    "channel_stats",
+    "error_stats",
+];
+
+/// One keyspace of a storage backend, ordered by key.
+pub trait StorageTree: Send + Sync {
+    /// Value stored under `key`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the read fails.
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, EventStoreError>;
+
+    /// Store `value` under `key`, replacing any existing value.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the write fails.
+    fn insert(&self, key: &[u8], value: &[u8]) -> Result<(), EventStoreError>;
+
+    /// Remove `key` if present.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the write fails.
+    fn remove(&self, key: &[u8]) -> Result<(), EventStoreError>;
+
+    /// Entries whose key starts with `prefix`; an empty prefix scans the
+    /// whole tree.
+    fn scan_prefix(&self, prefix: &[u8]) -> Entries<'_>;
+
+    /// Number of entries.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the read fails.
+    fn len(&self) -> Result<usize, EventStoreError>;
+
+    /// Whether the tree has no entries.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the read fails.
+    fn is_empty(&self) -> Result<bool, EventStoreError> {
+        Ok(self.scan_prefix(&[]).next().transpose()?.is_none())
+    }
+
+    /// Remove every entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the write fails.
+    fn clear(&self) -> Result<(), EventStoreError>;
+
+    /// Atomically replace the value under `key` with `update(current)`,
+    /// removing it if `update` returns `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the read or write fails.
+    fn update(&self, key: &[u8], update: &mut Update<'_>) -> Result<(), EventStoreError>;
+}
+
+/// Storage for an event store.
+pub trait StorageBackend: Send + Sync {
+    /// Open (or create) the tree named `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the tree cannot be opened.
+    fn open_tree(&self, name: &str) -> Result<Box<dyn StorageTree>, EventStoreError>;
+
+    /// The tree holding store-wide markers.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the tree cannot be opened.
+    fn metadata(&self) -> Result<Box<dyn StorageTree>, EventStoreError>;
+
+    /// Persist pending writes.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the flush fails.
+    fn flush(&self) -> Result<(), EventStoreError>;
+
+    /// Size of the backing files in bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the size cannot be read.
+    fn size_on_disk(&self) -> Result<u64, EventStoreError>;
+}
+
+/// A tree with convenience methods taking any byte-like key or value.
+pub(super) struct Tree(Box<dyn StorageTree>);
+
+impl Tree {
+    pub(super) fn new(tree: Box<dyn StorageTree>) -> Self {
+        Self(tree)
+    }
+
+    pub(super) fn get(&self, key: impl AsRef<[u8]>) -> Result<Option<Vec<u8>>, EventStoreError> {
+        self.0.get(key.as_ref())
+    }
+
+    pub(super) fn insert(
+        &self,
+        key: impl AsRef<[u8]>,
+        value: impl AsRef<[u8]>,
+    ) -> Result<(), EventStoreError> {
+        self.0.insert(key.as_ref(), value.as_ref())
+    }
+
+    pub(super) fn remove(&self, key: impl AsRef<[u8]>) -> Result<(), EventStoreError> {
+        self.0.remove(key.as_ref())
+    }
+
+    pub(super) fn contains_key(&self, key: impl AsRef<[u8]>) -> Result<bool, EventStoreError> {
+        Ok(self.0.get(key.as_ref())?.is_some())
+    }
+
+    pub(super) fn scan_prefix(&self, prefix: impl AsRef<[u8]>) -> Entries<'_> {
+        self.0.scan_prefix(prefix.as_ref())
+    }
+
+    pub(super) fn iter(&self) -> Entries<'_> {
+        self.0.scan_prefix(&[])
+    }
+
+    pub(super) fn len(&self) -> Result<usize, EventStoreError> {
+        self.0.len()
+    }
+
+    pub(super) fn is_empty(&self) -> Result<bool, EventStoreError> {
+        self.0.is_empty()
+    }
+
+    pub(super) fn clear(&self) -> Result<(), EventStoreError> {
+        self.0.clear()
+    }
+
+    pub(super) fn update(
+        &self,
+        key: impl AsRef<[u8]>,
+        mut update: impl FnMut(Option<&[u8]>) -> Option<Vec<u8>>,
+    ) -> Result<(), EventStoreError> {
+        self.0.update(key.as_ref(), &mut update)
+    }
+}
+
+impl<'a> IntoIterator for &'a Tree {
+    type Item = Result<(Vec<u8>, Vec<u8>), EventStoreError>;
+    type IntoIter = Entries<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// sled database in a directory.
+pub struct SledBackend {
+    db: sled::Db,
+}
+
+impl SledBackend {
+    /// Open or create a sled database at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database cannot be opened.
+    pub fn open(path: &Path) -> Result<Self, EventStoreError> {
+        #[cfg(test)]
+        wait_for_release(path);
+        Ok(Self {
+            db: sled::open(path)?,
+        })
+    }
+}
+
+/// Block until no other handle holds the lock on the sled database at
+/// `path`.
+///
+/// sled's background threads keep the lock for a moment after the last
+/// `Db` is dropped, so tests that reopen a store wait for them, as sled's
+/// own `testing` feature does.
+#[cfg(test)]
+fn wait_for_release(path: &Path) {
+    use fs2::FileExt;
+
+    if let Ok(file) = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path.join("db"))
+    {
+        let _ = file.lock_exclusive();
+    }
+}
+
+impl StorageBackend for SledBackend {
+    fn open_tree(&self, name: &str) -> Result<Box<dyn StorageTree>, EventStoreError> {
+        Ok(Box::new(self.db.open_tree(name)?))
+    }
+
+    fn metadata(&self) -> Result<Box<dyn StorageTree>, EventStoreError> {
+        // Markers predate the backend abstraction and live in the default tree
+        Ok(Box::new((*self.db).clone()))
+    }
+
+    fn flush(&self) -> Result<(), EventStoreError> {
+        self.db.flush()?;
+        Ok(())
+    }
+
+    fn size_on_disk(&self) -> Result<u64, EventStoreError> {
+        Ok(self.db.size_on_disk()?)
+    }
+}
+
+impl StorageTree for sled::Tree {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, EventStoreError> {
+        Ok(Self::get(self, key)?.map(|v| v.to_vec()))
+    }
+
+    fn insert(&self, key: &[u8], value: &[u8]) -> Result<(), EventStoreError> {
+        Self::insert(self, key, value)?;
+        Ok(())
+    }
+
+    fn remove(&self, key: &[u8]) -> Result<(), EventStoreError> {
+        Self::remove(self, key)?;
+        Ok(())
+    }
+
+    fn scan_prefix(&self, prefix: &[u8]) -> Entries<'_> {
+        Box::new(Self::scan_prefix(self, prefix).map(|entry| {
+            let (key, value) = entry?;
+            Ok((key.to_vec(), value.to_vec()))
+        }))
+    }
+
+    fn len(&self) -> Result<usize, EventStoreError> {
+        Ok(Self::len(self))
+    }
+
+    fn is_empty(&self) -> Result<bool, EventStoreError> {
+        Ok(Self::is_empty(self))
+    }
+
+    fn clear(&self) -> Result<(), EventStoreError> {
+        Self::clear(self)?;
+        Ok(())
+    }
+
+    fn update(&self, key: &[u8], update: &mut Update<'_>) -> Result<(), EventStoreError> {
+        self.update_and_fetch(key, update)?;
+        Ok(())
+    }
+}
+
+/// Copy every tree and marker from `source` to `target`, returning the
+/// number of entries copied. Values are copied as stored, so encrypted
+/// data stays encrypted under the same key.
+///
+/// # Errors
+///
+/// Returns error if a read or write fails.
+#[cfg(feature = "sqlite")]
+pub(super) fn copy_storage(
+    source: &dyn StorageBackend,
+    target: &dyn StorageBackend,
+) -> Result<usize, EventStoreError> {
+    let mut pairs = vec![(source.metadata()?, target.metadata()?)];
+    for name in TREES {
+        pairs.push((source.open_tree(name)?, target.open_tree(name)?));
+    }
+
+    let mut copied = 0;
+    for (from, to) in pairs {
+        for entry in from.scan_prefix(&[]) {
+            let (key, value) = entry?;
+            to.insert(&key, &value)?;
+            copied += 1;
+        }
+    }
+    target.flush()?;
+    Ok(copied)
+}
@@ -0,0 +1,275 @@
+//! Event record encoding.
+//!
+//! Events are written as JSON or as compact rkyv records, depending on
+//! `settings.eventStoreFormat`. The format of each record is detected on
+//! read, so a store may hold both; [`EventStore::compact`] rewrites every
+//! record in the configured format. Binary records keep the event payload
+//! as JSON so schema upcasters apply to both formats.
+
+use chrono::DateTime;
+use rkyv::Deserialize as _;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ulid::Ulid;
+
+use super::{EVENT_SCHEMA_VERSION, EventId, EventStore, EventStoreError, SessionEvent};
+use crate::config::EventStoreFormat;
+use crate::types::SessionKey;
+
+/// First byte of binary records. JSON records start with `{`.
+const BINARY_TAG: u8 = 0x01;
+
+/// Binary form of a [`SessionEvent`].
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[archive(check_bytes)]
+struct EventRecord {
+    version: u32,
+    id: [u8; 32],
+    ulid: u128,
+    session_key: String,
+    agent_id: String,
+    timestamp_secs: i64,
+    timestamp_nanos: u32,
+    /// JSON-encoded [`SessionEventKind`](super::SessionEventKind).
+    kind: Vec<u8>,
+}
+
+impl EventRecord {
+    fn from_event(event: &SessionEvent) -> Result<Self, EventStoreError> {
+        Ok(Self {
+            version: event.version,
+            id: event.id.0,
+            ulid: event.ulid.0,
+            session_key: event.session_key.as_ref().to_string(),
+            agent_id: event.agent_id.clone(),
+            timestamp_secs: event.timestamp.timestamp(),
+            timestamp_nanos: event.timestamp.timestamp_subsec_nanos(),
+            kind: serde_json::to_vec(&event.kind)?,
+        })
+    }
+
+    fn into_event(self) -> Result<SessionEvent, EventStoreError> {
+        Ok(SessionEvent {
+            id: EventId(self.id),
+            ulid: Ulid(self.ulid),
+            session_key: SessionKey::new(&self.session_key),
+            timestamp: self.timestamp()?,
+            kind: serde_json::from_slice(&self.kind)?,
+            agent_id: self.agent_id,
+            version: self.version,
+        })
+    }
+
+    /// The event as JSON, for upcasting.
+    fn into_value(self) -> Result<Value, EventStoreError> {
+        Ok(serde_json::json!({
+            "id": EventId(self.id),
+            "ulid": Ulid(self.ulid),
+            "session_key": self.session_key,
+            "agent_id": self.agent_id,
+            "timestamp": self.timestamp()?,
+            "kind": serde_json::from_slice::<Value>(&self.kind)?,
+            "version": self.version,
+        }))
+    }
+
+    fn timestamp(&self) -> Result<DateTime<chrono::Utc>, EventStoreError> {
+        DateTime::from_timestamp(self.timestamp_secs, self.timestamp_nanos).ok_or_else(|| {
+            EventStoreError::Encoding(format!(
+                "invalid timestamp {}.{}",
+                self.timestamp_secs, self.timestamp_nanos
+            ))
+        })
+    }
+}
+
+/// Format of a decrypted record.
+fn record_format(data: &[u8]) -> EventStoreFormat {
+    if data.first() == Some(&BINARY_TAG) {
+        EventStoreFormat::Binary
+    } else {
+        EventStoreFormat::Json
+    }
+}
+
+fn to_binary(event: &SessionEvent) -> Result<Vec<u8>, EventStoreError> {
+    let record = EventRecord::from_event(event)?;
+    let bytes =
+        rkyv::to_bytes::<_, 256>(&record).map_err(|e| EventStoreError::Encoding(e.to_string()))?;
+    let mut data = Vec::with_capacity(bytes.len() + 1);
+    data.push(BINARY_TAG);
+    data.extend_from_slice(&bytes);
+    Ok(data)
+}
+
+fn from_binary(data: &[u8]) -> Result<EventRecord, EventStoreError> {
+    // Archived data must be aligned; sled values are not
+    let mut aligned = rkyv::AlignedVec::with_capacity(data.len());
+    aligned.extend_from_slice(data);
+    let archived = rkyv::check_archived_root::<EventRecord>(&aligned)
+        .map_err(|e| EventStoreError::Encoding(e.to_string()))?;
+    archived
+        .deserialize(&mut rkyv::Infallible)
+        .map_err(|e| EventStoreError::Encoding(format!("{e:?}")))
+}
+
+/// Result of [`EventStore::compact`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompactionReport {
+    /// Format records were converted to.
+    pub format: EventStoreFormat,
+    /// Events read.
+    pub events_checked: usize,
+    /// Events rewritten in `format`.
+    pub events_converted: usize,
+    /// Stored size of all events before compaction, in bytes.
+    pub bytes_before: u64,
+    /// Stored size of all events after compaction, in bytes.
+    pub bytes_after: u64,
+}
+
+impl EventStore {
+    /// Write new events in `format`. Events in either format are read.
+    #[must_use]
+    pub const fn with_format(mut self, format: EventStoreFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Rewrite every event in active storage that is not in the store's
+    /// format, upcasting it to the current schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage fails or an event cannot be decoded.
+    pub fn compact(&self) -> Result<CompactionReport, EventStoreError> {
+        let mut report = CompactionReport {
+            format: self.format,
+            ..CompactionReport::default()
+        };
+
+        for result in &self.events_tree {
+            let (key, data) = result?;
+            report.events_checked += 1;
+            report.bytes_before += data.len() as u64;
+            if record_format(&self.unseal(&data)?) == self.format {
+                report.bytes_after += data.len() as u64;
+                continue;
+            }
+            let encoded = self.encode_event(&self.decode_event(&data)?)?;
+            report.bytes_after += encoded.len() as u64;
+            self.events_tree.insert(key, encoded)?;
+            report.events_converted += 1;
+        }
+
+        self.flush()?;
+        Ok(report)
+    }
+
+    /// Serialize an event in the store's format, encrypting it if the
+    /// store is encrypted.
+    pub(super) fn encode_event(&self, event: &SessionEvent) -> Result<Vec<u8>, EventStoreError> {
+        match self.format {
+            EventStoreFormat::Json => self.encode(event),
+            EventStoreFormat::Binary => self.seal(to_binary(event)?),
+        }
+    }
+
+    /// Decode a stored event in either format, upcasting it to the current
+    /// schema.
+    pub(super) fn decode_event(&self, data: &[u8]) -> Result<SessionEvent, EventStoreError> {
+        let data = self.unseal(data)?;
+        let mut value = match record_format(&data) {
+            EventStoreFormat::Binary => {
+                let record = from_binary(&data[1..])?;
+                if record.version == EVENT_SCHEMA_VERSION {
+                    return record.into_event();
+                }
+                record.into_value()?
+            }
+            EventStoreFormat::Json => serde_json::from_slice(&data)?,
+        };
+        self.upcasters.upcast(&mut value)?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// A stored event in either format as JSON, without upcasting.
+    pub(super) fn event_value(&self, data: &[u8]) -> Result<Value, EventStoreError> {
+        let data = self.unseal(data)?;
+        match record_format(&data) {
+            EventStoreFormat::Binary => from_binary(&data[1..])?.into_value(),
+            EventStoreFormat::Json => Ok(serde_json::from_slice(&data)?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::SessionEventKind;
+
+    fn message(key: &SessionKey, content: &str) -> SessionEvent {
+        SessionEvent::new(
+            key.clone(),
+            "agent".to_string(),
+            SessionEventKind::MessageReceived {
+                content: content.to_string(),
+                attachments: vec![],
+                sender_id: None,
+            },
+        )
+    }
+
+    #[test]
+    fn test_binary_roundtrip() {
+        let mut event = message(&SessionKey::new("s"), "hello");
+        event.ulid = Ulid::new();
+
+        let data = to_binary(&event).unwrap();
+        assert_eq!(record_format(&data), EventStoreFormat::Binary);
+        let decoded = from_binary(&data[1..]).unwrap().into_event().unwrap();
+        assert_eq!(decoded.id, event.id);
+        assert_eq!(decoded.ulid, event.ulid);
+        assert_eq!(decoded.timestamp, event.timestamp);
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&event).unwrap()
+        );
+
+        let value = from_binary(&data[1..]).unwrap().into_value().unwrap();
+        assert_eq!(value, serde_json::to_value(&event).unwrap());
+    }
+
+    #[test]
+    fn test_mixed_formats_and_compact() {
+        let temp = tempfile::tempdir().unwrap();
+        let key = SessionKey::new("s");
+        {
+            let store = EventStore::open(temp.path()).unwrap();
+            store.append(&message(&key, "json")).unwrap();
+            store.flush().unwrap();
+        }
+
+        let store = EventStore::open(temp.path())
+            .unwrap()
+            .with_format(EventStoreFormat::Binary);
+        store.append(&message(&key, "binary")).unwrap();
+        assert_eq!(store.get_events(&key).unwrap().len(), 2);
+
+        let report = store.compact().unwrap();
+        assert_eq!(report.events_checked, 2);
+        assert_eq!(report.events_converted, 1);
+        assert!(report.bytes_after < report.bytes_before);
+        for result in &store.events_tree {
+            let (_, data) = result.unwrap();
+            assert_eq!(record_format(&data), EventStoreFormat::Binary);
+        }
+
+        let events = store.get_events(&key).unwrap();
+        assert!(matches!(
+            &events[0].kind,
+            SessionEventKind::MessageReceived { content, .. } if content == "json"
+        ));
+        assert_eq!(store.compact().unwrap().events_converted, 0);
+    }
+}
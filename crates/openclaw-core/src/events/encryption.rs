@@ -0,0 +1,341 @@
+//! Encryption at rest for the event store.
+//!
+//! When opened with a key, event, projection, and reminder values are sealed
+//! with AES-256-GCM before they reach sled. Keys stay in plaintext so
+//! sessions can still be listed and scanned by prefix. The store records
+//! that it is encrypted, together with a check value that detects a wrong
+//! key on open.
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use super::{ArchivedSession, EventStore, EventStoreError};
+use crate::secrets::EncryptionKey;
+
+/// Marker recording how values are encrypted.
+const ENCRYPTION_MARKER: &[u8] = b"encryption";
+const ENCRYPTION_AES_GCM: &[u8] = b"aes-256-gcm";
+
+/// Known plaintext sealed with the store key, used to detect a wrong key.
+const ENCRYPTION_CHECK: &[u8] = b"encryption_check";
+const ENCRYPTION_CHECK_PLAINTEXT: &[u8] = b"openclaw event store";
+
+impl EventStore {
+    /// Open or create an event store whose values are encrypted with `key`.
+    ///
+    /// A new or empty store is marked as encrypted on first open.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Encryption` if `key` does not match the store or the store
+    /// holds unencrypted data (see [`EventStore::encrypt_store`]).
+    pub fn open_encrypted(path: &Path, key: EncryptionKey) -> Result<Self, EventStoreError> {
+        Self::open_with_key(path, Some(key))
+    }
+
+    /// Whether values are encrypted at rest.
+    #[must_use]
+    pub const fn is_encrypted(&self) -> bool {
+        self.key.is_some()
+    }
+
+    /// Encrypt an existing plaintext store in place.
+    ///
+    /// The store is copied into an encrypted store next to it, including
+    /// archive files, which are re-written encrypted; archives in a blob
+    /// store are left as they are. The original is kept as a backup, whose
+    /// path is returned; delete it (and its archive files) once the
+    /// encrypted store has been verified. The store must not be open
+    /// elsewhere.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Encryption` if the store is already encrypted, or an
+    /// error if it cannot be copied or renamed.
+    pub fn encrypt_store(path: &Path, key: &EncryptionKey) -> Result<PathBuf, EventStoreError> {
+        let staging = path.with_extension("encrypting");
+        if staging.exists() {
+            std::fs::remove_dir_all(&staging)?;
+        }
+
+        {
+            let source = Self::open(path)?;
+            let target = Self::open_encrypted(&staging, key.clone())?;
+
+            // The new store is marked with the current schema, so upcast
+            for result in &source.events_tree {
+                let (k, v) = result?;
+                let event = source.decode_event(&v)?;
+                target.events_tree.insert(k, target.encode_event(&event)?)?;
+            }
+            for (from, to) in [
+                (&source.sessions_tree, &target.sessions_tree),
+                (&source.reminders_tree, &target.reminders_tree),
+            ] {
+                for result in from {
+                    let (k, v) = result?;
+                    to.insert(k, target.seal(v)?)?;
+                }
+            }
+            // Hashes and activity counters hold no content
+            for (from, to) in [
+                (&source.hashes_tree, &target.hashes_tree),
+                (&source.agent_stats_tree, &target.agent_stats_tree),
+                (&source.channel_stats_tree, &target.channel_stats_tree),
+                (&source.error_stats_tree, &target.error_stats_tree),
+            ] {
+                for result in from {
+                    let (k, v) = result?;
+                    to.insert(k, v)?;
+                }
+            }
+            for result in &source.archive_tree {
+                let (k, v) = result?;
+                let mut entry: ArchivedSession = serde_json::from_slice(&v)?;
+                if entry.in_blob_store {
+                    // No blob store is open here; the archive stays readable as is
+                    target.archive_tree.insert(k, v)?;
+                    continue;
+                }
+                let events = source.read_archive(&entry)?;
+                let mut encrypted_path = entry.path.clone().into_os_string();
+                encrypted_path.push(".enc");
+                entry.path = encrypted_path.into();
+                target.write_archive(&entry.path, &events)?;
+                entry.encrypted = true;
+                target.archive_tree.insert(k, serde_json::to_vec(&entry)?)?;
+            }
+
+            target.flush()?;
+        }
+
+        let backup = path.with_extension(format!(
+            "plaintext-{}",
+            chrono::Utc::now().format("%Y%m%d%H%M%S")
+        ));
+        std::fs::rename(path, &backup)?;
+        std::fs::rename(&staging, path)?;
+
+        tracing::info!(
+            "Encrypted event store at {} (backup at {})",
+            path.display(),
+            backup.display()
+        );
+        Ok(backup)
+    }
+
+    /// Validate `self.key` against the store's encryption marker, marking
+    /// an empty store as encrypted.
+    pub(super) fn check_encryption(&self) -> Result<(), EventStoreError> {
+        let marked = self.meta.get(ENCRYPTION_MARKER)?.is_some();
+
+        match (&self.key, marked) {
+            (None, false) => Ok(()),
+            (None, true) => Err(EventStoreError::Encryption(
+                "store is encrypted; enable settings.encryptEventStore to open it".to_string(),
+            )),
+            (Some(key), true) => {
+                let check = self.meta.get(ENCRYPTION_CHECK)?.unwrap_or_default();
+                match key.decrypt(&check) {
+                    Ok(plaintext) if plaintext == ENCRYPTION_CHECK_PLAINTEXT => Ok(()),
+                    _ => Err(EventStoreError::Encryption(
+                        "wrong key for encrypted store".to_string(),
+                    )),
+                }
+            }
+            (Some(key), false) => {
+                let mut has_data = false;
+                for tree in [
+                    &self.events_tree,
+                    &self.sessions_tree,
+                    &self.reminders_tree,
+                    &self.archive_tree,
+                ] {
+                    has_data |= !tree.is_empty()?;
+                }
+                if has_data {
+                    return Err(EventStoreError::Encryption(
+                        "store holds unencrypted data; run `openclaw sessions encrypt` first"
+                            .to_string(),
+                    ));
+                }
+
+                let check = key
+                    .encrypt(ENCRYPTION_CHECK_PLAINTEXT)
+                    .map_err(|e| EventStoreError::Encryption(e.to_string()))?;
+                self.meta.insert(ENCRYPTION_CHECK, check)?;
+                self.meta.insert(ENCRYPTION_MARKER, ENCRYPTION_AES_GCM)?;
+                self.backend.flush()?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Serialize a value for storage, encrypting it if the store is
+    /// encrypted.
+    pub(super) fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, EventStoreError> {
+        self.seal(serde_json::to_vec(value)?)
+    }
+
+    /// Inverse of [`EventStore::encode`].
+    pub(super) fn decode<T: DeserializeOwned>(&self, data: &[u8]) -> Result<T, EventStoreError> {
+        Ok(serde_json::from_slice(&self.unseal(data)?)?)
+    }
+
+    /// Encrypt raw bytes if the store is encrypted.
+    pub(super) fn seal(&self, data: Vec<u8>) -> Result<Vec<u8>, EventStoreError> {
+        match &self.key {
+            Some(key) => key
+                .encrypt(&data)
+                .map_err(|e| EventStoreError::Encryption(e.to_string())),
+            None => Ok(data),
+        }
+    }
+
+    /// Decrypt raw bytes if the store is encrypted.
+    pub(super) fn unseal<'a>(&self, data: &'a [u8]) -> Result<Cow<'a, [u8]>, EventStoreError> {
+        self.key.as_ref().map_or_else(
+            || Ok(Cow::Borrowed(data)),
+            |key| {
+                key.decrypt(data)
+                    .map(Cow::Owned)
+                    .map_err(|e| EventStoreError::Encryption(e.to_string()))
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{SessionEvent, SessionEventKind};
+    use crate::types::SessionKey;
+
+    fn key(byte: u8) -> EncryptionKey {
+        EncryptionKey::new([byte; 32])
+    }
+
+    fn message(key: &SessionKey, content: &str) -> SessionEvent {
+        SessionEvent::new(
+            key.clone(),
+            "default".to_string(),
+            SessionEventKind::MessageReceived {
+                content: content.to_string(),
+                attachments: vec![],
+                sender_id: None,
+            },
+        )
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|w| w == needle)
+    }
+
+    #[test]
+    fn test_encrypted_roundtrip() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = EventStore::open_encrypted(temp.path(), key(1)).unwrap();
+        assert!(store.is_encrypted());
+        let session = SessionKey::new("agent:default:telegram:user123");
+
+        store.append(&message(&session, "top secret")).unwrap();
+
+        assert_eq!(store.list_sessions().unwrap(), vec![session.clone()]);
+        let events = store.get_events(&session).unwrap();
+        assert!(matches!(
+            &events[0].kind,
+            SessionEventKind::MessageReceived { content, .. } if content == "top secret"
+        ));
+        assert_eq!(store.get_projection(&session).unwrap().message_count, 1);
+
+        // Keys stay queryable; values do not leak content
+        for result in &store.events_tree {
+            let (k, v) = result.unwrap();
+            assert!(k.starts_with(session.as_ref().as_bytes()));
+            assert!(!contains(&v, b"top secret"));
+        }
+    }
+
+    #[test]
+    fn test_open_requires_matching_key() {
+        let temp = tempfile::tempdir().unwrap();
+        let session = SessionKey::new("agent:default:telegram:user123");
+        {
+            let store = EventStore::open_encrypted(temp.path(), key(1)).unwrap();
+            store.append(&message(&session, "hello")).unwrap();
+        }
+
+        assert!(matches!(
+            EventStore::open_encrypted(temp.path(), key(2)),
+            Err(EventStoreError::Encryption(_))
+        ));
+        assert!(matches!(
+            EventStore::open(temp.path()),
+            Err(EventStoreError::Encryption(_))
+        ));
+        let store = EventStore::open_encrypted(temp.path(), key(1)).unwrap();
+        assert_eq!(store.get_events(&session).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_key_rejected_for_plaintext_store() {
+        let temp = tempfile::tempdir().unwrap();
+        let session = SessionKey::new("agent:default:telegram:user123");
+        let store = EventStore::open(temp.path()).unwrap();
+        store.append(&message(&session, "hello")).unwrap();
+        store.flush().unwrap();
+        drop(store);
+
+        assert!(matches!(
+            EventStore::open_encrypted(temp.path(), key(1)),
+            Err(EventStoreError::Encryption(_))
+        ));
+    }
+
+    #[test]
+    fn test_encrypt_store() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("events");
+        let active = SessionKey::new("agent:default:telegram:active");
+        let archived = SessionKey::new("agent:default:telegram:archived");
+        {
+            let store = EventStore::open(&path)
+                .unwrap()
+                .with_archive_dir(temp.path().join("archive"));
+            store.append(&message(&active, "hello")).unwrap();
+            store.append(&message(&archived, "goodbye")).unwrap();
+            store
+                .append(&SessionEvent::new(
+                    archived.clone(),
+                    "default".to_string(),
+                    SessionEventKind::SessionEnded {
+                        reason: "done".to_string(),
+                    },
+                ))
+                .unwrap();
+            store.archive_session(&archived).unwrap();
+            store.flush().unwrap();
+        }
+
+        let backup = EventStore::encrypt_store(&path, &key(1)).unwrap();
+        assert!(backup.exists());
+        assert!(EventStore::open(&path).is_err());
+
+        let store = EventStore::open_encrypted(&path, key(1)).unwrap();
+        assert_eq!(store.get_events(&active).unwrap().len(), 1);
+        let entry = store.list_archived().unwrap().remove(0);
+        assert!(entry.encrypted);
+        assert!(!contains(&std::fs::read(&entry.path).unwrap(), b"goodbye"));
+        assert_eq!(store.get_events(&archived).unwrap().len(), 2);
+
+        // The backup still opens as a plaintext store
+        drop(store);
+        assert_eq!(
+            EventStore::open(&backup).unwrap().stats().unwrap().events,
+            1
+        );
+    }
+}
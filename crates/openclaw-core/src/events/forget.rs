@@ -0,0 +1,291 @@
+//! Erasing everything stored about one peer.
+//!
+//! Every session with the peer, active or archived, is removed for good:
+//! events, projection, content hashes, reminders, and archive files.
+//! Messages the peer sent in group and thread sessions on the same channel
+//! are removed from those sessions, which are otherwise kept. Aggregate
+//! activity counters carry no peer data and are kept.
+
+use serde::Serialize;
+
+use super::{EventStore, EventStoreError, SessionEvent, SessionEventKind};
+use crate::types::SessionKey;
+
+/// A session removed by [`EventStore::forget_peer`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ForgottenSession {
+    /// Session key.
+    pub session_key: SessionKey,
+    /// Agent the session belonged to.
+    pub agent_id: String,
+    /// Whether it was in cold storage.
+    pub archived: bool,
+}
+
+/// What [`EventStore::forget_peer`] removed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ForgottenPeer {
+    /// Sessions removed.
+    pub sessions: Vec<ForgottenSession>,
+    /// Events removed across those sessions, plus the messages counted in
+    /// `redacted`.
+    pub events: usize,
+    /// Messages the peer sent in other sessions, removed from them.
+    pub redacted: usize,
+    /// Text of the peer's removed messages, for scrubbing logs kept
+    /// outside the store.
+    #[serde(skip)]
+    pub messages: Vec<String>,
+}
+
+impl EventStore {
+    /// Permanently remove every session with `peer_id` on `channel`,
+    /// including archived ones, and the messages they sent in other
+    /// sessions on `channel`.
+    ///
+    /// Archives are read in place rather than rehydrated, so nothing about
+    /// the peer is written back to active storage on the way out.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage fails or an archive cannot be read or
+    /// deleted. Sessions removed before the failure stay removed.
+    pub fn forget_peer(
+        &self,
+        channel: &str,
+        peer_id: &str,
+    ) -> Result<ForgottenPeer, EventStoreError> {
+        let mut forgotten = ForgottenPeer::default();
+
+        for key in self.list_sessions()? {
+            let projection = self.get_projection(&key)?;
+            if projection.channel.as_ref() != channel {
+                continue;
+            }
+            if projection.peer_id != peer_id {
+                let sent = self.remove_sent_by(&key, peer_id)?;
+                forgotten.redacted += sent.len();
+                forgotten.messages.extend(sent);
+                continue;
+            }
+            forgotten.messages.extend(
+                self.get_events(&key)?
+                    .iter()
+                    .filter_map(|e| sent_by(e, None)),
+            );
+            forgotten.events += self.purge_session(&key)?;
+            forgotten.sessions.push(ForgottenSession {
+                session_key: key,
+                agent_id: projection.agent_id,
+                archived: false,
+            });
+        }
+
+        for entry in self.list_archived()? {
+            let events = self.read_archive(&entry)?;
+            if !events.iter().any(|e| is_started_by(e, channel, peer_id)) {
+                let (sent, kept): (Vec<_>, Vec<_>) = events
+                    .into_iter()
+                    .partition(|e| sent_by(e, Some(peer_id)).is_some());
+                if !sent.is_empty() && kept.iter().any(|e| is_on(e, channel)) {
+                    self.rewrite_archive(&entry, &kept)?;
+                    forgotten.redacted += sent.len();
+                    forgotten
+                        .messages
+                        .extend(sent.iter().filter_map(|e| sent_by(e, Some(peer_id))));
+                }
+                continue;
+            }
+            forgotten
+                .messages
+                .extend(events.iter().filter_map(|e| sent_by(e, None)));
+            self.remove_archive(&entry)?;
+            self.archive_tree
+                .remove(entry.session_key.as_ref().as_bytes())?;
+            forgotten.events += events.len();
+            forgotten.sessions.push(ForgottenSession {
+                session_key: entry.session_key,
+                agent_id: entry.agent_id,
+                archived: true,
+            });
+        }
+
+        forgotten.events += forgotten.redacted;
+        tracing::info!(
+            "Forgot a peer on {}: {} session(s), {} event(s), {} message(s) in other sessions",
+            channel,
+            forgotten.sessions.len(),
+            forgotten.events,
+            forgotten.redacted
+        );
+        Ok(forgotten)
+    }
+
+    /// Remove the messages `peer_id` sent in an active session, rebuilding
+    /// its projection without them. Returns their text.
+    fn remove_sent_by(
+        &self,
+        session_key: &SessionKey,
+        peer_id: &str,
+    ) -> Result<Vec<String>, EventStoreError> {
+        let mut removed = Vec::new();
+        for event in self.get_events(session_key)? {
+            let Some(content) = sent_by(&event, Some(peer_id)) else {
+                continue;
+            };
+            self.events_tree
+                .remove(format!("{session_key}:{}", event.ulid).as_bytes())?;
+            self.hashes_tree
+                .remove(format!("{session_key}:{}", event.id.to_hex()).as_bytes())?;
+            removed.push(content);
+        }
+        if !removed.is_empty() {
+            self.rebuild_projection(session_key)?;
+        }
+        Ok(removed)
+    }
+}
+
+/// Text of `event` if it is a message from `sender_id`, or from the
+/// session's own peer when `None`.
+fn sent_by(event: &SessionEvent, sender_id: Option<&str>) -> Option<String> {
+    match &event.kind {
+        SessionEventKind::MessageReceived {
+            content,
+            sender_id: sender,
+            ..
+        } if sender.as_deref() == sender_id => Some(content.clone()),
+        _ => None,
+    }
+}
+
+/// Whether `event` starts a session on `channel`.
+fn is_on(event: &SessionEvent, channel: &str) -> bool {
+    matches!(
+        &event.kind,
+        SessionEventKind::SessionStarted { channel: c, .. } if c == channel
+    )
+}
+
+/// Whether `event` starts a session with `peer_id` on `channel`.
+fn is_started_by(event: &SessionEvent, channel: &str, peer_id: &str) -> bool {
+    matches!(
+        &event.kind,
+        SessionEventKind::SessionStarted { channel: c, peer_id: p } if c == channel && p == peer_id
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(store: &EventStore, key: &SessionKey, peer_id: &str, end: bool) {
+        let mut kinds = vec![
+            SessionEventKind::SessionStarted {
+                channel: "telegram".to_string(),
+                peer_id: peer_id.to_string(),
+            },
+            SessionEventKind::MessageReceived {
+                content: "My address is 1 Main St".to_string(),
+                attachments: vec![],
+                sender_id: None,
+            },
+        ];
+        if end {
+            kinds.push(SessionEventKind::SessionEnded {
+                reason: "done".to_string(),
+            });
+        }
+        for kind in kinds {
+            store
+                .append(&SessionEvent::new(key.clone(), "default".to_string(), kind))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_forget_peer() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = EventStore::open(&temp.path().join("events"))
+            .unwrap()
+            .with_archive_dir(temp.path().join("archive"));
+        let active = SessionKey::new("agent:default:telegram:12345");
+        let old = SessionKey::new("agent:default:telegram:12345:old");
+        let other = SessionKey::new("agent:default:telegram:999");
+        session(&store, &active, "12345", false);
+        session(&store, &old, "12345", true);
+        session(&store, &other, "999", false);
+        let archive = store.archive_session(&old).unwrap();
+
+        let forgotten = store.forget_peer("telegram", "12345").unwrap();
+        assert_eq!(forgotten.events, 5);
+        assert_eq!(forgotten.sessions.len(), 2);
+        assert_eq!(forgotten.sessions[0].session_key, active);
+        assert!(forgotten.sessions[1].archived);
+
+        assert_eq!(store.list_sessions().unwrap(), vec![other]);
+        assert!(store.list_archived().unwrap().is_empty());
+        assert!(!archive.path.exists());
+        // Nothing comes back from cold storage
+        assert!(store.get_projection(&old).is_err());
+
+        let again = store.forget_peer("telegram", "12345").unwrap();
+        assert_eq!(again, ForgottenPeer::default());
+    }
+
+    #[test]
+    fn test_forget_peer_in_group() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = EventStore::open(&temp.path().join("events"))
+            .unwrap()
+            .with_archive_dir(temp.path().join("archive"));
+        let group = SessionKey::new("agent:default:telegram:-100");
+        let old_group = SessionKey::new("agent:default:telegram:-100:old");
+        for key in [&group, &old_group] {
+            session(&store, key, "-100", false);
+            let mut kinds: Vec<_> = [("12345", "Call me on 555-0100"), ("999", "Hi all")]
+                .into_iter()
+                .map(|(sender, content)| SessionEventKind::MessageReceived {
+                    content: content.to_string(),
+                    attachments: vec![],
+                    sender_id: Some(sender.to_string()),
+                })
+                .collect();
+            if key == &old_group {
+                kinds.push(SessionEventKind::SessionEnded {
+                    reason: "done".to_string(),
+                });
+            }
+            for kind in kinds {
+                store
+                    .append(&SessionEvent::new(key.clone(), "default".to_string(), kind))
+                    .unwrap();
+            }
+        }
+        store.archive_session(&old_group).unwrap();
+
+        let forgotten = store.forget_peer("telegram", "12345").unwrap();
+        assert!(forgotten.sessions.is_empty());
+        assert_eq!(forgotten.redacted, 2);
+        assert_eq!(forgotten.events, 2);
+        assert_eq!(forgotten.messages, vec!["Call me on 555-0100"; 2]);
+
+        // The group stays, without the peer's messages
+        for key in [&group, &old_group] {
+            let texts: Vec<_> = store
+                .get_events(key)
+                .unwrap()
+                .into_iter()
+                .filter_map(|e| match e.kind {
+                    SessionEventKind::MessageReceived { content, .. } => Some(content),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(texts, vec!["My address is 1 Main St", "Hi all"]);
+        }
+        assert_eq!(store.get_projection(&group).unwrap().message_count, 2);
+
+        let again = store.forget_peer("telegram", "12345").unwrap();
+        assert_eq!(again, ForgottenPeer::default());
+    }
+}
@@ -0,0 +1,152 @@
+//! Forking a session into a new one.
+//!
+//! A fork copies a session's events up to a chosen event into a new
+//! session, so the conversation can continue from that point without
+//! touching the original.
+
+use ulid::Ulid;
+
+use super::{EventStore, EventStoreError, SessionEvent, SessionEventKind};
+use crate::types::SessionKey;
+
+/// Key for a fork of `source`: `<source>:fork-<suffix>`.
+#[must_use]
+pub fn fork_key(source: &SessionKey, suffix: &str) -> SessionKey {
+    SessionKey::new(format!("{source}:fork-{suffix}"))
+}
+
+impl EventStore {
+    /// Copy the events of `source` up to and including the event stored as
+    /// `at` into a new session `target`, returning the number copied.
+    ///
+    /// Deletion markers and reminders stay with the source. Activity
+    /// counters are not changed by the copies, since the source already
+    /// counted them.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if `target` already exists, `at` is not an event of
+    /// `source`, or storage fails.
+    pub fn fork_session(
+        &self,
+        source: &SessionKey,
+        at: Ulid,
+        target: &SessionKey,
+    ) -> Result<usize, EventStoreError> {
+        if self
+            .sessions_tree
+            .contains_key(target.as_ref().as_bytes())?
+            || self.is_archived(target)?
+        {
+            return Err(EventStoreError::AlreadyExists(target.to_string()));
+        }
+
+        let events = self.get_events(source)?;
+        let end = events
+            .iter()
+            .position(|e| e.ulid == at)
+            .ok_or_else(|| EventStoreError::NotFound(format!("{at} in session {source}")))?;
+
+        let mut copied = 0;
+        for event in &events[..=end] {
+            if !is_copied(&event.kind) {
+                continue;
+            }
+            let mut copy = event.clone();
+            copy.session_key = target.clone();
+            copy.ulid = Ulid::nil();
+            self.insert_event(&copy, false)?;
+            copied += 1;
+        }
+
+        self.append(&SessionEvent::new(
+            target.clone(),
+            events[end].agent_id.clone(),
+            SessionEventKind::SessionForked {
+                from: source.clone(),
+                at,
+            },
+        ))?;
+        Ok(copied)
+    }
+}
+
+/// Whether a fork copies events of this kind.
+const fn is_copied(kind: &SessionEventKind) -> bool {
+    !matches!(
+        kind,
+        SessionEventKind::SessionDeleted { .. }
+            | SessionEventKind::SessionRestored
+            | SessionEventKind::ReminderScheduled { .. }
+            | SessionEventKind::ReminderFired { .. }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::SessionMessage;
+    use tempfile::tempdir;
+
+    fn event(key: &SessionKey, kind: SessionEventKind) -> SessionEvent {
+        SessionEvent::new(key.clone(), "default".to_string(), kind)
+    }
+
+    #[test]
+    fn test_fork_session() {
+        let temp = tempdir().unwrap();
+        let store = EventStore::open(temp.path()).unwrap();
+        let source = SessionKey::new("agent:default:main");
+
+        store
+            .append(&event(
+                &source,
+                SessionEventKind::SessionStarted {
+                    channel: "telegram".to_string(),
+                    peer_id: "alice".to_string(),
+                },
+            ))
+            .unwrap();
+        for n in 0..3 {
+            store
+                .append(&event(
+                    &source,
+                    SessionEventKind::MessageReceived {
+                        content: format!("message {n}"),
+                        attachments: vec![],
+                        sender_id: None,
+                    },
+                ))
+                .unwrap();
+        }
+        let events = store.get_events(&source).unwrap();
+
+        // Fork after the second message
+        let target = fork_key(&source, "a");
+        let copied = store
+            .fork_session(&source, events[2].ulid, &target)
+            .unwrap();
+        assert_eq!(copied, 3);
+
+        let projection = store.get_projection(&target).unwrap();
+        assert_eq!(projection.forked_from.as_ref(), Some(&source));
+        assert_eq!(projection.peer_id, "alice");
+        assert!(matches!(
+            projection.messages.last(),
+            Some(SessionMessage::Inbound(text)) if text == "message 1"
+        ));
+
+        // The source is unchanged and its key prefix does not pick up the fork
+        assert_eq!(store.get_events(&source).unwrap().len(), 4);
+        assert_eq!(store.get_events(&target).unwrap().len(), 4);
+
+        assert!(matches!(
+            store.fork_session(&source, events[1].ulid, &target),
+            Err(EventStoreError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            store.fork_session(&source, Ulid::new(), &fork_key(&source, "b")),
+            Err(EventStoreError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,130 @@
+//! Store integrity checks.
+//!
+//! Verification decodes every stored event and session projection, which
+//! catches corrupted entries and entries written with a different
+//! encryption key. Projections are derived data, so a corrupt one can be
+//! rebuilt from its session's events.
+
+use serde::{Deserialize, Serialize};
+
+use super::{EventStore, EventStoreError, SessionProjection};
+use crate::types::SessionKey;
+
+/// Result of [`EventStore::verify`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    /// Number of events decoded.
+    pub events_checked: usize,
+    /// Number of session projections decoded.
+    pub sessions_checked: usize,
+    /// Keys of events that could not be decoded.
+    pub corrupt_events: Vec<String>,
+    /// Sessions whose projection could not be decoded.
+    pub corrupt_sessions: Vec<SessionKey>,
+}
+
+impl IntegrityReport {
+    /// Whether every entry decoded.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.corrupt_events.is_empty() && self.corrupt_sessions.is_empty()
+    }
+}
+
+impl EventStore {
+    /// Decode every event and session projection in active storage.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the underlying storage cannot be read.
+    pub fn verify(&self) -> Result<IntegrityReport, EventStoreError> {
+        let mut report = IntegrityReport::default();
+
+        for result in &self.events_tree {
+            let (key, value) = result?;
+            report.events_checked += 1;
+            if self.decode_event(&value).is_err() {
+                report
+                    .corrupt_events
+                    .push(String::from_utf8_lossy(&key).into_owned());
+            }
+        }
+
+        for result in &self.sessions_tree {
+            let (key, value) = result?;
+            report.sessions_checked += 1;
+            if self.decode::<SessionProjection>(&value).is_err() {
+                report
+                    .corrupt_sessions
+                    .push(SessionKey::new(String::from_utf8_lossy(&key).into_owned()));
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Replace a session's projection with one replayed from its events.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the session's events cannot be read or it has none.
+    pub fn rebuild_projection(
+        &self,
+        session_key: &SessionKey,
+    ) -> Result<SessionProjection, EventStoreError> {
+        let events = self.get_events(session_key)?;
+        if events.is_empty() {
+            return Err(EventStoreError::NotFound(session_key.to_string()));
+        }
+
+        self.sessions_tree.remove(session_key.as_ref().as_bytes())?;
+        for event in &events {
+            self.update_projection(event)?;
+        }
+        self.get_projection(session_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{SessionEvent, SessionEventKind};
+
+    #[test]
+    fn test_verify_and_rebuild() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = EventStore::open(temp.path()).unwrap();
+        let key = SessionKey::new("agent:default:telegram:dm:user1");
+        for kind in [
+            SessionEventKind::SessionStarted {
+                channel: "telegram".to_string(),
+                peer_id: "user1".to_string(),
+            },
+            SessionEventKind::MessageReceived {
+                content: "Hello".to_string(),
+                attachments: vec![],
+                sender_id: None,
+            },
+        ] {
+            store
+                .append(&SessionEvent::new(key.clone(), "default".to_string(), kind))
+                .unwrap();
+        }
+
+        let report = store.verify().unwrap();
+        assert!(report.is_ok());
+        assert_eq!(report.events_checked, 2);
+        assert_eq!(report.sessions_checked, 1);
+
+        store
+            .sessions_tree
+            .insert(key.as_ref().as_bytes(), b"not json".as_slice())
+            .unwrap();
+        let report = store.verify().unwrap();
+        assert_eq!(report.corrupt_sessions, vec![key.clone()]);
+
+        let projection = store.rebuild_projection(&key).unwrap();
+        assert_eq!(projection.message_count, 1);
+        assert!(store.verify().unwrap().is_ok());
+    }
+}
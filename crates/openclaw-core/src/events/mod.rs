@@ -1,15 +1,50 @@
 //! Event-sourced session storage (grite pattern).
 //!
 //! Sessions are stored as append-only event logs with CRDT projections
-//! for materialized views. Uses sled for fast local storage.
+//! for materialized views. Uses sled (or `SQLite`) for local storage.
 
 use blake2::{Blake2b, Digest, digest::consts::U32};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use thiserror::Error;
-
-use crate::types::{ChannelId, SessionKey, TokenUsage};
+use ulid::Ulid;
+
+use crate::config::{EventStoreBackend, EventStoreFormat};
+use crate::secrets::EncryptionKey;
+use crate::types::{Attachment, ChannelId, Citation, SessionKey, TokenUsage};
+use backend::Tree;
+
+mod archive;
+mod backend;
+mod codec;
+mod encryption;
+mod forget;
+mod fork;
+mod integrity;
+mod schema;
+mod snapshot;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod stats;
+mod transcript;
+
+pub use archive::ArchivedSession;
+pub use backend::{Entries, SledBackend, StorageBackend, StorageTree};
+pub use codec::CompactionReport;
+pub use forget::{ForgottenPeer, ForgottenSession};
+pub use fork::fork_key;
+pub use integrity::IntegrityReport;
+pub use schema::{EVENT_SCHEMA_VERSION, MigrationReport, Upcaster, UpcasterRegistry};
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteBackend;
+pub use stats::{
+    ActivityStats, AgentDayStats, ChannelDayStats, ERROR_ATTACHMENT_REJECTED,
+    ERROR_BROADCAST_FAILED, ERROR_CONTENT_BLOCKED, ERROR_REMINDER_FAILED, ERROR_TOOL_DENIED,
+    ERROR_TOOL_FAILED,
+};
+pub use transcript::{Transcript, TranscriptFormat};
 
 /// Event store errors.
 #[derive(Error, Debug)]
@@ -25,9 +60,40 @@ pub enum EventStoreError {
     /// Event not found.
     #[error("Event not found: {0}")]
     NotFound(String),
+
+    /// Archive file error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Session cannot be archived.
+    #[error("Archive error: {0}")]
+    Archive(String),
+
+    /// Encrypted store cannot be opened or a value cannot be decrypted.
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+
+    /// Event schema version is unsupported or an event cannot be upcast.
+    #[error("Schema error: {0}")]
+    Schema(String),
+
+    /// Binary event record cannot be encoded or decoded.
+    #[error("Encoding error: {0}")]
+    Encoding(String),
+
+    /// Storage backend is unavailable or failed.
+    #[error("Storage backend error: {0}")]
+    Backend(String),
+
+    /// A session with the key already exists.
+    #[error("Session already exists: {0}")]
+    AlreadyExists(String),
 }
 
-/// Unique event identifier (`BLAKE2b` hash).
+/// Content hash of an event (`BLAKE2b`), used for deduplication.
+///
+/// Events are keyed in storage by their [`Ulid`], which is unique and
+/// time-ordered; this hash only identifies identical content.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct EventId(pub [u8; 32]);
 
@@ -56,11 +122,65 @@ impl std::fmt::Display for EventId {
     }
 }
 
+/// Source of storage identifiers for events.
+pub trait IdGenerator: Send + Sync {
+    /// Generate an identifier for an event that occurred at `timestamp`.
+    ///
+    /// Identifiers must sort in generation order.
+    fn generate(&self, timestamp: DateTime<Utc>) -> Ulid;
+}
+
+/// Monotonic ULID generator.
+///
+/// IDs generated within the same millisecond still sort in call order.
+pub struct MonotonicUlidGenerator {
+    inner: Mutex<ulid::Generator>,
+}
+
+impl MonotonicUlidGenerator {
+    /// Create a generator.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            inner: Mutex::new(ulid::Generator::new()),
+        }
+    }
+}
+
+impl Default for MonotonicUlidGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for MonotonicUlidGenerator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MonotonicUlidGenerator")
+            .finish_non_exhaustive()
+    }
+}
+
+impl IdGenerator for MonotonicUlidGenerator {
+    fn generate(&self, timestamp: DateTime<Utc>) -> Ulid {
+        let Ok(mut generator) = self.inner.lock() else {
+            return Ulid::from_datetime(timestamp.into());
+        };
+        // Never go backwards in time, even if the event timestamp does
+        let now = Utc::now().max(timestamp);
+        generator
+            .generate_from_datetime(now.into())
+            .unwrap_or_else(|_| Ulid::from_datetime(now.into()))
+    }
+}
+
 /// A session event.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionEvent {
-    /// Unique event identifier.
+    /// Content hash of the event.
     pub id: EventId,
+    /// Storage identifier, assigned by the store on append.
+    #[serde(default = "Ulid::nil")]
+    pub ulid: Ulid,
     /// Session this event belongs to.
     pub session_key: SessionKey,
     /// Agent that processed this event.
@@ -69,6 +189,10 @@ pub struct SessionEvent {
     pub timestamp: DateTime<Utc>,
     /// Event payload.
     pub kind: SessionEventKind,
+    /// Schema version the event was written with; 0 for events written
+    /// before versioning.
+    #[serde(default)]
+    pub version: u32,
 }
 
 impl SessionEvent {
@@ -81,10 +205,12 @@ impl SessionEvent {
 
         Self {
             id,
+            ulid: Ulid::nil(),
             session_key,
             agent_id,
             timestamp,
             kind,
+            version: EVENT_SCHEMA_VERSION,
         }
     }
 }
@@ -107,6 +233,10 @@ pub enum SessionEventKind {
         content: String,
         /// Attachment metadata.
         attachments: Vec<AttachmentMeta>,
+        /// Who sent it, when not the session's peer (a member of a group
+        /// chat, say).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sender_id: Option<String>,
     },
 
     /// Message sent to peer.
@@ -135,6 +265,16 @@ pub enum SessionEventKind {
         success: bool,
     },
 
+    /// The model asked for a tool the agent may not use; it was not run.
+    ToolDenied {
+        /// Tool name.
+        tool_name: String,
+        /// Parameters the model passed.
+        params: serde_json::Value,
+        /// Why the tool is not allowed.
+        reason: String,
+    },
+
     /// Agent produced a response.
     AgentResponse {
         /// Response content.
@@ -143,6 +283,12 @@ pub enum SessionEventKind {
         model: String,
         /// Token usage.
         tokens: TokenUsage,
+        /// Time taken to produce the response, in milliseconds.
+        #[serde(default)]
+        latency_ms: Option<u64>,
+        /// Sources cited in the response.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        citations: Vec<Citation>,
     },
 
     /// Session ended.
@@ -155,9 +301,202 @@ pub enum SessionEventKind {
     StateChanged {
         /// State key.
         key: String,
-        /// New value.
+        /// New value; `null` deletes the key.
         value: serde_json::Value,
     },
+
+    /// Usage incurred by a delegated sub-agent, attributed to this session.
+    DelegatedUsage {
+        /// Agent that handled the delegation.
+        agent_id: String,
+        /// Session key of the delegated run.
+        child_session: String,
+        /// Token usage including nested delegations.
+        tokens: TokenUsage,
+    },
+
+    /// User feedback on an agent response.
+    FeedbackReceived {
+        /// Hex ID of the `AgentResponse` event (or platform message ID) rated.
+        response_id: Option<String>,
+        /// Rating.
+        rating: FeedbackRating,
+        /// Optional free-text comment.
+        comment: Option<String>,
+        /// How the feedback was given (e.g., "reaction", "command", "api").
+        source: String,
+    },
+
+    /// Session was assigned to an experiment variant.
+    ExperimentAssigned {
+        /// Experiment ID.
+        experiment: String,
+        /// Variant name.
+        variant: String,
+    },
+
+    /// A reminder was scheduled for later delivery to this session's peer.
+    ReminderScheduled {
+        /// Reminder ID.
+        reminder_id: String,
+        /// Text to send when the reminder fires.
+        message: String,
+        /// When the reminder is due.
+        due_at: DateTime<Utc>,
+    },
+
+    /// A reminder fired.
+    ReminderFired {
+        /// Reminder ID.
+        reminder_id: String,
+        /// Platform message ID, if delivered.
+        message_id: Option<String>,
+        /// Delivery error, if it could not be sent.
+        error: Option<String>,
+    },
+
+    /// An inbound attachment was rejected by the attachment policy.
+    AttachmentRejected {
+        /// Attachment metadata.
+        attachment: AttachmentMeta,
+        /// File name, if known.
+        filename: Option<String>,
+        /// Why it was rejected (`too_large`, `mime_mismatch`, `infected`,
+        /// or `unscanned`).
+        reason: String,
+        /// Human-readable detail.
+        detail: String,
+    },
+
+    /// Content moderation caught an inbound message or a reply.
+    ContentModerated {
+        /// `inbound` or `outbound`.
+        stage: String,
+        /// Moderator that caught it.
+        moderator: String,
+        /// Why it was caught.
+        reason: String,
+        /// Policy categories it falls under.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        categories: Vec<String>,
+        /// Whether it was held back rather than let through.
+        blocked: bool,
+    },
+
+    /// A broadcast began sending one message to many chats.
+    BroadcastStarted {
+        /// Broadcast ID.
+        broadcast_id: String,
+        /// Message text.
+        content: String,
+        /// Chat IDs to deliver to, in send order.
+        targets: Vec<String>,
+        /// Who started the broadcast.
+        sent_by: String,
+    },
+
+    /// A broadcast message was delivered to one chat, or given up on.
+    BroadcastDelivered {
+        /// Chat ID.
+        chat_id: String,
+        /// Platform message ID, if delivered.
+        message_id: Option<String>,
+        /// Delivery error, if it could not be sent.
+        error: Option<String>,
+        /// Send attempts made.
+        attempts: u32,
+    },
+
+    /// A broadcast finished.
+    BroadcastCompleted {
+        /// Chats delivered to.
+        delivered: usize,
+        /// Chats that could not be delivered to.
+        failed: usize,
+    },
+
+    /// Everything stored about a peer was erased on request. Recorded in
+    /// the privacy audit log, which names the peer only by hash.
+    PeerForgotten {
+        /// Channel ID.
+        channel: String,
+        /// Content hash of `channel:peer_id`.
+        peer_hash: String,
+        /// Sessions removed.
+        sessions: usize,
+        /// Events removed.
+        events: usize,
+        /// Whether a peer profile was deleted.
+        profile: bool,
+        /// Uploaded files deleted.
+        uploads: usize,
+        /// Who asked for the erasure.
+        requested_by: String,
+    },
+
+    /// Session was soft-deleted; its data is kept until purged.
+    SessionDeleted {
+        /// Why the session was deleted.
+        reason: Option<String>,
+    },
+
+    /// A soft-deleted session was restored.
+    SessionRestored,
+
+    /// Dry-run mode was turned on or off for the session.
+    DryRunChanged {
+        /// New mode; `None` runs tools normally.
+        mode: Option<DryRunMode>,
+    },
+
+    /// The session was forked from another; the events before this one
+    /// were copied from it.
+    SessionForked {
+        /// Session the events were copied from.
+        from: SessionKey,
+        /// Storage ID of the last copied event in the source session.
+        at: Ulid,
+    },
+}
+
+/// How an agent handles tool calls when it must not act.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DryRunMode {
+    /// Answer each tool call with a synthetic "would have executed" result
+    /// and let the model carry on.
+    Simulate,
+    /// Stop at the first tool calls and return them as a plan.
+    Plan,
+}
+
+/// Thumbs up/down rating of an agent response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedbackRating {
+    /// Thumbs up.
+    Positive,
+    /// Thumbs down.
+    Negative,
+}
+
+/// Feedback counts for a session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedbackTally {
+    /// Positive ratings.
+    pub positive: u64,
+    /// Negative ratings.
+    pub negative: u64,
+}
+
+impl FeedbackTally {
+    /// Record a rating.
+    pub const fn record(&mut self, rating: FeedbackRating) {
+        match rating {
+            FeedbackRating::Positive => self.positive += 1,
+            FeedbackRating::Negative => self.negative += 1,
+        }
+    }
 }
 
 /// Attachment metadata for events.
@@ -171,6 +510,16 @@ pub struct AttachmentMeta {
     pub size: Option<u64>,
 }
 
+impl From<&Attachment> for AttachmentMeta {
+    fn from(attachment: &Attachment) -> Self {
+        Self {
+            kind: attachment.kind.as_str().to_string(),
+            mime_type: attachment.mime_type.clone(),
+            size: attachment.size,
+        }
+    }
+}
+
 /// Session state for projection.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -221,9 +570,31 @@ pub struct SessionProjection {
     pub last_activity: DateTime<Utc>,
     /// Message history.
     pub messages: Vec<SessionMessage>,
+    /// Index in `messages` where the current conversation starts. Earlier
+    /// messages are from before the session last ended.
+    #[serde(default)]
+    pub context_start: usize,
     /// Custom state key-value pairs.
     #[serde(default)]
     pub custom_state: std::collections::HashMap<String, serde_json::Value>,
+    /// Experiment this session belongs to, if any.
+    #[serde(default)]
+    pub experiment: Option<String>,
+    /// Experiment variant assigned to this session.
+    #[serde(default)]
+    pub variant: Option<String>,
+    /// User feedback on agent responses.
+    #[serde(default)]
+    pub feedback: FeedbackTally,
+    /// When the session was soft-deleted, if it is deleted.
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Dry-run mode, if tool calls are simulated for this session.
+    #[serde(default)]
+    pub dry_run: Option<DryRunMode>,
+    /// Session this one was forked from, if any.
+    #[serde(default)]
+    pub forked_from: Option<SessionKey>,
     /// Last event ID applied.
     pub last_event_id: Option<EventId>,
 }
@@ -246,18 +617,50 @@ impl SessionProjection {
             message_count: 0,
             last_activity: Utc::now(),
             messages: Vec::new(),
+            context_start: 0,
             custom_state: std::collections::HashMap::new(),
+            experiment: None,
+            variant: None,
+            feedback: FeedbackTally::default(),
+            deleted_at: None,
+            dry_run: None,
+            forked_from: None,
             last_event_id: None,
         }
     }
 
+    /// Whether the session is soft-deleted.
+    #[must_use]
+    pub const fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// Messages in the current conversation, i.e. since the session was
+    /// last reopened.
+    #[must_use]
+    pub fn context(&self) -> &[SessionMessage] {
+        self.messages
+            .get(self.context_start..)
+            .unwrap_or(&self.messages)
+    }
+
+    /// Start a fresh conversation if the session has ended.
+    fn reopen(&mut self) {
+        if self.state == SessionState::Ended {
+            self.state = SessionState::Active;
+            self.context_start = self.messages.len();
+        }
+    }
+
     /// Apply an event to update the projection.
     pub fn apply(&mut self, event: &SessionEvent) {
         match &event.kind {
             SessionEventKind::SessionStarted { .. } => {
+                self.reopen();
                 self.state = SessionState::Active;
             }
             SessionEventKind::MessageReceived { content, .. } => {
+                self.reopen();
                 self.messages.push(SessionMessage::Inbound(content.clone()));
                 self.message_count += 1;
             }
@@ -278,6 +681,11 @@ impl SessionProjection {
                     result: result_str,
                 });
             }
+            SessionEventKind::ToolDenied {
+                tool_name, reason, ..
+            } => {
+                tracing::debug!(tool = %tool_name, reason = %reason, "Tool denied");
+            }
             SessionEventKind::AgentResponse { content, .. } => {
                 self.messages
                     .push(SessionMessage::Outbound(content.clone()));
@@ -286,7 +694,84 @@ impl SessionProjection {
                 self.state = SessionState::Ended;
             }
             SessionEventKind::StateChanged { key, value } => {
-                self.custom_state.insert(key.clone(), value.clone());
+                if value.is_null() {
+                    self.custom_state.remove(key);
+                } else {
+                    self.custom_state.insert(key.clone(), value.clone());
+                }
+            }
+            SessionEventKind::DelegatedUsage {
+                agent_id,
+                child_session,
+                ..
+            } => {
+                tracing::debug!(agent = %agent_id, child = %child_session, "Delegated usage");
+            }
+            SessionEventKind::FeedbackReceived { rating, .. } => {
+                self.feedback.record(*rating);
+            }
+            SessionEventKind::ExperimentAssigned {
+                experiment,
+                variant,
+            } => {
+                self.experiment = Some(experiment.clone());
+                self.variant = Some(variant.clone());
+            }
+            SessionEventKind::ReminderScheduled {
+                reminder_id,
+                due_at,
+                ..
+            } => {
+                tracing::debug!(reminder = %reminder_id, due = %due_at, "Reminder scheduled");
+            }
+            SessionEventKind::ReminderFired {
+                reminder_id,
+                message_id,
+                ..
+            } => {
+                if message_id.is_some() {
+                    tracing::debug!(reminder = %reminder_id, "Reminder delivered");
+                }
+            }
+            SessionEventKind::AttachmentRejected { reason, detail, .. } => {
+                tracing::debug!(reason = %reason, detail = %detail, "Attachment rejected");
+            }
+            SessionEventKind::ContentModerated {
+                stage,
+                reason,
+                blocked,
+                ..
+            } => {
+                tracing::debug!(stage = %stage, reason = %reason, blocked, "Content moderated");
+            }
+            SessionEventKind::BroadcastStarted { content, .. } => {
+                self.messages
+                    .push(SessionMessage::Outbound(content.clone()));
+            }
+            SessionEventKind::BroadcastDelivered { chat_id, error, .. } => {
+                if let Some(error) = error {
+                    tracing::debug!(chat = %chat_id, error = %error, "Broadcast delivery failed");
+                }
+            }
+            SessionEventKind::BroadcastCompleted { .. } => {
+                self.state = SessionState::Ended;
+            }
+            SessionEventKind::PeerForgotten {
+                channel, sessions, ..
+            } => {
+                tracing::debug!(channel = %channel, sessions, "Peer forgotten");
+            }
+            SessionEventKind::SessionDeleted { .. } => {
+                self.deleted_at = Some(event.timestamp);
+            }
+            SessionEventKind::SessionRestored => {
+                self.deleted_at = None;
+            }
+            SessionEventKind::DryRunChanged { mode } => {
+                self.dry_run = *mode;
+            }
+            SessionEventKind::SessionForked { from, .. } => {
+                self.forked_from = Some(from.clone());
             }
         }
 
@@ -301,6 +786,7 @@ impl SessionProjection {
         // Last-write-wins for activity timestamp
         if other.last_activity > self.last_activity {
             self.state = other.state;
+            self.dry_run = other.dry_run;
             self.last_activity = other.last_activity;
             self.last_event_id = other.last_event_id.clone();
         }
@@ -311,57 +797,333 @@ impl SessionProjection {
             self.messages = other.messages.clone();
             self.message_count = other.message_count;
         }
+        self.context_start = self.context_start.max(other.context_start);
 
         // Merge custom state (last-write-wins per key)
         for (key, value) in &other.custom_state {
             self.custom_state.insert(key.clone(), value.clone());
         }
+
+        // Feedback only grows, so keep the larger tally
+        if other.feedback.positive + other.feedback.negative
+            > self.feedback.positive + self.feedback.negative
+        {
+            self.feedback = other.feedback;
+        }
+
+        // Variant assignment is sticky once set
+        if self.variant.is_none() {
+            self.experiment.clone_from(&other.experiment);
+            self.variant.clone_from(&other.variant);
+        }
+        if self.forked_from.is_none() {
+            self.forked_from.clone_from(&other.forked_from);
+        }
     }
 }
 
-/// Event store backed by sled.
+/// Event store size counters.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StoreStats {
+    /// Number of sessions.
+    pub sessions: usize,
+    /// Number of stored events.
+    pub events: usize,
+    /// Approximate size on disk in bytes.
+    pub size_bytes: u64,
+    /// Number of sessions in cold storage.
+    #[serde(default)]
+    pub archived: usize,
+}
+
+/// A reminder that has not fired yet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingReminder {
+    /// Reminder ID.
+    pub reminder_id: String,
+    /// Session the reminder belongs to.
+    pub session_key: SessionKey,
+    /// Agent that scheduled it.
+    pub agent_id: String,
+    /// Text to send.
+    pub message: String,
+    /// When the reminder is due.
+    pub due_at: DateTime<Utc>,
+}
+
+/// Marker recording that event keys use ULIDs rather than content hashes.
+const KEY_FORMAT_MARKER: &[u8] = b"events_key_format";
+const KEY_FORMAT_ULID: &[u8] = b"ulid";
+
+/// Appended events buffered per subscriber before a slow subscriber
+/// starts missing the oldest.
+const SUBSCRIBER_BUFFER: usize = 256;
+
+/// Event store over a [`StorageBackend`] (sled by default).
 pub struct EventStore {
-    db: sled::Db,
-    events_tree: sled::Tree,
-    sessions_tree: sled::Tree,
+    backend: Box<dyn StorageBackend>,
+    meta: Tree,
+    events_tree: Tree,
+    sessions_tree: Tree,
+    reminders_tree: Tree,
+    hashes_tree: Tree,
+    archive_tree: Tree,
+    agent_stats_tree: Tree,
+    channel_stats_tree: Tree,
+    error_stats_tree: Tree,
+    archive_dir: Option<PathBuf>,
+    archive_blobs: Option<archive::ArchiveBlobs>,
+    key: Option<EncryptionKey>,
+    id_generator: Arc<dyn IdGenerator>,
+    upcasters: Arc<UpcasterRegistry>,
+    format: EventStoreFormat,
+    appended: tokio::sync::broadcast::Sender<SessionEvent>,
 }
 
 impl EventStore {
     /// Open or create an event store.
     ///
+    /// Stores written before events were keyed by ULID are migrated in place.
+    ///
     /// # Errors
     ///
-    /// Returns error if database cannot be opened.
+    /// Returns error if database cannot be opened or migrated.
     pub fn open(path: &Path) -> Result<Self, EventStoreError> {
-        let db = sled::open(path)?;
-        let events_tree = db.open_tree("events")?;
-        let sessions_tree = db.open_tree("sessions")?;
-
-        Ok(Self {
-            db,
-            events_tree,
-            sessions_tree,
-        })
+        Self::open_with_key(path, None)
+    }
+
+    /// Open or create an event store with the configured backend: a sled
+    /// directory at `path`, or a `SQLite` file at
+    /// [`EventStore::backend_path`]. Values are encrypted with `key` if set
+    /// (see [`EventStore::open_encrypted`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the store cannot be opened, or `SQLite` is selected
+    /// in a build without the `sqlite` feature.
+    pub fn open_with(
+        path: &Path,
+        backend: EventStoreBackend,
+        key: Option<EncryptionKey>,
+    ) -> Result<Self, EventStoreError> {
+        Self::with_storage(open_backend(path, backend)?, key)
+    }
+
+    /// Where the store for `path` lives with `backend`.
+    #[must_use]
+    pub fn backend_path(path: &Path, backend: EventStoreBackend) -> PathBuf {
+        match backend {
+            EventStoreBackend::Sled => path.to_path_buf(),
+            EventStoreBackend::Sqlite => path.with_extension("db"),
+        }
+    }
+
+    /// Copy the sled store at `path` into a new `SQLite` file at
+    /// [`EventStore::backend_path`], returning the number of entries
+    /// copied. The sled store is left in place as a backup.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the `SQLite` file already exists, either store cannot
+    /// be opened, or the build lacks the `sqlite` feature.
+    pub fn migrate_to_sqlite(path: &Path) -> Result<usize, EventStoreError> {
+        #[cfg(feature = "sqlite")]
+        {
+            let target = Self::backend_path(path, EventStoreBackend::Sqlite);
+            if target.exists() {
+                return Err(EventStoreError::Backend(format!(
+                    "{} already exists",
+                    target.display()
+                )));
+            }
+            let source = SledBackend::open(path)?;
+            let copied = SqliteBackend::open(&target)
+                .and_then(|sqlite| backend::copy_storage(&source, &sqlite));
+            if copied.is_err() {
+                for suffix in ["", "-wal", "-shm"] {
+                    let mut file = target.clone().into_os_string();
+                    file.push(suffix);
+                    let _ = std::fs::remove_file(file);
+                }
+            }
+            copied
+        }
+        #[cfg(not(feature = "sqlite"))]
+        {
+            let _ = path;
+            Err(sqlite_unsupported())
+        }
+    }
+
+    fn open_with_key(path: &Path, key: Option<EncryptionKey>) -> Result<Self, EventStoreError> {
+        Self::with_storage(Box::new(SledBackend::open(path)?), key)
+    }
+
+    /// Open an event store over a custom storage backend.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend's trees cannot be opened, or the store
+    /// cannot be validated or migrated.
+    pub fn with_storage(
+        backend: Box<dyn StorageBackend>,
+        key: Option<EncryptionKey>,
+    ) -> Result<Self, EventStoreError> {
+        let tree = |name: &str| backend.open_tree(name).map(Tree::new);
+        let [
+            events,
+            sessions,
+            reminders,
+            hashes,
+            archive,
+            agent_stats,
+            channel_stats,
+            error_stats,
+        ] = backend::TREES;
+
+        let store = Self {
+            meta: Tree::new(backend.metadata()?),
+            events_tree: tree(events)?,
+            sessions_tree: tree(sessions)?,
+            reminders_tree: tree(reminders)?,
+            hashes_tree: tree(hashes)?,
+            archive_tree: tree(archive)?,
+            agent_stats_tree: tree(agent_stats)?,
+            channel_stats_tree: tree(channel_stats)?,
+            error_stats_tree: tree(error_stats)?,
+            backend,
+            archive_dir: None,
+            archive_blobs: None,
+            key,
+            id_generator: Arc::new(MonotonicUlidGenerator::new()),
+            upcasters: Arc::new(UpcasterRegistry::new()),
+            format: EventStoreFormat::default(),
+            appended: tokio::sync::broadcast::channel(SUBSCRIBER_BUFFER).0,
+        };
+        store.check_encryption()?;
+        store.check_schema()?;
+        store.migrate_keys()?;
+        store.init_stats()?;
+        Ok(store)
+    }
+
+    /// Use a custom ID generator (e.g. deterministic IDs in tests).
+    #[must_use]
+    pub fn with_id_generator(mut self, generator: Arc<dyn IdGenerator>) -> Self {
+        self.id_generator = generator;
+        self
     }
 
     /// Append an event to a session's event log.
     ///
+    /// The store assigns the event's ULID unless one is already set (e.g.
+    /// when importing). Appending an event whose content hash is already
+    /// stored for the session is a no-op. Appending to an archived session
+    /// rehydrates it first.
+    ///
     /// # Errors
     ///
     /// Returns error if storage fails.
     pub fn append(&self, event: &SessionEvent) -> Result<EventId, EventStoreError> {
-        let event_key = format!("{}:{}", event.session_key, event.id.to_hex());
-        let event_data = serde_json::to_vec(event)?;
+        self.rehydrate_if_archived(&event.session_key)?;
+        self.insert_event(event, true)
+    }
 
-        self.events_tree.insert(event_key.as_bytes(), event_data)?;
+    /// Store an event, updating the projection and indexes. Activity
+    /// counters are left alone unless `count` is set, e.g. when restoring
+    /// events that were counted before.
+    fn insert_event(&self, event: &SessionEvent, count: bool) -> Result<EventId, EventStoreError> {
+        let hash_key = format!("{}:{}", event.session_key, event.id.to_hex());
+        if self.hashes_tree.contains_key(hash_key.as_bytes())? {
+            return Ok(event.id.clone());
+        }
+
+        let mut event = event.clone();
+        if event.ulid.is_nil() {
+            event.ulid = self.id_generator.generate(event.timestamp);
+        }
+
+        let event_key = format!("{}:{}", event.session_key, event.ulid);
+        let event_data = self.encode_event(&event)?;
+
+        self.events_tree.insert(event_key, event_data)?;
+        self.hashes_tree
+            .insert(hash_key.as_bytes(), event.ulid.to_string().as_bytes())?;
 
         // Update session projection
-        self.update_projection(event)?;
+        let projection = self.update_projection(&event)?;
+
+        // Update activity counters
+        if count {
+            self.record_stats(&event, projection.channel.as_ref())?;
+        }
+
+        // Keep the pending reminder index in sync
+        self.update_reminders(&event)?;
+
+        // No receivers is not an error
+        let id = event.id.clone();
+        let _ = self.appended.send(event);
+        Ok(id)
+    }
+
+    /// Subscribe to events as they are appended through this store.
+    ///
+    /// Events written by other processes are not delivered. A subscriber
+    /// that falls behind by more than the buffer receives
+    /// [`RecvError::Lagged`](tokio::sync::broadcast::error::RecvError::Lagged)
+    /// and skips the oldest events.
+    #[must_use]
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<SessionEvent> {
+        self.appended.subscribe()
+    }
+
+    /// Re-key events stored under their content hash to ULID keys.
+    fn migrate_keys(&self) -> Result<(), EventStoreError> {
+        if self.meta.get(KEY_FORMAT_MARKER)?.as_deref() == Some(KEY_FORMAT_ULID) {
+            return Ok(());
+        }
+
+        let mut legacy = Vec::new();
+        for result in &self.events_tree {
+            let (key, value) = result?;
+            let is_hash_key = std::str::from_utf8(&key)
+                .ok()
+                .and_then(|k| k.rsplit_once(':'))
+                .is_some_and(|(_, id)| id.len() == 64 && id.bytes().all(|b| b.is_ascii_hexdigit()));
+            if is_hash_key {
+                let event = self.decode_event(&value)?;
+                legacy.push((key, event));
+            }
+        }
+
+        if !legacy.is_empty() {
+            tracing::info!("Migrating {} event(s) to ULID keys", legacy.len());
+        }
 
-        Ok(event.id.clone())
+        // Generate in timestamp order so ULID order matches history
+        legacy.sort_by_key(|(_, e)| e.timestamp);
+        let mut generator = ulid::Generator::new();
+        for (old_key, mut event) in legacy {
+            event.ulid = generator
+                .generate_from_datetime(event.timestamp.into())
+                .unwrap_or_else(|_| Ulid::from_datetime(event.timestamp.into()));
+
+            let hash_key = format!("{}:{}", event.session_key, event.id.to_hex());
+            let new_key = format!("{}:{}", event.session_key, event.ulid);
+            self.events_tree
+                .insert(new_key.as_bytes(), self.encode_event(&event)?)?;
+            self.hashes_tree
+                .insert(hash_key.as_bytes(), event.ulid.to_string().as_bytes())?;
+            self.events_tree.remove(old_key)?;
+        }
+
+        self.meta.insert(KEY_FORMAT_MARKER, KEY_FORMAT_ULID)?;
+        self.backend.flush()?;
+        Ok(())
     }
 
-    /// Get all events for a session.
+    /// Get all events for a session, rehydrating it if archived.
     ///
     /// # Errors
     ///
@@ -370,17 +1132,22 @@ impl EventStore {
         &self,
         session_key: &SessionKey,
     ) -> Result<Vec<SessionEvent>, EventStoreError> {
+        self.rehydrate_if_archived(session_key)?;
+
         let prefix = format!("{session_key}:");
         let mut events = Vec::new();
 
         for result in self.events_tree.scan_prefix(prefix.as_bytes()) {
-            let (_, value) = result?;
-            let event: SessionEvent = serde_json::from_slice(&value)?;
+            let (key, value) = result?;
+            if !is_own_key(&key, prefix.len()) {
+                continue;
+            }
+            let event = self.decode_event(&value)?;
             events.push(event);
         }
 
-        // Sort by timestamp
-        events.sort_by_key(|e| e.timestamp);
+        // Keys are ULIDs, but sort explicitly in case of prefix overlap
+        events.sort_by_key(|e| e.ulid);
         Ok(events)
     }
 
@@ -398,7 +1165,7 @@ impl EventStore {
         Ok(events.into_iter().filter(|e| e.timestamp > since).collect())
     }
 
-    /// Get the session projection.
+    /// Get the session projection, rehydrating the session if archived.
     ///
     /// # Errors
     ///
@@ -410,12 +1177,14 @@ impl EventStore {
         let key = session_key.as_ref().as_bytes();
 
         match self.sessions_tree.get(key)? {
-            Some(data) => Ok(serde_json::from_slice(&data)?),
+            Some(data) => self.decode(&data),
+            None if self.rehydrate(session_key)? => self.get_projection(session_key),
             None => Err(EventStoreError::NotFound(session_key.to_string())),
         }
     }
 
-    /// List all session keys.
+    /// List all session keys in active storage (see also
+    /// [`EventStore::list_archived`]).
     ///
     /// # Errors
     ///
@@ -433,12 +1202,155 @@ impl EventStore {
         Ok(sessions)
     }
 
+    /// List reminders that have not fired, soonest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage read fails.
+    pub fn pending_reminders(&self) -> Result<Vec<PendingReminder>, EventStoreError> {
+        let mut reminders = Vec::new();
+
+        for result in &self.reminders_tree {
+            let (_, value) = result?;
+            reminders.push(self.decode::<PendingReminder>(&value)?);
+        }
+
+        reminders.sort_by_key(|r| r.due_at);
+        Ok(reminders)
+    }
+
+    /// List reminders due at or before `now`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage read fails.
+    pub fn due_reminders(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<PendingReminder>, EventStoreError> {
+        Ok(self
+            .pending_reminders()?
+            .into_iter()
+            .filter(|r| r.due_at <= now)
+            .collect())
+    }
+
+    /// Permanently remove a session: events, projection, and reminders.
+    ///
+    /// Returns the number of events removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage fails.
+    pub fn purge_session(&self, session_key: &SessionKey) -> Result<usize, EventStoreError> {
+        let prefix = format!("{session_key}:");
+
+        let mut removed = 0;
+        for result in self.events_tree.scan_prefix(prefix.as_bytes()) {
+            let (key, _) = result?;
+            if is_own_key(&key, prefix.len()) {
+                self.events_tree.remove(key)?;
+                removed += 1;
+            }
+        }
+        for result in self.hashes_tree.scan_prefix(prefix.as_bytes()) {
+            let (key, _) = result?;
+            if is_own_key(&key, prefix.len()) {
+                self.hashes_tree.remove(key)?;
+            }
+        }
+        for reminder in self.pending_reminders()? {
+            if reminder.session_key == *session_key {
+                self.reminders_tree
+                    .remove(reminder.reminder_id.as_bytes())?;
+            }
+        }
+        self.sessions_tree.remove(session_key.as_ref().as_bytes())?;
+
+        Ok(removed)
+    }
+
+    /// Purge sessions soft-deleted before `cutoff`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage fails.
+    pub fn purge_deleted_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<SessionKey>, EventStoreError> {
+        let mut purged = Vec::new();
+
+        for key in self.list_sessions()? {
+            let projection = self.get_projection(&key)?;
+            if projection.deleted_at.is_some_and(|at| at < cutoff) {
+                self.purge_session(&key)?;
+                purged.push(key);
+            }
+        }
+
+        Ok(purged)
+    }
+
+    /// Active, non-deleted sessions with no activity since `cutoff`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage read fails.
+    pub fn idle_sessions(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<SessionProjection>, EventStoreError> {
+        let mut idle = Vec::new();
+
+        for key in self.list_sessions()? {
+            let projection = self.get_projection(&key)?;
+            if projection.state == SessionState::Active
+                && !projection.is_deleted()
+                && projection.last_activity < cutoff
+            {
+                idle.push(projection);
+            }
+        }
+
+        Ok(idle)
+    }
+
+    /// Index reminder events so pending reminders survive restarts.
+    fn update_reminders(&self, event: &SessionEvent) -> Result<(), EventStoreError> {
+        match &event.kind {
+            SessionEventKind::ReminderScheduled {
+                reminder_id,
+                message,
+                due_at,
+            } => {
+                let pending = PendingReminder {
+                    reminder_id: reminder_id.clone(),
+                    session_key: event.session_key.clone(),
+                    agent_id: event.agent_id.clone(),
+                    message: message.clone(),
+                    due_at: *due_at,
+                };
+                self.reminders_tree
+                    .insert(reminder_id.as_bytes(), self.encode(&pending)?)?;
+            }
+            SessionEventKind::ReminderFired { reminder_id, .. } => {
+                self.reminders_tree.remove(reminder_id.as_bytes())?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     /// Update the session projection after appending an event.
-    fn update_projection(&self, event: &SessionEvent) -> Result<(), EventStoreError> {
+    fn update_projection(
+        &self,
+        event: &SessionEvent,
+    ) -> Result<SessionProjection, EventStoreError> {
         let key = event.session_key.as_ref().as_bytes();
 
         let mut projection = match self.sessions_tree.get(key)? {
-            Some(data) => serde_json::from_slice(&data)?,
+            Some(data) => self.decode(&data)?,
             None => {
                 // Create new projection from SessionStarted event
                 if let SessionEventKind::SessionStarted { channel, peer_id } = &event.kind {
@@ -462,10 +1374,24 @@ impl EventStore {
 
         projection.apply(event);
 
-        let projection_data = serde_json::to_vec(&projection)?;
+        let projection_data = self.encode(&projection)?;
         self.sessions_tree.insert(key, projection_data)?;
 
-        Ok(())
+        Ok(projection)
+    }
+
+    /// Count sessions and events and measure the store's size on disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage read fails.
+    pub fn stats(&self) -> Result<StoreStats, EventStoreError> {
+        Ok(StoreStats {
+            sessions: self.sessions_tree.len()?,
+            events: self.events_tree.len()?,
+            size_bytes: self.backend.size_on_disk()?,
+            archived: self.archive_tree.len()?,
+        })
     }
 
     /// Flush all pending writes to disk.
@@ -474,17 +1400,257 @@ impl EventStore {
     ///
     /// Returns error if flush fails.
     pub fn flush(&self) -> Result<(), EventStoreError> {
-        self.db.flush()?;
+        self.backend.flush()
+    }
+
+    /// Write, flush, and remove a marker key to check the store accepts
+    /// writes.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the write or flush fails.
+    pub fn check_writable(&self) -> Result<(), EventStoreError> {
+        const MARKER: &[u8] = b"__health_check";
+        self.meta
+            .insert(MARKER, Utc::now().timestamp_millis().to_be_bytes())?;
+        self.backend.flush()?;
+        self.meta.remove(MARKER)?;
         Ok(())
     }
 }
 
+/// Open the storage for `backend` at [`EventStore::backend_path`].
+fn open_backend(
+    path: &Path,
+    backend: EventStoreBackend,
+) -> Result<Box<dyn StorageBackend>, EventStoreError> {
+    match backend {
+        EventStoreBackend::Sled => Ok(Box::new(SledBackend::open(path)?)),
+        #[cfg(feature = "sqlite")]
+        EventStoreBackend::Sqlite => Ok(Box::new(SqliteBackend::open(&EventStore::backend_path(
+            path, backend,
+        ))?)),
+        #[cfg(not(feature = "sqlite"))]
+        EventStoreBackend::Sqlite => Err(sqlite_unsupported()),
+    }
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn sqlite_unsupported() -> EventStoreError {
+    EventStoreError::Backend("built without SQLite support (the `sqlite` feature)".to_string())
+}
+
+/// Whether a `<session>:<id>` key belongs to the session whose prefix is
+/// `prefix_len` bytes long (`a:` is also a prefix of `a:b:<id>`).
+fn is_own_key(key: &[u8], prefix_len: usize) -> bool {
+    !key[prefix_len..].contains(&b':')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::types::AgentId;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_reminder_index() {
+        let temp = tempdir().unwrap();
+        let session_key = SessionKey::new("reminders");
+        let now = Utc::now();
+
+        {
+            let store = EventStore::open(temp.path()).unwrap();
+            for (id, offset) in [("r1", -60), ("r2", 3600)] {
+                store
+                    .append(&SessionEvent::new(
+                        session_key.clone(),
+                        "default".to_string(),
+                        SessionEventKind::ReminderScheduled {
+                            reminder_id: id.to_string(),
+                            message: format!("reminder {id}"),
+                            due_at: now + chrono::Duration::seconds(offset),
+                        },
+                    ))
+                    .unwrap();
+            }
+            store.flush().unwrap();
+        }
+
+        // Pending reminders survive reopening the store
+        let store = EventStore::open(temp.path()).unwrap();
+        assert_eq!(store.pending_reminders().unwrap().len(), 2);
+        let due = store.due_reminders(now).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].reminder_id, "r1");
+        assert_eq!(due[0].session_key, session_key);
+
+        store
+            .append(&SessionEvent::new(
+                session_key,
+                "default".to_string(),
+                SessionEventKind::ReminderFired {
+                    reminder_id: "r1".to_string(),
+                    message_id: Some("m1".to_string()),
+                    error: None,
+                },
+            ))
+            .unwrap();
+        assert!(store.due_reminders(now).unwrap().is_empty());
+        assert_eq!(store.pending_reminders().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_ulid_keys_preserve_order() {
+        let temp = tempdir().unwrap();
+        let store = EventStore::open(temp.path()).unwrap();
+        let session_key = SessionKey::new("ordered");
+
+        // Identical timestamps: ULIDs must still keep append order
+        let timestamp = Utc::now();
+        for n in 0..20 {
+            let mut event = SessionEvent::new(
+                session_key.clone(),
+                "default".to_string(),
+                SessionEventKind::MessageReceived {
+                    content: format!("message {n}"),
+                    attachments: vec![],
+                    sender_id: None,
+                },
+            );
+            event.timestamp = timestamp;
+            store.append(&event).unwrap();
+        }
+
+        let events = store.get_events(&session_key).unwrap();
+        assert_eq!(events.len(), 20);
+        for (n, event) in events.iter().enumerate() {
+            assert!(!event.ulid.is_nil());
+            assert!(matches!(
+                &event.kind,
+                SessionEventKind::MessageReceived { content, .. } if *content == format!("message {n}")
+            ));
+        }
+
+        // Re-appending the same event is deduplicated by content hash
+        let duplicate = events[0].clone();
+        store.append(&duplicate).unwrap();
+        assert_eq!(store.get_events(&session_key).unwrap().len(), 20);
+    }
+
+    #[test]
+    fn test_legacy_key_migration() {
+        let temp = tempdir().unwrap();
+        let session_key = SessionKey::new("legacy");
+
+        {
+            let store = EventStore::open(temp.path()).unwrap();
+            for (n, offset) in [(1, 10), (0, 0)] {
+                let mut event = SessionEvent::new(
+                    session_key.clone(),
+                    "default".to_string(),
+                    SessionEventKind::MessageReceived {
+                        content: format!("old {n}"),
+                        attachments: vec![],
+                        sender_id: None,
+                    },
+                );
+                event.timestamp += chrono::Duration::seconds(offset);
+                let mut legacy = serde_json::to_value(&event).unwrap();
+                legacy.as_object_mut().unwrap().remove("ulid");
+                store
+                    .events_tree
+                    .insert(
+                        format!("{session_key}:{}", event.id.to_hex()).as_bytes(),
+                        serde_json::to_vec(&legacy).unwrap(),
+                    )
+                    .unwrap();
+            }
+            store.meta.remove(KEY_FORMAT_MARKER).unwrap();
+            store.flush().unwrap();
+        }
+
+        let store = EventStore::open(temp.path()).unwrap();
+        let events = store.get_events(&session_key).unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events[0].ulid < events[1].ulid);
+        assert!(matches!(
+            &events[0].kind,
+            SessionEventKind::MessageReceived { content, .. } if content == "old 0"
+        ));
+        assert!(
+            store
+                .events_tree
+                .iter()
+                .all(|entry| entry.unwrap().0.len() == "legacy:".len() + 26)
+        );
+    }
+
+    #[test]
+    fn test_soft_delete_restore_purge() {
+        let temp = tempdir().unwrap();
+        let store = EventStore::open(temp.path()).unwrap();
+        let key = SessionKey::new("chat");
+        let nested = SessionKey::new("chat:thread");
+
+        for session in [&key, &nested] {
+            store
+                .append(&SessionEvent::new(
+                    session.clone(),
+                    "default".to_string(),
+                    SessionEventKind::SessionStarted {
+                        channel: "telegram".to_string(),
+                        peer_id: "1".to_string(),
+                    },
+                ))
+                .unwrap();
+        }
+
+        store
+            .append(&SessionEvent::new(
+                key.clone(),
+                "default".to_string(),
+                SessionEventKind::SessionDeleted { reason: None },
+            ))
+            .unwrap();
+        assert!(store.get_projection(&key).unwrap().is_deleted());
+
+        store
+            .append(&SessionEvent::new(
+                key.clone(),
+                "default".to_string(),
+                SessionEventKind::SessionRestored,
+            ))
+            .unwrap();
+        assert!(!store.get_projection(&key).unwrap().is_deleted());
+
+        store
+            .append(&SessionEvent::new(
+                key.clone(),
+                "default".to_string(),
+                SessionEventKind::SessionDeleted {
+                    reason: Some("spam".to_string()),
+                },
+            ))
+            .unwrap();
+
+        // Still within the grace period
+        let purged = store
+            .purge_deleted_before(Utc::now() - chrono::Duration::days(1))
+            .unwrap();
+        assert!(purged.is_empty());
+
+        let purged = store
+            .purge_deleted_before(Utc::now() + chrono::Duration::seconds(1))
+            .unwrap();
+        assert_eq!(purged, vec![key.clone()]);
+        assert!(store.get_projection(&key).is_err());
+        assert!(store.get_events(&key).unwrap().is_empty());
+
+        // Sessions sharing the key prefix are untouched
+        assert_eq!(store.get_events(&nested).unwrap().len(), 1);
+        assert!(store.get_projection(&nested).is_ok());
+    }
+
     #[test]
     fn test_event_id_generation() {
         let id1 = EventId::from_content(b"test content");
@@ -504,6 +1670,7 @@ mod tests {
             SessionEventKind::MessageReceived {
                 content: "Hello".to_string(),
                 attachments: vec![],
+                sender_id: None,
             },
         );
 
@@ -526,6 +1693,7 @@ mod tests {
             SessionEventKind::MessageReceived {
                 content: "Hello".to_string(),
                 attachments: vec![],
+                sender_id: None,
             },
         );
 
@@ -535,6 +1703,124 @@ mod tests {
         assert_eq!(projection.messages.len(), 1);
     }
 
+    #[test]
+    fn test_projection_reopens_with_fresh_context() {
+        let key = SessionKey::new("test");
+        let mut projection = SessionProjection::new(
+            key.clone(),
+            "agent".to_string(),
+            ChannelId::telegram(),
+            "user123".to_string(),
+        );
+        let received = |content: &str| {
+            SessionEvent::new(
+                key.clone(),
+                "agent".to_string(),
+                SessionEventKind::MessageReceived {
+                    content: content.to_string(),
+                    attachments: vec![],
+                    sender_id: None,
+                },
+            )
+        };
+
+        projection.apply(&received("Hello"));
+        projection.apply(&SessionEvent::new(
+            key.clone(),
+            "agent".to_string(),
+            SessionEventKind::SessionEnded {
+                reason: "idle_timeout".to_string(),
+            },
+        ));
+        assert_eq!(projection.state, SessionState::Ended);
+        assert_eq!(projection.context().len(), 1);
+
+        projection.apply(&received("Back again"));
+        assert_eq!(projection.state, SessionState::Active);
+        assert_eq!(projection.messages.len(), 2);
+        assert!(matches!(
+            projection.context(),
+            [SessionMessage::Inbound(text)] if text == "Back again"
+        ));
+    }
+
+    #[test]
+    fn test_projection_experiment_assignment() {
+        let mut projection = SessionProjection::new(
+            SessionKey::new("test"),
+            "agent".to_string(),
+            ChannelId::telegram(),
+            "user123".to_string(),
+        );
+
+        projection.apply(&SessionEvent::new(
+            SessionKey::new("test"),
+            "agent".to_string(),
+            SessionEventKind::ExperimentAssigned {
+                experiment: "prompt-v2".to_string(),
+                variant: "b".to_string(),
+            },
+        ));
+
+        assert_eq!(projection.experiment.as_deref(), Some("prompt-v2"));
+        assert_eq!(projection.variant.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn test_projection_feedback() {
+        let mut projection = SessionProjection::new(
+            SessionKey::new("test"),
+            "agent".to_string(),
+            ChannelId::telegram(),
+            "user123".to_string(),
+        );
+
+        for rating in [
+            FeedbackRating::Positive,
+            FeedbackRating::Positive,
+            FeedbackRating::Negative,
+        ] {
+            projection.apply(&SessionEvent::new(
+                SessionKey::new("test"),
+                "agent".to_string(),
+                SessionEventKind::FeedbackReceived {
+                    response_id: None,
+                    rating,
+                    comment: None,
+                    source: "reaction".to_string(),
+                },
+            ));
+        }
+
+        assert_eq!(projection.feedback.positive, 2);
+        assert_eq!(projection.feedback.negative, 1);
+    }
+
+    #[test]
+    fn test_projection_dry_run() {
+        let mut projection = SessionProjection::new(
+            SessionKey::new("test"),
+            "agent".to_string(),
+            ChannelId::telegram(),
+            "user123".to_string(),
+        );
+
+        for mode in [Some(DryRunMode::Plan), None] {
+            projection.apply(&SessionEvent::new(
+                SessionKey::new("test"),
+                "agent".to_string(),
+                SessionEventKind::DryRunChanged { mode },
+            ));
+            assert_eq!(projection.dry_run, mode);
+        }
+
+        let json = serde_json::to_value(SessionEventKind::DryRunChanged {
+            mode: Some(DryRunMode::Simulate),
+        })
+        .unwrap();
+        assert_eq!(json["mode"], "simulate");
+    }
+
     #[test]
     fn test_event_store_roundtrip() {
         let temp = tempdir().unwrap();
@@ -546,6 +1832,7 @@ mod tests {
             "bot123",
             crate::types::PeerType::Dm,
             &crate::types::PeerId::new("user456"),
+            None,
         );
 
         // Start session
@@ -566,6 +1853,7 @@ mod tests {
             SessionEventKind::MessageReceived {
                 content: "Hello, agent!".to_string(),
                 attachments: vec![],
+                sender_id: None,
             },
         );
         store.append(&msg_event).unwrap();
@@ -578,5 +1866,42 @@ mod tests {
         let projection = store.get_projection(&session_key).unwrap();
         assert_eq!(projection.message_count, 1);
         assert_eq!(projection.state, SessionState::Active);
+
+        let stats = store.stats().unwrap();
+        assert_eq!(stats.sessions, 1);
+        assert_eq!(stats.events, 2);
+    }
+
+    #[test]
+    fn test_check_writable() {
+        let temp = tempdir().unwrap();
+        let store = EventStore::open(temp.path()).unwrap();
+        store.check_writable().unwrap();
+        assert_eq!(store.stats().unwrap().events, 0);
+    }
+
+    #[test]
+    fn test_subscribe() {
+        let temp = tempdir().unwrap();
+        let store = EventStore::open(temp.path()).unwrap();
+        let mut events = store.subscribe();
+
+        let event = SessionEvent::new(
+            SessionKey::new("agent:default:telegram:dm:1"),
+            "default".to_string(),
+            SessionEventKind::MessageReceived {
+                content: "Hello".to_string(),
+                attachments: vec![],
+                sender_id: None,
+            },
+        );
+        store.append(&event).unwrap();
+        // Duplicate appends are no-ops and not delivered
+        store.append(&event).unwrap();
+
+        let received = events.try_recv().unwrap();
+        assert_eq!(received.id, event.id);
+        assert!(!received.ulid.is_nil());
+        assert!(events.try_recv().is_err());
     }
 }
@@ -0,0 +1,315 @@
+//! Event schema versioning.
+//!
+//! Every event records the schema version it was written with. Events from
+//! older versions are migrated to the current schema when read, by a chain
+//! of upcasters that each rewrite the event JSON one version forward.
+//! [`EventStore::migrate`] rewrites stored events so reads no longer need
+//! to upcast them.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{EventStore, EventStoreError, SessionEvent};
+
+/// Schema version stamped on new events.
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// Marker recording the schema version of every event in the store.
+const SCHEMA_MARKER: &[u8] = b"events_schema_version";
+
+/// Rewrites event JSON from one schema version to the next.
+pub type Upcaster = fn(&mut Value) -> Result<(), String>;
+
+/// Upcasters by the schema version they migrate from.
+#[derive(Clone)]
+pub struct UpcasterRegistry {
+    upcasters: BTreeMap<u32, Upcaster>,
+}
+
+impl UpcasterRegistry {
+    /// Create a registry with the built-in upcasters.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            upcasters: BTreeMap::new(),
+        }
+        .with(0, upcast_v0)
+    }
+
+    /// Register (or replace) the upcaster from version `from` to `from + 1`.
+    #[must_use]
+    pub fn with(mut self, from: u32, upcaster: Upcaster) -> Self {
+        self.upcasters.insert(from, upcaster);
+        self
+    }
+
+    /// Migrate event JSON to [`EVENT_SCHEMA_VERSION`].
+    ///
+    /// Returns whether the event was changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Schema` if the event is newer than this build supports, an
+    /// upcaster is missing, or an upcaster fails.
+    pub fn upcast(&self, event: &mut Value) -> Result<bool, EventStoreError> {
+        let mut version = schema_version(event);
+        if version > EVENT_SCHEMA_VERSION {
+            return Err(EventStoreError::Schema(format!(
+                "event schema version {version} is newer than the supported version {EVENT_SCHEMA_VERSION}"
+            )));
+        }
+        if version == EVENT_SCHEMA_VERSION {
+            return Ok(false);
+        }
+
+        while version < EVENT_SCHEMA_VERSION {
+            let upcaster = self.upcasters.get(&version).ok_or_else(|| {
+                EventStoreError::Schema(format!("no upcaster from event schema version {version}"))
+            })?;
+            upcaster(event).map_err(|e| {
+                EventStoreError::Schema(format!(
+                    "failed to upcast event from schema version {version}: {e}"
+                ))
+            })?;
+            version += 1;
+        }
+        if let Some(object) = event.as_object_mut() {
+            object.insert("version".to_string(), Value::from(version));
+        }
+        Ok(true)
+    }
+}
+
+impl Default for UpcasterRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for UpcasterRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UpcasterRegistry")
+            .field("versions", &self.upcasters.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Schema version of event JSON; events written before versioning are 0.
+#[must_use]
+pub fn schema_version(event: &Value) -> u32 {
+    event
+        .get("version")
+        .and_then(Value::as_u64)
+        .and_then(|v| u32::try_from(v).ok())
+        .unwrap_or(0)
+}
+
+/// Events written before versioning already have the version 1 shape.
+#[allow(clippy::unnecessary_wraps)]
+const fn upcast_v0(_event: &mut Value) -> Result<(), String> {
+    Ok(())
+}
+
+/// Result of [`EventStore::migrate`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MigrationReport {
+    /// Schema version the store was at.
+    pub from_version: u32,
+    /// Events read.
+    pub events_checked: usize,
+    /// Events rewritten in the current schema.
+    pub events_migrated: usize,
+}
+
+impl EventStore {
+    /// Use a custom upcaster registry, e.g. one with upcasters for an
+    /// embedder's own event changes.
+    #[must_use]
+    pub fn with_upcasters(mut self, upcasters: UpcasterRegistry) -> Self {
+        self.upcasters = std::sync::Arc::new(upcasters);
+        self
+    }
+
+    /// Schema version of the oldest events in the store.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage read fails.
+    pub fn schema_version(&self) -> Result<u32, EventStoreError> {
+        Ok(self
+            .meta
+            .get(SCHEMA_MARKER)?
+            .and_then(|v| std::str::from_utf8(&v).ok()?.parse().ok())
+            .unwrap_or(0))
+    }
+
+    /// Rewrite every event in active storage in the current schema.
+    ///
+    /// Archived sessions are upcast when they are restored.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage fails or an event cannot be upcast.
+    pub fn migrate(&self) -> Result<MigrationReport, EventStoreError> {
+        let mut report = MigrationReport {
+            from_version: self.schema_version()?,
+            ..MigrationReport::default()
+        };
+
+        for result in &self.events_tree {
+            let (key, data) = result?;
+            report.events_checked += 1;
+            let mut value = self.event_value(&data)?;
+            if self.upcasters.upcast(&mut value)? {
+                let event: SessionEvent = serde_json::from_value(value)?;
+                self.events_tree.insert(key, self.encode_event(&event)?)?;
+                report.events_migrated += 1;
+            }
+        }
+
+        self.mark_schema_current()?;
+        self.flush()?;
+        Ok(report)
+    }
+
+    /// Parse one archived event line, upcasting it to the current schema.
+    pub(super) fn parse_event(&self, line: &str) -> Result<SessionEvent, EventStoreError> {
+        let mut value: Value = serde_json::from_str(line)?;
+        self.upcasters.upcast(&mut value)?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Refuse stores written by a newer schema, and mark new stores current.
+    pub(super) fn check_schema(&self) -> Result<(), EventStoreError> {
+        let version = self.schema_version()?;
+        if version > EVENT_SCHEMA_VERSION {
+            return Err(EventStoreError::Schema(format!(
+                "event store uses schema version {version}, newer than the supported version {EVENT_SCHEMA_VERSION}; upgrade openclaw"
+            )));
+        }
+        if version < EVENT_SCHEMA_VERSION {
+            if self.events_tree.is_empty()? {
+                self.mark_schema_current()?;
+            } else {
+                tracing::info!(
+                    "Event store uses schema version {}; older events are upgraded when read. \
+                     Run `openclaw sessions migrate` to rewrite them.",
+                    version
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn mark_schema_current(&self) -> Result<(), EventStoreError> {
+        self.meta
+            .insert(SCHEMA_MARKER, EVENT_SCHEMA_VERSION.to_string().as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::SessionEventKind;
+    use crate::types::SessionKey;
+
+    fn legacy_event(key: &str, content: &str) -> Value {
+        let event = SessionEvent::new(
+            SessionKey::new(key),
+            "agent".to_string(),
+            SessionEventKind::MessageReceived {
+                content: content.to_string(),
+                attachments: vec![],
+                sender_id: None,
+            },
+        );
+        let mut value = serde_json::to_value(event).unwrap();
+        value.as_object_mut().unwrap().remove("version");
+        value
+    }
+
+    #[test]
+    fn test_upcast_chain() {
+        let registry = UpcasterRegistry::new();
+
+        let mut current = serde_json::to_value(SessionEvent::new(
+            SessionKey::new("s"),
+            "agent".to_string(),
+            SessionEventKind::SessionRestored,
+        ))
+        .unwrap();
+        assert!(!registry.upcast(&mut current).unwrap());
+
+        let mut legacy = legacy_event("s", "hi");
+        assert_eq!(schema_version(&legacy), 0);
+        assert!(registry.upcast(&mut legacy).unwrap());
+        assert_eq!(schema_version(&legacy), EVENT_SCHEMA_VERSION);
+
+        let mut newer = serde_json::json!({ "version": EVENT_SCHEMA_VERSION + 1 });
+        assert!(matches!(
+            registry.upcast(&mut newer),
+            Err(EventStoreError::Schema(_))
+        ));
+    }
+
+    #[test]
+    fn test_custom_upcaster() {
+        // An old shape where received messages used `text` for content
+        let registry = UpcasterRegistry::new().with(0, |event| {
+            let kind = event
+                .get_mut("kind")
+                .and_then(Value::as_object_mut)
+                .ok_or("missing kind")?;
+            if let Some(text) = kind.remove("text") {
+                kind.insert("content".to_string(), text);
+            }
+            Ok(())
+        });
+
+        let mut value = legacy_event("s", "hi");
+        let kind = value["kind"].as_object_mut().unwrap();
+        let content = kind.remove("content").unwrap();
+        kind.insert("text".to_string(), content);
+
+        registry.upcast(&mut value).unwrap();
+        let event: SessionEvent = serde_json::from_value(value).unwrap();
+        assert!(matches!(
+            event.kind,
+            SessionEventKind::MessageReceived { ref content, .. } if content == "hi"
+        ));
+    }
+
+    #[test]
+    fn test_migrate_store() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = EventStore::open(temp.path()).unwrap();
+        assert_eq!(store.schema_version().unwrap(), EVENT_SCHEMA_VERSION);
+
+        // Simulate a store written before versioning
+        let key = SessionKey::new("s");
+        store
+            .events_tree
+            .insert(
+                format!("{key}:{}", ulid::Ulid::new()),
+                serde_json::to_vec(&legacy_event("s", "old")).unwrap(),
+            )
+            .unwrap();
+        store.meta.remove(SCHEMA_MARKER).unwrap();
+
+        let events = store.get_events(&key).unwrap();
+        assert_eq!(events[0].version, EVENT_SCHEMA_VERSION);
+
+        let report = store.migrate().unwrap();
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.events_checked, 1);
+        assert_eq!(report.events_migrated, 1);
+        assert_eq!(store.schema_version().unwrap(), EVENT_SCHEMA_VERSION);
+
+        let report = store.migrate().unwrap();
+        assert_eq!(report.events_migrated, 0);
+    }
+}
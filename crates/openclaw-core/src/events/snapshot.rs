@@ -0,0 +1,218 @@
+//! Portable snapshots of an event store.
+//!
+//! A snapshot is every tree and marker written as length-prefixed entries,
+//! independent of the storage backend, so a sled store can be restored
+//! into `SQLite` and back. Values are written as stored: an encrypted store's
+//! snapshot stays encrypted under the same key.
+
+use std::io::{Read, Write};
+use std::path::Path;
+
+use super::backend::{StorageBackend, TREES};
+use super::{EventStore, EventStoreError, open_backend};
+use crate::config::EventStoreBackend;
+
+/// First bytes of every snapshot.
+const SNAPSHOT_MAGIC: &[u8] = b"OCEVSNAP1\n";
+
+/// Tree name used for store-wide markers in snapshots.
+const METADATA: &str = "__metadata";
+
+impl EventStore {
+    /// Write every entry in the store to `out`, returning the number of
+    /// entries written.
+    ///
+    /// Entries appended while the snapshot is written may or may not be
+    /// included.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage read or the write fails.
+    pub fn export_snapshot(&self, out: &mut dyn Write) -> Result<usize, EventStoreError> {
+        out.write_all(SNAPSHOT_MAGIC)?;
+        let mut written = 0;
+        let mut trees = vec![(METADATA, self.backend.metadata()?)];
+        for name in TREES {
+            trees.push((name, self.backend.open_tree(name)?));
+        }
+
+        for (name, tree) in trees {
+            for entry in tree.scan_prefix(&[]) {
+                let (key, value) = entry?;
+                write_chunk(out, name.as_bytes())?;
+                write_chunk(out, &key)?;
+                write_chunk(out, &value)?;
+                written += 1;
+            }
+        }
+        out.flush()?;
+        Ok(written)
+    }
+
+    /// Create a store at `path` with `backend` from a snapshot written by
+    /// [`EventStore::export_snapshot`], returning the number of entries
+    /// restored. Nothing is left behind if the restore fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a store already exists at `path`, the snapshot is
+    /// malformed, or storage fails.
+    pub fn restore_snapshot(
+        path: &Path,
+        backend: EventStoreBackend,
+        input: &mut dyn Read,
+    ) -> Result<usize, EventStoreError> {
+        let target = Self::backend_path(path, backend);
+        if target.exists() {
+            return Err(EventStoreError::Backend(format!(
+                "{} already exists",
+                target.display()
+            )));
+        }
+
+        let restored =
+            open_backend(path, backend).and_then(|storage| read_snapshot(storage.as_ref(), input));
+        if restored.is_err() {
+            remove_storage(&target);
+        }
+        restored
+    }
+}
+
+fn read_snapshot(
+    target: &dyn StorageBackend,
+    input: &mut dyn Read,
+) -> Result<usize, EventStoreError> {
+    let mut magic = [0u8; SNAPSHOT_MAGIC.len()];
+    input.read_exact(&mut magic)?;
+    if magic != SNAPSHOT_MAGIC {
+        return Err(EventStoreError::Backend(
+            "not an event store snapshot".to_string(),
+        ));
+    }
+
+    let mut restored = 0;
+    while let Some(len) = read_len(input)? {
+        let name = String::from_utf8(read_data(input, len)?)
+            .map_err(|_| EventStoreError::Backend("invalid tree name in snapshot".to_string()))?;
+        let tree = if name == METADATA {
+            target.metadata()?
+        } else if TREES.contains(&name.as_str()) {
+            target.open_tree(&name)?
+        } else {
+            return Err(EventStoreError::Backend(format!(
+                "unknown tree '{name}' in snapshot"
+            )));
+        };
+        let key = read_chunk(input)?;
+        let value = read_chunk(input)?;
+        tree.insert(&key, &value)?;
+        restored += 1;
+    }
+    target.flush()?;
+    Ok(restored)
+}
+
+fn write_chunk(out: &mut dyn Write, data: &[u8]) -> Result<(), EventStoreError> {
+    let len = u32::try_from(data.len())
+        .map_err(|_| EventStoreError::Backend("snapshot entry too large".to_string()))?;
+    out.write_all(&len.to_le_bytes())?;
+    out.write_all(data)?;
+    Ok(())
+}
+
+fn read_chunk(input: &mut dyn Read) -> Result<Vec<u8>, EventStoreError> {
+    let len = read_len(input)?.ok_or_else(truncated)?;
+    read_data(input, len)
+}
+
+/// Length prefix of the next chunk, or `None` at a clean end of input.
+fn read_len(input: &mut dyn Read) -> Result<Option<usize>, EventStoreError> {
+    let mut len = [0u8; 4];
+    let mut filled = 0;
+    while filled < len.len() {
+        match input.read(&mut len[filled..])? {
+            0 if filled == 0 => return Ok(None),
+            0 => return Err(truncated()),
+            n => filled += n,
+        }
+    }
+    usize::try_from(u32::from_le_bytes(len))
+        .map(Some)
+        .map_err(|_| EventStoreError::Backend("snapshot entry too large".to_string()))
+}
+
+fn read_data(input: &mut dyn Read, len: usize) -> Result<Vec<u8>, EventStoreError> {
+    let mut data = vec![0u8; len];
+    input.read_exact(&mut data).map_err(|_| truncated())?;
+    Ok(data)
+}
+
+fn truncated() -> EventStoreError {
+    EventStoreError::Backend("truncated snapshot".to_string())
+}
+
+/// Remove a partially written store.
+fn remove_storage(target: &Path) {
+    if target.is_dir() {
+        let _ = std::fs::remove_dir_all(target);
+        return;
+    }
+    for suffix in ["", "-wal", "-shm"] {
+        let mut file = target.as_os_str().to_owned();
+        file.push(suffix);
+        let _ = std::fs::remove_file(file);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{SessionEvent, SessionEventKind};
+    use crate::types::SessionKey;
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let temp = tempfile::tempdir().unwrap();
+        let key = SessionKey::new("s");
+        let mut snapshot = Vec::new();
+        {
+            let store = EventStore::open(&temp.path().join("source")).unwrap();
+            store
+                .append(&SessionEvent::new(
+                    key.clone(),
+                    "agent".to_string(),
+                    SessionEventKind::MessageReceived {
+                        content: "hello".to_string(),
+                        attachments: vec![],
+                        sender_id: None,
+                    },
+                ))
+                .unwrap();
+            assert!(store.export_snapshot(&mut snapshot).unwrap() > 0);
+        }
+
+        let target = temp.path().join("restored");
+        EventStore::restore_snapshot(&target, EventStoreBackend::Sled, &mut &snapshot[..]).unwrap();
+        let store = EventStore::open(&target).unwrap();
+        assert_eq!(store.get_events(&key).unwrap().len(), 1);
+        assert_eq!(store.get_projection(&key).unwrap().message_count, 1);
+        drop(store);
+
+        assert!(
+            EventStore::restore_snapshot(&target, EventStoreBackend::Sled, &mut &snapshot[..])
+                .is_err()
+        );
+
+        let truncated = temp.path().join("truncated");
+        assert!(
+            EventStore::restore_snapshot(
+                &truncated,
+                EventStoreBackend::Sled,
+                &mut &snapshot[..snapshot.len() - 1]
+            )
+            .is_err()
+        );
+        assert!(!truncated.exists());
+    }
+}
@@ -0,0 +1,339 @@
+//! `SQLite` storage backend.
+//!
+//! Every tree is stored in one `entries` table keyed by tree name and key,
+//! in a single database file opened in WAL mode. Values are stored as they
+//! are in sled: JSON or rkyv records, encrypted if the store is.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use rusqlite::{Connection, OptionalExtension, TransactionBehavior, params};
+
+use super::EventStoreError;
+use super::backend::{Entries, Entry, StorageBackend, StorageTree, Update};
+
+/// Rows fetched per query while scanning.
+const SCAN_PAGE: usize = 256;
+
+/// Tree holding store-wide markers.
+const METADATA_TREE: &str = "__metadata";
+
+/// Single-file `SQLite` database.
+pub struct SqliteBackend {
+    conn: Arc<Mutex<Connection>>,
+    path: PathBuf,
+}
+
+impl SqliteBackend {
+    /// Open or create a `SQLite` database at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database cannot be opened or initialized.
+    pub fn open(path: &Path) -> Result<Self, EventStoreError> {
+        let conn = Connection::open(path)?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS entries (
+                tree TEXT NOT NULL,
+                key BLOB NOT NULL,
+                value BLOB NOT NULL,
+                PRIMARY KEY (tree, key)
+            ) WITHOUT ROWID;",
+        )?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            path: path.to_path_buf(),
+        })
+    }
+
+    fn tree(&self, name: &str) -> Box<dyn StorageTree> {
+        Box::new(SqliteTree {
+            conn: self.conn.clone(),
+            name: name.to_string(),
+        })
+    }
+}
+
+impl StorageBackend for SqliteBackend {
+    fn open_tree(&self, name: &str) -> Result<Box<dyn StorageTree>, EventStoreError> {
+        Ok(self.tree(name))
+    }
+
+    fn metadata(&self) -> Result<Box<dyn StorageTree>, EventStoreError> {
+        Ok(self.tree(METADATA_TREE))
+    }
+
+    fn flush(&self) -> Result<(), EventStoreError> {
+        // Writes are durable once committed; move them into the main file
+        lock(&self.conn)
+            .query_row("PRAGMA wal_checkpoint(PASSIVE)", [], |_| Ok(()))
+            .map_err(EventStoreError::from)
+    }
+
+    fn size_on_disk(&self) -> Result<u64, EventStoreError> {
+        let mut size = std::fs::metadata(&self.path)?.len();
+        let mut wal = self.path.clone().into_os_string();
+        wal.push("-wal");
+        if let Ok(metadata) = std::fs::metadata(wal) {
+            size += metadata.len();
+        }
+        Ok(size)
+    }
+}
+
+struct SqliteTree {
+    conn: Arc<Mutex<Connection>>,
+    name: String,
+}
+
+impl SqliteTree {
+    /// Up to [`SCAN_PAGE`] entries from `start` (exclusive of `after`),
+    /// before `end`.
+    fn page(
+        &self,
+        start: &[u8],
+        after: Option<&[u8]>,
+        end: Option<&[u8]>,
+    ) -> Result<Vec<Entry>, EventStoreError> {
+        lock(&self.conn)
+            .prepare_cached(
+                "SELECT key, value FROM entries
+                 WHERE tree = ?1 AND key >= ?2
+                   AND (?3 IS NULL OR key > ?3)
+                   AND (?4 IS NULL OR key < ?4)
+                 ORDER BY key LIMIT ?5",
+            )
+            .and_then(|mut stmt| {
+                stmt.query_map(params![self.name, start, after, end, SCAN_PAGE], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })?
+                .collect()
+            })
+            .map_err(EventStoreError::from)
+    }
+}
+
+impl StorageTree for SqliteTree {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, EventStoreError> {
+        lock(&self.conn)
+            .prepare_cached("SELECT value FROM entries WHERE tree = ?1 AND key = ?2")
+            .and_then(|mut stmt| {
+                stmt.query_row(params![self.name, key], |row| row.get(0))
+                    .optional()
+            })
+            .map_err(EventStoreError::from)
+    }
+
+    fn insert(&self, key: &[u8], value: &[u8]) -> Result<(), EventStoreError> {
+        upsert(&lock(&self.conn), &self.name, key, value)
+    }
+
+    fn remove(&self, key: &[u8]) -> Result<(), EventStoreError> {
+        lock(&self.conn).execute(
+            "DELETE FROM entries WHERE tree = ?1 AND key = ?2",
+            params![self.name, key],
+        )?;
+        Ok(())
+    }
+
+    fn scan_prefix(&self, prefix: &[u8]) -> Entries<'_> {
+        Box::new(Scan {
+            tree: self,
+            start: prefix.to_vec(),
+            end: prefix_end(prefix),
+            after: None,
+            page: Vec::new().into_iter(),
+            done: false,
+        })
+    }
+
+    fn len(&self) -> Result<usize, EventStoreError> {
+        let count: i64 = lock(&self.conn).query_row(
+            "SELECT COUNT(*) FROM entries WHERE tree = ?1",
+            params![self.name],
+            |row| row.get(0),
+        )?;
+        Ok(usize::try_from(count).unwrap_or_default())
+    }
+
+    fn clear(&self) -> Result<(), EventStoreError> {
+        lock(&self.conn).execute("DELETE FROM entries WHERE tree = ?1", params![self.name])?;
+        Ok(())
+    }
+
+    fn update(&self, key: &[u8], update: &mut Update<'_>) -> Result<(), EventStoreError> {
+        read_modify_write(&mut lock(&self.conn), &self.name, key, update)
+    }
+}
+
+/// Scans a tree a page at a time, so no lock is held between pages.
+struct Scan<'a> {
+    tree: &'a SqliteTree,
+    start: Vec<u8>,
+    end: Option<Vec<u8>>,
+    after: Option<Vec<u8>>,
+    page: std::vec::IntoIter<Entry>,
+    done: bool,
+}
+
+impl Iterator for Scan<'_> {
+    type Item = Result<(Vec<u8>, Vec<u8>), EventStoreError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(entry) = self.page.next() {
+            return Some(Ok(entry));
+        }
+        if self.done {
+            return None;
+        }
+        match self
+            .tree
+            .page(&self.start, self.after.as_deref(), self.end.as_deref())
+        {
+            Ok(page) => {
+                self.done = page.len() < SCAN_PAGE;
+                self.after = page.last().map(|(key, _)| key.clone());
+                self.page = page.into_iter();
+                self.page.next().map(Ok)
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+fn upsert(conn: &Connection, tree: &str, key: &[u8], value: &[u8]) -> Result<(), EventStoreError> {
+    conn.prepare_cached(
+        "INSERT INTO entries (tree, key, value) VALUES (?1, ?2, ?3)
+         ON CONFLICT (tree, key) DO UPDATE SET value = excluded.value",
+    )
+    .and_then(|mut stmt| stmt.execute(params![tree, key, value]))?;
+    Ok(())
+}
+
+fn read_modify_write(
+    conn: &mut Connection,
+    tree: &str,
+    key: &[u8],
+    update: &mut Update<'_>,
+) -> Result<(), EventStoreError> {
+    // Immediate, so another process cannot write between read and write
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let current: Option<Vec<u8>> = tx
+        .query_row(
+            "SELECT value FROM entries WHERE tree = ?1 AND key = ?2",
+            params![tree, key],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match update(current.as_deref()) {
+        Some(value) => upsert(&tx, tree, key, &value)?,
+        None => {
+            tx.execute(
+                "DELETE FROM entries WHERE tree = ?1 AND key = ?2",
+                params![tree, key],
+            )?;
+        }
+    }
+    tx.commit().map_err(EventStoreError::from)
+}
+
+/// Smallest key greater than every key starting with `prefix`, if any.
+fn prefix_end(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut end = prefix.to_vec();
+    while let Some(last) = end.pop() {
+        if last < u8::MAX {
+            end.push(last + 1);
+            return Some(end);
+        }
+    }
+    None
+}
+
+fn lock(conn: &Mutex<Connection>) -> MutexGuard<'_, Connection> {
+    conn.lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+impl From<rusqlite::Error> for EventStoreError {
+    fn from(e: rusqlite::Error) -> Self {
+        Self::Backend(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{EventStore, SessionEvent, SessionEventKind};
+    use crate::types::SessionKey;
+
+    #[test]
+    fn test_tree_operations() {
+        let temp = tempfile::tempdir().unwrap();
+        let backend = SqliteBackend::open(&temp.path().join("events.db")).unwrap();
+        let tree = backend.open_tree("t").unwrap();
+        let other = backend.open_tree("other").unwrap();
+
+        for i in 0..600 {
+            tree.insert(format!("a:{i:04}").as_bytes(), b"x").unwrap();
+        }
+        tree.insert(b"b:1", b"y").unwrap();
+        other.insert(b"a:0000", b"z").unwrap();
+
+        let keys: Vec<_> = tree.scan_prefix(b"a:").map(|e| e.unwrap().0).collect();
+        assert_eq!(keys.len(), 600);
+        assert!(keys.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(tree.len().unwrap(), 601);
+        assert_eq!(tree.get(b"b:1").unwrap().as_deref(), Some(&b"y"[..]));
+
+        tree.update(b"n", &mut |old| {
+            assert!(old.is_none());
+            Some(b"1".to_vec())
+        })
+        .unwrap();
+        tree.update(b"n", &mut |_| None).unwrap();
+        assert!(tree.get(b"n").unwrap().is_none());
+
+        tree.clear().unwrap();
+        assert!(tree.is_empty().unwrap());
+        assert_eq!(other.len().unwrap(), 1);
+        assert_eq!(prefix_end(&[1, 0xFF]), Some(vec![2]));
+        assert_eq!(prefix_end(&[0xFF]), None);
+    }
+
+    #[test]
+    fn test_event_store_on_sqlite() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("events.db");
+        let key = SessionKey::new("s");
+        {
+            let backend = SqliteBackend::open(&path).unwrap();
+            let store = EventStore::with_storage(Box::new(backend), None).unwrap();
+            store
+                .append(&SessionEvent::new(
+                    key.clone(),
+                    "agent".to_string(),
+                    SessionEventKind::MessageReceived {
+                        content: "hello".to_string(),
+                        attachments: vec![],
+                        sender_id: None,
+                    },
+                ))
+                .unwrap();
+            store.flush().unwrap();
+        }
+
+        let store =
+            EventStore::with_storage(Box::new(SqliteBackend::open(&path).unwrap()), None).unwrap();
+        assert_eq!(store.get_events(&key).unwrap().len(), 1);
+        assert_eq!(store.get_projection(&key).unwrap().message_count, 1);
+        assert!(store.stats().unwrap().size_bytes > 0);
+    }
+}
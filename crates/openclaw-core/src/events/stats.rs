@@ -0,0 +1,446 @@
+//! Activity counters maintained as events are appended.
+//!
+//! Per-agent and per-channel daily counters and daily error counts live in
+//! their own trees, so activity can be charted without scanning events.
+//! Counters hold no message content and are stored unencrypted.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::AddAssign;
+
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::backend::Tree;
+use super::{EventStore, EventStoreError, SessionEvent, SessionEventKind, SessionProjection};
+
+/// Marker recording that the activity counters cover every stored event.
+const STATS_MARKER: &[u8] = b"stats_version";
+const STATS_VERSION: &[u8] = b"1";
+
+/// Error kind counted when a tool call fails.
+pub const ERROR_TOOL_FAILED: &str = "tool_failed";
+/// Error kind counted when a model asks for a tool its agent may not use.
+pub const ERROR_TOOL_DENIED: &str = "tool_denied";
+/// Error kind counted when a reminder cannot be delivered.
+pub const ERROR_REMINDER_FAILED: &str = "reminder_failed";
+/// Error kind counted when an inbound attachment is rejected.
+pub const ERROR_ATTACHMENT_REJECTED: &str = "attachment_rejected";
+/// Error kind counted when a broadcast cannot reach a chat.
+pub const ERROR_BROADCAST_FAILED: &str = "broadcast_failed";
+/// Error kind counted when moderation blocks a message or reply.
+pub const ERROR_CONTENT_BLOCKED: &str = "content_blocked";
+
+/// Counters for one agent on one day.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentDayStats {
+    /// Messages received from peers.
+    pub messages_received: u64,
+    /// Responses produced.
+    pub responses: u64,
+    /// Input tokens used.
+    pub input_tokens: u64,
+    /// Output tokens used.
+    pub output_tokens: u64,
+    /// Tool calls made.
+    pub tool_calls: u64,
+    /// Tool calls that failed.
+    pub tool_errors: u64,
+}
+
+impl AddAssign for AgentDayStats {
+    fn add_assign(&mut self, other: Self) {
+        self.messages_received += other.messages_received;
+        self.responses += other.responses;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.tool_calls += other.tool_calls;
+        self.tool_errors += other.tool_errors;
+    }
+}
+
+/// Counters for one channel on one day.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelDayStats {
+    /// Sessions started.
+    pub sessions_started: u64,
+    /// Messages received from peers.
+    pub messages_received: u64,
+    /// Messages sent to peers (agent responses and other deliveries).
+    pub messages_sent: u64,
+}
+
+impl AddAssign for ChannelDayStats {
+    fn add_assign(&mut self, other: Self) {
+        self.sessions_started += other.sessions_started;
+        self.messages_received += other.messages_received;
+        self.messages_sent += other.messages_sent;
+    }
+}
+
+/// Activity counters over a range of days. Days are `YYYY-MM-DD` strings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityStats {
+    /// Daily counters by agent ID.
+    pub agents: BTreeMap<String, BTreeMap<String, AgentDayStats>>,
+    /// Daily counters by channel ID.
+    pub channels: BTreeMap<String, BTreeMap<String, ChannelDayStats>>,
+    /// Daily error counts by error kind.
+    pub errors: BTreeMap<String, BTreeMap<String, u64>>,
+}
+
+impl ActivityStats {
+    /// Add another set of counters, e.g. from another store.
+    pub fn merge(&mut self, other: Self) {
+        for (agent, days) in other.agents {
+            let entry = self.agents.entry(agent).or_default();
+            for (day, stats) in days {
+                *entry.entry(day).or_default() += stats;
+            }
+        }
+        for (channel, days) in other.channels {
+            let entry = self.channels.entry(channel).or_default();
+            for (day, stats) in days {
+                *entry.entry(day).or_default() += stats;
+            }
+        }
+        for (day, kinds) in other.errors {
+            let entry = self.errors.entry(day).or_default();
+            for (kind, count) in kinds {
+                *entry.entry(kind).or_default() += count;
+            }
+        }
+    }
+
+    /// Totals per agent over the whole range.
+    #[must_use]
+    pub fn agent_totals(&self) -> BTreeMap<String, AgentDayStats> {
+        self.agents
+            .iter()
+            .map(|(agent, days)| {
+                let mut total = AgentDayStats::default();
+                for stats in days.values() {
+                    total += *stats;
+                }
+                (agent.clone(), total)
+            })
+            .collect()
+    }
+}
+
+impl EventStore {
+    /// Activity counters for the days from `from` to `to`, inclusive.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage read fails.
+    pub fn activity(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<ActivityStats, EventStoreError> {
+        let from = day_key(from);
+        let to = day_key(to);
+        let in_range = |day: &str| day >= from.as_str() && day <= to.as_str();
+        let mut stats = ActivityStats::default();
+
+        for (id, day, value) in scan::<AgentDayStats>(&self.agent_stats_tree)? {
+            if in_range(&day) {
+                stats.agents.entry(id).or_default().insert(day, value);
+            }
+        }
+        for (id, day, value) in scan::<ChannelDayStats>(&self.channel_stats_tree)? {
+            if in_range(&day) {
+                stats.channels.entry(id).or_default().insert(day, value);
+            }
+        }
+        for (kind, day, count) in scan::<u64>(&self.error_stats_tree)? {
+            if in_range(&day) {
+                stats.errors.entry(day).or_default().insert(kind, count);
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Recompute the activity counters from the events in active storage.
+    ///
+    /// Counters for archived or purged sessions are lost. Events that
+    /// cannot be decoded are skipped. Returns the number of events counted.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage fails.
+    pub fn rebuild_stats(&self) -> Result<usize, EventStoreError> {
+        self.agent_stats_tree.clear()?;
+        self.channel_stats_tree.clear()?;
+        self.error_stats_tree.clear()?;
+
+        let mut channels = HashMap::new();
+        for result in &self.sessions_tree {
+            let (key, value) = result?;
+            if let Ok(projection) = self.decode::<SessionProjection>(&value) {
+                channels.insert(key, projection.channel.as_ref().to_string());
+            }
+        }
+
+        let mut counted = 0;
+        for result in &self.events_tree {
+            let (_, value) = result?;
+            let Ok(event) = self.decode_event(&value) else {
+                continue;
+            };
+            let channel = channels
+                .get(event.session_key.as_ref().as_bytes())
+                .map_or("unknown", String::as_str);
+            self.record_stats(&event, channel)?;
+            counted += 1;
+        }
+
+        self.meta.insert(STATS_MARKER, STATS_VERSION)?;
+        Ok(counted)
+    }
+
+    /// Count existing events once, for stores written before the counters
+    /// existed.
+    pub(super) fn init_stats(&self) -> Result<(), EventStoreError> {
+        if self.meta.get(STATS_MARKER)?.as_deref() == Some(STATS_VERSION) {
+            return Ok(());
+        }
+        let counted = self.rebuild_stats()?;
+        if counted > 0 {
+            tracing::info!("Counted activity for {} existing event(s)", counted);
+        }
+        Ok(())
+    }
+
+    /// Update the counters for a newly appended event.
+    pub(super) fn record_stats(
+        &self,
+        event: &SessionEvent,
+        channel: &str,
+    ) -> Result<(), EventStoreError> {
+        let day = day_key(event.timestamp.date_naive());
+        let agent_key = format!("{}:{day}", event.agent_id);
+        let channel_key = format!("{channel}:{day}");
+        let error = |kind: &str| {
+            bump::<u64>(&self.error_stats_tree, &format!("{kind}:{day}"), |n| {
+                *n += 1;
+            })
+        };
+
+        match &event.kind {
+            SessionEventKind::SessionStarted { .. } => {
+                bump::<ChannelDayStats>(&self.channel_stats_tree, &channel_key, |s| {
+                    s.sessions_started += 1;
+                })?;
+            }
+            SessionEventKind::MessageReceived { .. } => {
+                bump::<AgentDayStats>(&self.agent_stats_tree, &agent_key, |s| {
+                    s.messages_received += 1;
+                })?;
+                bump::<ChannelDayStats>(&self.channel_stats_tree, &channel_key, |s| {
+                    s.messages_received += 1;
+                })?;
+            }
+            SessionEventKind::AgentResponse { tokens, .. } => {
+                bump::<AgentDayStats>(&self.agent_stats_tree, &agent_key, |s| {
+                    s.responses += 1;
+                    s.input_tokens += tokens.input_tokens;
+                    s.output_tokens += tokens.output_tokens;
+                })?;
+                bump::<ChannelDayStats>(&self.channel_stats_tree, &channel_key, |s| {
+                    s.messages_sent += 1;
+                })?;
+            }
+            SessionEventKind::MessageSent { .. } => {
+                bump::<ChannelDayStats>(&self.channel_stats_tree, &channel_key, |s| {
+                    s.messages_sent += 1;
+                })?;
+            }
+            SessionEventKind::ToolCalled { .. } => {
+                bump::<AgentDayStats>(&self.agent_stats_tree, &agent_key, |s| {
+                    s.tool_calls += 1;
+                })?;
+            }
+            SessionEventKind::ToolResult { success: false, .. } => {
+                bump::<AgentDayStats>(&self.agent_stats_tree, &agent_key, |s| {
+                    s.tool_errors += 1;
+                })?;
+                error(ERROR_TOOL_FAILED)?;
+            }
+            SessionEventKind::ToolDenied { .. } => {
+                error(ERROR_TOOL_DENIED)?;
+            }
+            SessionEventKind::ReminderFired { error: Some(_), .. } => {
+                error(ERROR_REMINDER_FAILED)?;
+            }
+            SessionEventKind::AttachmentRejected { .. } => {
+                error(ERROR_ATTACHMENT_REJECTED)?;
+            }
+            SessionEventKind::ContentModerated { blocked: true, .. } => {
+                error(ERROR_CONTENT_BLOCKED)?;
+            }
+            SessionEventKind::BroadcastDelivered {
+                message_id: Some(_),
+                ..
+            } => {
+                bump::<ChannelDayStats>(&self.channel_stats_tree, &channel_key, |s| {
+                    s.messages_sent += 1;
+                })?;
+            }
+            SessionEventKind::BroadcastDelivered { error: Some(_), .. } => {
+                error(ERROR_BROADCAST_FAILED)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+fn day_key(day: NaiveDate) -> String {
+    day.format("%Y-%m-%d").to_string()
+}
+
+/// Atomically update the counter stored under `key`.
+fn bump<T>(tree: &Tree, key: &str, update: impl Fn(&mut T)) -> Result<(), EventStoreError>
+where
+    T: Default + Serialize + DeserializeOwned,
+{
+    tree.update(key, |old| {
+        let mut value: T = old
+            .and_then(|data| serde_json::from_slice(data).ok())
+            .unwrap_or_default();
+        update(&mut value);
+        serde_json::to_vec(&value).ok()
+    })?;
+    Ok(())
+}
+
+/// Decode every `<id>:<day>` entry in a counter tree.
+fn scan<T: DeserializeOwned>(tree: &Tree) -> Result<Vec<(String, String, T)>, EventStoreError> {
+    let mut entries = Vec::new();
+    for result in tree {
+        let (key, value) = result?;
+        let Some((id, day)) = std::str::from_utf8(&key)
+            .ok()
+            .and_then(|k| k.rsplit_once(':'))
+        else {
+            continue;
+        };
+        entries.push((
+            id.to_string(),
+            day.to_string(),
+            serde_json::from_slice(&value)?,
+        ));
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{SessionKey, TokenUsage};
+
+    fn event(key: &SessionKey, kind: SessionEventKind) -> SessionEvent {
+        SessionEvent::new(key.clone(), "agent".to_string(), kind)
+    }
+
+    #[test]
+    fn test_activity_counters() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = EventStore::open(dir.path()).unwrap();
+        let key = SessionKey::new("s1");
+        let today = chrono::Utc::now().date_naive();
+
+        for kind in [
+            SessionEventKind::SessionStarted {
+                channel: "telegram".to_string(),
+                peer_id: "peer".to_string(),
+            },
+            SessionEventKind::MessageReceived {
+                content: "hi".to_string(),
+                attachments: vec![],
+                sender_id: None,
+            },
+            SessionEventKind::ToolCalled {
+                tool_name: "search".to_string(),
+                params: serde_json::json!({}),
+            },
+            SessionEventKind::ToolResult {
+                tool_name: "search".to_string(),
+                result: serde_json::json!("boom"),
+                success: false,
+            },
+            SessionEventKind::AgentResponse {
+                content: "hello".to_string(),
+                model: "m".to_string(),
+                tokens: TokenUsage {
+                    input_tokens: 10,
+                    output_tokens: 5,
+                    ..TokenUsage::default()
+                },
+                latency_ms: None,
+                citations: vec![],
+            },
+        ] {
+            store.append(&event(&key, kind)).unwrap();
+        }
+
+        let stats = store.activity(today, today).unwrap();
+        let day = day_key(today);
+        assert_eq!(
+            stats.agents["agent"][&day],
+            AgentDayStats {
+                messages_received: 1,
+                responses: 1,
+                input_tokens: 10,
+                output_tokens: 5,
+                tool_calls: 1,
+                tool_errors: 1,
+            }
+        );
+        assert_eq!(
+            stats.channels["telegram"][&day],
+            ChannelDayStats {
+                sessions_started: 1,
+                messages_received: 1,
+                messages_sent: 1,
+            }
+        );
+        assert_eq!(stats.errors[&day][ERROR_TOOL_FAILED], 1);
+
+        // Out of range
+        let yesterday = today.pred_opt().unwrap();
+        assert_eq!(
+            store.activity(yesterday, yesterday).unwrap(),
+            ActivityStats::default()
+        );
+
+        // Rebuilding counts the same events once
+        assert_eq!(store.rebuild_stats().unwrap(), 5);
+        assert_eq!(store.activity(today, today).unwrap(), stats);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut a = ActivityStats::default();
+        a.agents.entry("x".to_string()).or_default().insert(
+            "2024-01-01".to_string(),
+            AgentDayStats {
+                responses: 1,
+                ..AgentDayStats::default()
+            },
+        );
+        let mut b = a.clone();
+        b.errors
+            .entry("2024-01-01".to_string())
+            .or_default()
+            .insert(ERROR_TOOL_FAILED.to_string(), 2);
+
+        a.merge(b);
+        assert_eq!(a.agents["x"]["2024-01-01"].responses, 2);
+        assert_eq!(a.agent_totals()["x"].responses, 2);
+        assert_eq!(a.errors["2024-01-01"][ERROR_TOOL_FAILED], 2);
+    }
+}
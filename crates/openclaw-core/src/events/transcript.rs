@@ -0,0 +1,565 @@
+//! Readable session transcripts.
+//!
+//! A [`Transcript`] renders a session's projection and events as Markdown or
+//! a standalone HTML page: each message with its speaker and time, tool
+//! calls folded into `<details>` blocks, and a footer with token usage and,
+//! when rates are known, the cost.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{SessionEvent, SessionEventKind, SessionProjection, SessionState};
+use crate::types::TokenUsage;
+
+/// Transcript output format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    /// Markdown.
+    Markdown,
+    /// Standalone HTML page.
+    Html,
+}
+
+impl TranscriptFormat {
+    /// Parse `md`, `markdown`, or `html`.
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "md" | "markdown" => Some(Self::Markdown),
+            "html" | "htm" => Some(Self::Html),
+            _ => None,
+        }
+    }
+
+    /// File extension, without the dot.
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+        }
+    }
+
+    /// HTTP content type.
+    #[must_use]
+    pub const fn content_type(self) -> &'static str {
+        match self {
+            Self::Markdown => "text/markdown; charset=utf-8",
+            Self::Html => "text/html; charset=utf-8",
+        }
+    }
+}
+
+/// One entry in the transcript.
+enum Entry<'a> {
+    /// A message from the peer or the agent.
+    Message {
+        at: DateTime<Utc>,
+        speaker: String,
+        text: &'a str,
+    },
+    /// A tool call and its result, if one was recorded.
+    Tool {
+        at: DateTime<Utc>,
+        name: &'a str,
+        params: Option<&'a serde_json::Value>,
+        result: &'a serde_json::Value,
+        success: bool,
+    },
+    /// A session lifecycle note.
+    Note { at: DateTime<Utc>, text: String },
+}
+
+/// A session rendered for reading.
+pub struct Transcript<'a> {
+    projection: &'a SessionProjection,
+    events: &'a [SessionEvent],
+    rates: Option<(f64, f64)>,
+}
+
+impl<'a> Transcript<'a> {
+    /// Create a transcript from a session's projection and its events, in
+    /// order.
+    #[must_use]
+    pub const fn new(projection: &'a SessionProjection, events: &'a [SessionEvent]) -> Self {
+        Self {
+            projection,
+            events,
+            rates: None,
+        }
+    }
+
+    /// Show the cost in the footer, from prices per million input and
+    /// output tokens.
+    #[must_use]
+    pub const fn with_rates(mut self, input_per_mtok: f64, output_per_mtok: f64) -> Self {
+        self.rates = Some((input_per_mtok, output_per_mtok));
+        self
+    }
+
+    /// Render in `format`.
+    #[must_use]
+    pub fn render(&self, format: TranscriptFormat) -> String {
+        match format {
+            TranscriptFormat::Markdown => self.to_markdown(),
+            TranscriptFormat::Html => self.to_html(),
+        }
+    }
+
+    /// Render as Markdown.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let p = self.projection;
+        let mut out = format!("# Session `{}`\n\n", p.session_key);
+        for (label, value) in self.header() {
+            let _ = writeln!(out, "- **{label}:** {value}");
+        }
+        out.push_str("\n---\n");
+
+        for entry in self.entries() {
+            out.push('\n');
+            match entry {
+                Entry::Message { at, speaker, text } => {
+                    let _ = write!(out, "**{speaker}** · {}\n\n{text}\n", timestamp(at));
+                }
+                Entry::Tool {
+                    at,
+                    name,
+                    params,
+                    result,
+                    success,
+                } => {
+                    let _ = write!(
+                        out,
+                        "<details>\n<summary>Tool <code>{}</code> · {} · {}</summary>\n\n",
+                        escape_html(name),
+                        if success { "ok" } else { "failed" },
+                        timestamp(at)
+                    );
+                    if let Some(params) = params {
+                        out.push_str("**Parameters**\n\n");
+                        out.push_str(&fenced(&pretty(params)));
+                        out.push('\n');
+                    }
+                    out.push_str("**Result**\n\n");
+                    out.push_str(&fenced(&pretty(result)));
+                    out.push_str("\n</details>\n");
+                }
+                Entry::Note { at, text } => {
+                    let _ = writeln!(out, "_{text} · {}_", timestamp(at));
+                }
+            }
+        }
+
+        out.push_str("\n---\n\n");
+        out.push_str(&self.footer().join(" · "));
+        out.push('\n');
+        out
+    }
+
+    /// Render as a standalone HTML page.
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        let p = self.projection;
+        let title = format!("Session {}", p.session_key);
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n<h1>{}</h1>\n<dl>\n",
+            escape_html(&title),
+            escape_html(&title)
+        );
+        for (label, value) in self.header() {
+            let _ = writeln!(out, "<dt>{label}</dt><dd>{}</dd>", escape_html(&value));
+        }
+        out.push_str("</dl>\n<hr>\n");
+
+        for entry in self.entries() {
+            match entry {
+                Entry::Message { at, speaker, text } => {
+                    let _ = writeln!(
+                        out,
+                        "<div class=\"message\"><div class=\"meta\"><strong>{}</strong> · \
+                         <time>{}</time></div><div class=\"text\">{}</div></div>",
+                        escape_html(&speaker),
+                        timestamp(at),
+                        escape_html(text)
+                    );
+                }
+                Entry::Tool {
+                    at,
+                    name,
+                    params,
+                    result,
+                    success,
+                } => {
+                    let _ = writeln!(
+                        out,
+                        "<details class=\"tool\"><summary>Tool <code>{}</code> · {} · \
+                         <time>{}</time></summary>",
+                        escape_html(name),
+                        if success { "ok" } else { "failed" },
+                        timestamp(at)
+                    );
+                    if let Some(params) = params {
+                        let _ = writeln!(
+                            out,
+                            "<h4>Parameters</h4><pre>{}</pre>",
+                            escape_html(&pretty(params))
+                        );
+                    }
+                    let _ = writeln!(
+                        out,
+                        "<h4>Result</h4><pre>{}</pre></details>",
+                        escape_html(&pretty(result))
+                    );
+                }
+                Entry::Note { at, text } => {
+                    let _ = writeln!(
+                        out,
+                        "<p class=\"note\">{} · <time>{}</time></p>",
+                        escape_html(&text),
+                        timestamp(at)
+                    );
+                }
+            }
+        }
+
+        let _ = write!(
+            out,
+            "<hr>\n<footer>{}</footer>\n</body>\n</html>\n",
+            escape_html(&self.footer().join(" · "))
+        );
+        out
+    }
+
+    fn header(&self) -> Vec<(&'static str, String)> {
+        let p = self.projection;
+        let mut header = vec![
+            ("Agent", p.agent_id.clone()),
+            ("Channel", p.channel.to_string()),
+            ("Peer", p.peer_id.clone()),
+            (
+                "State",
+                match p.state {
+                    SessionState::Active => "active",
+                    SessionState::Paused => "paused",
+                    SessionState::Ended => "ended",
+                }
+                .to_string(),
+            ),
+        ];
+        if let Some(first) = self.events.first() {
+            header.push(("Started", timestamp(first.timestamp)));
+        }
+        header.push(("Last activity", timestamp(p.last_activity)));
+        if let (Some(experiment), Some(variant)) = (&p.experiment, &p.variant) {
+            header.push(("Experiment", format!("{experiment} ({variant})")));
+        }
+        header
+    }
+
+    fn entries(&self) -> Vec<Entry<'a>> {
+        let mut entries = Vec::new();
+        // Parameters of calls still waiting for their result, by tool
+        let mut calls: HashMap<&str, Vec<&serde_json::Value>> = HashMap::new();
+
+        for event in self.events {
+            let at = event.timestamp;
+            let entry = match &event.kind {
+                SessionEventKind::ToolCalled { tool_name, params } => {
+                    calls.entry(tool_name.as_str()).or_default().push(params);
+                    None
+                }
+                SessionEventKind::ToolResult {
+                    tool_name,
+                    result,
+                    success,
+                } => {
+                    let params = calls
+                        .get_mut(tool_name.as_str())
+                        .filter(|pending| !pending.is_empty())
+                        .map(|pending| pending.remove(0));
+                    Some(Entry::Tool {
+                        at,
+                        name: tool_name,
+                        params,
+                        result,
+                        success: *success,
+                    })
+                }
+                kind => self
+                    .message(event)
+                    .or_else(|| note(kind).map(|text| Entry::Note { at, text })),
+            };
+            entries.extend(entry);
+        }
+        entries
+    }
+
+    /// The message `event` records, if it is one.
+    fn message(&self, event: &'a SessionEvent) -> Option<Entry<'a>> {
+        let peer = &self.projection.peer_id;
+        let (speaker, text) = match &event.kind {
+            SessionEventKind::MessageReceived {
+                content,
+                attachments,
+                ..
+            } => {
+                let speaker = if attachments.is_empty() {
+                    peer.clone()
+                } else {
+                    format!("{peer} (+{} attachment(s))", attachments.len())
+                };
+                (speaker, content)
+            }
+            SessionEventKind::MessageSent { content, .. } => (event.agent_id.clone(), content),
+            SessionEventKind::AgentResponse { content, model, .. } => {
+                (format!("{} ({model})", event.agent_id), content)
+            }
+            SessionEventKind::BroadcastStarted {
+                content, targets, ..
+            } => (format!("broadcast to {} chat(s)", targets.len()), content),
+            _ => return None,
+        };
+        Some(Entry::Message {
+            at: event.timestamp,
+            speaker,
+            text,
+        })
+    }
+
+    fn footer(&self) -> Vec<String> {
+        let mut usage = TokenUsage::default();
+        let mut responses = 0;
+        for event in self.events {
+            match &event.kind {
+                SessionEventKind::AgentResponse { tokens, .. } => {
+                    usage.accumulate(tokens);
+                    responses += 1;
+                }
+                SessionEventKind::DelegatedUsage { tokens, .. } => usage.accumulate(tokens),
+                _ => {}
+            }
+        }
+
+        let mut footer = vec![
+            format!("Messages: {}", self.projection.message_count),
+            format!("Responses: {responses}"),
+            format!(
+                "Tokens: {} input, {} output",
+                usage.input_tokens, usage.output_tokens
+            ),
+        ];
+        if let Some((input_rate, output_rate)) = self.rates {
+            #[allow(clippy::cast_precision_loss)]
+            let cost = (usage.input_tokens as f64)
+                .mul_add(input_rate, usage.output_tokens as f64 * output_rate)
+                / 1_000_000.0;
+            footer.push(format!("Cost: ${cost:.4}"));
+        }
+        footer
+    }
+}
+
+/// The lifecycle note or failure an event records, if any.
+fn note(kind: &SessionEventKind) -> Option<String> {
+    let text = match kind {
+        SessionEventKind::SessionStarted { channel, .. } => {
+            format!("Session started on {channel}")
+        }
+        SessionEventKind::SessionEnded { reason } => format!("Session ended ({reason})"),
+        SessionEventKind::SessionForked { from, .. } => format!("Forked from session {from}"),
+        SessionEventKind::ReminderFired {
+            reminder_id,
+            error: Some(error),
+            ..
+        } => format!("Reminder {reminder_id} failed: {error}"),
+        SessionEventKind::ToolDenied {
+            tool_name, reason, ..
+        } => format!("Tool {tool_name} was not run: {reason}"),
+        SessionEventKind::AttachmentRejected {
+            filename, detail, ..
+        } => format!(
+            "Attachment {} rejected: {detail}",
+            filename.as_deref().unwrap_or("(unnamed)")
+        ),
+        SessionEventKind::ContentModerated {
+            stage,
+            moderator,
+            reason,
+            blocked,
+            ..
+        } => format!(
+            "{} {stage} content ({moderator}: {reason})",
+            if *blocked { "Blocked" } else { "Flagged" }
+        ),
+        SessionEventKind::PeerForgotten {
+            channel,
+            peer_hash,
+            sessions,
+            events,
+            requested_by,
+            ..
+        } => format!(
+            "{requested_by} erased peer {peer_hash} on {channel}: \
+             {sessions} session(s), {events} event(s)"
+        ),
+        SessionEventKind::BroadcastDelivered {
+            chat_id,
+            error: Some(error),
+            ..
+        } => format!("Broadcast to {chat_id} failed: {error}"),
+        _ => return None,
+    };
+    Some(text)
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:48rem;margin:2rem auto;\
+padding:0 1rem;line-height:1.5}dl{display:grid;grid-template-columns:max-content auto;gap:0 1rem}\
+dt{font-weight:600}dd{margin:0}.message{margin:1rem 0}.meta{color:#555;font-size:.9em}\
+.text{white-space:pre-wrap}.note{color:#777;font-style:italic}pre{background:#f4f4f4;\
+padding:.5rem;overflow-x:auto}footer{color:#555;font-size:.9em}";
+
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+}
+
+fn pretty(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(text) => text.clone(),
+        other => serde_json::to_string_pretty(other).unwrap_or_default(),
+    }
+}
+
+/// Wrap `text` in a code fence longer than any backtick run inside it.
+fn fenced(text: &str) -> String {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in text.chars() {
+        run = if c == '`' { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    let fence = "`".repeat((longest + 1).max(3));
+    format!("{fence}\n{text}\n{fence}\n")
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ChannelId, SessionKey};
+
+    fn session() -> (SessionProjection, Vec<SessionEvent>) {
+        let key = SessionKey::new("demo");
+        let event = |kind| SessionEvent::new(key.clone(), "helper".to_string(), kind);
+        let events = vec![
+            event(SessionEventKind::SessionStarted {
+                channel: "telegram".to_string(),
+                peer_id: "alice".to_string(),
+            }),
+            event(SessionEventKind::MessageReceived {
+                content: "What is <b>2+2</b>?".to_string(),
+                attachments: vec![],
+                sender_id: None,
+            }),
+            event(SessionEventKind::ToolCalled {
+                tool_name: "calc".to_string(),
+                params: serde_json::json!({ "expr": "2+2" }),
+            }),
+            event(SessionEventKind::ToolResult {
+                tool_name: "calc".to_string(),
+                result: serde_json::json!("```4```"),
+                success: true,
+            }),
+            event(SessionEventKind::AgentResponse {
+                content: "It is 4.".to_string(),
+                model: "claude".to_string(),
+                tokens: TokenUsage {
+                    input_tokens: 1_000,
+                    output_tokens: 500,
+                    ..TokenUsage::default()
+                },
+                latency_ms: None,
+                citations: vec![],
+            }),
+            event(SessionEventKind::SessionEnded {
+                reason: "done".to_string(),
+            }),
+        ];
+
+        let mut projection = SessionProjection::new(
+            key,
+            "helper".to_string(),
+            ChannelId::telegram(),
+            "alice".to_string(),
+        );
+        for event in &events {
+            projection.apply(event);
+        }
+        (projection, events)
+    }
+
+    #[test]
+    fn test_format_parse() {
+        assert_eq!(
+            TranscriptFormat::parse("md"),
+            Some(TranscriptFormat::Markdown)
+        );
+        assert_eq!(
+            TranscriptFormat::parse("HTML"),
+            Some(TranscriptFormat::Html)
+        );
+        assert_eq!(TranscriptFormat::parse("pdf"), None);
+    }
+
+    #[test]
+    fn test_markdown() {
+        let (projection, events) = session();
+        let md = Transcript::new(&projection, &events)
+            .with_rates(3.0, 15.0)
+            .to_markdown();
+
+        assert!(md.starts_with("# Session `demo`"));
+        assert!(md.contains("- **Peer:** alice"));
+        assert!(md.contains("**alice** · "));
+        assert!(md.contains("What is <b>2+2</b>?"));
+        assert!(md.contains("**helper (claude)** · "));
+        assert!(md.contains("<summary>Tool <code>calc</code> · ok"));
+        assert!(md.contains("\"expr\": \"2+2\""));
+        // The result holds a triple backtick, so the fence is longer
+        assert!(md.contains("````\n```4```\n````"));
+        assert!(md.contains("_Session ended (done) · "));
+        assert!(md.contains("Tokens: 1000 input, 500 output"));
+        assert!(md.contains("Cost: $0.0105"));
+    }
+
+    #[test]
+    fn test_html_escapes_content() {
+        let (projection, events) = session();
+        let html = Transcript::new(&projection, &events).to_html();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("What is &lt;b&gt;2+2&lt;/b&gt;?"));
+        assert!(!html.contains("<b>2+2</b>"));
+        assert!(html.contains("<details class=\"tool\">"));
+        assert!(!html.contains("Cost:"));
+    }
+}
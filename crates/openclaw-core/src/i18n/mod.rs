@@ -0,0 +1,282 @@
+//! Localized system messages.
+//!
+//! Messages sent to people on their own behalf rather than by an agent
+//! (rate-limit notices, rejected attachments, and the like) and key CLI
+//! output are looked up by ID in Fluent (`.ftl`) resources. English,
+//! German, Spanish, and French are built in; `<locale>.ftl` files in a
+//! locale directory add locales or override built-in messages.
+//!
+//! A lookup tries the requested locale, then its language alone (`pt-BR`
+//! falls back to `pt`), then English, and finally returns the message ID.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use thiserror::Error;
+use unic_langid::LanguageIdentifier;
+
+pub use fluent_bundle::FluentValue;
+
+/// Locale used when nothing more specific applies.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Built-in message resources, by locale.
+const BUILTIN: &[(&str, &str)] = &[
+    ("en", include_str!("../../locales/en.ftl")),
+    ("de", include_str!("../../locales/de.ftl")),
+    ("es", include_str!("../../locales/es.ftl")),
+    ("fr", include_str!("../../locales/fr.ftl")),
+];
+
+/// Localization errors.
+#[derive(Error, Debug)]
+pub enum I18nError {
+    /// IO error reading a locale directory.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Not a locale tag.
+    #[error("Invalid locale '{0}'")]
+    InvalidLocale(String),
+
+    /// A resource could not be parsed.
+    #[error("Invalid messages for {locale}: {errors}")]
+    Parse {
+        /// Locale of the resource.
+        locale: String,
+        /// What went wrong.
+        errors: String,
+    },
+}
+
+/// Looks up messages by locale.
+pub struct Localizer {
+    bundles: HashMap<LanguageIdentifier, FluentBundle<FluentResource>>,
+}
+
+impl Localizer {
+    /// The built-in messages.
+    #[must_use]
+    pub fn builtin() -> Self {
+        let mut localizer = Self {
+            bundles: HashMap::new(),
+        };
+        for (locale, source) in BUILTIN {
+            if let Err(e) = localizer.add(locale, (*source).to_string()) {
+                tracing::error!("Built-in messages: {}", e);
+            }
+        }
+        localizer
+    }
+
+    /// Add the `<locale>.ftl` files in `dir`. Their messages replace
+    /// built-in ones with the same ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the directory cannot be read, a file name is not a
+    /// locale, or a file is not valid Fluent.
+    pub fn with_dir(mut self, dir: &Path) -> Result<Self, I18nError> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("ftl") {
+                continue;
+            }
+            let Some(locale) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let source = std::fs::read_to_string(&path)?;
+            self.add(locale, source)?;
+        }
+        Ok(self)
+    }
+
+    fn add(&mut self, locale: &str, source: String) -> Result<(), I18nError> {
+        let id: LanguageIdentifier = locale
+            .parse()
+            .map_err(|_| I18nError::InvalidLocale(locale.to_string()))?;
+        let resource = FluentResource::try_new(source).map_err(|(_, errors)| I18nError::Parse {
+            locale: locale.to_string(),
+            errors: errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; "),
+        })?;
+        let bundle = self.bundles.entry(id.clone()).or_insert_with(|| {
+            let mut bundle = FluentBundle::new_concurrent(vec![id]);
+            // Chat clients show the Unicode isolation marks
+            bundle.set_use_isolating(false);
+            bundle
+        });
+        bundle.add_resource_overriding(resource);
+        Ok(())
+    }
+
+    /// Locales with messages, sorted.
+    #[must_use]
+    pub fn locales(&self) -> Vec<String> {
+        let mut locales: Vec<String> = self.bundles.keys().map(ToString::to_string).collect();
+        locales.sort();
+        locales
+    }
+
+    /// Message `id` in `locale`, with `args` filled in.
+    #[must_use]
+    pub fn message(&self, locale: &str, id: &str, args: &[(&str, FluentValue<'_>)]) -> String {
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+
+        for bundle in self.fallbacks(locale) {
+            let Some(pattern) = bundle.get_message(id).and_then(|m| m.value()) else {
+                continue;
+            };
+            let mut errors = Vec::new();
+            let text = bundle.format_pattern(pattern, Some(&fluent_args), &mut errors);
+            if !errors.is_empty() {
+                tracing::warn!(id, locale, "Message has errors: {:?}", errors);
+            }
+            return text.into_owned();
+        }
+        tracing::warn!(id, locale, "No such message");
+        id.to_string()
+    }
+
+    /// Bundles to look in for `locale`, most specific first.
+    fn fallbacks(&self, locale: &str) -> impl Iterator<Item = &FluentBundle<FluentResource>> {
+        let requested = locale.parse::<LanguageIdentifier>().ok();
+        let language = requested
+            .as_ref()
+            .map(|id| LanguageIdentifier::from_parts(id.language, None, None, &[]));
+        let default = DEFAULT_LOCALE.parse::<LanguageIdentifier>().ok();
+        [requested, language, default]
+            .into_iter()
+            .flatten()
+            .filter_map(|id| self.bundles.get(&id))
+    }
+}
+
+impl Default for Localizer {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+/// Whether `locale` is a valid locale tag.
+#[must_use]
+pub fn is_locale(locale: &str) -> bool {
+    locale.parse::<LanguageIdentifier>().is_ok()
+}
+
+/// Locale for the CLI: `OPENCLAW_LOCALE`, else the POSIX locale
+/// (`LC_ALL`, `LC_MESSAGES`, `LANG`), else English.
+#[must_use]
+pub fn system_locale() -> String {
+    ["OPENCLAW_LOCALE", "LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find_map(|value| posix_locale(&value))
+        .unwrap_or_else(|| DEFAULT_LOCALE.to_string())
+}
+
+/// Locale tag for a POSIX locale like `de_DE.UTF-8`, if it names one.
+fn posix_locale(value: &str) -> Option<String> {
+    let name = value.split(['.', '@']).next()?.replace('_', "-");
+    if name.is_empty() || name == "C" || name == "POSIX" {
+        return None;
+    }
+    name.parse::<LanguageIdentifier>()
+        .ok()
+        .map(|id| id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_fallbacks() {
+        let localizer = Localizer::builtin();
+        assert_eq!(localizer.locales(), ["de", "en", "es", "fr"]);
+
+        let args = [("retry_after", FluentValue::from(30))];
+        assert_eq!(
+            localizer.message("en", "rate-limited", &args),
+            "You're sending messages too quickly. Please wait 30s and try again."
+        );
+        assert!(
+            localizer
+                .message("de-AT", "rate-limited", &args)
+                .starts_with("Du sendest")
+        );
+        // Unknown locales and tags get English
+        assert_eq!(
+            localizer.message("ja", "feedback-thanks", &[]),
+            "Thanks for your feedback!"
+        );
+        assert_eq!(
+            localizer.message("not a locale", "feedback-thanks", &[]),
+            "Thanks for your feedback!"
+        );
+        assert_eq!(
+            localizer.message("fr", "no-such-message", &[]),
+            "no-such-message"
+        );
+        assert_eq!(
+            localizer.message("es", "cli-status-providers", &[("count", 1.into())]),
+            "1 proveedor"
+        );
+    }
+
+    #[test]
+    fn test_builtin_locales_are_complete() {
+        let localizer = Localizer::builtin();
+        let ids: Vec<&str> = BUILTIN[0]
+            .1
+            .lines()
+            .filter_map(|line| line.split_once(" = ").map(|(id, _)| id))
+            .collect();
+        assert!(ids.len() > 10);
+        for (locale, _) in BUILTIN {
+            let bundle = &localizer.bundles[&locale.parse().unwrap()];
+            for id in &ids {
+                assert!(bundle.has_message(id), "{locale} is missing {id}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_dir_overrides() {
+        let temp = tempdir().unwrap();
+        std::fs::write(temp.path().join("en.ftl"), "feedback-thanks = Cheers!\n").unwrap();
+        std::fs::write(temp.path().join("pt-BR.ftl"), "feedback-thanks = Valeu!\n").unwrap();
+        std::fs::write(temp.path().join("README.md"), "not messages").unwrap();
+
+        let localizer = Localizer::builtin().with_dir(temp.path()).unwrap();
+        assert_eq!(localizer.message("en", "feedback-thanks", &[]), "Cheers!");
+        assert_eq!(localizer.message("pt-BR", "feedback-thanks", &[]), "Valeu!");
+        // Other built-in messages are kept
+        assert!(
+            localizer
+                .message("pt-BR", "attachment-unscanned", &[])
+                .contains("scanned")
+        );
+
+        std::fs::write(temp.path().join("broken.ftl"), "= nope").unwrap();
+        assert!(Localizer::builtin().with_dir(temp.path()).is_err());
+    }
+
+    #[test]
+    fn test_posix_locale() {
+        assert_eq!(posix_locale("de_DE.UTF-8").as_deref(), Some("de-DE"));
+        assert_eq!(posix_locale("fr_FR@euro").as_deref(), Some("fr-FR"));
+        assert_eq!(posix_locale("C.UTF-8"), None);
+        assert_eq!(posix_locale("POSIX"), None);
+        assert_eq!(posix_locale(""), None);
+    }
+}
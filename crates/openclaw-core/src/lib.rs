@@ -7,23 +7,35 @@
 //! - Event-sourced session storage (grite pattern)
 //! - CRDT projections for session state
 //! - Secrets management with encryption at rest
+//! - Per-peer profiles (name, language, timezone, preferences)
+//! - Localized system messages (Fluent)
+//! - Object storage for blobs (local or S3-compatible)
+//! - Encrypted backups to object storage
 //! - Input validation and sanitization
 
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
 pub mod auth;
+pub mod backup;
 pub mod config;
 pub mod events;
+pub mod i18n;
+pub mod peers;
 pub mod secrets;
+pub mod storage;
 pub mod types;
 pub mod validation;
 
 pub use auth::AuthProfile;
 pub use config::{Config, ConfigError};
 pub use events::{EventStore, SessionEvent, SessionEventKind, SessionProjection};
+pub use i18n::Localizer;
+pub use peers::{PeerProfile, PeerProfileStore};
 pub use secrets::CredentialStore;
 pub use secrets::{ApiKey, scrub_secrets};
+pub use secrets::{EncryptionKey, MasterKey};
+pub use storage::{BlobNamespace, BlobStore, BlockingBlobStore, StorageError};
 pub use types::{AgentId, ChannelId, Message, PeerId, SessionKey};
 pub use validation::{ValidationError, validate_message_content};
 
@@ -0,0 +1,525 @@
+//! Peer profiles.
+//!
+//! What agents know about a person on a channel: display name, language,
+//! timezone, notes, and preference flags, kept per (channel, peer ID).
+//! Each field records whether it was set explicitly, by an operator or the
+//! person, or learned by an agent. Learned values never replace explicit
+//! ones.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::validation::limits::{
+    MAX_PROFILE_FIELD_LENGTH, MAX_PROFILE_NOTES_LENGTH, MAX_PROFILE_PREFERENCES,
+};
+
+/// Peer profile errors.
+#[derive(Error, Debug)]
+pub enum PeerProfileError {
+    /// Storage error.
+    #[error("Storage error: {0}")]
+    Storage(#[from] sled::Error),
+
+    /// Serialization error.
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    /// Update has an invalid value.
+    #[error("Invalid profile: {0}")]
+    Invalid(String),
+}
+
+/// How a profile field got its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileSource {
+    /// Set by an operator or the person.
+    Explicit,
+    /// Inferred by an agent from the conversation.
+    Learned,
+}
+
+/// What is known about one peer on one channel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerProfile {
+    /// Channel ID.
+    pub channel: String,
+    /// Peer ID on the channel.
+    pub peer_id: String,
+    /// Name to address the person by.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// Preferred language, as a BCP 47 tag (e.g. `pt-BR`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Timezone, as an IANA name (e.g. `Europe/Berlin`) or UTC offset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Free-form notes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Preference flags, by name.
+    #[serde(default)]
+    pub preferences: BTreeMap<String, bool>,
+    /// Where each set field's value came from, by field name; preference
+    /// flags are listed as `preferences.<name>`.
+    #[serde(default)]
+    pub sources: BTreeMap<String, ProfileSource>,
+    /// When the profile last changed.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Changes to a profile. Unset fields are left alone.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileUpdate {
+    /// New display name; an empty string clears it.
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// New language; an empty string clears it.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// New timezone; an empty string clears it.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// New notes; an empty string clears them.
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Preference flags to set, by name; `None` removes a flag.
+    #[serde(default)]
+    pub preferences: BTreeMap<String, Option<bool>>,
+}
+
+impl ProfileUpdate {
+    /// Whether the update changes nothing.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.display_name.is_none()
+            && self.language.is_none()
+            && self.timezone.is_none()
+            && self.notes.is_none()
+            && self.preferences.is_empty()
+    }
+
+    /// Check values against the profile limits and formats.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Invalid` describing the first bad value.
+    pub fn validate(&self) -> Result<(), PeerProfileError> {
+        let invalid = |message: String| Err(PeerProfileError::Invalid(message));
+        for (field, value) in [
+            ("display_name", &self.display_name),
+            ("language", &self.language),
+            ("timezone", &self.timezone),
+        ] {
+            if value
+                .as_ref()
+                .is_some_and(|v| v.len() > MAX_PROFILE_FIELD_LENGTH)
+            {
+                return invalid(format!(
+                    "{field} is longer than {MAX_PROFILE_FIELD_LENGTH} bytes"
+                ));
+            }
+        }
+        if let Some(language) = self.language.as_deref().filter(|l| !l.is_empty()) {
+            if !is_language_tag(language) {
+                return invalid(format!("'{language}' is not a language tag"));
+            }
+        }
+        if let Some(timezone) = self.timezone.as_deref().filter(|t| !t.is_empty()) {
+            if !is_timezone(timezone) {
+                return invalid(format!("'{timezone}' is not a timezone"));
+            }
+        }
+        if self
+            .notes
+            .as_ref()
+            .is_some_and(|n| n.len() > MAX_PROFILE_NOTES_LENGTH)
+        {
+            return invalid(format!(
+                "notes are longer than {MAX_PROFILE_NOTES_LENGTH} bytes"
+            ));
+        }
+        if let Some(name) = self
+            .preferences
+            .keys()
+            .find(|name| !is_preference_name(name))
+        {
+            return invalid(format!(
+                "preference '{name}' must be 1-{MAX_PROFILE_FIELD_LENGTH} letters, digits, '_', '-', or '.'"
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl PeerProfile {
+    /// An empty profile.
+    #[must_use]
+    pub fn new(channel: impl Into<String>, peer_id: impl Into<String>) -> Self {
+        Self {
+            channel: channel.into(),
+            peer_id: peer_id.into(),
+            display_name: None,
+            language: None,
+            timezone: None,
+            notes: None,
+            preferences: BTreeMap::new(),
+            sources: BTreeMap::new(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Whether nothing is known about the peer.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.display_name.is_none()
+            && self.language.is_none()
+            && self.timezone.is_none()
+            && self.notes.is_none()
+            && self.preferences.is_empty()
+    }
+
+    /// Apply an update from `source`, returning the names of the fields
+    /// that changed. Learned values skip fields set explicitly.
+    pub fn apply(&mut self, update: &ProfileUpdate, source: ProfileSource) -> Vec<String> {
+        let mut changed = Vec::new();
+        let fields = [
+            ("display_name", &mut self.display_name, &update.display_name),
+            ("language", &mut self.language, &update.language),
+            ("timezone", &mut self.timezone, &update.timezone),
+            ("notes", &mut self.notes, &update.notes),
+        ];
+        for (name, current, new) in fields {
+            let Some(new) = new else {
+                continue;
+            };
+            if !may_change(&self.sources, name, source) {
+                continue;
+            }
+            let new = Some(new.trim().to_string()).filter(|v| !v.is_empty());
+            if *current != new {
+                record_source(&mut self.sources, name, new.is_some(), source);
+                *current = new;
+                changed.push(name.to_string());
+            }
+        }
+        for (flag, value) in &update.preferences {
+            let name = format!("preferences.{flag}");
+            if !may_change(&self.sources, &name, source) {
+                continue;
+            }
+            let previous = match value {
+                Some(value) => self.preferences.insert(flag.clone(), *value),
+                None => self.preferences.remove(flag),
+            };
+            if previous != *value {
+                record_source(&mut self.sources, &name, value.is_some(), source);
+                changed.push(name);
+            }
+        }
+        if !changed.is_empty() {
+            self.updated_at = Utc::now();
+        }
+        changed
+    }
+
+    /// The profile as a system prompt section, if anything is known.
+    #[must_use]
+    pub fn prompt(&self) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut prompt = String::from("# User Profile\n");
+        if let Some(name) = &self.display_name {
+            let _ = write!(prompt, "\nName: {name}");
+        }
+        if let Some(language) = &self.language {
+            let _ = write!(
+                prompt,
+                "\nLanguage: {language} (reply in it unless asked otherwise)"
+            );
+        }
+        if let Some(timezone) = &self.timezone {
+            let _ = write!(prompt, "\nTimezone: {timezone}");
+        }
+        if !self.preferences.is_empty() {
+            let flags: Vec<String> = self
+                .preferences
+                .iter()
+                .map(|(name, on)| format!("{name}: {}", if *on { "yes" } else { "no" }))
+                .collect();
+            let _ = write!(prompt, "\nPreferences: {}", flags.join(", "));
+        }
+        if let Some(notes) = &self.notes {
+            let _ = write!(prompt, "\nNotes: {notes}");
+        }
+        Some(prompt)
+    }
+}
+
+/// Whether `source` may change field `name`.
+fn may_change(
+    sources: &BTreeMap<String, ProfileSource>,
+    name: &str,
+    source: ProfileSource,
+) -> bool {
+    source == ProfileSource::Explicit || sources.get(name) != Some(&ProfileSource::Explicit)
+}
+
+fn record_source(
+    sources: &mut BTreeMap<String, ProfileSource>,
+    name: &str,
+    is_set: bool,
+    source: ProfileSource,
+) {
+    if is_set {
+        sources.insert(name.to_string(), source);
+    } else {
+        sources.remove(name);
+    }
+}
+
+/// Loose BCP 47 check: a 2-8 letter language then alphanumeric subtags.
+fn is_language_tag(tag: &str) -> bool {
+    let mut parts = tag.split('-');
+    let language_ok = parts
+        .next()
+        .is_some_and(|l| (2..=8).contains(&l.len()) && l.chars().all(|c| c.is_ascii_alphabetic()));
+    language_ok
+        && parts.all(|p| (1..=8).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// IANA-style name (`America/New_York`, `UTC`) or offset (`+05:30`).
+fn is_timezone(tz: &str) -> bool {
+    let starts_ok = tz
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '+' || c == '-');
+    starts_ok
+        && tz
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '-' | '+' | ':'))
+}
+
+fn is_preference_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_PROFILE_FIELD_LENGTH
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// Peer profiles backed by sled.
+pub struct PeerProfileStore {
+    tree: sled::Tree,
+    /// Serializes read-modify-write updates.
+    writes: Mutex<()>,
+}
+
+impl PeerProfileStore {
+    /// Open or create a store at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database cannot be opened.
+    pub fn open(path: &Path) -> Result<Self, PeerProfileError> {
+        let db = sled::open(path)?;
+        Ok(Self {
+            tree: db.open_tree("peers")?,
+            writes: Mutex::new(()),
+        })
+    }
+
+    fn key(channel: &str, peer_id: &str) -> Vec<u8> {
+        format!("{channel}\0{peer_id}").into_bytes()
+    }
+
+    /// Profile of `peer_id` on `channel`, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the profile cannot be read.
+    pub fn get(
+        &self,
+        channel: &str,
+        peer_id: &str,
+    ) -> Result<Option<PeerProfile>, PeerProfileError> {
+        self.tree
+            .get(Self::key(channel, peer_id))?
+            .map(|value| serde_json::from_slice(&value))
+            .transpose()
+            .map_err(PeerProfileError::from)
+    }
+
+    /// All profiles, or those on `channel`, ordered by channel and peer.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the profiles cannot be read.
+    pub fn list(&self, channel: Option<&str>) -> Result<Vec<PeerProfile>, PeerProfileError> {
+        let prefix = channel.map(|c| format!("{c}\0")).unwrap_or_default();
+        self.tree
+            .scan_prefix(prefix)
+            .map(|entry| Ok(serde_json::from_slice(&entry?.1)?))
+            .collect()
+    }
+
+    /// Apply `update` from `source` to a peer's profile, creating it if
+    /// needed, and return the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Invalid` if the update fails validation, or an error if
+    /// the profile cannot be stored.
+    pub fn update(
+        &self,
+        channel: &str,
+        peer_id: &str,
+        update: &ProfileUpdate,
+        source: ProfileSource,
+    ) -> Result<PeerProfile, PeerProfileError> {
+        update.validate()?;
+        let _guard = self
+            .writes
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let mut profile = self
+            .get(channel, peer_id)?
+            .unwrap_or_else(|| PeerProfile::new(channel, peer_id));
+        if profile.apply(update, source).is_empty() {
+            return Ok(profile);
+        }
+        if profile.preferences.len() > MAX_PROFILE_PREFERENCES {
+            return Err(PeerProfileError::Invalid(format!(
+                "more than {MAX_PROFILE_PREFERENCES} preferences"
+            )));
+        }
+        let key = Self::key(channel, peer_id);
+        if profile.is_empty() {
+            self.tree.remove(key)?;
+        } else {
+            self.tree.insert(key, serde_json::to_vec(&profile)?)?;
+        }
+        self.tree.flush()?;
+        Ok(profile)
+    }
+
+    /// Delete a peer's profile, returning whether it existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the profile cannot be removed.
+    pub fn delete(&self, channel: &str, peer_id: &str) -> Result<bool, PeerProfileError> {
+        let existed = self.tree.remove(Self::key(channel, peer_id))?.is_some();
+        self.tree.flush()?;
+        Ok(existed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn update(display_name: &str) -> ProfileUpdate {
+        ProfileUpdate {
+            display_name: Some(display_name.to_string()),
+            ..ProfileUpdate::default()
+        }
+    }
+
+    #[test]
+    fn test_learned_values_keep_explicit_ones() {
+        let mut profile = PeerProfile::new("telegram", "42");
+
+        assert_eq!(
+            profile.apply(&update("Ada"), ProfileSource::Learned),
+            ["display_name"]
+        );
+        assert_eq!(profile.sources["display_name"], ProfileSource::Learned);
+
+        profile.apply(&update("Ada Lovelace"), ProfileSource::Explicit);
+        assert!(
+            profile
+                .apply(&update("Countess"), ProfileSource::Learned)
+                .is_empty()
+        );
+        assert_eq!(profile.display_name.as_deref(), Some("Ada Lovelace"));
+
+        // Clearing a field drops its source
+        profile.apply(&update(""), ProfileSource::Explicit);
+        assert!(profile.display_name.is_none());
+        assert!(profile.sources.is_empty());
+    }
+
+    #[test]
+    fn test_validate_update() {
+        let valid = ProfileUpdate {
+            language: Some("pt-BR".to_string()),
+            timezone: Some("America/Sao_Paulo".to_string()),
+            preferences: BTreeMap::from([("brief_replies".to_string(), Some(true))]),
+            ..ProfileUpdate::default()
+        };
+        assert!(valid.validate().is_ok());
+
+        for invalid in [
+            ProfileUpdate {
+                language: Some("portuguese please".to_string()),
+                ..ProfileUpdate::default()
+            },
+            ProfileUpdate {
+                timezone: Some("../etc".to_string()),
+                ..ProfileUpdate::default()
+            },
+            ProfileUpdate {
+                preferences: BTreeMap::from([("no spaces".to_string(), Some(true))]),
+                ..ProfileUpdate::default()
+            },
+        ] {
+            assert!(matches!(
+                invalid.validate(),
+                Err(PeerProfileError::Invalid(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let temp = tempdir().unwrap();
+        let store = PeerProfileStore::open(temp.path()).unwrap();
+
+        let profile = store
+            .update(
+                "telegram",
+                "42",
+                &ProfileUpdate {
+                    language: Some("de".to_string()),
+                    preferences: BTreeMap::from([("emoji".to_string(), Some(false))]),
+                    ..update("Ada")
+                },
+                ProfileSource::Explicit,
+            )
+            .unwrap();
+        store
+            .update("slack", "U1", &update("Grace"), ProfileSource::Learned)
+            .unwrap();
+
+        assert_eq!(store.get("telegram", "42").unwrap(), Some(profile.clone()));
+        assert_eq!(store.list(None).unwrap().len(), 2);
+        assert_eq!(store.list(Some("slack")).unwrap()[0].peer_id, "U1");
+        let prompt = profile.prompt().unwrap();
+        assert!(prompt.contains("Name: Ada\nLanguage: de"));
+        assert!(prompt.contains("Preferences: emoji: no"));
+
+        assert!(store.delete("slack", "U1").unwrap());
+        assert!(!store.delete("slack", "U1").unwrap());
+        assert!(store.get("slack", "U1").unwrap().is_none());
+    }
+}
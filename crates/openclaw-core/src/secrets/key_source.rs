@@ -0,0 +1,398 @@
+//! Where the master key is kept.
+//!
+//! - `FileKeySource`: hex key file in the state directory (default)
+//! - `KeychainKeySource`: macOS Keychain, Linux Secret Service, or Windows
+//!   Credential Manager (requires the `keychain` feature; falls back to the
+//!   key file when no credential store is reachable)
+//! - `PassphraseKeySource`: key file wrapped with an Argon2id-derived key
+
+use std::path::{Path, PathBuf};
+
+use argon2::Argon2;
+use secrecy::{ExposeSecret, SecretString};
+use zeroize::Zeroize;
+
+use super::{CredentialError, EncryptionKey, MasterKey};
+use crate::config::MasterKeySource;
+
+/// Environment variable holding the passphrase for `PassphraseKeySource`.
+pub const MASTER_PASSPHRASE_ENV: &str = "OPENCLAW_MASTER_PASSPHRASE";
+
+/// Salt length for passphrase-wrapped keys.
+const SALT_LEN: usize = 16;
+
+/// Storage backend for the master key.
+pub trait KeySource: Send + Sync {
+    /// Backend name for messages (e.g., `keychain`).
+    fn name(&self) -> &'static str;
+
+    /// Load the stored key, or `None` if no key has been stored yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend is unavailable or the key is unreadable.
+    fn load(&self) -> Result<Option<MasterKey>, CredentialError>;
+
+    /// Store `key`, replacing any existing key.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend is unavailable or the write fails.
+    fn store(&self, key: &MasterKey) -> Result<(), CredentialError>;
+
+    /// Load the stored key, generating and storing a new one if none exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the key cannot be loaded or stored.
+    fn load_or_create(&self) -> Result<MasterKey, CredentialError> {
+        if let Some(key) = self.load()? {
+            return Ok(key);
+        }
+
+        let key = MasterKey::generate();
+        self.store(&key)?;
+        tracing::info!("Generated new master key ({})", self.name());
+        Ok(key)
+    }
+}
+
+/// Build the key source selected by `settings.masterKeySource`.
+///
+/// The passphrase source uses `passphrase`, falling back to
+/// `OPENCLAW_MASTER_PASSPHRASE`. The keychain source falls back to the key
+/// file when the OS credential store can't be reached (e.g., a headless
+/// Linux host without a Secret Service).
+///
+/// # Errors
+///
+/// Returns `KeySource` if the passphrase source is selected and no
+/// passphrase is available.
+pub fn key_source(
+    source: MasterKeySource,
+    passphrase: Option<SecretString>,
+) -> Result<Box<dyn KeySource>, CredentialError> {
+    Ok(match source {
+        MasterKeySource::File => Box::new(FileKeySource::default()),
+        MasterKeySource::Keychain => {
+            let keychain = KeychainKeySource::default();
+            if keychain.is_available() {
+                Box::new(keychain)
+            } else {
+                tracing::warn!("OS credential store unavailable, keeping the master key in a file");
+                Box::new(FileKeySource::default())
+            }
+        }
+        MasterKeySource::Passphrase => {
+            let passphrase = passphrase
+                .or_else(|| std::env::var(MASTER_PASSPHRASE_ENV).ok().map(Into::into))
+                .ok_or_else(|| {
+                    CredentialError::KeySource(format!(
+                        "a passphrase is required; set {MASTER_PASSPHRASE_ENV}"
+                    ))
+                })?;
+            Box::new(PassphraseKeySource::new(
+                passphrase,
+                PassphraseKeySource::default_path(),
+            ))
+        }
+    })
+}
+
+/// Master key stored as hex in a file (permissions 0600 on Unix).
+#[derive(Debug, Clone)]
+pub struct FileKeySource {
+    path: PathBuf,
+}
+
+impl FileKeySource {
+    /// Key file at `path`.
+    #[must_use]
+    pub const fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl Default for FileKeySource {
+    fn default() -> Self {
+        Self::new(MasterKey::default_path())
+    }
+}
+
+impl KeySource for FileKeySource {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn load(&self) -> Result<Option<MasterKey>, CredentialError> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let mut hex = std::fs::read_to_string(&self.path)?;
+        let key = MasterKey::from_hex(&hex);
+        hex.zeroize();
+        key.map(Some)
+    }
+
+    fn store(&self, key: &MasterKey) -> Result<(), CredentialError> {
+        key.save(&self.path)
+    }
+}
+
+/// Master key stored in the OS credential store: macOS Keychain, Linux
+/// Secret Service (libsecret), or Windows Credential Manager.
+#[derive(Debug, Clone)]
+pub struct KeychainKeySource {
+    service: String,
+    account: String,
+}
+
+impl KeychainKeySource {
+    /// Entry for `account` under `service`.
+    #[must_use]
+    pub fn new(service: impl Into<String>, account: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+            account: account.into(),
+        }
+    }
+}
+
+impl Default for KeychainKeySource {
+    fn default() -> Self {
+        Self::new("openclaw", "master-key")
+    }
+}
+
+#[cfg(feature = "keychain")]
+impl KeychainKeySource {
+    fn entry(&self) -> Result<keyring::Entry, CredentialError> {
+        keyring::Entry::new(&self.service, &self.account)
+            .map_err(|e| CredentialError::KeySource(format!("keychain: {e}")))
+    }
+
+    /// Whether the OS credential store can be reached.
+    #[must_use]
+    pub fn is_available(&self) -> bool {
+        let Ok(entry) = keyring::Entry::new(&self.service, &self.account) else {
+            return false;
+        };
+        !matches!(
+            entry.get_password(),
+            Err(keyring::Error::PlatformFailure(_) | keyring::Error::NoStorageAccess(_))
+        )
+    }
+}
+
+#[cfg(feature = "keychain")]
+impl KeySource for KeychainKeySource {
+    fn name(&self) -> &'static str {
+        "keychain"
+    }
+
+    fn load(&self) -> Result<Option<MasterKey>, CredentialError> {
+        match self.entry()?.get_password() {
+            Ok(mut hex) => {
+                let key = MasterKey::from_hex(&hex);
+                hex.zeroize();
+                key.map(Some)
+            }
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(CredentialError::KeySource(format!("keychain: {e}"))),
+        }
+    }
+
+    fn store(&self, key: &MasterKey) -> Result<(), CredentialError> {
+        let mut hex = key.to_hex();
+        let stored = self.entry()?.set_password(&hex);
+        hex.zeroize();
+        stored.map_err(|e| CredentialError::KeySource(format!("keychain: {e}")))
+    }
+}
+
+#[cfg(not(feature = "keychain"))]
+impl KeySource for KeychainKeySource {
+    fn name(&self) -> &'static str {
+        "keychain"
+    }
+
+    fn load(&self) -> Result<Option<MasterKey>, CredentialError> {
+        Err(self.unsupported())
+    }
+
+    fn store(&self, _key: &MasterKey) -> Result<(), CredentialError> {
+        Err(self.unsupported())
+    }
+}
+
+#[cfg(not(feature = "keychain"))]
+impl KeychainKeySource {
+    /// Whether the OS credential store can be reached (never, without the
+    /// `keychain` feature).
+    #[must_use]
+    pub const fn is_available(&self) -> bool {
+        false
+    }
+
+    fn unsupported(&self) -> CredentialError {
+        CredentialError::KeySource(format!(
+            "built without keychain support, can't reach {}/{}",
+            self.service, self.account
+        ))
+    }
+}
+
+/// Master key stored encrypted under a key derived from a passphrase with
+/// Argon2id. The file holds `salt || nonce || ciphertext`.
+pub struct PassphraseKeySource {
+    passphrase: SecretString,
+    path: PathBuf,
+}
+
+impl PassphraseKeySource {
+    /// Wrapped key file at `path`, unlocked with `passphrase`.
+    #[must_use]
+    pub const fn new(passphrase: SecretString, path: PathBuf) -> Self {
+        Self { passphrase, path }
+    }
+
+    /// Path of the wrapped key file in the state directory.
+    #[must_use]
+    pub fn default_path() -> PathBuf {
+        crate::Config::state_dir().join("master.key.enc")
+    }
+
+    fn wrapping_key(&self, salt: &[u8]) -> Result<EncryptionKey, CredentialError> {
+        let mut bytes = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(self.passphrase.expose_secret().as_bytes(), salt, &mut bytes)
+            .map_err(|e| CredentialError::KeySource(format!("argon2: {e}")))?;
+        let key = EncryptionKey::new(bytes);
+        bytes.zeroize();
+        Ok(key)
+    }
+}
+
+impl std::fmt::Debug for PassphraseKeySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PassphraseKeySource")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl KeySource for PassphraseKeySource {
+    fn name(&self) -> &'static str {
+        "passphrase"
+    }
+
+    fn load(&self) -> Result<Option<MasterKey>, CredentialError> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let data = std::fs::read(&self.path)?;
+        if data.len() < SALT_LEN {
+            return Err(CredentialError::KeySource(format!(
+                "{} is truncated",
+                self.path.display()
+            )));
+        }
+
+        let (salt, wrapped) = data.split_at(SALT_LEN);
+        let mut bytes = self
+            .wrapping_key(salt)?
+            .decrypt(wrapped)
+            .map_err(|_| CredentialError::KeySource("wrong passphrase".to_string()))?;
+        let key = <[u8; 32]>::try_from(bytes.as_slice())
+            .map(MasterKey::from_bytes)
+            .map_err(|_| CredentialError::InvalidKey("wrapped key has wrong length".to_string()));
+        bytes.zeroize();
+        key.map(Some)
+    }
+
+    fn store(&self, key: &MasterKey) -> Result<(), CredentialError> {
+        let salt: [u8; SALT_LEN] = rand::random();
+        let wrapped = self.wrapping_key(&salt)?.encrypt(key.0.expose_secret())?;
+        write_atomic(&self.path, &[salt.as_slice(), &wrapped].concat())
+    }
+}
+
+/// Replace `path` with `data` (permissions 0600 on Unix).
+fn write_atomic(path: &Path, data: &[u8]) -> Result<(), CredentialError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+
+    super::write_private(Path::new(&tmp), data)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn same_key(a: &MasterKey, b: &MasterKey) -> bool {
+        let sealed = a.derive("test").encrypt(b"data").unwrap();
+        b.derive("test").decrypt(&sealed).is_ok()
+    }
+
+    #[test]
+    fn test_file_source() {
+        let temp = tempdir().unwrap();
+        let source = FileKeySource::new(temp.path().join("master.key"));
+        assert!(source.load().unwrap().is_none());
+
+        let created = source.load_or_create().unwrap();
+        let loaded = source.load().unwrap().unwrap();
+        assert!(same_key(&created, &loaded));
+    }
+
+    #[test]
+    fn test_passphrase_source() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join("master.key.enc");
+        let source = PassphraseKeySource::new("correct horse".into(), path.clone());
+
+        let created = source.load_or_create().unwrap();
+        let loaded = source.load().unwrap().unwrap();
+        assert!(same_key(&created, &loaded));
+
+        // The file does not contain the key in the clear
+        let data = std::fs::read(&path).unwrap();
+        let raw = created.0.expose_secret();
+        assert!(!data.windows(raw.len()).any(|w| w == raw.as_slice()));
+        assert!(!data.windows(64).any(|w| w == created.to_hex().as_bytes()));
+
+        let wrong = PassphraseKeySource::new("battery staple".into(), path);
+        assert!(matches!(wrong.load(), Err(CredentialError::KeySource(_))));
+    }
+
+    #[test]
+    fn test_keychain_falls_back_to_file() {
+        if KeychainKeySource::default().is_available() {
+            return;
+        }
+        assert_eq!(
+            key_source(MasterKeySource::Keychain, None).unwrap().name(),
+            "file"
+        );
+    }
+
+    #[test]
+    fn test_passphrase_required() {
+        if std::env::var_os(MASTER_PASSPHRASE_ENV).is_some() {
+            return;
+        }
+        assert!(key_source(MasterKeySource::Passphrase, None).is_err());
+        assert_eq!(
+            key_source(MasterKeySource::Passphrase, Some("secret".into()))
+                .unwrap()
+                .name(),
+            "passphrase"
+        );
+    }
+}
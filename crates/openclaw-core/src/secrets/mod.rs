@@ -1,18 +1,40 @@
 //! Secrets management with encryption at rest.
 //!
 //! - `ApiKey`: Wrapper that prevents accidental logging
+//! - `MasterKey`: Root key from which purpose-specific keys are derived
+//! - `EncryptionKey`: AES-256-GCM key for data at rest
+//! - `KeySource`: Where the master key is kept (file, OS keychain, or
+//!   passphrase-wrapped file)
 //! - `CredentialStore`: Encrypted storage for credentials
 //! - `scrub_secrets`: Redact secrets from error messages
 
 use aes_gcm::{
     Aes256Gcm, Nonce,
-    aead::{Aead, KeyInit},
+    aead::{Aead, KeyInit, Payload},
 };
+use hmac::{Hmac, Mac};
 use secrecy::{ExposeSecret, SecretBox};
-use std::path::PathBuf;
+use sha2::Sha256;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 use zeroize::Zeroize;
 
+mod key_source;
+
+pub use key_source::{
+    FileKeySource, KeySource, KeychainKeySource, MASTER_PASSPHRASE_ENV, PassphraseKeySource,
+    key_source,
+};
+
+/// Environment variable holding the master key as 64 hex characters.
+pub const MASTER_KEY_ENV: &str = "OPENCLAW_MASTER_KEY";
+
+/// AES-GCM nonce length.
+const NONCE_LEN: usize = 12;
+
+/// Credential file format version, stored between nonce and ciphertext.
+const CREDENTIAL_FORMAT_VERSION: u8 = 1;
+
 /// Errors from credential operations.
 #[derive(Error, Debug)]
 pub enum CredentialError {
@@ -31,6 +53,14 @@ pub enum CredentialError {
     /// Credential not found.
     #[error("Credential not found: {0}")]
     NotFound(String),
+
+    /// Master key is malformed.
+    #[error("Invalid master key: {0}")]
+    InvalidKey(String),
+
+    /// Master key backend is unavailable or misconfigured.
+    #[error("Key source error: {0}")]
+    KeySource(String),
 }
 
 /// API key wrapper that prevents accidental logging.
@@ -68,11 +98,219 @@ impl std::fmt::Display for ApiKey {
     }
 }
 
+/// Root key from which purpose-specific encryption keys are derived.
+pub struct MasterKey(SecretBox<[u8; 32]>);
+
+impl MasterKey {
+    /// Wrap raw key bytes.
+    #[must_use]
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(SecretBox::new(Box::new(bytes)))
+    }
+
+    /// Parse a key from 64 hex characters.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidKey` if `hex` is not 32 hex-encoded bytes.
+    pub fn from_hex(hex: &str) -> Result<Self, CredentialError> {
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(hex.trim(), &mut bytes)
+            .map_err(|e| CredentialError::InvalidKey(e.to_string()))?;
+        Ok(Self::from_bytes(bytes))
+    }
+
+    /// Generate a random key.
+    #[must_use]
+    pub fn generate() -> Self {
+        let mut bytes: [u8; 32] = rand::random();
+        let key = Self::from_bytes(bytes);
+        bytes.zeroize();
+        key
+    }
+
+    /// Hex encoding of the key, for storing it elsewhere (e.g. in
+    /// `OPENCLAW_MASTER_KEY`). Never log the result.
+    #[must_use]
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0.expose_secret())
+    }
+
+    /// Write the key to `path` (permissions 0600 on Unix), replacing any
+    /// existing key file atomically.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<(), CredentialError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+
+        let mut hex = self.to_hex();
+        let written = write_private(Path::new(&tmp), hex.as_bytes());
+        hex.zeroize();
+        written?;
+
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Path of the master key file in the state directory.
+    #[must_use]
+    pub fn default_path() -> PathBuf {
+        crate::Config::state_dir().join("master.key")
+    }
+
+    /// Load the master key from `OPENCLAW_MASTER_KEY`, falling back to the
+    /// source selected by `settings.masterKeySource` (a key is created on
+    /// first use).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the config cannot be read, the key is malformed, or
+    /// the key source is unusable.
+    pub fn load() -> Result<Self, CredentialError> {
+        if let Some(key) = Self::from_env()? {
+            return Ok(key);
+        }
+        let source = crate::Config::load_default()
+            .map_err(|e| CredentialError::KeySource(e.to_string()))?
+            .settings
+            .master_key_source;
+        key_source(source, None)?.load_or_create()
+    }
+
+    /// Load the master key from `OPENCLAW_MASTER_KEY`, falling back to
+    /// `source`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the key is malformed or the key source is unusable.
+    pub fn load_from(source: &dyn KeySource) -> Result<Self, CredentialError> {
+        Self::from_env()?.map_or_else(|| source.load_or_create(), Ok)
+    }
+
+    fn from_env() -> Result<Option<Self>, CredentialError> {
+        std::env::var(MASTER_KEY_ENV).map_or(Ok(None), |mut hex| {
+            let key = Self::from_hex(&hex);
+            hex.zeroize();
+            key.map(Some)
+        })
+    }
+
+    /// Derive the encryption key for `purpose` (e.g., `event-store`).
+    ///
+    /// Keys for different purposes are independent; the same purpose always
+    /// yields the same key.
+    #[must_use]
+    pub fn derive(&self, purpose: &str) -> EncryptionKey {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(self.0.expose_secret())
+            .unwrap_or_else(|_| unreachable!("HMAC accepts any key length"));
+        mac.update(b"openclaw:");
+        mac.update(purpose.as_bytes());
+        EncryptionKey::new(mac.finalize().into_bytes().into())
+    }
+}
+
+impl std::fmt::Debug for MasterKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MasterKey([REDACTED])")
+    }
+}
+
+/// AES-256-GCM key for encrypting data at rest.
+///
+/// Ciphertext is the random 12-byte nonce followed by the sealed data.
+pub struct EncryptionKey(SecretBox<[u8; 32]>);
+
+impl EncryptionKey {
+    /// Wrap raw key bytes.
+    #[must_use]
+    pub fn new(bytes: [u8; 32]) -> Self {
+        Self(SecretBox::new(Box::new(bytes)))
+    }
+
+    /// Encrypt data with AES-256-GCM.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Crypto` if encryption fails.
+    pub fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, CredentialError> {
+        let (nonce, ciphertext) = self.seal(data, &[])?;
+
+        // Prepend nonce to ciphertext
+        Ok([nonce.as_slice(), &ciphertext].concat())
+    }
+
+    /// Decrypt data produced by [`EncryptionKey::encrypt`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Crypto` if the data is truncated, tampered with, or was
+    /// encrypted with a different key.
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, CredentialError> {
+        if data.len() < NONCE_LEN {
+            return Err(CredentialError::Crypto("Data too short".to_string()));
+        }
+
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        self.open(nonce, ciphertext, &[])
+    }
+
+    /// Encrypt `data` under a random nonce, authenticating `aad` with it.
+    fn seal(&self, data: &[u8], aad: &[u8]) -> Result<([u8; NONCE_LEN], Vec<u8>), CredentialError> {
+        let cipher = Aes256Gcm::new(self.0.expose_secret().into());
+        let nonce: [u8; NONCE_LEN] = rand::random();
+
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: data, aad })
+            .map_err(|e| CredentialError::Crypto(e.to_string()))?;
+        Ok((nonce, ciphertext))
+    }
+
+    /// Inverse of [`EncryptionKey::seal`].
+    fn open(
+        &self,
+        nonce: &[u8],
+        ciphertext: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CredentialError> {
+        let cipher = Aes256Gcm::new(self.0.expose_secret().into());
+
+        cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad,
+                },
+            )
+            .map_err(|e| CredentialError::Crypto(e.to_string()))
+    }
+}
+
+impl Clone for EncryptionKey {
+    fn clone(&self) -> Self {
+        Self::new(*self.0.expose_secret())
+    }
+}
+
+impl std::fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EncryptionKey([REDACTED])")
+    }
+}
+
 /// Credential storage with encryption at rest.
 ///
-/// Uses AES-256-GCM for authenticated encryption.
+/// Uses AES-256-GCM for authenticated encryption. Each credential file is
+/// `nonce || version || ciphertext`, with the version byte authenticated;
+/// files written before versioning (`nonce || ciphertext`) are still read.
 pub struct CredentialStore {
-    encryption_key: SecretBox<[u8; 32]>,
+    encryption_key: EncryptionKey,
     store_path: PathBuf,
 }
 
@@ -85,8 +323,15 @@ impl CredentialStore {
     /// * `store_path` - Directory to store encrypted credentials
     #[must_use]
     pub fn new(encryption_key: [u8; 32], store_path: PathBuf) -> Self {
+        Self::from_key(EncryptionKey::new(encryption_key), store_path)
+    }
+
+    /// Create a credential store with an existing key (e.g. one derived
+    /// from the [`MasterKey`]).
+    #[must_use]
+    pub const fn from_key(encryption_key: EncryptionKey, store_path: PathBuf) -> Self {
         Self {
-            encryption_key: SecretBox::new(Box::new(encryption_key)),
+            encryption_key,
             store_path,
         }
     }
@@ -103,19 +348,8 @@ impl CredentialStore {
         // Ensure store directory exists
         std::fs::create_dir_all(&self.store_path)?;
 
-        let encrypted = self.encrypt(credential.expose().as_bytes())?;
-
-        let path = self.store_path.join(format!("{name}.enc"));
-        std::fs::write(&path, &encrypted)?;
-
-        // Set restrictive permissions on Unix
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
-        }
-
-        Ok(())
+        let encrypted = encrypt_credential(&self.encryption_key, credential.expose().as_bytes())?;
+        write_private(&self.credential_path(name), &encrypted)
     }
 
     /// Load and decrypt a credential.
@@ -124,14 +358,14 @@ impl CredentialStore {
     ///
     /// Returns error if file not found, decryption fails, or invalid UTF-8.
     pub fn load(&self, name: &str) -> Result<ApiKey, CredentialError> {
-        let path = self.store_path.join(format!("{name}.enc"));
+        let path = self.credential_path(name);
 
         if !path.exists() {
             return Err(CredentialError::NotFound(name.to_string()));
         }
 
         let encrypted = std::fs::read(&path)?;
-        let mut decrypted = self.decrypt(&encrypted)?;
+        let mut decrypted = decrypt_credential(&self.encryption_key, &encrypted)?;
 
         let key = ApiKey::new(String::from_utf8(decrypted.clone())?);
 
@@ -147,7 +381,7 @@ impl CredentialStore {
     ///
     /// Returns error if file deletion fails.
     pub fn delete(&self, name: &str) -> Result<(), CredentialError> {
-        let path = self.store_path.join(format!("{name}.enc"));
+        let path = self.credential_path(name);
         if path.exists() {
             std::fs::remove_file(&path)?;
         }
@@ -176,41 +410,135 @@ impl CredentialStore {
         Ok(names)
     }
 
-    /// Encrypt data with AES-256-GCM.
-    fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, CredentialError> {
-        let cipher = Aes256Gcm::new(self.encryption_key.expose_secret().into());
+    /// Re-encrypt every stored credential from `old_key` to `new_key`.
+    ///
+    /// Credentials are re-encrypted into a staging directory that then
+    /// replaces the store, so a wrong `old_key` or a failed write leaves
+    /// the store unchanged. On success the store uses `new_key`. Returns
+    /// the number of credentials re-encrypted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Crypto` if a credential cannot be decrypted with `old_key`,
+    /// or an IO error if the store cannot be rewritten.
+    pub fn rotate_key(
+        &mut self,
+        old_key: &EncryptionKey,
+        new_key: EncryptionKey,
+    ) -> Result<usize, CredentialError> {
+        let names = self.list()?;
+        if names.is_empty() {
+            self.encryption_key = new_key;
+            return Ok(0);
+        }
 
-        // Generate random 12-byte nonce
-        let nonce_bytes: [u8; 12] = rand::random();
-        let nonce = Nonce::from_slice(&nonce_bytes);
+        let staging = self.sibling_path("rotating");
+        if staging.exists() {
+            std::fs::remove_dir_all(&staging)?;
+        }
+        if let Err(e) = self.stage_rotation(&staging, old_key, &new_key) {
+            let _ = std::fs::remove_dir_all(&staging);
+            return Err(e);
+        }
 
-        let ciphertext = cipher
-            .encrypt(nonce, data)
-            .map_err(|e| CredentialError::Crypto(e.to_string()))?;
+        let previous = self.sibling_path("previous");
+        if previous.exists() {
+            std::fs::remove_dir_all(&previous)?;
+        }
+        std::fs::rename(&self.store_path, &previous)?;
+        if let Err(e) = std::fs::rename(&staging, &self.store_path) {
+            let _ = std::fs::rename(&previous, &self.store_path);
+            return Err(e.into());
+        }
+        std::fs::remove_dir_all(&previous)?;
 
-        // Prepend nonce to ciphertext
-        Ok([nonce_bytes.as_slice(), &ciphertext].concat())
+        self.encryption_key = new_key;
+        Ok(names.len())
     }
 
-    /// Decrypt data with AES-256-GCM.
-    fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, CredentialError> {
-        if data.len() < 12 {
-            return Err(CredentialError::Crypto("Data too short".to_string()));
+    /// Write every file in the store to `staging`, re-encrypting
+    /// credentials under `new_key`.
+    fn stage_rotation(
+        &self,
+        staging: &Path,
+        old_key: &EncryptionKey,
+        new_key: &EncryptionKey,
+    ) -> Result<(), CredentialError> {
+        std::fs::create_dir_all(staging)?;
+
+        for entry in std::fs::read_dir(&self.store_path)? {
+            let entry = entry?;
+            let target = staging.join(entry.file_name());
+            let is_credential = entry.file_name().to_str().is_some_and(|name| {
+                Path::new(name)
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("enc"))
+            });
+
+            if !is_credential {
+                std::fs::copy(entry.path(), &target)?;
+                continue;
+            }
+
+            let mut plaintext = decrypt_credential(old_key, &std::fs::read(entry.path())?)?;
+            let encrypted = encrypt_credential(new_key, &plaintext);
+            plaintext.zeroize();
+            write_private(&target, &encrypted?)?;
         }
+        Ok(())
+    }
 
-        let (nonce_bytes, ciphertext) = data.split_at(12);
-        let cipher = Aes256Gcm::new(self.encryption_key.expose_secret().into());
-        let nonce = Nonce::from_slice(nonce_bytes);
+    fn credential_path(&self, name: &str) -> PathBuf {
+        self.store_path.join(format!("{name}.enc"))
+    }
 
-        cipher
-            .decrypt(nonce, ciphertext)
-            .map_err(|e| CredentialError::Crypto(e.to_string()))
+    fn sibling_path(&self, suffix: &str) -> PathBuf {
+        let mut path = self.store_path.clone().into_os_string();
+        path.push(format!(".{suffix}"));
+        path.into()
     }
 }
 
+/// Encrypt a credential in the versioned file format.
+fn encrypt_credential(key: &EncryptionKey, data: &[u8]) -> Result<Vec<u8>, CredentialError> {
+    let version = [CREDENTIAL_FORMAT_VERSION];
+    let (nonce, ciphertext) = key.seal(data, &version)?;
+    Ok([nonce.as_slice(), &version, &ciphertext].concat())
+}
+
+/// Decrypt a credential file, accepting the unversioned legacy format.
+fn decrypt_credential(key: &EncryptionKey, data: &[u8]) -> Result<Vec<u8>, CredentialError> {
+    if data.get(NONCE_LEN) == Some(&CREDENTIAL_FORMAT_VERSION) {
+        let (nonce, rest) = data.split_at(NONCE_LEN);
+        let (version, ciphertext) = rest.split_at(1);
+        let decrypted = key.open(nonce, ciphertext, version);
+        if decrypted.is_ok() {
+            return decrypted;
+        }
+    }
+
+    // A legacy file whose ciphertext happens to start with the version byte
+    // fails the versioned check above and is read here.
+    key.decrypt(data)
+}
+
+/// Write `data` to `path` with restrictive permissions (0600 on Unix).
+fn write_private(path: &Path, data: &[u8]) -> Result<(), CredentialError> {
+    std::fs::write(path, data)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    Ok(())
+}
+
 /// Scrub secrets from error messages and logs.
 ///
-/// Replaces values after known secret patterns with `[REDACTED]`.
+/// Replaces values after known secret patterns with `[REDACTED]`. Quoted
+/// values (`token="abc"`) are redacted up to the closing quote.
 ///
 /// # Arguments
 ///
@@ -225,22 +553,49 @@ pub fn scrub_secrets(text: &str, patterns: &[&str]) -> String {
         let mut search_start = 0;
         while let Some(start) = result[search_start..].find(pattern) {
             let abs_start = search_start + start + pattern.len();
-
-            // Find the end of the value (whitespace, quote, or end of string)
-            let end = result[abs_start..]
-                .find(|c: char| c.is_whitespace() || c == '"' || c == '\'' || c == '&' || c == ',')
-                .map_or(result.len(), |e| abs_start + e);
+            let quote = result[abs_start..]
+                .chars()
+                .next()
+                .filter(|c| matches!(c, '"' | '\''));
+            let value_start = abs_start + quote.map_or(0, char::len_utf8);
+
+            // Find the end of the value (closing quote, or whitespace, quote,
+            // or end of string)
+            let rest = &result[value_start..];
+            let end = quote
+                .map_or_else(
+                    || {
+                        rest.find(|c: char| {
+                            c.is_whitespace() || c == '"' || c == '\'' || c == '&' || c == ','
+                        })
+                    },
+                    |quote| closing_quote(rest, quote),
+                )
+                .map_or(result.len(), |e| value_start + e);
 
             // Replace the value with [REDACTED]
-            result.replace_range(abs_start..end, "[REDACTED]");
+            result.replace_range(value_start..end, "[REDACTED]");
 
-            search_start = abs_start + "[REDACTED]".len();
+            search_start = value_start + "[REDACTED]".len();
         }
     }
 
     result
 }
 
+/// Offset of the first unescaped `quote` in `text`.
+fn closing_quote(text: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            c if c == quote && !escaped => return Some(i),
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
 /// Common secret patterns to scrub from logs.
 pub const COMMON_SECRET_PATTERNS: &[&str] = &[
     "api_key=",
@@ -313,6 +668,69 @@ mod tests {
         assert!(names.contains(&"cred2".to_string()));
     }
 
+    #[test]
+    fn test_credential_format_versioned() {
+        let temp = tempdir().unwrap();
+        let key = EncryptionKey::new(rand::random());
+        let store = CredentialStore::from_key(key.clone(), temp.path().to_path_buf());
+        store
+            .store("cred", &ApiKey::new("value".to_string()))
+            .unwrap();
+
+        let data = std::fs::read(temp.path().join("cred.enc")).unwrap();
+        assert_eq!(data[NONCE_LEN], CREDENTIAL_FORMAT_VERSION);
+
+        // Files from before versioning still load
+        std::fs::write(temp.path().join("legacy.enc"), key.encrypt(b"old").unwrap()).unwrap();
+        assert_eq!(store.load("legacy").unwrap().expose(), "old");
+    }
+
+    #[test]
+    fn test_rotate_key() {
+        let temp = tempdir().unwrap();
+        let dir = temp.path().join("credentials");
+        let old_key = EncryptionKey::new(rand::random());
+        let new_key = EncryptionKey::new(rand::random());
+        let mut store = CredentialStore::from_key(old_key.clone(), dir.clone());
+        store
+            .store("cred1", &ApiKey::new("value1".to_string()))
+            .unwrap();
+        store
+            .store("cred2", &ApiKey::new("value2".to_string()))
+            .unwrap();
+
+        // A wrong old key leaves the store untouched
+        let wrong = EncryptionKey::new(rand::random());
+        assert!(store.rotate_key(&wrong, new_key.clone()).is_err());
+        assert_eq!(store.load("cred1").unwrap().expose(), "value1");
+
+        assert_eq!(store.rotate_key(&old_key, new_key.clone()).unwrap(), 2);
+        assert_eq!(store.load("cred2").unwrap().expose(), "value2");
+        assert!(!temp.path().join("credentials.rotating").exists());
+        assert!(!temp.path().join("credentials.previous").exists());
+
+        let stale = CredentialStore::from_key(old_key, dir.clone());
+        assert!(stale.load("cred1").is_err());
+        let fresh = CredentialStore::from_key(new_key, dir);
+        assert_eq!(fresh.load("cred1").unwrap().expose(), "value1");
+    }
+
+    #[test]
+    fn test_master_key_derivation() {
+        let master = MasterKey::from_hex(&"ab".repeat(32)).unwrap();
+        let events = master.derive("event-store");
+
+        let sealed = events.encrypt(b"hello").unwrap();
+        assert_eq!(
+            master.derive("event-store").decrypt(&sealed).unwrap(),
+            b"hello"
+        );
+        assert!(master.derive("credentials").decrypt(&sealed).is_err());
+
+        assert!(MasterKey::from_hex("not-hex").is_err());
+        assert_eq!(format!("{master:?}"), "MasterKey([REDACTED])");
+    }
+
     #[test]
     fn test_scrub_secrets() {
         let text = "Error: api_key=sk-12345 failed with token=abc123";
@@ -323,6 +741,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_scrub_quoted_values() {
+        let text = r#"request failed token="abc \"def\" ghi" status=401"#;
+        assert_eq!(
+            scrub_secrets(text, &["token="]),
+            r#"request failed token="[REDACTED]" status=401"#
+        );
+    }
+
     #[test]
     fn test_scrub_secrets_with_quotes() {
         let text = r#"{"api_key":"sk-secret","other":"value"}"#;
@@ -0,0 +1,470 @@
+//! Object storage for blobs (attachments, backups, archives).
+//!
+//! Wraps the `object_store` crate so the same API works against a local
+//! directory or any S3-compatible service (AWS S3, `MinIO`, R2). Configured
+//! once via `GlobalSettings::storage`.
+
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::TryStreamExt;
+use object_store::aws::AmazonS3Builder;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore, PutPayload};
+use thiserror::Error;
+
+use crate::config::{Config, S3StorageConfig, StorageConfig};
+use crate::secrets::{CredentialError, CredentialStore};
+
+/// Object storage errors.
+#[derive(Error, Debug)]
+pub enum StorageError {
+    /// Backend error.
+    #[error("Object store error: {0}")]
+    Backend(#[from] object_store::Error),
+
+    /// IO error preparing local storage.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Credential lookup failed.
+    #[error("Credential error: {0}")]
+    Credential(#[from] CredentialError),
+
+    /// Invalid object key.
+    #[error("Invalid key: {0}")]
+    InvalidKey(String),
+
+    /// Object not found.
+    #[error("Object not found: {0}")]
+    NotFound(String),
+}
+
+/// Logical namespaces within the blob store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobNamespace {
+    /// Inbound and outbound message attachments.
+    Attachments,
+    /// State backups.
+    Backups,
+    /// Archived sessions.
+    Archives,
+}
+
+impl BlobNamespace {
+    /// Key prefix for this namespace.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Attachments => "attachments",
+            Self::Backups => "backups",
+            Self::Archives => "archives",
+        }
+    }
+}
+
+/// Metadata about a stored object.
+#[derive(Debug, Clone)]
+pub struct BlobMeta {
+    /// Key relative to the namespace.
+    pub key: String,
+    /// Size in bytes.
+    pub size: u64,
+    /// Last modification time.
+    pub last_modified: chrono::DateTime<chrono::Utc>,
+}
+
+/// Blob store backed by a local directory or S3-compatible service.
+#[derive(Clone)]
+pub struct BlobStore {
+    inner: Arc<dyn ObjectStore>,
+    prefix: Option<String>,
+}
+
+impl std::fmt::Debug for BlobStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlobStore")
+            .field("backend", &self.inner.to_string())
+            .field("prefix", &self.prefix)
+            .finish()
+    }
+}
+
+impl BlobStore {
+    /// Create a blob store rooted at a local directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the directory cannot be created.
+    pub fn local(root: &Path) -> Result<Self, StorageError> {
+        std::fs::create_dir_all(root)?;
+        let store = LocalFileSystem::new_with_prefix(root)?;
+        Ok(Self {
+            inner: Arc::new(store),
+            prefix: None,
+        })
+    }
+
+    /// Create a blob store from any `object_store` implementation.
+    #[must_use]
+    pub fn from_object_store(inner: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            inner,
+            prefix: None,
+        }
+    }
+
+    /// Create a blob store from configuration.
+    ///
+    /// S3 credentials are loaded from `credentials` when the config names
+    /// them, otherwise from the standard `AWS_*` environment variables.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend cannot be initialized.
+    pub fn from_config(
+        config: &StorageConfig,
+        credentials: Option<&CredentialStore>,
+    ) -> Result<Self, StorageError> {
+        match config {
+            StorageConfig::Local => Self::local(&Config::blobs_dir()),
+            StorageConfig::Dir { path } => Self::local(path),
+            StorageConfig::S3(s3) => Self::s3(s3, credentials),
+        }
+    }
+
+    fn s3(
+        config: &S3StorageConfig,
+        credentials: Option<&CredentialStore>,
+    ) -> Result<Self, StorageError> {
+        let mut builder = AmazonS3Builder::from_env()
+            .with_bucket_name(&config.bucket)
+            .with_region(&config.region)
+            .with_allow_http(config.allow_http);
+
+        if let Some(endpoint) = &config.endpoint {
+            builder = builder.with_endpoint(endpoint);
+        }
+
+        if let Some(store) = credentials {
+            if let Some(name) = &config.access_key_id_credential {
+                builder = builder.with_access_key_id(store.load(name)?.expose());
+            }
+            if let Some(name) = &config.secret_access_key_credential {
+                builder = builder.with_secret_access_key(store.load(name)?.expose());
+            }
+        }
+
+        Ok(Self {
+            inner: Arc::new(builder.build()?),
+            prefix: config.prefix.clone(),
+        })
+    }
+
+    /// Build the full object path for a namespaced key.
+    fn path(&self, namespace: BlobNamespace, key: &str) -> Result<ObjectPath, StorageError> {
+        if key.is_empty() || key.split('/').any(|seg| seg.is_empty() || seg == "..") {
+            return Err(StorageError::InvalidKey(key.to_string()));
+        }
+
+        let full = self.prefix.as_ref().map_or_else(
+            || format!("{}/{key}", namespace.as_str()),
+            |prefix| format!("{}/{}/{key}", prefix.trim_matches('/'), namespace.as_str()),
+        );
+
+        ObjectPath::parse(full).map_err(|e| StorageError::InvalidKey(e.to_string()))
+    }
+
+    fn namespace_root(&self, namespace: BlobNamespace) -> ObjectPath {
+        self.prefix.as_ref().map_or_else(
+            || ObjectPath::from(namespace.as_str()),
+            |prefix| {
+                ObjectPath::from(format!(
+                    "{}/{}",
+                    prefix.trim_matches('/'),
+                    namespace.as_str()
+                ))
+            },
+        )
+    }
+
+    /// Store an object, overwriting any existing one.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the key is invalid or the write fails.
+    pub async fn put(
+        &self,
+        namespace: BlobNamespace,
+        key: &str,
+        data: impl Into<Bytes> + Send,
+    ) -> Result<(), StorageError> {
+        let path = self.path(namespace, key)?;
+        self.inner
+            .put(&path, PutPayload::from_bytes(data.into()))
+            .await?;
+        Ok(())
+    }
+
+    /// Fetch an object.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if the object does not exist.
+    pub async fn get(&self, namespace: BlobNamespace, key: &str) -> Result<Bytes, StorageError> {
+        let path = self.path(namespace, key)?;
+        match self.inner.get(&path).await {
+            Ok(result) => Ok(result.bytes().await?),
+            Err(object_store::Error::NotFound { .. }) => {
+                Err(StorageError::NotFound(key.to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Check whether an object exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend cannot be queried.
+    pub async fn exists(&self, namespace: BlobNamespace, key: &str) -> Result<bool, StorageError> {
+        let path = self.path(namespace, key)?;
+        match self.inner.head(&path).await {
+            Ok(_) => Ok(true),
+            Err(object_store::Error::NotFound { .. }) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Delete an object. Missing objects are not an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the delete fails.
+    pub async fn delete(&self, namespace: BlobNamespace, key: &str) -> Result<(), StorageError> {
+        let path = self.path(namespace, key)?;
+        match self.inner.delete(&path).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// List objects in a namespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if listing fails.
+    pub async fn list(&self, namespace: BlobNamespace) -> Result<Vec<BlobMeta>, StorageError> {
+        let root = self.namespace_root(namespace);
+        let root_str = format!("{root}/");
+
+        let objects: Vec<_> = self.inner.list(Some(&root)).try_collect().await?;
+
+        let mut metas: Vec<BlobMeta> = objects
+            .into_iter()
+            .map(|meta| BlobMeta {
+                key: meta
+                    .location
+                    .as_ref()
+                    .strip_prefix(&root_str)
+                    .unwrap_or_else(|| meta.location.as_ref())
+                    .to_string(),
+                size: meta.size as u64,
+                last_modified: meta.last_modified,
+            })
+            .collect();
+
+        metas.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(metas)
+    }
+
+    /// Synchronous access to this store (see [`BlockingBlobStore`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the runtime serving requests cannot be started.
+    pub fn blocking(&self) -> Result<BlockingBlobStore, StorageError> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("openclaw-blobs")
+            .enable_all()
+            .build()?;
+        Ok(BlockingBlobStore {
+            blobs: self.clone(),
+            runtime: Arc::new(BlobRuntime(Some(runtime))),
+        })
+    }
+}
+
+/// Blocking handle to a [`BlobStore`], for synchronous code such as the
+/// event store.
+///
+/// Requests run on a small runtime of its own, so the handle can be used
+/// from any thread, including one already driving an async runtime.
+#[derive(Debug, Clone)]
+pub struct BlockingBlobStore {
+    blobs: BlobStore,
+    runtime: Arc<BlobRuntime>,
+}
+
+/// Runtime serving a [`BlockingBlobStore`]. Shut down without waiting, as
+/// the last handle may be dropped inside async code.
+#[derive(Debug)]
+struct BlobRuntime(Option<tokio::runtime::Runtime>);
+
+impl Drop for BlobRuntime {
+    fn drop(&mut self) {
+        if let Some(runtime) = self.0.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+impl BlockingBlobStore {
+    /// See [`BlobStore::put`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the key is invalid or the write fails.
+    pub fn put(
+        &self,
+        namespace: BlobNamespace,
+        key: &str,
+        data: impl Into<Bytes>,
+    ) -> Result<(), StorageError> {
+        let (blobs, key, data) = (self.blobs.clone(), key.to_string(), data.into());
+        self.block_on(async move { blobs.put(namespace, &key, data).await })
+    }
+
+    /// See [`BlobStore::get`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if the object does not exist.
+    pub fn get(&self, namespace: BlobNamespace, key: &str) -> Result<Bytes, StorageError> {
+        let (blobs, key) = (self.blobs.clone(), key.to_string());
+        self.block_on(async move { blobs.get(namespace, &key).await })
+    }
+
+    /// See [`BlobStore::delete`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the delete fails.
+    pub fn delete(&self, namespace: BlobNamespace, key: &str) -> Result<(), StorageError> {
+        let (blobs, key) = (self.blobs.clone(), key.to_string());
+        self.block_on(async move { blobs.delete(namespace, &key).await })
+    }
+
+    fn block_on<T, F>(&self, request: F) -> Result<T, StorageError>
+    where
+        T: Send + 'static,
+        F: Future<Output = Result<T, StorageError>> + Send + 'static,
+    {
+        let Some(runtime) = &self.runtime.0 else {
+            return Err(std::io::Error::other("blob runtime stopped").into());
+        };
+        let (tx, rx) = std::sync::mpsc::sync_channel(1);
+        runtime.spawn(async move {
+            let _ = tx.send(request.await);
+        });
+        rx.recv()
+            .map_err(|_| std::io::Error::other("blob request was dropped"))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_local_roundtrip() {
+        let temp = tempdir().unwrap();
+        let store = BlobStore::local(&temp.path().join("blobs")).unwrap();
+
+        store
+            .put(BlobNamespace::Attachments, "a/photo.jpg", b"jpeg".to_vec())
+            .await
+            .unwrap();
+
+        let data = store
+            .get(BlobNamespace::Attachments, "a/photo.jpg")
+            .await
+            .unwrap();
+        assert_eq!(&data[..], b"jpeg");
+        assert!(
+            store
+                .exists(BlobNamespace::Attachments, "a/photo.jpg")
+                .await
+                .unwrap()
+        );
+        assert!(
+            !store
+                .exists(BlobNamespace::Backups, "a/photo.jpg")
+                .await
+                .unwrap()
+        );
+
+        let listed = store.list(BlobNamespace::Attachments).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].key, "a/photo.jpg");
+        assert_eq!(listed[0].size, 4);
+
+        store
+            .delete(BlobNamespace::Attachments, "a/photo.jpg")
+            .await
+            .unwrap();
+        assert!(matches!(
+            store.get(BlobNamespace::Attachments, "a/photo.jpg").await,
+            Err(StorageError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_blocking_roundtrip() {
+        let temp = tempdir().unwrap();
+        let store = BlobStore::local(temp.path()).unwrap();
+        let blocking = store.blocking().unwrap();
+
+        // Usable from inside a runtime, even a single-threaded one
+        blocking
+            .put(BlobNamespace::Archives, "s/1.jsonl.zst", b"events".to_vec())
+            .unwrap();
+        assert_eq!(
+            &blocking
+                .get(BlobNamespace::Archives, "s/1.jsonl.zst")
+                .unwrap()[..],
+            b"events"
+        );
+        assert!(
+            store
+                .exists(BlobNamespace::Archives, "s/1.jsonl.zst")
+                .await
+                .unwrap()
+        );
+
+        blocking
+            .delete(BlobNamespace::Archives, "s/1.jsonl.zst")
+            .unwrap();
+        assert!(matches!(
+            blocking.get(BlobNamespace::Archives, "s/1.jsonl.zst"),
+            Err(StorageError::NotFound(_))
+        ));
+        drop(blocking);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_traversal() {
+        let temp = tempdir().unwrap();
+        let store = BlobStore::local(temp.path()).unwrap();
+
+        let result = store
+            .put(BlobNamespace::Backups, "../escape", b"x".to_vec())
+            .await;
+        assert!(matches!(result, Err(StorageError::InvalidKey(_))));
+    }
+}
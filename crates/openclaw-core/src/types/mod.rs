@@ -79,6 +79,12 @@ impl ChannelId {
     pub fn matrix() -> Self {
         Self("matrix".to_string())
     }
+
+    /// Mock channel for development.
+    #[must_use]
+    pub fn mock() -> Self {
+        Self("mock".to_string())
+    }
 }
 
 impl fmt::Display for ChannelId {
@@ -130,7 +136,8 @@ impl SessionKey {
         Self(id.into())
     }
 
-    /// Build a session key from components.
+    /// Build a session key from components. With a `thread_id`, the
+    /// session covers only that thread of the conversation.
     #[must_use]
     pub fn build(
         agent_id: &AgentId,
@@ -138,15 +145,20 @@ impl SessionKey {
         account_id: &str,
         peer_type: PeerType,
         peer_id: &PeerId,
+        thread_id: Option<&str>,
     ) -> Self {
-        Self(format!(
+        let key = format!(
             "agent:{}:channel:{}:account:{}:{}:{}",
             agent_id.0,
             channel.0,
             account_id,
             peer_type.as_str(),
             peer_id.0
-        ))
+        );
+        match thread_id {
+            Some(thread) => Self(format!("{key}:thread:{thread}")),
+            None => Self(key),
+        }
     }
 
     /// Get the main session key for an agent.
@@ -154,6 +166,26 @@ impl SessionKey {
     pub fn main_session(agent_id: &AgentId) -> Self {
         Self(format!("agent:{}:main", agent_id.0))
     }
+
+    /// The agent ID embedded in a key made by [`SessionKey::build`] or
+    /// [`SessionKey::main_session`].
+    #[must_use]
+    pub fn agent_id(&self) -> Option<&str> {
+        self.0
+            .strip_prefix("agent:")
+            .and_then(|rest| rest.split(':').next())
+            .filter(|id| !id.is_empty())
+    }
+
+    /// The channel embedded in a key made by [`SessionKey::build`].
+    #[must_use]
+    pub fn channel(&self) -> Option<&str> {
+        let mut parts = self.0.strip_prefix("agent:")?.split(':').skip(1);
+        match (parts.next(), parts.next()) {
+            (Some("channel"), Some(channel)) if !channel.is_empty() => Some(channel),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for SessionKey {
@@ -225,8 +257,40 @@ pub struct Message {
     pub raw: Option<serde_json::Value>,
 }
 
-/// An attachment to a message.
+impl Message {
+    /// Whether the message mentions the account it was received on.
+    #[must_use]
+    pub fn mentions_me(&self) -> bool {
+        !self.account_id.is_empty() && self.mentions.contains(&self.account_id)
+    }
+}
+
+/// A normalized reaction added to or removed from a message.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageReaction {
+    /// Channel the reaction came from.
+    pub channel: ChannelId,
+    /// Account ID on the channel.
+    pub account_id: String,
+    /// Peer who reacted.
+    pub peer_id: PeerId,
+    /// Chat/room containing the message.
+    pub chat_id: String,
+    /// ID of the message reacted to.
+    pub message_id: String,
+    /// Emoji, or the platform shortcode for platforms without Unicode reactions.
+    pub emoji: String,
+    /// Whether the reaction was removed rather than added.
+    pub removed: bool,
+    /// Timestamp of the reaction.
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Raw platform-specific data (for debugging).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<serde_json::Value>,
+}
+
+/// An attachment to a message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Attachment {
     /// Attachment type.
     pub kind: AttachmentKind,
@@ -268,6 +332,25 @@ pub enum AttachmentKind {
     Unknown,
 }
 
+impl AttachmentKind {
+    /// Lowercase name, as serialized.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Image => "image",
+            Self::Video => "video",
+            Self::Audio => "audio",
+            Self::Voice => "voice",
+            Self::Document => "document",
+            Self::Sticker => "sticker",
+            Self::Gif => "gif",
+            Self::Location => "location",
+            Self::Contact => "contact",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
 /// Result of delivering an outbound message.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeliveryResult {
@@ -284,6 +367,21 @@ pub struct DeliveryResult {
     pub meta: Option<serde_json::Value>,
 }
 
+/// A numbered source cited in an agent response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Citation {
+    /// Citation number as referenced in the text (`[1]`, `[2]`, ...).
+    pub index: u32,
+    /// Source title.
+    pub title: String,
+    /// Source URL, if any.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Relevant excerpt.
+    #[serde(default)]
+    pub snippet: Option<String>,
+}
+
 /// Token usage statistics from an LLM call.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TokenUsage {
@@ -303,6 +401,18 @@ impl TokenUsage {
     pub const fn total(&self) -> u64 {
         self.input_tokens + self.output_tokens
     }
+
+    /// Add another usage record to this one.
+    pub fn accumulate(&mut self, other: &Self) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        if let Some(n) = other.cache_read_tokens {
+            *self.cache_read_tokens.get_or_insert(0) += n;
+        }
+        if let Some(n) = other.cache_write_tokens {
+            *self.cache_write_tokens.get_or_insert(0) += n;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -317,11 +427,60 @@ mod tests {
             "bot123",
             PeerType::Dm,
             &PeerId::new("user456"),
+            None,
         );
         assert_eq!(
             key.0,
             "agent:default:channel:telegram:account:bot123:dm:user456"
         );
+        assert_eq!(key.agent_id(), Some("default"));
+        assert_eq!(key.channel(), Some("telegram"));
+        assert_eq!(
+            SessionKey::main_session(&AgentId::new("ops")).agent_id(),
+            Some("ops")
+        );
+        assert_eq!(
+            SessionKey::main_session(&AgentId::new("ops")).channel(),
+            None
+        );
+        assert_eq!(SessionKey::new("user-1").agent_id(), None);
+        assert_eq!(SessionKey::new("user-1").channel(), None);
+
+        let thread = SessionKey::build(
+            &AgentId::new("default"),
+            &ChannelId::slack(),
+            "U0BOT",
+            PeerType::Group,
+            &PeerId::new("C042"),
+            Some("1700000000.000100"),
+        );
+        assert_eq!(
+            thread.0,
+            "agent:default:channel:slack:account:U0BOT:group:C042:thread:1700000000.000100"
+        );
+        assert_eq!(thread.agent_id(), Some("default"));
+    }
+
+    #[test]
+    fn test_mentions_me() {
+        let mut message = Message {
+            id: "1".to_string(),
+            channel: ChannelId::slack(),
+            account_id: "U0BOT".to_string(),
+            peer_id: PeerId::new("U0ALICE"),
+            peer_type: PeerType::Group,
+            content: "<@U0BOT> hi".to_string(),
+            attachments: vec![],
+            timestamp: chrono::Utc::now(),
+            reply_to: None,
+            thread_id: None,
+            mentions: vec!["U0BOT".to_string()],
+            raw: None,
+        };
+        assert!(message.mentions_me());
+
+        message.mentions = vec!["U0BOB".to_string()];
+        assert!(!message.mentions_me());
     }
 
     #[test]
@@ -45,6 +45,9 @@ pub mod limits {
     /// Maximum skill file size (256KB).
     pub const MAX_SKILL_FILE_SIZE: usize = 256 * 1024;
 
+    /// Maximum prompt template file size (64KB).
+    pub const MAX_PROMPT_TEMPLATE_SIZE: usize = 64 * 1024;
+
     /// Maximum config file size (1MB).
     pub const MAX_CONFIG_FILE_SIZE: usize = 1024 * 1024;
 
@@ -53,6 +56,24 @@ pub mod limits {
 
     /// Maximum JSON nesting depth.
     pub const MAX_JSON_DEPTH: usize = 32;
+
+    /// Maximum session state key length.
+    pub const MAX_STATE_KEY_LENGTH: usize = 128;
+
+    /// Maximum serialized size of one session state value (16KB).
+    pub const MAX_STATE_VALUE_SIZE: usize = 16 * 1024;
+
+    /// Maximum number of state keys per session.
+    pub const MAX_STATE_KEYS: usize = 64;
+
+    /// Maximum length of a peer profile's notes (4KB).
+    pub const MAX_PROFILE_NOTES_LENGTH: usize = 4 * 1024;
+
+    /// Maximum length of a peer profile's name, language, or timezone.
+    pub const MAX_PROFILE_FIELD_LENGTH: usize = 128;
+
+    /// Maximum number of preference flags per peer profile.
+    pub const MAX_PROFILE_PREFERENCES: usize = 64;
 }
 
 /// Validate and sanitize message content from channels.
@@ -0,0 +1,197 @@
+//! Messages that arrive outside working hours.
+//!
+//! With `outside: "queue"`, a message that arrives while its channel or
+//! agent is closed is held here and answered once hours resume, the reply
+//! going out through the session's channel. Held messages are kept in
+//! memory and lost if the gateway restarts.
+
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::RwLock;
+
+use openclaw_channels::OutboundContext;
+use openclaw_core::i18n::{FluentValue, Localizer};
+use openclaw_core::types::{Attachment, SessionKey};
+
+use crate::server::{GatewayState, handle_session_message};
+
+/// How often held messages are checked.
+const AFTER_HOURS_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A message waiting for hours to resume.
+#[derive(Debug, Clone)]
+pub struct HeldMessage {
+    /// Session the message arrived in.
+    pub session_key: SessionKey,
+    /// Message text.
+    pub message: String,
+    /// Attachments sent with it.
+    pub attachments: Vec<Attachment>,
+    /// When hours resume.
+    pub due: DateTime<Utc>,
+}
+
+/// Messages waiting for hours to resume.
+#[derive(Debug, Default)]
+pub struct AfterHoursQueue {
+    held: Mutex<Vec<HeldMessage>>,
+}
+
+impl AfterHoursQueue {
+    /// Hold a message until it is due.
+    pub fn hold(&self, message: HeldMessage) {
+        self.lock().push(message);
+    }
+
+    /// Number of held messages.
+    pub fn pending(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Remove and return the messages due at `now`, oldest first.
+    fn take_due(&self, now: DateTime<Utc>) -> Vec<HeldMessage> {
+        let mut held = self.lock();
+        let (due, waiting) = held.drain(..).partition(|m| m.due <= now);
+        *held = waiting;
+        due
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<HeldMessage>> {
+        self.held.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The reply to a message that arrived outside hours.
+pub fn outside_hours_notice(
+    localizer: &Localizer,
+    locale: &str,
+    opens_at: Option<&str>,
+    queued: bool,
+    allow_urgent: bool,
+) -> String {
+    let mut notice = opens_at.map_or_else(
+        || localizer.message(locale, "outside-hours-closed", &[]),
+        |at| {
+            let id = if queued {
+                "outside-hours-queued"
+            } else {
+                "outside-hours"
+            };
+            localizer.message(locale, id, &[("opens_at", FluentValue::from(at))])
+        },
+    );
+    if allow_urgent {
+        notice.push(' ');
+        notice.push_str(&localizer.message(locale, "outside-hours-urgent", &[]));
+    }
+    notice
+}
+
+/// Answer held messages as they come due, forever.
+pub async fn run_after_hours_queue(state: Arc<RwLock<GatewayState>>) {
+    let mut interval = tokio::time::interval(AFTER_HOURS_POLL_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+        let due = state.read().await.after_hours.take_due(Utc::now());
+        for held in due {
+            answer(&state, held).await;
+        }
+    }
+}
+
+/// Run a held message through its session and deliver the reply.
+async fn answer(state: &Arc<RwLock<GatewayState>>, held: HeldMessage) {
+    let params = serde_json::json!({
+        "session_key": held.session_key.as_ref(),
+        "message": held.message,
+        "attachments": held.attachments,
+    });
+    let result = match handle_session_message(state, &params, None).await {
+        Ok(result) => result,
+        Err((_, e)) => {
+            tracing::warn!("Held message in {} failed: {}", held.session_key, e);
+            return;
+        }
+    };
+    let Some(text) = result["response"].as_str() else {
+        return;
+    };
+
+    // API and web sessions have no adapter; their clients get the reply
+    // from the session's events
+    let state = state.read().await;
+    let Ok(projection) = state
+        .store_for_session(&held.session_key)
+        .get_projection(&held.session_key)
+    else {
+        return;
+    };
+    let channel = state
+        .channels
+        .read()
+        .await
+        .get_outbound(projection.channel.as_ref())
+        .cloned();
+    if let Some(channel) = channel {
+        let ctx = OutboundContext {
+            chat_id: projection.peer_id.clone(),
+            reply_to: None,
+            thread_id: None,
+        };
+        if let Err(e) = state.outbound.send(channel.as_ref(), ctx, text).await {
+            tracing::warn!(
+                "Failed to deliver held reply in {}: {}",
+                held.session_key,
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn held(key: &str, due: &str) -> HeldMessage {
+        HeldMessage {
+            session_key: SessionKey::new(key),
+            message: "hello".to_string(),
+            attachments: vec![],
+            due: due.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_take_due() {
+        let queue = AfterHoursQueue::default();
+        queue.hold(held("a", "2025-01-16T08:00:00Z"));
+        queue.hold(held("b", "2025-01-20T08:00:00Z"));
+        queue.hold(held("c", "2025-01-16T08:00:00Z"));
+
+        assert!(
+            queue
+                .take_due("2025-01-16T07:59:00Z".parse().unwrap())
+                .is_empty()
+        );
+        let due = queue.take_due("2025-01-16T08:00:30Z".parse().unwrap());
+        let keys: Vec<&str> = due.iter().map(|m| m.session_key.as_ref()).collect();
+        assert_eq!(keys, ["a", "c"]);
+        assert_eq!(queue.pending(), 1);
+    }
+
+    #[test]
+    fn test_outside_hours_notice() {
+        let localizer = Localizer::builtin();
+        let notice = outside_hours_notice(&localizer, "en", Some("Mon 09:00 CET"), true, true);
+        assert!(notice.contains("Mon 09:00 CET"));
+        assert!(notice.contains("answer your message then"));
+        assert!(notice.ends_with("/urgent."));
+
+        let notice = outside_hours_notice(&localizer, "en", None, false, false);
+        assert_eq!(notice, "We're outside working hours.");
+    }
+}
@@ -0,0 +1,34 @@
+//! Tool approval notifications.
+
+use std::sync::Arc;
+
+use tokio::sync::RwLock;
+use tokio::sync::broadcast::error::RecvError;
+
+use openclaw_agents::tools::ApprovalEvent;
+
+use crate::events::UiEvent;
+use crate::server::GatewayState;
+
+/// Broadcast approval requests and decisions as UI events.
+pub async fn run_approval_forwarder(state: Arc<RwLock<GatewayState>>) {
+    let (mut approvals, events) = {
+        let state = state.read().await;
+        (state.approvals.subscribe(), state.events.clone())
+    };
+
+    loop {
+        let event = match approvals.recv().await {
+            Ok(ApprovalEvent::Requested(request)) => UiEvent::PendingApproval { request },
+            Ok(ApprovalEvent::Resolved { id, decision }) => {
+                UiEvent::ApprovalResolved { id, decision }
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!("Approval forwarder skipped {} event(s)", skipped);
+                continue;
+            }
+            Err(RecvError::Closed) => return,
+        };
+        let _ = events.broadcast(event);
+    }
+}
@@ -0,0 +1,43 @@
+//! Moving old ended sessions to cold storage.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::sync::RwLock;
+
+use crate::server::GatewayState;
+
+/// How often ended sessions are checked for archival.
+pub const SESSION_ARCHIVE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Archive ended sessions past the configured age, forever.
+pub async fn run_session_archiver(state: Arc<RwLock<GatewayState>>) {
+    let mut interval = tokio::time::interval(SESSION_ARCHIVE_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+        archive_ended(&state).await;
+    }
+}
+
+/// Archive every ended session inactive for longer than
+/// `archive_ended_after_days`. Does nothing if archival is disabled.
+pub async fn archive_ended(state: &Arc<RwLock<GatewayState>>) {
+    let state = state.read().await;
+    let Some(days) = state.config.archive_ended_after_days else {
+        return;
+    };
+    let cutoff = Utc::now() - chrono::Duration::days(i64::from(days));
+
+    for store in state.event_stores() {
+        match store.archive_ended_before(cutoff) {
+            Ok(archived) if !archived.is_empty() => {
+                tracing::info!("Archived {} ended session(s)", archived.len());
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to archive ended sessions: {}", e),
+        }
+    }
+}
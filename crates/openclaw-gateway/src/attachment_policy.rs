@@ -0,0 +1,536 @@
+//! Inbound attachment policy.
+//!
+//! Attachments on an inbound message are checked before the agent sees
+//! them: size against the channel's limit for the kind, contents against
+//! the file extension and declared MIME type, and optionally a `ClamAV`
+//! scan. Rejected attachments are dropped from the message.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use openclaw_agents::runtime::{AttachmentError, AttachmentResolver};
+use openclaw_core::config::{AttachmentPolicyConfig, ClamAvConfig};
+use openclaw_core::i18n::{FluentValue, Localizer};
+use openclaw_core::types::{Attachment, AttachmentKind};
+
+/// Bytes sent to `clamd` per `INSTREAM` chunk.
+const CLAMD_CHUNK: usize = 64 * 1024;
+
+/// Why an attachment was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RejectionReason {
+    /// Larger than the limit for its kind.
+    TooLarge {
+        /// Byte limit.
+        max: u64,
+    },
+    /// Contents are a different type than the name or MIME type says.
+    MimeMismatch {
+        /// Type the name or declared MIME type implies.
+        claimed: String,
+        /// Type found in the contents.
+        detected: String,
+    },
+    /// The virus scanner flagged it.
+    Infected {
+        /// Signature name.
+        signature: String,
+    },
+    /// A required scan could not be done.
+    Unscanned {
+        /// What went wrong.
+        error: String,
+    },
+}
+
+impl RejectionReason {
+    /// Short code recorded in events.
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::TooLarge { .. } => "too_large",
+            Self::MimeMismatch { .. } => "mime_mismatch",
+            Self::Infected { .. } => "infected",
+            Self::Unscanned { .. } => "unscanned",
+        }
+    }
+
+    /// The reason in `locale`, for the sender.
+    #[must_use]
+    pub fn localized(&self, localizer: &Localizer, locale: &str) -> String {
+        match self {
+            Self::TooLarge { max } => localizer.message(
+                locale,
+                "attachment-too-large",
+                &[("max", FluentValue::from(human_size(*max)))],
+            ),
+            Self::MimeMismatch { claimed, detected } => localizer.message(
+                locale,
+                "attachment-mime-mismatch",
+                &[
+                    ("claimed", FluentValue::from(claimed.as_str())),
+                    ("detected", FluentValue::from(detected.as_str())),
+                ],
+            ),
+            Self::Infected { signature } => localizer.message(
+                locale,
+                "attachment-infected",
+                &[("signature", FluentValue::from(signature.as_str()))],
+            ),
+            Self::Unscanned { .. } => localizer.message(locale, "attachment-unscanned", &[]),
+        }
+    }
+}
+
+impl fmt::Display for RejectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLarge { max } => write!(f, "larger than {}", human_size(*max)),
+            Self::MimeMismatch { claimed, detected } => {
+                write!(f, "contents are {detected}, not {claimed}")
+            }
+            Self::Infected { signature } => write!(f, "flagged by virus scan ({signature})"),
+            Self::Unscanned { .. } => write!(f, "could not be scanned"),
+        }
+    }
+}
+
+/// An attachment the policy turned away.
+#[derive(Debug, Clone)]
+pub struct Rejection {
+    /// The attachment.
+    pub attachment: Attachment,
+    /// Why.
+    pub reason: RejectionReason,
+}
+
+impl Rejection {
+    /// Name shown to the sender.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        self.attachment
+            .filename
+            .as_deref()
+            .unwrap_or(self.attachment.kind.as_str())
+    }
+}
+
+/// Checks inbound attachments against the configured policy.
+pub struct AttachmentPolicy {
+    config: AttachmentPolicyConfig,
+    resolver: Arc<dyn AttachmentResolver>,
+}
+
+impl AttachmentPolicy {
+    /// Create a policy that loads attachments with `resolver`.
+    #[must_use]
+    pub fn new(config: AttachmentPolicyConfig, resolver: Arc<dyn AttachmentResolver>) -> Self {
+        Self { config, resolver }
+    }
+
+    /// Split `attachments` arriving on `channel` into accepted and rejected.
+    pub async fn check(
+        &self,
+        channel: &str,
+        attachments: Vec<Attachment>,
+    ) -> (Vec<Attachment>, Vec<Rejection>) {
+        if attachments.is_empty() || !self.config.enabled_for(channel) {
+            return (attachments, Vec::new());
+        }
+
+        let mut accepted = Vec::new();
+        let mut rejected = Vec::new();
+        for attachment in attachments {
+            match self.check_one(channel, &attachment).await {
+                None => accepted.push(attachment),
+                Some(reason) => rejected.push(Rejection { attachment, reason }),
+            }
+        }
+        (accepted, rejected)
+    }
+
+    /// Reply telling the sender which attachments were rejected, in
+    /// `locale` unless a rejection message is configured.
+    #[must_use]
+    #[allow(clippy::literal_string_with_formatting_args)]
+    pub fn notice(&self, rejected: &[Rejection], localizer: &Localizer, locale: &str) -> String {
+        let files = rejected
+            .iter()
+            .map(|r| format!("{} ({})", r.name(), r.reason.localized(localizer, locale)))
+            .collect::<Vec<_>>()
+            .join("; ");
+        match &self.config.rejection_message {
+            Some(message) => message.replace("{files}", &files),
+            None => localizer.message(
+                locale,
+                "attachments-rejected",
+                &[("files", FluentValue::from(files))],
+            ),
+        }
+    }
+
+    async fn check_one(&self, channel: &str, attachment: &Attachment) -> Option<RejectionReason> {
+        let max = self.config.max_bytes_for(channel, attachment.kind.as_str());
+        if attachment.size.is_some_and(|size| size > max) {
+            return Some(RejectionReason::TooLarge { max });
+        }
+
+        // Locations and contacts carry no file
+        if matches!(
+            attachment.kind,
+            AttachmentKind::Location | AttachmentKind::Contact
+        ) {
+            return None;
+        }
+        let sniff_mime = self.config.sniff_mime_for(channel);
+        let clamav = self.config.clamav_for(channel);
+        if !sniff_mime && clamav.is_none() {
+            return None;
+        }
+
+        let data = match self.resolver.resolve(attachment, max).await {
+            Ok(resolved) => resolved.data,
+            Err(AttachmentError::TooLarge { .. }) => {
+                return Some(RejectionReason::TooLarge { max });
+            }
+            Err(e) => {
+                // Without the contents only a required scan can reject
+                tracing::warn!("Could not load attachment for checks: {}", e);
+                return clamav
+                    .filter(|c| !c.fail_open)
+                    .map(|_| RejectionReason::Unscanned {
+                        error: e.to_string(),
+                    });
+            }
+        };
+
+        if let Some(reason) = sniff_mime
+            .then(|| content_mismatch(attachment, &data))
+            .flatten()
+        {
+            return Some(reason);
+        }
+        if let Some(clamav) = clamav {
+            match clamd_scan(clamav, &data).await {
+                Ok(None) => {}
+                Ok(Some(signature)) => return Some(RejectionReason::Infected { signature }),
+                Err(error) if clamav.fail_open => {
+                    tracing::warn!("Attachment scan failed, accepting: {}", error);
+                }
+                Err(error) => {
+                    tracing::warn!("Attachment scan failed, rejecting: {}", error);
+                    return Some(RejectionReason::Unscanned { error });
+                }
+            }
+        }
+        None
+    }
+}
+
+/// What a file extension says the contents should be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expected {
+    /// A format `sniff` recognizes, as the type it reports.
+    Signature(&'static str),
+    /// A text format with no signature.
+    Text,
+}
+
+/// Type of a file from its leading bytes, for common formats.
+fn sniff(data: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"\x1f\x8b", "application/gzip"),
+        (b"OggS", "audio/ogg"),
+        (b"ID3", "audio/mpeg"),
+        (b"fLaC", "audio/flac"),
+        (b"\x1a\x45\xdf\xa3", "video/webm"),
+        (b"MZ", "application/x-msdownload"),
+        (b"\x7fELF", "application/x-executable"),
+    ];
+
+    if let Some((_, mime)) = SIGNATURES.iter().find(|(sig, _)| data.starts_with(sig)) {
+        return Some(*mime);
+    }
+    match (data.get(..4), data.get(4..8), data.get(8..12)) {
+        (Some(b"RIFF"), _, Some(b"WEBP")) => Some("image/webp"),
+        (Some(b"RIFF"), _, Some(b"WAVE")) => Some("audio/wav"),
+        (_, Some(b"ftyp"), _) => Some("video/mp4"),
+        _ => None,
+    }
+}
+
+/// What contents a file extension implies (`None` for extensions not
+/// checked).
+fn expected_for_extension(extension: &str) -> Option<Expected> {
+    let expected = match extension.to_ascii_lowercase().as_str() {
+        "png" => Expected::Signature("image/png"),
+        "jpg" | "jpeg" => Expected::Signature("image/jpeg"),
+        "gif" => Expected::Signature("image/gif"),
+        "webp" => Expected::Signature("image/webp"),
+        "pdf" => Expected::Signature("application/pdf"),
+        // Office and OpenDocument files are zip archives
+        "zip" | "docx" | "xlsx" | "pptx" | "odt" | "ods" | "odp" | "epub" => {
+            Expected::Signature("application/zip")
+        }
+        "gz" | "tgz" => Expected::Signature("application/gzip"),
+        "ogg" | "oga" | "opus" => Expected::Signature("audio/ogg"),
+        "mp3" => Expected::Signature("audio/mpeg"),
+        "flac" => Expected::Signature("audio/flac"),
+        "wav" => Expected::Signature("audio/wav"),
+        "mp4" | "m4a" | "m4v" | "mov" => Expected::Signature("video/mp4"),
+        "webm" => Expected::Signature("video/webm"),
+        "exe" | "dll" => Expected::Signature("application/x-msdownload"),
+        "txt" | "csv" | "md" | "json" | "log" => Expected::Text,
+        _ => return None,
+    };
+    Some(expected)
+}
+
+/// Compare an attachment's contents with its extension, or with its
+/// declared MIME type when the extension says nothing.
+fn content_mismatch(attachment: &Attachment, data: &[u8]) -> Option<RejectionReason> {
+    let detected = sniff(data);
+    let extension = attachment
+        .filename
+        .as_deref()
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| ext);
+
+    let (claimed, expected) = if let Some(expected) = extension.and_then(expected_for_extension) {
+        (format!(".{}", extension.unwrap_or_default()), expected)
+    } else {
+        // A declared type only counts when it is one sniffing can confirm
+        let declared = attachment.mime_type.as_deref()?;
+        let expected = sniff_types().find(|t| *t == declared)?;
+        (declared.to_string(), Expected::Signature(expected))
+    };
+
+    let matches = match (expected, detected) {
+        (Expected::Signature(expected), Some(detected)) => expected == detected,
+        (Expected::Text, detected) => detected.is_none(),
+        // Unrecognized contents are given the benefit of the doubt
+        (Expected::Signature(_), None) => true,
+    };
+    (!matches).then(|| RejectionReason::MimeMismatch {
+        claimed,
+        detected: detected.unwrap_or("binary data").to_string(),
+    })
+}
+
+/// Every type `sniff` can report.
+fn sniff_types() -> impl Iterator<Item = &'static str> {
+    [
+        "image/png",
+        "image/jpeg",
+        "image/gif",
+        "image/webp",
+        "application/pdf",
+        "application/zip",
+        "application/gzip",
+        "audio/ogg",
+        "audio/mpeg",
+        "audio/flac",
+        "audio/wav",
+        "video/mp4",
+        "video/webm",
+    ]
+    .into_iter()
+}
+
+/// Scan `data` with `clamd`'s `INSTREAM` command: `Ok(None)` if clean,
+/// `Ok(Some(signature))` if infected.
+async fn clamd_scan(config: &ClamAvConfig, data: &[u8]) -> Result<Option<String>, String> {
+    let scan = async {
+        let mut stream = TcpStream::connect(&config.address).await?;
+        stream.write_all(b"zINSTREAM\0").await?;
+        for chunk in data.chunks(CLAMD_CHUNK) {
+            let len = u32::try_from(chunk.len()).unwrap_or(u32::MAX);
+            stream.write_all(&len.to_be_bytes()).await?;
+            stream.write_all(chunk).await?;
+        }
+        stream.write_all(&0u32.to_be_bytes()).await?;
+
+        let mut reply = Vec::new();
+        stream.read_to_end(&mut reply).await?;
+        Ok::<_, std::io::Error>(reply)
+    };
+
+    let reply = tokio::time::timeout(Duration::from_secs(config.timeout_secs), scan)
+        .await
+        .map_err(|_| format!("clamd at {} timed out", config.address))?
+        .map_err(|e| format!("clamd at {}: {e}", config.address))?;
+    parse_clamd_reply(&String::from_utf8_lossy(&reply))
+}
+
+/// Parse a reply such as `stream: OK` or `stream: Eicar-Signature FOUND`.
+fn parse_clamd_reply(reply: &str) -> Result<Option<String>, String> {
+    let reply = reply.trim_end_matches(['\0', '\n']).trim();
+    let result = reply.strip_prefix("stream:").map_or(reply, str::trim);
+    if result == "OK" {
+        return Ok(None);
+    }
+    result.strip_suffix(" FOUND").map_or_else(
+        || Err(format!("clamd: {result}")),
+        |signature| Ok(Some(signature.to_string())),
+    )
+}
+
+fn human_size(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = 1024 * KB;
+    if bytes >= MB {
+        format!("{} MB", bytes / MB)
+    } else if bytes >= KB {
+        format!("{} KB", bytes / KB)
+    } else {
+        format!("{bytes} bytes")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openclaw_agents::runtime::HttpAttachmentResolver;
+    use tokio::net::TcpListener;
+
+    fn attachment(kind: AttachmentKind, filename: &str, url: &str) -> Attachment {
+        Attachment {
+            kind,
+            url: url.to_string(),
+            mime_type: None,
+            filename: Some(filename.to_string()),
+            size: None,
+            thumbnail_url: None,
+        }
+    }
+
+    #[test]
+    fn test_sniff() {
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n...."), Some("image/png"));
+        assert_eq!(sniff(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(sniff(b"\0\0\0\x18ftypmp42"), Some("video/mp4"));
+        assert_eq!(sniff(b"MZ\x90\0"), Some("application/x-msdownload"));
+        assert_eq!(sniff(b"hello world"), None);
+    }
+
+    #[test]
+    fn test_content_mismatch() {
+        let png = b"\x89PNG\r\n\x1a\n....";
+        let doc = |name: &str| attachment(AttachmentKind::Document, name, "");
+
+        assert!(content_mismatch(&doc("photo.PNG"), png).is_none());
+        assert!(content_mismatch(&doc("report.docx"), b"PK\x03\x04...").is_none());
+        assert!(content_mismatch(&doc("notes.txt"), b"plain text").is_none());
+        assert!(content_mismatch(&doc("unknown.xyz"), b"MZ\x90\0").is_none());
+
+        assert_eq!(
+            content_mismatch(&doc("invoice.pdf"), b"MZ\x90\0"),
+            Some(RejectionReason::MimeMismatch {
+                claimed: ".pdf".to_string(),
+                detected: "application/x-msdownload".to_string(),
+            })
+        );
+        assert!(content_mismatch(&doc("notes.txt"), b"\x7fELF....").is_some());
+
+        // Without a known extension the declared type is checked
+        let mut declared = doc("scan");
+        declared.mime_type = Some("application/pdf".to_string());
+        assert!(content_mismatch(&declared, png).is_some());
+        declared.mime_type = Some("application/octet-stream".to_string());
+        assert!(content_mismatch(&declared, png).is_none());
+    }
+
+    #[test]
+    fn test_parse_clamd_reply() {
+        assert_eq!(parse_clamd_reply("stream: OK\0"), Ok(None));
+        assert_eq!(
+            parse_clamd_reply("stream: Win.Test.EICAR_HDB-1 FOUND\0"),
+            Ok(Some("Win.Test.EICAR_HDB-1".to_string()))
+        );
+        assert!(parse_clamd_reply("INSTREAM size limit exceeded. ERROR\0").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_check() {
+        let mut config = AttachmentPolicyConfig::default();
+        config.max_bytes.insert("image".to_string(), 16);
+        let policy = AttachmentPolicy::new(config, Arc::new(HttpAttachmentResolver::default()));
+
+        let mut big = attachment(AttachmentKind::Image, "big.png", "");
+        big.size = Some(17);
+        let fake = attachment(
+            AttachmentKind::Document,
+            "invoice.pdf",
+            "data:application/pdf;base64,TVqQAA==",
+        );
+        let fine = attachment(
+            AttachmentKind::Image,
+            "pixel.png",
+            "data:image/png;base64,iVBORw0KGgo=",
+        );
+
+        let (accepted, rejected) = policy.check("telegram", vec![big, fake, fine]).await;
+        assert_eq!(accepted.len(), 1);
+        assert_eq!(accepted[0].filename.as_deref(), Some("pixel.png"));
+        assert_eq!(rejected[0].reason, RejectionReason::TooLarge { max: 16 });
+        assert_eq!(rejected[1].reason.code(), "mime_mismatch");
+        let localizer = Localizer::builtin();
+        assert_eq!(
+            policy.notice(&rejected, &localizer, "en"),
+            "I couldn't accept big.png (larger than 16 bytes); \
+             invoice.pdf (contents are application/x-msdownload, not .pdf)."
+        );
+        assert_eq!(
+            policy.notice(&rejected[..1], &localizer, "de"),
+            "Folgendes konnte ich nicht annehmen: big.png (größer als 16 bytes)."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_clamd_scan() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut command = [0u8; 10];
+            socket.read_exact(&mut command).await.unwrap();
+            assert_eq!(&command, b"zINSTREAM\0");
+
+            let mut data = Vec::new();
+            loop {
+                let len = socket.read_u32().await.unwrap() as usize;
+                if len == 0 {
+                    break;
+                }
+                let mut chunk = vec![0; len];
+                socket.read_exact(&mut chunk).await.unwrap();
+                data.extend(chunk);
+            }
+            let reply: &[u8] = if data.starts_with(b"X5O!") {
+                b"stream: Eicar-Signature FOUND\0"
+            } else {
+                b"stream: OK\0"
+            };
+            socket.write_all(reply).await.unwrap();
+        });
+
+        let config = ClamAvConfig {
+            address,
+            ..ClamAvConfig::default()
+        };
+        assert_eq!(
+            clamd_scan(&config, b"X5O!P%@AP").await,
+            Ok(Some("Eicar-Signature".to_string()))
+        );
+    }
+}
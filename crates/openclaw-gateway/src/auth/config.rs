@@ -39,6 +39,72 @@ pub struct AuthConfig {
     /// Methods that don't require authentication.
     #[serde(default = "default_public_methods")]
     pub public_methods: Vec<String>,
+
+    /// Slowing down and locking out repeated failed logins.
+    #[serde(default)]
+    pub login_throttle: LoginThrottleConfig,
+}
+
+/// Failed-login throttling, tracked per username and per client address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginThrottleConfig {
+    /// Whether failed logins are tracked at all.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Failures within `window_secs` that lock a username or address out.
+    #[serde(default = "default_max_failures")]
+    pub max_failures: u32,
+
+    /// Seconds after the first failure before the count starts over.
+    #[serde(default = "default_failure_window")]
+    pub window_secs: u64,
+
+    /// Seconds a lockout lasts.
+    #[serde(default = "default_lockout")]
+    pub lockout_secs: u64,
+
+    /// Delay before answering the first failed login, in milliseconds.
+    /// Each further failure doubles it.
+    #[serde(default = "default_base_delay")]
+    pub base_delay_ms: u64,
+
+    /// Longest delay before answering a failed login, in milliseconds.
+    #[serde(default = "default_max_delay")]
+    pub max_delay_ms: u64,
+}
+
+impl Default for LoginThrottleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            max_failures: default_max_failures(),
+            window_secs: default_failure_window(),
+            lockout_secs: default_lockout(),
+            base_delay_ms: default_base_delay(),
+            max_delay_ms: default_max_delay(),
+        }
+    }
+}
+
+const fn default_max_failures() -> u32 {
+    5
+}
+
+const fn default_failure_window() -> u64 {
+    15 * 60
+}
+
+const fn default_lockout() -> u64 {
+    15 * 60
+}
+
+const fn default_base_delay() -> u64 {
+    250
+}
+
+const fn default_max_delay() -> u64 {
+    8000
 }
 
 const fn default_enabled() -> bool {
@@ -60,10 +126,12 @@ const fn default_refresh_expiry() -> u64 {
 fn default_public_methods() -> Vec<String> {
     vec![
         "auth.login".to_string(),
+        "auth.oidc.status".to_string(),
         "setup.status".to_string(),
         "setup.init".to_string(),
         "system.health".to_string(),
         "system.version".to_string(),
+        "rpc.discover".to_string(),
     ]
 }
 
@@ -77,6 +145,7 @@ impl Default for AuthConfig {
             require_auth_for_rpc: default_true(),
             require_auth_for_ws: default_true(),
             public_methods: default_public_methods(),
+            login_throttle: LoginThrottleConfig::default(),
         }
     }
 }
@@ -180,6 +249,13 @@ impl AuthConfigBuilder {
         self
     }
 
+    /// Set failed-login throttling.
+    #[must_use]
+    pub const fn login_throttle(mut self, throttle: LoginThrottleConfig) -> Self {
+        self.config.login_throttle = throttle;
+        self
+    }
+
     /// Build the config.
     #[must_use]
     pub fn build(self) -> AuthConfig {
@@ -29,6 +29,9 @@ pub struct Claims {
     /// Token family ID (for refresh token rotation).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub family_id: Option<String>,
+    /// Token ID, unique per token (for revocation).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
 }
 
 /// Token type.
@@ -55,6 +58,10 @@ pub struct TokenPair {
     pub refresh_expires_at: DateTime<Utc>,
     /// Token type (always "Bearer").
     pub token_type: String,
+    /// Family both tokens belong to.
+    pub family_id: String,
+    /// ID of the refresh token.
+    pub refresh_jti: String,
 }
 
 /// JWT manager for creating and validating tokens.
@@ -119,23 +126,9 @@ impl JwtManager {
         username: &str,
         role: UserRole,
     ) -> Result<(String, DateTime<Utc>), AuthError> {
-        let now = Utc::now();
-        let exp = now + chrono::Duration::from_std(self.access_expiry).unwrap_or_default();
-
-        let claims = Claims {
-            sub: user_id.to_string(),
-            username: username.to_string(),
-            role,
-            iat: now.timestamp(),
-            exp: exp.timestamp(),
-            token_type: TokenType::Access,
-            family_id: None,
-        };
-
-        let token = encode(&Header::default(), &claims, &self.encoding_key)
-            .map_err(|e| AuthError::TokenError(format!("Encoding failed: {e}")))?;
-
-        Ok((token, exp))
+        let (token, claims) =
+            self.encode_claims(user_id, username, role, TokenType::Access, None)?;
+        Ok((token, expiry_of(&claims)))
     }
 
     /// Create a refresh token for a user.
@@ -150,30 +143,11 @@ impl JwtManager {
         role: UserRole,
         family_id: Option<String>,
     ) -> Result<(String, DateTime<Utc>), AuthError> {
-        let now = Utc::now();
-        let exp = now + chrono::Duration::from_std(self.refresh_expiry).unwrap_or_default();
-
         // Generate new family ID if not provided (new login)
-        let family_id = family_id.unwrap_or_else(|| {
-            let mut bytes = [0u8; 16];
-            rand::thread_rng().fill_bytes(&mut bytes);
-            hex::encode(bytes)
-        });
-
-        let claims = Claims {
-            sub: user_id.to_string(),
-            username: username.to_string(),
-            role,
-            iat: now.timestamp(),
-            exp: exp.timestamp(),
-            token_type: TokenType::Refresh,
-            family_id: Some(family_id),
-        };
-
-        let token = encode(&Header::default(), &claims, &self.encoding_key)
-            .map_err(|e| AuthError::TokenError(format!("Encoding failed: {e}")))?;
-
-        Ok((token, exp))
+        let family_id = family_id.unwrap_or_else(random_id);
+        let (token, claims) =
+            self.encode_claims(user_id, username, role, TokenType::Refresh, Some(family_id))?;
+        Ok((token, expiry_of(&claims)))
     }
 
     /// Create a token pair (access + refresh) for a user.
@@ -187,19 +161,81 @@ impl JwtManager {
         username: &str,
         role: UserRole,
     ) -> Result<TokenPair, AuthError> {
-        let (access_token, expires_at) = self.create_access_token(user_id, username, role)?;
-        let (refresh_token, refresh_expires_at) =
-            self.create_refresh_token(user_id, username, role, None)?;
+        self.create_family_pair(user_id, username, role, None)
+    }
+
+    /// Create a token pair in `family_id`, or in a new family when `None`.
+    /// Both tokens carry the family, so ending it ends both.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if token creation fails.
+    pub fn create_family_pair(
+        &self,
+        user_id: &str,
+        username: &str,
+        role: UserRole,
+        family_id: Option<String>,
+    ) -> Result<TokenPair, AuthError> {
+        let family_id = family_id.unwrap_or_else(random_id);
+        let (access_token, access) = self.encode_claims(
+            user_id,
+            username,
+            role,
+            TokenType::Access,
+            Some(family_id.clone()),
+        )?;
+        let (refresh_token, refresh) = self.encode_claims(
+            user_id,
+            username,
+            role,
+            TokenType::Refresh,
+            Some(family_id.clone()),
+        )?;
 
         Ok(TokenPair {
             access_token,
             refresh_token,
-            expires_at,
-            refresh_expires_at,
+            expires_at: expiry_of(&access),
+            refresh_expires_at: expiry_of(&refresh),
             token_type: "Bearer".to_string(),
+            family_id,
+            refresh_jti: refresh.jti.unwrap_or_default(),
         })
     }
 
+    fn encode_claims(
+        &self,
+        user_id: &str,
+        username: &str,
+        role: UserRole,
+        token_type: TokenType,
+        family_id: Option<String>,
+    ) -> Result<(String, Claims), AuthError> {
+        let expiry = match token_type {
+            TokenType::Access => self.access_expiry,
+            TokenType::Refresh => self.refresh_expiry,
+        };
+        let now = Utc::now();
+        let exp = now + chrono::Duration::from_std(expiry).unwrap_or_default();
+
+        let claims = Claims {
+            sub: user_id.to_string(),
+            username: username.to_string(),
+            role,
+            iat: now.timestamp(),
+            exp: exp.timestamp(),
+            token_type,
+            family_id,
+            jti: Some(random_id()),
+        };
+
+        let token = encode(&Header::default(), &claims, &self.encoding_key)
+            .map_err(|e| AuthError::TokenError(format!("Encoding failed: {e}")))?;
+
+        Ok((token, claims))
+    }
+
     /// Validate and decode a token.
     ///
     /// # Errors
@@ -229,7 +265,10 @@ impl JwtManager {
         Ok(claims)
     }
 
-    /// Validate a refresh token and optionally create new tokens.
+    /// Validate a refresh token and create new tokens in its family.
+    ///
+    /// This only checks the token itself; `AuthState::refresh_tokens` also
+    /// rotates it in the session store.
     ///
     /// # Errors
     ///
@@ -242,22 +281,7 @@ impl JwtManager {
         }
 
         // Create new tokens with the same family ID (for rotation tracking)
-        let (access_token, expires_at) =
-            self.create_access_token(&claims.sub, &claims.username, claims.role)?;
-        let (new_refresh_token, refresh_expires_at) = self.create_refresh_token(
-            &claims.sub,
-            &claims.username,
-            claims.role,
-            claims.family_id,
-        )?;
-
-        Ok(TokenPair {
-            access_token,
-            refresh_token: new_refresh_token,
-            expires_at,
-            refresh_expires_at,
-            token_type: "Bearer".to_string(),
-        })
+        self.create_family_pair(&claims.sub, &claims.username, claims.role, claims.family_id)
     }
 
     /// Extract token from Authorization header.
@@ -271,6 +295,17 @@ impl JwtManager {
     }
 }
 
+/// A random 128-bit ID as hex.
+fn random_id() -> String {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+fn expiry_of(claims: &Claims) -> DateTime<Utc> {
+    DateTime::from_timestamp(claims.exp, 0).unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
 impl std::fmt::Debug for JwtManager {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("JwtManager")
@@ -344,6 +379,18 @@ mod tests {
         // Validate refresh token
         let refresh_claims = manager.validate_token(&pair.refresh_token).unwrap();
         assert_eq!(refresh_claims.token_type, TokenType::Refresh);
+
+        // Both tokens belong to one family but have their own IDs
+        assert_eq!(
+            access_claims.family_id.as_deref(),
+            Some(pair.family_id.as_str())
+        );
+        assert_eq!(refresh_claims.family_id, access_claims.family_id);
+        assert_eq!(
+            refresh_claims.jti.as_deref(),
+            Some(pair.refresh_jti.as_str())
+        );
+        assert_ne!(access_claims.jti, refresh_claims.jti);
     }
 
     #[test]
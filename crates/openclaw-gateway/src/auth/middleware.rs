@@ -13,9 +13,11 @@ use tokio::sync::RwLock;
 
 use super::AuthError;
 use super::config::AuthConfig;
-use super::jwt::{Claims, JwtManager};
+use super::jwt::{Claims, JwtManager, TokenPair, TokenType};
+use super::sessions::LoginSessionStore;
 use super::setup::BootstrapManager;
-use super::users::{UserRole, UserStore};
+use super::throttle::LoginThrottle;
+use super::users::{User, UserRole, UserStore};
 
 /// Shared authentication state.
 pub struct AuthState {
@@ -27,18 +29,28 @@ pub struct AuthState {
     pub users: UserStore,
     /// Bootstrap manager.
     pub bootstrap: RwLock<BootstrapManager>,
+    /// Failed-login counts and lockouts.
+    pub throttle: LoginThrottle,
+    /// Login sessions and revoked tokens.
+    pub sessions: LoginSessionStore,
 }
 
 impl AuthState {
-    /// Create a new auth state.
-    #[must_use]
-    pub fn new(config: AuthConfig, jwt: JwtManager, users: UserStore) -> Self {
-        Self {
+    /// Create a new auth state, keeping sessions in the user store's
+    /// database.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the session tables cannot be opened.
+    pub fn new(config: AuthConfig, jwt: JwtManager, users: UserStore) -> Result<Self, AuthError> {
+        Ok(Self {
+            throttle: LoginThrottle::new(config.login_throttle.clone()),
+            sessions: LoginSessionStore::open(users.db())?,
             config,
             jwt,
             users,
             bootstrap: RwLock::new(BootstrapManager::new()),
-        }
+        })
     }
 
     /// Initialize auth state, auto-generating JWT secret if needed.
@@ -70,7 +82,7 @@ impl AuthState {
             config.refresh_expiry(),
         )?;
 
-        Ok(Self::new(config, jwt, users))
+        Self::new(config, jwt, users)
     }
 
     /// Check if auth is required for a method.
@@ -83,9 +95,54 @@ impl AuthState {
     ///
     /// # Errors
     ///
-    /// Returns error if token is invalid.
+    /// Returns error if token is invalid, revoked, or its session has ended.
     pub fn validate_token(&self, token: &str) -> Result<Claims, AuthError> {
-        self.jwt.validate_access_token(token)
+        let claims = self.jwt.validate_access_token(token)?;
+        self.sessions.check(&claims)?;
+        Ok(claims)
+    }
+
+    /// Issue tokens for a login and start its session.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if token creation or storage fails.
+    pub fn issue_tokens(&self, user: &User) -> Result<TokenPair, AuthError> {
+        let tokens = self
+            .jwt
+            .create_token_pair(&user.id, &user.username, user.role)?;
+        self.sessions.start(&user.id, &user.username, &tokens)?;
+        Ok(tokens)
+    }
+
+    /// Exchange a refresh token for new tokens, retiring it.
+    ///
+    /// The new tokens carry the user's current role.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the token is invalid, already used, or its session
+    /// has ended, or if the user is gone or disabled.
+    pub fn refresh_tokens(&self, refresh_token: &str) -> Result<TokenPair, AuthError> {
+        let claims = self.jwt.validate_token(refresh_token)?;
+        if claims.token_type != TokenType::Refresh {
+            return Err(AuthError::TokenError("Not a refresh token".to_string()));
+        }
+        self.sessions.check(&claims)?;
+
+        let user = self
+            .users
+            .get(&claims.sub)?
+            .filter(|user| user.active)
+            .ok_or_else(|| AuthError::TokenError("Account disabled or removed".to_string()))?;
+        let tokens = self.jwt.create_family_pair(
+            &user.id,
+            &user.username,
+            user.role,
+            claims.family_id.clone(),
+        )?;
+        self.sessions.rotate(&claims, &tokens)?;
+        Ok(tokens)
     }
 }
 
@@ -210,6 +267,7 @@ async fn extract_auth(parts: &Parts, auth_state: &AuthState) -> Result<RequireAu
                 exp: i64::MAX,
                 token_type: super::jwt::TokenType::Access,
                 family_id: None,
+                jti: None,
             },
         });
     }
@@ -305,6 +363,7 @@ mod tests {
                 exp: i64::MAX,
                 token_type: super::super::jwt::TokenType::Access,
                 family_id: None,
+                jti: None,
             },
         };
 
@@ -326,6 +385,7 @@ mod tests {
                 exp: i64::MAX,
                 token_type: super::super::jwt::TokenType::Access,
                 family_id: None,
+                jti: None,
             },
         };
 
@@ -3,20 +3,31 @@
 //! This module provides:
 //! - User management with role-based access control
 //! - JWT token generation and validation
+//! - Refresh token rotation and session revocation
+//! - Failed-login throttling and lockouts
+//! - Single sign-on through OpenID Connect
 //! - First-run setup and bootstrap
 //! - Auth middleware for protected routes
 
 mod config;
 mod jwt;
 mod middleware;
+mod oidc;
+mod sessions;
 /// First-run setup and bootstrap management.
 pub mod setup;
+mod throttle;
 mod users;
 
-pub use config::{AuthConfig, AuthConfigBuilder};
+pub use config::{AuthConfig, AuthConfigBuilder, LoginThrottleConfig};
 pub use jwt::{Claims, JwtManager, TokenPair};
 pub use middleware::{AuthLayer, AuthState, RequireAuth};
+pub use oidc::{
+    OIDC_CALLBACK_ROUTE, OIDC_LOGIN_ROUTE, OidcClient, OidcError, OidcIdentity, map_role,
+};
+pub use sessions::{LoginSession, LoginSessionStore};
 pub use setup::{BootstrapManager, SetupStatus};
+pub use throttle::{FailedLogin, LoginThrottle, ThrottleEntry, ThrottleKey};
 pub use users::{User, UserRole, UserStore};
 
 use thiserror::Error;
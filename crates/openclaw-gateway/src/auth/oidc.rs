@@ -0,0 +1,470 @@
+//! `OpenID` Connect login.
+//!
+//! The gateway acts as a confidential (or PKCE-only public) client of the
+//! provider: it sends the browser to the provider's authorization endpoint,
+//! exchanges the returned code for an ID token, verifies the token against
+//! the provider's published keys, and maps its claims to a role. The caller
+//! then issues the gateway's own tokens; provider tokens are not kept.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
+use rand::RngCore;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tokio::sync::OnceCell;
+
+use openclaw_core::config::OidcConfig;
+
+use super::users::UserRole;
+
+/// Route that starts a login.
+pub const OIDC_LOGIN_ROUTE: &str = "/auth/oidc/login";
+
+/// Route the provider redirects back to.
+pub const OIDC_CALLBACK_ROUTE: &str = "/auth/oidc/callback";
+
+/// How long a started login may take to come back.
+const PENDING_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Timeout for calls to the provider.
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// OIDC errors.
+#[derive(Error, Debug)]
+pub enum OidcError {
+    /// The client is misconfigured.
+    #[error("Config error: {0}")]
+    Config(String),
+
+    /// The provider could not be reached or answered with an error status.
+    #[error("Provider request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The discovery document is unusable.
+    #[error("Discovery failed: {0}")]
+    Discovery(String),
+
+    /// The callback does not belong to a login started here, or came too
+    /// late.
+    #[error("Unknown or expired login")]
+    UnknownLogin,
+
+    /// The provider reported an error instead of a code.
+    #[error("Provider refused the login: {0}")]
+    Provider(String),
+
+    /// The ID token failed verification.
+    #[error("Invalid ID token: {0}")]
+    InvalidToken(String),
+
+    /// The user is not allowed in.
+    #[error("Access denied: {0}")]
+    Denied(String),
+}
+
+/// Endpoints read from the provider's discovery document.
+#[derive(Debug, Deserialize)]
+struct Discovery {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// A login waiting for the provider to redirect back.
+#[derive(Debug)]
+struct PendingLogin {
+    nonce: String,
+    verifier: String,
+    return_to: Option<String>,
+    started: Instant,
+}
+
+/// A verified provider login.
+#[derive(Debug, Clone)]
+pub struct OidcIdentity {
+    /// Stable ID of the user at the provider, `<issuer>|<sub>`.
+    pub subject: String,
+    /// Gateway username.
+    pub username: String,
+    /// Email, if the provider shared it.
+    pub email: Option<String>,
+    /// Role from the role rules.
+    pub role: UserRole,
+    /// UI path to return to after login.
+    pub return_to: Option<String>,
+}
+
+/// `OpenID` Connect client for one provider.
+pub struct OidcClient {
+    config: OidcConfig,
+    client_secret: Option<String>,
+    http: reqwest::Client,
+    discovery: OnceCell<Discovery>,
+    pending: Mutex<HashMap<String, PendingLogin>>,
+}
+
+impl OidcClient {
+    /// Create a client, reading the client secret from the environment.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the configured secret variable is not set.
+    pub fn new(config: OidcConfig) -> Result<Self, OidcError> {
+        let client_secret = match &config.client_secret_env {
+            Some(var) => Some(std::env::var(var).map_err(|_| {
+                OidcError::Config(format!("Client secret variable {var} is not set"))
+            })?),
+            None => None,
+        };
+        let http = reqwest::Client::builder()
+            .timeout(PROVIDER_TIMEOUT)
+            .build()?;
+        Ok(Self {
+            config,
+            client_secret,
+            http,
+            discovery: OnceCell::new(),
+            pending: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Provider settings.
+    #[must_use]
+    pub const fn config(&self) -> &OidcConfig {
+        &self.config
+    }
+
+    /// Discovery document, fetched once.
+    async fn discovery(&self) -> Result<&Discovery, OidcError> {
+        self.discovery
+            .get_or_try_init(|| async {
+                let url = format!(
+                    "{}/.well-known/openid-configuration",
+                    self.config.issuer.trim_end_matches('/')
+                );
+                let discovery: Discovery = self
+                    .http
+                    .get(url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+                if discovery.issuer.trim_end_matches('/')
+                    != self.config.issuer.trim_end_matches('/')
+                {
+                    return Err(OidcError::Discovery(format!(
+                        "Provider reports issuer {}",
+                        discovery.issuer
+                    )));
+                }
+                Ok::<_, OidcError>(discovery)
+            })
+            .await
+    }
+
+    /// Start a login: the provider URL to send the browser to.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the provider's discovery document cannot be read.
+    pub async fn authorization_url(&self, return_to: Option<String>) -> Result<String, OidcError> {
+        let discovery = self.discovery().await?;
+        let state = random_token();
+        let nonce = random_token();
+        let verifier = random_token();
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        let scopes = self.config.scopes.join(" ");
+
+        let url = reqwest::Url::parse_with_params(
+            &discovery.authorization_endpoint,
+            [
+                ("response_type", "code"),
+                ("client_id", self.config.client_id.as_str()),
+                ("redirect_uri", self.config.redirect_url.as_str()),
+                ("scope", scopes.as_str()),
+                ("state", state.as_str()),
+                ("nonce", nonce.as_str()),
+                ("code_challenge", challenge.as_str()),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|e| OidcError::Discovery(format!("Invalid authorization endpoint: {e}")))?;
+
+        let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+        pending.retain(|_, login| login.started.elapsed() < PENDING_TTL);
+        pending.insert(
+            state,
+            PendingLogin {
+                nonce,
+                verifier,
+                return_to,
+                started: Instant::now(),
+            },
+        );
+        drop(pending);
+        Ok(url.into())
+    }
+
+    /// Finish a login from the provider's redirect.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the login was not started here, the code exchange
+    /// fails, the ID token does not verify, or no role applies.
+    pub async fn complete(&self, code: &str, state: &str) -> Result<OidcIdentity, OidcError> {
+        let login = self
+            .pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(state)
+            .filter(|login| login.started.elapsed() < PENDING_TTL)
+            .ok_or(OidcError::UnknownLogin)?;
+        let discovery = self.discovery().await?;
+
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.config.redirect_url.as_str()),
+            ("client_id", self.config.client_id.as_str()),
+            ("code_verifier", login.verifier.as_str()),
+        ];
+        if let Some(secret) = &self.client_secret {
+            form.push(("client_secret", secret));
+        }
+        let tokens: TokenResponse = self
+            .http
+            .post(&discovery.token_endpoint)
+            .form(&form)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        // Keys are fetched per login so rotated keys are picked up
+        let jwks: JwkSet = self
+            .http
+            .get(&discovery.jwks_uri)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let claims = self.verify_id_token(&tokens.id_token, &jwks, &login.nonce)?;
+        self.identity(&claims, login.return_to)
+    }
+
+    /// Check the ID token's signature, issuer, audience, expiry, and nonce.
+    fn verify_id_token(
+        &self,
+        id_token: &str,
+        jwks: &JwkSet,
+        nonce: &str,
+    ) -> Result<Map<String, Value>, OidcError> {
+        let header = decode_header(id_token).map_err(|e| OidcError::InvalidToken(e.to_string()))?;
+        if matches!(
+            header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            return Err(OidcError::InvalidToken(
+                "Symmetric signatures are not accepted".to_string(),
+            ));
+        }
+        let jwk = match &header.kid {
+            Some(kid) => jwks.find(kid),
+            None if jwks.keys.len() == 1 => jwks.keys.first(),
+            None => None,
+        }
+        .ok_or_else(|| OidcError::InvalidToken("Signing key not found".to_string()))?;
+        let key = DecodingKey::from_jwk(jwk).map_err(|e| OidcError::InvalidToken(e.to_string()))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[
+            self.config.issuer.as_str(),
+            self.config.issuer.trim_end_matches('/'),
+        ]);
+        validation.set_audience(&[self.config.client_id.as_str()]);
+        let claims = decode::<Map<String, Value>>(id_token, &key, &validation)
+            .map_err(|e| OidcError::InvalidToken(e.to_string()))?
+            .claims;
+
+        if claims.get("nonce").and_then(Value::as_str) != Some(nonce) {
+            return Err(OidcError::InvalidToken("Nonce mismatch".to_string()));
+        }
+        Ok(claims)
+    }
+
+    /// The gateway user the verified `claims` describe.
+    fn identity(
+        &self,
+        claims: &Map<String, Value>,
+        return_to: Option<String>,
+    ) -> Result<OidcIdentity, OidcError> {
+        let sub = claims
+            .get("sub")
+            .and_then(Value::as_str)
+            .ok_or_else(|| OidcError::InvalidToken("Missing sub claim".to_string()))?;
+        let email = claims
+            .get("email")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let username = claims
+            .get(&self.config.username_claim)
+            .and_then(Value::as_str)
+            .or(email.as_deref())
+            .unwrap_or(sub)
+            .to_string();
+        let role = map_role(&self.config, claims)
+            .ok_or_else(|| OidcError::Denied(format!("No role applies to {username}")))?;
+
+        Ok(OidcIdentity {
+            subject: format!("{}|{sub}", self.config.issuer.trim_end_matches('/')),
+            username,
+            email,
+            role,
+            return_to,
+        })
+    }
+}
+
+impl std::fmt::Debug for OidcClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OidcClient")
+            .field("issuer", &self.config.issuer)
+            .field("client_id", &self.config.client_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The highest role any rule gives `claims`, or the default role.
+#[must_use]
+pub fn map_role(config: &OidcConfig, claims: &Map<String, Value>) -> Option<UserRole> {
+    config
+        .role_rules
+        .iter()
+        .filter(|rule| {
+            claims
+                .get(&rule.claim)
+                .is_some_and(|value| claim_matches(value, &rule.value))
+        })
+        .filter_map(|rule| rule.role.parse::<UserRole>().ok())
+        .max_by_key(|role| role_rank(*role))
+        .or_else(|| {
+            config
+                .default_role
+                .as_deref()
+                .and_then(|role| role.parse().ok())
+        })
+}
+
+const fn role_rank(role: UserRole) -> u8 {
+    match role {
+        UserRole::Viewer => 0,
+        UserRole::Operator => 1,
+        UserRole::Admin => 2,
+    }
+}
+
+fn claim_matches(value: &Value, expected: &str) -> bool {
+    match value {
+        Value::String(s) => s == expected,
+        Value::Array(items) => items.iter().any(|item| claim_matches(item, expected)),
+        Value::Bool(b) => b.to_string() == expected,
+        Value::Number(n) => n.to_string() == expected,
+        _ => false,
+    }
+}
+
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openclaw_core::config::OidcRoleRule;
+    use serde_json::json;
+
+    fn config() -> OidcConfig {
+        OidcConfig {
+            issuer: "https://id.example.com".to_string(),
+            client_id: "openclaw".to_string(),
+            client_secret_env: None,
+            redirect_url: "http://localhost:18789/auth/oidc/callback".to_string(),
+            ui_url: "http://localhost:3000/login".to_string(),
+            scopes: vec!["openid".to_string()],
+            username_claim: "preferred_username".to_string(),
+            role_rules: vec![
+                OidcRoleRule {
+                    claim: "groups".to_string(),
+                    value: "ops".to_string(),
+                    role: "operator".to_string(),
+                },
+                OidcRoleRule {
+                    claim: "groups".to_string(),
+                    value: "platform".to_string(),
+                    role: "admin".to_string(),
+                },
+            ],
+            default_role: None,
+        }
+    }
+
+    fn claims(value: Value) -> Map<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_map_role() {
+        let mut config = config();
+        let ops = claims(json!({ "groups": ["ops", "platform"] }));
+        assert_eq!(map_role(&config, &ops), Some(UserRole::Admin));
+
+        let other = claims(json!({ "groups": ["sales"] }));
+        assert_eq!(map_role(&config, &other), None);
+        config.default_role = Some("viewer".to_string());
+        assert_eq!(map_role(&config, &other), Some(UserRole::Viewer));
+    }
+
+    #[tokio::test]
+    async fn test_identity_and_pending_logins() {
+        let client = OidcClient::new(config()).unwrap();
+        let identity = client
+            .identity(
+                &claims(json!({
+                    "sub": "123",
+                    "email": "ada@example.com",
+                    "groups": "ops",
+                })),
+                Some("/sessions".to_string()),
+            )
+            .unwrap();
+        assert_eq!(identity.subject, "https://id.example.com|123");
+        // Falls back to the email without a username claim
+        assert_eq!(identity.username, "ada@example.com");
+        assert_eq!(identity.role, UserRole::Operator);
+
+        let denied = client.identity(&claims(json!({ "sub": "456" })), None);
+        assert!(matches!(denied, Err(OidcError::Denied(_))));
+
+        let unknown = client.complete("code", "no-such-state").await;
+        assert!(matches!(unknown, Err(OidcError::UnknownLogin)));
+    }
+}
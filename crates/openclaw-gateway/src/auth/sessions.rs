@@ -0,0 +1,393 @@
+//! Server-side session records for issued tokens.
+//!
+//! Every login starts a session: a family of refresh tokens of which only
+//! the newest works. Refreshing replaces it, and presenting a replaced
+//! refresh token again is treated as theft and ends the session. Access
+//! tokens stay self-contained, but are refused once their session ends,
+//! their own ID is revoked, or they were issued before the user last
+//! signed out everywhere.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::AuthError;
+use super::jwt::{Claims, TokenPair};
+
+/// A login and the refresh tokens issued from it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoginSession {
+    /// Session (token family) ID.
+    pub id: String,
+    /// User the session belongs to.
+    pub user_id: String,
+    /// Username at login.
+    pub username: String,
+    /// When the user logged in.
+    pub created_at: DateTime<Utc>,
+    /// When the tokens were last refreshed.
+    pub refreshed_at: Option<DateTime<Utc>>,
+    /// When the current refresh token expires.
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionRecord {
+    #[serde(flatten)]
+    session: LoginSession,
+    /// ID of the only refresh token still accepted.
+    current_jti: String,
+}
+
+/// Sessions, revoked token IDs and per-user cutoffs, stored alongside the
+/// users.
+pub struct LoginSessionStore {
+    sessions: sled::Tree,
+    revoked: sled::Tree,
+    cutoffs: sled::Tree,
+}
+
+impl LoginSessionStore {
+    /// Open the session tables in `db`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a tree cannot be opened.
+    pub fn open(db: &sled::Db) -> Result<Self, AuthError> {
+        let tree = |name: &str| {
+            db.open_tree(name)
+                .map_err(|e| AuthError::Storage(format!("Failed to open {name} tree: {e}")))
+        };
+        Ok(Self {
+            sessions: tree("sessions")?,
+            revoked: tree("revoked_tokens")?,
+            cutoffs: tree("session_cutoffs")?,
+        })
+    }
+
+    /// Record the session started by a login that issued `tokens`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage fails.
+    pub fn start(
+        &self,
+        user_id: &str,
+        username: &str,
+        tokens: &TokenPair,
+    ) -> Result<(), AuthError> {
+        let record = SessionRecord {
+            session: LoginSession {
+                id: tokens.family_id.clone(),
+                user_id: user_id.to_string(),
+                username: username.to_string(),
+                created_at: Utc::now(),
+                refreshed_at: None,
+                expires_at: tokens.refresh_expires_at,
+            },
+            current_jti: tokens.refresh_jti.clone(),
+        };
+        self.sessions
+            .insert(record.session.id.as_bytes(), encode(&record)?)
+            .map_err(storage("Insert"))?;
+        self.prune()
+    }
+
+    /// Replace the refresh token `claims` with the one in `tokens`.
+    ///
+    /// Presenting a refresh token that was already replaced ends the
+    /// session, since either it or its replacement has leaked.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the session has ended, the token was already used,
+    /// or storage fails.
+    pub fn rotate(&self, claims: &Claims, tokens: &TokenPair) -> Result<(), AuthError> {
+        let (Some(family_id), Some(jti)) = (&claims.family_id, &claims.jti) else {
+            return Err(session_ended());
+        };
+        loop {
+            let Some(current) = self.sessions.get(family_id).map_err(storage("Get"))? else {
+                return Err(session_ended());
+            };
+            let mut record: SessionRecord = decode(&current)?;
+            if record.current_jti != *jti {
+                self.sessions.remove(family_id).map_err(storage("Remove"))?;
+                tracing::warn!(
+                    target: "security",
+                    session = %family_id,
+                    username = %record.session.username,
+                    "Replaced refresh token reused; session revoked"
+                );
+                return Err(AuthError::TokenError(
+                    "Refresh token already used; session revoked".to_string(),
+                ));
+            }
+
+            record.current_jti.clone_from(&tokens.refresh_jti);
+            record.session.refreshed_at = Some(Utc::now());
+            record.session.expires_at = tokens.refresh_expires_at;
+            // A concurrent refresh got there first; the next pass sees the
+            // token as reused
+            if self
+                .sessions
+                .compare_and_swap(family_id, Some(current), Some(encode(&record)?))
+                .map_err(storage("Update"))?
+                .is_ok()
+            {
+                return self.flush();
+            }
+        }
+    }
+
+    /// Refuse `claims` if their session has ended, their ID is revoked, or
+    /// they predate the user's last sign-out everywhere.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the token is no longer accepted or storage fails.
+    pub fn check(&self, claims: &Claims) -> Result<(), AuthError> {
+        if let Some(jti) = &claims.jti {
+            if self.revoked.contains_key(jti).map_err(storage("Get"))? {
+                return Err(AuthError::TokenError("Token revoked".to_string()));
+            }
+        }
+        if let Some(family_id) = &claims.family_id {
+            if !self
+                .sessions
+                .contains_key(family_id)
+                .map_err(storage("Get"))?
+            {
+                return Err(session_ended());
+            }
+        }
+        let cutoff = self
+            .cutoffs
+            .get(&claims.sub)
+            .map_err(storage("Get"))?
+            .map(|value| timestamp(&value));
+        if cutoff.is_some_and(|cutoff| claims.iat < cutoff) {
+            return Err(session_ended());
+        }
+        Ok(())
+    }
+
+    /// Refuse the token `claims` belong to until it expires.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage fails.
+    pub fn revoke_token(&self, claims: &Claims) -> Result<(), AuthError> {
+        if let Some(jti) = &claims.jti {
+            self.revoked
+                .insert(jti.as_bytes(), claims.exp.to_be_bytes().to_vec())
+                .map_err(storage("Insert"))?;
+        }
+        self.prune()
+    }
+
+    /// End session `id`. Returns whether it existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage fails.
+    pub fn revoke_session(&self, id: &str) -> Result<bool, AuthError> {
+        let removed = self.sessions.remove(id).map_err(storage("Remove"))?;
+        self.flush()?;
+        Ok(removed.is_some())
+    }
+
+    /// End every session of `user_id` and refuse every token issued to it
+    /// so far. Returns how many sessions ended.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage fails.
+    pub fn revoke_user(&self, user_id: &str) -> Result<usize, AuthError> {
+        // Tokens issued within the current second stay valid; `iat` has no
+        // finer resolution
+        self.cutoffs
+            .insert(
+                user_id.as_bytes(),
+                Utc::now().timestamp().to_be_bytes().to_vec(),
+            )
+            .map_err(storage("Insert"))?;
+        let mut ended = 0;
+        for session in self.list(Some(user_id))? {
+            if self
+                .sessions
+                .remove(&session.id)
+                .map_err(storage("Remove"))?
+                .is_some()
+            {
+                ended += 1;
+            }
+        }
+        self.flush()?;
+        Ok(ended)
+    }
+
+    /// Open sessions, of `user_id` only when given, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if storage fails.
+    pub fn list(&self, user_id: Option<&str>) -> Result<Vec<LoginSession>, AuthError> {
+        let now = Utc::now();
+        let mut sessions = Vec::new();
+        for result in &self.sessions {
+            let (_, value) = result.map_err(storage("Iter"))?;
+            let record: SessionRecord = decode(&value)?;
+            if record.session.expires_at > now
+                && user_id.is_none_or(|id| id == record.session.user_id)
+            {
+                sessions.push(record.session);
+            }
+        }
+        sessions.sort_by_key(|session| session.created_at);
+        Ok(sessions)
+    }
+
+    /// Drop expired sessions and revoked IDs of expired tokens.
+    fn prune(&self) -> Result<(), AuthError> {
+        let now = Utc::now();
+        for result in &self.sessions {
+            let (key, value) = result.map_err(storage("Iter"))?;
+            if decode::<SessionRecord>(&value).is_ok_and(|r| r.session.expires_at <= now) {
+                self.sessions.remove(key).map_err(storage("Remove"))?;
+            }
+        }
+        for result in &self.revoked {
+            let (key, value) = result.map_err(storage("Iter"))?;
+            if timestamp(&value) <= now.timestamp() {
+                self.revoked.remove(key).map_err(storage("Remove"))?;
+            }
+        }
+        self.flush()
+    }
+
+    fn flush(&self) -> Result<(), AuthError> {
+        for tree in [&self.sessions, &self.revoked, &self.cutoffs] {
+            tree.flush().map_err(storage("Flush"))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for LoginSessionStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoginSessionStore")
+            .field("sessions", &self.sessions.len())
+            .field("revoked", &self.revoked.len())
+            .finish_non_exhaustive()
+    }
+}
+
+fn session_ended() -> AuthError {
+    AuthError::TokenError("LoginSession has ended".to_string())
+}
+
+fn storage(action: &'static str) -> impl Fn(sled::Error) -> AuthError {
+    move |e| AuthError::Storage(format!("{action} error: {e}"))
+}
+
+fn encode(record: &SessionRecord) -> Result<Vec<u8>, AuthError> {
+    serde_json::to_vec(record).map_err(|e| AuthError::Storage(format!("Serialization error: {e}")))
+}
+
+fn decode<T: serde::de::DeserializeOwned>(value: &[u8]) -> Result<T, AuthError> {
+    serde_json::from_slice(value)
+        .map_err(|e| AuthError::Storage(format!("Deserialization error: {e}")))
+}
+
+fn timestamp(value: &[u8]) -> i64 {
+    value.try_into().map_or(i64::MAX, i64::from_be_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{JwtManager, UserRole};
+    use std::time::Duration;
+
+    fn setup() -> (LoginSessionStore, JwtManager) {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let jwt = JwtManager::new(
+            &JwtManager::generate_secret(),
+            Duration::from_secs(3600),
+            Duration::from_secs(86400),
+        );
+        (LoginSessionStore::open(&db).unwrap(), jwt)
+    }
+
+    fn login(store: &LoginSessionStore, jwt: &JwtManager) -> TokenPair {
+        let tokens = jwt
+            .create_token_pair("user_1", "ada", UserRole::Operator)
+            .unwrap();
+        store.start("user_1", "ada", &tokens).unwrap();
+        tokens
+    }
+
+    fn refresh(
+        store: &LoginSessionStore,
+        jwt: &JwtManager,
+        refresh_token: &str,
+    ) -> Result<TokenPair, AuthError> {
+        let claims = jwt.validate_token(refresh_token)?;
+        store.check(&claims)?;
+        let tokens = jwt.refresh_tokens(refresh_token)?;
+        store.rotate(&claims, &tokens)?;
+        Ok(tokens)
+    }
+
+    #[test]
+    fn test_rotation_and_reuse() {
+        let (store, jwt) = setup();
+        let first = login(&store, &jwt);
+
+        let second = refresh(&store, &jwt, &first.refresh_token).unwrap();
+        assert_eq!(second.family_id, first.family_id);
+        let session = &store.list(None).unwrap()[0];
+        assert!(session.refreshed_at.is_some());
+
+        // Replaying the replaced token ends the session for everyone
+        assert!(refresh(&store, &jwt, &first.refresh_token).is_err());
+        assert!(refresh(&store, &jwt, &second.refresh_token).is_err());
+        let access = jwt.validate_access_token(&second.access_token).unwrap();
+        assert!(store.check(&access).is_err());
+        assert!(store.list(None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_revocation() {
+        let (store, jwt) = setup();
+        let tokens = login(&store, &jwt);
+        let access = jwt.validate_access_token(&tokens.access_token).unwrap();
+        assert!(store.check(&access).is_ok());
+
+        store.revoke_token(&access).unwrap();
+        assert!(store.check(&access).is_err());
+
+        let other = login(&store, &jwt);
+        assert!(store.revoke_session(&other.family_id).unwrap());
+        assert!(!store.revoke_session(&other.family_id).unwrap());
+        assert!(refresh(&store, &jwt, &other.refresh_token).is_err());
+    }
+
+    #[test]
+    fn test_revoke_user() {
+        let (store, jwt) = setup();
+        login(&store, &jwt);
+        let tokens = login(&store, &jwt);
+        assert_eq!(store.list(Some("user_1")).unwrap().len(), 2);
+        assert!(store.list(Some("user_2")).unwrap().is_empty());
+
+        assert_eq!(store.revoke_user("user_1").unwrap(), 2);
+        assert!(store.list(None).unwrap().is_empty());
+
+        // Tokens without a session are caught by the cutoff
+        let mut legacy = jwt.validate_access_token(&tokens.access_token).unwrap();
+        legacy.family_id = None;
+        legacy.iat -= 60;
+        assert!(store.check(&legacy).is_err());
+    }
+}
@@ -0,0 +1,325 @@
+//! Failed-login throttling.
+//!
+//! Every failed login counts against both the username tried and the
+//! client address it came from. Each failure delays the answer twice as
+//! long as the one before, and too many within the window lock the
+//! username or address out for a while. Counts live in memory, so a
+//! restart clears them.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Serialize, Serializer};
+
+use super::config::LoginThrottleConfig;
+
+/// What failed logins are counted against.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ThrottleKey {
+    /// A username, whether or not such a user exists.
+    User(String),
+    /// A client address.
+    Ip(IpAddr),
+}
+
+impl fmt::Display for ThrottleKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::User(name) => write!(f, "user:{name}"),
+            Self::Ip(addr) => write!(f, "ip:{addr}"),
+        }
+    }
+}
+
+impl FromStr for ThrottleKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("user", name)) if !name.is_empty() => Ok(Self::User(name.to_string())),
+            Some(("ip", addr)) => addr
+                .parse()
+                .map(Self::Ip)
+                .map_err(|_| format!("Invalid address: {addr}")),
+            _ => Err(format!(
+                "Invalid key '{s}' (expected user:<name> or ip:<address>)"
+            )),
+        }
+    }
+}
+
+impl Serialize for ThrottleKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// A username or address with recent failed logins.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ThrottleEntry {
+    /// What the failures were counted against.
+    pub key: ThrottleKey,
+    /// Failures in the current window.
+    pub failures: u32,
+    /// End of the lockout, if locked out.
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+/// How a failed login was counted.
+#[derive(Debug, Clone)]
+pub struct FailedLogin {
+    /// How long to wait before answering.
+    pub delay: Duration,
+    /// Keys this failure locked out.
+    pub locked: Vec<ThrottleEntry>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Failures {
+    count: u32,
+    first: DateTime<Utc>,
+    locked_until: Option<DateTime<Utc>>,
+}
+
+/// Failed-login counts and lockouts.
+#[derive(Debug)]
+pub struct LoginThrottle {
+    config: LoginThrottleConfig,
+    failures: Mutex<HashMap<ThrottleKey, Failures>>,
+}
+
+impl LoginThrottle {
+    /// Create a throttle with no failures recorded.
+    #[must_use]
+    pub fn new(config: LoginThrottleConfig) -> Self {
+        Self {
+            config,
+            failures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// When the lockout on `username` or `ip` ends, if either is locked out.
+    #[must_use]
+    pub fn locked_until(&self, username: &str, ip: Option<IpAddr>) -> Option<DateTime<Utc>> {
+        self.locked_until_at(username, ip, Utc::now())
+    }
+
+    fn locked_until_at(
+        &self,
+        username: &str,
+        ip: Option<IpAddr>,
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let failures = self.failures.lock().unwrap_or_else(PoisonError::into_inner);
+        keys(username, ip)
+            .filter_map(|key| failures.get(&key).and_then(|f| f.locked_until))
+            .filter(|until| *until > now)
+            .max()
+    }
+
+    /// Count a failed login for `username` from `ip`.
+    pub fn record_failure(&self, username: &str, ip: Option<IpAddr>) -> FailedLogin {
+        self.record_failure_at(username, ip, Utc::now())
+    }
+
+    fn record_failure_at(
+        &self,
+        username: &str,
+        ip: Option<IpAddr>,
+        now: DateTime<Utc>,
+    ) -> FailedLogin {
+        let mut outcome = FailedLogin {
+            delay: Duration::ZERO,
+            locked: Vec::new(),
+        };
+        if !self.config.enabled {
+            return outcome;
+        }
+
+        let mut failures = self.failures.lock().unwrap_or_else(PoisonError::into_inner);
+        failures.retain(|_, f| !self.expired(f, now));
+        for key in keys(username, ip) {
+            let entry = failures.entry(key.clone()).or_insert(Failures {
+                count: 0,
+                first: now,
+                locked_until: None,
+            });
+            entry.count = entry.count.saturating_add(1);
+            outcome.delay = outcome.delay.max(self.delay_for(entry.count));
+
+            if entry.count >= self.config.max_failures && entry.locked_until.is_none() {
+                let until = now
+                    .checked_add_signed(seconds(self.config.lockout_secs))
+                    .unwrap_or(DateTime::<Utc>::MAX_UTC);
+                entry.locked_until = Some(until);
+                outcome.locked.push(ThrottleEntry {
+                    key,
+                    failures: entry.count,
+                    locked_until: Some(until),
+                });
+            }
+        }
+        drop(failures);
+        outcome
+    }
+
+    /// Forget the failures of `username` after it logs in. Failures from the
+    /// address still count.
+    pub fn record_success(&self, username: &str) {
+        self.failures
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&ThrottleKey::User(username.to_string()));
+    }
+
+    /// Usernames and addresses with recent failures, locked out or not.
+    #[must_use]
+    pub fn entries(&self) -> Vec<ThrottleEntry> {
+        self.entries_at(Utc::now())
+    }
+
+    fn entries_at(&self, now: DateTime<Utc>) -> Vec<ThrottleEntry> {
+        let mut failures = self.failures.lock().unwrap_or_else(PoisonError::into_inner);
+        failures.retain(|_, f| !self.expired(f, now));
+        let mut entries: Vec<ThrottleEntry> = failures
+            .iter()
+            .map(|(key, f)| ThrottleEntry {
+                key: key.clone(),
+                failures: f.count,
+                locked_until: f.locked_until,
+            })
+            .collect();
+        drop(failures);
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        entries
+    }
+
+    /// Clear the failures of `key`, or of everything when `key` is `None`.
+    /// Returns how many entries were cleared.
+    pub fn clear(&self, key: Option<&ThrottleKey>) -> usize {
+        let mut failures = self.failures.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(key) = key {
+            usize::from(failures.remove(key).is_some())
+        } else {
+            let count = failures.len();
+            failures.clear();
+            count
+        }
+    }
+
+    /// Whether `failures` no longer count: the lockout is over, or the
+    /// window passed without one.
+    fn expired(&self, failures: &Failures, now: DateTime<Utc>) -> bool {
+        failures.locked_until.map_or_else(
+            || {
+                failures
+                    .first
+                    .checked_add_signed(seconds(self.config.window_secs))
+                    .is_some_and(|end| end <= now)
+            },
+            |until| until <= now,
+        )
+    }
+
+    /// Delay before answering the `count`th failure in a row.
+    fn delay_for(&self, count: u32) -> Duration {
+        let factor = 2u64.saturating_pow(count.saturating_sub(1));
+        let delay_ms = self
+            .config
+            .base_delay_ms
+            .saturating_mul(factor)
+            .min(self.config.max_delay_ms);
+        Duration::from_millis(delay_ms)
+    }
+}
+
+fn keys(username: &str, ip: Option<IpAddr>) -> impl Iterator<Item = ThrottleKey> {
+    std::iter::once(ThrottleKey::User(username.to_string())).chain(ip.map(ThrottleKey::Ip))
+}
+
+fn seconds(secs: u64) -> TimeDelta {
+    TimeDelta::try_seconds(i64::try_from(secs).unwrap_or(i64::MAX)).unwrap_or(TimeDelta::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn throttle() -> LoginThrottle {
+        LoginThrottle::new(LoginThrottleConfig {
+            max_failures: 3,
+            window_secs: 60,
+            lockout_secs: 300,
+            base_delay_ms: 100,
+            max_delay_ms: 300,
+            ..LoginThrottleConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_lockout_and_delays() {
+        let throttle = throttle();
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let now = Utc::now();
+
+        let first = throttle.record_failure_at("alice", Some(ip), now);
+        assert_eq!(first.delay, Duration::from_millis(100));
+        let second = throttle.record_failure_at("alice", Some(ip), now);
+        assert_eq!(second.delay, Duration::from_millis(200));
+        assert!(throttle.locked_until_at("alice", Some(ip), now).is_none());
+
+        let third = throttle.record_failure_at("alice", Some(ip), now);
+        assert_eq!(third.delay, Duration::from_millis(300));
+        assert_eq!(third.locked.len(), 2);
+        let until = now + TimeDelta::seconds(300);
+        assert_eq!(throttle.locked_until_at("alice", None, now), Some(until));
+        // The address is locked out for other usernames too
+        assert_eq!(throttle.locked_until_at("bob", Some(ip), now), Some(until));
+
+        // Lockouts end on their own
+        assert!(throttle.locked_until_at("alice", Some(ip), until).is_none());
+        assert!(throttle.entries_at(until).is_empty());
+    }
+
+    #[test]
+    fn test_window_and_success() {
+        let throttle = throttle();
+        let now = Utc::now();
+
+        throttle.record_failure_at("alice", None, now);
+        throttle.record_failure_at("alice", None, now);
+        // The window passed, so the count starts over
+        let later = now + TimeDelta::seconds(61);
+        let failure = throttle.record_failure_at("alice", None, later);
+        assert!(failure.locked.is_empty());
+        assert_eq!(failure.delay, Duration::from_millis(100));
+
+        throttle.record_success("alice");
+        assert!(throttle.entries_at(later).is_empty());
+    }
+
+    #[test]
+    fn test_clear() {
+        let throttle = throttle();
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        for _ in 0..3 {
+            throttle.record_failure("alice", Some(ip));
+        }
+        assert!(throttle.locked_until("alice", None).is_some());
+
+        let key: ThrottleKey = "user:alice".parse().unwrap();
+        assert_eq!(throttle.clear(Some(&key)), 1);
+        assert!(throttle.locked_until("alice", None).is_none());
+        assert!(throttle.locked_until("alice", Some(ip)).is_some());
+
+        assert_eq!(throttle.clear(None), 1);
+        assert!(throttle.entries().is_empty());
+        assert!("host:x".parse::<ThrottleKey>().is_err());
+        assert_eq!(ThrottleKey::Ip(ip).to_string(), "ip:10.0.0.1");
+    }
+}
@@ -85,6 +85,10 @@ pub struct User {
     pub last_login: Option<DateTime<Utc>>,
     /// Whether the account is active.
     pub active: bool,
+    /// Identity at the single sign-on provider, `<issuer>|<sub>`, for
+    /// accounts created by an OIDC login.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
 }
 
 impl User {
@@ -111,6 +115,7 @@ impl User {
             created_at: Utc::now(),
             last_login: None,
             active: true,
+            external_id: None,
         })
     }
 
@@ -0,0 +1,498 @@
+//! One message sent to many chats.
+//!
+//! A broadcast is recorded as a `broadcast:<id>` session: `BroadcastStarted`
+//! with the message and its targets, a `BroadcastDelivered` per chat, and
+//! `BroadcastCompleted` with the totals. Messages go out one at a time at
+//! the channel's `channels.broadcast` rate, backing off when the platform
+//! reports rate limiting.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use thiserror::Error;
+
+use openclaw_channels::{ChannelError, ChannelOutbound, OutboundContext, OutboundPipeline};
+use openclaw_core::config::{AllowlistEntry, BroadcastConfig};
+use openclaw_core::events::{EventStore, EventStoreError, SessionEvent, SessionEventKind};
+use openclaw_core::types::{DeliveryResult, SessionKey};
+
+use crate::shutdown::RunGuard;
+
+/// Agent ID recorded on broadcast sessions.
+pub const BROADCAST_AGENT: &str = "broadcast";
+
+/// Longest wait before retrying a rate-limited message.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Broadcast target errors.
+#[derive(Error, Debug)]
+pub enum BroadcastError {
+    /// No group with this name.
+    #[error("Broadcast group not found: {0}")]
+    UnknownGroup(String),
+
+    /// The requested channel differs from the group's.
+    #[error("Broadcast group '{group}' is on {expected}, not {channel}")]
+    ChannelMismatch {
+        /// Group name.
+        group: String,
+        /// The group's channel.
+        expected: String,
+        /// The requested channel.
+        channel: String,
+    },
+
+    /// Neither a channel nor a group was given.
+    #[error("Missing channel")]
+    MissingChannel,
+
+    /// The targets resolved to no chats.
+    #[error("No chats to broadcast to")]
+    NoTargets,
+}
+
+/// Who a broadcast goes to, as requested.
+#[derive(Debug, Clone, Default)]
+pub struct BroadcastTargets {
+    /// Channel ID (optional when a group is given).
+    pub channel: Option<String>,
+    /// Configured group to include.
+    pub group: Option<String>,
+    /// Include every peer allowlisted on the channel by name.
+    pub allowlist: bool,
+    /// Chat IDs to include.
+    pub chat_ids: Vec<String>,
+}
+
+impl BroadcastTargets {
+    /// Resolve to a channel and its chat IDs: the group's chats, then
+    /// allowlisted peers, then explicit chat IDs, without duplicates.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for an unknown group, a channel other than the
+    /// group's, no channel at all, or no chats.
+    pub fn resolve(
+        &self,
+        config: &BroadcastConfig,
+        allowlist: &[AllowlistEntry],
+    ) -> Result<(String, Vec<String>), BroadcastError> {
+        let group = match &self.group {
+            Some(name) => Some((
+                name,
+                config
+                    .groups
+                    .get(name)
+                    .ok_or_else(|| BroadcastError::UnknownGroup(name.clone()))?,
+            )),
+            None => None,
+        };
+        let channel = match (&self.channel, group) {
+            (Some(channel), Some((name, group))) if *channel != group.channel => {
+                return Err(BroadcastError::ChannelMismatch {
+                    group: name.clone(),
+                    expected: group.channel.clone(),
+                    channel: channel.clone(),
+                });
+            }
+            (Some(channel), _) => channel.clone(),
+            (None, Some((_, group))) => group.channel.clone(),
+            (None, None) => return Err(BroadcastError::MissingChannel),
+        };
+
+        let allowlisted = allowlist
+            .iter()
+            .filter(|entry| self.allowlist && (entry.channel == "*" || entry.channel == channel))
+            .map(|entry| &entry.peer_id)
+            .filter(|peer| *peer != "*");
+        let mut seen = HashSet::new();
+        let chat_ids: Vec<String> = group
+            .into_iter()
+            .flat_map(|(_, group)| &group.chat_ids)
+            .chain(allowlisted)
+            .chain(&self.chat_ids)
+            .filter(|id| seen.insert(*id))
+            .cloned()
+            .collect();
+        if chat_ids.is_empty() {
+            return Err(BroadcastError::NoTargets);
+        }
+        Ok((channel, chat_ids))
+    }
+}
+
+/// Session key of a broadcast.
+#[must_use]
+pub fn session_key(broadcast_id: &str) -> SessionKey {
+    SessionKey::new(format!("broadcast:{broadcast_id}"))
+}
+
+/// A broadcast ready to send.
+pub struct Broadcast {
+    /// Broadcast ID.
+    pub id: String,
+    /// Channel to send on.
+    pub channel: Arc<dyn ChannelOutbound>,
+    /// Formats and chunks the message for the channel.
+    pub outbound: Arc<OutboundPipeline>,
+    /// Store the broadcast is recorded in.
+    pub store: Arc<EventStore>,
+    /// Message text.
+    pub content: String,
+    /// Chat IDs, in send order.
+    pub targets: Vec<String>,
+    /// Delay between messages.
+    pub interval: Duration,
+    /// Retries of a rate-limited message.
+    pub max_retries: u32,
+}
+
+impl Broadcast {
+    /// Record the start of the broadcast.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the events cannot be stored.
+    pub fn start(&self, sent_by: &str) -> Result<(), EventStoreError> {
+        self.append(SessionEventKind::SessionStarted {
+            channel: self.channel.id().to_string(),
+            peer_id: BROADCAST_AGENT.to_string(),
+        })?;
+        self.append(SessionEventKind::BroadcastStarted {
+            broadcast_id: self.id.clone(),
+            content: self.content.clone(),
+            targets: self.targets.clone(),
+            sent_by: sent_by.to_string(),
+        })
+    }
+
+    /// Send to every target, recording each outcome and then the totals.
+    pub async fn run(self, _run: RunGuard) {
+        let (mut delivered, mut failed) = (0, 0);
+        for (i, chat_id) in self.targets.iter().enumerate() {
+            if i > 0 {
+                tokio::time::sleep(self.interval).await;
+            }
+            let (result, attempts) = self.send(chat_id).await;
+            let (message_id, error) = match result {
+                Ok(result) => {
+                    delivered += 1;
+                    (Some(result.message_id), None)
+                }
+                Err(e) => {
+                    failed += 1;
+                    (None, Some(e.to_string()))
+                }
+            };
+            self.record(SessionEventKind::BroadcastDelivered {
+                chat_id: chat_id.clone(),
+                message_id,
+                error,
+                attempts,
+            });
+        }
+
+        tracing::info!(
+            "Broadcast {} finished: {} delivered, {} failed",
+            self.id,
+            delivered,
+            failed
+        );
+        self.record(SessionEventKind::BroadcastCompleted { delivered, failed });
+    }
+
+    /// Send to one chat, retrying while the channel is rate limited.
+    async fn send(&self, chat_id: &str) -> (Result<DeliveryResult, ChannelError>, u32) {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let ctx = OutboundContext {
+                chat_id: chat_id.to_string(),
+                reply_to: None,
+                thread_id: None,
+            };
+            match self
+                .outbound
+                .send(self.channel.as_ref(), ctx, &self.content)
+                .await
+            {
+                Err(ChannelError::RateLimited) if attempts <= self.max_retries => {
+                    tokio::time::sleep(backoff(self.interval, attempts)).await;
+                }
+                result => return (result, attempts),
+            }
+        }
+    }
+
+    fn append(&self, kind: SessionEventKind) -> Result<(), EventStoreError> {
+        self.store
+            .append(&SessionEvent::new(
+                session_key(&self.id),
+                BROADCAST_AGENT.to_string(),
+                kind,
+            ))
+            .map(|_| ())
+    }
+
+    fn record(&self, kind: SessionEventKind) {
+        if let Err(e) = self.append(kind) {
+            tracing::warn!("Failed to record broadcast {}: {}", self.id, e);
+        }
+    }
+}
+
+/// Wait before retry `attempt` (from 1) of a rate-limited message: at least
+/// a second, doubling each time.
+fn backoff(interval: Duration, attempt: u32) -> Duration {
+    interval
+        .max(Duration::from_secs(1))
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(MAX_BACKOFF)
+}
+
+/// Outcome for one chat.
+#[derive(Debug, Clone, Serialize)]
+pub struct BroadcastDelivery {
+    /// Chat ID.
+    pub chat_id: String,
+    /// Platform message ID, if delivered.
+    pub message_id: Option<String>,
+    /// Delivery error, if it could not be sent.
+    pub error: Option<String>,
+    /// Send attempts made.
+    pub attempts: u32,
+}
+
+/// Progress of a broadcast, read back from its events.
+#[derive(Debug, Clone, Serialize)]
+pub struct BroadcastStatus {
+    /// Broadcast ID.
+    pub broadcast_id: String,
+    /// Message text.
+    pub content: String,
+    /// Who started the broadcast.
+    pub sent_by: String,
+    /// When it started.
+    pub started_at: DateTime<Utc>,
+    /// Chats targeted.
+    pub total: usize,
+    /// Chats delivered to.
+    pub delivered: usize,
+    /// Chats that could not be delivered to.
+    pub failed: usize,
+    /// Chats not tried yet.
+    pub pending: usize,
+    /// Whether every chat has been tried.
+    pub completed: bool,
+    /// Outcomes so far, in send order.
+    pub deliveries: Vec<BroadcastDelivery>,
+}
+
+impl BroadcastStatus {
+    /// Summarize a broadcast session's events (`None` if there is no
+    /// `BroadcastStarted`).
+    #[must_use]
+    pub fn from_events(events: &[SessionEvent]) -> Option<Self> {
+        let mut status: Option<Self> = None;
+        for event in events {
+            match &event.kind {
+                SessionEventKind::BroadcastStarted {
+                    broadcast_id,
+                    content,
+                    targets,
+                    sent_by,
+                } => {
+                    status = Some(Self {
+                        broadcast_id: broadcast_id.clone(),
+                        content: content.clone(),
+                        sent_by: sent_by.clone(),
+                        started_at: event.timestamp,
+                        total: targets.len(),
+                        delivered: 0,
+                        failed: 0,
+                        pending: targets.len(),
+                        completed: false,
+                        deliveries: Vec::new(),
+                    });
+                }
+                SessionEventKind::BroadcastDelivered {
+                    chat_id,
+                    message_id,
+                    error,
+                    attempts,
+                } => {
+                    let Some(status) = status.as_mut() else {
+                        continue;
+                    };
+                    if message_id.is_some() {
+                        status.delivered += 1;
+                    } else {
+                        status.failed += 1;
+                    }
+                    status.pending = status.pending.saturating_sub(1);
+                    status.deliveries.push(BroadcastDelivery {
+                        chat_id: chat_id.clone(),
+                        message_id: message_id.clone(),
+                        error: error.clone(),
+                        attempts: *attempts,
+                    });
+                }
+                SessionEventKind::BroadcastCompleted { .. } => {
+                    if let Some(status) = status.as_mut() {
+                        status.completed = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openclaw_core::config::BroadcastGroupConfig;
+
+    fn config() -> BroadcastConfig {
+        let mut config = BroadcastConfig::default();
+        config.groups.insert(
+            "oncall".to_string(),
+            BroadcastGroupConfig {
+                description: None,
+                channel: "slack".to_string(),
+                chat_ids: vec!["U1".to_string(), "U2".to_string()],
+            },
+        );
+        config
+    }
+
+    fn allowlist() -> Vec<AllowlistEntry> {
+        [
+            ("slack", "U2"),
+            ("*", "U3"),
+            ("telegram", "42"),
+            ("slack", "*"),
+        ]
+        .into_iter()
+        .map(|(channel, peer_id)| AllowlistEntry {
+            channel: channel.to_string(),
+            peer_id: peer_id.to_string(),
+            label: None,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_resolve_targets() {
+        let targets = BroadcastTargets {
+            group: Some("oncall".to_string()),
+            allowlist: true,
+            chat_ids: vec!["U4".to_string(), "U1".to_string()],
+            ..BroadcastTargets::default()
+        };
+        let (channel, chat_ids) = targets.resolve(&config(), &allowlist()).unwrap();
+        assert_eq!(channel, "slack");
+        assert_eq!(chat_ids, ["U1", "U2", "U3", "U4"]);
+
+        let targets = BroadcastTargets {
+            channel: Some("telegram".to_string()),
+            allowlist: true,
+            ..BroadcastTargets::default()
+        };
+        let (_, chat_ids) = targets.resolve(&config(), &allowlist()).unwrap();
+        assert_eq!(chat_ids, ["U3", "42"]);
+    }
+
+    #[test]
+    fn test_resolve_errors() {
+        let resolve = |targets: BroadcastTargets| targets.resolve(&config(), &allowlist());
+        assert!(matches!(
+            resolve(BroadcastTargets {
+                group: Some("nope".to_string()),
+                ..BroadcastTargets::default()
+            }),
+            Err(BroadcastError::UnknownGroup(_))
+        ));
+        assert!(matches!(
+            resolve(BroadcastTargets {
+                channel: Some("telegram".to_string()),
+                group: Some("oncall".to_string()),
+                ..BroadcastTargets::default()
+            }),
+            Err(BroadcastError::ChannelMismatch { .. })
+        ));
+        assert!(matches!(
+            resolve(BroadcastTargets::default()),
+            Err(BroadcastError::MissingChannel)
+        ));
+        assert!(matches!(
+            resolve(BroadcastTargets {
+                channel: Some("discord".to_string()),
+                ..BroadcastTargets::default()
+            }),
+            Err(BroadcastError::NoTargets)
+        ));
+    }
+
+    #[test]
+    fn test_backoff() {
+        let interval = Duration::from_millis(50);
+        assert_eq!(backoff(interval, 1), Duration::from_secs(1));
+        assert_eq!(backoff(interval, 3), Duration::from_secs(4));
+        assert_eq!(backoff(interval, 20), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_status_from_events() {
+        let key = session_key("b1");
+        let event = |kind| SessionEvent::new(key.clone(), BROADCAST_AGENT.to_string(), kind);
+        let mut events = vec![
+            event(SessionEventKind::SessionStarted {
+                channel: "slack".to_string(),
+                peer_id: BROADCAST_AGENT.to_string(),
+            }),
+            event(SessionEventKind::BroadcastStarted {
+                broadcast_id: "b1".to_string(),
+                content: "Maintenance at 10:00".to_string(),
+                targets: vec!["U1".to_string(), "U2".to_string(), "U3".to_string()],
+                sent_by: "ops".to_string(),
+            }),
+            event(SessionEventKind::BroadcastDelivered {
+                chat_id: "U1".to_string(),
+                message_id: Some("m1".to_string()),
+                error: None,
+                attempts: 1,
+            }),
+            event(SessionEventKind::BroadcastDelivered {
+                chat_id: "U2".to_string(),
+                message_id: None,
+                error: Some("Rate limited".to_string()),
+                attempts: 4,
+            }),
+        ];
+
+        let status = BroadcastStatus::from_events(&events).unwrap();
+        assert_eq!(
+            (
+                status.total,
+                status.delivered,
+                status.failed,
+                status.pending
+            ),
+            (3, 1, 1, 1)
+        );
+        assert!(!status.completed);
+        assert_eq!(status.deliveries[1].attempts, 4);
+
+        events.push(event(SessionEventKind::BroadcastCompleted {
+            delivered: 1,
+            failed: 2,
+        }));
+        assert!(BroadcastStatus::from_events(&events).unwrap().completed);
+        assert!(BroadcastStatus::from_events(&events[..1]).is_none());
+    }
+}
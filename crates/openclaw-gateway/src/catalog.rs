@@ -0,0 +1,889 @@
+//! Catalog of the gateway's JSON-RPC methods.
+//!
+//! [`METHODS`] describes every method the gateway dispatches: its
+//! parameters and the role needed to call it. `rpc.discover` serves it as
+//! an [`OpenRPC`](https://spec.open-rpc.org) document so clients can validate
+//! calls and generate bindings.
+
+use serde::Serialize;
+use serde_json::{Value, json};
+
+/// `OpenRPC` specification version of the discovery document.
+pub const OPENRPC_VERSION: &str = "1.2.6";
+
+/// Role needed to call a method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    /// No authentication (the default public methods).
+    Public,
+    /// Any authenticated user. Session data is limited to the caller's
+    /// namespaces.
+    Viewer,
+    /// Admins and operators. For sessions, in the session's namespace.
+    Operator,
+    /// Admins only.
+    Admin,
+}
+
+/// JSON type of a parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamType {
+    /// String.
+    String,
+    /// Non-negative integer.
+    Integer,
+    /// Number, possibly fractional.
+    Number,
+    /// Boolean.
+    Boolean,
+    /// Object.
+    Object,
+    /// Array.
+    Array,
+    /// `YYYY-MM-DD` date.
+    Date,
+    /// RFC 3339 timestamp.
+    DateTime,
+    /// One of a fixed set of strings.
+    Enum(&'static [&'static str]),
+}
+
+impl ParamType {
+    /// JSON Schema for the type.
+    #[must_use]
+    pub fn schema(&self) -> Value {
+        match self {
+            Self::String => json!({ "type": "string" }),
+            Self::Integer => json!({ "type": "integer", "minimum": 0 }),
+            Self::Number => json!({ "type": "number" }),
+            Self::Boolean => json!({ "type": "boolean" }),
+            Self::Object => json!({ "type": "object" }),
+            Self::Array => json!({ "type": "array" }),
+            Self::Date => json!({ "type": "string", "format": "date" }),
+            Self::DateTime => json!({ "type": "string", "format": "date-time" }),
+            Self::Enum(values) => json!({ "type": "string", "enum": values }),
+        }
+    }
+}
+
+/// A method parameter. Parameters are passed by name.
+#[derive(Debug, Clone, Copy)]
+pub struct Param {
+    /// Parameter name.
+    pub name: &'static str,
+    /// Parameter type.
+    pub kind: ParamType,
+    /// Whether the parameter must be given.
+    pub required: bool,
+    /// What the parameter does.
+    pub description: &'static str,
+}
+
+/// A JSON-RPC method.
+#[derive(Debug, Clone, Copy)]
+pub struct MethodSpec {
+    /// Method name.
+    pub name: &'static str,
+    /// What the method does.
+    pub summary: &'static str,
+    /// Role needed to call it.
+    pub permission: Permission,
+    /// Parameters.
+    pub params: &'static [Param],
+}
+
+impl MethodSpec {
+    /// `OpenRPC` method object. `requires_auth` reports whether the running
+    /// gateway asks for a token, which depends on its auth settings.
+    #[must_use]
+    pub fn openrpc(&self, requires_auth: bool) -> Value {
+        let params: Vec<Value> = self
+            .params
+            .iter()
+            .map(|param| {
+                json!({
+                    "name": param.name,
+                    "description": param.description,
+                    "required": param.required,
+                    "schema": param.kind.schema(),
+                })
+            })
+            .collect();
+        json!({
+            "name": self.name,
+            "summary": self.summary,
+            "paramStructure": "by-name",
+            "params": params,
+            "result": { "name": "result", "schema": { "type": "object" } },
+            "x-permission": self.permission,
+            "x-requires-auth": requires_auth,
+        })
+    }
+}
+
+const fn required(name: &'static str, kind: ParamType, description: &'static str) -> Param {
+    Param {
+        name,
+        kind,
+        required: true,
+        description,
+    }
+}
+
+const fn optional(name: &'static str, kind: ParamType, description: &'static str) -> Param {
+    Param {
+        name,
+        kind,
+        required: false,
+        description,
+    }
+}
+
+const fn method(
+    name: &'static str,
+    summary: &'static str,
+    permission: Permission,
+    params: &'static [Param],
+) -> MethodSpec {
+    MethodSpec {
+        name,
+        summary,
+        permission,
+        params,
+    }
+}
+
+/// Every method the gateway dispatches.
+pub const METHODS: &[MethodSpec] = {
+    use ParamType::{Array, Boolean, Date, DateTime, Enum, Integer, Number, Object, String};
+    use Permission::{Admin, Operator, Public, Viewer};
+
+    const SESSION_KEY: Param = required("session_key", String, "Session key");
+    const CHANNEL_ID: Param = required("channel_id", String, "Channel ID");
+    const ROLES: &[&str] = &["admin", "operator", "viewer"];
+
+    &[
+        // Auth
+        method(
+            "auth.login",
+            "Log in and receive access and refresh tokens",
+            Public,
+            &[
+                required("username", String, "Username"),
+                required("password", String, "Password"),
+            ],
+        ),
+        method(
+            "auth.logout",
+            "End the current login",
+            Viewer,
+            &[optional(
+                "all",
+                Boolean,
+                "End every login of the user (default false)",
+            )],
+        ),
+        method(
+            "auth.refresh",
+            "Exchange a refresh token for new tokens",
+            Viewer,
+            &[required("refresh_token", String, "Refresh token")],
+        ),
+        method("auth.me", "The authenticated user", Viewer, &[]),
+        method(
+            "auth.oidc.status",
+            "Whether single sign-on is available",
+            Public,
+            &[],
+        ),
+        method(
+            "auth.lockouts.list",
+            "Usernames and addresses with recent failed logins",
+            Admin,
+            &[],
+        ),
+        method(
+            "auth.lockouts.clear",
+            "Clear failed logins and lockouts",
+            Admin,
+            &[optional(
+                "key",
+                String,
+                "user:<name> or ip:<address> (default everything)",
+            )],
+        ),
+        method(
+            "auth.sessions.list",
+            "Open login sessions",
+            Admin,
+            &[optional("user_id", String, "Only this user's sessions")],
+        ),
+        method(
+            "auth.sessions.revoke",
+            "End a login session or every session of a user",
+            Admin,
+            &[
+                optional("session_id", String, "Session to end"),
+                optional("user_id", String, "User whose sessions to end"),
+            ],
+        ),
+        // Setup
+        method(
+            "setup.status",
+            "Whether the gateway still needs an admin user",
+            Public,
+            &[],
+        ),
+        method(
+            "setup.init",
+            "Create the first admin user",
+            Public,
+            &[
+                required("bootstrap_token", String, "Token printed at startup"),
+                required("admin_username", String, "Admin username"),
+                required("admin_password", String, "Admin password"),
+                optional("email", String, "Admin email"),
+            ],
+        ),
+        // Users
+        method("users.list", "List users", Admin, &[]),
+        method(
+            "users.create",
+            "Create a user",
+            Admin,
+            &[
+                required("username", String, "Username"),
+                required("password", String, "Password"),
+                optional("role", Enum(ROLES), "Role (default viewer)"),
+                optional("email", String, "Email"),
+            ],
+        ),
+        method(
+            "users.update",
+            "Change a user's role, status, or email",
+            Admin,
+            &[
+                required("id", String, "User ID"),
+                optional("role", Enum(ROLES), "New role"),
+                optional("active", Boolean, "Enable or disable the account"),
+                optional("email", String, "New email"),
+            ],
+        ),
+        method(
+            "users.delete",
+            "Delete a user other than the last admin",
+            Admin,
+            &[required("id", String, "User ID")],
+        ),
+        // Runtime log filter
+        method(
+            "admin.log_level.get",
+            "Current log filter directives",
+            Admin,
+            &[],
+        ),
+        method(
+            "admin.log_level.set",
+            "Change the log filter without a restart",
+            Admin,
+            &[
+                optional("directives", String, "Full filter directives"),
+                optional("level", String, "Level, used when directives is not given"),
+                optional("module", String, "Module the level applies to"),
+            ],
+        ),
+        method(
+            "admin.log_level.reset",
+            "Restore the startup log filter",
+            Admin,
+            &[],
+        ),
+        // Approvals
+        method("approvals.list", "Pending tool approvals", Viewer, &[]),
+        method(
+            "approvals.approve",
+            "Approve a pending tool call",
+            Operator,
+            &[required("id", String, "Approval ID")],
+        ),
+        method(
+            "approvals.deny",
+            "Deny a pending tool call",
+            Operator,
+            &[
+                required("id", String, "Approval ID"),
+                optional("reason", String, "Reason passed back to the agent"),
+            ],
+        ),
+        // Peer profiles
+        method(
+            "peers.list",
+            "Profiles of the people agents talk to",
+            Viewer,
+            &[optional("channel", String, "Only peers on this channel")],
+        ),
+        method(
+            "peers.get",
+            "A peer's profile",
+            Viewer,
+            &[
+                required("channel", String, "Channel ID"),
+                required("peer_id", String, "Peer ID on the channel"),
+            ],
+        ),
+        method(
+            "peers.update",
+            "Set profile fields; empty strings clear them",
+            Operator,
+            &[
+                required("channel", String, "Channel ID"),
+                required("peer_id", String, "Peer ID on the channel"),
+                optional("display_name", String, "Name to call the peer"),
+                optional("language", String, "Preferred language (BCP 47 tag)"),
+                optional("timezone", String, "IANA timezone"),
+                optional("notes", String, "Notes shown to agents"),
+                optional(
+                    "preferences",
+                    Object,
+                    "Preference flags by name; null removes one",
+                ),
+            ],
+        ),
+        method(
+            "peers.delete",
+            "Delete a peer's profile",
+            Operator,
+            &[
+                required("channel", String, "Channel ID"),
+                required("peer_id", String, "Peer ID on the channel"),
+            ],
+        ),
+        method(
+            "privacy.forget",
+            "Erase a peer's sessions, profile, uploads, and workspaces",
+            Operator,
+            &[
+                required("channel", String, "Channel ID"),
+                required("peer_id", String, "Peer ID on the channel"),
+            ],
+        ),
+        // Gateway
+        method(
+            "gateway.shutdown",
+            "Drain requests and stop the gateway",
+            Admin,
+            &[],
+        ),
+        // Sessions
+        method(
+            "session.create",
+            "Start a session",
+            Operator,
+            &[
+                optional("agent_id", String, "Agent (default \"default\")"),
+                optional("channel", String, "Channel (default \"api\")"),
+                optional("peer_id", String, "Peer (default \"anonymous\")"),
+                optional("thread_id", String, "Thread the session covers"),
+            ],
+        ),
+        method(
+            "session.message",
+            "Send a message and wait for the agent's response",
+            Operator,
+            &[
+                SESSION_KEY,
+                required("message", String, "Message text"),
+                optional("attachments", Array, "Attachments"),
+                optional("agent_id", String, "Agent for a new session"),
+                optional("schema", Object, "JSON schema the reply must match"),
+                optional(
+                    "schema_name",
+                    String,
+                    "Name of the schema (default \"response\")",
+                ),
+                optional(
+                    "sender_id",
+                    String,
+                    "Who sent the message, in a group (default the session's peer)",
+                ),
+            ],
+        ),
+        method(
+            "session.history",
+            "A session's events",
+            Viewer,
+            &[SESSION_KEY],
+        ),
+        method(
+            "session.end",
+            "End a session",
+            Operator,
+            &[
+                SESSION_KEY,
+                optional("reason", String, "Reason (default \"user_requested\")"),
+            ],
+        ),
+        method(
+            "session.list",
+            "List sessions",
+            Viewer,
+            &[
+                optional("limit", Integer, "Maximum sessions (default 50)"),
+                optional("offset", Integer, "Sessions to skip"),
+                optional("channel", String, "Only this channel"),
+                optional("agent", String, "Only this agent"),
+                optional("state", String, "Only sessions in this state"),
+                optional("include_deleted", Boolean, "Include deleted sessions"),
+            ],
+        ),
+        method(
+            "session.search",
+            "Search message content across sessions",
+            Viewer,
+            &[
+                required("query", String, "Search text"),
+                optional("channel", String, "Only this channel"),
+                optional("agent", String, "Only this agent"),
+                optional("limit", Integer, "Maximum results (default 20)"),
+            ],
+        ),
+        method("session.stats", "Session counts", Viewer, &[]),
+        method(
+            "stats.get",
+            "Daily agent, channel, and error counters",
+            Viewer,
+            &[
+                optional("days", Integer, "Days ending at `to` (default 30)"),
+                optional("from", Date, "First day"),
+                optional("to", Date, "Last day (default today, UTC)"),
+                optional("agent_id", String, "Only this agent"),
+                optional("channel", String, "Only this channel"),
+            ],
+        ),
+        method(
+            "session.events",
+            "A session's raw events",
+            Viewer,
+            &[
+                SESSION_KEY,
+                optional("since", DateTime, "Only events after this time"),
+            ],
+        ),
+        method(
+            "session.delete",
+            "Soft-delete a session",
+            Operator,
+            &[SESSION_KEY, optional("reason", String, "Reason")],
+        ),
+        method(
+            "session.restore",
+            "Restore a soft-deleted session",
+            Operator,
+            &[SESSION_KEY],
+        ),
+        method(
+            "session.purge",
+            "Permanently delete a soft-deleted session",
+            Operator,
+            &[SESSION_KEY],
+        ),
+        method(
+            "session.dry_run",
+            "Set or clear a session's dry-run mode",
+            Operator,
+            &[
+                SESSION_KEY,
+                optional(
+                    "mode",
+                    Enum(&["simulate", "plan"]),
+                    "Mode, or null to clear",
+                ),
+            ],
+        ),
+        method(
+            "session.fork",
+            "Copy a session up to an event into a new session",
+            Operator,
+            &[
+                SESSION_KEY,
+                required("event_id", String, "Last event to copy (ULID)"),
+                optional(
+                    "suffix",
+                    String,
+                    "Suffix for the new session key; random if omitted",
+                ),
+            ],
+        ),
+        method(
+            "session.replay",
+            "Re-run a session's turns with another model or prompt and diff the replies",
+            Operator,
+            &[
+                SESSION_KEY,
+                optional(
+                    "agent_id",
+                    String,
+                    "Agent to replay with; the session's agent if omitted",
+                ),
+                optional("model", String, "Model to replay with"),
+                optional("temperature", Number, "Sampling temperature"),
+                optional("max_tokens", Integer, "Maximum tokens per reply"),
+                optional("system_prompt", String, "System prompt to replay with"),
+            ],
+        ),
+        // Channels
+        method("channels.list", "Configured channels", Viewer, &[]),
+        method("channels.status", "Channel health", Viewer, &[]),
+        method(
+            "channels.probe",
+            "Check a channel's connectivity",
+            Viewer,
+            &[CHANNEL_ID],
+        ),
+        method(
+            "channels.send",
+            "Send a message through a channel",
+            Viewer,
+            &[
+                CHANNEL_ID,
+                required("chat_id", String, "Chat to send to"),
+                optional("text", String, "Message text (required without a template)"),
+                optional(
+                    "template",
+                    Object,
+                    "Approved WhatsApp template (name, language, parameters) to send instead",
+                ),
+                optional("reply_to", String, "Message to reply to"),
+                optional("thread_id", String, "Thread to post in"),
+            ],
+        ),
+        method("channels.start", "Start a channel", Operator, &[CHANNEL_ID]),
+        method("channels.stop", "Stop a channel", Operator, &[CHANNEL_ID]),
+        method(
+            "channels.enable",
+            "Enable and start a channel, saving the setting",
+            Admin,
+            &[CHANNEL_ID],
+        ),
+        method(
+            "channels.disable",
+            "Drain and stop a channel, keeping it stopped across restarts",
+            Admin,
+            &[CHANNEL_ID],
+        ),
+        // Mock channel (development)
+        method(
+            "mock.inject",
+            "Send a message through the mock channel and wait for the reply",
+            Operator,
+            &[
+                required("text", String, "Message text"),
+                optional("peer_id", String, "Sender; dev if omitted"),
+                optional("chat_id", String, "Chat to send in; a DM if omitted"),
+                optional("thread_id", String, "Thread the message is sent in"),
+                optional("reply_to", String, "Message this one replies to"),
+                optional("attachments", Array, "Attached media"),
+                optional("agent_id", String, "Agent to answer; routed if omitted"),
+            ],
+        ),
+        method(
+            "mock.react",
+            "React to a message through the mock channel",
+            Operator,
+            &[
+                required("message_id", String, "Message reacted to"),
+                required("emoji", String, "Reaction emoji"),
+                optional("peer_id", String, "Who reacted; dev if omitted"),
+                optional("chat_id", String, "Chat the message is in; a DM if omitted"),
+                optional("removed", Boolean, "Whether the reaction was removed"),
+                optional(
+                    "agent_id",
+                    String,
+                    "Agent whose session it is; routed if omitted",
+                ),
+            ],
+        ),
+        method(
+            "mock.outbound",
+            "Messages the mock channel was asked to deliver",
+            Operator,
+            &[optional("clear", Boolean, "Clear the recorded messages")],
+        ),
+        // Workflows
+        method(
+            "workflow.run",
+            "Start a registered workflow",
+            Operator,
+            &[
+                required("workflow_id", String, "Workflow ID"),
+                optional("input", Object, "Workflow input"),
+                optional("channel", String, "Channel to ask for input in"),
+                optional("peer_id", String, "Peer whose reply resumes the run"),
+                optional("chat_id", String, "Chat to ask in; a DM if omitted"),
+            ],
+        ),
+        method(
+            "workflow.waiting",
+            "Workflow runs waiting for input",
+            Viewer,
+            &[],
+        ),
+        method(
+            "workflow.reply",
+            "Answer a workflow run waiting for input",
+            Operator,
+            &[
+                required("run_id", String, "Run ID"),
+                optional("text", String, "Text reply"),
+                optional("data", Object, "Structured reply, e.g. form fields"),
+            ],
+        ),
+        method(
+            "workflow.runs.list",
+            "Recorded workflow runs, newest first",
+            Viewer,
+            &[
+                optional("workflow_id", String, "Only runs of this workflow"),
+                optional("limit", Integer, "Maximum runs (default 50)"),
+            ],
+        ),
+        method(
+            "workflow.runs.get",
+            "A workflow run's steps and execution graph",
+            Viewer,
+            &[required("run_id", String, "Run ID")],
+        ),
+        // Notifications
+        method("notify.templates", "Notification templates", Viewer, &[]),
+        method(
+            "notify.send",
+            "Send a notification template through a channel",
+            Viewer,
+            &[
+                required("template", String, "Template name"),
+                optional("vars", Object, "Template variables"),
+                required("channel", String, "Channel ID"),
+                required("chat", String, "Chat to send to"),
+                optional("thread_id", String, "Thread to post in"),
+            ],
+        ),
+        // Broadcasts
+        method(
+            "broadcast.send",
+            "Send one message to many chats",
+            Operator,
+            &[
+                required("text", String, "Message text"),
+                optional("channel", String, "Channel ID (defaults to the group's)"),
+                optional("group", String, "Configured broadcast group"),
+                optional(
+                    "allowlist",
+                    Boolean,
+                    "Include peers allowlisted on the channel",
+                ),
+                optional("chat_ids", Array, "Chats to send to"),
+            ],
+        ),
+        method(
+            "broadcast.status",
+            "Delivery progress of a broadcast",
+            Operator,
+            &[required("broadcast_id", String, "Broadcast ID")],
+        ),
+        // Agents
+        method("agent.list", "Registered agents", Viewer, &[]),
+        method(
+            "agent.status",
+            "An agent's status",
+            Viewer,
+            &[required("agent_id", String, "Agent ID")],
+        ),
+        method(
+            "agent.get",
+            "An agent's configuration",
+            Viewer,
+            &[required("agent_id", String, "Agent ID")],
+        ),
+        method(
+            "agent.update",
+            "Change a running agent's model parameters and save them to the config",
+            Admin,
+            &[
+                required("agent_id", String, "Agent ID"),
+                optional("model", String, "Model name"),
+                optional("temperature", Number, "Sampling temperature, 0 to 2"),
+                optional("max_tokens", Integer, "Maximum tokens in a response"),
+                optional("system_prompt", String, "System prompt; empty clears it"),
+            ],
+        ),
+        // Feedback
+        method(
+            "feedback.submit",
+            "Rate an agent response with a rating, reaction, or /feedback command",
+            Viewer,
+            &[
+                SESSION_KEY,
+                optional(
+                    "rating",
+                    Enum(&["positive", "negative", "up", "down"]),
+                    "Rating",
+                ),
+                optional("comment", String, "Comment, with rating"),
+                optional("reaction", String, "Reaction emoji"),
+                optional("command", String, "/feedback command text"),
+                optional("response_id", String, "Rated response"),
+            ],
+        ),
+        method(
+            "feedback.summary",
+            "Feedback counts",
+            Viewer,
+            &[optional("agent_id", String, "Only this agent")],
+        ),
+        // Experiments
+        method("experiments.list", "Configured experiments", Viewer, &[]),
+        method(
+            "experiments.report",
+            "Results of an experiment by variant",
+            Viewer,
+            &[required("experiment_id", String, "Experiment ID")],
+        ),
+        // Tools
+        method("tools.list", "Registered tools", Viewer, &[]),
+        method(
+            "tools.execute",
+            "Run a tool",
+            Viewer,
+            &[
+                required("tool_name", String, "Tool name"),
+                optional("params", Object, "Tool parameters"),
+                optional(
+                    "session_key",
+                    String,
+                    "Session to run in; the result joins its history",
+                ),
+                optional(
+                    "agent_id",
+                    String,
+                    "Agent to run as; the session's agent or `default` if omitted",
+                ),
+            ],
+        ),
+        // Workspace
+        method(
+            "workspace.list",
+            "List workspace files",
+            Viewer,
+            &[
+                optional("agent_id", String, "Agent whose workspace to read"),
+                optional("session_key", String, "Session whose workspace to read"),
+                optional("path", String, "Directory (default root)"),
+            ],
+        ),
+        method(
+            "workspace.read",
+            "Read a workspace file",
+            Viewer,
+            &[
+                optional("agent_id", String, "Agent whose workspace to read"),
+                optional("session_key", String, "Session whose workspace to read"),
+                required("path", String, "File path"),
+                optional("max_bytes", Integer, "Maximum bytes returned"),
+            ],
+        ),
+        method(
+            "workspace.download",
+            "Download a workspace file",
+            Viewer,
+            &[
+                optional("agent_id", String, "Agent whose workspace to read"),
+                optional("session_key", String, "Session whose workspace to read"),
+                required("path", String, "File path"),
+            ],
+        ),
+        // System
+        method("system.health", "Gateway health", Public, &[]),
+        method("system.version", "Gateway version", Public, &[]),
+        method("system.startup", "Startup report", Viewer, &[]),
+        method(
+            "rpc.discover",
+            "This catalog as an OpenRPC document",
+            Public,
+            &[],
+        ),
+        // Events
+        method(
+            "events.subscribe",
+            "Stream UI events over this WebSocket",
+            Viewer,
+            &[],
+        ),
+    ]
+};
+
+/// Look up a method by name.
+#[must_use]
+pub fn method_spec(name: &str) -> Option<&'static MethodSpec> {
+    METHODS.iter().find(|spec| spec.name == name)
+}
+
+/// The catalog as an `OpenRPC` document. `requires_auth` reports whether
+/// the running gateway asks for a token for a method.
+#[must_use]
+pub fn openrpc(requires_auth: impl Fn(&str) -> bool) -> Value {
+    let methods: Vec<Value> = METHODS
+        .iter()
+        .map(|spec| spec.openrpc(requires_auth(spec.name)))
+        .collect();
+    json!({
+        "openrpc": OPENRPC_VERSION,
+        "info": {
+            "title": "OpenClaw Gateway",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "methods": methods,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_catalog_matches_dispatch() {
+        let dispatched: BTreeSet<&str> = include_str!("server.rs")
+            .lines()
+            .filter_map(|line| {
+                let name = line.trim().strip_prefix('"')?.split_once("\" =>")?.0;
+                name.contains('.').then_some(name)
+            })
+            .collect();
+        let cataloged: BTreeSet<&str> = METHODS.iter().map(|spec| spec.name).collect();
+
+        assert_eq!(cataloged.len(), METHODS.len(), "duplicate catalog entry");
+        assert_eq!(dispatched, cataloged);
+    }
+
+    #[test]
+    fn test_openrpc_document() {
+        let doc = openrpc(|name| name != "auth.login");
+        assert_eq!(doc["openrpc"], OPENRPC_VERSION);
+
+        let methods = doc["methods"].as_array().unwrap();
+        assert_eq!(methods.len(), METHODS.len());
+
+        let login = methods.iter().find(|m| m["name"] == "auth.login").unwrap();
+        assert_eq!(login["x-permission"], "public");
+        assert_eq!(login["x-requires-auth"], false);
+        assert_eq!(login["params"][0]["name"], "username");
+        assert_eq!(login["params"][0]["required"], true);
+        assert_eq!(login["params"][0]["schema"]["type"], "string");
+
+        let create = method_spec("users.create").unwrap();
+        assert_eq!(create.permission, Permission::Admin);
+        let role = create.openrpc(true)["params"][2]["schema"].clone();
+        assert_eq!(role["enum"], json!(["admin", "operator", "viewer"]));
+    }
+}
@@ -0,0 +1,503 @@
+//! Message bus transports.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use async_trait::async_trait;
+use futures::Stream;
+use futures::future::BoxFuture;
+use tokio::sync::broadcast;
+
+/// Messages on a subject, in publish order.
+pub type BusStream = Pin<Box<dyn Stream<Item = Vec<u8>> + Send>>;
+
+/// Messages on a subject handed to this member of a queue group.
+pub type QueueStream = Pin<Box<dyn Stream<Item = QueuedMessage> + Send>>;
+
+type AckFn = Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>;
+
+/// A message delivered to one member of a queue group.
+pub struct QueuedMessage {
+    /// Message body.
+    pub payload: Vec<u8>,
+    ack: Option<AckFn>,
+}
+
+impl QueuedMessage {
+    /// A message that needs no acknowledgement.
+    #[must_use]
+    pub fn new(payload: Vec<u8>) -> Self {
+        Self { payload, ack: None }
+    }
+
+    /// A message acknowledged by running `ack`.
+    pub fn with_ack(
+        payload: Vec<u8>,
+        ack: impl FnOnce() -> BoxFuture<'static, ()> + Send + 'static,
+    ) -> Self {
+        Self {
+            payload,
+            ack: Some(Box::new(ack)),
+        }
+    }
+
+    /// Mark the message handled, so the bus does not deliver it again.
+    pub async fn ack(self) {
+        if let Some(ack) = self.ack {
+            ack().await;
+        }
+    }
+}
+
+impl std::fmt::Debug for QueuedMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueuedMessage")
+            .field("payload", &self.payload.len())
+            .field("ack", &self.ack.is_some())
+            .finish()
+    }
+}
+
+/// Message bus errors.
+#[derive(Debug, thiserror::Error)]
+pub enum BusError {
+    /// Could not connect to the bus.
+    #[error("Bus connection failed: {0}")]
+    Connect(String),
+
+    /// A message could not be published.
+    #[error("Bus publish failed: {0}")]
+    Publish(String),
+
+    /// A subject could not be subscribed to.
+    #[error("Bus subscribe failed: {0}")]
+    Subscribe(String),
+
+    /// The transport was not compiled in.
+    #[error("{0}")]
+    Unsupported(String),
+}
+
+/// Publish/subscribe transport shared by cluster nodes.
+///
+/// Subscribers receive messages published after they subscribed; delivery
+/// is at most once.
+#[async_trait]
+pub trait MessageBus: Send + Sync {
+    /// Publish a message to a subject.
+    async fn publish(&self, subject: &str, payload: Vec<u8>) -> Result<(), BusError>;
+
+    /// Receive messages published to a subject from now on.
+    async fn subscribe(&self, subject: &str) -> Result<BusStream, BusError>;
+
+    /// Receive messages published to a subject from now on, shared with
+    /// the other subscribers in `group`: each message goes to one of them.
+    async fn queue_subscribe(&self, subject: &str, group: &str) -> Result<QueueStream, BusError>;
+}
+
+/// Per-subject buffer for [`MemoryBus`].
+const MEMORY_BUS_BUFFER: usize = 1024;
+
+/// In-process bus, for tests and for running gateway and worker roles in
+/// one process.
+#[derive(Debug, Default)]
+pub struct MemoryBus {
+    subjects: Mutex<HashMap<String, broadcast::Sender<Vec<u8>>>>,
+    groups: Mutex<HashMap<(String, String), SharedReceiver>>,
+}
+
+/// Receiver the members of a [`MemoryBus`] queue group take turns on.
+type SharedReceiver = Arc<tokio::sync::Mutex<broadcast::Receiver<Vec<u8>>>>;
+
+impl MemoryBus {
+    /// Create an empty bus.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn sender(&self, subject: &str) -> broadcast::Sender<Vec<u8>> {
+        self.subjects
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(subject.to_string())
+            .or_insert_with(|| broadcast::channel(MEMORY_BUS_BUFFER).0)
+            .clone()
+    }
+
+    fn group_receiver(&self, subject: &str, group: &str) -> SharedReceiver {
+        self.groups
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry((subject.to_string(), group.to_string()))
+            .or_insert_with(|| Arc::new(tokio::sync::Mutex::new(self.sender(subject).subscribe())))
+            .clone()
+    }
+}
+
+/// Next message from a broadcast receiver, skipping over lag.
+async fn next_broadcast(receiver: &mut broadcast::Receiver<Vec<u8>>) -> Option<Vec<u8>> {
+    loop {
+        match receiver.recv().await {
+            Ok(payload) => return Some(payload),
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                tracing::warn!("Memory bus subscriber lagged, missed {} messages", n);
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
+
+#[async_trait]
+impl MessageBus for MemoryBus {
+    async fn publish(&self, subject: &str, payload: Vec<u8>) -> Result<(), BusError> {
+        // No subscribers is not an error
+        let _ = self.sender(subject).send(payload);
+        Ok(())
+    }
+
+    async fn subscribe(&self, subject: &str) -> Result<BusStream, BusError> {
+        let receiver = self.sender(subject).subscribe();
+        Ok(Box::pin(futures::stream::unfold(
+            receiver,
+            |mut receiver| async move {
+                next_broadcast(&mut receiver)
+                    .await
+                    .map(|payload| (payload, receiver))
+            },
+        )))
+    }
+
+    async fn queue_subscribe(&self, subject: &str, group: &str) -> Result<QueueStream, BusError> {
+        let receiver = self.group_receiver(subject, group);
+        Ok(Box::pin(futures::stream::unfold(
+            receiver,
+            |receiver| async move {
+                let payload = next_broadcast(&mut *receiver.lock().await).await?;
+                Some((QueuedMessage::new(payload), receiver))
+            },
+        )))
+    }
+}
+
+/// NATS core pub/sub. Queue groups are NATS queue subscriptions, which
+/// need no acknowledgement.
+#[cfg(feature = "nats")]
+pub struct NatsBus {
+    client: async_nats::Client,
+}
+
+#[cfg(feature = "nats")]
+impl NatsBus {
+    /// Connect to a NATS server, e.g. `nats://localhost:4222`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the server cannot be reached.
+    pub async fn connect(url: &str) -> Result<Self, BusError> {
+        let client = async_nats::connect(url)
+            .await
+            .map_err(|e| BusError::Connect(e.to_string()))?;
+        Ok(Self { client })
+    }
+}
+
+#[cfg(feature = "nats")]
+#[async_trait]
+impl MessageBus for NatsBus {
+    async fn publish(&self, subject: &str, payload: Vec<u8>) -> Result<(), BusError> {
+        self.client
+            .publish(subject.to_string(), payload.into())
+            .await
+            .map_err(|e| BusError::Publish(e.to_string()))
+    }
+
+    async fn subscribe(&self, subject: &str) -> Result<BusStream, BusError> {
+        use futures::StreamExt;
+
+        let subscriber = self
+            .client
+            .subscribe(subject.to_string())
+            .await
+            .map_err(|e| BusError::Subscribe(e.to_string()))?;
+        Ok(Box::pin(subscriber.map(|message| message.payload.to_vec())))
+    }
+
+    async fn queue_subscribe(&self, subject: &str, group: &str) -> Result<QueueStream, BusError> {
+        use futures::StreamExt;
+
+        let subscriber = self
+            .client
+            .queue_subscribe(subject.to_string(), group.to_string())
+            .await
+            .map_err(|e| BusError::Subscribe(e.to_string()))?;
+        Ok(Box::pin(subscriber.map(|message| {
+            QueuedMessage::new(message.payload.to_vec())
+        })))
+    }
+}
+
+/// Approximate length Redis trims each stream to.
+#[cfg(feature = "redis")]
+const REDIS_STREAM_MAX_LEN: usize = 10_000;
+
+/// How long each `XREAD` blocks waiting for new entries.
+#[cfg(feature = "redis")]
+const REDIS_BLOCK_MS: u64 = 5000;
+
+/// Redis streams, one stream per subject.
+///
+/// Queue groups are consumer groups: entries stay pending until
+/// acknowledged with `XACK`, and a consumer that restarts under the same
+/// name gets its pending entries again.
+#[cfg(feature = "redis")]
+pub struct RedisBus {
+    client: redis::Client,
+    connection: redis::aio::MultiplexedConnection,
+    consumer: String,
+}
+
+#[cfg(feature = "redis")]
+impl RedisBus {
+    /// Connect to a Redis server, e.g. `redis://localhost:6379`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the server cannot be reached.
+    pub async fn connect(url: &str) -> Result<Self, BusError> {
+        let client = redis::Client::open(url).map_err(|e| BusError::Connect(e.to_string()))?;
+        let connection = client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| BusError::Connect(e.to_string()))?;
+        Ok(Self {
+            client,
+            connection,
+            consumer: format!("consumer-{}", hex::encode(rand::random::<[u8; 4]>())),
+        })
+    }
+
+    /// Name this bus reads from consumer groups as, e.g. the node ID.
+    #[must_use]
+    pub fn with_consumer(mut self, consumer: impl Into<String>) -> Self {
+        self.consumer = consumer.into();
+        self
+    }
+
+    /// A connection of its own for blocking reads, so they don't stall
+    /// publishes.
+    async fn reader(&self) -> Result<redis::aio::MultiplexedConnection, BusError> {
+        self.client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| BusError::Subscribe(e.to_string()))
+    }
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl MessageBus for RedisBus {
+    async fn publish(&self, subject: &str, payload: Vec<u8>) -> Result<(), BusError> {
+        let mut connection = self.connection.clone();
+        let _: String = redis::cmd("XADD")
+            .arg(subject)
+            .arg("MAXLEN")
+            .arg("~")
+            .arg(REDIS_STREAM_MAX_LEN)
+            .arg("*")
+            .arg("payload")
+            .arg(payload)
+            .query_async(&mut connection)
+            .await
+            .map_err(|e| BusError::Publish(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn subscribe(&self, subject: &str) -> Result<BusStream, BusError> {
+        let mut connection = self.reader().await?;
+        let subject = subject.to_string();
+        let (tx, rx) = tokio::sync::mpsc::channel(MEMORY_BUS_BUFFER);
+
+        tokio::spawn(async move {
+            let mut last_id = "$".to_string();
+            while !tx.is_closed() {
+                let reply: redis::RedisResult<Option<redis::streams::StreamReadReply>> =
+                    redis::cmd("XREAD")
+                        .arg("BLOCK")
+                        .arg(REDIS_BLOCK_MS)
+                        .arg("STREAMS")
+                        .arg(&subject)
+                        .arg(&last_id)
+                        .query_async(&mut connection)
+                        .await;
+                match reply {
+                    Ok(reply) => {
+                        for entry in reply.into_iter().flat_map(|r| r.keys).flat_map(|k| k.ids) {
+                            last_id.clone_from(&entry.id);
+                            let Some(payload) = entry.get::<Vec<u8>>("payload") else {
+                                continue;
+                            };
+                            if tx.send(payload).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("Redis read from {} failed: {}", subject, e);
+                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                    }
+                }
+            }
+        });
+
+        Ok(Box::pin(futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|payload| (payload, rx))
+        })))
+    }
+
+    async fn queue_subscribe(&self, subject: &str, group: &str) -> Result<QueueStream, BusError> {
+        let mut connection = self.reader().await?;
+        let created: redis::RedisResult<String> = redis::cmd("XGROUP")
+            .arg("CREATE")
+            .arg(subject)
+            .arg(group)
+            .arg("$")
+            .arg("MKSTREAM")
+            .query_async(&mut connection)
+            .await;
+        if let Err(e) = created {
+            if e.code() != Some("BUSYGROUP") {
+                return Err(BusError::Subscribe(e.to_string()));
+            }
+        }
+
+        let subject = subject.to_string();
+        let group = group.to_string();
+        let consumer = self.consumer.clone();
+        let acker = self.connection.clone();
+        let (tx, rx) = tokio::sync::mpsc::channel(MEMORY_BUS_BUFFER);
+
+        tokio::spawn(async move {
+            // Entries delivered before a restart but never acknowledged
+            // come first, then new ones
+            let mut backlog = true;
+            let mut last_id = "0".to_string();
+            while !tx.is_closed() {
+                let reply: redis::RedisResult<Option<redis::streams::StreamReadReply>> =
+                    redis::cmd("XREADGROUP")
+                        .arg("GROUP")
+                        .arg(&group)
+                        .arg(&consumer)
+                        .arg("BLOCK")
+                        .arg(REDIS_BLOCK_MS)
+                        .arg("STREAMS")
+                        .arg(&subject)
+                        .arg(&last_id)
+                        .query_async(&mut connection)
+                        .await;
+                let entries: Vec<_> = match reply {
+                    Ok(reply) => reply
+                        .into_iter()
+                        .flat_map(|r| r.keys)
+                        .flat_map(|k| k.ids)
+                        .collect(),
+                    Err(e) => {
+                        tracing::warn!("Redis group read from {} failed: {}", subject, e);
+                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                        continue;
+                    }
+                };
+                if backlog {
+                    if let Some(entry) = entries.last() {
+                        last_id.clone_from(&entry.id);
+                    } else {
+                        backlog = false;
+                        last_id = ">".to_string();
+                    }
+                }
+
+                for entry in entries {
+                    let ack = redis_ack(acker.clone(), &subject, &group, &entry.id);
+                    // Trimmed or malformed entries have nothing to deliver
+                    let Some(payload) = entry.get::<Vec<u8>>("payload") else {
+                        ack().await;
+                        continue;
+                    };
+                    if tx
+                        .send(QueuedMessage::with_ack(payload, ack))
+                        .await
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(Box::pin(futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|message| (message, rx))
+        })))
+    }
+}
+
+/// Acknowledge a consumer group entry.
+#[cfg(feature = "redis")]
+fn redis_ack(
+    mut connection: redis::aio::MultiplexedConnection,
+    subject: &str,
+    group: &str,
+    id: &str,
+) -> impl FnOnce() -> BoxFuture<'static, ()> + Send + 'static {
+    let command = redis::cmd("XACK").arg(subject).arg(group).arg(id).clone();
+    move || {
+        Box::pin(async move {
+            let acked: redis::RedisResult<u64> = command.query_async(&mut connection).await;
+            if let Err(e) = acked {
+                tracing::warn!("Redis XACK failed: {}", e);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_memory_bus() {
+        let bus = MemoryBus::new();
+        // Messages before subscribing are not delivered
+        bus.publish("a", b"early".to_vec()).await.unwrap();
+
+        let mut a = bus.subscribe("a").await.unwrap();
+        let mut b = bus.subscribe("b").await.unwrap();
+        bus.publish("a", b"one".to_vec()).await.unwrap();
+        bus.publish("b", b"two".to_vec()).await.unwrap();
+
+        assert_eq!(a.next().await.unwrap(), b"one");
+        assert_eq!(b.next().await.unwrap(), b"two");
+    }
+
+    #[tokio::test]
+    async fn test_memory_bus_queue_group() {
+        let bus = MemoryBus::new();
+        let mut first = bus.queue_subscribe("jobs", "workers").await.unwrap();
+        let mut second = bus.queue_subscribe("jobs", "workers").await.unwrap();
+        let mut other = bus.queue_subscribe("jobs", "audit").await.unwrap();
+        bus.publish("jobs", b"one".to_vec()).await.unwrap();
+        bus.publish("jobs", b"two".to_vec()).await.unwrap();
+
+        // Each member of a group takes a different message
+        let a = first.next().await.unwrap();
+        let b = second.next().await.unwrap();
+        assert_ne!(a.payload, b.payload);
+        a.ack().await;
+        b.ack().await;
+
+        // Other groups see every message
+        assert_eq!(other.next().await.unwrap().payload, b"one");
+        assert_eq!(other.next().await.unwrap().payload, b"two");
+    }
+}
@@ -0,0 +1,989 @@
+//! Distributed mode over a shared message bus.
+//!
+//! Gateway nodes accept requests and publish each `session.message` to a
+//! job subject; worker nodes consume jobs and run the agents. Jobs are
+//! partitioned by session key, and workers consume each partition as one
+//! queue group, so every job runs on exactly one worker. Workers either
+//! list their partitions or take a share of the rest: they announce
+//! themselves on a heartbeat, and each partition goes to one live worker
+//! by rendezvous hashing, so while membership is stable each session's
+//! messages run on the same worker and in order. When a worker joins or
+//! goes silent, partitions move and a session may briefly run on two
+//! workers. Every node publishes the session events it appends, and
+//! applies the events other nodes publish, so each node's event store
+//! converges on the same session state. Appends are idempotent (by content
+//! hash), so an event delivered twice is stored once.
+
+mod bus;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{RwLock, broadcast, oneshot};
+
+use openclaw_core::events::{EventStore, SessionEvent};
+
+#[cfg(feature = "nats")]
+pub use bus::NatsBus;
+#[cfg(feature = "redis")]
+pub use bus::RedisBus;
+pub use bus::{BusError, BusStream, MemoryBus, MessageBus, QueueStream, QueuedMessage};
+
+use crate::rpc;
+use crate::server::{GatewayState, handle_session_message};
+
+/// Subject session events are synced on.
+const EVENTS_SUBJECT: &str = "openclaw.events";
+
+/// Queue group workers consume jobs in.
+const WORKERS_GROUP: &str = "openclaw-workers";
+
+/// Subject workers announce themselves on.
+const MEMBERS_SUBJECT: &str = "openclaw.workers";
+
+/// Heartbeats a worker can miss before its partitions move to the others.
+const MISSED_HEARTBEATS: u32 = 3;
+
+/// Default interval between worker heartbeats.
+pub const DEFAULT_HEARTBEAT: Duration = Duration::from_secs(2);
+
+/// Remotely applied events remembered so they are not published again.
+const APPLIED_CAPACITY: usize = 4096;
+
+/// Default number of job partitions.
+pub const DEFAULT_PARTITIONS: u32 = 16;
+
+/// Which bus to connect to.
+#[derive(Clone)]
+pub enum BusConfig {
+    /// NATS server URL (requires the `nats` feature).
+    Nats(String),
+    /// Redis server URL (requires the `redis` feature).
+    Redis(String),
+    /// An existing bus, e.g. a shared [`MemoryBus`].
+    Custom(Arc<dyn MessageBus>),
+}
+
+impl std::fmt::Debug for BusConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Nats(url) => f.debug_tuple("Nats").field(url).finish(),
+            Self::Redis(url) => f.debug_tuple("Redis").field(url).finish(),
+            Self::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
+
+/// What a node does in the cluster.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClusterRole {
+    /// Accept requests and publish messages to workers.
+    Gateway,
+    /// Run agents for messages in these partitions, or in a share of the
+    /// partitions no other worker lists if empty.
+    Worker {
+        /// Partitions this worker consumes.
+        partitions: Vec<u32>,
+    },
+}
+
+/// Distributed mode configuration.
+#[derive(Debug, Clone)]
+pub struct ClusterConfig {
+    /// Bus shared by all nodes.
+    pub bus: BusConfig,
+    /// Unique ID of this node.
+    pub node_id: String,
+    /// This node's role.
+    pub role: ClusterRole,
+    /// Number of job partitions; must match across nodes.
+    pub partitions: u32,
+    /// Interval between worker heartbeats; should match across nodes.
+    pub heartbeat: Duration,
+}
+
+impl ClusterConfig {
+    /// Build from the file configuration. Without a configured node ID, a
+    /// random one is generated.
+    #[must_use]
+    pub fn from_config(config: &openclaw_core::config::ClusterConfig) -> Self {
+        use openclaw_core::config::{BusKind, NodeRole};
+
+        Self {
+            bus: match config.bus {
+                BusKind::Nats => BusConfig::Nats(config.url.clone()),
+                BusKind::Redis => BusConfig::Redis(config.url.clone()),
+            },
+            node_id: config
+                .node_id
+                .clone()
+                .unwrap_or_else(|| format!("node-{}", hex::encode(rand::random::<[u8; 4]>()))),
+            role: match config.role {
+                NodeRole::Gateway => ClusterRole::Gateway,
+                NodeRole::Worker => ClusterRole::Worker {
+                    partitions: config.worker_partitions.clone(),
+                },
+            },
+            partitions: config.partitions,
+            heartbeat: DEFAULT_HEARTBEAT,
+        }
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Partition a session key's messages go to.
+///
+/// Uses FNV-1a so every node, whatever its build, agrees.
+#[must_use]
+pub fn partition_for(session_key: &str, partitions: u32) -> u32 {
+    let hash = fnv1a(session_key.as_bytes());
+    u32::try_from(hash % u64::from(partitions.max(1))).unwrap_or(0)
+}
+
+fn jobs_subject(partition: u32) -> String {
+    format!("openclaw.jobs.{partition}")
+}
+
+fn replies_subject(node_id: &str) -> String {
+    format!("openclaw.replies.{node_id}")
+}
+
+/// Outcome of a forwarded call, as returned by RPC handlers.
+type JobResult = Result<serde_json::Value, (i32, String)>;
+
+/// A `session.message` call forwarded to a worker.
+#[derive(Debug, Serialize, Deserialize)]
+struct Job {
+    id: String,
+    reply_to: String,
+    params: serde_json::Value,
+    auth_token: Option<String>,
+}
+
+/// A worker's answer to a [`Job`].
+#[derive(Debug, Serialize, Deserialize)]
+struct JobReply {
+    id: String,
+    result: JobResult,
+}
+
+/// A session event published for other nodes.
+#[derive(Debug, Serialize, Deserialize)]
+struct SyncedEvent {
+    origin: String,
+    namespace: Option<String>,
+    event: SessionEvent,
+}
+
+/// A worker announcing itself and the partitions it lists.
+#[derive(Debug, Serialize, Deserialize)]
+struct Heartbeat {
+    node_id: String,
+    partitions: Vec<u32>,
+}
+
+/// Workers recently heard from, with the partitions they list.
+#[derive(Debug, Default)]
+struct Members {
+    seen: HashMap<String, (Vec<u32>, tokio::time::Instant)>,
+}
+
+impl Members {
+    /// Record a heartbeat; returns whether membership changed.
+    fn record(&mut self, heartbeat: Heartbeat) -> bool {
+        let changed = self
+            .seen
+            .get(&heartbeat.node_id)
+            .is_none_or(|(listed, _)| *listed != heartbeat.partitions);
+        self.seen.insert(
+            heartbeat.node_id,
+            (heartbeat.partitions, tokio::time::Instant::now()),
+        );
+        changed
+    }
+
+    /// Forget workers silent for longer than `timeout`; returns whether
+    /// membership changed.
+    fn expire(&mut self, timeout: Duration) -> bool {
+        let Some(cutoff) = tokio::time::Instant::now().checked_sub(timeout) else {
+            return false;
+        };
+        let before = self.seen.len();
+        self.seen.retain(|_, (_, at)| *at >= cutoff);
+        self.seen.len() != before
+    }
+
+    /// Partitions `node_id` consumes: its own list, or else those no live
+    /// worker lists whose rendezvous hash it wins among the workers without
+    /// a list.
+    fn assign(&self, node_id: &str, listed: &[u32], partitions: u32) -> Vec<u32> {
+        if !listed.is_empty() {
+            return listed.to_vec();
+        }
+        let claimed: HashSet<u32> = self
+            .seen
+            .values()
+            .flat_map(|(listed, _)| listed.iter().copied())
+            .collect();
+        let mut sharing: Vec<&str> = self
+            .seen
+            .iter()
+            .filter(|(_, (listed, _))| listed.is_empty())
+            .map(|(id, _)| id.as_str())
+            .collect();
+        sharing.push(node_id);
+        (0..partitions)
+            .filter(|partition| !claimed.contains(partition))
+            .filter(|partition| {
+                sharing
+                    .iter()
+                    .max_by_key(|id| fnv1a(format!("{id}:{partition}").as_bytes()))
+                    .is_some_and(|owner| *owner == node_id)
+            })
+            .collect()
+    }
+}
+
+/// Bounded set of recently seen keys.
+#[derive(Debug, Default)]
+struct RecentKeys {
+    order: VecDeque<String>,
+    keys: HashSet<String>,
+}
+
+impl RecentKeys {
+    fn insert(&mut self, key: String) {
+        if self.keys.insert(key.clone()) {
+            self.order.push_back(key);
+        }
+        while self.order.len() > APPLIED_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.keys.remove(&oldest);
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &str) -> bool {
+        self.keys.remove(key)
+    }
+}
+
+fn event_key(event: &SessionEvent) -> String {
+    format!("{}:{}", event.session_key, event.id.to_hex())
+}
+
+/// This node's connection to the cluster.
+pub struct Cluster {
+    config: ClusterConfig,
+    bus: Arc<dyn MessageBus>,
+    pending: Mutex<HashMap<String, oneshot::Sender<JobResult>>>,
+    applied: Mutex<RecentKeys>,
+    consumed: Mutex<Vec<u32>>,
+}
+
+impl std::fmt::Debug for Cluster {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cluster")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Cluster {
+    /// Connect to the configured bus.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the bus cannot be reached or its transport was not
+    /// compiled in.
+    #[cfg_attr(
+        not(any(feature = "nats", feature = "redis")),
+        allow(clippy::unused_async)
+    )]
+    pub async fn connect(config: ClusterConfig) -> Result<Self, BusError> {
+        let bus: Arc<dyn MessageBus> = match &config.bus {
+            #[cfg(feature = "nats")]
+            BusConfig::Nats(url) => Arc::new(NatsBus::connect(url).await?),
+            #[cfg(not(feature = "nats"))]
+            BusConfig::Nats(_) => {
+                return Err(BusError::Unsupported(
+                    "NATS support requires the `nats` feature".to_string(),
+                ));
+            }
+            #[cfg(feature = "redis")]
+            BusConfig::Redis(url) => Arc::new(
+                RedisBus::connect(url)
+                    .await?
+                    .with_consumer(config.node_id.clone()),
+            ),
+            #[cfg(not(feature = "redis"))]
+            BusConfig::Redis(_) => {
+                return Err(BusError::Unsupported(
+                    "Redis support requires the `redis` feature".to_string(),
+                ));
+            }
+            BusConfig::Custom(bus) => bus.clone(),
+        };
+
+        Ok(Self {
+            config,
+            bus,
+            pending: Mutex::new(HashMap::new()),
+            applied: Mutex::new(RecentKeys::default()),
+            consumed: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// This node's configuration.
+    #[must_use]
+    pub const fn config(&self) -> &ClusterConfig {
+        &self.config
+    }
+
+    /// Partitions this node currently consumes jobs from.
+    #[must_use]
+    pub fn partitions(&self) -> Vec<u32> {
+        self.consumed
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Whether `session.message` calls are sent to workers rather than run
+    /// here.
+    #[must_use]
+    pub fn forwards_messages(&self) -> bool {
+        self.config.role == ClusterRole::Gateway
+    }
+
+    /// Send a `session.message` call to the worker owning its session and
+    /// wait for the reply.
+    pub(crate) async fn forward_message(
+        &self,
+        params: &serde_json::Value,
+        auth_token: Option<&str>,
+    ) -> JobResult {
+        let session_key = params["session_key"]
+            .as_str()
+            .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing session_key".to_string()))?;
+        let job = Job {
+            id: hex::encode(rand::random::<[u8; 8]>()),
+            reply_to: replies_subject(&self.config.node_id),
+            params: params.clone(),
+            auth_token: auth_token.map(String::from),
+        };
+        let payload = serde_json::to_vec(&job)
+            .map_err(|e| (rpc::INTERNAL_ERROR, format!("Serialization error: {e}")))?;
+
+        let (tx, rx) = oneshot::channel();
+        let _pending = PendingJob::register(self, &job.id, tx);
+        self.bus
+            .publish(
+                &jobs_subject(partition_for(session_key, self.config.partitions)),
+                payload,
+            )
+            .await
+            .map_err(|e| (rpc::UNAVAILABLE, e.to_string()))?;
+
+        rx.await
+            .unwrap_or_else(|_| Err((rpc::UNAVAILABLE, "Worker reply lost".to_string())))
+    }
+
+    fn complete(&self, reply: JobReply) {
+        let sender = self
+            .pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&reply.id);
+        if let Some(sender) = sender {
+            let _ = sender.send(reply.result);
+        }
+    }
+}
+
+/// Removes a job's reply slot when the caller stops waiting (e.g. on
+/// timeout).
+struct PendingJob<'a> {
+    cluster: &'a Cluster,
+    id: String,
+}
+
+impl<'a> PendingJob<'a> {
+    fn register(cluster: &'a Cluster, id: &str, sender: oneshot::Sender<JobResult>) -> Self {
+        cluster
+            .pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(id.to_string(), sender);
+        Self {
+            cluster,
+            id: id.to_string(),
+        }
+    }
+}
+
+impl Drop for PendingJob<'_> {
+    fn drop(&mut self) {
+        self.cluster
+            .pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.id);
+    }
+}
+
+/// Subscribe to this node's subjects and start the background tasks:
+/// event sync, plus reply handling (gateway) or job consumption (worker).
+///
+/// A worker first listens for one heartbeat interval to learn the other
+/// workers, then subscribes to its partitions. Subscriptions are made
+/// before returning, so nothing published afterwards is missed.
+///
+/// # Errors
+///
+/// Returns error if a subscription fails.
+pub(crate) async fn start(
+    state: Arc<RwLock<GatewayState>>,
+    cluster: Arc<Cluster>,
+) -> Result<(), BusError> {
+    let events = cluster.bus.subscribe(EVENTS_SUBJECT).await?;
+    let stores = {
+        let state = state.read().await;
+        let mut stores = vec![(None, state.event_store.clone())];
+        stores.extend(
+            state
+                .tenants
+                .iter()
+                .map(|ns| (Some(ns.id.clone()), ns.event_store.clone())),
+        );
+        stores
+    };
+    for (namespace, store) in stores {
+        tokio::spawn(publish_events(cluster.clone(), namespace, store));
+    }
+    tokio::spawn(apply_events(state.clone(), cluster.clone(), events));
+
+    match &cluster.config.role {
+        ClusterRole::Gateway => {
+            let replies = cluster
+                .bus
+                .subscribe(&replies_subject(&cluster.config.node_id))
+                .await?;
+            tokio::spawn(receive_replies(cluster.clone(), replies));
+        }
+        ClusterRole::Worker { partitions } => {
+            let mut heartbeats = cluster.bus.subscribe(MEMBERS_SUBJECT).await?;
+            let members = discover_members(&cluster, &mut heartbeats).await;
+            let mut consumers = Consumers {
+                state: state.clone(),
+                cluster: cluster.clone(),
+                running: HashMap::new(),
+            };
+            consumers
+                .rebalance(members.assign(
+                    &cluster.config.node_id,
+                    partitions,
+                    cluster.config.partitions,
+                ))
+                .await?;
+            tokio::spawn(track_members(consumers, members, heartbeats));
+        }
+    }
+
+    tracing::info!(
+        "Joined cluster as {} ({:?})",
+        cluster.config.node_id,
+        cluster.config.role
+    );
+    Ok(())
+}
+
+/// Publish events appended to a local store, except those that came from
+/// other nodes.
+async fn publish_events(cluster: Arc<Cluster>, namespace: Option<String>, store: Arc<EventStore>) {
+    let mut appended = store.subscribe();
+    loop {
+        let event = match appended.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                tracing::warn!("Event sync lagged, {} events not published", n);
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        let remote = cluster
+            .applied
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&event_key(&event));
+        if remote {
+            continue;
+        }
+
+        let synced = SyncedEvent {
+            origin: cluster.config.node_id.clone(),
+            namespace: namespace.clone(),
+            event,
+        };
+        let Ok(payload) = serde_json::to_vec(&synced) else {
+            continue;
+        };
+        if let Err(e) = cluster.bus.publish(EVENTS_SUBJECT, payload).await {
+            tracing::warn!("Failed to publish session event: {}", e);
+        }
+    }
+}
+
+/// Append events published by other nodes to the matching local store.
+async fn apply_events(
+    state: Arc<RwLock<GatewayState>>,
+    cluster: Arc<Cluster>,
+    mut events: BusStream,
+) {
+    while let Some(payload) = events.next().await {
+        let synced: SyncedEvent = match serde_json::from_slice(&payload) {
+            Ok(synced) => synced,
+            Err(e) => {
+                tracing::warn!("Ignoring malformed synced event: {}", e);
+                continue;
+            }
+        };
+        if synced.origin == cluster.config.node_id {
+            continue;
+        }
+
+        cluster
+            .applied
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(event_key(&synced.event));
+        let store = {
+            let state = state.read().await;
+            synced
+                .namespace
+                .as_deref()
+                .and_then(|id| state.tenants.get(id))
+                .map_or_else(|| state.event_store.clone(), |ns| ns.event_store.clone())
+        };
+        if let Err(e) = store.append(&synced.event) {
+            tracing::warn!("Failed to apply synced event: {}", e);
+        }
+    }
+}
+
+/// Hand worker replies to the calls waiting for them.
+async fn receive_replies(cluster: Arc<Cluster>, mut replies: BusStream) {
+    while let Some(payload) = replies.next().await {
+        match serde_json::from_slice(&payload) {
+            Ok(reply) => cluster.complete(reply),
+            Err(e) => tracing::warn!("Ignoring malformed job reply: {}", e),
+        }
+    }
+}
+
+fn heartbeat(cluster: &Cluster) -> Vec<u8> {
+    let partitions = match &cluster.config.role {
+        ClusterRole::Worker { partitions } => partitions.clone(),
+        ClusterRole::Gateway => Vec::new(),
+    };
+    serde_json::to_vec(&Heartbeat {
+        node_id: cluster.config.node_id.clone(),
+        partitions,
+    })
+    .unwrap_or_default()
+}
+
+async fn publish_heartbeat(cluster: &Cluster) {
+    if let Err(e) = cluster
+        .bus
+        .publish(MEMBERS_SUBJECT, heartbeat(cluster))
+        .await
+    {
+        tracing::warn!("Failed to publish worker heartbeat: {}", e);
+    }
+}
+
+fn record_heartbeat(members: &mut Members, payload: &[u8]) -> bool {
+    match serde_json::from_slice(payload) {
+        Ok(heartbeat) => members.record(heartbeat),
+        Err(e) => {
+            tracing::warn!("Ignoring malformed worker heartbeat: {}", e);
+            false
+        }
+    }
+}
+
+/// Announce this worker and collect the others' heartbeats for one
+/// interval.
+async fn discover_members(cluster: &Cluster, heartbeats: &mut BusStream) -> Members {
+    let mut members = Members::default();
+    publish_heartbeat(cluster).await;
+    let listening = tokio::time::sleep(cluster.config.heartbeat);
+    tokio::pin!(listening);
+    loop {
+        tokio::select! {
+            () = &mut listening => return members,
+            payload = heartbeats.next() => match payload {
+                Some(payload) => {
+                    record_heartbeat(&mut members, &payload);
+                }
+                None => return members,
+            },
+        }
+    }
+}
+
+/// Job consumers a worker runs, by partition.
+struct Consumers {
+    state: Arc<RwLock<GatewayState>>,
+    cluster: Arc<Cluster>,
+    running: HashMap<u32, oneshot::Sender<()>>,
+}
+
+impl Consumers {
+    /// Consume exactly `owned`: stop the consumers of partitions no longer
+    /// owned (each finishes its current job first) and start the new ones.
+    async fn rebalance(&mut self, owned: Vec<u32>) -> Result<(), BusError> {
+        self.running
+            .retain(|partition, _| owned.contains(partition));
+        for partition in owned {
+            if self.running.contains_key(&partition) {
+                continue;
+            }
+            let jobs = self
+                .cluster
+                .bus
+                .queue_subscribe(&jobs_subject(partition), WORKERS_GROUP)
+                .await?;
+            let (stop, stopped) = oneshot::channel();
+            tokio::spawn(run_jobs(
+                self.state.clone(),
+                self.cluster.clone(),
+                jobs,
+                stopped,
+            ));
+            self.running.insert(partition, stop);
+        }
+
+        let mut consumed: Vec<u32> = self.running.keys().copied().collect();
+        consumed.sort_unstable();
+        tracing::debug!(
+            "Worker {} consumes partitions {:?}",
+            self.cluster.config.node_id,
+            consumed
+        );
+        *self
+            .cluster
+            .consumed
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = consumed;
+        Ok(())
+    }
+}
+
+/// Send heartbeats, track the other workers' and move partitions when
+/// membership changes.
+async fn track_members(mut consumers: Consumers, mut members: Members, mut heartbeats: BusStream) {
+    let cluster = consumers.cluster.clone();
+    let ClusterRole::Worker { partitions: listed } = &cluster.config.role else {
+        return;
+    };
+    let interval = cluster.config.heartbeat;
+    let mut ticks = tokio::time::interval(interval);
+    loop {
+        let changed = tokio::select! {
+            _ = ticks.tick() => {
+                publish_heartbeat(&cluster).await;
+                members.expire(interval * MISSED_HEARTBEATS)
+            }
+            payload = heartbeats.next() => match payload {
+                Some(payload) => record_heartbeat(&mut members, &payload),
+                None => return,
+            },
+        };
+        if !changed {
+            continue;
+        }
+        let owned = members.assign(&cluster.config.node_id, listed, cluster.config.partitions);
+        if let Err(e) = consumers.rebalance(owned).await {
+            tracing::warn!("Failed to rebalance partitions: {}", e);
+        }
+    }
+}
+
+/// Run one partition's jobs until told to stop, one at a time so each
+/// session's messages are handled in order, acknowledging each once its
+/// reply is sent. Jobs of a partition go to a single worker only while
+/// membership is stable (see [`Members::assign`]).
+async fn run_jobs(
+    state: Arc<RwLock<GatewayState>>,
+    cluster: Arc<Cluster>,
+    mut jobs: QueueStream,
+    mut stop: oneshot::Receiver<()>,
+) {
+    loop {
+        let message = tokio::select! {
+            message = jobs.next() => match message {
+                Some(message) => message,
+                None => return,
+            },
+            _ = &mut stop => return,
+        };
+        match serde_json::from_slice::<Job>(&message.payload) {
+            Ok(job) => run_job(&state, &cluster, job).await,
+            Err(e) => tracing::warn!("Ignoring malformed job: {}", e),
+        }
+        message.ack().await;
+    }
+}
+
+async fn run_job(state: &Arc<RwLock<GatewayState>>, cluster: &Cluster, job: Job) {
+    let result = handle_session_message(state, &job.params, job.auth_token.as_deref()).await;
+    let reply = JobReply { id: job.id, result };
+    let Ok(payload) = serde_json::to_vec(&reply) else {
+        return;
+    };
+    if let Err(e) = cluster.bus.publish(&job.reply_to, payload).await {
+        tracing::warn!("Failed to publish job reply: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{GatewayBuilder, GatewayConfig};
+    use openclaw_agents::harness::{MockCompletion, MockProvider};
+    use openclaw_agents::runtime::AgentRuntime;
+
+    #[test]
+    fn test_partition_for_is_stable() {
+        let p = partition_for("agent:default:main", DEFAULT_PARTITIONS);
+        assert!(p < DEFAULT_PARTITIONS);
+        assert_eq!(p, partition_for("agent:default:main", DEFAULT_PARTITIONS));
+        assert_eq!(partition_for("anything", 1), 0);
+        assert_eq!(partition_for("anything", 0), 0);
+
+        // Keys spread over partitions
+        let used: HashSet<u32> = (0..100)
+            .map(|i| partition_for(&format!("session-{i}"), 4))
+            .collect();
+        assert_eq!(used.len(), 4);
+    }
+
+    #[test]
+    fn test_recent_keys_bounded() {
+        let mut keys = RecentKeys::default();
+        for i in 0..=APPLIED_CAPACITY {
+            keys.insert(i.to_string());
+        }
+        assert!(!keys.remove("0"));
+        assert!(keys.remove(&APPLIED_CAPACITY.to_string()));
+        assert!(!keys.remove(&APPLIED_CAPACITY.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_forward_message_round_trip() {
+        let bus: Arc<dyn MessageBus> = Arc::new(MemoryBus::new());
+        let cluster = Arc::new(
+            Cluster::connect(ClusterConfig {
+                bus: BusConfig::Custom(bus.clone()),
+                node_id: "gw".to_string(),
+                role: ClusterRole::Gateway,
+                partitions: 1,
+                heartbeat: DEFAULT_HEARTBEAT,
+            })
+            .await
+            .unwrap(),
+        );
+        let replies = bus.subscribe(&replies_subject("gw")).await.unwrap();
+        tokio::spawn(receive_replies(cluster.clone(), replies));
+
+        // Stand-in worker that echoes the message back
+        let mut jobs = bus.subscribe(&jobs_subject(0)).await.unwrap();
+        let worker_bus = bus.clone();
+        tokio::spawn(async move {
+            let job: Job = serde_json::from_slice(&jobs.next().await.unwrap()).unwrap();
+            let reply = JobReply {
+                id: job.id,
+                result: Ok(serde_json::json!({ "response": job.params["message"] })),
+            };
+            worker_bus
+                .publish(&job.reply_to, serde_json::to_vec(&reply).unwrap())
+                .await
+                .unwrap();
+        });
+
+        let result = cluster
+            .forward_message(
+                &serde_json::json!({ "session_key": "s1", "message": "hello" }),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result["response"], "hello");
+        assert!(cluster.pending.lock().unwrap().is_empty());
+    }
+
+    const HEARTBEAT: Duration = Duration::from_millis(50);
+
+    /// Start a worker node without a partition list that answers each
+    /// message with "ok".
+    async fn start_worker(
+        bus: &Arc<dyn MessageBus>,
+        node_id: &str,
+        dir: &std::path::Path,
+    ) -> (Arc<MockProvider>, Arc<Cluster>) {
+        let provider = Arc::new(MockProvider::new(
+            (0..16).map(|_| MockCompletion::Text("ok".to_string())),
+        ));
+        let dir = dir.join(node_id);
+        let gateway = GatewayBuilder::new()
+            .with_config(GatewayConfig {
+                data_dir: dir.clone(),
+                ..GatewayConfig::default()
+            })
+            .with_event_store(Arc::new(EventStore::open(&dir.join("events")).unwrap()))
+            .with_agent("default", Arc::new(AgentRuntime::new(provider.clone())))
+            .build()
+            .unwrap();
+        let cluster = Cluster::connect(ClusterConfig {
+            bus: BusConfig::Custom(bus.clone()),
+            node_id: node_id.to_string(),
+            role: ClusterRole::Worker { partitions: vec![] },
+            partitions: 2,
+            heartbeat: HEARTBEAT,
+        })
+        .await
+        .unwrap();
+        let cluster = Arc::new(cluster);
+        start(gateway.state().clone(), cluster.clone())
+            .await
+            .unwrap();
+        (provider, cluster)
+    }
+
+    async fn start_gateway(bus: &Arc<dyn MessageBus>) -> Arc<Cluster> {
+        let cluster = Arc::new(
+            Cluster::connect(ClusterConfig {
+                bus: BusConfig::Custom(bus.clone()),
+                node_id: "gw".to_string(),
+                role: ClusterRole::Gateway,
+                partitions: 2,
+                heartbeat: HEARTBEAT,
+            })
+            .await
+            .unwrap(),
+        );
+        let replies = bus.subscribe(&replies_subject("gw")).await.unwrap();
+        tokio::spawn(receive_replies(cluster.clone(), replies));
+        cluster
+    }
+
+    #[test]
+    fn test_members_assign_each_partition_once() {
+        let mut members = Members::default();
+        for (node_id, partitions) in [("w1", vec![]), ("w2", vec![]), ("w3", vec![0])] {
+            members.record(Heartbeat {
+                node_id: node_id.to_string(),
+                partitions,
+            });
+        }
+        let first = members.assign("w1", &[], 8);
+        let second = members.assign("w2", &[], 8);
+        assert_eq!(members.assign("w3", &[0], 8), vec![0]);
+        assert!(first.iter().all(|p| !second.contains(p)));
+        let mut all: Vec<u32> = first.iter().chain(&second).copied().collect();
+        all.sort_unstable();
+        assert_eq!(all, (1..8).collect::<Vec<_>>());
+
+        // Without its peers, a worker takes every partition
+        assert_eq!(Members::default().assign("w1", &[], 4), vec![0, 1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_workers_run_each_job_once() {
+        let temp = tempfile::tempdir().unwrap();
+        let bus: Arc<dyn MessageBus> = Arc::new(MemoryBus::new());
+        let (first, _) = start_worker(&bus, "w1", temp.path()).await;
+        let (second, _) = start_worker(&bus, "w2", temp.path()).await;
+        let cluster = start_gateway(&bus).await;
+
+        let jobs = (0..4).map(|i| {
+            let cluster = cluster.clone();
+            async move {
+                let params = serde_json::json!({
+                    "session_key": format!("session-{i}"),
+                    "message": "hello",
+                });
+                cluster.forward_message(&params, None).await
+            }
+        });
+        for result in futures::future::join_all(jobs).await {
+            assert_eq!(result.unwrap()["response"], "ok");
+        }
+
+        // Give a duplicate delivery time to show up
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert_eq!(first.requests().len() + second.requests().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_workers_keep_session_order() {
+        let temp = tempfile::tempdir().unwrap();
+        let bus: Arc<dyn MessageBus> = Arc::new(MemoryBus::new());
+        let (first, first_cluster) = start_worker(&bus, "w1", temp.path()).await;
+        let (second, second_cluster) = start_worker(&bus, "w2", temp.path()).await;
+
+        // Wait for the workers to split the partitions between them
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let mut consumed = first_cluster.partitions();
+                consumed.extend(second_cluster.partitions());
+                consumed.sort_unstable();
+                if consumed == [0, 1] {
+                    break;
+                }
+                tokio::time::sleep(HEARTBEAT).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        // Published in order, without waiting for replies
+        let cluster = start_gateway(&bus).await;
+        let messages = (0..6).map(|i| {
+            let cluster = cluster.clone();
+            async move {
+                let params = serde_json::json!({
+                    "session_key": "session-a",
+                    "message": format!("message-{i}"),
+                });
+                cluster.forward_message(&params, None).await
+            }
+        });
+        for result in futures::future::join_all(messages).await {
+            assert_eq!(result.unwrap()["response"], "ok");
+        }
+
+        let (owner, other) = if first.requests().is_empty() {
+            (second, first)
+        } else {
+            (first, second)
+        };
+        assert!(other.requests().is_empty());
+        let requests = owner.requests();
+        assert_eq!(requests.len(), 6);
+        for (i, request) in requests.iter().enumerate() {
+            let last = serde_json::to_string(request.messages.last().unwrap()).unwrap();
+            assert!(last.contains(&format!("message-{i}")), "{last}");
+        }
+    }
+}
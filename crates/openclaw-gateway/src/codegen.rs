@@ -0,0 +1,477 @@
+//! TypeScript client types generated from the gateway's Rust definitions.
+//!
+//! [`typescript`] renders the RPC [`catalog`](crate::catalog) and the
+//! [`UiEvent`](crate::UiEvent) payloads as TypeScript. The output is checked
+//! in at [`TYPESCRIPT_PATH`] for the web UI; a test fails when it is stale.
+//! Regenerate it with:
+//!
+//! ```text
+//! OPENCLAW_UPDATE_GENERATED=1 cargo test -p openclaw-gateway codegen
+//! ```
+
+use std::fmt::Write;
+
+use crate::catalog::{METHODS, MethodSpec, ParamType};
+
+/// Generated file, relative to the workspace root.
+pub const TYPESCRIPT_PATH: &str = "crates/openclaw-ui/src/generated/gateway.ts";
+
+/// TypeScript fields of each [`UiEvent`](crate::UiEvent), by `type`.
+const UI_EVENTS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "session_created",
+        &[
+            ("session_key", "string"),
+            ("agent_id", "string"),
+            ("channel", "string"),
+            ("peer_id", "string"),
+        ],
+    ),
+    (
+        "session_updated",
+        &[("session_key", "string"), ("update", "SessionUpdate")],
+    ),
+    (
+        "message_received",
+        &[
+            ("session_key", "string"),
+            ("content", "string"),
+            ("peer_id", "string"),
+        ],
+    ),
+    (
+        "message_sent",
+        &[("session_key", "string"), ("content", "string")],
+    ),
+    (
+        "tool_executed",
+        &[
+            ("session_key", "string"),
+            ("tool", "string"),
+            ("result", "unknown"),
+            ("success", "boolean"),
+        ],
+    ),
+    (
+        "content_moderated",
+        &[
+            ("session_key", "string"),
+            ("agent_id", "string"),
+            ("stage", "'inbound' | 'outbound'"),
+            ("moderator", "string"),
+            ("reason", "string"),
+            ("categories", "string[]"),
+            ("blocked", "boolean"),
+        ],
+    ),
+    (
+        "reminder_failed",
+        &[
+            ("session_key", "string"),
+            ("reminder_id", "string"),
+            ("error", "string"),
+        ],
+    ),
+    ("pending_approval", &[("request", "ApprovalRequest")]),
+    (
+        "approval_resolved",
+        &[("id", "string"), ("decision", "ApprovalDecision")],
+    ),
+    (
+        "workflow_input_requested",
+        &[
+            ("run_id", "string"),
+            ("workflow_id", "string"),
+            ("node_id", "string"),
+            ("prompt", "string"),
+            ("choices", "string[]"),
+            ("expires_at", "string | null"),
+        ],
+    ),
+    (
+        "workflow_input_resolved",
+        &[("run_id", "string"), ("timed_out", "boolean")],
+    ),
+    (
+        "channel_status_changed",
+        &[
+            ("channel_id", "string"),
+            ("connected", "boolean"),
+            ("error", "string | null"),
+        ],
+    ),
+    (
+        "channel_state_changed",
+        &[
+            ("channel_id", "string"),
+            ("state", "'starting' | 'running' | 'backoff' | 'stopped'"),
+            ("enabled", "boolean"),
+            ("error", "string | null"),
+        ],
+    ),
+    ("gateway_started", &[("report", "Record<string, unknown>")]),
+    (
+        "gateway_stopping",
+        &[("active_runs", "number"), ("drain_timeout_ms", "number")],
+    ),
+    (
+        "transfer_progress",
+        &[
+            ("transfer_id", "string"),
+            ("direction", "'upload' | 'download'"),
+            ("name", "string"),
+            ("transferred", "number"),
+            ("total", "number"),
+            ("session_key", "string | null"),
+        ],
+    ),
+    (
+        "config_changed",
+        &[
+            ("section", "string"),
+            ("changes", "ConfigChange[]"),
+            ("changed_by", "string"),
+            ("persisted", "boolean"),
+        ],
+    ),
+    (
+        "login_locked_out",
+        &[
+            ("key", "string"),
+            ("failures", "number"),
+            ("locked_until", "string"),
+        ],
+    ),
+    ("heartbeat", &[("timestamp", "string")]),
+];
+
+/// Types referenced by event fields.
+const EVENT_SUPPORT_TYPES: &str = "\
+export type SessionUpdate =
+  | { state_changed: { new_state: string } }
+  | { message_count: { count: number } }
+  | { ended: { reason: string } }
+
+export interface ApprovalRequest {
+  id: string
+  agent_id: string
+  session_key: string | null
+  tool: string
+  params: unknown
+  requested_at: string
+  expires_at: string
+}
+
+export type ApprovalDecision =
+  | { decision: 'approved'; by: string }
+  | { decision: 'denied'; by: string; reason: string | null }
+  | { decision: 'timed_out' }
+
+export interface ConfigChange {
+  field: string
+  old: unknown
+  new: unknown
+}
+";
+
+/// The generated TypeScript module.
+#[must_use]
+pub fn typescript() -> String {
+    let mut out = String::from(
+        "// Generated from the gateway's Rust definitions. Do not edit.\n\
+         // Regenerate with: OPENCLAW_UPDATE_GENERATED=1 cargo test -p openclaw-gateway codegen\n\n\
+         export type Permission = 'public' | 'viewer' | 'operator' | 'admin'\n",
+    );
+
+    for spec in METHODS {
+        out.push('\n');
+        write_params(&mut out, spec);
+    }
+
+    out.push_str("\nexport interface RpcParams {\n");
+    for spec in METHODS {
+        let _ = writeln!(out, "  '{}': {}", spec.name, params_name(spec.name));
+    }
+    out.push_str("}\n\nexport type RpcMethod = keyof RpcParams\n");
+
+    out.push_str(
+        "\nexport const RPC_METHODS: Record<RpcMethod, { summary: string; permission: Permission }> = {\n",
+    );
+    for spec in METHODS {
+        let permission = serde_json::to_value(spec.permission).unwrap_or_default();
+        let _ = writeln!(
+            out,
+            "  '{}': {{ summary: '{}', permission: '{}' }},",
+            spec.name,
+            escape(spec.summary),
+            permission.as_str().unwrap_or_default()
+        );
+    }
+    out.push_str("}\n\n");
+
+    out.push_str(EVENT_SUPPORT_TYPES);
+    out.push_str("\nexport type UiEvent =\n");
+    for (name, fields) in UI_EVENTS {
+        let _ = write!(out, "  | {{ type: '{name}'");
+        for (field, ty) in *fields {
+            let _ = write!(out, "; {field}: {ty}");
+        }
+        out.push_str(" }\n");
+    }
+    out.push_str(
+        "\nexport type UiEventType = UiEvent['type']\n\n\
+         export interface UiEventEnvelope {\n  id: string\n  timestamp: string\n  event: UiEvent\n}\n",
+    );
+    out
+}
+
+fn write_params(out: &mut String, spec: &MethodSpec) {
+    let _ = writeln!(out, "/** {} */", spec.summary);
+    if spec.params.is_empty() {
+        let _ = writeln!(
+            out,
+            "export type {} = Record<string, never>",
+            params_name(spec.name)
+        );
+        return;
+    }
+
+    let _ = writeln!(out, "export interface {} {{", params_name(spec.name));
+    for param in spec.params {
+        let _ = writeln!(out, "  /** {} */", param.description);
+        let _ = writeln!(
+            out,
+            "  {}{}: {}",
+            param.name,
+            if param.required { "" } else { "?" },
+            ts_type(param.kind)
+        );
+    }
+    out.push_str("}\n");
+}
+
+/// `session.dry_run` becomes `SessionDryRunParams`.
+fn params_name(method: &str) -> String {
+    let mut name: String = method
+        .split(['.', '_'])
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect()
+            })
+        })
+        .collect();
+    name.push_str("Params");
+    name
+}
+
+fn ts_type(kind: ParamType) -> String {
+    match kind {
+        ParamType::String | ParamType::Date | ParamType::DateTime => "string".to_string(),
+        ParamType::Integer | ParamType::Number => "number".to_string(),
+        ParamType::Boolean => "boolean".to_string(),
+        ParamType::Object => "Record<string, unknown>".to_string(),
+        ParamType::Array => "unknown[]".to_string(),
+        ParamType::Enum(values) => values
+            .iter()
+            .map(|value| format!("'{}'", escape(value)))
+            .collect::<Vec<_>>()
+            .join(" | "),
+    }
+}
+
+/// Escape for a single-quoted TypeScript string.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::path::Path;
+
+    use chrono::Utc;
+    use openclaw_agents::tools::{ApprovalDecision, ApprovalRequest};
+    use openclaw_core::events::StoreStats;
+
+    use super::*;
+    use crate::events::{SessionUpdate, TransferDirection, UiEvent};
+    use crate::startup::StartupReport;
+    use crate::supervisor::ChannelRunState;
+
+    /// One of each event, so a new variant or field shows up here.
+    fn sample_events() -> Vec<UiEvent> {
+        let key = "s".to_string();
+        vec![
+            UiEvent::SessionCreated {
+                session_key: key.clone(),
+                agent_id: "a".to_string(),
+                channel: "c".to_string(),
+                peer_id: "p".to_string(),
+            },
+            UiEvent::SessionUpdated {
+                session_key: key.clone(),
+                update: SessionUpdate::MessageCount { count: 1 },
+            },
+            UiEvent::MessageReceived {
+                session_key: key.clone(),
+                content: "hi".to_string(),
+                peer_id: "p".to_string(),
+            },
+            UiEvent::MessageSent {
+                session_key: key.clone(),
+                content: "hello".to_string(),
+            },
+            UiEvent::ToolExecuted {
+                session_key: key.clone(),
+                tool: "t".to_string(),
+                result: serde_json::json!({}),
+                success: true,
+            },
+            UiEvent::ContentModerated {
+                session_key: key.clone(),
+                agent_id: "a".to_string(),
+                stage: "inbound".to_string(),
+                moderator: "word_list".to_string(),
+                reason: "contains 'spam'".to_string(),
+                categories: vec![],
+                blocked: true,
+            },
+            UiEvent::ReminderFailed {
+                session_key: key.clone(),
+                reminder_id: "r".to_string(),
+                error: "No outbound adapter".to_string(),
+            },
+            UiEvent::PendingApproval {
+                request: ApprovalRequest {
+                    id: "r".to_string(),
+                    agent_id: "a".to_string(),
+                    session_key: Some(key),
+                    tool: "t".to_string(),
+                    params: serde_json::json!({}),
+                    requested_at: Utc::now(),
+                    expires_at: Utc::now(),
+                },
+            },
+            UiEvent::ApprovalResolved {
+                id: "r".to_string(),
+                decision: ApprovalDecision::TimedOut,
+            },
+            UiEvent::WorkflowInputRequested {
+                run_id: "run".to_string(),
+                workflow_id: "w".to_string(),
+                node_id: "ask".to_string(),
+                prompt: "Continue?".to_string(),
+                choices: vec![],
+                expires_at: None,
+            },
+            UiEvent::WorkflowInputResolved {
+                run_id: "run".to_string(),
+                timed_out: false,
+            },
+            UiEvent::ChannelStatusChanged {
+                channel_id: "c".to_string(),
+                connected: false,
+                error: None,
+            },
+            UiEvent::ChannelStateChanged {
+                channel_id: "c".to_string(),
+                state: ChannelRunState::Stopped,
+                enabled: false,
+                error: None,
+            },
+            UiEvent::GatewayStarted {
+                report: Box::new(StartupReport {
+                    version: "0.1.0".to_string(),
+                    started_at: Utc::now(),
+                    config_source: None,
+                    address: "127.0.0.1:18789".to_string(),
+                    agents: vec![],
+                    channels: vec![],
+                    storage: StoreStats::default(),
+                    phases: vec![],
+                    total_ms: 0,
+                    budget_ms: 0,
+                }),
+            },
+            UiEvent::GatewayStopping {
+                active_runs: 0,
+                drain_timeout_ms: 0,
+            },
+            UiEvent::TransferProgress {
+                transfer_id: "t".to_string(),
+                direction: TransferDirection::Upload,
+                name: "f".to_string(),
+                transferred: 0,
+                total: 1,
+                session_key: None,
+            },
+            UiEvent::ConfigChanged {
+                section: "agents.default".to_string(),
+                changes: vec![],
+                changed_by: "admin".to_string(),
+                persisted: true,
+            },
+            UiEvent::LoginLockedOut {
+                key: "user:admin".to_string(),
+                failures: 5,
+                locked_until: Utc::now(),
+            },
+            UiEvent::Heartbeat {
+                timestamp: Utc::now(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_event_types_match_serialization() {
+        let samples = sample_events();
+        assert_eq!(samples.len(), UI_EVENTS.len());
+
+        for event in samples {
+            let value = serde_json::to_value(&event).unwrap();
+            let name = value["type"].as_str().unwrap();
+            let (_, fields) = UI_EVENTS
+                .iter()
+                .find(|(n, _)| *n == name)
+                .unwrap_or_else(|| panic!("{name} is not in UI_EVENTS"));
+
+            let serialized: BTreeSet<&str> = value
+                .as_object()
+                .unwrap()
+                .keys()
+                .map(String::as_str)
+                .filter(|key| *key != "type")
+                .collect();
+            let declared: BTreeSet<&str> = fields.iter().map(|(field, _)| *field).collect();
+            assert_eq!(serialized, declared, "fields of {name}");
+        }
+    }
+
+    #[test]
+    fn test_params_name() {
+        assert_eq!(params_name("session.dry_run"), "SessionDryRunParams");
+        assert_eq!(params_name("admin.log_level.get"), "AdminLogLevelGetParams");
+    }
+
+    #[test]
+    fn test_generated_typescript_is_current() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../..")
+            .join(TYPESCRIPT_PATH);
+        let generated = typescript();
+
+        if std::env::var_os("OPENCLAW_UPDATE_GENERATED").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, &generated).unwrap();
+            return;
+        }
+
+        let current = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            current == generated,
+            "{TYPESCRIPT_PATH} is out of date; run \
+             OPENCLAW_UPDATE_GENERATED=1 cargo test -p openclaw-gateway codegen"
+        );
+    }
+}
@@ -0,0 +1,216 @@
+//! Daemon control socket handler.
+//!
+//! Answers `openclaw daemon status/reload/probe/channel/stop` over the local
+//! IPC control socket, bypassing HTTP auth.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use tokio::sync::RwLock;
+
+use openclaw_channels::{
+    ChatCommands, Experiment, InboundDebouncer, InboundRateLimiter, WorkingHoursPolicy,
+};
+use openclaw_core::Config;
+use openclaw_ipc::{ControlCommand, ControlHandler};
+
+use crate::server::{GatewayState, open_localizer, set_channel_enabled};
+use crate::shutdown::Shutdown;
+use crate::startup::probe_channels;
+use crate::timeouts::RequestTimeouts;
+
+/// Gateway implementation of the control socket commands.
+pub struct GatewayControl {
+    state: Arc<RwLock<GatewayState>>,
+    shutdown: Arc<Shutdown>,
+    started: Instant,
+}
+
+impl GatewayControl {
+    pub(crate) fn new(state: Arc<RwLock<GatewayState>>, shutdown: Arc<Shutdown>) -> Self {
+        Self {
+            state,
+            shutdown,
+            started: Instant::now(),
+        }
+    }
+
+    async fn status(&self) -> Value {
+        let state = self.state.read().await;
+
+        let mut agents: Vec<&str> = state.agents.keys().map(String::as_str).collect();
+        agents.sort_unstable();
+        let registry = state.channels.read().await;
+        let mut channels = registry.list();
+        channels.sort_unstable();
+
+        let status = json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "pid": std::process::id(),
+            "uptime_secs": self.started.elapsed().as_secs(),
+            "address": format!("{}:{}", state.config.bind_address, state.config.port),
+            "config_source": state.config.config_source,
+            "agents": agents,
+            "channels": channels,
+            "channel_health": state.supervisor.health(),
+            "healthy": state.supervisor.is_healthy(),
+            "storage": state.event_store.stats().unwrap_or_default(),
+            "startup": state.startup,
+            "after_hours_held": state.after_hours.pending(),
+        });
+        drop(registry);
+        drop(state);
+        status
+    }
+
+    /// Re-read the config file and apply the settings that can change
+    /// without rebinding sockets or restarting background tasks.
+    async fn reload(&self) -> Result<Value, String> {
+        let path = {
+            let state = self.state.read().await;
+            state
+                .config
+                .config_source
+                .as_ref()
+                .map_or_else(Config::default_path, Into::into)
+        };
+        let config = if path.exists() {
+            Config::load(&path).map_err(|e| format!("Failed to load {}: {e}", path.display()))?
+        } else {
+            Config::default()
+        };
+
+        let localizer = open_localizer(&config.channels.locale).map_err(|e| e.to_string())?;
+
+        let mut state = self.state.write().await;
+
+        state.config.timeouts = RequestTimeouts::from_config(&config.gateway);
+        state.config.deleted_session_retention_days =
+            config.settings.deleted_session_retention_days;
+        state.config.archive_ended_after_days = config.settings.archive_ended_after_days;
+        state.config.idle_sessions = config.settings.idle_sessions;
+        state.inbound_limiter = Arc::new(InboundRateLimiter::new(&config.channels.rate_limit));
+        state.config.inbound_rate_limit = config.channels.rate_limit;
+        state.outbound = Arc::new(
+            state
+                .outbound
+                .reconfigured(config.channels.formatting.clone()),
+        );
+        state.config.outbound_formatting = config.channels.formatting;
+        state.debouncer = Arc::new(InboundDebouncer::new(&config.channels.debounce));
+        state.config.inbound_debounce = config.channels.debounce;
+        state.working_hours = Arc::new(WorkingHoursPolicy::new(&config.channels.working_hours));
+        state.config.working_hours = config.channels.working_hours;
+        state.groups = Arc::new(state.groups.reconfigured(config.channels.groups.clone()));
+        state.config.groups = config.channels.groups;
+        state.chat_commands = Arc::new(ChatCommands::new(config.channels.commands.clone()));
+        state.config.commands = config.channels.commands;
+        state.localizer = localizer;
+        state.config.locale = config.channels.locale;
+
+        let mut router = (*state.router).clone();
+        router.set_experiments(
+            config
+                .experiments
+                .iter()
+                .map(|(id, experiment)| Experiment::from_config(id, experiment))
+                .collect(),
+        );
+        state.router = Arc::new(router);
+        state.config.experiments = config.experiments;
+
+        let mut restart_required = Vec::new();
+        if config.gateway.port != state.config.port {
+            restart_required.push("gateway.port");
+        }
+        if serde_json::to_value(&config.schedules).ok()
+            != serde_json::to_value(&state.config.schedules).ok()
+        {
+            restart_required.push("schedules");
+        }
+        drop(state);
+
+        tracing::info!("Configuration reloaded from {}", path.display());
+        Ok(json!({
+            "source": path.display().to_string(),
+            "applied": [
+                "gateway.timeouts",
+                "channels.rateLimit",
+                "channels.formatting",
+                "channels.debounce",
+                "channels.workingHours",
+                "channels.groups",
+                "channels.commands",
+                "channels.locale",
+                "experiments",
+                "settings.deletedSessionRetentionDays",
+                "settings.archiveEndedAfterDays",
+                "settings.idleSessions",
+            ],
+            "restart_required": restart_required,
+        }))
+    }
+
+    async fn probe(&self, channel_id: Option<&str>) -> Result<Value, String> {
+        let channels = self.state.read().await.channels.clone();
+        let registry = channels.read().await;
+
+        let mut results = probe_channels(&registry).await;
+        drop(registry);
+        if let Some(id) = channel_id {
+            results.retain(|c| c.id == id);
+            if results.is_empty() {
+                return Err(format!("Channel not found: {id}"));
+            }
+        }
+        Ok(json!({ "channels": results }))
+    }
+
+    async fn set_enabled(&self, channel_id: &str, enabled: bool) -> Result<Value, String> {
+        let (health, persisted) =
+            set_channel_enabled(&self.state, channel_id, enabled, "control".to_string())
+                .await
+                .map_err(|(_, message)| message)?;
+        Ok(json!({ "health": health, "persisted": persisted }))
+    }
+}
+
+#[async_trait]
+impl ControlHandler for GatewayControl {
+    async fn handle(&self, command: ControlCommand) -> Result<Value, String> {
+        match command {
+            ControlCommand::Status => Ok(self.status().await),
+            ControlCommand::ReloadConfig => self.reload().await,
+            ControlCommand::ProbeChannels(channel_id) => self.probe(channel_id.as_deref()).await,
+            ControlCommand::StartChannel(channel_id) => {
+                let supervisor = self.state.read().await.supervisor.clone();
+                let health = supervisor.start(&channel_id).map_err(|e| e.to_string())?;
+                serde_json::to_value(health).map_err(|e| e.to_string())
+            }
+            ControlCommand::StopChannel(channel_id) => {
+                let supervisor = self.state.read().await.supervisor.clone();
+                let health = supervisor
+                    .stop(&channel_id)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                serde_json::to_value(health).map_err(|e| e.to_string())
+            }
+            ControlCommand::EnableChannel(channel_id) => self.set_enabled(&channel_id, true).await,
+            ControlCommand::DisableChannel(channel_id) => {
+                self.set_enabled(&channel_id, false).await
+            }
+            ControlCommand::Shutdown => {
+                if self.shutdown.request() {
+                    tracing::info!("Shutdown requested over control socket");
+                }
+                Ok(json!({
+                    "pid": std::process::id(),
+                    "phase": self.shutdown.phase(),
+                    "active_runs": self.shutdown.active_runs(),
+                }))
+            }
+        }
+    }
+}
@@ -1,12 +1,20 @@
 //! WebSocket UI events for real-time updates.
 //!
 //! This module provides a broadcast system for pushing events to connected
-//! WebSocket clients.
+//! WebSocket and Server-Sent Events clients.
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Mutex, RwLock};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 
+use openclaw_agents::tools::{ApprovalDecision, ApprovalRequest};
+
+use crate::startup::StartupReport;
+use crate::supervisor::ChannelRunState;
+
 /// UI event types that can be broadcast to connected clients.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -61,6 +69,73 @@ pub enum UiEvent {
         success: bool,
     },
 
+    /// Content moderation caught a message or reply, for agents that
+    /// notify admins.
+    ContentModerated {
+        /// Session key.
+        session_key: String,
+        /// Agent ID.
+        agent_id: String,
+        /// `inbound` or `outbound`.
+        stage: String,
+        /// Moderator that caught it.
+        moderator: String,
+        /// Why it was caught.
+        reason: String,
+        /// Policy categories it falls under.
+        categories: Vec<String>,
+        /// Whether it was held back rather than let through.
+        blocked: bool,
+    },
+
+    /// A reminder could not be delivered and was given up on.
+    ReminderFailed {
+        /// Session key.
+        session_key: String,
+        /// Reminder ID.
+        reminder_id: String,
+        /// Why it was not delivered.
+        error: String,
+    },
+
+    /// A tool call is waiting for operator approval.
+    PendingApproval {
+        /// The held call.
+        request: ApprovalRequest,
+    },
+
+    /// A pending tool call was approved, denied, or timed out.
+    ApprovalResolved {
+        /// Approval request ID.
+        id: String,
+        /// Outcome.
+        decision: ApprovalDecision,
+    },
+
+    /// A workflow run is waiting for someone to answer.
+    WorkflowInputRequested {
+        /// Run ID, for `workflow.reply`.
+        run_id: String,
+        /// Workflow ID.
+        workflow_id: String,
+        /// Node that is waiting.
+        node_id: String,
+        /// Question to answer.
+        prompt: String,
+        /// Suggested answers.
+        choices: Vec<String>,
+        /// When the wait times out, if ever.
+        expires_at: Option<DateTime<Utc>>,
+    },
+
+    /// A waiting workflow run was answered or timed out.
+    WorkflowInputResolved {
+        /// Run ID.
+        run_id: String,
+        /// Whether the wait timed out.
+        timed_out: bool,
+    },
+
     /// Channel status changed.
     ChannelStatusChanged {
         /// Channel ID.
@@ -71,6 +146,71 @@ pub enum UiEvent {
         error: Option<String>,
     },
 
+    /// A supervised channel started, stopped, failed, or was enabled or
+    /// disabled.
+    ChannelStateChanged {
+        /// Channel ID.
+        channel_id: String,
+        /// Current state.
+        state: ChannelRunState,
+        /// Whether the channel is enabled.
+        enabled: bool,
+        /// Most recent failure.
+        error: Option<String>,
+    },
+
+    /// The gateway finished booting.
+    GatewayStarted {
+        /// What was loaded and how long each phase took.
+        report: Box<StartupReport>,
+    },
+
+    /// The gateway is shutting down and draining agent runs.
+    GatewayStopping {
+        /// Agent runs still in progress.
+        active_runs: usize,
+        /// How long the gateway waits for them, in milliseconds.
+        drain_timeout_ms: u64,
+    },
+
+    /// Bytes of a file upload or download have been transferred.
+    TransferProgress {
+        /// Upload ID, or an ID for the download.
+        transfer_id: String,
+        /// Upload or download.
+        direction: TransferDirection,
+        /// File name.
+        name: String,
+        /// Bytes transferred so far.
+        transferred: u64,
+        /// File size in bytes.
+        total: u64,
+        /// Session the upload is for, if any.
+        session_key: Option<String>,
+    },
+
+    /// Configuration was changed on the running gateway.
+    ConfigChanged {
+        /// Changed section, e.g. `agents.default`.
+        section: String,
+        /// Fields that changed.
+        changes: Vec<ConfigChange>,
+        /// User who made the change.
+        changed_by: String,
+        /// Whether the change was written to the config file.
+        persisted: bool,
+    },
+
+    /// Too many failed logins locked a username or address out.
+    LoginLockedOut {
+        /// `user:<name>` or `ip:<address>`.
+        key: String,
+        /// Failed logins that led to the lockout.
+        failures: u32,
+        /// When the lockout ends.
+        locked_until: DateTime<Utc>,
+    },
+
     /// Heartbeat event.
     Heartbeat {
         /// Timestamp.
@@ -78,6 +218,81 @@ pub enum UiEvent {
     },
 }
 
+impl UiEvent {
+    /// Session this event is about, if any.
+    #[must_use]
+    pub fn session_key(&self) -> Option<&str> {
+        match self {
+            Self::SessionCreated { session_key, .. }
+            | Self::SessionUpdated { session_key, .. }
+            | Self::MessageReceived { session_key, .. }
+            | Self::MessageSent { session_key, .. }
+            | Self::ToolExecuted { session_key, .. }
+            | Self::ContentModerated { session_key, .. }
+            | Self::ReminderFailed { session_key, .. } => Some(session_key),
+            Self::PendingApproval { request } => request.session_key.as_deref(),
+            Self::TransferProgress { session_key, .. } => session_key.as_deref(),
+            Self::ApprovalResolved { .. }
+            | Self::WorkflowInputRequested { .. }
+            | Self::WorkflowInputResolved { .. }
+            | Self::ChannelStatusChanged { .. }
+            | Self::ChannelStateChanged { .. }
+            | Self::GatewayStarted { .. }
+            | Self::GatewayStopping { .. }
+            | Self::ConfigChanged { .. }
+            | Self::LoginLockedOut { .. }
+            | Self::Heartbeat { .. } => None,
+        }
+    }
+
+    /// Event type name, as serialized in the `type` field.
+    #[must_use]
+    pub const fn event_type(&self) -> &'static str {
+        match self {
+            Self::SessionCreated { .. } => "session_created",
+            Self::SessionUpdated { .. } => "session_updated",
+            Self::MessageReceived { .. } => "message_received",
+            Self::MessageSent { .. } => "message_sent",
+            Self::ToolExecuted { .. } => "tool_executed",
+            Self::ContentModerated { .. } => "content_moderated",
+            Self::ReminderFailed { .. } => "reminder_failed",
+            Self::PendingApproval { .. } => "pending_approval",
+            Self::ApprovalResolved { .. } => "approval_resolved",
+            Self::WorkflowInputRequested { .. } => "workflow_input_requested",
+            Self::WorkflowInputResolved { .. } => "workflow_input_resolved",
+            Self::ChannelStatusChanged { .. } => "channel_status_changed",
+            Self::ChannelStateChanged { .. } => "channel_state_changed",
+            Self::GatewayStarted { .. } => "gateway_started",
+            Self::GatewayStopping { .. } => "gateway_stopping",
+            Self::TransferProgress { .. } => "transfer_progress",
+            Self::ConfigChanged { .. } => "config_changed",
+            Self::LoginLockedOut { .. } => "login_locked_out",
+            Self::Heartbeat { .. } => "heartbeat",
+        }
+    }
+}
+
+/// Direction of a file transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferDirection {
+    /// From the client to the gateway.
+    Upload,
+    /// From the gateway to the client.
+    Download,
+}
+
+/// One field of a runtime config change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigChange {
+    /// Field name.
+    pub field: String,
+    /// Value before the change.
+    pub old: serde_json::Value,
+    /// Value after the change.
+    pub new: serde_json::Value,
+}
+
 /// Session update types.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -131,33 +346,98 @@ impl UiEventEnvelope {
 const DEFAULT_CHANNEL_CAPACITY: usize = 256;
 
 /// Event broadcaster for distributing UI events to subscribers.
+///
+/// The most recent events (as many as the channel capacity) are kept so
+/// reconnecting clients can resume with [`EventBroadcaster::events_since`].
 pub struct EventBroadcaster {
     sender: broadcast::Sender<UiEventEnvelope>,
+    hidden_sessions: Arc<RwLock<HashSet<String>>>,
+    history: Arc<Mutex<VecDeque<UiEventEnvelope>>>,
+    history_capacity: usize,
 }
 
 impl EventBroadcaster {
     /// Create a new event broadcaster.
     #[must_use]
     pub fn new() -> Self {
-        let (sender, _) = broadcast::channel(DEFAULT_CHANNEL_CAPACITY);
-        Self { sender }
+        Self::with_capacity(DEFAULT_CHANNEL_CAPACITY)
     }
 
     /// Create a new event broadcaster with custom capacity.
     #[must_use]
     pub fn with_capacity(capacity: usize) -> Self {
         let (sender, _) = broadcast::channel(capacity);
-        Self { sender }
+        Self {
+            sender,
+            hidden_sessions: Arc::new(RwLock::new(HashSet::new())),
+            history: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            history_capacity: capacity,
+        }
     }
 
     /// Broadcast an event to all subscribers.
     ///
+    /// Events for hidden (soft-deleted) sessions are dropped.
     /// Returns the number of subscribers that received the event.
     #[must_use]
     pub fn broadcast(&self, event: UiEvent) -> usize {
+        if let Some(session_key) = event.session_key() {
+            if self.is_hidden(session_key) {
+                return 0;
+            }
+        }
+
         let envelope = UiEventEnvelope::new(event);
+        // Record and send under one lock so a new subscriber sees each
+        // event in history, on its receiver, or both
+        let mut history = self
+            .history
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if history.len() == self.history_capacity {
+            history.pop_front();
+        }
+        history.push_back(envelope.clone());
         // Ignore send errors (no subscribers)
-        self.sender.send(envelope).unwrap_or(0)
+        let sent = self.sender.send(envelope).unwrap_or(0);
+        drop(history);
+        sent
+    }
+
+    /// Events broadcast after the one with `last_id`, oldest first.
+    ///
+    /// Returns `None` if that event is no longer in history, in which case
+    /// the caller has missed events and should reload its state.
+    #[must_use]
+    pub fn events_since(&self, last_id: &str) -> Option<Vec<UiEventEnvelope>> {
+        let history = self
+            .history
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let position = history.iter().position(|e| e.id == last_id)?;
+        Some(history.iter().skip(position + 1).cloned().collect())
+    }
+
+    /// Stop broadcasting events for a session.
+    pub fn hide_session(&self, session_key: &str) {
+        if let Ok(mut hidden) = self.hidden_sessions.write() {
+            hidden.insert(session_key.to_string());
+        }
+    }
+
+    /// Resume broadcasting events for a session.
+    pub fn unhide_session(&self, session_key: &str) {
+        if let Ok(mut hidden) = self.hidden_sessions.write() {
+            hidden.remove(session_key);
+        }
+    }
+
+    /// Whether events for a session are suppressed.
+    #[must_use]
+    pub fn is_hidden(&self, session_key: &str) -> bool {
+        self.hidden_sessions
+            .read()
+            .is_ok_and(|hidden| hidden.contains(session_key))
     }
 
     /// Subscribe to receive events.
@@ -183,6 +463,9 @@ impl Clone for EventBroadcaster {
     fn clone(&self) -> Self {
         Self {
             sender: self.sender.clone(),
+            hidden_sessions: Arc::clone(&self.hidden_sessions),
+            history: Arc::clone(&self.history),
+            history_capacity: self.history_capacity,
         }
     }
 }
@@ -231,4 +514,44 @@ mod tests {
         });
         assert_eq!(count, 0);
     }
+
+    #[test]
+    fn test_hidden_sessions() {
+        let broadcaster = EventBroadcaster::new();
+        let _rx = broadcaster.subscribe();
+        let event = || UiEvent::MessageSent {
+            session_key: "deleted".to_string(),
+            content: "hi".to_string(),
+        };
+
+        broadcaster.hide_session("deleted");
+        assert_eq!(broadcaster.broadcast(event()), 0);
+
+        broadcaster.unhide_session("deleted");
+        assert_eq!(broadcaster.broadcast(event()), 1);
+    }
+
+    #[test]
+    fn test_events_since() {
+        let broadcaster = EventBroadcaster::with_capacity(2);
+        let mut rx = broadcaster.subscribe();
+        let heartbeat = || UiEvent::Heartbeat {
+            timestamp: Utc::now(),
+        };
+
+        let _ = broadcaster.broadcast(heartbeat());
+        let first = rx.try_recv().unwrap().id;
+        let _ = broadcaster.broadcast(heartbeat());
+        let second = rx.try_recv().unwrap().id;
+
+        let missed = broadcaster.events_since(&first).unwrap();
+        assert_eq!(missed.len(), 1);
+        assert_eq!(missed[0].id, second);
+        assert!(broadcaster.events_since(&second).unwrap().is_empty());
+
+        // The first event falls out of history
+        let _ = broadcaster.broadcast(heartbeat());
+        assert!(broadcaster.events_since(&first).is_none());
+        assert!(broadcaster.events_since("unknown").is_none());
+    }
 }
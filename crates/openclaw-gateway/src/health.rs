@@ -0,0 +1,337 @@
+//! Liveness and readiness checks.
+//!
+//! `/healthz` answers as long as the process can serve HTTP. `/readyz` checks
+//! the gateway's dependencies (event store writes, free disk space, channel
+//! connectivity, and optionally provider reachability) and returns 503 when
+//! one the gateway cannot work without has failed.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use openclaw_providers::Provider;
+
+use crate::server::GatewayState;
+use crate::supervisor::ChannelRunState;
+
+/// How long a single provider probe may take.
+const PROVIDER_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Outcome of a check. Ordered from best to worst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    /// Working.
+    Ok,
+    /// Partly working; the gateway can still serve requests.
+    Degraded,
+    /// Not working; the gateway should not receive traffic.
+    Failed,
+}
+
+/// Result of one dependency check.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Check {
+    /// Check name, e.g. `event_store` or `provider:anthropic`.
+    pub name: String,
+    /// Outcome.
+    pub status: CheckStatus,
+    /// What was found, especially on failure.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// How long the check took.
+    pub duration_ms: u64,
+}
+
+impl Check {
+    /// Run `check` and time it.
+    async fn timed<F>(name: impl Into<String>, check: F) -> Self
+    where
+        F: std::future::Future<Output = (CheckStatus, Option<String>)>,
+    {
+        let started = Instant::now();
+        let (status, detail) = check.await;
+        Self {
+            name: name.into(),
+            status,
+            detail,
+            duration_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+        }
+    }
+}
+
+/// Body of `/readyz`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadinessReport {
+    /// Worst status of any check.
+    pub status: CheckStatus,
+    /// Whether the gateway should receive traffic (no check failed).
+    pub ready: bool,
+    /// Individual checks.
+    pub checks: Vec<Check>,
+    /// When the checks ran.
+    pub checked_at: DateTime<Utc>,
+}
+
+impl ReadinessReport {
+    /// Summarize a set of checks.
+    #[must_use]
+    pub fn from_checks(checks: Vec<Check>) -> Self {
+        let status = checks
+            .iter()
+            .map(|c| c.status)
+            .max()
+            .unwrap_or(CheckStatus::Ok);
+        Self {
+            status,
+            ready: status != CheckStatus::Failed,
+            checks,
+            checked_at: Utc::now(),
+        }
+    }
+}
+
+/// Recent provider probe results, so frequent readiness polls don't call
+/// provider APIs each time.
+#[derive(Debug, Default)]
+pub struct ProviderProbeCache {
+    results: Mutex<HashMap<String, (Instant, Check)>>,
+}
+
+impl ProviderProbeCache {
+    /// Create an empty cache.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Probe `provider` by listing its models, reusing a result younger
+    /// than `ttl`.
+    async fn probe(&self, provider: &Arc<dyn Provider>, ttl: Duration) -> Check {
+        let name = provider.name().to_string();
+        let cached = self
+            .results
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&name)
+            .filter(|(at, _)| at.elapsed() < ttl)
+            .map(|(_, check)| check.clone());
+        if let Some(check) = cached {
+            return check;
+        }
+
+        let check = Check::timed(format!("provider:{name}"), async {
+            match tokio::time::timeout(PROVIDER_PROBE_TIMEOUT, provider.list_models()).await {
+                Ok(Ok(_)) => (CheckStatus::Ok, None),
+                Ok(Err(e)) => (CheckStatus::Degraded, Some(e.to_string())),
+                Err(_) => (CheckStatus::Degraded, Some("Timed out".to_string())),
+            }
+        })
+        .await;
+        self.results
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(name, (Instant::now(), check.clone()));
+        check
+    }
+}
+
+/// Run every readiness check.
+pub async fn readiness(state: &Arc<RwLock<GatewayState>>) -> ReadinessReport {
+    let (shutdown, stores, supervisor, providers, probes, data_dir, config) = {
+        let state = state.read().await;
+        let mut providers: Vec<Arc<dyn Provider>> = Vec::new();
+        for agent in state.agents.values() {
+            if !providers
+                .iter()
+                .any(|p| p.name() == agent.provider().name())
+            {
+                providers.push(agent.provider().clone());
+            }
+        }
+        (
+            state.shutdown.clone(),
+            state.event_stores().cloned().collect::<Vec<_>>(),
+            state.supervisor.clone(),
+            providers,
+            state.provider_probes.clone(),
+            state.config.data_dir.clone(),
+            state.config.health.clone(),
+        )
+    };
+
+    let mut checks = vec![
+        Check::timed("shutdown", async {
+            if shutdown.is_draining() {
+                (CheckStatus::Failed, Some("Shutting down".to_string()))
+            } else {
+                (CheckStatus::Ok, None)
+            }
+        })
+        .await,
+        Check::timed("event_store", async {
+            let writable = tokio::task::spawn_blocking(move || {
+                stores.iter().try_for_each(|store| store.check_writable())
+            })
+            .await;
+            match writable {
+                Ok(Ok(())) => (CheckStatus::Ok, None),
+                Ok(Err(e)) => (CheckStatus::Failed, Some(e.to_string())),
+                Err(e) => (CheckStatus::Failed, Some(e.to_string())),
+            }
+        })
+        .await,
+        Check::timed("disk", async {
+            disk_status(&data_dir, config.min_free_disk_mb)
+        })
+        .await,
+        Check::timed("channels", async {
+            let health = supervisor.health();
+            let failing: Vec<String> = health
+                .iter()
+                .filter(|h| h.state == ChannelRunState::Backoff)
+                .map(|h| {
+                    h.last_error
+                        .as_ref()
+                        .map_or_else(|| h.id.clone(), |error| format!("{}: {error}", h.id))
+                })
+                .collect();
+            if failing.is_empty() {
+                (
+                    CheckStatus::Ok,
+                    Some(format!("{} channel(s)", health.len())),
+                )
+            } else {
+                (CheckStatus::Degraded, Some(failing.join("; ")))
+            }
+        })
+        .await,
+    ];
+
+    if config.probe_providers {
+        let ttl = Duration::from_secs(config.provider_probe_ttl_secs);
+        let provider_checks = providers.iter().map(|p| probes.probe(p, ttl));
+        checks.extend(futures::future::join_all(provider_checks).await);
+    }
+
+    ReadinessReport::from_checks(checks)
+}
+
+/// Check free space on the volume holding `data_dir`.
+fn disk_status(data_dir: &Path, min_free_mb: u64) -> (CheckStatus, Option<String>) {
+    // The data directory may not exist yet; measure the volume it will be on
+    let Some(existing) = data_dir.ancestors().find(|p| p.exists()) else {
+        return (
+            CheckStatus::Failed,
+            Some(format!("{} not found", data_dir.display())),
+        );
+    };
+    match fs2::available_space(existing) {
+        Ok(bytes) => {
+            let free_mb = bytes / (1024 * 1024);
+            let detail = Some(format!("{free_mb} MiB free"));
+            if free_mb < min_free_mb {
+                (CheckStatus::Failed, detail)
+            } else {
+                (CheckStatus::Ok, detail)
+            }
+        }
+        Err(e) => (CheckStatus::Degraded, Some(e.to_string())),
+    }
+}
+
+/// `GET /healthz`: the process is up and serving HTTP.
+pub(crate) async fn liveness_handler(
+    State(state): State<Arc<RwLock<GatewayState>>>,
+) -> impl IntoResponse {
+    let phase = state.read().await.shutdown.phase();
+    Json(serde_json::json!({
+        "status": "ok",
+        "version": env!("CARGO_PKG_VERSION"),
+        "phase": phase,
+    }))
+}
+
+/// `GET /readyz`: 200 when ready for traffic, 503 otherwise.
+pub(crate) async fn readiness_handler(
+    State(state): State<Arc<RwLock<GatewayState>>>,
+) -> impl IntoResponse {
+    let report = readiness(&state).await;
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{GatewayBuilder, GatewayConfig};
+    use openclaw_core::events::EventStore;
+
+    fn check(status: CheckStatus) -> Check {
+        Check {
+            name: "test".to_string(),
+            status,
+            detail: None,
+            duration_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_report_status() {
+        let report = ReadinessReport::from_checks(vec![]);
+        assert!(report.ready);
+        assert_eq!(report.status, CheckStatus::Ok);
+
+        let report = ReadinessReport::from_checks(vec![
+            check(CheckStatus::Ok),
+            check(CheckStatus::Degraded),
+        ]);
+        assert!(report.ready);
+        assert_eq!(report.status, CheckStatus::Degraded);
+
+        let report =
+            ReadinessReport::from_checks(vec![check(CheckStatus::Failed), check(CheckStatus::Ok)]);
+        assert!(!report.ready);
+        assert_eq!(report.status, CheckStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_readiness() {
+        let temp = tempfile::tempdir().unwrap();
+        let config = GatewayConfig {
+            data_dir: temp.path().join("gateway"),
+            ..GatewayConfig::default()
+        };
+        let gateway = GatewayBuilder::new()
+            .with_config(config)
+            .with_event_store(Arc::new(
+                EventStore::open(&temp.path().join("events")).unwrap(),
+            ))
+            .build()
+            .unwrap();
+        let state = gateway.state();
+
+        let report = readiness(state).await;
+        let names: Vec<&str> = report.checks.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["shutdown", "event_store", "disk", "channels"]);
+        assert_eq!(report.checks[1].status, CheckStatus::Ok);
+
+        state.read().await.shutdown.request();
+        let report = readiness(state).await;
+        assert!(!report.ready);
+        assert_eq!(report.checks[0].status, CheckStatus::Failed);
+    }
+}
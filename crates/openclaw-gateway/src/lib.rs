@@ -5,24 +5,93 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
+mod after_hours;
+mod approvals;
+mod archive;
+mod attachment_policy;
 /// Authentication and authorization.
 pub mod auth;
+mod backup;
+mod broadcast;
+/// JSON-RPC method catalog and `OpenRPC` discovery.
+pub mod catalog;
+/// Distributed mode over a shared message bus.
+pub mod cluster;
+/// TypeScript client types generated from the RPC catalog and UI events.
+pub mod codegen;
+mod control;
 /// WebSocket UI events.
 pub mod events;
+/// Liveness and readiness checks.
+pub mod health;
+mod lifecycle;
+mod limits;
+/// Rotating gateway log files.
+pub mod log_file;
+/// Runtime log filter adjustment, secret scrubbing, and output formats.
+pub mod logging;
+mod mcp;
 mod middleware;
+mod mock;
+mod privacy;
+mod proxy;
+mod purge;
+mod react;
+mod reminders;
 /// JSON-RPC protocol types and constants.
 pub mod rpc;
+mod schedule;
 mod server;
+/// Coordinated graceful shutdown.
+pub mod shutdown;
+mod sse;
+mod sso;
+/// Startup report and boot-time budget.
+pub mod startup;
+/// Channel supervision and restart backoff.
+pub mod supervisor;
+mod sweep;
+/// Multi-tenant namespaces.
+pub mod tenancy;
+/// Per-route and per-method request timeouts.
+pub mod timeouts;
+mod transcript;
+mod transfer;
+mod triggers;
+#[cfg(unix)]
+mod unix_socket;
+mod whatsapp;
+mod workflow_input;
+mod workspace;
 
 /// UI static file server (requires "ui" feature).
 #[cfg(feature = "ui")]
 pub mod ui_server;
 
 pub use auth::{AuthConfig, AuthError, AuthState, User, UserRole, UserStore};
-pub use events::{EventBroadcaster, UiEvent, UiEventEnvelope};
-pub use middleware::GatewayRateLimiter;
+pub use backup::BackupSchedule;
+pub use cluster::{BusConfig, Cluster, ClusterConfig, ClusterRole, MemoryBus, MessageBus};
+pub use events::{ConfigChange, EventBroadcaster, TransferDirection, UiEvent, UiEventEnvelope};
+pub use health::{Check, CheckStatus, ReadinessReport};
+pub use limits::{LimitCounters, LimitStats};
+pub use log_file::{RollingLogFile, active_log_file};
+pub use logging::{
+    LogFilter, LogFilterError, ScrubbingFields, ScrubbingJson, json_scrubbing_layer, output_layer,
+    scrubbing_layer,
+};
+pub use middleware::{GatewayRateLimiter, RateLimitCaller, RpcRateLimitStats};
+pub use react::ReactTool;
 pub use rpc::{RpcError, RpcRequest, RpcResponse};
 pub use server::{Gateway, GatewayBuilder, GatewayConfig, GatewayState};
+pub use shutdown::{Shutdown, ShutdownPhase};
+pub use startup::StartupReport;
+pub use supervisor::{
+    ChannelHealth, ChannelRunState, ChannelSupervisor, MessageGuard, SupervisorConfig,
+};
+pub use tenancy::{NamespaceAccess, NamespaceConfig, Tenants};
+pub use timeouts::RequestTimeouts;
+pub use transfer::{TransferError, UploadMeta, UploadResolver, UploadStatus, UploadStore};
+pub use triggers::TriggerDispatcher;
 
 #[cfg(feature = "ui")]
 pub use ui_server::UiServerConfig;
@@ -0,0 +1,174 @@
+//! Ending sessions after a period of inactivity.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::sync::RwLock;
+
+use openclaw_agents::runtime::AgentContext;
+use openclaw_channels::OutboundContext;
+use openclaw_core::events::{SessionEvent, SessionEventKind, SessionProjection};
+use openclaw_core::types::AgentId;
+
+use crate::events::UiEvent;
+use crate::server::GatewayState;
+
+/// How often sessions are checked for inactivity.
+pub const IDLE_SESSION_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Reason recorded on sessions ended for inactivity.
+pub const IDLE_END_REASON: &str = "idle_timeout";
+
+/// Instruction given to the agent when it summarizes a closing session.
+const SUMMARY_PROMPT: &str = "This conversation is being closed because it has been inactive. \
+     Summarize it for the user in two or three sentences, including anything left to follow up on.";
+
+/// End idle sessions past the configured TTL, forever.
+pub async fn run_idle_session_closer(state: Arc<RwLock<GatewayState>>) {
+    let mut interval = tokio::time::interval(IDLE_SESSION_CHECK_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+        close_idle(&state).await;
+    }
+}
+
+/// End every active session inactive for longer than the idle TTL,
+/// sending the goodbye message first if one is configured.
+///
+/// Returns the number of sessions ended. Does nothing if no TTL is set.
+pub async fn close_idle(state: &Arc<RwLock<GatewayState>>) -> usize {
+    let state = state.read().await;
+    let Some(minutes) = state.config.idle_sessions.ttl_minutes else {
+        return 0;
+    };
+    let cutoff = Utc::now() - chrono::Duration::minutes(i64::from(minutes));
+
+    let mut idle = Vec::new();
+    for store in state.event_stores() {
+        match store.idle_sessions(cutoff) {
+            Ok(sessions) => idle.extend(sessions),
+            Err(e) => tracing::warn!("Failed to find idle sessions: {}", e),
+        }
+    }
+
+    let mut ended = 0;
+    for projection in idle {
+        if let Some(text) = goodbye(&state, &projection).await {
+            send_goodbye(&state, &projection, &text).await;
+        }
+
+        let event = SessionEvent::new(
+            projection.session_key.clone(),
+            projection.agent_id.clone(),
+            SessionEventKind::SessionEnded {
+                reason: IDLE_END_REASON.to_string(),
+            },
+        );
+        match state
+            .store_for_session(&projection.session_key)
+            .append(&event)
+        {
+            Ok(_) => ended += 1,
+            Err(e) => tracing::warn!(
+                "Failed to end idle session {}: {}",
+                projection.session_key,
+                e
+            ),
+        }
+    }
+
+    if ended > 0 {
+        tracing::info!("Ended {} idle session(s)", ended);
+    }
+    ended
+}
+
+/// The goodbye message and summary configured for a closing session.
+async fn goodbye(state: &GatewayState, projection: &SessionProjection) -> Option<String> {
+    let config = &state.config.idle_sessions;
+    let summary = if config.summarize && !projection.context().is_empty() {
+        summarize(state, projection).await
+    } else {
+        None
+    };
+    match (config.goodbye_message.clone(), summary) {
+        (Some(goodbye), Some(summary)) => Some(format!("{goodbye}\n\n{summary}")),
+        (goodbye, summary) => goodbye.or(summary),
+    }
+}
+
+/// Ask the session's agent to summarize the conversation.
+async fn summarize(state: &GatewayState, projection: &SessionProjection) -> Option<String> {
+    let agent = state.agents.get(&projection.agent_id)?;
+    let mut ctx = AgentContext::new(
+        AgentId::new(&projection.agent_id),
+        projection.session_key.clone(),
+        projection.clone(),
+        state.tool_registry.clone(),
+    );
+    match agent.process(&mut ctx, SUMMARY_PROMPT).await {
+        Ok(reply) if !reply.content.trim().is_empty() => Some(reply.content),
+        Ok(_) => None,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to summarize session {}: {}",
+                projection.session_key,
+                e
+            );
+            None
+        }
+    }
+}
+
+async fn send_goodbye(state: &GatewayState, projection: &SessionProjection, text: &str) {
+    let channel = state
+        .channels
+        .read()
+        .await
+        .get_outbound(projection.channel.as_ref())
+        .cloned();
+
+    let message_id = if let Some(channel) = channel {
+        let ctx = OutboundContext {
+            chat_id: projection.peer_id.clone(),
+            reply_to: None,
+            thread_id: None,
+        };
+        match state.outbound.send(channel.as_ref(), ctx, text).await {
+            Ok(result) => result.message_id,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to send goodbye for session {}: {}",
+                    projection.session_key,
+                    e
+                );
+                return;
+            }
+        }
+    } else {
+        // API and web sessions have no adapter; push to connected UIs instead
+        let _ = state.events.broadcast(UiEvent::MessageSent {
+            session_key: projection.session_key.to_string(),
+            content: text.to_string(),
+        });
+        IDLE_END_REASON.to_string()
+    };
+
+    let sent = SessionEvent::new(
+        projection.session_key.clone(),
+        projection.agent_id.clone(),
+        SessionEventKind::MessageSent {
+            content: text.to_string(),
+            message_id,
+        },
+    );
+    if let Err(e) = state
+        .store_for_session(&projection.session_key)
+        .append(&sent)
+    {
+        tracing::warn!("Failed to log goodbye message: {}", e);
+    }
+}
@@ -0,0 +1,247 @@
+//! Request size and slow-client protections.
+//!
+//! HTTP request bodies are capped in size and must keep arriving: a body
+//! declared larger than the limit is refused with `413` before it is read,
+//! one that turns out larger fails the same way when buffered, and one that
+//! stalls between chunks is answered with `408`. WebSocket messages above
+//! the limit close the connection with code 1009, and clients that stop
+//! reading are disconnected instead of holding sends and buffers open.
+//! Counters for every refusal are reported by `system.health`.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+use axum::{
+    Json,
+    body::Body,
+    extract::{Request, State},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures::StreamExt;
+use serde::Serialize;
+
+use openclaw_core::config::RequestLimitsConfig;
+
+use crate::rpc::{self, RpcResponse};
+
+/// Counts of requests and connections refused by the limits.
+#[derive(Debug, Default)]
+pub struct LimitCounters {
+    bodies_rejected: AtomicU64,
+    body_timeouts: AtomicU64,
+    ws_messages_rejected: AtomicU64,
+    ws_slow_clients: AtomicU64,
+    ws_events_dropped: AtomicU64,
+}
+
+impl LimitCounters {
+    /// Count a request refused with `413`.
+    pub fn body_rejected(&self) {
+        self.bodies_rejected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a request body that stalled.
+    pub fn body_timed_out(&self) {
+        self.body_timeouts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a WebSocket closed for an oversized message.
+    pub fn ws_message_rejected(&self) {
+        self.ws_messages_rejected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a WebSocket closed because its client stopped reading.
+    pub fn ws_slow_client(&self) {
+        self.ws_slow_clients.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count events a lagging WebSocket client missed.
+    pub fn ws_events_dropped(&self, count: u64) {
+        self.ws_events_dropped.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Current counts.
+    #[must_use]
+    pub fn snapshot(&self) -> LimitStats {
+        LimitStats {
+            bodies_rejected: self.bodies_rejected.load(Ordering::Relaxed),
+            body_timeouts: self.body_timeouts.load(Ordering::Relaxed),
+            ws_messages_rejected: self.ws_messages_rejected.load(Ordering::Relaxed),
+            ws_slow_clients: self.ws_slow_clients.load(Ordering::Relaxed),
+            ws_events_dropped: self.ws_events_dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Counts since the gateway started.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct LimitStats {
+    /// Requests refused with `413`.
+    pub bodies_rejected: u64,
+    /// Requests answered with `408` after their body stalled.
+    pub body_timeouts: u64,
+    /// `WebSockets` closed with 1009 for an oversized message.
+    pub ws_messages_rejected: u64,
+    /// `WebSockets` closed because the client stopped reading.
+    pub ws_slow_clients: u64,
+    /// Events skipped for lagging WebSocket clients.
+    pub ws_events_dropped: u64,
+}
+
+/// Body limits applied to a group of routes.
+#[derive(Debug, Clone)]
+pub struct BodyGuard {
+    max_bytes: u64,
+    read_timeout: Duration,
+    counters: Arc<LimitCounters>,
+}
+
+impl BodyGuard {
+    pub const fn new(config: &RequestLimitsConfig, counters: Arc<LimitCounters>) -> Self {
+        Self {
+            max_bytes: config.max_body_bytes,
+            read_timeout: Duration::from_secs(config.body_read_timeout_secs),
+            counters,
+        }
+    }
+
+    /// The same guard with a different size limit.
+    pub const fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Size limit for `axum`'s body extractors.
+    pub fn max_bytes(&self) -> usize {
+        usize::try_from(self.max_bytes).unwrap_or(usize::MAX)
+    }
+}
+
+/// Refuse oversized bodies up front and time out stalled ones.
+pub async fn guard_body(State(guard): State<BodyGuard>, request: Request, next: Next) -> Response {
+    let declared = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if declared.is_some_and(|len| len > guard.max_bytes) {
+        guard.counters.body_rejected();
+        return limit_response(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("Request body exceeds {} bytes", guard.max_bytes),
+        );
+    }
+
+    let timed_out = Arc::new(AtomicBool::new(false));
+    let (parts, body) = request.into_parts();
+    let body = with_read_timeout(body, guard.read_timeout, timed_out.clone());
+    let response = next.run(Request::from_parts(parts, body)).await;
+
+    if timed_out.load(Ordering::Relaxed) {
+        guard.counters.body_timed_out();
+        return limit_response(
+            StatusCode::REQUEST_TIMEOUT,
+            format!("Request body stalled for {}s", guard.read_timeout.as_secs()),
+        );
+    }
+    // Bodies without a length are cut off by the extractors' limit
+    if response.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        guard.counters.body_rejected();
+    }
+    response
+}
+
+/// A body that fails if no chunk arrives within `timeout`.
+fn with_read_timeout(body: Body, timeout: Duration, timed_out: Arc<AtomicBool>) -> Body {
+    let chunks = futures::stream::unfold(Some(body.into_data_stream()), move |stream| {
+        let timed_out = timed_out.clone();
+        async move {
+            let mut stream = stream?;
+            match tokio::time::timeout(timeout, stream.next()).await {
+                Ok(Some(chunk)) => Some((chunk, Some(stream))),
+                Ok(None) => None,
+                Err(_) => {
+                    timed_out.store(true, Ordering::Relaxed);
+                    let error =
+                        std::io::Error::new(std::io::ErrorKind::TimedOut, "request body stalled");
+                    Some((Err(axum::Error::new(error)), None))
+                }
+            }
+        }
+    });
+    Body::from_stream(chunks)
+}
+
+fn limit_response(status: StatusCode, message: String) -> Response {
+    (
+        status,
+        Json(RpcResponse::error(None, rpc::INVALID_REQUEST, message)),
+    )
+        .into_response()
+}
+
+/// Whether a WebSocket receive failed because a message was over the size
+/// limit. axum does not expose the underlying error type, so this goes by
+/// its message.
+pub fn is_message_too_long(error: &axum::Error) -> bool {
+    error.to_string().contains("Message too long")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, routing::post};
+    use tower::ServiceExt;
+
+    fn app(max_bytes: u64, counters: Arc<LimitCounters>) -> Router {
+        let guard = BodyGuard {
+            max_bytes,
+            read_timeout: Duration::from_millis(50),
+            counters,
+        };
+        Router::new()
+            .route("/", post(|body: String| async move { body }))
+            .layer(axum::extract::DefaultBodyLimit::max(guard.max_bytes()))
+            .layer(axum::middleware::from_fn_with_state(guard, guard_body))
+    }
+
+    #[tokio::test]
+    async fn test_body_limits() {
+        let counters = Arc::new(LimitCounters::default());
+
+        let response = app(8, counters.clone())
+            .oneshot(Request::post("/").body(Body::from("hello")).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let declared = Request::post("/")
+            .header(header::CONTENT_LENGTH, 9)
+            .body(Body::from("too long!"))
+            .unwrap();
+        let response = app(8, counters.clone()).oneshot(declared).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        // No declared length: caught while buffering
+        let chunks = futures::stream::iter([Ok::<_, std::io::Error>("too "), Ok("long!")]);
+        let response = app(8, counters.clone())
+            .oneshot(Request::post("/").body(Body::from_stream(chunks)).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let stalled = futures::stream::pending::<Result<&str, std::io::Error>>();
+        let response = app(8, counters.clone())
+            .oneshot(Request::post("/").body(Body::from_stream(stalled)).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::REQUEST_TIMEOUT);
+
+        let stats = counters.snapshot();
+        assert_eq!(stats.bodies_rejected, 2);
+        assert_eq!(stats.body_timeouts, 1);
+    }
+}
@@ -0,0 +1,246 @@
+//! Rotating gateway log files.
+//!
+//! Logs go to `gateway.<date>.log`, with a new file each (UTC) day and
+//! `gateway.<date>.<n>.log` once a day's file reaches its size limit.
+//! Files older than the retention period are deleted when a new day's file
+//! is opened.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{Days, NaiveDate, Utc};
+use openclaw_core::config::LogFileConfig;
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Prefix of every log file name.
+const PREFIX: &str = "gateway.";
+
+/// Extension of every log file name.
+const EXTENSION: &str = ".log";
+
+/// Log file writer that rotates daily and by size.
+#[derive(Debug)]
+pub struct RollingLogFile {
+    dir: PathBuf,
+    max_file_bytes: u64,
+    retention_days: u32,
+    active: Mutex<Option<ActiveFile>>,
+}
+
+#[derive(Debug)]
+struct ActiveFile {
+    date: NaiveDate,
+    index: u32,
+    file: File,
+    size: u64,
+}
+
+impl RollingLogFile {
+    /// Write log files to `dir` with default limits.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let defaults = LogFileConfig::default();
+        Self {
+            dir: dir.into(),
+            max_file_bytes: defaults.max_file_bytes,
+            retention_days: defaults.retention_days,
+            active: Mutex::new(None),
+        }
+    }
+
+    /// Create from config.
+    #[must_use]
+    pub fn from_config(config: &LogFileConfig) -> Self {
+        Self::new(config.dir())
+            .with_max_file_bytes(config.max_file_bytes)
+            .with_retention_days(config.retention_days)
+    }
+
+    /// Set the size at which a new file is started.
+    #[must_use]
+    pub const fn with_max_file_bytes(mut self, max: u64) -> Self {
+        self.max_file_bytes = max;
+        self
+    }
+
+    /// Set the days of files kept.
+    #[must_use]
+    pub const fn with_retention_days(mut self, days: u32) -> Self {
+        self.retention_days = days;
+        self
+    }
+
+    /// Log directory.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn write_line(&self, buf: &[u8]) -> io::Result<()> {
+        let mut guard = self
+            .active
+            .lock()
+            .map_err(|_| io::Error::other("log file lock poisoned"))?;
+
+        let today = Utc::now().date_naive();
+        let len = buf.len() as u64;
+        let active = match guard.take() {
+            Some(active) if active.date != today => {
+                self.prune(today);
+                self.open(today, None)?
+            }
+            Some(active) if active.size > 0 && active.size + len > self.max_file_bytes => {
+                self.open(today, Some(active.index + 1))?
+            }
+            Some(active) => active,
+            None => {
+                self.prune(today);
+                self.open(today, None)?
+            }
+        };
+
+        let active = guard.insert(active);
+        active.file.write_all(buf)?;
+        active.size += len;
+        drop(guard);
+        Ok(())
+    }
+
+    /// Open the file for `date`, continuing the day's latest file unless
+    /// `index` is given.
+    fn open(&self, date: NaiveDate, index: Option<u32>) -> io::Result<ActiveFile> {
+        std::fs::create_dir_all(&self.dir)?;
+        let index = index.unwrap_or_else(|| {
+            log_files(&self.dir)
+                .into_iter()
+                .filter(|(d, ..)| *d == date)
+                .map(|(_, i, _)| i)
+                .max()
+                .unwrap_or(0)
+        });
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(file_name(date, index)))?;
+        let size = file.metadata()?.len();
+        Ok(ActiveFile {
+            date,
+            index,
+            file,
+            size,
+        })
+    }
+
+    /// Delete files from before the retention period.
+    fn prune(&self, today: NaiveDate) {
+        let Some(cutoff) = today.checked_sub_days(Days::new(u64::from(self.retention_days))) else {
+            return;
+        };
+        for (date, _, path) in log_files(&self.dir) {
+            if date < cutoff {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+}
+
+impl Write for &RollingLogFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_line(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for RollingLogFile {
+    type Writer = &'a Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self
+    }
+}
+
+/// The file currently being written in `dir`, if any.
+#[must_use]
+pub fn active_log_file(dir: &Path) -> Option<PathBuf> {
+    log_files(dir)
+        .into_iter()
+        .max_by_key(|(date, index, _)| (*date, *index))
+        .map(|(.., path)| path)
+}
+
+fn file_name(date: NaiveDate, index: u32) -> String {
+    if index == 0 {
+        format!("{PREFIX}{date}{EXTENSION}")
+    } else {
+        format!("{PREFIX}{date}.{index}{EXTENSION}")
+    }
+}
+
+/// Date, index, and path of every log file in `dir`.
+fn log_files(dir: &Path) -> Vec<(NaiveDate, u32, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let stem = name.strip_prefix(PREFIX)?.strip_suffix(EXTENSION)?;
+            let (date, index) = match stem.split_once('.') {
+                Some((date, index)) => (date, index.parse().ok()?),
+                None => (stem, 0),
+            };
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+            Some((date, index, entry.path()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_and_retention() {
+        let temp = tempfile::tempdir().unwrap();
+        let today = Utc::now().date_naive();
+        let stale = today.checked_sub_days(Days::new(30)).unwrap();
+        std::fs::write(temp.path().join(file_name(stale, 0)), "old\n").unwrap();
+        std::fs::write(temp.path().join("notes.txt"), "keep\n").unwrap();
+
+        let log = RollingLogFile::new(temp.path())
+            .with_max_file_bytes(100)
+            .with_retention_days(7);
+        for _ in 0..8 {
+            (&log).write_all(&[b'x'; 39]).unwrap();
+            (&log).write_all(b"\n").unwrap();
+        }
+
+        assert!(!temp.path().join(file_name(stale, 0)).exists());
+        assert!(temp.path().join("notes.txt").exists());
+        assert!(temp.path().join(file_name(today, 0)).exists());
+        assert_eq!(
+            active_log_file(temp.path()).unwrap(),
+            temp.path().join(file_name(today, 3))
+        );
+        for index in 0..=3 {
+            let size = std::fs::metadata(temp.path().join(file_name(today, index)))
+                .unwrap()
+                .len();
+            assert!(size <= 100);
+        }
+
+        // A restart continues the day's latest file
+        drop(log);
+        let log = RollingLogFile::new(temp.path()).with_max_file_bytes(100);
+        (&log).write_all(b"again\n").unwrap();
+        let latest = std::fs::read_to_string(temp.path().join(file_name(today, 3))).unwrap();
+        assert!(latest.ends_with("again\n"));
+    }
+}
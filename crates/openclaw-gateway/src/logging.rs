@@ -0,0 +1,431 @@
+//! Runtime log filter adjustment, secret scrubbing, and output formats.
+//!
+//! The host installs a reloadable `EnvFilter` layer and hands the gateway a
+//! [`LogFilter`], which the `admin.log_level.*` RPCs use to change filter
+//! directives without a restart. Output goes through [`output_layer`], in
+//! text or JSON, so credentials that end up in log fields are redacted
+//! before writing.
+
+use std::fmt;
+use std::sync::Arc;
+
+use openclaw_core::config::LogFormat;
+use openclaw_core::secrets::{COMMON_SECRET_PATTERNS, scrub_secrets};
+use serde_json::Value;
+use tracing::level_filters::LevelFilter;
+use tracing::{Event, Subscriber};
+use tracing_subscriber::field::RecordFields;
+use tracing_subscriber::fmt::format::{DefaultFields, Format, Json, JsonFields, Writer};
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, MakeWriter};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{EnvFilter, Layer, Registry, reload};
+
+/// Reloadable filter layer installed directly on the registry.
+pub type LogFilterLayer = reload::Layer<EnvFilter, Registry>;
+
+/// Log filter errors.
+#[derive(Debug, thiserror::Error)]
+pub enum LogFilterError {
+    /// Level, module, or directive could not be parsed.
+    #[error("Invalid log directive: {0}")]
+    Invalid(String),
+
+    /// The subscriber holding the filter is gone.
+    #[error("Failed to reload log filter: {0}")]
+    Reload(#[from] reload::Error),
+}
+
+/// Handle for reading and replacing the active log filter.
+#[derive(Clone)]
+pub struct LogFilter {
+    handle: reload::Handle<EnvFilter, Registry>,
+    initial: Arc<str>,
+}
+
+impl LogFilter {
+    /// Create a reloadable filter layer and a handle to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Invalid` if `directives` cannot be parsed.
+    pub fn layer(directives: &str) -> Result<(LogFilterLayer, Self), LogFilterError> {
+        let filter = parse(directives)?;
+        let (layer, handle) = reload::Layer::new(filter);
+        Ok((
+            layer,
+            Self {
+                handle,
+                initial: directives.into(),
+            },
+        ))
+    }
+
+    /// Directives the filter was created with.
+    #[must_use]
+    pub fn initial(&self) -> &str {
+        &self.initial
+    }
+
+    /// Active filter directives.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Reload` if the subscriber has been dropped.
+    pub fn current(&self) -> Result<String, LogFilterError> {
+        Ok(self.handle.with_current(ToString::to_string)?)
+    }
+
+    /// Set the level for one module, or the default level if `module` is
+    /// `None`, keeping all other directives. Returns the new directives.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Invalid` for an unknown level or malformed module path, or
+    /// `Reload` if the subscriber has been dropped.
+    pub fn set_level(&self, level: &str, module: Option<&str>) -> Result<String, LogFilterError> {
+        let level: LevelFilter = level
+            .parse()
+            .map_err(|_| LogFilterError::Invalid(format!("unknown level '{level}'")))?;
+        if let Some(module) = module {
+            validate_module(module)?;
+        }
+
+        let directives = with_directive(&self.current()?, module, level);
+        self.set_directives(&directives)
+    }
+
+    /// Replace the filter with `directives` (`EnvFilter` syntax). Returns the
+    /// new directives.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Invalid` if `directives` cannot be parsed, or `Reload` if the
+    /// subscriber has been dropped.
+    pub fn set_directives(&self, directives: &str) -> Result<String, LogFilterError> {
+        self.handle.reload(parse(directives)?)?;
+        tracing::info!("Log filter set to '{}'", directives);
+        self.current()
+    }
+
+    /// Restore the directives the filter was created with.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Reload` if the subscriber has been dropped.
+    pub fn reset(&self) -> Result<String, LogFilterError> {
+        self.set_directives(&self.initial)
+    }
+}
+
+impl fmt::Debug for LogFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogFilter")
+            .field("initial", &self.initial)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Formats event and span fields with secrets redacted.
+#[derive(Debug, Clone)]
+pub struct ScrubbingFields {
+    patterns: Arc<[String]>,
+}
+
+impl ScrubbingFields {
+    /// Redact values after [`COMMON_SECRET_PATTERNS`] and `extra`.
+    #[must_use]
+    pub fn new(extra: &[String]) -> Self {
+        Self {
+            patterns: COMMON_SECRET_PATTERNS
+                .iter()
+                .map(ToString::to_string)
+                .chain(extra.iter().cloned())
+                .collect(),
+        }
+    }
+}
+
+impl<'writer> FormatFields<'writer> for ScrubbingFields {
+    fn format_fields<R: RecordFields>(
+        &self,
+        mut writer: Writer<'writer>,
+        fields: R,
+    ) -> fmt::Result {
+        let mut formatted = String::new();
+        DefaultFields::new().format_fields(Writer::new(&mut formatted), fields)?;
+        fmt::Write::write_str(&mut writer, &self.scrub(&formatted))
+    }
+}
+
+impl ScrubbingFields {
+    fn scrub(&self, text: &str) -> String {
+        let patterns: Vec<&str> = self.patterns.iter().map(String::as_str).collect();
+        scrub_secrets(text, &patterns)
+    }
+
+    /// Scrub every string in `value`, and redact fields named like a
+    /// pattern outright.
+    fn scrub_value(&self, value: &mut Value) {
+        match value {
+            Value::String(text) => *text = self.scrub(text),
+            Value::Array(items) => {
+                for item in items {
+                    self.scrub_value(item);
+                }
+            }
+            Value::Object(entries) => {
+                for (key, item) in entries.iter_mut() {
+                    if self.is_secret_key(key) {
+                        *item = Value::String("[REDACTED]".to_string());
+                    } else {
+                        self.scrub_value(item);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether `key` names a pattern, e.g. `token` for `"token="`.
+    fn is_secret_key(&self, key: &str) -> bool {
+        self.patterns.iter().any(|pattern| {
+            let name = pattern.trim_end_matches(['=', ':', ' ']);
+            !name.is_empty() && name.eq_ignore_ascii_case(key)
+        })
+    }
+}
+
+/// Formats events as JSON lines with secrets redacted from every string,
+/// and from fields named like a pattern (`token`, `api_key`, ...).
+#[derive(Debug, Clone)]
+pub struct ScrubbingJson {
+    fields: ScrubbingFields,
+    inner: Format<Json>,
+}
+
+impl ScrubbingJson {
+    /// Redact values after [`COMMON_SECRET_PATTERNS`] and `extra`.
+    #[must_use]
+    pub fn new(extra: &[String]) -> Self {
+        Self {
+            fields: ScrubbingFields::new(extra),
+            inner: tracing_subscriber::fmt::format().json(),
+        }
+    }
+}
+
+impl<S, N> FormatEvent<S, N> for ScrubbingJson
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'writer> FormatFields<'writer> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let mut line = String::new();
+        self.inner
+            .format_event(ctx, Writer::new(&mut line), event)?;
+        let mut scrubbed = serde_json::from_str::<Value>(&line).map_or_else(
+            |_| self.fields.scrub(line.trim_end()),
+            |mut value| {
+                self.fields.scrub_value(&mut value);
+                value.to_string()
+            },
+        );
+        scrubbed.push('\n');
+        fmt::Write::write_str(&mut writer, &scrubbed)
+    }
+}
+
+/// Formatting layer that redacts secrets from every event and span field,
+/// using [`COMMON_SECRET_PATTERNS`] plus `extra` patterns.
+#[must_use]
+pub fn scrubbing_layer<S>(extra: &[String]) -> tracing_subscriber::fmt::Layer<S, ScrubbingFields>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    tracing_subscriber::fmt::layer().fmt_fields(ScrubbingFields::new(extra))
+}
+
+/// JSON formatting layer that redacts secrets, one object per line.
+#[must_use]
+pub fn json_scrubbing_layer<S>(
+    extra: &[String],
+) -> tracing_subscriber::fmt::Layer<S, JsonFields, ScrubbingJson>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    tracing_subscriber::fmt::layer()
+        .json()
+        .event_format(ScrubbingJson::new(extra))
+}
+
+/// Scrubbing output layer in `format`, writing to `writer`. ANSI colors are
+/// only used for text output when `ansi` is set.
+pub fn output_layer<S, W>(
+    format: &LogFormat,
+    extra: &[String],
+    writer: W,
+    ansi: bool,
+) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + 'static,
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    match format {
+        LogFormat::Pretty => scrubbing_layer(extra)
+            .with_target(false)
+            .with_ansi(ansi)
+            .with_writer(writer)
+            .boxed(),
+        LogFormat::Json => json_scrubbing_layer(extra).with_writer(writer).boxed(),
+    }
+}
+
+fn parse(directives: &str) -> Result<EnvFilter, LogFilterError> {
+    EnvFilter::try_new(directives).map_err(|e| LogFilterError::Invalid(e.to_string()))
+}
+
+fn validate_module(module: &str) -> Result<(), LogFilterError> {
+    let valid = !module.is_empty()
+        && module
+            .split("::")
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(LogFilterError::Invalid(format!(
+            "invalid module path '{module}'"
+        )))
+    }
+}
+
+/// Replace the directive for `module` (or the default level) in `current`.
+fn with_directive(current: &str, module: Option<&str>, level: LevelFilter) -> String {
+    let level = level.to_string().to_lowercase();
+    let mut directives: Vec<String> = current
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .filter(|d| match (module, d.split_once('=')) {
+            (Some(module), Some((target, _))) => target != module,
+            (Some(_), None) => true,
+            (None, target) => target.is_some(),
+        })
+        .map(String::from)
+        .collect();
+
+    match module {
+        Some(module) => directives.push(format!("{module}={level}")),
+        None => directives.insert(0, level),
+    }
+    directives.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::prelude::*;
+
+    #[test]
+    fn test_with_directive() {
+        let current = "info,openclaw_channels::telegram=warn,hyper=error";
+
+        assert_eq!(
+            with_directive(
+                current,
+                Some("openclaw_channels::telegram"),
+                LevelFilter::DEBUG
+            ),
+            "info,hyper=error,openclaw_channels::telegram=debug"
+        );
+        assert_eq!(
+            with_directive(current, None, LevelFilter::TRACE),
+            "trace,openclaw_channels::telegram=warn,hyper=error"
+        );
+        assert_eq!(
+            with_directive("", Some("sled"), LevelFilter::OFF),
+            "sled=off"
+        );
+    }
+
+    #[derive(Clone)]
+    struct SharedBuffer(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_scrubbing_layer() {
+        let buffer = SharedBuffer(Arc::default());
+        let writer = buffer.clone();
+        let subscriber = Registry::default().with(
+            scrubbing_layer(&["session_secret=".to_string()])
+                .with_ansi(false)
+                .with_writer(move || writer.clone()),
+        );
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("call", api_key = "sk-span");
+            let _guard = span.enter();
+            tracing::info!(token = "abc123", "sending session_secret=s3cr3t");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("session_secret=[REDACTED]"));
+        assert!(output.contains("token=\"[REDACTED]\""));
+        for secret in ["abc123", "s3cr3t", "sk-span"] {
+            assert!(!output.contains(secret), "{secret} leaked: {output}");
+        }
+    }
+
+    #[test]
+    fn test_json_scrubbing_layer() {
+        let buffer = SharedBuffer(Arc::default());
+        let writer = buffer.clone();
+        let subscriber = Registry::default().with(output_layer(
+            &LogFormat::Json,
+            &[],
+            move || writer.clone(),
+            false,
+        ));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(token = "abc123", count = 3, "sending api_key=sk-1");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let line: Value = serde_json::from_str(output.trim_end()).unwrap();
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["fields"]["token"], "[REDACTED]");
+        assert_eq!(line["fields"]["count"], 3);
+        assert_eq!(line["fields"]["message"], "sending api_key=[REDACTED]");
+    }
+
+    #[test]
+    fn test_set_and_reset() {
+        let (layer, filter) = LogFilter::layer("info").unwrap();
+        let _subscriber = Registry::default().with(layer);
+
+        let current = filter
+            .set_level("debug", Some("openclaw_channels::telegram"))
+            .unwrap();
+        assert!(current.contains("openclaw_channels::telegram=debug"));
+        assert!(current.contains("info"));
+
+        assert!(filter.set_level("loud", None).is_err());
+        assert!(filter.set_level("debug", Some("bad module")).is_err());
+
+        assert_eq!(filter.reset().unwrap(), "info");
+    }
+}
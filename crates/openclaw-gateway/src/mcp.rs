@@ -0,0 +1,486 @@
+//! Model Context Protocol (MCP) server.
+//!
+//! Serves the gateway's registered tools, sessions, and channel delivery to
+//! external MCP clients (desktop assistants, IDEs) over the streamable HTTP
+//! transport at `/mcp`. Clients authenticate with the same bearer tokens as
+//! `/rpc`, and every tool call is dispatched as an RPC method, so per-method
+//! auth, timeouts, and shutdown draining apply unchanged.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tokio::sync::RwLock;
+
+use crate::auth::JwtManager;
+use crate::rpc::{self, RpcRequest};
+use crate::server::{GatewayState, dispatch_with_timeout};
+
+/// MCP revisions this server speaks, newest first.
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// Gateway capabilities exposed as tools: name, backing RPC method, and
+/// description.
+const GATEWAY_TOOLS: &[(&str, &str, &str)] = &[
+    (
+        "openclaw_list_sessions",
+        "session.list",
+        "List OpenClaw sessions, optionally filtered by agent, channel, or state.",
+    ),
+    (
+        "openclaw_session_history",
+        "session.history",
+        "Get an OpenClaw session's details and message history.",
+    ),
+    (
+        "openclaw_send_message",
+        "session.message",
+        "Send a message to an OpenClaw agent in a session and return the reply. \
+         The session is created if it does not exist.",
+    ),
+    (
+        "openclaw_channel_send",
+        "channels.send",
+        "Send a text message to a chat on a connected channel (e.g. telegram, slack).",
+    ),
+];
+
+type McpResult = Result<Value, (i32, String)>;
+
+/// Incoming MCP message. Unlike gateway RPC, ids may be numbers.
+#[derive(Debug, Deserialize)]
+struct McpRequest {
+    method: String,
+    #[serde(default)]
+    params: Value,
+    id: Option<Value>,
+}
+
+/// MCP JSON-RPC response.
+#[derive(Debug, Serialize)]
+struct McpResponse {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<McpError>,
+}
+
+#[derive(Debug, Serialize)]
+struct McpError {
+    code: i32,
+    message: String,
+}
+
+impl McpResponse {
+    fn new(id: Value, result: McpResult) -> Self {
+        let (result, error) = match result {
+            Ok(value) => (Some(value), None),
+            Err((code, message)) => (None, Some(McpError { code, message })),
+        };
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+/// Handle a POST to `/mcp`.
+pub async fn mcp_handler(
+    State(state): State<Arc<RwLock<GatewayState>>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let auth_token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(JwtManager::extract_from_header);
+
+    // Reject at the HTTP layer so clients know to prompt for a token
+    {
+        let state = state.read().await;
+        if state.auth.requires_auth("mcp")
+            && auth_token.is_none_or(|t| state.auth.validate_token(t).is_err())
+        {
+            return (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Bearer")],
+                "Authentication required",
+            )
+                .into_response();
+        }
+    }
+
+    let request: McpRequest = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(e) => {
+            let error = Err((rpc::PARSE_ERROR, format!("Parse error: {e}")));
+            return Json(McpResponse::new(Value::Null, error)).into_response();
+        }
+    };
+
+    // Notifications (no id) are acknowledged without a body
+    let Some(id) = request.id else {
+        return StatusCode::ACCEPTED.into_response();
+    };
+
+    let result = handle_request(&state, &request.method, &request.params, auth_token).await;
+    Json(McpResponse::new(id, result)).into_response()
+}
+
+async fn handle_request(
+    state: &Arc<RwLock<GatewayState>>,
+    method: &str,
+    params: &Value,
+    auth_token: Option<&str>,
+) -> McpResult {
+    match method {
+        "initialize" => Ok(initialize(params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": list_tools(state).await })),
+        "tools/call" => call_tool(state, params, auth_token).await,
+        _ => Err((rpc::METHOD_NOT_FOUND, format!("Method not found: {method}"))),
+    }
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params["protocolVersion"].as_str();
+    let version = PROTOCOL_VERSIONS
+        .iter()
+        .find(|v| Some(**v) == requested)
+        .unwrap_or(&PROTOCOL_VERSIONS[0]);
+
+    json!({
+        "protocolVersion": version,
+        "capabilities": {
+            "tools": { "listChanged": false },
+        },
+        "serverInfo": {
+            "name": "openclaw",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "instructions": "Tools from an OpenClaw gateway: its registered agent tools, \
+                         plus openclaw_* tools for sessions and channel delivery.",
+    })
+}
+
+async fn list_tools(state: &Arc<RwLock<GatewayState>>) -> Vec<Value> {
+    let mut tools: Vec<Value> = GATEWAY_TOOLS
+        .iter()
+        .map(|(name, _, description)| {
+            json!({
+                "name": name,
+                "description": description,
+                "inputSchema": gateway_tool_schema(name),
+            })
+        })
+        .collect();
+
+    let mut registered = state.read().await.tool_registry.as_tool_definitions();
+    registered.sort_by(|a, b| a.name.cmp(&b.name));
+    tools.extend(
+        registered
+            .into_iter()
+            .filter(|t| gateway_tool_method(&t.name).is_none())
+            .map(|t| {
+                json!({
+                    "name": t.name,
+                    "description": t.description,
+                    "inputSchema": t.input_schema,
+                })
+            }),
+    );
+    tools
+}
+
+async fn call_tool(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &Value,
+    auth_token: Option<&str>,
+) -> McpResult {
+    let name = params["name"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing tool name".to_string()))?;
+    let arguments = params
+        .get("arguments")
+        .filter(|a| !a.is_null())
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+
+    let (method, rpc_params) = if let Some(method) = gateway_tool_method(name) {
+        (method, arguments)
+    } else if state.read().await.tool_registry.get(name).is_some() {
+        (
+            "tools.execute",
+            json!({ "tool_name": name, "params": arguments }),
+        )
+    } else {
+        return Err((rpc::INVALID_PARAMS, format!("Unknown tool: {name}")));
+    };
+
+    let request = RpcRequest {
+        jsonrpc: "2.0".to_string(),
+        method: method.to_string(),
+        params: rpc_params,
+        id: None,
+    };
+    let response = dispatch_with_timeout(state, &request, auth_token, None).await;
+
+    if let Some(error) = response.error {
+        // Auth failures are protocol errors; anything else is reported to
+        // the model as a failed tool call
+        if error.code == rpc::UNAUTHORIZED || error.code == rpc::FORBIDDEN {
+            return Err((error.code, error.message));
+        }
+        return Ok(tool_result(&error.message, true));
+    }
+
+    let result = response.result.unwrap_or(Value::Null);
+    if method == "tools.execute" {
+        let success = result["success"].as_bool().unwrap_or(false);
+        let text = if success {
+            result["content"].as_str()
+        } else {
+            result["error"]
+                .as_str()
+                .or_else(|| result["content"].as_str())
+        };
+        return Ok(tool_result(text.unwrap_or_default(), !success));
+    }
+    Ok(tool_result(
+        &serde_json::to_string_pretty(&result).unwrap_or_default(),
+        false,
+    ))
+}
+
+fn tool_result(text: &str, is_error: bool) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    })
+}
+
+fn gateway_tool_method(name: &str) -> Option<&'static str> {
+    GATEWAY_TOOLS
+        .iter()
+        .find(|(tool, _, _)| *tool == name)
+        .map(|(_, method, _)| *method)
+}
+
+fn gateway_tool_schema(name: &str) -> Value {
+    match name {
+        "openclaw_list_sessions" => json!({
+            "type": "object",
+            "properties": {
+                "agent": { "type": "string", "description": "Only sessions for this agent ID" },
+                "channel": { "type": "string", "description": "Only sessions on this channel" },
+                "state": { "type": "string", "description": "Only sessions in this state (e.g. active, ended)" },
+                "limit": { "type": "integer", "description": "Maximum sessions to return (default 50)" },
+                "offset": { "type": "integer", "description": "Sessions to skip" },
+            },
+        }),
+        "openclaw_session_history" => json!({
+            "type": "object",
+            "properties": {
+                "session_key": { "type": "string", "description": "Session key" },
+            },
+            "required": ["session_key"],
+        }),
+        "openclaw_send_message" => json!({
+            "type": "object",
+            "properties": {
+                "session_key": { "type": "string", "description": "Session key" },
+                "message": { "type": "string", "description": "Message text" },
+                "agent_id": { "type": "string", "description": "Agent for a new session (default \"default\")" },
+            },
+            "required": ["session_key", "message"],
+        }),
+        "openclaw_channel_send" => json!({
+            "type": "object",
+            "properties": {
+                "channel_id": { "type": "string", "description": "Channel ID (e.g. telegram)" },
+                "chat_id": { "type": "string", "description": "Target chat or room ID" },
+                "text": { "type": "string", "description": "Message text" },
+                "thread_id": { "type": "string", "description": "Thread to post in" },
+            },
+            "required": ["channel_id", "chat_id", "text"],
+        }),
+        _ => json!({ "type": "object" }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::AuthConfig;
+    use crate::server::{GatewayBuilder, GatewayConfig};
+    use async_trait::async_trait;
+    use openclaw_agents::harness::MockProvider;
+    use openclaw_agents::runtime::AgentRuntime;
+    use openclaw_agents::tools::{Tool, ToolError, ToolRegistry, ToolResult};
+    use openclaw_core::config::ToolPolicy;
+    use openclaw_core::events::EventStore;
+
+    struct EchoTool;
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &'static str {
+            "echo"
+        }
+
+        fn description(&self) -> &'static str {
+            "Echo the input"
+        }
+
+        fn input_schema(&self) -> Value {
+            json!({ "type": "object", "properties": { "text": { "type": "string" } } })
+        }
+
+        async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+            Ok(ToolResult::success(
+                params["text"].as_str().unwrap_or_default(),
+            ))
+        }
+    }
+
+    fn gateway_state(dir: &std::path::Path, auth: bool) -> Arc<RwLock<GatewayState>> {
+        let mut tools = ToolRegistry::new();
+        tools.register(Arc::new(EchoTool));
+        let tools = Arc::new(tools);
+        let agent = AgentRuntime::new(Arc::new(MockProvider::default())).with_tools(tools.clone());
+        let config = GatewayConfig {
+            data_dir: dir.join("gateway"),
+            auth: AuthConfig {
+                enabled: auth,
+                ..AuthConfig::default()
+            },
+            ..GatewayConfig::default()
+        };
+
+        GatewayBuilder::new()
+            .with_config(config)
+            .with_event_store(Arc::new(EventStore::open(&dir.join("events")).unwrap()))
+            .with_tool_registry(tools)
+            .with_agent("default", Arc::new(agent))
+            .build()
+            .unwrap()
+            .state()
+            .clone()
+    }
+
+    #[tokio::test]
+    async fn test_initialize_and_list_tools() {
+        let temp = tempfile::tempdir().unwrap();
+        let state = gateway_state(temp.path(), false);
+
+        let init = handle_request(
+            &state,
+            "initialize",
+            &json!({ "protocolVersion": "2025-03-26" }),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(init["protocolVersion"], "2025-03-26");
+        assert_eq!(init["serverInfo"]["name"], "openclaw");
+
+        let list = handle_request(&state, "tools/list", &Value::Null, None)
+            .await
+            .unwrap();
+        let names: Vec<&str> = list["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|t| t["name"].as_str())
+            .collect();
+        assert!(names.contains(&"echo"));
+        assert!(names.contains(&"openclaw_channel_send"));
+    }
+
+    #[tokio::test]
+    async fn test_call_tools() {
+        let temp = tempfile::tempdir().unwrap();
+        let state = gateway_state(temp.path(), false);
+
+        let result = handle_request(
+            &state,
+            "tools/call",
+            &json!({ "name": "echo", "arguments": { "text": "hi" } }),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(result["content"][0]["text"], "hi");
+        assert_eq!(result["isError"], false);
+
+        // Gateway errors become failed tool calls
+        let result = handle_request(
+            &state,
+            "tools/call",
+            &json!({
+                "name": "openclaw_channel_send",
+                "arguments": { "channel_id": "telegram", "chat_id": "1", "text": "hi" },
+            }),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(result["isError"], true);
+
+        let err = handle_request(&state, "tools/call", &json!({ "name": "nope" }), None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.0, rpc::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_follow_agent_policy() {
+        let temp = tempfile::tempdir().unwrap();
+        let state = gateway_state(temp.path(), false);
+        {
+            let mut state = state.write().await;
+            let agent = state.agents["default"]
+                .as_ref()
+                .clone()
+                .with_tool_policy(ToolPolicy::new(Vec::new(), vec!["echo".to_string()]));
+            state.agents.insert("default".to_string(), Arc::new(agent));
+        }
+
+        let err = handle_request(
+            &state,
+            "tools/call",
+            &json!({ "name": "echo", "arguments": { "text": "hi" } }),
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.0, rpc::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_require_token() {
+        let temp = tempfile::tempdir().unwrap();
+        let state = gateway_state(temp.path(), true);
+
+        let err = handle_request(
+            &state,
+            "tools/call",
+            &json!({ "name": "openclaw_list_sessions" }),
+            Some("not-a-token"),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.0, rpc::UNAUTHORIZED);
+    }
+}
@@ -1,36 +1,348 @@
 //! Gateway middleware.
 
-use governor::{Quota, RateLimiter, clock::DefaultClock, state::keyed::DefaultKeyedStateStore};
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
 use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
 
-/// Rate limiter for the gateway.
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method, header};
+use governor::clock::{Clock, DefaultClock};
+use governor::state::keyed::DefaultKeyedStateStore;
+use governor::{NotUntil, Quota, RateLimiter};
+use serde::Serialize;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use openclaw_core::config::{CorsConfig, RpcRateLimitConfig};
+
+use crate::rpc::{self, RpcResponse};
+
+type KeyedLimiter = RateLimiter<String, DefaultKeyedStateStore<String>, DefaultClock>;
+
+/// Who an RPC call is counted against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RateLimitCaller {
+    /// A signed-in user.
+    User {
+        /// User ID.
+        id: String,
+        /// Username, for per-user budgets.
+        username: String,
+    },
+    /// An anonymous client address.
+    Ip(IpAddr),
+}
+
+impl fmt::Display for RateLimitCaller {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::User { id, .. } => write!(f, "user:{id}"),
+            Self::Ip(addr) => write!(f, "ip:{addr}"),
+        }
+    }
+}
+
+/// RPC rate limiter counters.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RpcRateLimitStats {
+    /// Calls allowed.
+    pub allowed: u64,
+    /// Calls refused.
+    pub limited: u64,
+    /// Calls refused, by method.
+    pub limited_by_method: HashMap<String, u64>,
+}
+
+/// Rate limiter for the gateway's RPC methods.
+///
+/// Each caller has an overall budget, replaced by its own for users with
+/// one configured, and a budget per limited method or namespace.
 pub struct GatewayRateLimiter {
-    /// Per-client rate limiter.
-    client_limiter: RateLimiter<String, DefaultKeyedStateStore<String>, DefaultClock>,
+    enabled: bool,
+    caller_limiter: KeyedLimiter,
+    user_limiters: HashMap<String, KeyedLimiter>,
+    method_limiters: HashMap<String, KeyedLimiter>,
+    clock: DefaultClock,
+    allowed: AtomicU64,
+    limited: AtomicU64,
+    limited_by_method: Mutex<HashMap<String, u64>>,
 }
 
 impl GatewayRateLimiter {
-    /// Create a new rate limiter.
+    /// Create a rate limiter from configuration.
     #[must_use]
-    pub fn new(requests_per_minute: u32) -> Self {
-        let quota =
-            Quota::per_minute(NonZeroU32::new(requests_per_minute).unwrap_or(NonZeroU32::MIN));
+    pub fn new(config: &RpcRateLimitConfig) -> Self {
+        let per_minute = |n: u32| RateLimiter::keyed(Quota::per_minute(non_zero(n)));
         Self {
-            client_limiter: RateLimiter::keyed(quota),
+            enabled: config.enabled,
+            caller_limiter: RateLimiter::keyed(
+                Quota::per_minute(non_zero(config.per_minute)).allow_burst(non_zero(config.burst)),
+            ),
+            user_limiters: config
+                .users
+                .iter()
+                .map(|(name, n)| (name.clone(), per_minute(*n)))
+                .collect(),
+            method_limiters: config
+                .methods
+                .iter()
+                .map(|(key, n)| (key.clone(), per_minute(*n)))
+                .collect(),
+            clock: DefaultClock::default(),
+            allowed: AtomicU64::new(0),
+            limited: AtomicU64::new(0),
+            limited_by_method: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Check whether `caller` may call `method` now.
+    ///
+    /// # Errors
+    ///
+    /// Returns how long until the call would be allowed.
+    pub fn check(&self, method: &str, caller: &RateLimitCaller) -> Result<(), Duration> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let key = caller.to_string();
+        let overall = match caller {
+            RateLimitCaller::User { username, .. } => self.user_limiters.get(username),
+            RateLimitCaller::Ip(_) => None,
+        }
+        .unwrap_or(&self.caller_limiter);
+
+        let result = self
+            .method_limiter(method)
+            .map_or(Ok(()), |limiter| limiter.check_key(&key))
+            .and_then(|()| overall.check_key(&key));
+
+        match result {
+            Ok(()) => {
+                self.allowed.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            }
+            Err(not_until) => Err(self.refuse(method, &key, &not_until)),
         }
     }
 
-    /// Check if a request is allowed.
+    /// Snapshot of the counters.
     #[must_use]
-    pub fn check(&self, client_id: &str) -> bool {
-        self.client_limiter
-            .check_key(&client_id.to_string())
-            .is_ok()
+    pub fn stats(&self) -> RpcRateLimitStats {
+        RpcRateLimitStats {
+            allowed: self.allowed.load(Ordering::Relaxed),
+            limited: self.limited.load(Ordering::Relaxed),
+            limited_by_method: self
+                .limited_by_method
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone(),
+        }
+    }
+
+    /// The budget for `method`: its own, or its longest matching namespace's.
+    fn method_limiter(&self, method: &str) -> Option<&KeyedLimiter> {
+        if let Some(limiter) = self.method_limiters.get(method) {
+            return Some(limiter);
+        }
+        self.method_limiters
+            .iter()
+            .filter_map(|(key, limiter)| {
+                let namespace = key.strip_suffix('*')?;
+                method
+                    .starts_with(namespace)
+                    .then_some((namespace.len(), limiter))
+            })
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, limiter)| limiter)
+    }
+
+    fn refuse(
+        &self,
+        method: &str,
+        key: &str,
+        not_until: &NotUntil<<DefaultClock as Clock>::Instant>,
+    ) -> Duration {
+        self.limited.fetch_add(1, Ordering::Relaxed);
+        *self
+            .limited_by_method
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(method.to_string())
+            .or_insert(0) += 1;
+
+        let retry_after = not_until.wait_time_from(self.clock.now());
+        tracing::debug!(
+            "Rate limited {} calling {} for {:?}",
+            key,
+            method,
+            retry_after
+        );
+        retry_after
     }
 }
 
 impl Default for GatewayRateLimiter {
     fn default() -> Self {
-        Self::new(100) // 100 requests per minute
+        Self::new(&RpcRateLimitConfig::default())
+    }
+}
+
+impl fmt::Debug for GatewayRateLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GatewayRateLimiter")
+            .field("enabled", &self.enabled)
+            .field("stats", &self.stats())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The error response for a call refused by the rate limiter. `data`
+/// carries the wait in whole seconds, which HTTP callers also get as
+/// `Retry-After`.
+#[must_use]
+pub fn rate_limited_response(id: Option<String>, retry_after: Duration) -> RpcResponse {
+    let secs = retry_after_secs(retry_after);
+    let mut response = RpcResponse::error(
+        id,
+        rpc::TOO_MANY_REQUESTS,
+        format!("Rate limit exceeded; retry in {secs}s"),
+    );
+    if let Some(error) = &mut response.error {
+        error.data = Some(serde_json::json!({ "retry_after": secs }));
+    }
+    response
+}
+
+/// The `Retry-After` seconds of a rate-limited response, if it is one.
+#[must_use]
+pub fn retry_after(response: &RpcResponse) -> Option<u64> {
+    let error = response.error.as_ref()?;
+    if error.code != rpc::TOO_MANY_REQUESTS {
+        return None;
+    }
+    error.data.as_ref()?["retry_after"].as_u64()
+}
+
+/// Whole seconds to wait, rounded up so a retry is never early.
+fn retry_after_secs(retry_after: Duration) -> u64 {
+    let secs = retry_after.as_secs();
+    if retry_after.subsec_nanos() > 0 {
+        secs.saturating_add(1)
+    } else {
+        secs.max(1)
+    }
+}
+
+/// The CORS layer for `config`.
+pub fn cors_layer(config: &CorsConfig) -> CorsLayer {
+    let origins = if config.allows_any_origin() {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(config.allowed_origins.iter().filter_map(|origin| {
+            HeaderValue::from_str(&origin.trim_end_matches('/').to_ascii_lowercase()).ok()
+        }))
+    };
+    let headers: Vec<HeaderName> = config
+        .allowed_headers
+        .iter()
+        .filter_map(|name| HeaderName::from_bytes(name.as_bytes()).ok())
+        .collect();
+
+    CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods([Method::GET, Method::POST, Method::PUT])
+        .allow_headers(headers)
+        .allow_credentials(config.allow_credentials)
+        .expose_headers([header::RETRY_AFTER])
+        .max_age(Duration::from_secs(config.max_age_secs))
+}
+
+/// Whether a request's `Origin` is allowed. Requests without one come from
+/// outside a browser and are always allowed.
+pub fn origin_allowed(config: &CorsConfig, headers: &HeaderMap) -> bool {
+    headers.get(header::ORIGIN).is_none_or(|origin| {
+        origin
+            .to_str()
+            .is_ok_and(|origin| config.allows_origin(origin))
+    })
+}
+
+fn non_zero(n: u32) -> NonZeroU32 {
+    NonZeroU32::new(n).unwrap_or(NonZeroU32::MIN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(name: &str) -> RateLimitCaller {
+        RateLimitCaller::User {
+            id: format!("user_{name}"),
+            username: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_method_and_caller_budgets() {
+        let limiter = GatewayRateLimiter::new(&RpcRateLimitConfig {
+            per_minute: 1,
+            burst: 3,
+            methods: HashMap::from([("session.*".to_string(), 1)]),
+            users: HashMap::from([("ci".to_string(), 100)]),
+            ..RpcRateLimitConfig::default()
+        });
+        let alice = user("alice");
+
+        assert!(limiter.check("session.message", &alice).is_ok());
+        // The namespace budget is spent, the overall one is not
+        let retry = limiter.check("session.list", &alice).unwrap_err();
+        assert!(retry > Duration::ZERO);
+        assert!(limiter.check("system.health", &alice).is_ok());
+        assert!(limiter.check("system.health", &alice).is_ok());
+        assert!(limiter.check("system.health", &alice).is_err());
+
+        // Callers are counted separately, and per-user budgets replace the
+        // overall one
+        let ip = RateLimitCaller::Ip("10.0.0.1".parse().unwrap());
+        assert!(limiter.check("system.health", &ip).is_ok());
+        for _ in 0..10 {
+            assert!(limiter.check("system.health", &user("ci")).is_ok());
+        }
+
+        let stats = limiter.stats();
+        assert_eq!(stats.limited, 2);
+        assert_eq!(stats.limited_by_method["session.list"], 1);
+    }
+
+    #[test]
+    fn test_origin_allowed() {
+        let config = CorsConfig::default();
+        let mut headers = HeaderMap::new();
+        assert!(origin_allowed(&config, &headers));
+
+        headers.insert(
+            header::ORIGIN,
+            HeaderValue::from_static("http://localhost:3000"),
+        );
+        assert!(origin_allowed(&config, &headers));
+        headers.insert(
+            header::ORIGIN,
+            HeaderValue::from_static("https://evil.example.com"),
+        );
+        assert!(!origin_allowed(&config, &headers));
+        assert!(!origin_allowed(&CorsConfig::none(), &headers));
+    }
+
+    #[test]
+    fn test_rate_limited_response() {
+        let response = rate_limited_response(Some("1".to_string()), Duration::from_millis(1500));
+        assert_eq!(retry_after(&response), Some(2));
+
+        let other = RpcResponse::error(None, rpc::TOO_MANY_REQUESTS, "Locked out");
+        assert_eq!(retry_after(&other), None);
+        assert_eq!(retry_after_secs(Duration::ZERO), 1);
     }
 }
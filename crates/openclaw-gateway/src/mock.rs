@@ -0,0 +1,623 @@
+//! Answering messages from the mock channel.
+//!
+//! The mock channel has no platform to push messages, so the gateway reads
+//! its inbound queue, runs each message through the same session pipeline
+//! as `session.message`, and sends the reply back through the channel.
+//! Reactions injected into the channel are recorded as feedback.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use tokio::sync::RwLock;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use openclaw_channels::mock::{self, MOCK_CHANNEL_ID, MockChannel};
+use openclaw_channels::{
+    Channel, CitationStyle, GroupDecision, OutboundContext, rating_from_reaction, render_citations,
+};
+use openclaw_core::config::SessionScope;
+use openclaw_core::types::{
+    AgentId, ChannelId, Citation, DeliveryResult, Message, MessageReaction, PeerId, PeerType,
+    SessionKey,
+};
+
+use crate::rpc;
+use crate::server::{
+    GatewayState, authorize_session, handle_session_create, handle_session_message,
+    record_feedback, session_projection,
+};
+use crate::triggers::dispatch_message;
+use crate::workflow_input::resume_from_message;
+
+/// Answer messages read or injected into the mock channel until it closes.
+pub async fn run_mock_inbound(
+    state: Arc<RwLock<GatewayState>>,
+    channel: Arc<MockChannel>,
+    mut inbound: UnboundedReceiver<Message>,
+) {
+    while let Some(message) = inbound.recv().await {
+        if let Err((_, e)) = answer_mock_message(&state, &channel, &message, None, None).await {
+            tracing::warn!("Mock message {} failed: {}", message.id, e);
+        }
+    }
+}
+
+/// Run a mock message through its session and deliver the reply.
+///
+/// Each chat gets its own session, created on the first message. The agent
+/// is `agent_id`, or the one the router picks for the sender. A message
+/// answering a workflow that waits for the sender resumes the workflow
+/// instead, and one matching a workflow's message trigger starts it. In
+/// groups, only messages that mention the agent or reply to it are answered,
+/// and agents scoped to threads get a session per thread.
+pub async fn answer_mock_message(
+    state: &Arc<RwLock<GatewayState>>,
+    channel: &Arc<MockChannel>,
+    message: &Message,
+    agent_id: Option<&str>,
+    auth_token: Option<&str>,
+) -> Result<serde_json::Value, (i32, String)> {
+    let chat_id = mock::chat_id(message);
+    if let Some(result) = resume_from_message(state, message, &chat_id).await {
+        return result
+            .map(|workflow| serde_json::json!({ "message_id": message.id, "workflow": workflow }));
+    }
+    if let Some(workflows) = dispatch_message(state, message, &chat_id).await {
+        return Ok(serde_json::json!({ "message_id": message.id, "workflows": workflows }));
+    }
+
+    let groups = state.read().await.groups.clone();
+    let threads = channel.capabilities().threads;
+    let thread_id = match groups.admit(message, &chat_id, threads, Instant::now()) {
+        GroupDecision::Answer { thread_id } => thread_id,
+        decision => {
+            return Ok(serde_json::json!({
+                "message_id": message.id,
+                "response": null,
+                "ignored": decision.reason(),
+            }));
+        }
+    };
+
+    let (agent_id, session_thread, exists) = {
+        let state = state.read().await;
+        let agent_id = agent_id.map_or_else(
+            || {
+                state
+                    .router
+                    .route(&message.channel, &message.peer_id)
+                    .to_string()
+            },
+            String::from,
+        );
+        let session_thread = match state.config.groups.session_scope_for(&agent_id) {
+            SessionScope::Thread if message.peer_type != PeerType::Dm => thread_id.clone(),
+            _ => None,
+        };
+        let session_key = session_key(&agent_id, &chat_id, session_thread.as_deref());
+        let exists = state
+            .store_for_session(&session_key)
+            .get_projection(&session_key)
+            .is_ok();
+        drop(state);
+        (agent_id, session_thread, exists)
+    };
+
+    let session_key = if exists {
+        session_key(&agent_id, &chat_id, session_thread.as_deref())
+    } else {
+        let created = handle_session_create(
+            state,
+            &serde_json::json!({
+                "agent_id": agent_id,
+                "channel": MOCK_CHANNEL_ID,
+                "peer_id": chat_id,
+                "thread_id": session_thread,
+            }),
+            auth_token,
+        )
+        .await?;
+        SessionKey::new(created["session_key"].as_str().unwrap_or_default())
+    };
+
+    let result = handle_session_message(
+        state,
+        &serde_json::json!({
+            "session_key": session_key.as_ref(),
+            "message": message.content,
+            "attachments": message.attachments,
+            "sender_id": message.peer_id.as_ref(),
+        }),
+        auth_token,
+    )
+    .await?;
+
+    let delivery = match reply_text(&result, &ChannelId::mock()) {
+        Some(text) => {
+            let ctx = OutboundContext {
+                chat_id,
+                reply_to: Some(message.id.clone()),
+                thread_id,
+            };
+            Some(deliver(state, channel, message, ctx, &text).await?)
+        }
+        None => None,
+    };
+
+    let mut reply = serde_json::json!({
+        "session_key": session_key.as_ref(),
+        "message_id": message.id,
+        "response": result["response"],
+        "delivery": delivery,
+    });
+    // Say which chat command answered without the agent, or what it planned
+    for field in ["command", "planned_tools"] {
+        if let Some(value) = result.get(field) {
+            reply[field] = value.clone();
+        }
+    }
+    Ok(reply)
+}
+
+/// Record a reaction from the mock channel on its chat's session.
+///
+/// Adding a thumbs-up or thumbs-down is feedback on the message reacted
+/// to; other reactions, and removals, are acknowledged but not recorded.
+pub async fn answer_mock_reaction(
+    state: &Arc<RwLock<GatewayState>>,
+    reaction: &MessageReaction,
+    agent_id: Option<&str>,
+    auth_token: Option<&str>,
+) -> Result<serde_json::Value, (i32, String)> {
+    let state = state.read().await;
+    let agent_id = agent_id.map_or_else(
+        || {
+            state
+                .router
+                .route(&reaction.channel, &reaction.peer_id)
+                .to_string()
+        },
+        String::from,
+    );
+    let session_key = session_key(&agent_id, &reaction.chat_id, None);
+    let projection = session_projection(&state, &session_key)?;
+    authorize_session(&state, auth_token, &projection, false)?;
+
+    let rating = rating_from_reaction(&reaction.emoji).filter(|_| !reaction.removed);
+    let feedback = match rating {
+        Some(rating) => Some(record_feedback(
+            state.store_for_session(&session_key),
+            &session_key,
+            &agent_id,
+            Some(reaction.message_id.clone()),
+            rating,
+            None,
+            "reaction",
+        )?),
+        None => None,
+    };
+    drop(state);
+
+    Ok(serde_json::json!({
+        "session_key": session_key.as_ref(),
+        "rating": rating,
+        "feedback": feedback,
+    }))
+}
+
+/// Send a reply to the mock chat in `ctx`, noting it as the agent's last
+/// message there in groups.
+async fn deliver(
+    state: &Arc<RwLock<GatewayState>>,
+    channel: &MockChannel,
+    message: &Message,
+    ctx: OutboundContext,
+    text: &str,
+) -> Result<DeliveryResult, (i32, String)> {
+    let (outbound, groups) = {
+        let state = state.read().await;
+        (state.outbound.clone(), state.groups.clone())
+    };
+    let chat_id = ctx.chat_id.clone();
+    let delivered = outbound
+        .send(channel, ctx, text)
+        .await
+        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Delivery failed: {e}")))?;
+    if message.peer_type != PeerType::Dm {
+        groups.record_sent(MOCK_CHANNEL_ID, &chat_id, &delivered.message_id);
+    }
+    Ok(delivered)
+}
+
+/// The agent's reply from a `session.message` result, with the sources
+/// it cites listed after it in `channel`'s style.
+pub(crate) fn reply_text(result: &serde_json::Value, channel: &ChannelId) -> Option<String> {
+    let text = result["response"].as_str()?;
+    let citations: Vec<Citation> =
+        serde_json::from_value(result["citations"].clone()).unwrap_or_default();
+    Some(render_citations(
+        text,
+        &citations,
+        CitationStyle::for_channel(channel),
+    ))
+}
+
+/// Key of the session for a mock chat or one of its threads, as
+/// `session.create` builds it.
+fn session_key(agent_id: &str, chat_id: &str, thread_id: Option<&str>) -> SessionKey {
+    SessionKey::build(
+        &AgentId::new(agent_id),
+        &ChannelId::mock(),
+        "gateway",
+        PeerType::Dm,
+        &PeerId::new(chat_id),
+        thread_id,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use openclaw_agents::harness::{MockCompletion, MockProvider};
+    use openclaw_agents::runtime::AgentRuntime;
+    use openclaw_channels::mock::{MockAction, MockInbound, MockReaction};
+    use openclaw_channels::{ChannelInbound, ChannelReactions};
+    use openclaw_core::config::{ChatCommandsConfig, GroupsConfig, InboundRateLimitConfig};
+    use openclaw_core::events::{EventStore, SessionEvent, SessionEventKind};
+
+    use super::*;
+    use crate::server::{GatewayBuilder, GatewayConfig};
+
+    #[tokio::test]
+    async fn test_answer_mock_message() {
+        let temp = tempfile::tempdir().unwrap();
+        let provider = MockProvider::new([
+            MockCompletion::Text("pong".to_string()),
+            MockCompletion::Text("pong again".to_string()),
+        ]);
+        let channel = Arc::new(MockChannel::new());
+        let gateway = GatewayBuilder::new()
+            .with_config(GatewayConfig {
+                data_dir: temp.path().join("gateway"),
+                ..GatewayConfig::default()
+            })
+            .with_event_store(Arc::new(
+                EventStore::open(&temp.path().join("events")).unwrap(),
+            ))
+            .with_agent("default", Arc::new(AgentRuntime::new(Arc::new(provider))))
+            .with_mock_channel(channel.clone())
+            .build()
+            .unwrap();
+        let state = gateway.state();
+
+        let ping = channel
+            .normalize(MockInbound::text("alice", "ping"))
+            .unwrap();
+        let first = answer_mock_message(state, &channel, &ping, Some("default"), None)
+            .await
+            .unwrap();
+        assert_eq!(first["response"], "pong");
+
+        let again = channel
+            .normalize(MockInbound::text("alice", "ping again"))
+            .unwrap();
+        let second = answer_mock_message(state, &channel, &again, Some("default"), None)
+            .await
+            .unwrap();
+        assert_eq!(second["session_key"], first["session_key"]);
+
+        let outbound = channel.outbound();
+        assert_eq!(outbound.len(), 2);
+        assert_eq!(outbound[0].chat_id, "alice");
+        assert_eq!(outbound[0].reply_to.as_deref(), Some(ping.id.as_str()));
+        assert_eq!(
+            outbound[1].action,
+            MockAction::Text {
+                text: "pong again".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_answer_mock_message_lists_cited_sources() {
+        let temp = tempfile::tempdir().unwrap();
+        let provider = MockProvider::new([
+            MockCompletion::Text("Looking".to_string()),
+            MockCompletion::Text("Tokio is async [1].".to_string()),
+        ]);
+        let channel = Arc::new(MockChannel::new());
+        let gateway = GatewayBuilder::new()
+            .with_config(GatewayConfig {
+                data_dir: temp.path().join("gateway"),
+                ..GatewayConfig::default()
+            })
+            .with_event_store(Arc::new(
+                EventStore::open(&temp.path().join("events")).unwrap(),
+            ))
+            .with_agent("default", Arc::new(AgentRuntime::new(Arc::new(provider))))
+            .with_mock_channel(channel.clone())
+            .build()
+            .unwrap();
+        let state = gateway.state();
+
+        let ask = channel
+            .normalize(MockInbound::text("alice", "what is tokio?"))
+            .unwrap();
+        let first = answer_mock_message(state, &channel, &ask, Some("default"), None)
+            .await
+            .unwrap();
+        let session_key = SessionKey::new(first["session_key"].as_str().unwrap());
+        state
+            .read()
+            .await
+            .store_for_session(&session_key)
+            .append(&SessionEvent::new(
+                session_key.clone(),
+                "default".to_string(),
+                SessionEventKind::ToolResult {
+                    tool_name: "search".to_string(),
+                    result: serde_json::json!({
+                        "success": true,
+                        "content": "results",
+                        "sources": [{ "title": "Tokio", "url": "https://tokio.rs" }]
+                    }),
+                    success: true,
+                },
+            ))
+            .unwrap();
+
+        let more = channel
+            .normalize(MockInbound::text("alice", "go on"))
+            .unwrap();
+        let second = answer_mock_message(state, &channel, &more, Some("default"), None)
+            .await
+            .unwrap();
+        assert_eq!(second["response"], "Tokio is async [1].");
+
+        let outbound = channel.outbound();
+        assert_eq!(
+            outbound[1].action,
+            MockAction::Text {
+                text: "Tokio is async [1].\n\nSources:\n1. Tokio - https://tokio.rs".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_answer_mock_reaction() {
+        let temp = tempfile::tempdir().unwrap();
+        let provider = MockProvider::new([MockCompletion::Text("pong".to_string())]);
+        let channel = Arc::new(MockChannel::new());
+        let gateway = GatewayBuilder::new()
+            .with_config(GatewayConfig {
+                data_dir: temp.path().join("gateway"),
+                ..GatewayConfig::default()
+            })
+            .with_event_store(Arc::new(
+                EventStore::open(&temp.path().join("events")).unwrap(),
+            ))
+            .with_agent("default", Arc::new(AgentRuntime::new(Arc::new(provider))))
+            .with_mock_channel(channel.clone())
+            .build()
+            .unwrap();
+        let state = gateway.state();
+
+        let ping = channel
+            .normalize(MockInbound::text("alice", "ping"))
+            .unwrap();
+        let reply = answer_mock_message(state, &channel, &ping, Some("default"), None)
+            .await
+            .unwrap();
+        let reply_id = reply["delivery"]["message_id"].as_str().unwrap();
+
+        let react = |peer_id: &str, emoji: &str, removed: bool| {
+            channel
+                .normalize_reaction(MockReaction {
+                    peer_id: peer_id.to_string(),
+                    chat_id: None,
+                    message_id: reply_id.to_string(),
+                    emoji: emoji.to_string(),
+                    removed,
+                })
+                .unwrap()
+        };
+
+        let liked = answer_mock_reaction(state, &react("alice", "👍", false), None, None)
+            .await
+            .unwrap();
+        assert_eq!(liked["session_key"], reply["session_key"]);
+        assert_eq!(liked["rating"], "positive");
+        assert!(liked["feedback"].is_string());
+
+        // Removals and reactions that aren't ratings are not feedback
+        for (emoji, removed) in [("👍", true), ("🎉", false)] {
+            let result = answer_mock_reaction(state, &react("alice", emoji, removed), None, None)
+                .await
+                .unwrap();
+            assert!(result["feedback"].is_null());
+        }
+
+        let key = SessionKey::new(reply["session_key"].as_str().unwrap());
+        let events = state
+            .read()
+            .await
+            .store_for_session(&key)
+            .get_events(&key)
+            .unwrap();
+        let feedback: Vec<_> = events
+            .iter()
+            .filter_map(|e| match &e.kind {
+                SessionEventKind::FeedbackReceived {
+                    response_id,
+                    source,
+                    ..
+                } => Some((response_id.as_deref(), source.as_str())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(feedback, [(Some(reply_id), "reaction")]);
+
+        // Chats without a session have nothing to rate
+        let stranger = answer_mock_reaction(state, &react("bob", "👍", false), None, None).await;
+        assert_eq!(stranger.unwrap_err().0, rpc::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_group_messages_need_mention() {
+        let temp = tempfile::tempdir().unwrap();
+        let provider = MockProvider::new([
+            MockCompletion::Text("on it".to_string()),
+            MockCompletion::Text("done".to_string()),
+        ]);
+        let channel = Arc::new(MockChannel::new());
+        let gateway = GatewayBuilder::new()
+            .with_config(GatewayConfig {
+                data_dir: temp.path().join("gateway"),
+                ..GatewayConfig::default()
+            })
+            .with_event_store(Arc::new(
+                EventStore::open(&temp.path().join("events")).unwrap(),
+            ))
+            .with_agent("default", Arc::new(AgentRuntime::new(Arc::new(provider))))
+            .with_mock_channel(channel.clone())
+            .build()
+            .unwrap();
+        let state = gateway.state();
+        let in_team = |text: &str, reply_to: Option<&str>| {
+            let mut inbound = MockInbound::text("alice", text);
+            inbound.chat_id = Some("team".to_string());
+            inbound.reply_to = reply_to.map(String::from);
+            channel.normalize(inbound).unwrap()
+        };
+
+        let chatter = in_team("anyone for lunch?", None);
+        let ignored = answer_mock_message(state, &channel, &chatter, Some("default"), None)
+            .await
+            .unwrap();
+        assert_eq!(ignored["ignored"], "not_addressed");
+
+        let ask = in_team("@mock deploy the docs", None);
+        let answered = answer_mock_message(state, &channel, &ask, Some("default"), None)
+            .await
+            .unwrap();
+        assert_eq!(answered["response"], "on it");
+        let outbound = channel.outbound();
+        assert_eq!(outbound[0].thread_id.as_deref(), Some(ask.id.as_str()));
+
+        // Replying to the agent needs no mention
+        let reply = in_team("thanks, and the changelog?", Some(&outbound[0].message_id));
+        let answered = answer_mock_message(state, &channel, &reply, Some("default"), None)
+            .await
+            .unwrap();
+        assert_eq!(answered["response"], "done");
+    }
+
+    #[tokio::test]
+    async fn test_thread_scoped_sessions() {
+        let temp = tempfile::tempdir().unwrap();
+        let provider = MockProvider::new([
+            MockCompletion::Text("one".to_string()),
+            MockCompletion::Text("two".to_string()),
+            MockCompletion::Text("three".to_string()),
+        ]);
+        let channel = Arc::new(MockChannel::new());
+        let gateway = GatewayBuilder::new()
+            .with_config(GatewayConfig {
+                data_dir: temp.path().join("gateway"),
+                groups: GroupsConfig {
+                    require_mention: false,
+                    session_scope: SessionScope::Thread,
+                    ..GroupsConfig::default()
+                },
+                ..GatewayConfig::default()
+            })
+            .with_event_store(Arc::new(
+                EventStore::open(&temp.path().join("events")).unwrap(),
+            ))
+            .with_agent("default", Arc::new(AgentRuntime::new(Arc::new(provider))))
+            .with_mock_channel(channel.clone())
+            .build()
+            .unwrap();
+        let state = gateway.state();
+        let in_thread = |text: &str, thread: &str| {
+            let mut inbound = MockInbound::text("alice", text);
+            inbound.chat_id = Some("team".to_string());
+            inbound.thread_id = Some(thread.to_string());
+            channel.normalize(inbound).unwrap()
+        };
+
+        let mut keys = Vec::new();
+        for (text, thread) in [("deploy?", "t1"), ("lunch?", "t2"), ("and docs?", "t1")] {
+            let message = in_thread(text, thread);
+            let answered = answer_mock_message(state, &channel, &message, Some("default"), None)
+                .await
+                .unwrap();
+            keys.push(answered["session_key"].as_str().unwrap().to_string());
+        }
+        assert!(keys[0].ends_with(":thread:t1"));
+        assert_ne!(keys[0], keys[1]);
+        assert_eq!(keys[0], keys[2]);
+    }
+
+    #[tokio::test]
+    async fn test_chat_commands() {
+        let temp = tempfile::tempdir().unwrap();
+        let provider = Arc::new(MockProvider::new([
+            MockCompletion::Text("hi".to_string()),
+            MockCompletion::Text("hi from the large model".to_string()),
+        ]));
+        let channel = Arc::new(MockChannel::new());
+        let gateway = GatewayBuilder::new()
+            .with_config(GatewayConfig {
+                data_dir: temp.path().join("gateway"),
+                commands: ChatCommandsConfig {
+                    models: vec!["mock-large".to_string()],
+                    ..ChatCommandsConfig::default()
+                },
+                // More messages than the default burst allows
+                inbound_rate_limit: InboundRateLimitConfig {
+                    enabled: false,
+                    ..InboundRateLimitConfig::default()
+                },
+                ..GatewayConfig::default()
+            })
+            .with_event_store(Arc::new(
+                EventStore::open(&temp.path().join("events")).unwrap(),
+            ))
+            .with_agent("default", Arc::new(AgentRuntime::new(provider.clone())))
+            .with_mock_channel(channel.clone())
+            .build()
+            .unwrap();
+        let state = gateway.state();
+        let send = |text: &str| {
+            let message = channel.normalize(MockInbound::text("alice", text)).unwrap();
+            let channel = &channel;
+            async move {
+                answer_mock_message(state, channel, &message, Some("default"), None)
+                    .await
+                    .unwrap()
+            }
+        };
+
+        assert_eq!(send("hello").await["response"], "hi");
+
+        let unknown = send("/model mock-huge").await;
+        assert_eq!(unknown["command"], "model");
+        assert!(unknown["response"].as_str().unwrap().contains("mock-large"));
+        send("/model mock-large").await;
+        assert_eq!(
+            send("hello again").await["response"],
+            "hi from the large model"
+        );
+
+        let usage = send("/usage").await;
+        assert!(usage["response"].as_str().unwrap().contains("2 responses"));
+        let help = send("/help").await;
+        assert!(help["response"].as_str().unwrap().contains("/reset"));
+        assert_eq!(send("/reset").await["command"], "reset");
+
+        // Commands never reach the model
+        let requests = provider.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].model, "mock-large");
+    }
+}
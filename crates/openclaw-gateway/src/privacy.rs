@@ -0,0 +1,202 @@
+//! Erasing a peer's data on request.
+//!
+//! Forgetting a peer removes every session with them from the main and
+//! namespace event stores the caller manages, active or archived, along
+//! with the uploads and workspaces of those sessions and the peer's
+//! profile. Messages they sent in group and thread sessions are removed
+//! from those sessions, and provider wire log entries and recordings that
+//! quote any of the removed messages are deleted. A report is then
+//! appended to the privacy audit log, an internal session that names the
+//! peer only by hash. Backups taken earlier still hold the data.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+use thiserror::Error;
+
+use openclaw_core::events::{
+    EventId, EventStoreError, ForgottenSession, SessionEvent, SessionEventKind,
+};
+use openclaw_core::peers::PeerProfileError;
+use openclaw_core::types::SessionKey;
+use openclaw_providers::{Cassette, ProviderError, WireLog};
+
+use crate::server::GatewayState;
+use crate::tenancy::NamespaceAccess;
+use crate::transfer::TransferError;
+use crate::workspace::Workspace;
+
+/// Agent ID recorded on the privacy audit log.
+pub const PRIVACY_AGENT: &str = "privacy";
+
+/// Erasure errors.
+#[derive(Error, Debug)]
+pub enum PrivacyError {
+    /// Sessions could not be removed or the report recorded.
+    #[error("Event store error: {0}")]
+    Events(#[from] EventStoreError),
+
+    /// The profile could not be deleted.
+    #[error("Peer profile error: {0}")]
+    Profiles(#[from] PeerProfileError),
+
+    /// Uploads could not be deleted.
+    #[error("Upload error: {0}")]
+    Uploads(#[from] TransferError),
+
+    /// A workspace could not be deleted or the wire log rewritten.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Provider recordings could not be deleted.
+    #[error("Recording error: {0}")]
+    Recordings(#[from] ProviderError),
+}
+
+/// Session key of the privacy audit log.
+#[must_use]
+pub fn audit_session_key() -> SessionKey {
+    SessionKey::new("audit:privacy")
+}
+
+/// How a peer is named in the audit log.
+#[must_use]
+pub fn peer_hash(channel: &str, peer_id: &str) -> String {
+    EventId::from_content(format!("{channel}:{peer_id}").as_bytes()).to_hex()
+}
+
+/// What forgetting a peer removed.
+#[derive(Debug, Clone, Serialize)]
+pub struct ErasureReport {
+    /// Channel ID.
+    pub channel: String,
+    /// Hash naming the peer in the audit log.
+    pub peer_hash: String,
+    /// Sessions removed.
+    pub sessions: Vec<ForgottenSession>,
+    /// Events removed.
+    pub events: usize,
+    /// Messages the peer sent in other sessions, removed from them (also
+    /// counted in `events`).
+    pub redacted: usize,
+    /// Provider wire log entries deleted.
+    pub wire_log_entries: usize,
+    /// Provider recordings deleted.
+    pub recordings: usize,
+    /// Whether a peer profile was deleted.
+    pub profile: bool,
+    /// Uploaded files deleted.
+    pub uploads: usize,
+    /// Session workspaces deleted.
+    pub workspaces: usize,
+}
+
+/// Erase everything stored about `peer_id` on `channel` in the stores
+/// `access` may manage and record the report in the audit log.
+///
+/// # Errors
+///
+/// Returns error if any part of the data cannot be removed. What was
+/// removed before the failure stays removed; forgetting again finishes
+/// the job.
+pub async fn forget_peer(
+    state: &GatewayState,
+    access: &NamespaceAccess,
+    channel: &str,
+    peer_id: &str,
+    requested_by: &str,
+) -> Result<ErasureReport, PrivacyError> {
+    let mut sessions = Vec::new();
+    let mut events = 0;
+    let mut redacted = 0;
+    let mut messages = Vec::new();
+    for (namespace, store) in state.namespaced_event_stores() {
+        if !access.can_manage(namespace) {
+            continue;
+        }
+        let forgotten = store.forget_peer(channel, peer_id)?;
+        events += forgotten.events;
+        redacted += forgotten.redacted;
+        sessions.extend(forgotten.sessions);
+        messages.extend(forgotten.messages);
+    }
+    for session in &sessions {
+        state.events.unhide_session(session.session_key.as_ref());
+    }
+
+    let keys: HashSet<&str> = sessions.iter().map(|s| s.session_key.as_ref()).collect();
+    let uploads = state.uploads.delete_for_sessions(&keys).await?;
+
+    let mut workspaces = 0;
+    for session in &sessions {
+        let workspace = Workspace::for_session(
+            &state.config.workspace_dir,
+            &session.agent_id,
+            session.session_key.as_ref(),
+        );
+        if workspace.root().exists() {
+            std::fs::remove_dir_all(workspace.root())?;
+            workspaces += 1;
+        }
+    }
+
+    let wire_log_entries = match WireLog::from_config(&state.config.wire_log) {
+        Some(log) => log.forget(&messages)?,
+        None => 0,
+    };
+    let recordings = match Cassette::from_config(&state.config.recording) {
+        Some(cassette) => cassette.forget(&messages)?,
+        None => 0,
+    };
+
+    let profile = state.peer_profiles.delete(channel, peer_id)?;
+
+    let report = ErasureReport {
+        channel: channel.to_string(),
+        peer_hash: peer_hash(channel, peer_id),
+        sessions,
+        events,
+        redacted,
+        wire_log_entries,
+        recordings,
+        profile,
+        uploads,
+        workspaces,
+    };
+    record(state, &report, requested_by)?;
+    Ok(report)
+}
+
+/// Append `report` to the audit log, starting the log if needed.
+fn record(
+    state: &GatewayState,
+    report: &ErasureReport,
+    requested_by: &str,
+) -> Result<(), EventStoreError> {
+    let store = &state.event_store;
+    let key = audit_session_key();
+    let append = |kind| {
+        store.append(&SessionEvent::new(
+            key.clone(),
+            PRIVACY_AGENT.to_string(),
+            kind,
+        ))
+    };
+
+    if store.get_projection(&key).is_err() {
+        append(SessionEventKind::SessionStarted {
+            channel: "audit".to_string(),
+            peer_id: PRIVACY_AGENT.to_string(),
+        })?;
+    }
+    append(SessionEventKind::PeerForgotten {
+        channel: report.channel.clone(),
+        peer_hash: report.peer_hash.clone(),
+        sessions: report.sessions.len(),
+        events: report.events,
+        profile: report.profile,
+        uploads: report.uploads,
+        requested_by: requested_by.to_string(),
+    })?;
+    Ok(())
+}
@@ -0,0 +1,219 @@
+//! Reverse proxy support.
+//!
+//! Behind a proxy every connection comes from the proxy, so the client's
+//! address and scheme are taken from `X-Forwarded-For` and
+//! `X-Forwarded-Proto` instead, but only on connections from a trusted
+//! proxy; anyone else could set those headers to pose as another client.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, header},
+    middleware::Next,
+    response::Response,
+};
+
+use openclaw_core::config::{IpRange, ProxyConfig};
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+const X_FORWARDED_PROTO: &str = "x-forwarded-proto";
+const X_FORWARDED_HOST: &str = "x-forwarded-host";
+
+/// Who sent a request, as seen through any trusted proxies. Inserted into
+/// every request's extensions by [`resolve_client`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientInfo {
+    /// The client's address, if known.
+    pub ip: Option<IpAddr>,
+    /// URL the client reached the gateway at, base path included.
+    pub base_url: String,
+}
+
+/// Resolves clients from connection info and forwarded headers.
+#[derive(Debug, Clone, Default)]
+pub struct ProxyResolver {
+    trusted: Arc<Vec<IpRange>>,
+    base_path: String,
+    public_url: Option<String>,
+}
+
+impl ProxyResolver {
+    /// Create a resolver. Ranges that don't parse are skipped; configuration
+    /// validation reports them.
+    pub fn new(config: &ProxyConfig) -> Self {
+        Self {
+            trusted: Arc::new(config.trusted_ranges().unwrap_or_default()),
+            base_path: config.base_path.clone(),
+            public_url: config
+                .public_url
+                .as_ref()
+                .map(|url| url.trim_end_matches('/').to_string()),
+        }
+    }
+
+    /// Whether `ip` is one of the trusted proxies.
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.trusted.iter().any(|range| range.contains(ip))
+    }
+
+    /// The client behind a request from `peer`.
+    pub fn resolve(&self, peer: Option<IpAddr>, headers: &HeaderMap) -> ClientInfo {
+        let forwarded = peer.is_some_and(|ip| self.is_trusted(ip));
+        let ip = if forwarded {
+            self.forwarded_ip(headers).or(peer)
+        } else {
+            peer
+        };
+        ClientInfo {
+            ip,
+            base_url: self.base_url(forwarded, headers),
+        }
+    }
+
+    /// The client in `X-Forwarded-For`. Each proxy appends the address it
+    /// got the request from, so the client is the last entry that isn't a
+    /// trusted proxy; entries before it are whatever the client claimed.
+    /// The chain is walked from the right and stops at the first entry that
+    /// doesn't parse, so a junk entry from the client can't hide the rest.
+    fn forwarded_ip(&self, headers: &HeaderMap) -> Option<IpAddr> {
+        let entries = headers
+            .get_all(X_FORWARDED_FOR)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','));
+        let mut last_good = None;
+        for entry in entries.rev() {
+            let Ok(ip) = entry.trim().parse::<IpAddr>() else {
+                break;
+            };
+            if !self.is_trusted(ip) {
+                return Some(ip);
+            }
+            last_good = Some(ip);
+        }
+        last_good
+    }
+
+    /// The public URL, or the one the request was made to.
+    fn base_url(&self, forwarded: bool, headers: &HeaderMap) -> String {
+        if let Some(url) = &self.public_url {
+            return url.clone();
+        }
+        let forwarded_header = |name: &str| {
+            forwarded
+                .then(|| headers.get(name)?.to_str().ok())
+                .flatten()
+                .and_then(|value| value.split(',').next())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+        let scheme = forwarded_header(X_FORWARDED_PROTO)
+            .filter(|scheme| matches!(*scheme, "http" | "https"))
+            .unwrap_or("http");
+        let host = forwarded_header(X_FORWARDED_HOST)
+            .or_else(|| headers.get(header::HOST)?.to_str().ok())
+            .unwrap_or("localhost");
+        format!("{scheme}://{host}{}", self.base_path)
+    }
+}
+
+/// Record the [`ClientInfo`] of each request.
+pub async fn resolve_client(
+    State(resolver): State<ProxyResolver>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|info| info.0.ip());
+    let client = resolver.resolve(peer, request.headers());
+    request.extensions_mut().insert(client);
+    next.run(request).await
+}
+
+/// The URL clients reach the gateway at when no request says otherwise:
+/// the public URL, or the listening address and base path.
+pub fn public_base_url(config: &ProxyConfig, bind_address: &str, port: u16) -> String {
+    config.public_url.as_ref().map_or_else(
+        || format!("http://{bind_address}:{port}{}", config.base_path),
+        |url| url.trim_end_matches('/').to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn resolver() -> ProxyResolver {
+        ProxyResolver::new(&ProxyConfig {
+            base_path: "/claw".to_string(),
+            trusted_proxies: vec!["10.0.0.0/8".to_string()],
+            public_url: None,
+        })
+    }
+
+    fn forwarded(chain: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(X_FORWARDED_FOR, HeaderValue::from_static(chain));
+        headers.insert(X_FORWARDED_PROTO, HeaderValue::from_static("https"));
+        headers.insert(header::HOST, HeaderValue::from_static("claw.example.com"));
+        headers
+    }
+
+    #[test]
+    fn test_forwarded_headers_from_trusted_proxy() {
+        let resolver = resolver();
+        let proxy = Some("10.0.0.2".parse().unwrap());
+
+        // A client-supplied entry before the real address is ignored
+        let client = resolver.resolve(proxy, &forwarded("1.2.3.4, 203.0.113.7, 10.0.0.9"));
+        assert_eq!(client.ip, Some("203.0.113.7".parse().unwrap()));
+        assert_eq!(client.base_url, "https://claw.example.com/claw");
+
+        // Garbage falls back to the proxy's address
+        let client = resolver.resolve(proxy, &forwarded("not-an-ip"));
+        assert_eq!(client.ip, proxy);
+    }
+
+    #[test]
+    fn test_junk_forwarded_entry() {
+        let resolver = resolver();
+        let proxy = Some("10.0.0.2".parse().unwrap());
+
+        // Junk the client put first doesn't discard the real address
+        let client = resolver.resolve(proxy, &forwarded("junk, 203.0.113.7, 10.0.0.9"));
+        assert_eq!(client.ip, Some("203.0.113.7".parse().unwrap()));
+
+        // Junk further right stops the walk at the last address before it
+        let client = resolver.resolve(proxy, &forwarded("203.0.113.7, junk, 10.0.0.9"));
+        assert_eq!(client.ip, Some("10.0.0.9".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_forwarded_headers_from_untrusted_peer() {
+        let resolver = resolver();
+        let peer = Some("198.51.100.1".parse().unwrap());
+
+        let client = resolver.resolve(peer, &forwarded("203.0.113.7"));
+        assert_eq!(client.ip, peer);
+        assert_eq!(client.base_url, "http://claw.example.com/claw");
+    }
+
+    #[test]
+    fn test_public_url() {
+        let config = ProxyConfig {
+            public_url: Some("https://example.com/claw/".to_string()),
+            ..ProxyConfig::default()
+        };
+        let client = ProxyResolver::new(&config).resolve(None, &HeaderMap::new());
+        assert_eq!(client.base_url, "https://example.com/claw");
+        assert_eq!(
+            public_base_url(&ProxyConfig::default(), "127.0.0.1", 18789),
+            "http://127.0.0.1:18789"
+        );
+    }
+}
@@ -0,0 +1,44 @@
+//! Permanent removal of soft-deleted sessions.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::sync::RwLock;
+
+use crate::server::GatewayState;
+
+/// How often expired deleted sessions are purged.
+pub const SESSION_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Purge soft-deleted sessions past their retention period, forever.
+pub async fn run_session_purger(state: Arc<RwLock<GatewayState>>) {
+    let mut interval = tokio::time::interval(SESSION_PURGE_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+        purge_expired(&state).await;
+    }
+}
+
+/// Purge every deleted session whose retention period has passed.
+pub async fn purge_expired(state: &Arc<RwLock<GatewayState>>) {
+    let state = state.read().await;
+    let retention = chrono::Duration::days(i64::from(state.config.deleted_session_retention_days));
+
+    for store in state.event_stores() {
+        match store.purge_deleted_before(Utc::now() - retention) {
+            Ok(purged) => {
+                for key in &purged {
+                    state.events.unhide_session(key.as_ref());
+                }
+                if !purged.is_empty() {
+                    tracing::info!("Purged {} deleted session(s)", purged.len());
+                }
+            }
+            Err(e) => tracing::warn!("Failed to purge deleted sessions: {}", e),
+        }
+    }
+    drop(state);
+}
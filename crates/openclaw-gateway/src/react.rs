@@ -0,0 +1,211 @@
+//! Reaction tool.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+
+use openclaw_agents::tools::{DelegationScope, Tool, ToolError, ToolResult};
+use openclaw_channels::{ChannelRegistry, OutboundContext};
+use openclaw_core::events::EventStore;
+use openclaw_core::types::SessionKey;
+
+/// Tool that adds or removes the agent's emoji reaction on a message in
+/// the session's chat.
+///
+/// Reactions go through the session's channel adapter, so they only work
+/// on channels that implement them.
+pub struct ReactTool {
+    channels: Arc<RwLock<ChannelRegistry>>,
+    event_store: Arc<EventStore>,
+}
+
+impl ReactTool {
+    /// Create a reaction tool sending through the adapters in `channels`.
+    #[must_use]
+    pub const fn new(channels: Arc<RwLock<ChannelRegistry>>, event_store: Arc<EventStore>) -> Self {
+        Self {
+            channels,
+            event_store,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ReactTool {
+    fn name(&self) -> &'static str {
+        "react"
+    }
+
+    fn description(&self) -> &'static str {
+        "Add or remove an emoji reaction on a message in the current chat"
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "message_id": {
+                    "type": "string",
+                    "description": "Platform ID of the message to react to"
+                },
+                "emoji": {
+                    "type": "string",
+                    "description": "Emoji to react with"
+                },
+                "remove": {
+                    "type": "boolean",
+                    "description": "Remove the reaction instead of adding it"
+                },
+                "session_key": {
+                    "type": "string",
+                    "description": "Session whose chat the message is in (defaults to the current session)"
+                }
+            },
+            "required": ["message_id", "emoji"]
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value) -> Result<ToolResult, ToolError> {
+        let message_id = params["message_id"]
+            .as_str()
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| {
+                ToolError::InvalidParams("Missing 'message_id' parameter".to_string())
+            })?;
+        let emoji = params["emoji"]
+            .as_str()
+            .filter(|emoji| !emoji.is_empty())
+            .ok_or_else(|| ToolError::InvalidParams("Missing 'emoji' parameter".to_string()))?;
+        let remove = params["remove"].as_bool().unwrap_or(false);
+
+        let session_key = params["session_key"]
+            .as_str()
+            .map(SessionKey::new)
+            .or_else(|| DelegationScope::current().map(|s| s.session_key))
+            .ok_or_else(|| ToolError::InvalidParams("No session to react in".to_string()))?;
+        let projection = self
+            .event_store
+            .get_projection(&session_key)
+            .map_err(|e| ToolError::ExecutionFailed(format!("Unknown session: {e}")))?;
+
+        let channel = self
+            .channels
+            .read()
+            .await
+            .get_outbound(projection.channel.as_ref())
+            .cloned();
+        let Some(channel) = channel else {
+            return Ok(ToolResult::error(format!(
+                "No outbound adapter for channel {}",
+                projection.channel
+            )));
+        };
+        if !channel.capabilities().reactions {
+            return Ok(ToolResult::error(format!(
+                "{} does not support reactions",
+                channel.label()
+            )));
+        }
+
+        let ctx = OutboundContext {
+            chat_id: projection.peer_id,
+            reply_to: None,
+            thread_id: None,
+        };
+        let result = if remove {
+            channel.remove_reaction(ctx, message_id, emoji).await
+        } else {
+            channel.add_reaction(ctx, message_id, emoji).await
+        };
+
+        Ok(match result {
+            Ok(()) if remove => ToolResult::success(format!("Removed {emoji} from {message_id}")),
+            Ok(()) => ToolResult::success(format!("Reacted {emoji} to {message_id}")),
+            Err(e) => ToolResult::error(format!("Reaction failed: {e}")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openclaw_channels::mock::{MockAction, MockChannel};
+    use openclaw_core::events::{SessionEvent, SessionEventKind};
+    use openclaw_core::types::ChannelId;
+
+    use super::*;
+
+    fn started(session_key: &SessionKey, channel: &ChannelId) -> SessionEvent {
+        SessionEvent::new(
+            session_key.clone(),
+            "default".to_string(),
+            SessionEventKind::SessionStarted {
+                channel: channel.to_string(),
+                peer_id: "alice".to_string(),
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_react_through_session_channel() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = Arc::new(EventStore::open(temp.path()).unwrap());
+        let session_key = SessionKey::new("mock:alice");
+        store
+            .append(&started(&session_key, &ChannelId::mock()))
+            .unwrap();
+
+        let mock = Arc::new(MockChannel::new());
+        let mut channels = ChannelRegistry::new();
+        channels.register_outbound(mock.clone());
+        let tool = ReactTool::new(Arc::new(RwLock::new(channels)), store.clone());
+
+        let added = DelegationScope::root(session_key.clone())
+            .run(tool.execute(serde_json::json!({
+                "message_id": "mock-in-1",
+                "emoji": "👍"
+            })))
+            .await
+            .unwrap();
+        assert!(added.success);
+        let removed = tool
+            .execute(serde_json::json!({
+                "message_id": "mock-in-1",
+                "emoji": "👍",
+                "remove": true,
+                "session_key": "mock:alice"
+            }))
+            .await
+            .unwrap();
+        assert!(removed.success);
+
+        let outbound = mock.outbound();
+        assert_eq!(outbound.len(), 2);
+        assert_eq!(outbound[0].chat_id, "alice");
+        assert!(matches!(
+            &outbound[1].action,
+            MockAction::Reaction { target_id, removed: true, .. } if target_id == "mock-in-1"
+        ));
+
+        // Sessions on channels without an adapter can't be reacted in
+        let other = SessionKey::new("telegram:alice");
+        store
+            .append(&started(&other, &ChannelId::telegram()))
+            .unwrap();
+        let missing = tool
+            .execute(serde_json::json!({
+                "message_id": "1",
+                "emoji": "👍",
+                "session_key": "telegram:alice"
+            }))
+            .await
+            .unwrap();
+        assert!(!missing.success);
+
+        assert!(matches!(
+            tool.execute(serde_json::json!({ "message_id": "1", "emoji": "👍" }))
+                .await,
+            Err(ToolError::InvalidParams(_))
+        ));
+    }
+}
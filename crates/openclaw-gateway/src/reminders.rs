@@ -0,0 +1,292 @@
+//! Delayed message delivery for reminders.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::RwLock;
+
+use openclaw_channels::OutboundContext;
+use openclaw_core::events::{PendingReminder, SessionEvent, SessionEventKind};
+
+use crate::events::UiEvent;
+use crate::server::GatewayState;
+
+/// How often due reminders are checked.
+pub const REMINDER_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Tries a reminder gets while its channel has no outbound adapter, e.g.
+/// while the channel restarts, before it is given up on.
+pub const REMINDER_MAX_ATTEMPTS: u32 = 5;
+
+/// Reminders waiting for their channel's adapter, retried with
+/// exponential backoff from [`REMINDER_POLL_INTERVAL`].
+#[derive(Debug, Default)]
+pub struct ReminderRetries {
+    waiting: HashMap<String, Retry>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Retry {
+    attempts: u32,
+    next_at: DateTime<Utc>,
+}
+
+impl ReminderRetries {
+    fn is_waiting(&self, reminder_id: &str, now: DateTime<Utc>) -> bool {
+        self.waiting
+            .get(reminder_id)
+            .is_some_and(|retry| retry.next_at > now)
+    }
+
+    /// Count a failed try, returning whether to give up.
+    fn failed(&mut self, reminder_id: &str, now: DateTime<Utc>) -> bool {
+        let attempts = self.waiting.get(reminder_id).map_or(0, |r| r.attempts) + 1;
+        if attempts >= REMINDER_MAX_ATTEMPTS {
+            self.waiting.remove(reminder_id);
+            return true;
+        }
+        let backoff = REMINDER_POLL_INTERVAL * 2_u32.pow(attempts - 1);
+        let next_at = now + chrono::Duration::from_std(backoff).unwrap_or_default();
+        self.waiting
+            .insert(reminder_id.to_string(), Retry { attempts, next_at });
+        false
+    }
+}
+
+/// How a delivery attempt ended.
+enum Delivery {
+    /// Sent, or given up on; the reminder is no longer pending.
+    Settled,
+    /// The send failed; try again on the next poll.
+    Failed,
+    /// The session's channel has no outbound adapter.
+    NoAdapter(String),
+}
+
+/// Deliver due reminders forever.
+///
+/// Pending reminders live in the event store, so anything that came due
+/// while the gateway was down is sent on the first poll after startup.
+pub async fn run_reminder_dispatcher(state: Arc<RwLock<GatewayState>>) {
+    let mut interval = tokio::time::interval(REMINDER_POLL_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut retries = ReminderRetries::default();
+
+    loop {
+        interval.tick().await;
+        dispatch_due(&state, &mut retries).await;
+    }
+}
+
+/// Send every reminder that is due now and not waiting out a backoff.
+///
+/// A reminder whose channel has no outbound adapter stays pending and is
+/// retried; after [`REMINDER_MAX_ATTEMPTS`] tries it is recorded as failed
+/// and a `reminder_failed` event is broadcast.
+pub async fn dispatch_due(state: &Arc<RwLock<GatewayState>>, retries: &mut ReminderRetries) {
+    let now = Utc::now();
+    let mut due = Vec::new();
+    for store in state.read().await.event_stores() {
+        match store.due_reminders(Utc::now()) {
+            Ok(reminders) => due.extend(reminders),
+            Err(e) => tracing::warn!("Failed to load due reminders: {}", e),
+        }
+    }
+
+    for reminder in due {
+        if retries.is_waiting(&reminder.reminder_id, now) {
+            continue;
+        }
+        match deliver(state, &reminder).await {
+            Delivery::Settled => {
+                retries.waiting.remove(&reminder.reminder_id);
+            }
+            Delivery::Failed => {}
+            Delivery::NoAdapter(channel) => {
+                let error = format!("No outbound adapter for channel {channel}");
+                if retries.failed(&reminder.reminder_id, now) {
+                    record_fired(&*state.read().await, &reminder, None, Some(error));
+                } else {
+                    tracing::warn!(
+                        "Reminder {} not delivered, will retry: {}",
+                        reminder.reminder_id,
+                        error
+                    );
+                }
+            }
+        }
+    }
+}
+
+async fn deliver(state: &Arc<RwLock<GatewayState>>, reminder: &PendingReminder) -> Delivery {
+    let state = state.read().await;
+
+    let projection = match state
+        .store_for_session(&reminder.session_key)
+        .get_projection(&reminder.session_key)
+    {
+        Ok(projection) => projection,
+        Err(e) => {
+            record_fired(
+                &state,
+                reminder,
+                None,
+                Some(format!("Session missing: {e}")),
+            );
+            return Delivery::Settled;
+        }
+    };
+
+    let channel = state
+        .channels
+        .read()
+        .await
+        .get_outbound(projection.channel.as_ref())
+        .cloned();
+
+    let Some(channel) = channel else {
+        return Delivery::NoAdapter(projection.channel.to_string());
+    };
+    let ctx = OutboundContext {
+        chat_id: projection.peer_id.clone(),
+        reply_to: None,
+        thread_id: None,
+    };
+    let message_id = match state
+        .outbound
+        .send(channel.as_ref(), ctx, &reminder.message)
+        .await
+    {
+        Ok(result) => result.message_id,
+        Err(e) => {
+            // Transient failure: leave pending and retry on the next poll
+            tracing::warn!(
+                "Reminder {} delivery failed, will retry: {}",
+                reminder.reminder_id,
+                e
+            );
+            return Delivery::Failed;
+        }
+    };
+
+    let sent = SessionEvent::new(
+        reminder.session_key.clone(),
+        reminder.agent_id.clone(),
+        SessionEventKind::MessageSent {
+            content: reminder.message.clone(),
+            message_id: message_id.clone(),
+        },
+    );
+    if let Err(e) = state.store_for_session(&reminder.session_key).append(&sent) {
+        tracing::warn!("Failed to log reminder message: {}", e);
+    }
+    record_fired(&state, reminder, Some(message_id), None);
+    drop(state);
+    Delivery::Settled
+}
+
+fn record_fired(
+    state: &GatewayState,
+    reminder: &PendingReminder,
+    message_id: Option<String>,
+    error: Option<String>,
+) {
+    if let Some(error) = &error {
+        tracing::warn!("Reminder {} dropped: {}", reminder.reminder_id, error);
+        let _ = state.events.broadcast(UiEvent::ReminderFailed {
+            session_key: reminder.session_key.to_string(),
+            reminder_id: reminder.reminder_id.clone(),
+            error: error.clone(),
+        });
+    }
+
+    let event = SessionEvent::new(
+        reminder.session_key.clone(),
+        reminder.agent_id.clone(),
+        SessionEventKind::ReminderFired {
+            reminder_id: reminder.reminder_id.clone(),
+            message_id,
+            error,
+        },
+    );
+    if let Err(e) = state
+        .store_for_session(&reminder.session_key)
+        .append(&event)
+    {
+        tracing::warn!("Failed to record reminder {}: {}", reminder.reminder_id, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{GatewayBuilder, GatewayConfig};
+    use openclaw_core::events::EventStore;
+    use openclaw_core::types::SessionKey;
+
+    #[tokio::test]
+    async fn test_reminder_without_adapter_is_retried_then_failed() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = Arc::new(EventStore::open(&temp.path().join("events")).unwrap());
+        let gateway = GatewayBuilder::new()
+            .with_config(GatewayConfig {
+                data_dir: temp.path().join("gateway"),
+                ..GatewayConfig::default()
+            })
+            .with_event_store(store.clone())
+            .build()
+            .unwrap();
+        let state = gateway.state();
+
+        let key = SessionKey::new("agent:default:channel:web:account:gateway:dm:alice");
+        for kind in [
+            SessionEventKind::SessionStarted {
+                channel: "web".to_string(),
+                peer_id: "alice".to_string(),
+            },
+            SessionEventKind::ReminderScheduled {
+                reminder_id: "r1".to_string(),
+                message: "Stand up".to_string(),
+                due_at: Utc::now(),
+            },
+        ] {
+            store
+                .append(&SessionEvent::new(key.clone(), "default".to_string(), kind))
+                .unwrap();
+        }
+        let mut events = state.read().await.events.subscribe();
+
+        // Nothing is sent or fired while the channel has no adapter
+        let mut retries = ReminderRetries::default();
+        dispatch_due(state, &mut retries).await;
+        assert_eq!(store.pending_reminders().unwrap().len(), 1);
+        assert!(events.try_recv().is_err());
+
+        // Within the backoff, the reminder is not tried again
+        dispatch_due(state, &mut retries).await;
+        assert_eq!(retries.waiting["r1"].attempts, 1);
+
+        for _ in 1..REMINDER_MAX_ATTEMPTS {
+            retries.waiting.get_mut("r1").unwrap().next_at = Utc::now();
+            dispatch_due(state, &mut retries).await;
+        }
+        assert!(store.pending_reminders().unwrap().is_empty());
+        assert!(retries.waiting.is_empty());
+        let fired = store
+            .get_events(&key)
+            .unwrap()
+            .into_iter()
+            .find_map(|e| match e.kind {
+                SessionEventKind::ReminderFired { error, .. } => Some(error),
+                _ => None,
+            });
+        assert!(fired.flatten().unwrap().contains("No outbound adapter"));
+        let failed = events.try_recv().unwrap();
+        assert!(
+            matches!(failed.event, UiEvent::ReminderFailed { ref reminder_id, .. } if reminder_id == "r1")
+        );
+        assert!(events.try_recv().is_err());
+    }
+}
@@ -86,3 +86,9 @@ pub const UNAUTHORIZED: i32 = -32001;
 pub const FORBIDDEN: i32 = -32002;
 /// Resource not found.
 pub const NOT_FOUND: i32 = -32003;
+/// Request exceeded its timeout.
+pub const TIMEOUT: i32 = -32004;
+/// Gateway is shutting down and not accepting new requests.
+pub const UNAVAILABLE: i32 = -32005;
+/// Too many attempts; try again later.
+pub const TOO_MANY_REQUESTS: i32 = -32006;
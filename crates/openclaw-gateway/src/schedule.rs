@@ -0,0 +1,252 @@
+//! Scheduled task execution.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio::sync::RwLock;
+
+use openclaw_agents::runtime::AgentContext;
+use openclaw_agents::scheduler::{ScheduleError, ScheduleRunner, ScheduledTask};
+use openclaw_agents::workflow::{WorkflowEngine, WorkflowError};
+use openclaw_channels::{CitationStyle, OutboundContext, render_citations};
+use openclaw_core::config::ScheduleDelivery;
+use openclaw_core::events::{SessionEvent, SessionEventKind, SessionProjection};
+use openclaw_core::types::{AgentId, ChannelId, Citation, SessionKey};
+
+use crate::server::GatewayState;
+
+/// Runs scheduled prompts and workflows against the gateway's agents.
+///
+/// Each run gets its own session (`schedule:<id>:<timestamp>`) so the
+/// output shows up in session history like any other conversation.
+pub struct GatewayScheduleRunner {
+    state: Arc<RwLock<GatewayState>>,
+}
+
+impl GatewayScheduleRunner {
+    /// Create a runner over the gateway state.
+    pub const fn new(state: Arc<RwLock<GatewayState>>) -> Self {
+        Self { state }
+    }
+
+    async fn append(&self, event: &SessionEvent) -> Result<(), ScheduleError> {
+        self.state
+            .read()
+            .await
+            .store_for_session(&event.session_key)
+            .append(event)
+            .map(|_| ())
+            .map_err(|e| ScheduleError::Run(format!("Failed to log event: {e}")))
+    }
+
+    async fn run_prompt(
+        &self,
+        task: &ScheduledTask,
+        session_key: &SessionKey,
+        prompt: &str,
+    ) -> Result<(String, Vec<Citation>), ScheduleError> {
+        let agent_id = task.config.agent.as_str();
+        let (agent, tools, _run) = {
+            let state = self.state.read().await;
+            let agent = state
+                .agents
+                .get(agent_id)
+                .cloned()
+                .ok_or_else(|| ScheduleError::Run(format!("Agent not found: {agent_id}")))?;
+            let run = state
+                .shutdown
+                .begin_run()
+                .ok_or_else(|| ScheduleError::Run("Gateway is shutting down".to_string()))?;
+            (agent, state.tool_registry.clone(), run)
+        };
+
+        self.append(&SessionEvent::new(
+            session_key.clone(),
+            agent_id.to_string(),
+            SessionEventKind::MessageReceived {
+                content: prompt.to_string(),
+                attachments: vec![],
+                sender_id: None,
+            },
+        ))
+        .await?;
+
+        let mut ctx = AgentContext::new(
+            AgentId::new(agent_id),
+            session_key.clone(),
+            SessionProjection::new(
+                session_key.clone(),
+                agent_id.to_string(),
+                ChannelId::new("schedule"),
+                task.id.clone(),
+            ),
+            tools,
+        );
+
+        let started = std::time::Instant::now();
+        let reply = agent
+            .process(&mut ctx, prompt)
+            .await
+            .map_err(|e| ScheduleError::Run(format!("Agent error: {e}")))?;
+
+        self.append(&SessionEvent::new(
+            session_key.clone(),
+            agent_id.to_string(),
+            SessionEventKind::AgentResponse {
+                content: reply.content.clone(),
+                model: reply.model,
+                tokens: reply.usage,
+                latency_ms: u64::try_from(started.elapsed().as_millis()).ok(),
+                citations: reply.citations.clone(),
+            },
+        ))
+        .await?;
+
+        Ok((reply.content, reply.citations))
+    }
+
+    async fn run_workflow(
+        &self,
+        task: &ScheduledTask,
+        name: &str,
+    ) -> Result<String, ScheduleError> {
+        let (registry, history) = {
+            let state = self.state.read().await;
+            (state.workflows.clone(), state.run_history.clone())
+        };
+        let output = WorkflowEngine::new()
+            .with_registry(registry)
+            .with_history(history)
+            .execute_by_id(name, task.config.input.clone())
+            .await
+            .map_err(|e| match e {
+                WorkflowError::WorkflowNotFound(_) => {
+                    ScheduleError::Run(format!("Workflow not found: {name}"))
+                }
+                e => ScheduleError::Run(format!("Workflow error: {e}")),
+            })?;
+
+        Ok(match output {
+            serde_json::Value::String(text) => text,
+            other => other.to_string(),
+        })
+    }
+
+    /// Render the task's notification template, with the run's output as
+    /// `output`.
+    async fn render_template(
+        &self,
+        task: &ScheduledTask,
+        template: &str,
+        target: &ScheduleDelivery,
+        output: Option<String>,
+    ) -> Result<String, ScheduleError> {
+        let mut vars = task.config.vars.clone();
+        if let Some(output) = output {
+            vars.insert("output".to_string(), output.into());
+        }
+        self.state
+            .read()
+            .await
+            .config
+            .notifications
+            .render(template, &target.channel, &target.chat_id, &vars)
+            .map_err(|e| ScheduleError::Run(e.to_string()))
+    }
+
+    async fn deliver(
+        &self,
+        task: &ScheduledTask,
+        session_key: &SessionKey,
+        target: &ScheduleDelivery,
+        text: &str,
+    ) -> Result<(), ScheduleError> {
+        let (channel, outbound) = {
+            let state = self.state.read().await;
+            let channel = state
+                .channels
+                .read()
+                .await
+                .get_outbound(&target.channel)
+                .cloned()
+                .ok_or_else(|| {
+                    ScheduleError::Run(format!("Channel not available: {}", target.channel))
+                })?;
+            (channel, state.outbound.clone())
+        };
+
+        let ctx = OutboundContext {
+            chat_id: target.chat_id.clone(),
+            reply_to: None,
+            thread_id: target.thread_id.clone(),
+        };
+        let result = outbound
+            .send(channel.as_ref(), ctx, text)
+            .await
+            .map_err(|e| ScheduleError::Run(format!("Delivery failed: {e}")))?;
+
+        self.append(&SessionEvent::new(
+            session_key.clone(),
+            task.config.agent.clone(),
+            SessionEventKind::MessageSent {
+                content: text.to_string(),
+                message_id: result.message_id,
+            },
+        ))
+        .await
+    }
+}
+
+#[async_trait]
+impl ScheduleRunner for GatewayScheduleRunner {
+    async fn run(
+        &self,
+        task: &ScheduledTask,
+        scheduled_for: DateTime<Utc>,
+    ) -> Result<(), ScheduleError> {
+        let session_key = SessionKey::new(format!(
+            "schedule:{}:{}",
+            task.id,
+            scheduled_for.format("%Y%m%dT%H%M%SZ")
+        ));
+
+        self.append(&SessionEvent::new(
+            session_key.clone(),
+            task.config.agent.clone(),
+            SessionEventKind::SessionStarted {
+                channel: "schedule".to_string(),
+                peer_id: task.id.clone(),
+            },
+        ))
+        .await?;
+
+        let output = match (&task.config.prompt, &task.config.workflow) {
+            (Some(prompt), _) => {
+                let (content, citations) = self.run_prompt(task, &session_key, prompt).await?;
+                // Sources are listed the way the delivery channel shows them
+                Some(match &task.config.deliver {
+                    Some(target) => render_citations(
+                        &content,
+                        &citations,
+                        CitationStyle::for_channel(&ChannelId::new(target.channel.as_str())),
+                    ),
+                    None => content,
+                })
+            }
+            (None, Some(workflow)) => Some(self.run_workflow(task, workflow).await?),
+            (None, None) if task.config.template.is_some() => None,
+            (None, None) => return Err(ScheduleError::Run("Nothing to run".to_string())),
+        };
+
+        if let Some(target) = &task.config.deliver {
+            let text = match &task.config.template {
+                Some(template) => self.render_template(task, template, target, output).await?,
+                None => output.unwrap_or_default(),
+            };
+            self.deliver(task, &session_key, target, &text).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,33 +1,122 @@
 //! Gateway server.
 
 use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::{
     Json, Router,
-    extract::State,
-    extract::ws::{Message, WebSocket, WebSocketUpgrade},
-    response::IntoResponse,
+    error_handling::HandleErrorLayer,
+    extract::ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade, close_code},
+    extract::{ConnectInfo, DefaultBodyLimit, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
     routing::{get, post},
 };
 use chrono::Utc;
 use futures::{SinkExt, StreamExt};
 use tokio::sync::RwLock;
+use tower::ServiceBuilder;
+use tower::timeout::TimeoutLayer;
 
-use openclaw_agents::runtime::{AgentContext, AgentRuntime};
-use openclaw_agents::tools::ToolRegistry;
-use openclaw_channels::{ChannelCapabilities, ChannelRegistry};
+use openclaw_agents::notify::{NotificationTemplates, NotifyError};
+use openclaw_agents::runtime::{
+    AgentContext, AgentRuntime, AgentRuntimeError, ModerationStage, OutputValidator, PeerProfiles,
+    Violation,
+};
+use openclaw_agents::scheduler::{ScheduleStateStore, Scheduler};
+use openclaw_agents::tools::{
+    ApprovalDecision, ApprovalGate, DelegationScope, ToolError, ToolRegistry,
+};
+use openclaw_agents::workflow::{
+    InputOrigin, RunHistoryStore, SuspendedRunStore, Workflow, WorkflowRegistry,
+};
+use openclaw_channels::mock::{self, MockChannel, MockInbound, MockReaction};
+use openclaw_channels::whatsapp::TemplateMessage;
+use openclaw_channels::{
+    AgentRouter, ChannelCapabilities, ChannelContext, ChannelInbound, ChannelReactions,
+    ChannelRegistry, ChatCommand, ChatCommands, CommandAccess, Debounced, DebouncedBatch,
+    Experiment, GroupPolicy, HoursDecision, InboundDebouncer, InboundRateLimiter, OutboundContext,
+    OutboundPipeline, RateLimitDecision, WhatsAppChannel, WorkingHoursPolicy, parse_chat_command,
+    parse_feedback_command, parse_urgent_command, rating_from_reaction,
+};
+use openclaw_core::config::{
+    AgentOverrides, AllowlistEntry, AttachmentPolicyConfig, BroadcastConfig, ChannelFormatConfig,
+    ChannelsConfig, ChatCommandsConfig, CorsConfig, DebounceConfig, EventStoreBackend,
+    EventStoreFormat, ExperimentConfig, ExperimentVariant, GroupsConfig, HealthConfig,
+    IdleSessionConfig, InboundRateLimitConfig, LocaleConfig, OidcConfig, OutsideHoursAction,
+    ProviderRecordingConfig, ProxyConfig, RequestLimitsConfig, RpcRateLimitConfig, ScheduleConfig,
+    UnixSocketConfig, WhatsAppConfig, WireLogConfig, WorkingHoursConfig, WsLagPolicy,
+};
 use openclaw_core::events::{
-    EventStore, SessionEvent, SessionEventKind, SessionMessage, SessionProjection, SessionState,
+    ActivityStats, AttachmentMeta, DryRunMode, EventStore, EventStoreError, FeedbackRating,
+    FeedbackTally, SessionEvent, SessionEventKind, SessionMessage, SessionProjection, SessionState,
+    fork_key,
 };
-use openclaw_core::types::{AgentId, ChannelId, SessionKey};
+use openclaw_core::i18n::{FluentValue, Localizer};
+use openclaw_core::peers::{PeerProfileError, PeerProfileStore, ProfileSource, ProfileUpdate};
+use openclaw_core::secrets::EncryptionKey;
+use openclaw_core::storage::{BlobStore, BlockingBlobStore};
+use openclaw_core::types::{AgentId, Attachment, ChannelId, SessionKey};
+use openclaw_providers::traits::ResponseFormat;
 
 use crate::GatewayError;
-use crate::auth::{AuthConfig, AuthState, JwtManager, User, UserRole, setup::auto_setup_from_env};
-use crate::events::EventBroadcaster;
+use crate::after_hours::{
+    AfterHoursQueue, HeldMessage, outside_hours_notice, run_after_hours_queue,
+};
+use crate::approvals::run_approval_forwarder;
+use crate::archive::run_session_archiver;
+use crate::attachment_policy::{AttachmentPolicy, Rejection, RejectionReason};
+use crate::auth::{
+    AuthConfig, AuthError, AuthState, JwtManager, OIDC_CALLBACK_ROUTE, OIDC_LOGIN_ROUTE,
+    OidcClient, ThrottleKey, User, UserRole, setup::auto_setup_from_env,
+};
+use crate::backup::{BackupSchedule, run_backup_scheduler};
+use crate::broadcast::{self, Broadcast, BroadcastError, BroadcastStatus, BroadcastTargets};
+use crate::catalog;
+use crate::cluster::{Cluster, ClusterConfig};
+use crate::control::GatewayControl;
+use crate::events::{ConfigChange, EventBroadcaster, UiEvent};
+use crate::health::{ProviderProbeCache, liveness_handler, readiness_handler};
+use crate::lifecycle::run_idle_session_closer;
+use crate::limits::{BodyGuard, LimitCounters, guard_body, is_message_too_long};
+use crate::logging::LogFilter;
+use crate::mcp::mcp_handler;
+use crate::middleware::{
+    GatewayRateLimiter, RateLimitCaller, cors_layer, origin_allowed, rate_limited_response,
+    retry_after,
+};
+use crate::mock::{answer_mock_message, answer_mock_reaction, run_mock_inbound};
+use crate::privacy;
+use crate::proxy::{ClientInfo, ProxyResolver, public_base_url, resolve_client};
+use crate::purge::run_session_purger;
+use crate::reminders::run_reminder_dispatcher;
 use crate::rpc::{self, RpcRequest, RpcResponse};
+use crate::schedule::GatewayScheduleRunner;
+use crate::shutdown::{Shutdown, drain_on_request, request_on_signal};
+use crate::sse::sse_handler;
+use crate::sso::{oidc_callback_handler, oidc_login_handler};
+use crate::startup::{BootTimer, StartupReport, probe_channels};
+use crate::supervisor::{ChannelHealth, ChannelSupervisor, SupervisorError};
+use crate::sweep::run_rate_limit_sweeper;
+use crate::tenancy::{NamespaceAccess, NamespaceConfig, Tenants};
+use crate::timeouts::{RequestTimeouts, route_timeout_error, timeout_response};
+use crate::transcript::{TRANSCRIPT_ROUTE, transcript_handler};
+use crate::transfer::{
+    MAX_CHUNK_SIZE, UPLOAD_ROUTE, UPLOADS_ROUTE, UploadResolver, UploadStore, WORKSPACE_FILE_ROUTE,
+    start_upload_handler, upload_chunk_handler, upload_status_handler, workspace_file_handler,
+};
+use crate::triggers::{
+    TriggerDispatcher, WORKFLOW_HOOK_ROUTE, run_cron_triggers, run_event_triggers,
+    workflow_hook_handler,
+};
+use crate::whatsapp::{
+    WHATSAPP_HOOK_ROUTE, whatsapp_channel, whatsapp_hook_handler, whatsapp_verify_handler,
+};
+use crate::workflow_input::{resume_run, run_input_timeouts, start_workflow};
+use crate::workspace::{DEFAULT_READ_LIMIT, Workspace, WorkspaceError};
 
 #[cfg(feature = "ui")]
 use crate::ui_server::UiServerConfig;
@@ -39,12 +128,102 @@ pub struct GatewayConfig {
     pub port: u16,
     /// Bind address.
     pub bind_address: String,
-    /// Enable CORS.
-    pub cors: bool,
+    /// Cross-origin access for browsers.
+    pub cors: CorsConfig,
+    /// Per-route and per-method request timeouts.
+    pub timeouts: RequestTimeouts,
     /// Data directory for persistent storage.
     pub data_dir: PathBuf,
+    /// Root of agent and session workspaces browsable from the UI.
+    pub workspace_dir: PathBuf,
+    /// Days a soft-deleted session is kept before it is purged.
+    pub deleted_session_retention_days: u32,
+    /// Directory ended sessions are archived to when there is no blob store.
+    pub archive_dir: PathBuf,
+    /// Days after which ended sessions are archived (`None` to disable).
+    pub archive_ended_after_days: Option<u32>,
+    /// Ending of inactive sessions.
+    pub idle_sessions: IdleSessionConfig,
+    /// Key for encrypting the event store at rest (`None` for plaintext).
+    pub event_store_key: Option<EncryptionKey>,
+    /// Encoding for newly written session events.
+    pub event_store_format: EventStoreFormat,
+    /// Storage engine for the event store.
+    pub event_store_backend: EventStoreBackend,
+    /// Scheduled backups (`None` to disable).
+    pub backup: Option<BackupSchedule>,
+    /// Blob store for uploads and session archives (`None` keeps uploads
+    /// under the data directory and archives in `archive_dir`).
+    pub blobs: Option<BlobStore>,
     /// Authentication configuration.
     pub auth: AuthConfig,
+    /// Inbound message rate limiting.
+    pub inbound_rate_limit: InboundRateLimitConfig,
+    /// Outbound text formatting overrides by channel ID.
+    pub outbound_formatting: HashMap<String, ChannelFormatConfig>,
+    /// Coalescing of rapid consecutive inbound messages.
+    pub inbound_debounce: DebounceConfig,
+    /// Hours when channels and agents answer.
+    pub working_hours: WorkingHoursConfig,
+    /// Etiquette in group chats.
+    pub groups: GroupsConfig,
+    /// Slash commands answered without the agent.
+    pub commands: ChatCommandsConfig,
+    /// Checks on inbound attachments.
+    pub attachment_policy: AttachmentPolicyConfig,
+    /// Language of system messages sent to peers.
+    pub locale: LocaleConfig,
+    /// IDs of channels that stay stopped until enabled.
+    pub disabled_channels: Vec<String>,
+    /// A/B experiments by ID.
+    pub experiments: HashMap<String, ExperimentConfig>,
+    /// Scheduled tasks by ID.
+    pub schedules: HashMap<String, ScheduleConfig>,
+    /// Outbound notification templates.
+    pub notifications: NotificationTemplates,
+    /// Broadcast groups and pacing.
+    pub broadcast: BroadcastConfig,
+    /// Allowlist entries of every agent, for broadcasts to allowlisted chats.
+    pub allowlist: Vec<AllowlistEntry>,
+    /// Handle for changing the log filter at runtime, if the host installed
+    /// a reloadable one.
+    pub log_filter: Option<LogFilter>,
+    /// Where the configuration was loaded from, for the startup report.
+    pub config_source: Option<String>,
+    /// Boot time above which startup logs a warning.
+    pub boot_budget: Duration,
+    /// How long shutdown waits for in-flight agent runs.
+    pub drain_timeout: Duration,
+    /// Serve the MCP endpoint at `/mcp`.
+    pub mcp: bool,
+    /// IPC address for the daemon control socket (`None` to disable).
+    pub control_address: Option<String>,
+    /// Tenant namespaces by ID.
+    pub namespaces: HashMap<String, NamespaceConfig>,
+    /// Distributed mode (`None` to run everything in this process).
+    pub cluster: Option<ClusterConfig>,
+    /// Readiness check settings.
+    pub health: HealthConfig,
+    /// Request body and WebSocket limits.
+    pub limits: RequestLimitsConfig,
+    /// RPC request rates per caller.
+    pub rate_limit: RpcRateLimitConfig,
+    /// Base path, trusted proxies and public URL.
+    pub proxy: ProxyConfig,
+    /// Unix domain socket to listen on (`None` for TCP only).
+    pub unix_socket: Option<UnixSocketConfig>,
+    /// Single sign-on provider (`None` for local passwords only).
+    pub oidc: Option<OidcConfig>,
+    /// `WhatsApp` Cloud API credentials (`None` to leave the channel out).
+    /// With an app secret, inbound messages are accepted at
+    /// `/hooks/whatsapp`.
+    pub whatsapp: Option<WhatsAppConfig>,
+    /// Provider wire log, scrubbed of a peer's messages when they are
+    /// forgotten.
+    pub wire_log: WireLogConfig,
+    /// Recorded provider calls, scrubbed of a peer's messages when they
+    /// are forgotten.
+    pub recording: ProviderRecordingConfig,
     /// UI server configuration (optional, requires "ui" feature).
     #[cfg(feature = "ui")]
     pub ui: Option<UiServerConfig>,
@@ -60,9 +239,51 @@ impl Default for GatewayConfig {
         Self {
             port: 18789,
             bind_address: "127.0.0.1".to_string(),
-            cors: true,
+            cors: CorsConfig::default(),
+            timeouts: RequestTimeouts::default(),
             data_dir,
+            workspace_dir: openclaw_core::Config::workspace_dir(),
+            deleted_session_retention_days: 30,
+            archive_dir: openclaw_core::Config::state_dir().join("archive"),
+            archive_ended_after_days: None,
+            idle_sessions: IdleSessionConfig::default(),
+            event_store_key: None,
+            event_store_format: EventStoreFormat::default(),
+            event_store_backend: EventStoreBackend::default(),
+            backup: None,
+            blobs: None,
             auth: AuthConfig::default(),
+            inbound_rate_limit: InboundRateLimitConfig::default(),
+            outbound_formatting: HashMap::new(),
+            inbound_debounce: DebounceConfig::default(),
+            working_hours: WorkingHoursConfig::default(),
+            groups: GroupsConfig::default(),
+            commands: ChatCommandsConfig::default(),
+            attachment_policy: AttachmentPolicyConfig::default(),
+            locale: LocaleConfig::default(),
+            disabled_channels: Vec::new(),
+            experiments: HashMap::new(),
+            schedules: HashMap::new(),
+            notifications: NotificationTemplates::default(),
+            broadcast: BroadcastConfig::default(),
+            allowlist: Vec::new(),
+            log_filter: None,
+            config_source: None,
+            boot_budget: Duration::from_secs(5),
+            drain_timeout: Duration::from_secs(30),
+            mcp: false,
+            control_address: None,
+            namespaces: HashMap::new(),
+            cluster: None,
+            health: HealthConfig::default(),
+            limits: RequestLimitsConfig::default(),
+            rate_limit: RpcRateLimitConfig::default(),
+            proxy: ProxyConfig::default(),
+            unix_socket: None,
+            oidc: None,
+            whatsapp: None,
+            wire_log: WireLogConfig::default(),
+            recording: ProviderRecordingConfig::default(),
             #[cfg(feature = "ui")]
             ui: Some(UiServerConfig::default()),
         }
@@ -75,22 +296,131 @@ pub struct GatewayState {
     pub event_store: Arc<EventStore>,
     /// Agent runtimes by agent ID.
     pub agents: HashMap<String, Arc<AgentRuntime>>,
+    /// Tenant namespaces.
+    pub tenants: Arc<Tenants>,
     /// Shared tool registry.
     pub tool_registry: Arc<ToolRegistry>,
+    /// Tool calls waiting for operator approval.
+    pub approvals: Arc<ApprovalGate>,
+    /// Workflows by name (for scheduled tasks and sub-workflows).
+    pub workflows: Arc<WorkflowRegistry>,
+    /// Workflow runs waiting for input.
+    pub waiting_runs: Arc<SuspendedRunStore>,
+    /// Records of past and current workflow runs.
+    pub run_history: Arc<RunHistoryStore>,
+    /// Profiles of the people agents talk to.
+    pub peer_profiles: Arc<PeerProfileStore>,
+    /// System messages by locale.
+    pub localizer: Arc<Localizer>,
+    /// Workflow triggers.
+    pub triggers: Arc<TriggerDispatcher>,
     /// Authentication state.
     pub auth: Arc<AuthState>,
     /// Channel registry.
     pub channels: Arc<RwLock<ChannelRegistry>>,
+    /// Inbound message rate limiter.
+    pub inbound_limiter: Arc<InboundRateLimiter>,
+    /// Formats and chunks outbound text per channel.
+    pub outbound: Arc<OutboundPipeline>,
+    /// Coalesces rapid consecutive inbound messages.
+    pub debouncer: Arc<InboundDebouncer>,
+    /// Working hours of channels and agents.
+    pub working_hours: Arc<WorkingHoursPolicy>,
+    /// Messages waiting for working hours to resume.
+    pub after_hours: Arc<AfterHoursQueue>,
+    /// Etiquette in group chats.
+    pub groups: Arc<GroupPolicy>,
+    /// Slash commands answered without the agent.
+    pub chat_commands: Arc<ChatCommands>,
+    /// Checks inbound attachments.
+    pub attachment_policy: Arc<AttachmentPolicy>,
+    /// Files uploaded from the web UI.
+    pub uploads: UploadStore,
+    /// Agent router (rules and experiments).
+    pub router: Arc<AgentRouter>,
     /// UI event broadcaster.
     pub events: EventBroadcaster,
+    /// Startup report, once boot has finished.
+    pub startup: Option<StartupReport>,
+    /// Shutdown coordination and in-flight agent run tracking.
+    pub shutdown: Arc<Shutdown>,
+    /// Cluster connection, once joined (distributed mode only).
+    pub cluster: Option<Arc<Cluster>>,
+    /// Keeps channels running.
+    pub supervisor: Arc<ChannelSupervisor>,
+    /// Cached provider reachability for readiness checks.
+    pub provider_probes: Arc<ProviderProbeCache>,
+    /// Requests and connections refused by the limits.
+    pub limit_counters: Arc<LimitCounters>,
+    /// RPC rate limiter.
+    pub rate_limiter: Arc<GatewayRateLimiter>,
+    /// Single sign-on client, if configured.
+    pub oidc: Option<Arc<OidcClient>>,
+    /// Mock channel for development, if enabled.
+    pub mock_channel: Option<Arc<MockChannel>>,
+    /// `WhatsApp` channel, if configured.
+    pub whatsapp: Option<Arc<WhatsAppChannel>>,
     /// Gateway configuration.
     pub config: GatewayConfig,
 }
 
+impl GatewayState {
+    /// The event store holding an agent's sessions: its namespace's, or
+    /// the main store.
+    #[must_use]
+    pub fn store_for_agent(&self, agent_id: &str) -> &Arc<EventStore> {
+        self.tenants
+            .namespace_of_agent(agent_id)
+            .map_or(&self.event_store, |ns| &ns.event_store)
+    }
+
+    /// The event store holding a session, found from the agent in its key.
+    #[must_use]
+    pub fn store_for_session(&self, session_key: &SessionKey) -> &Arc<EventStore> {
+        if let Some(agent_id) = session_key.agent_id() {
+            return self.store_for_agent(agent_id);
+        }
+        // Keys chosen by clients don't name an agent, so look for the session
+        self.tenants
+            .iter()
+            .map(|ns| &ns.event_store)
+            .find(|store| store.get_projection(session_key).is_ok())
+            .unwrap_or(&self.event_store)
+    }
+
+    /// Every event store: the main one, then each namespace's.
+    pub fn event_stores(&self) -> impl Iterator<Item = &Arc<EventStore>> {
+        std::iter::once(&self.event_store).chain(self.tenants.iter().map(|ns| &ns.event_store))
+    }
+
+    /// Every event store with the namespace it belongs to (`None` for the
+    /// main one).
+    pub fn namespaced_event_stores(
+        &self,
+    ) -> impl Iterator<Item = (Option<&str>, &Arc<EventStore>)> {
+        std::iter::once((None, &self.event_store)).chain(
+            self.tenants
+                .iter()
+                .map(|ns| (Some(ns.id.as_str()), &ns.event_store)),
+        )
+    }
+
+    /// What the holder of `token` may access.
+    #[must_use]
+    pub fn namespace_access(&self, token: Option<&str>) -> NamespaceAccess {
+        if !self.auth.config.enabled {
+            return NamespaceAccess::All;
+        }
+        let claims = token.and_then(|t| self.auth.validate_token(t).ok());
+        self.tenants.access(claims.as_ref())
+    }
+}
+
 /// Gateway server.
 pub struct Gateway {
     config: GatewayConfig,
     state: Arc<RwLock<GatewayState>>,
+    boot: BootTimer,
 }
 
 /// Builder for constructing a Gateway with its dependencies.
@@ -99,9 +429,15 @@ pub struct GatewayBuilder {
     event_store: Option<Arc<EventStore>>,
     agents: HashMap<String, Arc<AgentRuntime>>,
     tool_registry: Arc<ToolRegistry>,
+    approvals: Arc<ApprovalGate>,
+    workflows: HashMap<String, Arc<Workflow>>,
     auth_state: Option<Arc<AuthState>>,
     channel_registry: Option<Arc<RwLock<ChannelRegistry>>>,
+    inbound_limiter: Option<Arc<InboundRateLimiter>>,
+    outbound: Option<OutboundPipeline>,
+    router: Option<Arc<AgentRouter>>,
     event_broadcaster: Option<EventBroadcaster>,
+    mock_channel: Option<Arc<MockChannel>>,
 }
 
 impl GatewayBuilder {
@@ -113,9 +449,15 @@ impl GatewayBuilder {
             event_store: None,
             agents: HashMap::new(),
             tool_registry: Arc::new(ToolRegistry::new()),
+            approvals: Arc::new(ApprovalGate::new()),
+            workflows: HashMap::new(),
             auth_state: None,
             channel_registry: None,
+            inbound_limiter: None,
+            outbound: None,
+            router: None,
             event_broadcaster: None,
+            mock_channel: None,
         }
     }
 
@@ -147,6 +489,21 @@ impl GatewayBuilder {
         self
     }
 
+    /// Set the approval gate shared with agents' approval-wrapped tools
+    /// (see `ToolRegistry::require_approval`).
+    #[must_use]
+    pub fn with_approval_gate(mut self, gate: Arc<ApprovalGate>) -> Self {
+        self.approvals = gate;
+        self
+    }
+
+    /// Register a workflow that scheduled tasks can run.
+    #[must_use]
+    pub fn with_workflow(mut self, name: impl Into<String>, workflow: Arc<Workflow>) -> Self {
+        self.workflows.insert(name.into(), workflow);
+        self
+    }
+
     /// Set the auth state.
     #[must_use]
     pub fn with_auth_state(mut self, auth: Arc<AuthState>) -> Self {
@@ -161,6 +518,28 @@ impl GatewayBuilder {
         self
     }
 
+    /// Set the inbound message rate limiter.
+    #[must_use]
+    pub fn with_inbound_limiter(mut self, limiter: Arc<InboundRateLimiter>) -> Self {
+        self.inbound_limiter = Some(limiter);
+        self
+    }
+
+    /// Set the outbound formatting pipeline, e.g. one with custom
+    /// formatters. Config overrides from [`GatewayConfig`] are applied to it.
+    #[must_use]
+    pub fn with_outbound_pipeline(mut self, pipeline: OutboundPipeline) -> Self {
+        self.outbound = Some(pipeline);
+        self
+    }
+
+    /// Set the agent router.
+    #[must_use]
+    pub fn with_router(mut self, router: Arc<AgentRouter>) -> Self {
+        self.router = Some(router);
+        self
+    }
+
     /// Set the event broadcaster.
     #[must_use]
     pub fn with_event_broadcaster(mut self, broadcaster: EventBroadcaster) -> Self {
@@ -168,12 +547,21 @@ impl GatewayBuilder {
         self
     }
 
+    /// Enable the mock channel. Its messages are answered like any other
+    /// channel's, and it can be driven with `mock.inject`.
+    #[must_use]
+    pub fn with_mock_channel(mut self, channel: Arc<MockChannel>) -> Self {
+        self.mock_channel = Some(channel);
+        self
+    }
+
     /// Build the gateway.
     ///
     /// # Errors
     ///
     /// Returns error if event store is not configured or auth initialization fails.
     pub fn build(self) -> Result<Gateway, GatewayError> {
+        let mut boot = BootTimer::start();
         let event_store = self
             .event_store
             .ok_or_else(|| GatewayError::Config("Event store is required".to_string()))?;
@@ -197,28 +585,114 @@ impl GatewayBuilder {
         if let Err(e) = auto_setup_from_env(&auth.users) {
             tracing::warn!("Auto-setup from env failed: {}", e);
         }
+        boot.mark("auth");
 
         // Initialize channel registry
         let channels = self
             .channel_registry
             .unwrap_or_else(|| Arc::new(RwLock::new(ChannelRegistry::new())));
 
+        // Initialize inbound rate limiter
+        let inbound_limiter = self
+            .inbound_limiter
+            .unwrap_or_else(|| Arc::new(InboundRateLimiter::new(&self.config.inbound_rate_limit)));
+
+        // Initialize outbound formatting
+        let outbound = Arc::new(
+            self.outbound
+                .unwrap_or_default()
+                .reconfigured(self.config.outbound_formatting.clone()),
+        );
+        let debouncer = Arc::new(InboundDebouncer::new(&self.config.inbound_debounce));
+        let working_hours = Arc::new(WorkingHoursPolicy::new(&self.config.working_hours));
+        let uploads = UploadStore::new(
+            self.config.data_dir.join("uploads"),
+            open_blobs(&self.config)?,
+        );
+        let attachment_policy = Arc::new(AttachmentPolicy::new(
+            self.config.attachment_policy.clone(),
+            Arc::new(UploadResolver::new(uploads.clone())),
+        ));
+
+        // Initialize router
+        let router = self
+            .router
+            .unwrap_or_else(|| Arc::new(router_from_config(&self.config)));
+
         // Initialize event broadcaster
         let events = self.event_broadcaster.unwrap_or_default();
 
+        let tenants = Tenants::open(
+            &self.config.data_dir,
+            &self.config.namespaces,
+            self.config.event_store_key.as_ref(),
+            self.config.event_store_format,
+            self.config.event_store_backend,
+            archive_blobs(&self.config)?.as_ref(),
+        )
+        .map_err(|e| GatewayError::Config(e.to_string()))?;
+        let waiting_runs = open_waiting_runs(&self.config)?;
+        let run_history = open_run_history(&self.config)?;
+        let peer_profiles = open_peer_profiles(&self.config)?;
+        let oidc = open_oidc(&self.config)?;
+        let localizer = open_localizer(&self.config.locale)?;
+        // Agents see what is known about the person they are talking to
+        let agents = self
+            .agents
+            .into_iter()
+            .map(|(id, runtime)| {
+                let runtime = (*runtime)
+                    .clone()
+                    .with_context_provider(Arc::new(PeerProfiles::new(peer_profiles.clone())));
+                (id, Arc::new(runtime))
+            })
+            .collect();
+        let workflows: WorkflowRegistry = self.workflows.into_iter().collect();
+        let triggers = TriggerDispatcher::new(&workflows)
+            .map_err(|e| GatewayError::Config(format!("Workflow triggers: {e}")))?;
+
         let state = GatewayState {
             event_store,
-            agents: self.agents,
+            agents,
+            tenants: Arc::new(tenants),
             tool_registry: self.tool_registry,
+            approvals: self.approvals,
+            workflows: Arc::new(workflows),
+            waiting_runs,
+            run_history,
+            peer_profiles,
+            localizer,
+            triggers: Arc::new(triggers),
             auth,
             channels,
+            inbound_limiter,
+            outbound,
+            debouncer,
+            working_hours,
+            after_hours: Arc::new(AfterHoursQueue::default()),
+            groups: Arc::new(GroupPolicy::new(self.config.groups.clone())),
+            chat_commands: Arc::new(ChatCommands::new(self.config.commands.clone())),
+            attachment_policy,
+            uploads,
+            router,
             events,
+            startup: None,
+            shutdown: Arc::new(Shutdown::new()),
+            cluster: None,
+            supervisor: Arc::new(ChannelSupervisor::default()),
+            provider_probes: Arc::new(ProviderProbeCache::new()),
+            limit_counters: Arc::default(),
+            rate_limiter: Arc::new(GatewayRateLimiter::new(&self.config.rate_limit)),
+            oidc,
+            mock_channel: self.mock_channel,
+            whatsapp: whatsapp_channel(self.config.whatsapp.as_ref()),
             config: self.config.clone(),
         };
 
         Ok(Gateway {
             config: self.config,
             state: Arc::new(RwLock::new(state)),
+            boot,
         })
     }
 }
@@ -229,18 +703,125 @@ impl Default for GatewayBuilder {
     }
 }
 
+/// Create the single sign-on client, if configured.
+fn open_oidc(config: &GatewayConfig) -> Result<Option<Arc<OidcClient>>, GatewayError> {
+    config
+        .oidc
+        .clone()
+        .map(|oidc| {
+            OidcClient::new(oidc)
+                .map(Arc::new)
+                .map_err(|e| GatewayError::Config(format!("OIDC: {e}")))
+        })
+        .transpose()
+}
+
+/// Open the blob store for uploads, falling back to one under the data
+/// directory.
+fn open_blobs(config: &GatewayConfig) -> Result<BlobStore, GatewayError> {
+    config.blobs.clone().map_or_else(
+        || {
+            BlobStore::local(&config.data_dir.join("blobs"))
+                .map_err(|e| GatewayError::Config(format!("Blob store: {e}")))
+        },
+        Ok,
+    )
+}
+
+/// Blocking handle on the configured blob store for session archives.
+fn archive_blobs(config: &GatewayConfig) -> Result<Option<BlockingBlobStore>, GatewayError> {
+    config
+        .blobs
+        .as_ref()
+        .map(|blobs| {
+            blobs
+                .blocking()
+                .map_err(|e| GatewayError::Config(format!("Blob store: {e}")))
+        })
+        .transpose()
+}
+
+/// Open the store of workflow runs waiting for input.
+fn open_waiting_runs(config: &GatewayConfig) -> Result<Arc<SuspendedRunStore>, GatewayError> {
+    SuspendedRunStore::open(&config.data_dir.join("workflows").join("waiting"))
+        .map(Arc::new)
+        .map_err(|e| GatewayError::Config(format!("Waiting workflow runs: {e}")))
+}
+
+/// Open the store of workflow run records.
+fn open_run_history(config: &GatewayConfig) -> Result<Arc<RunHistoryStore>, GatewayError> {
+    RunHistoryStore::open(&config.data_dir.join("workflows").join("runs"))
+        .map(Arc::new)
+        .map_err(|e| GatewayError::Config(format!("Workflow run history: {e}")))
+}
+
+/// Open the store of peer profiles.
+fn open_peer_profiles(config: &GatewayConfig) -> Result<Arc<PeerProfileStore>, GatewayError> {
+    PeerProfileStore::open(&config.data_dir.join("peers"))
+        .map(Arc::new)
+        .map_err(|e| GatewayError::Config(format!("Peer profiles: {e}")))
+}
+
+/// Load system messages, with the configured locale directory's.
+pub fn open_localizer(config: &LocaleConfig) -> Result<Arc<Localizer>, GatewayError> {
+    let localizer = Localizer::builtin();
+    let localizer = match &config.dir {
+        Some(dir) => localizer
+            .with_dir(dir)
+            .map_err(|e| GatewayError::Config(format!("Locales in {}: {e}", dir.display())))?,
+        None => localizer,
+    };
+    Ok(Arc::new(localizer))
+}
+
+/// Build the agent router from gateway configuration.
+fn router_from_config(config: &GatewayConfig) -> AgentRouter {
+    let mut router = AgentRouter::default();
+    for (id, experiment) in &config.experiments {
+        router.add_experiment(Experiment::from_config(id, experiment));
+    }
+    router
+}
+
 impl Gateway {
     /// Create a new gateway (for backward compatibility).
     pub fn new(config: GatewayConfig) -> Result<Self, GatewayError> {
+        let mut boot = BootTimer::start();
+
         // Ensure data directory exists
         std::fs::create_dir_all(&config.data_dir)
             .map_err(|e| GatewayError::Config(format!("Failed to create data dir: {e}")))?;
 
         // Create event store in data directory
-        let event_store = Arc::new(
-            EventStore::open(&config.data_dir.join("events"))
-                .map_err(|e| GatewayError::Server(format!("Failed to open event store: {e}")))?,
-        );
+        let events_path = config.data_dir.join("events");
+        let archive_blobs = archive_blobs(&config)?;
+        let mut event_store = EventStore::open_with(
+            &events_path,
+            config.event_store_backend,
+            config.event_store_key.clone(),
+        )
+        .map_err(|e| GatewayError::Server(format!("Failed to open event store: {e}")))?
+        .with_archive_dir(&config.archive_dir)
+        .with_format(config.event_store_format);
+        if let Some(blobs) = &archive_blobs {
+            event_store = event_store.with_archive_blobs(blobs.clone(), "sessions");
+        }
+        let event_store = Arc::new(event_store);
+        let tenants = Tenants::open(
+            &config.data_dir,
+            &config.namespaces,
+            config.event_store_key.as_ref(),
+            config.event_store_format,
+            config.event_store_backend,
+            archive_blobs.as_ref(),
+        )
+        .map_err(|e| GatewayError::Config(e.to_string()))?;
+        let waiting_runs = open_waiting_runs(&config)?;
+        let run_history = open_run_history(&config)?;
+        let peer_profiles = open_peer_profiles(&config)?;
+        let oidc = open_oidc(&config)?;
+        let localizer = open_localizer(&config.locale)?;
+        boot.mark("event_store");
 
         // Initialize auth
         let auth_config = config.auth.clone().with_env_overrides();
@@ -253,62 +834,502 @@ impl Gateway {
         if let Err(e) = auto_setup_from_env(&auth.users) {
             tracing::warn!("Auto-setup from env failed: {}", e);
         }
+        boot.mark("auth");
 
+        let uploads = UploadStore::new(config.data_dir.join("uploads"), open_blobs(&config)?);
         let state = GatewayState {
             event_store,
             agents: HashMap::new(),
+            tenants: Arc::new(tenants),
             tool_registry: Arc::new(ToolRegistry::new()),
+            approvals: Arc::new(ApprovalGate::new()),
+            workflows: Arc::new(WorkflowRegistry::new()),
+            waiting_runs,
+            run_history,
+            peer_profiles,
+            localizer,
+            triggers: Arc::default(),
             auth,
             channels: Arc::new(RwLock::new(ChannelRegistry::new())),
+            inbound_limiter: Arc::new(InboundRateLimiter::new(&config.inbound_rate_limit)),
+            outbound: Arc::new(OutboundPipeline::new(config.outbound_formatting.clone())),
+            debouncer: Arc::new(InboundDebouncer::new(&config.inbound_debounce)),
+            working_hours: Arc::new(WorkingHoursPolicy::new(&config.working_hours)),
+            after_hours: Arc::new(AfterHoursQueue::default()),
+            groups: Arc::new(GroupPolicy::new(config.groups.clone())),
+            chat_commands: Arc::new(ChatCommands::new(config.commands.clone())),
+            attachment_policy: Arc::new(AttachmentPolicy::new(
+                config.attachment_policy.clone(),
+                Arc::new(UploadResolver::new(uploads.clone())),
+            )),
+            uploads,
+            router: Arc::new(router_from_config(&config)),
             events: EventBroadcaster::new(),
+            startup: None,
+            shutdown: Arc::new(Shutdown::new()),
+            cluster: None,
+            supervisor: Arc::new(ChannelSupervisor::default()),
+            provider_probes: Arc::new(ProviderProbeCache::new()),
+            limit_counters: Arc::default(),
+            rate_limiter: Arc::new(GatewayRateLimiter::new(&config.rate_limit)),
+            oidc,
+            mock_channel: None,
+            whatsapp: whatsapp_channel(config.whatsapp.as_ref()),
             config: config.clone(),
         };
 
         Ok(Self {
             config,
             state: Arc::new(RwLock::new(state)),
+            boot,
         })
     }
 
+    /// The gateway's UI event broadcaster.
+    ///
+    /// Subscribe before calling [`Gateway::run`] to receive the startup report.
+    pub async fn events(&self) -> EventBroadcaster {
+        self.state.read().await.events.clone()
+    }
+
+    /// Shared state, for exercising handlers in tests.
+    #[cfg(test)]
+    pub(crate) const fn state(&self) -> &Arc<RwLock<GatewayState>> {
+        &self.state
+    }
+
     /// Run the gateway server.
     ///
     /// Starts the API server and optionally the UI server (if the "ui" feature is enabled
     /// and UI configuration is present).
     pub async fn run(&self) -> Result<(), GatewayError> {
         let state = self.state.clone();
+        let mut boot = self.boot.clone();
 
         // Check for bootstrap requirement
         {
             let state_read = state.read().await;
             let mut bootstrap = state_read.auth.bootstrap.write().await;
             if let Some(_token) = bootstrap.check_and_generate(&state_read.auth.users) {
-                let base_url = format!("http://{}:{}", self.config.bind_address, self.config.port);
-                bootstrap.print_bootstrap_info(&base_url);
+                bootstrap.print_bootstrap_info(&self.public_base_url());
+            }
+        }
+        boot.mark("bootstrap");
+
+        // Keep soft-deleted sessions out of live UI events
+        {
+            let state_read = state.read().await;
+            for store in state_read.event_stores() {
+                for key in store.list_sessions().unwrap_or_default() {
+                    if store.get_projection(&key).is_ok_and(|p| p.is_deleted()) {
+                        state_read.events.hide_session(key.as_ref());
+                    }
+                }
+            }
+        }
+        boot.mark("session_index");
+
+        // Deliver reminders, including any that came due while stopped
+        tokio::spawn(run_reminder_dispatcher(state.clone()));
+
+        // Answer messages held outside working hours
+        tokio::spawn(run_after_hours_queue(state.clone()));
+
+        // Tell the UI about tool calls waiting for approval
+        tokio::spawn(run_approval_forwarder(state.clone()));
+
+        // Resume workflow runs whose wait for input timed out
+        tokio::spawn(run_input_timeouts(state.clone()));
+
+        // Start workflows on their cron and session event triggers
+        tokio::spawn(run_cron_triggers(state.clone()));
+        tokio::spawn(run_event_triggers(state.clone()));
+
+        // Purge deleted sessions once their retention period has passed
+        tokio::spawn(run_session_purger(state.clone()));
+
+        // Move old ended sessions to cold storage
+        tokio::spawn(run_session_archiver(state.clone()));
+
+        // End sessions that have been inactive past their TTL
+        tokio::spawn(run_idle_session_closer(state.clone()));
+
+        // Forget rate limit state for senders that have gone quiet
+        tokio::spawn(run_rate_limit_sweeper(state.clone()));
+
+        // Back up sessions, config, and credentials
+        if let Some(schedule) = self.config.backup.clone() {
+            tokio::spawn(run_backup_scheduler(state.clone(), schedule));
+        }
+
+        // Start scheduler
+        if !self.config.schedules.is_empty() {
+            let store = ScheduleStateStore::open(&self.config.data_dir.join("schedules"))
+                .map_err(|e| GatewayError::Config(format!("Schedule state: {e}")))?;
+            let scheduler = Scheduler::new(&self.config.schedules, store)
+                .map_err(|e| GatewayError::Config(e.to_string()))?;
+            tracing::info!("Scheduler started with {} task(s)", scheduler.tasks().len());
+            let runner = Arc::new(GatewayScheduleRunner::new(state.clone()));
+            tokio::spawn(scheduler.run(runner));
+        }
+        boot.mark("scheduler");
+
+        // Join the cluster in distributed mode
+        if let Some(config) = self.config.cluster.clone() {
+            let cluster = Arc::new(
+                Cluster::connect(config)
+                    .await
+                    .map_err(|e| GatewayError::Server(format!("Cluster: {e}")))?,
+            );
+            crate::cluster::start(state.clone(), cluster.clone())
+                .await
+                .map_err(|e| GatewayError::Server(format!("Cluster: {e}")))?;
+            state.write().await.cluster = Some(cluster);
+            boot.mark("cluster");
+        }
+
+        // Answer mock channel messages; registering it lets the supervisor
+        // start its input reader with the other channels
+        {
+            let state_read = state.read().await;
+            if let Some(mock) = state_read.mock_channel.clone() {
+                state_read
+                    .channels
+                    .write()
+                    .await
+                    .register_outbound(mock.clone());
+                if let Some(inbound) = mock.take_inbound() {
+                    tokio::spawn(run_mock_inbound(state.clone(), mock, inbound));
+                }
+            }
+            if let Some(whatsapp) = state_read.whatsapp.clone() {
+                state_read
+                    .channels
+                    .write()
+                    .await
+                    .register_outbound(whatsapp);
+            }
+        }
+
+        // Start channels under supervision, and tell UI clients when they
+        // change state
+        {
+            let state = state.read().await;
+            tokio::spawn(forward_channel_changes(
+                state.supervisor.subscribe(),
+                state.events.clone(),
+            ));
+            let registry = state.channels.read().await;
+            let agent_id = state.router.default_agent().to_string();
+            for id in registry.list() {
+                if let Some(channel) = registry.get(id) {
+                    let enabled = !self.config.disabled_channels.iter().any(|d| d == id);
+                    state.supervisor.spawn(
+                        channel.clone(),
+                        ChannelContext {
+                            agent_id: agent_id.clone(),
+                            account_id: "default".to_string(),
+                        },
+                        enabled,
+                    );
+                }
             }
         }
 
-        // Build API router
-        let app = Router::new()
-            .route("/health", get(health_handler))
-            .route("/rpc", post(rpc_handler))
+        // Probe channels for the startup report
+        let channels = {
+            let channels = state.read().await.channels.clone();
+            let registry = channels.read().await;
+            probe_channels(&registry).await
+        };
+        boot.mark("channels");
+
+        // Build API router. The WebSocket and SSE routes are long-lived, so
+        // they have no route timeout (WebSocket RPC calls are bounded per
+        // method).
+        let health_timeout = self.config.timeouts.for_route("/health");
+        let liveness_timeout = self.config.timeouts.for_route("/healthz");
+        let readyz_timeout = self.config.timeouts.for_route("/readyz");
+        let rpc_timeout = self.config.timeouts.for_route("/rpc");
+        let mcp_timeout = self.config.timeouts.for_route("/mcp");
+        let oidc_login_timeout = self.config.timeouts.for_route(OIDC_LOGIN_ROUTE);
+        let oidc_callback_timeout = self.config.timeouts.for_route(OIDC_CALLBACK_ROUTE);
+        let transcript_timeout = self.config.timeouts.for_route(TRANSCRIPT_ROUTE);
+        let new_upload_timeout = self.config.timeouts.for_route(UPLOADS_ROUTE);
+        let upload_timeout = self.config.timeouts.for_route(UPLOAD_ROUTE);
+        // Downloads stream after the response starts, so this bounds only
+        // opening the file
+        let workspace_file_timeout = self.config.timeouts.for_route(WORKSPACE_FILE_ROUTE);
+        let workflow_hook_timeout = self.config.timeouts.for_route(WORKFLOW_HOOK_ROUTE);
+        let whatsapp_hook_timeout = self.config.timeouts.for_route(WHATSAPP_HOOK_ROUTE);
+        let limit_counters = state.read().await.limit_counters.clone();
+        let mut app = Router::new()
+            .route(
+                "/health",
+                get(health_handler).layer(
+                    ServiceBuilder::new()
+                        .layer(HandleErrorLayer::new(move |err| async move {
+                            route_timeout_error(health_timeout, &err)
+                        }))
+                        .layer(TimeoutLayer::new(health_timeout)),
+                ),
+            )
+            .route(
+                "/healthz",
+                get(liveness_handler).layer(
+                    ServiceBuilder::new()
+                        .layer(HandleErrorLayer::new(move |err| async move {
+                            route_timeout_error(liveness_timeout, &err)
+                        }))
+                        .layer(TimeoutLayer::new(liveness_timeout)),
+                ),
+            )
+            .route(
+                "/readyz",
+                get(readiness_handler).layer(
+                    ServiceBuilder::new()
+                        .layer(HandleErrorLayer::new(move |err| async move {
+                            route_timeout_error(readyz_timeout, &err)
+                        }))
+                        .layer(TimeoutLayer::new(readyz_timeout)),
+                ),
+            )
+            .route(
+                "/rpc",
+                post(rpc_handler).layer(
+                    ServiceBuilder::new()
+                        .layer(HandleErrorLayer::new(move |err| async move {
+                            route_timeout_error(rpc_timeout, &err)
+                        }))
+                        .layer(TimeoutLayer::new(rpc_timeout)),
+                ),
+            )
+            .route(
+                TRANSCRIPT_ROUTE,
+                get(transcript_handler).layer(
+                    ServiceBuilder::new()
+                        .layer(HandleErrorLayer::new(move |err| async move {
+                            route_timeout_error(transcript_timeout, &err)
+                        }))
+                        .layer(TimeoutLayer::new(transcript_timeout)),
+                ),
+            )
+            .route(
+                UPLOADS_ROUTE,
+                post(start_upload_handler).layer(
+                    ServiceBuilder::new()
+                        .layer(HandleErrorLayer::new(move |err| async move {
+                            route_timeout_error(new_upload_timeout, &err)
+                        }))
+                        .layer(TimeoutLayer::new(new_upload_timeout)),
+                ),
+            )
+            .route(
+                WORKSPACE_FILE_ROUTE,
+                get(workspace_file_handler).layer(
+                    ServiceBuilder::new()
+                        .layer(HandleErrorLayer::new(move |err| async move {
+                            route_timeout_error(workspace_file_timeout, &err)
+                        }))
+                        .layer(TimeoutLayer::new(workspace_file_timeout)),
+                ),
+            )
+            .route(
+                WORKFLOW_HOOK_ROUTE,
+                post(workflow_hook_handler).layer(
+                    ServiceBuilder::new()
+                        .layer(HandleErrorLayer::new(move |err| async move {
+                            route_timeout_error(workflow_hook_timeout, &err)
+                        }))
+                        .layer(TimeoutLayer::new(workflow_hook_timeout)),
+                ),
+            );
+        if self
+            .config
+            .whatsapp
+            .as_ref()
+            .is_some_and(|c| c.app_secret.is_some())
+        {
+            app = app.route(
+                WHATSAPP_HOOK_ROUTE,
+                get(whatsapp_verify_handler)
+                    .post(whatsapp_hook_handler)
+                    .layer(
+                        ServiceBuilder::new()
+                            .layer(HandleErrorLayer::new(move |err| async move {
+                                route_timeout_error(whatsapp_hook_timeout, &err)
+                            }))
+                            .layer(TimeoutLayer::new(whatsapp_hook_timeout)),
+                    ),
+            );
+        }
+        if self.config.mcp {
+            app = app.route(
+                "/mcp",
+                post(mcp_handler).layer(
+                    ServiceBuilder::new()
+                        .layer(HandleErrorLayer::new(move |err| async move {
+                            route_timeout_error(mcp_timeout, &err)
+                        }))
+                        .layer(TimeoutLayer::new(mcp_timeout)),
+                ),
+            );
+        }
+        if self.config.oidc.is_some() {
+            app = app
+                .route(
+                    OIDC_LOGIN_ROUTE,
+                    get(oidc_login_handler).layer(
+                        ServiceBuilder::new()
+                            .layer(HandleErrorLayer::new(move |err| async move {
+                                route_timeout_error(oidc_login_timeout, &err)
+                            }))
+                            .layer(TimeoutLayer::new(oidc_login_timeout)),
+                    ),
+                )
+                .route(
+                    OIDC_CALLBACK_ROUTE,
+                    get(oidc_callback_handler).layer(
+                        ServiceBuilder::new()
+                            .layer(HandleErrorLayer::new(move |err| async move {
+                                route_timeout_error(oidc_callback_timeout, &err)
+                            }))
+                            .layer(TimeoutLayer::new(oidc_callback_timeout)),
+                    ),
+                );
+        }
+        // Body limits cover the routes above; upload chunks stream to disk
+        // under their own cap, and the long-lived routes carry no body
+        let body_guard = BodyGuard::new(&self.config.limits, limit_counters);
+        let app = app
+            .layer(DefaultBodyLimit::max(body_guard.max_bytes()))
+            .layer(axum::middleware::from_fn_with_state(
+                body_guard.clone(),
+                guard_body,
+            ))
+            .route(
+                UPLOAD_ROUTE,
+                get(upload_status_handler).put(upload_chunk_handler).layer(
+                    ServiceBuilder::new()
+                        .layer(HandleErrorLayer::new(move |err| async move {
+                            route_timeout_error(upload_timeout, &err)
+                        }))
+                        .layer(TimeoutLayer::new(upload_timeout))
+                        .layer(axum::middleware::from_fn_with_state(
+                            body_guard.with_max_bytes(MAX_CHUNK_SIZE),
+                            guard_body,
+                        )),
+                ),
+            )
             .route("/ws", get(ws_handler))
+            .route("/events/stream", get(sse_handler))
             .with_state(state);
+        let base_path = &self.config.proxy.base_path;
+        let app = if base_path.is_empty() {
+            app
+        } else {
+            Router::new().nest(base_path, app)
+        };
+        let app = app
+            .layer(axum::middleware::from_fn_with_state(
+                ProxyResolver::new(&self.config.proxy),
+                resolve_client,
+            ))
+            // Outermost, so preflight requests are answered before any limit
+            .layer(cors_layer(&self.config.cors));
 
         let addr: SocketAddr = format!("{}:{}", self.config.bind_address, self.config.port)
             .parse()
             .map_err(|e| GatewayError::Config(format!("Invalid address: {e}")))?;
 
-        tracing::info!("Gateway API listening on http://{}", addr);
+        let unix_socket = self.config.unix_socket.clone();
+        let listen_tcp = unix_socket.as_ref().is_none_or(|socket| socket.tcp);
+        let mut addresses = Vec::new();
+        if listen_tcp {
+            tracing::info!("Gateway API listening on http://{}", addr);
+            addresses.push(addr.to_string());
+        }
+        if let Some(socket) = &unix_socket {
+            tracing::info!("Gateway API listening on {}", socket.path.display());
+            addresses.push(format!("unix:{}", socket.path.display()));
+        }
+        let base_url = self.public_base_url();
+        if base_url != format!("http://{addr}") {
+            tracing::info!("Gateway API served at {}", base_url);
+        }
+        let triggers = self.state.read().await.triggers.clone();
+        for url in triggers.webhook_urls(&base_url) {
+            tracing::info!("Workflow webhook at {}", url);
+        }
 
         // Start API server
-        let api_listener = tokio::net::TcpListener::bind(addr).await?;
-        let api_handle = tokio::spawn(async move { axum::serve(api_listener, app).await });
+        let api_listener = if listen_tcp {
+            Some(tokio::net::TcpListener::bind(addr).await?)
+        } else {
+            None
+        };
+        #[cfg(unix)]
+        let unix_listener = unix_socket
+            .as_ref()
+            .map(crate::unix_socket::bind)
+            .transpose()?;
+        #[cfg(not(unix))]
+        if unix_socket.is_some() {
+            return Err(GatewayError::Config(
+                "Unix sockets are not supported on this platform".to_string(),
+            ));
+        }
+        boot.mark("listen");
+        self.report_startup(&boot, addresses.join(", "), channels)
+            .await;
+        self.start_control_socket().await;
+
+        // Drain on SIGINT/SIGTERM, `gateway.shutdown`, or the control socket,
+        // then stop the listeners
+        let shutdown = self.state.read().await.shutdown.clone();
+        tokio::spawn(request_on_signal(shutdown.clone()));
+        tokio::spawn(drain_on_request(self.state.clone()));
+        let mut servers = tokio::task::JoinSet::new();
+        if let Some(listener) = api_listener {
+            let app = app.clone();
+            let shutdown = shutdown.clone();
+            servers.spawn(async move {
+                axum::serve(
+                    listener,
+                    app.into_make_service_with_connect_info::<SocketAddr>(),
+                )
+                .with_graceful_shutdown(async move { shutdown.stopped().await })
+                .await
+            });
+        }
+        #[cfg(unix)]
+        if let (Some(listener), Some(socket)) = (unix_listener, unix_socket) {
+            servers.spawn(async move {
+                let result = axum::serve(listener, app.into_make_service())
+                    .with_graceful_shutdown(async move { shutdown.stopped().await })
+                    .await;
+                crate::unix_socket::remove(&socket.path);
+                result
+            });
+        }
+        let api_handle = tokio::spawn(async move {
+            while let Some(result) = servers.join_next().await {
+                result.map_err(std::io::Error::other)??;
+            }
+            Ok::<(), std::io::Error>(())
+        });
 
         // Optionally start UI server
         #[cfg(feature = "ui")]
         let ui_handle = if let Some(ref ui_config) = self.config.ui {
             if ui_config.enabled {
-                let config = ui_config.clone();
+                let mut config = ui_config.clone();
+                // Point the UI at the gateway's public URL behind a proxy
+                if config.api_base_url.is_none() {
+                    config
+                        .api_base_url
+                        .clone_from(&self.config.proxy.public_url);
+                }
                 Some(tokio::spawn(async move {
                     crate::ui_server::run_ui_server(config).await
                 }))
@@ -353,6 +1374,68 @@ impl Gateway {
     }
 }
 
+impl Gateway {
+    /// The URL clients reach the gateway at.
+    fn public_base_url(&self) -> String {
+        public_base_url(
+            &self.config.proxy,
+            &self.config.bind_address,
+            self.config.port,
+        )
+    }
+
+    /// Listen for daemon control commands, if a control address is set.
+    ///
+    /// A bind failure is logged rather than fatal: the gateway still serves
+    /// HTTP, only `openclaw daemon` commands are unavailable.
+    async fn start_control_socket(&self) {
+        let Some(address) = &self.config.control_address else {
+            return;
+        };
+
+        match openclaw_ipc::ControlServer::bind(address) {
+            Ok(server) => {
+                let shutdown = self.state.read().await.shutdown.clone();
+                let handler = Arc::new(GatewayControl::new(self.state.clone(), shutdown));
+                tokio::spawn(server.serve(handler));
+            }
+            Err(e) => tracing::warn!("Control socket unavailable on {}: {}", address, e),
+        }
+    }
+
+    /// Build, log, store, and broadcast the startup report.
+    async fn report_startup(
+        &self,
+        boot: &BootTimer,
+        address: String,
+        channels: Vec<crate::startup::ChannelStartup>,
+    ) {
+        let mut state = self.state.write().await;
+
+        let mut agents: Vec<String> = state.agents.keys().cloned().collect();
+        agents.sort();
+
+        let report = StartupReport {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            started_at: Utc::now(),
+            config_source: self.config.config_source.clone(),
+            address,
+            agents,
+            channels,
+            storage: state.event_store.stats().unwrap_or_default(),
+            phases: boot.phases().to_vec(),
+            total_ms: boot.elapsed_ms(),
+            budget_ms: u64::try_from(self.config.boot_budget.as_millis()).unwrap_or(u64::MAX),
+        };
+        report.log();
+
+        let _ = state.events.broadcast(UiEvent::GatewayStarted {
+            report: Box::new(report.clone()),
+        });
+        state.startup = Some(report);
+    }
+}
+
 async fn health_handler() -> &'static str {
     "OK"
 }
@@ -360,22 +1443,39 @@ async fn health_handler() -> &'static str {
 async fn rpc_handler(
     State(state): State<Arc<RwLock<GatewayState>>>,
     headers: axum::http::HeaderMap,
+    extensions: axum::http::Extensions,
     Json(request): Json<RpcRequest>,
-) -> Json<RpcResponse> {
-    let id = request.id.clone();
-
+) -> Response {
     // Extract auth token from header
     let auth_token = headers
         .get(axum::http::header::AUTHORIZATION)
         .and_then(|v| v.to_str().ok())
         .and_then(JwtManager::extract_from_header);
 
-    let result = dispatch_rpc(&state, &request.method, &request.params, auth_token).await;
+    let response =
+        dispatch_with_timeout(&state, &request, auth_token, extensions.get::<ClientInfo>()).await;
+    match retry_after(&response) {
+        Some(secs) => (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(axum::http::header::RETRY_AFTER, secs.to_string())],
+            Json(response),
+        )
+            .into_response(),
+        None => Json(response).into_response(),
+    }
+}
 
-    Json(match result {
-        Ok(value) => RpcResponse::success(id, value),
-        Err((code, message)) => RpcResponse::error(id, code, message),
-    })
+/// Address of the client, as forwarded by a trusted proxy or else when
+/// the server records connection info.
+fn client_ip(extensions: &axum::http::Extensions) -> Option<IpAddr> {
+    extensions.get::<ClientInfo>().map_or_else(
+        || {
+            extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|info| info.0.ip())
+        },
+        |client| client.ip,
+    )
 }
 
 /// WebSocket query parameters.
@@ -388,15 +1488,82 @@ struct WsParams {
 async fn ws_handler(
     State(state): State<Arc<RwLock<GatewayState>>>,
     axum::extract::Query(params): axum::extract::Query<WsParams>,
+    headers: axum::http::HeaderMap,
+    extensions: axum::http::Extensions,
     ws: WebSocketUpgrade,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, state, params.token))
+) -> Response {
+    let client_ip = client_ip(&extensions);
+    let client = extensions.get::<ClientInfo>().cloned();
+    let (limits, cors) = {
+        let state = state.read().await;
+        (state.config.limits.clone(), state.config.cors.clone())
+    };
+    // Browsers don't apply CORS to WebSockets, so check the page's origin
+    // here
+    if !origin_allowed(&cors, &headers) {
+        tracing::warn!(
+            target: "security",
+            origin = ?headers.get(axum::http::header::ORIGIN),
+            ip = ?client_ip,
+            "WebSocket from disallowed origin refused"
+        );
+        return (StatusCode::FORBIDDEN, "Origin not allowed").into_response();
+    }
+    let max_message = usize::try_from(limits.ws_max_message_bytes).unwrap_or(usize::MAX);
+    // The write buffer cap must stay above the socket's own write buffer
+    let max_write_buffer = usize::try_from(limits.ws_max_write_buffer_bytes)
+        .unwrap_or(usize::MAX)
+        .max(MIN_WS_WRITE_BUFFER);
+    ws.max_message_size(max_message)
+        .max_frame_size(max_message)
+        .max_write_buffer_size(max_write_buffer)
+        .on_upgrade(move |socket| handle_socket(socket, state, params.token, client))
+        .into_response()
+}
+
+/// Smallest accepted cap on a WebSocket's unsent data.
+const MIN_WS_WRITE_BUFFER: usize = 256 * 1024;
+
+type WsSender = Arc<tokio::sync::Mutex<futures::stream::SplitSink<WebSocket, Message>>>;
+
+/// Why a WebSocket send did not go through.
+enum WsSendError {
+    /// The connection is gone.
+    Closed,
+    /// The client stopped reading.
+    Slow,
+}
+
+/// Send `message`, giving up if the client has not taken it within
+/// `timeout`.
+async fn send_within(
+    sender: &WsSender,
+    message: Message,
+    timeout: Duration,
+) -> Result<(), WsSendError> {
+    let send = async { sender.lock().await.send(message).await };
+    match tokio::time::timeout(timeout, send).await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(_)) => Err(WsSendError::Closed),
+        Err(_) => Err(WsSendError::Slow),
+    }
+}
+
+/// Send a close frame, without waiting long for a client that is not
+/// reading.
+async fn close_socket(sender: &WsSender, code: u16, reason: &'static str, timeout: Duration) {
+    let frame = Message::Close(Some(CloseFrame {
+        code,
+        reason: reason.into(),
+    }));
+    let _ = send_within(sender, frame, timeout).await;
 }
 
 async fn handle_socket(
     socket: WebSocket,
     state: Arc<RwLock<GatewayState>>,
     auth_token: Option<String>,
+    client: Option<ClientInfo>,
 ) {
     let (sender, mut receiver) = socket.split();
     let sender = Arc::new(tokio::sync::Mutex::new(sender));
@@ -428,12 +1595,26 @@ async fn handle_socket(
         }
     }
 
-    // Create a channel to stop the event listener
+    let (limits, counters) = {
+        let state_read = state.read().await;
+        (
+            state_read.config.limits.clone(),
+            state_read.limit_counters.clone(),
+        )
+    };
+    let send_timeout = Duration::from_secs(limits.ws_send_timeout_secs);
+
+    // Create a channel to stop the event listener, and one for the listener
+    // to end the connection
     let (stop_tx, mut stop_rx) = tokio::sync::oneshot::channel::<()>();
+    let kicked = Arc::new(tokio::sync::Notify::new());
     let subscribed = Arc::new(std::sync::atomic::AtomicBool::new(false));
     let subscribed_clone = subscribed.clone();
     let sender_clone = sender.clone();
     let state_clone = state.clone();
+    let kicked_clone = kicked.clone();
+    let counters_clone = counters.clone();
+    let token_clone = auth_token.clone();
 
     // Spawn event listener task
     let event_task = tokio::spawn(async move {
@@ -448,10 +1629,14 @@ async fn handle_socket(
             tokio::time::sleep(std::time::Duration::from_millis(100)).await;
         }
 
-        // Subscribe to events
-        let mut event_rx = {
+        // Subscribe to events from the namespaces the caller can see
+        let (mut event_rx, tenants, access) = {
             let state_read = state_clone.read().await;
-            state_read.events.subscribe()
+            (
+                state_read.events.subscribe(),
+                state_read.tenants.clone(),
+                state_read.namespace_access(token_clone.as_deref()),
+            )
         };
 
         loop {
@@ -461,6 +1646,7 @@ async fn handle_socket(
                 }
                 event_result = event_rx.recv() => {
                     match event_result {
+                        Ok(envelope) if !tenants.event_visible(&access, &envelope.event) => {}
                         Ok(envelope) => {
                             let event_msg = serde_json::json!({
                                 "jsonrpc": "2.0",
@@ -468,13 +1654,40 @@ async fn handle_socket(
                                 "params": envelope,
                             });
                             let msg_text = serde_json::to_string(&event_msg).unwrap_or_default();
-                            let mut guard = sender_clone.lock().await;
-                            if guard.send(Message::Text(msg_text.into())).await.is_err() {
-                                break;
+                            let message = Message::Text(msg_text.into());
+                            match send_within(&sender_clone, message, send_timeout).await {
+                                Ok(()) => {}
+                                Err(WsSendError::Closed) => break,
+                                Err(WsSendError::Slow) => {
+                                    tracing::warn!("WebSocket client stopped reading, disconnecting");
+                                    counters_clone.ws_slow_client();
+                                    close_socket(
+                                        &sender_clone,
+                                        close_code::POLICY,
+                                        "Client too slow",
+                                        send_timeout,
+                                    )
+                                    .await;
+                                    kicked_clone.notify_one();
+                                    break;
+                                }
                             }
                         }
                         Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
                             tracing::warn!("Event listener lagged, missed {} events", n);
+                            counters_clone.ws_events_dropped(n);
+                            if limits.ws_lag_policy == WsLagPolicy::Disconnect {
+                                counters_clone.ws_slow_client();
+                                close_socket(
+                                    &sender_clone,
+                                    close_code::POLICY,
+                                    "Client fell behind on events",
+                                    send_timeout,
+                                )
+                                .await;
+                                kicked_clone.notify_one();
+                                break;
+                            }
                         }
                         Err(tokio::sync::broadcast::error::RecvError::Closed) => {
                             break;
@@ -485,12 +1698,32 @@ async fn handle_socket(
         }
     });
 
-    // Handle incoming RPC requests
-    while let Some(msg) = receiver.next().await {
+    // Handle incoming RPC requests until the client leaves or the gateway
+    // finishes draining
+    let shutdown = state.read().await.shutdown.clone();
+    loop {
+        let msg = tokio::select! {
+            msg = receiver.next() => msg,
+            () = shutdown.stopped() => {
+                close_socket(&sender, close_code::AWAY, "Gateway shutting down", send_timeout)
+                    .await;
+                break;
+            }
+            () = kicked.notified() => break,
+        };
+        let Some(msg) = msg else {
+            break;
+        };
         let msg = match msg {
             Ok(Message::Text(text)) => text,
             Ok(Message::Close(_)) => break,
             Ok(_) => continue, // Ignore binary, ping, pong
+            Err(e) if is_message_too_long(&e) => {
+                tracing::warn!("WebSocket message over the size limit, disconnecting");
+                counters.ws_message_rejected();
+                close_socket(&sender, close_code::SIZE, "Message too large", send_timeout).await;
+                break;
+            }
             Err(e) => {
                 tracing::warn!("WebSocket receive error: {}", e);
                 break;
@@ -504,12 +1737,9 @@ async fn handle_socket(
                 let error_response =
                     RpcResponse::error(None, rpc::PARSE_ERROR, format!("Parse error: {e}"));
                 let response_text = serde_json::to_string(&error_response).unwrap_or_default();
-                let mut guard = sender.lock().await;
-                if guard
-                    .send(Message::Text(response_text.into()))
-                    .await
-                    .is_err()
-                {
+                let message = Message::Text(response_text.into());
+                if let Err(e) = send_within(&sender, message, send_timeout).await {
+                    note_send_failure(&e, &counters);
                     break;
                 }
                 continue;
@@ -521,22 +1751,13 @@ async fn handle_socket(
             subscribed.store(true, std::sync::atomic::Ordering::Relaxed);
         }
 
-        let id = request.id.clone();
-        let token_ref = auth_token.as_deref();
-        let result = dispatch_rpc(&state, &request.method, &request.params, token_ref).await;
-
-        let response = match result {
-            Ok(value) => RpcResponse::success(id, value),
-            Err((code, message)) => RpcResponse::error(id, code, message),
-        };
+        let response =
+            dispatch_with_timeout(&state, &request, auth_token.as_deref(), client.as_ref()).await;
 
         let response_text = serde_json::to_string(&response).unwrap_or_default();
-        let mut guard = sender.lock().await;
-        if guard
-            .send(Message::Text(response_text.into()))
-            .await
-            .is_err()
-        {
+        let message = Message::Text(response_text.into());
+        if let Err(e) = send_within(&sender, message, send_timeout).await {
+            note_send_failure(&e, &counters);
             break;
         }
     }
@@ -548,12 +1769,75 @@ async fn handle_socket(
     tracing::debug!("WebSocket connection closed");
 }
 
+/// Count a response that could not be sent because the client stopped
+/// reading.
+fn note_send_failure(error: &WsSendError, counters: &LimitCounters) {
+    if matches!(error, WsSendError::Slow) {
+        tracing::warn!("WebSocket client stopped reading, disconnecting");
+        counters.ws_slow_client();
+    }
+}
+
+/// Dispatch an RPC request, bounded by its method timeout.
+pub async fn dispatch_with_timeout(
+    state: &Arc<RwLock<GatewayState>>,
+    request: &RpcRequest,
+    auth_token: Option<&str>,
+    client: Option<&ClientInfo>,
+) -> RpcResponse {
+    let id = request.id.clone();
+    let timeout = {
+        let state = state.read().await;
+        if state.shutdown.is_draining() {
+            return RpcResponse::error(id, rpc::UNAVAILABLE, "Gateway is shutting down");
+        }
+        if let Some(caller) = rate_limit_caller(&state, auth_token, client.and_then(|c| c.ip)) {
+            if let Err(retry_after) = state.rate_limiter.check(&request.method, &caller) {
+                return rate_limited_response(id, retry_after);
+            }
+        }
+        state.config.timeouts.for_method(&request.method)
+    };
+
+    let dispatch = dispatch_rpc(state, &request.method, &request.params, auth_token, client);
+    match tokio::time::timeout(timeout, dispatch).await {
+        Ok(Ok(value)) => RpcResponse::success(id, value),
+        Ok(Err((code, message))) => RpcResponse::error(id, code, message),
+        Err(_) => {
+            tracing::warn!(
+                "RPC {} timed out after {}s",
+                request.method,
+                timeout.as_secs()
+            );
+            timeout_response(id, Some(&request.method), timeout)
+        }
+    }
+}
+
+/// Who a call is counted against: the signed-in user, else the client
+/// address. Calls with neither, such as from the MCP endpoint without a
+/// token, are not limited.
+fn rate_limit_caller(
+    state: &GatewayState,
+    auth_token: Option<&str>,
+    client_ip: Option<IpAddr>,
+) -> Option<RateLimitCaller> {
+    auth_token
+        .and_then(|t| state.auth.validate_token(t).ok())
+        .map(|claims| RateLimitCaller::User {
+            id: claims.sub,
+            username: claims.username,
+        })
+        .or_else(|| client_ip.map(RateLimitCaller::Ip))
+}
+
 /// Dispatch RPC request to appropriate handler.
 async fn dispatch_rpc(
     state: &Arc<RwLock<GatewayState>>,
     method: &str,
     params: &serde_json::Value,
     auth_token: Option<&str>,
+    client: Option<&ClientInfo>,
 ) -> RpcResult {
     let state_read = state.read().await;
 
@@ -572,13 +1856,18 @@ async fn dispatch_rpc(
 
     match method {
         // Auth methods
-        "auth.login" => handle_auth_login(state, params).await,
-        "auth.logout" => handle_auth_logout(state, params).await,
+        "auth.login" => handle_auth_login(state, params, client.and_then(|c| c.ip)).await,
+        "auth.logout" => handle_auth_logout(state, params, auth_token).await,
         "auth.refresh" => handle_auth_refresh(state, params).await,
         "auth.me" => handle_auth_me(state, auth_token).await,
+        "auth.oidc.status" => handle_auth_oidc_status(state).await,
+        "auth.lockouts.list" => handle_auth_lockouts_list(state, auth_token).await,
+        "auth.lockouts.clear" => handle_auth_lockouts_clear(state, params, auth_token).await,
+        "auth.sessions.list" => handle_auth_sessions_list(state, params, auth_token).await,
+        "auth.sessions.revoke" => handle_auth_sessions_revoke(state, params, auth_token).await,
 
         // Setup methods
-        "setup.status" => handle_setup_status(state).await,
+        "setup.status" => handle_setup_status(state, client).await,
         "setup.init" => handle_setup_init(state, params).await,
 
         // User management (admin only)
@@ -587,33 +1876,111 @@ async fn dispatch_rpc(
         "users.update" => handle_users_update(state, params, auth_token).await,
         "users.delete" => handle_users_delete(state, params, auth_token).await,
 
+        // Runtime log filter (admin only)
+        "admin.log_level.get" => handle_log_level_get(state, auth_token).await,
+        "admin.log_level.set" => handle_log_level_set(state, params, auth_token).await,
+        "admin.log_level.reset" => handle_log_level_reset(state, auth_token).await,
+
+        // Tool approvals (admin or operator)
+        "approvals.list" => handle_approvals_list(state, auth_token).await,
+        "approvals.approve" => handle_approvals_decide(state, params, auth_token, true).await,
+        "approvals.deny" => handle_approvals_decide(state, params, auth_token, false).await,
+
+        // Peer profiles
+        "peers.list" => handle_peers_list(state, params, auth_token).await,
+        "peers.get" => handle_peers_get(state, params, auth_token).await,
+        "peers.update" => handle_peers_update(state, params, auth_token).await,
+        "peers.delete" => handle_peers_delete(state, params, auth_token).await,
+        "privacy.forget" => handle_privacy_forget(state, params, auth_token).await,
+
+        // Graceful shutdown (admin only)
+        "gateway.shutdown" => handle_gateway_shutdown(state, auth_token).await,
+
         // Session methods
-        "session.create" => handle_session_create(state, params).await,
-        "session.message" => handle_session_message(state, params).await,
-        "session.history" => handle_session_history(state, params).await,
-        "session.end" => handle_session_end(state, params).await,
-        "session.list" => handle_session_list(state, params).await,
-        "session.search" => handle_session_search(state, params).await,
-        "session.stats" => handle_session_stats(state).await,
-        "session.events" => handle_session_events(state, params).await,
+        "session.create" => handle_session_create(state, params, auth_token).await,
+        "session.message" => {
+            let cluster = state.read().await.cluster.clone();
+            match cluster {
+                Some(cluster) if cluster.forwards_messages() => {
+                    cluster.forward_message(params, auth_token).await
+                }
+                _ => handle_session_message(state, params, auth_token).await,
+            }
+        }
+        "session.history" => handle_session_history(state, params, auth_token).await,
+        "session.end" => handle_session_end(state, params, auth_token).await,
+        "session.list" => handle_session_list(state, params, auth_token).await,
+        "session.search" => handle_session_search(state, params, auth_token).await,
+        "session.stats" => handle_session_stats(state, auth_token).await,
+        "stats.get" => handle_stats_get(state, params, auth_token).await,
+        "session.events" => handle_session_events(state, params, auth_token).await,
+        "session.delete" => handle_session_delete(state, params, auth_token).await,
+        "session.restore" => handle_session_restore(state, params, auth_token).await,
+        "session.purge" => handle_session_purge(state, params, auth_token).await,
+        "session.dry_run" => handle_session_dry_run(state, params, auth_token).await,
+        "session.fork" => handle_session_fork(state, params, auth_token).await,
+        "session.replay" => handle_session_replay(state, params, auth_token).await,
 
         // Channel methods
-        "channels.list" => handle_channels_list(state).await,
-        "channels.status" => handle_channels_status(state).await,
-        "channels.probe" => handle_channels_probe(state, params).await,
+        "channels.list" => handle_channels_list(state, auth_token).await,
+        "channels.status" => handle_channels_status(state, auth_token).await,
+        "channels.probe" => handle_channels_probe(state, params, auth_token).await,
+        "channels.send" => handle_channels_send(state, params, auth_token).await,
+        "channels.start" => handle_channels_start(state, params, auth_token).await,
+        "channels.stop" => handle_channels_stop(state, params, auth_token).await,
+        "channels.enable" => handle_channels_set_enabled(state, params, auth_token, true).await,
+        "channels.disable" => handle_channels_set_enabled(state, params, auth_token, false).await,
+
+        // Mock channel (development)
+        "mock.inject" => handle_mock_inject(state, params, auth_token).await,
+        "mock.react" => handle_mock_react(state, params, auth_token).await,
+        "mock.outbound" => handle_mock_outbound(state, params).await,
+
+        // Workflows
+        "workflow.run" => handle_workflow_run(state, params, auth_token).await,
+        "workflow.waiting" => handle_workflow_waiting(state, auth_token).await,
+        "workflow.reply" => handle_workflow_reply(state, params, auth_token).await,
+        "workflow.runs.list" => handle_workflow_runs_list(state, params, auth_token).await,
+        "workflow.runs.get" => handle_workflow_runs_get(state, params, auth_token).await,
+
+        // Notification templates
+        "notify.templates" => handle_notify_templates(state).await,
+        "notify.send" => handle_notify_send(state, params, auth_token).await,
+
+        // Broadcasts (admin or operator)
+        "broadcast.send" => handle_broadcast_send(state, params, auth_token).await,
+        "broadcast.status" => handle_broadcast_status(state, params, auth_token).await,
 
         // Agent methods
-        "agent.list" => handle_agent_list(state).await,
-        "agent.status" => handle_agent_status(state, params).await,
-        "agent.get" => handle_agent_get(state, params).await,
+        "agent.list" => handle_agent_list(state, auth_token).await,
+        "agent.status" => handle_agent_status(state, params, auth_token).await,
+        "agent.get" => handle_agent_get(state, params, auth_token).await,
+        "agent.update" => handle_agent_update(state, params, auth_token).await,
+
+        // Feedback methods
+        "feedback.submit" => handle_feedback_submit(state, params, auth_token).await,
+        "feedback.summary" => handle_feedback_summary(state, params, auth_token).await,
+
+        // Experiment methods
+        "experiments.list" => handle_experiments_list(state, auth_token).await,
+        "experiments.report" => handle_experiments_report(state, params, auth_token).await,
 
         // Tool methods
         "tools.list" => handle_tools_list(state).await,
-        "tools.execute" => handle_tools_execute(state, params).await,
+        "tools.execute" => handle_tools_execute(state, params, auth_token).await,
+
+        // Workspace methods
+        "workspace.list" => handle_workspace_list(state, params, auth_token).await,
+        "workspace.read" => handle_workspace_read(state, params, auth_token).await,
+        "workspace.download" => handle_workspace_download(state, params, auth_token).await,
 
         // System methods
         "system.health" => handle_system_health(state).await,
         "system.version" => handle_system_version().await,
+        "system.startup" => handle_system_startup(state).await,
+
+        // Method catalog
+        "rpc.discover" => handle_rpc_discover(state).await,
 
         // Event subscription (WebSocket-only, returns ack)
         "events.subscribe" => handle_events_subscribe().await,
@@ -631,6 +1998,7 @@ type RpcResult = Result<serde_json::Value, (i32, String)>;
 async fn handle_auth_login(
     state: &Arc<RwLock<GatewayState>>,
     params: &serde_json::Value,
+    client_ip: Option<IpAddr>,
 ) -> RpcResult {
     let username = params["username"]
         .as_str()
@@ -639,27 +2007,65 @@ async fn handle_auth_login(
         .as_str()
         .ok_or((rpc::INVALID_PARAMS, "Missing password".to_string()))?;
 
-    let state = state.read().await;
+    let (auth, events) = {
+        let state = state.read().await;
+        (state.auth.clone(), state.events.clone())
+    };
 
-    // Find user
-    let user = state
-        .auth
-        .users
-        .get_by_username(username)
-        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Storage error: {e}")))?
-        .ok_or((rpc::UNAUTHORIZED, "Invalid credentials".to_string()))?;
-
-    // Check if active
-    if !user.active {
-        return Err((rpc::UNAUTHORIZED, "Account disabled".to_string()));
+    if let Some(until) = auth.throttle.locked_until(username, client_ip) {
+        tracing::warn!(
+            target: "security",
+            username,
+            ip = ?client_ip,
+            "Login refused during lockout"
+        );
+        return Err((
+            rpc::TOO_MANY_REQUESTS,
+            format!(
+                "Too many failed logins; try again after {}",
+                until.to_rfc3339()
+            ),
+        ));
     }
 
-    // Verify password
-    user.verify_password(password)
-        .map_err(|_| (rpc::UNAUTHORIZED, "Invalid credentials".to_string()))?;
+    let user = match check_credentials(&auth, username, password) {
+        Ok(user) => user,
+        Err((rpc::UNAUTHORIZED, message)) => {
+            let failure = auth.throttle.record_failure(username, client_ip);
+            tracing::warn!(
+                target: "security",
+                username,
+                ip = ?client_ip,
+                reason = %message,
+                "Failed login"
+            );
+            for entry in failure.locked {
+                let Some(locked_until) = entry.locked_until else {
+                    continue;
+                };
+                tracing::warn!(
+                    target: "security",
+                    key = %entry.key,
+                    failures = entry.failures,
+                    until = %locked_until.to_rfc3339(),
+                    "Login locked out"
+                );
+                let _ = events.broadcast(UiEvent::LoginLockedOut {
+                    key: entry.key.to_string(),
+                    failures: entry.failures,
+                    locked_until,
+                });
+            }
+            // Slow down guessing without holding the gateway state
+            tokio::time::sleep(failure.delay).await;
+            return Err((rpc::UNAUTHORIZED, message));
+        }
+        Err(e) => return Err(e),
+    };
+    auth.throttle.record_success(username);
 
     // Update last login
-    state.auth.users.update_last_login(&user.id).map_err(|e| {
+    auth.users.update_last_login(&user.id).map_err(|e| {
         (
             rpc::INTERNAL_ERROR,
             format!("Failed to update login time: {e}"),
@@ -667,10 +2073,8 @@ async fn handle_auth_login(
     })?;
 
     // Generate tokens
-    let token_pair = state
-        .auth
-        .jwt
-        .create_token_pair(&user.id, &user.username, user.role)
+    let token_pair = auth
+        .issue_tokens(&user)
         .map_err(|e| (rpc::INTERNAL_ERROR, format!("Token generation failed: {e}")))?;
 
     Ok(serde_json::json!({
@@ -681,14 +2085,115 @@ async fn handle_auth_login(
     }))
 }
 
+/// The active user with `username` and `password`.
+fn check_credentials(
+    auth: &AuthState,
+    username: &str,
+    password: &str,
+) -> Result<User, (i32, String)> {
+    let user = auth
+        .users
+        .get_by_username(username)
+        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Storage error: {e}")))?
+        .ok_or_else(|| (rpc::UNAUTHORIZED, "Invalid credentials".to_string()))?;
+
+    // Check if active
+    if !user.active {
+        return Err((rpc::UNAUTHORIZED, "Account disabled".to_string()));
+    }
+
+    // Verify password
+    user.verify_password(password)
+        .map_err(|_| (rpc::UNAUTHORIZED, "Invalid credentials".to_string()))?;
+    Ok(user)
+}
+
+async fn handle_auth_oidc_status(state: &Arc<RwLock<GatewayState>>) -> RpcResult {
+    let enabled = state.read().await.oidc.is_some();
+    Ok(serde_json::json!({
+        "enabled": enabled,
+        "login_path": enabled.then_some(OIDC_LOGIN_ROUTE),
+    }))
+}
+
+async fn handle_auth_lockouts_list(
+    state: &Arc<RwLock<GatewayState>>,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let state = state.read().await;
+    require_admin(&state, auth_token)?;
+
+    let entries = state.auth.throttle.entries();
+    drop(state);
+    Ok(serde_json::json!({
+        "count": entries.len(),
+        "entries": entries,
+    }))
+}
+
+async fn handle_auth_lockouts_clear(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let state = state.read().await;
+    require_admin(&state, auth_token)?;
+
+    let key = params["key"]
+        .as_str()
+        .map(str::parse::<ThrottleKey>)
+        .transpose()
+        .map_err(|e| (rpc::INVALID_PARAMS, e))?;
+    let cleared = state.auth.throttle.clear(key.as_ref());
+    drop(state);
+    tracing::info!(
+        target: "security",
+        key = key.as_ref().map_or_else(|| "*".to_string(), ToString::to_string),
+        cleared,
+        "Login lockouts cleared"
+    );
+    Ok(serde_json::json!({ "cleared": cleared }))
+}
+
 async fn handle_auth_logout(
-    _state: &Arc<RwLock<GatewayState>>,
-    _params: &serde_json::Value,
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
 ) -> RpcResult {
-    // For stateless JWT, logout is handled client-side by discarding the token
-    // In a more complete implementation, we'd track refresh token families
+    let token = auth_token.ok_or_else(|| (rpc::UNAUTHORIZED, "Not authenticated".to_string()))?;
+    let all = params["all"].as_bool().unwrap_or(false);
+
+    let state = state.read().await;
+    let claims = state
+        .auth
+        .validate_token(token)
+        .map_err(|e| (rpc::UNAUTHORIZED, format!("Invalid token: {e}")))?;
+
+    let sessions = &state.auth.sessions;
+    let ended = if all {
+        sessions.revoke_user(&claims.sub)
+    } else {
+        sessions.revoke_token(&claims).and_then(|()| {
+            claims
+                .family_id
+                .as_deref()
+                .map_or(Ok(false), |id| sessions.revoke_session(id))
+                .map(usize::from)
+        })
+    }
+    .map_err(|e| (rpc::INTERNAL_ERROR, format!("Storage error: {e}")))?;
+    drop(state);
+    tracing::info!(
+        target: "security",
+        username = %claims.username,
+        all,
+        sessions = ended,
+        "Logged out"
+    );
+
     Ok(serde_json::json!({
         "success": true,
+        "sessions_ended": ended,
     }))
 }
 
@@ -704,9 +2209,11 @@ async fn handle_auth_refresh(
 
     let token_pair = state
         .auth
-        .jwt
         .refresh_tokens(refresh_token)
-        .map_err(|e| (rpc::UNAUTHORIZED, format!("Refresh failed: {e}")))?;
+        .map_err(|e| match e {
+            AuthError::Storage(_) => (rpc::INTERNAL_ERROR, format!("Refresh failed: {e}")),
+            _ => (rpc::UNAUTHORIZED, format!("Refresh failed: {e}")),
+        })?;
 
     Ok(serde_json::json!({
         "token": token_pair.access_token,
@@ -715,6 +2222,51 @@ async fn handle_auth_refresh(
     }))
 }
 
+async fn handle_auth_sessions_list(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let state = state.read().await;
+    require_admin(&state, auth_token)?;
+
+    let sessions = state
+        .auth
+        .sessions
+        .list(params["user_id"].as_str())
+        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Storage error: {e}")))?;
+    drop(state);
+    Ok(serde_json::json!({
+        "count": sessions.len(),
+        "sessions": sessions,
+    }))
+}
+
+async fn handle_auth_sessions_revoke(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let state = state.read().await;
+    require_admin(&state, auth_token)?;
+
+    let sessions = &state.auth.sessions;
+    let (target, result) = match (params["session_id"].as_str(), params["user_id"].as_str()) {
+        (Some(id), None) => (id, sessions.revoke_session(id).map(usize::from)),
+        (None, Some(user_id)) => (user_id, sessions.revoke_user(user_id)),
+        _ => {
+            return Err((
+                rpc::INVALID_PARAMS,
+                "Provide exactly one of session_id or user_id".to_string(),
+            ));
+        }
+    };
+    let revoked = result.map_err(|e| (rpc::INTERNAL_ERROR, format!("Storage error: {e}")))?;
+    drop(state);
+    tracing::info!(target: "security", target_id = target, revoked, "Sessions revoked");
+    Ok(serde_json::json!({ "revoked": revoked }))
+}
+
 async fn handle_auth_me(state: &Arc<RwLock<GatewayState>>, auth_token: Option<&str>) -> RpcResult {
     let token = auth_token.ok_or((rpc::UNAUTHORIZED, "Not authenticated".to_string()))?;
 
@@ -740,11 +2292,24 @@ async fn handle_auth_me(state: &Arc<RwLock<GatewayState>>, auth_token: Option<&s
 // Setup RPC Handlers
 // ============================================================================
 
-async fn handle_setup_status(state: &Arc<RwLock<GatewayState>>) -> RpcResult {
+async fn handle_setup_status(
+    state: &Arc<RwLock<GatewayState>>,
+    client: Option<&ClientInfo>,
+) -> RpcResult {
     let state = state.read().await;
     let bootstrap = state.auth.bootstrap.read().await;
 
-    let base_url = format!("http://{}:{}", state.config.bind_address, state.config.port);
+    // The URL this client reached the gateway at, through any proxy
+    let base_url = client.map_or_else(
+        || {
+            public_base_url(
+                &state.config.proxy,
+                &state.config.bind_address,
+                state.config.port,
+            )
+        },
+        |client| client.base_url.clone(),
+    );
 
     let status = bootstrap.status(&state.auth.users, Some(&base_url));
 
@@ -783,8 +2348,7 @@ async fn handle_setup_init(
     // Generate tokens for the new admin
     let token_pair = state
         .auth
-        .jwt
-        .create_token_pair(&admin.id, &admin.username, admin.role)
+        .issue_tokens(&admin)
         .map_err(|e| (rpc::INTERNAL_ERROR, format!("Token generation failed: {e}")))?;
 
     Ok(serde_json::json!({
@@ -888,6 +2452,7 @@ async fn handle_users_update(
         .get(id)
         .map_err(|e| (rpc::INTERNAL_ERROR, format!("Storage error: {e}")))?
         .ok_or((rpc::NOT_FOUND, format!("User not found: {id}")))?;
+    let previous_role = user.role;
 
     // Update fields if provided
     if let Some(role_str) = params["role"].as_str() {
@@ -910,6 +2475,15 @@ async fn handle_users_update(
         .update(&user)
         .map_err(|e| (rpc::INTERNAL_ERROR, format!("Storage error: {e}")))?;
 
+    // Tokens carry the role, so existing logins end when it changes
+    if user.role != previous_role || !user.active {
+        state
+            .auth
+            .sessions
+            .revoke_user(&user.id)
+            .map_err(|e| (rpc::INTERNAL_ERROR, format!("Storage error: {e}")))?;
+    }
+
     Ok(serde_json::json!({
         "user": user.to_public(),
     }))
@@ -951,27 +2525,358 @@ async fn handle_users_delete(
         .users
         .delete(id)
         .map_err(|e| (rpc::INTERNAL_ERROR, format!("Storage error: {e}")))?;
+    state
+        .auth
+        .sessions
+        .revoke_user(id)
+        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Storage error: {e}")))?;
 
     Ok(serde_json::json!({
         "success": deleted,
     }))
 }
 
+// ============================================================================
+// Admin RPC Handlers
+// ============================================================================
+
+fn log_filter(state: &GatewayState) -> Result<LogFilter, (i32, String)> {
+    state.config.log_filter.clone().ok_or_else(|| {
+        (
+            rpc::INTERNAL_ERROR,
+            "Runtime log level changes are not available".to_string(),
+        )
+    })
+}
+
+fn log_level_result(result: Result<String, crate::LogFilterError>, initial: &str) -> RpcResult {
+    match result {
+        Ok(filter) => Ok(serde_json::json!({
+            "filter": filter,
+            "initial": initial,
+        })),
+        Err(e @ crate::LogFilterError::Invalid(_)) => Err((rpc::INVALID_PARAMS, e.to_string())),
+        Err(e) => Err((rpc::INTERNAL_ERROR, e.to_string())),
+    }
+}
+
+async fn handle_log_level_get(
+    state: &Arc<RwLock<GatewayState>>,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let filter = {
+        let state = state.read().await;
+        require_admin(&state, auth_token)?;
+        log_filter(&state)?
+    };
+    log_level_result(filter.current(), filter.initial())
+}
+
+async fn handle_log_level_set(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let filter = {
+        let state = state.read().await;
+        require_admin(&state, auth_token)?;
+        log_filter(&state)?
+    };
+    let result = if let Some(directives) = params["directives"].as_str() {
+        filter.set_directives(directives)
+    } else {
+        let level = params["level"]
+            .as_str()
+            .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing level".to_string()))?;
+        filter.set_level(level, params["module"].as_str())
+    };
+    log_level_result(result, filter.initial())
+}
+
+async fn handle_log_level_reset(
+    state: &Arc<RwLock<GatewayState>>,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let filter = {
+        let state = state.read().await;
+        require_admin(&state, auth_token)?;
+        log_filter(&state)?
+    };
+    log_level_result(filter.reset(), filter.initial())
+}
+
+async fn handle_gateway_shutdown(
+    state: &Arc<RwLock<GatewayState>>,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let state = state.read().await;
+    require_admin(&state, auth_token)?;
+
+    if state.shutdown.request() {
+        tracing::info!("Shutdown requested over RPC");
+    }
+    Ok(serde_json::json!({
+        "phase": state.shutdown.phase(),
+        "active_runs": state.shutdown.active_runs(),
+        "drain_timeout_secs": state.config.drain_timeout.as_secs(),
+    }))
+}
+
+// ============================================================================
+// Approval RPC Handlers
+// ============================================================================
+
+/// Name of the user allowed to decide approvals (admins and operators).
+pub fn approver(state: &GatewayState, token: Option<&str>) -> Result<String, (i32, String)> {
+    if !state.auth.config.enabled {
+        return Ok("system".to_string());
+    }
+    let token = token.ok_or_else(|| (rpc::UNAUTHORIZED, "Not authenticated".to_string()))?;
+    let claims = state
+        .auth
+        .validate_token(token)
+        .map_err(|e| (rpc::UNAUTHORIZED, format!("Invalid token: {e}")))?;
+
+    if !claims.role.can_manage_sessions() {
+        return Err((
+            rpc::FORBIDDEN,
+            "Admin or operator role required".to_string(),
+        ));
+    }
+    Ok(claims.username)
+}
+
+async fn handle_approvals_list(
+    state: &Arc<RwLock<GatewayState>>,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let state = state.read().await;
+    let access = state.namespace_access(auth_token);
+    let pending: Vec<_> = state
+        .approvals
+        .pending()
+        .into_iter()
+        .filter(|request| access.can_view(state.tenants.namespace_of_approval(request)))
+        .collect();
+    drop(state);
+    Ok(serde_json::json!({
+        "count": pending.len(),
+        "approvals": pending,
+    }))
+}
+
+async fn handle_approvals_decide(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+    approve: bool,
+) -> RpcResult {
+    let id = params["id"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing id".to_string()))?;
+
+    let state = state.read().await;
+    let by = approver(&state, auth_token)?;
+    let namespace = state
+        .approvals
+        .pending()
+        .into_iter()
+        .find(|request| request.id == id)
+        .map(|request| {
+            state
+                .tenants
+                .namespace_of_approval(&request)
+                .map(String::from)
+        });
+    let access = state.namespace_access(auth_token);
+    match namespace {
+        Some(namespace) if access.can_manage(namespace.as_deref()) => {}
+        Some(namespace) if access.can_view(namespace.as_deref()) => {
+            return Err((
+                rpc::FORBIDDEN,
+                "Not permitted to decide approvals in this namespace".to_string(),
+            ));
+        }
+        _ => return Err((rpc::NOT_FOUND, format!("Approval request not found: {id}"))),
+    }
+    let decision = if approve {
+        ApprovalDecision::Approved { by }
+    } else {
+        ApprovalDecision::Denied {
+            by,
+            reason: params["reason"].as_str().map(str::to_string),
+        }
+    };
+
+    let request = state
+        .approvals
+        .decide(id, decision.clone())
+        .map_err(|e| (rpc::NOT_FOUND, e.to_string()))?;
+    drop(state);
+
+    Ok(serde_json::json!({
+        "request": request,
+        "decision": decision,
+    }))
+}
+
+// ============================================================================
+// Peer Profile RPC Handlers
+// ============================================================================
+
+/// Read a required string parameter.
+fn required_str<'a>(params: &'a serde_json::Value, name: &str) -> Result<&'a str, (i32, String)> {
+    params[name]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, format!("Missing {name}")))
+}
+
+async fn handle_peers_list(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let state = state.read().await;
+    let access = state.namespace_access(auth_token);
+    let peers: Vec<_> = state
+        .peer_profiles
+        .list(params["channel"].as_str())
+        .map_err(|e| (rpc::INTERNAL_ERROR, e.to_string()))?
+        .into_iter()
+        .filter(|profile| access.can_view(state.tenants.namespace_of_channel(&profile.channel)))
+        .collect();
+    drop(state);
+    Ok(serde_json::json!({ "peers": peers }))
+}
+
+async fn handle_peers_get(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let channel = required_str(params, "channel")?;
+    let peer_id = required_str(params, "peer_id")?;
+    let profiles = {
+        let state = state.read().await;
+        authorize_channel(&state, auth_token, channel, false)?;
+        state.peer_profiles.clone()
+    };
+    let profile = profiles
+        .get(channel, peer_id)
+        .map_err(|e| (rpc::INTERNAL_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                rpc::NOT_FOUND,
+                format!("No profile for {channel}:{peer_id}"),
+            )
+        })?;
+    Ok(serde_json::json!({ "profile": profile }))
+}
+
+/// Set profile fields. Values set here take precedence over learned ones.
+async fn handle_peers_update(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let channel = required_str(params, "channel")?;
+    let peer_id = required_str(params, "peer_id")?;
+    let update: ProfileUpdate = serde_json::from_value(params.clone())
+        .map_err(|e| (rpc::INVALID_PARAMS, format!("Invalid profile: {e}")))?;
+    if update.is_empty() {
+        return Err((rpc::INVALID_PARAMS, "Nothing to update".to_string()));
+    }
+
+    let profiles = {
+        let state = state.read().await;
+        approver(&state, auth_token)?;
+        authorize_channel(&state, auth_token, channel, true)?;
+        state.peer_profiles.clone()
+    };
+    let profile = profiles
+        .update(channel, peer_id, &update, ProfileSource::Explicit)
+        .map_err(|e| match e {
+            PeerProfileError::Invalid(_) => (rpc::INVALID_PARAMS, e.to_string()),
+            _ => (rpc::INTERNAL_ERROR, e.to_string()),
+        })?;
+    Ok(serde_json::json!({ "profile": profile }))
+}
+
+async fn handle_peers_delete(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let channel = required_str(params, "channel")?;
+    let peer_id = required_str(params, "peer_id")?;
+    let profiles = {
+        let state = state.read().await;
+        approver(&state, auth_token)?;
+        authorize_channel(&state, auth_token, channel, true)?;
+        state.peer_profiles.clone()
+    };
+    let deleted = profiles
+        .delete(channel, peer_id)
+        .map_err(|e| (rpc::INTERNAL_ERROR, e.to_string()))?;
+    Ok(serde_json::json!({ "deleted": deleted }))
+}
+
+/// Erase everything stored about a peer, across sessions and the
+/// namespaces the caller manages.
+async fn handle_privacy_forget(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let channel = required_str(params, "channel")?;
+    let peer_id = required_str(params, "peer_id")?;
+    let state = state.read().await;
+    let requested_by = approver(&state, auth_token)?;
+    authorize_channel(&state, auth_token, channel, true)?;
+    let access = state.namespace_access(auth_token);
+
+    let report = privacy::forget_peer(&state, &access, channel, peer_id, &requested_by)
+        .await
+        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to forget peer: {e}")))?;
+    drop(state);
+    serde_json::to_value(&report)
+        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Serialization error: {e}")))
+}
+
 // ============================================================================
 // System RPC Handlers
 // ============================================================================
 
 async fn handle_system_health(state: &Arc<RwLock<GatewayState>>) -> RpcResult {
     let state = state.read().await;
+    let status = if state.supervisor.is_healthy() {
+        "healthy"
+    } else {
+        "degraded"
+    };
 
     Ok(serde_json::json!({
-        "status": "healthy",
+        "status": status,
         "auth_enabled": state.auth.config.enabled,
         "users_configured": !state.auth.users.is_empty(),
         "agents_count": state.agents.len(),
+        "channels": state.supervisor.health(),
+        "limits": state.limit_counters.snapshot(),
+        "rate_limit": state.rate_limiter.stats(),
     }))
 }
 
+async fn handle_system_startup(state: &Arc<RwLock<GatewayState>>) -> RpcResult {
+    let report = state
+        .read()
+        .await
+        .startup
+        .clone()
+        .ok_or_else(|| (rpc::NOT_FOUND, "Gateway is still starting".to_string()))?;
+
+    serde_json::to_value(report).map_err(|e| (rpc::INTERNAL_ERROR, e.to_string()))
+}
+
 async fn handle_system_version() -> RpcResult {
     Ok(serde_json::json!({
         "version": env!("CARGO_PKG_VERSION"),
@@ -979,6 +2884,13 @@ async fn handle_system_version() -> RpcResult {
     }))
 }
 
+/// The method catalog, marking which methods this gateway's auth settings
+/// require a token for.
+async fn handle_rpc_discover(state: &Arc<RwLock<GatewayState>>) -> RpcResult {
+    let state = state.read().await;
+    Ok(catalog::openrpc(|method| state.auth.requires_auth(method)))
+}
+
 /// Handle event subscription request.
 /// This is a WebSocket-only method that returns an ack.
 /// The actual event streaming is handled separately.
@@ -993,9 +2905,10 @@ async fn handle_events_subscribe() -> RpcResult {
 // Session RPC Handlers
 // ============================================================================
 
-async fn handle_session_create(
+pub async fn handle_session_create(
     state: &Arc<RwLock<GatewayState>>,
     params: &serde_json::Value,
+    auth_token: Option<&str>,
 ) -> RpcResult {
     let agent_id = params["agent_id"].as_str().unwrap_or("default").to_string();
     let channel = params["channel"].as_str().unwrap_or("api").to_string();
@@ -1010,10 +2923,31 @@ async fn handle_session_create(
         "gateway",
         openclaw_core::types::PeerType::Dm,
         &openclaw_core::types::PeerId::new(&peer_id),
+        params["thread_id"].as_str(),
     );
 
-    let event = SessionEvent::new(
-        session_key.clone(),
+    let state = state.read().await;
+
+    // Assign to an experiment variant if one targets this agent
+    let assignment = state
+        .router
+        .assign_variant(&AgentId::new(&agent_id), &session_key);
+    let agent_id = assignment
+        .as_ref()
+        .map_or(agent_id, |a| a.agent_id.as_ref().to_string());
+
+    let namespace = state.tenants.namespace_of(&agent_id, &channel);
+    if !state.namespace_access(auth_token).can_manage(namespace) {
+        return Err((
+            rpc::FORBIDDEN,
+            format!("Not permitted to create sessions for agent {agent_id}"),
+        ));
+    }
+    let store = state.store_for_session(&session_key).clone();
+    drop(state);
+
+    let event = SessionEvent::new(
+        session_key.clone(),
         agent_id.clone(),
         SessionEventKind::SessionStarted {
             channel: channel.clone(),
@@ -1021,25 +2955,44 @@ async fn handle_session_create(
         },
     );
 
-    let state = state.read().await;
-    state.event_store.append(&event).map_err(|e| {
+    store.append(&event).map_err(|e| {
         (
             rpc::INTERNAL_ERROR,
             format!("Failed to create session: {e}"),
         )
     })?;
 
+    if let Some(assignment) = &assignment {
+        let event = SessionEvent::new(
+            session_key.clone(),
+            agent_id.clone(),
+            SessionEventKind::ExperimentAssigned {
+                experiment: assignment.experiment.clone(),
+                variant: assignment.variant.clone(),
+            },
+        );
+        store.append(&event).map_err(|e| {
+            (
+                rpc::INTERNAL_ERROR,
+                format!("Failed to record variant: {e}"),
+            )
+        })?;
+    }
+
     Ok(serde_json::json!({
         "session_key": session_key.as_ref(),
         "agent_id": agent_id,
         "channel": channel,
         "peer_id": peer_id,
+        "experiment": assignment.as_ref().map(|a| &a.experiment),
+        "variant": assignment.as_ref().map(|a| &a.variant),
     }))
 }
 
-async fn handle_session_message(
+pub async fn handle_session_message(
     state: &Arc<RwLock<GatewayState>>,
     params: &serde_json::Value,
+    auth_token: Option<&str>,
 ) -> RpcResult {
     let session_key_str = params["session_key"]
         .as_str()
@@ -1047,43 +3000,274 @@ async fn handle_session_message(
     let message = params["message"]
         .as_str()
         .ok_or((rpc::INVALID_PARAMS, "Missing message".to_string()))?;
-    let agent_id_str = params["agent_id"].as_str().unwrap_or("default");
-
+    let attachments: Vec<Attachment> = match params.get("attachments") {
+        Some(value) if !value.is_null() => serde_json::from_value(value.clone())
+            .map_err(|e| (rpc::INVALID_PARAMS, format!("Invalid attachments: {e}")))?,
+        _ => vec![],
+    };
+    let response_format = match params.get("schema") {
+        Some(schema) if !schema.is_null() => {
+            let format = ResponseFormat::new(
+                params["schema_name"].as_str().unwrap_or("response"),
+                schema.clone(),
+            );
+            OutputValidator::new(&format).map_err(|e| (rpc::INVALID_PARAMS, e))?;
+            Some(format)
+        }
+        _ => None,
+    };
     let session_key = SessionKey::new(session_key_str);
     let state = state.read().await;
 
-    // Log inbound message
+    // Get or create session projection; new sessions with client-chosen
+    // keys go to the requested agent's store
+    let requested_agent = params["agent_id"].as_str().unwrap_or("default");
+    let existing = state.store_for_session(&session_key);
+    let projection = existing.get_projection(&session_key).ok();
+    let store = if projection.is_some() || session_key.agent_id().is_some() {
+        existing
+    } else {
+        state.store_for_agent(requested_agent)
+    };
+    let projection = projection.unwrap_or_else(|| {
+        SessionProjection::new(
+            session_key.clone(),
+            requested_agent.to_string(),
+            ChannelId::new("api"),
+            "anonymous".to_string(),
+        )
+    });
+    authorize_session(&state, auth_token, &projection, true)?;
+
+    // Sessions in an experiment stay pinned to their variant's agent
+    let agent_id_owned = if projection.variant.is_some() {
+        projection.agent_id.clone()
+    } else {
+        params["agent_id"]
+            .as_str()
+            .unwrap_or(&projection.agent_id)
+            .to_string()
+    };
+    let agent_id_str = agent_id_owned.as_str();
+
+    // Count the message so stopping its channel waits for the answer
+    let _message = state
+        .supervisor
+        .begin_message(projection.channel.as_ref())
+        .map_err(|e| (rpc::UNAVAILABLE, e.to_string()))?;
+
+    // Throttle before doing any expensive work
+    if let RateLimitDecision::Limited {
+        retry_after,
+        cooldown_message,
+    } = state.inbound_limiter.check(
+        &projection.channel,
+        &openclaw_core::types::PeerId::new(&projection.peer_id),
+    ) {
+        let secs = retry_after.as_secs().max(1);
+        let response = cooldown_message.unwrap_or_else(|| {
+            state.localizer.message(
+                &peer_locale(&state, &projection),
+                "rate-limited",
+                &[("retry_after", FluentValue::from(secs))],
+            )
+        });
+        return Ok(serde_json::json!({
+            "response": response,
+            "rate_limited": true,
+            "retry_after_secs": secs,
+        }));
+    }
+
+    // `/feedback` rates the previous response instead of reaching the agent
+    if let Some(cmd) = parse_feedback_command(message) {
+        record_feedback(
+            store,
+            &session_key,
+            agent_id_str,
+            None,
+            cmd.rating,
+            cmd.comment,
+            "command",
+        )?;
+        return Ok(serde_json::json!({
+            "response": state.localizer.message(
+                &peer_locale(&state, &projection),
+                "feedback-thanks",
+                &[],
+            ),
+            "feedback": cmd.rating,
+        }));
+    }
+
+    // `/reset`, `/model`, `/usage`, and `/help` are answered here, for
+    // senders the agent's command allowlist lets in
+    if let Some(command) = parse_chat_command(message) {
+        let sender = params["sender_id"].as_str().unwrap_or(&projection.peer_id);
+        let peer = openclaw_core::types::PeerId::new(sender);
+        match state
+            .chat_commands
+            .access(agent_id_str, &projection.channel, &peer, &command)
+        {
+            CommandAccess::Run => {
+                return run_chat_command(&state, store, &projection, agent_id_str, command);
+            }
+            CommandAccess::Denied => {
+                return Ok(serde_json::json!({
+                    "response": state.localizer.message(
+                        &peer_locale(&state, &projection),
+                        "command-not-allowed",
+                        &[("command", FluentValue::from(command.name()))],
+                    ),
+                    "command": command.name(),
+                    "denied": true,
+                }));
+            }
+            CommandAccess::Off => {}
+        }
+    }
+
+    // `/urgent` opens the session for a while, even outside working hours
+    let now = Utc::now();
+    let channel_id = projection.channel.as_ref();
+    let mut message = message;
+    if let Some(rest) = parse_urgent_command(message) {
+        if let Some(until) =
+            state
+                .working_hours
+                .grant_urgent(channel_id, agent_id_str, session_key.as_ref(), now)
+        {
+            if rest.is_empty() && attachments.is_empty() {
+                let minutes = (until - now).num_minutes();
+                return Ok(serde_json::json!({
+                    "response": state.localizer.message(
+                        &peer_locale(&state, &projection),
+                        "urgent-open",
+                        &[("minutes", FluentValue::from(minutes))],
+                    ),
+                    "urgent_until": until,
+                }));
+            }
+            message = rest;
+        }
+    }
+
+    // Outside working hours the agent isn't asked; the message is answered
+    // with a notice, or held until hours resume
+    if let HoursDecision::Closed {
+        opens_at,
+        opens_at_local,
+        action,
+        message: configured,
+        allow_urgent,
+    } = state
+        .working_hours
+        .check(channel_id, agent_id_str, session_key.as_ref(), now)
+    {
+        let queued = action == OutsideHoursAction::Queue && opens_at.is_some();
+        if let (true, Some(due)) = (queued, opens_at) {
+            state.after_hours.hold(HeldMessage {
+                session_key: session_key.clone(),
+                message: message.to_string(),
+                attachments,
+                due,
+            });
+        }
+        let response = configured.unwrap_or_else(|| {
+            outside_hours_notice(
+                &state.localizer,
+                &peer_locale(&state, &projection),
+                opens_at_local.as_deref(),
+                queued,
+                allow_urgent,
+            )
+        });
+        return Ok(serde_json::json!({
+            "response": response,
+            "outside_hours": true,
+            "queued": queued,
+            "opens_at": opens_at,
+        }));
+    }
+
+    // Drop attachments the channel's policy turns away and tell the sender
+    let (attachments, rejected) = state
+        .attachment_policy
+        .check(projection.channel.as_ref(), attachments)
+        .await;
+    let rejection_notice = if rejected.is_empty() {
+        None
+    } else {
+        Some(reject_attachments(&state, store, &projection, agent_id_str, &rejected).await)
+    };
+    if attachments.is_empty() && message.trim().is_empty() {
+        if let Some(notice) = rejection_notice {
+            return Ok(serde_json::json!({
+                "response": notice,
+                "attachments_rejected": rejected.len(),
+            }));
+        }
+    }
+
+    // Answer rapid consecutive messages as one turn; callers whose message
+    // joined another caller's batch get no response of their own
+    let window = state
+        .debouncer
+        .window(projection.channel.as_ref(), agent_id_str);
+    let DebouncedBatch {
+        message,
+        attachments,
+        count,
+        turn: _turn,
+    } = match state
+        .debouncer
+        .submit(session_key.as_ref(), window, message, attachments)
+        .await
+    {
+        Debounced::Flush(batch) => batch,
+        Debounced::Coalesced => {
+            return Ok(serde_json::json!({
+                "response": null,
+                "coalesced": true,
+            }));
+        }
+    };
+
     let recv_event = SessionEvent::new(
         session_key.clone(),
         agent_id_str.to_string(),
         SessionEventKind::MessageReceived {
-            content: message.to_string(),
-            attachments: vec![],
+            content: message.clone(),
+            attachments: attachments.iter().map(AttachmentMeta::from).collect(),
+            sender_id: params["sender_id"]
+                .as_str()
+                .filter(|sender| *sender != projection.peer_id)
+                .map(String::from),
         },
     );
-    state
-        .event_store
-        .append(&recv_event)
-        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to log message: {e}")))?;
 
-    // Get agent runtime
+    // Get agent runtime, on the model picked with `/model` if any
     let agent = state.agents.get(agent_id_str).ok_or((
         rpc::INVALID_PARAMS,
         format!("Agent not found: {agent_id_str}"),
     ))?;
+    let switched;
+    let agent = match session_model(&state, &projection, agent_id_str) {
+        Some(model) if model != agent.model() => {
+            switched = Arc::new(agent.with_overrides(&AgentOverrides {
+                model: Some(model.to_string()),
+                ..AgentOverrides::default()
+            }));
+            &switched
+        }
+        _ => agent,
+    };
 
-    // Get or create session projection
-    let projection = state
-        .event_store
-        .get_projection(&session_key)
-        .unwrap_or_else(|_| {
-            SessionProjection::new(
-                session_key.clone(),
-                agent_id_str.to_string(),
-                ChannelId::new("api"),
-                "anonymous".to_string(),
-            )
-        });
+    // Track the run so shutdown can drain it
+    let _run = state
+        .shutdown
+        .begin_run()
+        .ok_or_else(|| (rpc::UNAVAILABLE, "Gateway is shutting down".to_string()))?;
 
     // Build agent context and process
     let mut ctx = AgentContext::new(
@@ -1093,10 +3277,82 @@ async fn handle_session_message(
         state.tool_registry.clone(),
     );
 
-    let response = agent
-        .process_message(&mut ctx, message)
-        .await
-        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Agent error: {e}")))?;
+    let started = std::time::Instant::now();
+    let reply = match &response_format {
+        Some(format) => {
+            agent
+                .process_structured(&mut ctx, &message, &attachments, format)
+                .await
+        }
+        None => {
+            agent
+                .process_with_attachments(&mut ctx, &message, &attachments)
+                .await
+        }
+    };
+
+    // Log the inbound message, unless moderation kept it from the agent so
+    // it stays out of the conversation
+    let inbound_blocked = matches!(
+        &reply,
+        Err(AgentRuntimeError::Blocked(violation)) if violation.stage == ModerationStage::Inbound
+    );
+    if !inbound_blocked {
+        store
+            .append(&recv_event)
+            .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to log message: {e}")))?;
+    }
+
+    let reply = match reply {
+        Ok(reply) => reply,
+        Err(AgentRuntimeError::Busy) => {
+            return Ok(serde_json::json!({
+                "response": agent.busy_message(),
+                "busy": true,
+            }));
+        }
+        Err(AgentRuntimeError::Blocked(violation)) => {
+            record_moderation(&state, store, &session_key, agent_id_str, agent, &violation);
+            let response = agent.moderation().blocked_message().map_or_else(
+                || {
+                    let locale = peer_locale(&state, &ctx.session);
+                    state.localizer.message(&locale, "moderation-blocked", &[])
+                },
+                str::to_string,
+            );
+            return Ok(serde_json::json!({
+                "response": response,
+                "blocked": violation.stage,
+            }));
+        }
+        Err(e) => return Err((rpc::INTERNAL_ERROR, format!("Agent error: {e}"))),
+    };
+    let latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    let response = reply.content;
+    let citations = reply.citations;
+    let planned_tools = reply.planned_tools;
+    for violation in &reply.flagged {
+        record_moderation(&state, store, &session_key, agent_id_str, agent, violation);
+    }
+
+    // Record tool calls the agent's policy refused
+    for denied in &reply.denied_tools {
+        let event = SessionEvent::new(
+            session_key.clone(),
+            agent_id_str.to_string(),
+            SessionEventKind::ToolDenied {
+                tool_name: denied.name.clone(),
+                params: denied.input.clone(),
+                reason: denied.reason.clone(),
+            },
+        );
+        store.append(&event).map_err(|e| {
+            (
+                rpc::INTERNAL_ERROR,
+                format!("Failed to log tool denial: {e}"),
+            )
+        })?;
+    }
 
     // Log agent response
     let resp_event = SessionEvent::new(
@@ -1104,23 +3360,271 @@ async fn handle_session_message(
         agent_id_str.to_string(),
         SessionEventKind::AgentResponse {
             content: response.clone(),
-            model: String::new(),
-            tokens: openclaw_core::types::TokenUsage::default(),
+            model: reply.model,
+            tokens: reply.usage,
+            latency_ms: Some(latency_ms),
+            citations: citations.clone(),
         },
     );
-    state
-        .event_store
+    store
         .append(&resp_event)
         .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to log response: {e}")))?;
 
-    Ok(serde_json::json!({
+    let mut result = serde_json::json!({
         "response": response,
+        "citations": citations,
+    });
+    if let Some(data) = reply.data {
+        result["data"] = data;
+    }
+    if !planned_tools.is_empty() {
+        result["planned_tools"] = serde_json::json!(planned_tools);
+    }
+    if !reply.denied_tools.is_empty() {
+        result["denied_tools"] = serde_json::json!(reply.denied_tools);
+    }
+    if count > 1 {
+        result["coalesced_messages"] = serde_json::json!(count);
+    }
+    if let Some(notice) = rejection_notice {
+        result["attachments_rejected"] = serde_json::json!(rejected.len());
+        result["rejection_notice"] = serde_json::json!(notice);
+    }
+    Ok(result)
+}
+
+/// Record content moderation caught, and tell operators if the agent
+/// notifies admins.
+fn record_moderation(
+    state: &GatewayState,
+    store: &EventStore,
+    session_key: &SessionKey,
+    agent_id: &str,
+    agent: &AgentRuntime,
+    violation: &Violation,
+) {
+    let event = SessionEvent::new(
+        session_key.clone(),
+        agent_id.to_string(),
+        SessionEventKind::ContentModerated {
+            stage: violation.stage.to_string(),
+            moderator: violation.moderator.clone(),
+            reason: violation.reason.clone(),
+            categories: violation.categories.clone(),
+            blocked: violation.blocked,
+        },
+    );
+    if let Err(e) = store.append(&event) {
+        tracing::warn!("Failed to record moderation: {}", e);
+    }
+    if agent.moderation().notify_admins() {
+        let _ = state.events.broadcast(UiEvent::ContentModerated {
+            session_key: session_key.to_string(),
+            agent_id: agent_id.to_string(),
+            stage: violation.stage.to_string(),
+            moderator: violation.moderator.clone(),
+            reason: violation.reason.clone(),
+            categories: violation.categories.clone(),
+            blocked: violation.blocked,
+        });
+    }
+}
+
+/// Locale for system messages to a session's peer: the peer's configured
+/// locale, else its profile language, else its channel's locale.
+fn peer_locale(state: &GatewayState, projection: &SessionProjection) -> String {
+    let channel = projection.channel.as_ref();
+    let locale = &state.config.locale;
+    if let Some(tag) = locale.for_peer(channel, &projection.peer_id) {
+        return tag.to_string();
+    }
+    let language = match state.peer_profiles.get(channel, &projection.peer_id) {
+        Ok(profile) => profile.and_then(|p| p.language),
+        Err(e) => {
+            tracing::warn!("Failed to load peer profile: {}", e);
+            None
+        }
+    };
+    language.unwrap_or_else(|| locale.for_channel(channel).to_string())
+}
+
+/// Record rejected attachments and tell the sender, through the session's
+/// channel when it has an adapter. Returns the notice.
+async fn reject_attachments(
+    state: &GatewayState,
+    store: &EventStore,
+    projection: &SessionProjection,
+    agent_id: &str,
+    rejected: &[Rejection],
+) -> String {
+    for rejection in rejected {
+        tracing::info!(
+            session = %projection.session_key,
+            reason = rejection.reason.code(),
+            "Rejected attachment {}: {}",
+            rejection.name(),
+            rejection.reason
+        );
+        let detail = match &rejection.reason {
+            RejectionReason::Unscanned { error } => format!("{}: {error}", rejection.reason),
+            reason => reason.to_string(),
+        };
+        let event = SessionEvent::new(
+            projection.session_key.clone(),
+            agent_id.to_string(),
+            SessionEventKind::AttachmentRejected {
+                attachment: AttachmentMeta::from(&rejection.attachment),
+                filename: rejection.attachment.filename.clone(),
+                reason: rejection.reason.code().to_string(),
+                detail,
+            },
+        );
+        if let Err(e) = store.append(&event) {
+            tracing::warn!("Failed to record rejected attachment: {}", e);
+        }
+    }
+
+    let notice =
+        state
+            .attachment_policy
+            .notice(rejected, &state.localizer, &peer_locale(state, projection));
+    let channel = state
+        .channels
+        .read()
+        .await
+        .get_outbound(projection.channel.as_ref())
+        .cloned();
+    if let Some(channel) = channel {
+        let ctx = OutboundContext {
+            chat_id: projection.peer_id.clone(),
+            reply_to: None,
+            thread_id: None,
+        };
+        if let Err(e) = state.outbound.send(channel.as_ref(), ctx, &notice).await {
+            tracing::warn!("Failed to send attachment rejection notice: {}", e);
+        }
+    }
+    notice
+}
+
+/// Session state key holding the model picked with `/model`.
+const SESSION_MODEL_KEY: &str = "model";
+
+/// The model a session picked with `/model`, if its agent still allows it.
+fn session_model<'a>(
+    state: &GatewayState,
+    projection: &'a SessionProjection,
+    agent_id: &str,
+) -> Option<&'a str> {
+    let model = projection.custom_state.get(SESSION_MODEL_KEY)?.as_str()?;
+    state
+        .chat_commands
+        .models(agent_id)
+        .iter()
+        .any(|m| m == model)
+        .then_some(model)
+}
+
+/// Answer a chat command without asking the agent.
+fn run_chat_command(
+    state: &GatewayState,
+    store: &EventStore,
+    projection: &SessionProjection,
+    agent_id: &str,
+    command: ChatCommand<'_>,
+) -> RpcResult {
+    let append = |kind| {
+        let event = SessionEvent::new(projection.session_key.clone(), agent_id.to_string(), kind);
+        store
+            .append(&event)
+            .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to run command: {e}")))
+    };
+
+    let (id, args): (&str, Vec<(&str, FluentValue<'_>)>) = match command {
+        ChatCommand::Reset => {
+            append(SessionEventKind::SessionEnded {
+                reason: "reset".to_string(),
+            })?;
+            ("session-reset", vec![])
+        }
+        ChatCommand::Model(requested) => {
+            let models = state.chat_commands.models(agent_id);
+            let allowed = FluentValue::from(models.join(", "));
+            let current = session_model(state, projection, agent_id)
+                .or_else(|| state.agents.get(agent_id).map(|agent| agent.model()))
+                .unwrap_or_default();
+            match requested {
+                _ if models.is_empty() => ("model-fixed", vec![("model", current.into())]),
+                None => (
+                    "model-current",
+                    vec![("model", current.into()), ("models", allowed)],
+                ),
+                Some(model) if models.iter().any(|m| m == model) => {
+                    append(SessionEventKind::StateChanged {
+                        key: SESSION_MODEL_KEY.to_string(),
+                        value: serde_json::Value::from(model),
+                    })?;
+                    ("model-switched", vec![("model", model.into())])
+                }
+                Some(model) => (
+                    "model-unknown",
+                    vec![("model", model.into()), ("models", allowed)],
+                ),
+            }
+        }
+        ChatCommand::Usage => {
+            let events = store
+                .get_events(&projection.session_key)
+                .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to get events: {e}")))?;
+            let (mut input, mut output, mut responses) = (0_u64, 0_u64, 0_u64);
+            for event in events {
+                match event.kind {
+                    SessionEventKind::AgentResponse { tokens, .. } => {
+                        responses += 1;
+                        input += tokens.input_tokens;
+                        output += tokens.output_tokens;
+                    }
+                    SessionEventKind::DelegatedUsage { tokens, .. } => {
+                        input += tokens.input_tokens;
+                        output += tokens.output_tokens;
+                    }
+                    _ => {}
+                }
+            }
+            (
+                "usage-summary",
+                vec![
+                    ("total", (input + output).into()),
+                    ("input", input.into()),
+                    ("output", output.into()),
+                    ("responses", responses.into()),
+                ],
+            )
+        }
+        ChatCommand::Help => {
+            let commands: Vec<String> = state
+                .chat_commands
+                .available(agent_id)
+                .into_iter()
+                .map(|name| format!("/{name}"))
+                .collect();
+            (
+                "commands-help",
+                vec![("commands", commands.join(", ").into())],
+            )
+        }
+    };
+
+    Ok(serde_json::json!({
+        "response": state.localizer.message(&peer_locale(state, projection), id, &args),
+        "command": command.name(),
     }))
 }
 
 async fn handle_session_history(
     state: &Arc<RwLock<GatewayState>>,
     params: &serde_json::Value,
+    auth_token: Option<&str>,
 ) -> RpcResult {
     let session_key_str = params["session_key"]
         .as_str()
@@ -1130,9 +3634,10 @@ async fn handle_session_history(
     let state = state.read().await;
 
     let projection = state
-        .event_store
+        .store_for_session(&session_key)
         .get_projection(&session_key)
         .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to get session: {e}")))?;
+    authorize_session(&state, auth_token, &projection, false)?;
 
     serde_json::to_value(&projection)
         .map_err(|e| (rpc::INTERNAL_ERROR, format!("Serialization error: {e}")))
@@ -1141,6 +3646,7 @@ async fn handle_session_history(
 async fn handle_session_end(
     state: &Arc<RwLock<GatewayState>>,
     params: &serde_json::Value,
+    auth_token: Option<&str>,
 ) -> RpcResult {
     let session_key_str = params["session_key"]
         .as_str()
@@ -1152,6 +3658,10 @@ async fn handle_session_end(
 
     let session_key = SessionKey::new(session_key_str);
     let state = state.read().await;
+    let store = state.store_for_session(&session_key);
+    if let Ok(projection) = store.get_projection(&session_key) {
+        authorize_session(&state, auth_token, &projection, true)?;
+    }
 
     let event = SessionEvent::new(
         session_key,
@@ -1161,8 +3671,7 @@ async fn handle_session_end(
         },
     );
 
-    state
-        .event_store
+    store
         .append(&event)
         .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to end session: {e}")))?;
 
@@ -1172,97 +3681,493 @@ async fn handle_session_end(
     }))
 }
 
-/// Extended session list with filtering and pagination.
-async fn handle_session_list(
+/// Look up a session's projection, mapping a miss to `NOT_FOUND`.
+pub fn session_projection(
+    state: &GatewayState,
+    session_key: &SessionKey,
+) -> Result<SessionProjection, (i32, String)> {
+    state
+        .store_for_session(session_key)
+        .get_projection(session_key)
+        .map_err(|e| (rpc::NOT_FOUND, format!("Session not found: {e}")))
+}
+
+/// Check the caller may see (or, with `manage`, change) a session.
+///
+/// Sessions in namespaces the caller can't see are reported as not found.
+pub fn authorize_session(
+    state: &GatewayState,
+    auth_token: Option<&str>,
+    projection: &SessionProjection,
+    manage: bool,
+) -> Result<(), (i32, String)> {
+    let access = state.namespace_access(auth_token);
+    let namespace = state.tenants.namespace_of_session(projection);
+    if !access.can_view(namespace) {
+        return Err((
+            rpc::NOT_FOUND,
+            format!("Session not found: {}", projection.session_key),
+        ));
+    }
+    if manage && !access.can_manage(namespace) {
+        return Err((
+            rpc::FORBIDDEN,
+            "Not permitted to change sessions in this namespace".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Check the caller may see (or, with `manage`, use) a channel.
+///
+/// Channels in namespaces the caller can't see are reported as not found.
+pub fn authorize_channel(
+    state: &GatewayState,
+    auth_token: Option<&str>,
+    channel_id: &str,
+    manage: bool,
+) -> Result<(), (i32, String)> {
+    let access = state.namespace_access(auth_token);
+    let namespace = state.tenants.namespace_of_channel(channel_id);
+    if !access.can_view(namespace) {
+        return Err((rpc::NOT_FOUND, format!("Channel not found: {channel_id}")));
+    }
+    if manage && !access.can_manage(namespace) {
+        return Err((
+            rpc::FORBIDDEN,
+            "Not permitted to use channels in this namespace".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Check the caller may see an agent.
+///
+/// Agents in namespaces the caller can't see are reported as not found.
+pub fn authorize_agent(
+    state: &GatewayState,
+    auth_token: Option<&str>,
+    agent_id: &str,
+) -> Result<(), (i32, String)> {
+    let namespace = state
+        .tenants
+        .namespace_of_agent(agent_id)
+        .map(|ns| ns.id.as_str());
+    if !state.namespace_access(auth_token).can_view(namespace) {
+        return Err((rpc::NOT_FOUND, format!("Agent not found: {agent_id}")));
+    }
+    Ok(())
+}
+
+/// Check the caller may see (or, with `manage`, run) workflows. Workflows
+/// span agents and channels, so they belong to no namespace.
+fn authorize_workflows(
+    state: &GatewayState,
+    auth_token: Option<&str>,
+    manage: bool,
+) -> Result<(), (i32, String)> {
+    let access = state.namespace_access(auth_token);
+    if !access.can_view(None) || (manage && !access.can_manage(None)) {
+        return Err((
+            rpc::FORBIDDEN,
+            "Workflows are available to admins only".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Soft-delete a session; it is hidden but kept until the retention period ends.
+async fn handle_session_delete(
     state: &Arc<RwLock<GatewayState>>,
     params: &serde_json::Value,
+    auth_token: Option<&str>,
 ) -> RpcResult {
-    let limit = params["limit"].as_u64().unwrap_or(50) as usize;
-    let offset = params["offset"].as_u64().unwrap_or(0) as usize;
-    let filter_channel = params["channel"].as_str();
-    let filter_agent = params["agent"].as_str();
-    let filter_state = params["state"].as_str();
+    let session_key_str = params["session_key"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing session_key".to_string()))?;
+    let session_key = SessionKey::new(session_key_str);
 
     let state = state.read().await;
-    let session_keys = state
-        .event_store
-        .list_sessions()
-        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to list sessions: {e}")))?;
-
-    // Get projections and apply filters
-    let mut sessions: Vec<SessionProjection> = session_keys
-        .into_iter()
-        .filter_map(|key| state.event_store.get_projection(&key).ok())
-        .filter(|p| {
-            // Apply filters
-            if let Some(ch) = filter_channel {
-                if p.channel.as_ref() != ch {
-                    return false;
-                }
-            }
-            if let Some(agent) = filter_agent {
-                if p.agent_id != agent {
-                    return false;
-                }
-            }
-            if let Some(st) = filter_state {
-                let state_match = match st {
-                    "active" => p.state == SessionState::Active,
-                    "paused" => p.state == SessionState::Paused,
-                    "ended" => p.state == SessionState::Ended,
-                    _ => true,
-                };
-                if !state_match {
-                    return false;
-                }
-            }
-            true
-        })
-        .collect();
-
-    // Sort by last activity (most recent first)
-    sessions.sort_by(|a, b| b.last_activity.cmp(&a.last_activity));
-
-    let total = sessions.len();
+    let projection = session_projection(&state, &session_key)?;
+    authorize_session(&state, auth_token, &projection, true)?;
+    if let Some(deleted_at) = projection.deleted_at {
+        return Ok(serde_json::json!({
+            "status": "deleted",
+            "deleted_at": deleted_at,
+        }));
+    }
 
-    // Apply pagination
-    let sessions: Vec<_> = sessions.into_iter().skip(offset).take(limit).collect();
+    let event = SessionEvent::new(
+        session_key.clone(),
+        projection.agent_id,
+        SessionEventKind::SessionDeleted {
+            reason: params["reason"].as_str().map(String::from),
+        },
+    );
+    state
+        .store_for_session(&session_key)
+        .append(&event)
+        .map_err(|e| {
+            (
+                rpc::INTERNAL_ERROR,
+                format!("Failed to delete session: {e}"),
+            )
+        })?;
+    state.events.hide_session(session_key.as_ref());
+    let retention_days = state.config.deleted_session_retention_days;
+    drop(state);
 
+    let purge_after = event.timestamp + chrono::Duration::days(i64::from(retention_days));
     Ok(serde_json::json!({
-        "sessions": sessions,
-        "total": total,
-        "limit": limit,
-        "offset": offset,
+        "status": "deleted",
+        "deleted_at": event.timestamp,
+        "purge_after": purge_after,
     }))
 }
 
-/// Search sessions by query.
-async fn handle_session_search(
+/// Restore a soft-deleted session.
+async fn handle_session_restore(
     state: &Arc<RwLock<GatewayState>>,
     params: &serde_json::Value,
+    auth_token: Option<&str>,
 ) -> RpcResult {
-    let query = params["query"]
+    let session_key_str = params["session_key"]
         .as_str()
-        .ok_or((rpc::INVALID_PARAMS, "Missing query".to_string()))?
-        .to_lowercase();
-    let filter_channel = params["channel"].as_str();
-    let filter_agent = params["agent"].as_str();
-    let limit = params["limit"].as_u64().unwrap_or(20) as usize;
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing session_key".to_string()))?;
+    let session_key = SessionKey::new(session_key_str);
 
     let state = state.read().await;
-    let session_keys = state
-        .event_store
-        .list_sessions()
-        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to list sessions: {e}")))?;
+    let projection = session_projection(&state, &session_key)?;
+    authorize_session(&state, auth_token, &projection, true)?;
+    if !projection.is_deleted() {
+        return Err((
+            rpc::INVALID_PARAMS,
+            format!("Session is not deleted: {session_key}"),
+        ));
+    }
 
-    // Search through sessions
-    let mut results: Vec<SessionProjection> = session_keys
-        .into_iter()
-        .filter_map(|key| state.event_store.get_projection(&key).ok())
-        .filter(|p| {
-            // Apply channel/agent filters
-            if let Some(ch) = filter_channel {
-                if p.channel.as_ref() != ch {
+    let event = SessionEvent::new(
+        session_key.clone(),
+        projection.agent_id,
+        SessionEventKind::SessionRestored,
+    );
+    state
+        .store_for_session(&session_key)
+        .append(&event)
+        .map_err(|e| {
+            (
+                rpc::INTERNAL_ERROR,
+                format!("Failed to restore session: {e}"),
+            )
+        })?;
+    state.events.unhide_session(session_key.as_ref());
+    drop(state);
+
+    Ok(serde_json::json!({
+        "status": "restored",
+    }))
+}
+
+/// Turn dry-run mode on (`simulate` or `plan`) or off (`null`) for a
+/// session.
+async fn handle_session_dry_run(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let session_key_str = params["session_key"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing session_key".to_string()))?;
+    let mode: Option<DryRunMode> =
+        serde_json::from_value(params["mode"].clone()).map_err(|_| {
+            (
+                rpc::INVALID_PARAMS,
+                "mode must be \"simulate\", \"plan\", or null".to_string(),
+            )
+        })?;
+    let session_key = SessionKey::new(session_key_str);
+
+    let state = state.read().await;
+    let projection = session_projection(&state, &session_key)?;
+    authorize_session(&state, auth_token, &projection, true)?;
+    let store = state.store_for_session(&session_key).clone();
+    drop(state);
+    let event = SessionEvent::new(
+        session_key,
+        projection.agent_id,
+        SessionEventKind::DryRunChanged { mode },
+    );
+    store.append(&event).map_err(|e| {
+        (
+            rpc::INTERNAL_ERROR,
+            format!("Failed to update session: {e}"),
+        )
+    })?;
+
+    Ok(serde_json::json!({
+        "dry_run": mode,
+    }))
+}
+
+/// Copy a session up to an event into a new session.
+async fn handle_session_fork(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let session_key_str = params["session_key"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing session_key".to_string()))?;
+    let at: ulid::Ulid = params["event_id"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing event_id".to_string()))?
+        .parse()
+        .map_err(|e| (rpc::INVALID_PARAMS, format!("Invalid event_id: {e}")))?;
+    let suffix = if let Some(suffix) = params["suffix"].as_str() {
+        if suffix.is_empty()
+            || !suffix
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err((
+                rpc::INVALID_PARAMS,
+                "suffix may only contain letters, digits, '-' and '_'".to_string(),
+            ));
+        }
+        suffix.to_string()
+    } else {
+        use rand::RngCore;
+        let mut bytes = [0u8; 3];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        hex::encode(bytes)
+    };
+    let session_key = SessionKey::new(session_key_str);
+
+    let state = state.read().await;
+    let projection = session_projection(&state, &session_key)?;
+    authorize_session(&state, auth_token, &projection, true)?;
+    let target = fork_key(&session_key, &suffix);
+    let copied = state
+        .store_for_session(&session_key)
+        .fork_session(&session_key, at, &target)
+        .map_err(|e| match e {
+            EventStoreError::AlreadyExists(_) => (rpc::INVALID_PARAMS, e.to_string()),
+            EventStoreError::NotFound(_) => (rpc::NOT_FOUND, e.to_string()),
+            e => (rpc::INTERNAL_ERROR, format!("Failed to fork session: {e}")),
+        })?;
+
+    let _ = state.events.broadcast(UiEvent::SessionCreated {
+        session_key: target.to_string(),
+        agent_id: projection.agent_id,
+        channel: projection.channel.to_string(),
+        peer_id: projection.peer_id,
+    });
+    drop(state);
+
+    Ok(serde_json::json!({
+        "session_key": target,
+        "forked_from": session_key,
+        "events_copied": copied,
+    }))
+}
+
+/// Re-run a session's recorded turns with different model parameters and
+/// diff the replies.
+async fn handle_session_replay(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let session_key_str = params["session_key"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing session_key".to_string()))?;
+    let overrides = agent_overrides(params)?;
+    overrides.validate().map_err(|e| (rpc::INVALID_PARAMS, e))?;
+    let session_key = SessionKey::new(session_key_str);
+
+    // Collect what the replay needs, then release the state lock for the
+    // provider calls
+    let (agent, events, tools, _run) = {
+        let state = state.read().await;
+        let projection = session_projection(&state, &session_key)?;
+        authorize_session(&state, auth_token, &projection, true)?;
+        let agent_id = params["agent_id"].as_str().unwrap_or(&projection.agent_id);
+        let agent = state
+            .agents
+            .get(agent_id)
+            .ok_or_else(|| (rpc::NOT_FOUND, format!("Agent not found: {agent_id}")))?
+            .with_overrides(&overrides);
+        let events = state
+            .store_for_session(&session_key)
+            .get_events(&session_key)
+            .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to get events: {e}")))?;
+        let run = state
+            .shutdown
+            .begin_run()
+            .ok_or_else(|| (rpc::UNAVAILABLE, "Gateway is shutting down".to_string()))?;
+        (agent, events, state.tool_registry.clone(), run)
+    };
+
+    let report = match agent.replay(&events, tools).await {
+        Ok(report) => report,
+        Err(AgentRuntimeError::Busy) => {
+            return Err((rpc::UNAVAILABLE, agent.busy_message().to_string()));
+        }
+        Err(e) => return Err((rpc::INTERNAL_ERROR, format!("Replay failed: {e}"))),
+    };
+
+    let mut result = serde_json::to_value(&report)
+        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Serialization error: {e}")))?;
+    result["session_key"] = serde_json::json!(session_key);
+    Ok(result)
+}
+
+/// Permanently remove a soft-deleted session.
+async fn handle_session_purge(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let session_key_str = params["session_key"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing session_key".to_string()))?;
+    let session_key = SessionKey::new(session_key_str);
+
+    let state = state.read().await;
+    let projection = session_projection(&state, &session_key)?;
+    authorize_session(&state, auth_token, &projection, true)?;
+    if !projection.is_deleted() {
+        return Err((
+            rpc::INVALID_PARAMS,
+            "Only deleted sessions can be purged; call session.delete first".to_string(),
+        ));
+    }
+
+    let events = state
+        .store_for_session(&session_key)
+        .purge_session(&session_key)
+        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to purge session: {e}")))?;
+    state.events.unhide_session(session_key.as_ref());
+    drop(state);
+
+    Ok(serde_json::json!({
+        "status": "purged",
+        "events_removed": events,
+    }))
+}
+
+/// Projections of every session the caller may see, across the main and
+/// namespace event stores.
+fn visible_sessions(
+    state: &GatewayState,
+    auth_token: Option<&str>,
+) -> Result<Vec<SessionProjection>, (i32, String)> {
+    let access = state.namespace_access(auth_token);
+    let mut sessions = Vec::new();
+    for store in state.event_stores() {
+        let keys = store
+            .list_sessions()
+            .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to list sessions: {e}")))?;
+        sessions.extend(
+            keys.iter()
+                .filter_map(|key| store.get_projection(key).ok())
+                .filter(|p| access.can_view(state.tenants.namespace_of_session(p))),
+        );
+    }
+    Ok(sessions)
+}
+
+/// Extended session list with filtering and pagination.
+async fn handle_session_list(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let limit = params["limit"].as_u64().unwrap_or(50) as usize;
+    let offset = params["offset"].as_u64().unwrap_or(0) as usize;
+    let filter_channel = params["channel"].as_str();
+    let filter_agent = params["agent"].as_str();
+    let filter_state = params["state"].as_str();
+    let include_deleted = params["include_deleted"].as_bool().unwrap_or(false);
+
+    let state = state.read().await;
+
+    // Get projections and apply filters
+    let mut sessions: Vec<SessionProjection> = visible_sessions(&state, auth_token)?
+        .into_iter()
+        .filter(|p| {
+            // Apply filters
+            if p.is_deleted() && !include_deleted {
+                return false;
+            }
+            if let Some(ch) = filter_channel {
+                if p.channel.as_ref() != ch {
+                    return false;
+                }
+            }
+            if let Some(agent) = filter_agent {
+                if p.agent_id != agent {
+                    return false;
+                }
+            }
+            if let Some(st) = filter_state {
+                let state_match = match st {
+                    "active" => p.state == SessionState::Active,
+                    "paused" => p.state == SessionState::Paused,
+                    "ended" => p.state == SessionState::Ended,
+                    _ => true,
+                };
+                if !state_match {
+                    return false;
+                }
+            }
+            true
+        })
+        .collect();
+
+    // Sort by last activity (most recent first)
+    sessions.sort_by(|a, b| b.last_activity.cmp(&a.last_activity));
+
+    let total = sessions.len();
+
+    // Apply pagination
+    let sessions: Vec<_> = sessions.into_iter().skip(offset).take(limit).collect();
+
+    Ok(serde_json::json!({
+        "sessions": sessions,
+        "total": total,
+        "limit": limit,
+        "offset": offset,
+    }))
+}
+
+/// Search sessions by query.
+async fn handle_session_search(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let query = params["query"]
+        .as_str()
+        .ok_or((rpc::INVALID_PARAMS, "Missing query".to_string()))?
+        .to_lowercase();
+    let filter_channel = params["channel"].as_str();
+    let filter_agent = params["agent"].as_str();
+    let limit = params["limit"].as_u64().unwrap_or(20) as usize;
+
+    let state = state.read().await;
+
+    // Search through sessions
+    let mut results: Vec<SessionProjection> = visible_sessions(&state, auth_token)?
+        .into_iter()
+        .filter(|p| {
+            if p.is_deleted() {
+                return false;
+            }
+
+            // Apply channel/agent filters
+            if let Some(ch) = filter_channel {
+                if p.channel.as_ref() != ch {
                     return false;
                 }
             }
@@ -1309,12 +4214,12 @@ async fn handle_session_search(
 }
 
 /// Get session statistics.
-async fn handle_session_stats(state: &Arc<RwLock<GatewayState>>) -> RpcResult {
+async fn handle_session_stats(
+    state: &Arc<RwLock<GatewayState>>,
+    auth_token: Option<&str>,
+) -> RpcResult {
     let state = state.read().await;
-    let session_keys = state
-        .event_store
-        .list_sessions()
-        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to list sessions: {e}")))?;
+    let sessions = visible_sessions(&state, auth_token)?;
 
     let mut total = 0;
     let mut active = 0;
@@ -1322,18 +4227,16 @@ async fn handle_session_stats(state: &Arc<RwLock<GatewayState>>) -> RpcResult {
     let mut by_agent: HashMap<String, u64> = HashMap::new();
     let mut total_messages: u64 = 0;
 
-    for key in session_keys {
-        if let Ok(projection) = state.event_store.get_projection(&key) {
-            total += 1;
-            if projection.state == SessionState::Active {
-                active += 1;
-            }
-            *by_channel
-                .entry(projection.channel.as_ref().to_string())
-                .or_insert(0) += 1;
-            *by_agent.entry(projection.agent_id.clone()).or_insert(0) += 1;
-            total_messages += projection.message_count;
+    for projection in sessions {
+        total += 1;
+        if projection.state == SessionState::Active {
+            active += 1;
         }
+        *by_channel
+            .entry(projection.channel.as_ref().to_string())
+            .or_insert(0) += 1;
+        *by_agent.entry(projection.agent_id.clone()).or_insert(0) += 1;
+        total_messages += projection.message_count;
     }
 
     Ok(serde_json::json!({
@@ -1345,10 +4248,73 @@ async fn handle_session_stats(state: &Arc<RwLock<GatewayState>>) -> RpcResult {
     }))
 }
 
+/// Longest range `stats.get` returns, in days.
+const MAX_STATS_DAYS: u64 = 366;
+
+/// Get daily activity counters (per agent, per channel, and errors) from
+/// the stats projections, without scanning events.
+async fn handle_stats_get(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let date = |name: &str| {
+        params[name]
+            .as_str()
+            .map(|s| {
+                chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                    .map_err(|e| (rpc::INVALID_PARAMS, format!("Invalid {name}: {e}")))
+            })
+            .transpose()
+    };
+    let to = date("to")?.unwrap_or_else(|| Utc::now().date_naive());
+    let days = params["days"]
+        .as_u64()
+        .unwrap_or(30)
+        .clamp(1, MAX_STATS_DAYS);
+    let from = date("from")?.unwrap_or_else(|| {
+        to.checked_sub_days(chrono::Days::new(days - 1))
+            .unwrap_or(to)
+    });
+    if from > to {
+        return Err((rpc::INVALID_PARAMS, "from is after to".to_string()));
+    }
+
+    let state = state.read().await;
+    let access = state.namespace_access(auth_token);
+    let mut activity = ActivityStats::default();
+    for (namespace, store) in state.namespaced_event_stores() {
+        if access.can_view(namespace) {
+            activity.merge(
+                store
+                    .activity(from, to)
+                    .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to read stats: {e}")))?,
+            );
+        }
+    }
+    drop(state);
+    if let Some(agent_id) = params["agent_id"].as_str() {
+        activity.agents.retain(|id, _| id == agent_id);
+    }
+    if let Some(channel) = params["channel"].as_str() {
+        activity.channels.retain(|id, _| id == channel);
+    }
+
+    Ok(serde_json::json!({
+        "from": from.to_string(),
+        "to": to.to_string(),
+        "totals": activity.agent_totals(),
+        "agents": activity.agents,
+        "channels": activity.channels,
+        "errors": activity.errors,
+    }))
+}
+
 /// Get events for a session.
 async fn handle_session_events(
     state: &Arc<RwLock<GatewayState>>,
     params: &serde_json::Value,
+    auth_token: Option<&str>,
 ) -> RpcResult {
     let session_key_str = params["session_key"]
         .as_str()
@@ -1361,15 +4327,17 @@ async fn handle_session_events(
 
     let session_key = SessionKey::new(session_key_str);
     let state = state.read().await;
+    let store = state.store_for_session(&session_key);
+    if let Ok(projection) = store.get_projection(&session_key) {
+        authorize_session(&state, auth_token, &projection, false)?;
+    }
 
     let events = if let Some(since_time) = since {
-        state
-            .event_store
+        store
             .get_events_since(&session_key, since_time)
             .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to get events: {e}")))?
     } else {
-        state
-            .event_store
+        store
             .get_events(&session_key)
             .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to get events: {e}")))?
     };
@@ -1392,30 +4360,48 @@ struct ChannelInfo {
     capabilities: ChannelCapabilities,
 }
 
-async fn handle_channels_list(state: &Arc<RwLock<GatewayState>>) -> RpcResult {
+async fn handle_channels_list(
+    state: &Arc<RwLock<GatewayState>>,
+    auth_token: Option<&str>,
+) -> RpcResult {
     let state = state.read().await;
     let registry = state.channels.read().await;
+    let access = state.namespace_access(auth_token);
+    let visible = |id: &str| access.can_view(state.tenants.namespace_of_channel(id));
 
     let channels: Vec<String> = registry
         .list()
         .iter()
         .map(std::string::ToString::to_string)
+        .filter(|id| visible(id))
+        .collect();
+    let health: Vec<ChannelHealth> = state
+        .supervisor
+        .health()
+        .into_iter()
+        .filter(|h| visible(&h.id))
         .collect();
 
     Ok(serde_json::json!({
         "channels": channels,
         "count": channels.len(),
+        "health": health,
     }))
 }
 
-async fn handle_channels_status(state: &Arc<RwLock<GatewayState>>) -> RpcResult {
+async fn handle_channels_status(
+    state: &Arc<RwLock<GatewayState>>,
+    auth_token: Option<&str>,
+) -> RpcResult {
     let state = state.read().await;
     let registry = state.channels.read().await;
+    let access = state.namespace_access(auth_token);
 
     let probes = registry.probe_all().await;
 
     let statuses: HashMap<String, serde_json::Value> = probes
         .into_iter()
+        .filter(|(id, _)| access.can_view(state.tenants.namespace_of_channel(id)))
         .map(|(id, result)| {
             let status = match result {
                 Ok(probe) => serde_json::json!({
@@ -1435,18 +4421,21 @@ async fn handle_channels_status(state: &Arc<RwLock<GatewayState>>) -> RpcResult
 
     Ok(serde_json::json!({
         "statuses": statuses,
+        "rate_limit": state.inbound_limiter.stats(),
     }))
 }
 
 async fn handle_channels_probe(
     state: &Arc<RwLock<GatewayState>>,
     params: &serde_json::Value,
+    auth_token: Option<&str>,
 ) -> RpcResult {
     let channel_id = params["channel_id"]
         .as_str()
         .ok_or((rpc::INVALID_PARAMS, "Missing channel_id".to_string()))?;
 
     let state = state.read().await;
+    authorize_channel(&state, auth_token, channel_id, false)?;
     let registry = state.channels.read().await;
 
     let channel = registry
@@ -1467,104 +4456,1398 @@ async fn handle_channels_probe(
     }))
 }
 
-// ============================================================================
-// Agent RPC Handlers
-// ============================================================================
-
-async fn handle_agent_list(state: &Arc<RwLock<GatewayState>>) -> RpcResult {
-    let state = state.read().await;
-    let agents: Vec<&str> = state.agents.keys().map(String::as_str).collect();
+/// Start a supervised channel (admin or operator).
+async fn handle_channels_start(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let channel_id = params["channel_id"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing channel_id".to_string()))?;
+
+    let supervisor = {
+        let state = state.read().await;
+        let by = approver(&state, auth_token)?;
+        authorize_channel(&state, auth_token, channel_id, true)?;
+        tracing::info!("Channel {} start requested by {}", channel_id, by);
+        state.supervisor.clone()
+    };
+    let health = supervisor
+        .start(channel_id)
+        .map_err(|e| supervisor_error(&e))?;
 
-    Ok(serde_json::json!({
-        "agents": agents,
-    }))
+    serde_json::to_value(health).map_err(|e| (rpc::INTERNAL_ERROR, e.to_string()))
 }
 
-async fn handle_agent_get(
+/// Stop a supervised channel until it is started again (admin or operator).
+async fn handle_channels_stop(
     state: &Arc<RwLock<GatewayState>>,
     params: &serde_json::Value,
+    auth_token: Option<&str>,
 ) -> RpcResult {
-    let agent_id = params["agent_id"]
+    let channel_id = params["channel_id"]
         .as_str()
-        .ok_or((rpc::INVALID_PARAMS, "Missing agent_id".to_string()))?;
-
-    let state = state.read().await;
-    let agent = state
-        .agents
-        .get(agent_id)
-        .ok_or((rpc::NOT_FOUND, format!("Agent not found: {agent_id}")))?;
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing channel_id".to_string()))?;
+
+    let supervisor = {
+        let state = state.read().await;
+        let by = approver(&state, auth_token)?;
+        authorize_channel(&state, auth_token, channel_id, true)?;
+        tracing::info!("Channel {} stop requested by {}", channel_id, by);
+        state.supervisor.clone()
+    };
+    let health = supervisor
+        .stop(channel_id)
+        .await
+        .map_err(|e| supervisor_error(&e))?;
 
-    // Return agent info
-    Ok(serde_json::json!({
-        "agent_id": agent_id,
-        "available": true,
-        "config": {
-            "model": agent.model(),
-            "system_prompt": agent.system_prompt(),
-            "max_tokens": agent.max_tokens(),
-            "temperature": agent.temperature(),
-        },
-    }))
+    serde_json::to_value(health).map_err(|e| (rpc::INTERNAL_ERROR, e.to_string()))
 }
 
-async fn handle_agent_status(
+/// Enable or disable a channel, saving the flag to the config file (admin).
+async fn handle_channels_set_enabled(
     state: &Arc<RwLock<GatewayState>>,
     params: &serde_json::Value,
+    auth_token: Option<&str>,
+    enabled: bool,
+) -> RpcResult {
+    let channel_id = params["channel_id"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing channel_id".to_string()))?;
+
+    let changed_by = {
+        let state = state.read().await;
+        require_admin(&state, auth_token)?;
+        approver(&state, auth_token)?
+    };
+    let (health, persisted) = set_channel_enabled(state, channel_id, enabled, changed_by).await?;
+
+    Ok(serde_json::json!({
+        "channel_id": channel_id,
+        "health": health,
+        "persisted": persisted,
+    }))
+}
+
+/// Enable and start, or disable and stop, a channel, and save the flag to
+/// the config file if there is one. Returns the channel's health and
+/// whether the flag was saved.
+pub async fn set_channel_enabled(
+    state: &Arc<RwLock<GatewayState>>,
+    channel_id: &str,
+    enabled: bool,
+    changed_by: String,
+) -> Result<(ChannelHealth, bool), (i32, String)> {
+    let (supervisor, events, config_source) = {
+        let state = state.read().await;
+        (
+            state.supervisor.clone(),
+            state.events.clone(),
+            state.config.config_source.clone(),
+        )
+    };
+    let was_enabled = supervisor
+        .health()
+        .into_iter()
+        .find(|h| h.id == channel_id)
+        .ok_or_else(|| (rpc::NOT_FOUND, format!("Channel not found: {channel_id}")))?
+        .enabled;
+
+    // Save first so a failed write leaves the channel as it was
+    let persisted = match &config_source {
+        Some(path) => {
+            ChannelsConfig::save_enabled(std::path::Path::new(path), channel_id, enabled)
+                .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to save config: {e}")))?;
+            true
+        }
+        None => false,
+    };
+    tracing::info!(
+        channel = %channel_id,
+        by = %changed_by,
+        enabled,
+        persisted,
+        "Channel enabled flag changed"
+    );
+    let _ = events.broadcast(UiEvent::ConfigChanged {
+        section: "channels.disabled".to_string(),
+        changes: vec![ConfigChange {
+            field: channel_id.to_string(),
+            old: serde_json::json!(was_enabled),
+            new: serde_json::json!(enabled),
+        }],
+        changed_by,
+        persisted,
+    });
+
+    let health = supervisor
+        .set_enabled(channel_id, enabled)
+        .await
+        .map_err(|e| supervisor_error(&e))?;
+    Ok((health, persisted))
+}
+
+/// The RPC error for a supervisor error.
+fn supervisor_error(e: &SupervisorError) -> (i32, String) {
+    let code = match e {
+        SupervisorError::NotFound(_) => rpc::NOT_FOUND,
+        SupervisorError::Disabled(_)
+        | SupervisorError::Timeout(_)
+        | SupervisorError::NotAccepting(_) => rpc::UNAVAILABLE,
+    };
+    (code, e.to_string())
+}
+
+/// Broadcast supervised channels' state changes to UI clients.
+async fn forward_channel_changes(
+    mut changes: tokio::sync::broadcast::Receiver<ChannelHealth>,
+    events: EventBroadcaster,
+) {
+    loop {
+        match changes.recv().await {
+            Ok(health) => {
+                let _ = events.broadcast(UiEvent::ChannelStateChanged {
+                    channel_id: health.id,
+                    state: health.state,
+                    enabled: health.enabled,
+                    error: health.last_error,
+                });
+            }
+            Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {}
+            Err(tokio::sync::broadcast::error::RecvError::Closed) => return,
+        }
+    }
+}
+
+async fn handle_channels_send(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let channel_id = params["channel_id"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing channel_id".to_string()))?;
+    let chat_id = params["chat_id"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing chat_id".to_string()))?;
+    // WhatsApp only: a pre-approved template instead of text
+    let template = params
+        .get("template")
+        .filter(|v| !v.is_null())
+        .map(|v| serde_json::from_value::<TemplateMessage>(v.clone()))
+        .transpose()
+        .map_err(|e| (rpc::INVALID_PARAMS, format!("Invalid template: {e}")))?;
+    let text = params["text"].as_str();
+    if text.is_none() && template.is_none() {
+        return Err((rpc::INVALID_PARAMS, "Missing text".to_string()));
+    }
+
+    let (channel, whatsapp, outbound) = {
+        let state = state.read().await;
+        authorize_channel(&state, auth_token, channel_id, true)?;
+        let channel = state
+            .channels
+            .read()
+            .await
+            .get_outbound(channel_id)
+            .cloned();
+        (channel, state.whatsapp.clone(), state.outbound.clone())
+    };
+    let channel =
+        channel.ok_or_else(|| (rpc::NOT_FOUND, format!("Channel not found: {channel_id}")))?;
+
+    let ctx = OutboundContext {
+        chat_id: chat_id.to_string(),
+        reply_to: params["reply_to"].as_str().map(str::to_string),
+        thread_id: params["thread_id"].as_str().map(str::to_string),
+    };
+    let result = if let Some(template) = template {
+        let whatsapp = whatsapp
+            .filter(|_| channel_id == ChannelId::whatsapp().as_ref())
+            .ok_or_else(|| {
+                (
+                    rpc::INVALID_PARAMS,
+                    "Templates can only be sent through WhatsApp".to_string(),
+                )
+            })?;
+        whatsapp.send_template(ctx, &template).await
+    } else {
+        outbound
+            .send(channel.as_ref(), ctx, text.unwrap_or_default())
+            .await
+    }
+    .map_err(|e| (rpc::INTERNAL_ERROR, format!("Delivery failed: {e}")))?;
+
+    serde_json::to_value(&result)
+        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Serialization error: {e}")))
+}
+
+// ============================================================================
+// Mock Channel RPC Handlers
+// ============================================================================
+
+fn mock_channel(state: &GatewayState) -> Result<Arc<MockChannel>, (i32, String)> {
+    state
+        .mock_channel
+        .clone()
+        .ok_or_else(|| (rpc::UNAVAILABLE, "Mock channel is not enabled".to_string()))
+}
+
+/// Deliver a message through the mock channel and wait for the reply.
+async fn handle_mock_inject(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let text = params["text"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing text".to_string()))?;
+    let mut raw = MockInbound::text(
+        params["peer_id"].as_str().unwrap_or(mock::DEFAULT_PEER),
+        text,
+    );
+    raw.chat_id = params["chat_id"].as_str().map(str::to_string);
+    raw.thread_id = params["thread_id"].as_str().map(str::to_string);
+    raw.reply_to = params["reply_to"].as_str().map(str::to_string);
+    if let Some(value) = params.get("attachments").filter(|v| !v.is_null()) {
+        raw.attachments = serde_json::from_value(value.clone())
+            .map_err(|e| (rpc::INVALID_PARAMS, format!("Invalid attachments: {e}")))?;
+    }
+
+    let channel = mock_channel(&*state.read().await)?;
+    let message = channel
+        .normalize(raw)
+        .map_err(|e| (rpc::INVALID_PARAMS, e.to_string()))?;
+    answer_mock_message(
+        state,
+        &channel,
+        &message,
+        params["agent_id"].as_str(),
+        auth_token,
+    )
+    .await
+}
+
+async fn handle_mock_react(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let raw = MockReaction {
+        peer_id: params["peer_id"]
+            .as_str()
+            .unwrap_or(mock::DEFAULT_PEER)
+            .to_string(),
+        chat_id: params["chat_id"].as_str().map(str::to_string),
+        message_id: params["message_id"]
+            .as_str()
+            .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing message_id".to_string()))?
+            .to_string(),
+        emoji: params["emoji"]
+            .as_str()
+            .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing emoji".to_string()))?
+            .to_string(),
+        removed: params["removed"].as_bool().unwrap_or(false),
+    };
+
+    let channel = mock_channel(&*state.read().await)?;
+    let reaction = channel
+        .normalize_reaction(raw)
+        .map_err(|e| (rpc::INVALID_PARAMS, e.to_string()))?;
+    answer_mock_reaction(state, &reaction, params["agent_id"].as_str(), auth_token).await
+}
+
+/// Messages the mock channel was asked to deliver.
+async fn handle_mock_outbound(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+) -> RpcResult {
+    let channel = mock_channel(&*state.read().await)?;
+    let messages = if params["clear"].as_bool().unwrap_or(false) {
+        channel.take_outbound()
+    } else {
+        channel.outbound()
+    };
+    Ok(serde_json::json!({ "messages": messages }))
+}
+
+// ============================================================================
+// Workflow RPC Handlers
+// ============================================================================
+
+/// Start a registered workflow.
+async fn handle_workflow_run(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let workflow_id = params["workflow_id"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing workflow_id".to_string()))?;
+    let input = params
+        .get("input")
+        .filter(|v| !v.is_null())
+        .cloned()
+        .unwrap_or_else(|| serde_json::json!({}));
+    let origin = match (params["channel"].as_str(), params["peer_id"].as_str()) {
+        (Some(channel), Some(peer_id)) => Some(InputOrigin {
+            channel: channel.to_string(),
+            chat_id: params["chat_id"].as_str().unwrap_or(peer_id).to_string(),
+            peer_id: peer_id.to_string(),
+        }),
+        (None, None) => None,
+        _ => {
+            return Err((
+                rpc::INVALID_PARAMS,
+                "channel and peer_id must be given together".to_string(),
+            ));
+        }
+    };
+    authorize_workflows(&*state.read().await, auth_token, true)?;
+    start_workflow(state, workflow_id, input, origin).await
+}
+
+/// Workflow runs waiting for input.
+async fn handle_workflow_waiting(
+    state: &Arc<RwLock<GatewayState>>,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let waiting_runs = {
+        let state = state.read().await;
+        authorize_workflows(&state, auth_token, false)?;
+        state.waiting_runs.clone()
+    };
+    let runs = waiting_runs
+        .list()
+        .map_err(|e| (rpc::INTERNAL_ERROR, e.to_string()))?;
+    Ok(serde_json::json!({ "runs": runs }))
+}
+
+/// Answer a workflow run waiting for input.
+async fn handle_workflow_reply(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let run_id = params["run_id"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing run_id".to_string()))?;
+    let reply = match (params.get("text"), params.get("data")) {
+        (Some(text @ serde_json::Value::String(_)), _) => text.clone(),
+        (_, Some(data @ serde_json::Value::Object(_))) => data.clone(),
+        _ => {
+            return Err((rpc::INVALID_PARAMS, "Missing text or data".to_string()));
+        }
+    };
+    authorize_workflows(&*state.read().await, auth_token, true)?;
+    resume_run(state, run_id, Some(reply)).await
+}
+
+/// Recorded workflow runs, newest first.
+async fn handle_workflow_runs_list(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let limit = params["limit"]
+        .as_u64()
+        .map_or(50, |n| usize::try_from(n).unwrap_or(usize::MAX));
+    let history = {
+        let state = state.read().await;
+        authorize_workflows(&state, auth_token, false)?;
+        state.run_history.clone()
+    };
+    let runs = history
+        .list(params["workflow_id"].as_str(), limit)
+        .map_err(|e| (rpc::INTERNAL_ERROR, e.to_string()))?;
+
+    let runs: Vec<serde_json::Value> = runs
+        .iter()
+        .map(|run| {
+            serde_json::json!({
+                "id": run.id,
+                "workflow_id": run.workflow_id,
+                "status": run.status,
+                "started_at": run.started_at,
+                "finished_at": run.finished_at,
+                "duration_ms": run.duration_ms(),
+                "steps": run.steps.len(),
+                "error": run.error,
+            })
+        })
+        .collect();
+    Ok(serde_json::json!({ "runs": runs }))
+}
+
+/// A recorded workflow run, with its steps laid over the workflow graph.
+async fn handle_workflow_runs_get(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let run_id = params["run_id"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing run_id".to_string()))?;
+    let (history, workflows) = {
+        let state = state.read().await;
+        authorize_workflows(&state, auth_token, false)?;
+        (state.run_history.clone(), state.workflows.clone())
+    };
+    let run = history
+        .load(run_id)
+        .map_err(|e| (rpc::INTERNAL_ERROR, e.to_string()))?
+        .ok_or_else(|| (rpc::NOT_FOUND, format!("Workflow run not found: {run_id}")))?;
+
+    let graph = run.graph(workflows.get(&run.workflow_id).map(Arc::as_ref));
+    Ok(serde_json::json!({ "run": run, "graph": graph }))
+}
+
+// ============================================================================
+// Notification RPC Handlers
+// ============================================================================
+
+/// List notification templates and the variables they read.
+async fn handle_notify_templates(state: &Arc<RwLock<GatewayState>>) -> RpcResult {
+    let state = state.read().await;
+    let notifications = &state.config.notifications;
+    let templates: Vec<serde_json::Value> = notifications
+        .names()
+        .into_iter()
+        .filter_map(|name| {
+            let template = notifications.get(name)?;
+            Some(serde_json::json!({
+                "name": name,
+                "description": template.description,
+                "channels": template.channels.keys().collect::<Vec<_>>(),
+                "required": template.required,
+                "variables": notifications.variables(name),
+            }))
+        })
+        .collect();
+    drop(state);
+
+    Ok(serde_json::json!({
+        "templates": templates,
+        "count": templates.len(),
+    }))
+}
+
+/// Render a notification template and send it through a channel.
+async fn handle_notify_send(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let template = params["template"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing template".to_string()))?;
+    let channel_id = params["channel"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing channel".to_string()))?;
+    let chat_id = params["chat"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing chat".to_string()))?;
+    let vars = match &params["vars"] {
+        serde_json::Value::Null => serde_json::Map::new(),
+        serde_json::Value::Object(vars) => vars.clone(),
+        _ => {
+            return Err((rpc::INVALID_PARAMS, "vars must be an object".to_string()));
+        }
+    };
+
+    let (text, channel, outbound) = {
+        let state = state.read().await;
+        authorize_channel(&state, auth_token, channel_id, true)?;
+        let text = state
+            .config
+            .notifications
+            .render(template, channel_id, chat_id, &vars)
+            .map_err(|e| match e {
+                NotifyError::NotFound(_) => (rpc::NOT_FOUND, e.to_string()),
+                NotifyError::MissingVariables { .. } => (rpc::INVALID_PARAMS, e.to_string()),
+                _ => (rpc::INTERNAL_ERROR, e.to_string()),
+            })?;
+        let channel = state
+            .channels
+            .read()
+            .await
+            .get_outbound(channel_id)
+            .cloned()
+            .ok_or_else(|| (rpc::NOT_FOUND, format!("Channel not found: {channel_id}")))?;
+        (text, channel, state.outbound.clone())
+    };
+
+    let ctx = OutboundContext {
+        chat_id: chat_id.to_string(),
+        reply_to: None,
+        thread_id: params["thread_id"].as_str().map(str::to_string),
+    };
+    let result = outbound
+        .send(channel.as_ref(), ctx, &text)
+        .await
+        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Delivery failed: {e}")))?;
+
+    Ok(serde_json::json!({
+        "text": text,
+        "result": result,
+    }))
+}
+
+// ============================================================================
+// Broadcast RPC Handlers
+// ============================================================================
+
+/// Start sending one message to many chats; returns before delivery.
+async fn handle_broadcast_send(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let text = params["text"]
+        .as_str()
+        .filter(|text| !text.trim().is_empty())
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing text".to_string()))?;
+    let chat_ids = match &params["chat_ids"] {
+        serde_json::Value::Null => Vec::new(),
+        serde_json::Value::Array(ids) => ids
+            .iter()
+            .map(|id| id.as_str().map(str::to_string))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| (rpc::INVALID_PARAMS, "chat_ids must be strings".to_string()))?,
+        _ => {
+            return Err((rpc::INVALID_PARAMS, "chat_ids must be an array".to_string()));
+        }
+    };
+    let targets = BroadcastTargets {
+        channel: params["channel"].as_str().map(str::to_string),
+        group: params["group"].as_str().map(str::to_string),
+        allowlist: params["allowlist"].as_bool().unwrap_or(false),
+        chat_ids,
+    };
+
+    let state = state.read().await;
+    let sent_by = approver(&state, auth_token)?;
+    let (channel_id, chat_ids) = targets
+        .resolve(&state.config.broadcast, &state.config.allowlist)
+        .map_err(|e| match e {
+            BroadcastError::UnknownGroup(_) => (rpc::NOT_FOUND, e.to_string()),
+            _ => (rpc::INVALID_PARAMS, e.to_string()),
+        })?;
+    let channel = state
+        .channels
+        .read()
+        .await
+        .get_outbound(&channel_id)
+        .cloned()
+        .ok_or_else(|| (rpc::NOT_FOUND, format!("Channel not found: {channel_id}")))?;
+    let run = state
+        .shutdown
+        .begin_run()
+        .ok_or_else(|| (rpc::UNAVAILABLE, "Gateway is shutting down".to_string()))?;
+
+    let broadcast_id = format!(
+        "{}-{:04x}",
+        Utc::now().format("%Y%m%dT%H%M%S"),
+        rand::random::<u16>()
+    );
+    let broadcast = Broadcast {
+        id: broadcast_id.clone(),
+        channel,
+        outbound: state.outbound.clone(),
+        store: state.event_store.clone(),
+        content: text.to_string(),
+        targets: chat_ids,
+        interval: state.config.broadcast.interval(&channel_id),
+        max_retries: state.config.broadcast.max_retries,
+    };
+    drop(state);
+    broadcast.start(&sent_by).map_err(|e| {
+        (
+            rpc::INTERNAL_ERROR,
+            format!("Failed to record broadcast: {e}"),
+        )
+    })?;
+    let targets = broadcast.targets.len();
+    tokio::spawn(broadcast.run(run));
+
+    Ok(serde_json::json!({
+        "broadcast_id": broadcast_id,
+        "session_key": broadcast::session_key(&broadcast_id).to_string(),
+        "channel": channel_id,
+        "targets": targets,
+    }))
+}
+
+/// Delivery progress of a broadcast.
+async fn handle_broadcast_status(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let broadcast_id = params["broadcast_id"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing broadcast_id".to_string()))?;
+
+    let state = state.read().await;
+    approver(&state, auth_token)?;
+    let session_key = broadcast::session_key(broadcast_id);
+    let projection = state
+        .event_store
+        .get_projection(&session_key)
+        .map_err(|_| {
+            (
+                rpc::NOT_FOUND,
+                format!("Broadcast not found: {broadcast_id}"),
+            )
+        })?;
+    let events = state
+        .event_store
+        .get_events(&session_key)
+        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to get events: {e}")))?;
+    drop(state);
+    let status = BroadcastStatus::from_events(&events).ok_or_else(|| {
+        (
+            rpc::NOT_FOUND,
+            format!("Broadcast not found: {broadcast_id}"),
+        )
+    })?;
+
+    let mut result = serde_json::to_value(status)
+        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Serialization error: {e}")))?;
+    result["channel"] = serde_json::json!(projection.channel.as_ref());
+    Ok(result)
+}
+
+// ============================================================================
+// Agent RPC Handlers
+// ============================================================================
+
+async fn handle_agent_list(
+    state: &Arc<RwLock<GatewayState>>,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let state = state.read().await;
+    let agents: Vec<&str> = state
+        .agents
+        .keys()
+        .map(String::as_str)
+        .filter(|agent_id| authorize_agent(&state, auth_token, agent_id).is_ok())
+        .collect();
+
+    Ok(serde_json::json!({
+        "agents": agents,
+    }))
+}
+
+async fn handle_agent_get(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let agent_id = params["agent_id"]
+        .as_str()
+        .ok_or((rpc::INVALID_PARAMS, "Missing agent_id".to_string()))?;
+
+    let state = state.read().await;
+    authorize_agent(&state, auth_token, agent_id)?;
+    let agent = state
+        .agents
+        .get(agent_id)
+        .ok_or((rpc::NOT_FOUND, format!("Agent not found: {agent_id}")))?;
+
+    // Return agent info
+    Ok(serde_json::json!({
+        "agent_id": agent_id,
+        "available": true,
+        "config": {
+            "model": agent.model(),
+            "system_prompt": agent.system_prompt(),
+            "prompt_template": agent.prompt_template().map(|t| &t.name),
+            "skills": agent.skills().iter().map(|s| &s.name).collect::<Vec<_>>(),
+            "max_tokens": agent.max_tokens(),
+            "temperature": agent.temperature(),
+        },
+    }))
+}
+
+async fn handle_agent_update(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
 ) -> RpcResult {
     let agent_id = params["agent_id"]
         .as_str()
-        .ok_or((rpc::INVALID_PARAMS, "Missing agent_id".to_string()))?;
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing agent_id".to_string()))?;
+    let overrides = agent_overrides(params)?;
+    if overrides.is_empty() {
+        return Err((
+            rpc::INVALID_PARAMS,
+            "Nothing to update: give model, temperature, max_tokens, or system_prompt".to_string(),
+        ));
+    }
+    overrides.validate().map_err(|e| (rpc::INVALID_PARAMS, e))?;
+
+    let mut state = state.write().await;
+    require_admin(&state, auth_token)?;
+    let changed_by = approver(&state, auth_token)?;
+
+    let current = state
+        .agents
+        .get(agent_id)
+        .ok_or_else(|| (rpc::NOT_FOUND, format!("Agent not found: {agent_id}")))?;
+    let updated = current.with_overrides(&overrides);
+    let changes = agent_changes(current, &updated);
+
+    // Save first so a failed write leaves the running agent as it was
+    let persisted = match &state.config.config_source {
+        Some(path) => {
+            overrides
+                .save(std::path::Path::new(path), agent_id)
+                .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to save config: {e}")))?;
+            true
+        }
+        None => false,
+    };
+    state.agents.insert(agent_id.to_string(), Arc::new(updated));
+
+    tracing::info!(
+        agent = %agent_id,
+        by = %changed_by,
+        fields = ?changes.iter().map(|c| c.field.as_str()).collect::<Vec<_>>(),
+        persisted,
+        "Agent configuration changed"
+    );
+    let _ = state.events.broadcast(UiEvent::ConfigChanged {
+        section: format!("agents.{agent_id}"),
+        changes: changes.clone(),
+        changed_by,
+        persisted,
+    });
+    drop(state);
+
+    Ok(serde_json::json!({
+        "agent_id": agent_id,
+        "changes": changes,
+        "persisted": persisted,
+    }))
+}
+
+/// Model parameters given to `agent.update`.
+fn agent_overrides(params: &serde_json::Value) -> Result<AgentOverrides, (i32, String)> {
+    let temperature = match &params["temperature"] {
+        serde_json::Value::Null => None,
+        value => Some(serde_json::from_value::<f32>(value.clone()).map_err(|_| {
+            (
+                rpc::INVALID_PARAMS,
+                "temperature must be a number".to_string(),
+            )
+        })?),
+    };
+    let max_tokens = match &params["max_tokens"] {
+        serde_json::Value::Null => None,
+        value => Some(
+            value
+                .as_u64()
+                .and_then(|n| u32::try_from(n).ok())
+                .ok_or_else(|| {
+                    (
+                        rpc::INVALID_PARAMS,
+                        "max_tokens must be a positive integer".to_string(),
+                    )
+                })?,
+        ),
+    };
+    Ok(AgentOverrides {
+        model: params["model"].as_str().map(String::from),
+        temperature,
+        max_tokens,
+        system_prompt: params["system_prompt"].as_str().map(String::from),
+    })
+}
+
+/// Model parameters that differ between two versions of an agent.
+fn agent_changes(old: &AgentRuntime, new: &AgentRuntime) -> Vec<ConfigChange> {
+    [
+        (
+            "model",
+            serde_json::json!(old.model()),
+            serde_json::json!(new.model()),
+        ),
+        (
+            "temperature",
+            serde_json::json!(old.temperature()),
+            serde_json::json!(new.temperature()),
+        ),
+        (
+            "max_tokens",
+            serde_json::json!(old.max_tokens()),
+            serde_json::json!(new.max_tokens()),
+        ),
+        (
+            "system_prompt",
+            serde_json::json!(old.system_prompt()),
+            serde_json::json!(new.system_prompt()),
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, old, new)| old != new)
+    .map(|(field, old, new)| ConfigChange {
+        field: field.to_string(),
+        old,
+        new,
+    })
+    .collect()
+}
+
+async fn handle_agent_status(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let agent_id = params["agent_id"]
+        .as_str()
+        .ok_or((rpc::INVALID_PARAMS, "Missing agent_id".to_string()))?;
+
+    let state = state.read().await;
+    authorize_agent(&state, auth_token, agent_id)?;
+    let agent = state.agents.get(agent_id);
+
+    Ok(serde_json::json!({
+        "agent_id": agent_id,
+        "available": agent.is_some(),
+        "concurrency": agent.map(|a| a.concurrency_stats()),
+    }))
+}
+
+async fn handle_tools_list(state: &Arc<RwLock<GatewayState>>) -> RpcResult {
+    let state = state.read().await;
+    let tools: Vec<serde_json::Value> = state
+        .tool_registry
+        .as_tool_definitions()
+        .iter()
+        .map(|t| {
+            serde_json::json!({
+                "name": t.name,
+                "description": t.description,
+                "input_schema": t.input_schema,
+            })
+        })
+        .collect();
+
+    Ok(serde_json::json!({
+        "tools": tools,
+    }))
+}
+
+async fn handle_tools_execute(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let tool_name = params["tool_name"]
+        .as_str()
+        .ok_or((rpc::INVALID_PARAMS, "Missing tool_name".to_string()))?;
+    let tool_params = params
+        .get("params")
+        .cloned()
+        .unwrap_or(serde_json::json!({}));
+
+    // Run as the session's agent, or the agent named, so its tool policy
+    // applies; calls made for a session are recorded in its transcript, so
+    // the caller must be allowed to change it
+    let session_key = params["session_key"].as_str().map(SessionKey::new);
+    let guard = state.read().await;
+    approver(&guard, auth_token)?;
+    let agent_id = if let Some(session_key) = &session_key {
+        let projection = session_projection(&guard, session_key)?;
+        authorize_session(&guard, auth_token, &projection, true)?;
+        if params["agent_id"]
+            .as_str()
+            .is_some_and(|agent_id| agent_id != projection.agent_id)
+        {
+            return Err((
+                rpc::INVALID_PARAMS,
+                format!(
+                    "Session {session_key} belongs to agent {}",
+                    projection.agent_id
+                ),
+            ));
+        }
+        projection.agent_id
+    } else {
+        let agent_id = params["agent_id"].as_str().unwrap_or("default");
+        let namespace = guard
+            .tenants
+            .namespace_of_agent(agent_id)
+            .map(|ns| ns.id.as_str());
+        if !guard.namespace_access(auth_token).can_manage(namespace) {
+            return Err((rpc::NOT_FOUND, format!("Agent not found: {agent_id}")));
+        }
+        agent_id.to_string()
+    };
+    let agent = guard
+        .agents
+        .get(&agent_id)
+        .cloned()
+        .ok_or_else(|| (rpc::NOT_FOUND, format!("Agent not found: {agent_id}")))?;
+    drop(guard);
+
+    // Tools called for a session run in its scope, as if the agent had
+    // called them there
+    let call = agent.execute_tool(tool_name, tool_params.clone());
+    let result = match &session_key {
+        Some(session_key) => DelegationScope::enter(session_key, call).await,
+        None => call.await,
+    }
+    .map_err(|e| match e {
+        ToolError::Denied { .. } => (rpc::FORBIDDEN, e.to_string()),
+        e => (rpc::INTERNAL_ERROR, format!("Tool error: {e}")),
+    })?;
+    let value = serde_json::to_value(&result)
+        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Serialization error: {e}")))?;
+
+    // The session's next turn sees the result, and any sources it cites
+    if let Some(session_key) = &session_key {
+        let store = state.read().await.store_for_session(session_key).clone();
+        for kind in [
+            SessionEventKind::ToolCalled {
+                tool_name: tool_name.to_string(),
+                params: tool_params,
+            },
+            SessionEventKind::ToolResult {
+                tool_name: tool_name.to_string(),
+                result: value.clone(),
+                success: result.success,
+            },
+        ] {
+            store
+                .append(&SessionEvent::new(
+                    session_key.clone(),
+                    agent_id.clone(),
+                    kind,
+                ))
+                .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to log tool call: {e}")))?;
+        }
+    }
+
+    Ok(value)
+}
+
+// ============================================================================
+// Workspace RPC Handlers
+// ============================================================================
+
+/// Resolve the workspace named by `session_key` or `agent_id`, if the
+/// caller can see it.
+async fn workspace_from_params(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> Result<Workspace, (i32, String)> {
+    let state = state.read().await;
+    let base = state.config.workspace_dir.clone();
+
+    if let Some(session_key) = params["session_key"].as_str() {
+        let projection = session_projection(&state, &SessionKey::new(session_key))?;
+        authorize_session(&state, auth_token, &projection, false)?;
+        return Ok(Workspace::for_session(
+            &base,
+            &projection.agent_id,
+            session_key,
+        ));
+    }
+
+    let agent_id = params["agent_id"].as_str().ok_or_else(|| {
+        (
+            rpc::INVALID_PARAMS,
+            "Missing agent_id or session_key".to_string(),
+        )
+    })?;
+    let namespace = state
+        .tenants
+        .namespace_of_agent(agent_id)
+        .map(|ns| ns.id.as_str());
+    if !state.agents.contains_key(agent_id)
+        || !state.namespace_access(auth_token).can_view(namespace)
+    {
+        return Err((rpc::NOT_FOUND, format!("Agent not found: {agent_id}")));
+    }
+    drop(state);
+    Ok(Workspace::for_agent(&base, agent_id))
+}
+
+fn workspace_error(e: &WorkspaceError) -> (i32, String) {
+    let code = match e {
+        WorkspaceError::InvalidPath(_) | WorkspaceError::TooLarge { .. } => rpc::INVALID_PARAMS,
+        WorkspaceError::NotFound(_) => rpc::NOT_FOUND,
+        WorkspaceError::Io(_) => rpc::INTERNAL_ERROR,
+    };
+    (code, e.to_string())
+}
+
+async fn handle_workspace_list(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let workspace = workspace_from_params(state, params, auth_token).await?;
+    let path = params["path"].as_str().unwrap_or("");
+
+    let entries = workspace.list(path).map_err(|e| workspace_error(&e))?;
+
+    Ok(serde_json::json!({
+        "path": path,
+        "entries": entries,
+    }))
+}
+
+async fn handle_workspace_read(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let workspace = workspace_from_params(state, params, auth_token).await?;
+    let path = params["path"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing path".to_string()))?;
+    let max_bytes = params["max_bytes"]
+        .as_u64()
+        .and_then(|n| usize::try_from(n).ok())
+        .map_or(DEFAULT_READ_LIMIT, |n| n.min(DEFAULT_READ_LIMIT));
+
+    let file = workspace
+        .read(path, max_bytes)
+        .map_err(|e| workspace_error(&e))?;
+
+    serde_json::to_value(&file)
+        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Serialization error: {e}")))
+}
+
+async fn handle_workspace_download(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let workspace = workspace_from_params(state, params, auth_token).await?;
+    let path = params["path"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing path".to_string()))?;
+
+    let file = workspace.download(path).map_err(|e| workspace_error(&e))?;
+
+    serde_json::to_value(&file)
+        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Serialization error: {e}")))
+}
+
+// ============================================================================
+// Feedback RPC Handlers
+// ============================================================================
+
+/// Append a `FeedbackReceived` event, linking it to the latest response if
+/// no response ID is given.
+pub fn record_feedback(
+    store: &EventStore,
+    session_key: &SessionKey,
+    agent_id: &str,
+    response_id: Option<String>,
+    rating: FeedbackRating,
+    comment: Option<String>,
+    source: &str,
+) -> Result<String, (i32, String)> {
+    let response_id = match response_id {
+        Some(id) => Some(id),
+        None => store
+            .get_events(session_key)
+            .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to get events: {e}")))?
+            .iter()
+            .rev()
+            .find(|e| matches!(e.kind, SessionEventKind::AgentResponse { .. }))
+            .map(|e| e.id.to_hex()),
+    };
+
+    let event = SessionEvent::new(
+        session_key.clone(),
+        agent_id.to_string(),
+        SessionEventKind::FeedbackReceived {
+            response_id,
+            rating,
+            comment,
+            source: source.to_string(),
+        },
+    );
+
+    store.append(&event).map(|id| id.to_hex()).map_err(|e| {
+        (
+            rpc::INTERNAL_ERROR,
+            format!("Failed to record feedback: {e}"),
+        )
+    })
+}
+
+async fn handle_feedback_submit(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let session_key_str = params["session_key"]
+        .as_str()
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing session_key".to_string()))?;
+
+    // Accept an explicit rating, a reaction emoji, or a /feedback command
+    let (rating, comment, source) = if let Some(rating) = params["rating"].as_str() {
+        let rating = match rating {
+            "positive" | "up" => FeedbackRating::Positive,
+            "negative" | "down" => FeedbackRating::Negative,
+            other => {
+                return Err((rpc::INVALID_PARAMS, format!("Invalid rating: {other}")));
+            }
+        };
+        (rating, params["comment"].as_str().map(String::from), "api")
+    } else if let Some(reaction) = params["reaction"].as_str() {
+        let rating = rating_from_reaction(reaction).ok_or_else(|| {
+            (
+                rpc::INVALID_PARAMS,
+                format!("Unsupported reaction: {reaction}"),
+            )
+        })?;
+        (rating, None, "reaction")
+    } else if let Some(command) = params["command"].as_str() {
+        let cmd = parse_feedback_command(command)
+            .ok_or_else(|| (rpc::INVALID_PARAMS, "Invalid feedback command".to_string()))?;
+        (cmd.rating, cmd.comment, "command")
+    } else {
+        return Err((
+            rpc::INVALID_PARAMS,
+            "Missing rating, reaction, or command".to_string(),
+        ));
+    };
 
+    let session_key = SessionKey::new(session_key_str);
     let state = state.read().await;
-    let exists = state.agents.contains_key(agent_id);
+
+    let projection = session_projection(&state, &session_key)?;
+    authorize_session(&state, auth_token, &projection, false)?;
+    let store = state.store_for_session(&session_key).clone();
+    drop(state);
+
+    let event_id = record_feedback(
+        &store,
+        &session_key,
+        &projection.agent_id,
+        params["response_id"].as_str().map(String::from),
+        rating,
+        comment,
+        source,
+    )?;
 
     Ok(serde_json::json!({
-        "agent_id": agent_id,
-        "available": exists,
+        "event_id": event_id,
+        "rating": rating,
     }))
 }
 
-async fn handle_tools_list(state: &Arc<RwLock<GatewayState>>) -> RpcResult {
+/// Aggregate feedback per agent and per experiment variant.
+async fn handle_feedback_summary(
+    state: &Arc<RwLock<GatewayState>>,
+    params: &serde_json::Value,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let agent_filter = params["agent_id"].as_str();
+
     let state = state.read().await;
-    let tools: Vec<serde_json::Value> = state
-        .tool_registry
-        .as_tool_definitions()
+    let access = state.namespace_access(auth_token);
+    let mut projections = Vec::new();
+    for store in state.event_stores() {
+        let session_keys = store
+            .list_sessions()
+            .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to list sessions: {e}")))?;
+        projections.extend(
+            session_keys
+                .iter()
+                .filter_map(|key| store.get_projection(key).ok())
+                .filter(|p| access.can_view(state.tenants.namespace_of_session(p))),
+        );
+    }
+    drop(state);
+
+    let mut by_agent: HashMap<String, FeedbackTally> = HashMap::new();
+    let mut by_variant: HashMap<String, FeedbackTally> = HashMap::new();
+
+    for projection in projections {
+        if agent_filter.is_some_and(|a| a != projection.agent_id) {
+            continue;
+        }
+
+        let tally = projection.feedback;
+        let entry = by_agent.entry(projection.agent_id.clone()).or_default();
+        entry.positive += tally.positive;
+        entry.negative += tally.negative;
+
+        if let (Some(experiment), Some(variant)) = (&projection.experiment, &projection.variant) {
+            let entry = by_variant
+                .entry(format!("{experiment}/{variant}"))
+                .or_default();
+            entry.positive += tally.positive;
+            entry.negative += tally.negative;
+        }
+    }
+
+    Ok(serde_json::json!({
+        "by_agent": by_agent,
+        "by_variant": by_variant,
+    }))
+}
+
+// ============================================================================
+// Experiment RPC Handlers
+// ============================================================================
+
+async fn handle_experiments_list(
+    state: &Arc<RwLock<GatewayState>>,
+    auth_token: Option<&str>,
+) -> RpcResult {
+    let state = state.read().await;
+
+    let experiments: Vec<serde_json::Value> = state
+        .router
+        .experiments()
         .iter()
-        .map(|t| {
+        .filter(|e| authorize_agent(&state, auth_token, e.agent.as_ref()).is_ok())
+        .map(|e| {
             serde_json::json!({
-                "name": t.name,
-                "description": t.description,
-                "input_schema": t.input_schema,
+                "id": e.id,
+                "agent": e.agent.as_ref(),
+                "enabled": e.enabled,
+                "variants": e.variants,
             })
         })
         .collect();
+    drop(state);
 
     Ok(serde_json::json!({
-        "tools": tools,
+        "experiments": experiments,
+        "count": experiments.len(),
     }))
 }
 
-async fn handle_tools_execute(
+/// Per-variant aggregates for an experiment report.
+#[derive(Debug, Default, serde::Serialize)]
+struct VariantReport {
+    sessions: u64,
+    responses: u64,
+    input_tokens: u64,
+    output_tokens: u64,
+    avg_latency_ms: Option<f64>,
+    cost: Option<f64>,
+    feedback: FeedbackTally,
+    #[serde(skip)]
+    latency_total_ms: u64,
+    #[serde(skip)]
+    latency_samples: u64,
+}
+
+impl VariantReport {
+    #[allow(clippy::cast_precision_loss)]
+    fn finish(&mut self, variant: Option<&ExperimentVariant>) {
+        if self.latency_samples > 0 {
+            self.avg_latency_ms = Some(self.latency_total_ms as f64 / self.latency_samples as f64);
+        }
+        if let Some(v) = variant {
+            if v.input_cost_per_mtok.is_some() || v.output_cost_per_mtok.is_some() {
+                let input = self.input_tokens as f64 * v.input_cost_per_mtok.unwrap_or(0.0);
+                let output = self.output_tokens as f64 * v.output_cost_per_mtok.unwrap_or(0.0);
+                self.cost = Some((input + output) / 1_000_000.0);
+            }
+        }
+    }
+}
+
+/// Report per-variant latency, token usage, and cost for an experiment.
+async fn handle_experiments_report(
     state: &Arc<RwLock<GatewayState>>,
     params: &serde_json::Value,
+    auth_token: Option<&str>,
 ) -> RpcResult {
-    let tool_name = params["tool_name"]
+    let experiment_id = params["experiment_id"]
         .as_str()
-        .ok_or((rpc::INVALID_PARAMS, "Missing tool_name".to_string()))?;
-    let tool_params = params
-        .get("params")
-        .cloned()
-        .unwrap_or(serde_json::json!({}));
+        .ok_or_else(|| (rpc::INVALID_PARAMS, "Missing experiment_id".to_string()))?;
 
     let state = state.read().await;
-    let result = state
-        .tool_registry
-        .execute(tool_name, tool_params)
-        .await
-        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Tool error: {e}")))?;
+    let experiment = state
+        .router
+        .experiments()
+        .iter()
+        .find(|e| e.id == experiment_id)
+        .cloned()
+        .ok_or_else(|| {
+            (
+                rpc::NOT_FOUND,
+                format!("Experiment not found: {experiment_id}"),
+            )
+        })?;
+    authorize_agent(&state, auth_token, experiment.agent.as_ref()).map_err(|_| {
+        (
+            rpc::NOT_FOUND,
+            format!("Experiment not found: {experiment_id}"),
+        )
+    })?;
+    let access = state.namespace_access(auth_token);
 
-    serde_json::to_value(&result)
-        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Serialization error: {e}")))
+    let mut variants: HashMap<String, VariantReport> = HashMap::new();
+
+    for store in state.event_stores() {
+        let session_keys = store
+            .list_sessions()
+            .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to list sessions: {e}")))?;
+        for key in session_keys {
+            let Ok(projection) = store.get_projection(&key) else {
+                continue;
+            };
+            if projection.experiment.as_deref() != Some(experiment_id)
+                || !access.can_view(state.tenants.namespace_of_session(&projection))
+            {
+                continue;
+            }
+            let Some(variant) = projection.variant else {
+                continue;
+            };
+
+            let report = variants.entry(variant).or_default();
+            report.sessions += 1;
+            report.feedback.positive += projection.feedback.positive;
+            report.feedback.negative += projection.feedback.negative;
+
+            let events = store
+                .get_events(&key)
+                .map_err(|e| (rpc::INTERNAL_ERROR, format!("Failed to get events: {e}")))?;
+
+            for event in events {
+                if let SessionEventKind::AgentResponse {
+                    tokens, latency_ms, ..
+                } = event.kind
+                {
+                    report.responses += 1;
+                    report.input_tokens += tokens.input_tokens;
+                    report.output_tokens += tokens.output_tokens;
+                    if let Some(ms) = latency_ms {
+                        report.latency_total_ms += ms;
+                        report.latency_samples += 1;
+                    }
+                }
+            }
+        }
+    }
+    drop(state);
+
+    for (name, report) in &mut variants {
+        report.finish(experiment.variants.iter().find(|v| &v.name == name));
+    }
+
+    Ok(serde_json::json!({
+        "experiment_id": experiment_id,
+        "variants": variants,
+    }))
 }
 
 #[cfg(test)]
@@ -1587,4 +5870,578 @@ mod tests {
 
         assert!(gateway.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_channels_send_templates_only_through_whatsapp() {
+        let temp = tempfile::tempdir().unwrap();
+        let gateway = GatewayBuilder::new()
+            .with_config(GatewayConfig {
+                data_dir: temp.path().to_path_buf(),
+                ..GatewayConfig::default()
+            })
+            .with_event_store(Arc::new(
+                EventStore::open(&temp.path().join("events")).unwrap(),
+            ))
+            .build()
+            .unwrap();
+        let state = gateway.state();
+        state
+            .read()
+            .await
+            .channels
+            .write()
+            .await
+            .register_outbound(Arc::new(MockChannel::new()));
+
+        let template = serde_json::json!({ "name": "order_update", "language": "en_US" });
+        let params =
+            serde_json::json!({ "channel_id": "mock", "chat_id": "c", "template": template });
+        let result = handle_channels_send(state, &params, None).await;
+        assert_eq!(code(result), rpc::INVALID_PARAMS);
+
+        let params = serde_json::json!({ "channel_id": "mock", "chat_id": "c" });
+        let result = handle_channels_send(state, &params, None).await;
+        assert_eq!(code(result), rpc::INVALID_PARAMS);
+    }
+
+    fn experiment_gateway(temp: &std::path::Path, enabled: bool) -> Gateway {
+        use openclaw_agents::harness::{MockCompletion, MockProvider};
+
+        let variant = |name: &str| ExperimentVariant {
+            name: name.to_string(),
+            agent: format!("agent-{name}"),
+            weight: 50,
+            input_cost_per_mtok: Some(1.0),
+            output_cost_per_mtok: None,
+        };
+        let agent = || {
+            Arc::new(AgentRuntime::new(Arc::new(MockProvider::new([
+                MockCompletion::Text("hello".to_string()),
+            ]))))
+        };
+        GatewayBuilder::new()
+            .with_config(GatewayConfig {
+                data_dir: temp.join("gateway"),
+                auth: AuthConfig {
+                    enabled: false,
+                    ..AuthConfig::default()
+                },
+                experiments: HashMap::from([(
+                    "prompt-v2".to_string(),
+                    ExperimentConfig {
+                        agent: "default".to_string(),
+                        enabled,
+                        variants: vec![variant("a"), variant("b")],
+                    },
+                )]),
+                ..GatewayConfig::default()
+            })
+            .with_event_store(Arc::new(EventStore::open(&temp.join("events")).unwrap()))
+            .with_agent("agent-a", agent())
+            .with_agent("agent-b", agent())
+            .build()
+            .unwrap()
+    }
+
+    async fn create_session(state: &Arc<RwLock<GatewayState>>, peer_id: &str) -> serde_json::Value {
+        dispatch_rpc(
+            state,
+            "session.create",
+            &serde_json::json!({ "agent_id": "default", "peer_id": peer_id }),
+            None,
+            None,
+        )
+        .await
+        .unwrap()
+    }
+
+    /// A gateway with auth on and two namespaces: acme (alice operates,
+    /// bob views) and globex (carol operates).
+    fn tenant_gateway(temp: &std::path::Path) -> Gateway {
+        use openclaw_agents::harness::{MockCompletion, MockProvider};
+
+        let agent = || {
+            Arc::new(AgentRuntime::new(Arc::new(MockProvider::new([
+                MockCompletion::Text("hello".to_string()),
+            ]))))
+        };
+        let namespace =
+            |agent: &str, channel: &str, members: &[(&str, UserRole)]| NamespaceConfig {
+                agents: vec![agent.to_string()],
+                channels: vec![channel.to_string()],
+                members: members
+                    .iter()
+                    .map(|(name, role)| ((*name).to_string(), *role))
+                    .collect(),
+                ..NamespaceConfig::default()
+            };
+        GatewayBuilder::new()
+            .with_config(GatewayConfig {
+                data_dir: temp.join("gateway"),
+                namespaces: HashMap::from([
+                    (
+                        "acme".to_string(),
+                        namespace(
+                            "acme-bot",
+                            "slack",
+                            &[("alice", UserRole::Operator), ("bob", UserRole::Viewer)],
+                        ),
+                    ),
+                    (
+                        "globex".to_string(),
+                        namespace("globex-bot", "discord", &[("carol", UserRole::Operator)]),
+                    ),
+                ]),
+                ..GatewayConfig::default()
+            })
+            .with_event_store(Arc::new(EventStore::open(&temp.join("events")).unwrap()))
+            .with_agent("acme-bot", agent())
+            .with_agent("globex-bot", agent())
+            .build()
+            .unwrap()
+    }
+
+    /// Create `username` with `role` and return an access token.
+    async fn login(state: &Arc<RwLock<GatewayState>>, username: &str, role: UserRole) -> String {
+        let state = state.read().await;
+        let user = User::new(username, "correct-horse-battery", role).unwrap();
+        state.auth.users.create(&user).unwrap();
+        state.auth.issue_tokens(&user).unwrap().access_token
+    }
+
+    /// Create a session for `agent_id` on `channel` as `token`.
+    async fn tenant_session(
+        state: &Arc<RwLock<GatewayState>>,
+        token: &str,
+        agent_id: &str,
+        channel: &str,
+    ) -> String {
+        let created = dispatch_rpc(
+            state,
+            "session.create",
+            &serde_json::json!({
+                "agent_id": agent_id,
+                "channel": channel,
+                "peer_id": "peer-1",
+            }),
+            Some(token),
+            None,
+        )
+        .await
+        .unwrap();
+        created["session_key"].as_str().unwrap().to_string()
+    }
+
+    /// A tenant gateway with tokens for alice, bob, and carol and a
+    /// session in each namespace.
+    struct Tenancy {
+        gateway: Gateway,
+        alice: String,
+        bob: String,
+        carol: String,
+        acme: String,
+        globex: String,
+    }
+
+    async fn tenancy(temp: &std::path::Path) -> Tenancy {
+        let gateway = tenant_gateway(temp);
+        let state = gateway.state();
+        let alice = login(state, "alice", UserRole::Operator).await;
+        let bob = login(state, "bob", UserRole::Viewer).await;
+        let carol = login(state, "carol", UserRole::Operator).await;
+        let acme = tenant_session(state, &alice, "acme-bot", "slack").await;
+        let globex = tenant_session(state, &carol, "globex-bot", "discord").await;
+        Tenancy {
+            gateway,
+            alice,
+            bob,
+            carol,
+            acme,
+            globex,
+        }
+    }
+
+    async fn rpc_as(
+        state: &Arc<RwLock<GatewayState>>,
+        method: &str,
+        params: serde_json::Value,
+        token: &str,
+    ) -> RpcResult {
+        dispatch_rpc(state, method, &params, Some(token), None).await
+    }
+
+    fn code(result: RpcResult) -> i32 {
+        result.unwrap_err().0
+    }
+
+    #[tokio::test]
+    async fn test_tools_execute_requires_session_access() {
+        let temp = tempfile::tempdir().unwrap();
+        let Tenancy {
+            gateway,
+            alice,
+            bob,
+            acme,
+            globex,
+            ..
+        } = tenancy(temp.path()).await;
+        let state = gateway.state();
+        let execute = |params, token| rpc_as(state, "tools.execute", params, token);
+
+        // Another tenant's session is invisible
+        let params = serde_json::json!({ "tool_name": "echo", "session_key": globex });
+        assert_eq!(code(execute(params, &alice).await), rpc::NOT_FOUND);
+
+        // Viewers can't write tool calls into a transcript
+        let params = serde_json::json!({ "tool_name": "echo", "session_key": acme });
+        assert_eq!(code(execute(params, &bob).await), rpc::FORBIDDEN);
+
+        // The call runs as the session's agent, not one the caller names
+        let params = serde_json::json!({
+            "tool_name": "echo",
+            "session_key": acme,
+            "agent_id": "globex-bot",
+        });
+        assert_eq!(code(execute(params, &alice).await), rpc::INVALID_PARAMS);
+
+        // Without a session, only agents in a namespace the caller manages
+        let params = serde_json::json!({ "tool_name": "echo", "agent_id": "globex-bot" });
+        assert_eq!(code(execute(params, &alice).await), rpc::NOT_FOUND);
+
+        let globex = SessionKey::new(&globex);
+        let store = state.read().await.store_for_session(&globex).clone();
+        assert_eq!(store.get_events(&globex).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_approvals_scoped_to_namespace() {
+        let temp = tempfile::tempdir().unwrap();
+        let Tenancy {
+            gateway,
+            alice,
+            bob,
+            carol,
+            acme,
+            globex,
+        } = tenancy(temp.path()).await;
+        let state = gateway.state();
+
+        // Approvals are listed and decided only within the caller's namespaces
+        let approvals = state.read().await.approvals.clone();
+        for (agent_id, session_key) in [("acme-bot", &acme), ("globex-bot", &globex)] {
+            let approvals = approvals.clone();
+            let (agent_id, session_key) = (agent_id.to_string(), session_key.clone());
+            tokio::spawn(async move {
+                approvals
+                    .request(
+                        &agent_id,
+                        Some(session_key),
+                        "exec",
+                        serde_json::json!({}),
+                        Duration::from_secs(30),
+                    )
+                    .await
+            });
+        }
+        while approvals.pending().len() < 2 {
+            tokio::task::yield_now().await;
+        }
+        let pending = approvals.pending();
+        let id_for = |agent: &str| {
+            pending
+                .iter()
+                .find(|r| r.agent_id == agent)
+                .map(|r| r.id.clone())
+                .unwrap()
+        };
+        let listed = rpc_as(state, "approvals.list", serde_json::json!({}), &bob)
+            .await
+            .unwrap();
+        assert_eq!(listed["count"], 1);
+        assert_eq!(listed["approvals"][0]["agent_id"], "acme-bot");
+        let globex_approval = serde_json::json!({ "id": id_for("globex-bot") });
+        assert_eq!(
+            code(rpc_as(state, "approvals.approve", globex_approval.clone(), &alice).await),
+            rpc::NOT_FOUND
+        );
+        let acme_approval = serde_json::json!({ "id": id_for("acme-bot") });
+        assert_eq!(
+            code(rpc_as(state, "approvals.deny", acme_approval.clone(), &bob).await),
+            rpc::FORBIDDEN
+        );
+        rpc_as(state, "approvals.approve", acme_approval, &alice)
+            .await
+            .unwrap();
+        rpc_as(state, "approvals.approve", globex_approval, &carol)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_peers_and_channels_scoped_to_namespace() {
+        let temp = tempfile::tempdir().unwrap();
+        let Tenancy {
+            gateway,
+            alice,
+            bob,
+            carol,
+            ..
+        } = tenancy(temp.path()).await;
+        let state = gateway.state();
+
+        // Peer profiles follow their channel's namespace
+        for (channel, token) in [("slack", &alice), ("discord", &carol)] {
+            rpc_as(
+                state,
+                "peers.update",
+                serde_json::json!({ "channel": channel, "peer_id": "p1", "display_name": "Pat" }),
+                token,
+            )
+            .await
+            .unwrap();
+        }
+        let peers = rpc_as(state, "peers.list", serde_json::json!({}), &alice)
+            .await
+            .unwrap();
+        assert_eq!(peers["peers"].as_array().unwrap().len(), 1);
+        assert_eq!(peers["peers"][0]["channel"], "slack");
+        let globex_peer = serde_json::json!({ "channel": "discord", "peer_id": "p1" });
+        assert_eq!(
+            code(rpc_as(state, "peers.get", globex_peer.clone(), &alice).await),
+            rpc::NOT_FOUND
+        );
+        assert_eq!(
+            code(rpc_as(state, "privacy.forget", globex_peer.clone(), &alice).await),
+            rpc::NOT_FOUND
+        );
+        rpc_as(state, "peers.get", globex_peer, &carol)
+            .await
+            .unwrap();
+
+        // Channels outside the caller's namespaces can't be used
+        let send = |channel: &str| serde_json::json!({ "channel_id": channel, "chat_id": "c", "text": "hi" });
+        assert_eq!(
+            code(rpc_as(state, "channels.send", send("discord"), &alice).await),
+            rpc::NOT_FOUND
+        );
+        assert_eq!(
+            code(rpc_as(state, "channels.send", send("slack"), &bob).await),
+            rpc::FORBIDDEN
+        );
+        assert_eq!(
+            code(
+                rpc_as(
+                    state,
+                    "channels.stop",
+                    serde_json::json!({ "channel_id": "discord" }),
+                    &alice
+                )
+                .await
+            ),
+            rpc::NOT_FOUND
+        );
+        assert_eq!(
+            code(
+                rpc_as(
+                    state,
+                    "notify.send",
+                    serde_json::json!({ "template": "t", "channel": "discord", "chat": "c" }),
+                    &alice,
+                )
+                .await
+            ),
+            rpc::NOT_FOUND
+        );
+    }
+
+    #[tokio::test]
+    async fn test_agents_and_reports_scoped_to_namespace() {
+        let temp = tempfile::tempdir().unwrap();
+        let Tenancy {
+            gateway,
+            alice,
+            bob,
+            carol,
+            acme,
+            globex,
+        } = tenancy(temp.path()).await;
+        let state = gateway.state();
+
+        // Agents and their reports are scoped to the caller's namespaces
+        let agents = rpc_as(state, "agent.list", serde_json::json!({}), &alice)
+            .await
+            .unwrap();
+        assert_eq!(agents["agents"], serde_json::json!(["acme-bot"]));
+        assert_eq!(
+            code(
+                rpc_as(
+                    state,
+                    "agent.get",
+                    serde_json::json!({ "agent_id": "globex-bot" }),
+                    &alice
+                )
+                .await
+            ),
+            rpc::NOT_FOUND
+        );
+        for (session_key, token) in [(&acme, &alice), (&globex, &carol)] {
+            rpc_as(
+                state,
+                "feedback.submit",
+                serde_json::json!({ "session_key": session_key, "rating": "up" }),
+                token,
+            )
+            .await
+            .unwrap();
+        }
+        let summary = rpc_as(state, "feedback.summary", serde_json::json!({}), &bob)
+            .await
+            .unwrap();
+        assert_eq!(summary["by_agent"].as_object().unwrap().len(), 1);
+        assert_eq!(summary["by_agent"]["acme-bot"]["positive"], 1);
+
+        // Workflows span namespaces, so members can't run or inspect them
+        assert_eq!(
+            code(rpc_as(state, "workflow.runs.list", serde_json::json!({}), &alice).await),
+            rpc::FORBIDDEN
+        );
+        assert_eq!(
+            code(
+                rpc_as(
+                    state,
+                    "workflow.run",
+                    serde_json::json!({ "workflow_id": "w" }),
+                    &alice
+                )
+                .await
+            ),
+            rpc::FORBIDDEN
+        );
+    }
+
+    #[tokio::test]
+    async fn test_experiment_assignment_over_rpc() {
+        let temp = tempfile::tempdir().unwrap();
+        let gateway = experiment_gateway(temp.path(), true);
+        let state = gateway.state();
+
+        let listed = dispatch_rpc(
+            state,
+            "experiments.list",
+            &serde_json::json!({}),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(listed["count"], 1);
+        assert_eq!(listed["experiments"][0]["id"], "prompt-v2");
+
+        let mut variants = std::collections::HashSet::new();
+        for i in 0..16 {
+            let peer_id = format!("peer-{i}");
+            let created = create_session(state, &peer_id).await;
+            assert_eq!(created["experiment"], "prompt-v2");
+            let variant = created["variant"].as_str().unwrap().to_string();
+            assert_eq!(created["agent_id"], format!("agent-{variant}"));
+
+            // The same session always lands in the same bucket
+            let again = create_session(state, &peer_id).await;
+            assert_eq!(again["variant"], created["variant"]);
+            assert_eq!(again["session_key"], created["session_key"]);
+            variants.insert(variant);
+        }
+        assert_eq!(variants.len(), 2);
+
+        // Other agents are not in the experiment
+        let other = dispatch_rpc(
+            state,
+            "session.create",
+            &serde_json::json!({ "agent_id": "other", "peer_id": "peer-0" }),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(other["variant"].is_null());
+        assert_eq!(other["agent_id"], "other");
+    }
+
+    #[tokio::test]
+    async fn test_disabled_experiment_assigns_nothing() {
+        let temp = tempfile::tempdir().unwrap();
+        let gateway = experiment_gateway(temp.path(), false);
+        let state = gateway.state();
+
+        let created = create_session(state, "peer-0").await;
+        assert!(created["experiment"].is_null());
+        assert!(created["variant"].is_null());
+        assert_eq!(created["agent_id"], "default");
+    }
+
+    #[tokio::test]
+    async fn test_experiment_report_over_rpc() {
+        let temp = tempfile::tempdir().unwrap();
+        let gateway = experiment_gateway(temp.path(), true);
+        let state = gateway.state();
+
+        let mut sessions = Vec::new();
+        for i in 0..8 {
+            sessions.push(create_session(state, &format!("peer-{i}")).await);
+        }
+        let answered = &sessions[0];
+        let response = dispatch_rpc(
+            state,
+            "session.message",
+            &serde_json::json!({
+                "session_key": answered["session_key"],
+                "message": "hi",
+            }),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response["response"], "hello");
+
+        let report = dispatch_rpc(
+            state,
+            "experiments.report",
+            &serde_json::json!({ "experiment_id": "prompt-v2" }),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let variants = report["variants"].as_object().unwrap();
+        let total: u64 = variants
+            .values()
+            .map(|v| v["sessions"].as_u64().unwrap())
+            .sum();
+        assert_eq!(total, 8);
+        let answered_variant = &report["variants"][answered["variant"].as_str().unwrap()];
+        assert_eq!(answered_variant["responses"], 1);
+        assert!(answered_variant["avg_latency_ms"].is_number());
+
+        let missing = dispatch_rpc(
+            state,
+            "experiments.report",
+            &serde_json::json!({}),
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(missing.0, rpc::INVALID_PARAMS);
+        let unknown = dispatch_rpc(
+            state,
+            "experiments.report",
+            &serde_json::json!({ "experiment_id": "nope" }),
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(unknown.0, rpc::NOT_FOUND);
+    }
 }
@@ -0,0 +1,282 @@
+//! Coordinated gateway shutdown.
+//!
+//! Shutdown is requested by a signal (SIGINT/SIGTERM), the `gateway.shutdown`
+//! RPC, or the daemon control socket. The gateway then rejects new RPCs,
+//! waits for in-flight agent runs up to the drain timeout, stops channels,
+//! flushes the event store, and finally closes WebSocket connections so the
+//! HTTP server can exit.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Notify, RwLock, watch};
+
+use crate::events::UiEvent;
+use crate::server::GatewayState;
+
+/// Where the gateway is in its lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShutdownPhase {
+    /// Serving requests.
+    Running,
+    /// Rejecting new work and waiting for agent runs to finish.
+    Draining,
+    /// Drained; connections are being closed.
+    Stopped,
+}
+
+/// Shared shutdown state.
+#[derive(Debug)]
+pub struct Shutdown {
+    phase: watch::Sender<ShutdownPhase>,
+    active_runs: AtomicUsize,
+    idle: Notify,
+}
+
+impl Shutdown {
+    /// Create shutdown state for a running gateway.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            phase: watch::Sender::new(ShutdownPhase::Running),
+            active_runs: AtomicUsize::new(0),
+            idle: Notify::new(),
+        }
+    }
+
+    /// Current phase.
+    #[must_use]
+    pub fn phase(&self) -> ShutdownPhase {
+        *self.phase.borrow()
+    }
+
+    /// Whether shutdown has been requested.
+    #[must_use]
+    pub fn is_draining(&self) -> bool {
+        self.phase() != ShutdownPhase::Running
+    }
+
+    /// Request shutdown. Returns `false` if it was already requested.
+    pub fn request(&self) -> bool {
+        self.phase.send_if_modified(|phase| {
+            if *phase == ShutdownPhase::Running {
+                *phase = ShutdownPhase::Draining;
+                true
+            } else {
+                false
+            }
+        })
+    }
+
+    /// Wait until shutdown is requested.
+    pub async fn requested(&self) {
+        let mut rx = self.phase.subscribe();
+        let _ = rx.wait_for(|p| *p != ShutdownPhase::Running).await;
+    }
+
+    /// Wait until draining has finished.
+    pub async fn stopped(&self) {
+        let mut rx = self.phase.subscribe();
+        let _ = rx.wait_for(|p| *p == ShutdownPhase::Stopped).await;
+    }
+
+    /// Mark draining as finished.
+    fn finish(&self) {
+        self.phase.send_replace(ShutdownPhase::Stopped);
+    }
+
+    /// Register an agent run. Returns `None` once shutdown has been
+    /// requested; the run is tracked until the guard is dropped.
+    #[must_use]
+    pub fn begin_run(self: &Arc<Self>) -> Option<RunGuard> {
+        // Count first so a concurrent drain either sees this run or makes
+        // us back out.
+        self.active_runs.fetch_add(1, Ordering::SeqCst);
+        let guard = RunGuard {
+            shutdown: self.clone(),
+        };
+        if self.is_draining() {
+            return None;
+        }
+        Some(guard)
+    }
+
+    /// Number of agent runs in progress.
+    #[must_use]
+    pub fn active_runs(&self) -> usize {
+        self.active_runs.load(Ordering::SeqCst)
+    }
+
+    /// Wait for all agent runs to finish. Returns `false` if `timeout`
+    /// passed first.
+    pub async fn wait_idle(&self, timeout: Duration) -> bool {
+        tokio::time::timeout(timeout, async {
+            loop {
+                let idle = self.idle.notified();
+                tokio::pin!(idle);
+                idle.as_mut().enable();
+                if self.active_runs() == 0 {
+                    return;
+                }
+                idle.await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Tracks one agent run; see [`Shutdown::begin_run`].
+#[derive(Debug)]
+pub struct RunGuard {
+    shutdown: Arc<Shutdown>,
+}
+
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        if self.shutdown.active_runs.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.shutdown.idle.notify_waiters();
+        }
+    }
+}
+
+/// Request shutdown on SIGINT or SIGTERM.
+pub(crate) async fn request_on_signal(shutdown: Arc<Shutdown>) {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::warn!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = ctrl_c => tracing::info!("Received SIGINT"),
+        () = terminate => tracing::info!("Received SIGTERM"),
+    }
+    shutdown.request();
+}
+
+/// Wait for a shutdown request, then drain the gateway.
+///
+/// Used as the HTTP server's graceful-shutdown future: the server keeps
+/// accepting connections (and rejecting RPCs) until this returns.
+pub(crate) async fn drain_on_request(state: Arc<RwLock<GatewayState>>) {
+    let shutdown = state.read().await.shutdown.clone();
+    shutdown.requested().await;
+
+    let (events, supervisor, event_stores, drain_timeout) = {
+        let state = state.read().await;
+        (
+            state.events.clone(),
+            state.supervisor.clone(),
+            state.event_stores().cloned().collect::<Vec<_>>(),
+            state.config.drain_timeout,
+        )
+    };
+
+    let active_runs = shutdown.active_runs();
+    tracing::info!(
+        "Shutting down, waiting up to {}s for {} agent run(s)",
+        drain_timeout.as_secs(),
+        active_runs
+    );
+    let _ = events.broadcast(UiEvent::GatewayStopping {
+        active_runs,
+        drain_timeout_ms: u64::try_from(drain_timeout.as_millis()).unwrap_or(u64::MAX),
+    });
+
+    if !shutdown.wait_idle(drain_timeout).await {
+        tracing::warn!(
+            "Drain timeout reached with {} agent run(s) still active",
+            shutdown.active_runs()
+        );
+    }
+
+    supervisor.stop_all().await;
+
+    for event_store in event_stores {
+        if let Err(e) = event_store.flush() {
+            tracing::error!("Failed to flush event store: {}", e);
+        }
+    }
+
+    shutdown.finish();
+    tracing::info!("Gateway drained");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_runs_rejected_after_request() {
+        let shutdown = Arc::new(Shutdown::new());
+        let run = shutdown.begin_run().unwrap();
+        assert_eq!(shutdown.active_runs(), 1);
+
+        assert!(shutdown.request());
+        assert!(!shutdown.request());
+        assert_eq!(shutdown.phase(), ShutdownPhase::Draining);
+        assert!(shutdown.begin_run().is_none());
+        assert_eq!(shutdown.active_runs(), 1);
+
+        drop(run);
+        assert_eq!(shutdown.active_runs(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_wait_idle() {
+        let shutdown = Arc::new(Shutdown::new());
+        assert!(shutdown.wait_idle(Duration::from_millis(10)).await);
+
+        let run = shutdown.begin_run().unwrap();
+        assert!(!shutdown.wait_idle(Duration::from_millis(10)).await);
+
+        let waiter = {
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move { shutdown.wait_idle(Duration::from_secs(5)).await })
+        };
+        tokio::task::yield_now().await;
+        drop(run);
+        assert!(waiter.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_phase_waiters() {
+        let shutdown = Arc::new(Shutdown::new());
+        let stopped = {
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move { shutdown.stopped().await })
+        };
+
+        shutdown.request();
+        shutdown.requested().await;
+        shutdown.finish();
+        stopped.await.unwrap();
+        assert_eq!(shutdown.phase(), ShutdownPhase::Stopped);
+    }
+}
@@ -0,0 +1,251 @@
+//! Server-Sent Events stream of UI events.
+//!
+//! `GET /events/stream` delivers the same [`UiEventEnvelope`]s as the
+//! WebSocket `events.subscribe` feed, for clients behind proxies or
+//! serverless frontends that cannot hold a WebSocket open. Each SSE event
+//! carries the envelope ID, so a reconnecting client (which sends
+//! `Last-Event-ID`) receives the events it missed. Authentication follows
+//! the WebSocket setting (`require_auth_for_ws`), with the token in an
+//! `Authorization: Bearer` header or the `token` query parameter. Callers
+//! only receive events from namespaces they can see.
+
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use futures::{Stream, StreamExt};
+use serde::Deserialize;
+use tokio::sync::{RwLock, broadcast};
+
+use crate::auth::JwtManager;
+use crate::events::{UiEvent, UiEventEnvelope};
+use crate::server::GatewayState;
+use crate::shutdown::Shutdown;
+use crate::tenancy::{NamespaceAccess, Tenants};
+
+/// Query parameters for `/events/stream`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SseParams {
+    /// Auth token, for clients that cannot set headers (`EventSource`).
+    token: Option<String>,
+    /// Only deliver events for this session.
+    session: Option<String>,
+    /// Comma-separated event types to deliver, e.g.
+    /// `message_received,message_sent`.
+    types: Option<String>,
+    /// Resume after this event ID, when `Last-Event-ID` cannot be set.
+    last_event_id: Option<String>,
+}
+
+/// Which events a connection receives.
+#[derive(Debug)]
+struct EventFilter {
+    session: Option<String>,
+    types: Option<HashSet<String>>,
+    tenants: Arc<Tenants>,
+    access: NamespaceAccess,
+}
+
+impl EventFilter {
+    fn from_params(params: &SseParams, tenants: Arc<Tenants>, access: NamespaceAccess) -> Self {
+        Self {
+            session: params.session.clone(),
+            types: params.types.as_ref().map(|types| {
+                types
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(String::from)
+                    .collect()
+            }),
+            tenants,
+            access,
+        }
+    }
+
+    /// Whether to deliver `event`. Events without a session (status,
+    /// heartbeats) pass a session filter.
+    fn matches(&self, event: &UiEvent) -> bool {
+        let session_ok = match (&self.session, event.session_key()) {
+            (Some(wanted), Some(key)) => wanted == key,
+            _ => true,
+        };
+        let type_ok = self
+            .types
+            .as_ref()
+            .is_none_or(|types| types.contains(event.event_type()));
+        session_ok && type_ok && self.tenants.event_visible(&self.access, event)
+    }
+}
+
+/// Handle a GET to `/events/stream`.
+pub async fn sse_handler(
+    State(state): State<Arc<RwLock<GatewayState>>>,
+    Query(params): Query<SseParams>,
+    headers: HeaderMap,
+) -> Response {
+    let header_token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(JwtManager::extract_from_header);
+    let token = header_token.or(params.token.as_deref());
+
+    let (events, shutdown, tenants, access) = {
+        let state = state.read().await;
+        let auth = &state.auth;
+        if auth.config.enabled
+            && auth.config.require_auth_for_ws
+            && token.is_none_or(|t| auth.validate_token(t).is_err())
+        {
+            return (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Bearer")],
+                "Authentication required",
+            )
+                .into_response();
+        }
+        (
+            state.events.clone(),
+            state.shutdown.clone(),
+            state.tenants.clone(),
+            state.namespace_access(token),
+        )
+    };
+
+    let filter = EventFilter::from_params(&params, tenants, access);
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .map(String::from)
+        .or(params.last_event_id);
+
+    // Subscribe before reading history so nothing falls between the two
+    let receiver = events.subscribe();
+    let mut initial = Vec::new();
+    let mut replayed = HashSet::new();
+    if let Some(last_id) = last_event_id {
+        match events.events_since(&last_id) {
+            Some(missed) => {
+                for envelope in missed {
+                    replayed.insert(envelope.id.clone());
+                    if filter.matches(&envelope.event) {
+                        initial.push(envelope_event(&envelope));
+                    }
+                }
+            }
+            None => initial.push(resync_event("unknown_last_event_id")),
+        }
+    }
+
+    let live = live_events(receiver, filter, replayed, shutdown);
+    let stream = futures::stream::iter(initial)
+        .chain(live)
+        .map(Ok::<_, Infallible>);
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// Live events after the initial replay, ending when the gateway stops.
+fn live_events(
+    receiver: broadcast::Receiver<UiEventEnvelope>,
+    filter: EventFilter,
+    replayed: HashSet<String>,
+    shutdown: Arc<Shutdown>,
+) -> impl Stream<Item = Event> {
+    futures::stream::unfold(
+        (receiver, filter, replayed, shutdown),
+        |(mut receiver, filter, mut replayed, shutdown)| async move {
+            loop {
+                let result = tokio::select! {
+                    result = receiver.recv() => result,
+                    () = shutdown.stopped() => return None,
+                };
+                let event = match result {
+                    Ok(envelope) => {
+                        // Already sent during replay
+                        if replayed.remove(&envelope.id) || !filter.matches(&envelope.event) {
+                            continue;
+                        }
+                        envelope_event(&envelope)
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        tracing::warn!("SSE client lagged, missed {} events", n);
+                        resync_event("lagged")
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                };
+                return Some((event, (receiver, filter, replayed, shutdown)));
+            }
+        },
+    )
+}
+
+fn envelope_event(envelope: &UiEventEnvelope) -> Event {
+    Event::default()
+        .id(&envelope.id)
+        .data(serde_json::to_string(envelope).unwrap_or_default())
+}
+
+/// Tell the client it missed events and should reload its state.
+fn resync_event(reason: &str) -> Event {
+    Event::default()
+        .event("resync")
+        .data(serde_json::json!({ "reason": reason }).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(session_key: &str) -> UiEvent {
+        UiEvent::MessageSent {
+            session_key: session_key.to_string(),
+            content: "hi".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let filter = EventFilter::from_params(
+            &SseParams {
+                session: Some("a".to_string()),
+                types: Some("message_sent, heartbeat".to_string()),
+                ..SseParams::default()
+            },
+            Arc::default(),
+            NamespaceAccess::All,
+        );
+        assert!(filter.matches(&message("a")));
+        assert!(!filter.matches(&message("b")));
+        assert!(filter.matches(&UiEvent::Heartbeat {
+            timestamp: chrono::Utc::now(),
+        }));
+        assert!(!filter.matches(&UiEvent::MessageReceived {
+            session_key: "a".to_string(),
+            content: "hi".to_string(),
+            peer_id: "user".to_string(),
+        }));
+
+        let everything =
+            EventFilter::from_params(&SseParams::default(), Arc::default(), NamespaceAccess::All);
+        assert!(everything.matches(&message("b")));
+
+        // Callers limited to namespaces don't see sessions outside them
+        let outsider = EventFilter::from_params(
+            &SseParams::default(),
+            Arc::default(),
+            NamespaceAccess::Members(std::collections::HashMap::new()),
+        );
+        assert!(!outsider.matches(&message("b")));
+    }
+}
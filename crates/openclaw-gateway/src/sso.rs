@@ -0,0 +1,219 @@
+//! Single sign-on routes.
+//!
+//! `GET /auth/oidc/login` sends the browser to the `OpenID` Connect provider,
+//! which sends it back to `GET /auth/oidc/callback`. The callback signs the
+//! user in, creating the account on first login and keeping its role in
+//! step with the role rules, then returns to the web UI's login page with
+//! the gateway's tokens in the URL fragment, or an `error` there instead.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Redirect, Response},
+};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+
+use crate::auth::{AuthState, JwtManager, OidcClient, OidcError, OidcIdentity, User};
+use crate::server::GatewayState;
+
+/// Query of the login route.
+#[derive(Debug, Deserialize)]
+pub struct LoginParams {
+    /// UI path to return to after login.
+    return_to: Option<String>,
+}
+
+/// Query the provider redirects back with.
+#[derive(Debug, Deserialize)]
+pub struct CallbackParams {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+/// Start a single sign-on login.
+pub async fn oidc_login_handler(
+    State(state): State<Arc<RwLock<GatewayState>>>,
+    Query(params): Query<LoginParams>,
+) -> Response {
+    let Some(oidc) = state.read().await.oidc.clone() else {
+        return (StatusCode::NOT_FOUND, "Single sign-on is not configured").into_response();
+    };
+    // Only paths within the UI, so the login can't be used as an open
+    // redirect
+    let return_to = params
+        .return_to
+        .filter(|path| path.starts_with('/') && !path.starts_with("//"));
+
+    match oidc.authorization_url(return_to).await {
+        Ok(url) => Redirect::to(&url).into_response(),
+        Err(e) => {
+            tracing::warn!("Single sign-on unavailable: {}", e);
+            (StatusCode::BAD_GATEWAY, e.to_string()).into_response()
+        }
+    }
+}
+
+/// Finish a single sign-on login and return to the UI.
+pub async fn oidc_callback_handler(
+    State(state): State<Arc<RwLock<GatewayState>>>,
+    Query(params): Query<CallbackParams>,
+) -> Response {
+    let Some(oidc) = state.read().await.oidc.clone() else {
+        return (StatusCode::NOT_FOUND, "Single sign-on is not configured").into_response();
+    };
+
+    let result = match (params.error, params.code, params.state) {
+        (Some(error), _, _) => {
+            Err(OidcError::Provider(params.error_description.unwrap_or(error)).to_string())
+        }
+        (None, Some(code), Some(login)) => sign_in(&state, &oidc, &code, &login).await,
+        _ => Err(OidcError::UnknownLogin.to_string()),
+    };
+    let fragment = result.unwrap_or_else(|message| {
+        tracing::warn!(target: "security", reason = %message, "Single sign-on failed");
+        vec![("error", message)]
+    });
+
+    let ui_url = &oidc.config().ui_url;
+    Redirect::to(&format!("{ui_url}#{}", form_encode(&fragment))).into_response()
+}
+
+/// Verify the provider's answer and issue the gateway's tokens.
+async fn sign_in(
+    state: &Arc<RwLock<GatewayState>>,
+    oidc: &OidcClient,
+    code: &str,
+    login: &str,
+) -> Result<Vec<(&'static str, String)>, String> {
+    let identity = oidc
+        .complete(code, login)
+        .await
+        .map_err(|e| e.to_string())?;
+    let auth = state.read().await.auth.clone();
+    let user = provision(&auth, &identity)?;
+    let tokens = auth
+        .issue_tokens(&user)
+        .map_err(|e| format!("Token generation failed: {e}"))?;
+    tracing::info!(
+        target: "security",
+        username = %user.username,
+        role = %user.role,
+        "Single sign-on login"
+    );
+
+    let mut fragment = vec![
+        ("token", tokens.access_token),
+        ("refresh_token", tokens.refresh_token),
+        ("expires_at", tokens.expires_at.to_rfc3339()),
+    ];
+    if let Some(path) = identity.return_to {
+        fragment.push(("return_to", path));
+    }
+    Ok(fragment)
+}
+
+/// The account for `identity`, created on first login.
+fn provision(auth: &AuthState, identity: &OidcIdentity) -> Result<User, String> {
+    let existing = auth
+        .users
+        .get_by_username(&identity.username)
+        .map_err(|e| format!("Storage error: {e}"))?;
+
+    let user = if let Some(mut user) = existing {
+        // A provider user may not take over a local account of the same name
+        if user.external_id.as_deref() != Some(identity.subject.as_str()) {
+            return Err(format!(
+                "{} already exists as a local account",
+                identity.username
+            ));
+        }
+        if !user.active {
+            return Err("Account disabled".to_string());
+        }
+        if user.role != identity.role || user.email != identity.email {
+            user.role = identity.role;
+            user.email.clone_from(&identity.email);
+            auth.users
+                .update(&user)
+                .map_err(|e| format!("Storage error: {e}"))?;
+        }
+        user
+    } else {
+        // The random password is never shown; these accounts sign in through
+        // the provider only
+        let mut user = User::new(
+            identity.username.clone(),
+            &JwtManager::generate_hex_secret(),
+            identity.role,
+        )
+        .map_err(|e| e.to_string())?;
+        user.email.clone_from(&identity.email);
+        user.external_id = Some(identity.subject.clone());
+        auth.users
+            .create(&user)
+            .map_err(|e| format!("Storage error: {e}"))?;
+        user
+    };
+
+    auth.users
+        .update_last_login(&user.id)
+        .map_err(|e| format!("Storage error: {e}"))?;
+    Ok(user)
+}
+
+/// `pairs` as `application/x-www-form-urlencoded` text.
+fn form_encode(pairs: &[(&str, String)]) -> String {
+    reqwest::Url::parse_with_params("http://localhost/", pairs)
+        .ok()
+        .and_then(|url| url.query().map(str::to_string))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{AuthConfig, UserRole};
+    use tempfile::TempDir;
+
+    fn identity(username: &str, role: UserRole) -> OidcIdentity {
+        OidcIdentity {
+            subject: format!("https://id.example.com|{username}"),
+            username: username.to_string(),
+            email: None,
+            role,
+            return_to: None,
+        }
+    }
+
+    #[test]
+    fn test_provision() {
+        let temp = TempDir::new().unwrap();
+        let auth = AuthState::initialize(AuthConfig::default(), temp.path()).unwrap();
+
+        let created = provision(&auth, &identity("ada", UserRole::Viewer)).unwrap();
+        assert_eq!(created.role, UserRole::Viewer);
+
+        // Later logins follow role changes at the provider
+        let updated = provision(&auth, &identity("ada", UserRole::Operator)).unwrap();
+        assert_eq!(updated.id, created.id);
+        assert_eq!(
+            auth.users.get(&created.id).unwrap().unwrap().role,
+            UserRole::Operator
+        );
+
+        let local = User::new("root", "secret", UserRole::Admin).unwrap();
+        auth.users.create(&local).unwrap();
+        assert!(provision(&auth, &identity("root", UserRole::Admin)).is_err());
+    }
+
+    #[test]
+    fn test_form_encode() {
+        let encoded = form_encode(&[("error", "No role & no access".to_string())]);
+        assert_eq!(encoded, "error=No+role+%26+no+access");
+    }
+}
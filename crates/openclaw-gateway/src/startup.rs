@@ -0,0 +1,237 @@
+//! Startup report and boot-time budget.
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use openclaw_channels::ChannelRegistry;
+use openclaw_core::events::StoreStats;
+
+/// How long a single channel probe may take during startup.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time spent in one boot phase.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupPhase {
+    /// Phase name (e.g., `event_store`).
+    pub name: String,
+    /// Duration in milliseconds.
+    pub duration_ms: u64,
+}
+
+/// Probe result for a channel at startup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelStartup {
+    /// Channel ID.
+    pub id: String,
+    /// Whether the probe succeeded.
+    pub connected: bool,
+    /// Account the channel is connected as.
+    pub account_id: Option<String>,
+    /// Probe error, if any.
+    pub error: Option<String>,
+}
+
+/// What the gateway loaded and how long each boot phase took.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupReport {
+    /// Gateway version.
+    pub version: String,
+    /// When the gateway finished booting.
+    pub started_at: DateTime<Utc>,
+    /// Where the configuration came from (`None` for built-in defaults).
+    pub config_source: Option<String>,
+    /// API listen addresses, comma-separated (`unix:` for a Unix socket).
+    pub address: String,
+    /// Registered agent IDs.
+    pub agents: Vec<String>,
+    /// Registered channels with probe results.
+    pub channels: Vec<ChannelStartup>,
+    /// Event store counters.
+    pub storage: StoreStats,
+    /// Boot phases in order.
+    pub phases: Vec<StartupPhase>,
+    /// Total boot time in milliseconds.
+    pub total_ms: u64,
+    /// Configured boot budget in milliseconds.
+    pub budget_ms: u64,
+}
+
+impl StartupReport {
+    /// Whether boot took longer than the budget.
+    #[must_use]
+    pub const fn over_budget(&self) -> bool {
+        self.total_ms > self.budget_ms
+    }
+
+    /// The phase that took longest.
+    #[must_use]
+    pub fn slowest_phase(&self) -> Option<&StartupPhase> {
+        self.phases.iter().max_by_key(|p| p.duration_ms)
+    }
+
+    /// Log the report, warning if boot exceeded the budget.
+    pub fn log(&self) {
+        tracing::info!(
+            total_ms = self.total_ms,
+            agents = self.agents.len(),
+            channels = self.channels.len(),
+            sessions = self.storage.sessions,
+            "Gateway started in {}ms",
+            self.total_ms
+        );
+
+        if self.over_budget() {
+            let slowest = self
+                .slowest_phase()
+                .map(|p| format!(" (slowest phase: {} {}ms)", p.name, p.duration_ms))
+                .unwrap_or_default();
+            tracing::warn!(
+                total_ms = self.total_ms,
+                budget_ms = self.budget_ms,
+                "Gateway boot took {}ms, over the {}ms budget{}",
+                self.total_ms,
+                self.budget_ms,
+                slowest
+            );
+        }
+    }
+}
+
+/// Records boot phases as they complete.
+#[derive(Debug, Clone)]
+pub struct BootTimer {
+    started: Instant,
+    last: Instant,
+    phases: Vec<StartupPhase>,
+}
+
+impl BootTimer {
+    /// Start timing.
+    #[must_use]
+    pub fn start() -> Self {
+        let now = Instant::now();
+        Self {
+            started: now,
+            last: now,
+            phases: Vec::new(),
+        }
+    }
+
+    /// Record the time since the previous mark as phase `name`.
+    pub fn mark(&mut self, name: &str) {
+        let now = Instant::now();
+        self.phases.push(StartupPhase {
+            name: name.to_string(),
+            duration_ms: millis(now - self.last),
+        });
+        self.last = now;
+    }
+
+    /// Phases recorded so far.
+    #[must_use]
+    pub fn phases(&self) -> &[StartupPhase] {
+        &self.phases
+    }
+
+    /// Milliseconds since the timer started.
+    #[must_use]
+    pub fn elapsed_ms(&self) -> u64 {
+        millis(self.started.elapsed())
+    }
+}
+
+/// Probe every registered channel concurrently.
+pub async fn probe_channels(registry: &ChannelRegistry) -> Vec<ChannelStartup> {
+    let probes = registry.list().into_iter().filter_map(|id| {
+        let channel = registry.get(id)?.clone();
+        let id = id.to_string();
+        Some(async move {
+            match tokio::time::timeout(PROBE_TIMEOUT, channel.probe()).await {
+                Ok(Ok(probe)) => ChannelStartup {
+                    id,
+                    connected: probe.connected,
+                    account_id: probe.account_id,
+                    error: probe.error,
+                },
+                Ok(Err(e)) => ChannelStartup {
+                    id,
+                    connected: false,
+                    account_id: None,
+                    error: Some(e.to_string()),
+                },
+                Err(_) => ChannelStartup {
+                    id,
+                    connected: false,
+                    account_id: None,
+                    error: Some(format!(
+                        "Probe timed out after {}s",
+                        PROBE_TIMEOUT.as_secs()
+                    )),
+                },
+            }
+        })
+    });
+
+    let mut channels = futures::future::join_all(probes).await;
+    channels.sort_by(|a, b| a.id.cmp(&b.id));
+    channels
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(total_ms: u64, budget_ms: u64) -> StartupReport {
+        StartupReport {
+            version: "0.1.0".to_string(),
+            started_at: Utc::now(),
+            config_source: None,
+            address: "127.0.0.1:18789".to_string(),
+            agents: vec![],
+            channels: vec![],
+            storage: StoreStats::default(),
+            phases: vec![
+                StartupPhase {
+                    name: "event_store".to_string(),
+                    duration_ms: 40,
+                },
+                StartupPhase {
+                    name: "channels".to_string(),
+                    duration_ms: 900,
+                },
+            ],
+            total_ms,
+            budget_ms,
+        }
+    }
+
+    #[test]
+    fn test_budget() {
+        assert!(!report(940, 5000).over_budget());
+
+        let slow = report(6000, 5000);
+        assert!(slow.over_budget());
+        assert_eq!(slow.slowest_phase().unwrap().name, "channels");
+    }
+
+    #[test]
+    fn test_boot_timer() {
+        let mut timer = BootTimer::start();
+        timer.mark("config");
+        timer.mark("listen");
+
+        let names: Vec<_> = timer.phases().iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["config", "listen"]);
+    }
+
+    #[tokio::test]
+    async fn test_probe_no_channels() {
+        assert!(probe_channels(&ChannelRegistry::new()).await.is_empty());
+    }
+}
@@ -0,0 +1,699 @@
+//! Channel supervisor.
+//!
+//! The daemon runs each registered channel under its own task: the task
+//! starts the channel, probes it periodically, and restarts it with
+//! exponential backoff when it fails to start, reports itself
+//! disconnected, or panics. Channels can be stopped and started
+//! individually without restarting the gateway; a stop first waits for
+//! the channel's in-flight inbound messages to be answered.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, watch};
+
+use openclaw_channels::{Channel, ChannelContext};
+
+/// How long a single channel may take to stop.
+const CHANNEL_STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long a single health probe may take.
+const CHANNEL_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Supervisor errors.
+#[derive(Debug, thiserror::Error)]
+pub enum SupervisorError {
+    /// No channel with this ID is supervised.
+    #[error("Channel not found: {0}")]
+    NotFound(String),
+
+    /// The channel did not reach the requested state in time.
+    #[error("Timed out waiting for channel {0}")]
+    Timeout(String),
+
+    /// The channel is disabled and must be enabled before it can start.
+    #[error("Channel {0} is disabled")]
+    Disabled(String),
+
+    /// The channel is stopping or stopped and takes no new messages.
+    #[error("Channel {0} is not accepting messages")]
+    NotAccepting(String),
+}
+
+/// Restart and health-check timing.
+#[derive(Debug, Clone, Copy)]
+pub struct SupervisorConfig {
+    /// Delay before the first restart of a failed channel.
+    pub initial_backoff: Duration,
+    /// Upper bound for the restart delay, which doubles on each failure.
+    pub max_backoff: Duration,
+    /// How often running channels are probed.
+    pub probe_interval: Duration,
+    /// How long a stop waits for in-flight messages before stopping anyway.
+    pub drain_timeout: Duration,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(300),
+            probe_interval: Duration::from_secs(30),
+            drain_timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Where a supervised channel is in its lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelRunState {
+    /// `start` is in progress.
+    Starting,
+    /// Started and passing health probes.
+    Running,
+    /// Failed; waiting to restart.
+    Backoff,
+    /// Stopped on request.
+    Stopped,
+}
+
+/// Health of one supervised channel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelHealth {
+    /// Channel ID.
+    pub id: String,
+    /// Current state.
+    pub state: ChannelRunState,
+    /// Whether the channel is enabled; disabled channels stay stopped.
+    pub enabled: bool,
+    /// Restarts after failures since the gateway started.
+    pub restarts: u32,
+    /// Most recent failure.
+    pub last_error: Option<String>,
+    /// When the channel entered its current state.
+    pub since: DateTime<Utc>,
+}
+
+impl ChannelHealth {
+    fn new(id: &str, enabled: bool) -> Self {
+        Self {
+            id: id.to_string(),
+            state: ChannelRunState::Starting,
+            enabled,
+            restarts: 0,
+            last_error: None,
+            since: Utc::now(),
+        }
+    }
+
+    fn enter(&mut self, state: ChannelRunState) {
+        if self.state != state {
+            self.state = state;
+            self.since = Utc::now();
+        }
+    }
+}
+
+/// A channel's health, shared by its task and the supervisor. Every
+/// change is also sent to the supervisor's subscribers.
+struct HealthReporter {
+    health: watch::Sender<ChannelHealth>,
+    changes: broadcast::Sender<ChannelHealth>,
+}
+
+impl HealthReporter {
+    fn update(&self, modify: impl FnOnce(&mut ChannelHealth)) {
+        self.health.send_modify(modify);
+        let _ = self.changes.send(self.snapshot());
+    }
+
+    fn enter(&self, state: ChannelRunState) {
+        self.update(|h| h.enter(state));
+    }
+
+    fn snapshot(&self) -> ChannelHealth {
+        self.health.borrow().clone()
+    }
+}
+
+/// Handle to one supervised channel's task.
+struct Supervised {
+    /// Whether the channel should be running.
+    desired: watch::Sender<bool>,
+    health: Arc<HealthReporter>,
+    /// Whether new inbound messages are taken; cleared when a stop begins.
+    accepting: bool,
+    /// Inbound messages being answered.
+    in_flight: Arc<watch::Sender<usize>>,
+}
+
+/// An inbound message being answered. Stopping its channel waits until
+/// this is dropped.
+#[derive(Debug)]
+#[must_use = "the message counts as answered once this is dropped"]
+pub struct MessageGuard(Option<Arc<watch::Sender<usize>>>);
+
+impl Drop for MessageGuard {
+    fn drop(&mut self) {
+        if let Some(in_flight) = &self.0 {
+            in_flight.send_modify(|n| *n = n.saturating_sub(1));
+        }
+    }
+}
+
+/// Runs channels and keeps them running.
+pub struct ChannelSupervisor {
+    config: SupervisorConfig,
+    channels: Mutex<HashMap<String, Supervised>>,
+    changes: broadcast::Sender<ChannelHealth>,
+}
+
+impl ChannelSupervisor {
+    /// Create a supervisor with no channels.
+    #[must_use]
+    pub fn new(config: SupervisorConfig) -> Self {
+        Self {
+            config,
+            channels: Mutex::new(HashMap::new()),
+            changes: broadcast::channel(64).0,
+        }
+    }
+
+    /// Start supervising `channel`, running it if `enabled`. A channel
+    /// already supervised under the same ID is left alone.
+    pub fn spawn(&self, channel: Arc<dyn Channel>, ctx: ChannelContext, enabled: bool) {
+        let mut channels = self.channels.lock().unwrap_or_else(PoisonError::into_inner);
+        let id = channel.id().to_string();
+        if channels.contains_key(&id) {
+            return;
+        }
+
+        let (desired, desired_rx) = watch::channel(enabled);
+        let health = Arc::new(HealthReporter {
+            health: watch::channel(ChannelHealth::new(&id, enabled)).0,
+            changes: self.changes.clone(),
+        });
+        tokio::spawn(supervise(
+            channel,
+            ctx,
+            self.config,
+            desired_rx,
+            health.clone(),
+        ));
+        channels.insert(
+            id,
+            Supervised {
+                desired,
+                health,
+                accepting: enabled,
+                in_flight: Arc::new(watch::channel(0).0),
+            },
+        );
+    }
+
+    /// Health of each channel whenever it changes.
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<ChannelHealth> {
+        self.changes.subscribe()
+    }
+
+    /// Start a stopped channel, or retry a failed one without waiting out
+    /// its backoff.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if the channel is not supervised, or `Disabled`
+    /// if it is disabled.
+    pub fn start(&self, id: &str) -> Result<ChannelHealth, SupervisorError> {
+        let mut channels = self.channels.lock().unwrap_or_else(PoisonError::into_inner);
+        let supervised = channels
+            .get_mut(id)
+            .ok_or_else(|| SupervisorError::NotFound(id.to_string()))?;
+        let health = supervised.health.snapshot();
+        if !health.enabled {
+            return Err(SupervisorError::Disabled(id.to_string()));
+        }
+        supervised.accepting = true;
+        supervised.desired.send_replace(true);
+        drop(channels);
+        Ok(health)
+    }
+
+    /// Stop a channel once its in-flight messages are answered, or the
+    /// drain timeout passes, and wait until it has stopped. New messages
+    /// are refused meanwhile.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if the channel is not supervised, or `Timeout` if
+    /// it did not stop in time.
+    pub async fn stop(&self, id: &str) -> Result<ChannelHealth, SupervisorError> {
+        let mut in_flight = {
+            let mut channels = self.channels.lock().unwrap_or_else(PoisonError::into_inner);
+            let supervised = channels
+                .get_mut(id)
+                .ok_or_else(|| SupervisorError::NotFound(id.to_string()))?;
+            supervised.accepting = false;
+            let in_flight = supervised.in_flight.subscribe();
+            drop(channels);
+            in_flight
+        };
+        let drain = in_flight.wait_for(|n| *n == 0);
+        if tokio::time::timeout(self.config.drain_timeout, drain)
+            .await
+            .is_err()
+        {
+            tracing::warn!(
+                "Stopping channel {} with {} messages still in flight",
+                id,
+                *in_flight.borrow()
+            );
+        }
+
+        let mut health = {
+            let channels = self.channels.lock().unwrap_or_else(PoisonError::into_inner);
+            let supervised = channels
+                .get(id)
+                .ok_or_else(|| SupervisorError::NotFound(id.to_string()))?;
+            supervised.desired.send_replace(false);
+            let health = supervised.health.health.subscribe();
+            drop(channels);
+            health
+        };
+
+        let stopped = tokio::time::timeout(
+            CHANNEL_STOP_TIMEOUT * 2,
+            health.wait_for(|h| h.state == ChannelRunState::Stopped),
+        )
+        .await
+        .map(|result| result.map(|h| (*h).clone()));
+        match stopped {
+            Ok(Ok(stopped)) => Ok(stopped),
+            // The task is gone, so the channel isn't running either
+            Ok(Err(_)) => Ok(health.borrow().clone()),
+            Err(_) => Err(SupervisorError::Timeout(id.to_string())),
+        }
+    }
+
+    /// Enable and start a channel, or disable and stop it. The flag only
+    /// lasts until the gateway restarts; callers persist it.
+    ///
+    /// # Errors
+    ///
+    /// As for [`start`](Self::start) and [`stop`](Self::stop).
+    pub async fn set_enabled(
+        &self,
+        id: &str,
+        enabled: bool,
+    ) -> Result<ChannelHealth, SupervisorError> {
+        {
+            let channels = self.channels.lock().unwrap_or_else(PoisonError::into_inner);
+            let supervised = channels
+                .get(id)
+                .ok_or_else(|| SupervisorError::NotFound(id.to_string()))?;
+            supervised.health.update(|h| h.enabled = enabled);
+            drop(channels);
+        }
+        if enabled {
+            self.start(id)
+        } else {
+            self.stop(id).await
+        }
+    }
+
+    /// Count an inbound message for channel `id` as in flight until the
+    /// guard is dropped. Messages for channels that are not supervised are
+    /// not counted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotAccepting` if the channel is stopping or stopped.
+    pub fn begin_message(&self, id: &str) -> Result<MessageGuard, SupervisorError> {
+        let channels = self.channels.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(supervised) = channels.get(id) else {
+            return Ok(MessageGuard(None));
+        };
+        if !supervised.accepting {
+            return Err(SupervisorError::NotAccepting(id.to_string()));
+        }
+        supervised.in_flight.send_modify(|n| *n += 1);
+        let guard = MessageGuard(Some(supervised.in_flight.clone()));
+        drop(channels);
+        Ok(guard)
+    }
+
+    /// Stop every channel, e.g. on shutdown.
+    pub async fn stop_all(&self) {
+        let ids: Vec<String> = self
+            .channels
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .keys()
+            .cloned()
+            .collect();
+        let stops = ids.iter().map(|id| self.stop(id));
+        for result in futures::future::join_all(stops).await {
+            if let Err(e) = result {
+                tracing::warn!("{}", e);
+            }
+        }
+    }
+
+    /// Health of every supervised channel, sorted by ID.
+    #[must_use]
+    pub fn health(&self) -> Vec<ChannelHealth> {
+        let mut health: Vec<ChannelHealth> = self
+            .channels
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .map(|s| s.health.snapshot())
+            .collect();
+        health.sort_by(|a, b| a.id.cmp(&b.id));
+        health
+    }
+
+    /// Whether every channel is running or was stopped on request.
+    #[must_use]
+    pub fn is_healthy(&self) -> bool {
+        self.health()
+            .iter()
+            .all(|h| matches!(h.state, ChannelRunState::Running | ChannelRunState::Stopped))
+    }
+}
+
+impl Default for ChannelSupervisor {
+    fn default() -> Self {
+        Self::new(SupervisorConfig::default())
+    }
+}
+
+/// Why a channel stopped running.
+enum Exit {
+    /// A stop was requested.
+    Requested,
+    /// The supervisor was dropped.
+    Closed,
+    /// The channel failed; `started` is whether `start` had succeeded.
+    Failed { error: String, started: bool },
+}
+
+/// Supervise one channel until the supervisor is dropped.
+async fn supervise(
+    channel: Arc<dyn Channel>,
+    ctx: ChannelContext,
+    config: SupervisorConfig,
+    mut desired: watch::Receiver<bool>,
+    health: Arc<HealthReporter>,
+) {
+    let id = channel.id().to_string();
+    let mut backoff = config.initial_backoff;
+
+    loop {
+        if !*desired.borrow_and_update() {
+            health.enter(ChannelRunState::Stopped);
+            tracing::info!("Channel {} stopped", id);
+            if desired.wait_for(|d| *d).await.is_err() {
+                return;
+            }
+            backoff = config.initial_backoff;
+        }
+
+        health.enter(ChannelRunState::Starting);
+        let exit = run(&channel, &ctx, config, &mut desired, &health).await;
+        stop_channel(&channel).await;
+
+        let (error, started) = match exit {
+            Exit::Requested => continue,
+            Exit::Closed => return,
+            Exit::Failed { error, started } => (error, started),
+        };
+        if started {
+            backoff = config.initial_backoff;
+        }
+        tracing::warn!(
+            "Channel {} failed: {}; restarting in {}s",
+            id,
+            error,
+            backoff.as_secs_f32()
+        );
+        health.update(|h| {
+            h.enter(ChannelRunState::Backoff);
+            h.restarts += 1;
+            h.last_error = Some(error);
+        });
+
+        // A start or stop request cuts the wait short
+        tokio::select! {
+            () = tokio::time::sleep(backoff) => {}
+            changed = desired.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+            }
+        }
+        backoff = (backoff * 2).min(config.max_backoff);
+    }
+}
+
+/// Start the channel and probe it until it fails or a stop is requested.
+async fn run(
+    channel: &Arc<dyn Channel>,
+    ctx: &ChannelContext,
+    config: SupervisorConfig,
+    desired: &mut watch::Receiver<bool>,
+    health: &HealthReporter,
+) -> Exit {
+    // Run `start` in its own task so a panic is reported, not propagated
+    let starting = {
+        let channel = channel.clone();
+        let ctx = ctx.clone();
+        tokio::spawn(async move { channel.start(ctx).await })
+    };
+    let abort = starting.abort_handle();
+    let started = tokio::select! {
+        result = starting => result,
+        stop = desired.wait_for(|d| !*d) => {
+            abort.abort();
+            return if stop.is_ok() { Exit::Requested } else { Exit::Closed };
+        }
+    };
+    let error = match started {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(e.to_string()),
+        Err(e) => Some(format!("Start panicked: {e}")),
+    };
+    if let Some(error) = error {
+        return Exit::Failed {
+            error,
+            started: false,
+        };
+    }
+
+    health.enter(ChannelRunState::Running);
+    tracing::info!("Channel {} running", channel.id());
+
+    loop {
+        tokio::select! {
+            () = tokio::time::sleep(config.probe_interval) => {}
+            stop = desired.wait_for(|d| !*d) => {
+                return if stop.is_ok() { Exit::Requested } else { Exit::Closed };
+            }
+        }
+
+        let error = match tokio::time::timeout(CHANNEL_PROBE_TIMEOUT, channel.probe()).await {
+            Ok(Ok(probe)) if probe.connected => continue,
+            Ok(Ok(probe)) => probe.error.unwrap_or_else(|| "Disconnected".to_string()),
+            Ok(Err(e)) => e.to_string(),
+            Err(_) => "Health probe timed out".to_string(),
+        };
+        return Exit::Failed {
+            error,
+            started: true,
+        };
+    }
+}
+
+/// Stop a channel, logging rather than returning failures.
+async fn stop_channel(channel: &Arc<dyn Channel>) {
+    match tokio::time::timeout(CHANNEL_STOP_TIMEOUT, channel.stop()).await {
+        Ok(Ok(())) => tracing::debug!("Stopped channel {}", channel.id()),
+        Ok(Err(e)) => tracing::warn!("Failed to stop channel {}: {}", channel.id(), e),
+        Err(_) => tracing::warn!("Timed out stopping channel {}", channel.id()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+    use async_trait::async_trait;
+    use openclaw_channels::{ChannelCapabilities, ChannelError, ChannelProbe};
+
+    /// Fails to start `failures` times, then stays connected until
+    /// `disconnect` is set.
+    #[derive(Default)]
+    struct FlakyChannel {
+        failures: AtomicU32,
+        starts: AtomicU32,
+        disconnect: AtomicBool,
+    }
+
+    #[async_trait]
+    impl Channel for FlakyChannel {
+        fn id(&self) -> &'static str {
+            "flaky"
+        }
+
+        fn label(&self) -> &'static str {
+            "Flaky"
+        }
+
+        fn capabilities(&self) -> ChannelCapabilities {
+            ChannelCapabilities::default()
+        }
+
+        async fn start(&self, _ctx: ChannelContext) -> Result<(), ChannelError> {
+            self.starts.fetch_add(1, Ordering::SeqCst);
+            let remaining = self.failures.load(Ordering::SeqCst);
+            if remaining > 0 {
+                self.failures.store(remaining - 1, Ordering::SeqCst);
+                return Err(ChannelError::Network("unreachable".to_string()));
+            }
+            self.disconnect.store(false, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn stop(&self) -> Result<(), ChannelError> {
+            Ok(())
+        }
+
+        async fn probe(&self) -> Result<ChannelProbe, ChannelError> {
+            Ok(ChannelProbe {
+                connected: !self.disconnect.load(Ordering::SeqCst),
+                account_id: None,
+                display_name: None,
+                error: None,
+            })
+        }
+    }
+
+    fn fast_config() -> SupervisorConfig {
+        SupervisorConfig {
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(40),
+            probe_interval: Duration::from_millis(10),
+            drain_timeout: Duration::from_secs(5),
+        }
+    }
+
+    fn context() -> ChannelContext {
+        ChannelContext {
+            agent_id: "default".to_string(),
+            account_id: "default".to_string(),
+        }
+    }
+
+    async fn wait_for_state(supervisor: &ChannelSupervisor, state: ChannelRunState) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while supervisor.health()[0].state != state {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restarts_after_failures() {
+        let channel = Arc::new(FlakyChannel::default());
+        channel.failures.store(2, Ordering::SeqCst);
+        let supervisor = ChannelSupervisor::new(fast_config());
+        supervisor.spawn(channel.clone(), context(), true);
+
+        wait_for_state(&supervisor, ChannelRunState::Running).await;
+        let health = &supervisor.health()[0];
+        assert_eq!(health.restarts, 2);
+        assert_eq!(
+            health.last_error.as_deref(),
+            Some("Network error: unreachable")
+        );
+        assert!(supervisor.is_healthy());
+
+        // A failed probe triggers another restart
+        channel.disconnect.store(true, Ordering::SeqCst);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while channel.starts.load(Ordering::SeqCst) < 4 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+        wait_for_state(&supervisor, ChannelRunState::Running).await;
+        assert_eq!(supervisor.health()[0].restarts, 3);
+    }
+
+    #[tokio::test]
+    async fn test_stop_and_start() {
+        let channel = Arc::new(FlakyChannel::default());
+        let supervisor = ChannelSupervisor::new(fast_config());
+        supervisor.spawn(channel.clone(), context(), true);
+        wait_for_state(&supervisor, ChannelRunState::Running).await;
+
+        let health = supervisor.stop("flaky").await.unwrap();
+        assert_eq!(health.state, ChannelRunState::Stopped);
+        assert!(supervisor.is_healthy());
+
+        supervisor.start("flaky").unwrap();
+        wait_for_state(&supervisor, ChannelRunState::Running).await;
+        assert_eq!(channel.starts.load(Ordering::SeqCst), 2);
+        assert_eq!(supervisor.health()[0].restarts, 0);
+
+        assert!(matches!(
+            supervisor.start("missing"),
+            Err(SupervisorError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_disable_drains_in_flight_messages() {
+        let channel = Arc::new(FlakyChannel::default());
+        let supervisor = Arc::new(ChannelSupervisor::new(fast_config()));
+        supervisor.spawn(channel.clone(), context(), false);
+        wait_for_state(&supervisor, ChannelRunState::Stopped).await;
+        assert_eq!(channel.starts.load(Ordering::SeqCst), 0);
+        assert!(matches!(
+            supervisor.start("flaky"),
+            Err(SupervisorError::Disabled(_))
+        ));
+
+        let mut changes = supervisor.subscribe();
+        supervisor.set_enabled("flaky", true).await.unwrap();
+        wait_for_state(&supervisor, ChannelRunState::Running).await;
+        assert!(changes.recv().await.unwrap().enabled);
+
+        // The stop waits for the message being answered, and refuses others
+        let message = supervisor.begin_message("flaky").unwrap();
+        let disabling = tokio::spawn({
+            let supervisor = supervisor.clone();
+            async move { supervisor.set_enabled("flaky", false).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(supervisor.health()[0].state, ChannelRunState::Running);
+        assert!(matches!(
+            supervisor.begin_message("flaky"),
+            Err(SupervisorError::NotAccepting(_))
+        ));
+
+        drop(message);
+        let health = disabling.await.unwrap().unwrap();
+        assert_eq!(health.state, ChannelRunState::Stopped);
+        assert!(!health.enabled);
+        assert!(supervisor.begin_message("other").is_ok());
+    }
+}
@@ -0,0 +1,27 @@
+//! Cleanup of idle inbound rate limit state.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::RwLock;
+
+use crate::server::GatewayState;
+
+/// How often idle rate limit state is dropped.
+pub const RATE_LIMIT_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Drop inbound rate limit state for peers and channels that have gone
+/// quiet, forever.
+///
+/// The limiter is looked up on each tick, so one swapped in by a config
+/// reload is swept too.
+pub async fn run_rate_limit_sweeper(state: Arc<RwLock<GatewayState>>) {
+    let mut interval = tokio::time::interval(RATE_LIMIT_SWEEP_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+        let limiter = state.read().await.inbound_limiter.clone();
+        limiter.retain_recent();
+    }
+}
@@ -0,0 +1,581 @@
+//! Multi-tenant namespaces.
+//!
+//! A namespace owns a set of agents and channels, the sessions they serve,
+//! and credentials for them. Each namespace has its own data directory
+//! (`<data_dir>/namespaces/<id>`) holding the event store for its agents'
+//! sessions. Users see sessions in namespaces they are members of; admins
+//! see everything. Once any namespace exists, sessions outside every
+//! namespace are visible to admins only.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use openclaw_agents::tools::ApprovalRequest;
+use openclaw_core::config::{EventStoreBackend, EventStoreFormat};
+use openclaw_core::events::{EventStore, SessionProjection};
+use openclaw_core::secrets::{ApiKey, EncryptionKey};
+use openclaw_core::storage::BlockingBlobStore;
+use openclaw_core::types::SessionKey;
+
+use crate::auth::{Claims, UserRole};
+use crate::events::UiEvent;
+
+/// Tenancy errors.
+#[derive(Debug, thiserror::Error)]
+pub enum TenancyError {
+    /// Invalid namespace configuration.
+    #[error("Invalid namespace '{namespace}': {reason}")]
+    Invalid {
+        /// Namespace ID.
+        namespace: String,
+        /// What is wrong with it.
+        reason: String,
+    },
+
+    /// A namespace's event store could not be opened.
+    #[error("Failed to open event store for namespace '{namespace}': {reason}")]
+    Storage {
+        /// Namespace ID.
+        namespace: String,
+        /// Underlying error.
+        reason: String,
+    },
+}
+
+/// Configuration for one namespace.
+#[derive(Debug, Clone, Default)]
+pub struct NamespaceConfig {
+    /// Agent IDs owned by the namespace.
+    pub agents: Vec<String>,
+    /// Channel IDs owned by the namespace.
+    pub channels: Vec<String>,
+    /// Role granted to each member, by username.
+    pub members: HashMap<String, UserRole>,
+    /// Credentials by name.
+    pub credentials: HashMap<String, ApiKey>,
+}
+
+impl NamespaceConfig {
+    /// Build from the file configuration, reading each credential from its
+    /// environment variable. Credentials whose variable is unset are
+    /// skipped with a warning.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a member's role is unknown.
+    pub fn from_config(
+        id: &str,
+        config: &openclaw_core::config::NamespaceConfig,
+    ) -> Result<Self, TenancyError> {
+        let members: HashMap<String, UserRole> = config
+            .members
+            .iter()
+            .map(|(username, role)| {
+                role.parse()
+                    .map(|role| (username.clone(), role))
+                    .map_err(|_| TenancyError::Invalid {
+                        namespace: id.to_string(),
+                        reason: format!("unknown role '{role}' for member '{username}'"),
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+
+        let mut credentials = HashMap::new();
+        for (name, var) in &config.credentials {
+            if let Ok(value) = std::env::var(var) {
+                credentials.insert(name.clone(), ApiKey::new(value));
+            } else {
+                tracing::warn!(
+                    "Namespace '{}' credential '{}': {} is not set",
+                    id,
+                    name,
+                    var
+                );
+            }
+        }
+
+        Ok(Self {
+            agents: config.agents.clone(),
+            channels: config.channels.clone(),
+            members,
+            credentials,
+        })
+    }
+}
+
+/// An open namespace.
+pub struct Namespace {
+    /// Namespace ID.
+    pub id: String,
+    /// Agent IDs owned by the namespace.
+    pub agents: HashSet<String>,
+    /// Channel IDs owned by the namespace.
+    pub channels: HashSet<String>,
+    /// Role granted to each member, by username.
+    pub members: HashMap<String, UserRole>,
+    /// Data directory.
+    pub data_dir: PathBuf,
+    /// Event store for the namespace's agents' sessions.
+    pub event_store: Arc<EventStore>,
+    credentials: HashMap<String, ApiKey>,
+}
+
+impl Namespace {
+    /// A credential by name.
+    #[must_use]
+    pub fn credential(&self, name: &str) -> Option<&ApiKey> {
+        self.credentials.get(name)
+    }
+}
+
+impl std::fmt::Debug for Namespace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Namespace")
+            .field("id", &self.id)
+            .field("agents", &self.agents)
+            .field("channels", &self.channels)
+            .field("members", &self.members)
+            .field("data_dir", &self.data_dir)
+            .field("credentials", &self.credentials.keys().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The gateway's namespaces.
+#[derive(Debug, Default)]
+pub struct Tenants {
+    namespaces: HashMap<String, Namespace>,
+    by_agent: HashMap<String, String>,
+    by_channel: HashMap<String, String>,
+}
+
+impl Tenants {
+    /// Open each namespace's data directory and event store.
+    ///
+    /// Sessions are archived to `archive_blobs` under
+    /// `namespaces/{id}` when given, otherwise to the namespace's data
+    /// directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a namespace ID is not a plain name, an agent or
+    /// channel belongs to two namespaces, or an event store cannot be opened.
+    pub fn open(
+        data_dir: &Path,
+        configs: &HashMap<String, NamespaceConfig>,
+        key: Option<&EncryptionKey>,
+        format: EventStoreFormat,
+        backend: EventStoreBackend,
+        archive_blobs: Option<&BlockingBlobStore>,
+    ) -> Result<Self, TenancyError> {
+        let mut tenants = Self::default();
+
+        for (id, config) in configs {
+            let invalid = |reason: String| TenancyError::Invalid {
+                namespace: id.clone(),
+                reason,
+            };
+            if id.is_empty()
+                || !id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                return Err(invalid(
+                    "IDs may only contain letters, digits, '-', and '_'".to_string(),
+                ));
+            }
+            for agent in &config.agents {
+                if let Some(owner) = tenants.by_agent.insert(agent.clone(), id.clone()) {
+                    return Err(invalid(format!(
+                        "agent '{agent}' already belongs to '{owner}'"
+                    )));
+                }
+            }
+            for channel in &config.channels {
+                if let Some(owner) = tenants.by_channel.insert(channel.clone(), id.clone()) {
+                    return Err(invalid(format!(
+                        "channel '{channel}' already belongs to '{owner}'"
+                    )));
+                }
+            }
+
+            let dir = data_dir.join("namespaces").join(id);
+            let storage = |e: String| TenancyError::Storage {
+                namespace: id.clone(),
+                reason: e,
+            };
+            std::fs::create_dir_all(&dir).map_err(|e| storage(e.to_string()))?;
+            let mut store = EventStore::open_with(&dir.join("events"), backend, key.cloned())
+                .map_err(|e| storage(e.to_string()))?
+                .with_archive_dir(dir.join("archive"))
+                .with_format(format);
+            if let Some(blobs) = archive_blobs {
+                store = store.with_archive_blobs(blobs.clone(), format!("namespaces/{id}"));
+            }
+
+            tenants.namespaces.insert(
+                id.clone(),
+                Namespace {
+                    id: id.clone(),
+                    agents: config.agents.iter().cloned().collect(),
+                    channels: config.channels.iter().cloned().collect(),
+                    members: config.members.clone(),
+                    data_dir: dir,
+                    event_store: Arc::new(store),
+                    credentials: config.credentials.clone(),
+                },
+            );
+        }
+
+        Ok(tenants)
+    }
+
+    /// Whether any namespaces are configured.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        !self.namespaces.is_empty()
+    }
+
+    /// A namespace by ID.
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<&Namespace> {
+        self.namespaces.get(id)
+    }
+
+    /// All namespaces, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &Namespace> {
+        self.namespaces.values()
+    }
+
+    /// The namespace owning an agent.
+    #[must_use]
+    pub fn namespace_of_agent(&self, agent_id: &str) -> Option<&Namespace> {
+        self.by_agent
+            .get(agent_id)
+            .and_then(|id| self.namespaces.get(id))
+    }
+
+    /// The namespace owning a conversation: the agent's, or failing that
+    /// the channel's.
+    #[must_use]
+    pub fn namespace_of(&self, agent_id: &str, channel: &str) -> Option<&str> {
+        self.by_agent
+            .get(agent_id)
+            .or_else(|| self.by_channel.get(channel))
+            .map(String::as_str)
+    }
+
+    /// The namespace owning a channel.
+    #[must_use]
+    pub fn namespace_of_channel(&self, channel: &str) -> Option<&str> {
+        self.by_channel.get(channel).map(String::as_str)
+    }
+
+    /// The namespace a session belongs to.
+    #[must_use]
+    pub fn namespace_of_session(&self, session: &SessionProjection) -> Option<&str> {
+        self.namespace_of(&session.agent_id, session.channel.as_ref())
+    }
+
+    /// The namespace owning a session, from the agent and channel in its
+    /// key.
+    #[must_use]
+    pub fn namespace_of_key(&self, session_key: &str) -> Option<&str> {
+        let key = SessionKey::new(session_key);
+        self.namespace_of(
+            key.agent_id().unwrap_or_default(),
+            key.channel().unwrap_or_default(),
+        )
+    }
+
+    /// The namespace a pending tool approval belongs to, from the calling
+    /// agent and the channel in its session key.
+    #[must_use]
+    pub fn namespace_of_approval(&self, request: &ApprovalRequest) -> Option<&str> {
+        let key = SessionKey::new(request.session_key.clone().unwrap_or_default());
+        self.namespace_of(&request.agent_id, key.channel().unwrap_or_default())
+    }
+
+    /// Whether a caller with `access` may receive a UI event. Session,
+    /// approval, and channel events follow their namespace; gateway
+    /// lifecycle events and heartbeats go to everyone; the rest go only to
+    /// callers who see everything.
+    #[must_use]
+    pub fn event_visible(&self, access: &NamespaceAccess, event: &UiEvent) -> bool {
+        if *access == NamespaceAccess::All {
+            return true;
+        }
+        let namespace = match event {
+            UiEvent::PendingApproval { request } => self.namespace_of_approval(request),
+            UiEvent::ChannelStatusChanged { channel_id, .. }
+            | UiEvent::ChannelStateChanged { channel_id, .. } => {
+                self.namespace_of_channel(channel_id)
+            }
+            UiEvent::GatewayStarted { .. }
+            | UiEvent::GatewayStopping { .. }
+            | UiEvent::Heartbeat { .. } => return true,
+            event => event
+                .session_key()
+                .and_then(|key| self.namespace_of_key(key)),
+        };
+        access.can_view(namespace)
+    }
+
+    /// What a caller may access. `None` is an unauthenticated caller.
+    #[must_use]
+    pub fn access(&self, claims: Option<&Claims>) -> NamespaceAccess {
+        if !self.is_enabled() {
+            return NamespaceAccess::All;
+        }
+        match claims {
+            Some(claims) if claims.role.is_admin() => NamespaceAccess::All,
+            Some(claims) => NamespaceAccess::Members(
+                self.namespaces
+                    .values()
+                    .filter_map(|ns| {
+                        ns.members
+                            .get(&claims.username)
+                            .map(|role| (ns.id.clone(), *role))
+                    })
+                    .collect(),
+            ),
+            None => NamespaceAccess::Members(HashMap::new()),
+        }
+    }
+}
+
+/// Namespaces a caller may access.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NamespaceAccess {
+    /// Everything, including sessions outside any namespace.
+    All,
+    /// Only these namespaces, with the role granted in each.
+    Members(HashMap<String, UserRole>),
+}
+
+impl NamespaceAccess {
+    /// Whether the caller may see sessions in `namespace` (`None` for
+    /// sessions outside every namespace).
+    #[must_use]
+    pub fn can_view(&self, namespace: Option<&str>) -> bool {
+        match self {
+            Self::All => true,
+            Self::Members(granted) => namespace.is_some_and(|ns| granted.contains_key(ns)),
+        }
+    }
+
+    /// Whether the caller may change sessions in `namespace`.
+    #[must_use]
+    pub fn can_manage(&self, namespace: Option<&str>) -> bool {
+        match self {
+            Self::All => true,
+            Self::Members(granted) => namespace
+                .and_then(|ns| granted.get(ns))
+                .is_some_and(UserRole::can_manage_sessions),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openclaw_core::types::{ChannelId, SessionKey};
+
+    fn claims(username: &str, role: UserRole) -> Claims {
+        serde_json::from_value(serde_json::json!({
+            "sub": format!("user_{username}"),
+            "username": username,
+            "role": role,
+            "iat": 0,
+            "exp": 0,
+        }))
+        .unwrap()
+    }
+
+    fn configs() -> HashMap<String, NamespaceConfig> {
+        HashMap::from([
+            (
+                "acme".to_string(),
+                NamespaceConfig {
+                    agents: vec!["acme-bot".to_string()],
+                    channels: vec!["slack".to_string()],
+                    members: HashMap::from([
+                        ("alice".to_string(), UserRole::Operator),
+                        ("bob".to_string(), UserRole::Viewer),
+                    ]),
+                    credentials: HashMap::from([(
+                        "anthropic".to_string(),
+                        ApiKey::new("sk-acme".to_string()),
+                    )]),
+                },
+            ),
+            (
+                "globex".to_string(),
+                NamespaceConfig {
+                    agents: vec!["globex-bot".to_string()],
+                    ..NamespaceConfig::default()
+                },
+            ),
+        ])
+    }
+
+    fn session(agent_id: &str, channel: &str) -> SessionProjection {
+        SessionProjection::new(
+            SessionKey::new("s"),
+            agent_id.to_string(),
+            ChannelId::new(channel),
+            "peer".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_open_namespaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let tenants = Tenants::open(
+            dir.path(),
+            &configs(),
+            None,
+            EventStoreFormat::default(),
+            EventStoreBackend::default(),
+            None,
+        )
+        .unwrap();
+
+        let acme = tenants.namespace_of_agent("acme-bot").unwrap();
+        assert_eq!(acme.id, "acme");
+        assert_eq!(acme.data_dir, dir.path().join("namespaces").join("acme"));
+        assert!(acme.data_dir.join("events").exists());
+        assert_eq!(acme.credential("anthropic").unwrap().expose(), "sk-acme");
+        assert!(tenants.namespace_of_agent("default").is_none());
+
+        assert_eq!(
+            tenants.namespace_of_session(&session("acme-bot", "api")),
+            Some("acme")
+        );
+        assert_eq!(
+            tenants.namespace_of_session(&session("default", "slack")),
+            Some("acme")
+        );
+        assert_eq!(
+            tenants.namespace_of_session(&session("default", "api")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_open_rejects_shared_agent() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut configs = configs();
+        configs
+            .get_mut("globex")
+            .unwrap()
+            .agents
+            .push("acme-bot".to_string());
+        assert!(matches!(
+            Tenants::open(
+                dir.path(),
+                &configs,
+                None,
+                EventStoreFormat::default(),
+                EventStoreBackend::default(),
+                None
+            ),
+            Err(TenancyError::Invalid { .. })
+        ));
+
+        let bad_id = HashMap::from([("../up".to_string(), NamespaceConfig::default())]);
+        assert!(
+            Tenants::open(
+                dir.path(),
+                &bad_id,
+                None,
+                EventStoreFormat::default(),
+                EventStoreBackend::default(),
+                None
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_access() {
+        let dir = tempfile::tempdir().unwrap();
+        let tenants = Tenants::open(
+            dir.path(),
+            &configs(),
+            None,
+            EventStoreFormat::default(),
+            EventStoreBackend::default(),
+            None,
+        )
+        .unwrap();
+
+        let admin = tenants.access(Some(&claims("root", UserRole::Admin)));
+        assert!(admin.can_view(None));
+        assert!(admin.can_manage(Some("globex")));
+
+        let alice = tenants.access(Some(&claims("alice", UserRole::Viewer)));
+        assert!(alice.can_view(Some("acme")));
+        assert!(alice.can_manage(Some("acme")));
+        assert!(!alice.can_view(Some("globex")));
+        assert!(!alice.can_view(None));
+
+        let bob = tenants.access(Some(&claims("bob", UserRole::Operator)));
+        assert!(bob.can_view(Some("acme")));
+        assert!(!bob.can_manage(Some("acme")));
+
+        assert!(!tenants.access(None).can_view(Some("acme")));
+
+        // Without namespaces everyone sees everything
+        let open = Tenants::default();
+        assert_eq!(open.access(None), NamespaceAccess::All);
+    }
+
+    #[test]
+    fn test_event_visible() {
+        let dir = tempfile::tempdir().unwrap();
+        let tenants = Tenants::open(
+            dir.path(),
+            &configs(),
+            None,
+            EventStoreFormat::default(),
+            EventStoreBackend::default(),
+            None,
+        )
+        .unwrap();
+        let message = |agent_id: &str, channel: &str| UiEvent::MessageSent {
+            session_key: SessionKey::build(
+                &openclaw_core::types::AgentId::new(agent_id),
+                &ChannelId::new(channel),
+                "gateway",
+                openclaw_core::types::PeerType::Dm,
+                &openclaw_core::types::PeerId::new("peer"),
+                None,
+            )
+            .0,
+            content: "hi".to_string(),
+        };
+        let channel = |channel_id: &str| UiEvent::ChannelStatusChanged {
+            channel_id: channel_id.to_string(),
+            connected: true,
+            error: None,
+        };
+        let heartbeat = UiEvent::Heartbeat {
+            timestamp: chrono::Utc::now(),
+        };
+
+        let bob = tenants.access(Some(&claims("bob", UserRole::Viewer)));
+        assert!(tenants.event_visible(&bob, &message("acme-bot", "api")));
+        assert!(tenants.event_visible(&bob, &message("default", "slack")));
+        assert!(!tenants.event_visible(&bob, &message("globex-bot", "api")));
+        assert!(!tenants.event_visible(&bob, &message("default", "api")));
+        assert!(tenants.event_visible(&bob, &channel("slack")));
+        assert!(!tenants.event_visible(&bob, &channel("discord")));
+        assert!(tenants.event_visible(&bob, &heartbeat));
+
+        let admin = tenants.access(Some(&claims("root", UserRole::Admin)));
+        assert!(tenants.event_visible(&admin, &message("globex-bot", "api")));
+        assert!(tenants.event_visible(&admin, &channel("discord")));
+    }
+}
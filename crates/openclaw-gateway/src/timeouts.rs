@@ -0,0 +1,184 @@
+//! Per-route and per-method request timeouts.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use axum::{BoxError, Json, http::StatusCode};
+
+use crate::rpc::{self, RpcResponse};
+
+/// Resolved request timeouts.
+///
+/// Method keys are exact names (`session.message`) or namespaces
+/// (`system.*`); an exact match wins over a namespace, and the longest
+/// namespace wins over shorter ones.
+#[derive(Debug, Clone)]
+pub struct RequestTimeouts {
+    default: Duration,
+    routes: HashMap<String, Duration>,
+    methods: HashMap<String, Duration>,
+}
+
+impl RequestTimeouts {
+    /// Create timeouts with a single default and no overrides.
+    #[must_use]
+    pub fn new(default: Duration) -> Self {
+        Self {
+            default,
+            routes: HashMap::new(),
+            methods: HashMap::new(),
+        }
+    }
+
+    /// Build from the gateway section of the config file.
+    #[must_use]
+    pub fn from_config(config: &openclaw_core::config::GatewayConfig) -> Self {
+        let secs = |map: &HashMap<String, u64>| {
+            map.iter()
+                .map(|(k, v)| (k.clone(), Duration::from_secs(*v)))
+                .collect()
+        };
+
+        Self {
+            default: Duration::from_secs(config.timeout_secs),
+            routes: secs(&config.route_timeouts),
+            methods: secs(&config.method_timeouts),
+        }
+    }
+
+    /// Override the timeout for a route path.
+    #[must_use]
+    pub fn with_route(mut self, path: impl Into<String>, timeout: Duration) -> Self {
+        self.routes.insert(path.into(), timeout);
+        self
+    }
+
+    /// Override the timeout for a method or `namespace.*`.
+    #[must_use]
+    pub fn with_method(mut self, method: impl Into<String>, timeout: Duration) -> Self {
+        self.methods.insert(method.into(), timeout);
+        self
+    }
+
+    /// Timeout for a route path.
+    ///
+    /// Routes without an override get the longest method timeout, so the
+    /// route layer never cuts a slower method short.
+    #[must_use]
+    pub fn for_route(&self, path: &str) -> Duration {
+        self.routes.get(path).copied().unwrap_or_else(|| {
+            self.methods
+                .values()
+                .copied()
+                .fold(self.default, Duration::max)
+        })
+    }
+
+    /// Timeout for an RPC method.
+    #[must_use]
+    pub fn for_method(&self, method: &str) -> Duration {
+        if let Some(timeout) = self.methods.get(method) {
+            return *timeout;
+        }
+
+        self.methods
+            .iter()
+            .filter_map(|(key, timeout)| {
+                let namespace = key.strip_suffix('*')?;
+                method
+                    .starts_with(namespace)
+                    .then_some((namespace.len(), *timeout))
+            })
+            .max_by_key(|(len, _)| *len)
+            .map_or(self.default, |(_, timeout)| timeout)
+    }
+}
+
+impl Default for RequestTimeouts {
+    fn default() -> Self {
+        Self::from_config(&openclaw_core::config::GatewayConfig::default())
+    }
+}
+
+/// Build the error response for a request that exceeded its timeout.
+#[must_use]
+pub fn timeout_response(
+    id: Option<String>,
+    method: Option<&str>,
+    timeout: Duration,
+) -> RpcResponse {
+    let message = method.map_or_else(
+        || format!("Request timed out after {}s", timeout.as_secs()),
+        |m| format!("Method '{m}' timed out after {}s", timeout.as_secs()),
+    );
+
+    let mut response = RpcResponse::error(id, rpc::TIMEOUT, message);
+    if let Some(error) = response.error.as_mut() {
+        error.data = Some(serde_json::json!({
+            "method": method,
+            "timeout_ms": u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX),
+        }));
+    }
+    response
+}
+
+/// Map errors from a route-level `tower` timeout layer to a JSON-RPC error.
+///
+/// The route layer is a backstop behind per-method timeouts, so it does not
+/// know the request ID or method.
+pub fn route_timeout_error(timeout: Duration, err: &BoxError) -> (StatusCode, Json<RpcResponse>) {
+    if err.is::<tower::timeout::error::Elapsed>() {
+        (StatusCode::OK, Json(timeout_response(None, None, timeout)))
+    } else {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(RpcResponse::error(
+                None,
+                rpc::INTERNAL_ERROR,
+                format!("Unhandled error: {err}"),
+            )),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_method_resolution() {
+        let timeouts = RequestTimeouts::new(Duration::from_secs(300))
+            .with_method("system.*", Duration::from_secs(10))
+            .with_method("session.*", Duration::from_secs(60))
+            .with_method("session.message", Duration::from_secs(600))
+            .with_route("/rpc", Duration::from_secs(900));
+
+        assert_eq!(
+            timeouts.for_method("system.health"),
+            Duration::from_secs(10)
+        );
+        assert_eq!(
+            timeouts.for_method("session.message"),
+            Duration::from_secs(600)
+        );
+        assert_eq!(
+            timeouts.for_method("session.history"),
+            Duration::from_secs(60)
+        );
+        assert_eq!(timeouts.for_method("tools.list"), Duration::from_secs(300));
+        assert_eq!(timeouts.for_route("/rpc"), Duration::from_secs(900));
+        assert_eq!(timeouts.for_route("/health"), Duration::from_secs(600));
+    }
+
+    #[test]
+    fn test_timeout_response() {
+        let response = timeout_response(
+            Some("1".to_string()),
+            Some("session.message"),
+            Duration::from_secs(5),
+        );
+        let error = response.error.unwrap();
+        assert_eq!(error.code, rpc::TIMEOUT);
+        assert_eq!(error.data.unwrap()["timeout_ms"], 5000);
+    }
+}
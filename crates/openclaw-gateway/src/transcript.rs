@@ -0,0 +1,156 @@
+//! Session transcript downloads.
+//!
+//! `GET /sessions/{key}/transcript?format=md|html` returns a session
+//! rendered as Markdown (the default) or HTML, as an attachment. When auth
+//! is enabled the token is required, in an `Authorization: Bearer` header or
+//! the `token` query parameter, and sessions in namespaces the caller can't
+//! see are not found.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+
+use openclaw_core::events::{Transcript, TranscriptFormat};
+use openclaw_core::types::SessionKey;
+
+use crate::auth::JwtManager;
+use crate::server::{GatewayState, authorize_session};
+
+/// Route path of the transcript download.
+pub const TRANSCRIPT_ROUTE: &str = "/sessions/{key}/transcript";
+
+/// Query parameters for `/sessions/{key}/transcript`.
+#[derive(Debug, Default, Deserialize)]
+pub struct TranscriptParams {
+    /// `md` (default) or `html`.
+    format: Option<String>,
+    /// Auth token, for links that cannot set headers.
+    token: Option<String>,
+}
+
+/// Handle a GET to `/sessions/{key}/transcript`.
+pub async fn transcript_handler(
+    State(state): State<Arc<RwLock<GatewayState>>>,
+    Path(key): Path<String>,
+    Query(params): Query<TranscriptParams>,
+    headers: HeaderMap,
+) -> Response {
+    let format = match params.format.as_deref() {
+        None => TranscriptFormat::Markdown,
+        Some(name) => match TranscriptFormat::parse(name) {
+            Some(format) => format,
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    format!("Unknown format '{name}' (use md or html)"),
+                )
+                    .into_response();
+            }
+        },
+    };
+
+    let header_token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(JwtManager::extract_from_header);
+    let token = header_token.or(params.token.as_deref());
+
+    let state = state.read().await;
+    let auth = &state.auth;
+    if auth.config.enabled && token.is_none_or(|t| auth.validate_token(t).is_err()) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            "Authentication required",
+        )
+            .into_response();
+    }
+
+    let session_key = SessionKey::new(&key);
+    let store = state.store_for_session(&session_key);
+    let Ok(projection) = store.get_projection(&session_key) else {
+        return (StatusCode::NOT_FOUND, format!("Session not found: {key}")).into_response();
+    };
+    if let Err((_, message)) = authorize_session(&state, token, &projection, false) {
+        return (StatusCode::NOT_FOUND, message).into_response();
+    }
+    let events = match store.get_events(&session_key) {
+        Ok(events) => events,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to get events: {e}"),
+            )
+                .into_response();
+        }
+    };
+
+    let mut transcript = Transcript::new(&projection, &events);
+    let variant = state
+        .router
+        .experiments()
+        .iter()
+        .find(|e| projection.experiment.as_deref() == Some(e.id.as_str()))
+        .and_then(|e| {
+            e.variants
+                .iter()
+                .find(|v| projection.variant.as_deref() == Some(v.name.as_str()))
+        });
+    if let Some(v) =
+        variant.filter(|v| v.input_cost_per_mtok.is_some() || v.output_cost_per_mtok.is_some())
+    {
+        transcript = transcript.with_rates(
+            v.input_cost_per_mtok.unwrap_or(0.0),
+            v.output_cost_per_mtok.unwrap_or(0.0),
+        );
+    }
+    drop(state);
+
+    let disposition = format!(
+        "attachment; filename=\"{}\"",
+        file_name(&key, format.extension())
+    );
+    (
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        transcript.render(format),
+    )
+        .into_response()
+}
+
+/// Download file name for a session; keys may hold `:` and `/`.
+fn file_name(key: &str, extension: &str) -> String {
+    let stem: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("session-{stem}.{extension}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(
+            file_name("agent:helper:telegram:123", "md"),
+            "session-agent-helper-telegram-123.md"
+        );
+        assert_eq!(file_name("a/\"b\"", "html"), "session-a--b-.html");
+    }
+}
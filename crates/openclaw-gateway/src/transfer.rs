@@ -0,0 +1,1122 @@
+//! Streamed file transfers between the gateway and the web UI.
+//!
+//! Uploads arrive in chunks so a large file never sits in memory:
+//!
+//! - `POST /uploads` with `{"name", "size", "mime_type"?, "session_key"?}`
+//!   starts an upload and returns its ID and the suggested chunk size.
+//! - `PUT /uploads/{id}?offset=N` appends a chunk. The offset must match
+//!   the bytes already received, so an interrupted upload resumes from the
+//!   `received` count that `GET /uploads/{id}` reports.
+//! - The chunk that completes the file moves it to the blob store and
+//!   returns an `upload://{id}` attachment to pass to `session.message`.
+//!
+//! `GET /workspace/file?agent_id=…|session_key=…&path=…` streams a file
+//! from an agent or session workspace, honouring a single `Range` so
+//! downloads can resume.
+//!
+//! Both directions broadcast `transfer_progress` UI events. When auth is
+//! enabled the token goes in an `Authorization: Bearer` header or the
+//! `token` query parameter; uploads need the admin or operator role.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use async_trait::async_trait;
+use axum::{
+    Json,
+    body::{Body, Bytes},
+    extract::{Path as UrlPath, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::RwLock;
+
+use openclaw_agents::runtime::{
+    AttachmentError, AttachmentResolver, HttpAttachmentResolver, ResolvedAttachment,
+};
+use openclaw_core::storage::{BlobNamespace, BlobStore, StorageError};
+use openclaw_core::types::{Attachment, AttachmentKind, SessionKey};
+use openclaw_core::validation::limits;
+
+use crate::auth::JwtManager;
+use crate::events::{EventBroadcaster, TransferDirection, UiEvent};
+use crate::server::{GatewayState, approver, authorize_session};
+use crate::workspace::{Workspace, WorkspaceError};
+
+/// Route that starts an upload.
+pub const UPLOADS_ROUTE: &str = "/uploads";
+
+/// Route for an upload's status and chunks.
+pub const UPLOAD_ROUTE: &str = "/uploads/{id}";
+
+/// Route of the workspace file download.
+pub const WORKSPACE_FILE_ROUTE: &str = "/workspace/file";
+
+/// URL scheme of uploaded attachments.
+pub const UPLOAD_SCHEME: &str = "upload://";
+
+/// Chunk size suggested to clients.
+pub const CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Largest chunk accepted in one request.
+pub const MAX_CHUNK_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Bytes between download progress events.
+const PROGRESS_INTERVAL: u64 = 1024 * 1024;
+
+/// Read buffer for downloads.
+const READ_BUFFER: usize = 64 * 1024;
+
+/// File transfer errors.
+#[derive(Error, Debug)]
+pub enum TransferError {
+    /// Request is malformed.
+    #[error("{0}")]
+    Invalid(String),
+
+    /// Upload, session, agent, or file does not exist.
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    /// Chunk does not continue from the bytes already received.
+    #[error("Upload expects offset {expected}")]
+    OffsetMismatch {
+        /// Bytes received so far.
+        expected: u64,
+    },
+
+    /// Another request is writing to the same upload.
+    #[error("Upload {0} is already receiving a chunk")]
+    Busy(String),
+
+    /// File or chunk exceeds a limit.
+    #[error("Too large ({size} bytes, max {max})")]
+    TooLarge {
+        /// Size in bytes.
+        size: u64,
+        /// Limit in bytes.
+        max: u64,
+    },
+
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Blob store error.
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
+}
+
+impl TransferError {
+    const fn status(&self) -> StatusCode {
+        match self {
+            Self::Invalid(_) => StatusCode::BAD_REQUEST,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::OffsetMismatch { .. } | Self::Busy(_) => StatusCode::CONFLICT,
+            Self::TooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::Io(_) | Self::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<WorkspaceError> for TransferError {
+    fn from(e: WorkspaceError) -> Self {
+        match e {
+            WorkspaceError::InvalidPath(_) => Self::Invalid(e.to_string()),
+            WorkspaceError::NotFound(path) => Self::NotFound(path),
+            WorkspaceError::TooLarge { size, max } => Self::TooLarge { size, max },
+            WorkspaceError::Io(e) => Self::Io(e),
+        }
+    }
+}
+
+impl IntoResponse for TransferError {
+    fn into_response(self) -> Response {
+        (self.status(), self.to_string()).into_response()
+    }
+}
+
+/// An upload's metadata, stored next to its data.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadMeta {
+    /// Upload ID.
+    pub id: String,
+    /// File name.
+    pub name: String,
+    /// Declared size in bytes.
+    pub size: u64,
+    /// MIME type, as declared or guessed from the name.
+    pub mime_type: String,
+    /// Session the file is meant for, if any.
+    pub session_key: Option<String>,
+    /// Who started the upload.
+    pub uploaded_by: String,
+    /// When the upload started.
+    pub created_at: DateTime<Utc>,
+    /// When the last byte arrived.
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl UploadMeta {
+    /// The attachment to send with a message.
+    #[must_use]
+    pub fn attachment(&self) -> Attachment {
+        Attachment {
+            kind: kind_for(&self.mime_type),
+            url: format!("{UPLOAD_SCHEME}{}", self.id),
+            mime_type: Some(self.mime_type.clone()),
+            filename: Some(self.name.clone()),
+            size: Some(self.size),
+            thumbnail_url: None,
+        }
+    }
+}
+
+/// Progress of an upload.
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadStatus {
+    /// Upload ID.
+    pub upload_id: String,
+    /// File name.
+    pub name: String,
+    /// Declared size in bytes.
+    pub size: u64,
+    /// Bytes received so far.
+    pub received: u64,
+    /// Suggested chunk size.
+    pub chunk_size: u64,
+    /// Whether every byte has arrived.
+    pub completed: bool,
+    /// Attachment for `session.message`, once completed.
+    pub attachment: Option<Attachment>,
+}
+
+/// Uploaded files.
+///
+/// Each upload keeps `{id}.json` metadata beside `{id}.part` in a local
+/// directory while chunks arrive. Once complete, the file moves to the
+/// blob store's `attachments` namespace as `{id}`.
+#[derive(Debug, Clone)]
+pub struct UploadStore {
+    dir: PathBuf,
+    blobs: BlobStore,
+    writing: Arc<Mutex<HashSet<String>>>,
+}
+
+impl UploadStore {
+    /// Stage uploads in `dir` and keep finished ones in `blobs`.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>, blobs: BlobStore) -> Self {
+        Self {
+            dir: dir.into(),
+            blobs,
+            writing: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Upload directory.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Start an upload.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Invalid` for an empty name or file, `TooLarge` above the
+    /// attachment size limit, or `Io` if the metadata cannot be written.
+    pub fn create(
+        &self,
+        name: &str,
+        size: u64,
+        mime_type: Option<String>,
+        session_key: Option<String>,
+        uploaded_by: &str,
+    ) -> Result<UploadMeta, TransferError> {
+        // Keep only the final component of whatever path the browser sent
+        let name = name
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        if name.is_empty() || name == "." || name == ".." {
+            return Err(TransferError::Invalid("Missing file name".to_string()));
+        }
+        if size == 0 {
+            return Err(TransferError::Invalid(format!("{name} is empty")));
+        }
+        let max = limits::MAX_ATTACHMENT_SIZE as u64;
+        if size > max {
+            return Err(TransferError::TooLarge { size, max });
+        }
+
+        let mime_type = mime_type.filter(|m| !m.is_empty()).unwrap_or_else(|| {
+            mime_guess::from_path(&name)
+                .first_or_octet_stream()
+                .essence_str()
+                .to_string()
+        });
+        let meta = UploadMeta {
+            id: new_id(),
+            name,
+            size,
+            mime_type,
+            session_key,
+            uploaded_by: uploaded_by.to_string(),
+            created_at: Utc::now(),
+            completed_at: None,
+        };
+        std::fs::create_dir_all(&self.dir)?;
+        self.save(&meta)?;
+        Ok(meta)
+    }
+
+    /// Look up an upload.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` for an unknown or malformed ID.
+    pub fn get(&self, id: &str) -> Result<UploadMeta, TransferError> {
+        let not_found = || TransferError::NotFound(format!("upload {id}"));
+        if !is_upload_id(id) {
+            return Err(not_found());
+        }
+        let data = std::fs::read(self.dir.join(format!("{id}.json"))).map_err(|_| not_found())?;
+        serde_json::from_slice(&data).map_err(|_| not_found())
+    }
+
+    /// Progress of an upload.
+    #[must_use]
+    pub fn status(&self, meta: &UploadMeta) -> UploadStatus {
+        let completed = meta.completed_at.is_some();
+        let received = if completed {
+            meta.size
+        } else {
+            std::fs::metadata(self.part_path(&meta.id)).map_or(0, |m| m.len())
+        };
+        UploadStatus {
+            upload_id: meta.id.clone(),
+            name: meta.name.clone(),
+            size: meta.size,
+            received,
+            chunk_size: CHUNK_SIZE,
+            completed,
+            attachment: completed.then(|| meta.attachment()),
+        }
+    }
+
+    /// Look up a completed upload.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if the upload is unknown or still in progress.
+    pub fn completed(&self, id: &str) -> Result<UploadMeta, TransferError> {
+        let meta = self.get(id)?;
+        if meta.completed_at.is_none() {
+            return Err(TransferError::NotFound(format!(
+                "upload {id} is incomplete"
+            )));
+        }
+        Ok(meta)
+    }
+
+    /// Read a completed upload's data from the blob store.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Storage` if the data cannot be read.
+    pub async fn read(&self, meta: &UploadMeta) -> Result<Bytes, TransferError> {
+        Ok(self.blobs.get(BlobNamespace::Attachments, &meta.id).await?)
+    }
+
+    /// Append a chunk starting at `offset`, completing the upload when its
+    /// last byte arrives.
+    ///
+    /// Bytes are written as they arrive, so a chunk cut short still counts
+    /// towards `received` and the client resumes from there.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OffsetMismatch` if `offset` is not the bytes received so far,
+    /// `Busy` if another chunk is being written, `Invalid` if the chunk runs
+    /// past the declared size, or `TooLarge` above the chunk size limit.
+    pub async fn append<S, E>(
+        &self,
+        id: &str,
+        offset: u64,
+        mut chunk: S,
+    ) -> Result<UploadStatus, TransferError>
+    where
+        S: Stream<Item = Result<Bytes, E>> + Unpin,
+        E: std::fmt::Display,
+    {
+        let mut meta = self.get(id)?;
+        let _claim = WriteClaim::take(&self.writing, id)?;
+
+        let mut received = self.status(&meta).received;
+        if meta.completed_at.is_some() || offset != received {
+            return Err(TransferError::OffsetMismatch { expected: received });
+        }
+
+        let part = self.part_path(id);
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&part)
+            .await?;
+        let mut written = 0u64;
+        while let Some(frame) = chunk.next().await {
+            let bytes = frame.map_err(|e| {
+                TransferError::Invalid(format!("Chunk interrupted after {written} bytes: {e}"))
+            })?;
+            let len = bytes.len() as u64;
+            if received + len > meta.size {
+                return Err(TransferError::Invalid(format!(
+                    "Chunk runs past the declared size of {} bytes",
+                    meta.size
+                )));
+            }
+            if written + len > MAX_CHUNK_SIZE {
+                return Err(TransferError::TooLarge {
+                    size: written + len,
+                    max: MAX_CHUNK_SIZE,
+                });
+            }
+            file.write_all(&bytes).await?;
+            written += len;
+            received += len;
+        }
+        file.flush().await?;
+
+        if received == meta.size {
+            drop(file);
+            let data = tokio::fs::read(&part).await?;
+            self.blobs.put(BlobNamespace::Attachments, id, data).await?;
+            tokio::fs::remove_file(&part).await?;
+            meta.completed_at = Some(Utc::now());
+            self.save(&meta)?;
+        }
+        Ok(self.status(&meta))
+    }
+
+    /// Delete every upload meant for one of `sessions`, finished or not,
+    /// returning the number deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` if the upload directory cannot be read or a file
+    /// cannot be removed, or `Storage` if finished data cannot be deleted.
+    pub async fn delete_for_sessions(
+        &self,
+        sessions: &HashSet<&str>,
+    ) -> Result<usize, TransferError> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut deleted = 0;
+        for entry in entries {
+            let path = entry?.path();
+            let Some(id) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(".json"))
+            else {
+                continue;
+            };
+            let Ok(meta) = self.get(id) else {
+                continue;
+            };
+            if !meta
+                .session_key
+                .as_deref()
+                .is_some_and(|key| sessions.contains(key))
+            {
+                continue;
+            }
+            self.blobs.delete(BlobNamespace::Attachments, id).await?;
+            for file in [self.part_path(id), path.clone()] {
+                match std::fs::remove_file(&file) {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                    _ => {}
+                }
+            }
+            deleted += 1;
+        }
+        Ok(deleted)
+    }
+
+    fn part_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}.part"))
+    }
+
+    fn save(&self, meta: &UploadMeta) -> Result<(), TransferError> {
+        let data = serde_json::to_vec_pretty(meta)
+            .map_err(|e| TransferError::Io(std::io::Error::other(e)))?;
+        std::fs::write(self.dir.join(format!("{}.json", meta.id)), data)?;
+        Ok(())
+    }
+}
+
+/// Marks an upload as being written until dropped.
+struct WriteClaim {
+    writing: Arc<Mutex<HashSet<String>>>,
+    id: String,
+}
+
+impl WriteClaim {
+    fn take(writing: &Arc<Mutex<HashSet<String>>>, id: &str) -> Result<Self, TransferError> {
+        let claimed = writing
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(id.to_string());
+        if !claimed {
+            return Err(TransferError::Busy(id.to_string()));
+        }
+        Ok(Self {
+            writing: writing.clone(),
+            id: id.to_string(),
+        })
+    }
+}
+
+impl Drop for WriteClaim {
+    fn drop(&mut self) {
+        self.writing
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.id);
+    }
+}
+
+/// Resolves `upload://` attachments from an [`UploadStore`] and everything
+/// else over HTTP.
+///
+/// Hosts embedding the gateway pass this to
+/// [`AgentRuntime::with_attachment_resolver`](openclaw_agents::AgentRuntime::with_attachment_resolver)
+/// so agents can view files uploaded from the web UI.
+#[derive(Debug, Clone)]
+pub struct UploadResolver {
+    uploads: UploadStore,
+    http: HttpAttachmentResolver,
+}
+
+impl UploadResolver {
+    /// Resolve uploads from `uploads`.
+    #[must_use]
+    pub fn new(uploads: UploadStore) -> Self {
+        Self {
+            uploads,
+            http: HttpAttachmentResolver::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl AttachmentResolver for UploadResolver {
+    async fn resolve(
+        &self,
+        attachment: &Attachment,
+        max_bytes: u64,
+    ) -> Result<ResolvedAttachment, AttachmentError> {
+        let Some(id) = attachment.url.strip_prefix(UPLOAD_SCHEME) else {
+            return self.http.resolve(attachment, max_bytes).await;
+        };
+
+        let meta = self
+            .uploads
+            .completed(id)
+            .map_err(|e| AttachmentError::Fetch(e.to_string()))?;
+        if meta.size > max_bytes {
+            return Err(AttachmentError::TooLarge {
+                name: meta.name,
+                max: max_bytes,
+            });
+        }
+        let data = self
+            .uploads
+            .read(&meta)
+            .await
+            .map_err(|e| AttachmentError::Fetch(format!("{}: {e}", meta.name)))?;
+        Ok(ResolvedAttachment {
+            data: data.to_vec(),
+            mime_type: Some(meta.mime_type),
+        })
+    }
+}
+
+// ============================================================================
+// HTTP handlers
+// ============================================================================
+
+/// Body of `POST /uploads`.
+#[derive(Debug, Deserialize)]
+pub struct StartUpload {
+    /// File name.
+    name: String,
+    /// Size in bytes.
+    size: u64,
+    /// MIME type (guessed from the name if absent).
+    mime_type: Option<String>,
+    /// Session the file is for.
+    session_key: Option<String>,
+}
+
+/// Query parameters of the transfer routes.
+#[derive(Debug, Default, Deserialize)]
+pub struct TransferParams {
+    /// Chunk offset, for `PUT /uploads/{id}`.
+    offset: Option<u64>,
+    /// Agent whose workspace to read.
+    agent_id: Option<String>,
+    /// Session whose workspace to read.
+    session_key: Option<String>,
+    /// Path within the workspace.
+    path: Option<String>,
+    /// Auth token, for links that cannot set headers.
+    token: Option<String>,
+}
+
+fn request_token<'a>(headers: &'a HeaderMap, params: &'a TransferParams) -> Option<&'a str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(JwtManager::extract_from_header)
+        .or(params.token.as_deref())
+}
+
+fn unauthorized(message: String) -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        [(header::WWW_AUTHENTICATE, "Bearer")],
+        message,
+    )
+        .into_response()
+}
+
+/// Response refusing a request from a caller who may not upload.
+fn refusal((code, message): (i32, String)) -> Response {
+    if code == crate::rpc::FORBIDDEN {
+        (StatusCode::FORBIDDEN, message).into_response()
+    } else {
+        unauthorized(message)
+    }
+}
+
+/// Handle a POST to `/uploads`.
+pub async fn start_upload_handler(
+    State(state): State<Arc<RwLock<GatewayState>>>,
+    Query(params): Query<TransferParams>,
+    headers: HeaderMap,
+    Json(request): Json<StartUpload>,
+) -> Response {
+    let token = request_token(&headers, &params);
+    let state = state.read().await;
+    let uploaded_by = match approver(&state, token) {
+        Ok(name) => name,
+        Err(e) => return refusal(e),
+    };
+
+    if let Some(key) = &request.session_key {
+        let session_key = SessionKey::new(key);
+        let Ok(projection) = state
+            .store_for_session(&session_key)
+            .get_projection(&session_key)
+        else {
+            return TransferError::NotFound(format!("session {key}")).into_response();
+        };
+        if let Err((code, message)) = authorize_session(&state, token, &projection, true) {
+            let status = if code == crate::rpc::FORBIDDEN {
+                StatusCode::FORBIDDEN
+            } else {
+                StatusCode::NOT_FOUND
+            };
+            return (status, message).into_response();
+        }
+    }
+
+    match state.uploads.create(
+        &request.name,
+        request.size,
+        request.mime_type,
+        request.session_key,
+        &uploaded_by,
+    ) {
+        Ok(meta) => {
+            tracing::info!(upload = %meta.id, name = %meta.name, size = meta.size, "Upload started");
+            (StatusCode::CREATED, Json(state.uploads.status(&meta))).into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Handle a GET to `/uploads/{id}`.
+pub async fn upload_status_handler(
+    State(state): State<Arc<RwLock<GatewayState>>>,
+    UrlPath(id): UrlPath<String>,
+    Query(params): Query<TransferParams>,
+    headers: HeaderMap,
+) -> Response {
+    let token = request_token(&headers, &params);
+    let state = state.read().await;
+    if let Err(e) = approver(&state, token) {
+        return refusal(e);
+    }
+    match state.uploads.get(&id) {
+        Ok(meta) => Json(state.uploads.status(&meta)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Handle a PUT to `/uploads/{id}?offset=N`.
+pub async fn upload_chunk_handler(
+    State(state): State<Arc<RwLock<GatewayState>>>,
+    UrlPath(id): UrlPath<String>,
+    Query(params): Query<TransferParams>,
+    headers: HeaderMap,
+    body: Body,
+) -> Response {
+    let token = request_token(&headers, &params);
+    let (uploads, events) = {
+        let state = state.read().await;
+        if let Err(e) = approver(&state, token) {
+            return refusal(e);
+        }
+        (state.uploads.clone(), state.events.clone())
+    };
+    let Some(offset) = params.offset else {
+        return TransferError::Invalid("Missing offset".to_string()).into_response();
+    };
+
+    let status = match uploads.append(&id, offset, body.into_data_stream()).await {
+        Ok(status) => status,
+        Err(e) => return e.into_response(),
+    };
+    let session_key = uploads.get(&id).ok().and_then(|meta| meta.session_key);
+    let _ = events.broadcast(UiEvent::TransferProgress {
+        transfer_id: status.upload_id.clone(),
+        direction: TransferDirection::Upload,
+        name: status.name.clone(),
+        transferred: status.received,
+        total: status.size,
+        session_key,
+    });
+    if status.completed {
+        tracing::info!(upload = %status.upload_id, "Upload completed");
+    }
+    Json(status).into_response()
+}
+
+/// Handle a GET to `/workspace/file`.
+pub async fn workspace_file_handler(
+    State(state): State<Arc<RwLock<GatewayState>>>,
+    Query(params): Query<TransferParams>,
+    headers: HeaderMap,
+) -> Response {
+    let token = request_token(&headers, &params);
+    let (workspace, events) = {
+        let state = state.read().await;
+        let auth = &state.auth;
+        if auth.config.enabled && token.is_none_or(|t| auth.validate_token(t).is_err()) {
+            return unauthorized("Authentication required".to_string());
+        }
+        let workspace = match workspace_for(&state, token, &params) {
+            Ok(workspace) => workspace,
+            Err(e) => return e.into_response(),
+        };
+        (workspace, state.events.clone())
+    };
+    let Some(relative) = params.path.as_deref() else {
+        return TransferError::Invalid("Missing path".to_string()).into_response();
+    };
+
+    match stream_file(&workspace, relative, headers.get(header::RANGE), events).await {
+        Ok(response) => response,
+        Err(e) => e.into_response(),
+    }
+}
+
+/// The workspace named by `session_key` or `agent_id`.
+fn workspace_for(
+    state: &GatewayState,
+    token: Option<&str>,
+    params: &TransferParams,
+) -> Result<Workspace, TransferError> {
+    let base = &state.config.workspace_dir;
+    if let Some(key) = &params.session_key {
+        let session_key = SessionKey::new(key);
+        let projection = state
+            .store_for_session(&session_key)
+            .get_projection(&session_key)
+            .map_err(|_| TransferError::NotFound(format!("session {key}")))?;
+        authorize_session(state, token, &projection, false)
+            .map_err(|_| TransferError::NotFound(format!("session {key}")))?;
+        return Ok(Workspace::for_session(base, &projection.agent_id, key));
+    }
+
+    let agent_id = params
+        .agent_id
+        .as_deref()
+        .ok_or_else(|| TransferError::Invalid("Missing agent_id or session_key".to_string()))?;
+    if !state.agents.contains_key(agent_id) {
+        return Err(TransferError::NotFound(format!("agent {agent_id}")));
+    }
+    Ok(Workspace::for_agent(base, agent_id))
+}
+
+/// Stream a workspace file, or the part of it named by `range`.
+async fn stream_file(
+    workspace: &Workspace,
+    relative: &str,
+    range: Option<&HeaderValue>,
+    events: EventBroadcaster,
+) -> Result<Response, TransferError> {
+    let path = workspace.resolve(relative)?;
+    if !path.is_file() {
+        return Err(TransferError::NotFound(relative.to_string()));
+    }
+    let mut file = tokio::fs::File::open(&path).await?;
+    let size = file.metadata().await?.len();
+
+    let requested = range.and_then(|v| v.to_str().ok());
+    let (start, end) = match requested.map(|r| parse_range(r, size)) {
+        None => (0, size.saturating_sub(1)),
+        Some(Some(bounds)) => bounds,
+        Some(None) => {
+            return Ok((
+                StatusCode::RANGE_NOT_SATISFIABLE,
+                [(header::CONTENT_RANGE, format!("bytes */{size}"))],
+            )
+                .into_response());
+        }
+    };
+    let length = if size == 0 { 0 } else { end - start + 1 };
+    file.seek(std::io::SeekFrom::Start(start)).await?;
+
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mime_type = mime_guess::from_path(&path)
+        .first_or_octet_stream()
+        .essence_str()
+        .to_string();
+    let progress = DownloadProgress {
+        events,
+        id: new_id(),
+        name: name.clone(),
+        sent: start,
+        total: size,
+        reported: start,
+    };
+    let body = Body::from_stream(read_chunks(file.take(length), progress));
+
+    let mut response = Response::builder()
+        .header(header::CONTENT_TYPE, mime_type)
+        .header(header::CONTENT_LENGTH, length)
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"{}\"",
+                name.replace(['"', '\\'], "_")
+            ),
+        );
+    if requested.is_some() {
+        response = response
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(header::CONTENT_RANGE, format!("bytes {start}-{end}/{size}"));
+    }
+    response
+        .body(body)
+        .map_err(|e| TransferError::Io(std::io::Error::other(e)))
+}
+
+/// Progress of one download, reported every [`PROGRESS_INTERVAL`] bytes
+/// and at the end.
+struct DownloadProgress {
+    events: EventBroadcaster,
+    id: String,
+    name: String,
+    sent: u64,
+    total: u64,
+    reported: u64,
+}
+
+impl DownloadProgress {
+    fn advance(&mut self, bytes: u64) {
+        self.sent += bytes;
+        if self.sent - self.reported < PROGRESS_INTERVAL && self.sent < self.total {
+            return;
+        }
+        self.reported = self.sent;
+        let _ = self.events.broadcast(UiEvent::TransferProgress {
+            transfer_id: self.id.clone(),
+            direction: TransferDirection::Download,
+            name: self.name.clone(),
+            transferred: self.sent,
+            total: self.total,
+            session_key: None,
+        });
+    }
+}
+
+/// Read `reader` in [`READ_BUFFER`]-sized chunks.
+fn read_chunks<R>(
+    reader: R,
+    progress: DownloadProgress,
+) -> impl Stream<Item = std::io::Result<Bytes>>
+where
+    R: tokio::io::AsyncRead + Unpin + Send + 'static,
+{
+    futures::stream::unfold(
+        (reader, progress),
+        |(mut reader, mut progress)| async move {
+            let mut buf = vec![0u8; READ_BUFFER];
+            match reader.read(&mut buf).await {
+                Ok(0) => None,
+                Ok(n) => {
+                    buf.truncate(n);
+                    progress.advance(n as u64);
+                    Some((Ok(Bytes::from(buf)), (reader, progress)))
+                }
+                Err(e) => Some((Err(e), (reader, progress))),
+            }
+        },
+    )
+}
+
+/// Parse a single `bytes=` range into inclusive bounds within `size`.
+fn parse_range(value: &str, size: u64) -> Option<(u64, u64)> {
+    let spec = value.trim().strip_prefix("bytes=")?;
+    if spec.contains(',') || size == 0 {
+        return None;
+    }
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = if start.is_empty() {
+        // Suffix range: the last N bytes
+        let suffix: u64 = end.parse().ok()?;
+        if suffix == 0 {
+            return None;
+        }
+        (size.saturating_sub(suffix), size - 1)
+    } else {
+        let start: u64 = start.parse().ok()?;
+        let end = if end.is_empty() {
+            size - 1
+        } else {
+            end.parse::<u64>().ok()?.min(size - 1)
+        };
+        (start, end)
+    };
+    (start <= end && start < size).then_some((start, end))
+}
+
+fn kind_for(mime_type: &str) -> AttachmentKind {
+    if mime_type == "image/gif" {
+        return AttachmentKind::Gif;
+    }
+    match mime_type.split('/').next().unwrap_or_default() {
+        "image" => AttachmentKind::Image,
+        "video" => AttachmentKind::Video,
+        "audio" => AttachmentKind::Audio,
+        _ => AttachmentKind::Document,
+    }
+}
+
+fn new_id() -> String {
+    use rand::RngCore;
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+fn is_upload_id(id: &str) -> bool {
+    id.len() == 32 && id.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn chunk(data: &[u8]) -> impl Stream<Item = Result<Bytes, std::io::Error>> + Unpin {
+        futures::stream::iter(vec![Ok(Bytes::copy_from_slice(data))])
+    }
+
+    fn uploads(dir: &Path) -> UploadStore {
+        UploadStore::new(
+            dir.join("uploads"),
+            BlobStore::local(&dir.join("blobs")).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_chunked_upload() {
+        let temp = tempdir().unwrap();
+        let store = uploads(temp.path());
+        let meta = store
+            .create("C:\\photos\\cat.png", 10, None, None, "alice")
+            .unwrap();
+        assert_eq!(meta.name, "cat.png");
+        assert_eq!(meta.mime_type, "image/png");
+
+        let status = store.append(&meta.id, 0, chunk(b"0123")).await.unwrap();
+        assert_eq!(status.received, 4);
+        assert!(!status.completed);
+
+        // A retried or skipped chunk reports where to resume
+        assert!(matches!(
+            store.append(&meta.id, 0, chunk(b"0123")).await,
+            Err(TransferError::OffsetMismatch { expected: 4 })
+        ));
+        assert!(matches!(
+            store.append(&meta.id, 4, chunk(b"456789!")).await,
+            Err(TransferError::Invalid(_))
+        ));
+
+        let status = store.append(&meta.id, 4, chunk(b"456789")).await.unwrap();
+        assert!(status.completed);
+        let attachment = status.attachment.unwrap();
+        assert_eq!(attachment.kind, AttachmentKind::Image);
+        assert_eq!(attachment.url, format!("upload://{}", meta.id));
+        assert!(
+            temp.path()
+                .join("blobs/attachments")
+                .join(&meta.id)
+                .exists()
+        );
+        assert!(
+            !temp
+                .path()
+                .join("uploads")
+                .join(format!("{}.part", meta.id))
+                .exists()
+        );
+
+        let resolved = UploadResolver::new(store.clone())
+            .resolve(&attachment, 1024)
+            .await
+            .unwrap();
+        assert_eq!(resolved.data, b"0123456789");
+        assert!(matches!(
+            UploadResolver::new(store).resolve(&attachment, 5).await,
+            Err(AttachmentError::TooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_create_limits() {
+        let temp = tempdir().unwrap();
+        let store = uploads(temp.path());
+        assert!(matches!(
+            store.create("a.txt", 0, None, None, "alice"),
+            Err(TransferError::Invalid(_))
+        ));
+        assert!(matches!(
+            store.create("big.bin", u64::MAX, None, None, "alice"),
+            Err(TransferError::TooLarge { .. })
+        ));
+        assert!(matches!(
+            store.get("../etc/passwd"),
+            Err(TransferError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_delete_for_sessions() {
+        let temp = tempdir().unwrap();
+        let store = uploads(temp.path());
+        assert_eq!(
+            store
+                .delete_for_sessions(&HashSet::from(["a"]))
+                .await
+                .unwrap(),
+            0
+        );
+
+        let done = store
+            .create("a.txt", 2, None, Some("a".to_string()), "alice")
+            .unwrap();
+        store.append(&done.id, 0, chunk(b"hi")).await.unwrap();
+        let partial = store
+            .create("b.txt", 2, None, Some("a".to_string()), "alice")
+            .unwrap();
+        store.append(&partial.id, 0, chunk(b"h")).await.unwrap();
+        let other = store
+            .create("c.txt", 2, None, Some("b".to_string()), "alice")
+            .unwrap();
+
+        assert_eq!(
+            store
+                .delete_for_sessions(&HashSet::from(["a"]))
+                .await
+                .unwrap(),
+            2
+        );
+        assert!(store.get(&done.id).is_err());
+        assert!(
+            !temp
+                .path()
+                .join("blobs/attachments")
+                .join(&done.id)
+                .exists()
+        );
+        assert!(store.get(&partial.id).is_err());
+        assert!(store.get(&other.id).is_ok());
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-", 10), Some((0, 9)));
+        assert_eq!(parse_range("bytes=4-6", 10), Some((4, 6)));
+        assert_eq!(parse_range("bytes=4-100", 10), Some((4, 9)));
+        assert_eq!(parse_range("bytes=-3", 10), Some((7, 9)));
+        assert_eq!(parse_range("bytes=10-", 10), None);
+        assert_eq!(parse_range("bytes=0-1,4-5", 10), None);
+        assert_eq!(parse_range("items=0-1", 10), None);
+    }
+
+    #[tokio::test]
+    async fn test_stream_file_range() {
+        let temp = tempdir().unwrap();
+        let ws = Workspace::for_agent(temp.path(), "default");
+        std::fs::create_dir_all(ws.root()).unwrap();
+        std::fs::write(ws.root().join("report.txt"), "0123456789").unwrap();
+
+        let events = EventBroadcaster::new();
+        let mut receiver = events.subscribe();
+        let range = HeaderValue::from_static("bytes=6-");
+        let response = stream_file(&ws, "report.txt", Some(&range), events)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes 6-9/10");
+
+        let body = axum::body::to_bytes(response.into_body(), 1024)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"6789");
+        match receiver.recv().await.unwrap().event {
+            UiEvent::TransferProgress {
+                transferred, total, ..
+            } => assert_eq!((transferred, total), (10, 10)),
+            other => panic!("unexpected event: {other:?}"),
+        }
+
+        assert!(matches!(
+            stream_file(&ws, "../secret", None, EventBroadcaster::new()).await,
+            Err(TransferError::Invalid(_) | TransferError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,403 @@
+//! Starting workflows from their triggers.
+//!
+//! [`TriggerDispatcher`] indexes the triggers declared by registered
+//! workflows. Message triggers are checked for each inbound message, cron
+//! triggers and session event triggers run in background tasks, and
+//! webhook triggers are served at `POST /hooks/workflow/{id}`. Every run
+//! starts through [`start_workflow`], with a payload describing what
+//! triggered it under `trigger`.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use tokio::sync::RwLock;
+use tokio::sync::broadcast::error::RecvError;
+
+use openclaw_agents::scheduler::parse_cron;
+use openclaw_agents::workflow::{InputOrigin, WorkflowError, WorkflowRegistry, WorkflowTrigger};
+use openclaw_core::events::EventStore;
+use openclaw_core::types::Message;
+
+use crate::auth::JwtManager;
+use crate::rpc;
+use crate::server::GatewayState;
+use crate::workflow_input::start_workflow;
+
+/// Route path of workflow webhooks.
+pub const WORKFLOW_HOOK_ROUTE: &str = "/hooks/workflow/{id}";
+
+/// Header carrying a webhook trigger's secret.
+const SECRET_HEADER: &str = "x-openclaw-secret";
+
+/// Triggers of the registered workflows, by kind.
+#[derive(Debug, Default)]
+pub struct TriggerDispatcher {
+    messages: Vec<(String, WorkflowTrigger)>,
+    crons: Vec<(String, cron::Schedule, serde_json::Value)>,
+    webhooks: Vec<(String, Option<String>)>,
+    events: Vec<(String, String)>,
+}
+
+impl TriggerDispatcher {
+    /// Index the triggers of every workflow in `registry`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidWorkflow` if a trigger cannot fire.
+    pub fn new(registry: &WorkflowRegistry) -> Result<Self, WorkflowError> {
+        let mut dispatcher = Self::default();
+        for id in registry.ids() {
+            let Some(workflow) = registry.get(id) else {
+                continue;
+            };
+            for trigger in &workflow.triggers {
+                let id = id.to_string();
+                match trigger {
+                    WorkflowTrigger::Message { .. } => {
+                        dispatcher.messages.push((id, trigger.clone()));
+                    }
+                    WorkflowTrigger::Cron { cron, input } => {
+                        let schedule = parse_cron(cron).map_err(|e| {
+                            WorkflowError::InvalidWorkflow(format!("Invalid cron '{cron}': {e}"))
+                        })?;
+                        dispatcher.crons.push((id, schedule, input.clone()));
+                    }
+                    WorkflowTrigger::Webhook { secret } => {
+                        dispatcher.webhooks.push((id, secret.clone()));
+                    }
+                    WorkflowTrigger::Event { event } => {
+                        dispatcher.events.push((id, event.clone()));
+                    }
+                }
+            }
+        }
+        Ok(dispatcher)
+    }
+
+    /// Workflows triggered by `text` arriving on `channel`.
+    #[must_use]
+    pub fn for_message(&self, channel: &str, text: &str) -> Vec<&str> {
+        let mut ids: Vec<&str> = self
+            .messages
+            .iter()
+            .filter(|(_, trigger)| trigger.matches_message(channel, text))
+            .map(|(id, _)| id.as_str())
+            .collect();
+        ids.dedup();
+        ids
+    }
+
+    /// Workflows triggered by session events of type `event`.
+    #[must_use]
+    pub fn for_event(&self, event: &str) -> Vec<&str> {
+        let mut ids: Vec<&str> = self
+            .events
+            .iter()
+            .filter(|(_, name)| name == event)
+            .map(|(id, _)| id.as_str())
+            .collect();
+        ids.dedup();
+        ids
+    }
+
+    /// Whether workflow `id` has a webhook trigger, and the secret it
+    /// requires: `None` if it has none, `Some(None)` if it is open.
+    #[must_use]
+    pub fn webhook(&self, id: &str) -> Option<Option<&str>> {
+        self.webhooks
+            .iter()
+            .find(|(workflow, _)| workflow == id)
+            .map(|(_, secret)| secret.as_deref())
+    }
+
+    /// URLs of the workflow webhooks, for a gateway reached at `base_url`.
+    #[must_use]
+    #[allow(clippy::literal_string_with_formatting_args)]
+    pub fn webhook_urls(&self, base_url: &str) -> Vec<String> {
+        self.webhooks
+            .iter()
+            .map(|(id, _)| format!("{base_url}{}", WORKFLOW_HOOK_ROUTE.replace("{id}", id)))
+            .collect()
+    }
+
+    /// Whether any workflow starts on a session event.
+    #[must_use]
+    pub fn has_event_triggers(&self) -> bool {
+        !self.events.is_empty()
+    }
+}
+
+/// Start the workflows `message` triggers. Each run asks for input in the
+/// chat the message came from.
+///
+/// Returns the result of each run, or `None` if nothing was triggered.
+pub async fn dispatch_message(
+    state: &Arc<RwLock<GatewayState>>,
+    message: &Message,
+    chat_id: &str,
+) -> Option<Vec<serde_json::Value>> {
+    let triggers = state.read().await.triggers.clone();
+    let ids = triggers.for_message(message.channel.as_ref(), &message.content);
+    if ids.is_empty() {
+        return None;
+    }
+
+    let payload = serde_json::json!({ "trigger": "message", "message": message });
+    let origin = InputOrigin {
+        channel: message.channel.as_ref().to_string(),
+        chat_id: chat_id.to_string(),
+        peer_id: message.peer_id.as_ref().to_string(),
+    };
+    let mut results = Vec::with_capacity(ids.len());
+    for id in ids {
+        let result = start_workflow(state, id, payload.clone(), Some(origin.clone())).await;
+        results.push(report(id, result));
+    }
+    Some(results)
+}
+
+/// Start workflows on their cron schedules, forever.
+pub async fn run_cron_triggers(state: Arc<RwLock<GatewayState>>) {
+    let triggers = state.read().await.triggers.clone();
+    if triggers.crons.is_empty() {
+        return;
+    }
+
+    let mut after = Utc::now();
+    loop {
+        let due = triggers
+            .crons
+            .iter()
+            .filter_map(|(_, schedule, _)| schedule.after(&after).next())
+            .min();
+        let Some(due) = due else {
+            return;
+        };
+        if let Ok(wait) = (due - Utc::now()).to_std() {
+            tokio::time::sleep(wait).await;
+        }
+
+        for (id, schedule, input) in &triggers.crons {
+            if schedule.after(&after).next() != Some(due) {
+                continue;
+            }
+            let payload = serde_json::json!({
+                "trigger": "cron",
+                "scheduled_at": due,
+                "input": input,
+            });
+            if let Err((_, e)) = start_workflow(&state, id, payload, None).await {
+                tracing::warn!("Cron-triggered workflow {} failed: {}", id, e);
+            }
+        }
+        after = due;
+    }
+}
+
+/// Start workflows on session events appended to any event store, forever.
+pub async fn run_event_triggers(state: Arc<RwLock<GatewayState>>) {
+    let stores: Vec<Arc<EventStore>> = {
+        let state = state.read().await;
+        if !state.triggers.has_event_triggers() {
+            return;
+        }
+        state.event_stores().cloned().collect()
+    };
+    for store in stores {
+        tokio::spawn(forward_events(state.clone(), store));
+    }
+}
+
+async fn forward_events(state: Arc<RwLock<GatewayState>>, store: Arc<EventStore>) {
+    let triggers = state.read().await.triggers.clone();
+    let mut events = store.subscribe();
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!("Event triggers skipped {} event(s)", skipped);
+                continue;
+            }
+            Err(RecvError::Closed) => return,
+        };
+        let Ok(payload) = serde_json::to_value(&event) else {
+            continue;
+        };
+        let Some(kind) = payload["kind"]["type"].as_str() else {
+            continue;
+        };
+        let ids = triggers.for_event(kind);
+        if ids.is_empty() {
+            continue;
+        }
+
+        let payload = serde_json::json!({ "trigger": "event", "event": payload });
+        for id in ids {
+            if let Err((_, e)) = start_workflow(&state, id, payload.clone(), None).await {
+                tracing::warn!("Event-triggered workflow {} failed: {}", id, e);
+            }
+        }
+    }
+}
+
+/// Handle a POST to `/hooks/workflow/{id}`.
+///
+/// A hook with a secret requires it in the `X-Openclaw-Secret` header; one
+/// without requires a valid bearer token when auth is enabled. The JSON
+/// body, if any, is passed to the run as `body`.
+pub async fn workflow_hook_handler(
+    State(state): State<Arc<RwLock<GatewayState>>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    {
+        let state = state.read().await;
+        let Some(secret) = state.triggers.webhook(&id) else {
+            return (
+                StatusCode::NOT_FOUND,
+                format!("No webhook for workflow {id}"),
+            )
+                .into_response();
+        };
+        let authorized = secret.map_or_else(
+            || {
+                !state.auth.config.enabled
+                    || headers
+                        .get(header::AUTHORIZATION)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(JwtManager::extract_from_header)
+                        .is_some_and(|t| state.auth.validate_token(t).is_ok())
+            },
+            |secret| {
+                headers
+                    .get(SECRET_HEADER)
+                    .and_then(|v| v.to_str().ok())
+                    .is_some_and(|given| secrets_match(given, secret))
+            },
+        );
+        drop(state);
+        if !authorized {
+            return (StatusCode::UNAUTHORIZED, "Invalid webhook credentials").into_response();
+        }
+    }
+
+    let body: serde_json::Value = if body.is_empty() {
+        serde_json::Value::Null
+    } else {
+        match serde_json::from_slice(&body) {
+            Ok(body) => body,
+            Err(e) => {
+                return (StatusCode::BAD_REQUEST, format!("Invalid JSON body: {e}"))
+                    .into_response();
+            }
+        }
+    };
+
+    let payload = serde_json::json!({ "trigger": "webhook", "body": body });
+    match start_workflow(&state, &id, payload, None).await {
+        Ok(result) => Json(result).into_response(),
+        Err((code, message)) => {
+            let status = if code == rpc::NOT_FOUND {
+                StatusCode::NOT_FOUND
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            (status, message).into_response()
+        }
+    }
+}
+
+/// Compare secrets in time independent of where they differ.
+fn secrets_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// A run's result tagged with its workflow, logging failures.
+fn report(id: &str, result: Result<serde_json::Value, (i32, String)>) -> serde_json::Value {
+    match result {
+        Ok(result) => serde_json::json!({ "workflow_id": id, "result": result }),
+        Err((_, e)) => {
+            tracing::warn!("Message-triggered workflow {} failed: {}", id, e);
+            serde_json::json!({ "workflow_id": id, "error": e })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openclaw_agents::workflow::Workflow;
+    use openclaw_agents::workflow::testing::StubNode;
+
+    use super::*;
+
+    fn registry() -> WorkflowRegistry {
+        let mut deploy = Workflow::new("deploy", "Deploy", "run");
+        deploy.add_node(Arc::new(StubNode::new("run")));
+        deploy.add_trigger(WorkflowTrigger::Message {
+            pattern: "deploy *".to_string(),
+            channel: None,
+        });
+        deploy.add_trigger(WorkflowTrigger::Webhook {
+            secret: Some("s3cret".to_string()),
+        });
+
+        let mut survey = Workflow::new("survey", "Survey", "run");
+        survey.add_node(Arc::new(StubNode::new("run")));
+        survey.add_trigger(WorkflowTrigger::Event {
+            event: "session_ended".to_string(),
+        });
+        survey.add_trigger(WorkflowTrigger::Cron {
+            cron: "0 9 * * *".to_string(),
+            input: serde_json::Value::Null,
+        });
+
+        [deploy, survey]
+            .into_iter()
+            .map(|w| (w.id.clone(), Arc::new(w)))
+            .collect()
+    }
+
+    #[test]
+    fn test_dispatcher_index() {
+        let dispatcher = TriggerDispatcher::new(&registry()).unwrap();
+        assert_eq!(dispatcher.for_message("mock", "deploy api"), vec!["deploy"]);
+        assert!(dispatcher.for_message("mock", "hello").is_empty());
+        assert_eq!(dispatcher.for_event("session_ended"), vec!["survey"]);
+        assert_eq!(dispatcher.webhook("deploy"), Some(Some("s3cret")));
+        assert_eq!(dispatcher.webhook("survey"), None);
+        assert_eq!(
+            dispatcher.webhook_urls("https://claw.example.com/claw"),
+            vec!["https://claw.example.com/claw/hooks/workflow/deploy"]
+        );
+        assert_eq!(dispatcher.crons.len(), 1);
+
+        assert!(secrets_match("s3cret", "s3cret"));
+        assert!(!secrets_match("s3cre", "s3cret"));
+        assert!(!secrets_match("s3creT", "s3cret"));
+    }
+
+    #[test]
+    fn test_invalid_cron_rejected() {
+        let mut workflow = Workflow::new("bad", "Bad", "run");
+        workflow.add_trigger(WorkflowTrigger::Cron {
+            cron: "whenever".to_string(),
+            input: serde_json::Value::Null,
+        });
+        let registry: WorkflowRegistry =
+            std::iter::once(("bad".to_string(), Arc::new(workflow))).collect();
+        assert!(TriggerDispatcher::new(&registry).is_err());
+    }
+}
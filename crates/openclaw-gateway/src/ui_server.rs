@@ -12,7 +12,12 @@ use axum::{
 use std::net::SocketAddr;
 
 #[cfg(feature = "ui")]
-use axum::{extract::Path, http::header};
+use axum::{
+    extract::{Path, State},
+    http::header,
+};
+#[cfg(feature = "ui")]
+use std::sync::Arc;
 
 #[cfg(feature = "ui")]
 use rust_embed::Embed;
@@ -38,6 +43,10 @@ pub struct UiServerConfig {
     pub bind_address: String,
     /// Whether the UI server is enabled.
     pub enabled: bool,
+    /// Gateway URL the UI calls, for a gateway behind a proxy (default:
+    /// the page's host on port 18789).
+    #[serde(default)]
+    pub api_base_url: Option<String>,
 }
 
 impl Default for UiServerConfig {
@@ -46,6 +55,7 @@ impl Default for UiServerConfig {
             port: 3000,
             bind_address: "127.0.0.1".to_string(),
             enabled: true,
+            api_base_url: None,
         }
     }
 }
@@ -71,18 +81,42 @@ impl UiServerConfig {
 // UI feature enabled - serve embedded assets
 // ============================================================================
 
+/// Gateway URL announced to the UI, if configured.
+#[cfg(feature = "ui")]
+type ApiBase = Option<Arc<str>>;
+
+#[cfg(feature = "ui")]
+async fn serve_index(State(api_base): State<ApiBase>) -> Response {
+    serve_file("index.html", api_base.as_deref()).await
+}
+
 #[cfg(feature = "ui")]
-async fn serve_index() -> Response {
-    serve_file("index.html").await
+async fn serve_static(State(api_base): State<ApiBase>, Path(path): Path<String>) -> Response {
+    serve_file(&path, api_base.as_deref()).await
 }
 
+/// `index.html` with the gateway URL in a `<meta name="openclaw-api-base">`
+/// tag, which the UI reads before falling back to its default.
 #[cfg(feature = "ui")]
-async fn serve_static(Path(path): Path<String>) -> Response {
-    serve_file(&path).await
+fn index_html(html: &[u8], api_base: Option<&str>) -> Vec<u8> {
+    let Some(url) = api_base else {
+        return html.to_vec();
+    };
+    let html = String::from_utf8_lossy(html);
+    let escaped = url
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    let meta = format!("<meta name=\"openclaw-api-base\" content=\"{escaped}\" />\n");
+    match html.find("</head>") {
+        Some(at) => format!("{}{meta}{}", &html[..at], &html[at..]).into_bytes(),
+        None => html.into_owned().into_bytes(),
+    }
 }
 
 #[cfg(feature = "ui")]
-async fn serve_file(path: &str) -> Response {
+async fn serve_file(path: &str, api_base: Option<&str>) -> Response {
     // Try to get the file from embedded assets
     if let Some(content) = UiAssets::get(path) {
         // Determine MIME type from file extension
@@ -110,7 +144,11 @@ async fn serve_file(path: &str) -> Response {
                 (header::CONTENT_TYPE, mime.as_ref()),
                 (header::CACHE_CONTROL, cache_control),
             ],
-            content.data.into_owned(),
+            if path == "index.html" {
+                index_html(&content.data, api_base)
+            } else {
+                content.data.into_owned()
+            },
         )
             .into_response()
     } else {
@@ -124,7 +162,7 @@ async fn serve_file(path: &str) -> Response {
                         (header::CONTENT_TYPE, "text/html"),
                         (header::CACHE_CONTROL, "no-cache, no-store, must-revalidate"),
                     ],
-                    index.data.into_owned(),
+                    index_html(&index.data, api_base),
                 )
                     .into_response();
             }
@@ -171,15 +209,17 @@ async fn serve_ui_not_available() -> Response {
 /// Sets up routes for serving static files with SPA fallback.
 /// When the `ui` feature is disabled, returns a "not available" message.
 #[cfg(feature = "ui")]
-pub fn create_ui_router() -> Router {
+pub fn create_ui_router(config: &UiServerConfig) -> Router {
+    let api_base: ApiBase = config.api_base_url.as_deref().map(Arc::from);
     Router::new()
         .route("/", get(serve_index))
         .route("/{*path}", get(serve_static))
+        .with_state(api_base)
 }
 
 /// Create the UI router (stub when UI feature is disabled).
 #[cfg(not(feature = "ui"))]
-pub fn create_ui_router() -> Router {
+pub fn create_ui_router(_config: &UiServerConfig) -> Router {
     Router::new()
         .route("/", get(serve_ui_not_available))
         .fallback(get(serve_ui_not_available))
@@ -202,7 +242,7 @@ pub async fn run_ui_server(config: UiServerConfig) -> Result<(), std::io::Error>
         return Ok(());
     }
 
-    let app = create_ui_router();
+    let app = create_ui_router(&config);
 
     let addr: SocketAddr = config
         .address()
@@ -241,6 +281,20 @@ mod tests {
         assert_eq!(config.bind_address, "127.0.0.1");
     }
 
+    #[cfg(feature = "ui")]
+    #[test]
+    fn test_index_html_api_base() {
+        let html = b"<html><head><title>UI</title></head></html>";
+        assert_eq!(index_html(html, None), html.to_vec());
+
+        let injected = index_html(html, Some("https://example.com/claw?a=\"1\""));
+        assert_eq!(
+            String::from_utf8(injected).unwrap(),
+            "<html><head><title>UI</title><meta name=\"openclaw-api-base\" \
+             content=\"https://example.com/claw?a=&quot;1&quot;\" />\n</head></html>"
+        );
+    }
+
     #[test]
     fn test_ui_server_config_address() {
         let config = UiServerConfig::default();
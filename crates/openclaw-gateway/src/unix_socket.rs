@@ -0,0 +1,94 @@
+//! Unix domain socket listener.
+//!
+//! Serves the same routes as the TCP listener, for local clients that
+//! should not need an open loopback port. Access is controlled by the
+//! socket file's permissions.
+
+use std::io;
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::path::Path;
+
+use tokio::net::UnixListener;
+
+use openclaw_core::config::UnixSocketConfig;
+
+/// Bind the socket in `config`, replacing a stale one left by a previous
+/// run, and set its permissions.
+///
+/// # Errors
+///
+/// Returns an error if the mode is invalid, the path holds something other
+/// than a socket, or binding fails.
+pub fn bind(config: &UnixSocketConfig) -> io::Result<UnixListener> {
+    let mode = config
+        .permissions()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let path = &config.path;
+
+    remove_stale(path)?;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+    Ok(listener)
+}
+
+/// Remove the socket file, if it is still one.
+pub fn remove(path: &Path) {
+    if let Err(e) = remove_stale(path) {
+        tracing::warn!("Failed to remove socket {}: {}", path.display(), e);
+    }
+}
+
+fn remove_stale(path: &Path) -> io::Result<()> {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_socket() => std::fs::remove_file(path),
+        Ok(_) => Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} exists and is not a socket", path.display()),
+        )),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn config(path: &Path) -> UnixSocketConfig {
+        UnixSocketConfig {
+            path: path.to_path_buf(),
+            mode: "660".to_string(),
+            tcp: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bind_replaces_stale_socket() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("run").join("gateway.sock");
+
+        let first = bind(&config(&path)).unwrap();
+        drop(first);
+        // The file outlives the listener, as after a crash
+        let _listener = bind(&config(&path)).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o660);
+
+        remove(&path);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_bind_refuses_regular_file() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("gateway.sock");
+        std::fs::write(&path, "data").unwrap();
+
+        assert!(bind(&config(&path)).is_err());
+        assert!(path.exists());
+    }
+}
@@ -0,0 +1,377 @@
+//! Inbound `WhatsApp` messages.
+//!
+//! The Cloud API delivers messages by POSTing them to a webhook signed with
+//! the app secret. The gateway rejects requests whose signature does not
+//! match, acknowledges the rest at once, then runs each message through its
+//! sender's session and sends the reply back through the channel. Reactions
+//! are recorded as feedback.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use tokio::sync::RwLock;
+
+use openclaw_channels::whatsapp::{
+    SIGNATURE_HEADER, WebhookChange, WebhookEntry, WebhookValue, WhatsAppWebhookPayload,
+};
+use openclaw_channels::{
+    ChannelError, ChannelInbound, ChannelReactions, OutboundContext, WhatsAppChannel,
+    rating_from_reaction,
+};
+use openclaw_core::config::WhatsAppConfig;
+use openclaw_core::secrets::ApiKey;
+use openclaw_core::types::{
+    AgentId, ChannelId, Message, MessageReaction, PeerId, PeerType, SessionKey,
+};
+
+use crate::mock::reply_text;
+use crate::rpc;
+use crate::server::{
+    GatewayState, authorize_session, handle_session_create, handle_session_message,
+    record_feedback, session_projection,
+};
+
+/// Route path of the `WhatsApp` webhook.
+pub const WHATSAPP_HOOK_ROUTE: &str = "/hooks/whatsapp";
+
+/// The `WhatsApp` channel for configured credentials, verifying webhooks
+/// with the app secret if one is set.
+#[must_use]
+pub fn whatsapp_channel(config: Option<&WhatsAppConfig>) -> Option<Arc<WhatsAppChannel>> {
+    let config = config?;
+    let token = config.access_token.clone()?;
+    let phone_number_id = config.phone_number_id.clone()?;
+    let channel = WhatsAppChannel::new(ApiKey::new(token), phone_number_id);
+    Some(Arc::new(match config.app_secret.clone() {
+        Some(secret) => channel.with_app_secret(ApiKey::new(secret)),
+        None => channel,
+    }))
+}
+
+/// Handle Meta's GET to `/hooks/whatsapp` when the webhook is subscribed,
+/// echoing the challenge if the verify token matches.
+pub async fn whatsapp_verify_handler(
+    State(state): State<Arc<RwLock<GatewayState>>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    let expected = state
+        .read()
+        .await
+        .config
+        .whatsapp
+        .as_ref()
+        .and_then(|c| c.verify_token.clone());
+    let verified = params
+        .get("hub.mode")
+        .is_some_and(|mode| mode == "subscribe")
+        && expected.is_some_and(|expected| params.get("hub.verify_token") == Some(&expected));
+    if !verified {
+        return (StatusCode::FORBIDDEN, "Invalid verify token").into_response();
+    }
+    params
+        .get("hub.challenge")
+        .cloned()
+        .unwrap_or_default()
+        .into_response()
+}
+
+/// Handle a POST to `/hooks/whatsapp`.
+///
+/// Requests without a valid `X-Hub-Signature-256` are refused with 401.
+/// Messages are answered in the background, since Meta retries webhooks
+/// that are not acknowledged quickly.
+pub async fn whatsapp_hook_handler(
+    State(state): State<Arc<RwLock<GatewayState>>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let Some(channel) = state.read().await.whatsapp.clone() else {
+        return (StatusCode::NOT_FOUND, "WhatsApp is not configured").into_response();
+    };
+    let signature = headers.get(SIGNATURE_HEADER).and_then(|v| v.to_str().ok());
+    let payload = match channel.parse_webhook(&body, signature) {
+        Ok(payload) => payload,
+        Err(ChannelError::AuthFailed(e)) => {
+            return (StatusCode::UNAUTHORIZED, e).into_response();
+        }
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
+
+    tokio::spawn(async move {
+        for (is_reaction, raw) in split_messages(&payload) {
+            let result = if is_reaction {
+                match channel.normalize_reaction(raw) {
+                    Ok(reaction) => record_reaction(&state, &reaction).await,
+                    Err(e) => Err((rpc::INVALID_PARAMS, e.to_string())),
+                }
+            } else {
+                match channel.normalize(raw) {
+                    Ok(message) => answer_message(&state, &channel, &message).await,
+                    Err(e) => Err((rpc::INVALID_PARAMS, e.to_string())),
+                }
+            };
+            if let Err((_, e)) = result {
+                tracing::warn!("WhatsApp webhook message failed: {}", e);
+            }
+        }
+    });
+    StatusCode::OK.into_response()
+}
+
+/// One payload per message, each flagged as a reaction or not, since
+/// normalizing reads only the first message of a payload.
+fn split_messages(payload: &WhatsAppWebhookPayload) -> Vec<(bool, WhatsAppWebhookPayload)> {
+    let mut split = Vec::new();
+    for entry in &payload.entry {
+        for change in &entry.changes {
+            for message in change.value.messages.iter().flatten() {
+                split.push((
+                    message.reaction.is_some(),
+                    WhatsAppWebhookPayload {
+                        object: payload.object.clone(),
+                        entry: vec![WebhookEntry {
+                            id: entry.id.clone(),
+                            changes: vec![WebhookChange {
+                                field: change.field.clone(),
+                                value: WebhookValue {
+                                    messages: Some(vec![message.clone()]),
+                                    ..change.value.clone()
+                                },
+                            }],
+                        }],
+                    },
+                ));
+            }
+        }
+    }
+    split
+}
+
+/// Run a message through its sender's session and deliver the reply.
+///
+/// Each sender gets their own session, created on the first message, with
+/// the agent the router picks for them.
+async fn answer_message(
+    state: &Arc<RwLock<GatewayState>>,
+    channel: &WhatsAppChannel,
+    message: &Message,
+) -> Result<(), (i32, String)> {
+    let (agent_id, exists) = {
+        let state = state.read().await;
+        let agent_id = state
+            .router
+            .route(&message.channel, &message.peer_id)
+            .to_string();
+        let key = session_key(&agent_id, message.peer_id.as_ref());
+        let exists = state.store_for_session(&key).get_projection(&key).is_ok();
+        drop(state);
+        (agent_id, exists)
+    };
+    if !exists {
+        handle_session_create(
+            state,
+            &serde_json::json!({
+                "agent_id": agent_id,
+                "channel": ChannelId::whatsapp().as_ref(),
+                "peer_id": message.peer_id.as_ref(),
+            }),
+            None,
+        )
+        .await?;
+    }
+
+    let result = handle_session_message(
+        state,
+        &serde_json::json!({
+            "session_key": session_key(&agent_id, message.peer_id.as_ref()).as_ref(),
+            "message": message.content,
+            "attachments": message.attachments,
+            "sender_id": message.peer_id.as_ref(),
+        }),
+        None,
+    )
+    .await?;
+
+    let Some(text) = reply_text(&result, &ChannelId::whatsapp()) else {
+        return Ok(());
+    };
+    let outbound = state.read().await.outbound.clone();
+    let ctx = OutboundContext {
+        chat_id: message.peer_id.to_string(),
+        reply_to: Some(message.id.clone()),
+        thread_id: None,
+    };
+    outbound
+        .send(channel, ctx, &text)
+        .await
+        .map_err(|e| (rpc::INTERNAL_ERROR, format!("Delivery failed: {e}")))?;
+    Ok(())
+}
+
+/// Record a thumbs-up or thumbs-down on a message as feedback in the
+/// sender's session.
+async fn record_reaction(
+    state: &Arc<RwLock<GatewayState>>,
+    reaction: &MessageReaction,
+) -> Result<(), (i32, String)> {
+    let Some(rating) = rating_from_reaction(&reaction.emoji).filter(|_| !reaction.removed) else {
+        return Ok(());
+    };
+    let state = state.read().await;
+    let agent_id = state
+        .router
+        .route(&reaction.channel, &reaction.peer_id)
+        .to_string();
+    let key = session_key(&agent_id, &reaction.chat_id);
+    let projection = session_projection(&state, &key)?;
+    authorize_session(&state, None, &projection, false)?;
+    record_feedback(
+        state.store_for_session(&key),
+        &key,
+        &agent_id,
+        Some(reaction.message_id.clone()),
+        rating,
+        None,
+        "reaction",
+    )?;
+    drop(state);
+    Ok(())
+}
+
+/// Key of a `WhatsApp` sender's session, as `session.create` builds it.
+fn session_key(agent_id: &str, peer_id: &str) -> SessionKey {
+    SessionKey::build(
+        &AgentId::new(agent_id),
+        &ChannelId::whatsapp(),
+        "gateway",
+        PeerType::Dm,
+        &PeerId::new(peer_id),
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use openclaw_agents::harness::{MockCompletion, MockProvider};
+    use openclaw_agents::runtime::AgentRuntime;
+    use openclaw_core::events::{EventStore, SessionMessage};
+
+    use super::*;
+    use crate::server::{GatewayBuilder, GatewayConfig};
+
+    const MESSAGE: &[u8] = br#"{"object":"whatsapp_business_account","entry":[{"id":"1","changes":[{"field":"messages","value":{"messages":[{"id":"wamid.1","from":"15550001","timestamp":"1700000000","type":"text","text":{"body":"hello"}}]}}]}]}"#;
+    const SIGNATURE: &str =
+        "sha256=4d38a9efc6870e43004da98e363cef2390f03e6ed73a5c80a3ae749ef6338e71";
+
+    fn gateway(temp: &tempfile::TempDir) -> Arc<RwLock<GatewayState>> {
+        let provider = Arc::new(MockProvider::new([MockCompletion::Text(
+            "hi there".to_string(),
+        )]));
+        GatewayBuilder::new()
+            .with_config(GatewayConfig {
+                data_dir: temp.path().to_path_buf(),
+                whatsapp: Some(WhatsAppConfig {
+                    access_token: Some("token".to_string()),
+                    phone_number_id: Some("123456789".to_string()),
+                    app_secret: Some("app-secret".to_string()),
+                    verify_token: Some("verify-me".to_string()),
+                }),
+                ..GatewayConfig::default()
+            })
+            .with_event_store(Arc::new(
+                EventStore::open(&temp.path().join("events")).unwrap(),
+            ))
+            .with_agent("default", Arc::new(AgentRuntime::new(provider)))
+            .build()
+            .unwrap()
+            .state()
+            .clone()
+    }
+
+    fn signed(signature: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(SIGNATURE_HEADER, signature.parse().unwrap());
+        headers
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unsigned_webhooks() {
+        let temp = tempfile::tempdir().unwrap();
+        let state = gateway(&temp);
+
+        let forged = "sha256=d3e4f9da0ce6c71ab3dba55929b8eeeee2455349e534924ead19f98d143e904f";
+        for headers in [HeaderMap::new(), signed(forged)] {
+            let response =
+                whatsapp_hook_handler(State(state.clone()), headers, Bytes::from(MESSAGE)).await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+        let key = session_key("default", "15550001");
+        let store = state.read().await.event_store.clone();
+        assert!(store.get_projection(&key).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_answers_signed_webhooks() {
+        let temp = tempfile::tempdir().unwrap();
+        let state = gateway(&temp);
+
+        let response = whatsapp_hook_handler(
+            State(state.clone()),
+            signed(SIGNATURE),
+            Bytes::from(MESSAGE),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The message runs in the background
+        let key = session_key("default", "15550001");
+        let store = state.read().await.event_store.clone();
+        let projection = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            loop {
+                if let Ok(projection) = store.get_projection(&key) {
+                    if projection.messages.len() == 2 {
+                        return projection;
+                    }
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(
+            matches!(&projection.messages[0], SessionMessage::Inbound(text) if text == "hello")
+        );
+        assert!(
+            matches!(&projection.messages[1], SessionMessage::Outbound(text) if text == "hi there")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_challenge() {
+        let temp = tempfile::tempdir().unwrap();
+        let state = gateway(&temp);
+        let query = |token: &str| {
+            Query(HashMap::from([
+                ("hub.mode".to_string(), "subscribe".to_string()),
+                ("hub.verify_token".to_string(), token.to_string()),
+                ("hub.challenge".to_string(), "1158201444".to_string()),
+            ]))
+        };
+
+        let response = whatsapp_verify_handler(State(state.clone()), query("verify-me")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), 1024)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"1158201444");
+
+        let response = whatsapp_verify_handler(State(state), query("wrong")).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,314 @@
+//! Workflows waiting for a person to answer.
+//!
+//! A run that reaches a `WaitForInputNode` is saved to the suspended run
+//! store, its prompt goes to the conversation the run came from and to the
+//! UI, and the run continues when the person replies in that conversation,
+//! an operator answers with `workflow.reply`, or the wait times out.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::sync::RwLock;
+
+use openclaw_agents::workflow::{
+    InputOrigin, RunOutcome, SuspendedRun, WorkflowEngine, WorkflowError,
+};
+use openclaw_channels::OutboundContext;
+use openclaw_core::types::Message;
+
+use crate::events::UiEvent;
+use crate::rpc;
+use crate::server::GatewayState;
+
+/// How often timed-out waits are checked for.
+const TIMEOUT_SWEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Start registered workflow `workflow_id`. If it waits for input, the
+/// prompt goes to `origin` and replies there resume it.
+pub async fn start_workflow(
+    state: &Arc<RwLock<GatewayState>>,
+    workflow_id: &str,
+    input: serde_json::Value,
+    origin: Option<InputOrigin>,
+) -> Result<serde_json::Value, (i32, String)> {
+    let (registry, history) = {
+        let state = state.read().await;
+        (state.workflows.clone(), state.run_history.clone())
+    };
+    let workflow = registry
+        .get(workflow_id)
+        .cloned()
+        .ok_or_else(|| (rpc::NOT_FOUND, format!("Workflow not found: {workflow_id}")))?;
+    let outcome = WorkflowEngine::new()
+        .with_registry(registry)
+        .with_history(history)
+        .start(&workflow, input)
+        .await
+        .map_err(run_error)?;
+    settle(state, outcome, origin).await
+}
+
+/// Resume waiting run `run_id` with `reply`, or `None` for a timeout.
+pub async fn resume_run(
+    state: &Arc<RwLock<GatewayState>>,
+    run_id: &str,
+    reply: Option<serde_json::Value>,
+) -> Result<serde_json::Value, (i32, String)> {
+    let (registry, history, store, events) = {
+        let state = state.read().await;
+        (
+            state.workflows.clone(),
+            state.run_history.clone(),
+            state.waiting_runs.clone(),
+            state.events.clone(),
+        )
+    };
+    let not_found = || (rpc::NOT_FOUND, format!("No run waiting: {run_id}"));
+    let run = store
+        .load(run_id)
+        .map_err(run_error)?
+        .ok_or_else(not_found)?;
+    // Whoever removes the run resumes it; a second reply finds nothing
+    if !store.remove(run_id).map_err(run_error)? {
+        return Err(not_found());
+    }
+
+    let _ = events.broadcast(UiEvent::WorkflowInputResolved {
+        run_id: run.id.clone(),
+        timed_out: reply.is_none(),
+    });
+    let workflow = registry.get(&run.workflow_id).cloned().ok_or_else(|| {
+        (
+            rpc::NOT_FOUND,
+            format!("Workflow not found: {}", run.workflow_id),
+        )
+    })?;
+    let outcome = WorkflowEngine::new()
+        .with_registry(registry)
+        .with_history(history)
+        .resume(&workflow, &run, reply)
+        .await
+        .map_err(run_error)?;
+    settle(state, outcome, run.origin).await
+}
+
+/// Resume the oldest run waiting on `message`'s sender in `chat_id`, if
+/// any, with the message text.
+pub async fn resume_from_message(
+    state: &Arc<RwLock<GatewayState>>,
+    message: &Message,
+    chat_id: &str,
+) -> Option<Result<serde_json::Value, (i32, String)>> {
+    let store = state.read().await.waiting_runs.clone();
+    let runs = match store.list() {
+        Ok(runs) => runs,
+        Err(e) => {
+            tracing::warn!("Failed to list waiting workflow runs: {}", e);
+            return None;
+        }
+    };
+    let run = runs.into_iter().find(|run| {
+        run.origin.as_ref().is_some_and(|origin| {
+            origin.channel == message.channel.as_ref()
+                && origin.chat_id == chat_id
+                && origin.peer_id == message.peer_id.as_ref()
+        })
+    })?;
+    let reply = serde_json::Value::String(message.content.clone());
+    Some(resume_run(state, &run.id, Some(reply)).await)
+}
+
+/// Resume runs whose wait has timed out, forever.
+pub async fn run_input_timeouts(state: Arc<RwLock<GatewayState>>) {
+    let store = state.read().await.waiting_runs.clone();
+    let mut interval = tokio::time::interval(TIMEOUT_SWEEP_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+        let now = Utc::now();
+        let expired = match store.list() {
+            Ok(runs) => runs.into_iter().filter(|run| run.is_expired(now)),
+            Err(e) => {
+                tracing::warn!("Failed to list waiting workflow runs: {}", e);
+                continue;
+            }
+        };
+        for run in expired {
+            if let Err((_, e)) = resume_run(&state, &run.id, None).await {
+                tracing::warn!("Timed-out workflow run {} failed: {}", run.id, e);
+            }
+        }
+    }
+}
+
+/// Finish handling a run: report its output, or save it and ask for input.
+async fn settle(
+    state: &Arc<RwLock<GatewayState>>,
+    outcome: RunOutcome,
+    origin: Option<InputOrigin>,
+) -> Result<serde_json::Value, (i32, String)> {
+    let mut run = match outcome {
+        RunOutcome::Completed(output) => {
+            return Ok(serde_json::json!({ "status": "completed", "output": output }));
+        }
+        RunOutcome::Suspended(run) => run,
+    };
+    run.origin = origin;
+    let (store, events) = {
+        let state = state.read().await;
+        (state.waiting_runs.clone(), state.events.clone())
+    };
+    store.save(&run).map_err(run_error)?;
+
+    send_prompt(state, &run).await;
+    let _ = events.broadcast(UiEvent::WorkflowInputRequested {
+        run_id: run.id.clone(),
+        workflow_id: run.workflow_id.clone(),
+        node_id: run.node_id.clone(),
+        prompt: run.request.prompt.clone(),
+        choices: run.request.choices.clone(),
+        expires_at: run.expires_at,
+    });
+    Ok(serde_json::json!({
+        "status": "waiting",
+        "run_id": run.id,
+        "prompt": run.request.prompt,
+        "choices": run.request.choices,
+        "expires_at": run.expires_at,
+    }))
+}
+
+/// Send the run's prompt to the conversation it came from.
+async fn send_prompt(state: &Arc<RwLock<GatewayState>>, run: &SuspendedRun) {
+    let Some(origin) = &run.origin else {
+        return;
+    };
+    let (channel, outbound) = {
+        let state = state.read().await;
+        let channel = state
+            .channels
+            .read()
+            .await
+            .get_outbound(&origin.channel)
+            .cloned();
+        (channel, state.outbound.clone())
+    };
+    let Some(channel) = channel else {
+        tracing::warn!(
+            "Workflow run {} waits on unknown channel {}",
+            run.id,
+            origin.channel
+        );
+        return;
+    };
+
+    let text = if run.request.choices.is_empty() {
+        run.request.prompt.clone()
+    } else {
+        format!(
+            "{} ({})",
+            run.request.prompt,
+            run.request.choices.join(" / ")
+        )
+    };
+    let ctx = OutboundContext {
+        chat_id: origin.chat_id.clone(),
+        reply_to: None,
+        thread_id: None,
+    };
+    if let Err(e) = outbound.send(channel.as_ref(), ctx, &text).await {
+        tracing::warn!("Failed to send workflow prompt for {}: {}", run.id, e);
+    }
+}
+
+fn run_error(e: WorkflowError) -> (i32, String) {
+    match e {
+        WorkflowError::WorkflowNotFound(_) => (rpc::NOT_FOUND, e.to_string()),
+        e => (rpc::INTERNAL_ERROR, format!("Workflow error: {e}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openclaw_agents::workflow::testing::StubNode;
+    use openclaw_agents::workflow::{RunStatus, WaitForInputNode, Workflow};
+    use openclaw_channels::ChannelInbound;
+    use openclaw_channels::mock::{MockAction, MockChannel, MockInbound};
+    use openclaw_core::events::EventStore;
+
+    use super::*;
+    use crate::server::{GatewayBuilder, GatewayConfig};
+
+    #[tokio::test]
+    async fn test_prompt_and_resume_from_message() {
+        let temp = tempfile::tempdir().unwrap();
+        let done = Arc::new(StubNode::new("done"));
+        let mut workflow = Workflow::new("deploy", "Deploy", "ask");
+        workflow.add_node(Arc::new(
+            WaitForInputNode::new("ask", "Deploy now?").with_choices(["yes", "no"]),
+        ));
+        workflow.add_node(done.clone());
+        workflow.add_conditional_edge("ask", "done", "yes");
+
+        let channel = Arc::new(MockChannel::new());
+        let gateway = GatewayBuilder::new()
+            .with_config(GatewayConfig {
+                data_dir: temp.path().join("gateway"),
+                ..GatewayConfig::default()
+            })
+            .with_event_store(Arc::new(
+                EventStore::open(&temp.path().join("events")).unwrap(),
+            ))
+            .with_workflow("deploy", Arc::new(workflow))
+            .with_mock_channel(channel.clone())
+            .build()
+            .unwrap();
+        let state = gateway.state();
+        state
+            .read()
+            .await
+            .channels
+            .write()
+            .await
+            .register_outbound(channel.clone());
+
+        let origin = InputOrigin {
+            channel: "mock".to_string(),
+            chat_id: "alice".to_string(),
+            peer_id: "alice".to_string(),
+        };
+        let started = start_workflow(state, "deploy", serde_json::json!({}), Some(origin))
+            .await
+            .unwrap();
+        assert_eq!(started["status"], "waiting");
+        assert_eq!(
+            channel.outbound()[0].action,
+            MockAction::Text {
+                text: "Deploy now? (yes / no)".to_string()
+            }
+        );
+
+        let other = channel.normalize(MockInbound::text("bob", "yes")).unwrap();
+        assert!(resume_from_message(state, &other, "bob").await.is_none());
+
+        let reply = channel
+            .normalize(MockInbound::text("alice", "yes"))
+            .unwrap();
+        let resumed = resume_from_message(state, &reply, "alice")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(resumed["status"], "completed");
+        assert_eq!(done.calls()[0].input["reply"], "yes");
+
+        let run_id = started["run_id"].as_str().unwrap();
+        assert!(resume_run(state, run_id, None).await.is_err());
+
+        let history = state.read().await.run_history.clone();
+        let record = history.load(run_id).unwrap().unwrap();
+        assert_eq!(record.status, RunStatus::Completed);
+        assert_eq!(record.steps.len(), 2);
+    }
+}
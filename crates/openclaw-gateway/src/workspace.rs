@@ -0,0 +1,323 @@
+//! Read-only browsing of agent and session workspaces.
+//!
+//! Lets the dashboard show artifacts an agent produced without shell access
+//! to the server. All paths are relative to a workspace root and confined to
+//! it, including through symlinks.
+
+use std::path::{Component, Path, PathBuf};
+
+use base64::Engine;
+use serde::Serialize;
+use thiserror::Error;
+
+use openclaw_core::validation::{limits, validate_path};
+
+/// Default cap on bytes returned by a read.
+pub const DEFAULT_READ_LIMIT: usize = 256 * 1024;
+
+/// Workspace access errors.
+#[derive(Error, Debug)]
+pub enum WorkspaceError {
+    /// Path escapes the workspace or is malformed.
+    #[error("Invalid path: {0}")]
+    InvalidPath(String),
+
+    /// File or directory does not exist.
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    /// File exceeds the download limit.
+    #[error("File too large ({size} bytes, max {max})")]
+    TooLarge {
+        /// File size.
+        size: u64,
+        /// Limit.
+        max: u64,
+    },
+
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A directory entry.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceEntry {
+    /// File name.
+    pub name: String,
+    /// Path relative to the workspace root.
+    pub path: String,
+    /// Whether this is a directory.
+    pub is_dir: bool,
+    /// Size in bytes (0 for directories).
+    pub size: u64,
+    /// Last modification time.
+    pub modified: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// File contents returned to the UI.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceFile {
+    /// Path relative to the workspace root.
+    pub path: String,
+    /// Full file size in bytes.
+    pub size: u64,
+    /// Guessed MIME type.
+    pub mime_type: String,
+    /// `"utf8"` or `"base64"`.
+    pub encoding: &'static str,
+    /// File content (possibly truncated).
+    pub content: String,
+    /// Whether `content` was cut at the read limit.
+    pub truncated: bool,
+}
+
+/// A workspace directory confined to its root.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    root: PathBuf,
+}
+
+impl Workspace {
+    /// Workspace for an agent.
+    #[must_use]
+    pub fn for_agent(base: &Path, agent_id: &str) -> Self {
+        Self {
+            root: openclaw_agents::Workspace::for_agent(base, agent_id)
+                .root()
+                .to_path_buf(),
+        }
+    }
+
+    /// Workspace for a session, nested under its agent's workspace.
+    #[must_use]
+    pub fn for_session(base: &Path, agent_id: &str, session_key: &str) -> Self {
+        Self {
+            root: openclaw_agents::Workspace::for_session(base, agent_id, session_key)
+                .root()
+                .to_path_buf(),
+        }
+    }
+
+    /// Workspace root.
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Resolve a relative path inside the workspace.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidPath` if the path is absolute, contains traversal,
+    /// or resolves (via symlinks) outside the root.
+    pub fn resolve(&self, relative: &str) -> Result<PathBuf, WorkspaceError> {
+        validate_path(relative).map_err(|_| WorkspaceError::InvalidPath(relative.to_string()))?;
+
+        let relative_path = Path::new(relative.trim_start_matches("./"));
+        if relative_path
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(WorkspaceError::InvalidPath(relative.to_string()));
+        }
+
+        let root = self
+            .root
+            .canonicalize()
+            .map_err(|_| WorkspaceError::NotFound(relative.to_string()))?;
+        let target = root
+            .join(relative_path)
+            .canonicalize()
+            .map_err(|_| WorkspaceError::NotFound(relative.to_string()))?;
+
+        if !target.starts_with(&root) {
+            return Err(WorkspaceError::InvalidPath(relative.to_string()));
+        }
+        Ok(target)
+    }
+
+    fn relative(&self, path: &Path) -> String {
+        self.root
+            .canonicalize()
+            .ok()
+            .and_then(|root| path.strip_prefix(root).ok().map(Path::to_path_buf))
+            .unwrap_or_default()
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    /// List a directory, directories first then by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the path is invalid or not a directory.
+    pub fn list(&self, relative: &str) -> Result<Vec<WorkspaceEntry>, WorkspaceError> {
+        // An agent that has not written anything yet has an empty workspace
+        if relative.is_empty() && !self.root.exists() {
+            return Ok(Vec::new());
+        }
+
+        let dir = self.resolve(relative)?;
+        if !dir.is_dir() {
+            return Err(WorkspaceError::NotFound(relative.to_string()));
+        }
+
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            if entry.file_name() == openclaw_agents::workspace::MARKER_FILE {
+                continue;
+            }
+            let meta = entry.metadata()?;
+            entries.push(WorkspaceEntry {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                path: self.relative(&entry.path()),
+                is_dir: meta.is_dir(),
+                size: if meta.is_dir() { 0 } else { meta.len() },
+                modified: meta.modified().ok().map(chrono::DateTime::from),
+            });
+        }
+
+        entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+        Ok(entries)
+    }
+
+    /// Read up to `max_bytes` of a file.
+    ///
+    /// Text files are returned as UTF-8, anything else as base64.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the path is invalid or not a file.
+    pub fn read(&self, relative: &str, max_bytes: usize) -> Result<WorkspaceFile, WorkspaceError> {
+        use std::io::Read;
+
+        let path = self.resolve(relative)?;
+        if !path.is_file() {
+            return Err(WorkspaceError::NotFound(relative.to_string()));
+        }
+
+        let size = std::fs::metadata(&path)?.len();
+        let mut data = Vec::new();
+        std::fs::File::open(&path)?
+            .take(max_bytes as u64)
+            .read_to_end(&mut data)?;
+
+        Ok(self.encode(&path, size, data))
+    }
+
+    /// Read a whole file for download, up to the attachment size limit.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TooLarge` if the file exceeds `limits::MAX_ATTACHMENT_SIZE`.
+    pub fn download(&self, relative: &str) -> Result<WorkspaceFile, WorkspaceError> {
+        let path = self.resolve(relative)?;
+        if !path.is_file() {
+            return Err(WorkspaceError::NotFound(relative.to_string()));
+        }
+
+        let size = std::fs::metadata(&path)?.len();
+        let max = limits::MAX_ATTACHMENT_SIZE as u64;
+        if size > max {
+            return Err(WorkspaceError::TooLarge { size, max });
+        }
+
+        let data = std::fs::read(&path)?;
+        let mut file = self.encode(&path, size, data.clone());
+        // Downloads are always base64 so the client can save bytes verbatim
+        if file.encoding == "utf8" {
+            file.encoding = "base64";
+            file.content = base64::engine::general_purpose::STANDARD.encode(data);
+        }
+        Ok(file)
+    }
+
+    fn encode(&self, path: &Path, size: u64, data: Vec<u8>) -> WorkspaceFile {
+        let truncated = (data.len() as u64) < size;
+        let mime_type = mime_guess::from_path(path)
+            .first_or_octet_stream()
+            .essence_str()
+            .to_string();
+
+        let (encoding, content) = match String::from_utf8(data) {
+            Ok(text) => ("utf8", text),
+            Err(e) => (
+                "base64",
+                base64::engine::general_purpose::STANDARD.encode(e.into_bytes()),
+            ),
+        };
+
+        WorkspaceFile {
+            path: self.relative(path),
+            size,
+            mime_type,
+            encoding,
+            content,
+            truncated,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn workspace() -> (Workspace, tempfile::TempDir) {
+        let temp = tempdir().unwrap();
+        let ws = Workspace::for_agent(temp.path(), "default");
+        std::fs::create_dir_all(ws.root().join("reports")).unwrap();
+        std::fs::write(ws.root().join("reports/summary.txt"), "# Summary").unwrap();
+        std::fs::write(ws.root().join("chart.png"), [0x89, b'P', b'N', b'G', 0xff]).unwrap();
+        (ws, temp)
+    }
+
+    #[test]
+    fn test_list_and_read() {
+        let (ws, _temp) = workspace();
+
+        let entries = ws.list("").unwrap();
+        assert_eq!(entries[0].name, "reports");
+        assert!(entries[0].is_dir);
+        assert_eq!(entries[1].path, "chart.png");
+
+        let file = ws.read("reports/summary.txt", DEFAULT_READ_LIMIT).unwrap();
+        assert_eq!(file.encoding, "utf8");
+        assert_eq!(file.content, "# Summary");
+        assert_eq!(file.mime_type, "text/plain");
+
+        let file = ws.read("chart.png", 2).unwrap();
+        assert!(file.truncated);
+        assert_eq!(file.size, 5);
+
+        let file = ws.download("reports/summary.txt").unwrap();
+        assert_eq!(file.encoding, "base64");
+    }
+
+    #[test]
+    fn test_confinement() {
+        let (ws, temp) = workspace();
+        std::fs::write(temp.path().join("secret.txt"), "nope").unwrap();
+
+        assert!(matches!(
+            ws.read("../secret.txt", 10),
+            Err(WorkspaceError::InvalidPath(_))
+        ));
+        assert!(matches!(
+            ws.read("/etc/passwd", 10),
+            Err(WorkspaceError::InvalidPath(_))
+        ));
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(temp.path().join("secret.txt"), ws.root().join("link"))
+                .unwrap();
+            assert!(matches!(
+                ws.read("link", 10),
+                Err(WorkspaceError::InvalidPath(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,343 @@
+//! Daemon control over IPC.
+//!
+//! A running gateway listens on a local reply socket for control commands
+//! (status, config reload, channel probe and start/stop, shutdown). Access is governed by
+//! filesystem permissions on the socket rather than HTTP auth, so the socket
+//! lives in the user's state directory.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use thiserror::Error;
+
+use crate::messages::{IpcMessage, IpcPayload, IpcRequest};
+use crate::transport::{IpcTransport, TransportError};
+
+/// How often the server wakes up from a blocking receive.
+const SERVER_POLL: Duration = Duration::from_secs(1);
+
+/// Control errors.
+#[derive(Error, Debug)]
+pub enum ControlError {
+    /// Transport failure (daemon not running, timeout, ...).
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+
+    /// The daemon could not carry out the command.
+    #[error("Daemon error: {0}")]
+    Remote(String),
+
+    /// The request is not a known control command.
+    #[error("Unknown control command: {0}")]
+    UnknownCommand(String),
+
+    /// The command parameters are invalid.
+    #[error("Invalid parameters: {0}")]
+    InvalidParams(String),
+
+    /// The daemon replied with something other than a response.
+    #[error("Unexpected reply from daemon")]
+    UnexpectedReply,
+}
+
+/// Commands understood by the daemon control socket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlCommand {
+    /// Report process and gateway status.
+    Status,
+    /// Re-read the config file and apply settings that can change live.
+    ReloadConfig,
+    /// Probe one channel, or all channels if `None`.
+    ProbeChannels(Option<String>),
+    /// Start a stopped channel, or restart one that is backing off.
+    StartChannel(String),
+    /// Stop a channel until it is started again.
+    StopChannel(String),
+    /// Enable and start a channel, saving the setting in the config file.
+    EnableChannel(String),
+    /// Stop a channel and keep it stopped across restarts.
+    DisableChannel(String),
+    /// Stop the gateway.
+    Shutdown,
+}
+
+impl ControlCommand {
+    /// IPC method name for this command.
+    #[must_use]
+    pub const fn method(&self) -> &'static str {
+        match self {
+            Self::Status => "daemon.status",
+            Self::ReloadConfig => "daemon.reload",
+            Self::ProbeChannels(_) => "daemon.probe",
+            Self::StartChannel(_) => "daemon.channel.start",
+            Self::StopChannel(_) => "daemon.channel.stop",
+            Self::EnableChannel(_) => "daemon.channel.enable",
+            Self::DisableChannel(_) => "daemon.channel.disable",
+            Self::Shutdown => "daemon.shutdown",
+        }
+    }
+
+    /// Build the IPC request message for this command.
+    #[must_use]
+    pub fn to_message(&self) -> IpcMessage {
+        let params = match self {
+            Self::ProbeChannels(Some(channel))
+            | Self::StartChannel(channel)
+            | Self::StopChannel(channel)
+            | Self::EnableChannel(channel)
+            | Self::DisableChannel(channel) => serde_json::json!({ "channel_id": channel }),
+            _ => serde_json::json!({}),
+        };
+        IpcMessage::request(self.method(), params)
+    }
+
+    /// Parse a command from an IPC request.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnknownCommand` for unrecognized methods or `InvalidParams`
+    /// for malformed parameters.
+    pub fn from_request(request: &IpcRequest) -> Result<Self, ControlError> {
+        match request.method.as_str() {
+            "daemon.status" => Ok(Self::Status),
+            "daemon.reload" => Ok(Self::ReloadConfig),
+            "daemon.probe" => match request.params.get("channel_id") {
+                None | Some(serde_json::Value::Null) => Ok(Self::ProbeChannels(None)),
+                Some(serde_json::Value::String(id)) => Ok(Self::ProbeChannels(Some(id.clone()))),
+                Some(_) => Err(ControlError::InvalidParams(
+                    "channel_id must be a string".to_string(),
+                )),
+            },
+            "daemon.channel.start" => channel_id(request).map(Self::StartChannel),
+            "daemon.channel.stop" => channel_id(request).map(Self::StopChannel),
+            "daemon.channel.enable" => channel_id(request).map(Self::EnableChannel),
+            "daemon.channel.disable" => channel_id(request).map(Self::DisableChannel),
+            "daemon.shutdown" => Ok(Self::Shutdown),
+            other => Err(ControlError::UnknownCommand(other.to_string())),
+        }
+    }
+}
+
+/// The required `channel_id` parameter.
+fn channel_id(request: &IpcRequest) -> Result<String, ControlError> {
+    request
+        .params
+        .get("channel_id")
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| ControlError::InvalidParams("channel_id is required".to_string()))
+}
+
+/// Daemon-side handler for control commands.
+#[async_trait]
+pub trait ControlHandler: Send + Sync {
+    /// Execute a command and return its result.
+    async fn handle(&self, command: ControlCommand) -> Result<serde_json::Value, String>;
+}
+
+/// Daemon-side control socket.
+pub struct ControlServer {
+    transport: IpcTransport,
+}
+
+impl ControlServer {
+    /// Listen for control commands on `address`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the socket cannot be bound (e.g., another daemon is
+    /// already listening).
+    pub fn bind(address: &str) -> Result<Self, ControlError> {
+        let socket_dir = address
+            .strip_prefix("ipc://")
+            .and_then(|path| std::path::Path::new(path).parent());
+        if let Some(dir) = socket_dir {
+            std::fs::create_dir_all(dir).map_err(|e| TransportError::Socket(e.to_string()))?;
+        }
+
+        Ok(Self {
+            transport: IpcTransport::new_server_with_timeout(address, SERVER_POLL)?,
+        })
+    }
+
+    /// Serve commands until a shutdown command has been answered or the
+    /// socket closes.
+    pub async fn serve(self, handler: Arc<dyn ControlHandler>) {
+        tracing::info!("Control socket listening on {}", self.transport.address());
+
+        loop {
+            let message = match self.transport.recv_async().await {
+                Ok(message) => message,
+                Err(TransportError::Timeout) => continue,
+                Err(TransportError::Closed) => break,
+                Err(e) => {
+                    tracing::warn!("Control socket receive failed: {}", e);
+                    continue;
+                }
+            };
+
+            let IpcPayload::Request(request) = &message.payload else {
+                let reply = IpcMessage::error(&message.id, "Expected a request");
+                if let Err(e) = self.transport.send_async(&reply).await {
+                    tracing::warn!("Control socket send failed: {}", e);
+                }
+                continue;
+            };
+
+            let command = ControlCommand::from_request(request);
+            let shutdown = matches!(command, Ok(ControlCommand::Shutdown));
+            let reply = match command {
+                Ok(command) => match handler.handle(command).await {
+                    Ok(result) => IpcMessage::success(&message.id, result),
+                    Err(e) => IpcMessage::error(&message.id, e),
+                },
+                Err(e) => IpcMessage::error(&message.id, e.to_string()),
+            };
+
+            if let Err(e) = self.transport.send_async(&reply).await {
+                tracing::warn!("Control socket send failed: {}", e);
+            }
+            if shutdown {
+                break;
+            }
+        }
+    }
+}
+
+/// CLI-side control client.
+pub struct ControlClient {
+    transport: IpcTransport,
+}
+
+impl ControlClient {
+    /// Connect to a daemon's control socket.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if nothing is listening on `address`.
+    pub fn connect(address: &str, timeout: Duration) -> Result<Self, ControlError> {
+        Ok(Self {
+            transport: IpcTransport::new_client(address, timeout)?,
+        })
+    }
+
+    /// Send a command and wait for its result.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the request fails or the daemon reports an error.
+    pub async fn call(&self, command: &ControlCommand) -> Result<serde_json::Value, ControlError> {
+        let reply = self.transport.request_async(&command.to_message()).await?;
+        match reply.payload {
+            IpcPayload::Response(response) if response.success => {
+                Ok(response.result.unwrap_or(serde_json::Value::Null))
+            }
+            IpcPayload::Response(response) => Err(ControlError::Remote(
+                response
+                    .error
+                    .unwrap_or_else(|| "Unknown error".to_string()),
+            )),
+            _ => Err(ControlError::UnexpectedReply),
+        }
+    }
+}
+
+/// Default control socket address.
+#[must_use]
+pub fn default_control_address() -> String {
+    if cfg!(unix) {
+        format!(
+            "ipc://{}",
+            openclaw_core::Config::state_dir()
+                .join("control.ipc")
+                .display()
+        )
+    } else {
+        "tcp://127.0.0.1:18791".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_roundtrip() {
+        for command in [
+            ControlCommand::Status,
+            ControlCommand::ReloadConfig,
+            ControlCommand::ProbeChannels(None),
+            ControlCommand::ProbeChannels(Some("telegram".to_string())),
+            ControlCommand::StartChannel("telegram".to_string()),
+            ControlCommand::StopChannel("discord".to_string()),
+            ControlCommand::EnableChannel("slack".to_string()),
+            ControlCommand::DisableChannel("slack".to_string()),
+            ControlCommand::Shutdown,
+        ] {
+            let IpcPayload::Request(request) = command.to_message().payload else {
+                panic!("Expected request payload");
+            };
+            assert_eq!(ControlCommand::from_request(&request).unwrap(), command);
+        }
+    }
+
+    #[test]
+    fn test_unknown_command() {
+        let request = IpcRequest {
+            method: "daemon.explode".to_string(),
+            params: serde_json::json!({}),
+        };
+        assert!(matches!(
+            ControlCommand::from_request(&request),
+            Err(ControlError::UnknownCommand(_))
+        ));
+    }
+
+    #[test]
+    fn test_channel_command_requires_id() {
+        let request = IpcRequest {
+            method: "daemon.channel.stop".to_string(),
+            params: serde_json::json!({}),
+        };
+        assert!(matches!(
+            ControlCommand::from_request(&request),
+            Err(ControlError::InvalidParams(_))
+        ));
+    }
+
+    struct EchoHandler;
+
+    #[async_trait]
+    impl ControlHandler for EchoHandler {
+        async fn handle(&self, command: ControlCommand) -> Result<serde_json::Value, String> {
+            match command {
+                ControlCommand::ReloadConfig => Err("Config is invalid".to_string()),
+                other => Ok(serde_json::json!({ "method": other.method() })),
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires actual IPC setup"]
+    async fn test_client_server() {
+        let dir = tempfile::tempdir().unwrap();
+        let address = format!("ipc://{}", dir.path().join("control.ipc").display());
+
+        let server = ControlServer::bind(&address).unwrap();
+        let serving = tokio::spawn(server.serve(Arc::new(EchoHandler)));
+
+        let client = ControlClient::connect(&address, Duration::from_secs(5)).unwrap();
+        let status = client.call(&ControlCommand::Status).await.unwrap();
+        assert_eq!(status["method"], "daemon.status");
+
+        let err = client
+            .call(&ControlCommand::ReloadConfig)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ControlError::Remote(_)));
+
+        client.call(&ControlCommand::Shutdown).await.unwrap();
+        serving.await.unwrap();
+    }
+}
@@ -7,8 +7,10 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
+pub mod control;
 pub mod messages;
 pub mod transport;
 
+pub use control::{ControlClient, ControlCommand, ControlError, ControlHandler, ControlServer};
 pub use messages::{IpcMessage, IpcRequest, IpcResponse};
 pub use transport::IpcTransport;
@@ -7,8 +7,8 @@ use serde::{Deserialize, Serialize};
 use std::pin::Pin;
 
 use crate::traits::{
-    ChunkType, CompletionRequest, CompletionResponse, ContentBlock, MessageContent, Provider,
-    ProviderError, Role, StopReason, StreamingChunk,
+    ChunkType, CompletionRequest, CompletionResponse, ContentBlock, ImageSource, MessageContent,
+    Provider, ProviderError, ResponseFormat, Role, StopReason, StreamingChunk,
 };
 use openclaw_core::secrets::ApiKey;
 use openclaw_core::types::TokenUsage;
@@ -16,6 +16,9 @@ use openclaw_core::types::TokenUsage;
 const ANTHROPIC_VERSION: &str = "2023-06-01";
 const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
 
+/// Tool the model is made to call when the reply must follow a schema.
+const STRUCTURED_OUTPUT_TOOL: &str = "structured_output";
+
 /// Anthropic API provider.
 pub struct AnthropicProvider {
     client: Client,
@@ -66,7 +69,7 @@ impl AnthropicProvider {
             })
             .collect();
 
-        let tools = request.tools.as_ref().map(|tools| {
+        let mut tools: Option<Vec<AnthropicTool>> = request.tools.as_ref().map(|tools| {
             tools
                 .iter()
                 .map(|t| AnthropicTool {
@@ -77,6 +80,31 @@ impl AnthropicProvider {
                 .collect()
         });
 
+        // Anthropic has no JSON mode; force a call to a tool taking the
+        // schema as its input instead
+        if let Some(format) = &request.response_format {
+            let input_schema = if wraps_schema(&format.schema) {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": { "value": format.schema },
+                    "required": ["value"],
+                })
+            } else {
+                format.schema.clone()
+            };
+            tools.get_or_insert_with(Vec::new).push(AnthropicTool {
+                name: STRUCTURED_OUTPUT_TOOL.to_string(),
+                description: format!("Reply with {} as this tool's input.", format.name),
+                input_schema,
+            });
+        }
+        let tool_choice = request.response_format.as_ref().map(|_| {
+            serde_json::json!({
+                "type": "tool",
+                "name": STRUCTURED_OUTPUT_TOOL,
+            })
+        });
+
         AnthropicRequest {
             model: request.model.clone(),
             messages,
@@ -85,17 +113,50 @@ impl AnthropicProvider {
             temperature: Some(request.temperature),
             stop_sequences: request.stop.clone(),
             tools,
+            tool_choice,
             stream: Some(false),
         }
     }
 }
 
+/// Whether a schema is wrapped in an object to be a tool input, which
+/// must be an object.
+fn wraps_schema(schema: &serde_json::Value) -> bool {
+    schema.get("type").and_then(serde_json::Value::as_str) != Some("object")
+}
+
+/// Replace the forced structured output tool call with its input as
+/// the reply's text.
+fn structured_reply(response: &mut CompletionResponse, format: &ResponseFormat) {
+    let input = response.content.iter().find_map(|block| match block {
+        ContentBlock::ToolUse { name, input, .. } if name == STRUCTURED_OUTPUT_TOOL => Some(input),
+        _ => None,
+    });
+    let Some(input) = input else {
+        return;
+    };
+    let data = if wraps_schema(&format.schema) {
+        input.get("value").unwrap_or(&serde_json::Value::Null)
+    } else {
+        input
+    };
+    response.content = vec![ContentBlock::Text {
+        text: data.to_string(),
+    }];
+    response.stop_reason = Some(StopReason::EndTurn);
+}
+
 #[async_trait]
 impl Provider for AnthropicProvider {
     fn name(&self) -> &'static str {
         "anthropic"
     }
 
+    fn supports_vision(&self, model: &str) -> bool {
+        // Every model since Claude 3 accepts images
+        !model.starts_with("claude-2") && !model.starts_with("claude-instant")
+    }
+
     async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
         Ok(vec![
             "claude-sonnet-4-20250514".to_string(),
@@ -124,27 +185,15 @@ impl Provider for AnthropicProvider {
             .await?;
 
         if !response.status().is_success() {
-            let status = response.status().as_u16();
-
-            // Check for rate limiting
-            if status == 429 {
-                let retry_after = response
-                    .headers()
-                    .get("retry-after")
-                    .and_then(|v| v.to_str().ok())
-                    .and_then(|v| v.parse().ok())
-                    .unwrap_or(60);
-                return Err(ProviderError::RateLimited {
-                    retry_after_secs: retry_after,
-                });
-            }
-
-            let message = response.text().await.unwrap_or_default();
-            return Err(ProviderError::Api { status, message });
+            return Err(ProviderError::from_http(response).await);
         }
 
         let result: AnthropicResponse = response.json().await?;
-        Ok(result.into())
+        let mut response: CompletionResponse = result.into();
+        if let Some(format) = &request.response_format {
+            structured_reply(&mut response, format);
+        }
+        Ok(response)
     }
 
     async fn complete_stream(
@@ -169,9 +218,7 @@ impl Provider for AnthropicProvider {
             .await?;
 
         if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let message = response.text().await.unwrap_or_default();
-            return Err(ProviderError::Api { status, message });
+            return Err(ProviderError::from_http(response).await);
         }
 
         let stream = response.bytes_stream().map(move |result| match result {
@@ -229,6 +276,8 @@ struct AnthropicRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<AnthropicTool>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     stream: Option<bool>,
 }
 
@@ -266,11 +315,10 @@ enum AnthropicContentBlock {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct ImageSourceApi {
-    #[serde(rename = "type")]
-    source_type: String,
-    media_type: String,
-    data: String,
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ImageSourceApi {
+    Base64 { media_type: String, data: String },
+    Url { url: String },
 }
 
 #[derive(Debug, Serialize)]
@@ -323,10 +371,12 @@ impl From<ContentBlock> for AnthropicContentBlock {
         match block {
             ContentBlock::Text { text } => Self::Text { text },
             ContentBlock::Image { source } => Self::Image {
-                source: ImageSourceApi {
-                    source_type: source.source_type,
-                    media_type: source.media_type,
-                    data: source.data,
+                source: match source.url {
+                    Some(url) if source.source_type == "url" => ImageSourceApi::Url { url },
+                    _ => ImageSourceApi::Base64 {
+                        media_type: source.media_type,
+                        data: source.data,
+                    },
                 },
             },
             ContentBlock::ToolUse { id, name, input } => Self::ToolUse { id, name, input },
@@ -347,10 +397,11 @@ impl From<AnthropicContentBlock> for ContentBlock {
         match block {
             AnthropicContentBlock::Text { text } => Self::Text { text },
             AnthropicContentBlock::Image { source } => Self::Image {
-                source: crate::traits::ImageSource {
-                    source_type: source.source_type,
-                    media_type: source.media_type,
-                    data: source.data,
+                source: match source {
+                    ImageSourceApi::Base64 { media_type, data } => {
+                        ImageSource::base64(media_type, data)
+                    }
+                    ImageSourceApi::Url { url } => ImageSource::url(url),
                 },
             },
             AnthropicContentBlock::ToolUse { id, name, input } => Self::ToolUse { id, name, input },
@@ -434,6 +485,7 @@ mod tests {
             temperature: 0.7,
             stop: None,
             tools: None,
+            response_format: None,
         };
 
         let anthropic_req = provider.to_anthropic_request(&request);
@@ -441,4 +493,73 @@ mod tests {
         assert_eq!(anthropic_req.messages.len(), 1);
         assert_eq!(anthropic_req.system, Some("You are helpful".to_string()));
     }
+
+    #[test]
+    fn test_structured_output_tool() {
+        let provider = AnthropicProvider::new(ApiKey::new("test".to_string()));
+        let format = ResponseFormat::new("tags", serde_json::json!({ "type": "array" }));
+        let request = CompletionRequest {
+            model: "claude-3-5-sonnet-20241022".to_string(),
+            messages: vec![Message {
+                role: Role::User,
+                content: MessageContent::Text("Tag this".to_string()),
+            }],
+            system: None,
+            max_tokens: 1024,
+            temperature: 0.0,
+            stop: None,
+            tools: None,
+            response_format: Some(format.clone()),
+        };
+
+        let anthropic_req = provider.to_anthropic_request(&request);
+        let tool = &anthropic_req.tools.unwrap()[0];
+        assert_eq!(tool.name, STRUCTURED_OUTPUT_TOOL);
+        assert_eq!(tool.input_schema["properties"]["value"]["type"], "array");
+        assert_eq!(
+            anthropic_req.tool_choice.unwrap()["name"],
+            STRUCTURED_OUTPUT_TOOL
+        );
+
+        let mut response = CompletionResponse {
+            id: "msg_1".to_string(),
+            model: "claude-3-5-sonnet-20241022".to_string(),
+            content: vec![ContentBlock::ToolUse {
+                id: "toolu_1".to_string(),
+                name: STRUCTURED_OUTPUT_TOOL.to_string(),
+                input: serde_json::json!({ "value": ["billing"] }),
+            }],
+            stop_reason: Some(StopReason::ToolUse),
+            usage: TokenUsage::default(),
+        };
+        structured_reply(&mut response, &format);
+        assert!(matches!(
+            &response.content[..],
+            [ContentBlock::Text { text }] if text == r#"["billing"]"#
+        ));
+        assert_eq!(response.stop_reason, Some(StopReason::EndTurn));
+    }
+
+    #[test]
+    fn test_image_sources() {
+        let inline = serde_json::to_value(AnthropicContentBlock::from(ContentBlock::Image {
+            source: ImageSource::base64("image/png", "aGVsbG8="),
+        }))
+        .unwrap();
+        assert_eq!(inline["source"]["type"], "base64");
+        assert_eq!(inline["source"]["media_type"], "image/png");
+
+        let linked = serde_json::to_value(AnthropicContentBlock::from(ContentBlock::Image {
+            source: ImageSource::url("https://example.com/cat.jpg"),
+        }))
+        .unwrap();
+        assert_eq!(
+            linked["source"],
+            serde_json::json!({"type": "url", "url": "https://example.com/cat.jpg"})
+        );
+
+        let provider = AnthropicProvider::new(ApiKey::new("test".to_string()));
+        assert!(provider.supports_vision("claude-3-5-sonnet-20241022"));
+        assert!(!provider.supports_vision("claude-2.1"));
+    }
 }
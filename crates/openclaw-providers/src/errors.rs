@@ -0,0 +1,258 @@
+//! Provider error classification.
+//!
+//! Maps HTTP error responses from Anthropic- and `OpenAI`-style APIs onto
+//! [`ProviderError`] variants, and decides which errors are worth retrying.
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::traits::ProviderError;
+
+/// Coarse category of a [`ProviderError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderErrorKind {
+    /// Too many requests (HTTP 429).
+    RateLimited,
+    /// Provider temporarily overloaded.
+    Overloaded,
+    /// Other server-side error (HTTP 5xx).
+    Server,
+    /// Connection failure or timeout.
+    Network,
+    /// Invalid or unauthorized credentials.
+    Auth,
+    /// Request exceeds the model's context window.
+    ContextTooLong,
+    /// Blocked by content filtering.
+    ContentFiltered,
+    /// Other request rejected by the provider (HTTP 4xx).
+    InvalidRequest,
+    /// Local serialization or configuration error.
+    Other,
+}
+
+impl ProviderErrorKind {
+    /// Stable name for logs and metrics.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::RateLimited => "rate_limited",
+            Self::Overloaded => "overloaded",
+            Self::Server => "server",
+            Self::Network => "network",
+            Self::Auth => "auth",
+            Self::ContextTooLong => "context_too_long",
+            Self::ContentFiltered => "content_filtered",
+            Self::InvalidRequest => "invalid_request",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl std::fmt::Display for ProviderErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl ProviderError {
+    /// Classify an HTTP error response.
+    ///
+    /// `body` may be an Anthropic (`{"error": {"type", "message"}}`) or
+    /// `OpenAI` (`{"error": {"code", "type", "message"}}`) error payload, or
+    /// arbitrary text.
+    #[must_use]
+    pub fn from_response(status: u16, retry_after_secs: Option<u64>, body: &str) -> Self {
+        let detail = ErrorDetail::parse(body);
+        let message = detail.message.clone();
+
+        // Exhausted quota also comes back as 429 but will not clear by waiting
+        if detail.is("insufficient_quota") {
+            return Self::Api { status, message };
+        }
+
+        match status {
+            401 | 403 => Self::Auth(message),
+            429 => Self::RateLimited { retry_after_secs },
+            503 | 529 => Self::Overloaded {
+                message,
+                retry_after_secs,
+            },
+            _ if detail.is("overloaded_error") => Self::Overloaded {
+                message,
+                retry_after_secs,
+            },
+            _ if detail.is("context_length_exceeded") || detail.mentions_context_limit() => {
+                Self::ContextTooLong(message)
+            }
+            _ if detail.is("content_filter") || detail.is("content_policy_violation") => {
+                Self::ContentFiltered(message)
+            }
+            _ => Self::Api { status, message },
+        }
+    }
+
+    /// Read the status, `retry-after` header, and body of a failed response
+    /// and classify it.
+    pub(crate) async fn from_http(response: reqwest::Response) -> Self {
+        let status = response.status().as_u16();
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse().ok());
+        let body = response.text().await.unwrap_or_default();
+        Self::from_response(status, retry_after, &body)
+    }
+
+    /// Category of this error.
+    #[must_use]
+    pub const fn kind(&self) -> ProviderErrorKind {
+        match self {
+            Self::RateLimited { .. } => ProviderErrorKind::RateLimited,
+            Self::Overloaded { .. } => ProviderErrorKind::Overloaded,
+            Self::Api { status, .. } if *status >= 500 => ProviderErrorKind::Server,
+            Self::Api { status: 408, .. } | Self::Network(_) => ProviderErrorKind::Network,
+            Self::Api { .. } => ProviderErrorKind::InvalidRequest,
+            Self::Auth(_) => ProviderErrorKind::Auth,
+            Self::ContextTooLong(_) => ProviderErrorKind::ContextTooLong,
+            Self::ContentFiltered(_) => ProviderErrorKind::ContentFiltered,
+            Self::Serialization(_) | Self::Config(_) => ProviderErrorKind::Other,
+        }
+    }
+
+    /// Whether the same request may succeed if retried later.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        // A malformed request or unreadable body fails the same way every time
+        if let Self::Network(e) = self {
+            if e.is_builder() || e.is_decode() {
+                return false;
+            }
+        }
+        matches!(
+            self.kind(),
+            ProviderErrorKind::RateLimited
+                | ProviderErrorKind::Overloaded
+                | ProviderErrorKind::Server
+                | ProviderErrorKind::Network
+        )
+    }
+
+    /// Delay requested by the provider before retrying.
+    #[must_use]
+    pub const fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimited {
+                retry_after_secs: Some(secs),
+            }
+            | Self::Overloaded {
+                retry_after_secs: Some(secs),
+                ..
+            } => Some(Duration::from_secs(*secs)),
+            _ => None,
+        }
+    }
+}
+
+/// Fields of interest from an error body.
+struct ErrorDetail {
+    codes: Vec<String>,
+    message: String,
+}
+
+impl ErrorDetail {
+    fn parse(body: &str) -> Self {
+        let value: serde_json::Value = serde_json::from_str(body).unwrap_or_default();
+        let error = &value["error"];
+
+        let codes = ["code", "type"]
+            .iter()
+            .filter_map(|field| error[field].as_str())
+            .map(str::to_string)
+            .collect();
+        let message = error["message"]
+            .as_str()
+            .map_or_else(|| body.trim().to_string(), str::to_string);
+
+        Self { codes, message }
+    }
+
+    fn is(&self, code: &str) -> bool {
+        self.codes.iter().any(|c| c == code)
+    }
+
+    fn mentions_context_limit(&self) -> bool {
+        let message = self.message.to_lowercase();
+        message.contains("prompt is too long") || message.contains("maximum context length")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_anthropic_errors() {
+        let overloaded =
+            r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#;
+        let err = ProviderError::from_response(529, None, overloaded);
+        assert_eq!(err.kind(), ProviderErrorKind::Overloaded);
+        assert!(err.is_retryable());
+
+        let too_long = r#"{"type":"error","error":{"type":"invalid_request_error","message":"prompt is too long: 210000 tokens > 200000 maximum"}}"#;
+        let err = ProviderError::from_response(400, None, too_long);
+        assert_eq!(err.kind(), ProviderErrorKind::ContextTooLong);
+        assert!(!err.is_retryable());
+
+        let auth = r#"{"type":"error","error":{"type":"authentication_error","message":"invalid x-api-key"}}"#;
+        let err = ProviderError::from_response(401, None, auth);
+        assert!(matches!(&err, ProviderError::Auth(m) if m == "invalid x-api-key"));
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn test_classify_openai_errors() {
+        let rate_limited = r#"{"error":{"message":"Rate limit reached","type":"requests","code":"rate_limit_exceeded"}}"#;
+        let err = ProviderError::from_response(429, Some(7), rate_limited);
+        assert_eq!(err.kind(), ProviderErrorKind::RateLimited);
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(7)));
+
+        let quota = r#"{"error":{"message":"You exceeded your current quota","type":"insufficient_quota","code":"insufficient_quota"}}"#;
+        let err = ProviderError::from_response(429, None, quota);
+        assert_eq!(err.kind(), ProviderErrorKind::InvalidRequest);
+        assert!(!err.is_retryable());
+
+        let filtered = r#"{"error":{"message":"blocked","type":"invalid_request_error","code":"content_filter"}}"#;
+        assert_eq!(
+            ProviderError::from_response(400, None, filtered).kind(),
+            ProviderErrorKind::ContentFiltered
+        );
+
+        let too_long = r#"{"error":{"message":"This model's maximum context length is 128000 tokens","type":"invalid_request_error","code":"context_length_exceeded"}}"#;
+        assert_eq!(
+            ProviderError::from_response(400, None, too_long).kind(),
+            ProviderErrorKind::ContextTooLong
+        );
+    }
+
+    #[test]
+    fn test_classify_plain_errors() {
+        let err = ProviderError::from_response(502, None, "Bad Gateway\n");
+        assert!(
+            matches!(&err, ProviderError::Api { status: 502, message } if message == "Bad Gateway")
+        );
+        assert_eq!(err.kind(), ProviderErrorKind::Server);
+        assert!(err.is_retryable());
+
+        let err = ProviderError::from_response(408, None, "Request Timeout");
+        assert_eq!(err.kind(), ProviderErrorKind::Network);
+        assert!(err.is_retryable());
+
+        let err = ProviderError::from_response(404, None, "not found");
+        assert_eq!(err.kind(), ProviderErrorKind::InvalidRequest);
+        assert!(!err.is_retryable());
+    }
+}
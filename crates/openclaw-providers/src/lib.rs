@@ -6,14 +6,23 @@
 #![warn(missing_docs)]
 
 mod anthropic;
+mod errors;
 mod openai;
+pub mod recording;
+pub mod retry;
 pub mod traits;
 mod usage;
+pub mod wirelog;
 
 pub use anthropic::AnthropicProvider;
+pub use errors::ProviderErrorKind;
 pub use openai::OpenAIProvider;
+pub use recording::{Cassette, RecordingProvider};
+pub use retry::{RetryPolicy, RetryProvider};
 pub use traits::{
     CompletionRequest, CompletionResponse, ContentBlock, ImageSource, Message, MessageContent,
-    Provider, ProviderError, Role, StopReason, StreamingChunk, Tool,
+    ModerationResult, Provider, ProviderError, ResponseFormat, Role, StopReason, StreamingChunk,
+    Tool,
 };
-pub use usage::UsageTracker;
+pub use usage::{TokenUsageSummary, UsageTracker};
+pub use wirelog::{LoggedProvider, WireLog};
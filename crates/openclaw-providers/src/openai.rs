@@ -7,14 +7,17 @@ use serde::{Deserialize, Serialize};
 use std::pin::Pin;
 
 use crate::traits::{
-    ChunkType, CompletionRequest, CompletionResponse, ContentBlock, MessageContent, Provider,
-    ProviderError, Role, StopReason, StreamingChunk,
+    ChunkType, CompletionRequest, CompletionResponse, ContentBlock, MessageContent,
+    ModerationResult, Provider, ProviderError, Role, StopReason, StreamingChunk,
 };
 use openclaw_core::secrets::ApiKey;
 use openclaw_core::types::TokenUsage;
 
 const DEFAULT_BASE_URL: &str = "https://api.openai.com";
 
+/// Model used by the moderation endpoint.
+const MODERATION_MODEL: &str = "omni-moderation-latest";
+
 /// `OpenAI` API provider.
 pub struct OpenAIProvider {
     client: Client,
@@ -136,6 +139,16 @@ impl OpenAIProvider {
             temperature: Some(request.temperature),
             stop: request.stop.clone(),
             tools,
+            response_format: request.response_format.as_ref().map(|format| {
+                serde_json::json!({
+                    "type": "json_schema",
+                    "json_schema": {
+                        "name": format.name,
+                        "schema": format.schema,
+                        "strict": format.strict,
+                    },
+                })
+            }),
             stream: Some(false),
         }
     }
@@ -153,7 +166,9 @@ fn content_to_openai(content: &MessageContent) -> OpenAIContent {
                     }
                     ContentBlock::Image { source } => Some(OpenAIContentPart::ImageUrl {
                         image_url: OpenAIImageUrl {
-                            url: format!("data:{};base64,{}", source.media_type, source.data),
+                            url: source.url.clone().unwrap_or_else(|| {
+                                format!("data:{};base64,{}", source.media_type, source.data)
+                            }),
                         },
                     }),
                     _ => None,
@@ -213,6 +228,23 @@ impl Provider for OpenAIProvider {
         "openai"
     }
 
+    fn supports_vision(&self, model: &str) -> bool {
+        const VISION: &[&str] = &[
+            "gpt-4o",
+            "gpt-4.1",
+            "gpt-4-turbo",
+            "gpt-4-vision",
+            "gpt-5",
+            "o1",
+            "o3",
+            "o4",
+        ];
+        const TEXT_ONLY: &[&str] = &["o1-mini", "o1-preview", "o3-mini"];
+
+        VISION.iter().any(|p| model.starts_with(p))
+            && !TEXT_ONLY.iter().any(|p| model.starts_with(p))
+    }
+
     async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
         let url = format!("{}/v1/models", self.base_url);
 
@@ -228,15 +260,38 @@ impl Provider for OpenAIProvider {
         let response = req.send().await?;
 
         if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let message = response.text().await.unwrap_or_default();
-            return Err(ProviderError::Api { status, message });
+            return Err(ProviderError::from_http(response).await);
         }
 
         let result: OpenAIModelsResponse = response.json().await?;
         Ok(result.data.into_iter().map(|m| m.id).collect())
     }
 
+    async fn moderate(&self, input: &str) -> Result<ModerationResult, ProviderError> {
+        let url = format!("{}/v1/moderations", self.base_url);
+
+        let mut req = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key.expose()));
+
+        if let Some(org) = &self.org_id {
+            req = req.header("OpenAI-Organization", org);
+        }
+
+        let response = req
+            .json(&serde_json::json!({ "model": MODERATION_MODEL, "input": input }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(ProviderError::from_http(response).await);
+        }
+
+        let result: OpenAIModerationResponse = response.json().await?;
+        Ok(result.into())
+    }
+
     async fn complete(
         &self,
         request: CompletionRequest,
@@ -257,22 +312,7 @@ impl Provider for OpenAIProvider {
         let response = req.json(&openai_request).send().await?;
 
         if !response.status().is_success() {
-            let status = response.status().as_u16();
-
-            if status == 429 {
-                let retry_after = response
-                    .headers()
-                    .get("retry-after")
-                    .and_then(|v| v.to_str().ok())
-                    .and_then(|v| v.parse().ok())
-                    .unwrap_or(60);
-                return Err(ProviderError::RateLimited {
-                    retry_after_secs: retry_after,
-                });
-            }
-
-            let message = response.text().await.unwrap_or_default();
-            return Err(ProviderError::Api { status, message });
+            return Err(ProviderError::from_http(response).await);
         }
 
         let result: OpenAIResponse = response.json().await?;
@@ -303,9 +343,7 @@ impl Provider for OpenAIProvider {
         let response = req.json(&openai_request).send().await?;
 
         if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let message = response.text().await.unwrap_or_default();
-            return Err(ProviderError::Api { status, message });
+            return Err(ProviderError::from_http(response).await);
         }
 
         let stream = response.bytes_stream().map(move |result| match result {
@@ -369,6 +407,8 @@ struct OpenAIRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<OpenAITool>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     stream: Option<bool>,
 }
 
@@ -440,6 +480,33 @@ struct OpenAIModel {
     id: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct OpenAIModerationResponse {
+    results: Vec<OpenAIModeration>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIModeration {
+    flagged: bool,
+    #[serde(default)]
+    categories: std::collections::BTreeMap<String, bool>,
+}
+
+impl From<OpenAIModerationResponse> for ModerationResult {
+    fn from(resp: OpenAIModerationResponse) -> Self {
+        let mut result = Self::default();
+        for moderation in resp.results {
+            result.flagged |= moderation.flagged;
+            for (category, flagged) in moderation.categories {
+                if flagged && !result.categories.contains(&category) {
+                    result.categories.push(category);
+                }
+            }
+        }
+        result
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct OpenAIResponse {
     id: String,
@@ -537,6 +604,7 @@ impl From<OpenAIResponse> for CompletionResponse {
 mod tests {
     use super::*;
     use crate::Message;
+    use crate::traits::ResponseFormat;
 
     #[test]
     fn test_provider_name() {
@@ -558,10 +626,34 @@ mod tests {
             temperature: 0.7,
             stop: None,
             tools: None,
+            response_format: Some(ResponseFormat::new(
+                "ticket",
+                serde_json::json!({ "type": "object" }),
+            )),
         };
 
         let openai_req = provider.to_openai_request(&request);
         assert_eq!(openai_req.model, "gpt-4o");
         assert_eq!(openai_req.messages.len(), 2); // system + user
+        let format = openai_req.response_format.unwrap();
+        assert_eq!(format["type"], "json_schema");
+        assert_eq!(format["json_schema"]["name"], "ticket");
+    }
+
+    #[test]
+    fn test_moderation_response() {
+        let response: OpenAIModerationResponse = serde_json::from_value(serde_json::json!({
+            "id": "modr-1",
+            "model": "omni-moderation-latest",
+            "results": [{
+                "flagged": true,
+                "categories": { "harassment": true, "violence": false, "self-harm": true },
+                "category_scores": { "harassment": 0.91, "violence": 0.01, "self-harm": 0.7 }
+            }]
+        }))
+        .unwrap();
+        let result = ModerationResult::from(response);
+        assert!(result.flagged);
+        assert_eq!(result.categories, ["harassment", "self-harm"]);
     }
 }
@@ -0,0 +1,507 @@
+//! Recording and replaying provider calls.
+//!
+//! [`RecordingProvider`] saves each request/response pair to a directory,
+//! one JSON file per request named by a hash of the request. In replay
+//! mode responses come from those files instead of the provider, so agent
+//! logic can be developed and tested offline and deterministically.
+//! String values are scrubbed of secrets before anything is written, and
+//! requests are hashed after scrubbing. Recordings whose requests quote
+//! given messages can be deleted, e.g. when a peer is forgotten.
+
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use openclaw_core::config::{ProviderRecordingConfig, RecordingMode};
+use openclaw_core::secrets::{COMMON_SECRET_PATTERNS, scrub_secrets};
+
+use crate::traits::{
+    ChunkType, CompletionRequest, CompletionResponse, ContentBlock, ModerationResult, Provider,
+    ProviderError, StreamingChunk,
+};
+
+/// A recorded provider call, as stored on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recording {
+    /// When the call was recorded.
+    pub recorded_at: DateTime<Utc>,
+    /// Provider that answered.
+    pub provider: String,
+    /// Scrubbed request.
+    pub request: Value,
+    /// Scrubbed response.
+    pub response: CompletionResponse,
+}
+
+/// Directory of recorded provider calls.
+#[derive(Debug, Clone)]
+pub struct Cassette {
+    dir: PathBuf,
+    mode: RecordingMode,
+}
+
+impl Cassette {
+    /// Create a cassette over `dir`.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>, mode: RecordingMode) -> Self {
+        Self {
+            dir: dir.into(),
+            mode,
+        }
+    }
+
+    /// Create from config, or `None` if recording is off.
+    #[must_use]
+    pub fn from_config(config: &ProviderRecordingConfig) -> Option<Self> {
+        if config.mode == RecordingMode::Off {
+            return None;
+        }
+        let dir = config
+            .dir
+            .clone()
+            .unwrap_or_else(openclaw_core::Config::recordings_dir);
+        Some(Self::new(dir, config.mode))
+    }
+
+    /// Recordings directory.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Recording mode.
+    #[must_use]
+    pub const fn mode(&self) -> RecordingMode {
+        self.mode
+    }
+
+    /// Scrubbed form of a request and the key it is stored under.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the request cannot be serialized.
+    pub fn key(request: &CompletionRequest) -> Result<(Value, String), ProviderError> {
+        let mut value = serde_json::to_value(request)?;
+        scrub_value(&mut value);
+        let hash = Sha256::digest(serde_json::to_vec(&value)?);
+        Ok((value, format!("{hash:x}")))
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+
+    /// The recording stored under `key`, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the recording exists but cannot be read.
+    pub fn load(&self, key: &str) -> Result<Option<Recording>, ProviderError> {
+        let path = self.path(key);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(ProviderError::Config(format!(
+                    "Failed to read recording {}: {e}",
+                    path.display()
+                )));
+            }
+        };
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Store a call under `key`, scrubbing the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the recording cannot be written.
+    pub fn save(
+        &self,
+        key: &str,
+        provider: &str,
+        request: Value,
+        response: &CompletionResponse,
+    ) -> Result<(), ProviderError> {
+        let mut response = serde_json::to_value(response)?;
+        scrub_value(&mut response);
+        let recording = Recording {
+            recorded_at: Utc::now(),
+            provider: provider.to_string(),
+            request,
+            response: serde_json::from_value(response)?,
+        };
+
+        let write = || -> std::io::Result<()> {
+            std::fs::create_dir_all(&self.dir)?;
+            let body = serde_json::to_string_pretty(&recording)?;
+            std::fs::write(self.path(key), body)
+        };
+        write().map_err(|e| {
+            ProviderError::Config(format!(
+                "Failed to write recording in {}: {e}",
+                self.dir.display()
+            ))
+        })
+    }
+}
+
+impl Cassette {
+    /// Delete recordings whose requests quote any of `messages`.
+    ///
+    /// Returns the number of recordings deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the directory cannot be read or a matching
+    /// recording cannot be deleted.
+    pub fn forget(&self, messages: &[String]) -> Result<usize, ProviderError> {
+        let needles: Vec<String> = messages
+            .iter()
+            .filter(|m| !m.is_empty())
+            .map(|m| scrub_secrets(m, COMMON_SECRET_PATTERNS))
+            .collect();
+        let failed = |e: std::io::Error| {
+            ProviderError::Config(format!(
+                "Failed to forget recordings in {}: {e}",
+                self.dir.display()
+            ))
+        };
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(failed(e)),
+        };
+
+        let mut removed = 0;
+        for entry in entries {
+            let path = entry.map_err(failed)?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Some(recording) = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<Recording>(&content).ok())
+            else {
+                continue;
+            };
+            if needles
+                .iter()
+                .any(|needle| quotes(&recording.request, needle))
+            {
+                std::fs::remove_file(&path).map_err(failed)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// Whether any string in a JSON value contains `needle`.
+fn quotes(value: &Value, needle: &str) -> bool {
+    match value {
+        Value::String(text) => text.contains(needle),
+        Value::Array(items) => items.iter().any(|item| quotes(item, needle)),
+        Value::Object(fields) => fields.values().any(|field| quotes(field, needle)),
+        _ => false,
+    }
+}
+
+/// Scrub secrets from every string in a JSON value.
+fn scrub_value(value: &mut Value) {
+    match value {
+        Value::String(text) => *text = scrub_secrets(text, COMMON_SECRET_PATTERNS),
+        Value::Array(items) => items.iter_mut().for_each(scrub_value),
+        Value::Object(fields) => fields.values_mut().for_each(scrub_value),
+        _ => {}
+    }
+}
+
+/// Provider wrapper that records calls to, or replays them from, a
+/// [`Cassette`].
+pub struct RecordingProvider {
+    inner: Arc<dyn Provider>,
+    cassette: Cassette,
+}
+
+impl RecordingProvider {
+    /// Wrap a provider.
+    #[must_use]
+    pub const fn new(inner: Arc<dyn Provider>, cassette: Cassette) -> Self {
+        Self { inner, cassette }
+    }
+
+    /// The recorded response for `request`, if replaying and one exists.
+    fn replayed(&self, key: &str) -> Result<Option<CompletionResponse>, ProviderError> {
+        match self.cassette.mode {
+            RecordingMode::Replay | RecordingMode::Auto => {
+                let recording = self.cassette.load(key)?;
+                if recording.is_none() && self.cassette.mode == RecordingMode::Replay {
+                    return Err(ProviderError::Config(format!(
+                        "No recording for request {key} in {}",
+                        self.cassette.dir.display()
+                    )));
+                }
+                Ok(recording.map(|r| r.response))
+            }
+            RecordingMode::Off | RecordingMode::Record => Ok(None),
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for RecordingProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn supports_vision(&self, model: &str) -> bool {
+        self.inner.supports_vision(model)
+    }
+
+    async fn moderate(&self, input: &str) -> Result<ModerationResult, ProviderError> {
+        self.inner.moderate(input).await
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
+        self.inner.list_models().await
+    }
+
+    async fn complete(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<CompletionResponse, ProviderError> {
+        if self.cassette.mode == RecordingMode::Off {
+            return self.inner.complete(request).await;
+        }
+        let (scrubbed, key) = Cassette::key(&request)?;
+        if let Some(response) = self.replayed(&key)? {
+            return Ok(response);
+        }
+
+        let response = self.inner.complete(request).await?;
+        if let Err(e) = self
+            .cassette
+            .save(&key, self.inner.name(), scrubbed, &response)
+        {
+            tracing::warn!("{}", e);
+        }
+        Ok(response)
+    }
+
+    /// Replayed streams yield the recorded text in one delta per block.
+    /// Streamed calls are not recorded.
+    async fn complete_stream(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<
+        Pin<Box<dyn futures::Stream<Item = Result<StreamingChunk, ProviderError>> + Send>>,
+        ProviderError,
+    > {
+        if matches!(
+            self.cassette.mode,
+            RecordingMode::Replay | RecordingMode::Auto
+        ) {
+            let (_, key) = Cassette::key(&request)?;
+            if let Some(response) = self.replayed(&key)? {
+                let chunks = stream_chunks(&response).into_iter().map(Ok);
+                return Ok(Box::pin(futures::stream::iter(chunks)));
+            }
+        }
+        self.inner.complete_stream(request).await
+    }
+}
+
+/// Streaming chunks that deliver a response's text.
+fn stream_chunks(response: &CompletionResponse) -> Vec<StreamingChunk> {
+    let chunk = |chunk_type, delta: Option<&str>, index| StreamingChunk {
+        chunk_type,
+        delta: delta.map(String::from),
+        index,
+    };
+    let mut chunks = vec![chunk(ChunkType::MessageStart, None, None)];
+    for (index, block) in response.content.iter().enumerate() {
+        if let ContentBlock::Text { text } = block {
+            chunks.push(chunk(ChunkType::ContentBlockStart, None, Some(index)));
+            chunks.push(chunk(ChunkType::ContentBlockDelta, Some(text), Some(index)));
+            chunks.push(chunk(ChunkType::ContentBlockStop, None, Some(index)));
+        }
+    }
+    chunks.push(chunk(ChunkType::MessageStop, None, None));
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use futures::StreamExt;
+    use openclaw_core::types::TokenUsage;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::traits::{Message, MessageContent, Role, StopReason};
+
+    #[derive(Default)]
+    struct CountingProvider {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Provider for CountingProvider {
+        fn name(&self) -> &'static str {
+            "counting"
+        }
+
+        async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
+            Ok(vec!["count-1".to_string()])
+        }
+
+        async fn complete(
+            &self,
+            request: CompletionRequest,
+        ) -> Result<CompletionResponse, ProviderError> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(CompletionResponse {
+                id: format!("resp-{n}"),
+                model: request.model,
+                content: vec![ContentBlock::Text {
+                    text: format!("call {n}, token=abc123"),
+                }],
+                stop_reason: Some(StopReason::EndTurn),
+                usage: TokenUsage::default(),
+            })
+        }
+
+        async fn complete_stream(
+            &self,
+            _request: CompletionRequest,
+        ) -> Result<
+            Pin<Box<dyn futures::Stream<Item = Result<StreamingChunk, ProviderError>> + Send>>,
+            ProviderError,
+        > {
+            Err(ProviderError::Config("not supported".to_string()))
+        }
+    }
+
+    fn request(text: &str) -> CompletionRequest {
+        CompletionRequest {
+            model: "count-1".to_string(),
+            messages: vec![Message {
+                role: Role::User,
+                content: MessageContent::Text(text.to_string()),
+            }],
+            system: None,
+            max_tokens: 100,
+            temperature: 0.0,
+            stop: None,
+            tools: None,
+            response_format: None,
+        }
+    }
+
+    fn text(response: &CompletionResponse) -> &str {
+        match &response.content[0] {
+            ContentBlock::Text { text } => text,
+            other => panic!("unexpected block {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let temp = tempdir().unwrap();
+        let inner = Arc::new(CountingProvider::default());
+        let recorder = RecordingProvider::new(
+            inner.clone(),
+            Cassette::new(temp.path(), RecordingMode::Record),
+        );
+        let live = recorder
+            .complete(request("hi, password=hunter2"))
+            .await
+            .unwrap();
+        assert_eq!(text(&live), "call 1, token=abc123");
+
+        let files: Vec<_> = std::fs::read_dir(temp.path()).unwrap().collect();
+        assert_eq!(files.len(), 1);
+        let stored = std::fs::read_to_string(files[0].as_ref().unwrap().path()).unwrap();
+        assert!(!stored.contains("hunter2"));
+        assert!(!stored.contains("abc123"));
+
+        let replayer = RecordingProvider::new(
+            inner.clone(),
+            Cassette::new(temp.path(), RecordingMode::Replay),
+        );
+        let cached = replayer
+            .complete(request("hi, password=hunter2"))
+            .await
+            .unwrap();
+        assert_eq!(cached.id, "resp-1");
+        assert_eq!(text(&cached), "call 1, token=[REDACTED]");
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+
+        let miss = replayer.complete(request("something else")).await;
+        assert!(matches!(miss, Err(ProviderError::Config(_))));
+
+        let mut stream = replayer
+            .complete_stream(request("hi, password=hunter2"))
+            .await
+            .unwrap();
+        let mut streamed = String::new();
+        while let Some(chunk) = stream.next().await {
+            streamed.push_str(chunk.unwrap().delta.as_deref().unwrap_or_default());
+        }
+        assert_eq!(streamed, "call 1, token=[REDACTED]");
+    }
+
+    #[tokio::test]
+    async fn test_auto_records_misses() {
+        let temp = tempdir().unwrap();
+        let inner = Arc::new(CountingProvider::default());
+        let provider = RecordingProvider::new(
+            inner.clone(),
+            Cassette::new(temp.path(), RecordingMode::Auto),
+        );
+
+        provider.complete(request("one")).await.unwrap();
+        provider.complete(request("one")).await.unwrap();
+        provider.complete(request("two")).await.unwrap();
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_forget() {
+        let temp = tempdir().unwrap();
+        let cassette = Cassette::new(temp.path(), RecordingMode::Record);
+        let recorder =
+            RecordingProvider::new(Arc::new(CountingProvider::default()), cassette.clone());
+        for text in ["My address is 1 Main St, password=hunter2", "Hi all"] {
+            recorder.complete(request(text)).await.unwrap();
+        }
+
+        let forgotten = vec!["My address is 1 Main St, password=hunter2".to_string()];
+        assert_eq!(cassette.forget(&forgotten).unwrap(), 1);
+        assert_eq!(std::fs::read_dir(temp.path()).unwrap().count(), 1);
+        assert!(
+            cassette
+                .load(&Cassette::key(&request("Hi all")).unwrap().1)
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(cassette.forget(&forgotten).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_key_ignores_secrets() {
+        let (_, a) = Cassette::key(&request("api_key=first")).unwrap();
+        let (_, b) = Cassette::key(&request("api_key=second")).unwrap();
+        let (_, c) = Cassette::key(&request("different")).unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+}
@@ -0,0 +1,364 @@
+//! Automatic retries for transient provider errors.
+//!
+//! [`RetryProvider`] wraps any provider and retries rate-limited,
+//! overloaded, 5xx, and network failures with exponential backoff and
+//! jitter. Streaming calls are retried only until the stream is open;
+//! errors after the first chunk are passed through.
+
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use rand::Rng;
+
+use openclaw_core::config::ProviderRetryConfig;
+
+use crate::traits::{
+    CompletionRequest, CompletionResponse, ModerationResult, Provider, ProviderError,
+    StreamingChunk,
+};
+use crate::usage::UsageTracker;
+
+/// When and how long to wait before retrying.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt.
+    pub max_retries: u32,
+    /// Backoff before the first retry.
+    pub initial_backoff: Duration,
+    /// Upper bound on a single backoff.
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Create from config.
+    #[must_use]
+    pub const fn from_config(config: &ProviderRetryConfig) -> Self {
+        Self {
+            max_retries: config.max_retries,
+            initial_backoff: Duration::from_millis(config.initial_backoff_ms),
+            max_backoff: Duration::from_millis(config.max_backoff_ms),
+        }
+    }
+
+    /// Policy that never retries.
+    #[must_use]
+    pub const fn none() -> Self {
+        Self {
+            max_retries: 0,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        }
+    }
+
+    /// Backoff before retry number `attempt` (0-based): `initial * 2^attempt`
+    /// capped at `max_backoff`, with jitter drawn from the upper half.
+    #[must_use]
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff);
+        let millis = u64::try_from(exponential.as_millis()).unwrap_or(u64::MAX);
+        if millis == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_millis(rand::thread_rng().gen_range(millis / 2..=millis))
+    }
+
+    /// Delay before retrying `error` after `attempt` retries, or `None` if
+    /// it should not be retried.
+    ///
+    /// A provider-supplied `retry-after` is honored; if it exceeds
+    /// `max_backoff` the error is returned instead of waiting.
+    #[must_use]
+    pub fn delay(&self, attempt: u32, error: &ProviderError) -> Option<Duration> {
+        if attempt >= self.max_retries || !error.is_retryable() {
+            return None;
+        }
+        match error.retry_after() {
+            Some(wait) if wait > self.max_backoff => None,
+            Some(wait) => Some(wait),
+            None => Some(self.backoff(attempt)),
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::from_config(&ProviderRetryConfig::default())
+    }
+}
+
+/// Provider wrapper that retries transient errors.
+pub struct RetryProvider {
+    inner: Arc<dyn Provider>,
+    policy: RetryPolicy,
+    usage: Option<Arc<UsageTracker>>,
+}
+
+impl RetryProvider {
+    /// Wrap a provider.
+    #[must_use]
+    pub const fn new(inner: Arc<dyn Provider>, policy: RetryPolicy) -> Self {
+        Self {
+            inner,
+            policy,
+            usage: None,
+        }
+    }
+
+    /// Record retries and final failures in `usage`.
+    #[must_use]
+    pub fn with_usage_tracker(mut self, usage: Arc<UsageTracker>) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    /// Run `call` until it succeeds, fails permanently, or retries run out.
+    ///
+    /// Usage is recorded against `model`, if given.
+    async fn retry<T, F, Fut>(&self, model: Option<&str>, mut call: F) -> Result<T, ProviderError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, ProviderError>>,
+    {
+        let mut attempt = 0;
+        loop {
+            let error = match call().await {
+                Ok(value) => return Ok(value),
+                Err(e) => e,
+            };
+
+            let Some(wait) = self.policy.delay(attempt, &error) else {
+                if let (Some(usage), Some(model)) = (&self.usage, model) {
+                    usage.record_failure(model, error.kind());
+                }
+                return Err(error);
+            };
+
+            tracing::warn!(
+                provider = self.inner.name(),
+                model,
+                kind = %error.kind(),
+                attempt = attempt + 1,
+                wait_ms = u64::try_from(wait.as_millis()).unwrap_or(u64::MAX),
+                "Retrying provider call: {}",
+                error
+            );
+            if let (Some(usage), Some(model)) = (&self.usage, model) {
+                usage.record_retry(model, error.kind(), wait);
+            }
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for RetryProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn supports_vision(&self, model: &str) -> bool {
+        self.inner.supports_vision(model)
+    }
+
+    async fn moderate(&self, input: &str) -> Result<ModerationResult, ProviderError> {
+        self.retry(None, || self.inner.moderate(input)).await
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
+        self.retry(None, || self.inner.list_models()).await
+    }
+
+    async fn complete(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<CompletionResponse, ProviderError> {
+        self.retry(Some(&request.model), || {
+            self.inner.complete(request.clone())
+        })
+        .await
+    }
+
+    async fn complete_stream(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<
+        Pin<Box<dyn futures::Stream<Item = Result<StreamingChunk, ProviderError>> + Send>>,
+        ProviderError,
+    > {
+        self.retry(Some(&request.model), || {
+            self.inner.complete_stream(request.clone())
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{ContentBlock, Message, MessageContent, Role, StopReason};
+    use openclaw_core::types::TokenUsage;
+    use std::sync::Mutex;
+
+    /// Fails with queued errors, then succeeds.
+    struct FlakyProvider {
+        errors: Mutex<Vec<ProviderError>>,
+        calls: Mutex<u32>,
+    }
+
+    impl FlakyProvider {
+        fn new(mut errors: Vec<ProviderError>) -> Self {
+            errors.reverse();
+            Self {
+                errors: Mutex::new(errors),
+                calls: Mutex::new(0),
+            }
+        }
+
+        fn calls(&self) -> u32 {
+            *self.calls.lock().unwrap()
+        }
+    }
+
+    #[async_trait]
+    impl Provider for FlakyProvider {
+        fn name(&self) -> &'static str {
+            "flaky"
+        }
+
+        async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
+            Ok(vec![])
+        }
+
+        async fn complete(
+            &self,
+            request: CompletionRequest,
+        ) -> Result<CompletionResponse, ProviderError> {
+            *self.calls.lock().unwrap() += 1;
+            let error = self.errors.lock().unwrap().pop();
+            if let Some(error) = error {
+                return Err(error);
+            }
+            Ok(CompletionResponse {
+                id: "resp-1".to_string(),
+                model: request.model,
+                content: vec![ContentBlock::Text {
+                    text: "ok".to_string(),
+                }],
+                stop_reason: Some(StopReason::EndTurn),
+                usage: TokenUsage::default(),
+            })
+        }
+
+        async fn complete_stream(
+            &self,
+            _request: CompletionRequest,
+        ) -> Result<
+            Pin<Box<dyn futures::Stream<Item = Result<StreamingChunk, ProviderError>> + Send>>,
+            ProviderError,
+        > {
+            Err(ProviderError::Config("not supported".to_string()))
+        }
+    }
+
+    fn request() -> CompletionRequest {
+        CompletionRequest {
+            model: "flaky-1".to_string(),
+            messages: vec![Message {
+                role: Role::User,
+                content: MessageContent::Text("hi".to_string()),
+            }],
+            system: None,
+            max_tokens: 100,
+            temperature: 0.0,
+            stop: None,
+            tools: None,
+            response_format: None,
+        }
+    }
+
+    fn overloaded() -> ProviderError {
+        ProviderError::Overloaded {
+            message: "Overloaded".to_string(),
+            retry_after_secs: None,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retries_transient_errors() {
+        let inner = Arc::new(FlakyProvider::new(vec![
+            overloaded(),
+            ProviderError::RateLimited {
+                retry_after_secs: Some(2),
+            },
+        ]));
+        let usage = Arc::new(UsageTracker::new());
+        let provider = RetryProvider::new(inner.clone(), RetryPolicy::default())
+            .with_usage_tracker(usage.clone());
+
+        let response = provider.complete(request()).await.unwrap();
+        assert_eq!(response.id, "resp-1");
+        assert_eq!(inner.calls(), 3);
+
+        let summary = usage.get_usage("flaky-1").unwrap();
+        assert_eq!(summary.retries, 2);
+        assert_eq!(summary.overloaded, 1);
+        assert_eq!(summary.rate_limited, 1);
+        assert!(summary.retry_wait_ms >= 2500);
+        assert_eq!(summary.failed_requests, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_gives_up_after_max_retries() {
+        let inner = Arc::new(FlakyProvider::new((0..5).map(|_| overloaded()).collect()));
+        let usage = Arc::new(UsageTracker::new());
+        let policy = RetryPolicy {
+            max_retries: 2,
+            ..RetryPolicy::default()
+        };
+        let provider = RetryProvider::new(inner.clone(), policy).with_usage_tracker(usage.clone());
+
+        let err = provider.complete(request()).await.unwrap_err();
+        assert!(matches!(err, ProviderError::Overloaded { .. }));
+        assert_eq!(inner.calls(), 3);
+        assert_eq!(usage.get_usage("flaky-1").unwrap().failed_requests, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_permanent_errors_not_retried() {
+        let inner = Arc::new(FlakyProvider::new(vec![ProviderError::Auth(
+            "invalid x-api-key".to_string(),
+        )]));
+        let provider = RetryProvider::new(inner.clone(), RetryPolicy::default());
+
+        assert!(matches!(
+            provider.complete(request()).await,
+            Err(ProviderError::Auth(_))
+        ));
+        assert_eq!(inner.calls(), 1);
+    }
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::default();
+        for attempt in 0..10 {
+            let expected = Duration::from_secs(1)
+                .saturating_mul(2u32.pow(attempt))
+                .min(policy.max_backoff);
+            let backoff = policy.backoff(attempt);
+            assert!(backoff >= expected / 2 && backoff <= expected);
+        }
+
+        // retry-after beyond the cap is not waited out
+        let long_wait = ProviderError::RateLimited {
+            retry_after_secs: Some(120),
+        };
+        assert_eq!(policy.delay(0, &long_wait), None);
+        assert_eq!(RetryPolicy::none().delay(0, &overloaded()), None);
+    }
+}
@@ -27,13 +27,37 @@ pub enum ProviderError {
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
-    /// Rate limited.
-    #[error("Rate limited, retry after {retry_after_secs} seconds")]
+    /// Rate limited (HTTP 429).
+    #[error(
+        "Rate limited{}",
+        retry_after_secs.map(|s| format!(", retry after {s} seconds")).unwrap_or_default()
+    )]
     RateLimited {
-        /// Seconds to wait before retry.
-        retry_after_secs: u64,
+        /// Seconds to wait before retry, if the provider said.
+        retry_after_secs: Option<u64>,
     },
 
+    /// Provider temporarily overloaded (HTTP 503/529).
+    #[error("Provider overloaded: {message}")]
+    Overloaded {
+        /// Error message.
+        message: String,
+        /// Seconds to wait before retry, if the provider said.
+        retry_after_secs: Option<u64>,
+    },
+
+    /// Authentication or permission failure (HTTP 401/403).
+    #[error("Authentication failed: {0}")]
+    Auth(String),
+
+    /// Request exceeds the model's context window.
+    #[error("Context too long: {0}")]
+    ContextTooLong(String),
+
+    /// Request or output blocked by the provider's content filter.
+    #[error("Content filtered: {0}")]
+    ContentFiltered(String),
+
     /// Invalid configuration.
     #[error("Invalid configuration: {0}")]
     Config(String),
@@ -62,6 +86,37 @@ pub struct CompletionRequest {
 
     /// Tools available.
     pub tools: Option<Vec<Tool>>,
+
+    /// JSON schema the reply must follow, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
+}
+
+/// Reply constrained to a JSON schema.
+///
+/// Providers return the JSON as the reply's text, whatever mechanism
+/// they use to constrain it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponseFormat {
+    /// Schema name: letters, digits, `_` and `-`.
+    pub name: String,
+    /// JSON Schema for the reply.
+    pub schema: serde_json::Value,
+    /// Have the provider enforce the schema exactly, where supported.
+    #[serde(default)]
+    pub strict: bool,
+}
+
+impl ResponseFormat {
+    /// A format named `name` following `schema`.
+    #[must_use]
+    pub fn new(name: impl Into<String>, schema: serde_json::Value) -> Self {
+        Self {
+            name: name.into(),
+            schema,
+            strict: false,
+        }
+    }
 }
 
 /// A message in the conversation.
@@ -135,13 +190,42 @@ pub enum ContentBlock {
 /// Image source.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImageSource {
-    /// Source type.
+    /// Source type (`base64` or `url`).
     #[serde(rename = "type")]
     pub source_type: String,
     /// Media type.
+    #[serde(default)]
     pub media_type: String,
     /// Base64 data.
+    #[serde(default)]
     pub data: String,
+    /// Image URL for `url` sources.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl ImageSource {
+    /// Base64-encoded image data.
+    #[must_use]
+    pub fn base64(media_type: impl Into<String>, data: impl Into<String>) -> Self {
+        Self {
+            source_type: "base64".to_string(),
+            media_type: media_type.into(),
+            data: data.into(),
+            url: None,
+        }
+    }
+
+    /// Image the provider fetches from a URL.
+    #[must_use]
+    pub fn url(url: impl Into<String>) -> Self {
+        Self {
+            source_type: "url".to_string(),
+            media_type: String::new(),
+            data: String::new(),
+            url: Some(url.into()),
+        }
+    }
 }
 
 /// Tool definition.
@@ -174,6 +258,15 @@ pub struct CompletionResponse {
     pub usage: TokenUsage,
 }
 
+/// Verdict from a provider moderation endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModerationResult {
+    /// Whether the input violates the provider's policies.
+    pub flagged: bool,
+    /// Policy categories the input was flagged for.
+    pub categories: Vec<String>,
+}
+
 /// Reason the generation stopped.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -240,4 +333,22 @@ pub trait Provider: Send + Sync {
         Pin<Box<dyn futures::Stream<Item = Result<StreamingChunk, ProviderError>> + Send>>,
         ProviderError,
     >;
+
+    /// Whether `model` accepts image input.
+    fn supports_vision(&self, _model: &str) -> bool {
+        false
+    }
+
+    /// Check `input` with the provider's moderation endpoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Config` if the provider has no moderation endpoint, or an
+    /// error if the call fails.
+    async fn moderate(&self, _input: &str) -> Result<ModerationResult, ProviderError> {
+        Err(ProviderError::Config(format!(
+            "{} has no moderation endpoint",
+            self.name()
+        )))
+    }
 }
@@ -3,9 +3,12 @@
 use std::collections::HashMap;
 use std::sync::RwLock;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
 use openclaw_core::types::TokenUsage;
 
+use crate::errors::ProviderErrorKind;
+
 /// Usage tracker for monitoring token consumption.
 pub struct UsageTracker {
     totals: RwLock<HashMap<String, ModelUsage>>,
@@ -17,6 +20,11 @@ pub struct ModelUsage {
     input_tokens: AtomicU64,
     output_tokens: AtomicU64,
     request_count: AtomicU64,
+    retries: AtomicU64,
+    retry_wait_ms: AtomicU64,
+    failed_requests: AtomicU64,
+    rate_limited: AtomicU64,
+    overloaded: AtomicU64,
 }
 
 impl UsageTracker {
@@ -42,15 +50,39 @@ impl UsageTracker {
         entry.request_count.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Record a retry of a request to a model after a `kind` error, waiting
+    /// `wait` before the next attempt.
+    pub fn record_retry(&self, model: &str, kind: ProviderErrorKind, wait: Duration) {
+        self.update(model, |entry| {
+            entry.retries.fetch_add(1, Ordering::Relaxed);
+            entry.retry_wait_ms.fetch_add(
+                u64::try_from(wait.as_millis()).unwrap_or(u64::MAX),
+                Ordering::Relaxed,
+            );
+            entry.count_error(kind);
+        });
+    }
+
+    /// Record a request to a model that failed with a `kind` error after
+    /// any retries.
+    pub fn record_failure(&self, model: &str, kind: ProviderErrorKind) {
+        self.update(model, |entry| {
+            entry.failed_requests.fetch_add(1, Ordering::Relaxed);
+            entry.count_error(kind);
+        });
+    }
+
+    /// Update a model's counters, creating them on first use.
+    fn update(&self, model: &str, f: impl FnOnce(&ModelUsage)) {
+        let mut totals = self.totals.write().unwrap();
+        f(totals.entry(model.to_string()).or_default());
+    }
+
     /// Get total usage for a model.
     #[must_use]
     pub fn get_usage(&self, model: &str) -> Option<TokenUsageSummary> {
         let totals = self.totals.read().unwrap();
-        totals.get(model).map(|u| TokenUsageSummary {
-            input_tokens: u.input_tokens.load(Ordering::Relaxed),
-            output_tokens: u.output_tokens.load(Ordering::Relaxed),
-            request_count: u.request_count.load(Ordering::Relaxed),
-        })
+        totals.get(model).map(ModelUsage::summary)
     }
 
     /// Get total usage across all models.
@@ -60,9 +92,15 @@ impl UsageTracker {
         let mut summary = TokenUsageSummary::default();
 
         for usage in totals.values() {
-            summary.input_tokens += usage.input_tokens.load(Ordering::Relaxed);
-            summary.output_tokens += usage.output_tokens.load(Ordering::Relaxed);
-            summary.request_count += usage.request_count.load(Ordering::Relaxed);
+            let model = usage.summary();
+            summary.input_tokens += model.input_tokens;
+            summary.output_tokens += model.output_tokens;
+            summary.request_count += model.request_count;
+            summary.retries += model.retries;
+            summary.retry_wait_ms += model.retry_wait_ms;
+            summary.failed_requests += model.failed_requests;
+            summary.rate_limited += model.rate_limited;
+            summary.overloaded += model.overloaded;
         }
 
         summary
@@ -75,6 +113,29 @@ impl UsageTracker {
     }
 }
 
+impl ModelUsage {
+    fn count_error(&self, kind: ProviderErrorKind) {
+        match kind {
+            ProviderErrorKind::RateLimited => self.rate_limited.fetch_add(1, Ordering::Relaxed),
+            ProviderErrorKind::Overloaded => self.overloaded.fetch_add(1, Ordering::Relaxed),
+            _ => 0,
+        };
+    }
+
+    fn summary(&self) -> TokenUsageSummary {
+        TokenUsageSummary {
+            input_tokens: self.input_tokens.load(Ordering::Relaxed),
+            output_tokens: self.output_tokens.load(Ordering::Relaxed),
+            request_count: self.request_count.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+            retry_wait_ms: self.retry_wait_ms.load(Ordering::Relaxed),
+            failed_requests: self.failed_requests.load(Ordering::Relaxed),
+            rate_limited: self.rate_limited.load(Ordering::Relaxed),
+            overloaded: self.overloaded.load(Ordering::Relaxed),
+        }
+    }
+}
+
 impl Default for UsageTracker {
     fn default() -> Self {
         Self::new()
@@ -90,6 +151,16 @@ pub struct TokenUsageSummary {
     pub output_tokens: u64,
     /// Total request count.
     pub request_count: u64,
+    /// Retries after transient errors.
+    pub retries: u64,
+    /// Total time spent waiting between retries.
+    pub retry_wait_ms: u64,
+    /// Requests that failed after any retries.
+    pub failed_requests: u64,
+    /// Rate-limit errors seen (retried or not).
+    pub rate_limited: u64,
+    /// Overloaded errors seen (retried or not).
+    pub overloaded: u64,
 }
 
 impl TokenUsageSummary {
@@ -163,4 +234,30 @@ mod tests {
         assert_eq!(total.output_tokens, 150);
         assert_eq!(total.total_tokens(), 450);
     }
+
+    #[test]
+    fn test_retry_metadata() {
+        let tracker = UsageTracker::new();
+
+        tracker.record_retry(
+            "model1",
+            ProviderErrorKind::RateLimited,
+            Duration::from_millis(500),
+        );
+        tracker.record_retry(
+            "model1",
+            ProviderErrorKind::Overloaded,
+            Duration::from_millis(1500),
+        );
+        tracker.record_failure("model1", ProviderErrorKind::Overloaded);
+
+        let usage = tracker.get_usage("model1").unwrap();
+        assert_eq!(usage.retries, 2);
+        assert_eq!(usage.retry_wait_ms, 2000);
+        assert_eq!(usage.failed_requests, 1);
+        assert_eq!(usage.rate_limited, 1);
+        assert_eq!(usage.overloaded, 2);
+        assert_eq!(usage.request_count, 0);
+        assert_eq!(tracker.total_usage().retries, 2);
+    }
 }
@@ -0,0 +1,572 @@
+//! Provider request/response wire log.
+//!
+//! Writes one JSON line per provider call to a dedicated, size-rotated
+//! file. Bodies are truncated and scrubbed of secrets unless full-body
+//! capture is explicitly enabled for debugging. Entries quoting given
+//! messages can be removed again, e.g. when a peer is forgotten.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use openclaw_core::config::WireLogConfig;
+use openclaw_core::secrets::{COMMON_SECRET_PATTERNS, scrub_secrets};
+use openclaw_core::types::TokenUsage;
+
+use crate::traits::{
+    CompletionRequest, CompletionResponse, ModerationResult, Provider, ProviderError, StopReason,
+    StreamingChunk,
+};
+
+/// Marker appended to truncated bodies.
+const TRUNCATED: &str = "...[truncated]";
+
+/// Shortest tail of a truncated body that counts as quoting a message.
+const MIN_PARTIAL_BYTES: usize = 8;
+
+/// One logged provider call.
+#[derive(Debug, Clone, Serialize)]
+pub struct WireLogEntry {
+    /// When the call completed.
+    pub timestamp: DateTime<Utc>,
+    /// Provider name.
+    pub provider: String,
+    /// Requested model.
+    pub model: String,
+    /// Whether this was a streaming call.
+    pub streaming: bool,
+    /// Messages in the request.
+    pub messages: usize,
+    /// Tools offered in the request.
+    pub tools: usize,
+    /// Time until the response (or stream) was available.
+    pub latency_ms: u64,
+    /// Request body (scrubbed, possibly truncated).
+    pub request: Option<String>,
+    /// Response body (scrubbed, possibly truncated).
+    pub response: Option<String>,
+    /// Token usage.
+    pub usage: Option<TokenUsage>,
+    /// Stop reason.
+    pub stop_reason: Option<StopReason>,
+    /// Error, if the call failed.
+    pub error: Option<String>,
+}
+
+/// Rotating JSON-lines file for provider calls.
+pub struct WireLog {
+    path: PathBuf,
+    max_body_chars: usize,
+    full_bodies: bool,
+    max_file_bytes: u64,
+    max_files: usize,
+    file: Mutex<Option<File>>,
+}
+
+impl WireLog {
+    /// Create a wire log at `path` with default limits.
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let defaults = WireLogConfig::default();
+        Self {
+            path: path.into(),
+            max_body_chars: defaults.max_body_chars,
+            full_bodies: defaults.full_bodies,
+            max_file_bytes: defaults.max_file_bytes,
+            max_files: defaults.max_files,
+            file: Mutex::new(None),
+        }
+    }
+
+    /// Create from config, or `None` if the wire log is disabled.
+    #[must_use]
+    pub fn from_config(config: &WireLogConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+
+        let path = config
+            .path
+            .clone()
+            .unwrap_or_else(|| openclaw_core::Config::logs_dir().join("providers.jsonl"));
+        if config.full_bodies {
+            tracing::warn!(
+                "Provider wire log is capturing full bodies at {}",
+                path.display()
+            );
+        }
+
+        Some(Self {
+            path,
+            max_body_chars: config.max_body_chars,
+            full_bodies: config.full_bodies,
+            max_file_bytes: config.max_file_bytes,
+            max_files: config.max_files,
+            file: Mutex::new(None),
+        })
+    }
+
+    /// Set the per-body character limit.
+    #[must_use]
+    pub const fn with_max_body_chars(mut self, max: usize) -> Self {
+        self.max_body_chars = max;
+        self
+    }
+
+    /// Capture full bodies instead of truncating.
+    #[must_use]
+    pub const fn with_full_bodies(mut self, full: bool) -> Self {
+        self.full_bodies = full;
+        self
+    }
+
+    /// Set rotation size and number of rotated files kept.
+    #[must_use]
+    pub const fn with_rotation(mut self, max_file_bytes: u64, max_files: usize) -> Self {
+        self.max_file_bytes = max_file_bytes;
+        self.max_files = max_files;
+        self
+    }
+
+    /// Log file path.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Prepare a body for logging: scrub secrets, then truncate.
+    #[must_use]
+    pub fn body(&self, raw: &str) -> String {
+        let scrubbed = scrub_secrets(raw, COMMON_SECRET_PATTERNS);
+        if self.full_bodies || scrubbed.chars().count() <= self.max_body_chars {
+            return scrubbed;
+        }
+
+        let mut truncated: String = scrubbed.chars().take(self.max_body_chars).collect();
+        truncated.push_str(TRUNCATED);
+        truncated
+    }
+
+    /// Remove entries whose bodies quote any of `messages` from the live
+    /// and rotated files. A truncated body that ends partway into a
+    /// message counts as quoting it.
+    ///
+    /// Returns the number of entries removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a log file cannot be read or rewritten.
+    pub fn forget(&self, messages: &[String]) -> std::io::Result<usize> {
+        let needles: Vec<String> = messages
+            .iter()
+            .filter(|m| !m.is_empty())
+            .filter_map(|m| serde_json::to_string(m).ok())
+            .map(|quoted| scrub_secrets(&quoted[1..quoted.len() - 1], COMMON_SECRET_PATTERNS))
+            .collect();
+        if needles.is_empty() {
+            return Ok(0);
+        }
+
+        let mut guard = self
+            .file
+            .lock()
+            .map_err(|_| std::io::Error::other("wire log lock poisoned"))?;
+        *guard = None;
+
+        let mut removed = 0;
+        let files = std::iter::once(self.path.clone()).chain(
+            (1..=self.max_files).map(|n| PathBuf::from(format!("{}.{n}", self.path.display()))),
+        );
+        for path in files {
+            let content = match std::fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            let mut kept = String::with_capacity(content.len());
+            for line in content.lines() {
+                if quotes_any(line, &needles) {
+                    removed += 1;
+                } else {
+                    kept.push_str(line);
+                    kept.push('\n');
+                }
+            }
+            // Rewritten in place, so other handles appending to it keep
+            // writing to the live file
+            if kept.len() != content.len() {
+                std::fs::write(&path, kept)?;
+            }
+        }
+        drop(guard);
+        Ok(removed)
+    }
+
+    /// Append an entry, rotating the file if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the log file cannot be written.
+    pub fn write(&self, entry: &WireLogEntry) -> std::io::Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        let mut guard = self
+            .file
+            .lock()
+            .map_err(|_| std::io::Error::other("wire log lock poisoned"))?;
+
+        let current = std::fs::metadata(&self.path).map_or(0, |m| m.len());
+        if current > 0 && current + line.len() as u64 > self.max_file_bytes {
+            *guard = None;
+            self.rotate()?;
+        }
+
+        if guard.is_none() {
+            if let Some(parent) = self.path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            *guard = Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)?,
+            );
+        }
+
+        if let Some(file) = guard.as_mut() {
+            file.write_all(line.as_bytes())?;
+        }
+        drop(guard);
+        Ok(())
+    }
+
+    /// Shift `providers.jsonl.N` up by one and move the live file to `.1`.
+    fn rotate(&self) -> std::io::Result<()> {
+        let rotated = |n: usize| PathBuf::from(format!("{}.{n}", self.path.display()));
+
+        if self.max_files == 0 {
+            return std::fs::remove_file(&self.path);
+        }
+
+        let _ = std::fs::remove_file(rotated(self.max_files));
+        for n in (1..self.max_files).rev() {
+            let from = rotated(n);
+            if from.exists() {
+                std::fs::rename(&from, rotated(n + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, rotated(1))
+    }
+}
+
+/// Whether a logged line's request, response, or error quotes a needle.
+fn quotes_any(line: &str, needles: &[String]) -> bool {
+    let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) else {
+        return false;
+    };
+    ["request", "response", "error"]
+        .iter()
+        .filter_map(|field| entry[field].as_str())
+        .any(|body| needles.iter().any(|needle| quotes(body, needle)))
+}
+
+/// Whether `body` contains `needle`, or was cut off partway into it.
+fn quotes(body: &str, needle: &str) -> bool {
+    if body.contains(needle) {
+        return true;
+    }
+    body.strip_suffix(TRUNCATED).is_some_and(|cut| {
+        (MIN_PARTIAL_BYTES.min(needle.len())..needle.len())
+            .any(|n| needle.is_char_boundary(n) && cut.ends_with(&needle[..n]))
+    })
+}
+
+impl std::fmt::Debug for WireLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WireLog")
+            .field("path", &self.path)
+            .field("full_bodies", &self.full_bodies)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Provider wrapper that records every call in a [`WireLog`].
+pub struct LoggedProvider {
+    inner: Arc<dyn Provider>,
+    log: Arc<WireLog>,
+}
+
+impl LoggedProvider {
+    /// Wrap a provider.
+    #[must_use]
+    pub const fn new(inner: Arc<dyn Provider>, log: Arc<WireLog>) -> Self {
+        Self { inner, log }
+    }
+
+    fn entry(
+        &self,
+        request: &CompletionRequest,
+        streaming: bool,
+        started: Instant,
+    ) -> WireLogEntry {
+        WireLogEntry {
+            timestamp: Utc::now(),
+            provider: self.inner.name().to_string(),
+            model: request.model.clone(),
+            streaming,
+            messages: request.messages.len(),
+            tools: request.tools.as_ref().map_or(0, Vec::len),
+            latency_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+            request: serde_json::to_string(request)
+                .ok()
+                .map(|body| self.log.body(&body)),
+            response: None,
+            usage: None,
+            stop_reason: None,
+            error: None,
+        }
+    }
+
+    fn record(&self, entry: &WireLogEntry) {
+        if let Err(e) = self.log.write(entry) {
+            tracing::warn!("Failed to write provider wire log: {}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for LoggedProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn supports_vision(&self, model: &str) -> bool {
+        self.inner.supports_vision(model)
+    }
+
+    async fn moderate(&self, input: &str) -> Result<ModerationResult, ProviderError> {
+        self.inner.moderate(input).await
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
+        self.inner.list_models().await
+    }
+
+    async fn complete(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<CompletionResponse, ProviderError> {
+        let started = Instant::now();
+        let logged = request.clone();
+        let result = self.inner.complete(request).await;
+
+        let mut entry = self.entry(&logged, false, started);
+        match &result {
+            Ok(response) => {
+                entry.response = serde_json::to_string(&response.content)
+                    .ok()
+                    .map(|body| self.log.body(&body));
+                entry.usage = Some(response.usage.clone());
+                entry.stop_reason = response.stop_reason;
+            }
+            Err(e) => entry.error = Some(self.log.body(&e.to_string())),
+        }
+        self.record(&entry);
+
+        result
+    }
+
+    async fn complete_stream(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<
+        Pin<Box<dyn futures::Stream<Item = Result<StreamingChunk, ProviderError>> + Send>>,
+        ProviderError,
+    > {
+        let started = Instant::now();
+        let logged = request.clone();
+        let result = self.inner.complete_stream(request).await;
+
+        // Only the request and time-to-stream are logged for streaming calls
+        let mut entry = self.entry(&logged, true, started);
+        if let Err(e) = &result {
+            entry.error = Some(self.log.body(&e.to_string()));
+        }
+        self.record(&entry);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{ContentBlock, Message, MessageContent, Role};
+    use tempfile::tempdir;
+
+    struct EchoProvider;
+
+    #[async_trait]
+    impl Provider for EchoProvider {
+        fn name(&self) -> &'static str {
+            "echo"
+        }
+
+        async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
+            Ok(vec!["echo-1".to_string()])
+        }
+
+        async fn complete(
+            &self,
+            request: CompletionRequest,
+        ) -> Result<CompletionResponse, ProviderError> {
+            Ok(CompletionResponse {
+                id: "resp-1".to_string(),
+                model: request.model,
+                content: vec![ContentBlock::Text {
+                    text: "a".repeat(100),
+                }],
+                stop_reason: Some(StopReason::EndTurn),
+                usage: TokenUsage::default(),
+            })
+        }
+
+        async fn complete_stream(
+            &self,
+            _request: CompletionRequest,
+        ) -> Result<
+            Pin<Box<dyn futures::Stream<Item = Result<StreamingChunk, ProviderError>> + Send>>,
+            ProviderError,
+        > {
+            Err(ProviderError::Config("not supported".to_string()))
+        }
+    }
+
+    fn request(text: &str) -> CompletionRequest {
+        CompletionRequest {
+            model: "echo-1".to_string(),
+            messages: vec![Message {
+                role: Role::User,
+                content: MessageContent::Text(text.to_string()),
+            }],
+            system: None,
+            max_tokens: 100,
+            temperature: 0.0,
+            stop: None,
+            tools: None,
+            response_format: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_logged_provider_writes_scrubbed_entries() {
+        let temp = tempdir().unwrap();
+        let log =
+            Arc::new(WireLog::new(temp.path().join("providers.jsonl")).with_max_body_chars(40));
+        let provider = LoggedProvider::new(Arc::new(EchoProvider), log.clone());
+
+        provider
+            .complete(request("use token=abc123 please"))
+            .await
+            .unwrap();
+        assert!(provider.complete_stream(request("hi")).await.is_err());
+
+        let content = std::fs::read_to_string(log.path()).unwrap();
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+
+        let first = &lines[0];
+        assert_eq!(first["provider"], "echo");
+        assert_eq!(first["stop_reason"], "end_turn");
+        assert!(!content.contains("abc123"));
+        assert!(
+            first["response"]
+                .as_str()
+                .unwrap()
+                .ends_with("...[truncated]")
+        );
+
+        assert_eq!(lines[1]["streaming"], true);
+        assert!(lines[1]["error"].is_string());
+    }
+
+    #[test]
+    fn test_rotation() {
+        let temp = tempdir().unwrap();
+        let log = WireLog::new(temp.path().join("providers.jsonl")).with_rotation(200, 2);
+        let entry = WireLogEntry {
+            timestamp: Utc::now(),
+            provider: "echo".to_string(),
+            model: "echo-1".to_string(),
+            streaming: false,
+            messages: 1,
+            tools: 0,
+            latency_ms: 1,
+            request: None,
+            response: None,
+            usage: None,
+            stop_reason: None,
+            error: None,
+        };
+
+        for _ in 0..10 {
+            log.write(&entry).unwrap();
+        }
+
+        assert!(log.path().exists());
+        assert!(temp.path().join("providers.jsonl.1").exists());
+        assert!(temp.path().join("providers.jsonl.2").exists());
+        assert!(!temp.path().join("providers.jsonl.3").exists());
+    }
+
+    #[tokio::test]
+    async fn test_forget() {
+        let temp = tempdir().unwrap();
+        let log = Arc::new(
+            WireLog::new(temp.path().join("providers.jsonl"))
+                .with_max_body_chars(100)
+                .with_rotation(400, 2),
+        );
+        let provider = LoggedProvider::new(Arc::new(EchoProvider), log.clone());
+        // Long enough to be cut off by the body limit
+        let secret = "My \"address\" is 1 Main St, I'm free after six and my door code is 4471";
+        for text in ["hello", secret, "goodbye"] {
+            provider.complete(request(text)).await.unwrap();
+        }
+        let read_all = || {
+            ["providers.jsonl.2", "providers.jsonl.1", "providers.jsonl"]
+                .iter()
+                .filter_map(|name| std::fs::read_to_string(temp.path().join(name)).ok())
+                .collect::<String>()
+        };
+        assert!(read_all().contains("...[truncated]"));
+        assert!(!read_all().contains("4471"));
+
+        let removed = log.forget(&[secret.to_string()]).unwrap();
+        assert_eq!(removed, 1);
+        let content = read_all();
+        assert_eq!(content.lines().count(), 2);
+        assert!(!content.contains("Main St"));
+        assert!(content.contains("goodbye"));
+
+        // Writing carries on after the files were rewritten
+        provider.complete(request("again")).await.unwrap();
+        assert!(
+            std::fs::read_to_string(log.path())
+                .unwrap()
+                .contains("again")
+        );
+        assert_eq!(log.forget(&[secret.to_string()]).unwrap(), 0);
+    }
+}
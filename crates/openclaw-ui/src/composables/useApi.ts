@@ -1,10 +1,13 @@
 import { ref } from 'vue'
 import { useAuthStore } from '@/stores/auth'
+import type { RpcMethod, RpcParams } from '@/generated/gateway'
+
+export type { RpcMethod, RpcParams } from '@/generated/gateway'
 
 export interface RpcRequest {
   jsonrpc: '2.0'
-  method: string
-  params: Record<string, unknown>
+  method: RpcMethod
+  params: RpcParams[RpcMethod]
   id: string
 }
 
@@ -15,20 +18,31 @@ export interface RpcResponse<T = unknown> {
   id: string
 }
 
+// Gateway URL announced by the UI server when the gateway is behind a
+// reverse proxy
+export function gatewayBaseUrl(): string | null {
+  const meta = document.querySelector<HTMLMetaElement>('meta[name="openclaw-api-base"]')
+  return meta?.content ? meta.content.replace(/\/$/, '') : null
+}
+
 export function useApi() {
   const authStore = useAuthStore()
   const loading = ref(false)
   const error = ref<string | null>(null)
 
   // Determine API base URL
-  // In production (embedded), use the gateway API port
+  // In production (embedded), use the announced gateway URL or the gateway API port
   // In development, use the Vite proxy
-  const apiBaseUrl = import.meta.env.VITE_API_BASE_URL ||
+  const apiBaseUrl = import.meta.env.VITE_API_BASE_URL || gatewayBaseUrl() ||
     (import.meta.env.PROD
       ? `${window.location.protocol}//${window.location.hostname}:18789`
       : '')
 
-  async function call<T>(method: string, params: Record<string, unknown> = {}): Promise<T> {
+  // Method names and parameters are checked against the gateway's catalog
+  async function call<T, M extends RpcMethod = RpcMethod>(
+    method: M,
+    params: RpcParams[M] = {} as RpcParams[M],
+  ): Promise<T> {
     loading.value = true
     error.value = null
 
@@ -0,0 +1,106 @@
+import { useApi } from '@/composables/useApi'
+import { useAuthStore } from '@/stores/auth'
+
+export interface Attachment {
+  kind: string
+  url: string
+  mime_type: string | null
+  filename: string | null
+  size: number | null
+  thumbnail_url: string | null
+}
+
+export interface UploadStatus {
+  upload_id: string
+  name: string
+  size: number
+  received: number
+  chunk_size: number
+  completed: boolean
+  attachment: Attachment | null
+}
+
+export type WorkspaceTarget = { agent_id: string } | { session_key: string }
+
+// Streams files to and from the gateway in chunks; see the gateway's
+// transfer module for the protocol
+export function useTransfers() {
+  const authStore = useAuthStore()
+  const { apiBaseUrl } = useApi()
+
+  function authHeaders(): Record<string, string> {
+    return authStore.token ? { Authorization: `Bearer ${authStore.token}` } : {}
+  }
+
+  async function check(response: Response): Promise<Response> {
+    if (!response.ok) {
+      throw new Error((await response.text()) || response.statusText)
+    }
+    return response
+  }
+
+  async function uploadStatus(uploadId: string): Promise<UploadStatus> {
+    const response = await fetch(`${apiBaseUrl}/uploads/${uploadId}`, {
+      headers: authHeaders(),
+    })
+    return (await check(response)).json()
+  }
+
+  // Upload a file chunk by chunk. A chunk the gateway rejects as out of
+  // order (after a dropped connection) resumes from what it has.
+  async function uploadFile(
+    file: File,
+    options: { sessionKey?: string; onProgress?: (received: number, total: number) => void } = {},
+  ): Promise<Attachment> {
+    const started = await fetch(`${apiBaseUrl}/uploads`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json', ...authHeaders() },
+      body: JSON.stringify({
+        name: file.name,
+        size: file.size,
+        mime_type: file.type || undefined,
+        session_key: options.sessionKey,
+      }),
+    })
+    let status: UploadStatus = await (await check(started)).json()
+
+    while (!status.completed) {
+      const offset = status.received
+      const response = await fetch(
+        `${apiBaseUrl}/uploads/${status.upload_id}?offset=${offset}`,
+        {
+          method: 'PUT',
+          headers: { 'Content-Type': 'application/octet-stream', ...authHeaders() },
+          body: file.slice(offset, offset + status.chunk_size),
+        },
+      )
+      status = response.status === 409
+        ? await uploadStatus(status.upload_id)
+        : await (await check(response)).json()
+      options.onProgress?.(status.received, status.size)
+    }
+    if (!status.attachment) {
+      throw new Error(`Upload of ${file.name} did not complete`)
+    }
+    return status.attachment
+  }
+
+  // Link to a workspace file. The token rides in the query so the link
+  // works as a plain download without buffering the file in the page.
+  function workspaceFileUrl(target: WorkspaceTarget, path: string): string {
+    const params = new URLSearchParams({ ...target, path })
+    if (authStore.token) {
+      params.set('token', authStore.token)
+    }
+    return `${apiBaseUrl}/workspace/file?${params}`
+  }
+
+  function downloadWorkspaceFile(target: WorkspaceTarget, path: string) {
+    const link = document.createElement('a')
+    link.href = workspaceFileUrl(target, path)
+    link.download = path.split('/').pop() ?? path
+    link.click()
+  }
+
+  return { uploadFile, uploadStatus, workspaceFileUrl, downloadWorkspaceFile }
+}
@@ -1,16 +1,9 @@
 import { ref, onMounted, onUnmounted } from 'vue'
 import { useAuthStore } from '@/stores/auth'
+import { gatewayBaseUrl } from '@/composables/useApi'
+import type { UiEventEnvelope } from '@/generated/gateway'
 
-export interface UiEvent {
-  type: string
-  [key: string]: unknown
-}
-
-export interface UiEventEnvelope {
-  id: string
-  timestamp: string
-  event: UiEvent
-}
+export type { UiEvent, UiEventEnvelope, UiEventType } from '@/generated/gateway'
 
 export function useWebSocket() {
   const authStore = useAuthStore()
@@ -21,6 +14,7 @@ export function useWebSocket() {
 
   // Determine WebSocket URL
   const wsBaseUrl = import.meta.env.VITE_WS_BASE_URL ||
+    gatewayBaseUrl()?.replace(/^http/, 'ws') ||
     (import.meta.env.PROD
       ? `ws://${window.location.hostname}:18789`
       : `ws://${window.location.hostname}:18789`)
@@ -0,0 +1,851 @@
+// Generated from the gateway's Rust definitions. Do not edit.
+// Regenerate with: OPENCLAW_UPDATE_GENERATED=1 cargo test -p openclaw-gateway codegen
+
+export type Permission = 'public' | 'viewer' | 'operator' | 'admin'
+
+/** Log in and receive access and refresh tokens */
+export interface AuthLoginParams {
+  /** Username */
+  username: string
+  /** Password */
+  password: string
+}
+
+/** End the current login */
+export interface AuthLogoutParams {
+  /** End every login of the user (default false) */
+  all?: boolean
+}
+
+/** Exchange a refresh token for new tokens */
+export interface AuthRefreshParams {
+  /** Refresh token */
+  refresh_token: string
+}
+
+/** The authenticated user */
+export type AuthMeParams = Record<string, never>
+
+/** Whether single sign-on is available */
+export type AuthOidcStatusParams = Record<string, never>
+
+/** Usernames and addresses with recent failed logins */
+export type AuthLockoutsListParams = Record<string, never>
+
+/** Clear failed logins and lockouts */
+export interface AuthLockoutsClearParams {
+  /** user:<name> or ip:<address> (default everything) */
+  key?: string
+}
+
+/** Open login sessions */
+export interface AuthSessionsListParams {
+  /** Only this user's sessions */
+  user_id?: string
+}
+
+/** End a login session or every session of a user */
+export interface AuthSessionsRevokeParams {
+  /** Session to end */
+  session_id?: string
+  /** User whose sessions to end */
+  user_id?: string
+}
+
+/** Whether the gateway still needs an admin user */
+export type SetupStatusParams = Record<string, never>
+
+/** Create the first admin user */
+export interface SetupInitParams {
+  /** Token printed at startup */
+  bootstrap_token: string
+  /** Admin username */
+  admin_username: string
+  /** Admin password */
+  admin_password: string
+  /** Admin email */
+  email?: string
+}
+
+/** List users */
+export type UsersListParams = Record<string, never>
+
+/** Create a user */
+export interface UsersCreateParams {
+  /** Username */
+  username: string
+  /** Password */
+  password: string
+  /** Role (default viewer) */
+  role?: 'admin' | 'operator' | 'viewer'
+  /** Email */
+  email?: string
+}
+
+/** Change a user's role, status, or email */
+export interface UsersUpdateParams {
+  /** User ID */
+  id: string
+  /** New role */
+  role?: 'admin' | 'operator' | 'viewer'
+  /** Enable or disable the account */
+  active?: boolean
+  /** New email */
+  email?: string
+}
+
+/** Delete a user other than the last admin */
+export interface UsersDeleteParams {
+  /** User ID */
+  id: string
+}
+
+/** Current log filter directives */
+export type AdminLogLevelGetParams = Record<string, never>
+
+/** Change the log filter without a restart */
+export interface AdminLogLevelSetParams {
+  /** Full filter directives */
+  directives?: string
+  /** Level, used when directives is not given */
+  level?: string
+  /** Module the level applies to */
+  module?: string
+}
+
+/** Restore the startup log filter */
+export type AdminLogLevelResetParams = Record<string, never>
+
+/** Pending tool approvals */
+export type ApprovalsListParams = Record<string, never>
+
+/** Approve a pending tool call */
+export interface ApprovalsApproveParams {
+  /** Approval ID */
+  id: string
+}
+
+/** Deny a pending tool call */
+export interface ApprovalsDenyParams {
+  /** Approval ID */
+  id: string
+  /** Reason passed back to the agent */
+  reason?: string
+}
+
+/** Profiles of the people agents talk to */
+export interface PeersListParams {
+  /** Only peers on this channel */
+  channel?: string
+}
+
+/** A peer's profile */
+export interface PeersGetParams {
+  /** Channel ID */
+  channel: string
+  /** Peer ID on the channel */
+  peer_id: string
+}
+
+/** Set profile fields; empty strings clear them */
+export interface PeersUpdateParams {
+  /** Channel ID */
+  channel: string
+  /** Peer ID on the channel */
+  peer_id: string
+  /** Name to call the peer */
+  display_name?: string
+  /** Preferred language (BCP 47 tag) */
+  language?: string
+  /** IANA timezone */
+  timezone?: string
+  /** Notes shown to agents */
+  notes?: string
+  /** Preference flags by name; null removes one */
+  preferences?: Record<string, unknown>
+}
+
+/** Delete a peer's profile */
+export interface PeersDeleteParams {
+  /** Channel ID */
+  channel: string
+  /** Peer ID on the channel */
+  peer_id: string
+}
+
+/** Erase a peer's sessions, profile, uploads, and workspaces */
+export interface PrivacyForgetParams {
+  /** Channel ID */
+  channel: string
+  /** Peer ID on the channel */
+  peer_id: string
+}
+
+/** Drain requests and stop the gateway */
+export type GatewayShutdownParams = Record<string, never>
+
+/** Start a session */
+export interface SessionCreateParams {
+  /** Agent (default "default") */
+  agent_id?: string
+  /** Channel (default "api") */
+  channel?: string
+  /** Peer (default "anonymous") */
+  peer_id?: string
+  /** Thread the session covers */
+  thread_id?: string
+}
+
+/** Send a message and wait for the agent's response */
+export interface SessionMessageParams {
+  /** Session key */
+  session_key: string
+  /** Message text */
+  message: string
+  /** Attachments */
+  attachments?: unknown[]
+  /** Agent for a new session */
+  agent_id?: string
+  /** JSON schema the reply must match */
+  schema?: Record<string, unknown>
+  /** Name of the schema (default "response") */
+  schema_name?: string
+  /** Who sent the message, in a group (default the session's peer) */
+  sender_id?: string
+}
+
+/** A session's events */
+export interface SessionHistoryParams {
+  /** Session key */
+  session_key: string
+}
+
+/** End a session */
+export interface SessionEndParams {
+  /** Session key */
+  session_key: string
+  /** Reason (default "user_requested") */
+  reason?: string
+}
+
+/** List sessions */
+export interface SessionListParams {
+  /** Maximum sessions (default 50) */
+  limit?: number
+  /** Sessions to skip */
+  offset?: number
+  /** Only this channel */
+  channel?: string
+  /** Only this agent */
+  agent?: string
+  /** Only sessions in this state */
+  state?: string
+  /** Include deleted sessions */
+  include_deleted?: boolean
+}
+
+/** Search message content across sessions */
+export interface SessionSearchParams {
+  /** Search text */
+  query: string
+  /** Only this channel */
+  channel?: string
+  /** Only this agent */
+  agent?: string
+  /** Maximum results (default 20) */
+  limit?: number
+}
+
+/** Session counts */
+export type SessionStatsParams = Record<string, never>
+
+/** Daily agent, channel, and error counters */
+export interface StatsGetParams {
+  /** Days ending at `to` (default 30) */
+  days?: number
+  /** First day */
+  from?: string
+  /** Last day (default today, UTC) */
+  to?: string
+  /** Only this agent */
+  agent_id?: string
+  /** Only this channel */
+  channel?: string
+}
+
+/** A session's raw events */
+export interface SessionEventsParams {
+  /** Session key */
+  session_key: string
+  /** Only events after this time */
+  since?: string
+}
+
+/** Soft-delete a session */
+export interface SessionDeleteParams {
+  /** Session key */
+  session_key: string
+  /** Reason */
+  reason?: string
+}
+
+/** Restore a soft-deleted session */
+export interface SessionRestoreParams {
+  /** Session key */
+  session_key: string
+}
+
+/** Permanently delete a soft-deleted session */
+export interface SessionPurgeParams {
+  /** Session key */
+  session_key: string
+}
+
+/** Set or clear a session's dry-run mode */
+export interface SessionDryRunParams {
+  /** Session key */
+  session_key: string
+  /** Mode, or null to clear */
+  mode?: 'simulate' | 'plan'
+}
+
+/** Copy a session up to an event into a new session */
+export interface SessionForkParams {
+  /** Session key */
+  session_key: string
+  /** Last event to copy (ULID) */
+  event_id: string
+  /** Suffix for the new session key; random if omitted */
+  suffix?: string
+}
+
+/** Re-run a session's turns with another model or prompt and diff the replies */
+export interface SessionReplayParams {
+  /** Session key */
+  session_key: string
+  /** Agent to replay with; the session's agent if omitted */
+  agent_id?: string
+  /** Model to replay with */
+  model?: string
+  /** Sampling temperature */
+  temperature?: number
+  /** Maximum tokens per reply */
+  max_tokens?: number
+  /** System prompt to replay with */
+  system_prompt?: string
+}
+
+/** Configured channels */
+export type ChannelsListParams = Record<string, never>
+
+/** Channel health */
+export type ChannelsStatusParams = Record<string, never>
+
+/** Check a channel's connectivity */
+export interface ChannelsProbeParams {
+  /** Channel ID */
+  channel_id: string
+}
+
+/** Send a message through a channel */
+export interface ChannelsSendParams {
+  /** Channel ID */
+  channel_id: string
+  /** Chat to send to */
+  chat_id: string
+  /** Message text (required without a template) */
+  text?: string
+  /** Approved WhatsApp template (name, language, parameters) to send instead */
+  template?: Record<string, unknown>
+  /** Message to reply to */
+  reply_to?: string
+  /** Thread to post in */
+  thread_id?: string
+}
+
+/** Start a channel */
+export interface ChannelsStartParams {
+  /** Channel ID */
+  channel_id: string
+}
+
+/** Stop a channel */
+export interface ChannelsStopParams {
+  /** Channel ID */
+  channel_id: string
+}
+
+/** Enable and start a channel, saving the setting */
+export interface ChannelsEnableParams {
+  /** Channel ID */
+  channel_id: string
+}
+
+/** Drain and stop a channel, keeping it stopped across restarts */
+export interface ChannelsDisableParams {
+  /** Channel ID */
+  channel_id: string
+}
+
+/** Send a message through the mock channel and wait for the reply */
+export interface MockInjectParams {
+  /** Message text */
+  text: string
+  /** Sender; dev if omitted */
+  peer_id?: string
+  /** Chat to send in; a DM if omitted */
+  chat_id?: string
+  /** Thread the message is sent in */
+  thread_id?: string
+  /** Message this one replies to */
+  reply_to?: string
+  /** Attached media */
+  attachments?: unknown[]
+  /** Agent to answer; routed if omitted */
+  agent_id?: string
+}
+
+/** React to a message through the mock channel */
+export interface MockReactParams {
+  /** Message reacted to */
+  message_id: string
+  /** Reaction emoji */
+  emoji: string
+  /** Who reacted; dev if omitted */
+  peer_id?: string
+  /** Chat the message is in; a DM if omitted */
+  chat_id?: string
+  /** Whether the reaction was removed */
+  removed?: boolean
+  /** Agent whose session it is; routed if omitted */
+  agent_id?: string
+}
+
+/** Messages the mock channel was asked to deliver */
+export interface MockOutboundParams {
+  /** Clear the recorded messages */
+  clear?: boolean
+}
+
+/** Start a registered workflow */
+export interface WorkflowRunParams {
+  /** Workflow ID */
+  workflow_id: string
+  /** Workflow input */
+  input?: Record<string, unknown>
+  /** Channel to ask for input in */
+  channel?: string
+  /** Peer whose reply resumes the run */
+  peer_id?: string
+  /** Chat to ask in; a DM if omitted */
+  chat_id?: string
+}
+
+/** Workflow runs waiting for input */
+export type WorkflowWaitingParams = Record<string, never>
+
+/** Answer a workflow run waiting for input */
+export interface WorkflowReplyParams {
+  /** Run ID */
+  run_id: string
+  /** Text reply */
+  text?: string
+  /** Structured reply, e.g. form fields */
+  data?: Record<string, unknown>
+}
+
+/** Recorded workflow runs, newest first */
+export interface WorkflowRunsListParams {
+  /** Only runs of this workflow */
+  workflow_id?: string
+  /** Maximum runs (default 50) */
+  limit?: number
+}
+
+/** A workflow run's steps and execution graph */
+export interface WorkflowRunsGetParams {
+  /** Run ID */
+  run_id: string
+}
+
+/** Notification templates */
+export type NotifyTemplatesParams = Record<string, never>
+
+/** Send a notification template through a channel */
+export interface NotifySendParams {
+  /** Template name */
+  template: string
+  /** Template variables */
+  vars?: Record<string, unknown>
+  /** Channel ID */
+  channel: string
+  /** Chat to send to */
+  chat: string
+  /** Thread to post in */
+  thread_id?: string
+}
+
+/** Send one message to many chats */
+export interface BroadcastSendParams {
+  /** Message text */
+  text: string
+  /** Channel ID (defaults to the group's) */
+  channel?: string
+  /** Configured broadcast group */
+  group?: string
+  /** Include peers allowlisted on the channel */
+  allowlist?: boolean
+  /** Chats to send to */
+  chat_ids?: unknown[]
+}
+
+/** Delivery progress of a broadcast */
+export interface BroadcastStatusParams {
+  /** Broadcast ID */
+  broadcast_id: string
+}
+
+/** Registered agents */
+export type AgentListParams = Record<string, never>
+
+/** An agent's status */
+export interface AgentStatusParams {
+  /** Agent ID */
+  agent_id: string
+}
+
+/** An agent's configuration */
+export interface AgentGetParams {
+  /** Agent ID */
+  agent_id: string
+}
+
+/** Change a running agent's model parameters and save them to the config */
+export interface AgentUpdateParams {
+  /** Agent ID */
+  agent_id: string
+  /** Model name */
+  model?: string
+  /** Sampling temperature, 0 to 2 */
+  temperature?: number
+  /** Maximum tokens in a response */
+  max_tokens?: number
+  /** System prompt; empty clears it */
+  system_prompt?: string
+}
+
+/** Rate an agent response with a rating, reaction, or /feedback command */
+export interface FeedbackSubmitParams {
+  /** Session key */
+  session_key: string
+  /** Rating */
+  rating?: 'positive' | 'negative' | 'up' | 'down'
+  /** Comment, with rating */
+  comment?: string
+  /** Reaction emoji */
+  reaction?: string
+  /** /feedback command text */
+  command?: string
+  /** Rated response */
+  response_id?: string
+}
+
+/** Feedback counts */
+export interface FeedbackSummaryParams {
+  /** Only this agent */
+  agent_id?: string
+}
+
+/** Configured experiments */
+export type ExperimentsListParams = Record<string, never>
+
+/** Results of an experiment by variant */
+export interface ExperimentsReportParams {
+  /** Experiment ID */
+  experiment_id: string
+}
+
+/** Registered tools */
+export type ToolsListParams = Record<string, never>
+
+/** Run a tool */
+export interface ToolsExecuteParams {
+  /** Tool name */
+  tool_name: string
+  /** Tool parameters */
+  params?: Record<string, unknown>
+  /** Session to run in; the result joins its history */
+  session_key?: string
+  /** Agent to run as; the session's agent or `default` if omitted */
+  agent_id?: string
+}
+
+/** List workspace files */
+export interface WorkspaceListParams {
+  /** Agent whose workspace to read */
+  agent_id?: string
+  /** Session whose workspace to read */
+  session_key?: string
+  /** Directory (default root) */
+  path?: string
+}
+
+/** Read a workspace file */
+export interface WorkspaceReadParams {
+  /** Agent whose workspace to read */
+  agent_id?: string
+  /** Session whose workspace to read */
+  session_key?: string
+  /** File path */
+  path: string
+  /** Maximum bytes returned */
+  max_bytes?: number
+}
+
+/** Download a workspace file */
+export interface WorkspaceDownloadParams {
+  /** Agent whose workspace to read */
+  agent_id?: string
+  /** Session whose workspace to read */
+  session_key?: string
+  /** File path */
+  path: string
+}
+
+/** Gateway health */
+export type SystemHealthParams = Record<string, never>
+
+/** Gateway version */
+export type SystemVersionParams = Record<string, never>
+
+/** Startup report */
+export type SystemStartupParams = Record<string, never>
+
+/** This catalog as an OpenRPC document */
+export type RpcDiscoverParams = Record<string, never>
+
+/** Stream UI events over this WebSocket */
+export type EventsSubscribeParams = Record<string, never>
+
+export interface RpcParams {
+  'auth.login': AuthLoginParams
+  'auth.logout': AuthLogoutParams
+  'auth.refresh': AuthRefreshParams
+  'auth.me': AuthMeParams
+  'auth.oidc.status': AuthOidcStatusParams
+  'auth.lockouts.list': AuthLockoutsListParams
+  'auth.lockouts.clear': AuthLockoutsClearParams
+  'auth.sessions.list': AuthSessionsListParams
+  'auth.sessions.revoke': AuthSessionsRevokeParams
+  'setup.status': SetupStatusParams
+  'setup.init': SetupInitParams
+  'users.list': UsersListParams
+  'users.create': UsersCreateParams
+  'users.update': UsersUpdateParams
+  'users.delete': UsersDeleteParams
+  'admin.log_level.get': AdminLogLevelGetParams
+  'admin.log_level.set': AdminLogLevelSetParams
+  'admin.log_level.reset': AdminLogLevelResetParams
+  'approvals.list': ApprovalsListParams
+  'approvals.approve': ApprovalsApproveParams
+  'approvals.deny': ApprovalsDenyParams
+  'peers.list': PeersListParams
+  'peers.get': PeersGetParams
+  'peers.update': PeersUpdateParams
+  'peers.delete': PeersDeleteParams
+  'privacy.forget': PrivacyForgetParams
+  'gateway.shutdown': GatewayShutdownParams
+  'session.create': SessionCreateParams
+  'session.message': SessionMessageParams
+  'session.history': SessionHistoryParams
+  'session.end': SessionEndParams
+  'session.list': SessionListParams
+  'session.search': SessionSearchParams
+  'session.stats': SessionStatsParams
+  'stats.get': StatsGetParams
+  'session.events': SessionEventsParams
+  'session.delete': SessionDeleteParams
+  'session.restore': SessionRestoreParams
+  'session.purge': SessionPurgeParams
+  'session.dry_run': SessionDryRunParams
+  'session.fork': SessionForkParams
+  'session.replay': SessionReplayParams
+  'channels.list': ChannelsListParams
+  'channels.status': ChannelsStatusParams
+  'channels.probe': ChannelsProbeParams
+  'channels.send': ChannelsSendParams
+  'channels.start': ChannelsStartParams
+  'channels.stop': ChannelsStopParams
+  'channels.enable': ChannelsEnableParams
+  'channels.disable': ChannelsDisableParams
+  'mock.inject': MockInjectParams
+  'mock.react': MockReactParams
+  'mock.outbound': MockOutboundParams
+  'workflow.run': WorkflowRunParams
+  'workflow.waiting': WorkflowWaitingParams
+  'workflow.reply': WorkflowReplyParams
+  'workflow.runs.list': WorkflowRunsListParams
+  'workflow.runs.get': WorkflowRunsGetParams
+  'notify.templates': NotifyTemplatesParams
+  'notify.send': NotifySendParams
+  'broadcast.send': BroadcastSendParams
+  'broadcast.status': BroadcastStatusParams
+  'agent.list': AgentListParams
+  'agent.status': AgentStatusParams
+  'agent.get': AgentGetParams
+  'agent.update': AgentUpdateParams
+  'feedback.submit': FeedbackSubmitParams
+  'feedback.summary': FeedbackSummaryParams
+  'experiments.list': ExperimentsListParams
+  'experiments.report': ExperimentsReportParams
+  'tools.list': ToolsListParams
+  'tools.execute': ToolsExecuteParams
+  'workspace.list': WorkspaceListParams
+  'workspace.read': WorkspaceReadParams
+  'workspace.download': WorkspaceDownloadParams
+  'system.health': SystemHealthParams
+  'system.version': SystemVersionParams
+  'system.startup': SystemStartupParams
+  'rpc.discover': RpcDiscoverParams
+  'events.subscribe': EventsSubscribeParams
+}
+
+export type RpcMethod = keyof RpcParams
+
+export const RPC_METHODS: Record<RpcMethod, { summary: string; permission: Permission }> = {
+  'auth.login': { summary: 'Log in and receive access and refresh tokens', permission: 'public' },
+  'auth.logout': { summary: 'End the current login', permission: 'viewer' },
+  'auth.refresh': { summary: 'Exchange a refresh token for new tokens', permission: 'viewer' },
+  'auth.me': { summary: 'The authenticated user', permission: 'viewer' },
+  'auth.oidc.status': { summary: 'Whether single sign-on is available', permission: 'public' },
+  'auth.lockouts.list': { summary: 'Usernames and addresses with recent failed logins', permission: 'admin' },
+  'auth.lockouts.clear': { summary: 'Clear failed logins and lockouts', permission: 'admin' },
+  'auth.sessions.list': { summary: 'Open login sessions', permission: 'admin' },
+  'auth.sessions.revoke': { summary: 'End a login session or every session of a user', permission: 'admin' },
+  'setup.status': { summary: 'Whether the gateway still needs an admin user', permission: 'public' },
+  'setup.init': { summary: 'Create the first admin user', permission: 'public' },
+  'users.list': { summary: 'List users', permission: 'admin' },
+  'users.create': { summary: 'Create a user', permission: 'admin' },
+  'users.update': { summary: 'Change a user\'s role, status, or email', permission: 'admin' },
+  'users.delete': { summary: 'Delete a user other than the last admin', permission: 'admin' },
+  'admin.log_level.get': { summary: 'Current log filter directives', permission: 'admin' },
+  'admin.log_level.set': { summary: 'Change the log filter without a restart', permission: 'admin' },
+  'admin.log_level.reset': { summary: 'Restore the startup log filter', permission: 'admin' },
+  'approvals.list': { summary: 'Pending tool approvals', permission: 'viewer' },
+  'approvals.approve': { summary: 'Approve a pending tool call', permission: 'operator' },
+  'approvals.deny': { summary: 'Deny a pending tool call', permission: 'operator' },
+  'peers.list': { summary: 'Profiles of the people agents talk to', permission: 'viewer' },
+  'peers.get': { summary: 'A peer\'s profile', permission: 'viewer' },
+  'peers.update': { summary: 'Set profile fields; empty strings clear them', permission: 'operator' },
+  'peers.delete': { summary: 'Delete a peer\'s profile', permission: 'operator' },
+  'privacy.forget': { summary: 'Erase a peer\'s sessions, profile, uploads, and workspaces', permission: 'operator' },
+  'gateway.shutdown': { summary: 'Drain requests and stop the gateway', permission: 'admin' },
+  'session.create': { summary: 'Start a session', permission: 'operator' },
+  'session.message': { summary: 'Send a message and wait for the agent\'s response', permission: 'operator' },
+  'session.history': { summary: 'A session\'s events', permission: 'viewer' },
+  'session.end': { summary: 'End a session', permission: 'operator' },
+  'session.list': { summary: 'List sessions', permission: 'viewer' },
+  'session.search': { summary: 'Search message content across sessions', permission: 'viewer' },
+  'session.stats': { summary: 'Session counts', permission: 'viewer' },
+  'stats.get': { summary: 'Daily agent, channel, and error counters', permission: 'viewer' },
+  'session.events': { summary: 'A session\'s raw events', permission: 'viewer' },
+  'session.delete': { summary: 'Soft-delete a session', permission: 'operator' },
+  'session.restore': { summary: 'Restore a soft-deleted session', permission: 'operator' },
+  'session.purge': { summary: 'Permanently delete a soft-deleted session', permission: 'operator' },
+  'session.dry_run': { summary: 'Set or clear a session\'s dry-run mode', permission: 'operator' },
+  'session.fork': { summary: 'Copy a session up to an event into a new session', permission: 'operator' },
+  'session.replay': { summary: 'Re-run a session\'s turns with another model or prompt and diff the replies', permission: 'operator' },
+  'channels.list': { summary: 'Configured channels', permission: 'viewer' },
+  'channels.status': { summary: 'Channel health', permission: 'viewer' },
+  'channels.probe': { summary: 'Check a channel\'s connectivity', permission: 'viewer' },
+  'channels.send': { summary: 'Send a message through a channel', permission: 'viewer' },
+  'channels.start': { summary: 'Start a channel', permission: 'operator' },
+  'channels.stop': { summary: 'Stop a channel', permission: 'operator' },
+  'channels.enable': { summary: 'Enable and start a channel, saving the setting', permission: 'admin' },
+  'channels.disable': { summary: 'Drain and stop a channel, keeping it stopped across restarts', permission: 'admin' },
+  'mock.inject': { summary: 'Send a message through the mock channel and wait for the reply', permission: 'operator' },
+  'mock.react': { summary: 'React to a message through the mock channel', permission: 'operator' },
+  'mock.outbound': { summary: 'Messages the mock channel was asked to deliver', permission: 'operator' },
+  'workflow.run': { summary: 'Start a registered workflow', permission: 'operator' },
+  'workflow.waiting': { summary: 'Workflow runs waiting for input', permission: 'viewer' },
+  'workflow.reply': { summary: 'Answer a workflow run waiting for input', permission: 'operator' },
+  'workflow.runs.list': { summary: 'Recorded workflow runs, newest first', permission: 'viewer' },
+  'workflow.runs.get': { summary: 'A workflow run\'s steps and execution graph', permission: 'viewer' },
+  'notify.templates': { summary: 'Notification templates', permission: 'viewer' },
+  'notify.send': { summary: 'Send a notification template through a channel', permission: 'viewer' },
+  'broadcast.send': { summary: 'Send one message to many chats', permission: 'operator' },
+  'broadcast.status': { summary: 'Delivery progress of a broadcast', permission: 'operator' },
+  'agent.list': { summary: 'Registered agents', permission: 'viewer' },
+  'agent.status': { summary: 'An agent\'s status', permission: 'viewer' },
+  'agent.get': { summary: 'An agent\'s configuration', permission: 'viewer' },
+  'agent.update': { summary: 'Change a running agent\'s model parameters and save them to the config', permission: 'admin' },
+  'feedback.submit': { summary: 'Rate an agent response with a rating, reaction, or /feedback command', permission: 'viewer' },
+  'feedback.summary': { summary: 'Feedback counts', permission: 'viewer' },
+  'experiments.list': { summary: 'Configured experiments', permission: 'viewer' },
+  'experiments.report': { summary: 'Results of an experiment by variant', permission: 'viewer' },
+  'tools.list': { summary: 'Registered tools', permission: 'viewer' },
+  'tools.execute': { summary: 'Run a tool', permission: 'viewer' },
+  'workspace.list': { summary: 'List workspace files', permission: 'viewer' },
+  'workspace.read': { summary: 'Read a workspace file', permission: 'viewer' },
+  'workspace.download': { summary: 'Download a workspace file', permission: 'viewer' },
+  'system.health': { summary: 'Gateway health', permission: 'public' },
+  'system.version': { summary: 'Gateway version', permission: 'public' },
+  'system.startup': { summary: 'Startup report', permission: 'viewer' },
+  'rpc.discover': { summary: 'This catalog as an OpenRPC document', permission: 'public' },
+  'events.subscribe': { summary: 'Stream UI events over this WebSocket', permission: 'viewer' },
+}
+
+export type SessionUpdate =
+  | { state_changed: { new_state: string } }
+  | { message_count: { count: number } }
+  | { ended: { reason: string } }
+
+export interface ApprovalRequest {
+  id: string
+  agent_id: string
+  session_key: string | null
+  tool: string
+  params: unknown
+  requested_at: string
+  expires_at: string
+}
+
+export type ApprovalDecision =
+  | { decision: 'approved'; by: string }
+  | { decision: 'denied'; by: string; reason: string | null }
+  | { decision: 'timed_out' }
+
+export interface ConfigChange {
+  field: string
+  old: unknown
+  new: unknown
+}
+
+export type UiEvent =
+  | { type: 'session_created'; session_key: string; agent_id: string; channel: string; peer_id: string }
+  | { type: 'session_updated'; session_key: string; update: SessionUpdate }
+  | { type: 'message_received'; session_key: string; content: string; peer_id: string }
+  | { type: 'message_sent'; session_key: string; content: string }
+  | { type: 'tool_executed'; session_key: string; tool: string; result: unknown; success: boolean }
+  | { type: 'content_moderated'; session_key: string; agent_id: string; stage: 'inbound' | 'outbound'; moderator: string; reason: string; categories: string[]; blocked: boolean }
+  | { type: 'reminder_failed'; session_key: string; reminder_id: string; error: string }
+  | { type: 'pending_approval'; request: ApprovalRequest }
+  | { type: 'approval_resolved'; id: string; decision: ApprovalDecision }
+  | { type: 'workflow_input_requested'; run_id: string; workflow_id: string; node_id: string; prompt: string; choices: string[]; expires_at: string | null }
+  | { type: 'workflow_input_resolved'; run_id: string; timed_out: boolean }
+  | { type: 'channel_status_changed'; channel_id: string; connected: boolean; error: string | null }
+  | { type: 'channel_state_changed'; channel_id: string; state: 'starting' | 'running' | 'backoff' | 'stopped'; enabled: boolean; error: string | null }
+  | { type: 'gateway_started'; report: Record<string, unknown> }
+  | { type: 'gateway_stopping'; active_runs: number; drain_timeout_ms: number }
+  | { type: 'transfer_progress'; transfer_id: string; direction: 'upload' | 'download'; name: string; transferred: number; total: number; session_key: string | null }
+  | { type: 'config_changed'; section: string; changes: ConfigChange[]; changed_by: string; persisted: boolean }
+  | { type: 'login_locked_out'; key: string; failures: number; locked_until: string }
+  | { type: 'heartbeat'; timestamp: string }
+
+export type UiEventType = UiEvent['type']
+
+export interface UiEventEnvelope {
+  id: string
+  timestamp: string
+  event: UiEvent
+}
@@ -53,6 +53,18 @@ export const useAuthStore = defineStore('auth', () => {
     localStorage.setItem('openclaw_refresh_token', result.refresh_token)
   }
 
+  // Tokens handed back by a single sign-on login
+  async function acceptTokens(accessToken: string, newRefreshToken: string): Promise<void> {
+    token.value = accessToken
+    refreshToken.value = newRefreshToken
+    localStorage.setItem('openclaw_token', accessToken)
+    localStorage.setItem('openclaw_refresh_token', newRefreshToken)
+    await fetchCurrentUser()
+    if (!user.value) {
+      throw new Error('Single sign-on login failed')
+    }
+  }
+
   async function logout(): Promise<void> {
     try {
       const api = useApi()
@@ -118,6 +130,7 @@ export const useAuthStore = defineStore('auth', () => {
     isAdmin,
     checkSetupStatus,
     login,
+    acceptTokens,
     logout,
     fetchCurrentUser,
     setup,
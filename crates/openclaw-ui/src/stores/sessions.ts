@@ -1,6 +1,7 @@
 import { defineStore } from 'pinia'
 import { ref } from 'vue'
 import { useApi } from '@/composables/useApi'
+import type { Attachment } from '@/composables/useTransfers'
 
 export interface SessionMessage {
   type: 'inbound' | 'outbound' | 'tool'
@@ -29,10 +30,36 @@ export interface SessionStats {
   total_messages: number
 }
 
+export interface AgentDayStats {
+  messages_received: number
+  responses: number
+  input_tokens: number
+  output_tokens: number
+  tool_calls: number
+  tool_errors: number
+}
+
+export interface ChannelDayStats {
+  sessions_started: number
+  messages_received: number
+  messages_sent: number
+}
+
+/** Daily activity counters, keyed by ID and then by `YYYY-MM-DD` day. */
+export interface ActivityStats {
+  from: string
+  to: string
+  totals: Record<string, AgentDayStats>
+  agents: Record<string, Record<string, AgentDayStats>>
+  channels: Record<string, Record<string, ChannelDayStats>>
+  errors: Record<string, Record<string, number>>
+}
+
 export const useSessionsStore = defineStore('sessions', () => {
   const sessions = ref<Session[]>([])
   const currentSession = ref<Session | null>(null)
   const stats = ref<SessionStats | null>(null)
+  const activity = ref<ActivityStats | null>(null)
   const loading = ref(false)
 
   async function fetchSessions(params: {
@@ -66,6 +93,19 @@ export const useSessionsStore = defineStore('sessions', () => {
     return result
   }
 
+  async function fetchActivity(params: {
+    days?: number
+    from?: string
+    to?: string
+    agent_id?: string
+    channel?: string
+  } = {}): Promise<ActivityStats> {
+    const api = useApi()
+    const result = await api.call<ActivityStats>('stats.get', params)
+    activity.value = result
+    return result
+  }
+
   async function createSession(agentId: string, channel: string = 'ui', peerId: string = 'web-user'): Promise<string> {
     const api = useApi()
     const result = await api.call<{ session_key: string }>('session.create', {
@@ -76,12 +116,18 @@ export const useSessionsStore = defineStore('sessions', () => {
     return result.session_key
   }
 
-  async function sendMessage(sessionKey: string, message: string, agentId: string = 'default'): Promise<string> {
+  async function sendMessage(
+    sessionKey: string,
+    message: string,
+    agentId: string = 'default',
+    attachments: Attachment[] = [],
+  ): Promise<string> {
     const api = useApi()
     const result = await api.call<{ response: string }>('session.message', {
       session_key: sessionKey,
       message,
       agent_id: agentId,
+      attachments,
     })
     return result.response
   }
@@ -105,10 +151,12 @@ export const useSessionsStore = defineStore('sessions', () => {
     sessions,
     currentSession,
     stats,
+    activity,
     loading,
     fetchSessions,
     fetchSession,
     fetchStats,
+    fetchActivity,
     createSession,
     sendMessage,
     endSession,